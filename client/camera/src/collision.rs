@@ -0,0 +1,147 @@
+//! Collision-aware flycam.
+//!
+//! The freelook camera normally flies straight through terrain and buildings.
+//! With [`FlycamCollision`] enabled, each frame's flycam movement is replayed
+//! as a sweep of a small sphere against the physics colliders and slides along
+//! whatever it hits instead of passing through. Colliders only exist within
+//! the physics streaming range ([`PhysicsStreamingConfig::range`]) of the
+//! camera, which is where clipping is noticeable anyway; farther out the
+//! camera flies freely.
+//!
+//! [`PhysicsStreamingConfig::range`]: veldera_physics::PhysicsStreamingConfig::range
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use veldera_camera::{FlightCamera, FlycamConstraintSet, FreelookCameraControl};
+use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_physics::{GameLayer, PhysicsState};
+
+/// Most slides per frame; a camera wedged into a corner stops after this many.
+const MAX_SLIDES: usize = 4;
+
+/// Gap (m) kept between the sphere and any surface it slides along, so the
+/// next sweep doesn't start in contact.
+const SKIN: f32 = 0.02;
+
+/// Plugin for the collision-aware flycam.
+pub(super) struct FlycamCollisionPlugin;
+
+impl Plugin for FlycamCollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlycamCollision>()
+            .add_systems(Update, collide_flycam.in_set(FlycamConstraintSet));
+    }
+}
+
+/// Flycam collision settings, editable from the Camera tab.
+#[derive(Resource, Clone, Debug)]
+pub struct FlycamCollision {
+    /// Sweep flycam movement against the physics colliders.
+    pub enabled: bool,
+    /// Radius of the swept sphere (m).
+    pub radius: f32,
+}
+
+impl Default for FlycamCollision {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 0.5,
+        }
+    }
+}
+
+impl FlycamCollision {
+    /// Smallest sphere radius the UI offers (m).
+    pub const MIN_RADIUS: f32 = 0.1;
+    /// Largest sphere radius the UI offers (m).
+    pub const MAX_RADIUS: f32 = 10.0;
+}
+
+/// Replace this frame's flycam movement with a sliding sphere sweep.
+///
+/// The flycam has already moved by [`FlightCamera::velocity`] times the
+/// frame's delta time; the sweep starts from where it was before that and
+/// walks the same displacement, removing the into-surface component at each
+/// hit so the remainder slides along it.
+pub(super) fn collide_flycam(
+    time: Res<Time>,
+    settings: Res<FlycamCollision>,
+    control: Res<FreelookCameraControl>,
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
+    mut camera_query: Query<(&mut FloatingOriginCamera, &FlightCamera)>,
+) {
+    if !settings.enabled || !control.input_active {
+        return;
+    }
+    let Some(origin) = physics_state.origin_camera_position() else {
+        return;
+    };
+    let Ok((mut camera, flight_camera)) = camera_query.single_mut() else {
+        return;
+    };
+    let displacement = flight_camera.velocity * time.delta_secs();
+    if displacement == Vec3::ZERO {
+        return;
+    }
+
+    let start = camera.position - displacement.as_dvec3();
+    let end = slide(
+        &spatial_query,
+        (start - origin).as_vec3(),
+        displacement,
+        settings.radius,
+    );
+    camera.position = origin + end.as_dvec3();
+}
+
+/// Sweep a sphere of `radius` from `start` (physics space) by `displacement`,
+/// sliding along surfaces it hits. Returns where the sphere comes to rest.
+fn slide(spatial_query: &SpatialQuery, start: Vec3, displacement: Vec3, radius: f32) -> Vec3 {
+    let shape = Collider::sphere(radius);
+    let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground, GameLayer::Vehicle]);
+    let mut position = start;
+    let mut remaining = displacement;
+    for _ in 0..MAX_SLIDES {
+        let Ok((direction, length)) = Dir3::new_and_length(remaining) else {
+            break;
+        };
+        let config = ShapeCastConfig {
+            max_distance: length + SKIN,
+            // Let a camera that starts inside geometry (collision just
+            // enabled) fly out of it.
+            ignore_origin_penetration: true,
+            ..Default::default()
+        };
+        let Some(hit) = spatial_query.cast_shape(
+            &shape,
+            position,
+            Quat::IDENTITY,
+            direction,
+            &config,
+            &filter,
+        ) else {
+            position += remaining;
+            break;
+        };
+
+        let travel = (hit.distance - SKIN).clamp(0.0, length);
+        position += direction * travel;
+        remaining -= direction * travel;
+
+        // Drop the part of the remaining movement that pushes into the
+        // surface.
+        let normal = if hit.normal1.dot(*direction) > 0.0 {
+            -hit.normal1
+        } else {
+            hit.normal1
+        };
+        let into = remaining.dot(normal);
+        if into < 0.0 {
+            remaining -= normal * into;
+        }
+    }
+    position
+}
@@ -0,0 +1,250 @@
+//! Speed and impact feedback for the chase camera.
+//!
+//! While the camera chases a target (e.g. a vehicle), three optional effects
+//! read the target's physics velocity:
+//!
+//! - **Shake**: hard acceleration (braking, collisions, landing a jump) adds
+//!   "trauma" that decays over time; the view jitters by the square of it, so
+//!   normal driving stays steady and impacts jolt.
+//! - **FOV kick**: the field of view widens with speed, selling it.
+//! - **Motion blur**: Bevy's [`MotionBlur`] on the camera, which brings the
+//!   depth and motion-vector prepasses with it.
+//!
+//! The live state sits in [`FollowCameraFeedback`] on the camera, which doubles
+//! as a hook for effects elsewhere: it carries the target's smoothed velocity
+//! and acceleration. Everything is tuned from the Camera tab through
+//! [`FollowCameraEffects`], and undone when the chase ends.
+
+use avian3d::prelude::*;
+use bevy::{
+    core_pipeline::prepass::{DepthPrepass, MotionVectorPrepass},
+    post_process::motion_blur::MotionBlur,
+    prelude::*,
+};
+
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use super::{FollowEntityTarget, FollowStyle, follow};
+
+/// Time constant (s) smoothing the target's acceleration. Physics steps at
+/// its own rate, so the per-frame velocity difference is spiky on its own.
+const ACCELERATION_SMOOTHING: f32 = 0.08;
+
+/// Plugin for the chase camera's speed and impact effects.
+pub(super) struct FollowCameraEffectsPlugin;
+
+impl Plugin for FollowCameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FollowCameraEffects>().add_systems(
+            Update,
+            apply_follow_camera_effects.after(follow::follow_entity_camera_system),
+        );
+    }
+}
+
+/// Chase camera effect settings, editable from the Camera tab.
+#[derive(Resource, Clone, Debug)]
+pub struct FollowCameraEffects {
+    /// Shake the view on hard acceleration.
+    pub shake: bool,
+    /// Peak shake rotation at full trauma (degrees).
+    pub shake_max_angle_deg: f32,
+    /// Acceleration below which nothing shakes (m/s²).
+    pub shake_min_acceleration: f32,
+    /// Acceleration that brings trauma to full (m/s²).
+    pub shake_full_acceleration: f32,
+    /// Trauma lost per second.
+    pub shake_decay: f32,
+    /// Rough frequency of the shake (Hz).
+    pub shake_frequency: f32,
+    /// Widen the field of view with speed.
+    pub fov_kick: bool,
+    /// Field of view added at `fov_kick_full_speed` (degrees).
+    pub fov_kick_max_deg: f32,
+    /// Speed at which the kick is full (m/s).
+    pub fov_kick_full_speed: f32,
+    /// Time constant (s) the kick follows speed changes with.
+    pub fov_kick_smoothing: f32,
+    /// Add Bevy's motion blur while chasing. Not available on WebGL2.
+    pub motion_blur: bool,
+    /// Motion blur exposure, as a fraction of a frame (1 = 360° shutter).
+    pub motion_blur_shutter_angle: f32,
+    /// Motion blur samples per pixel.
+    pub motion_blur_samples: u32,
+}
+
+impl Default for FollowCameraEffects {
+    fn default() -> Self {
+        Self {
+            shake: true,
+            shake_max_angle_deg: 1.5,
+            shake_min_acceleration: 15.0,
+            shake_full_acceleration: 60.0,
+            shake_decay: 1.5,
+            shake_frequency: 12.0,
+            fov_kick: true,
+            fov_kick_max_deg: 10.0,
+            fov_kick_full_speed: 60.0,
+            fov_kick_smoothing: 0.4,
+            motion_blur: false,
+            motion_blur_shutter_angle: 0.5,
+            motion_blur_samples: 4,
+        }
+    }
+}
+
+/// Live state of the chase camera effects, on the camera while it chases a
+/// target with a physics velocity; removed (and its effects undone) when the
+/// chase ends.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct FollowCameraFeedback {
+    /// The target's velocity (physics-space axes, m/s).
+    pub velocity: Vec3,
+    /// The target's smoothed acceleration magnitude (m/s²).
+    pub acceleration: f32,
+    /// Shake intensity, 0..1.
+    pub trauma: f32,
+    /// Field of view the speed kick currently adds (radians).
+    pub fov_kick: f32,
+    /// Seconds of shake noise elapsed.
+    shake_time: f32,
+    /// The FOV this system last wrote, to tell its own writes from the UI's
+    /// or the settings'.
+    written_fov: Option<f32>,
+    /// Whether this system added the motion blur.
+    motion_blur: bool,
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_follow_camera_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    effects: Res<FollowCameraEffects>,
+    mut camera_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Projection,
+            Option<&FollowEntityTarget>,
+            Option<&mut FollowCameraFeedback>,
+        ),
+        With<FloatingOriginCamera>,
+    >,
+    target_query: Query<&LinearVelocity>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut transform, mut projection, follow, feedback) in &mut camera_query {
+        let velocity = follow
+            .filter(|follow| follow.style == FollowStyle::Chase)
+            .and_then(|follow| target_query.get(follow.target).ok())
+            .map(|velocity| velocity.0);
+
+        let Some(velocity) = velocity else {
+            // Not chasing (any more): undo the effects.
+            if let Some(mut feedback) = feedback {
+                set_fov_kick(&mut projection, &mut feedback, 0.0);
+                if feedback.motion_blur {
+                    commands
+                        .entity(entity)
+                        .remove::<(MotionBlur, MotionVectorPrepass, DepthPrepass)>();
+                }
+                commands.entity(entity).remove::<FollowCameraFeedback>();
+            }
+            continue;
+        };
+        let Some(mut feedback) = feedback else {
+            commands.entity(entity).insert(FollowCameraFeedback {
+                velocity,
+                ..default()
+            });
+            continue;
+        };
+
+        if dt > 0.0 {
+            let acceleration = (velocity - feedback.velocity).length() / dt;
+            let blend = 1.0 - (-dt / ACCELERATION_SMOOTHING).exp();
+            feedback.acceleration += (acceleration - feedback.acceleration) * blend;
+        }
+        feedback.velocity = velocity;
+
+        // Shake: trauma jumps to the acceleration's level and decays.
+        let jolt = ramp(
+            effects.shake_min_acceleration,
+            effects.shake_full_acceleration,
+            feedback.acceleration,
+        );
+        feedback.trauma = (feedback.trauma - effects.shake_decay * dt).max(jolt);
+        feedback.shake_time += dt;
+        if effects.shake && feedback.trauma > 0.0 {
+            let amplitude =
+                effects.shake_max_angle_deg.to_radians() * feedback.trauma * feedback.trauma;
+            let t = feedback.shake_time * effects.shake_frequency * std::f32::consts::TAU;
+            let shake = Quat::from_euler(
+                EulerRot::YXZ,
+                amplitude * shake_noise(t, 0.0),
+                amplitude * shake_noise(t, 11.3),
+                0.5 * amplitude * shake_noise(t, 23.7),
+            );
+            // The follow rig rewrites the rotation every frame, so the shake
+            // never accumulates.
+            transform.rotation *= shake;
+        }
+
+        // FOV kick.
+        let target_kick = if effects.fov_kick {
+            effects.fov_kick_max_deg.to_radians()
+                * ramp(0.0, effects.fov_kick_full_speed, velocity.length())
+        } else {
+            0.0
+        };
+        let blend = 1.0 - (-dt / effects.fov_kick_smoothing.max(1e-3)).exp();
+        let kick = feedback.fov_kick + (target_kick - feedback.fov_kick) * blend;
+        set_fov_kick(&mut projection, &mut feedback, kick);
+
+        // Motion blur.
+        if effects.motion_blur && (!feedback.motion_blur || effects.is_changed()) {
+            commands.entity(entity).insert(MotionBlur {
+                shutter_angle: effects.motion_blur_shutter_angle,
+                samples: effects.motion_blur_samples,
+            });
+            feedback.motion_blur = true;
+        } else if !effects.motion_blur && feedback.motion_blur {
+            commands
+                .entity(entity)
+                .remove::<(MotionBlur, MotionVectorPrepass, DepthPrepass)>();
+            feedback.motion_blur = false;
+        }
+    }
+}
+
+/// Replace the speed kick in the camera's FOV with `kick`. If something else
+/// set the FOV since this system last did, that value is the new base.
+fn set_fov_kick(projection: &mut Projection, feedback: &mut FollowCameraFeedback, kick: f32) {
+    let Projection::Perspective(perspective) = projection else {
+        return;
+    };
+    let base = match feedback.written_fov {
+        Some(written) if written == perspective.fov => perspective.fov - feedback.fov_kick,
+        _ => perspective.fov,
+    };
+    perspective.fov = base + kick;
+    feedback.fov_kick = kick;
+    feedback.written_fov = Some(perspective.fov);
+}
+
+/// Smooth pseudo-random wobble in -1..1; `seed` decorrelates the axes.
+fn shake_noise(t: f32, seed: f32) -> f32 {
+    let a = (t + seed).sin();
+    let b = (t * 2.31 + seed * 1.7).sin();
+    let c = (t * 4.77 + seed * 0.6).sin();
+    (a + 0.5 * b + 0.25 * c) / 1.75
+}
+
+/// 0 at `start`, 1 at `end`, smoothstepped between.
+fn ramp(start: f32, end: f32, x: f32) -> f32 {
+    if end <= start {
+        return f32::from(x >= end);
+    }
+    let t = ((x - start) / (end - start)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
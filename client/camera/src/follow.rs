@@ -1,16 +1,29 @@
 //! Follow entity camera system.
 //!
-//! Third-person camera that follows a target entity (e.g., vehicle).
+//! Camera that follows a target entity: either a chase camera riding at the
+//! target's configured offsets (e.g., vehicle), a spectator orbit whose yaw,
+//! pitch, and distance are under mouse control, or a passenger's eye in the
+//! target's [`PassengerSeat`], looking around freely as it turns. The
+//! cinematic point-of-interest orbit lives in its own module.
 
+use std::f32::consts::{PI, TAU};
+
+use avian3d::prelude::*;
 use bevy::prelude::*;
 use glam::DVec3;
+use leafwing_input_manager::prelude::*;
 
+use veldera_game_input::CameraAction;
 use veldera_geo::{
     coords::RadialFrame,
     floating_origin::{FloatingOriginCamera, WorldPosition},
 };
+use veldera_physics::{GameLayer, PhysicsState};
 
-use super::{CameraModeState, CameraModeTransitions, FlightCamera};
+use super::{
+    CameraConfig, CameraModeState, CameraModeTransitions, FlightCamera, FollowStyle,
+    poi::CinematicOrbit,
+};
 
 // ============================================================================
 // Plugin
@@ -23,7 +36,12 @@ impl Plugin for FollowCameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (follow_entity_camera_system, exit_follow_on_missing_target)
+            (
+                follow_entity_camera_system,
+                (orbit_camera_input, orbit_camera_system).chain(),
+                passenger_camera_system,
+                exit_follow_on_missing_target,
+            )
                 .run_if(is_follow_entity_mode),
         );
     }
@@ -39,14 +57,23 @@ fn is_follow_entity_mode(state: Res<CameraModeState>) -> bool {
 /// The physics engine despawns out-of-range entities without knowing about
 /// camera modes, so this gameplay-side guard returns the camera to its prior
 /// mode once its target is gone (e.g. a followed vehicle that drove out of
-/// physics range was cleaned up).
+/// physics range was cleaned up, or a spectated projectile despawned). A chase
+/// target must also still be a [`FollowedEntity`], and a passenger's must
+/// have a [`PassengerSeat`] as well; orbit and cinematic targets need only a
+/// [`WorldPosition`].
 fn exit_follow_on_missing_target(
     mut transitions: ResMut<CameraModeTransitions>,
     camera_query: Query<&FollowEntityTarget>,
-    followed_query: Query<(), With<FollowedEntity>>,
+    target_query: Query<(Has<FollowedEntity>, Has<PassengerSeat>), With<WorldPosition>>,
 ) {
     for follow in &camera_query {
-        if followed_query.get(follow.target).is_err() {
+        let valid = match (follow.style, target_query.get(follow.target)) {
+            (_, Err(_)) => false,
+            (FollowStyle::Chase, Ok((followable, _))) => followable,
+            (FollowStyle::Passenger, Ok((followable, seated))) => followable && seated,
+            (FollowStyle::Orbit | FollowStyle::Cinematic, Ok(_)) => true,
+        };
+        if !valid {
             transitions.request_exit();
         }
     }
@@ -57,10 +84,103 @@ fn exit_follow_on_missing_target(
 // ============================================================================
 
 /// Component marking the camera as following an entity.
-#[derive(Component)]
+#[derive(Component, Clone, Copy, Debug)]
 pub struct FollowEntityTarget {
     /// The entity being followed.
     pub target: Entity,
+    /// How the camera frames the target.
+    pub style: FollowStyle,
+}
+
+/// Spectator orbit around the followed entity, in the local east-north-up
+/// frame at the target.
+///
+/// Added to the camera the first frame of an orbit, seeded from where the
+/// camera already is so entering the orbit doesn't jump, and removed when
+/// follow mode ends.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OrbitCamera {
+    /// Distance from the target (m).
+    pub distance: f32,
+    /// Bearing from the target to the camera, clockwise from north (radians).
+    pub yaw: f32,
+    /// Elevation of the camera above the target's horizon (radians).
+    pub pitch: f32,
+}
+
+impl OrbitCamera {
+    /// Closest the orbit may zoom in (m).
+    pub const MIN_DISTANCE: f32 = 2.0;
+    /// Farthest the orbit may zoom out (m).
+    pub const MAX_DISTANCE: f32 = 5_000.0;
+    /// Pitch limit either side of the horizon (radians), short of straight up
+    /// or down so the look-at basis stays well defined.
+    pub const MAX_PITCH: f32 = 1.5;
+
+    /// Seed the orbit from the camera's current placement relative to the
+    /// target.
+    pub fn from_positions(camera: DVec3, target: DVec3) -> Self {
+        let frame = RadialFrame::from_ecef_position(target);
+        let offset = camera - target;
+        let distance = offset.length();
+        if distance < 1e-3 {
+            return Self::default();
+        }
+        let east = offset.dot(frame.east.as_dvec3());
+        let north = offset.dot(frame.north.as_dvec3());
+        let up = offset.dot(frame.up.as_dvec3());
+        Self {
+            distance: (distance as f32).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE),
+            yaw: east.atan2(north) as f32,
+            pitch: ((up / distance).asin() as f32).clamp(-Self::MAX_PITCH, Self::MAX_PITCH),
+        }
+    }
+
+    /// Camera offset from the target in ECEF axes.
+    pub fn offset(&self, frame: &RadialFrame) -> DVec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let horizontal = frame.east * sin_yaw + frame.north * cos_yaw;
+        ((horizontal * cos_pitch + frame.up * sin_pitch) * self.distance).as_dvec3()
+    }
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            distance: 15.0,
+            yaw: 0.0,
+            pitch: 0.35,
+        }
+    }
+}
+
+/// Where a passenger's eye sits in this entity, in entity-local space.
+///
+/// Set by gameplay on entities that can carry a passenger (the vehicle crate
+/// places it from the car's body bounds); a target without one can only be
+/// chased or orbited.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PassengerSeat {
+    /// Eye position in entity-local space (m).
+    pub eye_offset: Vec3,
+}
+
+/// Free look of a passenger, relative to the seat's forward.
+///
+/// Added to the camera the first frame in the seat, looking ahead, and
+/// removed when follow mode ends.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct PassengerLook {
+    /// Turn of the head, counter-clockwise from straight ahead (radians).
+    pub yaw: f32,
+    /// Tilt of the head above the seat's horizon (radians).
+    pub pitch: f32,
+}
+
+impl PassengerLook {
+    /// Pitch limit either side of the seat's horizon (radians).
+    pub const MAX_PITCH: f32 = 1.4;
 }
 
 /// Where the player should appear when leaving FollowEntity mode for the
@@ -83,9 +203,17 @@ pub struct FollowCameraConfig {
     pub camera_offset: Vec3,
     /// Look-at target offset in entity-local space.
     pub look_target_offset: Vec3,
-    /// Position smoothing time constant (s): the camera lags toward its
-    /// target position, giving it swing and weight. 0 snaps rigidly.
+    /// Position smoothing time (s) of the critically damped spring the
+    /// camera rides on: it lags toward its target position, giving it swing
+    /// and weight. 0 snaps rigidly.
     pub position_smoothing: f32,
+    /// Smoothing time (s) of the look target's spring, which softens how
+    /// quickly the view turns with the entity. 0 snaps rigidly.
+    pub rotation_smoothing: f32,
+    /// Radius (m) of the sphere cast from the look target towards the camera;
+    /// the camera is pulled in front of terrain or buildings it hits. 0
+    /// disables collision avoidance.
+    pub collision_radius: f32,
 }
 
 impl Default for FollowCameraConfig {
@@ -94,10 +222,26 @@ impl Default for FollowCameraConfig {
             camera_offset: Vec3::new(0.0, 4.5, 20.0),
             look_target_offset: Vec3::new(0.0, 4.5, 12.0),
             position_smoothing: 0.25,
+            rotation_smoothing: 0.1,
+            collision_radius: 0.3,
         }
     }
 }
 
+/// Spring state of the chase camera, on the camera entity.
+///
+/// The camera's own position is the position spring's value; this holds the
+/// rest. Added on the first chase frame and removed when follow mode ends.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FollowCameraRig {
+    /// Velocity of the position spring (m/s).
+    velocity: DVec3,
+    /// Smoothed point the camera looks at (ECEF).
+    look_target: DVec3,
+    /// Velocity of the look-target spring (m/s).
+    look_velocity: DVec3,
+}
+
 // ============================================================================
 // Mode transition helpers
 // ============================================================================
@@ -112,9 +256,13 @@ pub(super) fn cleanup(
     let direction = frame.north;
     let transform = Transform::IDENTITY.looking_to(direction, frame.up);
 
-    commands
-        .entity(camera_entity)
-        .remove::<FollowEntityTarget>();
+    commands.entity(camera_entity).remove::<(
+        FollowEntityTarget,
+        OrbitCamera,
+        FollowCameraRig,
+        CinematicOrbit,
+        PassengerLook,
+    )>();
     commands.entity(camera_entity).insert((
         FlightCamera {
             direction,
@@ -131,17 +279,26 @@ pub(super) fn cleanup(
 
 /// Camera follows a target entity in third-person view.
 ///
-/// Positions the camera behind and above the entity, looking at it, with
-/// exponential position smoothing so the camera swings into corners and
-/// catches up rather than tracking rigidly. Uses `FollowCameraConfig` if
-/// present on the target, otherwise uses defaults.
-fn follow_entity_camera_system(
+/// Positions the camera at the target's configured offsets, looking at it,
+/// with critically damped springs on both the camera position and the look
+/// target so the camera swings into corners and settles without overshoot
+/// rather than tracking rigidly. A sphere cast from the look target then pulls
+/// the camera in front of any terrain or building between it and the target.
+/// Uses `FollowCameraConfig` if present on the target, otherwise uses
+/// defaults.
+#[allow(clippy::type_complexity)]
+pub(super) fn follow_entity_camera_system(
+    mut commands: Commands,
     time: Res<Time>,
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
     mut camera_query: Query<
         (
+            Entity,
             &mut FloatingOriginCamera,
             &mut Transform,
             &FollowEntityTarget,
+            Option<&mut FollowCameraRig>,
         ),
         Without<FollowedEntity>,
     >,
@@ -150,7 +307,11 @@ fn follow_entity_camera_system(
         With<FollowedEntity>,
     >,
 ) {
-    for (mut camera, mut camera_transform, follow_target) in &mut camera_query {
+    let dt = time.delta_secs_f64();
+    for (camera_entity, mut camera, mut camera_transform, follow_target, rig) in &mut camera_query {
+        if follow_target.style != FollowStyle::Chase {
+            continue;
+        }
         let Ok((target_transform, target_world_pos, follow_config)) =
             target_query.get(follow_target.target)
         else {
@@ -165,34 +326,301 @@ fn follow_entity_camera_system(
         let local_up = frame.up;
 
         // Transform the local-space offsets to world space using entity rotation.
-        let camera_offset = target_transform.rotation * fc.camera_offset;
-        let look_target = target_world_pos.position
+        let desired =
+            target_world_pos.position + (target_transform.rotation * fc.camera_offset).as_dvec3();
+        let desired_look = target_world_pos.position
             + (target_transform.rotation * fc.look_target_offset).as_dvec3();
 
-        // Desired camera position, approached exponentially. The smoothing is
-        // skipped on the first frame after a large jump (e.g. just entered
-        // the vehicle) so the camera doesn't swoop across the map.
-        let desired = target_world_pos.position + camera_offset.as_dvec3();
-        let blend = if fc.position_smoothing > 1e-3 {
-            1.0 - (-time.delta_secs_f64() / f64::from(fc.position_smoothing)).exp()
-        } else {
-            1.0
-        };
+        // Spring state. Snap on the first frame and after a large jump (e.g.
+        // just entered the vehicle, or it was teleported) so the camera
+        // doesn't swoop across the map.
         let snap = camera.position.distance_squared(desired) > 100.0 * 100.0;
-        camera.position = if snap {
-            desired
-        } else {
-            camera.position + (desired - camera.position) * blend
+        let look_target = match rig {
+            Some(mut rig) if !snap => {
+                let rig = &mut *rig;
+                camera.position = smooth_damp(
+                    camera.position,
+                    desired,
+                    &mut rig.velocity,
+                    fc.position_smoothing,
+                    dt,
+                );
+                rig.look_target = smooth_damp(
+                    rig.look_target,
+                    desired_look,
+                    &mut rig.look_velocity,
+                    fc.rotation_smoothing,
+                    dt,
+                );
+                rig.look_target
+            }
+            _ => {
+                camera.position = desired;
+                commands.entity(camera_entity).insert(FollowCameraRig {
+                    velocity: DVec3::ZERO,
+                    look_target: desired_look,
+                    look_velocity: DVec3::ZERO,
+                });
+                desired_look
+            }
         };
 
+        // Spring arm: keep the camera in front of whatever lies between it and
+        // the look target. The pull-in is immediate; pushing back out is left
+        // to the position spring, which starts from the pulled-in position
+        // next frame.
+        if fc.collision_radius > 0.0
+            && let Some(origin) = physics_state.origin_camera_position()
+            && let Some(distance) = spring_arm_length(
+                &spatial_query,
+                (look_target - origin).as_vec3(),
+                (camera.position - origin).as_vec3(),
+                fc.collision_radius,
+            )
+        {
+            let arm = camera.position - look_target;
+            camera.position = look_target + arm.normalize() * f64::from(distance);
+        }
+
         // Camera transform stays at origin (floating origin system).
         camera_transform.translation = Vec3::ZERO;
 
-        // Look at the target offset point from the smoothed position.
-        let look_direction = (look_target - camera.position).normalize().as_vec3();
-
+        // Look at the smoothed look target from the smoothed position.
+        let Ok(look_direction) = Dir3::new((look_target - camera.position).as_vec3()) else {
+            continue;
+        };
         camera_transform.rotation = Transform::default()
             .looking_to(look_direction, local_up)
             .rotation;
     }
 }
+
+/// Length the spring arm from `pivot` to `camera` (physics space) may extend
+/// before a sphere of `radius` would touch the ground layer, or `None` if the
+/// whole arm is clear.
+fn spring_arm_length(
+    spatial_query: &SpatialQuery,
+    pivot: Vec3,
+    camera: Vec3,
+    radius: f32,
+) -> Option<f32> {
+    let (direction, length) = Dir3::new_and_length(camera - pivot).ok()?;
+    let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground]);
+    let config = ShapeCastConfig {
+        max_distance: length,
+        ..Default::default()
+    };
+    spatial_query
+        .cast_shape(
+            &Collider::sphere(radius),
+            pivot,
+            Quat::IDENTITY,
+            direction,
+            &config,
+            &filter,
+        )
+        .map(|hit| hit.distance)
+}
+
+/// Critically damped spring from `current` towards `target`.
+///
+/// `smooth_time` is roughly the time to close most of the gap (s); the spring
+/// never overshoots. `velocity` carries the spring's state between calls. A
+/// non-positive `smooth_time` snaps to the target.
+fn smooth_damp(
+    current: DVec3,
+    target: DVec3,
+    velocity: &mut DVec3,
+    smooth_time: f32,
+    dt: f64,
+) -> DVec3 {
+    if smooth_time <= 1e-3 {
+        *velocity = DVec3::ZERO;
+        return target;
+    }
+    // Exact integration of x'' = -ω²(x - target) - 2ωx' over `dt`.
+    let omega = 2.0 / f64::from(smooth_time);
+    let offset = current - target;
+    let temp = (*velocity + offset * omega) * dt;
+    let decay = (-omega * dt).exp();
+    *velocity = (*velocity - temp * omega) * decay;
+    target + (offset + temp) * decay
+}
+
+/// Drive the orbit from mouse look (yaw and pitch) and the scroll wheel
+/// (distance), seeding it on the first frame.
+fn orbit_camera_input(
+    mut commands: Commands,
+    config: Res<CameraConfig>,
+    action_query: Query<&ActionState<CameraAction>>,
+    mut camera_query: Query<(
+        Entity,
+        &FloatingOriginCamera,
+        &FollowEntityTarget,
+        Option<&mut OrbitCamera>,
+    )>,
+    target_query: Query<&WorldPosition>,
+) {
+    for (camera_entity, camera, follow_target, orbit) in &mut camera_query {
+        if follow_target.style != FollowStyle::Orbit {
+            continue;
+        }
+        let Some(mut orbit) = orbit else {
+            if let Ok(target) = target_query.get(follow_target.target) {
+                commands
+                    .entity(camera_entity)
+                    .insert(OrbitCamera::from_positions(
+                        camera.position,
+                        target.position,
+                    ));
+            }
+            continue;
+        };
+        let Ok(action_state) = action_query.single() else {
+            continue;
+        };
+
+        let look = action_state.axis_pair(&CameraAction::Look);
+        orbit.yaw += look.x * config.mouse_sensitivity;
+        orbit.pitch = (orbit.pitch + look.y * config.mouse_sensitivity)
+            .clamp(-OrbitCamera::MAX_PITCH, OrbitCamera::MAX_PITCH);
+
+        // Each scroll notch zooms by a fixed fraction, so zooming feels the
+        // same at arm's length and from high above.
+        let scroll = action_state.clamped_value(&CameraAction::AdjustSpeed);
+        if scroll != 0.0 {
+            orbit.distance = (orbit.distance * 0.9_f32.powf(scroll))
+                .clamp(OrbitCamera::MIN_DISTANCE, OrbitCamera::MAX_DISTANCE);
+        }
+    }
+}
+
+/// Place the camera on its orbit around the target, looking at it.
+///
+/// The orbit is expressed in the target's local east-north-up frame, so it
+/// ignores the target's own heading: a spinning projectile or a turning
+/// vehicle doesn't drag the view around with it.
+fn orbit_camera_system(
+    mut camera_query: Query<(
+        &mut FloatingOriginCamera,
+        &mut Transform,
+        &FollowEntityTarget,
+        &OrbitCamera,
+    )>,
+    target_query: Query<&WorldPosition>,
+) {
+    for (mut camera, mut camera_transform, follow_target, orbit) in &mut camera_query {
+        if follow_target.style != FollowStyle::Orbit {
+            continue;
+        }
+        let Ok(target) = target_query.get(follow_target.target) else {
+            continue;
+        };
+
+        let frame = RadialFrame::from_ecef_position(target.position);
+        let offset = orbit.offset(&frame);
+        camera.position = target.position + offset;
+
+        // Camera transform stays at origin (floating origin system).
+        camera_transform.translation = Vec3::ZERO;
+        camera_transform.rotation = Transform::default()
+            .looking_to(-offset.normalize().as_vec3(), frame.up)
+            .rotation;
+    }
+}
+
+/// Seat the camera at the target's [`PassengerSeat`], turning and tilting
+/// with it, and look around it with the mouse.
+///
+/// The seat is rigid: the view rolls with the car over a bump rather than
+/// holding the horizon, as a passenger's would. Nothing here touches the
+/// target's controls.
+#[allow(clippy::type_complexity)]
+fn passenger_camera_system(
+    mut commands: Commands,
+    config: Res<CameraConfig>,
+    action_query: Query<&ActionState<CameraAction>>,
+    mut camera_query: Query<
+        (
+            Entity,
+            &mut FloatingOriginCamera,
+            &mut Transform,
+            &FollowEntityTarget,
+            Option<&mut PassengerLook>,
+        ),
+        Without<PassengerSeat>,
+    >,
+    target_query: Query<(&Transform, &WorldPosition, &PassengerSeat)>,
+) {
+    for (camera_entity, mut camera, mut camera_transform, follow_target, look) in &mut camera_query
+    {
+        if follow_target.style != FollowStyle::Passenger {
+            continue;
+        }
+        let Ok((target_transform, target_world_pos, seat)) = target_query.get(follow_target.target)
+        else {
+            continue;
+        };
+
+        let look = match look {
+            Some(mut look) => {
+                if let Ok(action_state) = action_query.single() {
+                    let delta =
+                        action_state.axis_pair(&CameraAction::Look) * config.mouse_sensitivity;
+                    look.yaw = (look.yaw - delta.x + PI).rem_euclid(TAU) - PI;
+                    look.pitch = (look.pitch - delta.y)
+                        .clamp(-PassengerLook::MAX_PITCH, PassengerLook::MAX_PITCH);
+                }
+                *look
+            }
+            None => {
+                commands
+                    .entity(camera_entity)
+                    .insert(PassengerLook::default());
+                PassengerLook::default()
+            }
+        };
+
+        camera.position =
+            target_world_pos.position + (target_transform.rotation * seat.eye_offset).as_dvec3();
+
+        // Camera transform stays at origin (floating origin system).
+        camera_transform.translation = Vec3::ZERO;
+        camera_transform.rotation =
+            target_transform.rotation * Quat::from_euler(EulerRot::YXZ, look.yaw, look.pitch, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_damp_settles_without_overshoot() {
+        let target = DVec3::new(10.0, 0.0, 0.0);
+        let mut position = DVec3::ZERO;
+        let mut velocity = DVec3::ZERO;
+        for _ in 0..600 {
+            position = smooth_damp(position, target, &mut velocity, 0.25, 1.0 / 60.0);
+            assert!(position.x <= target.x + 1e-9);
+        }
+        assert!(position.distance(target) < 1e-3);
+        assert!(velocity.length() < 1e-2);
+    }
+
+    #[test]
+    fn orbit_round_trips_through_camera_placement() {
+        let target = DVec3::new(4_000_000.0, 3_000_000.0, 3_500_000.0);
+        let orbit = OrbitCamera {
+            distance: 42.0,
+            yaw: 1.2,
+            pitch: 0.4,
+        };
+        let frame = RadialFrame::from_ecef_position(target);
+        let seeded = OrbitCamera::from_positions(target + orbit.offset(&frame), target);
+
+        assert!((seeded.distance - orbit.distance).abs() < 1e-3);
+        assert!((seeded.yaw - orbit.yaw).abs() < 1e-4);
+        assert!((seeded.pitch - orbit.pitch).abs() < 1e-4);
+    }
+}
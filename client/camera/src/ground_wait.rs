@@ -0,0 +1,107 @@
+//! Hold a newly spawned first-person player until there's ground under it.
+//!
+//! Dropping into first-person over terrain whose colliders haven't streamed
+//! in yet would let the player fall through the surface into the void. So a
+//! newly spawned player is held, the controller suppressed, until a ground
+//! raycast beneath it hits, mirroring the teleport's settle logic: if the
+//! ground is there straight away the player is released at once, otherwise
+//! after [`GeoConfig::physics_settle_delay`] of continuous ground. A player
+//! too high for the ray to reach is released to fall once the rendered
+//! terrain shows the ground is beyond it; the colliders stream in on the way
+//! down.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use veldera_game_player::controller::LogicalPlayer;
+use veldera_game_teleport::{GeoConfig, TeleportAnimation};
+use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
+
+use crate::TerrainFollow;
+
+/// Reach (m) of the ground ray below the player.
+const GROUND_RAY_M: f32 = 2_000.0;
+
+/// Plugin for the ground wait.
+pub(super) struct GroundWaitPlugin;
+
+impl Plugin for GroundWaitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FpsGroundWait>();
+    }
+}
+
+/// Whether the first-person player is being held until the ground under it
+/// loads.
+#[derive(Resource, Default)]
+pub struct FpsGroundWait {
+    /// The held player and when it was first found over ground, if it has
+    /// been since.
+    held: Option<(Entity, Option<f32>)>,
+}
+
+impl FpsGroundWait {
+    /// Whether the player is being held.
+    pub fn is_waiting(&self) -> bool {
+        self.held.is_some()
+    }
+}
+
+/// Hold newly spawned players, and release them once there's ground under
+/// them.
+pub(super) fn update_ground_wait(
+    time: Res<Time>,
+    config: Res<GeoConfig>,
+    teleport: Res<TeleportAnimation>,
+    follow: Res<TerrainFollow>,
+    spatial_query: SpatialQuery,
+    mut wait: ResMut<FpsGroundWait>,
+    spawned: Query<Entity, Added<LogicalPlayer>>,
+    players: Query<&WorldPosition, With<LogicalPlayer>>,
+    camera: Query<&FloatingOriginCamera>,
+) {
+    // A teleport settles its own player before respawning it.
+    let spawned = spawned.iter().last().filter(|_| !teleport.is_active());
+    if let Some(player) = spawned {
+        wait.held = Some((player, None));
+    }
+    let Some((player, grounded_since)) = wait.held else {
+        return;
+    };
+    let (Ok(player_position), Ok(camera)) = (players.get(player), camera.single()) else {
+        // Despawned (e.g. back to the flycam) before the ground loaded.
+        wait.held = None;
+        return;
+    };
+
+    let ecef = player_position.position;
+    let down = Dir3::new(-ecef.normalize().as_vec3()).unwrap_or(Dir3::NEG_Y);
+    let origin = (ecef - camera.position).as_vec3();
+    // The player's own capsule is on the ground layer.
+    let filter = SpatialQueryFilter::from_excluded_entities([player]);
+    let grounded = spatial_query
+        .cast_ray(origin, down, GROUND_RAY_M, true, &filter)
+        .is_some();
+
+    let now = time.elapsed_secs();
+    if grounded {
+        match grounded_since {
+            // Ground from the start: nothing to wait for.
+            None if spawned.is_some() => wait.held = None,
+            None => wait.held = Some((player, Some(now))),
+            Some(since) if now - since >= config.physics_settle_delay => {
+                tracing::info!("Ground loaded under the player, releasing");
+                wait.held = None;
+            }
+            Some(_) => {}
+        }
+    } else if follow
+        .height_above_ground(ecef)
+        .is_some_and(|height| height > f64::from(GROUND_RAY_M))
+    {
+        // Too high for the ray; let the fall begin.
+        wait.held = None;
+    } else if grounded_since.is_some() {
+        wait.held = Some((player, None));
+    }
+}
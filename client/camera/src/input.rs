@@ -1,6 +1,7 @@
 //! Input handling for camera controls.
 //!
-//! Handles cursor grab/ungrab and camera mode toggling.
+//! Handles cursor grab/ungrab, camera mode toggling, and skipping teleport
+//! flights and moving through their history.
 //! Input focus is managed centrally by [`veldera_game_input`].
 
 use bevy::{
@@ -11,9 +12,9 @@ use bevy_egui::EguiContexts;
 use leafwing_input_manager::prelude::*;
 
 use veldera_game_input::{CameraAction, set_cursor_grab};
-use veldera_game_teleport::TeleportAnimation;
+use veldera_game_teleport::{TeleportAnimation, TeleportHistory, TeleportState};
 
-use super::{CameraMode, CameraModeState, CameraModeTransitions};
+use super::{CameraMode, CameraModeState, CameraModeTransitions, FollowEntityTarget, FollowStyle};
 
 // ============================================================================
 // Plugin
@@ -29,6 +30,8 @@ impl Plugin for CameraInputPlugin {
             (
                 toggle_camera_mode.run_if(teleport_animation_not_active),
                 cursor_grab_system,
+                skip_teleport,
+                navigate_history,
             ),
         );
     }
@@ -74,15 +77,50 @@ fn cursor_grab_system(
     }
 }
 
+// ============================================================================
+// Teleport skip
+// ============================================================================
+
+/// Skip the rest of a teleport flight on [`CameraAction::SkipTeleport`].
+fn skip_teleport(
+    action_query: Query<&ActionState<CameraAction>>,
+    mut animation: ResMut<TeleportAnimation>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if action_state.just_pressed(&CameraAction::SkipTeleport) && animation.can_skip() {
+        animation.skip();
+    }
+}
+
+/// Fly Back or Forward through the teleport history.
+fn navigate_history(
+    action_query: Query<&ActionState<CameraAction>>,
+    mut history: ResMut<TeleportHistory>,
+    mut teleport: ResMut<TeleportState>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if action_state.just_pressed(&CameraAction::HistoryBack) {
+        history.back(&mut teleport);
+    } else if action_state.just_pressed(&CameraAction::HistoryForward) {
+        history.forward(&mut teleport);
+    }
+}
+
 // ============================================================================
 // Mode toggle
 // ============================================================================
 
-/// Toggle between flycam and FPS controller modes with the N key.
+/// Toggle between flycam and FPS controller modes with the N key; in a
+/// spectator or cinematic orbit, N leaves it for the previous mode.
 fn toggle_camera_mode(
     action_query: Query<&ActionState<CameraAction>>,
     state: Res<CameraModeState>,
     mut transitions: ResMut<CameraModeTransitions>,
+    follow_query: Query<&FollowEntityTarget>,
 ) {
     let Ok(action_state) = action_query.single() else {
         return;
@@ -100,7 +138,14 @@ fn toggle_camera_mode(
             transitions.request_flycam();
         }
         CameraMode::FollowEntity => {
-            // In FollowEntity mode, use the interact key (E) instead of N.
+            // Vehicles, driven or ridden in, are left with the interact keys
+            // (E, F) instead, which also handle getting out.
+            if follow_query
+                .iter()
+                .any(|follow| !matches!(follow.style, FollowStyle::Chase | FollowStyle::Passenger))
+            {
+                transitions.request_exit();
+            }
         }
     }
 }
@@ -14,9 +14,15 @@
 //!
 //! ### States
 //!
-//! - **Flycam**: the engine freelook camera (WASD + mouse look).
+//! - **Flycam**: the engine freelook camera (WASD + mouse look), optionally
+//!   colliding with nearby geometry (see [`FlycamCollision`]), or in orbital
+//!   free fall high above the atmosphere (see [`OrbitalFlight`]).
 //! - **FpsController**: first-person controller with physics (walking, jumping).
-//! - **FollowEntity**: camera follows a target entity (e.g., vehicle).
+//! - **FollowEntity**: camera follows a target entity, either as a chase
+//!   camera (e.g., vehicle), from its passenger seat with free look (see
+//!   [`PassengerSeat`]), as a mouse-controlled spectator orbit around any
+//!   entity with a `WorldPosition`, or as a hands-off cinematic orbit around a
+//!   point of interest (see [`CinematicOrbit`]).
 //!
 //! ### Valid transitions
 //!
@@ -32,21 +38,40 @@
 //! [`sync_freelook_control`] system translates the current mode (and teleport
 //! animation state) into the engine's [`FreelookCameraControl`] each frame,
 //! running `.before(FreelookCameraSet)`.
+//!
+//! Entering first-person over terrain whose colliders haven't loaded would
+//! drop the player through it, so a newly spawned player is held in place
+//! until a ground raycast beneath it hits (see [`FpsGroundWait`]).
 
+mod collision;
+mod effects;
 mod follow;
+mod ground_wait;
 mod input;
+mod orbital;
+mod poi;
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
 use veldera_game_player::controller as fps;
-use veldera_game_teleport::TeleportAnimation;
+use veldera_game_teleport::{RoutePlanner, TeleportAnimation};
 use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
+use veldera_physics::PhysicsState;
+use veldera_terrain::raycast::{TerrainRaycast, TerrainRaycastSystems};
 
-pub use follow::{FollowCameraConfig, FollowEntityTarget, FollowExitAnchor, FollowedEntity};
+pub use collision::FlycamCollision;
+pub use effects::{FollowCameraEffects, FollowCameraFeedback};
+pub use follow::{
+    FollowCameraConfig, FollowCameraRig, FollowEntityTarget, FollowExitAnchor, FollowedEntity,
+    OrbitCamera, PassengerLook, PassengerSeat,
+};
+pub use ground_wait::FpsGroundWait;
+pub use orbital::{OrbitReadout, OrbitalFlight, OrbitalFlightSettings};
+pub use poi::{CinematicOrbit, CinematicOrbitRequest, CinematicOrbitSettings, PointOfInterest};
 pub use veldera_camera::{
     AltitudeRequest, CameraConfig, FlightCamera, HeadingRequest, TeleportAnimationMode,
-    TranslateRequest,
+    TerrainFollow, TranslateRequest,
 };
 use veldera_camera::{
     FreelookCameraControl, FreelookCameraPlugin, FreelookCameraSet, translate_ecef,
@@ -59,7 +84,9 @@ use veldera_camera::{
 // ============================================================================
 
 use veldera_game_camera_state::CameraModeTransition;
-pub use veldera_game_camera_state::{CameraMode, CameraModeState, CameraModeTransitions};
+pub use veldera_game_camera_state::{
+    CameraMode, CameraModeState, CameraModeTransitions, FollowStyle, Spectatable,
+};
 
 // ============================================================================
 // Plugin
@@ -97,15 +124,35 @@ impl Plugin for CameraControllerPlugin {
             .init_resource::<CameraModeState>()
             .init_resource::<CameraModeTransitions>()
             .init_resource::<follow::FollowExitAnchor>()
-            .add_plugins((follow::FollowCameraPlugin, input::CameraInputPlugin))
-            // Run the mode machine, then translate the resulting mode into the
-            // engine's freelook control, before the freelook systems read it.
+            .add_plugins((
+                collision::FlycamCollisionPlugin,
+                effects::FollowCameraEffectsPlugin,
+                follow::FollowCameraPlugin,
+                ground_wait::GroundWaitPlugin,
+                input::CameraInputPlugin,
+                orbital::OrbitalFlightPlugin,
+                poi::CinematicOrbitPlugin,
+            ))
+            // Run the mode machine, hold a newly spawned player until the
+            // ground under it loads, then translate the resulting mode into
+            // the engine's freelook control, before the freelook systems read
+            // it.
             .add_systems(
                 Update,
-                (process_mode_transitions, sync_freelook_control)
+                (
+                    process_mode_transitions,
+                    ground_wait::update_ground_wait,
+                    sync_freelook_control,
+                )
                     .chain()
                     .before(FreelookCameraSet),
             )
+            .add_systems(
+                Update,
+                sample_ground_under_camera
+                    .after(TerrainRaycastSystems)
+                    .before(FreelookCameraSet),
+            )
             // First-person arms of the viewer requests: they move the player
             // body instead of the camera, so they run only in FPS mode (the
             // engine's camera-path handlers run in every other mode).
@@ -131,21 +178,61 @@ fn is_fps_controller_mode(state: Res<CameraModeState>) -> bool {
 fn sync_freelook_control(
     mode: Res<CameraModeState>,
     teleport: Res<TeleportAnimation>,
+    route: Res<RoutePlanner>,
+    ground_wait: Res<FpsGroundWait>,
     mut control: ResMut<FreelookCameraControl>,
     mut fps_suppressed: ResMut<fps::FpsControllerSuppressed>,
 ) {
     let teleporting = teleport.is_active();
-    // Freelook input is suppressed during a teleport animation so it doesn't
-    // fight the scripted camera path.
-    control.input_active = mode.is_flycam() && !teleporting;
+    // Freelook input is suppressed during a teleport animation or a route
+    // flight so it doesn't fight the scripted camera path.
+    control.input_active = mode.is_flycam() && !teleporting && !route.is_flying();
     // The freelook camera owns the view in every mode except first-person; in
     // FollowEntity mode the follow rig drives the camera position and the
     // freelook origin sync still applies.
     control.view_active = !mode.is_fps_controller();
     // Likewise, suppress the FPS controller while teleporting so it doesn't
     // fight the scripted player respawn (replaces the controller's former
-    // direct read of `TeleportAnimation`).
-    fps_suppressed.0 = teleporting;
+    // direct read of `TeleportAnimation`), and while the player is held
+    // until the ground under it loads.
+    fps_suppressed.0 = teleporting || ground_wait.is_waiting();
+}
+
+/// Height above sea level (m) the ground probe starts from when the camera is
+/// lower, above the highest terrain.
+const GROUND_PROBE_TOP: f64 = 9_000.0;
+
+/// Depth below sea level (m) the ground probe reaches, below the lowest
+/// terrain.
+const GROUND_PROBE_BOTTOM: f64 = 1_000.0;
+
+/// Report the ground beneath the camera to [`TerrainFollow`], for terrain
+/// following and the above-ground-level readout.
+///
+/// The probe casts against the loaded render meshes rather than the physics
+/// colliders, so it finds the ground from any altitude. It starts above the
+/// highest terrain, so a camera that dipped below the surface still finds the
+/// ground above it.
+fn sample_ground_under_camera(
+    raycast: TerrainRaycast,
+    mut follow: ResMut<TerrainFollow>,
+    camera_query: Query<&FloatingOriginCamera>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let Some(up) = camera.position.try_normalize() else {
+        return;
+    };
+    let top = camera
+        .position
+        .length()
+        .max(veldera_constants::EARTH_RADIUS_M_F64 + GROUND_PROBE_TOP);
+    let bottom = veldera_constants::EARTH_RADIUS_M_F64 - GROUND_PROBE_BOTTOM;
+    let ground = raycast
+        .cast_ray(up * top, -up, top - bottom)
+        .map(|hit| hit.position.length());
+    follow.set_ground_radius(ground);
 }
 
 // ============================================================================
@@ -188,14 +275,14 @@ fn process_mode_transitions(
                     &camera_query,
                 );
             }
-            CameraModeTransition::ToFollowEntity { target } => {
+            CameraModeTransition::ToFollowEntity { target, style } => {
                 transition_to_follow_entity(
                     &mut commands,
                     &mut state,
                     &mut preserved_fps,
                     &camera_query,
                     &logical_player_query,
-                    target,
+                    FollowEntityTarget { target, style },
                 );
             }
             CameraModeTransition::ExitCurrentMode => {
@@ -303,7 +390,7 @@ fn transition_to_follow_entity(
         (Entity, &WorldPosition, &fps::FpsController),
         (With<fps::LogicalPlayer>, Without<FloatingOriginCamera>),
     >,
-    target: Entity,
+    follow: FollowEntityTarget,
 ) {
     let return_mode = state.current();
 
@@ -315,26 +402,28 @@ fn transition_to_follow_entity(
             fps::preserve_and_cleanup(commands, preserved_fps, logical_player_query);
         }
         CameraMode::FollowEntity => {
-            // Already following; just update the target.
+            // Already following; just update the target. Any orbit or
+            // passenger look is re-seeded for the new target.
             if let Ok((camera_entity, _, _)) = camera_query.single() {
                 commands
                     .entity(camera_entity)
-                    .insert(FollowEntityTarget { target });
+                    .remove::<(OrbitCamera, CinematicOrbit, PassengerLook)>()
+                    .insert(follow);
             }
             return;
         }
     }
 
     // Add follow target to camera.
+    let style = follow.style;
     if let Ok((camera_entity, _, _)) = camera_query.single() {
-        commands
-            .entity(camera_entity)
-            .insert(FollowEntityTarget { target });
+        commands.entity(camera_entity).insert(follow);
     }
 
     state.set(CameraMode::FollowEntity, Some(return_mode));
     tracing::info!(
-        "Transitioned to FollowEntity mode (return: {:?})",
+        "Transitioned to FollowEntity mode ({:?}, return: {:?})",
+        style,
         return_mode
     );
 }
@@ -408,6 +497,7 @@ fn exit_current_mode(
 /// own altitude handler runs in every other mode.
 fn process_altitude_request_fps(
     mut request: ResMut<AltitudeRequest>,
+    physics_state: Res<PhysicsState>,
     mut camera_query: Query<&mut FloatingOriginCamera>,
     mut player_query: Query<
         (&mut WorldPosition, &mut Position, &mut LinearVelocity),
@@ -423,7 +513,7 @@ fn process_altitude_request_fps(
         let new_ecef = world_pos.position.normalize() * new_radius;
 
         world_pos.position = new_ecef;
-        *physics_pos = Position(Vec3::ZERO);
+        *physics_pos = Position(physics_state.to_physics(new_ecef).unwrap_or_default());
         *velocity = LinearVelocity::ZERO;
 
         if let Ok(mut camera) = camera_query.single_mut() {
@@ -439,6 +529,7 @@ fn process_altitude_request_fps(
 /// physics. Runs only in FPS mode.
 fn process_translate_request_fps(
     mut request: ResMut<TranslateRequest>,
+    physics_state: Res<PhysicsState>,
     mut camera_query: Query<&mut FloatingOriginCamera>,
     mut player_query: Query<
         (&mut WorldPosition, &mut Position, &mut LinearVelocity),
@@ -451,7 +542,11 @@ fn process_translate_request_fps(
 
     if let Ok((mut world_pos, mut physics_pos, mut velocity)) = player_query.single_mut() {
         world_pos.position = translate_ecef(world_pos.position, bearing_deg, distance_m);
-        *physics_pos = Position(Vec3::ZERO);
+        *physics_pos = Position(
+            physics_state
+                .to_physics(world_pos.position)
+                .unwrap_or_default(),
+        );
         *velocity = LinearVelocity::ZERO;
         if let Ok(mut camera) = camera_query.single_mut() {
             camera.position = world_pos.position;
@@ -0,0 +1,381 @@
+//! Orbital free fall for the flycam.
+//!
+//! High enough above the atmosphere, the flycam can be handed over to
+//! two-body orbital mechanics, so you can actually orbit the planet instead
+//! of flying at a fixed speed. Engaging puts the camera on a circular orbit
+//! along its current heading; from there it falls freely around a point-mass
+//! Earth, integrated in double precision with semi-implicit Euler (which keeps
+//! the orbit's energy bounded over many revolutions). The movement keys fire
+//! thrusters along the flycam's usual directions instead of moving it
+//! directly, and mouse look still turns the view.
+//!
+//! Below the top of the atmosphere an exponential density profile drags on
+//! the orbit, so a periapsis skimming the atmosphere decays each pass. Falling
+//! below [`OrbitalFlightSettings::exit_altitude_m`] hands the camera back to
+//! the ordinary flycam, as does leaving flycam mode or starting a teleport.
+//!
+//! The integration runs in the ECEF frame as if it were inertial: the planet
+//! doesn't turn under the orbit. Altitudes are over the mean Earth radius.
+
+use bevy::prelude::*;
+use glam::DVec3;
+
+use veldera_camera::{FlightCamera, FlycamConstraintSet, FreelookCameraControl};
+use veldera_constants::{ATMOSPHERE_HEIGHT_M, EARTH_RADIUS_M_F64};
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+/// Earth's standard gravitational parameter, GM (m³/s²).
+const EARTH_GM: f64 = 3.986_004_418e14;
+
+/// Air density at sea level (kg/m³).
+const SEA_LEVEL_DENSITY: f64 = 1.225;
+
+/// Scale height of the drag density profile (m). Fitted to the density near
+/// the top of the atmosphere, where drag matters to an orbit, rather than to
+/// the lower atmosphere.
+const DENSITY_SCALE_HEIGHT: f64 = 7_000.0;
+
+/// Longest integration step (s); a long frame takes several.
+const MAX_STEP_S: f64 = 0.02;
+
+// ============================================================================
+// Plugin
+// ============================================================================
+
+/// Plugin for orbital free fall.
+pub(super) struct OrbitalFlightPlugin;
+
+impl Plugin for OrbitalFlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrbitalFlightSettings>()
+            .init_resource::<OrbitalFlight>()
+            .add_systems(
+                Update,
+                process_orbital_request
+                    .after(super::sync_freelook_control)
+                    .before(FlycamConstraintSet),
+            )
+            // Replaces the flycam's own movement, before the collision
+            // constraint looks at it.
+            .add_systems(
+                Update,
+                integrate_orbit
+                    .in_set(FlycamConstraintSet)
+                    .before(super::collision::collide_flycam),
+            );
+    }
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Orbital flight tuning, editable from the Camera tab.
+#[derive(Resource, Clone, Debug)]
+pub struct OrbitalFlightSettings {
+    /// Lowest altitude at which orbital flight can be engaged (m).
+    pub min_altitude_m: f64,
+    /// Altitude below which a falling orbit hands back to the flycam (m).
+    pub exit_altitude_m: f64,
+    /// Acceleration of the movement-key thrusters (m/s²).
+    pub thrust: f32,
+    /// Ballistic coefficient of the camera, mass over drag area (kg/m²).
+    /// Lower values decay faster in the upper atmosphere.
+    pub ballistic_coefficient: f64,
+}
+
+impl Default for OrbitalFlightSettings {
+    fn default() -> Self {
+        Self {
+            min_altitude_m: 80_000.0,
+            exit_altitude_m: 20_000.0,
+            thrust: 10.0,
+            ballistic_coefficient: 100.0,
+        }
+    }
+}
+
+/// Orbital flight state: whether the flycam is in free fall, and the orbit
+/// it's on.
+#[derive(Resource, Default)]
+pub struct OrbitalFlight {
+    /// Velocity of the free-falling camera in ECEF axes (m/s), while in
+    /// orbital flight.
+    velocity: Option<DVec3>,
+    /// The orbit as of the last step.
+    readout: Option<OrbitReadout>,
+    /// Engage or leave orbital flight on the next update.
+    toggle: bool,
+}
+
+impl OrbitalFlight {
+    /// Whether the flycam is in orbital flight.
+    pub fn is_active(&self) -> bool {
+        self.velocity.is_some()
+    }
+
+    /// Request to engage orbital flight (if high enough) or leave it.
+    pub fn request_toggle(&mut self) {
+        self.toggle = true;
+    }
+
+    /// The current orbit, while in orbital flight.
+    pub fn readout(&self) -> Option<&OrbitReadout> {
+        self.readout.as_ref()
+    }
+}
+
+/// Readouts of an orbit from its state vector.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitReadout {
+    /// Current altitude (m).
+    pub altitude_m: f64,
+    /// Current speed (m/s).
+    pub speed: f64,
+    /// Altitude of the lowest point of the orbit (m); negative if the orbit
+    /// meets the ground.
+    pub periapsis_m: f64,
+    /// Altitude of the highest point (m), or `None` on an escape trajectory.
+    pub apoapsis_m: Option<f64>,
+    /// Orbital period (s), or `None` on an escape trajectory.
+    pub period_s: Option<f64>,
+    /// Deceleration from atmospheric drag (m/s²).
+    pub drag: f64,
+}
+
+// ============================================================================
+// Orbital mechanics
+// ============================================================================
+
+/// Gravitational acceleration of a point-mass Earth at `position` (ECEF).
+fn gravity(position: DVec3) -> DVec3 {
+    let r = position.length();
+    -position * (EARTH_GM / (r * r * r))
+}
+
+/// Air density (kg/m³) at `altitude` (m); none above the atmosphere.
+fn air_density(altitude: f64) -> f64 {
+    if altitude >= ATMOSPHERE_HEIGHT_M {
+        return 0.0;
+    }
+    SEA_LEVEL_DENSITY * (-altitude.max(0.0) / DENSITY_SCALE_HEIGHT).exp()
+}
+
+/// Drag acceleration on a body moving at `velocity` through air of
+/// `density`.
+fn drag(velocity: DVec3, density: f64, ballistic_coefficient: f64) -> DVec3 {
+    -velocity * (0.5 * density * velocity.length() / ballistic_coefficient.max(1.0))
+}
+
+/// Speed of a circular orbit at `position` (m/s).
+fn circular_speed(position: DVec3) -> f64 {
+    (EARTH_GM / position.length()).sqrt()
+}
+
+/// Advance `position` and `velocity` by `dt` with semi-implicit Euler steps,
+/// under gravity, drag, and a constant `thrust` acceleration.
+fn step_orbit(
+    position: &mut DVec3,
+    velocity: &mut DVec3,
+    thrust: DVec3,
+    ballistic_coefficient: f64,
+    dt: f64,
+) {
+    let steps = (dt / MAX_STEP_S).ceil().max(1.0);
+    let h = dt / steps;
+    for _ in 0..steps as usize {
+        let density = air_density(position.length() - EARTH_RADIUS_M_F64);
+        let acceleration =
+            gravity(*position) + drag(*velocity, density, ballistic_coefficient) + thrust;
+        *velocity += acceleration * h;
+        *position += *velocity * h;
+    }
+}
+
+/// Describe the orbit through `position` at `velocity` (ECEF).
+fn orbit_readout(position: DVec3, velocity: DVec3, ballistic_coefficient: f64) -> OrbitReadout {
+    let r = position.length();
+    let speed = velocity.length();
+    let altitude_m = r - EARTH_RADIUS_M_F64;
+    let energy = 0.5 * speed * speed - EARTH_GM / r;
+    let momentum = position.cross(velocity);
+    let eccentricity = (velocity.cross(momentum) / EARTH_GM - position / r).length();
+
+    let (periapsis, apoapsis, period_s) = if energy < 0.0 {
+        let semi_major = -EARTH_GM / (2.0 * energy);
+        (
+            semi_major * (1.0 - eccentricity),
+            Some(semi_major * (1.0 + eccentricity)),
+            Some(std::f64::consts::TAU * (semi_major.powi(3) / EARTH_GM).sqrt()),
+        )
+    } else {
+        (
+            momentum.length_squared() / (EARTH_GM * (1.0 + eccentricity)),
+            None,
+            None,
+        )
+    };
+
+    OrbitReadout {
+        altitude_m,
+        speed,
+        periapsis_m: periapsis - EARTH_RADIUS_M_F64,
+        apoapsis_m: apoapsis.map(|radius| radius - EARTH_RADIUS_M_F64),
+        period_s,
+        drag: drag(velocity, air_density(altitude_m), ballistic_coefficient).length(),
+    }
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Engage or leave orbital flight on request, and leave it whenever the
+/// flycam stops taking input (another mode, or a teleport).
+fn process_orbital_request(
+    settings: Res<OrbitalFlightSettings>,
+    control: Res<FreelookCameraControl>,
+    mut orbital: ResMut<OrbitalFlight>,
+    camera_query: Query<(&FloatingOriginCamera, &FlightCamera)>,
+) {
+    let toggle = std::mem::take(&mut orbital.toggle);
+    if !control.input_active || (toggle && orbital.is_active()) {
+        if orbital.is_active() {
+            tracing::info!("Left orbital flight");
+        }
+        orbital.velocity = None;
+        orbital.readout = None;
+        return;
+    }
+    if !toggle {
+        return;
+    }
+    let Ok((camera, flight)) = camera_query.single() else {
+        return;
+    };
+    let altitude = camera.position.length() - EARTH_RADIUS_M_F64;
+    if altitude < settings.min_altitude_m {
+        tracing::warn!(
+            "Orbital flight needs at least {:.0} km of altitude",
+            settings.min_altitude_m / 1000.0
+        );
+        return;
+    }
+
+    // A circular orbit along the view's heading (any horizontal direction
+    // if looking straight up or down).
+    let up = camera.position.normalize();
+    let heading = flight
+        .direction
+        .as_dvec3()
+        .reject_from_normalized(up)
+        .try_normalize()
+        .unwrap_or_else(|| up.any_orthonormal_vector());
+    let velocity = heading * circular_speed(camera.position);
+    orbital.velocity = Some(velocity);
+    orbital.readout = Some(orbit_readout(
+        camera.position,
+        velocity,
+        settings.ballistic_coefficient,
+    ));
+    tracing::info!(
+        "Entered orbital flight at {:.0} km, {:.2} km/s",
+        altitude / 1000.0,
+        velocity.length() / 1000.0
+    );
+}
+
+/// Replace this frame's flycam movement with a step of free fall.
+///
+/// The flycam has already moved by [`FlightCamera::velocity`] times the
+/// frame's delta time, in the direction the movement keys asked for; that
+/// movement is undone and its direction becomes the thrust. Afterwards the
+/// flycam velocity is the step's mean velocity, so constraints later in the
+/// set still see the movement that was applied.
+fn integrate_orbit(
+    time: Res<Time>,
+    settings: Res<OrbitalFlightSettings>,
+    control: Res<FreelookCameraControl>,
+    mut orbital: ResMut<OrbitalFlight>,
+    mut camera_query: Query<(&mut FloatingOriginCamera, &mut Transform, &mut FlightCamera)>,
+) {
+    let Some(mut velocity) = orbital.velocity else {
+        return;
+    };
+    if !control.input_active {
+        return;
+    }
+    let Ok((mut camera, mut transform, mut flight)) = camera_query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs_f64();
+    if dt <= 0.0 {
+        return;
+    }
+
+    let flycam_up = camera.position.normalize();
+    let start = camera.position - (flight.velocity.as_dvec3() * dt);
+    let thrust = flight.velocity.normalize_or_zero().as_dvec3() * f64::from(settings.thrust);
+    let mut position = start;
+    step_orbit(
+        &mut position,
+        &mut velocity,
+        thrust,
+        settings.ballistic_coefficient,
+        dt,
+    );
+
+    let altitude = position.length() - EARTH_RADIUS_M_F64;
+    if altitude < settings.exit_altitude_m {
+        // The flycam has no inertia to hand the fall to; it stops here.
+        tracing::info!("Fell out of orbital flight at {:.0} km", altitude / 1000.0);
+        orbital.velocity = None;
+        orbital.readout = None;
+        return;
+    }
+
+    camera.position = position;
+    flight.velocity = ((position - start) / dt).as_vec3();
+    // Parallel transport the view, as the flycam does when it moves.
+    let up = position.normalize().as_vec3();
+    flight.direction =
+        (Quat::from_rotation_arc(flycam_up.as_vec3(), up) * flight.direction).normalize();
+    transform.look_to(flight.direction, up);
+
+    orbital.velocity = Some(velocity);
+    orbital.readout = Some(orbit_readout(
+        position,
+        velocity,
+        settings.ballistic_coefficient,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circular_orbit_holds_its_apsides() {
+        let mut position = DVec3::new(EARTH_RADIUS_M_F64 + 400_000.0, 0.0, 0.0);
+        let mut velocity = DVec3::new(0.0, circular_speed(position), 0.0);
+        let readout = orbit_readout(position, velocity, 100.0);
+        assert!((readout.periapsis_m - 400_000.0).abs() < 1.0);
+        assert!((readout.apoapsis_m.unwrap() - 400_000.0).abs() < 1.0);
+        let period = readout.period_s.unwrap();
+        assert!((period - 5_545.0).abs() < 5.0);
+
+        // A full revolution comes back round, still circular.
+        step_orbit(&mut position, &mut velocity, DVec3::ZERO, 100.0, period);
+        let after = orbit_readout(position, velocity, 100.0);
+        assert!((after.periapsis_m - 400_000.0).abs() < 1.0);
+        assert!((after.apoapsis_m.unwrap() - 400_000.0).abs() < 1.0);
+        assert!(position.distance(DVec3::new(EARTH_RADIUS_M_F64 + 400_000.0, 0.0, 0.0)) < 10.0);
+    }
+
+    #[test]
+    fn drag_only_inside_the_atmosphere() {
+        assert_eq!(air_density(ATMOSPHERE_HEIGHT_M + 1.0), 0.0);
+        assert!(air_density(90_000.0) > air_density(99_000.0));
+        assert!(air_density(99_000.0) > 0.0);
+    }
+}
@@ -0,0 +1,477 @@
+//! Cinematic point-of-interest orbit.
+//!
+//! A hands-off showcase camera: pick a ground point (whatever lies under the
+//! view, or the current location when nothing does) and the camera slowly
+//! circles it at a fixed radius and height, looking at it. The point becomes
+//! an anchor entity with a [`WorldPosition`], and the orbit itself runs as the
+//! [`FollowStyle::Cinematic`] style of FollowEntity mode, so entering and
+//! leaving goes through the regular mode machine (returning to flycam or the
+//! first-person controller as appropriate).
+//!
+//! While orbiting, the terrain [`LodFocus`] keeps the area around the point
+//! loaded, so the far side doesn't re-stream on every revolution. On native,
+//! one revolution can also be recorded as a numbered PNG sequence at a fixed
+//! frame rate, stepping the orbit per captured frame rather than by wall-clock
+//! time so the footage is smooth regardless of how fast frames render.
+
+use std::path::PathBuf;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use glam::DVec3;
+use leafwing_input_manager::prelude::*;
+
+use veldera_game_input::CameraAction;
+use veldera_geo::{
+    coords::RadialFrame,
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+use veldera_physics::{GameLayer, PhysicsState};
+use veldera_terrain::lod::LodFocus;
+
+use super::{
+    CameraModeState, CameraModeTransitions, FollowEntityTarget, FollowExitAnchor, FollowStyle,
+};
+
+// ============================================================================
+// Plugin
+// ============================================================================
+
+/// Plugin for the cinematic point-of-interest orbit.
+pub(super) struct CinematicOrbitPlugin;
+
+impl Plugin for CinematicOrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CinematicOrbitSettings>()
+            .init_resource::<CinematicOrbitRequest>()
+            // Requests spawn the anchor and queue the transition before the
+            // mode machine runs, so the anchor is already targeted by the
+            // time unused anchors are swept.
+            .add_systems(
+                Update,
+                (cinematic_orbit_hotkey, process_cinematic_orbit_request)
+                    .chain()
+                    .before(super::process_mode_transitions),
+            )
+            .add_systems(
+                Update,
+                (
+                    cinematic_orbit_system.run_if(is_follow_entity_mode),
+                    sync_lod_focus,
+                    despawn_unused_anchors,
+                )
+                    .chain()
+                    .after(super::process_mode_transitions),
+            );
+    }
+}
+
+/// Run condition: FollowEntity mode is active.
+fn is_follow_entity_mode(state: Res<CameraModeState>) -> bool {
+    state.is_follow_entity()
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Shape and pace of the cinematic orbit, editable from the debug UI.
+#[derive(Resource, Clone, Debug)]
+pub struct CinematicOrbitSettings {
+    /// Horizontal distance from the point of interest (m).
+    pub radius: f32,
+    /// Height of the camera above the point of interest (m).
+    pub height: f32,
+    /// Angular speed around the point (degrees per second).
+    pub speed_deg: f32,
+    /// Frame rate of recorded revolutions (frames per second of footage).
+    pub record_fps: u32,
+}
+
+impl Default for CinematicOrbitSettings {
+    fn default() -> Self {
+        Self {
+            radius: 600.0,
+            height: 300.0,
+            speed_deg: 6.0,
+            record_fps: 30,
+        }
+    }
+}
+
+impl CinematicOrbitSettings {
+    /// Smallest orbit radius the UI offers (m).
+    pub const MIN_RADIUS: f32 = 20.0;
+    /// Largest orbit radius the UI offers (m).
+    pub const MAX_RADIUS: f32 = 20_000.0;
+}
+
+/// Pending cinematic orbit requests, from the hotkey or the UI.
+#[derive(Resource, Default)]
+pub struct CinematicOrbitRequest {
+    /// Start (or re-centre) an orbit around the point under the view.
+    start: bool,
+    /// Record one revolution of the running orbit.
+    record: bool,
+}
+
+impl CinematicOrbitRequest {
+    /// Request an orbit around the point under the view, or the current
+    /// location if the view isn't on the ground.
+    pub fn request_orbit(&mut self) {
+        self.start = true;
+    }
+
+    /// Request a recording of one revolution of the running orbit.
+    pub fn request_recording(&mut self) {
+        self.record = true;
+    }
+}
+
+/// Marker for the anchor entity a cinematic orbit circles.
+///
+/// Spawned when an orbit starts and despawned once no camera targets it.
+#[derive(Component, Debug)]
+pub struct PointOfInterest;
+
+/// Progress of a cinematic orbit, on the camera entity.
+///
+/// Added on the first frame of the orbit, seeded from the camera's bearing to
+/// the point so the camera swings in from where it was, and removed when
+/// follow mode ends.
+#[derive(Component, Debug)]
+pub struct CinematicOrbit {
+    /// Bearing from the point to the camera, clockwise from north (radians).
+    pub angle: f32,
+    /// The revolution being recorded, if any.
+    recording: Option<OrbitRecording>,
+}
+
+impl CinematicOrbit {
+    /// Whether a revolution is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Recording progress as `(frames captured, frames in the revolution)`.
+    pub fn recording_progress(&self) -> Option<(u32, u32)> {
+        self.recording.as_ref().map(|r| (r.frame, r.frames))
+    }
+}
+
+/// A revolution being captured to disk.
+#[derive(Debug)]
+struct OrbitRecording {
+    /// Directory the frames are written to.
+    directory: PathBuf,
+    /// Next frame number.
+    frame: u32,
+    /// Frames in one full revolution.
+    frames: u32,
+}
+
+// ============================================================================
+// Requests
+// ============================================================================
+
+/// Toggle the orbit with the hotkey: leave a running cinematic orbit, or
+/// start one around the point under the view.
+fn cinematic_orbit_hotkey(
+    action_query: Query<&ActionState<CameraAction>>,
+    follow_query: Query<&FollowEntityTarget>,
+    mut transitions: ResMut<CameraModeTransitions>,
+    mut request: ResMut<CinematicOrbitRequest>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if !action_state.just_pressed(&CameraAction::CinematicOrbit) {
+        return;
+    }
+
+    if follow_query
+        .iter()
+        .any(|follow| follow.style == FollowStyle::Cinematic)
+    {
+        transitions.request_exit();
+    } else {
+        request.request_orbit();
+    }
+}
+
+/// Start orbits and recordings requested through [`CinematicOrbitRequest`].
+///
+/// Starting from first person leaves an exit anchor at the player's eye, so
+/// leaving the orbit puts the player back where they stood rather than
+/// dropping them from the orbit height. Chase cameras (driving) are left
+/// alone: the vehicle owns that mode.
+#[allow(clippy::too_many_arguments)]
+fn process_cinematic_orbit_request(
+    mut commands: Commands,
+    mut request: ResMut<CinematicOrbitRequest>,
+    mut transitions: ResMut<CameraModeTransitions>,
+    mut exit_anchor: ResMut<FollowExitAnchor>,
+    state: Res<CameraModeState>,
+    settings: Res<CinematicOrbitSettings>,
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
+    mut camera_query: Query<(
+        &FloatingOriginCamera,
+        &Transform,
+        Option<&FollowEntityTarget>,
+        Option<&mut CinematicOrbit>,
+    )>,
+) {
+    let Ok((camera, transform, follow, orbit)) = camera_query.single_mut() else {
+        return;
+    };
+
+    if std::mem::take(&mut request.record) {
+        match orbit {
+            Some(mut orbit) if !orbit.is_recording() => start_recording(&mut orbit, &settings),
+            Some(_) => tracing::warn!("Already recording the cinematic orbit"),
+            None => tracing::warn!("Recording needs a running cinematic orbit"),
+        }
+    }
+
+    if !std::mem::take(&mut request.start) {
+        return;
+    }
+    if follow
+        .is_some_and(|follow| matches!(follow.style, FollowStyle::Chase | FollowStyle::Passenger))
+    {
+        tracing::warn!("Leave the vehicle before starting a cinematic orbit");
+        return;
+    }
+    if state.is_fps_controller() {
+        exit_anchor.0 = Some(camera.position);
+    }
+
+    let center = pick_orbit_center(
+        &spatial_query,
+        &physics_state,
+        camera.position,
+        transform.forward(),
+        f64::from(settings.height),
+    );
+    let anchor = commands
+        .spawn((
+            Name::new("Point of interest"),
+            PointOfInterest,
+            WorldPosition::from_dvec3(center),
+        ))
+        .id();
+    transitions.request_cinematic_orbit(anchor);
+}
+
+/// The ground point to orbit: where the view ray meets the ground, else the
+/// ground straight below the camera, else `fallback_depth` below the camera.
+///
+/// Ground colliders only exist near the camera, so a view aimed at distant
+/// terrain (or the sky) falls through to the current location.
+fn pick_orbit_center(
+    spatial_query: &SpatialQuery,
+    physics_state: &PhysicsState,
+    camera: DVec3,
+    forward: Dir3,
+    fallback_depth: f64,
+) -> DVec3 {
+    /// Longest ray cast when looking for ground (m).
+    const MAX_PICK_DISTANCE: f32 = 20_000.0;
+
+    let up = camera.normalize();
+    if let Some(origin) = physics_state.origin_camera_position() {
+        let start = (camera - origin).as_vec3();
+        let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground]);
+        let down = Dir3::new(-up.as_vec3()).unwrap_or(Dir3::NEG_Y);
+        for direction in [forward, down] {
+            if let Some(hit) =
+                spatial_query.cast_ray(start, direction, MAX_PICK_DISTANCE, true, &filter)
+            {
+                return origin + (start + *direction * hit.distance).as_dvec3();
+            }
+        }
+    }
+    camera - up * fallback_depth
+}
+
+/// Begin capturing one revolution into a fresh directory.
+#[cfg(not(target_family = "wasm"))]
+fn start_recording(orbit: &mut CinematicOrbit, settings: &CinematicOrbitSettings) {
+    let step_deg = settings.speed_deg.abs() / settings.record_fps.max(1) as f32;
+    if step_deg <= 0.0 {
+        tracing::warn!("Cannot record a cinematic orbit with zero speed");
+        return;
+    }
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let directory = PathBuf::from("recordings").join(format!("orbit-{stamp}"));
+    if let Err(e) = std::fs::create_dir_all(&directory) {
+        tracing::warn!("Failed to create {}: {e}", directory.display());
+        return;
+    }
+    let frames = (360.0 / step_deg).ceil() as u32;
+    tracing::info!("Recording {frames} orbit frames to {}", directory.display());
+    orbit.recording = Some(OrbitRecording {
+        directory,
+        frame: 0,
+        frames,
+    });
+}
+
+/// Recording writes to disk, which the web build can't.
+#[cfg(target_family = "wasm")]
+fn start_recording(_orbit: &mut CinematicOrbit, _settings: &CinematicOrbitSettings) {
+    tracing::warn!("Orbit recording is not available on the web");
+}
+
+// ============================================================================
+// Camera system
+// ============================================================================
+
+/// Circle the point of interest, looking at it, and capture a frame when
+/// recording.
+fn cinematic_orbit_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<CinematicOrbitSettings>,
+    mut camera_query: Query<(
+        Entity,
+        &mut FloatingOriginCamera,
+        &mut Transform,
+        &FollowEntityTarget,
+        Option<&mut CinematicOrbit>,
+    )>,
+    target_query: Query<&WorldPosition>,
+) {
+    for (camera_entity, mut camera, mut camera_transform, follow_target, orbit) in &mut camera_query
+    {
+        if follow_target.style != FollowStyle::Cinematic {
+            continue;
+        }
+        let Ok(target) = target_query.get(follow_target.target) else {
+            continue;
+        };
+        let frame = RadialFrame::from_ecef_position(target.position);
+
+        let Some(mut orbit) = orbit else {
+            // Start on the camera's side of the point.
+            let offset = camera.position - target.position;
+            let angle = offset
+                .dot(frame.east.as_dvec3())
+                .atan2(offset.dot(frame.north.as_dvec3())) as f32;
+            commands.entity(camera_entity).insert(CinematicOrbit {
+                angle,
+                recording: None,
+            });
+            continue;
+        };
+
+        // A recording steps by one footage frame per rendered frame.
+        let step = if orbit.is_recording() {
+            settings.speed_deg / settings.record_fps.max(1) as f32
+        } else {
+            settings.speed_deg * time.delta_secs()
+        };
+        orbit.angle = (orbit.angle + step.to_radians()).rem_euclid(std::f32::consts::TAU);
+
+        let (sin, cos) = orbit.angle.sin_cos();
+        let horizontal = frame.east * sin + frame.north * cos;
+        let offset = horizontal * settings.radius + frame.up * settings.height;
+        camera.position = target.position + offset.as_dvec3();
+
+        // Camera transform stays at origin (floating origin system).
+        camera_transform.translation = Vec3::ZERO;
+        if let Ok(look_direction) = Dir3::new(-offset) {
+            camera_transform.rotation = Transform::default()
+                .looking_to(look_direction, frame.up)
+                .rotation;
+        }
+
+        capture_frame(&mut commands, &mut orbit);
+    }
+}
+
+/// Save the next rendered frame of a recording, ending it after one
+/// revolution.
+#[cfg(not(target_family = "wasm"))]
+fn capture_frame(commands: &mut Commands, orbit: &mut CinematicOrbit) {
+    use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+    let Some(recording) = orbit.recording.as_mut() else {
+        return;
+    };
+    let path = recording
+        .directory
+        .join(format!("frame_{:05}.png", recording.frame));
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path));
+    recording.frame += 1;
+    if recording.frame >= recording.frames {
+        tracing::info!(
+            "Recorded {} orbit frames to {}",
+            recording.frames,
+            recording.directory.display()
+        );
+        orbit.recording = None;
+    }
+}
+
+/// Recording is native-only; see [`start_recording`].
+#[cfg(target_family = "wasm")]
+fn capture_frame(_commands: &mut Commands, _orbit: &mut CinematicOrbit) {}
+
+// ============================================================================
+// Housekeeping
+// ============================================================================
+
+/// Keep the terrain around the orbited point loaded while a cinematic orbit
+/// runs, and release it afterwards.
+///
+/// Only clears a focus this system set (tracked in `owned`), so other users of
+/// [`LodFocus`] aren't stomped on while no orbit is running.
+fn sync_lod_focus(
+    settings: Res<CinematicOrbitSettings>,
+    mut focus: ResMut<LodFocus>,
+    mut owned: Local<bool>,
+    camera_query: Query<&FollowEntityTarget>,
+    target_query: Query<&WorldPosition>,
+) {
+    let point = camera_query
+        .iter()
+        .filter(|follow| follow.style == FollowStyle::Cinematic)
+        .find_map(|follow| target_query.get(follow.target).ok())
+        .map(|target| target.position);
+    match point {
+        Some(point) => {
+            // Cover the whole ring the camera sweeps, plus some margin for
+            // the terrain it looks across.
+            focus.set_if_neq(LodFocus {
+                point: Some(point),
+                radius: f64::from(settings.radius) * 1.25,
+            });
+            *owned = true;
+        }
+        None if *owned => {
+            *focus = LodFocus::default();
+            *owned = false;
+        }
+        None => {}
+    }
+}
+
+/// Despawn point-of-interest anchors no camera is orbiting any more (the orbit
+/// ended or moved to a new point).
+fn despawn_unused_anchors(
+    mut commands: Commands,
+    anchor_query: Query<Entity, With<PointOfInterest>>,
+    follow_query: Query<&FollowEntityTarget>,
+) {
+    for anchor in &anchor_query {
+        if !follow_query.iter().any(|follow| follow.target == anchor) {
+            commands.entity(anchor).despawn();
+        }
+    }
+}
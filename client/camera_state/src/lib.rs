@@ -14,7 +14,7 @@ use bevy::prelude::*;
 ///
 /// Use [`CameraModeTransitions`] to change modes rather than modifying
 /// [`CameraModeState`] directly.
-#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, serde::Deserialize)]
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(not(target_family = "wasm"), derive(clap::ValueEnum))]
 pub enum CameraMode {
     /// Free-flight camera (default).
@@ -27,6 +27,32 @@ pub enum CameraMode {
     FollowEntity,
 }
 
+/// How the camera frames its target in FollowEntity mode.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FollowStyle {
+    /// Chase camera at the target's configured offsets, turning with it.
+    #[default]
+    Chase,
+    /// Spectator orbit around the target, with mouse-controlled yaw, pitch,
+    /// and distance independent of the target's heading.
+    Orbit,
+    /// Hands-off showcase orbit that slowly circles the target at a fixed
+    /// radius and height, typically around a point-of-interest anchor rather
+    /// than a moving entity.
+    Cinematic,
+    /// Riding along in the target's passenger seat, turning with it, with
+    /// free mouse look and no control over it.
+    Passenger,
+}
+
+/// Marker for entities worth offering as spectator-camera targets (vehicles,
+/// projectiles, remote players).
+///
+/// Purely a discovery hint for UI: the orbit camera can follow any entity with
+/// a `WorldPosition`, marked or not.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Spectatable;
+
 /// Camera mode state machine.
 ///
 /// Tracks the current mode and the mode to return to when exiting FollowEntity.
@@ -105,10 +131,39 @@ impl CameraModeTransitions {
         self.pending.push(CameraModeTransition::ToFpsController);
     }
 
-    /// Request transition to FollowEntity mode.
+    /// Request transition to FollowEntity mode with the chase camera.
     pub fn request_follow_entity(&mut self, target: Entity) {
-        self.pending
-            .push(CameraModeTransition::ToFollowEntity { target });
+        self.pending.push(CameraModeTransition::ToFollowEntity {
+            target,
+            style: FollowStyle::Chase,
+        });
+    }
+
+    /// Request transition to FollowEntity mode with the spectator orbit
+    /// camera. `target` needs only a `WorldPosition`.
+    pub fn request_orbit_entity(&mut self, target: Entity) {
+        self.pending.push(CameraModeTransition::ToFollowEntity {
+            target,
+            style: FollowStyle::Orbit,
+        });
+    }
+
+    /// Request transition to FollowEntity mode with the cinematic orbit.
+    /// `target` needs only a `WorldPosition`.
+    pub fn request_cinematic_orbit(&mut self, target: Entity) {
+        self.pending.push(CameraModeTransition::ToFollowEntity {
+            target,
+            style: FollowStyle::Cinematic,
+        });
+    }
+
+    /// Request transition to FollowEntity mode riding in `target`'s
+    /// passenger seat, leaving the driving to whatever else controls it.
+    pub fn request_passenger(&mut self, target: Entity) {
+        self.pending.push(CameraModeTransition::ToFollowEntity {
+            target,
+            style: FollowStyle::Passenger,
+        });
     }
 
     /// Request to exit the current mode (returns to previous mode from FollowEntity).
@@ -134,6 +189,8 @@ pub enum CameraModeTransition {
     ToFollowEntity {
         /// The entity to follow.
         target: Entity,
+        /// How the camera frames the target.
+        style: FollowStyle,
     },
     /// Exit the current mode.
     ExitCurrentMode,
@@ -0,0 +1,332 @@
+//! Developer console command registry.
+//!
+//! A command is a name, a usage line, a one-line description, and a one-shot
+//! system that takes the command's arguments and returns what to print (or
+//! an error message). Any plugin can add commands with
+//! [`ConsoleAppExt::add_console_command`]; the console UI submits lines with
+//! [`Console::submit`] and shows [`Console::log`]. Submitted lines run in
+//! `Update`, in order, each with the world to itself.
+//!
+//! Other front ends can run a line directly with [`run_console_line`].
+//!
+//! Lines split on whitespace; double quotes keep words together as one
+//! argument (`vehicle spawn "monster truck"`).
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use bevy::{ecs::system::SystemId, prelude::*};
+
+/// What a command prints on success (nothing if empty), or its error.
+pub type CommandResult = Result<String, String>;
+
+/// Most lines the console log keeps.
+const MAX_LOG_LINES: usize = 500;
+
+/// Most submitted lines the history keeps.
+const MAX_HISTORY: usize = 100;
+
+/// Plugin for the console command registry, with the built-in `help` and
+/// `clear` commands.
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .add_console_command(
+                "help",
+                "help [command]",
+                "List the commands, or show one's usage",
+                help_command,
+            )
+            .add_console_command("clear", "clear", "Clear the console", clear_command)
+            .add_systems(Update, run_console_commands);
+    }
+}
+
+/// Registering console commands on an [`App`].
+pub trait ConsoleAppExt {
+    /// Add the command `name`, run by `system` with the line's remaining
+    /// words. Replaces any command already registered under `name`.
+    fn add_console_command<M>(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        description: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, CommandResult, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command<M>(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        description: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, CommandResult, M> + 'static,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        let system = world.register_system(system);
+        world
+            .get_resource_or_init::<ConsoleCommands>()
+            .commands
+            .insert(
+                name,
+                ConsoleCommand {
+                    usage,
+                    description,
+                    system,
+                },
+            );
+        self
+    }
+}
+
+/// A registered command.
+struct ConsoleCommand {
+    usage: &'static str,
+    description: &'static str,
+    system: SystemId<In<Vec<String>>, CommandResult>,
+}
+
+/// Every registered command, by name.
+#[derive(Resource, Default)]
+pub struct ConsoleCommands {
+    commands: BTreeMap<&'static str, ConsoleCommand>,
+}
+
+impl ConsoleCommands {
+    /// Registered command names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.commands.keys().copied()
+    }
+}
+
+/// What a console log line is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleLineKind {
+    /// A submitted line, echoed.
+    Input,
+    /// A command's output.
+    Output,
+    /// A command's error.
+    Error,
+}
+
+/// A line in the console log.
+#[derive(Clone, Debug)]
+pub struct ConsoleLine {
+    pub kind: ConsoleLineKind,
+    pub text: String,
+}
+
+/// The console's log, history, and the lines waiting to run.
+#[derive(Resource, Default)]
+pub struct Console {
+    log: Vec<ConsoleLine>,
+    history: Vec<String>,
+    pending: Vec<String>,
+}
+
+impl Console {
+    /// Queue `line` to run this frame and add it to the history.
+    pub fn submit(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last().is_none_or(|last| last != line) {
+            self.history.push(line.to_string());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.pending.push(line.to_string());
+    }
+
+    /// Everything printed so far, oldest first.
+    pub fn log(&self) -> &[ConsoleLine] {
+        &self.log
+    }
+
+    /// Submitted lines, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Append to the log; a multi-line `text` is split into lines.
+    pub fn print(&mut self, kind: ConsoleLineKind, text: &str) {
+        self.log.extend(text.lines().map(|line| ConsoleLine {
+            kind,
+            text: line.to_string(),
+        }));
+        let excess = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..excess);
+    }
+}
+
+/// Parse argument `index` of a command as a `T`, naming it `what` in errors.
+pub fn parse_arg<T: FromStr>(args: &[String], index: usize, what: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("Missing {what}"))?;
+    arg.parse().map_err(|_| format!("Invalid {what} '{arg}'"))
+}
+
+/// Split a console line into words, keeping double-quoted runs together.
+fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Run the submitted lines, printing each one and its result.
+fn run_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    for line in pending {
+        world
+            .resource_mut::<Console>()
+            .print(ConsoleLineKind::Input, &format!("> {line}"));
+        let result = run_console_line(world, &line);
+        let mut console = world.resource_mut::<Console>();
+        match result {
+            Ok(output) => console.print(ConsoleLineKind::Output, &output),
+            Err(error) => console.print(ConsoleLineKind::Error, &error),
+        }
+    }
+}
+
+/// Run `line` now, without logging it or adding it to the history. For
+/// callers other than the console UI, such as the remote-control server.
+pub fn run_console_line(world: &mut World, line: &str) -> CommandResult {
+    let mut words = split_line(line)?;
+    if words.is_empty() {
+        return Ok(String::new());
+    }
+    let name = words.remove(0);
+    let Some(system) = world
+        .resource::<ConsoleCommands>()
+        .commands
+        .get(name.as_str())
+        .map(|command| command.system)
+    else {
+        return Err(format!("Unknown command '{name}'; try 'help'"));
+    };
+    world
+        .run_system_with(system, words)
+        .map_err(|e| format!("{name}: {e}"))?
+}
+
+fn help_command(In(args): In<Vec<String>>, commands: Res<ConsoleCommands>) -> CommandResult {
+    if let Some(name) = args.first() {
+        let command = commands
+            .commands
+            .get(name.as_str())
+            .ok_or_else(|| format!("Unknown command '{name}'"))?;
+        return Ok(format!("{}\n  {}", command.usage, command.description));
+    }
+    let width = commands
+        .commands
+        .values()
+        .map(|command| command.usage.len())
+        .max()
+        .unwrap_or(0);
+    Ok(commands
+        .commands
+        .values()
+        .map(|command| format!("{:width$}  {}", command.usage, command.description))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn clear_command(In(_): In<Vec<String>>, mut console: ResMut<Console>) -> CommandResult {
+    console.log.clear();
+    Ok(String::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(
+            split_line("  tp 51.5  -0.12 ").unwrap(),
+            ["tp", "51.5", "-0.12"]
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_words_together() {
+        assert_eq!(
+            split_line(r#"vehicle spawn "monster truck""#).unwrap(),
+            ["vehicle", "spawn", "monster truck"]
+        );
+        assert_eq!(split_line(r#"say """#).unwrap(), ["say", ""]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!(split_line(r#"vehicle spawn "monster"#).is_err());
+    }
+
+    #[test]
+    fn runs_registered_commands() {
+        fn echo(In(args): In<Vec<String>>) -> CommandResult {
+            Ok(args.join(" "))
+        }
+
+        let mut app = App::new();
+        app.add_plugins(ConsolePlugin).add_console_command(
+            "echo",
+            "echo <words>",
+            "Print the words",
+            echo,
+        );
+        let mut console = app.world_mut().resource_mut::<Console>();
+        console.submit("echo hello \"big world\"");
+        console.submit("nope");
+        app.update();
+
+        let log: Vec<_> = app
+            .world()
+            .resource::<Console>()
+            .log()
+            .iter()
+            .map(|line| (line.kind, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            log,
+            [
+                (ConsoleLineKind::Input, "> echo hello \"big world\""),
+                (ConsoleLineKind::Output, "hello big world"),
+                (ConsoleLineKind::Input, "> nope"),
+                (ConsoleLineKind::Error, "Unknown command 'nope'; try 'help'"),
+            ]
+        );
+    }
+}
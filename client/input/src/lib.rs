@@ -3,6 +3,10 @@
 //! Defines all gameplay actions using `leafwing-input-manager` for declarative,
 //! rebindable input mapping. Provides a single system that manages input focus
 //! based on UI state and cursor grab, replacing scattered run conditions.
+//! Button actions can be rebound through [`CameraBindings`], which the client
+//! persists in its user settings.
+
+use std::collections::BTreeMap;
 
 use bevy::{
     prelude::*,
@@ -10,6 +14,7 @@ use bevy::{
 };
 use bevy_egui::{EguiContexts, EguiGlobalSettings, EguiInputSystemSettings};
 use leafwing_input_manager::{plugin::InputManagerSystem, prelude::*};
+use serde::{Deserialize, Serialize};
 use veldera_input::{InputIntentPlugin, LookIntent, MovementIntent, ZoomIntent};
 
 // ============================================================================
@@ -19,7 +24,20 @@ use veldera_input::{InputIntentPlugin, LookIntent, MovementIntent, ZoomIntent};
 /// Actions for camera and general player control.
 ///
 /// Shared by flycam, FPS controller, and general camera systems.
-#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+#[derive(
+    Actionlike,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    Reflect,
+    Serialize,
+    Deserialize,
+)]
 pub enum CameraAction {
     /// WASD movement (forward/back/strafe).
     #[actionlike(DualAxis)]
@@ -49,46 +67,168 @@ pub enum CameraAction {
     AdjustSpeed,
     /// Enter/exit vehicle (E).
     InteractVehicle,
+    /// Ride along in the passenger seat of the vehicle in view, or leave it
+    /// (F).
+    RideVehicle,
+    /// Start or leave the cinematic orbit around the point under the view (O).
+    CinematicOrbit,
     /// Fire projectile (left click).
     Fire,
     /// Raise the right arm to point at the look direction (right mouse,
     /// held). Purely cosmetic — the charged yeet lives on a held
     /// [`Ascend`](Self::Ascend).
     Point,
+    /// Drop an annotation on the terrain under the cursor (M).
+    DropAnnotation,
+    /// Cycle the terrain debug view: wireframe, UV checker, texel density,
+    /// overdraw, off (F3).
+    CycleTerrainDebug,
+    /// Open or close the developer console (backtick).
+    ToggleConsole,
+    /// Skip the rest of a teleport flight (Enter).
+    SkipTeleport,
+    /// Fly back to the previous place in the teleport history (`[`, mouse
+    /// back).
+    HistoryBack,
+    /// Fly forward to the next place in the teleport history (`]`, mouse
+    /// forward).
+    HistoryForward,
 }
 
 /// Actions for vehicle control.
-#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect)]
+#[derive(Actionlike, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
 pub enum VehicleAction {
     /// Drive input (WASD: throttle/brake on Y, steering on X).
     #[actionlike(DualAxis)]
     Drive,
     /// Handbrake (Space).
     Handbrake,
+    /// Respawn on the nearest safe ground, repaired (R).
+    Respawn,
 }
 
 // ============================================================================
 // Input maps
 // ============================================================================
 
+/// Camera actions the player may rebind, in display order.
+///
+/// Only single-button actions: the movement, look, and speed axes stay on
+/// their defaults, as do the cursor grab/release buttons (rebinding those
+/// away could leave no way to release the mouse).
+pub const REBINDABLE_CAMERA_ACTIONS: &[CameraAction] = &[
+    CameraAction::Ascend,
+    CameraAction::Descend,
+    CameraAction::Sprint,
+    CameraAction::ToggleCameraMode,
+    CameraAction::ToggleUi,
+    CameraAction::InteractVehicle,
+    CameraAction::RideVehicle,
+    CameraAction::CinematicOrbit,
+    CameraAction::DropAnnotation,
+    CameraAction::CycleTerrainDebug,
+    CameraAction::ToggleConsole,
+    CameraAction::SkipTeleport,
+    CameraAction::HistoryBack,
+    CameraAction::HistoryForward,
+    CameraAction::Fire,
+    CameraAction::Point,
+];
+
+/// A key or mouse button bound to a button action.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ButtonBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl ButtonBinding {
+    /// Short human-readable name, e.g. `Q`, `ShiftLeft`, `Mouse Right`.
+    pub fn label(self) -> String {
+        match self {
+            ButtonBinding::Key(key) => {
+                let name = format!("{key:?}");
+                match name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                {
+                    Some(short) if !short.is_empty() => short.to_string(),
+                    _ => name,
+                }
+            }
+            ButtonBinding::Mouse(button) => format!("Mouse {button:?}"),
+        }
+    }
+}
+
+/// Button bindings per camera action. Actions missing from the map use
+/// [`default_camera_bindings`].
+pub type CameraBindings = BTreeMap<CameraAction, Vec<ButtonBinding>>;
+
+/// The default button bindings for camera actions.
+pub fn default_camera_bindings() -> CameraBindings {
+    use ButtonBinding::{Key, Mouse};
+    BTreeMap::from([
+        (CameraAction::Ascend, vec![Key(KeyCode::Space)]),
+        (
+            CameraAction::Descend,
+            vec![Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight)],
+        ),
+        (
+            CameraAction::Sprint,
+            vec![Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)],
+        ),
+        (CameraAction::ToggleCameraMode, vec![Key(KeyCode::KeyN)]),
+        (CameraAction::ToggleUi, vec![Key(KeyCode::KeyQ)]),
+        (CameraAction::InteractVehicle, vec![Key(KeyCode::KeyE)]),
+        (CameraAction::RideVehicle, vec![Key(KeyCode::KeyF)]),
+        (CameraAction::CinematicOrbit, vec![Key(KeyCode::KeyO)]),
+        (CameraAction::DropAnnotation, vec![Key(KeyCode::KeyM)]),
+        (CameraAction::CycleTerrainDebug, vec![Key(KeyCode::F3)]),
+        (CameraAction::ToggleConsole, vec![Key(KeyCode::Backquote)]),
+        (CameraAction::SkipTeleport, vec![Key(KeyCode::Enter)]),
+        (
+            CameraAction::HistoryBack,
+            vec![Key(KeyCode::BracketLeft), Mouse(MouseButton::Back)],
+        ),
+        (
+            CameraAction::HistoryForward,
+            vec![Key(KeyCode::BracketRight), Mouse(MouseButton::Forward)],
+        ),
+        (CameraAction::Fire, vec![Mouse(MouseButton::Left)]),
+        (CameraAction::Point, vec![Mouse(MouseButton::Right)]),
+        (CameraAction::GrabCursor, vec![Mouse(MouseButton::Left)]),
+        (CameraAction::ReleaseCursor, vec![Key(KeyCode::Escape)]),
+    ])
+}
+
 /// Create the default input map for camera actions.
 pub fn default_camera_input_map() -> InputMap<CameraAction> {
-    InputMap::default()
+    camera_input_map(&CameraBindings::new())
+}
+
+/// Create the input map for camera actions, with `overrides` replacing the
+/// default buttons of the actions they list.
+pub fn camera_input_map(overrides: &CameraBindings) -> InputMap<CameraAction> {
+    let mut map = InputMap::default()
         .with_dual_axis(CameraAction::Move, VirtualDPad::wasd())
         .with_dual_axis(CameraAction::Look, MouseMove::default())
-        .with(CameraAction::Ascend, KeyCode::Space)
-        .with(CameraAction::Descend, KeyCode::ControlLeft)
-        .with(CameraAction::Descend, KeyCode::ControlRight)
-        .with(CameraAction::Sprint, KeyCode::ShiftLeft)
-        .with(CameraAction::Sprint, KeyCode::ShiftRight)
-        .with(CameraAction::ToggleCameraMode, KeyCode::KeyN)
-        .with(CameraAction::ToggleUi, KeyCode::KeyQ)
-        .with_axis(CameraAction::AdjustSpeed, MouseScrollAxis::Y)
-        .with(CameraAction::InteractVehicle, KeyCode::KeyE)
-        .with(CameraAction::Fire, MouseButton::Left)
-        .with(CameraAction::Point, MouseButton::Right)
-        .with(CameraAction::GrabCursor, MouseButton::Left)
-        .with(CameraAction::ReleaseCursor, KeyCode::Escape)
+        .with_axis(CameraAction::AdjustSpeed, MouseScrollAxis::Y);
+    let mut bindings = default_camera_bindings();
+    bindings.extend(
+        overrides
+            .iter()
+            .map(|(action, buttons)| (*action, buttons.clone())),
+    );
+    for (action, buttons) in bindings {
+        for button in buttons {
+            match button {
+                ButtonBinding::Key(key) => map.insert(action, key),
+                ButtonBinding::Mouse(mouse) => map.insert(action, mouse),
+            };
+        }
+    }
+    map
 }
 
 /// Create the default input map for vehicle actions.
@@ -96,6 +236,7 @@ pub fn default_vehicle_input_map() -> InputMap<VehicleAction> {
     InputMap::default()
         .with_dual_axis(VehicleAction::Drive, VirtualDPad::wasd())
         .with(VehicleAction::Handbrake, KeyCode::Space)
+        .with(VehicleAction::Respawn, KeyCode::KeyR)
 }
 
 // ============================================================================
@@ -105,6 +246,19 @@ pub fn default_vehicle_input_map() -> InputMap<VehicleAction> {
 /// Plugin that registers input action types and the input focus management system.
 pub struct InputPlugin;
 
+/// Per-frame input systems, in `PreUpdate` after leafwing's update.
+///
+/// Systems that drive the action states themselves (e.g. input playback) run
+/// between [`Focus`](Self::Focus) and [`Intents`](Self::Intents), so focus
+/// gating can't disable them and the engine intents still see them.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameInputSet {
+    /// Focus gating of the action states (by UI focus and cursor grab).
+    Focus,
+    /// The camera action state mapped onto the engine's input intents.
+    Intents,
+}
+
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<CameraAction>::default())
@@ -113,11 +267,19 @@ impl Plugin for InputPlugin {
             // onto them each frame (after focus gating, so disabled actions
             // produce no intent).
             .add_plugins(InputIntentPlugin)
-            .add_systems(
+            .init_resource::<PointerPicking>()
+            .configure_sets(
                 PreUpdate,
-                (manage_input_focus, populate_camera_intents)
+                (GameInputSet::Focus, GameInputSet::Intents)
                     .chain()
                     .after(InputManagerSystem::Update),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    manage_input_focus.in_set(GameInputSet::Focus),
+                    populate_camera_intents.in_set(GameInputSet::Intents),
+                ),
             );
     }
 }
@@ -181,6 +343,11 @@ pub fn set_cursor_grab(cursor: &mut CursorOptions, window: &mut Window, grabbed:
 // Input focus management
 // ============================================================================
 
+/// Set while a picking tool (e.g. the node inspector) owns left clicks on the
+/// world: with the cursor free, a click picks instead of grabbing the cursor.
+#[derive(Resource, Default)]
+pub struct PointerPicking(pub bool);
+
 /// Keyboard-bound gameplay actions that should be disabled when egui wants keyboard input.
 const KEYBOARD_ACTIONS: &[CameraAction] = &[
     CameraAction::Move,
@@ -189,6 +356,19 @@ const KEYBOARD_ACTIONS: &[CameraAction] = &[
     CameraAction::Sprint,
     CameraAction::ToggleCameraMode,
     CameraAction::InteractVehicle,
+    CameraAction::RideVehicle,
+    CameraAction::CinematicOrbit,
+];
+
+/// Keyboard shortcuts available in every cursor-grab state, but still
+/// suppressed while egui is capturing the keyboard.
+const SHORTCUT_ACTIONS: &[CameraAction] = &[
+    CameraAction::ToggleUi,
+    CameraAction::CycleTerrainDebug,
+    CameraAction::ToggleConsole,
+    CameraAction::SkipTeleport,
+    CameraAction::HistoryBack,
+    CameraAction::HistoryForward,
 ];
 
 /// Mouse-bound gameplay actions that remain active even when egui wants keyboard input.
@@ -208,6 +388,8 @@ const GAMEPLAY_ACTIONS: &[CameraAction] = &[
     CameraAction::Sprint,
     CameraAction::ToggleCameraMode,
     CameraAction::InteractVehicle,
+    CameraAction::RideVehicle,
+    CameraAction::CinematicOrbit,
     // Mouse.
     CameraAction::Look,
     CameraAction::AdjustSpeed,
@@ -233,9 +415,9 @@ fn set_actions(
 ///
 /// Disables keyboard-bound camera actions when egui wants keyboard input,
 /// and disables gameplay actions when the cursor is not grabbed.
-/// `ToggleUi` stays enabled regardless of cursor-grab state, but is still
-/// suppressed while egui is capturing the keyboard (e.g. typing into a search
-/// box). Also gates `bevy_egui`'s own
+/// `ToggleUi` and the other shortcuts stay enabled regardless of cursor-grab
+/// state, but are still suppressed while egui is capturing the keyboard
+/// (e.g. typing into a search box). Also gates `bevy_egui`'s own
 /// input intake — while the cursor is grabbed, egui's pointer and
 /// keyboard systems are turned off so a hidden cursor sitting over a
 /// debug window can't drag it or click buttons.
@@ -245,6 +427,7 @@ fn manage_input_focus(
     mut contexts: EguiContexts,
     mut egui_settings: ResMut<EguiGlobalSettings>,
     cursor: Single<&CursorOptions>,
+    picking: Res<PointerPicking>,
 ) {
     let egui_wants_kb = contexts
         .ctx_mut()
@@ -307,20 +490,22 @@ fn manage_input_focus(
     }
 
     for mut action_state in &mut camera_query {
-        // ToggleUi is available in every cursor-grab state, but still yields to
-        // egui when a widget is capturing the keyboard — otherwise typing "q"
-        // into a search box would hide the UI instead of entering the letter.
-        if egui_wants_kb {
-            action_state.disable_action(&CameraAction::ToggleUi);
-        } else {
-            action_state.enable_action(&CameraAction::ToggleUi);
-        }
+        // Shortcuts are available in every cursor-grab state, but still yield
+        // to egui when a widget is capturing the keyboard — otherwise typing
+        // "q" into a search box would hide the UI instead of entering the
+        // letter.
+        set_actions(&mut action_state, SHORTCUT_ACTIONS, !egui_wants_kb);
 
         if !is_grabbed {
             // When cursor is not grabbed, disable all gameplay actions.
             set_actions(&mut action_state, GAMEPLAY_ACTIONS, false);
-            // Allow grabbing cursor via click.
-            action_state.enable_action(&CameraAction::GrabCursor);
+            // Allow grabbing cursor via click, unless a picking tool wants
+            // the click.
+            if picking.0 {
+                action_state.disable_action(&CameraAction::GrabCursor);
+            } else {
+                action_state.enable_action(&CameraAction::GrabCursor);
+            }
             action_state.disable_action(&CameraAction::ReleaseCursor);
         } else if egui_wants_kb {
             // When cursor is grabbed but egui wants keyboard, disable keyboard actions only.
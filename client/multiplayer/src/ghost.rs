@@ -0,0 +1,280 @@
+//! Remote-player ghosts: the snapshot buffer each peer's states land in, and
+//! the entities that render them.
+//!
+//! States arrive at the sender's rate with network jitter, so a ghost is not
+//! placed at the newest state. Instead each peer keeps a short buffer of
+//! states on the sender's clock, and the ghost is drawn a fixed
+//! interpolation delay in the past, blending between the two states that
+//! bracket that instant. The ghost carries a [`WorldPosition`], so the
+//! floating-origin sync places it camera-relative like any other world
+//! entity.
+
+use std::{collections::VecDeque, net::SocketAddr};
+
+use bevy::prelude::*;
+use glam::DVec3;
+
+use veldera_game_camera::Spectatable;
+use veldera_game_vehicle::ExternalDriver;
+use veldera_geo::floating_origin::WorldPosition;
+
+use crate::protocol::{PeerKind, PeerState};
+
+/// Most snapshots kept per peer; at the default send rate this spans
+/// several seconds, far more than any sensible interpolation delay.
+const MAX_SNAPSHOTS: usize = 64;
+
+/// One received pose on the sender's clock.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Snapshot {
+    /// Sender time (s).
+    pub time: f64,
+    /// ECEF position (m).
+    pub position: DVec3,
+    /// Orientation in ECEF axes.
+    pub rotation: Quat,
+}
+
+/// Time-ordered snapshots from one peer.
+#[derive(Default, Debug)]
+pub(crate) struct SnapshotBuffer {
+    snapshots: VecDeque<Snapshot>,
+}
+
+impl SnapshotBuffer {
+    /// Add a snapshot, dropping it if it is not newer than the latest one
+    /// (UDP may reorder or duplicate datagrams).
+    pub fn push(&mut self, snapshot: Snapshot) -> bool {
+        if self
+            .snapshots
+            .back()
+            .is_some_and(|last| snapshot.time <= last.time)
+        {
+            return false;
+        }
+        self.snapshots.push_back(snapshot);
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+        true
+    }
+
+    /// The pose at sender time `time`.
+    ///
+    /// Blends between the bracketing snapshots; before the first snapshot or
+    /// after the last it holds the nearest one rather than extrapolating, so
+    /// a stalled peer freezes in place instead of drifting off.
+    pub fn sample(&self, time: f64) -> Option<(DVec3, Quat)> {
+        let first = self.snapshots.front()?;
+        if time <= first.time {
+            return Some((first.position, first.rotation));
+        }
+        for (a, b) in self.snapshots.iter().zip(self.snapshots.iter().skip(1)) {
+            if time < b.time {
+                let t = (time - a.time) / (b.time - a.time);
+                return Some((
+                    a.position.lerp(b.position, t),
+                    a.rotation.slerp(b.rotation, t as f32),
+                ));
+            }
+        }
+        let last = self.snapshots.back()?;
+        Some((last.position, last.rotation))
+    }
+
+    /// Drop snapshots no longer needed to sample at or after `time`, keeping
+    /// the one just before it as the lower bracket.
+    pub fn prune_before(&mut self, time: f64) {
+        while self.snapshots.len() > 2 && self.snapshots[1].time <= time {
+            self.snapshots.pop_front();
+        }
+    }
+}
+
+/// A connected remote peer.
+pub(crate) struct RemotePeer {
+    /// The ghost entity drawing this peer.
+    pub entity: Entity,
+    /// Display name from the latest state.
+    pub name: String,
+    /// Embodiment from the latest state.
+    pub kind: PeerKind,
+    /// Received poses.
+    pub buffer: SnapshotBuffer,
+    /// Estimated `local time - sender time` (s): the smallest difference seen,
+    /// i.e. the offset observed over the least-delayed datagram.
+    pub clock_offset: f64,
+    /// Local time the last state arrived (s).
+    pub last_heard: f64,
+    /// Address the latest state came from, which states are sent back to
+    /// until the peer times out.
+    pub address: SocketAddr,
+}
+
+impl RemotePeer {
+    /// Record a received state, returning whether its embodiment changed.
+    pub fn receive(&mut self, state: &PeerState, from: SocketAddr, now: f64) -> bool {
+        self.address = from;
+        self.clock_offset = self.clock_offset.min(now - state.time);
+        self.last_heard = now;
+        self.name.clone_from(&state.name);
+        self.buffer.push(Snapshot {
+            time: state.time,
+            position: state.position(),
+            rotation: state.rotation(),
+        });
+        let changed = self.kind != state.kind;
+        self.kind = state.kind;
+        changed
+    }
+}
+
+/// Marker on the entity rendering a remote peer.
+#[derive(Component, Debug)]
+pub struct RemoteGhost {
+    /// The peer's session identifier.
+    pub peer_id: u64,
+}
+
+/// Shared meshes for the two ghost shapes.
+#[derive(Resource)]
+pub(crate) struct GhostMeshes {
+    /// Upright capsule for a peer on foot or flying.
+    pub camera: Handle<Mesh>,
+    /// Car-sized box, longest along local +Z (forward).
+    pub vehicle: Handle<Mesh>,
+}
+
+impl GhostMeshes {
+    /// The mesh for a peer embodied as `kind`.
+    pub fn for_kind(&self, kind: PeerKind) -> Handle<Mesh> {
+        match kind {
+            PeerKind::Camera => self.camera.clone(),
+            PeerKind::Vehicle => self.vehicle.clone(),
+        }
+    }
+}
+
+/// Create the shared ghost meshes on startup.
+pub(crate) fn init_ghost_meshes(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(GhostMeshes {
+        camera: meshes.add(Capsule3d::new(0.35, 1.1)),
+        vehicle: meshes.add(Cuboid::new(1.9, 1.5, 4.5)),
+    });
+}
+
+/// Spawn the ghost entity for a newly heard peer.
+pub(crate) fn spawn_ghost(
+    commands: &mut Commands,
+    meshes: &GhostMeshes,
+    materials: &mut Assets<StandardMaterial>,
+    state: &PeerState,
+) -> Entity {
+    // A stable per-peer tint derived from its identifier.
+    let hue = (state.id % 360) as f32;
+    let color = Color::hsla(hue, 0.8, 0.6, 0.55);
+    let material = materials.add(StandardMaterial {
+        base_color: color,
+        emissive: color.to_linear() * 0.3,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+
+    let mut ghost = commands.spawn((
+        Name::new(format!("Ghost: {}", state.name)),
+        RemoteGhost { peer_id: state.id },
+        Spectatable,
+        MeshMaterial3d(material),
+        Transform::from_rotation(state.rotation()),
+        WorldPosition::from_dvec3(state.position()),
+    ));
+    set_ghost_kind(&mut ghost, meshes, state.kind);
+    ghost.id()
+}
+
+/// Give a ghost the shape of `kind`. A vehicle ghost is driven by its peer's
+/// states, never by local input, so it is marked [`ExternalDriver`] for as
+/// long as the peer stays in the car, even while the player follows or rides
+/// in it.
+pub(crate) fn set_ghost_kind(ghost: &mut EntityCommands, meshes: &GhostMeshes, kind: PeerKind) {
+    ghost.insert(Mesh3d(meshes.for_kind(kind)));
+    match kind {
+        PeerKind::Vehicle => ghost.insert(ExternalDriver),
+        PeerKind::Camera => ghost.remove::<ExternalDriver>(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(time: f64, x: f64) -> Snapshot {
+        Snapshot {
+            time,
+            position: DVec3::new(x, 0.0, 0.0),
+            rotation: Quat::IDENTITY,
+        }
+    }
+
+    #[test]
+    fn interpolates_between_brackets_and_holds_at_ends() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(1.0, 0.0));
+        buffer.push(snapshot(2.0, 10.0));
+
+        assert_eq!(buffer.sample(0.5).unwrap().0.x, 0.0);
+        assert!((buffer.sample(1.25).unwrap().0.x - 2.5).abs() < 1e-9);
+        assert_eq!(buffer.sample(3.0).unwrap().0.x, 10.0);
+    }
+
+    #[test]
+    fn vehicle_ghosts_are_externally_driven() {
+        let mut world = World::new();
+        let meshes = GhostMeshes {
+            camera: Handle::default(),
+            vehicle: Handle::default(),
+        };
+        let mut materials = Assets::<StandardMaterial>::default();
+        let mut spawn = |kind| {
+            let state = PeerState {
+                version: crate::protocol::PROTOCOL_VERSION,
+                id: 1,
+                name: "peer".into(),
+                time: 0.0,
+                position: [0.0; 3],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                kind,
+                vehicle: None,
+            };
+            let ghost = spawn_ghost(&mut world.commands(), &meshes, &mut materials, &state);
+            world.flush();
+            world.entity(ghost).contains::<ExternalDriver>()
+        };
+        assert!(spawn(PeerKind::Vehicle));
+        assert!(!spawn(PeerKind::Camera));
+
+        // The peer leaving its car hands the ghost back to nobody.
+        let ghost = world.spawn(ExternalDriver).id();
+        set_ghost_kind(
+            &mut world.commands().entity(ghost),
+            &meshes,
+            PeerKind::Camera,
+        );
+        world.flush();
+        assert!(!world.entity(ghost).contains::<ExternalDriver>());
+    }
+
+    #[test]
+    fn drops_stale_snapshots_and_prunes_behind_render_time() {
+        let mut buffer = SnapshotBuffer::default();
+        assert!(buffer.push(snapshot(1.0, 0.0)));
+        assert!(buffer.push(snapshot(2.0, 1.0)));
+        assert!(!buffer.push(snapshot(1.5, 5.0)));
+        assert!(buffer.push(snapshot(3.0, 2.0)));
+
+        buffer.prune_before(2.5);
+        assert_eq!(buffer.snapshots.len(), 2);
+        assert!((buffer.sample(2.5).unwrap().0.x - 1.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,135 @@
+//! Return-address verification, so ghost mode can't be turned into a UDP
+//! reflector.
+//!
+//! States are only ever sent to configured peers and to addresses that have
+//! proved they receive what is sent there. A state from any other address
+//! gets a [`Message::Challenge`](crate::protocol::Message::Challenge) with a
+//! random nonce instead, at most once per outstanding challenge, and the
+//! address is verified once a response echoes that nonce. A sender spoofing
+//! someone else's address never sees the nonce, so the spoofed address gets
+//! one challenge, smaller than the state that provoked it, and nothing more.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+};
+
+/// Verified addresses and the challenges still outstanding for the rest.
+#[derive(Default, Debug)]
+pub(crate) struct Handshakes {
+    /// Addresses that echoed a challenge.
+    verified: HashSet<SocketAddr>,
+    /// Outstanding challenges: nonce and the local time they were sent (s).
+    pending: HashMap<SocketAddr, (u64, f64)>,
+}
+
+impl Handshakes {
+    /// Whether `address` has answered a challenge.
+    pub fn is_verified(&self, address: SocketAddr) -> bool {
+        self.verified.contains(&address)
+    }
+
+    /// Verified addresses, which states are sent to.
+    pub fn verified(&self) -> impl Iterator<Item = &SocketAddr> {
+        self.verified.iter()
+    }
+
+    /// Start a challenge for `address` with `nonce`, returning the nonce to
+    /// send, or `None` if one is already outstanding. At most `max_pending`
+    /// challenges are kept; the oldest is dropped to make room.
+    pub fn challenge(
+        &mut self,
+        address: SocketAddr,
+        nonce: u64,
+        now: f64,
+        max_pending: usize,
+    ) -> Option<u64> {
+        if self.pending.contains_key(&address) {
+            return None;
+        }
+        while self.pending.len() >= max_pending.max(1) {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by(|a, b| a.1.1.total_cmp(&b.1.1))
+                .map(|(address, _)| *address)?;
+            self.pending.remove(&oldest);
+        }
+        self.pending.insert(address, (nonce, now));
+        Some(nonce)
+    }
+
+    /// Verify `address` if `nonce` answers its outstanding challenge.
+    /// Returns whether it did.
+    pub fn answer(&mut self, address: SocketAddr, nonce: u64) -> bool {
+        if self
+            .pending
+            .get(&address)
+            .is_none_or(|&(sent, _)| sent != nonce)
+        {
+            return false;
+        }
+        self.pending.remove(&address);
+        self.verified.insert(address)
+    }
+
+    /// Stop sending to `address`, e.g. once its peer times out.
+    pub fn forget(&mut self, address: SocketAddr) {
+        self.verified.remove(&address);
+    }
+
+    /// Drop challenges older than `timeout` (s), so an unanswered address
+    /// can be challenged again.
+    pub fn expire(&mut self, now: f64, timeout: f64) {
+        self.pending
+            .retain(|_, &mut (_, sent)| now - sent <= timeout);
+    }
+
+    /// Forget everything, e.g. when the socket closes.
+    pub fn clear(&mut self) {
+        self.verified.clear();
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(port: u16) -> SocketAddr {
+        SocketAddr::from(([192, 0, 2, 1], port))
+    }
+
+    #[test]
+    fn only_the_right_nonce_verifies() {
+        let mut handshakes = Handshakes::default();
+        assert_eq!(handshakes.challenge(address(1), 7, 0.0, 8), Some(7));
+        // A second state before the answer doesn't provoke another challenge.
+        assert_eq!(handshakes.challenge(address(1), 8, 0.1, 8), None);
+
+        assert!(!handshakes.answer(address(1), 8));
+        assert!(!handshakes.answer(address(2), 7));
+        assert!(!handshakes.is_verified(address(1)));
+        assert!(handshakes.answer(address(1), 7));
+        assert!(handshakes.is_verified(address(1)));
+
+        handshakes.forget(address(1));
+        assert_eq!(handshakes.verified().count(), 0);
+    }
+
+    #[test]
+    fn pending_challenges_are_capped_and_expire() {
+        let mut handshakes = Handshakes::default();
+        for port in 0..4 {
+            handshakes.challenge(address(port), u64::from(port), f64::from(port), 2);
+        }
+        assert_eq!(handshakes.pending.len(), 2);
+        // The oldest were dropped, so their answers no longer verify.
+        assert!(!handshakes.answer(address(0), 0));
+        assert!(handshakes.answer(address(3), 3));
+
+        handshakes.expire(10.0, 5.0);
+        assert!(handshakes.pending.is_empty());
+        assert_eq!(handshakes.challenge(address(2), 9, 10.0, 2), Some(9));
+    }
+}
@@ -0,0 +1,691 @@
+//! Multiplayer ghost mode: share this client's position with peers and draw
+//! theirs.
+//!
+//! A deliberately lightweight layer with no server and no authority: each
+//! client broadcasts its own pose (the camera, or the vehicle it is driving)
+//! as small UDP datagrams ([`protocol`]) to a configured list of peers, and
+//! renders every peer it hears from as a translucent ghost ([`ghost`]).
+//! Ghosts are purely visual — they have no colliders and nothing is
+//! simulated for them — so clients never disagree about anything that
+//! matters.
+//!
+//! Peers need only be listed on one side: a client also replies to a peer it
+//! hears from once that peer has proved it receives at its source address
+//! ([`handshake`]), until the peer times out, so a "host" with an empty peer
+//! list still reaches everyone who lists it. The socket binds to loopback
+//! unless the config opts into a wider address, and at most
+//! [`MultiplayerConfig::max_peers`] peers are tracked at once. Browsers cannot
+//! open raw UDP sockets, so on the web the bind fails and ghost mode stays
+//! off.
+
+pub mod ghost;
+mod handshake;
+pub mod protocol;
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use bevy::{prelude::*, reflect::TypePath};
+use serde::Deserialize;
+
+use veldera_config::ConfigPlugin;
+use veldera_game_camera::FollowEntityTarget;
+use veldera_game_vehicle::Vehicle;
+use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
+
+use crate::{
+    ghost::{
+        GhostMeshes, RemoteGhost, RemotePeer, SnapshotBuffer, init_ghost_meshes, set_ghost_kind,
+        spawn_ghost,
+    },
+    handshake::Handshakes,
+    protocol::{
+        Handshake, MAX_DATAGRAM_BYTES, Message, PROTOCOL_VERSION, PeerKind, PeerState,
+        truncate_name,
+    },
+};
+
+/// Plugin for multiplayer ghost mode.
+///
+/// The host supplies the [`MultiplayerConfig`] path.
+pub struct MultiplayerPlugin {
+    /// Path to the [`MultiplayerConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl MultiplayerPlugin {
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Plugin for MultiplayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<MultiplayerConfig>::new(self.config_path))
+            .init_resource::<MultiplayerState>()
+            .add_systems(Startup, init_ghost_meshes)
+            .add_systems(
+                Update,
+                (
+                    manage_socket,
+                    receive_peer_states,
+                    send_local_state,
+                    update_ghosts,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Multiplayer tuning, loaded from `assets/game/config/multiplayer.toml`.
+#[derive(Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiplayerConfig {
+    /// Master switch. Turning it off closes the socket and removes all ghosts.
+    pub enabled: bool,
+    /// Name shown to peers, cut to [`protocol::MAX_NAME_CHARS`] characters.
+    pub player_name: String,
+    /// Local address to listen on. Loopback by default, so only clients on
+    /// this machine can reach it; listening on the network (e.g.
+    /// `0.0.0.0:7777`) is opt-in. Read when the socket opens; toggle
+    /// `enabled` off and on to rebind.
+    pub bind_address: String,
+    /// Peer addresses (`host:port`) to send to. Resolved when the socket
+    /// opens, like `bind_address`.
+    pub peers: Vec<String>,
+    /// States sent per second.
+    pub send_rate_hz: f32,
+    /// How far in the past ghosts are drawn (s), so there is always a newer
+    /// state to blend towards despite jitter. Around two to three send
+    /// intervals works well.
+    pub interpolation_delay_secs: f32,
+    /// Silence after which a peer's ghost is removed (s).
+    pub peer_timeout_secs: f32,
+    /// Most remote peers tracked at once; the longest silent is dropped to
+    /// make room for a new one. Also bounds outstanding handshakes.
+    pub max_peers: usize,
+}
+
+impl Default for MultiplayerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            player_name: "player".to_string(),
+            bind_address: "127.0.0.1:7777".to_string(),
+            peers: Vec::new(),
+            send_rate_hz: 15.0,
+            interpolation_delay_secs: 0.15,
+            peer_timeout_secs: 5.0,
+            max_peers: 32,
+        }
+    }
+}
+
+/// Live ghost-mode state: the socket, known peer addresses, and remote peers.
+#[derive(Resource)]
+pub struct MultiplayerState {
+    /// This client's random session identifier.
+    id: u64,
+    /// The open socket, while enabled.
+    socket: Option<UdpSocket>,
+    /// Whether opening the socket failed; cleared when ghost mode is turned
+    /// off, so toggling it retries.
+    open_failed: bool,
+    /// Configured peer addresses. States also go to verified addresses,
+    /// which are dropped with their peer when it times out.
+    addresses: HashSet<SocketAddr>,
+    /// Return-address verification for unconfigured senders.
+    handshakes: Handshakes,
+    /// Time accumulated towards the next send (s).
+    send_accumulator: f32,
+    /// Remote peers by session identifier.
+    remotes: HashMap<u64, RemotePeer>,
+}
+
+impl Default for MultiplayerState {
+    fn default() -> Self {
+        Self {
+            id: rand::random(),
+            socket: None,
+            open_failed: false,
+            addresses: HashSet::new(),
+            handshakes: Handshakes::default(),
+            send_accumulator: 0.0,
+            remotes: HashMap::new(),
+        }
+    }
+}
+
+impl MultiplayerState {
+    /// Whether the socket is open.
+    pub fn is_connected(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// The socket's local address, while open.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.socket.as_ref().and_then(|s| s.local_addr().ok())
+    }
+
+    /// Session identifiers and names of the peers currently heard from.
+    pub fn peers(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.remotes.iter().map(|(id, p)| (*id, p.name.as_str()))
+    }
+
+    /// Addresses states are sent to: the configured peers and every address
+    /// that answered a challenge.
+    fn send_targets(&self) -> HashSet<SocketAddr> {
+        self.addresses
+            .iter()
+            .chain(self.handshakes.verified())
+            .copied()
+            .collect()
+    }
+
+    /// Whether states from `address` are accepted.
+    fn is_trusted(&self, address: SocketAddr) -> bool {
+        self.addresses.contains(&address) || self.handshakes.is_verified(address)
+    }
+}
+
+/// Open or close the socket to follow the config's master switch.
+fn manage_socket(
+    mut commands: Commands,
+    config: Res<MultiplayerConfig>,
+    mut state: ResMut<MultiplayerState>,
+) {
+    if !config.enabled {
+        if state.socket.take().is_some() {
+            for (_, remote) in state.remotes.drain() {
+                commands.entity(remote.entity).try_despawn();
+            }
+            state.addresses.clear();
+            state.handshakes.clear();
+            tracing::info!("Multiplayer ghost mode disabled");
+        }
+        state.open_failed = false;
+        return;
+    }
+    if state.socket.is_some() || state.open_failed {
+        return;
+    }
+
+    let socket = match UdpSocket::bind(&config.bind_address)
+        .and_then(|socket| socket.set_nonblocking(true).map(|()| socket))
+    {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to open multiplayer socket on '{}': {e}",
+                config.bind_address
+            );
+            state.open_failed = true;
+            return;
+        }
+    };
+
+    for peer in &config.peers {
+        match peer.to_socket_addrs() {
+            Ok(addresses) => state.addresses.extend(addresses),
+            Err(e) => tracing::warn!("Failed to resolve multiplayer peer '{peer}': {e}"),
+        }
+    }
+
+    tracing::info!(
+        "Multiplayer ghost mode listening on {} with {} configured peer(s)",
+        config.bind_address,
+        state.addresses.len(),
+    );
+    state.socket = Some(socket);
+}
+
+/// Drain received datagrams into the remote peers, spawning ghosts for new
+/// peers, and run the handshake with unverified senders.
+fn receive_peer_states(
+    mut commands: Commands,
+    config: Res<MultiplayerConfig>,
+    time: Res<Time<Real>>,
+    mut state: ResMut<MultiplayerState>,
+    meshes: Option<Res<GhostMeshes>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(meshes) = meshes else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    let state = &mut *state;
+    let Some(socket) = &state.socket else {
+        return;
+    };
+
+    // One byte over the limit, so an oversized datagram shows as too long
+    // rather than silently truncated.
+    let mut buf = [0u8; MAX_DATAGRAM_BYTES + 1];
+    loop {
+        let (len, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            // Windows reports an ICMP port-unreachable from an earlier send
+            // as a reset on the next receive; it says nothing about this one.
+            Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+            Err(e) if is_oversized(&e) => {
+                tracing::debug!("Dropping oversized datagram");
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Multiplayer receive failed: {e}");
+                break;
+            }
+        };
+        if len > MAX_DATAGRAM_BYTES {
+            tracing::debug!("Dropping oversized datagram from {from}");
+            continue;
+        }
+
+        let peer_state = match protocol::decode(&buf[..len]) {
+            Ok(Message::State(peer_state)) => peer_state,
+            Ok(Message::Challenge(challenge)) => {
+                // Only answer peers we chose to talk to, so a spoofed
+                // challenge can't aim responses at a third party.
+                if state.addresses.contains(&from) {
+                    let response = Message::Response(Handshake {
+                        id: state.id,
+                        ..challenge
+                    });
+                    send(socket, &protocol::encode(&response), from);
+                }
+                continue;
+            }
+            Ok(Message::Response(response)) => {
+                if state.handshakes.answer(from, response.nonce) {
+                    tracing::debug!("Verified multiplayer peer address {from}");
+                }
+                continue;
+            }
+            Err(e) => {
+                tracing::debug!("Dropping datagram from {from}: {e}");
+                continue;
+            }
+        };
+        if peer_state.id == state.id {
+            continue;
+        }
+        if !state.is_trusted(from) {
+            if let Some(nonce) =
+                state
+                    .handshakes
+                    .challenge(from, rand::random(), now, config.max_peers)
+            {
+                let challenge = Message::Challenge(Handshake {
+                    version: PROTOCOL_VERSION,
+                    id: state.id,
+                    nonce,
+                });
+                send(socket, &protocol::encode(&challenge), from);
+            }
+            continue;
+        }
+
+        match state.remotes.get_mut(&peer_state.id) {
+            Some(remote) => {
+                if remote.address != from {
+                    // The peer moved (e.g. a NAT rebinding); stop replying to
+                    // its old address.
+                    state.handshakes.forget(remote.address);
+                }
+                if remote.receive(&peer_state, from, now) {
+                    set_ghost_kind(&mut commands.entity(remote.entity), &meshes, remote.kind);
+                }
+            }
+            None => {
+                for evicted in make_room(&mut state.remotes, config.max_peers) {
+                    tracing::info!("Multiplayer peer '{}' dropped for a new peer", evicted.name);
+                    commands.entity(evicted.entity).try_despawn();
+                    state.handshakes.forget(evicted.address);
+                }
+                tracing::info!("Multiplayer peer '{}' joined from {from}", peer_state.name);
+                let entity = spawn_ghost(&mut commands, &meshes, &mut materials, &peer_state);
+                let mut remote = RemotePeer {
+                    entity,
+                    name: peer_state.name.clone(),
+                    kind: peer_state.kind,
+                    buffer: SnapshotBuffer::default(),
+                    clock_offset: f64::INFINITY,
+                    last_heard: now,
+                    address: from,
+                };
+                remote.receive(&peer_state, from, now);
+                state.remotes.insert(peer_state.id, remote);
+            }
+        }
+    }
+}
+
+/// Broadcast this client's pose at the configured rate.
+///
+/// While the camera follows a vehicle the vehicle's pose is sent, so peers see
+/// the car rather than the chase camera; otherwise the camera's own pose is.
+fn send_local_state(
+    config: Res<MultiplayerConfig>,
+    time: Res<Time<Real>>,
+    mut state: ResMut<MultiplayerState>,
+    camera_query: Query<(
+        &FloatingOriginCamera,
+        &Transform,
+        Option<&FollowEntityTarget>,
+    )>,
+    vehicle_query: Query<(&Vehicle, &WorldPosition, &Transform)>,
+) {
+    if state.socket.is_none() || config.send_rate_hz <= 0.0 {
+        return;
+    }
+    state.send_accumulator += time.delta_secs();
+    let interval = config.send_rate_hz.recip();
+    if state.send_accumulator < interval {
+        return;
+    }
+    // Send at most once per frame; a long frame doesn't queue a burst.
+    state.send_accumulator = (state.send_accumulator - interval).min(interval);
+
+    let Ok((camera, camera_transform, follow)) = camera_query.single() else {
+        return;
+    };
+    let vehicle = follow.and_then(|follow| vehicle_query.get(follow.target).ok());
+    let (position, rotation, kind, vehicle) = match vehicle {
+        Some((vehicle, world_pos, transform)) => (
+            world_pos.position,
+            transform.rotation,
+            PeerKind::Vehicle,
+            Some(truncate_name(&vehicle.name)),
+        ),
+        None => (
+            camera.position,
+            camera_transform.rotation,
+            PeerKind::Camera,
+            None,
+        ),
+    };
+
+    let payload = protocol::encode(&Message::State(PeerState {
+        version: PROTOCOL_VERSION,
+        id: state.id,
+        name: truncate_name(&config.player_name),
+        time: time.elapsed_secs_f64(),
+        position: position.to_array(),
+        rotation: rotation.to_array(),
+        kind,
+        vehicle,
+    }));
+
+    let Some(socket) = &state.socket else {
+        return;
+    };
+    for address in state.send_targets() {
+        send(socket, &payload, address);
+    }
+}
+
+/// Send one datagram, logging rather than failing on errors.
+fn send(socket: &UdpSocket, payload: &[u8], address: SocketAddr) {
+    if let Err(e) = socket.send_to(payload, address) {
+        tracing::debug!("Failed to send to {address}: {e}");
+    }
+}
+
+/// Remove the longest silent peers until a new one fits under `max_peers`,
+/// returning them so their ghosts can be despawned.
+fn make_room(remotes: &mut HashMap<u64, RemotePeer>, max_peers: usize) -> Vec<RemotePeer> {
+    let mut evicted = Vec::new();
+    while !remotes.is_empty() && remotes.len() >= max_peers.max(1) {
+        let Some(stalest) = remotes
+            .iter()
+            .min_by(|a, b| a.1.last_heard.total_cmp(&b.1.last_heard))
+            .map(|(id, _)| *id)
+        else {
+            break;
+        };
+        evicted.extend(remotes.remove(&stalest));
+    }
+    evicted
+}
+
+/// Whether a receive failed because the datagram didn't fit the buffer.
+/// Unix truncates silently (caught by the length check instead); Windows
+/// fails the receive with `WSAEMSGSIZE`.
+fn is_oversized(error: &io::Error) -> bool {
+    const WSAEMSGSIZE: i32 = 10040;
+    cfg!(windows) && error.raw_os_error() == Some(WSAEMSGSIZE)
+}
+
+/// Place each ghost at its peer's interpolated pose and remove silent peers.
+fn update_ghosts(
+    mut commands: Commands,
+    config: Res<MultiplayerConfig>,
+    time: Res<Time<Real>>,
+    mut state: ResMut<MultiplayerState>,
+    mut ghost_query: Query<(&mut WorldPosition, &mut Transform), With<RemoteGhost>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let timeout = f64::from(config.peer_timeout_secs);
+    let delay = f64::from(config.interpolation_delay_secs);
+
+    let state = &mut *state;
+    state.handshakes.expire(now, timeout);
+    state.remotes.retain(|_, remote| {
+        if now - remote.last_heard > timeout {
+            tracing::info!("Multiplayer peer '{}' timed out", remote.name);
+            commands.entity(remote.entity).try_despawn();
+            state.handshakes.forget(remote.address);
+            return false;
+        }
+
+        let render_time = now - remote.clock_offset - delay;
+        if let (Some((position, rotation)), Ok((mut world_pos, mut transform))) = (
+            remote.buffer.sample(render_time),
+            ghost_query.get_mut(remote.entity),
+        ) {
+            world_pos.position = position;
+            transform.rotation = rotation;
+        }
+        remote.buffer.prune_before(render_time);
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// An app running the receive step on a loopback socket.
+    fn app(max_peers: usize) -> (App, SocketAddr) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_nonblocking(true).unwrap();
+        let address = socket.local_addr().unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(MultiplayerConfig {
+            enabled: true,
+            max_peers,
+            ..default()
+        })
+        .insert_resource(MultiplayerState {
+            socket: Some(socket),
+            ..default()
+        })
+        .insert_resource(GhostMeshes {
+            camera: Handle::default(),
+            vehicle: Handle::default(),
+        })
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<Time<Real>>()
+        .add_systems(Update, receive_peer_states);
+        (app, address)
+    }
+
+    /// A socket standing in for another client.
+    fn stranger() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(2)))
+            .unwrap();
+        socket
+    }
+
+    fn state_from(id: u64) -> Vec<u8> {
+        protocol::encode(&Message::State(PeerState {
+            version: PROTOCOL_VERSION,
+            id,
+            name: format!("peer {id}"),
+            time: 0.0,
+            position: [6_378_137.0, 0.0, 0.0],
+            rotation: Quat::IDENTITY.to_array(),
+            kind: PeerKind::Camera,
+            vehicle: None,
+        }))
+    }
+
+    /// Update until `done` holds; loopback delivery isn't synchronous.
+    fn update_until(app: &mut App, done: impl Fn(&MultiplayerState) -> bool) {
+        for _ in 0..200 {
+            app.update();
+            if done(app.world().resource::<MultiplayerState>()) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        panic!("condition not reached");
+    }
+
+    /// Update until `socket` receives something, and decode it.
+    fn update_and_receive(app: &mut App, socket: &UdpSocket) -> Message {
+        let mut buf = [0u8; MAX_DATAGRAM_BYTES];
+        for _ in 0..200 {
+            app.update();
+            if let Ok((len, _)) = socket.recv_from(&mut buf) {
+                return protocol::decode(&buf[..len]).unwrap();
+            }
+        }
+        panic!("nothing received");
+    }
+
+    /// Update for a while, for checking that nothing happens.
+    fn settle(app: &mut App) {
+        for _ in 0..20 {
+            app.update();
+            std::thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    #[test]
+    fn unknown_senders_are_challenged_before_they_get_states() {
+        let (mut app, address) = app(8);
+        let peer = stranger();
+        let peer_address = peer.local_addr().unwrap();
+
+        peer.send_to(&state_from(1), address).unwrap();
+        let Message::Challenge(challenge) = update_and_receive(&mut app, &peer) else {
+            panic!("expected a challenge");
+        };
+        let state = app.world().resource::<MultiplayerState>();
+        assert_eq!(state.peers().count(), 0);
+        assert!(!state.send_targets().contains(&peer_address));
+
+        // A wrong nonce, as a spoofer guessing would send, verifies nothing,
+        // and further states don't provoke further challenges.
+        let wrong = Handshake {
+            nonce: challenge.nonce.wrapping_add(1),
+            ..challenge
+        };
+        peer.send_to(&protocol::encode(&Message::Response(wrong)), address)
+            .unwrap();
+        peer.send_to(&state_from(1), address).unwrap();
+        settle(&mut app);
+        let mut buf = [0u8; MAX_DATAGRAM_BYTES];
+        assert!(peer.recv_from(&mut buf).is_err());
+        let state = app.world().resource::<MultiplayerState>();
+        assert_eq!(state.peers().count(), 0);
+        assert!(!state.send_targets().contains(&peer_address));
+
+        // Echoing the real nonce proves the address, and states then count.
+        peer.send_to(&protocol::encode(&Message::Response(challenge)), address)
+            .unwrap();
+        update_until(&mut app, |state| {
+            state.send_targets().contains(&peer_address)
+        });
+        peer.send_to(&state_from(1), address).unwrap();
+        update_until(&mut app, |state| state.peers().count() == 1);
+    }
+
+    #[test]
+    fn challenges_are_only_answered_for_configured_peers() {
+        let (mut app, address) = app(8);
+        let peer = stranger();
+        let challenge = protocol::encode(&Message::Challenge(Handshake {
+            version: PROTOCOL_VERSION,
+            id: 9,
+            nonce: 5,
+        }));
+
+        peer.send_to(&challenge, address).unwrap();
+        settle(&mut app);
+        let mut buf = [0u8; MAX_DATAGRAM_BYTES];
+        assert!(peer.recv_from(&mut buf).is_err());
+
+        app.world_mut()
+            .resource_mut::<MultiplayerState>()
+            .addresses
+            .insert(peer.local_addr().unwrap());
+        peer.send_to(&challenge, address).unwrap();
+        let Message::Response(response) = update_and_receive(&mut app, &peer) else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.nonce, 5);
+    }
+
+    #[test]
+    fn peer_count_is_capped_by_evicting_the_stalest() {
+        let (mut app, address) = app(2);
+        let peer = stranger();
+        app.world_mut()
+            .resource_mut::<MultiplayerState>()
+            .addresses
+            .insert(peer.local_addr().unwrap());
+
+        // A flood of fresh identifiers from one trusted address.
+        for id in 1..=5 {
+            peer.send_to(&state_from(id), address).unwrap();
+            update_until(&mut app, |state| state.remotes.contains_key(&id));
+            app.world_mut()
+                .resource_mut::<Time<Real>>()
+                .advance_by(Duration::from_millis(10));
+        }
+        let state = app.world().resource::<MultiplayerState>();
+        let mut ids: Vec<u64> = state.peers().map(|(id, _)| id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, [4, 5]);
+    }
+
+    #[test]
+    fn make_room_drops_the_longest_silent() {
+        let peer = |last_heard| RemotePeer {
+            entity: Entity::PLACEHOLDER,
+            name: String::new(),
+            kind: PeerKind::Camera,
+            buffer: SnapshotBuffer::default(),
+            clock_offset: 0.0,
+            last_heard,
+            address: SocketAddr::from(([127, 0, 0, 1], 1)),
+        };
+        let mut remotes = HashMap::from([(1, peer(3.0)), (2, peer(1.0)), (3, peer(2.0))]);
+        let evicted = make_room(&mut remotes, 2);
+        assert_eq!(evicted.len(), 2);
+        assert_eq!(remotes.keys().copied().collect::<Vec<_>>(), [1]);
+        assert!(make_room(&mut remotes, 2).is_empty());
+    }
+}
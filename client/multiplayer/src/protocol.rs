@@ -0,0 +1,222 @@
+//! Wire format for the ghost-mode state broadcast.
+//!
+//! Each datagram carries one [`Message`] encoded as JSON: a [`PeerState`], or
+//! one half of the handshake that proves a sender receives at its source
+//! address. The format is deliberately self-describing
+//! rather than compact: at the default send rate a state is a few hundred
+//! bytes, well inside a single UDP datagram, and a readable format keeps the
+//! protocol easy to inspect with a packet capture. A challenge is always
+//! smaller than the smallest state, so answering a spoofed state can't
+//! amplify traffic towards the spoofed address.
+
+use glam::{DVec3, Quat};
+use serde::{Deserialize, Serialize};
+
+/// Protocol version; datagrams with any other version are dropped.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Largest datagram the receiver accepts (bytes).
+pub const MAX_DATAGRAM_BYTES: usize = 1200;
+
+/// Longest player or vehicle name sent (characters). Even fully escaped, two
+/// names this long leave an encoded state well under [`MAX_DATAGRAM_BYTES`].
+pub const MAX_NAME_CHARS: usize = 48;
+
+/// What a peer is currently embodied as, which selects its ghost's shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerKind {
+    /// Free-flying or on foot; the state is the camera pose.
+    Camera,
+    /// Driving a vehicle; the state is the vehicle's pose.
+    Vehicle,
+}
+
+/// One datagram's payload.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// The sender's pose.
+    State(PeerState),
+    /// Sent to an unverified address a state came from; only a sender that
+    /// really listens there can see the nonce and echo it back.
+    Challenge(Handshake),
+    /// Echo of a [`Message::Challenge`]'s nonce.
+    Response(Handshake),
+}
+
+impl Message {
+    fn version(&self) -> u32 {
+        match self {
+            Self::State(state) => state.version,
+            Self::Challenge(handshake) | Self::Response(handshake) => handshake.version,
+        }
+    }
+}
+
+/// A handshake challenge or its response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// Must equal [`PROTOCOL_VERSION`].
+    pub version: u32,
+    /// Session identifier of the sending client.
+    pub id: u64,
+    /// Random value chosen by the challenger.
+    pub nonce: u64,
+}
+
+/// One peer's pose at one instant, as sent over the wire.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerState {
+    /// Must equal [`PROTOCOL_VERSION`].
+    pub version: u32,
+    /// Random per-session identifier of the sending client.
+    pub id: u64,
+    /// Display name chosen by the sender.
+    pub name: String,
+    /// Sender's clock when the state was captured (s since its startup).
+    pub time: f64,
+    /// ECEF position (m).
+    pub position: [f64; 3],
+    /// Orientation in ECEF axes, as `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+    /// What the sender is embodied as.
+    pub kind: PeerKind,
+    /// Vehicle display name, when `kind` is [`PeerKind::Vehicle`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vehicle: Option<String>,
+}
+
+impl PeerState {
+    /// ECEF position as a vector.
+    pub fn position(&self) -> DVec3 {
+        DVec3::from_array(self.position)
+    }
+
+    /// Orientation as a normalized quaternion.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_array(self.rotation).normalize()
+    }
+}
+
+/// Error decoding a received datagram.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The payload was not a valid JSON state.
+    Malformed(serde_json::Error),
+    /// The payload was produced by an incompatible protocol version.
+    Version(u32),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(e) => write!(f, "malformed peer state: {e}"),
+            Self::Version(v) => write!(
+                f,
+                "unsupported protocol version {v} (expected {PROTOCOL_VERSION})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Malformed(e) => Some(e),
+            Self::Version(_) => None,
+        }
+    }
+}
+
+/// `name` cut to at most [`MAX_NAME_CHARS`] characters.
+pub fn truncate_name(name: &str) -> String {
+    name.chars().take(MAX_NAME_CHARS).collect()
+}
+
+/// Encode a message into a datagram payload.
+pub fn encode(message: &Message) -> Vec<u8> {
+    // Serializing plain data with string keys cannot fail.
+    serde_json::to_vec(message).expect("message serializes")
+}
+
+/// Decode a datagram payload, rejecting other protocol versions.
+pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+    let message: Message = serde_json::from_slice(bytes).map_err(DecodeError::Malformed)?;
+    if message.version() != PROTOCOL_VERSION {
+        return Err(DecodeError::Version(message.version()));
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> PeerState {
+        PeerState {
+            version: PROTOCOL_VERSION,
+            id: 42,
+            name: "tester".to_string(),
+            time: 12.5,
+            position: [6_378_137.0, 1.5, -2.25],
+            rotation: Quat::from_rotation_y(0.5).to_array(),
+            kind: PeerKind::Vehicle,
+            vehicle: Some("Hatchback".to_string()),
+        }
+    }
+
+    #[test]
+    fn round_trips_and_fits_a_datagram() {
+        let message = Message::State(state());
+        let bytes = encode(&message);
+        assert!(bytes.len() <= MAX_DATAGRAM_BYTES);
+        assert_eq!(decode(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn challenges_are_smaller_than_any_state() {
+        let challenge = Message::Challenge(Handshake {
+            version: PROTOCOL_VERSION,
+            id: u64::MAX,
+            nonce: u64::MAX,
+        });
+        let smallest_state = Message::State(PeerState {
+            id: 0,
+            name: String::new(),
+            time: 0.0,
+            position: [0.0; 3],
+            rotation: [0.0; 4],
+            kind: PeerKind::Camera,
+            vehicle: None,
+            ..state()
+        });
+        let challenge = encode(&challenge);
+        assert_eq!(decode(&challenge).unwrap().version(), PROTOCOL_VERSION);
+        assert!(challenge.len() < encode(&smallest_state).len());
+    }
+
+    #[test]
+    fn truncated_names_fit_a_datagram() {
+        // Control characters escape to six bytes each, the worst case.
+        let long = "\u{1}".repeat(10 * MAX_NAME_CHARS);
+        let state = PeerState {
+            name: truncate_name(&long),
+            vehicle: Some(truncate_name(&long)),
+            ..state()
+        };
+        assert_eq!(state.name.chars().count(), MAX_NAME_CHARS);
+        assert!(encode(&Message::State(state)).len() <= MAX_DATAGRAM_BYTES);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut state = state();
+        state.version = PROTOCOL_VERSION + 1;
+        assert!(matches!(
+            decode(&encode(&Message::State(state))),
+            Err(DecodeError::Version(_))
+        ));
+        assert!(matches!(decode(b"{"), Err(DecodeError::Malformed(_))));
+    }
+}
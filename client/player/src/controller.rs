@@ -17,11 +17,15 @@ use veldera_game_camera_state::CameraModeState;
 use veldera_game_input::CameraAction;
 use veldera_geo::{
     coords::RadialFrame,
-    floating_origin::{FloatingOrigin, FloatingOriginCamera, WorldPosition},
+    floating_origin::{FloatingOriginCamera, WorldPosition},
 };
 use veldera_physics::{GameLayer, ManualGravity, OriginShiftSystems};
 
-use crate::{effects::PlayerLanded, yeet::YeetState};
+use crate::{
+    effects::PlayerLanded,
+    swim::{self, SwimConfig},
+    yeet::YeetState,
+};
 
 // ============================================================================
 // Plugin
@@ -63,6 +67,7 @@ impl Plugin for FpsControllerPlugin {
                     fps_controller_sync_position,
                 )
                     .chain()
+                    .in_set(FpsControllerSystems)
                     // `fps_controller_sync_position` re-derives the player's
                     // physics Position from the floating-origin Transform,
                     // which already reflects the camera position the origin
@@ -82,7 +87,6 @@ impl Plugin for FpsControllerPlugin {
                     (
                         clear_input.run_if(did_fixed_timestep_run_this_frame),
                         fps_controller_render.run_if(not_suppressed),
-                        sync_floating_origin_fps,
                     )
                         .chain()
                         .in_set(RunFixedMainLoopSystems::AfterFixedMainLoop),
@@ -92,6 +96,10 @@ impl Plugin for FpsControllerPlugin {
     }
 }
 
+/// The controller's fixed-step systems, for ordering against them.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct FpsControllerSystems;
+
 /// Host-driven flag that suppresses the FPS controller for a frame.
 ///
 /// The first-person client sets this while a teleport animation is playing so
@@ -290,6 +298,8 @@ pub struct FpsControllerInput {
     pub sprint: bool,
     pub jump: bool,
     pub crouch: bool,
+    /// Ascend held; swims upward while swimming.
+    pub ascend: bool,
     pub pitch: f32,
     pub yaw: f32,
     pub movement: Vec3,
@@ -357,6 +367,19 @@ pub struct FpsController {
     /// body transform sync, both of which pivot the lean about the head
     /// so the camera never moves.
     pub ragdoll_pitch: f32,
+    /// Fraction (0–1) of the capsule under water, from
+    /// [`swim::submersion`]. Updated by [`fps_controller_prepare`].
+    pub submersion: f32,
+    /// Whether the player is swimming: submerged past
+    /// [`SwimConfig::swim_submersion`]. Swimming replaces walking and
+    /// falling, and rules out ragdolling, jumping and yeeting.
+    pub swimming: bool,
+    /// Seconds of lost control left after a hit; input doesn't drive the
+    /// player until it runs out. Set by the knockback, counted down by
+    /// [`fps_controller_prepare`].
+    pub stagger_s: f32,
+    /// Hit shake intensity, 0..1. Set by the knockback, decayed by its shake.
+    pub trauma: f32,
 }
 
 impl FpsController {
@@ -654,6 +677,7 @@ fn clear_input(mut query: Query<&mut FpsControllerInput>) {
         input.sprint = false;
         input.jump = false;
         input.crouch = false;
+        input.ascend = false;
     }
 }
 
@@ -699,6 +723,7 @@ fn fps_controller_input(
         // with the charged leap.
         input.jump |= yeet_state.take_queued_jump();
         input.crouch |= action_state.pressed(&CameraAction::Descend);
+        input.ascend |= action_state.pressed(&CameraAction::Ascend);
     }
 }
 
@@ -722,6 +747,7 @@ fn fps_controller_prepare(
     time: Res<Time<Fixed>>,
     player_config: Res<FpsPlayerConfig>,
     fps_config: Res<FpsConfig>,
+    swim_config: Res<SwimConfig>,
     physics_config: Res<veldera_physics::PhysicsConfig>,
     camera_query: Query<&FloatingOriginCamera>,
     mut query: Query<
@@ -775,6 +801,10 @@ fn fps_controller_prepare(
         let is_grounded = controller.ground_tick >= 1;
         let is_ragdolling = controller.ragdoll_state == RagdollState::Ragdolling;
 
+        controller.submersion = swim::submersion(&swim_config, ecef_pos, controller.height);
+        controller.swimming = controller.submersion >= swim_config.swim_submersion;
+        let swimming = controller.swimming;
+
         // Gravity plus passive resistance: ground friction when grounded, air
         // drag when airborne. Both branches integrate radial gravity first, then
         // their resistance. Both apply always — including during ragdoll — so a
@@ -782,7 +812,17 @@ fn fps_controller_prepare(
         // gating either behind the ragdoll `continue` below would freeze the
         // capsule's speed the instant it ragdolls (e.g. a fast flycam hand-off
         // ragdolls after `airborne_threshold_s` and would then coast forever).
-        if is_grounded {
+        // In the water, buoyancy and water drag take the place of both.
+        if swimming {
+            velocity.0 = swim::swim_velocity_step(
+                velocity.0,
+                local_up,
+                physics_config.gravity,
+                controller.submersion,
+                &swim_config,
+                dt,
+            );
+        } else if is_grounded {
             velocity.0 += -local_up * physics_config.gravity * dt;
 
             // Ground friction. While ragdolling we use a much stronger
@@ -822,16 +862,46 @@ fn fps_controller_prepare(
                 dt,
             );
         }
+        // Wading: the water drags on whatever's in it.
+        if !swimming && controller.submersion > 0.0 {
+            velocity.0 = swim::water_drag_step(velocity.0, controller.submersion, &swim_config, dt);
+        }
 
         // The rest of the controller logic — input-driven acceleration, jump,
         // crouch height updates, collider resize — is for the player driving.
-        // While ragdolling there's no driving; gravity plus the passive
-        // resistance above are the only forces on the capsule.
+        // While ragdolling, or staggered by a hit, there's no driving;
+        // gravity plus the passive resistance above are the only forces on
+        // the capsule.
+        if controller.stagger_s > 0.0 {
+            controller.stagger_s = (controller.stagger_s - dt).max(0.0);
+            continue;
+        }
         if is_ragdolling {
             continue;
         }
 
-        if is_grounded {
+        if swimming {
+            // Swim along the look direction, so looking down dives, with
+            // Ascend and Descend swimming straight up and down. No jumping
+            // off the water.
+            let look = frame.look(input.yaw, input.pitch);
+            let vertical = f32::from(u8::from(input.ascend)) - f32::from(u8::from(input.crouch));
+            let swim_wish =
+                right * input.movement.x + look * input.movement.z + local_up * vertical;
+            let swim_speed = if input.sprint {
+                swim_config.sprint_swim_speed
+            } else {
+                swim_config.swim_speed
+            };
+            let add = acceleration(
+                swim_wish.normalize_or_zero(),
+                swim_speed * swim_wish.length().min(1.0),
+                swim_config.swim_acceleration,
+                velocity.0,
+                dt,
+            );
+            velocity.0 += add;
+        } else if is_grounded {
             // Ground acceleration.
             let add = acceleration(
                 wish_direction,
@@ -867,8 +937,9 @@ fn fps_controller_prepare(
         controller.upright_height = player_config.height;
         controller.crouch_height = player_config.crouch_height();
 
-        // Update crouch height.
-        let crouch_speed = if input.crouch {
+        // Update crouch height. Descend dives rather than crouches in the
+        // water.
+        let crouch_speed = if input.crouch && !swimming {
             -controller.crouch_speed
         } else {
             controller.uncrouch_speed
@@ -994,6 +1065,10 @@ fn fps_controller_slide(
         if controller.ground_tick >= 1 {
             controller.grounded_time_s += dt;
             controller.airborne_time_s = 0.0;
+        } else if controller.swimming {
+            // The water holds the player up: no airtime, so no ragdoll.
+            controller.airborne_time_s = 0.0;
+            controller.grounded_time_s = 0.0;
         } else {
             controller.airborne_time_s += dt;
             controller.grounded_time_s = 0.0;
@@ -1011,7 +1086,9 @@ fn fps_controller_slide(
                     }
                 }
                 RagdollState::Ragdolling => {
-                    if controller.grounded_time_s >= fps_config.ground_recovery_s {
+                    if controller.grounded_time_s >= fps_config.ground_recovery_s
+                        || controller.swimming
+                    {
                         controller.ragdoll_state = RagdollState::Active;
                         controller.airborne_time_s = 0.0;
                         controller.grounded_time_s = 0.0;
@@ -1135,12 +1212,3 @@ fn fps_controller_render(
         }
     }
 }
-
-fn sync_floating_origin_fps(
-    mut origin: ResMut<FloatingOrigin>,
-    query: Query<&FloatingOriginCamera>,
-) {
-    if let Ok(camera) = query.single() {
-        origin.position = camera.position;
-    }
-}
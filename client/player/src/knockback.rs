@@ -0,0 +1,203 @@
+//! Knockback: the player is thrown by vehicles and projectiles that hit them.
+//!
+//! The player's capsule is kinematic, so the physics solver treats it as
+//! immovable: a car hitting a pedestrian stops dead, and the pedestrian
+//! doesn't budge. [`apply_knockback`] reconciles each such contact with a
+//! player of [`KnockbackConfig::player_mass_kg`] instead. It undoes the
+//! solver's impulse to recover the other body's velocity before the contact,
+//! then resolves the collision between the two masses along the contact
+//! normal with [`KnockbackConfig::restitution`]: the player gets the velocity
+//! change (lifted a little so it leaves the ground), and the other body keeps
+//! what the lighter player couldn't take from it. Contacts closing slower
+//! than [`KnockbackConfig::min_impact_speed`] are left alone, so walking into
+//! a parked car is still just a push.
+//!
+//! A hit costs the player control for a moment in proportion to its speed,
+//! ragdolls them past [`KnockbackConfig::ragdoll_speed`], and adds trauma
+//! that shakes the first-person view as it decays. The shake is the one lens
+//! effect in this crate; [`KnockbackConfig::trauma_max_angle_deg`] of zero
+//! turns it off for a view that mustn't move (such as VR).
+
+use std::f32::consts::TAU;
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, reflect::TypePath};
+use serde::Deserialize;
+
+use veldera_config::ConfigPlugin;
+use veldera_geo::{coords::RadialFrame, floating_origin::WorldPosition};
+
+use crate::{
+    LogicalPlayer, RagdollState, RenderPlayer,
+    controller::{FpsConfig, FpsController},
+};
+
+/// Plugin for knockback and the hit shake.
+pub(crate) struct KnockbackPlugin {
+    pub path: &'static str,
+}
+
+impl Plugin for KnockbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<KnockbackConfig>::new(self.path))
+            .add_systems(
+                FixedPreUpdate,
+                apply_knockback.before(crate::controller::FpsControllerSystems),
+            )
+            .add_systems(Update, shake_on_hit);
+    }
+}
+
+/// Hot-reloadable knockback tuning, loaded from
+/// `assets/config/game/player/knockback.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KnockbackConfig {
+    /// Master switch. `false` leaves the player as immovable as the solver
+    /// makes it.
+    pub enabled: bool,
+    /// The player's mass in collisions (kg).
+    pub player_mass_kg: f32,
+    /// Coefficient of restitution of a hit: 0 moves off together, 1 bounces
+    /// apart elastically.
+    pub restitution: f32,
+    /// Closing speed below which a contact is a push rather than a hit (m/s).
+    pub min_impact_speed: f32,
+    /// Upward speed added per unit of knockback speed, so the hit lifts the
+    /// player off the ground instead of being eaten by ground friction.
+    pub lift_ratio: f32,
+    /// Seconds of lost control per m/s of knockback.
+    pub stagger_s_per_speed: f32,
+    /// Longest loss of control (s).
+    pub max_stagger_s: f32,
+    /// Knockback speed past which the player ragdolls at once (m/s), when
+    /// [`FpsConfig::enable_ragdoll`] is set.
+    pub ragdoll_speed: f32,
+    /// Knockback speed that brings trauma to full (m/s).
+    pub trauma_full_speed: f32,
+    /// Trauma lost per second.
+    pub trauma_decay: f32,
+    /// Peak shake rotation at full trauma (degrees). `0` disables the shake.
+    pub trauma_max_angle_deg: f32,
+    /// Rough frequency of the shake (Hz).
+    pub trauma_frequency: f32,
+}
+
+/// Resolve last step's contacts between the player and dynamic bodies as
+/// collisions with a player of finite mass.
+///
+/// Runs before the controller, when the contact graph still holds the
+/// impulses the solver applied in the previous physics step.
+#[allow(clippy::type_complexity)]
+fn apply_knockback(
+    config: Res<KnockbackConfig>,
+    fps_config: Res<FpsConfig>,
+    collisions: Collisions,
+    mut player_query: Query<
+        (
+            Entity,
+            &WorldPosition,
+            &mut FpsController,
+            &mut LinearVelocity,
+        ),
+        With<LogicalPlayer>,
+    >,
+    mut body_query: Query<(&RigidBody, &ComputedMass, &mut LinearVelocity), Without<LogicalPlayer>>,
+) {
+    if !config.enabled {
+        return;
+    }
+    let Ok((player, world_pos, mut controller, mut player_velocity)) = player_query.single_mut()
+    else {
+        return;
+    };
+    let player_mass = config.player_mass_kg.max(1.0);
+
+    for pair in collisions.collisions_with(player) {
+        // The impulse the solver applied to the other body, and the body.
+        let (impulse, other) = if pair.collider1 == player {
+            (pair.total_normal_impulse(), pair.body2)
+        } else {
+            (-pair.total_normal_impulse(), pair.body1)
+        };
+        let Some(other) = other else {
+            continue;
+        };
+        let Ok((rigid_body, mass, mut other_velocity)) = body_query.get_mut(other) else {
+            continue;
+        };
+        let magnitude = impulse.length();
+        if !rigid_body.is_dynamic() || magnitude <= f32::EPSILON || mass.inverse() <= 0.0 {
+            continue;
+        }
+        let to_player = -impulse / magnitude;
+
+        // Before the solver stopped it against the immovable capsule.
+        let approach_velocity = other_velocity.0 - impulse * mass.inverse();
+        let closing = (approach_velocity - player_velocity.0).dot(to_player);
+        if closing < config.min_impact_speed {
+            continue;
+        }
+
+        let other_mass = mass.value();
+        let exchange = (1.0 + config.restitution) * closing / (player_mass + other_mass);
+        let knockback = exchange * other_mass;
+        other_velocity.0 = approach_velocity - to_player * (exchange * player_mass);
+
+        let up = RadialFrame::from_ecef_position(world_pos.position).up;
+        player_velocity.0 += to_player * knockback + up * (knockback * config.lift_ratio);
+        // Airborne from here, so ground friction doesn't swallow the hit.
+        controller.ground_tick = 0;
+        controller.stagger_s = controller
+            .stagger_s
+            .max((knockback * config.stagger_s_per_speed).min(config.max_stagger_s));
+        let trauma = (knockback / config.trauma_full_speed.max(0.1)).min(1.0);
+        controller.trauma = controller.trauma.max(trauma);
+        if fps_config.enable_ragdoll
+            && knockback >= config.ragdoll_speed
+            && controller.ragdoll_state == RagdollState::Active
+            && !controller.swimming
+        {
+            controller.ragdoll_state = RagdollState::Ragdolling;
+            tracing::info!("Knocked into ragdoll at {knockback:.1} m/s");
+        }
+    }
+}
+
+/// Shake the first-person view by the player's trauma, and decay it.
+fn shake_on_hit(
+    time: Res<Time>,
+    config: Res<KnockbackConfig>,
+    mut camera_query: Query<(&mut Transform, &RenderPlayer)>,
+    mut player_query: Query<&mut FpsController, With<LogicalPlayer>>,
+) {
+    let dt = time.delta_secs();
+    for (mut transform, render_player) in &mut camera_query {
+        let Ok(mut controller) = player_query.get_mut(render_player.logical_entity) else {
+            continue;
+        };
+        if controller.trauma <= 0.0 {
+            continue;
+        }
+        let amplitude =
+            config.trauma_max_angle_deg.to_radians() * controller.trauma * controller.trauma;
+        let t = time.elapsed_secs() * config.trauma_frequency * TAU;
+        // The controller's render system rewrites the rotation every frame,
+        // so the shake never accumulates.
+        transform.rotation *= Quat::from_euler(
+            EulerRot::YXZ,
+            amplitude * shake_noise(t, 0.0),
+            amplitude * shake_noise(t, 11.3),
+            0.5 * amplitude * shake_noise(t, 23.7),
+        );
+        controller.trauma = (controller.trauma - config.trauma_decay * dt).max(0.0);
+    }
+}
+
+/// Smooth pseudo-random wobble in -1..1; `seed` decorrelates the axes.
+fn shake_noise(t: f32, seed: f32) -> f32 {
+    let a = (t + seed).sin();
+    let b = (t * 2.31 + seed * 1.7).sin();
+    let c = (t * 4.77 + seed * 0.6).sin();
+    (a + 0.5 * b + 0.25 * c) / 1.75
+}
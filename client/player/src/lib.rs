@@ -13,6 +13,10 @@
 //! - [`effects`] — takeoff/landing impact effects (a procedural thump and a
 //!   ground dust burst); world-space only, so a future VR camera stays
 //!   untouched.
+//! - [`knockback`] — hits from vehicles and projectiles: resolved against the
+//!   player's mass, with a loss of control, a ragdoll and a view shake.
+//! - [`swim`] — the sea as a water volume: buoyancy, swimming and diving for
+//!   the controller, and the underwater camera tint.
 //! - [`trajectory`] — the shared leap flight math (used by the controller and
 //!   yeet) and the in-world arc previewing where a charged leap will land.
 
@@ -20,6 +24,8 @@ pub mod controller;
 
 mod body;
 mod effects;
+mod knockback;
+mod swim;
 mod trajectory;
 mod yeet;
 
@@ -30,6 +36,8 @@ pub use controller::{
     FpsController, FpsControllerSuppressed, FpsPlayerConfig, LogicalPlayer, RagdollState,
     RenderPlayer, direction_to_yaw_pitch, spawn_fps_player,
 };
+pub use knockback::KnockbackConfig;
+pub use swim::SwimConfig;
 
 /// Config-file paths for the player's hot-reloadable tuning, supplied by the
 /// host (the engine crates own the config *types*; the app owns the paths).
@@ -48,10 +56,14 @@ pub struct PlayerConfigPaths {
     pub effects: &'static str,
     /// Predicted-leap-arc tuning (`LeapArcConfig`).
     pub leap_arc: &'static str,
+    /// Swimming and underwater tuning (`SwimConfig`).
+    pub swim: &'static str,
+    /// Knockback tuning (`KnockbackConfig`).
+    pub knockback: &'static str,
 }
 
 /// Bundles the first-person controller, the body avatar, the yeet mechanic,
-/// and the impact effects.
+/// the impact effects, swimming, and knockback.
 pub struct PlayerPlugin {
     /// Config-file paths for the player's tuning.
     pub paths: PlayerConfigPaths,
@@ -80,6 +92,12 @@ impl Plugin for PlayerPlugin {
             trajectory::LeapArcPlugin {
                 path: self.paths.leap_arc,
             },
+            swim::SwimPlugin {
+                path: self.paths.swim,
+            },
+            knockback::KnockbackPlugin {
+                path: self.paths.knockback,
+            },
         ));
     }
 }
@@ -0,0 +1,181 @@
+//! Swimming: the sea as a water volume for the first-person controller.
+//!
+//! The terrain has no water the physics can touch (the sea is shaded as water
+//! but collides as whatever the photogrammetry left there), so the water
+//! volume is everything below [`SwimConfig::sea_level_m`]. The controller asks
+//! [`submersion`] how much of the capsule is under it each tick: a partly
+//! submerged player wades through extra drag, and one past
+//! [`SwimConfig::swim_submersion`] swims instead of walking or falling.
+//!
+//! Swimming trades gravity for buoyancy, which grows with the submerged
+//! fraction and balances gravity at [`SwimConfig::float_submersion`], so a
+//! player at rest bobs at the surface with their head out, and one who dives
+//! or falls in rises back to it. Movement follows the look direction, so
+//! looking down while moving forward dives; Ascend and Descend swim straight
+//! up and down. There's no ragdoll, jump or yeet in the water.
+//!
+//! While the camera is below the surface in first-person it's given a
+//! [`DistanceFog`] of [`SwimConfig::underwater_color`], murking everything
+//! beyond a few metres.
+
+use bevy::{prelude::*, reflect::TypePath};
+use glam::DVec3;
+use serde::Deserialize;
+
+use veldera_config::ConfigPlugin;
+use veldera_geo::{coords::ecef_to_geodetic, floating_origin::FloatingOriginCamera};
+
+use crate::RenderPlayer;
+
+/// Plugin for the water volume's config and the underwater tint.
+pub(crate) struct SwimPlugin {
+    pub path: &'static str,
+}
+
+impl Plugin for SwimPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<SwimConfig>::new(self.path))
+            .add_systems(Update, apply_underwater_tint);
+    }
+}
+
+/// Hot-reloadable swimming tuning, loaded from
+/// `assets/config/game/player/swim.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SwimConfig {
+    /// Master switch. `false` leaves the sea as dry (if bottomless) land.
+    pub enabled: bool,
+    /// Height of the water surface above the WGS84 ellipsoid (m). The real
+    /// sea surface follows the geoid, which strays up to about 100 m from the
+    /// ellipsoid, so this is a global approximation of sea level.
+    pub sea_level_m: f64,
+    /// Submerged fraction of the body past which the player swims rather
+    /// than wading.
+    pub swim_submersion: f32,
+    /// Submerged fraction at which buoyancy balances gravity: where a player
+    /// at rest floats. Below 1 keeps the head above water.
+    pub float_submersion: f32,
+    /// Linear water drag at full submersion (1/s), scaled by the submerged
+    /// fraction. Arrests a dive from height and slows wading.
+    pub water_drag: f32,
+    /// Swimming speed (m/s).
+    pub swim_speed: f32,
+    /// Swimming speed while sprinting (m/s).
+    pub sprint_swim_speed: f32,
+    /// Swimming acceleration (m/s²).
+    pub swim_acceleration: f32,
+    /// Linear RGB tint of the water around an underwater camera.
+    pub underwater_color: [f32; 3],
+    /// Visibility under water (m): the distance at which the tint swallows
+    /// everything.
+    pub underwater_visibility_m: f32,
+}
+
+/// Fraction (0–1) of a body `height` m tall, centred at `centre_ecef`, that's
+/// under water.
+pub(crate) fn submersion(config: &SwimConfig, centre_ecef: DVec3, height: f32) -> f32 {
+    if !config.enabled || height <= 0.0 {
+        return 0.0;
+    }
+    let (_, _, altitude) = ecef_to_geodetic(centre_ecef);
+    let feet = altitude - f64::from(height) * 0.5;
+    ((config.sea_level_m - feet) / f64::from(height)).clamp(0.0, 1.0) as f32
+}
+
+/// Advance a swimming velocity by one `dt` step: gravity against buoyancy
+/// (which balances it at [`SwimConfig::float_submersion`]), then water drag.
+pub(crate) fn swim_velocity_step(
+    velocity: Vec3,
+    up: Vec3,
+    gravity: f32,
+    submersion: f32,
+    config: &SwimConfig,
+    dt: f32,
+) -> Vec3 {
+    let buoyancy = gravity * submersion / config.float_submersion.max(0.05);
+    water_drag_step(
+        velocity + up * ((buoyancy - gravity) * dt),
+        submersion,
+        config,
+        dt,
+    )
+}
+
+/// Slow `velocity` by one `dt` step of water drag at `submersion`.
+pub(crate) fn water_drag_step(
+    velocity: Vec3,
+    submersion: f32,
+    config: &SwimConfig,
+    dt: f32,
+) -> Vec3 {
+    velocity * (1.0 - config.water_drag * submersion * dt).max(0.0)
+}
+
+/// Tint the first-person camera while it's under water, and clear the tint
+/// once it surfaces or leaves first-person.
+fn apply_underwater_tint(
+    mut commands: Commands,
+    config: Res<SwimConfig>,
+    mut underwater: Local<bool>,
+    camera: Query<(Entity, &FloatingOriginCamera, Has<RenderPlayer>)>,
+) {
+    let Ok((entity, camera, first_person)) = camera.single() else {
+        return;
+    };
+    let submerged =
+        config.enabled && first_person && ecef_to_geodetic(camera.position).2 < config.sea_level_m;
+    if submerged == *underwater && !(submerged && config.is_changed()) {
+        return;
+    }
+    *underwater = submerged;
+
+    if submerged {
+        let [r, g, b] = config.underwater_color;
+        commands.entity(entity).insert(DistanceFog {
+            color: Color::linear_rgb(r, g, b),
+            falloff: FogFalloff::from_visibility(config.underwater_visibility_m.max(1.0)),
+            ..default()
+        });
+    } else {
+        commands.entity(entity).remove::<DistanceFog>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use veldera_geo::coords::geodetic_to_ecef;
+
+    use super::*;
+
+    fn config() -> SwimConfig {
+        SwimConfig {
+            enabled: true,
+            sea_level_m: 0.0,
+            swim_submersion: 0.6,
+            float_submersion: 0.85,
+            water_drag: 2.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn floats_where_buoyancy_balances_gravity() {
+        let config = config();
+        let up = Vec3::Z;
+        // Centred 0.6 m below the surface, a 2 m body is 80% under.
+        let centre = geodetic_to_ecef(10.0, 20.0, -0.6);
+        let under = submersion(&config, centre, 2.0);
+        assert!((under - 0.8).abs() < 1e-3);
+        assert_eq!(
+            submersion(&config, geodetic_to_ecef(10.0, 20.0, 5.0), 2.0),
+            0.0
+        );
+
+        // Less than the float fraction sinks, more rises, and at it rests.
+        let sink = swim_velocity_step(Vec3::ZERO, up, 9.81, under, &config, 0.1);
+        let rise = swim_velocity_step(Vec3::ZERO, up, 9.81, 1.0, &config, 0.1);
+        let rest = swim_velocity_step(Vec3::ZERO, up, 9.81, 0.85, &config, 0.1);
+        assert!(sink.z < 0.0 && rise.z > 0.0 && rest.z.abs() < 1e-5);
+    }
+}
@@ -395,6 +395,8 @@ pub(super) fn drive_arm_point(
     // goes limp with the rest of the body and the gesture ramps back down.
     let player = logical_query.single().ok();
     let is_ragdolling = player.is_some_and(|(c, _)| c.ragdoll_state == RagdollState::Ragdolling);
+    // Holding Ascend in the water swims upward rather than charging a leap.
+    let is_swimming = player.is_some_and(|(c, _)| c.swimming);
 
     // Tick the cooldown regardless of input.
     if state.cooldown_s > 0.0 {
@@ -429,7 +431,8 @@ pub(super) fn drive_arm_point(
     }
 
     // The hold becomes a charge once past the tap threshold, when allowed.
-    let charging = state.is_charge_hold(&config) && !is_ragdolling && state.cooldown_s <= 0.0;
+    let charging =
+        state.is_charge_hold(&config) && !is_ragdolling && !is_swimming && state.cooldown_s <= 0.0;
 
     // The arm raises while charging (the wind-up tell) and for the purely
     // cosmetic Point gesture.
@@ -515,6 +518,10 @@ pub(super) fn handle_yeet(
     if controller.ragdoll_state == RagdollState::Ragdolling {
         return;
     }
+    // Nor while swimming, where holding Ascend swims upward.
+    if controller.swimming {
+        return;
+    }
 
     let charge_ratio = (state.charge_seconds / config.max_charge_duration_s).clamp(0.0, 1.0);
 
@@ -0,0 +1,148 @@
+//! Remote control: drive the viewer from another machine.
+//!
+//! Meant for demo installations. When enabled, a small server accepts
+//! command lines over HTTP (`POST /command`, the line as the body) or a
+//! WebSocket (`GET /ws`, one line per text message) and runs them through the
+//! same registry as the developer console, so `tp`, `time`, `track`,
+//! `screenshot`, and whatever else plugins register are all available. Every
+//! reply is JSON: `{"ok": true, "output": ...}` or `{"ok": false, "error":
+//! ...}`.
+//!
+//! Requests must carry the configured token, as an `Authorization: Bearer`
+//! header or a `token` query parameter; the server refuses to start without
+//! one. Connections are served on their own threads, which hand lines to the
+//! world over a channel and wait for the result, so commands run in `Update`
+//! like console input. Native only: browsers can't listen on sockets.
+
+mod server;
+
+use std::net::SocketAddr;
+
+use async_channel::Receiver;
+use bevy::{prelude::*, reflect::TypePath};
+use serde::Deserialize;
+
+use veldera_config::ConfigPlugin;
+use veldera_game_console::{ConsolePlugin, run_console_line};
+
+use crate::server::{RemoteRequest, Server};
+
+/// Plugin for the remote-control server.
+///
+/// The host supplies the [`RemoteControlConfig`] path.
+pub struct RemoteControlPlugin {
+    /// Path to the [`RemoteControlConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl RemoteControlPlugin {
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Plugin for RemoteControlPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ConsolePlugin>() {
+            app.add_plugins(ConsolePlugin);
+        }
+        app.add_plugins(ConfigPlugin::<RemoteControlConfig>::new(self.config_path))
+            .init_resource::<RemoteControlState>()
+            .add_systems(Update, (manage_server, run_remote_commands).chain());
+    }
+}
+
+/// Remote-control settings, loaded from `assets/game/config/remote.toml`.
+#[derive(Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemoteControlConfig {
+    /// Master switch. Turning it off stops the server and closes its
+    /// connections.
+    pub enabled: bool,
+    /// Local address to listen on. Read when the server starts; toggle
+    /// `enabled` off and on to rebind.
+    pub bind_address: String,
+    /// Token every request must carry. Read when the server starts, like
+    /// `bind_address`; the server won't start while it is empty.
+    pub token: String,
+}
+
+impl Default for RemoteControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:7780".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+/// Live remote-control state: the server and the commands it has received.
+#[derive(Resource, Default)]
+pub struct RemoteControlState {
+    /// The running server, while enabled.
+    server: Option<Server>,
+    /// Command lines from the server's connections.
+    requests: Option<Receiver<RemoteRequest>>,
+    /// Whether starting the server failed; cleared when remote control is
+    /// turned off, so toggling it retries.
+    start_failed: bool,
+}
+
+impl RemoteControlState {
+    /// The address the server listens on, while running.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.server.as_ref().map(Server::local_address)
+    }
+}
+
+/// Start or stop the server to follow the config's master switch.
+fn manage_server(config: Res<RemoteControlConfig>, mut state: ResMut<RemoteControlState>) {
+    if !config.enabled {
+        if state.server.take().is_some() {
+            // Connections waiting on a command get an error.
+            state.requests = None;
+            tracing::info!("Remote control disabled");
+        }
+        state.start_failed = false;
+        return;
+    }
+    if state.server.is_some() || state.start_failed {
+        return;
+    }
+
+    if config.token.trim().is_empty() {
+        tracing::warn!("Remote control needs a token; not starting the server");
+        state.start_failed = true;
+        return;
+    }
+    let (sender, receiver) = async_channel::unbounded();
+    match Server::start(&config.bind_address, config.token.trim(), sender) {
+        Ok(server) => {
+            tracing::info!("Remote control listening on {}", server.local_address());
+            state.server = Some(server);
+            state.requests = Some(receiver);
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to start remote control on '{}': {e}",
+                config.bind_address
+            );
+            state.start_failed = true;
+        }
+    }
+}
+
+/// Run the command lines received since the last frame, replying to each.
+fn run_remote_commands(world: &mut World) {
+    let Some(requests) = world.resource::<RemoteControlState>().requests.clone() else {
+        return;
+    };
+    while let Ok(request) = requests.try_recv() {
+        tracing::info!("Remote command: {}", request.line);
+        let result = run_console_line(world, &request.line);
+        // The client may have hung up meanwhile.
+        let _ = request.reply.try_send(result);
+    }
+}
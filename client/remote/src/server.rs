@@ -0,0 +1,425 @@
+//! The server side: accepting connections, speaking just enough HTTP/1.1 and
+//! WebSocket, and checking tokens.
+//!
+//! Each connection gets its own thread. HTTP connections carry one request
+//! and close; WebSocket connections stay open and run one command per text
+//! message, replying in order.
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use async_channel::Sender;
+use tungstenite::{Message, WebSocket, handshake::derive_accept_key, protocol::Role};
+
+use veldera_game_console::CommandResult;
+
+/// How often idle threads check whether the server is shutting down.
+const SHUTDOWN_POLL: Duration = Duration::from_millis(250);
+
+/// How long a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest request head (request line and headers) accepted, in bytes.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// Largest `POST /command` body accepted, in bytes.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A command line from a connection, and where to send its result.
+pub(crate) struct RemoteRequest {
+    pub line: String,
+    pub reply: Sender<CommandResult>,
+}
+
+/// A running server. Dropping it stops accepting connections and closes the
+/// open WebSockets.
+pub(crate) struct Server {
+    shutdown: Arc<AtomicBool>,
+    local_address: SocketAddr,
+}
+
+impl Server {
+    /// Listen on `bind_address`, sending authorised command lines to
+    /// `requests`.
+    pub fn start(
+        bind_address: &str,
+        token: &str,
+        requests: Sender<RemoteRequest>,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind_address)?;
+        // Polled, so the accept thread notices a shutdown.
+        listener.set_nonblocking(true)?;
+        let local_address = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let connection = Connection {
+            token: token.into(),
+            requests,
+            shutdown: shutdown.clone(),
+        };
+        thread::Builder::new()
+            .name("remote-control".to_string())
+            .spawn(move || accept_connections(&listener, &connection))?;
+        Ok(Self {
+            shutdown,
+            local_address,
+        })
+    }
+
+    /// The address the server listens on.
+    pub fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What every connection thread shares.
+#[derive(Clone)]
+struct Connection {
+    token: Arc<str>,
+    requests: Sender<RemoteRequest>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl Connection {
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// Run `line` in the world and wait for its result.
+    fn run(&self, line: String) -> CommandResult {
+        let closed = || "The viewer stopped accepting remote commands".to_string();
+        let (reply, result) = async_channel::bounded(1);
+        self.requests
+            .send_blocking(RemoteRequest { line, reply })
+            .map_err(|_| closed())?;
+        result.recv_blocking().map_err(|_| closed())?
+    }
+}
+
+fn accept_connections(listener: &TcpListener, connection: &Connection) {
+    while !connection.is_shutting_down() {
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(SHUTDOWN_POLL);
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Remote control accept failed: {e}");
+                thread::sleep(SHUTDOWN_POLL);
+                continue;
+            }
+        };
+        let connection = connection.clone();
+        let spawned = thread::Builder::new()
+            .name(format!("remote-control {peer}"))
+            .spawn(move || {
+                if let Err(e) = serve(stream, &connection) {
+                    tracing::debug!("Remote control connection from {peer} failed: {e}");
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("Failed to start a remote control connection thread: {e}");
+        }
+    }
+}
+
+/// Serve one connection: check the token, then route the request.
+fn serve(mut stream: TcpStream, connection: &Connection) -> io::Result<()> {
+    // Some platforms hand out accepted sockets with the listener's
+    // non-blocking mode.
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let (request, leftover) = read_head(&mut stream)?;
+
+    if !request.is_authorized(&connection.token) {
+        return respond(&mut stream, 401, &Err("Missing or wrong token".to_string()));
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/ws") if request.is_websocket_upgrade() => {
+            serve_websocket(stream, &request, connection)
+        }
+        ("POST", "/command") => {
+            let body = read_body(&mut stream, &request, leftover)?;
+            let result = match String::from_utf8(body) {
+                Ok(line) => connection.run(line),
+                Err(_) => Err("The command isn't UTF-8".to_string()),
+            };
+            let status = if result.is_ok() { 200 } else { 400 };
+            respond(&mut stream, status, &result)
+        }
+        (_, "/command" | "/ws") => respond(&mut stream, 405, &Err("Method not allowed".into())),
+        _ => respond(&mut stream, 404, &Err("Not found".into())),
+    }
+}
+
+/// Complete a WebSocket handshake and run each text message as a command.
+fn serve_websocket(
+    mut stream: TcpStream,
+    request: &Request,
+    connection: &Connection,
+) -> io::Result<()> {
+    let key = request
+        .header("sec-websocket-key")
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "missing Sec-WebSocket-Key"))?;
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    )?;
+    // Wake up now and then to notice a shutdown.
+    stream.set_read_timeout(Some(SHUTDOWN_POLL))?;
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    loop {
+        let message = match socket.read() {
+            Ok(message) => message,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                if connection.is_shutting_down() {
+                    // Best effort; the server is going away either way.
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return Ok(());
+                }
+                continue;
+            }
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                return Ok(());
+            }
+            Err(e) => return Err(io::Error::other(e)),
+        };
+        // Pings and the closing handshake are answered by `read`.
+        if let Message::Text(line) = message {
+            let reply = reply_json(&connection.run(line.to_string()));
+            socket
+                .send(Message::text(reply))
+                .map_err(io::Error::other)?;
+        }
+    }
+}
+
+/// A parsed request head.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    /// Decoded query parameters.
+    query: Vec<(String, String)>,
+    /// Headers, with lowercase names.
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Parse a request head (without the blank line that ends it).
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split(' ');
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?;
+        if !request_line.next()?.starts_with("HTTP/1.") {
+            return None;
+        }
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                Some((decode(name)?, decode(value)?))
+            })
+            .collect::<Option<_>>()?;
+        let headers = lines
+            .map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            method,
+            path: path.to_string(),
+            query,
+            headers,
+        })
+    }
+
+    /// The first value of header `name` (lowercase).
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the request carries `token`, as a bearer token or a `token`
+    /// query parameter (browsers can't set headers on WebSockets).
+    fn is_authorized(&self, token: &str) -> bool {
+        let bearer = self
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let query = self
+            .query
+            .iter()
+            .find(|(name, _)| name == "token")
+            .map(|(_, value)| value.as_str());
+        bearer
+            .into_iter()
+            .chain(query)
+            .any(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()))
+    }
+
+    fn is_websocket_upgrade(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+fn decode(text: &str) -> Option<String> {
+    urlencoding::decode(&text.replace('+', " "))
+        .ok()
+        .map(|decoded| decoded.into_owned())
+}
+
+/// Compare without stopping at the first difference, so response timing
+/// doesn't leak how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Read and parse the request head, returning it with any body bytes read
+/// past it.
+fn read_head(stream: &mut TcpStream) -> io::Result<(Request, Vec<u8>)> {
+    let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 1024];
+    let end = loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(invalid("request head too large"));
+        }
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Err(invalid("connection closed mid-request"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+    let head =
+        std::str::from_utf8(&buffer[..end]).map_err(|_| invalid("request head isn't UTF-8"))?;
+    let request = Request::parse(head).ok_or_else(|| invalid("malformed request"))?;
+    Ok((request, buffer[end + 4..].to_vec()))
+}
+
+/// Read the body announced by `Content-Length`, continuing from `body`.
+fn read_body(stream: &mut TcpStream, request: &Request, mut body: Vec<u8>) -> io::Result<Vec<u8>> {
+    let length: usize = request
+        .header("content-length")
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "missing Content-Length"))?;
+    if length > MAX_BODY_BYTES {
+        return Err(io::Error::new(ErrorKind::InvalidData, "body too large"));
+    }
+    let already = body.len().min(length);
+    body.resize(length, 0);
+    stream.read_exact(&mut body[already..])?;
+    Ok(body)
+}
+
+/// Send a JSON response for `result` and close.
+fn respond(stream: &mut TcpStream, status: u16, result: &CommandResult) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Method Not Allowed",
+    };
+    let body = reply_json(result);
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// `{"ok": true, "output": ...}` or `{"ok": false, "error": ...}`.
+fn reply_json(result: &CommandResult) -> String {
+    match result {
+        Ok(output) => serde_json::json!({ "ok": true, "output": output }),
+        Err(error) => serde_json::json!({ "ok": false, "error": error }),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_heads() {
+        let request = Request::parse(
+            "GET /ws?token=s3cr%2Bt&x HTTP/1.1\r\nHost: viewer\r\nUpgrade: WebSocket\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/ws");
+        assert_eq!(
+            request.query,
+            [
+                ("token".to_string(), "s3cr+t".to_string()),
+                ("x".to_string(), String::new())
+            ]
+        );
+        assert_eq!(request.header("host"), Some("viewer"));
+        assert!(request.is_websocket_upgrade());
+
+        assert!(Request::parse("GET /ws").is_none());
+        assert!(Request::parse("GET / HTTP/1.1\r\nno colon").is_none());
+    }
+
+    #[test]
+    fn checks_tokens() {
+        let request = |head: &str| Request::parse(head).unwrap();
+        let bearer = request("POST /command HTTP/1.1\r\nAuthorization: Bearer s3cret");
+        assert!(bearer.is_authorized("s3cret"));
+        assert!(!bearer.is_authorized("s3cre"));
+        assert!(request("GET /ws?token=s3cret HTTP/1.1").is_authorized("s3cret"));
+        assert!(!request("GET /ws HTTP/1.1").is_authorized("s3cret"));
+        assert!(!request("GET /ws?token=other HTTP/1.1").is_authorized("s3cret"));
+    }
+
+    #[test]
+    fn replies_in_json() {
+        let parse = |json: String| serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(
+            parse(reply_json(&Ok("Teleporting".to_string()))),
+            serde_json::json!({ "ok": true, "output": "Teleporting" })
+        );
+        assert_eq!(
+            parse(reply_json(&Err("Missing latitude".to_string()))),
+            serde_json::json!({ "ok": false, "error": "Missing latitude" })
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Teleport history: browser-style Back and Forward through visited places.
+//!
+//! Every teleport records where it left from and where it's headed, and a
+//! manual move further than [`GeoConfig::history_move_threshold_m`] from the
+//! current entry records the new position. Going Back or Forward flies to the
+//! neighbouring entry with the teleport animation, capped at
+//! [`GeoConfig::history_flight_max_s`]; as in a browser, visiting somewhere
+//! new drops the entries ahead of the current one.
+
+use bevy::prelude::*;
+use glam::DVec3;
+
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use crate::{GeoConfig, RoutePlanner, TeleportAnimation, TeleportState};
+
+/// Most entries kept; the oldest are dropped beyond this.
+const MAX_ENTRIES: usize = 100;
+
+/// Plugin for the teleport history.
+pub(crate) struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TeleportHistory>()
+            .add_systems(Update, record_manual_moves);
+    }
+}
+
+/// Visited positions, oldest first, and which one the camera is at.
+#[derive(Resource, Default)]
+pub struct TeleportHistory {
+    /// Visited positions in ECEF (m).
+    entries: Vec<DVec3>,
+    /// Index of the current entry; meaningless while `entries` is empty.
+    cursor: usize,
+}
+
+impl TeleportHistory {
+    /// Visited positions in ECEF, oldest first.
+    pub fn entries(&self) -> &[DVec3] {
+        &self.entries
+    }
+
+    /// Index of the current entry, if any.
+    pub fn current(&self) -> Option<usize> {
+        (!self.entries.is_empty()).then_some(self.cursor)
+    }
+
+    /// Whether there's an entry before the current one.
+    pub fn can_go_back(&self) -> bool {
+        self.current().is_some_and(|i| i > 0)
+    }
+
+    /// Whether there's an entry after the current one.
+    pub fn can_go_forward(&self) -> bool {
+        self.current().is_some_and(|i| i + 1 < self.entries.len())
+    }
+
+    /// Fly to the previous entry. Returns whether there was one.
+    pub fn back(&mut self, teleport: &mut TeleportState) -> bool {
+        self.can_go_back() && self.go_to(self.cursor - 1, teleport)
+    }
+
+    /// Fly to the next entry. Returns whether there was one.
+    pub fn forward(&mut self, teleport: &mut TeleportState) -> bool {
+        self.can_go_forward() && self.go_to(self.cursor + 1, teleport)
+    }
+
+    /// Fly to entry `index`, keeping the entries on both sides of it.
+    /// Returns whether the entry exists.
+    pub fn go_to(&mut self, index: usize, teleport: &mut TeleportState) -> bool {
+        let Some(&position) = self.entries.get(index) else {
+            return false;
+        };
+        self.cursor = index;
+        teleport.request_history(position);
+        true
+    }
+
+    /// Record a visit to `position`: drop the entries ahead of the current
+    /// one, then make `position` the current entry unless it's within
+    /// `min_distance_m` of it already.
+    pub(crate) fn visit(&mut self, position: DVec3, min_distance_m: f64) {
+        if let Some(current) = self.current() {
+            self.entries.truncate(current + 1);
+            if self.entries[current].distance(position) < min_distance_m {
+                return;
+            }
+        }
+        self.entries.push(position);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+}
+
+/// Record the camera's position once it has moved far enough from the
+/// current entry on its own (not in a teleport or a route flight).
+fn record_manual_moves(
+    config: Res<GeoConfig>,
+    animation: Res<TeleportAnimation>,
+    route: Res<RoutePlanner>,
+    teleport: Res<TeleportState>,
+    mut history: ResMut<TeleportHistory>,
+    camera: Query<&FloatingOriginCamera>,
+) {
+    if config.history_move_threshold_m <= 0.0
+        || animation.is_active()
+        || route.is_flying()
+        || teleport.is_pending()
+    {
+        return;
+    }
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+    let moved = history.current().is_none_or(|i| {
+        history.entries[i].distance(camera.position) >= config.history_move_threshold_m
+    });
+    if moved {
+        history.visit(camera.position, config.history_move_threshold_m);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visiting_drops_the_entries_ahead() {
+        let mut history = TeleportHistory::default();
+        let mut teleport = TeleportState::default();
+        for x in [0.0, 100.0, 200.0] {
+            history.visit(DVec3::new(x, 0.0, 0.0), 10.0);
+        }
+        assert!(history.back(&mut teleport));
+        assert!(history.back(&mut teleport));
+        assert_eq!(history.current(), Some(0));
+        assert!(!history.back(&mut teleport));
+        assert!(history.forward(&mut teleport));
+
+        // Close to the current entry: nothing new, but the entry ahead goes.
+        history.visit(DVec3::new(105.0, 0.0, 0.0), 10.0);
+        assert_eq!(history.entries().len(), 2);
+        assert!(!history.can_go_forward());
+
+        history.visit(DVec3::new(300.0, 0.0, 0.0), 10.0);
+        assert_eq!(history.entries().len(), 3);
+        assert_eq!(history.current(), Some(2));
+    }
+}
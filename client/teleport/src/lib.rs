@@ -5,7 +5,18 @@
 //! On a teleport request the destination elevation is fetched (via
 //! [`veldera_places::fetch_elevation`]); once it arrives the flight arc begins. Two
 //! orientation styles are supported (classic zoom-out and horizon-chasing),
-//! and the wind-loop / whoosh audio is driven from the animation phase.
+//! and the wind-loop / whoosh audio is driven from the animation phase. The
+//! flight can be skipped ([`TeleportAnimation::skip`]), jumping straight to
+//! the wait for destination physics, and a new request mid-flight takes over
+//! from wherever the camera is.
+//!
+//! The [`route`] module adds a great-circle route planner alongside: the route
+//! between two chosen places is drawn on the globe, and the camera can cruise
+//! along it. The [`history`] module keeps the places visited, for Back and
+//! Forward.
+
+pub mod history;
+pub mod route;
 
 use avian3d::prelude::*;
 use bevy::{audio::Volume, prelude::*, reflect::TypePath};
@@ -26,6 +37,9 @@ use veldera_geo::{
 };
 use veldera_places::{HttpClient, fetch_elevation};
 
+pub use history::TeleportHistory;
+pub use route::{GreatCircle, RouteEndpoint, RoutePlanner};
+
 /// Plugin for the cinematic fly-to-location teleport.
 ///
 /// The host supplies the [`GeoConfig`] path. The shared HTTP client comes from
@@ -47,6 +61,7 @@ impl Plugin for TeleportPlugin {
         app.add_plugins(ConfigPlugin::<GeoConfig>::new(self.config_path))
             .init_resource::<TeleportState>()
             .init_resource::<TeleportAnimation>()
+            .add_plugins((route::RoutePlugin, history::HistoryPlugin))
             .add_systems(Startup, load_teleport_sounds)
             .add_systems(
                 Update,
@@ -67,6 +82,11 @@ struct WooshSoundHandle(Handle<AudioSource>);
 #[derive(Resource)]
 struct WindLoopSoundHandle(Handle<AudioSource>);
 
+/// Longest step (s) the animation advances in one frame. A stall (focus
+/// loss, a long load) then doesn't jump the camera most of the way along the
+/// arc, or past the physics wait, in a single frame.
+const MAX_ANIMATION_STEP_S: f32 = 0.1;
+
 /// Marker component for the teleport wind loop audio entity.
 #[derive(Component)]
 struct TeleportWindLoop;
@@ -110,14 +130,18 @@ pub struct TeleportState {
     pub error: Option<String>,
     /// Whether a departure woosh should be played (set on request, cleared after playing).
     play_departure_woosh: bool,
-    elevation_rx: async_channel::Receiver<Result<f64, String>>,
-    elevation_tx: async_channel::Sender<Result<f64, String>>,
+    /// Id of the next request, so the elevation of a superseded one is ignored.
+    next_request_id: u64,
+    elevation_rx: async_channel::Receiver<(u64, Result<f64, String>)>,
+    elevation_tx: async_channel::Sender<(u64, Result<f64, String>)>,
 }
 
-/// A pending teleport request waiting for elevation data.
-struct PendingTeleport {
-    lat: f64,
-    lon: f64,
+/// A pending teleport request.
+enum PendingTeleport {
+    /// Waiting for the elevation at `lat`, `lon`.
+    LatLon { id: u64, lat: f64, lon: f64 },
+    /// A history entry, whose position is known: flown straight away.
+    History { position: DVec3 },
 }
 
 impl Default for TeleportState {
@@ -127,6 +151,7 @@ impl Default for TeleportState {
             pending: None,
             error: None,
             play_departure_woosh: false,
+            next_request_id: 0,
             elevation_rx,
             elevation_tx,
         }
@@ -150,8 +175,11 @@ impl TeleportState {
         client: &HttpClient,
         spawner: &TaskSpawner<'_, '_>,
     ) {
-        // Cancel any existing pending teleport.
-        self.pending = Some(PendingTeleport { lat, lon });
+        // Supersede any existing pending teleport; its elevation is dropped
+        // when it arrives.
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+        self.pending = Some(PendingTeleport::LatLon { id, lat, lon });
         self.error = None;
         self.play_departure_woosh = true;
 
@@ -160,9 +188,17 @@ impl TeleportState {
 
         spawner.spawn(async move {
             let result = fetch_elevation(&client, lat, lon).await;
-            let _ = tx.send(result).await;
+            let _ = tx.send((id, result)).await;
         });
     }
+
+    /// Request a flight to a history entry at ECEF `position`; see
+    /// [`TeleportHistory`].
+    fn request_history(&mut self, position: DVec3) {
+        self.pending = Some(PendingTeleport::History { position });
+        self.error = None;
+        self.play_departure_woosh = true;
+    }
 }
 
 /// State for the cinematic teleportation animation.
@@ -196,6 +232,28 @@ impl TeleportAnimation {
         })
     }
 
+    /// Returns true if the arc is still playing, so it can be skipped.
+    pub fn can_skip(&self) -> bool {
+        self.phase
+            .as_ref()
+            .is_some_and(|p| matches!(p.state, AnimationState::Flying))
+    }
+
+    /// Skip the rest of the arc: the camera jumps to the destination on the
+    /// next update and waits for physics there. Returns whether there was an
+    /// arc to skip.
+    pub fn skip(&mut self) -> bool {
+        match &mut self.phase {
+            Some(phase) if matches!(phase.state, AnimationState::Flying) => {
+                phase.elapsed = phase.duration;
+                // The arrival woosh belongs to the descent, which is skipped.
+                phase.arrival_woosh_played = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Cancel the current animation and return the current position if any.
     pub fn cancel(&mut self) -> Option<DVec3> {
         self.phase.take().map(|p| p.current_position())
@@ -238,6 +296,13 @@ pub struct GeoConfig {
     /// Height above the detected ground (m) at which the player respawns after a
     /// teleport.
     pub spawn_height_above_ground_m: f32,
+    /// Longest flight (s) between history entries, for Back and Forward.
+    pub history_flight_max_s: f32,
+    /// Distance (m) from the current history entry past which a manual move
+    /// records a new one; 0 records only teleports.
+    pub history_move_threshold_m: f64,
+    /// Drawing and flying planned routes.
+    pub route: route::RouteConfig,
 }
 
 /// Tuning for the teleport flight arc.
@@ -672,13 +737,15 @@ fn horizon_cruise_orientation(phase: &TeleportPhase, t: f64, dt: f64) -> Quat {
         .rotation
 }
 
-/// Poll for elevation results and start teleport animation.
+/// Poll for elevation results and history requests, and start the teleport
+/// animation.
 #[allow(clippy::too_many_arguments)]
 fn poll_teleport(
     mut commands: Commands,
     config: Res<GeoConfig>,
     mut teleport_state: ResMut<TeleportState>,
     mut animation: ResMut<TeleportAnimation>,
+    mut history: ResMut<TeleportHistory>,
     camera_config: Res<CameraConfig>,
     camera_mode: Res<CameraModeState>,
     camera_query: Query<(Entity, &FloatingOriginCamera, &FlightCamera)>,
@@ -686,152 +753,178 @@ fn poll_teleport(
     wind_loop_query: Query<Entity, With<TeleportWindLoop>>,
     wind_loop_sound: Option<Res<WindLoopSoundHandle>>,
 ) {
-    while let Ok(result) = teleport_state.elevation_rx.try_recv() {
-        let Some(pending) = teleport_state.pending.take() else {
+    // The destination, and whether it's a history entry (a short flight that
+    // isn't itself recorded).
+    let mut destination = None;
+    if let Some(PendingTeleport::History { position }) = teleport_state.pending {
+        teleport_state.pending = None;
+        destination = Some((position, true));
+    }
+    while let Ok((id, result)) = teleport_state.elevation_rx.try_recv() {
+        // Drop the elevation of a request that's since been superseded.
+        let Some(PendingTeleport::LatLon { lat, lon, .. }) = teleport_state.pending.take_if(
+            |p| matches!(p, PendingTeleport::LatLon { id: pending_id, .. } if *pending_id == id),
+        ) else {
             continue;
         };
 
         match result {
             Ok(elevation) => {
                 teleport_state.error = None;
-
-                if let Ok((camera_entity, origin_camera, flight_camera)) = camera_query.single() {
-                    // Get the current position (either from current animation or from camera).
-                    // Also stop any existing wind loop if we're canceling an animation.
-                    for entity in &wind_loop_query {
-                        commands.entity(entity).despawn();
-                    }
-                    let start_position = animation.cancel().unwrap_or(origin_camera.position);
-                    let start_direction = flight_camera.direction;
-
-                    // Set radius to earth_radius + elevation + small offset above ground.
-                    let radius = veldera_constants::EARTH_RADIUS_M_F64 + elevation + 10.0;
-                    let target_position = lat_lon_to_ecef(pending.lat, pending.lon, radius);
-
-                    // Check for very short distance: skip animation.
-                    let distance = (target_position - start_position).length();
-                    if distance < 1.0 {
-                        // Same position, skip entirely.
-                        continue;
-                    }
-
-                    // If in FPS mode, despawn the logical player and remove RenderPlayer from camera.
-                    if camera_mode.is_fps_controller() {
-                        if let Ok(player_entity) = logical_player_query.single() {
-                            commands.entity(player_entity).despawn();
-                        }
-                        commands.entity(camera_entity).remove::<RenderPlayer>();
-                    }
-
-                    // Compute surface distance for duration calculation.
-                    let start_norm = start_position.normalize();
-                    let target_norm = target_position.normalize();
-                    let arc_angle = start_norm.dot(target_norm).clamp(-1.0, 1.0).acos();
-                    let surface_distance = arc_angle * veldera_constants::EARTH_RADIUS_M_F64;
-
-                    // Create the trajectory.
-                    let trajectory = ArcTrajectory::new(
-                        &config.arc,
-                        start_position,
-                        target_position,
-                        camera_config.teleport_animation_mode,
-                    );
-                    let duration =
-                        ArcTrajectory::compute_duration(&config.arc.duration, surface_distance);
-                    let apex_altitude = trajectory.apex_altitude;
-
-                    // Compute orientation keyframes and up vectors.
-                    let start_up = start_norm.as_vec3();
-                    let target_up = target_norm.as_vec3();
-
-                    // Direction of travel (tangent to great circle at start).
-                    let travel_dir = (target_norm - start_norm * start_norm.dot(target_norm))
-                        .normalize()
-                        .as_vec3();
-                    // Handle antipodal case: pick arbitrary perpendicular direction.
-                    let travel_dir = if travel_dir.length_squared() < 0.01 {
-                        let start_frame = RadialFrame::from_ecef_position(start_position);
-                        start_frame.north
-                    } else {
-                        travel_dir
-                    };
-
-                    let target_frame = RadialFrame::from_ecef_position(target_position);
-
-                    // orient_start: Initial camera orientation.
-                    let orient_start = Transform::IDENTITY
-                        .looking_to(start_direction, start_up)
-                        .rotation;
-
-                    // orient_end: Final orientation at the destination.
-                    // Classic mode looks north; horizon-chasing looks along the
-                    // arrival travel direction so the descent doesn't require a yaw turn.
-                    let end_direction = match camera_config.teleport_animation_mode {
-                        TeleportAnimationMode::Classic => target_frame.north,
-                        TeleportAnimationMode::HorizonChasing => {
-                            // Travel tangent at the target, continuing in the same
-                            // direction we were flying (start -> target).
-                            let arrival_tangent = great_circle_tangent(
-                                target_position,
-                                start_position,
-                                target_frame.north,
-                            );
-                            // Negate because great_circle_tangent points toward start,
-                            // but we want to continue in the direction of travel.
-                            -arrival_tangent
-                        }
-                    };
-                    let orient_end = Transform::IDENTITY
-                        .looking_to(end_direction, target_up)
-                        .rotation;
-
-                    // ascent_up: When looking down at end of ascent, this is "up" in camera view.
-                    // Use travel direction so ascent rotates toward the destination.
-                    let ascent_up = travel_dir;
-
-                    // descent_up: When looking down at start of descent, this is "up" in camera view.
-                    // Use target's north so descent rotates smoothly to final orientation.
-                    let descent_up = target_frame.north;
-
-                    // Start the animation.
-                    animation.phase = Some(TeleportPhase {
-                        start_position,
-                        target_position,
-                        orient_start,
-                        orient_end,
-                        ascent_up,
-                        descent_up,
-                        duration,
-                        elapsed: 0.0,
-                        trajectory,
-                        camera_mode: camera_mode.current(),
-                        state: AnimationState::Flying,
-                        arrival_woosh_played: false,
-                        animation_mode: camera_config.teleport_animation_mode,
-                    });
-
-                    tracing::info!(
-                        "Starting teleport animation: {:.0}km surface distance, {:.1}s duration, {:.0}km apex",
-                        surface_distance / 1000.0,
-                        duration,
-                        apex_altitude / 1000.0
-                    );
-
-                    // Start wind loop.
-                    if let Some(ref wind) = wind_loop_sound {
-                        commands.spawn((
-                            AudioPlayer::new(wind.0.clone()),
-                            PlaybackSettings::LOOP,
-                            TeleportWindLoop,
-                        ));
-                    }
-                }
+                // Set radius to earth_radius + elevation + small offset above ground.
+                let radius = veldera_constants::EARTH_RADIUS_M_F64 + elevation + 10.0;
+                destination = Some((lat_lon_to_ecef(lat, lon, radius), false));
             }
             Err(e) => {
                 teleport_state.error = Some(e);
             }
         }
     }
+
+    let Some((target_position, from_history)) = destination else {
+        return;
+    };
+    let Ok((camera_entity, origin_camera, flight_camera)) = camera_query.single() else {
+        return;
+    };
+
+    // Check for very short distance: skip animation. Checked before
+    // cancelling, so a repeat request for where a flight is already headed
+    // leaves that flight alone.
+    let current_position = animation
+        .phase
+        .as_ref()
+        .map_or(origin_camera.position, TeleportPhase::current_position);
+    if (target_position - current_position).length() < 1.0 {
+        // Same position, skip entirely.
+        return;
+    }
+
+    // Take over from the current animation, if any: start where it has got
+    // to, and return control in the mode it started from (its player is
+    // already despawned).
+    for entity in &wind_loop_query {
+        commands.entity(entity).despawn();
+    }
+    let previous_mode = animation.phase.as_ref().map(|p| p.camera_mode);
+    let start_position = animation.cancel().unwrap_or(origin_camera.position);
+    let start_direction = flight_camera.direction;
+
+    // A new destination is a visit, from wherever the camera is now.
+    if !from_history {
+        history.visit(start_position, config.history_move_threshold_m);
+        history.visit(target_position, config.history_move_threshold_m);
+    }
+
+    // If in FPS mode, despawn the logical player and remove RenderPlayer from camera.
+    if camera_mode.is_fps_controller() {
+        if let Ok(player_entity) = logical_player_query.single() {
+            commands.entity(player_entity).despawn();
+        }
+        commands.entity(camera_entity).remove::<RenderPlayer>();
+    }
+
+    // Compute surface distance for duration calculation.
+    let start_norm = start_position.normalize();
+    let target_norm = target_position.normalize();
+    let arc_angle = start_norm.dot(target_norm).clamp(-1.0, 1.0).acos();
+    let surface_distance = arc_angle * veldera_constants::EARTH_RADIUS_M_F64;
+
+    // Create the trajectory.
+    let trajectory = ArcTrajectory::new(
+        &config.arc,
+        start_position,
+        target_position,
+        camera_config.teleport_animation_mode,
+    );
+    let mut duration = ArcTrajectory::compute_duration(&config.arc.duration, surface_distance);
+    if from_history {
+        duration = duration.min(config.history_flight_max_s);
+    }
+    let apex_altitude = trajectory.apex_altitude;
+
+    // Compute orientation keyframes and up vectors.
+    let start_up = start_norm.as_vec3();
+    let target_up = target_norm.as_vec3();
+
+    // Direction of travel (tangent to great circle at start).
+    let travel_dir = (target_norm - start_norm * start_norm.dot(target_norm))
+        .normalize()
+        .as_vec3();
+    // Handle antipodal case: pick arbitrary perpendicular direction.
+    let travel_dir = if travel_dir.length_squared() < 0.01 {
+        let start_frame = RadialFrame::from_ecef_position(start_position);
+        start_frame.north
+    } else {
+        travel_dir
+    };
+
+    let target_frame = RadialFrame::from_ecef_position(target_position);
+
+    // orient_start: Initial camera orientation.
+    let orient_start = Transform::IDENTITY
+        .looking_to(start_direction, start_up)
+        .rotation;
+
+    // orient_end: Final orientation at the destination.
+    // Classic mode looks north; horizon-chasing looks along the
+    // arrival travel direction so the descent doesn't require a yaw turn.
+    let end_direction = match camera_config.teleport_animation_mode {
+        TeleportAnimationMode::Classic => target_frame.north,
+        TeleportAnimationMode::HorizonChasing => {
+            // Travel tangent at the target, continuing in the same
+            // direction we were flying (start -> target).
+            let arrival_tangent =
+                great_circle_tangent(target_position, start_position, target_frame.north);
+            // Negate because great_circle_tangent points toward start,
+            // but we want to continue in the direction of travel.
+            -arrival_tangent
+        }
+    };
+    let orient_end = Transform::IDENTITY
+        .looking_to(end_direction, target_up)
+        .rotation;
+
+    // ascent_up: When looking down at end of ascent, this is "up" in camera view.
+    // Use travel direction so ascent rotates toward the destination.
+    let ascent_up = travel_dir;
+
+    // descent_up: When looking down at start of descent, this is "up" in camera view.
+    // Use target's north so descent rotates smoothly to final orientation.
+    let descent_up = target_frame.north;
+
+    // Start the animation.
+    animation.phase = Some(TeleportPhase {
+        start_position,
+        target_position,
+        orient_start,
+        orient_end,
+        ascent_up,
+        descent_up,
+        duration,
+        elapsed: 0.0,
+        trajectory,
+        camera_mode: previous_mode.unwrap_or(camera_mode.current()),
+        state: AnimationState::Flying,
+        arrival_woosh_played: false,
+        animation_mode: camera_config.teleport_animation_mode,
+    });
+
+    tracing::info!(
+        "Starting teleport animation: {:.0}km surface distance, {:.1}s duration, {:.0}km apex",
+        surface_distance / 1000.0,
+        duration,
+        apex_altitude / 1000.0
+    );
+
+    // Start wind loop.
+    if let Some(ref wind) = wind_loop_sound {
+        commands.spawn((
+            AudioPlayer::new(wind.0.clone()),
+            PlaybackSettings::LOOP,
+            TeleportWindLoop,
+        ));
+    }
 }
 
 /// Update the teleport animation each frame.
@@ -857,8 +950,8 @@ fn update_teleport_animation(
         return;
     };
 
-    // Advance elapsed time.
-    phase.elapsed += time.delta_secs();
+    // Advance elapsed time, clamped so a stalled frame doesn't jump ahead.
+    phase.elapsed += time.delta_secs().min(MAX_ANIMATION_STEP_S);
 
     // Get the current camera position for physics-relative coordinates.
     let camera_ecef = camera_query
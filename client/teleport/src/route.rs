@@ -0,0 +1,339 @@
+//! Great-circle route planner: pick an origin and destination, see the route
+//! drawn on the globe, and fly the camera along it.
+//!
+//! A route is the shortest path over the sphere between its two endpoints.
+//! While drawn it is sampled into a gizmo polyline lifted slightly above sea
+//! level; while flown the camera cruises along it at a fixed altitude and
+//! speed, looking ahead and slightly down, and the terrain [`LodFocus`] is
+//! kept a few seconds ahead of the camera so tiles along the path are already
+//! streaming in by the time the camera gets there. The drawing and flight
+//! tuning is the `[route]` table of the [`GeoConfig`].
+
+use bevy::{color::palettes::css, prelude::*};
+use glam::DVec3;
+use serde::Deserialize;
+
+use veldera_camera::FlightCamera;
+use veldera_game_camera_state::{CameraModeState, CameraModeTransitions};
+use veldera_geo::{
+    coords::{RadialFrame, lat_lon_to_ecef, slerp_dvec3},
+    floating_origin::FloatingOriginCamera,
+};
+use veldera_terrain::lod::LodFocus;
+
+use crate::{GeoConfig, TeleportAnimation};
+
+/// Tuning for drawing and flying routes.
+#[derive(Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RouteConfig {
+    /// Segments the drawn route is split into per 1000 km, before clamping.
+    pub segments_per_1000_km: f64,
+    /// Height (m) the drawn route floats above sea level, so it isn't hidden
+    /// under the terrain of low-lying coasts.
+    pub draw_altitude_m: f64,
+    /// How far ahead of the camera (s of travel) the LOD focus sits while
+    /// flying.
+    pub lod_lead_secs: f64,
+    /// Radius (m) of the LOD focus ahead of the camera.
+    pub lod_lead_radius_m: f64,
+    /// Downward pitch of the camera while flying a route (degrees).
+    pub flight_pitch_deg: f32,
+}
+
+impl RouteConfig {
+    /// Segments to draw `route` with.
+    fn segments(&self, route: &GreatCircle) -> usize {
+        ((route.length_m() / 1_000_000.0) * self.segments_per_1000_km).clamp(8.0, 512.0) as usize
+    }
+}
+
+/// Plugin for the route planner.
+pub(crate) struct RoutePlugin;
+
+impl Plugin for RoutePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RoutePlanner>()
+            .add_systems(Update, (fly_route, draw_route).chain());
+    }
+}
+
+/// One end of a route.
+#[derive(Clone, Debug)]
+pub struct RouteEndpoint {
+    /// Display name (a search result or "Current location").
+    pub name: String,
+    /// Latitude (degrees).
+    pub lat: f64,
+    /// Longitude (degrees).
+    pub lon: f64,
+}
+
+impl RouteEndpoint {
+    /// Unit vector from the Earth's centre through this endpoint.
+    fn direction(&self) -> DVec3 {
+        lat_lon_to_ecef(self.lat, self.lon, 1.0).normalize()
+    }
+}
+
+/// The great-circle path between two points on the globe.
+#[derive(Clone, Copy, Debug)]
+pub struct GreatCircle {
+    /// Unit direction of the start point.
+    from: DVec3,
+    /// Unit direction of the end point.
+    to: DVec3,
+    /// Central angle between them (radians).
+    angle: f64,
+}
+
+impl GreatCircle {
+    /// The great circle from `from` to `to` (any nonzero ECEF vectors).
+    pub fn new(from: DVec3, to: DVec3) -> Self {
+        let from = from.normalize();
+        let to = to.normalize();
+        Self {
+            from,
+            to,
+            angle: from.dot(to).clamp(-1.0, 1.0).acos(),
+        }
+    }
+
+    /// Surface length of the route (m).
+    pub fn length_m(&self) -> f64 {
+        self.angle * veldera_constants::EARTH_RADIUS_M_F64
+    }
+
+    /// Unit direction of the point a fraction `t` (`[0, 1]`) along the route.
+    pub fn direction_at(&self, t: f64) -> DVec3 {
+        slerp_dvec3(self.from, self.to, t.clamp(0.0, 1.0))
+    }
+
+    /// Unit direction of travel at fraction `t`, tangent to the globe.
+    pub fn heading_at(&self, t: f64) -> DVec3 {
+        let step = 1e-4;
+        let (a, b) = if t + step <= 1.0 {
+            (t, t + step)
+        } else {
+            (t - step, t)
+        };
+        let here = self.direction_at(t);
+        let delta = self.direction_at(b) - self.direction_at(a);
+        (delta - here * here.dot(delta)).normalize_or_zero()
+    }
+}
+
+/// Route planner state: the chosen endpoints, flight tuning, and the flight
+/// in progress, if any.
+#[derive(Resource)]
+pub struct RoutePlanner {
+    /// Where the route starts.
+    pub origin: Option<RouteEndpoint>,
+    /// Where the route ends.
+    pub destination: Option<RouteEndpoint>,
+    /// Cruise altitude above sea level while flying (m).
+    pub cruise_altitude_m: f64,
+    /// Ground speed while flying (m/s).
+    pub speed_mps: f64,
+    /// Distance flown so far (m), while a flight is in progress.
+    flown_m: Option<f64>,
+}
+
+impl Default for RoutePlanner {
+    fn default() -> Self {
+        Self {
+            origin: None,
+            destination: None,
+            cruise_altitude_m: 3_000.0,
+            speed_mps: 1_000.0,
+            flown_m: None,
+        }
+    }
+}
+
+impl RoutePlanner {
+    /// The route between the chosen endpoints, once both are set.
+    pub fn route(&self) -> Option<GreatCircle> {
+        let origin = self.origin.as_ref()?;
+        let destination = self.destination.as_ref()?;
+        Some(GreatCircle::new(
+            origin.direction(),
+            destination.direction(),
+        ))
+    }
+
+    /// Whether the camera is flying the route.
+    pub fn is_flying(&self) -> bool {
+        self.flown_m.is_some()
+    }
+
+    /// Fraction of the route flown, while flying.
+    pub fn progress(&self) -> Option<f64> {
+        let flown = self.flown_m?;
+        let length = self.route()?.length_m();
+        Some(if length > 0.0 { flown / length } else { 1.0 })
+    }
+
+    /// Start flying the route from the origin. Does nothing without a route.
+    pub fn start_flight(&mut self) {
+        if self.route().is_some() {
+            self.flown_m = Some(0.0);
+        }
+    }
+
+    /// Stop flying, leaving the camera where it is.
+    pub fn stop_flight(&mut self) {
+        self.flown_m = None;
+    }
+
+    /// Swap origin and destination.
+    pub fn reverse(&mut self) {
+        std::mem::swap(&mut self.origin, &mut self.destination);
+    }
+}
+
+/// Move the camera along the route while a flight is in progress.
+///
+/// Flights run in flycam mode: from any other mode the flight first requests
+/// flycam and waits for it. A teleport animation in progress takes precedence
+/// and ends the flight.
+#[allow(clippy::too_many_arguments)]
+fn fly_route(
+    time: Res<Time>,
+    config: Res<GeoConfig>,
+    mut planner: ResMut<RoutePlanner>,
+    mode: Res<CameraModeState>,
+    mut transitions: ResMut<CameraModeTransitions>,
+    teleport: Res<TeleportAnimation>,
+    mut focus: ResMut<LodFocus>,
+    mut owns_focus: Local<bool>,
+    mut camera_query: Query<(&mut FloatingOriginCamera, &mut Transform, &mut FlightCamera)>,
+) {
+    if teleport.is_active() {
+        planner.stop_flight();
+    }
+    let (Some(flown), Some(route)) = (planner.flown_m, planner.route()) else {
+        if std::mem::take(&mut *owns_focus) {
+            *focus = LodFocus::default();
+        }
+        return;
+    };
+    if !mode.is_flycam() {
+        transitions.request_flycam();
+        return;
+    }
+    let Ok((mut camera, mut transform, mut flight_camera)) = camera_query.single_mut() else {
+        return;
+    };
+
+    let config = &config.route;
+    let length = route.length_m();
+    let flown = (flown + planner.speed_mps * time.delta_secs_f64()).min(length);
+    let t = if length > 0.0 { flown / length } else { 1.0 };
+
+    let radius = veldera_constants::EARTH_RADIUS_M_F64 + planner.cruise_altitude_m;
+    camera.position = route.direction_at(t) * radius;
+
+    // Look along the route, pitched down towards the ground ahead.
+    let frame = RadialFrame::from_ecef_position(camera.position);
+    let heading = route.heading_at(t).as_vec3();
+    if heading != Vec3::ZERO {
+        let (sin, cos) = config.flight_pitch_deg.to_radians().sin_cos();
+        let direction = heading * cos - frame.up * sin;
+        transform.rotation = Transform::default()
+            .looking_to(direction, frame.up)
+            .rotation;
+        flight_camera.direction = direction;
+    }
+    flight_camera.velocity = Vec3::ZERO;
+
+    // Keep the ground a few seconds ahead loading.
+    let lead_t = if length > 0.0 {
+        ((flown + planner.speed_mps * config.lod_lead_secs) / length).min(1.0)
+    } else {
+        1.0
+    };
+    focus.set_if_neq(LodFocus {
+        point: Some(route.direction_at(lead_t) * veldera_constants::EARTH_RADIUS_M_F64),
+        radius: config.lod_lead_radius_m,
+    });
+    *owns_focus = true;
+
+    if flown >= length {
+        tracing::info!("Route flight complete ({:.0} km)", length / 1000.0);
+        planner.stop_flight();
+    } else {
+        planner.flown_m = Some(flown);
+    }
+}
+
+/// Draw the planned route on the globe.
+fn draw_route(
+    planner: Res<RoutePlanner>,
+    config: Res<GeoConfig>,
+    camera_query: Query<&FloatingOriginCamera>,
+    mut gizmos: Gizmos,
+) {
+    let Some(route) = planner.route() else {
+        return;
+    };
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    let radius = veldera_constants::EARTH_RADIUS_M_F64 + config.route.draw_altitude_m;
+    let segments = config.route.segments(&route);
+    let points = (0..=segments).map(|i| {
+        let t = i as f64 / segments as f64;
+        (route.direction_at(t) * radius - camera.position).as_vec3()
+    });
+    gizmos.linestrip(points, css::ORANGE);
+
+    // Mark the endpoints with short posts.
+    for t in [0.0, 1.0] {
+        let direction = route.direction_at(t);
+        let base = (direction * radius - camera.position).as_vec3();
+        let top = (direction * (radius + 2_000.0) - camera.position).as_vec3();
+        gizmos.line(base, top, css::ORANGE_RED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_circle_length_and_midpoint() {
+        let route = GreatCircle::new(DVec3::X, DVec3::Y);
+        let quarter = std::f64::consts::FRAC_PI_2 * veldera_constants::EARTH_RADIUS_M_F64;
+        assert!((route.length_m() - quarter).abs() < 1e-3);
+
+        let mid = route.direction_at(0.5);
+        let expected = DVec3::new(1.0, 1.0, 0.0).normalize();
+        assert!(mid.distance(expected) < 1e-9);
+
+        // Heading is tangent to the globe and points towards the destination.
+        let heading = route.heading_at(0.5);
+        assert!(heading.dot(mid).abs() < 1e-6);
+        assert!(heading.dot(DVec3::Y) > 0.0);
+    }
+
+    #[test]
+    fn drawn_segments_follow_the_config_within_the_clamp() {
+        let config = RouteConfig {
+            segments_per_1000_km: 32.0,
+            ..default()
+        };
+        // A quarter circle is about 10 000 km.
+        let quarter = GreatCircle::new(DVec3::X, DVec3::Y);
+        assert_eq!(config.segments(&quarter), 320);
+
+        let short = GreatCircle::new(DVec3::X, DVec3::new(1.0, 1e-4, 0.0));
+        assert_eq!(config.segments(&short), 8);
+        let dense = RouteConfig {
+            segments_per_1000_km: 1000.0,
+            ..config
+        };
+        assert_eq!(dense.segments(&quarter), 512);
+    }
+}
@@ -0,0 +1,571 @@
+//! GPX/KML track overlay: draw imported tracks on the terrain and play them
+//! back.
+//!
+//! Files are parsed by [`veldera_tracks`]; this crate converts their points to
+//! ECEF and draws each track as a gizmo polyline draped a few metres above
+//! the ground, with a post at every waypoint. Points start at their recorded
+//! elevation (or sea level) and snap onto the terrain once colliders load
+//! beneath them, since recorded GPS elevations rarely match the mesh.
+//!
+//! Playback moves a cursor entity along a track at a chosen speed and puts
+//! the camera in the spectator orbit around it, so the usual mouse orbit and
+//! zoom apply. The orbit ends with the playback: when the track runs out,
+//! the cursor is despawned and the camera returns to its previous mode.
+//! Loading and playback are also available from the console (`track`).
+
+use std::{fmt, path::Path};
+
+use avian3d::prelude::*;
+use bevy::{color::palettes::css, prelude::*};
+use glam::DVec3;
+
+use veldera_game_camera_state::{CameraModeState, CameraModeTransitions, Spectatable};
+use veldera_game_console::{CommandResult, ConsoleAppExt, parse_arg};
+use veldera_geo::{
+    coords::lat_lon_to_ecef,
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+use veldera_physics::{GameLayer, PhysicsState};
+use veldera_tracks::{Format, TrackFile, TrackPoint};
+
+/// Height (m) the drawn tracks float above the ground.
+const DRAPE_HEIGHT_M: f64 = 3.0;
+
+/// Points farther than this from the camera (m) aren't ground-snapped; no
+/// terrain colliders exist that far out.
+const SNAP_RANGE_M: f64 = 5_000.0;
+
+/// Most ground-snap raycasts per frame, so a freshly loaded long track
+/// spreads its snapping over a few frames.
+const MAX_SNAPS_PER_FRAME: usize = 256;
+
+/// Height (m) above a point the ground-snap ray starts from.
+const SNAP_RAY_START_M: f64 = 2_000.0;
+
+/// Height (m) of the posts drawn at waypoints.
+const WAYPOINT_POST_M: f32 = 25.0;
+
+/// Plugin for the track overlay and playback.
+pub struct TracksPlugin;
+
+impl Plugin for TracksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadedTracks>()
+            .init_resource::<TrackPlayback>()
+            .add_console_command(
+                "track",
+                "track [list | load <file> | play <file> [track] [m/s] | stop]",
+                "Load GPX/KML tracks and fly the camera along them",
+                track_command,
+            )
+            .add_systems(
+                Update,
+                (snap_tracks_to_ground, update_playback, draw_tracks).chain(),
+            );
+    }
+}
+
+// ============================================================================
+// Loaded tracks
+// ============================================================================
+
+/// Every imported file.
+#[derive(Resource, Default)]
+pub struct LoadedTracks {
+    /// Files in load order.
+    pub files: Vec<LoadedFile>,
+}
+
+/// One imported file, in ECEF.
+pub struct LoadedFile {
+    /// Display name (the document name, else the file name).
+    pub name: String,
+    /// Whether the file's tracks and waypoints are drawn.
+    pub visible: bool,
+    /// The file's tracks.
+    pub tracks: Vec<TrackPath>,
+    /// The file's waypoints.
+    pub waypoints: Vec<WaypointMarker>,
+}
+
+/// A track as a draped ECEF polyline.
+pub struct TrackPath {
+    /// Display name.
+    pub name: String,
+    /// Draped points (ECEF).
+    points: Vec<DVec3>,
+    /// Whether each point has been snapped to the ground.
+    snapped: Vec<bool>,
+    /// Distance along the track to each point (m); refreshed after snapping.
+    cumulative: Vec<f64>,
+}
+
+/// A waypoint marker.
+pub struct WaypointMarker {
+    /// Display name.
+    pub name: String,
+    /// Draped position (ECEF).
+    position: DVec3,
+    /// Whether the position has been snapped to the ground.
+    snapped: bool,
+}
+
+/// Errors that can occur while loading a track file.
+#[derive(Debug)]
+pub enum TrackLoadError {
+    /// The extension isn't `.gpx` or `.kml`.
+    UnknownFormat,
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file couldn't be parsed.
+    Parse(veldera_tracks::Error),
+}
+
+impl fmt::Display for TrackLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "unknown format (expected .gpx or .kml)"),
+            Self::Io(e) => write!(f, "failed to read file: {e}"),
+            Self::Parse(e) => write!(f, "failed to parse file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TrackLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnknownFormat => None,
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl LoadedTracks {
+    /// Read and import the GPX or KML file at `path`.
+    pub fn load_path(&mut self, path: &Path) -> Result<(), TrackLoadError> {
+        let format = Format::from_path(path).ok_or(TrackLoadError::UnknownFormat)?;
+        let text = std::fs::read_to_string(path).map_err(TrackLoadError::Io)?;
+        let file = veldera_tracks::parse(format, &text).map_err(TrackLoadError::Parse)?;
+        let fallback = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into(),
+        );
+        self.add(file, fallback);
+        Ok(())
+    }
+
+    /// Import a parsed file, named `fallback_name` if it has no name of its
+    /// own.
+    pub fn add(&mut self, file: TrackFile, fallback_name: String) {
+        let tracks: Vec<TrackPath> = file
+            .tracks
+            .into_iter()
+            .map(|track| TrackPath::new(track.name, track.points.iter().map(draped)))
+            .collect();
+        let waypoints = file
+            .waypoints
+            .into_iter()
+            .map(|waypoint| WaypointMarker {
+                name: waypoint.name,
+                position: draped(&waypoint.point),
+                snapped: false,
+            })
+            .collect();
+        tracing::info!(
+            "Imported {} track(s) from {}",
+            tracks.len(),
+            file.name.as_deref().unwrap_or(&fallback_name)
+        );
+        self.files.push(LoadedFile {
+            name: file.name.unwrap_or(fallback_name),
+            visible: true,
+            tracks,
+            waypoints,
+        });
+    }
+}
+
+impl TrackPath {
+    /// A track through `points` (ECEF).
+    fn new(name: String, points: impl Iterator<Item = DVec3>) -> Self {
+        let points: Vec<DVec3> = points.collect();
+        let mut track = Self {
+            name,
+            snapped: vec![false; points.len()],
+            cumulative: Vec::new(),
+            points,
+        };
+        track.measure();
+        track
+    }
+
+    /// Recompute the cumulative distances.
+    fn measure(&mut self) {
+        self.cumulative.clear();
+        let mut total = 0.0;
+        let mut previous = None;
+        for &point in &self.points {
+            if let Some(previous) = previous {
+                total += point.distance(previous);
+            }
+            self.cumulative.push(total);
+            previous = Some(point);
+        }
+    }
+
+    /// Length of the track (m).
+    pub fn length_m(&self) -> f64 {
+        self.cumulative.last().copied().unwrap_or(0.0)
+    }
+
+    /// Number of points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the track has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Position `distance` metres along the track, clamped to its ends.
+    pub fn position_at(&self, distance: f64) -> Option<DVec3> {
+        let last = self.points.len().checked_sub(1)?;
+        let end = self.cumulative.partition_point(|&d| d < distance);
+        if end == 0 {
+            return Some(self.points[0]);
+        }
+        if end > last {
+            return Some(self.points[last]);
+        }
+        let (d0, d1) = (self.cumulative[end - 1], self.cumulative[end]);
+        let t = if d1 > d0 {
+            (distance - d0) / (d1 - d0)
+        } else {
+            0.0
+        };
+        Some(self.points[end - 1].lerp(self.points[end], t))
+    }
+}
+
+/// A file point in ECEF, draped above its recorded elevation.
+fn draped(point: &TrackPoint) -> DVec3 {
+    let radius =
+        veldera_constants::EARTH_RADIUS_M_F64 + point.elevation.unwrap_or(0.0) + DRAPE_HEIGHT_M;
+    lat_lon_to_ecef(point.lat, point.lon, radius)
+}
+
+/// Snap nearby track points and waypoints onto loaded terrain.
+fn snap_tracks_to_ground(
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
+    mut loaded: ResMut<LoadedTracks>,
+) {
+    let Some(origin) = physics_state.origin_camera_position() else {
+        return;
+    };
+    let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground]);
+    let mut budget = MAX_SNAPS_PER_FRAME;
+    let mut snap = |position: &mut DVec3| -> bool {
+        if budget == 0 || position.distance(origin) > SNAP_RANGE_M {
+            return false;
+        }
+        budget -= 1;
+        let up = position.normalize();
+        let start_ecef = *position + up * SNAP_RAY_START_M;
+        let Ok(down) = Dir3::new(-up.as_vec3()) else {
+            return false;
+        };
+        let start = (start_ecef - origin).as_vec3();
+        let max_distance = (SNAP_RAY_START_M * 2.0) as f32;
+        let Some(hit) = spatial_query.cast_ray(start, down, max_distance, true, &filter) else {
+            return false;
+        };
+        *position = start_ecef - up * (f64::from(hit.distance) - DRAPE_HEIGHT_M);
+        true
+    };
+
+    // Only touch the resource when something snapped, so change detection
+    // stays quiet.
+    let loaded = loaded.bypass_change_detection();
+    for file in &mut loaded.files {
+        for track in &mut file.tracks {
+            let mut moved = false;
+            for (point, snapped) in track.points.iter_mut().zip(&mut track.snapped) {
+                if !*snapped && snap(point) {
+                    *snapped = true;
+                    moved = true;
+                }
+            }
+            if moved {
+                track.measure();
+            }
+        }
+        for waypoint in &mut file.waypoints {
+            if !waypoint.snapped && snap(&mut waypoint.position) {
+                waypoint.snapped = true;
+            }
+        }
+    }
+}
+
+/// Draw every visible track and waypoint.
+fn draw_tracks(
+    loaded: Res<LoadedTracks>,
+    camera_query: Query<&FloatingOriginCamera>,
+    mut gizmos: Gizmos,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    for file in loaded.files.iter().filter(|file| file.visible) {
+        for track in &file.tracks {
+            gizmos.linestrip(
+                track
+                    .points
+                    .iter()
+                    .map(|&point| (point - camera.position).as_vec3()),
+                css::YELLOW,
+            );
+        }
+        for waypoint in &file.waypoints {
+            let base = (waypoint.position - camera.position).as_vec3();
+            let up = waypoint.position.normalize().as_vec3();
+            gizmos.line(base, base + up * WAYPOINT_POST_M, css::DEEP_SKY_BLUE);
+            gizmos.sphere(
+                Isometry3d::from_translation(base + up * WAYPOINT_POST_M),
+                2.0,
+                css::DEEP_SKY_BLUE,
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Playback
+// ============================================================================
+
+/// Track playback: which track is being followed, and how fast.
+#[derive(Resource)]
+pub struct TrackPlayback {
+    /// Playback speed along the track (m/s).
+    pub speed_mps: f64,
+    /// Track to start following, set by [`play`](Self::play).
+    requested: Option<(usize, usize)>,
+    /// The playback in progress.
+    active: Option<ActivePlayback>,
+}
+
+/// A playback in progress.
+struct ActivePlayback {
+    /// Index of the file in [`LoadedTracks::files`].
+    file: usize,
+    /// Index of the track in the file.
+    track: usize,
+    /// Distance along the track (m).
+    distance_m: f64,
+    /// The entity the camera orbits.
+    cursor: Entity,
+    /// Whether the camera has entered the orbit yet; leaving it afterwards
+    /// stops the playback.
+    following: bool,
+}
+
+impl Default for TrackPlayback {
+    fn default() -> Self {
+        Self {
+            speed_mps: 30.0,
+            requested: None,
+            active: None,
+        }
+    }
+}
+
+impl TrackPlayback {
+    /// Follow track `track` of file `file` from its start.
+    pub fn play(&mut self, file: usize, track: usize) {
+        self.requested = Some((file, track));
+    }
+
+    /// Stop the playback (the camera returns to its previous mode).
+    pub fn stop(&mut self) {
+        self.requested = None;
+        if let Some(active) = &mut self.active {
+            // Let the update despawn the cursor.
+            active.distance_m = f64::INFINITY;
+        }
+    }
+
+    /// The track being played as `(file, track, distance along it in m)`.
+    pub fn current(&self) -> Option<(usize, usize, f64)> {
+        self.active
+            .as_ref()
+            .map(|active| (active.file, active.track, active.distance_m))
+    }
+}
+
+/// The `track` console command.
+fn track_command(
+    In(args): In<Vec<String>>,
+    mut loaded: ResMut<LoadedTracks>,
+    mut playback: ResMut<TrackPlayback>,
+) -> CommandResult {
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            if loaded.files.is_empty() {
+                return Ok("No tracks loaded".to_string());
+            }
+            let mut lines = Vec::new();
+            for (f, file) in loaded.files.iter().enumerate() {
+                for (t, track) in file.tracks.iter().enumerate() {
+                    lines.push(format!(
+                        "{f} {t}  {} / {} ({:.1} km)",
+                        file.name,
+                        track.name,
+                        track.length_m() / 1000.0
+                    ));
+                }
+            }
+            Ok(lines.join("\n"))
+        }
+        Some("load") => {
+            let path = args.get(1).ok_or("Missing file path")?;
+            loaded
+                .load_path(Path::new(path))
+                .map_err(|e| format!("{path}: {e}"))?;
+            let index = loaded.files.len() - 1;
+            let file = &loaded.files[index];
+            Ok(format!(
+                "Loaded {} track(s) from {} as file {index}",
+                file.tracks.len(),
+                file.name
+            ))
+        }
+        Some("play") => {
+            let file: usize = parse_arg(&args, 1, "file index")?;
+            let track: usize = if args.len() > 2 {
+                parse_arg(&args, 2, "track index")?
+            } else {
+                0
+            };
+            let path = loaded
+                .files
+                .get(file)
+                .and_then(|f| f.tracks.get(track))
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| format!("No track {file} {track}; try 'track list'"))?;
+            if args.len() > 3 {
+                let speed: f64 = parse_arg(&args, 3, "speed")?;
+                if speed <= 0.0 {
+                    return Err("Speed must be positive".to_string());
+                }
+                playback.speed_mps = speed;
+            }
+            playback.play(file, track);
+            Ok(format!(
+                "Following {} at {:.0} m/s",
+                path.name, playback.speed_mps
+            ))
+        }
+        Some("stop") => {
+            playback.stop();
+            Ok("Playback stopped".to_string())
+        }
+        Some(other) => Err(format!(
+            "Unknown option '{other}'; expected list, load, play or stop"
+        )),
+    }
+}
+
+/// Marker for the entity a track playback moves along the track.
+#[derive(Component)]
+pub struct TrackCursor;
+
+/// Start requested playbacks and advance the one in progress.
+fn update_playback(
+    mut commands: Commands,
+    time: Res<Time>,
+    loaded: Res<LoadedTracks>,
+    mode: Res<CameraModeState>,
+    mut playback: ResMut<TrackPlayback>,
+    mut transitions: ResMut<CameraModeTransitions>,
+    mut cursor_query: Query<&mut WorldPosition, With<TrackCursor>>,
+) {
+    if let Some((file, track)) = playback.requested.take()
+        && let Some(path) = loaded.files.get(file).and_then(|f| f.tracks.get(track))
+        && let Some(start) = path.position_at(0.0)
+    {
+        if let Some(previous) = playback.active.take() {
+            commands.entity(previous.cursor).try_despawn();
+        }
+        let cursor = commands
+            .spawn((
+                Name::new(format!("Track: {}", path.name)),
+                TrackCursor,
+                Spectatable,
+                Transform::default(),
+                WorldPosition::from_dvec3(start),
+            ))
+            .id();
+        transitions.request_orbit_entity(cursor);
+        playback.active = Some(ActivePlayback {
+            file,
+            track,
+            distance_m: 0.0,
+            cursor,
+            following: false,
+        });
+    }
+
+    let speed = playback.speed_mps;
+    let Some(active) = &mut playback.active else {
+        return;
+    };
+    let path = loaded
+        .files
+        .get(active.file)
+        .and_then(|f| f.tracks.get(active.track));
+
+    if mode.is_follow_entity() {
+        active.following = true;
+    }
+    let left_orbit = active.following && !mode.is_follow_entity();
+    let finished = path.is_none_or(|path| active.distance_m >= path.length_m());
+    if left_orbit || finished {
+        // Despawning the cursor ends the orbit, if it's still running.
+        commands.entity(active.cursor).try_despawn();
+        playback.active = None;
+        return;
+    }
+
+    active.distance_m += speed * time.delta_secs_f64();
+    if let Some(position) = path.and_then(|path| path.position_at(active.distance_m))
+        && let Ok(mut world_pos) = cursor_query.get_mut(active.cursor)
+    {
+        world_pos.position = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_at_interpolates_and_clamps() {
+        let track = TrackPath::new(
+            "test".to_string(),
+            [
+                DVec3::ZERO,
+                DVec3::new(10.0, 0.0, 0.0),
+                DVec3::new(10.0, 20.0, 0.0),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(track.length_m(), 30.0);
+        assert_eq!(track.position_at(-5.0), Some(DVec3::ZERO));
+        assert_eq!(track.position_at(5.0), Some(DVec3::new(5.0, 0.0, 0.0)));
+        assert_eq!(track.position_at(20.0), Some(DVec3::new(10.0, 10.0, 0.0)));
+        assert_eq!(track.position_at(99.0), Some(DVec3::new(10.0, 20.0, 0.0)));
+    }
+}
@@ -0,0 +1,581 @@
+//! User annotations: placemarks with a note, anchored to the terrain.
+//!
+//! Press M to drop an annotation on the terrain under the cursor, with the
+//! note and icon drafted in the Annotations tab; it records the picked ground point, so the marker sits on
+//! the surface wherever the camera later is. Annotations are drawn as pins
+//! with a labelled icon (sharing the search pins' meshes and constant-size
+//! scaling), listed and searchable in the Annotations tab, and saved to
+//! `<OS data dir>/veldera/annotations.json` on every change. The web build
+//! keeps them for the session only.
+
+use std::path::PathBuf;
+
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use veldera_async::TaskSpawner;
+use veldera_game_input::CameraAction;
+use veldera_game_teleport::TeleportState;
+use veldera_geo::{
+    coords::{RadialFrame, lat_lon_to_ecef},
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+use veldera_places::HttpClient;
+use veldera_terrain::{
+    pick::{TerrainPicker, pick_viewport},
+    raycast::{TerrainHit, TerrainRaycast},
+};
+
+use crate::{
+    UiVisible,
+    elevation_profile::{ElevationProfileParams, render_elevation_profile},
+    i18n::{fmt_distance, fmt_lat_lon, fmt_number, tr, trf},
+    search_pins::{PinAssets, ScreenSizedPin, scale_pins},
+    viewshed::{ViewshedParams, render_viewshed},
+};
+
+/// Plugin: loads the saved annotations, keeps their markers in sync, and
+/// draws their labels.
+pub struct AnnotationsPlugin;
+
+impl Plugin for AnnotationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Annotations>()
+            .add_systems(Startup, init_icon_materials)
+            .add_systems(
+                Update,
+                (
+                    drop_annotation_on_key,
+                    (
+                        sync_annotation_markers,
+                        save_annotations,
+                        teleport_to_annotation,
+                    ),
+                )
+                    .chain()
+                    .before(scale_pins),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                draw_annotation_labels.run_if(|visible: Res<UiVisible>| visible.0),
+            );
+    }
+}
+
+// ============================================================================
+// Data
+// ============================================================================
+
+/// Marker icon, chosen per annotation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum AnnotationIcon {
+    #[default]
+    Pin,
+    Star,
+    Flag,
+    Warning,
+    Camera,
+}
+
+impl AnnotationIcon {
+    const ALL: [Self; 5] = [
+        Self::Pin,
+        Self::Star,
+        Self::Flag,
+        Self::Warning,
+        Self::Camera,
+    ];
+
+    /// Glyph drawn in labels and the list.
+    fn glyph(self) -> &'static str {
+        match self {
+            Self::Pin => "📍",
+            Self::Star => "★",
+            Self::Flag => "⚑",
+            Self::Warning => "⚠",
+            Self::Camera => "📷",
+        }
+    }
+
+    /// Name shown in the icon picker.
+    fn label(self) -> &'static str {
+        tr(match self {
+            Self::Pin => "annotations.icon.pin",
+            Self::Star => "annotations.icon.star",
+            Self::Flag => "annotations.icon.flag",
+            Self::Warning => "annotations.icon.warning",
+            Self::Camera => "annotations.icon.viewpoint",
+        })
+    }
+
+    /// Marker colour.
+    fn color(self) -> Color {
+        match self {
+            Self::Pin => Color::srgb(0.2, 0.6, 1.0),
+            Self::Star => Color::srgb(1.0, 0.85, 0.2),
+            Self::Flag => Color::srgb(0.3, 0.9, 0.4),
+            Self::Warning => Color::srgb(1.0, 0.3, 0.3),
+            Self::Camera => Color::srgb(0.8, 0.5, 1.0),
+        }
+    }
+}
+
+/// One placemark, as saved to disk.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Annotation {
+    /// Latitude (degrees).
+    lat: f64,
+    /// Longitude (degrees).
+    lon: f64,
+    /// Height of the picked ground point above the reference sphere (m).
+    altitude: f64,
+    /// Free-form note.
+    note: String,
+    /// Marker icon.
+    icon: AnnotationIcon,
+}
+
+impl Annotation {
+    /// Anchor position (ECEF).
+    fn position(&self) -> glam::DVec3 {
+        lat_lon_to_ecef(
+            self.lat,
+            self.lon,
+            veldera_constants::EARTH_RADIUS_M_F64 + self.altitude,
+        )
+    }
+}
+
+/// Every annotation, plus the tab's draft and filter state.
+#[derive(Resource)]
+pub(super) struct Annotations {
+    entries: Vec<Annotation>,
+    /// Bumped on every change; markers respawn and the file is rewritten when
+    /// it moves.
+    revision: u64,
+    /// Save file, if this platform has one.
+    path: Option<PathBuf>,
+    /// Last load or save error, shown in the tab.
+    error: Option<String>,
+    /// Note for the next dropped annotation.
+    pub draft_note: String,
+    /// Icon for the next dropped annotation.
+    pub draft_icon: AnnotationIcon,
+    /// List filter (case-insensitive substring of the note).
+    pub filter: String,
+    /// Lat/lon of an annotation the user clicked, to teleport to.
+    teleport_to: Option<(f64, f64)>,
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        let path = annotations_path();
+        let (entries, error) = match path.as_deref().map(load_annotations) {
+            Some(Ok(entries)) => (entries, None),
+            Some(Err(e)) => {
+                warn!("Failed to load annotations: {e}");
+                (Vec::new(), Some(format!("Load failed: {e}")))
+            }
+            None => (Vec::new(), None),
+        };
+        Self {
+            entries,
+            // Start dirty so markers spawn for the loaded entries.
+            revision: 1,
+            path,
+            error,
+            draft_note: String::new(),
+            draft_icon: AnnotationIcon::default(),
+            filter: String::new(),
+            teleport_to: None,
+        }
+    }
+}
+
+impl Annotations {
+    /// Add an annotation at `hit` from the current draft, clearing the draft
+    /// note.
+    fn drop_at(&mut self, hit: &TerrainHit) {
+        let note = std::mem::take(&mut self.draft_note);
+        self.entries.push(Annotation {
+            lat: hit.lat_deg,
+            lon: hit.lon_deg,
+            altitude: hit.altitude,
+            note: if note.is_empty() {
+                format!("{:.5}°, {:.5}°", hit.lat_deg, hit.lon_deg)
+            } else {
+                note
+            },
+            icon: self.draft_icon,
+        });
+        self.revision += 1;
+    }
+}
+
+/// `<OS data dir>/veldera/annotations.json`.
+#[cfg(not(target_family = "wasm"))]
+fn annotations_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("veldera").join("annotations.json"))
+}
+
+/// The web build has nowhere to save to.
+#[cfg(target_family = "wasm")]
+fn annotations_path() -> Option<PathBuf> {
+    None
+}
+
+/// Read the annotations at `path`; a missing file is an empty list.
+fn load_annotations(path: &std::path::Path) -> Result<Vec<Annotation>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Write `entries` to `path`, creating its directory.
+fn write_annotations(path: &std::path::Path, entries: &[Annotation]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// A marker for the annotation at this index.
+#[derive(Component)]
+struct AnnotationMarker {
+    index: usize,
+}
+
+/// One material per icon.
+#[derive(Resource)]
+struct IconMaterials(Vec<(AnnotationIcon, Handle<StandardMaterial>)>);
+
+impl IconMaterials {
+    fn get(&self, icon: AnnotationIcon) -> Handle<StandardMaterial> {
+        self.0
+            .iter()
+            .find(|(i, _)| *i == icon)
+            .map(|(_, material)| material.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Create the per-icon marker materials on startup.
+fn init_icon_materials(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let icons = AnnotationIcon::ALL
+        .into_iter()
+        .map(|icon| {
+            let color = icon.color();
+            let material = materials.add(StandardMaterial {
+                base_color: color,
+                emissive: color.to_linear() * 0.5,
+                unlit: true,
+                ..default()
+            });
+            (icon, material)
+        })
+        .collect();
+    commands.insert_resource(IconMaterials(icons));
+}
+
+/// Drop an annotation on the terrain under the cursor when M is pressed. With
+/// the cursor grabbed there is no pointer, so the screen centre is used.
+fn drop_annotation_on_key(
+    action_query: Query<&ActionState<CameraAction>>,
+    picker: Res<TerrainPicker>,
+    raycast: TerrainRaycast,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &FloatingOriginCamera)>,
+    mut annotations: ResMut<Annotations>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if !action_state.just_pressed(&CameraAction::DropAnnotation) {
+        return;
+    }
+    let hit = picker.hit().copied().or_else(|| {
+        let window = window_query.single().ok()?;
+        let (camera, camera_transform, origin) = camera_query.single().ok()?;
+        let centre = Vec2::new(window.width(), window.height()) * 0.5;
+        pick_viewport(&raycast, camera, camera_transform, origin, centre)
+    });
+    match hit {
+        Some(hit) => annotations.drop_at(&hit),
+        None => info!("No terrain under the cursor to annotate"),
+    }
+}
+
+/// Respawn the markers whenever the annotations change.
+fn sync_annotation_markers(
+    mut commands: Commands,
+    annotations: Res<Annotations>,
+    pin_assets: Option<Res<PinAssets>>,
+    icon_materials: Option<Res<IconMaterials>>,
+    marker_query: Query<Entity, With<AnnotationMarker>>,
+    mut synced: Local<u64>,
+) {
+    let (Some(pin_assets), Some(icon_materials)) = (pin_assets, icon_materials) else {
+        return;
+    };
+    if *synced == annotations.revision {
+        return;
+    }
+    *synced = annotations.revision;
+
+    for entity in &marker_query {
+        commands.entity(entity).despawn();
+    }
+    for (index, annotation) in annotations.entries.iter().enumerate() {
+        let position = annotation.position();
+        let up = RadialFrame::from_ecef_position(position).up;
+        let material = icon_materials.get(annotation.icon);
+        commands
+            .spawn((
+                Name::new(format!("Annotation: {}", annotation.note)),
+                AnnotationMarker { index },
+                Transform::from_rotation(Quat::from_rotation_arc(Vec3::Y, up)),
+                Visibility::default(),
+                WorldPosition::from_dvec3(position),
+                ScreenSizedPin,
+            ))
+            .with_children(|marker| {
+                marker.spawn((
+                    Mesh3d(pin_assets.stalk.clone()),
+                    MeshMaterial3d(material.clone()),
+                ));
+                marker.spawn((Mesh3d(pin_assets.head.clone()), MeshMaterial3d(material)));
+            });
+    }
+}
+
+/// Rewrite the save file whenever the annotations change.
+fn save_annotations(mut annotations: ResMut<Annotations>, mut saved: Local<Option<u64>>) {
+    let revision = annotations.revision;
+    // The first sighting is the loaded state; nothing to write.
+    let Some(last) = saved.replace(revision) else {
+        return;
+    };
+    if last == revision {
+        return;
+    }
+    let Some(path) = annotations.path.clone() else {
+        return;
+    };
+    annotations.error = write_annotations(&path, &annotations.entries)
+        .inspect_err(|e| warn!("Failed to save annotations: {e}"))
+        .err()
+        .map(|e| format!("Save failed: {e}"));
+}
+
+/// Teleport to the annotation clicked in the tab or on a label. Deferred to
+/// here because the debug UI system already holds the teleport state.
+fn teleport_to_annotation(
+    mut annotations: ResMut<Annotations>,
+    mut teleport_state: ResMut<TeleportState>,
+    http_client: Res<HttpClient>,
+    spawner: TaskSpawner,
+) {
+    if let Some((lat, lon)) = annotations.teleport_to.take() {
+        teleport_state.request(lat, lon, &http_client, &spawner);
+    }
+}
+
+/// Resources for drawing and clicking annotation labels.
+#[derive(SystemParam)]
+struct AnnotationLabelParams<'w, 's> {
+    contexts: EguiContexts<'w, 's>,
+    annotations: ResMut<'w, Annotations>,
+    camera_query: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static FloatingOriginCamera,
+        ),
+    >,
+    marker_query: Query<
+        'w,
+        's,
+        (
+            &'static AnnotationMarker,
+            &'static WorldPosition,
+            &'static GlobalTransform,
+        ),
+    >,
+}
+
+/// Draw a clickable label above each on-screen marker; clicking one teleports
+/// there.
+fn draw_annotation_labels(mut params: AnnotationLabelParams) -> Result {
+    let Ok((camera, camera_transform, origin)) = params.camera_query.single() else {
+        return Ok(());
+    };
+    let camera_position = origin.position;
+
+    let mut clicked: Option<(f64, f64)> = None;
+    let ctx = params.contexts.ctx_mut()?;
+    for (marker, world_pos, transform) in &params.marker_query {
+        let Some(annotation) = params.annotations.entries.get(marker.index) else {
+            continue;
+        };
+        let head = transform.transform_point(Vec3::Y * 1.15);
+        let Ok(screen) = camera.world_to_viewport(camera_transform, head) else {
+            continue;
+        };
+        let distance = fmt_distance(world_pos.position.distance(camera_position));
+        egui::Area::new(egui::Id::new(("annotation", marker.index)))
+            .fixed_pos(egui::pos2(screen.x, screen.y))
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .show(ctx, |ui| {
+                if ui
+                    .button(format!(
+                        "{} {} ({distance})",
+                        annotation.icon.glyph(),
+                        first_line(&annotation.note)
+                    ))
+                    .on_hover_text(&annotation.note)
+                    .clicked()
+                {
+                    clicked = Some((annotation.lat, annotation.lon));
+                }
+            });
+    }
+
+    if clicked.is_some() {
+        params.annotations.teleport_to = clicked;
+    }
+    Ok(())
+}
+
+/// The first line of a note, for compact labels.
+fn first_line(note: &str) -> &str {
+    note.lines().next().unwrap_or_default()
+}
+
+// ============================================================================
+// Tab
+// ============================================================================
+
+/// Resources for the annotations tab.
+#[derive(SystemParam)]
+pub(super) struct AnnotationParams<'w, 's> {
+    pub annotations: ResMut<'w, Annotations>,
+    pub camera_query: Query<'w, 's, &'static FloatingOriginCamera>,
+    pub elevation_profile: ElevationProfileParams<'w>,
+    pub viewshed: ViewshedParams<'w>,
+}
+
+/// Render the annotations tab: the draft for the next drop, the elevation
+/// profile and viewshed tools, then the filterable list with teleport, edit and delete
+/// controls.
+pub(super) fn render_annotations_tab(ui: &mut egui::Ui, params: &mut AnnotationParams) {
+    let annotations = &mut *params.annotations;
+
+    ui.label(tr("annotations.help"));
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("annotation_icon")
+            .selected_text(format!(
+                "{} {}",
+                annotations.draft_icon.glyph(),
+                annotations.draft_icon.label()
+            ))
+            .show_ui(ui, |ui| {
+                for icon in AnnotationIcon::ALL {
+                    ui.selectable_value(
+                        &mut annotations.draft_icon,
+                        icon,
+                        format!("{} {}", icon.glyph(), icon.label()),
+                    );
+                }
+            });
+        ui.add(
+            egui::TextEdit::singleline(&mut annotations.draft_note)
+                .desired_width(f32::INFINITY)
+                .hint_text(tr("annotations.note_hint")),
+        );
+    });
+    if let Some(error) = &annotations.error {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+    match &annotations.path {
+        Some(path) => ui.weak(trf("annotations.saved_to", &[("path", &path.display())])),
+        None => ui.weak(tr("annotations.not_saved")),
+    };
+    ui.separator();
+    render_elevation_profile(ui, &mut params.elevation_profile);
+    render_viewshed(ui, &mut params.viewshed);
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.label(tr("search.label"));
+        ui.add(
+            egui::TextEdit::singleline(&mut annotations.filter)
+                .desired_width(f32::INFINITY)
+                .hint_text(tr("annotations.filter_hint")),
+        );
+    });
+
+    let camera_position = params.camera_query.single().ok().map(|c| c.position);
+    let filter = annotations.filter.to_lowercase();
+    let mut teleport: Option<(f64, f64)> = None;
+    let mut delete: Option<usize> = None;
+    let mut edited = false;
+    let mut shown = 0;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for (index, annotation) in annotations.entries.iter_mut().enumerate() {
+            if !filter.is_empty() && !annotation.note.to_lowercase().contains(&filter) {
+                continue;
+            }
+            shown += 1;
+            ui.horizontal(|ui| {
+                ui.label(annotation.icon.glyph());
+                edited |= ui
+                    .add(egui::TextEdit::singleline(&mut annotation.note).desired_width(180.0))
+                    .lost_focus();
+                let distance = camera_position.map_or_else(String::new, |camera| {
+                    fmt_distance(annotation.position().distance(camera))
+                });
+                ui.weak(distance).on_hover_text(format!(
+                    "{}  ·  {} m",
+                    fmt_lat_lon(annotation.lat, annotation.lon, 5),
+                    fmt_number(annotation.altitude, 0)
+                ));
+                if ui.small_button(tr("search.go")).clicked() {
+                    teleport = Some((annotation.lat, annotation.lon));
+                }
+                if ui.small_button(tr("annotations.delete")).clicked() {
+                    delete = Some(index);
+                }
+            });
+        }
+    });
+    if shown == 0 {
+        ui.weak(tr(if annotations.entries.is_empty() {
+            "annotations.empty"
+        } else {
+            "annotations.no_match"
+        }));
+    }
+
+    if let Some(index) = delete {
+        annotations.entries.remove(index);
+        edited = true;
+    }
+    if edited {
+        annotations.revision += 1;
+    }
+    if teleport.is_some() {
+        annotations.teleport_to = teleport;
+    }
+}
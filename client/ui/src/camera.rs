@@ -1,17 +1,30 @@
 //! Camera tab for the debug UI.
 //!
-//! Displays camera mode and provides settings for flycam and teleport animation.
+//! Displays camera mode and provides settings for flycam (including orbital
+//! flight) and teleport animation, tone mapping and exposure with a
+//! split-screen tonemapper comparison, plus a picker for the spectator orbit
+//! camera and the cinematic point-of-interest orbit controls.
 
-use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui;
+use bevy::{
+    camera::Exposure, core_pipeline::tonemapping::Tonemapping, ecs::system::SystemParam, prelude::*,
+};
+use bevy_egui::{EguiContexts, EguiTextureHandle, egui};
 
+use veldera_engine::{
+    DEFAULT_EV100,
+    tonemap_compare::{ComparisonSide, HISTOGRAM_BINS, LuminanceHistogram, TonemapComparison},
+};
 use veldera_game_camera::{
-    CameraConfig, CameraMode, CameraModeState, FlightCamera, FollowCameraConfig,
-    FollowEntityTarget, TeleportAnimationMode,
+    CameraConfig, CameraMode, CameraModeState, CameraModeTransitions, CinematicOrbit,
+    CinematicOrbitRequest, CinematicOrbitSettings, FlightCamera, FlycamCollision,
+    FollowCameraConfig, FollowCameraEffects, FollowEntityTarget, FollowStyle, OrbitCamera,
+    OrbitalFlight, OrbitalFlightSettings, Spectatable, TeleportAnimationMode,
 };
 use veldera_game_player::{BodyConfig, BodyTuning, CharacterMetrics, FpsPlayerConfig};
 
-use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
+
+use crate::i18n::{fmt_distance, fmt_number, tr, trf};
 
 /// Resources for camera display and control.
 #[derive(SystemParam)]
@@ -19,6 +32,7 @@ pub(super) struct CameraParams<'w, 's> {
     pub config: ResMut<'w, CameraConfig>,
     pub body_config: Res<'w, BodyConfig>,
     pub camera_mode: Res<'w, CameraModeState>,
+    pub transitions: ResMut<'w, CameraModeTransitions>,
     pub player_config: ResMut<'w, FpsPlayerConfig>,
     pub body_tuning: ResMut<'w, BodyTuning>,
     pub character_metrics: Res<'w, CharacterMetrics>,
@@ -34,38 +48,80 @@ pub(super) struct CameraParams<'w, 's> {
     /// Live FoV is owned by the camera's `Projection`; the slider edits it
     /// directly (a `camera.toml` reload re-applies the configured default).
     pub projection_query: Query<'w, 's, &'static mut Projection, With<FloatingOriginCamera>>,
+    pub view_query: Query<
+        'w,
+        's,
+        (&'static mut Tonemapping, &'static mut Exposure),
+        With<FloatingOriginCamera>,
+    >,
+    pub comparison: ResMut<'w, TonemapComparison>,
     pub follow_target_query: Query<'w, 's, &'static FollowEntityTarget>,
     pub follow_config_query: Query<'w, 's, &'static mut FollowCameraConfig>,
+    pub orbit_query: Query<'w, 's, &'static mut OrbitCamera>,
+    pub spectatable_query:
+        Query<'w, 's, (Entity, Option<&'static Name>, &'static WorldPosition), With<Spectatable>>,
+    pub cinematic_settings: ResMut<'w, CinematicOrbitSettings>,
+    pub cinematic_request: ResMut<'w, CinematicOrbitRequest>,
+    pub cinematic_query: Query<'w, 's, &'static CinematicOrbit>,
+    pub flycam_collision: ResMut<'w, FlycamCollision>,
+    pub orbital: ResMut<'w, OrbitalFlight>,
+    pub orbital_settings: ResMut<'w, OrbitalFlightSettings>,
+    pub follow_effects: ResMut<'w, FollowCameraEffects>,
 }
 
 /// Render the camera tab content.
 pub(super) fn render_camera_tab(ui: &mut egui::Ui, camera: &mut CameraParams) {
     // Camera mode indicator.
     let mode_str = match camera.camera_mode.current() {
-        CameraMode::Flycam => "Flycam",
-        CameraMode::FpsController => "FPS controller",
-        CameraMode::FollowEntity => "Following entity",
+        CameraMode::Flycam => tr("camera.mode.flycam"),
+        CameraMode::FpsController => tr("camera.mode.fps"),
+        CameraMode::FollowEntity => match camera.follow_target_query.iter().next() {
+            Some(follow) if follow.style == FollowStyle::Orbit => tr("camera.mode.spectating"),
+            Some(follow) if follow.style == FollowStyle::Cinematic => tr("camera.mode.cinematic"),
+            Some(follow) if follow.style == FollowStyle::Passenger => tr("camera.mode.passenger"),
+            _ => tr("camera.mode.following"),
+        },
     };
-    ui.label(format!("Mode: {mode_str} (N to toggle)"));
+    ui.label(trf("camera.mode", &[("mode", &mode_str)]));
 
     ui.separator();
 
     // Field of view: always editable regardless of mode.
     render_fov_slider(ui, camera);
 
+    ui.collapsing(tr("camera.tone_mapping"), |ui| {
+        render_tone_mapping(ui, camera);
+    });
+
     ui.separator();
 
     // Speed slider (only in flycam mode).
     if camera.camera_mode.is_flycam() {
         let (min_speed, max_speed) = (camera.config.min_speed, camera.config.max_speed);
         ui.horizontal(|ui| {
-            ui.label("Speed:");
+            ui.label(tr("common.speed"));
             ui.add(
                 egui::Slider::new(&mut camera.config.base_speed, min_speed..=max_speed)
                     .logarithmic(true)
                     .suffix(" m/s"),
             );
         });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut camera.flycam_collision.enabled, tr("camera.collide"))
+                .on_hover_text(tr("camera.collide.hover"));
+            let enabled = camera.flycam_collision.enabled;
+            ui.add_enabled(
+                enabled,
+                egui::Slider::new(
+                    &mut camera.flycam_collision.radius,
+                    FlycamCollision::MIN_RADIUS..=FlycamCollision::MAX_RADIUS,
+                )
+                .logarithmic(true)
+                .text(tr("camera.radius"))
+                .suffix(" m"),
+            );
+        });
+        render_orbital_flight(ui, camera);
 
         ui.separator();
     }
@@ -84,12 +140,17 @@ pub(super) fn render_camera_tab(ui: &mut egui::Ui, camera: &mut CameraParams) {
         ui.separator();
     }
 
+    render_spectate_picker(ui, camera);
+    render_cinematic_orbit(ui, camera);
+
+    ui.separator();
+
     // Teleport animation mode selector.
     ui.horizontal(|ui| {
-        ui.label("Teleport style:");
+        ui.label(tr("camera.teleport_style"));
         let current_label = match camera.config.teleport_animation_mode {
-            TeleportAnimationMode::Classic => "Classic",
-            TeleportAnimationMode::HorizonChasing => "Horizon",
+            TeleportAnimationMode::Classic => tr("settings.camera.teleport_classic"),
+            TeleportAnimationMode::HorizonChasing => tr("settings.camera.teleport_horizon"),
         };
         egui::ComboBox::from_id_salt("teleport_style")
             .selected_text(current_label)
@@ -97,12 +158,12 @@ pub(super) fn render_camera_tab(ui: &mut egui::Ui, camera: &mut CameraParams) {
                 ui.selectable_value(
                     &mut camera.config.teleport_animation_mode,
                     TeleportAnimationMode::Classic,
-                    "Classic",
+                    tr("settings.camera.teleport_classic"),
                 );
                 ui.selectable_value(
                     &mut camera.config.teleport_animation_mode,
                     TeleportAnimationMode::HorizonChasing,
-                    "Horizon",
+                    tr("settings.camera.teleport_horizon"),
                 );
             });
     });
@@ -124,7 +185,7 @@ fn render_fov_slider(ui: &mut egui::Ui, camera: &mut CameraParams) {
         })
         .unwrap_or(min_fov_deg);
     ui.horizontal(|ui| {
-        ui.label("FoV:");
+        ui.label(tr("camera.fov"));
         let response = ui.add(
             egui::Slider::new(&mut fov_deg, min_fov_deg..=max_fov_deg)
                 .step_by(1.0)
@@ -152,14 +213,14 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
     let tuning = &mut *camera.body_tuning;
     let model = camera.character_metrics.resolved.as_ref();
 
-    ui.collapsing("Body tuning", |ui| {
+    ui.collapsing(tr("camera.body_tuning"), |ui| {
         if model.is_none() {
-            ui.label("(character model not loaded yet)");
+            ui.label(tr("camera.body_tuning.not_loaded"));
             return;
         }
 
         ui.horizontal(|ui| {
-            ui.label("Eye height:");
+            ui.label(tr("camera.eye_height"));
             ui.add(
                 egui::Slider::new(
                     &mut tuning.eye_height_m,
@@ -171,7 +232,7 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
             if let Some(m) = model
                 && ui
                     .button("\u{21bb}")
-                    .on_hover_text("Reset to model default")
+                    .on_hover_text(tr("camera.reset_to_model"))
                     .clicked()
             {
                 tuning.eye_height_m = m.eye_height_m;
@@ -179,7 +240,7 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
         });
 
         ui.horizontal(|ui| {
-            ui.label("Eye forward:");
+            ui.label(tr("camera.eye_forward"));
             ui.add(
                 egui::Slider::new(
                     &mut tuning.eye_forward_offset_m,
@@ -191,7 +252,7 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
             if let Some(m) = model
                 && ui
                     .button("\u{21bb}")
-                    .on_hover_text("Reset to model default")
+                    .on_hover_text(tr("camera.reset_to_model"))
                     .clicked()
             {
                 tuning.eye_forward_offset_m = m.eye_forward_offset_m;
@@ -199,7 +260,7 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
         });
 
         ui.horizontal(|ui| {
-            ui.label("Eye lerp duration:");
+            ui.label(tr("camera.eye_lerp"));
             ui.add(
                 egui::Slider::new(&mut tuning.eye_lerp_duration_s, 0.0..=max_eye_lerp)
                     .step_by(0.05)
@@ -208,9 +269,13 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
         });
 
         if let Some(m) = model {
-            ui.label(format!(
-                "Model: stand={:.3} m, eye={:.3} m, fwd={:.3} m",
-                m.stand_height_m, m.eye_height_m, m.eye_forward_offset_m,
+            ui.label(trf(
+                "camera.model_metrics",
+                &[
+                    ("stand", &fmt_number(f64::from(m.stand_height_m), 3)),
+                    ("eye", &fmt_number(f64::from(m.eye_height_m), 3)),
+                    ("forward", &fmt_number(f64::from(m.eye_forward_offset_m), 3)),
+                ],
             ));
         }
     });
@@ -223,9 +288,9 @@ fn render_body_tuning(ui: &mut egui::Ui, camera: &mut CameraParams) {
 fn render_player_size_config(ui: &mut egui::Ui, camera: &mut CameraParams) {
     let config = &mut *camera.player_config;
     let (min_radius_ratio, max_radius_ratio) = (config.min_radius_ratio, config.max_radius_ratio);
-    ui.collapsing("Player size", |ui| {
+    ui.collapsing(tr("camera.player_size"), |ui| {
         ui.horizontal(|ui| {
-            ui.label("Height:");
+            ui.label(tr("camera.height"));
             ui.add(
                 egui::Slider::new(&mut config.height, 0.5..=3.0)
                     .step_by(0.05)
@@ -233,7 +298,7 @@ fn render_player_size_config(ui: &mut egui::Ui, camera: &mut CameraParams) {
             );
         });
         ui.horizontal(|ui| {
-            ui.label("Radius / height:");
+            ui.label(tr("camera.radius_ratio"));
             ui.add(
                 egui::Slider::new(
                     &mut config.radius_ratio,
@@ -242,39 +307,590 @@ fn render_player_size_config(ui: &mut egui::Ui, camera: &mut CameraParams) {
                 .step_by(0.01),
             );
         });
-        ui.label(format!(
-            "Capsule radius: {:.2} m, eye height: {:.2} m",
-            config.radius(),
-            config.height
+        ui.label(trf(
+            "camera.capsule",
+            &[
+                ("radius", &fmt_number(f64::from(config.radius()), 2)),
+                ("height", &fmt_number(f64::from(config.height), 2)),
+            ],
         ));
     });
 }
 
+/// Render the spectator picker: every [`Spectatable`] entity, nearest first,
+/// each with a button to orbit it.
+fn render_spectate_picker(ui: &mut egui::Ui, camera: &mut CameraParams) {
+    let Ok((camera_pos, _, _)) = camera.camera_query.single() else {
+        return;
+    };
+    let camera_pos = camera_pos.position;
+    let spectating = camera
+        .follow_target_query
+        .iter()
+        .find(|follow| follow.style == FollowStyle::Orbit)
+        .map(|follow| follow.target);
+
+    let mut targets: Vec<(Entity, String, f64)> = camera
+        .spectatable_query
+        .iter()
+        .map(|(entity, name, world_pos)| {
+            let label = name.map_or_else(|| format!("{entity}"), |n| n.as_str().to_string());
+            (entity, label, world_pos.position.distance(camera_pos))
+        })
+        .collect();
+    targets.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    ui.collapsing(trf("camera.spectate", &[("count", &targets.len())]), |ui| {
+        if targets.is_empty() {
+            ui.label(tr("camera.spectate.none"));
+            return;
+        }
+        for (entity, label, distance) in targets {
+            ui.horizontal(|ui| {
+                let current = spectating == Some(entity);
+                if ui
+                    .add_enabled(!current, egui::Button::new(tr("camera.spectate.orbit")))
+                    .clicked()
+                {
+                    camera.transitions.request_orbit_entity(entity);
+                }
+                ui.label(format!("{label} ({})", fmt_distance(distance)));
+            });
+        }
+        if spectating.is_some() {
+            ui.label(tr("camera.spectate.help"));
+        }
+    });
+}
+
+/// Render the cinematic orbit controls: start an orbit around the point under
+/// the view, shape it, and record a revolution.
+fn render_cinematic_orbit(ui: &mut egui::Ui, camera: &mut CameraParams) {
+    let orbiting = camera
+        .follow_target_query
+        .iter()
+        .any(|follow| follow.style == FollowStyle::Cinematic);
+
+    ui.collapsing(tr("camera.cinematic"), |ui| {
+        ui.horizontal(|ui| {
+            if ui.button(tr("camera.cinematic.here")).clicked() {
+                camera.cinematic_request.request_orbit();
+            }
+            if orbiting && ui.button(tr("common.stop")).clicked() {
+                camera.transitions.request_exit();
+            }
+        });
+
+        let settings = &mut camera.cinematic_settings;
+        ui.horizontal(|ui| {
+            ui.label(tr("camera.radius_label"));
+            ui.add(
+                egui::Slider::new(
+                    &mut settings.radius,
+                    CinematicOrbitSettings::MIN_RADIUS..=CinematicOrbitSettings::MAX_RADIUS,
+                )
+                .logarithmic(true)
+                .suffix(" m"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("camera.height"));
+            ui.add(
+                egui::Slider::new(&mut settings.height, 0.0..=10_000.0)
+                    .logarithmic(true)
+                    .suffix(" m"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("common.speed"));
+            ui.add(egui::Slider::new(&mut settings.speed_deg, -45.0..=45.0).suffix(" °/s"));
+        });
+
+        if !cfg!(target_family = "wasm") {
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.cinematic.record_rate"));
+                ui.add(egui::Slider::new(&mut settings.record_fps, 10..=60).suffix(" fps"));
+            });
+            match camera
+                .cinematic_query
+                .iter()
+                .next()
+                .and_then(CinematicOrbit::recording_progress)
+            {
+                Some((frame, frames)) => {
+                    ui.label(trf(
+                        "camera.cinematic.recording",
+                        &[("frame", &frame), ("frames", &frames)],
+                    ));
+                }
+                None => {
+                    if ui
+                        .add_enabled(orbiting, egui::Button::new(tr("camera.cinematic.record")))
+                        .clicked()
+                    {
+                        camera.cinematic_request.request_recording();
+                    }
+                }
+            }
+        }
+
+        ui.label(tr("camera.cinematic.help"));
+    });
+}
+
+/// Render the orbital flight toggle, and the orbit's readouts while in it.
+fn render_orbital_flight(ui: &mut egui::Ui, camera: &mut CameraParams) {
+    let active = camera.orbital.is_active();
+    let altitude = camera
+        .camera_query
+        .single()
+        .map(|(origin, _, _)| origin.position.length() - veldera_constants::EARTH_RADIUS_M_F64)
+        .unwrap_or(0.0);
+    let settings = &mut camera.orbital_settings;
+    let high_enough = altitude >= settings.min_altitude_m;
+
+    ui.horizontal(|ui| {
+        let label = if active {
+            tr("camera.orbital.leave")
+        } else {
+            tr("camera.orbital")
+        };
+        if ui
+            .add_enabled(active || high_enough, egui::Button::new(label))
+            .on_disabled_hover_text(trf(
+                "camera.orbital.too_low",
+                &[("altitude", &fmt_distance(settings.min_altitude_m))],
+            ))
+            .on_hover_text(tr("camera.orbital.hover"))
+            .clicked()
+        {
+            camera.orbital.request_toggle();
+        }
+        ui.add(
+            egui::Slider::new(&mut settings.thrust, 0.0..=100.0)
+                .text(tr("camera.orbital.thrust"))
+                .suffix(" m/s²"),
+        );
+    });
+
+    let Some(orbit) = camera.orbital.readout() else {
+        return;
+    };
+    let altitude_text = |meters: f64| {
+        if meters < 0.0 {
+            tr("camera.orbital.below_ground").to_string()
+        } else {
+            fmt_distance(meters)
+        }
+    };
+    ui.label(trf(
+        "camera.orbital.speed",
+        &[
+            ("speed", &fmt_number(orbit.speed / 1000.0, 2)),
+            ("altitude", &fmt_distance(orbit.altitude_m)),
+        ],
+    ));
+    ui.label(trf(
+        "camera.orbital.periapsis",
+        &[("altitude", &altitude_text(orbit.periapsis_m))],
+    ));
+    match (orbit.apoapsis_m, orbit.period_s) {
+        (Some(apoapsis), Some(period)) => {
+            ui.label(trf(
+                "camera.orbital.apoapsis",
+                &[("altitude", &altitude_text(apoapsis))],
+            ));
+            let minutes = (period / 60.0).floor();
+            ui.label(trf(
+                "camera.orbital.period",
+                &[
+                    ("minutes", &fmt_number(minutes, 0)),
+                    ("seconds", &fmt_number(period - minutes * 60.0, 0)),
+                ],
+            ));
+        }
+        _ => {
+            ui.label(tr("camera.orbital.escaping"));
+        }
+    }
+    if orbit.drag > 0.0 {
+        ui.label(trf(
+            "camera.orbital.drag",
+            &[("drag", &fmt_number(orbit.drag, 3))],
+        ));
+    }
+}
+
 /// Render follow camera configuration sliders.
+/// Tonemappers offered in the Camera tab, with their name keys. The
+/// LUT-based ones need Bevy's `tonemapping_luts` feature, which the client
+/// enables.
+const TONEMAPPERS: [(Tonemapping, &str); 8] = [
+    (Tonemapping::None, "tonemapper.none"),
+    (Tonemapping::Reinhard, "tonemapper.reinhard"),
+    (
+        Tonemapping::ReinhardLuminance,
+        "tonemapper.reinhard_luminance",
+    ),
+    (Tonemapping::AcesFitted, "tonemapper.aces_fitted"),
+    (Tonemapping::AgX, "tonemapper.agx"),
+    (
+        Tonemapping::SomewhatBoringDisplayTransform,
+        "tonemapper.somewhat_boring",
+    ),
+    (Tonemapping::TonyMcMapface, "tonemapper.tony_mc_mapface"),
+    (Tonemapping::BlenderFilmic, "tonemapper.blender_filmic"),
+];
+
+fn tonemapper_name(tonemapping: Tonemapping) -> &'static str {
+    TONEMAPPERS
+        .iter()
+        .find(|(t, _)| *t == tonemapping)
+        .map_or("?", |(_, key)| tr(key))
+}
+
+fn tonemapper_combo(ui: &mut egui::Ui, id: &str, tonemapping: &mut Tonemapping) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(tonemapper_name(*tonemapping))
+        .show_ui(ui, |ui| {
+            for (option, key) in TONEMAPPERS {
+                changed |= ui.selectable_value(tonemapping, option, tr(key)).changed();
+            }
+        });
+    changed
+}
+
+/// Tonemapper and exposure compensation for the main camera, and the
+/// split-screen comparison against a second tonemapper.
+fn render_tone_mapping(ui: &mut egui::Ui, camera: &mut CameraParams) {
+    let Ok((mut tonemapping, mut exposure)) = camera.view_query.single_mut() else {
+        ui.weak(tr("camera.no_world_camera"));
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.label(tr("camera.tonemapper"));
+        let mut selected = *tonemapping;
+        if tonemapper_combo(ui, "main_tonemapper", &mut selected) {
+            *tonemapping = selected;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("camera.exposure"));
+        let mut compensation = DEFAULT_EV100 - exposure.ev100;
+        if ui
+            .add(
+                egui::Slider::new(&mut compensation, -4.0..=4.0)
+                    .step_by(0.1)
+                    .suffix(" EV"),
+            )
+            .on_hover_text(tr("camera.exposure.hover"))
+            .changed()
+        {
+            exposure.ev100 = DEFAULT_EV100 - compensation;
+        }
+        if ui.button(tr("common.reset")).clicked() {
+            exposure.ev100 = DEFAULT_EV100;
+        }
+    });
+
+    let comparison = &mut *camera.comparison;
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut comparison.enabled, tr("camera.compare"))
+            .on_hover_text(tr("camera.compare.hover"));
+        tonemapper_combo(ui, "comparison_tonemapper", &mut comparison.tonemapping);
+    });
+    if !comparison.enabled {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label(tr("camera.split"));
+        ui.add(egui::Slider::new(&mut comparison.split, 0.0..=1.0));
+    });
+    draw_luminance_histograms(
+        ui,
+        comparison.histogram(ComparisonSide::A),
+        comparison.histogram(ComparisonSide::B),
+    );
+}
+
+const SIDE_A_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 170, 60);
+const SIDE_B_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 170, 240);
+
+/// Both sides' luma histograms, overlaid, with their mean and clipping.
+fn draw_luminance_histograms(
+    ui: &mut egui::Ui,
+    a: Option<&LuminanceHistogram>,
+    b: Option<&LuminanceHistogram>,
+) {
+    if a.is_none() && b.is_none() {
+        ui.weak(tr("camera.histogram.waiting"));
+        return;
+    }
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width().min(360.0), 90.0),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_rgb(18, 20, 26));
+    let peak = [a, b]
+        .into_iter()
+        .flatten()
+        .flat_map(|histogram| histogram.bins)
+        .fold(0.0_f32, f32::max)
+        .max(f32::EPSILON);
+    let bin_w = rect.width() / HISTOGRAM_BINS as f32;
+    for (histogram, color) in [(a, SIDE_A_COLOR), (b, SIDE_B_COLOR)] {
+        let Some(histogram) = histogram else {
+            continue;
+        };
+        let points: Vec<egui::Pos2> = histogram
+            .bins
+            .iter()
+            .enumerate()
+            .map(|(bin, &fraction)| {
+                egui::pos2(
+                    rect.left() + (bin as f32 + 0.5) * bin_w,
+                    rect.bottom() - fraction / peak * (rect.height() - 4.0),
+                )
+            })
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+    }
+    for (side, histogram, color) in [("A", a, SIDE_A_COLOR), ("B", b, SIDE_B_COLOR)] {
+        if let Some(histogram) = histogram {
+            ui.colored_label(
+                color,
+                trf(
+                    "camera.histogram",
+                    &[
+                        ("side", &side),
+                        ("mean", &fmt_number(f64::from(histogram.mean), 2)),
+                        (
+                            "clipped",
+                            &fmt_number(f64::from(histogram.clipped) * 100.0, 1),
+                        ),
+                    ],
+                ),
+            );
+        }
+    }
+}
+
+/// Paint the comparison tonemapper's image right of the split, under the
+/// debug UI, with a divider and labels.
+pub(super) fn draw_tonemap_comparison(
+    mut contexts: EguiContexts,
+    comparison: Res<TonemapComparison>,
+    main: Query<&Tonemapping, With<FloatingOriginCamera>>,
+    mut registered: Local<Option<AssetId<Image>>>,
+) -> Result {
+    let image = comparison.image().map(Handle::id);
+    if *registered != image
+        && let Some(previous) = registered.take()
+    {
+        contexts.remove_image(previous);
+    }
+    let Some(image) = image else {
+        return Ok(());
+    };
+    let texture = contexts.add_image(EguiTextureHandle::Weak(image));
+    *registered = Some(image);
+
+    let ctx = contexts.ctx_mut()?;
+    let rect = ctx.viewport_rect();
+    let split = comparison.split.clamp(0.0, 1.0);
+    let split_x = rect.left() + rect.width() * split;
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    painter.image(
+        texture,
+        egui::Rect::from_min_max(egui::pos2(split_x, rect.top()), rect.max),
+        egui::Rect::from_min_max(egui::pos2(split, 0.0), egui::pos2(1.0, 1.0)),
+        egui::Color32::WHITE,
+    );
+    painter.vline(
+        split_x,
+        rect.y_range(),
+        egui::Stroke::new(1.0, egui::Color32::WHITE),
+    );
+    let font = egui::FontId::proportional(14.0);
+    if let Ok(tonemapping) = main.single() {
+        painter.text(
+            egui::pos2(split_x - 8.0, rect.bottom() - 8.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("A: {}", tonemapper_name(*tonemapping)),
+            font.clone(),
+            SIDE_A_COLOR,
+        );
+    }
+    painter.text(
+        egui::pos2(split_x + 8.0, rect.bottom() - 8.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("B: {}", tonemapper_name(comparison.tonemapping)),
+        font,
+        SIDE_B_COLOR,
+    );
+    Ok(())
+}
+
 fn render_follow_camera_config(ui: &mut egui::Ui, camera: &mut CameraParams) {
     // Find the followed entity from the camera's FollowEntityTarget.
-    let Some(follow_target) = camera.follow_target_query.iter().next() else {
-        ui.label("No follow target");
+    let Some(follow_target) = camera.follow_target_query.iter().next().copied() else {
+        ui.label(tr("camera.no_follow_target"));
         return;
     };
 
+    if follow_target.style == FollowStyle::Orbit {
+        let Some(mut orbit) = camera.orbit_query.iter_mut().next() else {
+            return;
+        };
+        ui.collapsing(tr("camera.orbit_camera"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.distance"));
+                ui.add(
+                    egui::Slider::new(
+                        &mut orbit.distance,
+                        OrbitCamera::MIN_DISTANCE..=OrbitCamera::MAX_DISTANCE,
+                    )
+                    .logarithmic(true)
+                    .suffix(" m"),
+                );
+            });
+        });
+        return;
+    }
+
     let Ok(mut config) = camera.follow_config_query.get_mut(follow_target.target) else {
-        ui.label("Target has no FollowCameraConfig");
+        ui.label(tr("camera.no_follow_config"));
         return;
     };
 
-    ui.collapsing("Follow camera", |ui| {
+    ui.collapsing(tr("camera.follow"), |ui| {
         super::vec3_sliders(
             ui,
-            "Camera offset:",
+            tr("camera.offset"),
             &mut config.camera_offset,
             -50.0..=50.0,
         );
         super::vec3_sliders(
             ui,
-            "Look target offset:",
+            tr("camera.look_offset"),
             &mut config.look_target_offset,
             -50.0..=50.0,
         );
+        ui.horizontal(|ui| {
+            ui.label(tr("camera.position_smoothing"));
+            ui.add(
+                egui::Slider::new(&mut config.position_smoothing, 0.0..=1.0)
+                    .step_by(0.01)
+                    .suffix(" s"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("camera.rotation_smoothing"));
+            ui.add(
+                egui::Slider::new(&mut config.rotation_smoothing, 0.0..=1.0)
+                    .step_by(0.01)
+                    .suffix(" s"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("camera.collision_radius"));
+            ui.add(
+                egui::Slider::new(&mut config.collision_radius, 0.0..=2.0)
+                    .step_by(0.05)
+                    .suffix(" m"),
+            );
+        });
+    });
+    render_follow_camera_effects(ui, &mut camera.follow_effects);
+}
+
+/// Render the chase camera's shake, FOV kick and motion blur settings.
+fn render_follow_camera_effects(ui: &mut egui::Ui, effects: &mut FollowCameraEffects) {
+    ui.collapsing(tr("camera.effects"), |ui| {
+        ui.checkbox(&mut effects.shake, tr("camera.shake"))
+            .on_hover_text(tr("camera.shake.hover"));
+        ui.add_enabled_ui(effects.shake, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.max_angle"));
+                ui.add(
+                    egui::Slider::new(&mut effects.shake_max_angle_deg, 0.0..=5.0)
+                        .step_by(0.1)
+                        .suffix("°"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.acceleration"));
+                ui.add(
+                    egui::Slider::new(&mut effects.shake_min_acceleration, 0.0..=100.0)
+                        .text(tr("camera.from"))
+                        .suffix(" m/s²"),
+                );
+                let min = effects.shake_min_acceleration;
+                ui.add(
+                    egui::Slider::new(&mut effects.shake_full_acceleration, min..=200.0)
+                        .text(tr("camera.full"))
+                        .suffix(" m/s²"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.decay"));
+                ui.add(
+                    egui::Slider::new(&mut effects.shake_decay, 0.1..=5.0)
+                        .step_by(0.1)
+                        .suffix(" /s"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.frequency"));
+                ui.add(egui::Slider::new(&mut effects.shake_frequency, 1.0..=30.0).suffix(" Hz"));
+            });
+        });
+
+        ui.checkbox(&mut effects.fov_kick, tr("camera.fov_kick"))
+            .on_hover_text(tr("camera.fov_kick.hover"));
+        ui.add_enabled_ui(effects.fov_kick, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.max_kick"));
+                ui.add(
+                    egui::Slider::new(&mut effects.fov_kick_max_deg, 0.0..=30.0)
+                        .step_by(0.5)
+                        .suffix("°"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.full_at"));
+                ui.add(
+                    egui::Slider::new(&mut effects.fov_kick_full_speed, 5.0..=300.0)
+                        .logarithmic(true)
+                        .suffix(" m/s"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.smoothing"));
+                ui.add(
+                    egui::Slider::new(&mut effects.fov_kick_smoothing, 0.0..=2.0)
+                        .step_by(0.05)
+                        .suffix(" s"),
+                );
+            });
+        });
+
+        ui.checkbox(&mut effects.motion_blur, tr("camera.motion_blur"))
+            .on_hover_text(tr("camera.motion_blur.hover"));
+        ui.add_enabled_ui(effects.motion_blur, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.shutter_angle"));
+                ui.add(
+                    egui::Slider::new(&mut effects.motion_blur_shutter_angle, 0.0..=1.0)
+                        .step_by(0.05),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("camera.samples"));
+                ui.add(egui::Slider::new(&mut effects.motion_blur_samples, 1..=16));
+            });
+        });
     });
 }
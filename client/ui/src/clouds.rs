@@ -2,18 +2,28 @@
 //!
 //! Live-edits the camera's [`CloudLayers`] container, split across
 //! sub-tabs (overview, layers, shadows, climate, god rays). Each
-//! sub-tab renders its own slice of the cloud state.
+//! sub-tab renders its own slice of the cloud state. The Sky sub-tab
+//! (see `sky.rs`) edits the scattering atmosphere itself, and the Weather
+//! sub-tab (see `weather.rs`) the fog, haze, precipitation and aurora over
+//! it.
 
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::egui;
 use veldera_clouds::{
     CloudDebugMode, CloudLayerKind, CloudLayers, CloudQuality, CloudShadowBakeDiag, CloudWorldTime,
 };
+use veldera_places::LiveWeather;
+use veldera_sky::{aurora::Aurora, weather::Weather};
 
 #[derive(SystemParam)]
 pub(super) struct CloudParams<'w, 's> {
     pub cloud_query: Query<'w, 's, &'static mut CloudLayers>,
     pub world_time: Res<'w, CloudWorldTime>,
+    pub sky: super::sky::SkyParams<'w, 's>,
+    pub weather: ResMut<'w, Weather>,
+    pub aurora: ResMut<'w, Aurora>,
+    pub live_weather: Res<'w, LiveWeather>,
+    pub real_time: Res<'w, Time<Real>>,
 }
 
 /// Currently-selected sub-tab inside the Atmosphere panel.
@@ -25,6 +35,8 @@ pub enum AtmosphereSubTab {
     Shadows,
     GodRays,
     Climate,
+    Sky,
+    Weather,
     Inspector,
 }
 
@@ -39,14 +51,16 @@ pub struct AtmosphereImageIds {
 
 impl AtmosphereSubTab {
     fn label(self) -> &'static str {
-        match self {
-            Self::Overview => "Overview",
-            Self::Layers => "Layers",
-            Self::Shadows => "Shadows",
-            Self::GodRays => "God rays",
-            Self::Climate => "Climate",
-            Self::Inspector => "Inspector",
-        }
+        crate::i18n::tr(match self {
+            Self::Overview => "atmosphere.overview",
+            Self::Layers => "atmosphere.layers",
+            Self::Shadows => "atmosphere.shadows",
+            Self::GodRays => "atmosphere.god_rays",
+            Self::Climate => "atmosphere.climate",
+            Self::Sky => "atmosphere.sky",
+            Self::Weather => "atmosphere.weather",
+            Self::Inspector => "atmosphere.inspector",
+        })
     }
 }
 
@@ -67,6 +81,8 @@ pub(super) fn render_atmosphere_tab(
             AtmosphereSubTab::Shadows,
             AtmosphereSubTab::GodRays,
             AtmosphereSubTab::Climate,
+            AtmosphereSubTab::Sky,
+            AtmosphereSubTab::Weather,
             AtmosphereSubTab::Inspector,
         ] {
             if ui.selectable_label(*subtab == tab, tab.label()).clicked() {
@@ -76,12 +92,30 @@ pub(super) fn render_atmosphere_tab(
     });
     ui.separator();
 
-    // Inspector doesn't touch `CloudLayers`, so it can render even if
-    // the cloud component is missing. Handle it first to skip the
+    // Sky, Weather and Inspector don't touch `CloudLayers`, so they can render
+    // even if the cloud component is missing. Handle them first to skip the
     // cloud-query unwrap below.
-    if matches!(subtab, AtmosphereSubTab::Inspector) {
-        super::inspector::render_inspector_tab(ui, inspector);
-        return;
+    match subtab {
+        AtmosphereSubTab::Sky => {
+            super::sky::render_sky(ui, &mut clouds.sky);
+            return;
+        }
+        AtmosphereSubTab::Weather => {
+            let now = clouds.real_time.elapsed_secs_f64();
+            super::weather::render_weather(
+                ui,
+                &mut clouds.weather,
+                &mut clouds.aurora,
+                &clouds.live_weather,
+                now,
+            );
+            return;
+        }
+        AtmosphereSubTab::Inspector => {
+            super::inspector::render_inspector_tab(ui, inspector);
+            return;
+        }
+        _ => {}
     }
 
     let world_time = clouds.world_time.0;
@@ -96,7 +130,9 @@ pub(super) fn render_atmosphere_tab(
         AtmosphereSubTab::Shadows => render_shadows(ui, &mut cloud, shadow_diag),
         AtmosphereSubTab::GodRays => render_god_rays(ui, &mut cloud),
         AtmosphereSubTab::Climate => render_climate(ui, &mut cloud, image_ids),
-        AtmosphereSubTab::Inspector => unreachable!("handled above"),
+        AtmosphereSubTab::Sky | AtmosphereSubTab::Weather | AtmosphereSubTab::Inspector => {
+            unreachable!("handled above")
+        }
     }
 }
 
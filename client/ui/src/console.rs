@@ -0,0 +1,438 @@
+//! Developer console overlay and the built-in world commands.
+//!
+//! The backtick opens a console along the top of the screen (regardless of
+//! whether the debug UI is visible). Lines run through the
+//! [`veldera_game_console`] registry; this module adds the commands that only
+//! need what the UI already depends on:
+//!
+//! - `tp <lat> <lon>`: teleport.
+//! - `time [HH:MM[:SS] | now]`: set (or show) the local time at the camera.
+//! - `speed [m/s]`: set (or show) the flycam speed.
+//! - `lod [freeze | unfreeze | toggle]`: freeze LoD selection.
+//! - `screenshot [path]`: save the next frame as a PNG (native only).
+//! - `panorama [width] [path]`: capture a 360° panorama as a PNG (native
+//!   only).
+
+use bevy::{
+    prelude::*,
+    window::{CursorOptions, PrimaryWindow},
+};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use leafwing_input_manager::prelude::*;
+
+use veldera_async::TaskSpawner;
+use veldera_game_camera::CameraConfig;
+use veldera_game_console::{
+    CommandResult, Console, ConsoleAppExt, ConsoleCommands, ConsoleLineKind, ConsolePlugin,
+    parse_arg,
+};
+use veldera_game_input::{CameraAction, set_cursor_grab};
+use veldera_game_teleport::TeleportState;
+use veldera_geo::{coords::ecef_to_lat_lon, floating_origin::FloatingOriginCamera};
+use veldera_places::HttpClient;
+use veldera_sky::time_of_day::{TimeOfDayState, local_to_utc, seconds_to_hms};
+use veldera_terrain::lod::FreezeLod;
+
+/// Default height of the console panel (points).
+const CONSOLE_HEIGHT: f32 = 260.0;
+
+/// Plugin for the developer console overlay.
+pub(crate) struct ConsoleUiPlugin;
+
+impl Plugin for ConsoleUiPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ConsolePlugin>() {
+            app.add_plugins(ConsolePlugin);
+        }
+        app.init_resource::<ConsoleUiState>()
+            .add_console_command("tp", "tp <lat> <lon>", "Teleport", tp_command)
+            .add_console_command(
+                "time",
+                "time [HH:MM[:SS] | now]",
+                "Set the local time at the camera, or follow real time",
+                time_command,
+            )
+            .add_console_command(
+                "speed",
+                "speed [m/s]",
+                "Set the flycam speed",
+                speed_command,
+            )
+            .add_console_command(
+                "lod",
+                "lod [freeze | unfreeze | toggle]",
+                "Freeze LoD selection where it is",
+                lod_command,
+            )
+            .add_console_command(
+                "screenshot",
+                "screenshot [path]",
+                "Save the next frame as a PNG",
+                screenshot_command,
+            )
+            .add_console_command(
+                "panorama",
+                "panorama [width] [path]",
+                "Capture a 360° equirectangular panorama as a PNG",
+                panorama_command,
+            )
+            .add_systems(Update, toggle_console)
+            .add_systems(EguiPrimaryContextPass, draw_console);
+    }
+}
+
+/// Console overlay state.
+#[derive(Resource, Default)]
+struct ConsoleUiState {
+    open: bool,
+    input: String,
+    /// The history entry shown while browsing with the arrow keys.
+    history_index: Option<usize>,
+    /// Focus the input on the next draw.
+    focus: bool,
+}
+
+/// Open or close the console on the toggle action. Opening releases the
+/// cursor so the console can be typed into and clicked.
+fn toggle_console(
+    action_query: Query<&ActionState<CameraAction>>,
+    mut state: ResMut<ConsoleUiState>,
+    mut cursor: Single<&mut CursorOptions>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if !action_state.just_pressed(&CameraAction::ToggleConsole) {
+        return;
+    }
+    state.open = !state.open;
+    if state.open {
+        state.focus = true;
+        set_cursor_grab(&mut cursor, &mut window, false);
+    }
+}
+
+fn draw_console(
+    mut contexts: EguiContexts,
+    mut state: ResMut<ConsoleUiState>,
+    mut console: ResMut<Console>,
+    commands: Res<ConsoleCommands>,
+) -> Result {
+    if !state.open {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+
+    // While the input has the keyboard, the toggle action is suppressed, so
+    // the console closes itself on backtick (or Escape). Checking focus first
+    // keeps the key press that opened the console from closing it again.
+    let input_id = egui::Id::new("console_input");
+    let focused = ctx.memory(|memory| memory.has_focus(input_id));
+    if focused
+        && ctx.input_mut(|input| {
+            input.consume_key(egui::Modifiers::NONE, egui::Key::Backtick)
+                || input.consume_key(egui::Modifiers::NONE, egui::Key::Escape)
+        })
+    {
+        state.open = false;
+        state.input.clear();
+        state.history_index = None;
+        return Ok(());
+    }
+
+    let mut move_cursor_to_end = false;
+    if focused {
+        let (up, down, tab) = ctx.input_mut(|input| {
+            (
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+            )
+        });
+        if up || down {
+            browse_history(&mut state, console.history(), up);
+            move_cursor_to_end = true;
+        }
+        if tab {
+            move_cursor_to_end = complete_command(&mut state.input, &commands, &mut console);
+        }
+    }
+
+    egui::TopBottomPanel::top("console")
+        .resizable(true)
+        .default_height(CONSOLE_HEIGHT)
+        .show(ctx, |ui| {
+            let input_height = ui.spacing().interact_size.y + 2.0 * ui.spacing().item_spacing.y;
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .stick_to_bottom(true)
+                .max_height((ui.available_height() - input_height).max(0.0))
+                .show(ui, |ui| {
+                    for line in console.log() {
+                        let text = egui::RichText::new(&line.text).monospace();
+                        ui.label(match line.kind {
+                            ConsoleLineKind::Input => text.weak(),
+                            ConsoleLineKind::Output => text,
+                            ConsoleLineKind::Error => text.color(ui.visuals().error_fg_color),
+                        });
+                    }
+                });
+            ui.separator();
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.input)
+                    .id(input_id)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("help")
+                    // Tab completes (above) rather than moving focus.
+                    .lock_focus(true)
+                    .desired_width(f32::INFINITY),
+            );
+            // The backtick that opened the console may land in the input.
+            state.input.retain(|c| c != '`');
+            if std::mem::take(&mut state.focus) {
+                response.request_focus();
+            }
+            if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                console.submit(&state.input);
+                state.input.clear();
+                state.history_index = None;
+                response.request_focus();
+            }
+            if move_cursor_to_end
+                && let Some(mut text_state) = egui::TextEdit::load_state(ui.ctx(), input_id)
+            {
+                let end = egui::text::CCursor::new(state.input.chars().count());
+                text_state
+                    .cursor
+                    .set_char_range(Some(egui::text::CCursorRange::one(end)));
+                text_state.store(ui.ctx(), input_id);
+            }
+        });
+    Ok(())
+}
+
+/// Step through the submitted lines: back (`up`) or forward, past the newest
+/// to an empty line.
+fn browse_history(state: &mut ConsoleUiState, history: &[String], up: bool) {
+    if history.is_empty() {
+        return;
+    }
+    state.history_index = match (state.history_index, up) {
+        (None, true) => Some(history.len() - 1),
+        (Some(index), true) => Some(index.saturating_sub(1)),
+        (Some(index), false) if index + 1 < history.len() => Some(index + 1),
+        (_, false) => None,
+    };
+    state.input = state
+        .history_index
+        .map(|index| history[index].clone())
+        .unwrap_or_default();
+}
+
+/// Complete the command name being typed. A unique match is filled in;
+/// several are listed in the log. Returns whether the input changed.
+fn complete_command(input: &mut String, commands: &ConsoleCommands, console: &mut Console) -> bool {
+    let prefix = input.trim_start();
+    if prefix.contains(char::is_whitespace) {
+        return false;
+    }
+    let matches: Vec<_> = commands
+        .names()
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    match matches.as_slice() {
+        [] => false,
+        [name] => {
+            *input = format!("{name} ");
+            true
+        }
+        names => {
+            console.print(ConsoleLineKind::Output, &names.join("  "));
+            false
+        }
+    }
+}
+
+fn tp_command(
+    In(args): In<Vec<String>>,
+    mut teleport: ResMut<TeleportState>,
+    http_client: Res<HttpClient>,
+    spawner: TaskSpawner,
+) -> CommandResult {
+    let lat: f64 = parse_arg(&args, 0, "latitude")?;
+    let lon: f64 = parse_arg(&args, 1, "longitude")?;
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err(format!("Latitude {lat} is outside -90..90"));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("Longitude {lon} is outside -180..180"));
+    }
+    teleport.request(lat, lon, &http_client, &spawner);
+    Ok(format!("Teleporting to {lat:.5}, {lon:.5}"))
+}
+
+fn time_command(
+    In(args): In<Vec<String>>,
+    mut time_of_day: ResMut<TimeOfDayState>,
+    camera_query: Query<&FloatingOriginCamera>,
+) -> CommandResult {
+    // Local time is solar time at the camera's longitude, as in the Location
+    // tab.
+    let lon = camera_query
+        .single()
+        .map(|camera| ecef_to_lat_lon(camera.position).1)
+        .unwrap_or(0.0);
+    match args.first().map(String::as_str) {
+        None => {
+            let (seconds, _) = time_of_day.current_local(lon);
+            Ok(format!("Local time {}", format_clock(seconds)))
+        }
+        Some("now") => {
+            time_of_day.sync_to_realtime();
+            Ok("Following real time".to_string())
+        }
+        Some(value) => {
+            let seconds = parse_clock(value)
+                .ok_or_else(|| format!("Expected HH:MM[:SS] or 'now', got '{value}'"))?;
+            let (_, local_date) = time_of_day.current_local(lon);
+            let (utc_seconds, utc_date) = local_to_utc(seconds, local_date, lon);
+            time_of_day.set_override_utc(utc_date, utc_seconds);
+            Ok(format!("Local time set to {}", format_clock(seconds)))
+        }
+    }
+}
+
+fn speed_command(In(args): In<Vec<String>>, mut config: ResMut<CameraConfig>) -> CommandResult {
+    if !args.is_empty() {
+        let speed: f32 = parse_arg(&args, 0, "speed")?;
+        config.base_speed = speed.clamp(config.min_speed, config.max_speed);
+    }
+    Ok(format!("Flycam speed {:.0} m/s", config.base_speed))
+}
+
+fn lod_command(In(args): In<Vec<String>>, mut freeze: ResMut<FreezeLod>) -> CommandResult {
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("freeze") => freeze.0 = true,
+        Some("unfreeze") => freeze.0 = false,
+        Some("toggle") => freeze.0 = !freeze.0,
+        Some(other) => {
+            return Err(format!(
+                "Unknown option '{other}'; expected freeze, unfreeze or toggle"
+            ));
+        }
+    }
+    Ok(if freeze.0 { "LoD frozen" } else { "LoD live" }.to_string())
+}
+
+/// Directory screenshots and panoramas go to when no path is given.
+#[cfg(not(target_family = "wasm"))]
+const SCREENSHOT_DIR: &str = "screenshots";
+
+/// A fresh path in [`SCREENSHOT_DIR`] for a capture named after `prefix`.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn capture_path(prefix: &str) -> std::path::PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    std::path::Path::new(SCREENSHOT_DIR).join(format!("{prefix}_{timestamp}.png"))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn screenshot_command(In(args): In<Vec<String>>, mut commands: Commands) -> CommandResult {
+    use std::path::PathBuf;
+
+    use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+    let path = args
+        .first()
+        .map_or_else(|| capture_path("veldera"), PathBuf::from);
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path.clone()));
+    Ok(format!("Saving screenshot to {}", path.display()))
+}
+
+#[cfg(target_family = "wasm")]
+fn screenshot_command(In(_): In<Vec<String>>) -> CommandResult {
+    Err("Screenshots aren't available on the web".to_string())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn panorama_command(
+    In(args): In<Vec<String>>,
+    mut capture: ResMut<veldera_engine::panorama::PanoramaCapture>,
+) -> CommandResult {
+    use veldera_engine::panorama::{DEFAULT_PANORAMA_WIDTH, PANORAMA_WIDTH_RANGE};
+
+    if capture.stage().is_some() {
+        return Err("A panorama capture is already running".to_string());
+    }
+    let width = match args.first() {
+        Some(_) => parse_arg::<u32>(&args, 0, "width")?,
+        None => DEFAULT_PANORAMA_WIDTH,
+    };
+    let (min, max) = PANORAMA_WIDTH_RANGE;
+    if !(min..=max).contains(&width) {
+        return Err(format!("Width must be between {min} and {max}"));
+    }
+    let path = args
+        .get(1)
+        .map_or_else(|| capture_path("panorama"), std::path::PathBuf::from);
+    capture.request(width, path.clone());
+    Ok(format!(
+        "Capturing a {width}×{} panorama to {}; hold still",
+        width / 2,
+        path.display()
+    ))
+}
+
+#[cfg(target_family = "wasm")]
+fn panorama_command(In(_): In<Vec<String>>) -> CommandResult {
+    Err("Panoramas aren't available on the web".to_string())
+}
+
+/// Parse `HH`, `HH:MM` or `HH:MM:SS` as seconds since midnight.
+fn parse_clock(value: &str) -> Option<f64> {
+    let parts: Vec<u32> = value
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours] => (*hours, 0, 0),
+        [hours, minutes] => (*hours, *minutes, 0),
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        _ => return None,
+    };
+    (hours < 24 && minutes < 60 && seconds < 60)
+        .then(|| f64::from(hours * 3600 + minutes * 60 + seconds))
+}
+
+fn format_clock(seconds: f64) -> String {
+    let (hours, minutes, seconds) = seconds_to_hms(seconds);
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clock_times() {
+        assert_eq!(parse_clock("18:30"), Some(66_600.0));
+        assert_eq!(parse_clock("6"), Some(21_600.0));
+        assert_eq!(parse_clock("00:00:59"), Some(59.0));
+        assert_eq!(parse_clock("24:00"), None);
+        assert_eq!(parse_clock("12:60"), None);
+        assert_eq!(parse_clock("12:30:00:00"), None);
+        assert_eq!(parse_clock("noon"), None);
+    }
+}
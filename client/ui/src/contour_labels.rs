@@ -0,0 +1,207 @@
+//! Height labels on the terrain shader's index contours.
+//!
+//! The contour lines themselves are drawn by the terrain material (see
+//! [`TerrainStyle::contours`]); this labels the heavier index contours. A
+//! coarse grid of rays is cast through the screen a few times a second, and
+//! wherever the ground height between two neighbouring samples crosses an
+//! index contour, the crossing becomes a label candidate. A greedy declutter
+//! pass keeps labels apart on screen, so only a sparse few are drawn.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use glam::DVec3;
+
+use veldera_geo::{coords::ecef_to_geodetic, floating_origin::FloatingOriginCamera};
+use veldera_terrain::{
+    pick::pick_viewport, raycast::TerrainRaycast, terrain_material::TerrainStyle,
+};
+
+use crate::UiVisible;
+
+/// Sample grid columns across the viewport.
+const GRID_COLUMNS: usize = 24;
+
+/// Sample grid rows down the viewport.
+const GRID_ROWS: usize = 14;
+
+/// Seconds between resamples.
+const RESAMPLE_SECS: f32 = 0.25;
+
+/// Most labels drawn at once, after decluttering.
+const MAX_LABELS: usize = 24;
+
+/// Minimum screen distance between two labels (logical pixels).
+const MIN_SPACING_PX: f32 = 140.0;
+
+/// Plugin that samples and draws the index contour labels.
+pub(super) struct ContourLabelsPlugin;
+
+impl Plugin for ContourLabelsPlugin {
+    fn build(&self, app: &mut App) {
+        let contours_on = |style: Res<TerrainStyle>| style.contours;
+        app.init_resource::<ContourLabels>()
+            .add_systems(Update, sample_contour_crossings.run_if(contours_on))
+            .add_systems(
+                EguiPrimaryContextPass,
+                draw_contour_labels
+                    .run_if(contours_on)
+                    .run_if(|visible: Res<UiVisible>| visible.0),
+            );
+    }
+}
+
+/// Index contour crossings found by the last sample.
+#[derive(Resource, Default)]
+struct ContourLabels {
+    /// Crossing position (ECEF) and contour height (m).
+    crossings: Vec<(DVec3, f64)>,
+    /// Seconds until the next resample.
+    cooldown: f32,
+}
+
+/// Where an index contour between two sample heights lies, as the contour
+/// height and the fraction of the way from `a` to `b`. When several lie
+/// between them, the highest is taken.
+fn contour_crossing(a: f64, b: f64, interval: f64) -> Option<(f64, f64)> {
+    let (low, high) = (a.min(b), a.max(b));
+    let level = (high / interval).floor() * interval;
+    if level <= low || (high - low) < f64::EPSILON {
+        return None;
+    }
+    Some((level, (level - a) / (b - a)))
+}
+
+/// Cast the sample grid and record the index contour crossings between
+/// horizontal and vertical neighbours.
+fn sample_contour_crossings(
+    time: Res<Time>,
+    style: Res<TerrainStyle>,
+    raycast: TerrainRaycast,
+    camera_query: Query<(&Camera, &GlobalTransform, &FloatingOriginCamera)>,
+    mut labels: ResMut<ContourLabels>,
+) {
+    labels.cooldown -= time.delta_secs();
+    if labels.cooldown > 0.0 {
+        return;
+    }
+    labels.cooldown = RESAMPLE_SECS;
+    labels.crossings.clear();
+
+    let Ok((camera, camera_transform, origin)) = camera_query.single() else {
+        return;
+    };
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    let samples: Vec<Option<(DVec3, f64)>> = (0..GRID_ROWS)
+        .flat_map(|row| (0..GRID_COLUMNS).map(move |column| (row, column)))
+        .map(|(row, column)| {
+            let point = Vec2::new(
+                (column as f32 + 0.5) / GRID_COLUMNS as f32,
+                (row as f32 + 0.5) / GRID_ROWS as f32,
+            ) * size;
+            let hit = pick_viewport(&raycast, camera, camera_transform, origin, point)?;
+            Some((hit.position, ecef_to_geodetic(hit.position).2))
+        })
+        .collect();
+
+    let interval = f64::from(style.major_contour_interval_m());
+    let sample = |row: usize, column: usize| samples[row * GRID_COLUMNS + column];
+    for row in 0..GRID_ROWS {
+        for column in 0..GRID_COLUMNS {
+            let Some(a) = sample(row, column) else {
+                continue;
+            };
+            let neighbours = [
+                (column + 1 < GRID_COLUMNS).then(|| sample(row, column + 1)),
+                (row + 1 < GRID_ROWS).then(|| sample(row + 1, column)),
+            ];
+            for b in neighbours.into_iter().flatten().flatten() {
+                if let Some((level, t)) = contour_crossing(a.1, b.1, interval) {
+                    labels.crossings.push((a.0.lerp(b.0, t), level));
+                }
+            }
+        }
+    }
+}
+
+/// Resources for drawing the labels.
+#[derive(SystemParam)]
+struct ContourLabelParams<'w, 's> {
+    contexts: EguiContexts<'w, 's>,
+    labels: Res<'w, ContourLabels>,
+    camera_query: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static FloatingOriginCamera,
+        ),
+    >,
+}
+
+/// Draw the crossings nearest the camera first, skipping any too close to a
+/// label already placed.
+fn draw_contour_labels(mut params: ContourLabelParams) -> Result {
+    let Ok((camera, camera_transform, origin)) = params.camera_query.single() else {
+        return Ok(());
+    };
+    let camera_position = origin.position;
+    let mut candidates: Vec<&(DVec3, f64)> = params.labels.crossings.iter().collect();
+    candidates.sort_by(|a, b| {
+        a.0.distance_squared(camera_position)
+            .total_cmp(&b.0.distance_squared(camera_position))
+    });
+
+    let ctx = params.contexts.ctx_mut()?;
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("contour_labels"),
+    ));
+    let color = egui::Color32::from_rgb(250, 225, 180);
+    let mut placed: Vec<egui::Pos2> = Vec::new();
+    for (position, level) in candidates {
+        if placed.len() >= MAX_LABELS {
+            break;
+        }
+        let relative = (*position - camera_position).as_vec3();
+        let Ok(screen) = camera.world_to_viewport(camera_transform, relative) else {
+            continue;
+        };
+        let anchor = egui::pos2(screen.x, screen.y);
+        if placed
+            .iter()
+            .any(|other| other.distance(anchor) < MIN_SPACING_PX)
+        {
+            continue;
+        }
+        let text = format!("{level:.0} m");
+        let font = egui::FontId::proportional(12.0);
+        painter.text(
+            anchor + egui::vec2(1.0, 1.0),
+            egui::Align2::CENTER_CENTER,
+            &text,
+            font.clone(),
+            egui::Color32::BLACK,
+        );
+        painter.text(anchor, egui::Align2::CENTER_CENTER, text, font, color);
+        placed.push(anchor);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_index_contour_crossings() {
+        assert_eq!(contour_crossing(240.0, 260.0, 250.0), Some((250.0, 0.5)));
+        assert_eq!(contour_crossing(260.0, 240.0, 250.0), Some((250.0, 0.5)));
+        assert_eq!(contour_crossing(260.0, 490.0, 250.0), None);
+        assert_eq!(contour_crossing(100.0, 100.0, 250.0), None);
+        // Several contours between: the highest is labelled.
+        assert_eq!(contour_crossing(0.0, 1000.0, 250.0), Some((1000.0, 1.0)));
+    }
+}
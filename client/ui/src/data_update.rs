@@ -0,0 +1,94 @@
+//! Notice shown when the map data updates while running.
+//!
+//! [`EpochRefresh`] notices Google publishing a new planetoid epoch and
+//! refetches the changed tiles in place; this shows a small notice in the
+//! corner while that happens and for a few seconds after, whether or not
+//! the debug UI is open.
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use veldera_terrain::epoch_refresh::EpochRefresh;
+
+use crate::i18n::{tr, trf};
+
+/// How long the notice stays up once the refresh has finished (s).
+const LINGER_SECS: f64 = 8.0;
+
+/// Plugin: shows the data-update notice.
+pub(crate) struct DataUpdateNoticePlugin;
+
+impl Plugin for DataUpdateNoticePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(EguiPrimaryContextPass, show_data_update_notice);
+    }
+}
+
+/// The epoch change the notice was dismissed for, and when the refresh
+/// behind the current one finished (real time, s).
+#[derive(Default)]
+struct NoticeState {
+    dismissed: Option<u32>,
+    finished_at: Option<f64>,
+}
+
+fn show_data_update_notice(
+    mut contexts: EguiContexts,
+    refresh: Res<EpochRefresh>,
+    real_time: Res<Time<Real>>,
+    mut state: Local<NoticeState>,
+) -> Result {
+    let Some(change) = refresh.last_change() else {
+        return Ok(());
+    };
+    if state.dismissed == Some(change.to) {
+        return Ok(());
+    }
+    let now = real_time.elapsed_secs_f64();
+    if refresh.is_refreshing() {
+        state.finished_at = None;
+    } else {
+        let finished_at = *state.finished_at.get_or_insert(now);
+        if now - finished_at > LINGER_SECS {
+            state.dismissed = Some(change.to);
+            return Ok(());
+        }
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new(tr("data_update.title"))
+        .id(egui::Id::new("data_update_notice"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .show(ctx, |ui| {
+            ui.label(trf(
+                "data_update.message",
+                &[("from", &change.from), ("to", &change.to)],
+            ));
+            ui.horizontal(|ui| {
+                if refresh.is_refreshing() {
+                    ui.spinner();
+                    ui.label(trf(
+                        "data_update.progress",
+                        &[
+                            (
+                                "done",
+                                &(refresh.bulks_refreshed() + refresh.nodes_refetched()),
+                            ),
+                            ("remaining", &refresh.remaining()),
+                        ],
+                    ));
+                } else {
+                    ui.label(trf(
+                        "data_update.done",
+                        &[("tiles", &refresh.nodes_refetched())],
+                    ));
+                }
+                if ui.small_button(tr("data_update.dismiss")).clicked() {
+                    state.dismissed = Some(change.to);
+                }
+            });
+        });
+    Ok(())
+}
@@ -0,0 +1,250 @@
+//! Shareable location links.
+//!
+//! A link is a URL query string carrying the camera's position, look
+//! direction, camera mode and (if overridden) the simulation time:
+//!
+//! ```text
+//! ?lat=48.858370&lon=2.294481&alt=350.0&heading=120.0&pitch=-15.0&mode=flycam&time=2024-06-21T18:30:00
+//! ```
+//!
+//! The web build reads it from the page URL at startup; native takes it via
+//! `--link`. Every key is optional and unknown keys are ignored, so links
+//! survive being pasted with extra tracking parameters or a fragment.
+
+use veldera_game_camera_state::CameraMode;
+use veldera_sky::time_of_day::{SimpleDate, seconds_to_hms};
+
+/// A parsed or to-be-shared location link.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeepLink {
+    /// Latitude in degrees.
+    pub lat: Option<f64>,
+    /// Longitude in degrees.
+    pub lon: Option<f64>,
+    /// Altitude above sea level in metres.
+    pub altitude: Option<f64>,
+    /// Look heading in degrees clockwise from north.
+    pub heading: Option<f64>,
+    /// Look pitch in degrees above the horizon.
+    pub pitch: Option<f64>,
+    /// Camera mode. Follow mode has no standalone meaning and is never
+    /// written.
+    pub camera_mode: Option<CameraMode>,
+    /// UTC time override: date plus seconds since midnight.
+    pub datetime: Option<(SimpleDate, f64)>,
+}
+
+impl DeepLink {
+    /// Parse a link: a full URL, a bare query string (with or without the
+    /// leading `?`). Anything after a `#` is ignored.
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let link = link.split_once('#').map_or(link, |(before, _)| before);
+        let query = link.split_once('?').map_or(link, |(_, query)| query);
+
+        let mut parsed = Self::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = value.replace("%3A", ":").replace("%3a", ":");
+            let number = || {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| format!("invalid {key}: '{value}'"))
+            };
+            match key {
+                "lat" => parsed.lat = Some(number()?.clamp(-90.0, 90.0)),
+                "lon" => parsed.lon = Some(number()?),
+                "alt" => parsed.altitude = Some(number()?),
+                "heading" => parsed.heading = Some(number()?),
+                "pitch" => parsed.pitch = Some(number()?.clamp(-90.0, 90.0)),
+                "mode" => {
+                    parsed.camera_mode = Some(match value.as_str() {
+                        "flycam" => CameraMode::Flycam,
+                        "fps" => CameraMode::FpsController,
+                        _ => return Err(format!("unknown mode: '{value}'")),
+                    });
+                }
+                "time" => parsed.datetime = Some(parse_utc_datetime(&value)?),
+                _ => {}
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// The link as a query string, starting with `?`. Fields that are `None`
+    /// are left out.
+    pub fn to_query(&self) -> String {
+        let mut pairs = Vec::new();
+        if let Some(lat) = self.lat {
+            pairs.push(format!("lat={lat:.6}"));
+        }
+        if let Some(lon) = self.lon {
+            pairs.push(format!("lon={lon:.6}"));
+        }
+        if let Some(altitude) = self.altitude {
+            pairs.push(format!("alt={altitude:.1}"));
+        }
+        if let Some(heading) = self.heading {
+            pairs.push(format!("heading={heading:.1}"));
+        }
+        if let Some(pitch) = self.pitch {
+            pairs.push(format!("pitch={pitch:.1}"));
+        }
+        match self.camera_mode {
+            Some(CameraMode::Flycam) => pairs.push("mode=flycam".to_string()),
+            Some(CameraMode::FpsController) => pairs.push("mode=fps".to_string()),
+            Some(CameraMode::FollowEntity) | None => {}
+        }
+        if let Some((date, seconds)) = self.datetime {
+            let (h, m, s) = seconds_to_hms(seconds);
+            pairs.push(format!(
+                "time={:04}-{:02}-{:02}T{h:02}:{m:02}:{s:02}",
+                date.year, date.month, date.day
+            ));
+        }
+        format!("?{}", pairs.join("&"))
+    }
+
+    /// The link to hand out: the page URL with this query on the web; the
+    /// bare query (for `--link`) on native.
+    pub fn share_url(&self) -> String {
+        match page_base_url() {
+            Some(base) => format!("{base}{}", self.to_query()),
+            None => self.to_query(),
+        }
+    }
+}
+
+/// Parse a `YYYY-MM-DDTHH:MM:SS` UTC string (a space also separates date and
+/// time) into a date and seconds since midnight.
+pub fn parse_utc_datetime(s: &str) -> Result<(SimpleDate, f64), String> {
+    let s = s.replace(' ', "T");
+    let Some((date, time)) = s.split_once('T') else {
+        return Err(format!("expected YYYY-MM-DDTHH:MM:SS, got '{s}'"));
+    };
+
+    let date_parts: Vec<&str> = date.split('-').collect();
+    let time_parts: Vec<&str> = time.split(':').collect();
+    if date_parts.len() != 3 || time_parts.len() != 3 {
+        return Err(format!("expected YYYY-MM-DDTHH:MM:SS, got '{s}'"));
+    }
+
+    let year = date_parts[0]
+        .parse::<i32>()
+        .map_err(|e| format!("invalid year: {e}"))?;
+    let month = date_parts[1]
+        .parse::<u32>()
+        .map_err(|e| format!("invalid month: {e}"))?;
+    let day = date_parts[2]
+        .parse::<u32>()
+        .map_err(|e| format!("invalid day: {e}"))?;
+    let hour = time_parts[0]
+        .parse::<u32>()
+        .map_err(|e| format!("invalid hour: {e}"))?;
+    let minute = time_parts[1]
+        .parse::<u32>()
+        .map_err(|e| format!("invalid minute: {e}"))?;
+    let second = time_parts[2]
+        .parse::<u32>()
+        .map_err(|e| format!("invalid second: {e}"))?;
+
+    if !(1..=12).contains(&month) {
+        return Err(format!("month out of range: {month}"));
+    }
+    if !(1..=31).contains(&day) {
+        return Err(format!("day out of range: {day}"));
+    }
+    if hour >= 24 {
+        return Err(format!("hour out of range: {hour}"));
+    }
+    if minute >= 60 {
+        return Err(format!("minute out of range: {minute}"));
+    }
+    if second >= 60 {
+        return Err(format!("second out of range: {second}"));
+    }
+
+    Ok((
+        SimpleDate::new(year, month, day),
+        f64::from(hour) * 3600.0 + f64::from(minute) * 60.0 + f64::from(second),
+    ))
+}
+
+/// The page's full URL, including any query string.
+#[cfg(target_family = "wasm")]
+pub fn page_url() -> Option<String> {
+    use wasm_bindgen::JsValue;
+
+    let location = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("location")).ok()?;
+    js_sys::Reflect::get(&location, &JsValue::from_str("href"))
+        .ok()?
+        .as_string()
+}
+
+/// The page URL without its query string or fragment.
+#[cfg(target_family = "wasm")]
+fn page_base_url() -> Option<String> {
+    let url = page_url()?;
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    Some(url[..end].to_string())
+}
+
+/// Native builds have no page; links are shared as bare queries.
+#[cfg(not(target_family = "wasm"))]
+fn page_base_url() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_query() {
+        let link = DeepLink {
+            lat: Some(48.85837),
+            lon: Some(-2.294481),
+            altitude: Some(350.5),
+            heading: Some(120.0),
+            pitch: Some(-15.5),
+            camera_mode: Some(CameraMode::FpsController),
+            datetime: Some((
+                SimpleDate::new(2024, 6, 21),
+                18.0 * 3600.0 + 30.0 * 60.0 + 5.0,
+            )),
+        };
+        assert_eq!(DeepLink::parse(&link.to_query()), Ok(link));
+    }
+
+    #[test]
+    fn parses_full_url_ignoring_extras() {
+        let link = DeepLink::parse(
+            "https://example.com/app/?utm=x&lat=10&lon=20&time=2024-01-02T03%3A04%3A05#top",
+        )
+        .unwrap();
+        assert_eq!(link.lat, Some(10.0));
+        assert_eq!(link.lon, Some(20.0));
+        assert_eq!(link.altitude, None);
+        assert_eq!(
+            link.datetime,
+            Some((SimpleDate::new(2024, 1, 2), 3.0 * 3600.0 + 4.0 * 60.0 + 5.0))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        assert!(DeepLink::parse("?lat=north").is_err());
+        assert!(DeepLink::parse("?mode=orbit").is_err());
+        assert!(DeepLink::parse("?time=2024-13-01T00:00:00").is_err());
+    }
+
+    #[test]
+    fn omits_follow_mode() {
+        let link = DeepLink {
+            camera_mode: Some(CameraMode::FollowEntity),
+            ..Default::default()
+        };
+        assert_eq!(link.to_query(), "?");
+    }
+}
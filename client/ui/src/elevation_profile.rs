@@ -0,0 +1,540 @@
+//! Elevation profile along a path picked on the terrain.
+//!
+//! With picking on, each click on the terrain (cursor free) appends a vertex
+//! to the path. The profile samples the path evenly along its great-circle
+//! legs: each sample is cast straight down onto the loaded terrain meshes,
+//! and samples over terrain that isn't loaded are fetched from the Open
+//! Elevation API in one batched request. The Annotations tab plots distance
+//! against altitude and exports the samples as CSV.
+
+use std::path::PathBuf;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+use bevy_egui::{egui, input::EguiWantsInput};
+use egui_plot::{Line, Plot, PlotPoints, Points};
+use glam::DVec3;
+
+use veldera_async::TaskSpawner;
+use veldera_constants::EARTH_RADIUS_M_F64;
+use veldera_geo::{
+    coords::{ecef_to_lat_lon, lat_lon_to_ecef, slerp_dvec3},
+    floating_origin::FloatingOriginCamera,
+};
+use veldera_places::{HttpClient, fetch_elevations};
+use veldera_terrain::{pick::TerrainPicker, raycast::TerrainRaycast};
+
+use crate::i18n::{fmt_distance, fmt_number, tr, trf};
+
+/// Samples along the whole path.
+const SAMPLE_COUNT: usize = 256;
+
+/// Height above the reference sphere the downward sample rays start from (m);
+/// above every summit.
+const RAY_START_HEIGHT_M: f64 = 12_000.0;
+
+/// Length of the downward sample rays (m); reaches below the deepest trench.
+const RAY_LENGTH_M: f64 = 25_000.0;
+
+/// Where the profile CSV files are written.
+const PROFILES_DIR: &str = "profiles";
+
+/// Plugin for the elevation profile's picking, sampling and in-world path.
+pub(super) struct ElevationProfilePlugin;
+
+impl Plugin for ElevationProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ElevationProfile>().add_systems(
+            Update,
+            (
+                add_point_on_click,
+                sample_profile,
+                receive_fetched_elevations,
+                draw_profile_path,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Where a sample's altitude came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SampleSource {
+    /// Cast onto the loaded terrain meshes.
+    Terrain,
+    /// Fetched from the elevation API.
+    Fetched,
+}
+
+/// One point of the profile.
+#[derive(Clone, Copy, Debug)]
+struct ProfileSample {
+    /// Distance along the path from its start (m).
+    distance: f64,
+    lat: f64,
+    lon: f64,
+    /// Altitude above the reference sphere (m), once known.
+    altitude: Option<f64>,
+    source: Option<SampleSource>,
+}
+
+/// Fetched elevations for the samples at these indices, tagged with the
+/// path revision they were requested for.
+type FetchResult = (u64, Vec<usize>, Result<Vec<f64>, String>);
+
+/// The picked path and its sampled profile.
+#[derive(Resource)]
+pub(super) struct ElevationProfile {
+    /// Whether left clicks on the terrain add path points.
+    pub picking: bool,
+    /// Path vertices as `(lat, lon, altitude)`: degrees, and metres above
+    /// the reference sphere as picked.
+    points: Vec<(f64, f64, f64)>,
+    /// Bumped whenever the path changes; the profile is resampled and stale
+    /// fetches are dropped.
+    revision: u64,
+    /// Revision the samples were taken for.
+    sampled: u64,
+    samples: Vec<ProfileSample>,
+    /// Whether a fetch for unloaded samples is in flight.
+    fetching: bool,
+    /// Last fetch error.
+    error: Option<String>,
+    /// Outcome of the last CSV export.
+    last_export: Option<Result<PathBuf, String>>,
+    fetch_tx: async_channel::Sender<FetchResult>,
+    fetch_rx: async_channel::Receiver<FetchResult>,
+}
+
+impl Default for ElevationProfile {
+    fn default() -> Self {
+        let (fetch_tx, fetch_rx) = async_channel::unbounded();
+        Self {
+            picking: false,
+            points: Vec::new(),
+            revision: 0,
+            sampled: 0,
+            samples: Vec::new(),
+            fetching: false,
+            error: None,
+            last_export: None,
+            fetch_tx,
+            fetch_rx,
+        }
+    }
+}
+
+impl ElevationProfile {
+    fn push_point(&mut self, lat: f64, lon: f64, altitude: f64) {
+        self.points.push((lat, lon, altitude));
+        self.revision += 1;
+    }
+
+    fn clear(&mut self) {
+        self.points.clear();
+        self.samples.clear();
+        self.error = None;
+        self.revision += 1;
+    }
+
+    /// Whether every sample has an altitude.
+    fn is_complete(&self) -> bool {
+        self.samples.iter().all(|s| s.altitude.is_some())
+    }
+}
+
+/// Evenly spaced `(distance, lat, lon)` samples along the great-circle legs
+/// between `points`, including both ends.
+fn path_samples(points: &[(f64, f64)], count: usize) -> Vec<(f64, f64, f64)> {
+    let directions: Vec<DVec3> = points
+        .iter()
+        .map(|&(lat, lon)| lat_lon_to_ecef(lat, lon, 1.0))
+        .collect();
+    let legs: Vec<f64> = directions
+        .windows(2)
+        .map(|pair| pair[0].dot(pair[1]).clamp(-1.0, 1.0).acos() * EARTH_RADIUS_M_F64)
+        .collect();
+    let total: f64 = legs.iter().sum();
+    if legs.is_empty() || count < 2 || total <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut samples = Vec::with_capacity(count);
+    let mut leg = 0;
+    let mut leg_start = 0.0;
+    for i in 0..count {
+        let distance = total * i as f64 / (count - 1) as f64;
+        while leg + 1 < legs.len() && distance > leg_start + legs[leg] {
+            leg_start += legs[leg];
+            leg += 1;
+        }
+        let t = if legs[leg] > 0.0 {
+            ((distance - leg_start) / legs[leg]).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (lat, lon) = ecef_to_lat_lon(slerp_dvec3(directions[leg], directions[leg + 1], t));
+        samples.push((distance, lat, lon));
+    }
+    samples
+}
+
+/// Total climb and descent between consecutive known altitudes (m).
+fn ascent_descent(samples: &[ProfileSample]) -> (f64, f64) {
+    let altitudes: Vec<f64> = samples.iter().filter_map(|s| s.altitude).collect();
+    altitudes.windows(2).fold((0.0, 0.0), |(up, down), pair| {
+        let delta = pair[1] - pair[0];
+        if delta > 0.0 {
+            (up + delta, down)
+        } else {
+            (up, down - delta)
+        }
+    })
+}
+
+/// The samples as CSV: a header row, then one row per sample with a missing
+/// altitude left empty.
+fn profile_csv(samples: &[ProfileSample]) -> String {
+    let mut csv = String::from("distance_m,lat,lon,altitude_m,source\n");
+    for sample in samples {
+        let altitude = sample
+            .altitude
+            .map_or_else(String::new, |altitude| format!("{altitude:.2}"));
+        let source = match sample.source {
+            Some(SampleSource::Terrain) => "terrain",
+            Some(SampleSource::Fetched) => "fetched",
+            None => "",
+        };
+        csv.push_str(&format!(
+            "{:.2},{:.7},{:.7},{altitude},{source}\n",
+            sample.distance, sample.lat, sample.lon
+        ));
+    }
+    csv
+}
+
+/// Write the profile to a fresh file under [`PROFILES_DIR`].
+#[cfg(not(target_family = "wasm"))]
+fn export_csv(samples: &[ProfileSample]) -> Result<PathBuf, String> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    std::fs::create_dir_all(PROFILES_DIR).map_err(|e| e.to_string())?;
+    let path = PathBuf::from(PROFILES_DIR).join(format!("profile-{stamp}.csv"));
+    std::fs::write(&path, profile_csv(samples)).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Exporting writes to disk, which the web build can't; copy the CSV instead.
+#[cfg(target_family = "wasm")]
+fn export_csv(_samples: &[ProfileSample]) -> Result<PathBuf, String> {
+    Err(format!(
+        "saving to {PROFILES_DIR}/ is not available on the web; copy the CSV instead"
+    ))
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Append the terrain point under the cursor on a left click, while picking
+/// is on and the cursor is free.
+fn add_point_on_click(
+    mouse: Res<ButtonInput<MouseButton>>,
+    egui_wants: Res<EguiWantsInput>,
+    cursor: Single<&CursorOptions>,
+    picker: Res<TerrainPicker>,
+    mut profile: ResMut<ElevationProfile>,
+) {
+    if !profile.picking
+        || cursor.grab_mode != CursorGrabMode::None
+        || !mouse.just_pressed(MouseButton::Left)
+        || egui_wants.is_pointer_over_area()
+    {
+        return;
+    }
+    if let Some(hit) = picker.hit() {
+        profile.push_point(hit.lat_deg, hit.lon_deg, hit.altitude);
+    }
+}
+
+/// Resample the profile when the path changes: cast each sample onto the
+/// loaded terrain, and fetch the rest in one batch.
+fn sample_profile(
+    mut profile: ResMut<ElevationProfile>,
+    raycast: TerrainRaycast,
+    http_client: Res<HttpClient>,
+    spawner: TaskSpawner,
+) {
+    if profile.sampled == profile.revision {
+        return;
+    }
+    let profile = &mut *profile;
+    profile.sampled = profile.revision;
+    profile.error = None;
+    let vertices: Vec<(f64, f64)> = profile
+        .points
+        .iter()
+        .map(|&(lat, lon, _)| (lat, lon))
+        .collect();
+    profile.samples = path_samples(&vertices, SAMPLE_COUNT)
+        .into_iter()
+        .map(|(distance, lat, lon)| {
+            let up = lat_lon_to_ecef(lat, lon, 1.0);
+            let hit = raycast.cast_ray(
+                up * (EARTH_RADIUS_M_F64 + RAY_START_HEIGHT_M),
+                -up,
+                RAY_LENGTH_M,
+            );
+            ProfileSample {
+                distance,
+                lat,
+                lon,
+                altitude: hit.map(|hit| hit.altitude),
+                source: hit.map(|_| SampleSource::Terrain),
+            }
+        })
+        .collect();
+
+    let (missing, points): (Vec<usize>, Vec<(f64, f64)>) = profile
+        .samples
+        .iter()
+        .enumerate()
+        .filter(|(_, sample)| sample.altitude.is_none())
+        .map(|(index, sample)| (index, (sample.lat, sample.lon)))
+        .unzip();
+    profile.fetching = !missing.is_empty();
+    if missing.is_empty() {
+        return;
+    }
+    let revision = profile.revision;
+    let tx = profile.fetch_tx.clone();
+    let client = http_client.inner().clone();
+    spawner.spawn(async move {
+        let result = fetch_elevations(&client, &points).await;
+        let _ = tx.send((revision, missing, result)).await;
+    });
+}
+
+/// Fill in fetched altitudes for the current path; results for an older path
+/// are dropped.
+fn receive_fetched_elevations(mut profile: ResMut<ElevationProfile>) {
+    while let Ok((revision, indices, result)) = profile.fetch_rx.try_recv() {
+        if revision != profile.revision {
+            continue;
+        }
+        profile.fetching = false;
+        match result {
+            Ok(elevations) => {
+                for (index, elevation) in indices.into_iter().zip(elevations) {
+                    if let Some(sample) = profile.samples.get_mut(index) {
+                        sample.altitude = Some(elevation);
+                        sample.source = Some(SampleSource::Fetched);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("Elevation profile fetch failed: {e}");
+                profile.error = Some(e);
+            }
+        }
+    }
+}
+
+/// Draw the path draped over its samples, with a marker at each vertex.
+fn draw_profile_path(
+    profile: Res<ElevationProfile>,
+    camera_query: Query<&FloatingOriginCamera>,
+    mut gizmos: Gizmos,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let color = Color::srgb(1.0, 0.45, 0.1);
+    let relative = |lat: f64, lon: f64, altitude: f64| {
+        (lat_lon_to_ecef(lat, lon, EARTH_RADIUS_M_F64 + altitude) - camera.position).as_vec3()
+    };
+    gizmos.linestrip(
+        profile
+            .samples
+            .iter()
+            .filter_map(|s| Some(relative(s.lat, s.lon, s.altitude? + 2.0))),
+        color,
+    );
+    for &(lat, lon, altitude) in &profile.points {
+        let position = relative(lat, lon, altitude);
+        gizmos.sphere(
+            Isometry3d::from_translation(position),
+            (position.length() * 0.005).max(1.0),
+            color,
+        );
+    }
+}
+
+// ============================================================================
+// Panel
+// ============================================================================
+
+/// Resources for the elevation profile panel.
+#[derive(SystemParam)]
+pub(super) struct ElevationProfileParams<'w> {
+    pub profile: ResMut<'w, ElevationProfile>,
+}
+
+/// Render the elevation profile section: picking controls, summary, plot and
+/// export.
+pub(super) fn render_elevation_profile(ui: &mut egui::Ui, params: &mut ElevationProfileParams) {
+    let profile = &mut *params.profile;
+    ui.collapsing(tr("profile.title"), |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut profile.picking, tr("profile.pick"))
+                .on_hover_text(tr("profile.pick.hover"));
+            if !profile.points.is_empty() && ui.button(tr("profile.undo")).clicked() {
+                profile.points.pop();
+                profile.revision += 1;
+            }
+            if !profile.points.is_empty() && ui.button(tr("common.clear")).clicked() {
+                profile.clear();
+            }
+        });
+
+        if profile.points.len() < 2 {
+            ui.weak(trf("profile.too_few", &[("count", &profile.points.len())]));
+            return;
+        }
+
+        let length = profile.samples.last().map_or(0.0, |s| s.distance);
+        let known = || profile.samples.iter().filter_map(|s| s.altitude);
+        let (ascent, descent) = ascent_descent(&profile.samples);
+        ui.label(trf(
+            "profile.summary",
+            &[
+                ("count", &profile.points.len()),
+                ("length", &fmt_distance(length)),
+            ],
+        ));
+        if let (Some(min), Some(max)) = (known().reduce(f64::min), known().reduce(f64::max)) {
+            ui.label(trf(
+                "profile.altitudes",
+                &[
+                    ("min", &fmt_number(min, 0)),
+                    ("max", &fmt_number(max, 0)),
+                    ("ascent", &fmt_number(ascent, 0)),
+                    ("descent", &fmt_number(descent, 0)),
+                ],
+            ));
+        }
+        if profile.fetching {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(tr("profile.fetching"));
+            });
+        }
+        if let Some(error) = &profile.error {
+            ui.colored_label(
+                egui::Color32::RED,
+                trf("profile.fetch_failed", &[("error", error)]),
+            );
+        }
+
+        let line: PlotPoints = profile
+            .samples
+            .iter()
+            .filter_map(|s| Some([s.distance, s.altitude?]))
+            .collect();
+        let fetched: PlotPoints = profile
+            .samples
+            .iter()
+            .filter(|s| s.source == Some(SampleSource::Fetched))
+            .filter_map(|s| Some([s.distance, s.altitude?]))
+            .collect();
+        Plot::new("elevation_profile_plot")
+            .height(160.0)
+            .x_axis_label(tr("profile.axis.distance"))
+            .y_axis_label(tr("profile.axis.altitude"))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("altitude", line).color(egui::Color32::ORANGE));
+                plot_ui.points(
+                    Points::new("fetched", fetched)
+                        .radius(1.5)
+                        .color(egui::Color32::LIGHT_BLUE),
+                );
+            });
+        ui.weak(tr("profile.fetched_note"));
+
+        ui.horizontal(|ui| {
+            let complete = profile.is_complete();
+            if ui
+                .add_enabled(complete, egui::Button::new(tr("profile.export")))
+                .clicked()
+            {
+                profile.last_export = Some(export_csv(&profile.samples));
+            }
+            if ui
+                .add_enabled(complete, egui::Button::new(tr("profile.copy")))
+                .clicked()
+            {
+                ui.ctx().copy_text(profile_csv(&profile.samples));
+            }
+        });
+        match &profile.last_export {
+            Some(Ok(path)) => {
+                ui.label(trf("profile.saved", &[("path", &path.display())]));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    trf("profile.export_failed", &[("error", e)]),
+                );
+            }
+            None => {}
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_span_the_whole_path() {
+        let points = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)];
+        let samples = path_samples(&points, 101);
+        assert_eq!(samples.len(), 101);
+        let (first, last) = (samples[0], samples[100]);
+        assert!(first.0 == 0.0 && first.1.abs() < 1e-9 && first.2.abs() < 1e-9);
+        assert!((last.1 - 1.0).abs() < 1e-9 && (last.2 - 1.0).abs() < 1e-9);
+        // Two one-degree legs along great circles.
+        let degree = EARTH_RADIUS_M_F64.to_radians();
+        assert!((last.0 - 2.0 * degree).abs() < 1.0);
+        // The midpoint is the corner.
+        assert!(samples[50].1.abs() < 1e-6 && (samples[50].2 - 1.0).abs() < 1e-6);
+        assert!(path_samples(&points[..1], 101).is_empty());
+    }
+
+    #[test]
+    fn climb_and_csv() {
+        let sample = |distance, altitude| ProfileSample {
+            distance,
+            lat: 0.0,
+            lon: 0.0,
+            altitude,
+            source: altitude.map(|_| SampleSource::Terrain),
+        };
+        let samples = [
+            sample(0.0, Some(100.0)),
+            sample(10.0, Some(150.0)),
+            sample(20.0, None),
+            sample(30.0, Some(120.0)),
+        ];
+        assert_eq!(ascent_descent(&samples), (50.0, 30.0));
+        let csv = profile_csv(&samples);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "distance_m,lat,lon,altitude_m,source");
+        assert_eq!(lines[1], "0.00,0.0000000,0.0000000,100.00,terrain");
+        assert_eq!(lines[3], "20.00,0.0000000,0.0000000,,");
+    }
+}
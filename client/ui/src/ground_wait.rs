@@ -0,0 +1,54 @@
+//! Notice shown while the first-person player waits for the ground to load.
+//!
+//! A player dropped into first-person over terrain whose colliders haven't
+//! streamed in is held in place until there's ground under it (see
+//! [`FpsGroundWait`]); this says so, whether or not the debug UI is open, and
+//! offers a way back to the flycam should the ground be slow to arrive.
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use veldera_game_camera::{CameraModeTransitions, FpsGroundWait};
+
+use crate::i18n::tr;
+
+/// Plugin: shows the ground-wait notice.
+pub(crate) struct GroundWaitNoticePlugin;
+
+impl Plugin for GroundWaitNoticePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(EguiPrimaryContextPass, show_ground_wait_notice);
+    }
+}
+
+fn show_ground_wait_notice(
+    mut contexts: EguiContexts,
+    ground_wait: Res<FpsGroundWait>,
+    mut transitions: ResMut<CameraModeTransitions>,
+) -> Result {
+    if !ground_wait.is_waiting() {
+        return Ok(());
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new(tr("ground_wait.title"))
+        .id(egui::Id::new("ground_wait_notice"))
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -40.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(tr("ground_wait.message"));
+                if ui
+                    .small_button(tr("ground_wait.flycam"))
+                    .on_hover_text(tr("ground_wait.flycam.hover"))
+                    .clicked()
+                {
+                    transitions.request_flycam();
+                }
+            });
+        });
+    Ok(())
+}
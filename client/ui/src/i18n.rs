@@ -0,0 +1,236 @@
+//! UI localization: string bundles, the current language, and locale-aware
+//! number and coordinate formatting.
+//!
+//! Strings live in flat key → text JSON bundles under `locales/`, one per
+//! language, compiled into the binary. [`tr`] looks a key up in the current
+//! language, falling back to English and then to the key itself, so a
+//! missing translation reads as English rather than blank; [`trf`] fills
+//! `{name}` placeholders. The current language is process-wide rather than
+//! a resource, since egui draws from many systems and nested render helpers.
+//! The Settings tab switches it at runtime and
+//! [`UserSettings`](crate::settings::UserSettings) persists it.
+//!
+//! Everything a user of the explorer reads is keyed: the tab strip, the
+//! recovery prompt, and the Location & time, Settings, Camera and
+//! Annotations tabs (with the elevation profile and viewshed), the Vehicles
+//! spawner, status and recorder, the Atmosphere subtab names and its Weather
+//! subtab. The diagnostics tabs are keyed too: Streaming, Physics (with the
+//! node inspector), Rendering and the Atmosphere Sky subtab. Only the
+//! Profiler tab, the Clouds tuning subtab and the vehicle tuner readouts and
+//! sliders stay English.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A UI language with a bundled translation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    /// Every bundled language, in picker order.
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// The language's name in itself, for the picker.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    fn bundle_source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.json"),
+            Language::German => include_str!("../locales/de.json"),
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Language::English => '.',
+            Language::German => ',',
+        }
+    }
+
+    fn group_separator(self) -> char {
+        match self {
+            Language::English => ',',
+            Language::German => '.',
+        }
+    }
+}
+
+/// Index into [`Language::ALL`] of the current language.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Parsed bundles, indexed like [`Language::ALL`]. A malformed bundle is a
+/// build-time authoring error, caught by the tests.
+static BUNDLES: LazyLock<Vec<HashMap<String, String>>> = LazyLock::new(|| {
+    Language::ALL
+        .iter()
+        .map(|language| serde_json::from_str(language.bundle_source()).unwrap_or_default())
+        .collect()
+});
+
+/// The current UI language.
+pub fn language() -> Language {
+    Language::ALL
+        .get(usize::from(CURRENT.load(Ordering::Relaxed)))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Switch the UI language; takes effect from the next frame drawn.
+pub fn set_language(language: Language) {
+    let index = Language::ALL
+        .iter()
+        .position(|l| *l == language)
+        .unwrap_or(0);
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    let index = Language::ALL.iter().position(|l| *l == language)?;
+    BUNDLES.get(index)?.get(key).map(String::as_str)
+}
+
+/// The text for `key` in the current language.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(language(), key)
+        .or_else(|| lookup(Language::English, key))
+        .unwrap_or(key)
+}
+
+/// The text for `key` with each `{name}` replaced by its argument.
+pub fn trf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+/// `value` with `decimals` places, in the current language's separators.
+pub fn fmt_number(value: f64, decimals: usize) -> String {
+    format_number_in(language(), value, decimals)
+}
+
+fn format_number_in(language: Language, value: f64, decimals: usize) -> String {
+    let plain = format!("{:.*}", decimals, value.abs());
+    let (int, frac) = plain.split_once('.').unwrap_or((&plain, ""));
+
+    let mut out = String::with_capacity(plain.len() + int.len() / 3 + 1);
+    if value.is_sign_negative() && plain.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        out.push('-');
+    }
+    // Group only from five digits up: "1234 m" reads better ungrouped.
+    let group = int.len() > 4;
+    for (i, digit) in int.chars().enumerate() {
+        if group && i > 0 && (int.len() - i) % 3 == 0 {
+            out.push(language.group_separator());
+        }
+        out.push(digit);
+    }
+    if !frac.is_empty() {
+        out.push(language.decimal_separator());
+        out.push_str(frac);
+    }
+    out
+}
+
+/// A distance in metres below a kilometre, else in kilometres: one decimal
+/// below 100 km, whole kilometres beyond.
+pub fn fmt_distance(meters: f64) -> String {
+    format_distance_in(language(), meters)
+}
+
+fn format_distance_in(language: Language, meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{} m", format_number_in(language, meters, 0))
+    } else {
+        let km = meters / 1000.0;
+        let decimals = if km < 100.0 { 1 } else { 0 };
+        format!("{} km", format_number_in(language, km, decimals))
+    }
+}
+
+/// Parse a number typed in either the current language's decimal separator
+/// or a plain `.`.
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    text.parse().ok().or_else(|| {
+        text.replace(language().decimal_separator(), ".")
+            .parse()
+            .ok()
+    })
+}
+
+/// `lat, lon` as hemisphere-suffixed degrees, e.g. `47.37690° N, 8.54170° E`.
+pub fn fmt_lat_lon(lat_deg: f64, lon_deg: f64, decimals: usize) -> String {
+    let lat_hemisphere = if lat_deg >= 0.0 { "dir.n" } else { "dir.s" };
+    let lon_hemisphere = if lon_deg >= 0.0 { "dir.e" } else { "dir.w" };
+    format!(
+        "{}° {}, {}° {}",
+        fmt_number(lat_deg.abs(), decimals),
+        tr(lat_hemisphere),
+        fmt_number(lon_deg.abs(), decimals),
+        tr(lon_hemisphere),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_parse_and_translations_have_english_keys() {
+        let english: HashMap<String, String> =
+            serde_json::from_str(Language::English.bundle_source()).unwrap();
+        for language in Language::ALL {
+            let bundle: HashMap<String, String> =
+                serde_json::from_str(language.bundle_source()).unwrap();
+            for key in bundle.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{language:?} key {key} missing from English"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn numbers_use_the_language_separators() {
+        assert_eq!(format_number_in(Language::English, 1234.5, 1), "1234.5");
+        assert_eq!(
+            format_number_in(Language::English, 6_371_000.0, 0),
+            "6,371,000"
+        );
+        assert_eq!(
+            format_number_in(Language::German, -12_345.678, 2),
+            "-12.345,68"
+        );
+        assert_eq!(format_number_in(Language::German, -0.001, 1), "0,0");
+    }
+
+    #[test]
+    fn distances_switch_units_and_precision() {
+        assert_eq!(format_distance_in(Language::English, 999.4), "999 m");
+        assert_eq!(format_distance_in(Language::English, 1260.0), "1.3 km");
+        assert_eq!(format_distance_in(Language::German, 12_340.0), "12,3 km");
+        assert_eq!(format_distance_in(Language::English, 384_400.0), "384 km");
+    }
+}
@@ -2,20 +2,41 @@
 //!
 //! Shows FPS, camera position, altitude, and loaded node count.
 
+mod annotations;
 mod camera;
 mod clouds;
+mod console;
+mod contour_labels;
+mod data_update;
+pub mod deep_link;
+mod elevation_profile;
+mod ground_wait;
+mod i18n;
 mod inspector;
 mod location;
+mod node_inspector;
 mod physics;
+mod place_labels;
+pub mod presets;
 mod profiler;
+mod recovery;
 mod rendering;
+mod satellites;
+mod search_pins;
+pub mod settings;
 mod shadow_diag;
+mod sky;
 mod streaming;
 mod vehicle;
+mod viewshed;
+mod weather;
 
 use std::sync::Arc;
 
-use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
+use bevy::{
+    diagnostic::{DEFAULT_MAX_HISTORY_LENGTH, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
 use bevy_egui::{
     EguiContexts, EguiPlugin, EguiPrimaryContextPass, EguiTextureHandle, EguiUserTextures, egui,
 };
@@ -23,8 +44,11 @@ use egui_dock::{DockArea, DockState, Style};
 use glam::DVec3;
 use leafwing_input_manager::prelude::*;
 
-use veldera_game_input::CameraAction;
+use veldera_game_input::{CameraAction, PointerPicking};
 use veldera_game_vehicle::VehicleTabOpen;
+use veldera_physics::DebugPalette;
+
+use crate::i18n::tr;
 
 /// Resource controlling whether the debug UI is visible.
 #[derive(Resource)]
@@ -45,19 +69,42 @@ pub struct HasInitialisedFonts;
 impl Plugin for DebugUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(EguiPlugin::default())
-            .add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .add_plugins(FrameTimeDiagnosticsPlugin {
+                // Long enough for the Frames profiler's p99 to be meaningful;
+                // the FPS readout keeps Bevy's default smoothing.
+                max_history_length: profiler::FRAME_TIME_HISTORY,
+                smoothing_factor: 2.0 / (DEFAULT_MAX_HISTORY_LENGTH as f64 + 1.0),
+            })
+            // Before the `init_resource` calls below: it seeds `UiVisible`
+            // and `DebugUiState` from the saved settings.
+            .add_plugins(settings::SettingsPlugin)
             .add_plugins(shadow_diag::ShadowDiagPlugin)
+            .add_plugins(search_pins::SearchPinsPlugin)
+            .add_plugins(place_labels::PlaceLabelsPlugin)
+            .add_plugins(satellites::SatelliteOverlayPlugin)
+            .add_plugins(contour_labels::ContourLabelsPlugin)
+            .add_plugins(annotations::AnnotationsPlugin)
+            .add_plugins(recovery::RecoveryPlugin)
+            .add_plugins(data_update::DataUpdateNoticePlugin)
+            .add_plugins(ground_wait::GroundWaitNoticePlugin)
+            .add_plugins(node_inspector::NodeInspectorPlugin)
+            .add_plugins(elevation_profile::ElevationProfilePlugin)
+            .add_plugins(viewshed::ViewshedPlugin)
+            .add_plugins(console::ConsoleUiPlugin)
             .init_resource::<location::CoordinateInputState>()
             .init_resource::<DebugUiState>()
             .init_resource::<vehicle::VehicleHistory>()
             .init_resource::<streaming::DiagnosticsViewState>()
+            .init_resource::<streaming::AreaPrefetchView>()
             .init_resource::<UiVisible>()
             .add_systems(
                 Update,
                 (
                     toggle_ui_visible,
+                    rendering::cycle_terrain_debug_view,
                     inspector::sync_inspect_cursor,
                     register_cloud_climate_textures,
+                    sync_pointer_picking,
                 ),
             )
             .add_systems(
@@ -65,11 +112,28 @@ impl Plugin for DebugUiPlugin {
                 (
                     setup_fonts.run_if(not(resource_exists::<HasInitialisedFonts>)),
                     debug_ui_system.run_if(|visible: Res<UiVisible>| visible.0),
+                    rendering::draw_terrain_debug_badge,
+                    camera::draw_tonemap_comparison,
                 ),
             );
     }
 }
 
+/// Hand left clicks on the world to the picking tools (see
+/// [`PointerPicking`]) while the node inspector, the elevation profile or the
+/// viewshed is picking.
+fn sync_pointer_picking(
+    node_inspector: Res<node_inspector::NodeInspectorState>,
+    profile: Res<elevation_profile::ElevationProfile>,
+    viewshed: Res<viewshed::Viewshed>,
+    mut pointer_picking: ResMut<PointerPicking>,
+) {
+    let picking = node_inspector.picking || profile.picking || viewshed.picking;
+    if pointer_picking.0 != picking {
+        pointer_picking.0 = picking;
+    }
+}
+
 /// Register the cloud crate's climate-preview images with egui so the Climate
 /// debug tab can display them. `veldera_sky` exposes the handles via
 /// [`CloudClimateAssets`](veldera_sky::clouds::CloudClimateAssets) and
@@ -97,7 +161,7 @@ fn register_cloud_climate_textures(
 }
 
 /// Which tab in the debug UI dock.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 enum DebugTab {
     LocationAndTime,
     Camera,
@@ -107,19 +171,37 @@ enum DebugTab {
     Physics,
     Rendering,
     Profiler,
+    Annotations,
+    Settings,
 }
 
 impl DebugTab {
+    /// Every tab, in their default order.
+    const ALL: [DebugTab; 10] = [
+        DebugTab::LocationAndTime,
+        DebugTab::Camera,
+        DebugTab::Vehicles,
+        DebugTab::Atmosphere,
+        DebugTab::Streaming,
+        DebugTab::Physics,
+        DebugTab::Rendering,
+        DebugTab::Profiler,
+        DebugTab::Annotations,
+        DebugTab::Settings,
+    ];
+
     fn label(self) -> &'static str {
         match self {
-            DebugTab::LocationAndTime => "Location & time",
-            DebugTab::Camera => "Camera",
-            DebugTab::Vehicles => "Vehicles",
-            DebugTab::Atmosphere => "Atmosphere",
-            DebugTab::Streaming => "Streaming",
-            DebugTab::Physics => "Physics",
-            DebugTab::Rendering => "Rendering",
-            DebugTab::Profiler => "Profiler",
+            DebugTab::LocationAndTime => tr("tab.location_and_time"),
+            DebugTab::Camera => tr("tab.camera"),
+            DebugTab::Vehicles => tr("tab.vehicles"),
+            DebugTab::Atmosphere => tr("tab.atmosphere"),
+            DebugTab::Streaming => tr("tab.streaming"),
+            DebugTab::Physics => tr("tab.physics"),
+            DebugTab::Rendering => tr("tab.rendering"),
+            DebugTab::Profiler => tr("tab.profiler"),
+            DebugTab::Annotations => tr("tab.annotations"),
+            DebugTab::Settings => tr("tab.settings"),
         }
     }
 }
@@ -140,16 +222,7 @@ pub struct DebugUiState {
 impl Default for DebugUiState {
     fn default() -> Self {
         Self {
-            dock_state: DockState::new(vec![
-                DebugTab::LocationAndTime,
-                DebugTab::Camera,
-                DebugTab::Vehicles,
-                DebugTab::Atmosphere,
-                DebugTab::Streaming,
-                DebugTab::Physics,
-                DebugTab::Rendering,
-                DebugTab::Profiler,
-            ]),
+            dock_state: DockState::new(DebugTab::ALL.to_vec()),
             atmosphere_subtab: clouds::AtmosphereSubTab::default(),
             profiler_subtab: profiler::ProfilerSubTab::default(),
         }
@@ -209,7 +282,9 @@ fn debug_ui_system(
     mut vehicle_params: vehicle::VehicleParams,
     mut inspector_params: inspector::InspectorParams,
     mut shadow_diag_params: shadow_diag::ShadowDiagParams,
-    profiler_params: profiler::ProfilerParams,
+    // Bevy caps a system at 16 parameters; the Settings tab shares a slot.
+    (profiler_params, mut settings_params): (profiler::ProfilerParams, settings::SettingsParams),
+    mut annotation_params: annotations::AnnotationParams,
     climate_assets: Res<veldera_sky::clouds::CloudClimateAssets>,
 ) -> Result {
     // Resolve egui image ids BEFORE taking `ctx_mut` (same borrow on
@@ -296,9 +371,21 @@ fn debug_ui_system(
         DebugTab::Profiler => {
             profiler::render_profiler_tab(ui, &profiler_params, profiler_subtab);
         }
+        DebugTab::Annotations => {
+            annotations::render_annotations_tab(ui, &mut annotation_params);
+        }
+        DebugTab::Settings => {
+            settings::render_settings_tab(
+                ui,
+                &mut settings_params,
+                &camera_params,
+                &streaming_params,
+            );
+        }
     };
 
-    egui::Window::new("Debug")
+    egui::Window::new(tr("window.debug"))
+        .id(egui::Id::new("debug_window"))
         .default_pos([10.0, 10.0])
         .default_size([520.0, 480.0])
         .show(ctx, |ui| {
@@ -330,6 +417,12 @@ impl<F: FnMut(&mut egui::Ui, &mut DebugTab)> egui_dock::TabViewer for ClosureVie
         tab.label().into()
     }
 
+    /// Keyed by tab rather than by (translated) title, so per-tab widget
+    /// state survives a language switch.
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        egui::Id::new(*tab)
+    }
+
     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
         (self.render)(ui, tab);
     }
@@ -339,6 +432,37 @@ impl<F: FnMut(&mut egui::Ui, &mut DebugTab)> egui_dock::TabViewer for ClosureVie
 // UI helpers
 // ============================================================================
 
+/// A Bevy colour as an opaque egui colour, for painting gizmo palettes in
+/// the UI.
+pub(crate) fn egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, _] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// A byte count in KiB, MiB or GiB, whichever reads best.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * MIB {
+        format!("{:.2} GiB", bytes / (1024.0 * MIB))
+    } else if bytes >= MIB {
+        format!("{:.2} MiB", bytes / MIB)
+    } else {
+        format!("{:.1} KiB", bytes / 1024.0)
+    }
+}
+
+/// Colour for the `index`th line of a diagnostics plot: `standard` under the
+/// standard palette, the palette's categorical set otherwise.
+pub(crate) fn plot_color(
+    palette: DebugPalette,
+    index: usize,
+    standard: egui::Color32,
+) -> egui::Color32 {
+    let [r, g, b, a] = standard.to_srgba_unmultiplied();
+    egui_color(palette.categorical(index, Color::srgba_u8(r, g, b, a)))
+}
+
 /// Render sliders for a Vec3 with configurable range (but uncapped input).
 ///
 /// Returns true if any component was changed.
@@ -2,6 +2,8 @@
 //!
 //! Provides geocoding search, coordinate input, altitude control, and time-of-day settings.
 
+use std::hash::{BuildHasher, RandomState};
+
 use avian3d::prelude::LinearVelocity;
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
@@ -11,16 +13,35 @@ use bevy::{
 use bevy_egui::egui;
 use glam::DVec3;
 
-use veldera_game_camera::{AltitudeRequest, FlightCamera, HeadingRequest, TranslateRequest};
+use veldera_game_camera::{
+    AltitudeRequest, FlightCamera, HeadingRequest, TerrainFollow, TranslateRequest,
+};
+use veldera_game_camera_state::CameraModeState;
 use veldera_game_player::LogicalPlayer;
 
 use veldera_async::TaskSpawner;
-use veldera_game_teleport::{TeleportAnimation, TeleportState};
-use veldera_geo::coords::ecef_to_lat_lon;
+use veldera_engine::resolution::DynamicResolution;
+use veldera_game_teleport::{
+    RouteEndpoint, RoutePlanner, TeleportAnimation, TeleportHistory, TeleportState,
+};
+use veldera_game_tracks::{LoadedTracks, TrackPlayback};
+use veldera_geo::coords::{RadialFrame, ecef_to_lat_lon};
 use veldera_places::{GEOCODING_THROTTLE_SECS, GeocodingState, HttpClient};
 use veldera_sky::{
     moon::compute_moon_state,
-    time_of_day::{SECONDS_PER_HOUR, TimeMode, TimeOfDayState, local_to_utc, seconds_to_hms},
+    time_of_day::{
+        SECONDS_PER_HOUR, TimeMode, TimeOfDayState, day_length_hours, local_to_utc, seconds_to_hms,
+        solar_declination_deg,
+    },
+};
+use veldera_terrain::pick::TerrainPicker;
+
+use super::{
+    deep_link::DeepLink,
+    i18n::{fmt_lat_lon, fmt_number, parse_number, tr, trf},
+    place_labels::PlaceLabels,
+    presets,
+    satellites::SatelliteOverlay,
 };
 
 /// State for the lat/long text input fields.
@@ -32,6 +53,8 @@ pub(super) struct CoordinateInputState {
     is_editing: bool,
     /// Selected distance (metres) for the precise-translation buttons.
     translate_distance_m: f64,
+    /// Last link produced by the Share button, shown for manual copying.
+    share_link: Option<String>,
 }
 
 impl Default for CoordinateInputState {
@@ -41,6 +64,7 @@ impl Default for CoordinateInputState {
             lon_text: String::new(),
             is_editing: false,
             translate_distance_m: 1000.0,
+            share_link: None,
         }
     }
 }
@@ -51,13 +75,15 @@ pub(super) struct LocationParams<'w, 's> {
     pub coord_state: ResMut<'w, CoordinateInputState>,
     pub geocoding_state: ResMut<'w, GeocodingState>,
     pub teleport_state: ResMut<'w, TeleportState>,
-    pub teleport_animation: Res<'w, TeleportAnimation>,
+    pub teleport_animation: ResMut<'w, TeleportAnimation>,
+    pub teleport_history: ResMut<'w, TeleportHistory>,
     pub time_of_day: ResMut<'w, TimeOfDayState>,
     pub http_client: Res<'w, HttpClient>,
     pub spawner: TaskSpawner<'w, 's>,
     pub altitude_request: ResMut<'w, AltitudeRequest>,
     pub heading_request: ResMut<'w, HeadingRequest>,
     pub translate_request: ResMut<'w, TranslateRequest>,
+    pub terrain_follow: ResMut<'w, TerrainFollow>,
     /// Read-only — coexists with the camera tab's read-only flight-camera
     /// query in the same system. Heading changes flow back through
     /// [`HeadingRequest`].
@@ -67,6 +93,301 @@ pub(super) struct LocationParams<'w, 's> {
     /// [`FlightCamera`] in the other modes.
     pub player_velocity_query: Query<'w, 's, &'static LinearVelocity, With<LogicalPlayer>>,
     pub diagnostics: Res<'w, DiagnosticsStore>,
+    /// Render scale, shown next to the FPS while below native resolution.
+    pub render_scale_query: Query<'w, 's, &'static DynamicResolution>,
+    pub place_labels: ResMut<'w, PlaceLabels>,
+    pub satellites: ResMut<'w, SatelliteOverlay>,
+    pub travel: TravelParams<'w, 's>,
+    pub terrain_picker: Res<'w, TerrainPicker>,
+    pub camera_mode: Res<'w, CameraModeState>,
+}
+
+/// Route planner and track playback state.
+#[derive(SystemParam)]
+pub(super) struct TravelParams<'w, 's> {
+    pub route: ResMut<'w, RoutePlanner>,
+    pub tracks: ResMut<'w, LoadedTracks>,
+    pub track_playback: ResMut<'w, TrackPlayback>,
+    pub track_import: Local<'s, TrackImportInput>,
+}
+
+/// Text input and last error for the track importer.
+#[derive(Default)]
+pub(super) struct TrackImportInput {
+    path_text: String,
+    error: Option<String>,
+}
+
+/// Render the Share button: captures the current view as a [`DeepLink`],
+/// copies it to the clipboard, and shows it for manual copying.
+fn render_share_link(ui: &mut egui::Ui, location: &mut LocationParams, position: DVec3) {
+    ui.horizontal(|ui| {
+        if ui
+            .button(tr("location.share"))
+            .on_hover_text(tr("location.share.hover"))
+            .clicked()
+        {
+            let (lat, lon) = ecef_to_lat_lon(position);
+            let (heading, pitch) =
+                location
+                    .flight_camera_query
+                    .single()
+                    .map_or((None, None), |flight_cam| {
+                        let frame = RadialFrame::from_ecef_position(position);
+                        let direction = flight_cam.direction;
+                        let heading = direction
+                            .dot(frame.east)
+                            .atan2(direction.dot(frame.north))
+                            .to_degrees()
+                            .rem_euclid(360.0);
+                        let pitch = direction.dot(frame.up).clamp(-1.0, 1.0).asin().to_degrees();
+                        (Some(f64::from(heading)), Some(f64::from(pitch)))
+                    });
+            let datetime = (location.time_of_day.mode == TimeMode::Override).then(|| {
+                (
+                    location.time_of_day.current_date(),
+                    location.time_of_day.current_utc_seconds(),
+                )
+            });
+            let link = DeepLink {
+                lat: Some(lat),
+                lon: Some(lon),
+                altitude: Some(position.length() - veldera_constants::EARTH_RADIUS_M_F64),
+                heading,
+                pitch,
+                camera_mode: Some(location.camera_mode.current()),
+                datetime,
+            }
+            .share_url();
+            ui.ctx().copy_text(link.clone());
+            location.coord_state.share_link = Some(link);
+        }
+        if let Some(link) = &mut location.coord_state.share_link {
+            ui.add(egui::TextEdit::singleline(link).desired_width(f32::INFINITY));
+        }
+    });
+}
+
+/// Render the teleport history: Back and Forward, and the places visited,
+/// newest first, each a button flying back there.
+fn render_history(ui: &mut egui::Ui, history: &mut TeleportHistory, teleport: &mut TeleportState) {
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                history.can_go_back(),
+                egui::Button::new(tr("teleport.back")),
+            )
+            .on_hover_text(tr("action.history_back"))
+            .clicked()
+        {
+            history.back(teleport);
+        }
+        if ui
+            .add_enabled(
+                history.can_go_forward(),
+                egui::Button::new(tr("teleport.forward")),
+            )
+            .on_hover_text(tr("action.history_forward"))
+            .clicked()
+        {
+            history.forward(teleport);
+        }
+    });
+
+    ui.collapsing(tr("teleport.history"), |ui| {
+        if history.entries().is_empty() {
+            ui.label(tr("teleport.history.empty"));
+            return;
+        }
+        let current = history.current();
+        let mut go_to = None;
+        egui::ScrollArea::vertical()
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for (i, position) in history.entries().iter().enumerate().rev() {
+                    let (lat, lon) = ecef_to_lat_lon(*position);
+                    let altitude = position.length() - veldera_constants::EARTH_RADIUS_M_F64;
+                    let text = trf(
+                        "teleport.history.entry",
+                        &[
+                            ("coords", &fmt_lat_lon(lat, lon, 4)),
+                            ("altitude", &fmt_number(altitude, 0)),
+                        ],
+                    );
+                    if ui.selectable_label(current == Some(i), text).clicked() {
+                        go_to = Some(i);
+                    }
+                }
+            });
+        if let Some(i) = go_to
+            && current != Some(i)
+        {
+            history.go_to(i, teleport);
+        }
+    });
+}
+
+/// Render the route planner: endpoints (set from search results with the A/B
+/// buttons, or the current location), the great-circle distance, and flight
+/// controls.
+fn render_route_planner(ui: &mut egui::Ui, route: &mut RoutePlanner, lat_deg: f64, lon_deg: f64) {
+    ui.collapsing(tr("route.title"), |ui| {
+        let here = || RouteEndpoint {
+            name: tr("route.current_location").to_string(),
+            lat: lat_deg,
+            lon: lon_deg,
+        };
+        ui.horizontal(|ui| {
+            ui.label(tr("route.from"));
+            ui.label(route.origin.as_ref().map_or("-", |e| e.name.as_str()));
+            if ui.small_button(tr("route.here")).clicked() {
+                route.origin = Some(here());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("route.to"));
+            ui.label(route.destination.as_ref().map_or("-", |e| e.name.as_str()));
+            if ui.small_button(tr("route.here")).clicked() {
+                route.destination = Some(here());
+            }
+        });
+
+        let Some(path) = route.route() else {
+            ui.label(tr("route.pick_ends"));
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label(trf(
+                "route.distance",
+                &[("km", &fmt_number(path.length_m() / 1000.0, 1))],
+            ));
+            if ui.small_button(tr("route.reverse")).clicked() {
+                route.reverse();
+            }
+            if ui.small_button(tr("common.clear")).clicked() {
+                route.stop_flight();
+                route.origin = None;
+                route.destination = None;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("route.altitude"));
+            ui.add(
+                egui::Slider::new(&mut route.cruise_altitude_m, 100.0..=100_000.0)
+                    .logarithmic(true)
+                    .suffix(" m"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("common.speed"));
+            ui.add(
+                egui::Slider::new(&mut route.speed_mps, 10.0..=50_000.0)
+                    .logarithmic(true)
+                    .suffix(" m/s"),
+            );
+        });
+
+        if let Some(progress) = route.progress() {
+            ui.horizontal(|ui| {
+                if ui.button(tr("common.stop")).clicked() {
+                    route.stop_flight();
+                }
+                ui.add(egui::ProgressBar::new(progress as f32).show_percentage());
+            });
+        } else if ui.button(tr("route.fly")).clicked() {
+            route.start_flight();
+        }
+    });
+}
+
+/// Render the track importer: a path field for GPX/KML files, then each
+/// loaded file's tracks with follow buttons.
+fn render_tracks(
+    ui: &mut egui::Ui,
+    input: &mut TrackImportInput,
+    tracks: &mut LoadedTracks,
+    playback: &mut TrackPlayback,
+) {
+    ui.collapsing(tr("tracks.title"), |ui| {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut input.path_text)
+                    .hint_text(tr("tracks.path_hint"))
+                    .desired_width(220.0),
+            );
+            if ui.button(tr("tracks.load")).clicked() {
+                let path = std::path::Path::new(input.path_text.trim());
+                input.error = tracks.load_path(path).err().map(|e| e.to_string());
+            }
+        });
+        if let Some(error) = &input.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let current = playback.current();
+        let mut unload = None;
+        for (file_index, file) in tracks.files.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut file.visible, &file.name);
+                ui.label(trf("tracks.waypoints", &[("count", &file.waypoints.len())]));
+                if ui.small_button(tr("tracks.unload")).clicked() {
+                    unload = Some(file_index);
+                }
+            });
+            ui.indent(("track_file", file_index), |ui| {
+                for (track_index, track) in file.tracks.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{} ({} km)",
+                            track.name,
+                            fmt_number(track.length_m() / 1000.0, 1)
+                        ));
+                        match current {
+                            Some((f, t, distance)) if (f, t) == (file_index, track_index) => {
+                                if ui.small_button(tr("common.stop")).clicked() {
+                                    playback.stop();
+                                }
+                                let length = track.length_m().max(1.0);
+                                ui.add(
+                                    egui::ProgressBar::new((distance / length) as f32)
+                                        .desired_width(80.0),
+                                );
+                            }
+                            _ => {
+                                if ui
+                                    .add_enabled(
+                                        !track.is_empty(),
+                                        egui::Button::new(tr("tracks.follow")),
+                                    )
+                                    .clicked()
+                                {
+                                    playback.play(file_index, track_index);
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+        }
+        if let Some(index) = unload {
+            // Playback refers to files by index, so unloading one before it
+            // would retarget it.
+            if current.is_some_and(|(f, _, _)| f >= index) {
+                playback.stop();
+            }
+            tracks.files.remove(index);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr("tracks.playback_speed"));
+            ui.add(
+                egui::Slider::new(&mut playback.speed_mps, 1.0..=2_000.0)
+                    .logarithmic(true)
+                    .suffix(" m/s"),
+            );
+        });
+    });
 }
 
 /// Render the location & time tab content and execute any resulting actions.
@@ -81,9 +402,22 @@ pub(super) fn render_location_tab(
         .get(&FrameTimeDiagnosticsPlugin::FPS)
         .and_then(bevy::diagnostic::Diagnostic::smoothed)
         .unwrap_or(0.0);
-    ui.label(format!(
-        "FPS: {fps:.0}  ·  Position: ({:.0}, {:.0}, {:.0})",
-        position.x, position.y, position.z
+    let render_scale = match location.render_scale_query.single() {
+        Ok(dynamic) if dynamic.scale < 1.0 => trf(
+            "location.render_scale",
+            &[("percent", &fmt_number(f64::from(dynamic.scale) * 100.0, 0))],
+        ),
+        _ => String::new(),
+    };
+    ui.label(trf(
+        "location.fps",
+        &[
+            ("fps", &fmt_number(fps, 0)),
+            ("scale", &render_scale),
+            ("x", &fmt_number(position.x, 0)),
+            ("y", &fmt_number(position.y, 0)),
+            ("z", &fmt_number(position.z, 0)),
+        ],
     ));
 
     // Current movement speed. In first-person it's the physics body's
@@ -96,10 +430,30 @@ pub(super) fn render_location_tab(
     } else {
         0.0
     };
-    ui.label(format!(
-        "Speed: {speed_mps:.1} m/s ({:.0} km/h)",
-        speed_mps * 3.6
+    ui.label(trf(
+        "location.speed",
+        &[
+            ("mps", &fmt_number(f64::from(speed_mps), 1)),
+            ("kmh", &fmt_number(f64::from(speed_mps) * 3.6, 0)),
+        ],
     ));
+    // Terrain under the mouse, picked against the rendered meshes.
+    match location.terrain_picker.hit() {
+        Some(hit) => {
+            ui.label(trf(
+                "location.cursor",
+                &[
+                    ("coords", &fmt_lat_lon(hit.lat_deg, hit.lon_deg, 5)),
+                    ("altitude", &fmt_number(hit.altitude, 0)),
+                    ("distance", &fmt_number(hit.distance, 0)),
+                ],
+            ))
+            .on_hover_text(trf("location.cursor_node", &[("path", &hit.path)]));
+        }
+        None => {
+            ui.weak(tr("location.cursor_none"));
+        }
+    }
     ui.separator();
 
     let (lat_deg, lon_deg) = ecef_to_lat_lon(position);
@@ -107,8 +461,8 @@ pub(super) fn render_location_tab(
 
     // Update text fields when not editing and not teleporting.
     if !location.coord_state.is_editing && !location.teleport_state.is_pending() {
-        location.coord_state.lat_text = format!("{lat_deg:.6}");
-        location.coord_state.lon_text = format!("{lon_deg:.6}");
+        location.coord_state.lat_text = fmt_number(lat_deg, 6);
+        location.coord_state.lon_text = fmt_number(lon_deg, 6);
     }
 
     let mut start_geocoding = false;
@@ -117,37 +471,46 @@ pub(super) fn render_location_tab(
 
     // Geocoding search.
     ui.horizontal(|ui| {
-        ui.label("Search:");
+        ui.label(tr("search.label"));
         let response = ui.add(
             egui::TextEdit::singleline(&mut location.geocoding_state.search_text)
                 .desired_width(150.0)
-                .hint_text("City, address..."),
+                .hint_text(tr("search.hint")),
         );
         if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
             start_geocoding = true;
         }
-        if ui.button("Go").clicked() {
+        if ui.button(tr("search.go")).clicked() {
             start_geocoding = true;
         }
         if ui
-            .button("Here?")
-            .on_hover_text("Look up current location")
+            .button(tr("search.here"))
+            .on_hover_text(tr("search.here.hover"))
             .clicked()
         {
             start_reverse_geocoding = true;
         }
+        if !location.geocoding_state.results.is_empty()
+            && ui
+                .button(tr("common.clear"))
+                .on_hover_text(tr("search.clear.hover"))
+                .clicked()
+        {
+            location.geocoding_state.search_text.clear();
+            location.geocoding_state.results.clear();
+        }
     });
 
     // Show loading/throttle status.
     let current_time = time.elapsed_secs_f64();
     if location.geocoding_state.is_loading {
-        ui.label("Searching...");
+        ui.label(tr("search.searching"));
     } else if let Some(last_time) = location.geocoding_state.last_request_time {
         let elapsed = current_time - last_time;
         if elapsed < GEOCODING_THROTTLE_SECS {
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
             let remaining = (GEOCODING_THROTTLE_SECS - elapsed).ceil() as u64;
-            ui.label(format!("Wait {remaining}s before next search"));
+            ui.label(trf("search.wait", &[("seconds", &remaining)]));
         }
     }
 
@@ -163,9 +526,30 @@ pub(super) fn render_location_tab(
             .max_height(150.0)
             .show(ui, |ui| {
                 for result in &location.geocoding_state.results {
-                    if ui.link(&result.display_name).clicked() {
-                        new_coords = Some((result.lat, result.lon));
-                    }
+                    ui.horizontal(|ui| {
+                        let endpoint = || RouteEndpoint {
+                            name: result.display_name.clone(),
+                            lat: result.lat,
+                            lon: result.lon,
+                        };
+                        if ui
+                            .small_button("A")
+                            .on_hover_text(tr("search.route_from"))
+                            .clicked()
+                        {
+                            location.travel.route.origin = Some(endpoint());
+                        }
+                        if ui
+                            .small_button("B")
+                            .on_hover_text(tr("search.route_to"))
+                            .clicked()
+                        {
+                            location.travel.route.destination = Some(endpoint());
+                        }
+                        if ui.link(&result.display_name).clicked() {
+                            new_coords = Some((result.lat, result.lon));
+                        }
+                    });
                 }
             });
     }
@@ -174,38 +558,109 @@ pub(super) fn render_location_tab(
     ui.separator();
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 2.0;
-        ui.label("Search by");
+        ui.label(tr("search.attribution"));
         ui.hyperlink_to("Nominatim", "https://nominatim.openstreetmap.org/");
         ui.label("\u{00a9} OpenStreetMap");
     });
 
+    render_route_planner(ui, &mut location.travel.route, lat_deg, lon_deg);
+    render_tracks(
+        ui,
+        &mut location.travel.track_import,
+        &mut location.travel.tracks,
+        &mut location.travel.track_playback,
+    );
+
+    // Offline place-name overlay.
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut location.place_labels.enabled, tr("labels.toggle"))
+            .on_hover_text(tr("labels.hover"));
+        ui.add_enabled(
+            location.place_labels.enabled,
+            egui::Slider::new(&mut location.place_labels.density, 0.25..=4.0)
+                .logarithmic(true)
+                .text(tr("labels.density")),
+        );
+    });
+
+    // Satellite overlay, from bundled or fetched element sets.
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut location.satellites.enabled, tr("satellites.toggle"))
+            .on_hover_text(tr("satellites.hover"));
+        let count = location.satellites.count();
+        if location.satellites.is_fetching() {
+            ui.spinner();
+            ui.label(tr("satellites.fetching"));
+        } else {
+            let source = if location.satellites.is_fetched() {
+                "satellites.fetched"
+            } else {
+                "satellites.bundled"
+            };
+            ui.label(trf(source, &[("count", &count)]));
+            if ui
+                .button(tr("satellites.fetch"))
+                .on_hover_text(tr("satellites.fetch.hover"))
+                .clicked()
+            {
+                location
+                    .satellites
+                    .request_fetch(&location.http_client, &location.spawner);
+            }
+        }
+    });
+    if let Some(error) = location.satellites.error() {
+        ui.colored_label(
+            egui::Color32::RED,
+            trf("satellites.failed", &[("error", &error)]),
+        );
+    }
+
+    render_share_link(ui, location, position);
+
     ui.separator();
 
     // Show teleport status.
     if location.teleport_animation.is_waiting_for_physics() {
         ui.horizontal(|ui| {
             ui.spinner();
-            ui.label("Waiting for terrain to load...");
+            ui.label(tr("teleport.waiting_terrain"));
         });
         ui.add(egui::ProgressBar::new(1.0).show_percentage());
     } else if let Some(progress) = location.teleport_animation.progress() {
         ui.horizontal(|ui| {
             ui.spinner();
-            ui.label("Flying...");
+            ui.label(tr("teleport.flying"));
+            if ui
+                .button(tr("teleport.skip"))
+                .on_hover_text(tr("teleport.skip.hover"))
+                .clicked()
+            {
+                location.teleport_animation.skip();
+            }
         });
         ui.add(egui::ProgressBar::new(progress).show_percentage());
     } else if location.teleport_state.is_pending() {
         ui.horizontal(|ui| {
             ui.spinner();
-            ui.label("Fetching elevation...");
+            ui.label(tr("teleport.fetching"));
         });
     } else if let Some(ref error) = location.teleport_state.error {
-        ui.colored_label(egui::Color32::RED, format!("Teleport failed: {error}"));
+        ui.colored_label(
+            egui::Color32::RED,
+            trf("teleport.failed", &[("error", error)]),
+        );
     }
 
+    render_history(
+        ui,
+        &mut location.teleport_history,
+        &mut location.teleport_state,
+    );
+
     // Lat/lon input fields on the same row.
     ui.horizontal(|ui| {
-        ui.label("Lat:");
+        ui.label(tr("coords.lat"));
         let lat_response = ui.add(
             egui::TextEdit::singleline(&mut location.coord_state.lat_text).desired_width(80.0),
         );
@@ -213,16 +668,16 @@ pub(super) fn render_location_tab(
             location.coord_state.is_editing = true;
         }
         if lat_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-            if let (Ok(lat), Ok(lon)) = (
-                location.coord_state.lat_text.parse::<f64>(),
-                location.coord_state.lon_text.parse::<f64>(),
+            if let (Some(lat), Some(lon)) = (
+                parse_number(&location.coord_state.lat_text),
+                parse_number(&location.coord_state.lon_text),
             ) {
                 new_coords = Some((lat.clamp(-90.0, 90.0), lon));
             }
             location.coord_state.is_editing = false;
         }
 
-        ui.label("Lon:");
+        ui.label(tr("coords.lon"));
         let lon_response = ui.add(
             egui::TextEdit::singleline(&mut location.coord_state.lon_text).desired_width(80.0),
         );
@@ -230,9 +685,9 @@ pub(super) fn render_location_tab(
             location.coord_state.is_editing = true;
         }
         if lon_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-            if let (Ok(lat), Ok(lon)) = (
-                location.coord_state.lat_text.parse::<f64>(),
-                location.coord_state.lon_text.parse::<f64>(),
+            if let (Some(lat), Some(lon)) = (
+                parse_number(&location.coord_state.lat_text),
+                parse_number(&location.coord_state.lon_text),
             ) {
                 new_coords = Some((lat.clamp(-90.0, 90.0), lon));
             }
@@ -240,10 +695,34 @@ pub(super) fn render_location_tab(
         }
     });
 
+    // Curated presets and a random land point for exploration.
+    ui.horizontal(|ui| {
+        ui.label(tr("presets.label"));
+        egui::ComboBox::from_id_salt("location_preset")
+            .selected_text(tr("presets.choose"))
+            .show_ui(ui, |ui| {
+                for preset in presets::presets() {
+                    if ui.selectable_label(false, &preset.name).clicked() {
+                        new_coords = Some((preset.lat, preset.lon));
+                    }
+                }
+            });
+        if ui
+            .button(tr("presets.random"))
+            .on_hover_text(tr("presets.random.hover"))
+            .clicked()
+        {
+            let seed = RandomState::new().hash_one(time.elapsed_secs_f64().to_bits());
+            if let Some(point) = presets::random_land_point(presets::uniform_sequence(seed)) {
+                new_coords = Some(point);
+            }
+        }
+    });
+
     // Altitude slider (logarithmic scale from 1m to 10,000km).
     let mut slider_alt = altitude.clamp(1.0, 10_000_000.0);
     ui.horizontal(|ui| {
-        ui.label("Alt:");
+        ui.label(tr("coords.alt"));
         if ui
             .add(
                 egui::Slider::new(&mut slider_alt, 1.0..=10_000_000.0)
@@ -257,6 +736,27 @@ pub(super) fn render_location_tab(
         }
     });
 
+    // Height above the terrain beneath the camera, and the flycam's
+    // terrain-following floor.
+    ui.horizontal(|ui| {
+        match location.terrain_follow.height_above_ground(position) {
+            Some(agl) => ui.label(trf("location.agl", &[("metres", &fmt_number(agl, 0))])),
+            None => ui.weak(tr("location.agl_none")),
+        };
+        ui.checkbox(
+            &mut location.terrain_follow.enabled,
+            tr("location.terrain_follow"),
+        )
+        .on_hover_text(tr("location.terrain_follow.hover"));
+        let enabled = location.terrain_follow.enabled;
+        ui.add_enabled(
+            enabled,
+            egui::Slider::new(&mut location.terrain_follow.min_clearance_m, 2.0..=2_000.0)
+                .logarithmic(true)
+                .suffix(" m"),
+        );
+    });
+
     // Compass: shows the camera's yaw relative to local north (the
     // tangent direction toward the world +Z pole). Useful for aligning
     // with cardinal axes when reasoning about parallax / wind / shadow
@@ -284,15 +784,21 @@ pub(super) fn render_location_tab(
 
     // Time of day controls.
     ui.horizontal(|ui| {
-        ui.label("Time of day:");
+        ui.label(tr("time.title"));
         if ui
-            .selectable_label(location.time_of_day.mode == TimeMode::Realtime, "Realtime")
+            .selectable_label(
+                location.time_of_day.mode == TimeMode::Realtime,
+                tr("time.realtime"),
+            )
             .clicked()
         {
             location.time_of_day.sync_to_realtime();
         }
         if ui
-            .selectable_label(location.time_of_day.mode == TimeMode::Override, "Manual")
+            .selectable_label(
+                location.time_of_day.mode == TimeMode::Override,
+                tr("time.manual"),
+            )
             .clicked()
             && location.time_of_day.mode != TimeMode::Override
         {
@@ -308,11 +814,13 @@ pub(super) fn render_location_tab(
     // disagree on the date for ~half of any given day).
     let local_date = location.time_of_day.current_date_at_longitude(lon_deg);
     let (utc_h, utc_m, utc_s) = seconds_to_hms(location.time_of_day.current_utc_seconds());
-    ui.label(format!(
-        "Date: {}-{:02}-{:02}",
+    let date = format!(
+        "{}-{:02}-{:02}",
         local_date.year, local_date.month, local_date.day
-    ));
-    ui.label(format!("UTC: {utc_h:02}:{utc_m:02}:{utc_s:02}"));
+    );
+    ui.label(trf("time.date", &[("date", &date)]));
+    let utc = format!("{utc_h:02}:{utc_m:02}:{utc_s:02}");
+    ui.label(trf("time.utc", &[("time", &utc)]));
 
     // Display current local time with timezone offset.
     let local_hours = location.time_of_day.local_hours_at_longitude(lon_deg);
@@ -322,14 +830,14 @@ pub(super) fn render_location_tab(
 
     let is_override = location.time_of_day.mode == TimeMode::Override;
     ui.horizontal(|ui| {
-        ui.label(format!(
-            "Local: {hours:02}:{minutes:02}:{seconds:02} (UTC{offset_sign}{offset_hours:.1})"
-        ));
+        let local = format!("{hours:02}:{minutes:02}:{seconds:02}");
+        let offset = format!("{offset_sign}{}", fmt_number(offset_hours, 1));
+        ui.label(trf("time.local", &[("time", &local), ("offset", &offset)]));
         if is_override {
             let mut slider_hours = local_hours;
             ui.add(
                 egui::Slider::new(&mut slider_hours, 0.0..=24.0)
-                    .text("hours")
+                    .text(tr("time.hours"))
                     .fixed_decimals(2),
             );
             // Only update time if there was a significant change.
@@ -345,6 +853,21 @@ pub(super) fn render_location_tab(
         }
     });
 
+    // Day length at the camera's latitude on the local date, from the same
+    // declination that places the sun.
+    let day_length = day_length_hours(lat_deg, solar_declination_deg(local_date));
+    if day_length >= 24.0 {
+        ui.label(tr("time.polar_day"));
+    } else if day_length <= 0.0 {
+        ui.label(tr("time.polar_night"));
+    } else {
+        let (hours, minutes, _) = seconds_to_hms(day_length * SECONDS_PER_HOUR);
+        ui.label(trf(
+            "time.day_length",
+            &[("length", &format!("{hours} h {minutes:02} min"))],
+        ));
+    }
+
     // Time and date controls (only in override mode).
     if is_override {
         // Local-date picker. Setting a new date preserves the local
@@ -353,7 +876,7 @@ pub(super) fn render_location_tab(
         // may need to be the day before/after the picked local
         // date).
         ui.horizontal(|ui| {
-            ui.label("Date:");
+            ui.label(tr("time.date_picker"));
             if let Some(mut picked) = local_date.to_naive() {
                 let before = picked;
                 ui.add(egui_extras::DatePickerButton::new(&mut picked).id_salt("local_date"));
@@ -367,13 +890,16 @@ pub(super) fn render_location_tab(
                     location.time_of_day.set_override_utc(utc_date, utc_seconds);
                 }
             } else {
-                ui.label("(invalid date)");
+                ui.label(tr("time.invalid_date"));
             }
         });
 
         // Show sun declination for reference.
         let declination = location.time_of_day.sun_declination_deg();
-        ui.label(format!("Sun declination: {declination:.1}\u{00b0}"));
+        ui.label(trf(
+            "time.sun_declination",
+            &[("degrees", &fmt_number(declination, 1))],
+        ));
     }
 
     // Moon state — useful for verifying night-side lighting and phase logic.
@@ -381,25 +907,31 @@ pub(super) fn render_location_tab(
     let local_up = position.normalize().as_vec3();
     let moon_altitude_deg = moon.altitude_at(local_up).to_degrees();
     let visible = if moon_altitude_deg > 0.0 {
-        "up"
+        tr("moon.up")
     } else {
-        "down"
+        tr("moon.down")
     };
-    ui.label(format!(
-        "Moon: {phase} ({pct:.0}%), altitude {alt:.1}\u{00b0} ({visible})",
-        phase = moon.phase_name(),
-        pct = moon.illuminated_fraction * 100.0,
-        alt = moon_altitude_deg,
+    ui.label(trf(
+        "moon.summary",
+        &[
+            ("phase", &moon.phase_name()),
+            (
+                "percent",
+                &fmt_number(f64::from(moon.illuminated_fraction) * 100.0, 0),
+            ),
+            ("altitude", &fmt_number(f64::from(moon_altitude_deg), 1)),
+            ("visible", &visible),
+        ],
     ));
 
     // Time-speed controls — pause toggle + logarithmic slider from
     // 0.1× to 100 000×. Pause is a separate boolean so the slider
     // remembers the user's previous non-zero speed across un-pause.
     ui.horizontal(|ui| {
-        ui.label("Time speed:");
+        ui.label(tr("time.speed"));
         let current_speed = location.time_of_day.speed_multiplier;
         let is_paused = current_speed == 0.0;
-        if ui.selectable_label(is_paused, "Pause").clicked() {
+        if ui.selectable_label(is_paused, tr("time.pause")).clicked() {
             if is_paused {
                 let resume = location.time_of_day.last_unpaused_speed.max(0.1);
                 location.time_of_day.set_speed(resume);
@@ -521,7 +1053,12 @@ fn render_compass(
         let stroke_color = ui.visuals().widgets.noninteractive.fg_stroke.color;
         painter.circle_stroke(center, radius, egui::Stroke::new(1.0, stroke_color));
         // Cardinal labels around the rose.
-        for (label, deg) in [("N", 0.0_f32), ("E", 90.0), ("S", 180.0), ("W", 270.0)] {
+        for (label, deg) in [
+            (tr("dir.n"), 0.0_f32),
+            (tr("dir.e"), 90.0),
+            (tr("dir.s"), 180.0),
+            (tr("dir.w"), 270.0),
+        ] {
             let rad = deg.to_radians();
             // egui Y is screen-down, so subtract the cos term to put
             // north at the top of the rose.
@@ -544,7 +1081,13 @@ fn render_compass(
         painter.circle_filled(arrow_end, 2.5, egui::Color32::from_rgb(255, 80, 80));
 
         ui.vertical(|ui| {
-            ui.label(format!("Heading: {bearing_deg:5.1}\u{00b0} ({cardinal})"));
+            ui.label(trf(
+                "compass.heading",
+                &[
+                    ("degrees", &fmt_number(f64::from(bearing_deg), 1)),
+                    ("cardinal", &cardinal),
+                ],
+            ));
             let mut new_bearing = bearing_deg;
             if ui
                 .add(
@@ -557,17 +1100,10 @@ fn render_compass(
                 heading_request.request(new_bearing);
             }
             ui.horizontal(|ui| {
-                if ui.button("N").clicked() {
-                    heading_request.request(0.0);
-                }
-                if ui.button("E").clicked() {
-                    heading_request.request(90.0);
-                }
-                if ui.button("S").clicked() {
-                    heading_request.request(180.0);
-                }
-                if ui.button("W").clicked() {
-                    heading_request.request(270.0);
+                for (key, bearing) in CARDINAL_BUTTONS {
+                    if ui.button(tr(key)).clicked() {
+                        heading_request.request(bearing);
+                    }
                 }
             });
         });
@@ -584,9 +1120,9 @@ fn render_translation_controls(
     translate_request: &mut TranslateRequest,
 ) {
     ui.separator();
-    ui.label("Precise move:");
+    ui.label(tr("move.title"));
     ui.horizontal(|ui| {
-        ui.label("Distance:");
+        ui.label(tr("move.distance"));
         for (label, metres) in [
             ("100 m", 100.0),
             ("1 km", 1000.0),
@@ -606,29 +1142,30 @@ fn render_translation_controls(
     });
     ui.horizontal(|ui| {
         let d = *distance_m;
-        ui.label(format!("Move {:.0} m:", d));
-        if ui.button("N").clicked() {
-            translate_request.request(0.0, d);
-        }
-        if ui.button("E").clicked() {
-            translate_request.request(90.0, d);
-        }
-        if ui.button("S").clicked() {
-            translate_request.request(180.0, d);
-        }
-        if ui.button("W").clicked() {
-            translate_request.request(270.0, d);
+        ui.label(trf("move.by", &[("metres", &fmt_number(d, 0))]));
+        for (key, bearing) in CARDINAL_BUTTONS {
+            if ui.button(tr(key)).clicked() {
+                translate_request.request(f64::from(bearing), d);
+            }
         }
     });
 }
 
+/// Snap buttons for the compass and precise move: label key and bearing.
+const CARDINAL_BUTTONS: [(&str, f32); 4] = [
+    ("dir.n", 0.0),
+    ("dir.e", 90.0),
+    ("dir.s", 180.0),
+    ("dir.w", 270.0),
+];
+
 /// 16-point cardinal label for a bearing in degrees (clockwise from north).
 fn cardinal_for_bearing(deg: f32) -> &'static str {
-    const LABELS: [&str; 16] = [
-        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
-        "NW", "NNW",
+    const KEYS: [&str; 16] = [
+        "dir.n", "dir.nne", "dir.ne", "dir.ene", "dir.e", "dir.ese", "dir.se", "dir.sse", "dir.s",
+        "dir.ssw", "dir.sw", "dir.wsw", "dir.w", "dir.wnw", "dir.nw", "dir.nnw",
     ];
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let idx = (((deg / 22.5) + 0.5).floor() as i32).rem_euclid(16) as usize;
-    LABELS[idx]
+    tr(KEYS[idx])
 }
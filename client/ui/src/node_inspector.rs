@@ -0,0 +1,213 @@
+//! Terrain node inspector, shown in the Streaming tab.
+//!
+//! With picking on, clicking the terrain (cursor free) selects the octree node
+//! whose mesh was hit. The panel lists what the bulk metadata and the decoded
+//! data say about it, the world draws its OBB, and a button forces the node to
+//! be fetched and spawned again.
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+use bevy_egui::{egui, input::EguiWantsInput};
+use glam::DQuat;
+
+use rocktree_decode::OctreePath;
+use veldera_geo::{coords::ecef_to_lat_lon, floating_origin::FloatingOriginCamera};
+use veldera_terrain::{
+    lod::{LodState, NodeReloadRequest},
+    pick::TerrainPicker,
+    query::{NodeDetails, requested_texture_format_name},
+};
+
+use crate::{
+    format_bytes,
+    i18n::{fmt_lat_lon, fmt_number, tr, trf},
+};
+
+/// Plugin for the node inspector's picking and in-world highlight.
+pub(super) struct NodeInspectorPlugin;
+
+impl Plugin for NodeInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NodeInspectorState>()
+            .add_systems(Update, (select_node_on_click, draw_selected_node).chain());
+    }
+}
+
+/// Node inspector state.
+#[derive(Resource, Default)]
+pub(super) struct NodeInspectorState {
+    /// Whether left clicks on the terrain select nodes.
+    pub picking: bool,
+    /// The inspected node.
+    pub selected: Option<OctreePath>,
+}
+
+/// Resources for the node inspector panel.
+#[derive(SystemParam)]
+pub(super) struct NodeInspectorParams<'w> {
+    pub state: ResMut<'w, NodeInspectorState>,
+    pub lod_state: Res<'w, LodState>,
+    pub reload: ResMut<'w, NodeReloadRequest>,
+}
+
+/// Render the node inspector section.
+pub(super) fn render_node_inspector(ui: &mut egui::Ui, params: &mut NodeInspectorParams) {
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.strong(tr("inspector.title"));
+        ui.checkbox(&mut params.state.picking, tr("inspector.pick"))
+            .on_hover_text(tr("inspector.pick.hover"));
+        if params.state.selected.is_some() && ui.button(tr("common.clear")).clicked() {
+            params.state.selected = None;
+        }
+    });
+
+    let Some(path) = params.state.selected else {
+        ui.label(tr("inspector.none_selected"));
+        return;
+    };
+    let Some(details) = params.lod_state.node_details(path) else {
+        ui.label(trf("inspector.evicted", &[("path", &path)]));
+        return;
+    };
+
+    egui::Grid::new("node_inspector_grid")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| node_rows(ui, &details));
+
+    if ui
+        .add_enabled(details.loaded, egui::Button::new(tr("inspector.reload")))
+        .on_hover_text(tr("inspector.reload.hover"))
+        .clicked()
+    {
+        params.reload.path = Some(path);
+    }
+}
+
+fn node_rows(ui: &mut egui::Ui, details: &NodeDetails) {
+    let (lat, lon) = ecef_to_lat_lon(details.obb.center);
+    row(ui, tr("inspector.path"), details.path.to_string());
+    row(ui, tr("inspector.level"), details.depth.to_string());
+    let state = if details.loaded {
+        tr("inspector.loaded")
+    } else {
+        tr("inspector.not_loaded")
+    };
+    row(ui, tr("inspector.state"), state.to_string());
+    row(ui, tr("inspector.epoch"), details.epoch.to_string());
+    row(
+        ui,
+        tr("inspector.imagery_epoch"),
+        details
+            .imagery_epoch
+            .map_or_else(|| "—".to_string(), |epoch| epoch.to_string()),
+    );
+    row(
+        ui,
+        tr("inspector.texture_format"),
+        requested_texture_format_name(details.requested_texture_format),
+    );
+    row(
+        ui,
+        tr("inspector.meters_per_texel"),
+        fmt_number(f64::from(details.meters_per_texel), 3),
+    );
+    row(ui, tr("inspector.centre"), fmt_lat_lon(lat, lon, 5));
+    row(
+        ui,
+        tr("inspector.half_extents"),
+        trf(
+            "inspector.half_extents_value",
+            &[
+                ("x", &fmt_number(details.obb.extents.x, 0)),
+                ("y", &fmt_number(details.obb.extents.y, 0)),
+                ("z", &fmt_number(details.obb.extents.z, 0)),
+            ],
+        ),
+    );
+
+    for (index, (width, height, format)) in details.textures.iter().enumerate() {
+        row(
+            ui,
+            &trf("inspector.texture", &[("index", &index)]),
+            format!("{width}×{height} {format:?}"),
+        );
+    }
+    if let Some(stats) = details.stats {
+        row(ui, tr("inspector.meshes"), stats.mesh_count.to_string());
+        row(ui, tr("inspector.vertices"), stats.vertex_count.to_string());
+        row(
+            ui,
+            tr("inspector.triangles"),
+            stats.triangle_count.to_string(),
+        );
+        row(
+            ui,
+            tr("inspector.texture_data"),
+            format_bytes(stats.texture_bytes as u64),
+        );
+        row(
+            ui,
+            tr("inspector.geometry_data"),
+            format_bytes(stats.geometry_bytes as u64),
+        );
+    }
+}
+
+fn row(ui: &mut egui::Ui, label: &str, value: String) {
+    ui.label(label);
+    ui.monospace(value);
+    ui.end_row();
+}
+
+/// Select the node under the cursor on a left click, while picking is on
+/// and the cursor is free.
+fn select_node_on_click(
+    mouse: Res<ButtonInput<MouseButton>>,
+    egui_wants: Res<EguiWantsInput>,
+    cursor: Single<&CursorOptions>,
+    picker: Res<TerrainPicker>,
+    mut state: ResMut<NodeInspectorState>,
+) {
+    if !state.picking
+        || cursor.grab_mode != CursorGrabMode::None
+        || !mouse.just_pressed(MouseButton::Left)
+        || egui_wants.is_pointer_over_area()
+    {
+        return;
+    }
+    if let Some(hit) = picker.hit() {
+        state.selected = Some(hit.path);
+    }
+}
+
+/// Outline the selected node's OBB.
+fn draw_selected_node(
+    state: Res<NodeInspectorState>,
+    lod_state: Res<LodState>,
+    camera_query: Query<&FloatingOriginCamera>,
+    mut gizmos: Gizmos,
+) {
+    let Some(path) = state.selected else {
+        return;
+    };
+    let (Some(metadata), Ok(camera)) = (lod_state.node_metadata(path), camera_query.single())
+    else {
+        return;
+    };
+    let obb = &metadata.obb;
+    gizmos.primitive_3d(
+        &Cuboid {
+            half_size: obb.extents.as_vec3(),
+        },
+        Isometry3d::new(
+            (obb.center - camera.position).as_vec3(),
+            DQuat::from_mat3(&obb.orientation).as_quat(),
+        ),
+        Color::srgb(1.0, 0.85, 0.1),
+    );
+}
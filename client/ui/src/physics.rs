@@ -18,6 +18,8 @@ use veldera_terrain::{
     lod::{LodState, TileDumpRequest},
 };
 
+use crate::i18n::{fmt_number, tr, trf};
+
 /// Radius (m) for the nearby-collider diagnostics table.
 const NEARBY_RADIUS_M: f32 = 30.0;
 
@@ -48,22 +50,14 @@ pub(super) fn render_physics_tab(ui: &mut egui::Ui, params: &mut PhysicsParams)
     let fallbacks = params.lod_state.octant_axis_fallbacks();
 
     ui.horizontal(|ui| {
-        ui.label(format!(
-            "Colliders: {collider_count}   (tag-classified octant bits: {fallbacks})"
+        ui.label(trf(
+            "physics.colliders",
+            &[("count", &collider_count), ("fallbacks", &fallbacks)],
         ))
-        .on_hover_text(
-            "Mesh builds where an octant bit had no confident geometric \
-             axis and was classified from the decoded vertex tags instead. \
-             Geometry survives; only boundary triangles whose corners \
-             disagree on such a bit are dropped. Common on flat terrain.",
-        );
+        .on_hover_text(tr("physics.colliders.hover"));
         if ui
-            .button("Dump nearby tiles")
-            .on_hover_text(
-                "Capture the selected tiles within the wireframe radius to \
-                 dumps/tiles-<time>.json, for offline fusion experiments \
-                 with tools/fuse_lab. Native builds only.",
-            )
+            .button(tr("physics.dump_tiles"))
+            .on_hover_text(tr("physics.dump_tiles.hover"))
             .clicked()
         {
             params.dump_request.wanted = true;
@@ -93,20 +87,43 @@ pub(super) fn render_physics_tab(ui: &mut egui::Ui, params: &mut PhysicsParams)
         .collect();
     nearby.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
 
-    ui.label(format!("Within {NEARBY_RADIUS_M:.0} m:"));
+    ui.label(trf(
+        "physics.nearby",
+        &[("radius", &fmt_number(f64::from(NEARBY_RADIUS_M), 0))],
+    ));
     for (distance, depth, mask, target, has_collider) in nearby.iter().take(12) {
         let status = match target {
-            Some(t) if t == mask => "ok".to_string(),
-            Some(t) => format!("rebuild {mask:08b}->{t:08b}"),
-            None => "stale".to_string(),
+            Some(t) if t == mask => tr("physics.collider_ok").to_string(),
+            Some(t) => trf(
+                "physics.collider_rebuild",
+                &[
+                    ("from", &format!("{mask:08b}")),
+                    ("to", &format!("{t:08b}")),
+                ],
+            ),
+            None => tr("physics.collider_stale").to_string(),
         };
-        let kind = if *has_collider { "" } else { " (empty)" };
-        ui.monospace(format!(
-            "d{depth:>2}  {distance:5.1} m  mask {mask:08b}  {status}{kind}"
+        let kind = if *has_collider {
+            ""
+        } else {
+            tr("physics.collider_empty")
+        };
+        ui.monospace(trf(
+            "physics.nearby_row",
+            &[
+                ("depth", &format!("{depth:>2}")),
+                (
+                    "distance",
+                    &format!("{:>5}", fmt_number(f64::from(*distance), 1)),
+                ),
+                ("mask", &format!("{mask:08b}")),
+                ("status", &status),
+                ("kind", &kind),
+            ],
         ));
     }
     if nearby.len() > 12 {
-        ui.label(format!("… and {} more", nearby.len() - 12));
+        ui.label(trf("physics.more", &[("count", &(nearby.len() - 12))]));
     }
 
     ui.separator();
@@ -116,67 +133,60 @@ pub(super) fn render_physics_tab(ui: &mut egui::Ui, params: &mut PhysicsParams)
     // If this reads zero, the game's fetch/fit pipeline isn't populating the
     // overlay and roads are doing nothing.
     ui.horizontal(|ui| {
-        ui.label(format!(
-            "Road ribbons: {} (overlay v{})",
-            params.road_overlay.ribbons.len(),
-            params.road_overlay.version
+        ui.label(trf(
+            "physics.road_ribbons",
+            &[
+                ("count", &params.road_overlay.ribbons.len()),
+                ("version", &params.road_overlay.version),
+            ],
         ))
-        .on_hover_text(
-            "Fitted road ribbons currently published to the engine's \
-             RoadOverlay by the game's fetch/fit pipeline. Zero means no \
-             roads are active.",
-        );
-        ui.checkbox(&mut params.road_viz.enabled, "Show ribbons")
-            .on_hover_text(
-                "Draw every fitted ribbon (centerline, edges, and a vertical \
-                 tick per station), coloured by class, at any distance.",
-            );
+        .on_hover_text(tr("physics.road_ribbons.hover"));
+        ui.checkbox(&mut params.road_viz.enabled, tr("physics.show_ribbons"))
+            .on_hover_text(tr("physics.show_ribbons.hover"));
     });
     // Pipeline trace: which stage zeroes out when the overlay is empty.
     let d = &params.roads_diag;
-    ui.monospace(format!(
-        "  fetch {} ways → {} terrain tiles → {} fit-ways → {} ribbons",
-        d.fetched_ways, d.terrain_tiles, d.fit_ways, d.fitted_ribbons
+    ui.monospace(trf(
+        "physics.road_pipeline",
+        &[
+            ("ways", &d.fetched_ways),
+            ("tiles", &d.terrain_tiles),
+            ("fit_ways", &d.fit_ways),
+            ("ribbons", &d.fitted_ribbons),
+        ],
     ))
-    .on_hover_text(
-        "The road fetch→fit pipeline stage counts. The first one that reads \
-         zero is where roads are dropping out: 0 ways = Overpass fetch, \
-         0 tiles = terrain not streamed, 0 fit-ways = all tunnels/sunk, \
-         0 ribbons = the fit found no terrain under the ways.",
-    );
+    .on_hover_text(tr("physics.road_pipeline.hover"));
     if d.fitted_ribbons > 0 {
-        ui.monospace(format!("  nearest station: {:.0} m", d.nearest_station_m))
-            .on_hover_text(
-                "Distance from the camera to the closest fitted station. If \
-                 this is large while you stand on a road, you have driven off \
-                 the last-fitted patch — coverage is a moving window, not a \
-                 growing trail.",
-            );
+        ui.monospace(trf(
+            "physics.nearest_station",
+            &[("metres", &fmt_number(d.nearest_station_m, 0))],
+        ))
+        .on_hover_text(tr("physics.nearest_station.hover"));
     }
-    let region = d
-        .region
-        .map_or_else(|| "none".to_string(), |(la, lo)| format!("{la},{lo}"));
-    ui.monospace(format!(
-        "  region {region} · fetch {} · fit {} · {} fits",
-        if d.fetch_in_flight { "busy" } else { "idle" },
-        if d.fit_in_flight { "busy" } else { "idle" },
-        d.fits,
-    ))
-    .on_hover_text(
-        "Live pipeline state. Drive on the ground and watch: the region cell \
-         should change as you cross ~1 km boundaries and the fit count should \
-         climb. A flag stuck on 'busy' while moving means the pipeline has \
-         latched and stopped loading new roads.",
+    let region = d.region.map_or_else(
+        || tr("physics.region_none").to_string(),
+        |(la, lo)| format!("{la},{lo}"),
     );
+    let activity = |busy: bool| tr(if busy { "physics.busy" } else { "physics.idle" });
+    ui.monospace(trf(
+        "physics.road_state",
+        &[
+            ("region", &region),
+            ("fetch", &activity(d.fetch_in_flight)),
+            ("fit", &activity(d.fit_in_flight)),
+            ("fits", &d.fits),
+        ],
+    ))
+    .on_hover_text(tr("physics.road_state.hover"));
     if !d.status.is_empty() {
-        ui.monospace(format!("  roads: {}", d.status));
+        ui.monospace(trf("physics.road_status", &[("status", &d.status)]));
     }
 
     ui.separator();
 
     let mut debug_enabled = is_physics_debug_enabled(&params.config_store);
     if ui
-        .checkbox(&mut debug_enabled, "Debug visualization")
+        .checkbox(&mut debug_enabled, tr("physics.debug_visualization"))
         .changed()
     {
         toggle_physics_debug(&mut params.config_store);
@@ -187,34 +197,31 @@ pub(super) fn render_physics_tab(ui: &mut egui::Ui, params: &mut PhysicsParams)
     let filter = &mut *params.viz_filter;
     ui.add_enabled_ui(debug_enabled, |ui| {
         ui.horizontal(|ui| {
-            ui.label("Wireframe radius:");
+            ui.label(tr("physics.wireframe_radius"));
             ui.add(
                 egui::Slider::new(&mut filter.radius_m, 0.5..=2000.0)
                     .logarithmic(true)
                     .suffix(" m"),
             )
-            .on_hover_text(
-                "Terrain colliders beyond this distance are excluded from \
-                 the wireframe overlay. Dynamic colliders always draw.",
-            );
+            .on_hover_text(tr("physics.wireframe_radius.hover"));
         });
         // Split min/max sliders (egui has no built-in dual-range slider). Each
         // is clamped against the other so the range stays well-ordered.
         ui.horizontal(|ui| {
-            ui.label("Depth min:");
+            ui.label(tr("physics.depth_min"));
             ui.add(egui::Slider::new(
                 &mut filter.depth_min,
                 0..=OctreePath::MAX_DEPTH,
             ))
-            .on_hover_text("Inclusive minimum octree depth for terrain-collider wireframes.");
+            .on_hover_text(tr("physics.depth_min.hover"));
         });
         ui.horizontal(|ui| {
-            ui.label("Depth max:");
+            ui.label(tr("physics.depth_max"));
             ui.add(egui::Slider::new(
                 &mut filter.depth_max,
                 0..=OctreePath::MAX_DEPTH,
             ))
-            .on_hover_text("Inclusive maximum octree depth for terrain-collider wireframes.");
+            .on_hover_text(tr("physics.depth_max.hover"));
         });
         // Keep the range well-ordered after either slider moves.
         filter.depth_min = filter.depth_min.min(filter.depth_max);
@@ -0,0 +1,262 @@
+//! Place-name overlay from a bundled offline gazetteer.
+//!
+//! Draws the names of major cities and landmarks as screen-space labels so
+//! orientation is easy while flying high. The gazetteer ranks each place by
+//! prominence; the camera's altitude decides which ranks are shown, places
+//! behind the horizon are dropped, and a greedy declutter pass places labels
+//! most-prominent first, skipping any that would overlap one already placed.
+
+use std::{error::Error, fmt};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use glam::DVec3;
+
+use veldera_geo::{coords::lat_lon_to_ecef, floating_origin::FloatingOriginCamera};
+
+use crate::UiVisible;
+
+/// The bundled gazetteer: `name,kind,rank,lat,lon` rows, `#` comments.
+const GAZETTEER: &str = include_str!("../assets/gazetteer.csv");
+
+/// Most labels drawn in one frame, after decluttering.
+const MAX_LABELS: usize = 80;
+
+/// Altitude (m) below which rank-2 places appear, at density 1.
+const RANK_2_ALTITUDE: f64 = 2_500_000.0;
+
+/// Altitude (m) below which rank-3 places appear, at density 1.
+const RANK_3_ALTITUDE: f64 = 400_000.0;
+
+/// Plugin: loads the gazetteer and draws the labels.
+pub struct PlaceLabelsPlugin;
+
+impl Plugin for PlaceLabelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlaceLabels>().add_systems(
+            EguiPrimaryContextPass,
+            draw_place_labels.run_if(|visible: Res<UiVisible>, labels: Res<PlaceLabels>| {
+                visible.0 && labels.enabled
+            }),
+        );
+    }
+}
+
+/// Settings and data for the place-label overlay.
+#[derive(Resource)]
+pub(super) struct PlaceLabels {
+    /// Whether labels are drawn.
+    pub enabled: bool,
+    /// Scales the altitudes at which less prominent places appear; above 1
+    /// shows more places from higher up.
+    pub density: f64,
+    /// Every place in the gazetteer.
+    places: Vec<Place>,
+}
+
+impl Default for PlaceLabels {
+    fn default() -> Self {
+        let places = parse_gazetteer(GAZETTEER).unwrap_or_else(|e| {
+            error!("Failed to parse the bundled gazetteer: {e}");
+            Vec::new()
+        });
+        Self {
+            enabled: false,
+            density: 1.0,
+            places,
+        }
+    }
+}
+
+/// What a gazetteer entry names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlaceKind {
+    City,
+    Landmark,
+}
+
+/// One gazetteer entry.
+#[derive(Clone, Debug)]
+struct Place {
+    name: String,
+    kind: PlaceKind,
+    /// Prominence: 1 is visible from orbit, 3 is regional.
+    rank: u8,
+    /// ECEF position at sea level.
+    position: DVec3,
+}
+
+/// A malformed gazetteer row.
+#[derive(Debug)]
+struct GazetteerError {
+    /// 1-based line number.
+    line: usize,
+    reason: &'static str,
+}
+
+impl fmt::Display for GazetteerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for GazetteerError {}
+
+/// Parse gazetteer rows, skipping blank lines and `#` comments.
+fn parse_gazetteer(text: &str) -> Result<Vec<Place>, GazetteerError> {
+    let mut places = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |reason| GazetteerError {
+            line: index + 1,
+            reason,
+        };
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [name, kind, rank, lat, lon] = fields[..] else {
+            return Err(error("expected 5 fields"));
+        };
+        let kind = match kind {
+            "city" => PlaceKind::City,
+            "landmark" => PlaceKind::Landmark,
+            _ => return Err(error("unknown kind")),
+        };
+        let rank = rank
+            .parse::<u8>()
+            .ok()
+            .filter(|rank| (1..=3).contains(rank))
+            .ok_or_else(|| error("rank must be 1, 2, or 3"))?;
+        let (Ok(lat), Ok(lon)) = (lat.parse::<f64>(), lon.parse::<f64>()) else {
+            return Err(error("invalid coordinates"));
+        };
+        places.push(Place {
+            name: name.to_string(),
+            kind,
+            rank,
+            position: lat_lon_to_ecef(lat, lon, veldera_constants::EARTH_RADIUS_M_F64),
+        });
+    }
+    Ok(places)
+}
+
+/// The least prominent rank worth showing from `altitude` (m).
+fn max_rank(altitude: f64, density: f64) -> u8 {
+    if altitude < RANK_3_ALTITUDE * density {
+        3
+    } else if altitude < RANK_2_ALTITUDE * density {
+        2
+    } else {
+        1
+    }
+}
+
+/// Resources for drawing the labels.
+#[derive(SystemParam)]
+struct PlaceLabelParams<'w, 's> {
+    contexts: EguiContexts<'w, 's>,
+    labels: Res<'w, PlaceLabels>,
+    camera_query: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static FloatingOriginCamera,
+        ),
+    >,
+}
+
+/// Draw the labels for the visible places, decluttered.
+fn draw_place_labels(mut params: PlaceLabelParams) -> Result {
+    let Ok((camera, camera_transform, origin)) = params.camera_query.single() else {
+        return Ok(());
+    };
+    let camera_position = origin.position;
+    let altitude = camera_position.length() - veldera_constants::EARTH_RADIUS_M_F64;
+    let max_rank = max_rank(altitude, params.labels.density);
+
+    // Candidates above the horizon, most prominent (then nearest) first.
+    let mut candidates: Vec<(&Place, f64)> = params
+        .labels
+        .places
+        .iter()
+        .filter(|place| place.rank <= max_rank)
+        .filter(|place| (camera_position - place.position).dot(place.position) > 0.0)
+        .map(|place| (place, place.position.distance(camera_position)))
+        .collect();
+    candidates.sort_by(|a, b| a.0.rank.cmp(&b.0.rank).then(a.1.total_cmp(&b.1)));
+
+    let ctx = params.contexts.ctx_mut()?;
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("place_labels"),
+    ));
+    let mut placed: Vec<egui::Rect> = Vec::new();
+    for (place, _) in candidates {
+        if placed.len() >= MAX_LABELS {
+            break;
+        }
+        // The camera sits at the floating origin, so places are drawn
+        // camera-relative.
+        let relative = (place.position - camera_position).as_vec3();
+        let Ok(screen) = camera.world_to_viewport(camera_transform, relative) else {
+            continue;
+        };
+        let anchor = egui::pos2(screen.x, screen.y);
+
+        let (size, color) = match (place.kind, place.rank) {
+            (PlaceKind::Landmark, _) => (13.0, egui::Color32::from_rgb(255, 220, 150)),
+            (PlaceKind::City, 1) => (18.0, egui::Color32::WHITE),
+            (PlaceKind::City, 2) => (15.0, egui::Color32::WHITE),
+            (PlaceKind::City, _) => (13.0, egui::Color32::from_gray(220)),
+        };
+        let font = egui::FontId::proportional(size);
+        let galley = painter.layout_no_wrap(place.name.clone(), font.clone(), color);
+        let rect = egui::Align2::CENTER_BOTTOM
+            .anchor_size(anchor - egui::vec2(0.0, 4.0), galley.size())
+            .expand(2.0);
+        if placed.iter().any(|other| other.intersects(rect)) {
+            continue;
+        }
+
+        let shadow = painter.layout_no_wrap(place.name.clone(), font, egui::Color32::BLACK);
+        painter.galley(
+            rect.min + egui::vec2(3.0, 3.0),
+            shadow,
+            egui::Color32::BLACK,
+        );
+        painter.galley(rect.min + egui::vec2(2.0, 2.0), galley, color);
+        painter.circle_filled(anchor, 2.5, color);
+        placed.push(rect);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_gazetteer_parses() {
+        let places = parse_gazetteer(GAZETTEER).unwrap();
+        assert!(places.len() > 100);
+        assert!(places.iter().any(|p| p.kind == PlaceKind::Landmark));
+    }
+
+    #[test]
+    fn rejects_malformed_rows() {
+        let error = parse_gazetteer("# header\nParis,city,9,48.8,2.3\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_gazetteer("Paris,city,1,48.8").is_err());
+    }
+
+    #[test]
+    fn density_reveals_more_places_lower_down() {
+        assert_eq!(max_rank(10_000_000.0, 1.0), 1);
+        assert_eq!(max_rank(1_000_000.0, 1.0), 2);
+        assert_eq!(max_rank(1_000_000.0, 3.0), 3);
+        assert_eq!(max_rank(1_000.0, 1.0), 3);
+    }
+}
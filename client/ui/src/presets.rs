@@ -0,0 +1,228 @@
+//! Curated starting locations and a random-place picker.
+//!
+//! The presets (natural wonders and cities) are compiled in from
+//! `assets/presets.csv`, for the Location tab and the viewer's
+//! `--preset <id>` flag. The random-place button samples the sphere
+//! uniformly and keeps the first point a coarse, compiled-in land mask
+//! marks as land.
+
+use std::{error::Error, fmt, sync::LazyLock};
+
+/// The bundled presets: `id,kind,lat,lon,altitude,heading,pitch,name` rows,
+/// `#` comments.
+const PRESETS: &str = include_str!("../assets/presets.csv");
+
+/// The bundled land mask: one character per cell, `#` for land, after
+/// `# ` comment lines.
+const LAND_MASK: &str = include_str!("../assets/land_mask.txt");
+
+/// Attempts at drawing a land point before giving up.
+const MAX_RANDOM_ATTEMPTS: usize = 1000;
+
+/// What a preset shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresetKind {
+    Nature,
+    City,
+}
+
+/// A curated starting location and view.
+#[derive(Clone, Debug)]
+pub struct LocationPreset {
+    /// Identifier for `--preset`, e.g. `grand-canyon`.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    pub kind: PresetKind,
+    pub lat: f64,
+    pub lon: f64,
+    /// Altitude above sea level (m).
+    pub altitude: f64,
+    /// Look heading in degrees clockwise from north.
+    pub heading_deg: f64,
+    /// Look pitch in degrees above the horizon.
+    pub pitch_deg: f64,
+}
+
+/// A malformed preset row.
+#[derive(Debug)]
+struct PresetError {
+    /// 1-based line number.
+    line: usize,
+    reason: &'static str,
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for PresetError {}
+
+static BUNDLED: LazyLock<Vec<LocationPreset>> = LazyLock::new(|| {
+    parse_presets(PRESETS).unwrap_or_else(|e| {
+        bevy::log::error!("Failed to parse the bundled location presets: {e}");
+        Vec::new()
+    })
+});
+
+/// Every bundled preset, in file order.
+pub fn presets() -> &'static [LocationPreset] {
+    &BUNDLED
+}
+
+/// The preset with this id, ignoring case.
+pub fn find(id: &str) -> Option<&'static LocationPreset> {
+    presets()
+        .iter()
+        .find(|preset| preset.id.eq_ignore_ascii_case(id.trim()))
+}
+
+/// Parse preset rows, skipping blank lines and `#` comments.
+fn parse_presets(text: &str) -> Result<Vec<LocationPreset>, PresetError> {
+    let mut presets = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |reason| PresetError {
+            line: index + 1,
+            reason,
+        };
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [id, kind, lat, lon, altitude, heading, pitch, name] = fields[..] else {
+            return Err(error("expected 8 fields"));
+        };
+        let kind = match kind {
+            "nature" => PresetKind::Nature,
+            "city" => PresetKind::City,
+            _ => return Err(error("unknown kind")),
+        };
+        let number = |field: &str| field.parse::<f64>().map_err(|_| error("invalid number"));
+        let (lat, lon) = (number(lat)?, number(lon)?);
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(error("coordinates out of range"));
+        }
+        presets.push(LocationPreset {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind,
+            lat,
+            lon,
+            altitude: number(altitude)?,
+            heading_deg: number(heading)?,
+            pitch_deg: number(pitch)?,
+        });
+    }
+    Ok(presets)
+}
+
+/// A coarse equirectangular land mask.
+struct LandMask {
+    /// Cells, row-major from the north-west corner.
+    land: Vec<bool>,
+    columns: usize,
+    rows: usize,
+}
+
+static MASK: LazyLock<LandMask> = LazyLock::new(|| LandMask::parse(LAND_MASK));
+
+impl LandMask {
+    fn parse(text: &str) -> Self {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("# "))
+            .collect();
+        let columns = rows.first().map_or(0, |row| row.len());
+        let land = rows
+            .iter()
+            .flat_map(|row| row.bytes().chain(std::iter::repeat(b'.')).take(columns))
+            .map(|cell| cell == b'#')
+            .collect();
+        Self {
+            land,
+            columns,
+            rows: rows.len(),
+        }
+    }
+
+    fn is_land(&self, lat: f64, lon: f64) -> bool {
+        if self.rows == 0 || self.columns == 0 {
+            return false;
+        }
+        let row = ((90.0 - lat) / 180.0 * self.rows as f64) as usize;
+        let column = ((lon + 180.0).rem_euclid(360.0) / 360.0 * self.columns as f64) as usize;
+        self.land[row.min(self.rows - 1) * self.columns + column.min(self.columns - 1)]
+    }
+}
+
+/// Whether the coarse land mask has land at `(lat, lon)` (degrees).
+pub fn is_land(lat: f64, lon: f64) -> bool {
+    MASK.is_land(lat, lon)
+}
+
+/// A uniformly distributed point on land, as `(lat, lon)` in degrees.
+/// `next` yields uniform numbers in `[0, 1)`.
+pub fn random_land_point(mut next: impl FnMut() -> f64) -> Option<(f64, f64)> {
+    (0..MAX_RANDOM_ATTEMPTS).find_map(|_| {
+        // Uniform in area: the sine of the latitude is uniform.
+        let lat = (2.0 * next() - 1.0).asin().to_degrees();
+        let lon = next() * 360.0 - 180.0;
+        is_land(lat, lon).then_some((lat, lon))
+    })
+}
+
+/// Uniform numbers in `[0, 1)` from a SplitMix64 sequence.
+pub(crate) fn uniform_sequence(seed: u64) -> impl FnMut() -> f64 {
+    let mut state = seed;
+    move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_presets_parse() {
+        let presets = parse_presets(PRESETS).unwrap();
+        assert!(presets.len() >= 20);
+        assert!(presets.iter().any(|p| p.kind == PresetKind::Nature));
+        assert!(presets.iter().any(|p| p.kind == PresetKind::City));
+        assert_eq!(
+            find("Grand-Canyon").map(|p| p.name.as_str()),
+            Some("Grand Canyon")
+        );
+        assert!(find("atlantis").is_none());
+    }
+
+    #[test]
+    fn land_mask_knows_land_from_sea() {
+        // The Sahara, central Asia, and the Amazon basin.
+        assert!(is_land(23.0, 12.0));
+        assert!(is_land(45.0, 80.0));
+        assert!(is_land(-5.0, -62.0));
+        // The central Pacific, South Atlantic, and Indian Ocean.
+        assert!(!is_land(0.0, -150.0));
+        assert!(!is_land(-30.0, -15.0));
+        assert!(!is_land(-20.0, 80.0));
+    }
+
+    #[test]
+    fn random_points_land_on_land() {
+        let mut next = uniform_sequence(7);
+        for _ in 0..50 {
+            let (lat, lon) = random_land_point(&mut next).unwrap();
+            assert!(is_land(lat, lon));
+        }
+    }
+}
@@ -1,23 +1,85 @@
 //! Profiler tab for the debug UI.
 //!
-//! Two sub-tabs:
+//! Four sub-tabs:
 //! - **Logic** — per-Bevy-system CPU times, sourced from the
 //!   [`crate::profiler::CpuProfile`] resource (populated by our
 //!   custom `tracing-subscriber::Layer`).
 //! - **Render** — per-render-pass GPU + CPU times, sourced from
 //!   [`bevy::diagnostic::DiagnosticsStore`] (populated by
-//!   [`bevy::render::diagnostic::RenderDiagnosticsPlugin`]).
+//!   [`bevy::render::diagnostic::RenderDiagnosticsPlugin`]), with a
+//!   GPU-time history plot of the atmosphere and terrain passes.
+//! - **Frames** — frame-time percentiles and hitch counts over the last
+//!   [`FRAME_TIME_HISTORY`] frames of Bevy's frame-time diagnostic, and
+//!   counts of the entities that drive frame cost (tiles, colliders,
+//!   vehicles).
+//! - **Memory** — terrain bytes per subsystem and per LOD level, sourced
+//!   from [`veldera_terrain::memory::TerrainMemory`], as a stacked bar and
+//!   tables.
 
 use std::collections::BTreeMap;
 
+use avian3d::prelude::{Collider, RigidBody};
 use bevy::{
-    diagnostic::DiagnosticsStore,
-    ecs::system::{Res, SystemParam},
+    diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::{
+        entity::Entities,
+        query::With,
+        system::{Query, Res, SystemParam},
+    },
 };
 use bevy_egui::egui;
 use egui_extras::{Column, TableBuilder};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
 
 use veldera_engine::profiler::CpuProfile;
+use veldera_game_vehicle::Vehicle;
+use veldera_physics::{DebugPalette, TerrainCollider};
+use veldera_terrain::{
+    memory::{MemoryCategory, TerrainMemory},
+    mesh::RocktreeMeshMarker,
+};
+
+use crate::{format_bytes, plot_color};
+
+/// Frames of frame-time history kept for the Frames sub-tab's percentiles:
+/// about 15 s at 60 fps.
+pub(super) const FRAME_TIME_HISTORY: usize = 1000;
+
+/// A frame counts as a hitch when it takes this many times the median.
+const HITCH_FACTOR: f64 = 2.0;
+
+/// Passes plotted in the Render sub-tab: diagnostic pass path, legend label
+/// and line colour under the standard [`DebugPalette`]. Terrain has no pass of its own; it's nearly all of the
+/// main opaque pass.
+const PLOTTED_PASSES: [(&str, &str, egui::Color32); 4] = [
+    (
+        "atmosphere_luts",
+        "Atmosphere LUTs",
+        egui::Color32::LIGHT_BLUE,
+    ),
+    ("render_sky", "Sky", egui::Color32::from_rgb(120, 160, 255)),
+    (
+        "atmosphere_environment",
+        "Sky environment map",
+        egui::Color32::LIGHT_GREEN,
+    ),
+    (
+        "main_opaque_pass_3d",
+        "Terrain (main opaque pass)",
+        egui::Color32::from_rgb(230, 170, 90),
+    ),
+];
+
+/// Standard colour of each [`MemoryCategory`] in the Memory sub-tab, in
+/// [`MemoryCategory::ALL`] order.
+const MEMORY_COLOURS: [egui::Color32; MemoryCategory::ALL.len()] = [
+    egui::Color32::from_rgb(230, 170, 90),
+    egui::Color32::from_rgb(220, 110, 110),
+    egui::Color32::LIGHT_BLUE,
+    egui::Color32::LIGHT_GREEN,
+    egui::Color32::from_rgb(180, 140, 230),
+    egui::Color32::GRAY,
+];
 
 /// Selected sub-tab in the Profiler tab.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -25,6 +87,8 @@ pub enum ProfilerSubTab {
     #[default]
     Logic,
     Render,
+    Frames,
+    Memory,
 }
 
 impl ProfilerSubTab {
@@ -32,14 +96,44 @@ impl ProfilerSubTab {
         match self {
             Self::Logic => "Logic",
             Self::Render => "Render",
+            Self::Frames => "Frames",
+            Self::Memory => "Memory",
         }
     }
 }
 
 #[derive(SystemParam)]
-pub(super) struct ProfilerParams<'w> {
+pub(super) struct ProfilerParams<'w, 's> {
     pub cpu_profile: Res<'w, CpuProfile>,
     pub render_diagnostics: Res<'w, DiagnosticsStore>,
+    pub palette: Res<'w, DebugPalette>,
+    pub memory: Res<'w, TerrainMemory>,
+    pub entities: EntityCounts<'w, 's>,
+}
+
+/// Entity counts shown in the Frames sub-tab.
+#[derive(SystemParam)]
+pub(super) struct EntityCounts<'w, 's> {
+    all: &'w Entities,
+    tiles: Query<'w, 's, (), With<RocktreeMeshMarker>>,
+    tile_colliders: Query<'w, 's, (), With<TerrainCollider>>,
+    colliders: Query<'w, 's, (), With<Collider>>,
+    rigid_bodies: Query<'w, 's, (), With<RigidBody>>,
+    vehicles: Query<'w, 's, (), With<Vehicle>>,
+}
+
+impl EntityCounts<'_, '_> {
+    /// `(label, count)` rows, all entities first.
+    fn rows(&self) -> [(&'static str, usize); 6] {
+        [
+            ("All entities", self.all.count_spawned() as usize),
+            ("Terrain tile meshes", self.tiles.iter().count()),
+            ("Terrain tile colliders", self.tile_colliders.iter().count()),
+            ("Colliders (all)", self.colliders.iter().count()),
+            ("Rigid bodies", self.rigid_bodies.iter().count()),
+            ("Vehicles", self.vehicles.iter().count()),
+        ]
+    }
 }
 
 pub(super) fn render_profiler_tab(
@@ -49,7 +143,12 @@ pub(super) fn render_profiler_tab(
 ) {
     // Sub-tab bar.
     ui.horizontal(|ui| {
-        for tab in [ProfilerSubTab::Logic, ProfilerSubTab::Render] {
+        for tab in [
+            ProfilerSubTab::Logic,
+            ProfilerSubTab::Render,
+            ProfilerSubTab::Frames,
+            ProfilerSubTab::Memory,
+        ] {
             if ui.selectable_label(*subtab == tab, tab.label()).clicked() {
                 *subtab = tab;
             }
@@ -59,7 +158,11 @@ pub(super) fn render_profiler_tab(
 
     match *subtab {
         ProfilerSubTab::Logic => render_logic(ui, &params.cpu_profile),
-        ProfilerSubTab::Render => render_render(ui, &params.render_diagnostics),
+        ProfilerSubTab::Render => {
+            render_render(ui, &params.render_diagnostics, *params.palette);
+        }
+        ProfilerSubTab::Frames => render_frames(ui, params),
+        ProfilerSubTab::Memory => render_memory(ui, &params.memory, *params.palette),
     }
 }
 
@@ -129,7 +232,11 @@ fn short_system_name(full: &str) -> String {
     }
 }
 
-fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
+/// Smoothed GPU and CPU time (ms) of each render pass, keyed by pass name,
+/// plus whether the pass has stopped reporting.
+pub(super) fn render_pass_times(
+    diagnostics: &DiagnosticsStore,
+) -> BTreeMap<String, (Option<f64>, Option<f64>, bool)> {
     // A pass that ran once at startup (e.g. cloud_noise_bake) writes
     // a single measurement and never updates the diagnostic again.
     // Bevy's `smoothed()` keeps returning that one stale value
@@ -167,6 +274,11 @@ fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
         // so in practice both halves agree.
         entry.2 = entry.2 || is_stale;
     }
+    rows
+}
+
+fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore, palette: DebugPalette) {
+    let rows = render_pass_times(diagnostics);
 
     if rows.is_empty() {
         ui.label("No render diagnostics yet.");
@@ -175,17 +287,15 @@ fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
 
     // Totals exclude stale passes — they aren't actually running this
     // frame, so summing their startup cost into "this frame's budget"
-    // would be misleading.
-    let total_gpu: f64 = rows
-        .values()
-        .filter(|(_, _, stale)| !stale)
-        .filter_map(|(g, _, _)| *g)
-        .sum();
-    let total_cpu: f64 = rows
-        .values()
-        .filter(|(_, _, stale)| !stale)
-        .filter_map(|(_, c, _)| *c)
-        .sum();
+    // would be misleading. Nested spans (`parent/child`) are already
+    // inside their parent's time.
+    let top_level = || {
+        rows.iter()
+            .filter(|(name, (_, _, stale))| !stale && !name.contains('/'))
+            .map(|(_, times)| times)
+    };
+    let total_gpu: f64 = top_level().filter_map(|(g, _, _)| *g).sum();
+    let total_cpu: f64 = top_level().filter_map(|(_, c, _)| *c).sum();
     ui.label(format!(
         "Per-pass GPU / CPU time (smoothed). Total GPU: {total_gpu:.3} ms, total CPU: {total_cpu:.3} ms",
     ));
@@ -194,6 +304,8 @@ fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
          aren't supported.",
     );
     ui.add_space(2.0);
+    render_pass_plot(ui, diagnostics, palette);
+    ui.add_space(2.0);
 
     // Sort: non-stale by GPU ms desc, then stale (one-shot) rows
     // bottom-of-table so they don't dominate the active hot list.
@@ -270,3 +382,337 @@ fn render_render(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
                 });
         });
 }
+
+/// GPU time history of [`PLOTTED_PASSES`], from the diagnostics' own sample
+/// history (the most recent frames, newest on the right).
+fn render_pass_plot(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore, palette: DebugPalette) {
+    let lines: Vec<_> = PLOTTED_PASSES
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &(pass, label, colour))| {
+            let path = DiagnosticPath::new(format!("render/{pass}/elapsed_gpu"));
+            let diagnostic = diagnostics.get(&path)?;
+            let len = diagnostic.history_len();
+            if len == 0 {
+                return None;
+            }
+            // Right-align so every line's newest sample shares an x.
+            let offset = diagnostic.get_max_history_length().saturating_sub(len);
+            let points: PlotPoints = diagnostic
+                .values()
+                .enumerate()
+                .map(|(i, &ms)| [(offset + i) as f64, ms])
+                .collect();
+            Some(Line::new(label, points).color(plot_color(palette, index, colour)))
+        })
+        .collect();
+    if lines.is_empty() {
+        return;
+    }
+
+    ui.label("GPU time history (ms):");
+    Plot::new("render_pass_plot")
+        .height(120.0)
+        .legend(Legend::default())
+        .show_x(false)
+        .include_y(0.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show(ui, |plot_ui| {
+            for line in lines {
+                plot_ui.line(line);
+            }
+        });
+}
+
+/// Summary of a window of frame times (ms).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameTimeSummary {
+    frames: usize,
+    mean: f64,
+    p50: f64,
+    p95: f64,
+    p99: f64,
+    max: f64,
+    /// Frames over [`HITCH_FACTOR`] times the median.
+    hitches: usize,
+}
+
+impl FrameTimeSummary {
+    /// Summarize `times` (ms), or `None` if there are none.
+    fn new(times: impl Iterator<Item = f64>) -> Option<Self> {
+        let mut sorted: Vec<f64> = times.collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        // Nearest-rank percentile.
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        let p50 = percentile(50.0);
+        Some(Self {
+            frames: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50,
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            max: sorted[sorted.len() - 1],
+            hitches: sorted.iter().filter(|&&ms| ms > p50 * HITCH_FACTOR).count(),
+        })
+    }
+}
+
+fn render_frames(ui: &mut egui::Ui, params: &ProfilerParams) {
+    let frame_times = params
+        .render_diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME);
+    match frame_times.and_then(|diagnostic| FrameTimeSummary::new(diagnostic.values().copied())) {
+        Some(summary) => {
+            ui.label(format!(
+                "Frame time over the last {} frames (ms). Mean {:.2} is {:.0} fps.",
+                summary.frames,
+                summary.mean,
+                1000.0 / summary.mean.max(f64::EPSILON),
+            ));
+            egui::Grid::new("frame_time_percentiles")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (label, ms) in [
+                        ("p50", summary.p50),
+                        ("p95", summary.p95),
+                        ("p99", summary.p99),
+                        ("max", summary.max),
+                    ] {
+                        ui.label(label);
+                        ui.monospace(format!("{ms:>7.2}"));
+                        ui.end_row();
+                    }
+                    ui.label("hitches");
+                    ui.monospace(format!("{:>7} (over {HITCH_FACTOR}× p50)", summary.hitches));
+                    ui.end_row();
+                });
+        }
+        None => {
+            ui.label("No frame times yet.");
+        }
+    }
+    if let Some(diagnostic) = frame_times {
+        let points: PlotPoints = diagnostic
+            .values()
+            .enumerate()
+            .map(|(i, &ms)| [i as f64, ms])
+            .collect();
+        Plot::new("frame_time_plot")
+            .height(100.0)
+            .show_x(false)
+            .include_y(0.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Frame time (ms)", points).color(plot_color(
+                    *params.palette,
+                    0,
+                    egui::Color32::from_rgb(230, 170, 90),
+                )));
+            });
+    }
+
+    ui.add_space(4.0);
+    ui.label("Entities:");
+    egui::Grid::new("entity_counts")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (label, count) in params.entities.rows() {
+                ui.label(label);
+                ui.monospace(format!("{count:>7}"));
+                ui.end_row();
+            }
+        });
+    ui.label("Per-system CPU times are in the Logic sub-tab.");
+}
+
+fn render_memory(ui: &mut egui::Ui, memory: &TerrainMemory, palette: DebugPalette) {
+    if memory.updated_at().is_none() {
+        ui.label("No memory report yet.");
+        return;
+    }
+    let colours: Vec<_> = MEMORY_COLOURS
+        .iter()
+        .enumerate()
+        .map(|(index, &colour)| plot_color(palette, index, colour))
+        .collect();
+
+    ui.label(format!(
+        "Terrain RAM: {}   VRAM: {}",
+        format_bytes(memory.ram_bytes()),
+        format_bytes(memory.vram_bytes()),
+    ));
+    ui.label(
+        "Payload sizes only. GPU meshes and textures also keep a main-world copy, so they \
+         count against RAM too.",
+    );
+    ui.add_space(2.0);
+    memory_bar(ui, memory, &colours);
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            TableBuilder::new(ui)
+                .id_salt("memory_categories_table")
+                .column(Column::remainder().resizable(true))
+                .column(Column::exact(80.0))
+                .column(Column::exact(60.0))
+                .column(Column::exact(40.0))
+                .header(18.0, |mut row| {
+                    for heading in ["Subsystem", "size", "items", "GPU"] {
+                        row.col(|ui| {
+                            ui.strong(heading);
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    for (category, &colour) in MemoryCategory::ALL.into_iter().zip(&colours) {
+                        let usage = memory.total(category);
+                        body.row(16.0, |mut row| {
+                            row.col(|ui| {
+                                ui.colored_label(colour, category.label());
+                            });
+                            row.col(|ui| {
+                                ui.label(format_bytes(usage.bytes));
+                            });
+                            row.col(|ui| {
+                                ui.label(format!("{}", usage.items));
+                            });
+                            row.col(|ui| {
+                                ui.label(if category.on_gpu() { "yes" } else { "" });
+                            });
+                        });
+                    }
+                });
+
+            ui.add_space(6.0);
+            ui.label("By node depth (caches and bulk metadata aren't split by depth):");
+            let per_depth = [
+                MemoryCategory::RenderMeshes,
+                MemoryCategory::RenderTextures,
+                MemoryCategory::NodeData,
+                MemoryCategory::Colliders,
+            ];
+            let columns: Vec<Vec<_>> = per_depth
+                .iter()
+                .map(|&category| memory.by_depth(category).map(|(_, usage)| usage).collect())
+                .collect();
+            TableBuilder::new(ui)
+                .id_salt("memory_depths_table")
+                .column(Column::exact(40.0))
+                .columns(Column::exact(90.0), per_depth.len() + 1)
+                .header(18.0, |mut row| {
+                    row.col(|ui| {
+                        ui.strong("Depth");
+                    });
+                    for category in per_depth {
+                        row.col(|ui| {
+                            ui.strong(category.label());
+                        });
+                    }
+                    row.col(|ui| {
+                        ui.strong("Total");
+                    });
+                })
+                .body(|mut body| {
+                    for (row_index, depth) in memory.depths().enumerate() {
+                        body.row(16.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(format!("{depth}"));
+                            });
+                            let mut total = 0;
+                            for column in &columns {
+                                let bytes = column[row_index].bytes;
+                                total += bytes;
+                                row.col(|ui| {
+                                    ui.label(format_bytes(bytes));
+                                });
+                            }
+                            row.col(|ui| {
+                                ui.strong(format_bytes(total));
+                            });
+                        });
+                    }
+                });
+        });
+}
+
+/// One horizontal bar split into a segment per [`MemoryCategory`], sized by
+/// its share of the RAM total, with a legend below.
+fn memory_bar(ui: &mut egui::Ui, memory: &TerrainMemory, colours: &[egui::Color32]) {
+    let total = memory.ram_bytes();
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 18.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let mut hovered = None;
+    if total > 0 {
+        let mut x = rect.left();
+        for (category, &colour) in MemoryCategory::ALL.into_iter().zip(colours) {
+            let share = memory.total(category).bytes as f32 / total as f32;
+            let segment = egui::Rect::from_min_size(
+                egui::pos2(x, rect.top()),
+                egui::vec2(share * rect.width(), rect.height()),
+            );
+            painter.rect_filled(segment, 0.0, colour);
+            if response
+                .hover_pos()
+                .is_some_and(|pos| segment.contains(pos))
+            {
+                hovered = Some((category, share));
+            }
+            x = segment.right();
+        }
+    }
+    if let Some((category, share)) = hovered {
+        response.on_hover_text(format!(
+            "{}: {} ({:.0}%)",
+            category.label(),
+            format_bytes(memory.total(category).bytes),
+            share * 100.0,
+        ));
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for (category, &colour) in MemoryCategory::ALL.into_iter().zip(colours) {
+            let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+            ui.painter().rect_filled(swatch, 1.0, colour);
+            ui.label(category.label());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_time_percentiles_use_nearest_rank() {
+        // 1..=100 ms: each percentile is its own rank.
+        let summary = FrameTimeSummary::new((1..=100).map(f64::from)).unwrap();
+        assert_eq!(
+            (summary.p50, summary.p95, summary.p99, summary.max),
+            (50.0, 95.0, 99.0, 100.0)
+        );
+        assert_eq!(summary.hitches, 0);
+        assert!(FrameTimeSummary::new(std::iter::empty()).is_none());
+    }
+
+    #[test]
+    fn slow_frames_count_as_hitches() {
+        let times = std::iter::repeat_n(16.0, 98).chain([40.0, 100.0]);
+        let summary = FrameTimeSummary::new(times).unwrap();
+        assert_eq!((summary.p50, summary.hitches), (16.0, 2));
+        assert_eq!(summary.p99, 40.0);
+    }
+}
@@ -0,0 +1,306 @@
+//! Crash recovery: restore where you were after a panic.
+//!
+//! Every frame the camera's ECEF position and look direction, the time
+//! override and a few viewer settings are copied into a process-wide
+//! snapshot. A panic hook (chained in front of the existing one) writes that
+//! snapshot to `<OS data dir>/veldera/recovery.json`, so a GPU device loss
+//! or a decode bug doesn't lose the session. On the next launch a prompt
+//! offers to restore it; either answer deletes the file. The web build has
+//! nowhere to write and skips all of this.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+
+use veldera_game_camera::FlightCamera;
+use veldera_game_camera_state::{CameraModeState, CameraModeTransitions};
+use veldera_geo::{coords::ecef_to_lat_lon, floating_origin::FloatingOriginCamera};
+use veldera_sky::time_of_day::{SimpleDate, TimeMode, TimeOfDayState, seconds_to_hms};
+use veldera_terrain::lod::{LodTuning, TextureQuality};
+
+use crate::{
+    UiVisible,
+    i18n::{fmt_lat_lon, fmt_number, tr},
+};
+
+/// Latest session state, written by [`snapshot_session`] and read by the
+/// panic hook (which can't reach the ECS).
+static SNAPSHOT: Mutex<Option<SessionSnapshot>> = Mutex::new(None);
+
+/// Plugin: installs the panic hook, keeps the snapshot current, and offers
+/// to restore a crashed session.
+pub struct RecoveryPlugin;
+
+impl Plugin for RecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        let recovery = Recovery::load();
+        if let Some(path) = &recovery.path {
+            install_panic_hook(path.clone());
+        }
+        app.insert_resource(recovery)
+            .add_systems(Last, snapshot_session)
+            .add_systems(EguiPrimaryContextPass, offer_restore);
+    }
+}
+
+// ============================================================================
+// Data
+// ============================================================================
+
+/// Everything restored after a crash.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SessionSnapshot {
+    /// Camera position (ECEF, m).
+    ecef: [f64; 3],
+    /// Camera look direction (ECEF, unit).
+    direction: [f32; 3],
+    /// Whether the walking (FPS) controller was active.
+    fps_mode: bool,
+    /// The time override, if the clock wasn't following real time.
+    time_override: Option<TimeOverride>,
+    texture_quality: TextureQuality,
+    ui_visible: bool,
+    /// The panic message, filled in by the hook.
+    #[serde(default)]
+    panic: Option<String>,
+}
+
+/// A UTC date and time, and the speed the clock was running at.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct TimeOverride {
+    year: i32,
+    month: u32,
+    day: u32,
+    /// Seconds since midnight UTC.
+    utc_seconds: f64,
+    speed: f32,
+}
+
+/// The recovery file and the crashed session waiting to be restored.
+#[derive(Resource)]
+pub(super) struct Recovery {
+    /// Recovery file, if this platform has one.
+    path: Option<PathBuf>,
+    /// The crashed session, until the user restores or dismisses it.
+    pending: Option<SessionSnapshot>,
+    /// Set by the prompt's Restore button; applied by [`snapshot_session`].
+    restore: Option<SessionSnapshot>,
+}
+
+impl Recovery {
+    fn load() -> Self {
+        let path = recovery_path();
+        let pending = path.as_deref().and_then(|path| {
+            load_snapshot(path)
+                .inspect_err(|e| warn!("Ignoring unreadable recovery file: {e}"))
+                .ok()
+                .flatten()
+        });
+        if pending.is_some() {
+            info!("Found a crashed session to restore");
+        }
+        Self {
+            path,
+            pending,
+            restore: None,
+        }
+    }
+
+    /// Drop the crashed session and its file.
+    fn resolve(&mut self) {
+        self.pending = None;
+        if let Some(path) = &self.path
+            && let Err(e) = std::fs::remove_file(path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove recovery file: {e}");
+        }
+    }
+}
+
+/// `<OS data dir>/veldera/recovery.json`.
+#[cfg(not(target_family = "wasm"))]
+fn recovery_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("veldera").join("recovery.json"))
+}
+
+/// The web build has nowhere to save to.
+#[cfg(target_family = "wasm")]
+fn recovery_path() -> Option<PathBuf> {
+    None
+}
+
+/// Read the snapshot at `path`; a missing file is no crashed session.
+fn load_snapshot(path: &Path) -> Result<Option<SessionSnapshot>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Write `snapshot` to `path`, creating its directory.
+fn write_snapshot(path: &Path, snapshot: &SessionSnapshot) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Write the latest snapshot to `path` on panic, then run the previous hook.
+fn install_panic_hook(path: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // `try_lock`: the panic may have happened while the snapshot was
+        // being updated, and a poisoned or held lock must not hang the hook.
+        let snapshot = SNAPSHOT.try_lock().ok().and_then(|guard| guard.clone());
+        if let Some(mut snapshot) = snapshot {
+            let message = info
+                .payload()
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_string())
+                .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            snapshot.panic = Some(match info.location() {
+                Some(location) => format!("{message} ({location})"),
+                None => message,
+            });
+            match write_snapshot(&path, &snapshot) {
+                Ok(()) => eprintln!("Session saved to {} for recovery", path.display()),
+                Err(e) => eprintln!("Failed to save session for recovery: {e}"),
+            }
+        }
+        previous(info);
+    }));
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Copy the session into [`SNAPSHOT`], and apply a restore the prompt asked
+/// for.
+fn snapshot_session(
+    mut recovery: ResMut<Recovery>,
+    mut camera_query: Query<(&mut FloatingOriginCamera, &mut FlightCamera)>,
+    mut time_of_day: ResMut<TimeOfDayState>,
+    camera_mode: Res<CameraModeState>,
+    mut transitions: ResMut<CameraModeTransitions>,
+    mut tuning: ResMut<LodTuning>,
+    mut ui_visible: ResMut<UiVisible>,
+) {
+    if recovery.path.is_none() {
+        return;
+    }
+    let Ok((mut camera, mut flight)) = camera_query.single_mut() else {
+        return;
+    };
+
+    if let Some(snapshot) = recovery.restore.take() {
+        // The walking controller owns the camera position; leave it first.
+        if !camera_mode.is_flycam() {
+            transitions.request_flycam();
+        }
+        camera.position = glam::DVec3::from_array(snapshot.ecef);
+        flight.direction = Vec3::from_array(snapshot.direction).normalize_or(flight.direction);
+        if snapshot.fps_mode {
+            transitions.request_fps_controller();
+        }
+        if let Some(time) = snapshot.time_override {
+            let date = SimpleDate {
+                year: time.year,
+                month: time.month,
+                day: time.day,
+            };
+            time_of_day.set_override_utc(date, time.utc_seconds);
+            time_of_day.set_speed(time.speed);
+        }
+        tuning.texture_quality = snapshot.texture_quality;
+        ui_visible.0 = snapshot.ui_visible;
+        info!("Restored the crashed session");
+        return;
+    }
+
+    let time_override = (time_of_day.mode == TimeMode::Override).then(|| {
+        let date = time_of_day.current_date();
+        TimeOverride {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            utc_seconds: time_of_day.current_utc_seconds(),
+            speed: time_of_day.speed_multiplier,
+        }
+    });
+    let snapshot = SessionSnapshot {
+        ecef: camera.position.to_array(),
+        direction: flight.direction.to_array(),
+        fps_mode: camera_mode.is_fps_controller(),
+        time_override,
+        texture_quality: tuning.texture_quality,
+        ui_visible: ui_visible.0,
+        panic: None,
+    };
+    if let Ok(mut guard) = SNAPSHOT.lock() {
+        *guard = Some(snapshot);
+    }
+}
+
+/// Ask whether to restore the crashed session. Shown whether or not the
+/// debug UI is visible.
+fn offer_restore(mut contexts: EguiContexts, mut recovery: ResMut<Recovery>) -> Result {
+    let Some(snapshot) = &recovery.pending else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+
+    let (lat, lon) = ecef_to_lat_lon(glam::DVec3::from_array(snapshot.ecef));
+    let altitude =
+        glam::DVec3::from_array(snapshot.ecef).length() - veldera_constants::EARTH_RADIUS_M_F64;
+    let mut choice = None;
+    egui::Window::new(tr("recovery.title"))
+        .id(egui::Id::new("recovery_prompt"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(tr("recovery.message"));
+            if let Some(panic) = &snapshot.panic {
+                ui.weak(panic);
+            }
+            ui.separator();
+            ui.monospace(format!(
+                "{}, {} m",
+                fmt_lat_lon(lat, lon, 5),
+                fmt_number(altitude, 0)
+            ));
+            if let Some(time) = &snapshot.time_override {
+                let (h, m, s) = seconds_to_hms(time.utc_seconds);
+                ui.monospace(format!(
+                    "{:04}-{:02}-{:02} {h:02}:{m:02}:{s:02} UTC ({}×)",
+                    time.year, time.month, time.day, time.speed
+                ));
+            }
+            ui.horizontal(|ui| {
+                if ui.button(tr("recovery.restore")).clicked() {
+                    choice = Some(true);
+                }
+                if ui.button(tr("recovery.dismiss")).clicked() {
+                    choice = Some(false);
+                }
+            });
+        });
+
+    if let Some(restore) = choice {
+        if restore {
+            recovery.restore = recovery.pending.clone();
+        }
+        recovery.resolve();
+    }
+    Ok(())
+}
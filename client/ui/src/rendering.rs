@@ -1,54 +1,576 @@
 //! Rendering tab for the debug UI.
 //!
-//! Hosts the render-mesh wireframe overlay: the triangles the terrain
-//! renderer actually rasterizes near the camera, with the shader's
-//! octant-mask vertex collapse replicated. Compare against the Physics
-//! tab's collider wireframes to tell photogrammetry artifacts from
-//! collider/welding divergence.
+//! Shows the dynamic resolution controller's current render scale and frame
+//! time, with its target and limits, reports GPU device losses and rebuilds
+//! the GPU resources on request, toggles the optional terrain stylization
+//! and contour lines, tunes the water shading and the ambient occlusion,
+//! toggles the proxy globe and shows its bake progress, picks the terrain
+//! debug view (also cycled with a key and named in a corner badge while
+//! active) with the slope and aspect analysis bands, captures 360°
+//! panoramas, and hosts the render-mesh wireframe overlay: the triangles the terrain renderer actually rasterizes near the
+//! camera, with the shader's octant-mask vertex collapse replicated. Compare
+//! against the Physics tab's collider wireframes to tell photogrammetry
+//! artifacts from collider/welding divergence.
 
 use bevy::{ecs::system::SystemParam, prelude::*};
-use bevy_egui::egui;
+use bevy_egui::{EguiContexts, egui};
+use leafwing_input_manager::prelude::ActionState;
 
-use veldera_terrain::collider::viz::RenderMeshVizFilter;
+use veldera_engine::{
+    gpu_recovery::GpuHealth,
+    panorama::{DEFAULT_PANORAMA_WIDTH, PanoramaCapture, PanoramaStage},
+    resolution::{
+        DynamicResolution, DynamicResolutionConfig, DynamicResolutionStats, FrameTimeSource,
+    },
+};
+use veldera_game_input::CameraAction;
+use veldera_terrain::{
+    ambient_occlusion::{AmbientOcclusionConfig, AmbientOcclusionQuality},
+    collider::viz::RenderMeshVizFilter,
+    proxy_globe::{ProxyGlobeBake, ProxyGlobeConfig},
+    terrain_material::{TerrainAnalysis, TerrainDebugView, TerrainStyle},
+    water::WaterConfig,
+};
+
+use crate::i18n::{fmt_number, tr, trf};
 
 /// Resources for the rendering tab.
 #[derive(SystemParam)]
-pub(super) struct RenderingParams<'w> {
+pub(super) struct RenderingParams<'w, 's> {
     pub mesh_viz: ResMut<'w, RenderMeshVizFilter>,
+    pub resolution_config: ResMut<'w, DynamicResolutionConfig>,
+    pub resolution_stats: Res<'w, DynamicResolutionStats>,
+    pub resolution_query: Query<'w, 's, (&'static DynamicResolution, &'static Camera)>,
+    pub gpu_health: ResMut<'w, GpuHealth>,
+    pub terrain_style: ResMut<'w, TerrainStyle>,
+    pub terrain_debug_view: ResMut<'w, TerrainDebugView>,
+    pub terrain_analysis: ResMut<'w, TerrainAnalysis>,
+    pub ambient_occlusion: ResMut<'w, AmbientOcclusionConfig>,
+    pub water: ResMut<'w, WaterConfig>,
+    pub proxy_globe: ResMut<'w, ProxyGlobeConfig>,
+    pub proxy_globe_bake: Res<'w, ProxyGlobeBake>,
+    pub panorama: ResMut<'w, PanoramaCapture>,
+    pub panorama_width: Local<'s, PanoramaWidth>,
+}
+
+/// Width picked for the next panorama capture.
+pub(super) struct PanoramaWidth(u32);
+
+impl Default for PanoramaWidth {
+    fn default() -> Self {
+        Self(DEFAULT_PANORAMA_WIDTH)
+    }
 }
 
 /// Render the rendering tab content.
 pub(super) fn render_rendering_tab(ui: &mut egui::Ui, params: &mut RenderingParams) {
+    render_dynamic_resolution(ui, params);
+    render_gpu_health(ui, &mut params.gpu_health);
+    ui.separator();
+    render_terrain_style(ui, &mut params.terrain_style);
+    render_contours(ui, &mut params.terrain_style);
+    ui.separator();
+    render_water(ui, &mut params.water);
+    render_proxy_globe(ui, &mut params.proxy_globe, &params.proxy_globe_bake);
+    ui.separator();
+    render_ambient_occlusion(ui, &mut params.ambient_occlusion);
+    ui.separator();
+    render_terrain_debug_view(ui, &mut params.terrain_debug_view);
+    if params.terrain_debug_view.is_analysis() {
+        render_terrain_analysis(ui, *params.terrain_debug_view, &mut params.terrain_analysis);
+    }
+    ui.separator();
+    render_panorama(ui, &mut params.panorama, &mut params.panorama_width);
+    ui.separator();
+
     let filter = &mut *params.mesh_viz;
-    ui.checkbox(&mut filter.enabled, "Render-mesh wireframes")
-        .on_hover_text(
-            "Draw the displayed terrain triangles near the camera (orange), \
-             with the shader's octant-mask vertex collapse applied — what \
-             the GPU actually rasterizes. Compare with the Physics tab's \
-             collider wireframes to separate photogrammetry artifacts from \
-             collider divergence.",
-        );
+    ui.checkbox(&mut filter.enabled, tr("rendering.mesh_wireframes"))
+        .on_hover_text(tr("rendering.mesh_wireframes.hover"));
     ui.add_enabled_ui(filter.enabled, |ui| {
         ui.horizontal(|ui| {
-            ui.label("Radius:");
+            ui.label(tr("rendering.mesh_radius"));
             ui.add(
                 egui::Slider::new(&mut filter.radius_m, 0.5..=200.0)
                     .logarithmic(true)
                     .suffix(" m"),
             )
-            .on_hover_text(
-                "Meshes whose bounds are farther than this from the camera \
-                 are excluded. Wireframes cost a line per triangle edge, so \
-                 keep this tight.",
+            .on_hover_text(tr("rendering.mesh_radius.hover"));
+        });
+        ui.checkbox(
+            &mut filter.show_collapsed_slivers,
+            tr("rendering.collapsed_slivers"),
+        )
+        .on_hover_text(tr("rendering.collapsed_slivers.hover"));
+    });
+}
+
+/// Dynamic resolution readout and controls.
+fn render_dynamic_resolution(ui: &mut egui::Ui, params: &mut RenderingParams) {
+    ui.strong(tr("rendering.dynamic_resolution"));
+
+    if let Ok((dynamic, camera)) = params.resolution_query.single() {
+        let size = camera
+            .physical_viewport_size()
+            .map(|full| {
+                let render = (full.as_vec2() * dynamic.scale).round();
+                trf(
+                    "rendering.render_size",
+                    &[
+                        ("width", &render.x),
+                        ("height", &render.y),
+                        ("full_width", &full.x),
+                        ("full_height", &full.y),
+                    ],
+                )
+            })
+            .unwrap_or_default();
+        ui.label(trf(
+            "rendering.render_scale",
+            &[
+                ("percent", &fmt_number(f64::from(dynamic.scale) * 100.0, 0)),
+                ("size", &size),
+            ],
+        ));
+    } else {
+        ui.label(tr("rendering.render_scale_none"));
+    }
+    let stats = *params.resolution_stats;
+    let source = match stats.source {
+        FrameTimeSource::Gpu => tr("rendering.source.gpu"),
+        FrameTimeSource::FrameTime => tr("rendering.source.frame"),
+    };
+    match stats.frame_ms {
+        Some(ms) => ui.label(trf(
+            "rendering.frame_time",
+            &[("source", &source), ("ms", &fmt_number(f64::from(ms), 1))],
+        )),
+        None => ui.label(trf("rendering.frame_time_none", &[("source", &source)])),
+    }
+    .on_hover_text(tr("rendering.frame_time.hover"));
+
+    let config = &mut *params.resolution_config;
+    ui.checkbox(&mut config.enabled, tr("rendering.automatic"))
+        .on_hover_text(tr("rendering.automatic.hover"));
+    ui.add_enabled_ui(config.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.target"));
+            ui.add(
+                egui::Slider::new(&mut config.target_gpu_ms, 4.0..=50.0)
+                    .suffix(" ms")
+                    .fixed_decimals(1),
             );
         });
-        ui.checkbox(&mut filter.show_collapsed_slivers, "Collapsed slivers")
-            .on_hover_text(
-                "Also draw triangles the octant mask partially collapses to \
-                 the tile origin. The GPU rasterizes them as invisible \
-                 hairline slivers; as wireframes they read as diagonal fans \
-                 converging on a point in the air. Only useful when \
-                 artifact-hunting the masking shader itself.",
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.min_scale"));
+            ui.add(egui::Slider::new(&mut config.min_scale, 0.25..=1.0).fixed_decimals(2));
+        });
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("rendering.max_scale"));
+        ui.add(egui::Slider::new(&mut config.max_scale, 0.25..=1.0).fixed_decimals(2))
+            .on_hover_text(tr("rendering.max_scale.hover"));
+    });
+}
+
+/// 360° panorama capture button, its width, and the last capture's outcome.
+fn render_panorama(ui: &mut egui::Ui, capture: &mut PanoramaCapture, width: &mut PanoramaWidth) {
+    if cfg!(target_family = "wasm") {
+        ui.label(tr("rendering.panorama_native_only"));
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(capture.stage().is_none(), |ui| {
+            egui::ComboBox::from_id_salt("panorama_width")
+                .selected_text(format!("{}×{}", width.0, width.0 / 2))
+                .show_ui(ui, |ui| {
+                    for option in [4096, 8192, 16384] {
+                        ui.selectable_value(
+                            &mut width.0,
+                            option,
+                            format!("{option}×{}", option / 2),
+                        );
+                    }
+                });
+            if ui
+                .button(tr("rendering.panorama"))
+                .on_hover_text(tr("rendering.panorama.hover"))
+                .clicked()
+            {
+                #[cfg(not(target_family = "wasm"))]
+                capture.request(width.0, crate::console::capture_path("panorama"));
+            }
+        });
+    });
+    match (capture.stage(), capture.last_result()) {
+        (Some(PanoramaStage::Streaming), _) => ui.label(tr("rendering.panorama.streaming")),
+        (Some(PanoramaStage::Capturing), _) => ui.label(tr("rendering.panorama.capturing")),
+        (Some(PanoramaStage::Stitching), _) => ui.label(tr("rendering.panorama.stitching")),
+        (None, Some(Ok(path))) => ui.label(trf(
+            "rendering.panorama.saved",
+            &[("path", &path.display())],
+        )),
+        (None, Some(Err(e))) => ui.colored_label(egui::Color32::LIGHT_RED, e),
+        (None, None) => return,
+    };
+}
+
+/// Terrain stylization toggle and its main knobs.
+fn render_terrain_style(ui: &mut egui::Ui, style: &mut TerrainStyle) {
+    ui.checkbox(&mut style.enabled, tr("rendering.stylization"))
+        .on_hover_text(tr("rendering.stylization.hover"));
+    ui.add_enabled_ui(style.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.snow_line"));
+            ui.add(
+                egui::Slider::new(&mut style.snow_line_m, 0.0..=6000.0)
+                    .suffix(" m")
+                    .fixed_decimals(0),
+            )
+            .on_hover_text(tr("rendering.snow_line.hover"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.snow_strength"));
+            ui.add(egui::Slider::new(&mut style.snow_strength, 0.0..=1.0).fixed_decimals(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.desaturation"));
+            ui.add(egui::Slider::new(&mut style.desaturate_amount, 0.0..=1.0).fixed_decimals(2));
+        });
+    });
+}
+
+/// Contour line toggle, spacing and index contour cadence.
+fn render_contours(ui: &mut egui::Ui, style: &mut TerrainStyle) {
+    ui.checkbox(&mut style.contours, tr("rendering.contours"))
+        .on_hover_text(tr("rendering.contours.hover"));
+    ui.add_enabled_ui(style.contours, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.contour_interval"));
+            ui.add(
+                egui::Slider::new(&mut style.contour_interval_m, 5.0..=1000.0)
+                    .logarithmic(true)
+                    .suffix(" m")
+                    .fixed_decimals(0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.index_contour"));
+            ui.add(
+                egui::Slider::new(&mut style.contour_major_every, 1..=10)
+                    .suffix(tr("rendering.index_contour.suffix")),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.opacity"));
+            ui.add(egui::Slider::new(&mut style.contour_opacity, 0.0..=1.0).fixed_decimals(2));
+        });
+    });
+}
+
+/// Water shading toggle, detection thresholds and surface.
+fn render_water(ui: &mut egui::Ui, config: &mut WaterConfig) {
+    ui.checkbox(&mut config.enabled, tr("rendering.water"))
+        .on_hover_text(tr("rendering.water.hover"));
+    ui.add_enabled_ui(config.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.water_likeness"));
+            ui.add(egui::Slider::new(&mut config.min_likeness, 0.05..=0.5).fixed_decimals(2))
+                .on_hover_text(tr("rendering.water_likeness.hover"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.water_altitude"));
+            ui.add(
+                egui::DragValue::new(&mut config.max_altitude_m)
+                    .range(-200.0..=5_000.0)
+                    .suffix(" m"),
+            )
+            .on_hover_text(tr("rendering.water_altitude.hover"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.water_tint"));
+            ui.color_edit_button_rgb(&mut config.color);
+            ui.add(egui::Slider::new(&mut config.color_blend, 0.0..=1.0).fixed_decimals(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.water_roughness"));
+            ui.add(egui::Slider::new(&mut config.roughness, 0.02..=0.5).fixed_decimals(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.water_waves"));
+            ui.add(egui::Slider::new(&mut config.wave_strength, 0.0..=0.5).fixed_decimals(2));
+        });
+    });
+}
+
+/// Device-loss report and the manual GPU resource rebuild.
+fn render_gpu_health(ui: &mut egui::Ui, health: &mut GpuHealth) {
+    if let Some(loss) = health.loss() {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 90, 80),
+            trf(
+                "rendering.device_lost",
+                &[
+                    ("seconds", &fmt_number(loss.at_secs, 0)),
+                    ("reason", &loss.reason),
+                ],
+            ),
+        )
+        .on_hover_text(loss.message.clone());
+    }
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                health.loss().is_none(),
+                egui::Button::new(tr("rendering.rebuild_gpu")),
+            )
+            .on_hover_text(tr("rendering.rebuild_gpu.hover"))
+            .on_disabled_hover_text(tr("rendering.rebuild_gpu.disabled"))
+            .clicked()
+        {
+            health.request_rebuild();
+        }
+        if health.rebuilds() > 0 {
+            ui.weak(trf("rendering.rebuilds", &[("count", &health.rebuilds())]));
+        }
+    });
+}
+
+/// Proxy globe toggle and bake progress.
+fn render_proxy_globe(ui: &mut egui::Ui, config: &mut ProxyGlobeConfig, bake: &ProxyGlobeBake) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut config.enabled, tr("rendering.proxy_globe"))
+            .on_hover_text(tr("rendering.proxy_globe.hover"));
+        let status = if bake.uses_base_map() {
+            tr("rendering.proxy_globe.base_map").to_owned()
+        } else if bake.done() {
+            trf(
+                "rendering.proxy_globe.baked",
+                &[("count", &bake.nodes_baked())],
+            )
+        } else {
+            trf(
+                "rendering.proxy_globe.baking",
+                &[("count", &bake.nodes_baked())],
+            )
+        };
+        ui.weak(status);
+        if bake.nodes_failed() > 0 {
+            ui.weak(trf(
+                "rendering.proxy_globe.failed",
+                &[("count", &bake.nodes_failed())],
+            ));
+        }
+    });
+}
+
+/// Ambient occlusion toggle, quality, strength, thickness and fade.
+fn render_ambient_occlusion(ui: &mut egui::Ui, config: &mut AmbientOcclusionConfig) {
+    ui.checkbox(&mut config.enabled, tr("rendering.ao"))
+        .on_hover_text(tr("rendering.ao.hover"));
+    ui.add_enabled_ui(config.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.ao_quality"));
+            egui::ComboBox::from_id_salt("ambient_occlusion_quality")
+                .selected_text(occlusion_quality_label(config.quality))
+                .show_ui(ui, |ui| {
+                    for quality in AmbientOcclusionQuality::ALL {
+                        ui.selectable_value(
+                            &mut config.quality,
+                            quality,
+                            occlusion_quality_label(quality),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.ao_strength"));
+            ui.add(egui::Slider::new(&mut config.strength, 0.0..=1.0).fixed_decimals(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.ao_thickness"));
+            ui.add(
+                egui::Slider::new(&mut config.thickness_m, 0.05..=4.0)
+                    .logarithmic(true)
+                    .suffix(" m"),
+            )
+            .on_hover_text(tr("rendering.ao_thickness.hover"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("rendering.ao_fade"));
+            ui.add(
+                egui::DragValue::new(&mut config.fade_start_m)
+                    .range(0.0..=10_000.0)
+                    .suffix(" m"),
+            );
+            ui.label(tr("rendering.ao_fade.to"));
+            ui.add(
+                egui::DragValue::new(&mut config.fade_end_m)
+                    .range(0.0..=20_000.0)
+                    .suffix(" m"),
             );
+        })
+        .response
+        .on_hover_text(tr("rendering.ao_fade.hover"));
     });
 }
+
+/// The localized name of an ambient occlusion quality level.
+fn occlusion_quality_label(quality: AmbientOcclusionQuality) -> &'static str {
+    match quality {
+        AmbientOcclusionQuality::Low => tr("settings.preset.low"),
+        AmbientOcclusionQuality::Medium => tr("settings.preset.medium"),
+        AmbientOcclusionQuality::High => tr("settings.preset.high"),
+        AmbientOcclusionQuality::Ultra => tr("rendering.ao_quality.ultra"),
+    }
+}
+
+// ============================================================================
+// Terrain debug view
+// ============================================================================
+
+/// Terrain debug view picker.
+fn render_terrain_debug_view(ui: &mut egui::Ui, view: &mut TerrainDebugView) {
+    ui.horizontal(|ui| {
+        ui.label(tr("rendering.debug_view"));
+        let mut selected = *view;
+        egui::ComboBox::from_id_salt("terrain_debug_view")
+            .selected_text(debug_view_label(selected))
+            .show_ui(ui, |ui| {
+                for option in TerrainDebugView::ALL {
+                    ui.selectable_value(&mut selected, option, debug_view_label(option));
+                }
+            });
+        // Only write on an actual pick, so the view isn't marked changed
+        // every frame the tab is open.
+        if selected != *view {
+            *view = selected;
+        }
+    })
+    .response
+    .on_hover_text(tr("rendering.debug_view.hover"));
+}
+
+/// The localized name of a terrain debug view.
+fn debug_view_label(view: TerrainDebugView) -> &'static str {
+    match view {
+        TerrainDebugView::Off => tr("rendering.debug_view.off"),
+        TerrainDebugView::Wireframe => tr("rendering.debug_view.wireframe"),
+        TerrainDebugView::UvChecker => tr("rendering.debug_view.uv_checker"),
+        TerrainDebugView::TexelDensity => tr("rendering.debug_view.texel_density"),
+        TerrainDebugView::Overdraw => tr("rendering.debug_view.overdraw"),
+        TerrainDebugView::Slope => tr("rendering.debug_view.slope"),
+        TerrainDebugView::Aspect => tr("rendering.debug_view.aspect"),
+    }
+}
+
+/// Band controls and a legend for the slope and aspect views.
+fn render_terrain_analysis(
+    ui: &mut egui::Ui,
+    view: TerrainDebugView,
+    analysis: &mut TerrainAnalysis,
+) {
+    const SLOPE_COLORS: [egui::Color32; 4] = [
+        egui::Color32::from_rgb(242, 230, 26),
+        egui::Color32::from_rgb(242, 140, 13),
+        egui::Color32::from_rgb(230, 26, 13),
+        egui::Color32::from_rgb(140, 26, 191),
+    ];
+
+    // Edit a copy and write back only on change, so the materials aren't
+    // rewritten every frame the tab is open.
+    let mut edited = *analysis;
+    if view == TerrainDebugView::Slope {
+        let edges = edited.slope_bands_deg;
+        for (i, edge) in edited.slope_bands_deg.iter_mut().enumerate() {
+            let low = if i == 0 { 0.0 } else { edges[i - 1] };
+            let high = edges.get(i + 1).copied().unwrap_or(90.0);
+            ui.horizontal(|ui| {
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, SLOPE_COLORS[i]);
+                ui.add(
+                    egui::Slider::new(edge, low..=high)
+                        .suffix("°")
+                        .fixed_decimals(0)
+                        .text(tr("rendering.slope_band")),
+                );
+            });
+        }
+    } else {
+        ui.horizontal(|ui| {
+            for (label, turn) in [
+                (tr("dir.n"), 0.0),
+                (tr("dir.e"), 0.25),
+                (tr("dir.s"), 0.5),
+                (tr("dir.w"), 0.75),
+            ] {
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, hue_color(turn));
+                ui.label(label);
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut edited.aspect_min_slope_deg, 0.0..=30.0)
+                .suffix("°")
+                .fixed_decimals(0)
+                .text(tr("rendering.aspect_min_slope")),
+        );
+    }
+    ui.add(egui::Slider::new(&mut edited.opacity, 0.0..=1.0).text(tr("rendering.band_opacity")));
+    if ui.button(tr("rendering.reset_bands")).clicked() {
+        edited = TerrainAnalysis::default();
+    }
+    if edited != *analysis {
+        *analysis = edited;
+    }
+}
+
+/// The aspect view's fully saturated hue at `turn` (0 = north, clockwise),
+/// matching `terrain_material.wgsl`.
+fn hue_color(turn: f32) -> egui::Color32 {
+    let k = turn.rem_euclid(1.0) * 6.0;
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0) as u8;
+    egui::Color32::from_rgb(
+        channel((k - 3.0).abs() - 1.0),
+        channel(2.0 - (k - 2.0).abs()),
+        channel(2.0 - (k - 4.0).abs()),
+    )
+}
+
+/// Cycle the terrain debug view on [`CameraAction::CycleTerrainDebug`].
+pub(super) fn cycle_terrain_debug_view(
+    action_query: Query<&ActionState<CameraAction>>,
+    mut view: ResMut<TerrainDebugView>,
+) {
+    let Ok(action_state) = action_query.single() else {
+        return;
+    };
+    if action_state.just_pressed(&CameraAction::CycleTerrainDebug) {
+        *view = view.next();
+    }
+}
+
+/// Name the active terrain debug view in the bottom-left corner, so odd
+/// terrain colours aren't mistaken for a rendering bug.
+pub(super) fn draw_terrain_debug_badge(
+    mut contexts: EguiContexts,
+    view: Res<TerrainDebugView>,
+) -> Result {
+    if *view == TerrainDebugView::Off {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    egui::Area::new(egui::Id::new("terrain_debug_badge"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(trf(
+                    "rendering.debug_badge",
+                    &[("view", &debug_view_label(*view))],
+                ));
+            });
+        });
+    Ok(())
+}
@@ -0,0 +1,276 @@
+//! Satellite overlay: moving markers for bright satellites.
+//!
+//! Propagates two-line element sets with SGP4 ([`veldera_sky::satellites`]) to
+//! the in-world clock and draws each satellite as a labelled screen-space
+//! marker. Satellites behind the Earth are dropped; those in the Earth's
+//! shadow are drawn dim, so after dusk the bright ones are the ones you could
+//! see with the naked eye. A bundled file of approximate element sets works
+//! offline, and the Location tab can replace it with current sets from
+//! CelesTrak.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use glam::DVec3;
+
+use veldera_async::TaskSpawner;
+use veldera_constants::EARTH_RADIUS_M_F64;
+use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_places::{HttpClient, fetch_tles};
+use veldera_sky::{
+    satellites::{Satellite, TleError, parse_tles},
+    time_of_day::{Sun, TimeOfDayState, days_since_j2000},
+};
+
+use crate::UiVisible;
+
+/// The bundled element sets, in the three-line format.
+const BUNDLED_TLES: &str = include_str!("../assets/satellites.tle");
+
+/// Marker colour for a sunlit satellite.
+const SUNLIT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 245, 200);
+
+/// Marker colour for a satellite in the Earth's shadow.
+const ECLIPSED_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 120, 150);
+
+/// Plugin: loads the bundled element sets and draws the markers.
+pub struct SatelliteOverlayPlugin;
+
+impl Plugin for SatelliteOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SatelliteOverlay>()
+            .add_systems(Update, receive_fetched_tles)
+            .add_systems(
+                EguiPrimaryContextPass,
+                draw_satellites.run_if(
+                    |visible: Res<UiVisible>, overlay: Res<SatelliteOverlay>| {
+                        visible.0 && overlay.enabled
+                    },
+                ),
+            );
+    }
+}
+
+/// Settings and data for the satellite overlay.
+#[derive(Resource)]
+pub(super) struct SatelliteOverlay {
+    /// Whether markers are drawn.
+    pub enabled: bool,
+    satellites: Vec<Satellite>,
+    /// Whether the satellites came from CelesTrak rather than the bundle.
+    fetched: bool,
+    /// Whether a fetch is in flight.
+    fetching: bool,
+    /// Last fetch error.
+    error: Option<String>,
+    fetch_tx: async_channel::Sender<Result<String, String>>,
+    fetch_rx: async_channel::Receiver<Result<String, String>>,
+}
+
+impl Default for SatelliteOverlay {
+    fn default() -> Self {
+        let satellites = load_satellites(BUNDLED_TLES).unwrap_or_else(|e| {
+            error!("Failed to parse the bundled satellite elements: {e}");
+            Vec::new()
+        });
+        let (fetch_tx, fetch_rx) = async_channel::unbounded();
+        Self {
+            enabled: false,
+            satellites,
+            fetched: false,
+            fetching: false,
+            error: None,
+            fetch_tx,
+            fetch_rx,
+        }
+    }
+}
+
+impl SatelliteOverlay {
+    /// Number of satellites tracked.
+    pub fn count(&self) -> usize {
+        self.satellites.len()
+    }
+
+    /// Whether the satellites came from CelesTrak rather than the bundle.
+    pub fn is_fetched(&self) -> bool {
+        self.fetched
+    }
+
+    /// Whether a fetch is in flight.
+    pub fn is_fetching(&self) -> bool {
+        self.fetching
+    }
+
+    /// Last fetch error.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Start fetching current element sets; they replace the tracked
+    /// satellites when they arrive.
+    pub fn request_fetch(&mut self, http_client: &HttpClient, spawner: &TaskSpawner) {
+        if self.fetching {
+            return;
+        }
+        self.fetching = true;
+        self.error = None;
+        let tx = self.fetch_tx.clone();
+        let client = http_client.inner().clone();
+        spawner.spawn(async move {
+            let _ = tx.send(fetch_tles(&client).await).await;
+        });
+    }
+}
+
+/// Parse element sets and prepare each for propagation, skipping the
+/// deep-space orbits SGP4 can't handle.
+fn load_satellites(text: &str) -> Result<Vec<Satellite>, TleError> {
+    Ok(parse_tles(text)?
+        .into_iter()
+        .filter_map(|tle| Satellite::new(tle).ok())
+        .collect())
+}
+
+/// Swap in fetched element sets.
+fn receive_fetched_tles(mut overlay: ResMut<SatelliteOverlay>) {
+    while let Ok(result) = overlay.fetch_rx.try_recv() {
+        overlay.fetching = false;
+        match result.and_then(|text| load_satellites(&text).map_err(|e| e.to_string())) {
+            Ok(satellites) if !satellites.is_empty() => {
+                overlay.satellites = satellites;
+                overlay.fetched = true;
+            }
+            Ok(_) => overlay.error = Some("no near-Earth satellites in response".to_string()),
+            Err(e) => {
+                warn!("Satellite fetch failed: {e}");
+                overlay.error = Some(e);
+            }
+        }
+    }
+}
+
+/// Whether the Earth blocks the line of sight from `from` to `to` (ECEF).
+fn behind_earth(from: DVec3, to: DVec3) -> bool {
+    let segment = to - from;
+    // Closest point of the segment to the Earth's centre.
+    let t = (-from.dot(segment) / segment.length_squared()).clamp(0.0, 1.0);
+    (from + segment * t).length() < EARTH_RADIUS_M_F64
+}
+
+/// Whether a satellite at `position` (ECEF) is outside the Earth's
+/// cylindrical shadow, given the unit direction towards the sun.
+fn is_sunlit(position: DVec3, sun_direction: DVec3) -> bool {
+    let along = position.dot(sun_direction);
+    along > 0.0 || (position - sun_direction * along).length() > EARTH_RADIUS_M_F64
+}
+
+/// Resources for drawing the markers.
+#[derive(SystemParam)]
+struct SatelliteParams<'w, 's> {
+    contexts: EguiContexts<'w, 's>,
+    overlay: Res<'w, SatelliteOverlay>,
+    time_of_day: Res<'w, TimeOfDayState>,
+    sun_query: Query<'w, 's, &'static GlobalTransform, With<Sun>>,
+    camera_query: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static FloatingOriginCamera,
+        ),
+    >,
+}
+
+/// Draw a labelled marker for every satellite in view.
+fn draw_satellites(mut params: SatelliteParams) -> Result {
+    let Ok((camera, camera_transform, origin)) = params.camera_query.single() else {
+        return Ok(());
+    };
+    let camera_position = origin.position;
+    let days = days_since_j2000(
+        params.time_of_day.current_date(),
+        params.time_of_day.current_utc_seconds(),
+    );
+    // The sun light shines along its forward axis, away from the sun.
+    let sun_direction = params
+        .sun_query
+        .single()
+        .map(|transform| transform.back().as_dvec3())
+        .ok();
+
+    let ctx = params.contexts.ctx_mut()?;
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("satellites"),
+    ));
+    let font = egui::FontId::proportional(12.0);
+    for satellite in &params.overlay.satellites {
+        let Some(position) = satellite.position_ecef(days) else {
+            continue;
+        };
+        if behind_earth(camera_position, position) {
+            continue;
+        }
+        // The camera sits at the floating origin, so satellites are drawn
+        // camera-relative.
+        let relative = (position - camera_position).as_vec3();
+        let Ok(screen) = camera.world_to_viewport(camera_transform, relative) else {
+            continue;
+        };
+        let anchor = egui::pos2(screen.x, screen.y);
+
+        let color = if sun_direction.is_none_or(|sun| is_sunlit(position, sun)) {
+            SUNLIT_COLOR
+        } else {
+            ECLIPSED_COLOR
+        };
+        painter.circle_stroke(anchor, 4.0, egui::Stroke::new(1.5, color));
+        painter.circle_filled(anchor, 1.5, color);
+
+        let offset = egui::vec2(7.0, -7.0);
+        let name = &satellite.tle.name;
+        let shadow = painter.layout_no_wrap(name.clone(), font.clone(), egui::Color32::BLACK);
+        painter.galley(
+            anchor + offset + egui::vec2(1.0, 1.0),
+            shadow,
+            egui::Color32::BLACK,
+        );
+        let galley = painter.layout_no_wrap(name.clone(), font.clone(), color);
+        painter.galley(anchor + offset, galley, color);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_elements_propagate() {
+        let satellites = load_satellites(BUNDLED_TLES).unwrap();
+        assert_eq!(satellites.len(), 8);
+        for satellite in &satellites {
+            let position = satellite.position_ecef(satellite.tle.epoch_days).unwrap();
+            let altitude = position.length() - EARTH_RADIUS_M_F64;
+            assert!(
+                (300_000.0..1_000_000.0).contains(&altitude),
+                "{}: {altitude} m",
+                satellite.tle.name
+            );
+        }
+    }
+
+    #[test]
+    fn earth_hides_and_shadows() {
+        let r = EARTH_RADIUS_M_F64;
+        let sun = DVec3::X;
+        // Over the night side, inside the shadow; over the terminator, lit.
+        assert!(!is_sunlit(DVec3::new(-r - 400_000.0, 0.0, 0.0), sun));
+        assert!(is_sunlit(DVec3::new(-r, r + 400_000.0, 0.0), sun));
+        // Seen from the far side of the planet, hidden; from nearby, not.
+        let satellite = DVec3::new(0.0, 0.0, r + 400_000.0);
+        assert!(behind_earth(DVec3::new(0.0, 0.0, -r - 10.0), satellite));
+        assert!(!behind_earth(DVec3::new(0.0, 1_000.0, r + 10.0), satellite));
+    }
+}
@@ -0,0 +1,294 @@
+//! Search result pins in the 3D world.
+//!
+//! Every geocoding result gets a pin entity at its lat/lon, placed through
+//! the floating origin via [`WorldPosition`], plus an egui label projected
+//! above it that teleports there when clicked. Results carry no elevation, so
+//! pins start at sea level and snap to the ground once terrain colliders load
+//! beneath them. Pins scale with distance from the camera so they keep a
+//! constant on-screen size, and are despawned when the search is cleared.
+//!
+//! The pin meshes ([`PinAssets`]) and the constant-size scaling
+//! ([`ScreenSizedPin`]) are shared with the user annotations.
+
+use avian3d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use veldera_async::TaskSpawner;
+use veldera_game_teleport::TeleportState;
+use veldera_geo::{
+    coords::{RadialFrame, lat_lon_to_ecef},
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+use veldera_physics::{GameLayer, PhysicsState};
+use veldera_places::{GeocodingResult, GeocodingState, HttpClient};
+
+use crate::{UiVisible, i18n::fmt_distance};
+
+/// Pin height as a fraction of its distance from the camera.
+const PIN_SCALE_PER_METRE: f32 = 0.04;
+
+/// Smallest pin height (m), so a pin the camera is standing next to stays
+/// legible rather than shrinking to nothing.
+const MIN_PIN_SCALE: f32 = 4.0;
+
+/// Pins farther than this from the camera (m) aren't ground-snapped; no
+/// terrain colliders exist that far out.
+const GROUND_SNAP_RANGE: f64 = 20_000.0;
+
+/// Height above sea level (m) the ground-snap ray starts from, above the
+/// highest terrain.
+const GROUND_RAY_START_HEIGHT: f64 = 9_000.0;
+
+/// Plugin: keeps pins in sync with the geocoding results and draws their
+/// labels.
+pub struct SearchPinsPlugin;
+
+impl Plugin for SearchPinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, init_pin_assets)
+            .add_systems(
+                Update,
+                (sync_search_pins, ground_search_pins, scale_pins).chain(),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                draw_pin_labels.run_if(|visible: Res<UiVisible>| visible.0),
+            );
+    }
+}
+
+/// A pin marking one geocoding result.
+#[derive(Component, Debug)]
+struct SearchPin {
+    /// The result this pin marks.
+    result: GeocodingResult,
+    /// Whether the pin has been snapped to loaded terrain.
+    grounded: bool,
+}
+
+/// Marks a pin entity scaled with its distance from the camera so it keeps a
+/// constant on-screen size.
+#[derive(Component, Default)]
+pub(crate) struct ScreenSizedPin;
+
+/// Shared pin meshes and material.
+#[derive(Resource)]
+pub(crate) struct PinAssets {
+    /// Unit-height stalk, base at the origin.
+    pub stalk: Handle<Mesh>,
+    /// Head at the top of the stalk.
+    pub head: Handle<Mesh>,
+    /// Search-pin material.
+    material: Handle<StandardMaterial>,
+}
+
+/// Create the shared pin assets on startup.
+fn init_pin_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let color = Color::srgb(1.0, 0.35, 0.2);
+    commands.insert_resource(PinAssets {
+        stalk: meshes.add(
+            Cylinder::new(0.03, 1.0)
+                .mesh()
+                .build()
+                .translated_by(Vec3::Y * 0.5),
+        ),
+        head: meshes.add(Sphere::new(0.12).mesh().build().translated_by(Vec3::Y)),
+        material: materials.add(StandardMaterial {
+            base_color: color,
+            emissive: color.to_linear() * 0.5,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Respawn the pins whenever the results change; an empty result list (a
+/// cleared search) leaves no pins.
+fn sync_search_pins(
+    mut commands: Commands,
+    geocoding: Res<GeocodingState>,
+    assets: Option<Res<PinAssets>>,
+    pin_query: Query<(Entity, &SearchPin)>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if !geocoding.is_changed() {
+        return;
+    }
+    let unchanged = pin_query.iter().count() == geocoding.results.len()
+        && pin_query.iter().all(|(_, pin)| {
+            geocoding
+                .results
+                .iter()
+                .any(|r| r.lat == pin.result.lat && r.lon == pin.result.lon)
+        });
+    if unchanged {
+        return;
+    }
+
+    for (entity, _) in &pin_query {
+        commands.entity(entity).despawn();
+    }
+    for result in &geocoding.results {
+        let position = lat_lon_to_ecef(
+            result.lat,
+            result.lon,
+            veldera_constants::EARTH_RADIUS_M_F64,
+        );
+        let up = RadialFrame::from_ecef_position(position).up;
+        commands
+            .spawn((
+                Name::new(format!("Search pin: {}", result.display_name)),
+                SearchPin {
+                    result: result.clone(),
+                    grounded: false,
+                },
+                Transform::from_rotation(Quat::from_rotation_arc(Vec3::Y, up)),
+                Visibility::default(),
+                WorldPosition::from_dvec3(position),
+                ScreenSizedPin,
+            ))
+            .with_children(|pin| {
+                pin.spawn((
+                    Mesh3d(assets.stalk.clone()),
+                    MeshMaterial3d(assets.material.clone()),
+                ));
+                pin.spawn((
+                    Mesh3d(assets.head.clone()),
+                    MeshMaterial3d(assets.material.clone()),
+                ));
+            });
+    }
+}
+
+/// Snap pins onto the terrain once colliders have loaded under them.
+fn ground_search_pins(
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
+    mut pin_query: Query<(&mut SearchPin, &mut WorldPosition)>,
+) {
+    let Some(origin) = physics_state.origin_camera_position() else {
+        return;
+    };
+    let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground]);
+    for (mut pin, mut world_pos) in &mut pin_query {
+        if pin.grounded || world_pos.position.distance(origin) > GROUND_SNAP_RANGE {
+            continue;
+        }
+        let up = world_pos.position.normalize();
+        let start_ecef = up * (veldera_constants::EARTH_RADIUS_M_F64 + GROUND_RAY_START_HEIGHT);
+        let start = (start_ecef - origin).as_vec3();
+        let Ok(down) = Dir3::new(-up.as_vec3()) else {
+            continue;
+        };
+        if let Some(hit) = spatial_query.cast_ray(
+            start,
+            down,
+            (GROUND_RAY_START_HEIGHT * 2.0) as f32,
+            true,
+            &filter,
+        ) {
+            world_pos.position = start_ecef - up * f64::from(hit.distance);
+            pin.grounded = true;
+        }
+    }
+}
+
+/// Scale each pin with its distance from the camera so it stays the same
+/// size on screen.
+pub(crate) fn scale_pins(
+    camera_query: Query<&FloatingOriginCamera>,
+    mut pin_query: Query<(&WorldPosition, &mut Transform), With<ScreenSizedPin>>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    for (world_pos, mut transform) in &mut pin_query {
+        let distance = world_pos.position.distance(camera.position) as f32;
+        transform.scale = Vec3::splat((distance * PIN_SCALE_PER_METRE).max(MIN_PIN_SCALE));
+    }
+}
+
+/// Resources for drawing and clicking pin labels.
+#[derive(SystemParam)]
+struct PinLabelParams<'w, 's> {
+    contexts: EguiContexts<'w, 's>,
+    teleport_state: ResMut<'w, TeleportState>,
+    http_client: Res<'w, HttpClient>,
+    spawner: TaskSpawner<'w, 's>,
+    camera_query: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static FloatingOriginCamera,
+        ),
+    >,
+    pin_query: Query<
+        'w,
+        's,
+        (
+            &'static SearchPin,
+            &'static WorldPosition,
+            &'static GlobalTransform,
+        ),
+    >,
+}
+
+/// Draw a clickable label above each on-screen pin; clicking one teleports
+/// there.
+fn draw_pin_labels(mut params: PinLabelParams) -> Result {
+    let Ok((camera, camera_transform, origin)) = params.camera_query.single() else {
+        return Ok(());
+    };
+    let camera_position = origin.position;
+
+    let mut clicked: Option<(f64, f64)> = None;
+    let ctx = params.contexts.ctx_mut()?;
+    for (index, (pin, world_pos, transform)) in params.pin_query.iter().enumerate() {
+        // Skip pins on the far side of the globe; the viewport projection
+        // alone would draw them through it.
+        if (camera_position - world_pos.position).dot(world_pos.position) <= 0.0 {
+            continue;
+        }
+        // Anchor the label at the pin head.
+        let head = transform.transform_point(Vec3::Y * 1.15);
+        let Ok(screen) = camera.world_to_viewport(camera_transform, head) else {
+            continue;
+        };
+        let name = pin
+            .result
+            .display_name
+            .split(',')
+            .next()
+            .unwrap_or(&pin.result.display_name);
+        let distance = fmt_distance(world_pos.position.distance(camera_position));
+
+        egui::Area::new(egui::Id::new(("search_pin", index)))
+            .fixed_pos(egui::pos2(screen.x, screen.y))
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .show(ctx, |ui| {
+                if ui
+                    .button(format!("{name} ({distance})"))
+                    .on_hover_text(&pin.result.display_name)
+                    .clicked()
+                {
+                    clicked = Some((pin.result.lat, pin.result.lon));
+                }
+            });
+    }
+
+    if let Some((lat, lon)) = clicked {
+        params
+            .teleport_state
+            .request(lat, lon, &params.http_client, &params.spawner);
+    }
+    Ok(())
+}
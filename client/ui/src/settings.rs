@@ -0,0 +1,1078 @@
+//! User settings that persist across runs, and the Settings tab.
+//!
+//! [`UserSettings`] holds the UI language, accessibility options, camera
+//! overrides, graphics preset, sun shadow quality, network limits, key
+//! bindings, and the debug UI's visibility and dock layout. It's stored as
+//! JSON in `<OS config dir>/veldera/settings.json` on native and in the
+//! page's `localStorage` on the web. `main` loads it before any plugin
+//! builds, so the camera spawns with the saved FoV and bindings;
+//! [`SettingsPlugin`] loads it itself if the host didn't.
+//!
+//! Camera, graphics, and network values are overrides on top of the TOML configs: a
+//! field left unset follows the file, and a set one is re-applied whenever
+//! its file (re)loads. The Settings tab edits the overrides and bindings;
+//! the UI's visibility and layout are picked up as they change. Changes are
+//! written back after a short debounce.
+
+#[cfg(not(target_family = "wasm"))]
+use std::path::{Path, PathBuf};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{EguiContext, EguiContextSettings, PrimaryEguiContext, egui};
+use egui_dock::DockState;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use veldera_engine::resolution::DynamicResolutionConfig;
+use veldera_game_camera::{CameraConfig, TeleportAnimationMode};
+use veldera_game_input::{
+    ButtonBinding, CameraAction, CameraBindings, REBINDABLE_CAMERA_ACTIONS, camera_input_map,
+    default_camera_bindings,
+};
+use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_physics::DebugPalette;
+use veldera_places::LiveWeather;
+use veldera_sky::sun_shadows::SunShadowQuality;
+use veldera_terrain::{
+    ambient_occlusion::{AmbientOcclusionConfig, AmbientOcclusionQuality},
+    lod::{LodTuning, TextureQuality},
+    network::NetworkTuning,
+};
+
+use crate::{
+    DebugTab, DebugUiState, UiVisible,
+    camera::CameraParams,
+    i18n::{self, Language, tr, trf},
+    streaming::StreamingParams,
+};
+
+/// How long the settings must stay unchanged before they're written (s).
+const SAVE_DEBOUNCE_S: f32 = 1.0;
+
+/// Range of the UI scale slider.
+const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.0;
+
+/// Plugin: seeds the UI from the saved settings, re-applies the overrides
+/// when their configs reload, and saves changes.
+///
+/// Added by [`DebugUiPlugin`](crate::DebugUiPlugin) ahead of its own
+/// `init_resource` calls, so the restored [`UiVisible`] and
+/// [`DebugUiState`] take precedence over the defaults.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<UserSettings>() {
+            app.insert_resource(UserSettings::load());
+        }
+        let settings = app.world().resource::<UserSettings>();
+        i18n::set_language(settings.language);
+        let ui_visible = UiVisible(settings.ui.visible);
+        let ui_state = DebugUiState {
+            dock_state: restore_dock(settings)
+                .unwrap_or_else(|| DockState::new(DebugTab::ALL.to_vec())),
+            ..default()
+        };
+        app.insert_resource(ui_visible)
+            .insert_resource(ui_state)
+            .add_systems(PostUpdate, (apply_user_settings, apply_accessibility))
+            .add_systems(Last, save_user_settings);
+    }
+}
+
+// ============================================================================
+// Data
+// ============================================================================
+
+/// Everything remembered between runs.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub language: Language,
+    pub accessibility: AccessibilitySettings,
+    pub camera: CameraSettings,
+    /// Graphics preset; `None` follows the LOD and dynamic resolution configs.
+    pub graphics_preset: Option<GraphicsPreset>,
+    pub sun_shadows: SunShadowQuality,
+    pub network: NetworkSettings,
+    pub ui: UiSettings,
+    /// Rebound camera actions; the rest keep their default buttons.
+    pub bindings: CameraBindings,
+}
+
+/// UI scale, contrast, and the gizmo and plot palette.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// egui scale factor, on top of the window's DPI scaling.
+    pub ui_scale: f32,
+    /// High-contrast theme: white text and outlines on black.
+    pub high_contrast: bool,
+    pub palette: DebugPalette,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            high_contrast: false,
+            palette: DebugPalette::default(),
+        }
+    }
+}
+
+/// Overrides for [`CameraConfig`]; `None` follows `camera.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    /// Flycam base speed (m/s).
+    pub base_speed: Option<f32>,
+    /// Look sensitivity (radians per pixel).
+    pub mouse_sensitivity: Option<f32>,
+    /// Vertical field of view (degrees).
+    pub fov_deg: Option<f32>,
+    pub teleport_animation_mode: Option<TeleportAnimationMode>,
+}
+
+impl CameraSettings {
+    fn apply(&self, config: &mut CameraConfig) {
+        if let Some(speed) = self.base_speed {
+            config.base_speed = speed.clamp(config.min_speed, config.max_speed);
+        }
+        if let Some(sensitivity) = self.mouse_sensitivity {
+            config.mouse_sensitivity = sensitivity;
+        }
+        if let Some(mode) = self.teleport_animation_mode {
+            config.teleport_animation_mode = mode;
+        }
+    }
+}
+
+/// A bundle of streaming and resolution settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsPreset {
+    Low,
+    Medium,
+    High,
+}
+
+impl GraphicsPreset {
+    const ALL: [GraphicsPreset; 3] = [Self::Low, Self::Medium, Self::High];
+
+    fn label(self) -> &'static str {
+        match self {
+            GraphicsPreset::Low => tr("settings.preset.low"),
+            GraphicsPreset::Medium => tr("settings.preset.medium"),
+            GraphicsPreset::High => tr("settings.preset.high"),
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            GraphicsPreset::Low => tr("settings.preset.low.hover"),
+            GraphicsPreset::Medium => tr("settings.preset.medium.hover"),
+            GraphicsPreset::High => tr("settings.preset.high.hover"),
+        }
+    }
+
+    fn texture_quality(self) -> TextureQuality {
+        match self {
+            GraphicsPreset::Low => TextureQuality::Quarter,
+            GraphicsPreset::Medium => TextureQuality::Half,
+            GraphicsPreset::High => TextureQuality::Full,
+        }
+    }
+
+    /// Dynamic resolution `(min_scale, max_scale)`.
+    fn scale_limits(self) -> (f32, f32) {
+        match self {
+            GraphicsPreset::Low => (0.5, 0.75),
+            GraphicsPreset::Medium => (0.5, 1.0),
+            GraphicsPreset::High => (0.75, 1.0),
+        }
+    }
+
+    /// Ambient occlusion quality and strength; `None` turns it off.
+    fn ambient_occlusion(self) -> Option<(AmbientOcclusionQuality, f32)> {
+        match self {
+            GraphicsPreset::Low => None,
+            GraphicsPreset::Medium => Some((AmbientOcclusionQuality::Low, 0.7)),
+            GraphicsPreset::High => Some((AmbientOcclusionQuality::High, 0.9)),
+        }
+    }
+}
+
+/// Overrides for the LOD config's [`NetworkTuning`] (`None` follows
+/// `lod.toml`), and the opt-in for online services beyond the tiles.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Download cap (KiB/s); 0 is unlimited.
+    pub bandwidth_limit_kib_per_sec: Option<f64>,
+    pub metered: Option<bool>,
+    /// Fetch the current weather at the camera, which sends its coarse
+    /// location to Open-Meteo. Off unless chosen.
+    pub live_weather: bool,
+}
+
+impl NetworkSettings {
+    fn apply(&self, tuning: &mut NetworkTuning) {
+        if let Some(limit) = self.bandwidth_limit_kib_per_sec {
+            tuning.bandwidth_limit_kib_per_sec = limit.max(0.0);
+        }
+        if let Some(metered) = self.metered {
+            tuning.metered = metered;
+        }
+    }
+}
+
+/// Debug UI visibility and dock layout.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    pub visible: bool,
+    /// The serialized [`DockState`]. Kept as a JSON value so a layout from
+    /// a build with different tabs is dropped on its own instead of failing
+    /// the whole file.
+    dock: Option<serde_json::Value>,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            dock: None,
+        }
+    }
+}
+
+impl UserSettings {
+    /// Load the saved settings; a missing or unreadable store gives the
+    /// defaults.
+    pub fn load() -> Self {
+        Self::from_stored(read_stored())
+    }
+
+    /// The settings in what the store returned: the defaults if it was empty,
+    /// unreadable, or held something that isn't settings JSON.
+    fn from_stored(stored: Result<Option<String>, String>) -> Self {
+        match stored {
+            Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable user settings: {e}");
+                Self::default()
+            }),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                warn!("Failed to read user settings: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_stored(&self.to_json()?)
+    }
+
+    fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// The saved dock layout, with any tab it lacks added to the first leaf.
+fn restore_dock(settings: &UserSettings) -> Option<DockState<DebugTab>> {
+    let value = settings.ui.dock.clone()?;
+    let mut dock: DockState<DebugTab> = serde_json::from_value(value)
+        .inspect_err(|e| warn!("Ignoring saved UI layout: {e}"))
+        .ok()?;
+    for tab in DebugTab::ALL {
+        if dock.find_tab(&tab).is_none() {
+            dock.push_to_first_leaf(tab);
+        }
+    }
+    Some(dock)
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// `<OS config dir>/veldera/settings.json`.
+#[cfg(not(target_family = "wasm"))]
+fn settings_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("veldera").join("settings.json"))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_stored() -> Result<Option<String>, String> {
+    settings_path().map_or(Ok(None), |path| read_file(&path))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_stored(json: &str) -> Result<(), String> {
+    write_file(&settings_path().ok_or("no config directory")?, json)
+}
+
+/// The contents of `path`, or `None` if there's no such file.
+#[cfg(not(target_family = "wasm"))]
+fn read_file(path: &Path) -> Result<Option<String>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => Ok(Some(json)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Write `json` to `path`, creating its directory.
+#[cfg(not(target_family = "wasm"))]
+fn write_file(path: &Path, json: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn storage_location() -> String {
+    settings_path().map_or_else(
+        || tr("settings.no_config_dir").to_string(),
+        |path| trf("settings.saved_to", &[("path", &path.display())]),
+    )
+}
+
+/// `localStorage` key holding the settings JSON.
+#[cfg(target_family = "wasm")]
+const STORAGE_KEY: &str = "veldera.settings";
+
+/// Call `localStorage.<method>(args…)`.
+#[cfg(target_family = "wasm")]
+fn call_local_storage(method: &str, args: &js_sys::Array) -> Result<wasm_bindgen::JsValue, String> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let storage = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("localStorage"))
+        .ok()
+        .filter(|storage| !storage.is_undefined() && !storage.is_null())
+        .ok_or("localStorage unavailable")?;
+    let function: js_sys::Function = js_sys::Reflect::get(&storage, &JsValue::from_str(method))
+        .map_err(|e| format!("{e:?}"))?
+        .dyn_into()
+        .map_err(|e| format!("{e:?}"))?;
+    function.apply(&storage, args).map_err(|e| format!("{e:?}"))
+}
+
+#[cfg(target_family = "wasm")]
+fn read_stored() -> Result<Option<String>, String> {
+    let args = js_sys::Array::of1(&STORAGE_KEY.into());
+    Ok(call_local_storage("getItem", &args)?.as_string())
+}
+
+#[cfg(target_family = "wasm")]
+fn write_stored(json: &str) -> Result<(), String> {
+    let args = js_sys::Array::of2(&STORAGE_KEY.into(), &json.into());
+    call_local_storage("setItem", &args).map(drop)
+}
+
+#[cfg(target_family = "wasm")]
+fn storage_location() -> String {
+    tr("settings.saved_local").to_string()
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Push the overrides into their configs when the settings change or a
+/// config (re)loads, and rebuild the camera input map when the bindings
+/// change.
+#[allow(clippy::too_many_arguments)]
+fn apply_user_settings(
+    settings: Res<UserSettings>,
+    mut camera_events: MessageReader<AssetEvent<CameraConfig>>,
+    mut lod_events: MessageReader<AssetEvent<LodTuning>>,
+    mut resolution_events: MessageReader<AssetEvent<DynamicResolutionConfig>>,
+    mut occlusion_events: MessageReader<AssetEvent<AmbientOcclusionConfig>>,
+    mut camera_config: ResMut<CameraConfig>,
+    mut lod_tuning: ResMut<LodTuning>,
+    mut resolution_config: ResMut<DynamicResolutionConfig>,
+    mut occlusion_config: ResMut<AmbientOcclusionConfig>,
+    mut shadow_quality: ResMut<SunShadowQuality>,
+    mut live_weather: ResMut<LiveWeather>,
+    mut projections: Query<&mut Projection, With<FloatingOriginCamera>>,
+    mut input_maps: Query<&mut InputMap<CameraAction>>,
+) {
+    let changed = settings.is_changed();
+    if changed {
+        i18n::set_language(settings.language);
+    }
+
+    if reloaded(&mut camera_events) || changed {
+        settings.camera.apply(&mut camera_config);
+        if let Some(fov_deg) = settings.camera.fov_deg {
+            let fov = fov_deg
+                .clamp(camera_config.min_fov_deg, camera_config.max_fov_deg)
+                .to_radians();
+            for mut projection in &mut projections {
+                if let Projection::Perspective(p) = &mut *projection {
+                    p.fov = fov;
+                }
+            }
+        }
+    }
+
+    let lod_reloaded = reloaded(&mut lod_events);
+    if lod_reloaded || changed {
+        settings.network.apply(&mut lod_tuning.network);
+    }
+
+    if let Some(preset) = settings.graphics_preset {
+        if lod_reloaded || changed {
+            lod_tuning.texture_quality = preset.texture_quality();
+        }
+        if reloaded(&mut resolution_events) || changed {
+            let (min_scale, max_scale) = preset.scale_limits();
+            resolution_config.enabled = true;
+            resolution_config.min_scale = min_scale;
+            resolution_config.max_scale = max_scale;
+        }
+        if reloaded(&mut occlusion_events) || changed {
+            let occlusion = preset.ambient_occlusion();
+            occlusion_config.enabled = occlusion.is_some();
+            if let Some((quality, strength)) = occlusion {
+                occlusion_config.quality = quality;
+                occlusion_config.strength = strength;
+            }
+        }
+    }
+
+    if changed && *shadow_quality != settings.sun_shadows {
+        *shadow_quality = settings.sun_shadows;
+    }
+
+    if changed && live_weather.enabled != settings.network.live_weather {
+        live_weather.enabled = settings.network.live_weather;
+    }
+
+    // Cameras spawned later get the bindings from `main`.
+    if changed {
+        let input_map = camera_input_map(&settings.bindings);
+        for mut map in &mut input_maps {
+            if *map != input_map {
+                *map = input_map.clone();
+            }
+        }
+    }
+}
+
+/// Push the accessibility options into egui and the [`DebugPalette`] when
+/// they change, and into the primary egui context when it's created.
+fn apply_accessibility(
+    settings: Res<UserSettings>,
+    mut palette: ResMut<DebugPalette>,
+    mut contexts: Query<(
+        Ref<PrimaryEguiContext>,
+        &mut EguiContextSettings,
+        &EguiContext,
+    )>,
+) {
+    let accessibility = &settings.accessibility;
+    if settings.is_changed() && *palette != accessibility.palette {
+        *palette = accessibility.palette;
+    }
+
+    for (primary, mut context_settings, context) in &mut contexts {
+        if !settings.is_changed() && !primary.is_added() {
+            continue;
+        }
+        let scale = accessibility
+            .ui_scale
+            .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+        if context_settings.scale_factor != scale {
+            context_settings.scale_factor = scale;
+        }
+        for theme in [egui::Theme::Dark, egui::Theme::Light] {
+            let visuals = if accessibility.high_contrast {
+                high_contrast_visuals()
+            } else {
+                theme.default_visuals()
+            };
+            context.get().set_visuals_of(theme, visuals);
+        }
+    }
+}
+
+/// White text and outlines on black, with yellow focus outlines, used for
+/// both egui themes.
+fn high_contrast_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(egui::Color32::WHITE);
+    visuals.panel_fill = egui::Color32::BLACK;
+    visuals.window_fill = egui::Color32::BLACK;
+    visuals.extreme_bg_color = egui::Color32::BLACK;
+    visuals.faint_bg_color = egui::Color32::from_gray(40);
+    visuals.window_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+    visuals.hyperlink_color = egui::Color32::from_rgb(120, 200, 255);
+    visuals.selection.bg_fill = egui::Color32::from_rgb(0, 90, 200);
+    visuals.selection.stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+
+    let widgets = &mut visuals.widgets;
+    widgets.noninteractive.bg_stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(200));
+    widgets.noninteractive.fg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+    for (widget, outline) in [
+        (&mut widgets.inactive, egui::Color32::from_gray(220)),
+        (&mut widgets.hovered, egui::Color32::YELLOW),
+        (&mut widgets.active, egui::Color32::YELLOW),
+        (&mut widgets.open, egui::Color32::WHITE),
+    ] {
+        widget.bg_fill = egui::Color32::from_gray(30);
+        widget.weak_bg_fill = egui::Color32::from_gray(30);
+        widget.bg_stroke = egui::Stroke::new(1.5, outline);
+        widget.fg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+    }
+    visuals
+}
+
+/// Whether any of `events` is a (re)load. Drains the reader.
+fn reloaded<A: Asset>(events: &mut MessageReader<AssetEvent<A>>) -> bool {
+    events.read().fold(false, |any, event| {
+        any | matches!(
+            event,
+            AssetEvent::Added { .. }
+                | AssetEvent::Modified { .. }
+                | AssetEvent::LoadedWithDependencies { .. }
+        )
+    })
+}
+
+/// Pick up the UI's visibility and layout, and write the settings once
+/// they've been left alone for [`SAVE_DEBOUNCE_S`].
+fn save_user_settings(
+    time: Res<Time>,
+    mut settings: ResMut<UserSettings>,
+    ui_visible: Res<UiVisible>,
+    ui_state: Res<DebugUiState>,
+    mut saved: Local<Option<UserSettings>>,
+    mut since_change: Local<f32>,
+) {
+    let saved = saved.get_or_insert_with(|| settings.clone());
+
+    if ui_visible.is_changed() && settings.ui.visible != ui_visible.0 {
+        settings.ui.visible = ui_visible.0;
+    }
+    if ui_state.is_changed() {
+        let dock = serde_json::to_value(&ui_state.dock_state).ok();
+        if settings.ui.dock != dock {
+            settings.ui.dock = dock;
+        }
+    }
+
+    if *settings == *saved {
+        *since_change = 0.0;
+        return;
+    }
+    if settings.is_changed() {
+        *since_change = 0.0;
+        return;
+    }
+    *since_change += time.delta_secs();
+    if *since_change < SAVE_DEBOUNCE_S {
+        return;
+    }
+    if let Err(e) = settings.save() {
+        warn!("Failed to save user settings: {e}");
+    }
+    *saved = settings.clone();
+}
+
+// ============================================================================
+// Settings tab
+// ============================================================================
+
+/// Resources for the Settings tab. The live camera config and projection
+/// are read through [`CameraParams`], and the LOD tuning and loader through
+/// [`StreamingParams`], which own them in the debug UI.
+#[derive(SystemParam)]
+pub(super) struct SettingsParams<'w, 's> {
+    pub settings: ResMut<'w, UserSettings>,
+    pub keys: Res<'w, ButtonInput<KeyCode>>,
+    pub mouse: Res<'w, ButtonInput<MouseButton>>,
+    /// The action waiting for a new button, if any.
+    pub rebinding: Local<'s, Option<CameraAction>>,
+    /// The UI scale being dragged to; applied on release so the slider
+    /// doesn't move under the pointer.
+    pub ui_scale_drag: Local<'s, Option<f32>>,
+}
+
+/// Render the Settings tab content.
+pub(super) fn render_settings_tab(
+    ui: &mut egui::Ui,
+    params: &mut SettingsParams,
+    camera: &CameraParams,
+    streaming: &StreamingParams,
+) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.language"));
+        for language in Language::ALL {
+            if ui
+                .selectable_label(params.settings.language == language, language.native_name())
+                .clicked()
+                && params.settings.language != language
+            {
+                params.settings.language = language;
+            }
+        }
+    });
+
+    // Explicit ids keep the open state across a language switch.
+    egui::CollapsingHeader::new(tr("settings.accessibility"))
+        .id_salt("settings_accessibility")
+        .default_open(true)
+        .show(ui, |ui| render_accessibility(ui, params));
+
+    egui::CollapsingHeader::new(tr("settings.camera"))
+        .id_salt("settings_camera")
+        .default_open(true)
+        .show(ui, |ui| render_camera_settings(ui, params, camera));
+
+    egui::CollapsingHeader::new(tr("settings.graphics"))
+        .id_salt("settings_graphics")
+        .default_open(true)
+        .show(ui, |ui| {
+            render_graphics_preset(ui, params);
+            render_sun_shadows(ui, params);
+        });
+
+    egui::CollapsingHeader::new(tr("settings.network"))
+        .id_salt("settings_network")
+        .default_open(true)
+        .show(ui, |ui| render_network_settings(ui, params, streaming));
+
+    egui::CollapsingHeader::new(tr("settings.bindings"))
+        .id_salt("settings_bindings")
+        .default_open(true)
+        .show(ui, |ui| render_bindings(ui, params));
+
+    ui.separator();
+    ui.label(tr("settings.remembered"));
+    ui.horizontal(|ui| {
+        ui.weak(storage_location());
+        if ui.button(tr("settings.reset_all")).clicked() {
+            let ui_settings = params.settings.ui.clone();
+            *params.settings = UserSettings {
+                language: params.settings.language,
+                ui: ui_settings,
+                ..default()
+            };
+            *params.rebinding = None;
+        }
+    });
+}
+
+fn render_accessibility(ui: &mut egui::Ui, params: &mut SettingsParams) {
+    let accessibility = &params.settings.accessibility;
+    let mut ui_scale = params.ui_scale_drag.unwrap_or(accessibility.ui_scale);
+    let mut high_contrast = accessibility.high_contrast;
+    let mut palette = accessibility.palette;
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.ui_scale"));
+        let response = ui.add(
+            egui::Slider::new(&mut ui_scale, UI_SCALE_RANGE)
+                .step_by(0.05)
+                .suffix("×"),
+        );
+        *params.ui_scale_drag = response.dragged().then_some(ui_scale);
+        if ui.button(tr("settings.bindings.default")).clicked() {
+            ui_scale = 1.0;
+        }
+    });
+    ui.checkbox(&mut high_contrast, tr("settings.high_contrast"))
+        .on_hover_text(tr("settings.high_contrast.hover"));
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.palette"));
+        for option in DebugPalette::ALL {
+            let label = match option {
+                DebugPalette::Standard => tr("settings.palette.standard"),
+                DebugPalette::ColorblindSafe => tr("settings.palette.colorblind"),
+            };
+            ui.selectable_value(&mut palette, option, label);
+        }
+    })
+    .response
+    .on_hover_text(tr("settings.palette.hover"));
+
+    // Compare before writing so the resource is only marked changed by real
+    // edits.
+    if params.ui_scale_drag.is_none() && params.settings.accessibility.ui_scale != ui_scale {
+        params.settings.accessibility.ui_scale = ui_scale;
+    }
+    if params.settings.accessibility.high_contrast != high_contrast {
+        params.settings.accessibility.high_contrast = high_contrast;
+    }
+    if params.settings.accessibility.palette != palette {
+        params.settings.accessibility.palette = palette;
+    }
+}
+
+/// Camera overrides, each seeded from the live value when first ticked.
+fn render_camera_settings(ui: &mut egui::Ui, params: &mut SettingsParams, camera: &CameraParams) {
+    ui.label(tr("settings.camera.help"));
+    let config = &*camera.config;
+    let live_fov_deg = camera
+        .projection_query
+        .iter()
+        .find_map(|projection| match projection {
+            Projection::Perspective(p) => Some(p.fov.to_degrees()),
+            _ => None,
+        })
+        .unwrap_or(config.default_fov_deg);
+
+    // Edit a copy so the resource is only marked changed by real edits.
+    let mut overrides = params.settings.camera.clone();
+    egui::Grid::new("settings_camera_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            override_row(
+                ui,
+                tr("settings.camera.speed"),
+                &mut overrides.base_speed,
+                config.base_speed,
+                |ui, value| {
+                    ui.add(
+                        egui::Slider::new(value, config.min_speed..=config.max_speed)
+                            .logarithmic(true)
+                            .suffix(" m/s"),
+                    );
+                },
+            );
+            override_row(
+                ui,
+                tr("settings.camera.sensitivity"),
+                &mut overrides.mouse_sensitivity,
+                config.mouse_sensitivity,
+                |ui, value| {
+                    ui.add(
+                        egui::Slider::new(value, 0.0002..=0.01)
+                            .logarithmic(true)
+                            .suffix(" rad/px"),
+                    );
+                },
+            );
+            override_row(
+                ui,
+                tr("settings.camera.fov"),
+                &mut overrides.fov_deg,
+                live_fov_deg,
+                |ui, value| {
+                    ui.add(
+                        egui::Slider::new(value, config.min_fov_deg..=config.max_fov_deg)
+                            .suffix("°"),
+                    );
+                },
+            );
+            override_row(
+                ui,
+                tr("settings.camera.teleport_style"),
+                &mut overrides.teleport_animation_mode,
+                config.teleport_animation_mode,
+                |ui, value| {
+                    ui.selectable_value(
+                        value,
+                        TeleportAnimationMode::Classic,
+                        tr("settings.camera.teleport_classic"),
+                    );
+                    ui.selectable_value(
+                        value,
+                        TeleportAnimationMode::HorizonChasing,
+                        tr("settings.camera.teleport_horizon"),
+                    );
+                },
+            );
+        });
+    if overrides != params.settings.camera {
+        params.settings.camera = overrides;
+    }
+}
+
+/// One override: a checkbox that seeds `value` from `live`, and the editor.
+fn override_row<T: Copy>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    live: T,
+    edit: impl FnOnce(&mut egui::Ui, &mut T),
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(live);
+    }
+    ui.horizontal(|ui| match value {
+        Some(value) => edit(ui, value),
+        None => {
+            // Show the live value, greyed out.
+            let mut shown = live;
+            ui.add_enabled_ui(false, |ui| edit(ui, &mut shown));
+        }
+    });
+    ui.end_row();
+}
+
+/// Bandwidth cap, metered mode and live weather, with the current download
+/// rate.
+fn render_network_settings(
+    ui: &mut egui::Ui,
+    params: &mut SettingsParams,
+    streaming: &StreamingParams,
+) {
+    ui.label(tr("settings.network.help"));
+    let live = streaming.tuning.network;
+
+    let mut overrides = params.settings.network.clone();
+    egui::Grid::new("settings_network_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            override_row(
+                ui,
+                tr("settings.network.limit"),
+                &mut overrides.bandwidth_limit_kib_per_sec,
+                live.bandwidth_limit_kib_per_sec,
+                |ui, value| {
+                    ui.add(
+                        egui::Slider::new(value, 0.0..=65536.0)
+                            .logarithmic(true)
+                            .suffix(" KiB/s"),
+                    )
+                    .on_hover_text(tr("settings.network.limit.hover"));
+                },
+            );
+            override_row(
+                ui,
+                tr("settings.network.metered"),
+                &mut overrides.metered,
+                live.metered,
+                |ui, value| {
+                    ui.checkbox(value, "")
+                        .on_hover_text(tr("settings.network.metered.hover"));
+                },
+            );
+
+            ui.checkbox(
+                &mut overrides.live_weather,
+                tr("settings.network.live_weather"),
+            )
+            .on_hover_text(tr("settings.network.live_weather.hover"));
+            ui.end_row();
+        });
+    if overrides != params.settings.network {
+        params.settings.network = overrides;
+    }
+
+    let bandwidth = streaming.loader.client.bandwidth();
+    let rate = i18n::fmt_number(bandwidth.rate() / 1024.0, 0);
+    let total = i18n::fmt_number(bandwidth.total_bytes() as f64 / (1024.0 * 1024.0), 1);
+    let usage = match bandwidth.limit() {
+        Some(limit) => trf(
+            "settings.network.usage_capped",
+            &[
+                ("rate", &rate),
+                ("limit", &i18n::fmt_number(limit as f64 / 1024.0, 0)),
+                ("total", &total),
+            ],
+        ),
+        None => trf(
+            "settings.network.usage",
+            &[("rate", &rate), ("total", &total)],
+        ),
+    };
+    ui.label(usage);
+}
+
+fn render_graphics_preset(ui: &mut egui::Ui, params: &mut SettingsParams) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.preset"));
+        let current = params.settings.graphics_preset;
+        if ui
+            .selectable_label(current.is_none(), tr("settings.preset.config"))
+            .on_hover_text(tr("settings.preset.config.hover"))
+            .clicked()
+            && current.is_some()
+        {
+            params.settings.graphics_preset = None;
+        }
+        for preset in GraphicsPreset::ALL {
+            if ui
+                .selectable_label(current == Some(preset), preset.label())
+                .on_hover_text(preset.description())
+                .clicked()
+                && current != Some(preset)
+            {
+                params.settings.graphics_preset = Some(preset);
+            }
+        }
+    });
+    if params.settings.graphics_preset.is_none() {
+        ui.weak(tr("settings.preset.config.note"));
+    }
+}
+
+fn render_sun_shadows(ui: &mut egui::Ui, params: &mut SettingsParams) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.shadows"))
+            .on_hover_text(tr("settings.shadows.hover"));
+        let current = params.settings.sun_shadows;
+        for quality in SunShadowQuality::ALL {
+            let label = match quality {
+                SunShadowQuality::Off => tr("settings.shadows.off"),
+                SunShadowQuality::Low => tr("settings.preset.low"),
+                SunShadowQuality::Medium => tr("settings.preset.medium"),
+                SunShadowQuality::High => tr("settings.preset.high"),
+            };
+            if ui.selectable_label(current == quality, label).clicked() && current != quality {
+                params.settings.sun_shadows = quality;
+            }
+        }
+    });
+}
+
+/// Rebind button actions: click Rebind, then press a key or mouse button.
+fn render_bindings(ui: &mut egui::Ui, params: &mut SettingsParams) {
+    if let Some(action) = *params.rebinding {
+        let pressed = if params.keys.just_pressed(KeyCode::Escape) {
+            *params.rebinding = None;
+            None
+        } else if let Some(key) = params.keys.get_just_pressed().next() {
+            Some(ButtonBinding::Key(*key))
+        } else {
+            params
+                .mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| ButtonBinding::Mouse(*button))
+        };
+        if let Some(binding) = pressed {
+            params.settings.bindings.insert(action, vec![binding]);
+            *params.rebinding = None;
+        }
+    }
+
+    let defaults = default_camera_bindings();
+    egui::Grid::new("settings_bindings_grid")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for &action in REBINDABLE_CAMERA_ACTIONS {
+                ui.label(action_label(action));
+                let overridden = params.settings.bindings.get(&action);
+                let buttons = overridden.or_else(|| defaults.get(&action));
+                let text = buttons.map_or_else(String::new, |buttons| {
+                    buttons
+                        .iter()
+                        .map(|button| button.label())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                });
+                ui.monospace(text);
+                ui.horizontal(|ui| {
+                    if *params.rebinding == Some(action) {
+                        ui.label(tr("settings.bindings.waiting"));
+                    } else if ui.button(tr("settings.bindings.rebind")).clicked() {
+                        *params.rebinding = Some(action);
+                    }
+                    if ui
+                        .add_enabled(
+                            overridden.is_some(),
+                            egui::Button::new(tr("settings.bindings.default")),
+                        )
+                        .clicked()
+                    {
+                        params.settings.bindings.remove(&action);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+}
+
+fn action_label(action: CameraAction) -> &'static str {
+    match action {
+        CameraAction::Move => tr("action.move"),
+        CameraAction::Look => tr("action.look"),
+        CameraAction::Ascend => tr("action.ascend"),
+        CameraAction::Descend => tr("action.descend"),
+        CameraAction::Sprint => tr("action.sprint"),
+        CameraAction::ToggleCameraMode => tr("action.toggle_camera_mode"),
+        CameraAction::ToggleUi => tr("action.toggle_ui"),
+        CameraAction::GrabCursor => tr("action.grab_cursor"),
+        CameraAction::ReleaseCursor => tr("action.release_cursor"),
+        CameraAction::AdjustSpeed => tr("action.adjust_speed"),
+        CameraAction::InteractVehicle => tr("action.interact_vehicle"),
+        CameraAction::RideVehicle => tr("action.ride_vehicle"),
+        CameraAction::CinematicOrbit => tr("action.cinematic_orbit"),
+        CameraAction::Fire => tr("action.fire"),
+        CameraAction::Point => tr("action.point"),
+        CameraAction::DropAnnotation => tr("action.drop_annotation"),
+        CameraAction::CycleTerrainDebug => tr("action.cycle_terrain_debug"),
+        CameraAction::ToggleConsole => tr("action.toggle_console"),
+        CameraAction::SkipTeleport => tr("action.skip_teleport"),
+        CameraAction::HistoryBack => tr("action.history_back"),
+        CameraAction::HistoryForward => tr("action.history_forward"),
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("veldera-settings-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("settings.json")
+    }
+
+    #[test]
+    fn settings_survive_a_save_and_load() {
+        let path = temp_path("roundtrip");
+        let mut settings = UserSettings {
+            language: Language::German,
+            graphics_preset: Some(GraphicsPreset::Low),
+            ..default()
+        };
+        settings.accessibility.ui_scale = 1.5;
+        settings.accessibility.high_contrast = true;
+        settings.camera.fov_deg = Some(75.0);
+        settings.network.bandwidth_limit_kib_per_sec = Some(512.0);
+        settings.network.live_weather = true;
+        settings.ui.visible = false;
+
+        write_file(&path, &settings.to_json().unwrap()).unwrap();
+        assert_eq!(UserSettings::from_stored(read_file(&path)), settings);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn missing_or_corrupt_files_give_the_defaults() {
+        let path = temp_path("fallback");
+        assert_eq!(read_file(&path), Ok(None));
+        assert_eq!(
+            UserSettings::from_stored(read_file(&path)),
+            UserSettings::default()
+        );
+
+        write_file(&path, "{ \"language\": \"de\", \"ui\": ").unwrap();
+        assert_eq!(
+            UserSettings::from_stored(read_file(&path)),
+            UserSettings::default()
+        );
+        assert_eq!(
+            UserSettings::from_stored(Err("permission denied".to_string())),
+            UserSettings::default()
+        );
+
+        // A file from an older build, missing newer fields, keeps what it has.
+        write_file(&path, r#"{ "language": "de" }"#).unwrap();
+        let settings = UserSettings::from_stored(read_file(&path));
+        assert_eq!(settings.language, Language::German);
+        assert_eq!(settings.accessibility, AccessibilitySettings::default());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,453 @@
+//! Sky sub-tab of the Atmosphere tab.
+//!
+//! Live-edits the scattering atmosphere: the LUT sizes, sample counts and
+//! render method of [`AtmosphereSettings`] and the ground albedo (through
+//! [`AtmosphereConfig`], whose apply system pushes them to every camera),
+//! the planet and atmosphere radii of each [`SphericalAtmosphere`], and the
+//! absorption, scattering and falloff of every term of its
+//! [`ScatteringMedium`], and the artistic overrides on top of the result. The
+//! atmosphere passes' GPU times sit on top, so the
+//! cost of a change shows as it's made. Whole looks can be switched between
+//! with the loaded [`AtmospherePresets`].
+
+use bevy::{
+    diagnostic::DiagnosticsStore,
+    ecs::system::SystemParam,
+    math::{UVec2, UVec3},
+    pbr::{Falloff, ScatteringMedium},
+    prelude::*,
+};
+use bevy_egui::egui;
+use veldera_atmosphere::{
+    AtmosphereArtistic, AtmosphereMode, AtmosphereSettings, LutPrecision, SphericalAtmosphere,
+};
+use veldera_sky::{
+    atmosphere::AtmosphereConfig,
+    preset::{AtmospherePreset, AtmospherePresets},
+    weather::WeatherMedium,
+};
+
+use crate::i18n::{fmt_number, tr, trf};
+
+/// Display scale for medium coefficients: m⁻¹ shown as Mm⁻¹.
+const PER_MEGAMETRE: f32 = 1.0e6;
+
+#[derive(SystemParam)]
+pub(super) struct SkyParams<'w, 's> {
+    pub config: ResMut<'w, AtmosphereConfig>,
+    pub atmospheres: Query<'w, 's, &'static mut SphericalAtmosphere>,
+    pub media: ResMut<'w, Assets<ScatteringMedium>>,
+    pub diagnostics: Res<'w, DiagnosticsStore>,
+    pub presets: ResMut<'w, AtmospherePresets>,
+    pub preset_assets: Res<'w, Assets<AtmospherePreset>>,
+    pub weather_medium: ResMut<'w, WeatherMedium>,
+}
+
+pub(super) fn render_sky(ui: &mut egui::Ui, params: &mut SkyParams) {
+    render_pass_timings(ui, &params.diagnostics);
+    ui.separator();
+
+    egui::CollapsingHeader::new(tr("sky.presets"))
+        .default_open(true)
+        .show(ui, |ui| render_presets(ui, params));
+
+    egui::CollapsingHeader::new(tr("sky.luts"))
+        .default_open(true)
+        .show(ui, |ui| {
+            let mut settings = params.config.settings.clone();
+            if render_settings(ui, &mut settings) {
+                params.config.settings = settings;
+            }
+        });
+
+    egui::CollapsingHeader::new(tr("sky.planet"))
+        .default_open(true)
+        .show(ui, |ui| render_planet(ui, params));
+
+    egui::CollapsingHeader::new(tr("sky.medium"))
+        .default_open(false)
+        .show(ui, |ui| render_medium(ui, params));
+
+    egui::CollapsingHeader::new(tr("sky.artistic"))
+        .default_open(false)
+        .show(ui, |ui| {
+            let mut artistic = params.config.artistic;
+            if render_artistic(ui, &mut artistic) {
+                params.config.artistic = artistic;
+            }
+        });
+}
+
+/// GPU and CPU time of the atmosphere's own passes.
+fn render_pass_timings(ui: &mut egui::Ui, diagnostics: &DiagnosticsStore) {
+    let rows: Vec<_> = super::profiler::render_pass_times(diagnostics)
+        .into_iter()
+        .filter(|(pass, (_, _, stale))| {
+            !stale && (pass.contains("atmosphere") || pass.contains("sky"))
+        })
+        .collect();
+    if rows.is_empty() {
+        ui.label(tr("sky.no_timings"));
+        return;
+    }
+    let ms = |value: Option<f64>| value.map_or_else(|| "—".to_string(), |v| fmt_number(v, 3));
+    let mut total_gpu = 0.0;
+    for (pass, (gpu, cpu, _)) in &rows {
+        // Nested spans (the individual LUTs) are inside their parent's time.
+        if !pass.contains('/') {
+            total_gpu += gpu.unwrap_or(0.0);
+        }
+        ui.monospace(trf(
+            "sky.pass_timing",
+            &[
+                ("pass", &format!("{pass:<24}")),
+                ("gpu", &format!("{:>7}", ms(*gpu))),
+                ("cpu", &format!("{:>7}", ms(*cpu))),
+            ],
+        ));
+    }
+    ui.monospace(trf(
+        "sky.total_timing",
+        &[
+            ("total", &format!("{:<24}", tr("sky.total"))),
+            ("gpu", &format!("{:>7}", fmt_number(total_gpu, 3))),
+        ],
+    ));
+}
+
+/// The loaded `.atmo.ron` presets; clicking one applies it.
+fn render_presets(ui: &mut egui::Ui, params: &mut SkyParams) {
+    let presets = params.presets.list(&params.preset_assets);
+    if presets.is_empty() {
+        ui.label(tr("sky.no_presets"));
+        return;
+    }
+    let selected = params.presets.selected();
+    let mut clicked = None;
+    ui.horizontal_wrapped(|ui| {
+        for (id, preset) in &presets {
+            if ui
+                .selectable_label(selected == Some(*id), &preset.name)
+                .on_hover_text(&preset.description)
+                .clicked()
+            {
+                clicked = Some(*id);
+            }
+        }
+    });
+    if let Some(id) = clicked {
+        params.presets.select(id);
+    }
+    ui.label(tr("sky.presets_note"));
+}
+
+/// Edit `settings`; returns whether anything changed.
+fn render_settings(ui: &mut egui::Ui, settings: &mut AtmosphereSettings) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(tr("sky.method"));
+        for (mode, label) in [
+            (
+                AtmosphereMode::LookupTexture,
+                tr("sky.method.lookup_texture"),
+            ),
+            (AtmosphereMode::Raymarched, tr("sky.method.raymarched")),
+        ] {
+            let selected = settings.rendering_method as u32 == mode as u32;
+            if ui.selectable_label(selected, label).clicked() && !selected {
+                settings.rendering_method = mode;
+                changed = true;
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("sky.lut_precision"));
+        for (precision, label) in [
+            (LutPrecision::Full, tr("sky.lut_precision.full")),
+            (LutPrecision::Reduced, tr("sky.lut_precision.reduced")),
+        ] {
+            let selected = settings.lut_precision == precision;
+            if ui.selectable_label(selected, label).clicked() && !selected {
+                settings.lut_precision = precision;
+                changed = true;
+            }
+        }
+    });
+
+    egui::Grid::new("atmosphere_settings_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            changed |= uvec2_row(
+                ui,
+                tr("sky.transmittance_lut"),
+                &mut settings.transmittance_lut_size,
+            );
+            changed |= uvec2_row(
+                ui,
+                tr("sky.multiscattering_lut"),
+                &mut settings.multiscattering_lut_size,
+            );
+            changed |= uvec2_row(ui, tr("sky.sky_view_lut"), &mut settings.sky_view_lut_size);
+            changed |= uvec3_row(
+                ui,
+                tr("sky.aerial_view_lut"),
+                &mut settings.aerial_view_lut_size,
+            );
+            changed |= samples_row(
+                ui,
+                tr("sky.transmittance_samples"),
+                &mut settings.transmittance_lut_samples,
+            );
+            changed |= samples_row(
+                ui,
+                tr("sky.multiscattering_dirs"),
+                &mut settings.multiscattering_lut_dirs,
+            );
+            changed |= samples_row(
+                ui,
+                tr("sky.multiscattering_samples"),
+                &mut settings.multiscattering_lut_samples,
+            );
+            changed |= samples_row(
+                ui,
+                tr("sky.sky_view_samples"),
+                &mut settings.sky_view_lut_samples,
+            );
+            changed |= samples_row(
+                ui,
+                tr("sky.aerial_view_samples"),
+                &mut settings.aerial_view_lut_samples,
+            );
+            changed |= samples_row(ui, tr("sky.sky_max_samples"), &mut settings.sky_max_samples);
+
+            ui.label(tr("sky.aerial_view_range"));
+            let mut km = settings.aerial_view_lut_max_distance / 1000.0;
+            if ui
+                .add(egui::DragValue::new(&mut km).range(1.0..=500.0).speed(0.5))
+                .changed()
+            {
+                settings.aerial_view_lut_max_distance = km * 1000.0;
+                changed = true;
+            }
+            ui.end_row();
+
+            ui.label(tr("sky.raymarch_midpoint"));
+            changed |= ui
+                .add(egui::Slider::new(
+                    &mut settings.raymarch_midpoint_ratio,
+                    0.0..=1.0,
+                ))
+                .changed();
+            ui.end_row();
+        });
+    changed
+}
+
+fn uvec2_row(ui: &mut egui::Ui, label: &str, value: &mut UVec2) -> bool {
+    ui.label(label);
+    let changed = ui
+        .horizontal(|ui| {
+            let x = ui.add(lut_size(&mut value.x)).changed();
+            let y = ui.add(lut_size(&mut value.y)).changed();
+            x | y
+        })
+        .inner;
+    ui.end_row();
+    changed
+}
+
+fn uvec3_row(ui: &mut egui::Ui, label: &str, value: &mut UVec3) -> bool {
+    ui.label(label);
+    let changed = ui
+        .horizontal(|ui| {
+            let x = ui.add(lut_size(&mut value.x)).changed();
+            let y = ui.add(lut_size(&mut value.y)).changed();
+            let z = ui.add(lut_size(&mut value.z)).changed();
+            x | y | z
+        })
+        .inner;
+    ui.end_row();
+    changed
+}
+
+fn lut_size(value: &mut u32) -> egui::DragValue<'_> {
+    egui::DragValue::new(value).range(4..=1024).speed(1.0)
+}
+
+fn samples_row(ui: &mut egui::Ui, label: &str, value: &mut u32) -> bool {
+    ui.label(label);
+    let changed = ui
+        .add(egui::DragValue::new(value).range(1..=256).speed(0.5))
+        .changed();
+    ui.end_row();
+    changed
+}
+
+/// Radii of every atmosphere camera, edited together, and the ground albedo.
+fn render_planet(ui: &mut egui::Ui, params: &mut SkyParams) {
+    let Some(first) = params.atmospheres.iter().next() else {
+        ui.label(tr("sky.no_atmosphere"));
+        return;
+    };
+    let mut bottom_km = first.bottom_radius / 1000.0;
+    let mut top_km = first.top_radius / 1000.0;
+
+    let mut radii_changed = false;
+    egui::Grid::new("atmosphere_planet_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label(tr("sky.planet_radius"));
+            radii_changed |= ui
+                .add(
+                    egui::DragValue::new(&mut bottom_km)
+                        .range(100.0..=100_000.0)
+                        .speed(1.0),
+                )
+                .changed();
+            ui.end_row();
+
+            ui.label(tr("sky.atmosphere_height"));
+            let mut height_km = top_km - bottom_km;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut height_km)
+                        .range(1.0..=1000.0)
+                        .speed(0.5),
+                )
+                .changed()
+            {
+                radii_changed = true;
+            }
+            top_km = bottom_km + height_km;
+            ui.end_row();
+
+            ui.label(tr("sky.ground_albedo"));
+            let mut albedo = params.config.ground_albedo;
+            if ui.color_edit_button_rgb(&mut albedo).changed() {
+                params.config.ground_albedo = albedo;
+            }
+            ui.end_row();
+        });
+
+    if radii_changed {
+        for mut atmosphere in &mut params.atmospheres {
+            atmosphere.bottom_radius = bottom_km * 1000.0;
+            atmosphere.top_radius = top_km * 1000.0;
+        }
+    }
+    ui.label(tr("sky.planet_note"));
+}
+
+/// Edit the non-physical overrides; returns whether anything changed.
+fn render_artistic(ui: &mut egui::Ui, artistic: &mut AtmosphereArtistic) -> bool {
+    let mut changed = false;
+    egui::Grid::new("atmosphere_artistic_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label(tr("sky.scattering_strength"));
+            changed |= ui
+                .add(egui::Slider::new(
+                    &mut artistic.scattering_strength,
+                    0.0..=4.0,
+                ))
+                .changed();
+            ui.end_row();
+
+            ui.label(tr("sky.horizon_haze"));
+            changed |= ui
+                .add(egui::Slider::new(&mut artistic.horizon_haze, 0.0..=4.0))
+                .on_hover_text(tr("sky.horizon_haze.hover"))
+                .changed();
+            ui.end_row();
+
+            ui.label(tr("sky.sky_tint"));
+            let mut tint = artistic.sky_tint.to_array();
+            if ui.color_edit_button_rgb(&mut tint).changed() {
+                artistic.sky_tint = Vec3::from_array(tint);
+                changed = true;
+            }
+            ui.end_row();
+        });
+    if ui.button(tr("common.reset")).clicked() && *artistic != AtmosphereArtistic::default() {
+        *artistic = AtmosphereArtistic::default();
+        changed = true;
+    }
+    ui.label(tr("sky.artistic_note"));
+    changed
+}
+
+/// Absorption, scattering and falloff of each term of the camera's medium.
+/// Under the weather, that's its clear-weather medium.
+fn render_medium(ui: &mut egui::Ui, params: &mut SkyParams) {
+    let Some(handle) = params
+        .atmospheres
+        .iter()
+        .next()
+        .map(|atmosphere| atmosphere.medium.clone())
+    else {
+        ui.label(tr("sky.no_atmosphere"));
+        return;
+    };
+    let Some(medium) = params
+        .weather_medium
+        .base()
+        .or_else(|| params.media.get(&handle))
+    else {
+        ui.label(tr("sky.medium_not_loaded"));
+        return;
+    };
+
+    let mut terms = medium.terms.clone();
+    let mut changed = false;
+    ui.label(tr("sky.medium_note"));
+    for (i, term) in terms.iter_mut().enumerate() {
+        ui.separator();
+        ui.strong(trf("sky.term", &[("index", &i)]));
+        changed |= coefficient_row(ui, tr("sky.scattering"), &mut term.scattering);
+        changed |= coefficient_row(ui, tr("sky.absorption"), &mut term.absorption);
+        if let Falloff::Exponential { scale } = &mut term.falloff {
+            ui.horizontal(|ui| {
+                ui.label(tr("sky.falloff_scale"));
+                changed |= ui
+                    .add(egui::DragValue::new(scale).range(0.001..=1.0).speed(0.001))
+                    .on_hover_text(tr("sky.falloff_scale.hover"))
+                    .changed();
+            });
+        }
+    }
+
+    // Only take the medium mutably on an edit: every mutable access rebuilds
+    // the medium's GPU LUTs.
+    if !changed {
+        return;
+    }
+    if let Some(base) = params.weather_medium.base_mut() {
+        base.terms = terms;
+    } else if let Some(medium) = params.media.get_mut(&handle) {
+        medium.terms = terms;
+    }
+}
+
+fn coefficient_row(ui: &mut egui::Ui, label: &str, value: &mut Vec3) -> bool {
+    let mut scaled = *value * PER_MEGAMETRE;
+    let changed = ui
+        .horizontal(|ui| {
+            ui.label(label);
+            let mut changed = false;
+            for component in [&mut scaled.x, &mut scaled.y, &mut scaled.z] {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(component)
+                            .range(0.0..=1000.0)
+                            .speed(0.05),
+                    )
+                    .changed();
+            }
+            changed
+        })
+        .inner;
+    if changed {
+        *value = scaled / PER_MEGAMETRE;
+    }
+    changed
+}
@@ -2,7 +2,9 @@
 //!
 //! Single view: a top-down map of the octree streaming state for both
 //! the render and physics BFSes, plus per-depth histogram, aggregate
-//! counters, and tuning sliders for the LoD system.
+//! counters, tuning sliders for the LoD system, failed loads with their
+//! retries, the node inspector, and named-area downloads (the area is a
+//! radius around the camera, or a rectangle dragged out on the map).
 //!
 //! The view consumes a per-frame [`LodSnapshot`] populated by the LoD
 //! system. Snapshot population is gated on this tab being visible:
@@ -15,14 +17,39 @@ use bevy_egui::egui;
 use glam::DVec3;
 
 use rocktree_decode::OctreePath;
-use veldera_geo::coords::RadialFrame;
-use veldera_physics::PhysicsStreamingConfig;
+use veldera_geo::coords::{RadialFrame, ecef_to_lat_lon, lat_lon_to_ecef};
+use veldera_physics::{DebugPalette, PhysicsStreamingConfig};
 use veldera_terrain::{
-    collider::viz::LodVizSettings,
-    lod::{FreezeLod, LodSnapshot, LodSnapshotRequest, LodTuning, SnapshotNode, SnapshotNodeState},
+    area_prefetch::{AreaPrefetch, MAX_PREFETCH_DEPTH, PrefetchArea, approximate_node_size_m},
+    area_refresh::AreaRefresh,
+    collider::{
+        camera_centred::{ColliderTierStats, TierStats},
+        viz::LodVizSettings,
+    },
+    detail::{DetailBoostRequest, LodDetail},
+    epoch_refresh::EpochRefresh,
+    heatmap::VisitHeatmap,
+    load_errors::{LoadErrorKind, LoadErrorLedger, RetryState},
+    loader::LoaderState,
+    lod::{
+        FreezeLod, LodRefinement, LodSnapshot, LodSnapshotRequest, LodTuning, RefinementStrategy,
+        SnapshotNode, SnapshotNodeState, TextureQuality,
+    },
     mesh::RocktreeMeshMarker,
+    network::NetworkTuning,
+    qos::{LoadQos, QosTuning, RequestKind},
+    query::NodeExportRequest,
 };
 
+use crate::{
+    egui_color,
+    i18n::{fmt_number, tr, trf},
+    node_inspector,
+};
+
+/// Bytes per MiB, for the size readouts.
+const MIB: f64 = 1024.0 * 1024.0;
+
 /// Resources for the streaming tab.
 #[derive(SystemParam)]
 pub(super) struct StreamingParams<'w, 's> {
@@ -33,7 +60,25 @@ pub(super) struct StreamingParams<'w, 's> {
     pub tuning: ResMut<'w, LodTuning>,
     pub streaming: Res<'w, PhysicsStreamingConfig>,
     pub freeze: ResMut<'w, FreezeLod>,
+    pub refinement: ResMut<'w, LodRefinement>,
+    pub detail: ResMut<'w, LodDetail>,
+    pub detail_boost: ResMut<'w, DetailBoostRequest>,
     pub viz: ResMut<'w, LodVizSettings>,
+    pub qos: Res<'w, LoadQos>,
+    pub loader: Res<'w, LoaderState>,
+    pub heatmap: ResMut<'w, VisitHeatmap>,
+    pub epoch_refresh: ResMut<'w, EpochRefresh>,
+    pub area_refresh: ResMut<'w, AreaRefresh>,
+    pub load_errors: ResMut<'w, LoadErrorLedger>,
+    pub real_time: Res<'w, Time<Real>>,
+    pub area_prefetch: ResMut<'w, AreaPrefetch>,
+    pub area_view: ResMut<'w, AreaPrefetchView>,
+    pub palette: Res<'w, DebugPalette>,
+    pub node_export: ResMut<'w, NodeExportRequest>,
+    pub node_inspector: node_inspector::NodeInspectorParams<'w>,
+    /// Per-tier collider budgets; only present on the camera-centred
+    /// collider algorithms.
+    pub tier_stats: Option<Res<'w, ColliderTierStats>>,
 }
 
 /// Per-frame UI state for the diagnostics map (zoom, layer toggles).
@@ -69,21 +114,22 @@ pub(super) fn render_streaming_tab(ui: &mut egui::Ui, params: &mut StreamingPara
     let tuning = &mut *params.tuning;
     let streaming = &*params.streaming;
     let freeze = &mut *params.freeze;
+    let palette = *params.palette;
     let mesh_count = params.mesh_query.iter().count();
 
     if snapshot.camera_pos.is_none() {
-        ui.label("Waiting for first snapshot…");
+        ui.label(tr("streaming.waiting"));
         return;
     }
 
     // Layer toggles + zoom.
     ui.horizontal(|ui| {
-        ui.checkbox(&mut view.show_render, "Render BFS");
-        ui.checkbox(&mut view.show_physics, "Physics BFS");
+        ui.checkbox(&mut view.show_render, tr("streaming.render_bfs"));
+        ui.checkbox(&mut view.show_physics, tr("streaming.physics_bfs"));
         ui.separator();
-        ui.label("Map radius:");
+        ui.label(tr("streaming.map_radius"));
         ui.add(
-            egui::Slider::new(&mut view.map_radius_m, 200.0..=5000.0)
+            egui::Slider::new(&mut view.map_radius_m, 200.0..=10_000.0)
                 .logarithmic(true)
                 .suffix(" m"),
         );
@@ -92,45 +138,864 @@ pub(super) fn render_streaming_tab(ui: &mut egui::Ui, params: &mut StreamingPara
     // Streaming tuning sliders. Both knobs feed `LodTuning`, read by
     // `update_lod_requests` on the next tick.
     ui.horizontal(|ui| {
-        ui.label("Keep-loaded radius:");
+        ui.label(tr("streaming.keep_loaded"));
         ui.add(
             egui::Slider::new(&mut tuning.keep_loaded_radius, 50.0..=2000.0)
                 .logarithmic(true)
                 .suffix(" m"),
         )
-        .on_hover_text(
-            "Render-BFS bypass radius for frustum culling. Wider \
-             = fewer tile reloads when turning, more memory.",
-        );
+        .on_hover_text(tr("streaming.keep_loaded.hover"));
     });
     ui.horizontal(|ui| {
-        ui.label("Unload grace:");
+        ui.label(tr("streaming.unload_grace"));
         ui.add(
             egui::Slider::new(&mut tuning.unload_grace_period_secs, 0.0..=15.0)
                 .step_by(0.1)
                 .suffix(" s"),
         )
-        .on_hover_text(
-            "Seconds a tile stays loaded after dropping out of \
-             every BFS. Longer = less churn on quick view shifts.",
-        );
+        .on_hover_text(tr("streaming.unload_grace.hover"));
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(tr("streaming.texture_quality"));
+        for (quality, label) in [
+            (TextureQuality::Full, tr("streaming.texture_quality.full")),
+            (TextureQuality::Half, tr("streaming.texture_quality.half")),
+            (
+                TextureQuality::Quarter,
+                tr("streaming.texture_quality.quarter"),
+            ),
+        ] {
+            if ui
+                .selectable_label(tuning.texture_quality == quality, label)
+                .on_hover_text(tr("streaming.texture_quality.hover"))
+                .clicked()
+            {
+                tuning.texture_quality = quality;
+            }
+        }
     });
 
-    ui.checkbox(&mut freeze.0, "Freeze LoD").on_hover_text(
-        "Reuse the current octree selection every frame instead of \
-             re-walking it. Streaming stops churning so the LoD set \
-             settles — handy for isolating LoD-transition artifacts.",
+    ui.checkbox(&mut freeze.0, tr("streaming.freeze"))
+        .on_hover_text(tr("streaming.freeze.hover"));
+
+    draw_refinement_controls(ui, &mut params.refinement.0);
+    draw_detail_controls(
+        ui,
+        &mut params.detail,
+        &mut params.detail_boost,
+        params.real_time.elapsed_secs_f64(),
     );
 
+    draw_qos_panel(ui, &params.qos, &mut tuning.qos);
+
     draw_in_world_overlay_controls(ui, &mut params.viz);
 
-    draw_top_down_map(ui, snapshot, view, tuning, streaming);
+    let area_view = &mut *params.area_view;
+    draw_top_down_map(ui, snapshot, view, area_view, tuning, streaming, palette);
 
     ui.separator();
-    draw_per_depth_histogram(ui, snapshot);
+    draw_per_depth_histogram(ui, snapshot, palette);
 
     ui.separator();
     draw_counters_panel(ui, snapshot, mesh_count);
+    if ui
+        .button(tr("streaming.export_nodes"))
+        .on_hover_text(tr("streaming.export_nodes.hover"))
+        .clicked()
+    {
+        params.node_export.wanted = true;
+    }
+    if let Some(textures) = params.loader.client.texture_cache() {
+        let stats = textures.stats();
+        let hit_rate = stats.hit_rate().map_or("—".to_string(), |rate| {
+            trf(
+                "streaming.hit_rate",
+                &[
+                    ("percent", &fmt_number(rate * 100.0, 0)),
+                    ("requests", &(stats.hits + stats.misses)),
+                ],
+            )
+        });
+        ui.monospace(trf(
+            "streaming.textures",
+            &[
+                ("entries", &format!("{:>4}", stats.entries)),
+                ("mib", &padded(stats.bytes as f64 / MIB, 1, 5)),
+                ("hit_rate", &hit_rate),
+            ],
+        ));
+    }
+    draw_bandwidth_line(ui, &params.loader, &tuning.network);
+    draw_epoch_line(
+        ui,
+        &params.loader,
+        &mut params.epoch_refresh,
+        params.real_time.elapsed_secs_f64(),
+    );
+    draw_area_refresh_line(
+        ui,
+        &params.loader,
+        &mut params.area_refresh,
+        params.real_time.elapsed_secs_f64(),
+    );
+    draw_load_errors_panel(
+        ui,
+        &mut params.load_errors,
+        params.real_time.elapsed_secs_f64(),
+    );
+    draw_heatmap_panel(ui, &mut params.heatmap);
+    if let Some(camera_pos) = snapshot.camera_pos {
+        draw_area_prefetch_panel(ui, &mut params.area_prefetch, area_view, camera_pos);
+    }
+    if let Some(tier_stats) = &params.tier_stats {
+        draw_collider_tiers(ui, tier_stats);
+    }
+
+    node_inspector::render_node_inspector(ui, &mut params.node_inspector);
+}
+
+// ============================================================================
+// Adaptive load concurrency
+// ============================================================================
+
+fn draw_qos_panel(ui: &mut egui::Ui, qos: &LoadQos, tuning: &mut QosTuning) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut tuning.enabled, tr("streaming.adaptive"))
+            .on_hover_text(tr("streaming.adaptive.hover"));
+        ui.label(trf(
+            "streaming.qos",
+            &[
+                ("nodes", &qos.node_limit()),
+                ("bulks", &qos.bulk_limit()),
+                ("latency", &fmt_number(qos.latency_secs() * 1000.0, 0)),
+                ("failures", &fmt_number(qos.failure_rate() * 100.0, 0)),
+            ],
+        ));
+        if qos.coarsening_steps() > 0 {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                trf(
+                    "streaming.coarsened",
+                    &[("factor", &fmt_number(1.0 / qos.coarsening_factor(), 2))],
+                ),
+            )
+            .on_hover_text(tr("streaming.coarsened.hover"));
+        }
+    });
+}
+
+// ============================================================================
+// Bandwidth
+// ============================================================================
+
+/// Download rate against the cap, and the session total. The cap and
+/// metered mode are set in the Settings tab.
+fn draw_bandwidth_line(ui: &mut egui::Ui, loader: &LoaderState, network: &NetworkTuning) {
+    let bandwidth = loader.client.bandwidth();
+    ui.horizontal(|ui| {
+        ui.monospace(trf(
+            "streaming.bandwidth",
+            &[
+                ("rate", &padded(bandwidth.rate() / 1024.0, 0, 6)),
+                (
+                    "limit",
+                    &bandwidth.limit().map_or("∞".to_string(), |limit| {
+                        fmt_number(limit as f64 / 1024.0, 0)
+                    }),
+                ),
+                ("total", &padded(bandwidth.total_bytes() as f64 / MIB, 1, 6)),
+            ],
+        ));
+        if network.metered {
+            ui.colored_label(egui::Color32::LIGHT_BLUE, tr("streaming.metered"))
+                .on_hover_text(tr("streaming.metered.hover"));
+        }
+        if bandwidth.is_throttling() {
+            ui.colored_label(egui::Color32::YELLOW, tr("streaming.throttled"))
+                .on_hover_text(tr("streaming.throttled.hover"));
+        }
+    });
+}
+
+// ============================================================================
+// Data epoch
+// ============================================================================
+
+/// The planetoid's epoch, when it was last checked, and any refresh in
+/// progress.
+fn draw_epoch_line(ui: &mut egui::Ui, loader: &LoaderState, refresh: &mut EpochRefresh, now: f64) {
+    let Some(planetoid) = &loader.planetoid else {
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.monospace(trf(
+            "streaming.epoch",
+            &[("epoch", &format!("{:>6}", planetoid.root_epoch))],
+        ));
+        if refresh.is_refreshing() {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                trf(
+                    "streaming.epoch.refreshing",
+                    &[("remaining", &refresh.remaining())],
+                ),
+            );
+        } else if let Some(checked) = refresh.last_checked() {
+            ui.weak(trf(
+                "streaming.epoch.checked",
+                &[("minutes", &fmt_number((now - checked) / 60.0, 0))],
+            ));
+        }
+        if ui
+            .add_enabled(
+                !refresh.is_checking() && !loader.client.has_session(),
+                egui::Button::new(tr("streaming.epoch.check")),
+            )
+            .on_hover_text(tr("streaming.epoch.check.hover"))
+            .clicked()
+        {
+            refresh.request_check();
+        }
+    });
+}
+
+/// When the tiles in view were loaded and at which epochs, with a button to
+/// refetch them.
+fn draw_area_refresh_line(
+    ui: &mut egui::Ui,
+    loader: &LoaderState,
+    refresh: &mut AreaRefresh,
+    now: f64,
+) {
+    let ages = refresh.ages();
+    ui.horizontal(|ui| {
+        match (ages.oldest_loaded_at, ages.newest_loaded_at) {
+            (Some(oldest), Some(newest)) => ui.monospace(trf(
+                "streaming.in_view",
+                &[
+                    ("tiles", &format!("{:>6}", ages.tiles)),
+                    ("oldest", &format_age(now - oldest)),
+                    ("newest", &format_age(now - newest)),
+                ],
+            )),
+            _ => ui.monospace(tr("streaming.in_view.none")),
+        };
+        if refresh.is_running() {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                trf(
+                    "streaming.in_view.refreshing",
+                    &[
+                        ("refetched", &refresh.nodes_refetched()),
+                        ("remaining", &refresh.remaining()),
+                    ],
+                ),
+            );
+        }
+        if ui
+            .add_enabled(
+                !refresh.is_running() && !loader.client.has_session() && ages.tiles > 0,
+                egui::Button::new(tr("streaming.refresh_area")),
+            )
+            .on_hover_text(tr("streaming.refresh_area.hover"))
+            .clicked()
+        {
+            refresh.request();
+        }
+    });
+    if ages.tiles > 0 {
+        let list = |counts: Vec<(String, usize)>| {
+            counts
+                .into_iter()
+                .map(|(epoch, tiles)| format!("{epoch}×{tiles}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        ui.monospace(trf(
+            "streaming.epochs",
+            &[(
+                "list",
+                &list(
+                    ages.epochs
+                        .iter()
+                        .map(|(epoch, tiles)| (epoch.to_string(), *tiles))
+                        .collect(),
+                ),
+            )],
+        ));
+        ui.monospace(trf(
+            "streaming.imagery_epochs",
+            &[(
+                "list",
+                &list(
+                    ages.imagery_epochs
+                        .iter()
+                        .map(|(epoch, tiles)| {
+                            (
+                                epoch.map_or_else(
+                                    || tr("streaming.imagery_epochs.none").to_string(),
+                                    |e| e.to_string(),
+                                ),
+                                *tiles,
+                            )
+                        })
+                        .collect(),
+                ),
+            )],
+        ));
+    }
+}
+
+/// A duration (s) as seconds, minutes or hours.
+fn format_age(secs: f64) -> String {
+    let secs = secs.max(0.0);
+    if secs < 60.0 {
+        format!("{} s", fmt_number(secs, 0))
+    } else if secs < 3600.0 {
+        format!("{} min", fmt_number(secs / 60.0, 0))
+    } else {
+        format!("{} h", fmt_number(secs / 3600.0, 1))
+    }
+}
+
+/// `value` with `decimals` places in the UI language, right-aligned to
+/// `width` for the monospace readouts.
+fn padded(value: f64, decimals: usize, width: usize) -> String {
+    format!("{:>width$}", fmt_number(value, decimals))
+}
+
+/// The localized name of a load error kind.
+fn error_kind_label(kind: LoadErrorKind) -> &'static str {
+    match kind {
+        LoadErrorKind::Network => tr("streaming.errors.network"),
+        LoadErrorKind::Status => tr("streaming.errors.status"),
+        LoadErrorKind::Decode => tr("streaming.errors.decode"),
+        LoadErrorKind::Cache => tr("streaming.errors.cache"),
+        LoadErrorKind::Session => tr("streaming.errors.session"),
+    }
+}
+
+// ============================================================================
+// Load errors
+// ============================================================================
+
+/// Failed node and bulk loads with their retry schedule, and a button to
+/// retry each by hand.
+fn draw_load_errors_panel(ui: &mut egui::Ui, ledger: &mut LoadErrorLedger, now: f64) {
+    if ledger.is_empty()
+        && LoadErrorKind::ALL
+            .iter()
+            .all(|&kind| ledger.count(kind) == 0)
+    {
+        return;
+    }
+    egui::CollapsingHeader::new(trf("streaming.errors", &[("count", &ledger.len())]))
+        .id_salt("load_errors")
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for kind in LoadErrorKind::ALL {
+                    ui.label(format!("{} {}", error_kind_label(kind), ledger.count(kind)));
+                }
+                ui.separator();
+                if ui
+                    .button(tr("streaming.errors.retry_all"))
+                    .on_hover_text(tr("streaming.errors.retry_all.hover"))
+                    .clicked()
+                {
+                    ledger.retry_all();
+                }
+                if ui.button(tr("common.clear")).clicked() {
+                    ledger.clear();
+                }
+            });
+
+            let mut retry = None;
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("load_errors_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for entry in ledger.entries() {
+                                ui.label(match entry.kind {
+                                    RequestKind::Node => tr("streaming.errors.node"),
+                                    RequestKind::Bulk => tr("streaming.errors.bulk"),
+                                });
+                                ui.monospace(entry.path.to_string());
+                                ui.label(error_kind_label(entry.error_kind))
+                                    .on_hover_text(&entry.message);
+                                ui.label(format!("×{}", entry.attempts));
+                                match entry.retry {
+                                    RetryState::Scheduled(at) => {
+                                        ui.weak(trf(
+                                            "streaming.errors.retry_in",
+                                            &[("seconds", &fmt_number((at - now).max(0.0), 0))],
+                                        ));
+                                    }
+                                    RetryState::Retrying => {
+                                        ui.weak(tr("streaming.errors.retrying"));
+                                    }
+                                    RetryState::Manual => {
+                                        ui.colored_label(
+                                            egui::Color32::YELLOW,
+                                            tr("streaming.errors.gave_up"),
+                                        );
+                                    }
+                                }
+                                if ui
+                                    .add_enabled(
+                                        entry.retry != RetryState::Retrying,
+                                        egui::Button::new(tr("streaming.errors.retry")),
+                                    )
+                                    .clicked()
+                                {
+                                    retry = Some((entry.kind, entry.path));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some((kind, path)) = retry {
+                ledger.request_retry(kind, path);
+            }
+        });
+}
+
+// ============================================================================
+// Visited-area heat map
+// ============================================================================
+
+fn draw_heatmap_panel(ui: &mut egui::Ui, heatmap: &mut VisitHeatmap) {
+    if !heatmap.is_persistent() {
+        return;
+    }
+    ui.horizontal(|ui| {
+        let mut enabled = heatmap.enabled();
+        if ui
+            .checkbox(&mut enabled, tr("streaming.heatmap"))
+            .on_hover_text(tr("streaming.heatmap.hover"))
+            .changed()
+        {
+            heatmap.set_enabled(enabled);
+        }
+        if heatmap.enabled() {
+            ui.label(trf("streaming.heatmap.areas", &[("count", &heatmap.len())]));
+            if ui
+                .add_enabled(
+                    !heatmap.is_empty(),
+                    egui::Button::new(tr("streaming.heatmap.forget")),
+                )
+                .clicked()
+            {
+                heatmap.forget();
+            }
+        }
+    });
+    let prefetch = heatmap.prefetch();
+    if prefetch.warmed() > 0 || !prefetch.done() {
+        ui.monospace(trf(
+            "streaming.heatmap.prefetch",
+            &[
+                ("warmed", &format!("{:>4}", prefetch.warmed())),
+                ("fetched", &format!("{:>4}", prefetch.fetched())),
+                (
+                    "state",
+                    &if prefetch.done() {
+                        tr("streaming.done")
+                    } else {
+                        tr("streaming.running")
+                    },
+                ),
+            ],
+        ));
+    }
+    if let Some(error) = heatmap.error() {
+        ui.colored_label(egui::Color32::YELLOW, error);
+    }
+}
+
+// ============================================================================
+// Named-area downloads
+// ============================================================================
+
+/// Shape of the area to download.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AreaShape {
+    /// A circle around the camera.
+    Radius,
+    /// A rectangle dragged out on the top-down map.
+    Rect,
+}
+
+/// Area-download form state.
+#[derive(Resource)]
+pub struct AreaPrefetchView {
+    /// Name the download is kept under.
+    pub name: String,
+    pub shape: AreaShape,
+    /// Radius around the camera (m), for [`AreaShape::Radius`].
+    pub radius_m: f64,
+    /// Rectangle drawn on the map, for [`AreaShape::Rect`].
+    pub rect: Option<PrefetchArea>,
+    /// Where the rectangle drag began, while dragging.
+    pub drag_start: Option<egui::Pos2>,
+    /// Deepest octree level to download.
+    pub max_depth: usize,
+}
+
+impl Default for AreaPrefetchView {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            shape: AreaShape::Radius,
+            radius_m: 2000.0,
+            rect: None,
+            drag_start: None,
+            max_depth: 18,
+        }
+    }
+}
+
+impl AreaPrefetchView {
+    /// The selected area, if there is one.
+    fn area(&self, camera_pos: DVec3) -> Option<PrefetchArea> {
+        match self.shape {
+            AreaShape::Radius => {
+                let (lat, lon) = ecef_to_lat_lon(camera_pos);
+                Some(PrefetchArea::Circle {
+                    lat,
+                    lon,
+                    radius_m: self.radius_m,
+                })
+            }
+            AreaShape::Rect => self.rect,
+        }
+    }
+}
+
+fn draw_area_prefetch_panel(
+    ui: &mut egui::Ui,
+    prefetch: &mut AreaPrefetch,
+    view: &mut AreaPrefetchView,
+    camera_pos: DVec3,
+) {
+    if !prefetch.is_persistent() {
+        return;
+    }
+    ui.separator();
+    ui.strong(tr("streaming.area"));
+    ui.horizontal(|ui| {
+        ui.label(tr("streaming.area.name"));
+        ui.text_edit_singleline(&mut view.name);
+    });
+    ui.horizontal(|ui| {
+        ui.selectable_value(
+            &mut view.shape,
+            AreaShape::Radius,
+            tr("streaming.area.radius"),
+        )
+        .on_hover_text(tr("streaming.area.radius.hover"));
+        ui.selectable_value(&mut view.shape, AreaShape::Rect, tr("streaming.area.rect"))
+            .on_hover_text(tr("streaming.area.rect.hover"));
+        match view.shape {
+            AreaShape::Radius => {
+                ui.add(
+                    egui::Slider::new(&mut view.radius_m, 200.0..=50_000.0)
+                        .logarithmic(true)
+                        .suffix(" m"),
+                );
+            }
+            AreaShape::Rect => match view.rect {
+                Some(PrefetchArea::Rect {
+                    south,
+                    west,
+                    north,
+                    east,
+                }) => {
+                    ui.label(trf(
+                        "streaming.area.bounds",
+                        &[
+                            ("south", &fmt_number(south, 4)),
+                            ("north", &fmt_number(north, 4)),
+                            ("west", &fmt_number(west, 4)),
+                            ("east", &fmt_number(east, 4)),
+                        ],
+                    ));
+                }
+                _ => {
+                    ui.label(tr("streaming.area.rect.draw"));
+                }
+            },
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("streaming.area.max_depth"));
+        ui.add(egui::Slider::new(
+            &mut view.max_depth,
+            10..=MAX_PREFETCH_DEPTH,
+        ));
+        ui.label(trf(
+            "streaming.area.node_size",
+            &[(
+                "metres",
+                &fmt_number(approximate_node_size_m(view.max_depth), 0),
+            )],
+        ));
+    });
+
+    let area = view.area(camera_pos);
+    let name = view.name.trim();
+    ui.horizontal(|ui| {
+        if let Some(area) = area {
+            ui.label(format!("{} km²", fmt_number(area.area_km2(), 1)));
+        }
+        if ui
+            .add_enabled(
+                area.is_some(),
+                egui::Button::new(tr("streaming.area.estimate")),
+            )
+            .on_hover_text(tr("streaming.area.estimate.hover"))
+            .clicked()
+            && let Some(area) = area
+        {
+            prefetch.estimate(area, view.max_depth);
+        }
+        if ui
+            .add_enabled(
+                area.is_some() && !name.is_empty(),
+                egui::Button::new(tr("streaming.area.download")),
+            )
+            .on_hover_text(tr("streaming.area.download.hover"))
+            .clicked()
+            && let Some(area) = area
+        {
+            prefetch.download(name, area, view.max_depth);
+        }
+    });
+
+    if let Some(job) = prefetch.job() {
+        let progress = &job.progress;
+        ui.horizontal(|ui| {
+            match &job.name {
+                Some(name) => ui.monospace(trf(
+                    "streaming.area.downloading",
+                    &[
+                        ("name", name),
+                        ("warmed", &format!("{:>5}", progress.warmed())),
+                        ("nodes", &format!("{:>5}", progress.nodes())),
+                        ("mib", &padded(progress.bytes() as f64 / MIB, 1, 6)),
+                        ("failed", &progress.failed()),
+                    ],
+                )),
+                None => ui.monospace(trf(
+                    "streaming.area.estimating",
+                    &[
+                        ("nodes", &format!("{:>5}", progress.nodes())),
+                        ("bulks", &format!("{:>4}", progress.bulks())),
+                    ],
+                )),
+            };
+            if ui.button(tr("streaming.area.pause")).clicked() {
+                prefetch.pause();
+            }
+        });
+    } else if let Some(job) = prefetch.finished() {
+        let progress = &job.progress;
+        let outcome = if progress.cancelled() {
+            tr("streaming.stopped")
+        } else {
+            tr("streaming.done")
+        };
+        match &job.name {
+            Some(name) => ui.monospace(trf(
+                "streaming.area.downloaded",
+                &[
+                    ("name", name),
+                    ("outcome", &outcome),
+                    ("warmed", &progress.warmed()),
+                    ("fetched", &progress.fetched()),
+                    ("mib", &fmt_number(progress.bytes() as f64 / MIB, 1)),
+                    ("failed", &progress.failed()),
+                ],
+            )),
+            None => ui.monospace(trf(
+                "streaming.area.estimated",
+                &[
+                    ("outcome", &outcome),
+                    ("nodes", &progress.nodes()),
+                    ("bulks", &progress.bulks()),
+                    (
+                        "mib",
+                        &fmt_number(prefetch.estimated_bytes(progress.nodes()) as f64 / MIB, 0),
+                    ),
+                ],
+            )),
+        }
+        .on_hover_text(tr("streaming.area.estimated.hover"));
+    }
+
+    let mut action = None;
+    for named in prefetch.areas() {
+        ui.horizontal(|ui| {
+            ui.label(trf(
+                "streaming.area.named",
+                &[
+                    ("name", &named.name),
+                    (
+                        "state",
+                        &if named.complete {
+                            tr("streaming.area.complete")
+                        } else {
+                            tr("streaming.area.incomplete")
+                        },
+                    ),
+                    ("depth", &named.max_depth),
+                ],
+            ));
+            let label = if named.complete {
+                tr("streaming.area.refresh")
+            } else {
+                tr("streaming.area.resume")
+            };
+            if ui.small_button(label).clicked() {
+                action = Some((named.name.clone(), true));
+            }
+            if ui.small_button(tr("streaming.area.remove")).clicked() {
+                action = Some((named.name.clone(), false));
+            }
+        });
+    }
+    match action {
+        Some((name, true)) => prefetch.resume(&name),
+        Some((name, false)) => prefetch.remove(&name),
+        None => {}
+    }
+    if let Some(error) = prefetch.error() {
+        ui.colored_label(egui::Color32::YELLOW, error);
+    }
+}
+
+// ============================================================================
+// Refinement strategy controls
+// ============================================================================
+
+/// Deepest selectable depth cap; tiles rarely go past it.
+const MAX_CAPPED_DEPTH: usize = 22;
+
+fn draw_refinement_controls(ui: &mut egui::Ui, strategy: &mut RefinementStrategy) {
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label(tr("streaming.refinement"));
+        for (preset, label, hover) in [
+            (
+                RefinementStrategy::SCREEN_SPACE_ERROR,
+                tr("streaming.refinement.screen_space"),
+                tr("streaming.refinement.screen_space.hover"),
+            ),
+            (
+                RefinementStrategy::METERS_PER_TEXEL,
+                tr("streaming.refinement.meters_per_texel"),
+                tr("streaming.refinement.meters_per_texel.hover"),
+            ),
+            (
+                RefinementStrategy::DISTANCE_BANDS,
+                tr("streaming.refinement.distance_bands"),
+                tr("streaming.refinement.distance_bands.hover"),
+            ),
+        ] {
+            let selected = std::mem::discriminant(strategy) == std::mem::discriminant(&preset);
+            if ui
+                .selectable_label(selected, label)
+                .on_hover_text(hover)
+                .clicked()
+                && !selected
+            {
+                *strategy = preset;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| match strategy {
+        RefinementStrategy::ScreenSpaceError { max_error_px } => {
+            ui.label(tr("streaming.refinement.max_error"));
+            ui.add(
+                egui::Slider::new(max_error_px, 0.1..=8.0)
+                    .logarithmic(true)
+                    .suffix(" px"),
+            );
+        }
+        RefinementStrategy::MetersPerTexel { max_per_km } => {
+            ui.label(tr("streaming.refinement.max_texel_size"));
+            ui.add(
+                egui::Slider::new(max_per_km, 0.05..=20.0)
+                    .logarithmic(true)
+                    .suffix(" m/km"),
+            );
+        }
+        RefinementStrategy::DistanceBands {
+            first_band_m,
+            first_band_meters_per_texel,
+        } => {
+            ui.label(tr("streaming.refinement.first_band"));
+            ui.add(
+                egui::Slider::new(first_band_m, 25.0..=5000.0)
+                    .logarithmic(true)
+                    .suffix(" m"),
+            );
+            ui.label(tr("streaming.refinement.first_band.at"));
+            ui.add(
+                egui::Slider::new(first_band_meters_per_texel, 0.01..=10.0)
+                    .logarithmic(true)
+                    .suffix(tr("streaming.refinement.first_band.suffix")),
+            );
+        }
+    });
+}
+
+/// Depth cap slider and the "Boost detail here" button.
+fn draw_detail_controls(
+    ui: &mut egui::Ui,
+    detail: &mut LodDetail,
+    boost_request: &mut DetailBoostRequest,
+    now: f64,
+) {
+    ui.horizontal(|ui| {
+        let mut capped = detail.depth_cap.is_some();
+        if ui
+            .checkbox(&mut capped, tr("streaming.depth_cap"))
+            .on_hover_text(tr("streaming.depth_cap.hover"))
+            .changed()
+        {
+            detail.depth_cap = capped.then_some(MAX_CAPPED_DEPTH);
+        }
+        if let Some(cap) = &mut detail.depth_cap {
+            ui.add(egui::Slider::new(cap, 4..=MAX_CAPPED_DEPTH));
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui
+            .button(tr("streaming.boost"))
+            .on_hover_text(tr("streaming.boost.hover"))
+            .clicked()
+        {
+            boost_request.wanted = true;
+        }
+        if let Some(boost) = detail.boost() {
+            ui.label(trf(
+                "streaming.boost.active",
+                &[
+                    ("radius", &fmt_number(boost.radius, 0)),
+                    ("seconds", &fmt_number((boost.expires_at - now).max(0.0), 0)),
+                ],
+            ));
+            if ui.small_button(tr("common.stop")).clicked() {
+                detail.cancel_boost();
+            }
+        } else if detail.ended_over_budget() {
+            ui.colored_label(egui::Color32::YELLOW, tr("streaming.boost.over_budget"));
+        }
+    });
 }
 
 // ============================================================================
@@ -139,17 +1004,29 @@ pub(super) fn render_streaming_tab(ui: &mut egui::Ui, params: &mut StreamingPara
 
 fn draw_in_world_overlay_controls(ui: &mut egui::Ui, viz: &mut LodVizSettings) {
     ui.separator();
-    ui.label("In-world overlay:");
+    ui.label(tr("streaming.overlay"));
     ui.horizontal(|ui| {
-        ui.checkbox(&mut viz.draw_render_tiles, "Render tiles")
-            .on_hover_text("OBBs of the terrain meshes currently visible, coloured by depth.");
-        ui.checkbox(&mut viz.draw_collider_tiles, "Collider tiles")
-            .on_hover_text("OBBs of the tiles currently hosting physics colliders (white-tinted).");
-        ui.checkbox(&mut viz.draw_loading_nodes, "Loading")
-            .on_hover_text("Dim OBBs of the nodes with in-flight load requests.");
+        ui.checkbox(&mut viz.draw_render_tiles, tr("streaming.overlay.render"))
+            .on_hover_text(tr("streaming.overlay.render.hover"));
+        ui.checkbox(
+            &mut viz.draw_collider_tiles,
+            tr("streaming.overlay.colliders"),
+        )
+        .on_hover_text(tr("streaming.overlay.colliders.hover"));
+        ui.checkbox(&mut viz.draw_loading_nodes, tr("streaming.overlay.loading"))
+            .on_hover_text(tr("streaming.overlay.loading.hover"));
+    });
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut viz.tint_by_depth, tr("streaming.overlay.tint"))
+            .on_hover_text(tr("streaming.overlay.tint.hover"));
+        ui.add_enabled(
+            viz.tint_by_depth,
+            egui::Slider::new(&mut viz.tint_strength, 0.1..=1.0)
+                .text(tr("streaming.overlay.tint.strength")),
+        );
     });
     ui.horizontal(|ui| {
-        ui.label("Overlay range:");
+        ui.label(tr("streaming.overlay.range"));
         ui.add(
             egui::Slider::new(&mut viz.max_distance_m, 100.0..=20000.0)
                 .logarithmic(true)
@@ -157,13 +1034,13 @@ fn draw_in_world_overlay_controls(ui: &mut egui::Ui, viz: &mut LodVizSettings) {
         );
     });
     ui.horizontal(|ui| {
-        ui.label("Overlay depth:");
+        ui.label(tr("streaming.overlay.depth"));
         ui.add(
             egui::DragValue::new(&mut viz.depth_min)
                 .range(0..=viz.depth_max)
                 .speed(0.1),
         );
-        ui.label("to");
+        ui.label(tr("streaming.overlay.depth.to"));
         ui.add(
             egui::DragValue::new(&mut viz.depth_max)
                 .range(viz.depth_min..=OctreePath::MAX_DEPTH)
@@ -180,8 +1057,10 @@ fn draw_top_down_map(
     ui: &mut egui::Ui,
     snapshot: &LodSnapshot,
     view: &DiagnosticsViewState,
+    area_view: &mut AreaPrefetchView,
     tuning: &LodTuning,
     streaming: &PhysicsStreamingConfig,
+    palette: DebugPalette,
 ) {
     let Some(camera_pos) = snapshot.camera_pos else {
         return;
@@ -190,7 +1069,13 @@ fn draw_top_down_map(
 
     // Square map area. The egui painter clips to the allocated rect.
     let size = egui::Vec2::splat(360.0);
-    let (rect, _response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    // Dragging draws the area-download rectangle, when that's the shape.
+    let sense = if area_view.shape == AreaShape::Rect {
+        egui::Sense::drag()
+    } else {
+        egui::Sense::hover()
+    };
+    let (rect, response) = ui.allocate_exact_size(size, sense);
     let painter = ui.painter_at(rect);
 
     // Background.
@@ -232,6 +1117,20 @@ fn draw_top_down_map(
         );
     }
 
+    // Coarse collider tier reach (cool tint, lighter than the full-detail
+    // range it backs).
+    let coarse_r = streaming.coarse_range as f32 * pixels_per_m;
+    if coarse_r > 1.0 && coarse_r < rect.width() {
+        painter.circle_stroke(
+            center,
+            coarse_r,
+            egui::Stroke::new(
+                1.0,
+                egui::Color32::from_rgba_unmultiplied(80, 120, 200, 200),
+            ),
+        );
+    }
+
     // Keep-loaded radius (render-BFS proximity bypass), dashed-feel
     // green so it visually reads as "this is a retention boundary,
     // not a physics one".
@@ -257,7 +1156,7 @@ fn draw_top_down_map(
         let screen_pos = world_to_screen(node.obb.center);
         let r_px = (node.obb.extents.length() as f32 * pixels_per_m).clamp(2.0, 24.0);
 
-        let color = depth_color(node.depth);
+        let color = depth_color(node.depth, palette);
         let alpha: u8 = match node.state {
             SnapshotNodeState::Loaded => 230,
             SnapshotNodeState::Loading => 130,
@@ -307,6 +1206,65 @@ fn draw_top_down_map(
         painter.circle_filled(end_pos, 3.0, egui::Color32::from_rgb(255, 180, 80));
     }
 
+    // Area-download selection.
+    let screen_to_lat_lon = |pos: egui::Pos2| -> (f64, f64) {
+        let east = f64::from((pos.x - center.x) / pixels_per_m);
+        let north = f64::from((center.y - pos.y) / pixels_per_m);
+        ecef_to_lat_lon(camera_pos + frame.east.as_dvec3() * east + frame.north.as_dvec3() * north)
+    };
+    if let Some(start) = response.interact_pointer_pos()
+        && response.drag_started()
+    {
+        area_view.drag_start = Some(start);
+    }
+    if let (Some(start), Some(end)) = (area_view.drag_start, response.interact_pointer_pos()) {
+        // West and east come from the left and right edges of the drag, not
+        // the smaller and larger longitude, so a drag over the antimeridian
+        // gives a rectangle that wraps across it rather than a band around
+        // the planet.
+        let (left, right) = if start.x <= end.x {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let (lat_a, west) = screen_to_lat_lon(left);
+        let (lat_b, east) = screen_to_lat_lon(right);
+        area_view.rect = PrefetchArea::Rect {
+            south: lat_a.min(lat_b),
+            west,
+            north: lat_a.max(lat_b),
+            east,
+        }
+        .normalized();
+    }
+    if response.drag_stopped() {
+        area_view.drag_start = None;
+    }
+    let selection_stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(230, 200, 60));
+    match area_view.shape {
+        AreaShape::Radius => {
+            painter.circle_stroke(
+                center,
+                area_view.radius_m as f32 * pixels_per_m,
+                selection_stroke,
+            );
+        }
+        AreaShape::Rect => {
+            if let Some(PrefetchArea::Rect {
+                south,
+                west,
+                north,
+                east,
+            }) = area_view.rect
+            {
+                let radius = camera_pos.length();
+                let corners = [(south, west), (south, east), (north, east), (north, west)]
+                    .map(|(lat, lon)| world_to_screen(lat_lon_to_ecef(lat, lon, radius)));
+                painter.add(egui::Shape::closed_line(corners.to_vec(), selection_stroke));
+            }
+        }
+    }
+
     // Player marker.
     painter.circle_filled(center, 4.5, egui::Color32::WHITE);
     painter.circle_stroke(
@@ -319,7 +1277,7 @@ fn draw_top_down_map(
     painter.text(
         egui::pos2(center.x, rect.top() + 12.0),
         egui::Align2::CENTER_CENTER,
-        "N",
+        tr("dir.n"),
         egui::FontId::proportional(13.0),
         egui::Color32::from_rgba_unmultiplied(180, 180, 200, 200),
     );
@@ -328,19 +1286,16 @@ fn draw_top_down_map(
 /// Map an octree depth to a color along a cool-→-warm gradient. Defers to the
 /// engine's [`veldera_terrain::collider::viz::depth_color`] so the top-down map
 /// and the in-world overlay use the same colour language.
-fn depth_color(depth: usize) -> egui::Color32 {
-    let [r, g, b, _] = veldera_terrain::collider::viz::depth_color(depth)
-        .to_srgba()
-        .to_u8_array();
-    egui::Color32::from_rgb(r, g, b)
+fn depth_color(depth: usize, palette: DebugPalette) -> egui::Color32 {
+    egui_color(veldera_terrain::collider::viz::depth_color(depth, palette))
 }
 
 // ============================================================================
 // Histogram
 // ============================================================================
 
-fn draw_per_depth_histogram(ui: &mut egui::Ui, snapshot: &LodSnapshot) {
-    ui.label("Nodes per depth (render | physics, loaded ▓ loading ░):");
+fn draw_per_depth_histogram(ui: &mut egui::Ui, snapshot: &LodSnapshot, palette: DebugPalette) {
+    ui.label(tr("streaming.histogram"));
 
     let counters = &snapshot.counters;
     let max_count = counters
@@ -354,7 +1309,7 @@ fn draw_per_depth_histogram(ui: &mut egui::Ui, snapshot: &LodSnapshot) {
         .unwrap_or(0);
 
     if max_count == 0 {
-        ui.weak("(no nodes in current snapshot)");
+        ui.weak(tr("streaming.histogram.empty"));
         return;
     }
 
@@ -379,7 +1334,7 @@ fn draw_per_depth_histogram(ui: &mut egui::Ui, snapshot: &LodSnapshot) {
 
         let h_for = |count: f32| count / max_count as f32 * (rect.height() - 14.0);
 
-        let color = depth_color(depth);
+        let color = depth_color(depth, palette);
 
         let bottom = rect.bottom() - 12.0;
 
@@ -450,39 +1405,77 @@ fn draw_per_depth_histogram(ui: &mut egui::Ui, snapshot: &LodSnapshot) {
 
 fn draw_counters_panel(ui: &mut egui::Ui, snapshot: &LodSnapshot, mesh_count: usize) {
     let c = &snapshot.counters;
-    ui.monospace(format!(
-        "Render BFS   loaded {:>4}   loading {:>4}   meshes {:>4}",
-        c.render_loaded, c.render_loading, mesh_count,
+    ui.monospace(trf(
+        "streaming.counters.render",
+        &[
+            ("loaded", &format!("{:>4}", c.render_loaded)),
+            ("loading", &format!("{:>4}", c.render_loading)),
+            ("meshes", &format!("{mesh_count:>4}")),
+            ("cancelled", &format!("{:>5}", c.render_cancelled)),
+        ],
     ));
     let (phys_min, phys_max) = collider_depth_range(snapshot);
-    ui.monospace(format!(
-        "Physics      colliders {:>4}   pending {:>4}   depth {}..{}",
-        c.physics_colliders,
-        c.physics_pending,
-        phys_min.map_or("—".to_string(), |d| d.to_string()),
-        phys_max.map_or("—".to_string(), |d| d.to_string()),
+    ui.monospace(trf(
+        "streaming.counters.physics",
+        &[
+            ("colliders", &format!("{:>4}", c.physics_colliders)),
+            ("pending", &format!("{:>4}", c.physics_pending)),
+            ("min", &phys_min.map_or("—".to_string(), |d| d.to_string())),
+            ("max", &phys_max.map_or("—".to_string(), |d| d.to_string())),
+        ],
     ));
     if c.physics_uncovered > 0 {
         ui.colored_label(
             egui::Color32::from_rgb(255, 80, 80),
-            format!(
-                "  ⚠ {} region(s) with NO collider coverage",
-                c.physics_uncovered
+            trf(
+                "streaming.counters.uncovered",
+                &[("count", &c.physics_uncovered)],
             ),
         );
     }
-    ui.monospace(format!(
-        "Bulks        cached {:>4}   loading {:>4}   failed {:>4}",
-        c.bulks_cached, c.bulks_loading, c.bulks_failed
+    ui.monospace(trf(
+        "streaming.counters.bulks",
+        &[
+            ("cached", &format!("{:>4}", c.bulks_cached)),
+            ("loading", &format!("{:>4}", c.bulks_loading)),
+            ("failed", &format!("{:>4}", c.bulks_failed)),
+        ],
     ));
-    let speed = snapshot.velocity.length();
-    let lead = snapshot.lead.length();
-    ui.monospace(format!(
-        "Motion       speed {:>6.2} m/s   lead {:>5.1} m",
-        speed, lead
+    ui.monospace(trf(
+        "streaming.counters.motion",
+        &[
+            ("speed", &padded(snapshot.velocity.length(), 2, 6)),
+            ("lead", &padded(snapshot.lead.length(), 1, 5)),
+        ],
     ));
 }
 
+fn draw_collider_tiers(ui: &mut egui::Ui, stats: &ColliderTierStats) {
+    let row = |name: &str, tier: &TierStats| {
+        trf(
+            "streaming.tier",
+            &[
+                ("name", &format!("{name:<11}")),
+                ("reach", &padded(tier.reach_m, 0, 6)),
+                ("tiles", &format!("{:>4}", tier.tiles)),
+                ("triangles", &format!("{:>7}", tier.triangles)),
+                ("drift", &padded(tier.drift_m, 0, 5)),
+                ("builds", &format!("{:>4}", tier.builds)),
+                (
+                    "building",
+                    &if tier.building {
+                        tr("streaming.tier.building")
+                    } else {
+                        ""
+                    },
+                ),
+            ],
+        )
+    };
+    ui.monospace(row(tr("streaming.tier.full"), &stats.fine));
+    ui.monospace(row(tr("streaming.tier.coarse"), &stats.coarse));
+}
+
 fn collider_depth_range(snapshot: &LodSnapshot) -> (Option<usize>, Option<usize>) {
     let mut min = None;
     let mut max = None;
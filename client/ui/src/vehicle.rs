@@ -1,7 +1,8 @@
 //! Vehicles tab for the debug UI.
 //!
-//! Spawner buttons up top, plus drivetrain/per-wheel diagnostics, plots, and
-//! tuning sliders for the currently-driven vehicle (if any).
+//! Spawner buttons and the telemetry recorder up top, plus drivetrain/per-wheel
+//! diagnostics, plots, and tuning sliders for the currently-driven vehicle (if
+//! any).
 
 use std::collections::VecDeque;
 
@@ -13,10 +14,18 @@ use egui_plot::{Line, Plot, PlotPoints};
 use veldera_game_camera::FollowEntityTarget;
 use veldera_game_camera_state::CameraModeState;
 
+use veldera_physics::DebugPalette;
+
 use veldera_game_vehicle::{
-    Vehicle, VehicleActions, VehicleChassisConfig, VehicleDefinitions, VehicleEngineConfig,
-    VehicleInput, VehicleRightRequest, VehicleState, VehicleSteeringConfig,
-    VehicleSuspensionConfig, VehicleTireConfig, VehicleTransmissionConfig,
+    Vehicle, VehicleActions, VehicleChassisConfig, VehicleDamage, VehicleDefinitions,
+    VehicleEngineConfig, VehicleInput, VehicleRespawnRequest, VehicleRightRequest, VehicleState,
+    VehicleSteeringConfig, VehicleSuspensionConfig, VehicleTireConfig, VehicleTransmissionConfig,
+    recorder::{RecordingFormat, TelemetryRecorder},
+};
+
+use crate::{
+    egui_color,
+    i18n::{fmt_number, tr, trf},
 };
 
 /// Number of samples to keep in vehicle history.
@@ -65,6 +74,7 @@ pub(super) struct VehicleParams<'w, 's> {
             &'static Vehicle,
             &'static VehicleState,
             &'static VehicleInput,
+            &'static VehicleDamage,
             (
                 &'static mut VehicleChassisConfig,
                 &'static mut VehicleSuspensionConfig,
@@ -77,13 +87,18 @@ pub(super) struct VehicleParams<'w, 's> {
     >,
     pub vehicle_history: ResMut<'w, VehicleHistory>,
     pub vehicle_right_request: ResMut<'w, VehicleRightRequest>,
+    pub vehicle_respawn_request: ResMut<'w, VehicleRespawnRequest>,
     pub follow_query: Query<'w, 's, &'static FollowEntityTarget>,
+    pub recorder: ResMut<'w, TelemetryRecorder>,
+    pub time: Res<'w, Time>,
+    pub palette: Res<'w, DebugPalette>,
 }
 
 /// Render the vehicles tab content: spawner first, then diagnostics and
 /// tuning for the currently-driven vehicle (or the first spawned one).
 pub(super) fn render_vehicles_tab(ui: &mut egui::Ui, params: &mut VehicleParams) {
     render_spawner(ui, params);
+    render_recorder(ui, &mut params.recorder, &params.time);
 
     // Prefer the vehicle the camera is following; several may be parked.
     let followed = params.follow_query.iter().next().map(|f| f.target);
@@ -91,7 +106,7 @@ pub(super) fn render_vehicles_tab(ui: &mut egui::Ui, params: &mut VehicleParams)
         Some(entry) => Some(entry),
         None => params.vehicle_query.iter_mut().next(),
     };
-    let Some((vehicle, state, input, mut configs)) = entry else {
+    let Some((vehicle, state, input, damage, mut configs)) = entry else {
         params.vehicle_history.clear();
         return;
     };
@@ -104,18 +119,21 @@ pub(super) fn render_vehicles_tab(ui: &mut egui::Ui, params: &mut VehicleParams)
         vehicle,
         state,
         input,
+        damage,
         &mut configs,
         &params.vehicle_history,
         &mut params.vehicle_right_request,
+        &mut params.vehicle_respawn_request,
+        *params.palette,
     );
 }
 
 /// Spawner buttons + the minimal "you're in this vehicle" status with
 /// an Exit button when the camera is following one.
 fn render_spawner(ui: &mut egui::Ui, params: &mut VehicleParams) {
-    ui.label("Spawn:");
+    ui.label(tr("vehicle.spawn"));
     if params.vehicle_definitions.vehicles.is_empty() {
-        ui.label("Loading...");
+        ui.label(tr("vehicle.loading"));
     } else {
         ui.horizontal_wrapped(|ui| {
             for (idx, def) in params.vehicle_definitions.vehicles.iter().enumerate() {
@@ -132,12 +150,72 @@ fn render_spawner(ui: &mut egui::Ui, params: &mut VehicleParams) {
 
     if params.camera_mode.is_follow_entity()
         && !params.vehicle_query.is_empty()
-        && ui.button("Exit vehicle (E)").clicked()
+        && ui.button(tr("vehicle.exit")).clicked()
     {
         params.vehicle_actions.request_exit();
     }
 }
 
+/// Telemetry recorder controls: channel and rate selection while stopped,
+/// a start/stop toggle, and the outcome of the last export.
+fn render_recorder(ui: &mut egui::Ui, recorder: &mut TelemetryRecorder, time: &Time) {
+    ui.collapsing(tr("vehicle.recorder"), |ui| {
+        let recording = recorder.is_recording();
+        ui.add_enabled_ui(!recording, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let channels = &mut recorder.channels;
+                ui.checkbox(&mut channels.inputs, tr("vehicle.recorder.inputs"));
+                ui.checkbox(&mut channels.speed, tr("vehicle.recorder.speed"));
+                ui.checkbox(&mut channels.forces, tr("vehicle.recorder.forces"));
+                ui.checkbox(&mut channels.altitude, tr("vehicle.recorder.altitude"));
+                ui.checkbox(&mut channels.position, tr("vehicle.recorder.position"));
+            });
+            ui.add(
+                egui::Slider::new(&mut recorder.sample_rate_hz, 1.0..=120.0)
+                    .text(tr("vehicle.recorder.rate")),
+            );
+            if RecordingFormat::ALL.len() > 1 {
+                ui.horizontal(|ui| {
+                    ui.label(tr("vehicle.recorder.format"));
+                    for &format in RecordingFormat::ALL {
+                        ui.radio_value(&mut recorder.format, format, format.extension());
+                    }
+                });
+            }
+        });
+
+        if recording {
+            ui.horizontal(|ui| {
+                if ui.button(tr("vehicle.recorder.stop")).clicked() {
+                    recorder.stop();
+                }
+                ui.label(trf(
+                    "vehicle.recorder.progress",
+                    &[
+                        ("samples", &recorder.sample_count()),
+                        ("seconds", &fmt_number(recorder.duration(time), 1)),
+                    ],
+                ));
+            });
+        } else if ui.button(tr("vehicle.recorder.start")).clicked() {
+            recorder.start(time);
+        }
+
+        match recorder.last_export() {
+            Some(Ok(path)) => {
+                ui.label(trf("vehicle.recorder.saved", &[("path", &path.display())]));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    trf("vehicle.recorder.failed", &[("error", e)]),
+                );
+            }
+            None => {}
+        }
+    });
+}
+
 /// Mutable references to all per-vehicle tuning configs.
 type VehicleConfigs<'a> = (
     Mut<'a, VehicleChassisConfig>,
@@ -149,24 +227,50 @@ type VehicleConfigs<'a> = (
 );
 
 /// Render vehicle diagnostics section.
+#[allow(clippy::too_many_arguments)]
 fn render_vehicle_diagnostics(
     ui: &mut egui::Ui,
     vehicle: &Vehicle,
     state: &VehicleState,
     input: &VehicleInput,
+    damage: &VehicleDamage,
     configs: &mut VehicleConfigs,
     history: &VehicleHistory,
     right_request: &mut VehicleRightRequest,
+    respawn_request: &mut VehicleRespawnRequest,
+    palette: DebugPalette,
 ) {
     let (chassis, suspension, engine, transmission, steering, tire) = configs;
 
     ui.horizontal(|ui| {
-        ui.heading(format!("Vehicle: {}", vehicle.name));
-        if ui.button("Right vehicle").clicked() {
+        ui.heading(trf("vehicle.heading", &[("name", &vehicle.name)]));
+        if ui.button(tr("vehicle.right")).clicked() {
             right_request.pending = true;
         }
+        if ui
+            .button(tr("vehicle.respawn"))
+            .on_hover_text(tr("vehicle.respawn.hover"))
+            .clicked()
+        {
+            respawn_request.pending = true;
+        }
     });
 
+    // Damage bar, shading along the palette's good-to-bad ramp as the car
+    // gets worse.
+    let damage_color = egui_color(palette.ramp(damage.damage));
+    ui.add(
+        egui::ProgressBar::new(damage.damage)
+            .fill(damage_color)
+            .text(trf(
+                "vehicle.damage",
+                &[
+                    ("percent", &fmt_number(f64::from(damage.damage) * 100.0, 0)),
+                    ("speed", &fmt_number(f64::from(damage.worst_impact), 1)),
+                ],
+            )),
+    );
+
     // Two-column layout: main diagnostics on left, tuning on right.
     ui.columns(2, |columns| {
         // Left column: vehicle state, wheels, and plots.
@@ -248,11 +352,7 @@ fn render_vehicle_diagnostics(
                             ui.label(format!("{:+.1}", wheel.lateral_slip));
                         });
                         row.col(|ui| {
-                            let color = egui::Color32::from_rgb(
-                                (wheel.saturation * 255.0) as u8,
-                                ((1.0 - wheel.saturation) * 200.0) as u8,
-                                40,
-                            );
+                            let color = egui_color(palette.ramp(wheel.saturation));
                             ui.colored_label(color, format!("{:.2}", wheel.saturation));
                         });
                     });
@@ -276,7 +376,11 @@ fn render_vehicle_diagnostics(
             .allow_zoom(false)
             .allow_scroll(false)
             .show(ui, |plot_ui| {
-                plot_ui.line(Line::new("speed", speed_points).color(egui::Color32::LIGHT_BLUE));
+                plot_ui.line(Line::new("speed", speed_points).color(plot_color(
+                    palette,
+                    1,
+                    egui::Color32::LIGHT_BLUE,
+                )));
             });
 
         // RPM plot.
@@ -297,10 +401,14 @@ fn render_vehicle_diagnostics(
             .allow_zoom(false)
             .allow_scroll(false)
             .show(ui, |plot_ui| {
-                plot_ui.line(Line::new("rpm", rpm_points).color(egui::Color32::LIGHT_GREEN));
+                plot_ui.line(Line::new("rpm", rpm_points).color(plot_color(
+                    palette,
+                    2,
+                    egui::Color32::LIGHT_GREEN,
+                )));
                 plot_ui.line(
                     Line::new("redline", redline)
-                        .color(egui::Color32::RED)
+                        .color(plot_color(palette, 5, egui::Color32::RED))
                         .style(egui_plot::LineStyle::dashed_dense()),
                 );
             });
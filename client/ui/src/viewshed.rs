@@ -0,0 +1,451 @@
+//! Viewshed analysis: which terrain around an observer is in line of sight.
+//!
+//! With picking on, clicking the terrain (cursor free) places the observer.
+//! A polar grid of cells around it, out to the chosen radius, is dropped onto
+//! the loaded terrain meshes by downward raycasts, then each cell is tested
+//! with a ray from the observer's eye. The work is spread over frames. The
+//! result is an overlay mesh draped over the terrain, tinted green where the
+//! terrain is visible and red where it is hidden.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    ecs::system::SystemParam,
+    light::{NotShadowCaster, NotShadowReceiver},
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+use bevy_egui::{egui, input::EguiWantsInput};
+use glam::DVec3;
+
+use veldera_geo::{
+    coords::{RadialFrame, ecef_to_lat_lon},
+    floating_origin::WorldPosition,
+};
+use veldera_terrain::{
+    pick::TerrainPicker,
+    raycast::{TerrainHit, TerrainRaycast},
+};
+
+use crate::i18n::{fmt_lat_lon, fmt_number, tr, trf};
+
+/// Spokes of the polar grid.
+const AZIMUTHS: usize = 120;
+
+/// Rings of the polar grid.
+const RINGS: usize = 48;
+
+/// Cells tested per frame, keeping each frame's raycasts cheap.
+const CELLS_PER_FRAME: usize = 400;
+
+/// Height above the observer's ground the downward cell rays start from (m).
+const DROP_HEIGHT_M: f64 = 10_000.0;
+
+/// Height the overlay floats above the terrain, against z-fighting (m).
+const OVERLAY_LIFT_M: f32 = 1.5;
+
+/// Overlay opacity.
+const OVERLAY_ALPHA: f32 = 0.4;
+
+/// Plugin for the viewshed's picking, computation and overlay.
+pub(super) struct ViewshedPlugin;
+
+impl Plugin for ViewshedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Viewshed>().add_systems(
+            Update,
+            (place_observer_on_click, compute_viewshed, sync_overlay).chain(),
+        );
+    }
+}
+
+/// One grid cell's analysis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Cell {
+    /// Not tested yet.
+    #[default]
+    Pending,
+    /// No loaded terrain under the cell.
+    Unknown,
+    /// Terrain at this ground point (ECEF), and whether the eye sees it.
+    Tested { ground: DVec3, visible: bool },
+}
+
+/// The observer, parameters and per-cell results.
+#[derive(Resource)]
+pub(super) struct Viewshed {
+    /// Whether left clicks on the terrain place the observer.
+    pub picking: bool,
+    /// Analysis radius (m).
+    pub radius_m: f64,
+    /// Eye height above the observer's ground (m).
+    pub eye_height_m: f64,
+    /// Observer ground point (ECEF).
+    observer: Option<DVec3>,
+    /// Cells ring-major, outward from the observer.
+    cells: Vec<Cell>,
+    /// Next cell to test.
+    next: usize,
+    /// Bumped whenever cells change; the overlay is rebuilt when it moves.
+    revision: u64,
+}
+
+impl Default for Viewshed {
+    fn default() -> Self {
+        Self {
+            picking: false,
+            radius_m: 3_000.0,
+            eye_height_m: 1.7,
+            observer: None,
+            cells: Vec::new(),
+            next: 0,
+            revision: 0,
+        }
+    }
+}
+
+impl Viewshed {
+    /// Place the observer and restart the analysis.
+    fn set_observer(&mut self, hit: &TerrainHit) {
+        self.observer = Some(hit.position);
+        self.restart();
+    }
+
+    /// Restart the analysis from the first cell.
+    fn restart(&mut self) {
+        self.cells = vec![Cell::default(); AZIMUTHS * RINGS];
+        self.next = 0;
+        self.revision += 1;
+    }
+
+    fn clear(&mut self) {
+        self.observer = None;
+        self.cells.clear();
+        self.next = 0;
+        self.revision += 1;
+    }
+
+    /// Fraction of cells tested.
+    fn progress(&self) -> f32 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.next as f32 / self.cells.len() as f32
+    }
+
+    /// Visible and hidden cell counts.
+    fn counts(&self) -> (usize, usize) {
+        self.cells
+            .iter()
+            .fold((0, 0), |(seen, hidden), cell| match cell {
+                Cell::Tested { visible: true, .. } => (seen + 1, hidden),
+                Cell::Tested { visible: false, .. } => (seen, hidden + 1),
+                _ => (seen, hidden),
+            })
+    }
+}
+
+/// Horizontal offset (m, in the observer's east/north plane) of cell
+/// `index` for a grid of `radius` metres.
+fn cell_offset(index: usize, radius: f64) -> (f64, f64) {
+    let (ring, azimuth) = (index / AZIMUTHS, index % AZIMUTHS);
+    let distance = radius * (ring + 1) as f64 / RINGS as f64;
+    let angle = std::f64::consts::TAU * azimuth as f64 / AZIMUTHS as f64;
+    (distance * angle.sin(), distance * angle.cos())
+}
+
+/// Triangle indices for the overlay: a fan from the centre vertex (index 0)
+/// to the first ring, then quads between consecutive rings. Cell `i` is
+/// vertex `i + 1`.
+fn overlay_indices() -> Vec<u32> {
+    let vertex = |ring: usize, azimuth: usize| (1 + ring * AZIMUTHS + azimuth % AZIMUTHS) as u32;
+    let mut indices = Vec::with_capacity(AZIMUTHS * (3 + (RINGS - 1) * 6));
+    for azimuth in 0..AZIMUTHS {
+        indices.extend([0, vertex(0, azimuth + 1), vertex(0, azimuth)]);
+    }
+    for ring in 0..RINGS - 1 {
+        for azimuth in 0..AZIMUTHS {
+            let (a, b) = (vertex(ring, azimuth), vertex(ring, azimuth + 1));
+            let (c, d) = (vertex(ring + 1, azimuth), vertex(ring + 1, azimuth + 1));
+            indices.extend([a, b, c, b, d, c]);
+        }
+    }
+    indices
+}
+
+/// Overlay colour for a cell: green if visible, red if hidden, clear
+/// otherwise.
+fn cell_color(cell: &Cell) -> [f32; 4] {
+    match cell {
+        Cell::Tested { visible: true, .. } => [0.1, 0.9, 0.2, OVERLAY_ALPHA],
+        Cell::Tested { visible: false, .. } => [0.95, 0.15, 0.1, OVERLAY_ALPHA],
+        Cell::Pending | Cell::Unknown => [0.0; 4],
+    }
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Place the observer on the terrain under the cursor on a left click, while
+/// picking is on and the cursor is free.
+fn place_observer_on_click(
+    mouse: Res<ButtonInput<MouseButton>>,
+    egui_wants: Res<EguiWantsInput>,
+    cursor: Single<&CursorOptions>,
+    picker: Res<TerrainPicker>,
+    mut viewshed: ResMut<Viewshed>,
+) {
+    if !viewshed.picking
+        || cursor.grab_mode != CursorGrabMode::None
+        || !mouse.just_pressed(MouseButton::Left)
+        || egui_wants.is_pointer_over_area()
+    {
+        return;
+    }
+    if let Some(hit) = picker.hit() {
+        viewshed.set_observer(hit);
+    }
+}
+
+/// Test the next batch of cells: drop each onto the terrain, then cast from
+/// the eye towards it. Terrain in the way stops the ray short of the cell.
+fn compute_viewshed(mut viewshed: ResMut<Viewshed>, raycast: TerrainRaycast) {
+    let Some(observer) = viewshed.observer else {
+        return;
+    };
+    if viewshed.next >= viewshed.cells.len() {
+        return;
+    }
+    let frame = RadialFrame::from_ecef_position(observer);
+    let (up, east, north) = (
+        frame.up.as_dvec3(),
+        frame.east.as_dvec3(),
+        frame.north.as_dvec3(),
+    );
+    let eye = observer + up * viewshed.eye_height_m;
+    let radius = observer.length();
+
+    let viewshed = &mut *viewshed;
+    let end = (viewshed.next + CELLS_PER_FRAME).min(viewshed.cells.len());
+    for index in viewshed.next..end {
+        let (x, y) = cell_offset(index, viewshed.radius_m);
+        // Follow the curvature: project the tangent offset back onto the
+        // sphere through the observer before dropping.
+        let cell_up = (observer + east * x + north * y).normalize();
+        let drop_from = cell_up * (radius + DROP_HEIGHT_M);
+        let ground = raycast
+            .cast_ray(drop_from, -cell_up, 2.0 * DROP_HEIGHT_M)
+            .map(|hit| hit.position);
+        viewshed.cells[index] = match ground {
+            Some(ground) => {
+                let to_ground = ground - eye;
+                let distance = to_ground.length();
+                // Grazing rays hit the cell's own triangles a little short.
+                let tolerance = 1.0 + distance * 0.002;
+                let blocked = raycast
+                    .cast_ray(eye, to_ground, distance - tolerance)
+                    .is_some();
+                Cell::Tested {
+                    ground,
+                    visible: !blocked,
+                }
+            }
+            None => Cell::Unknown,
+        };
+    }
+    viewshed.next = end;
+    viewshed.revision += 1;
+}
+
+/// Marks the overlay entity.
+#[derive(Component)]
+struct ViewshedOverlay;
+
+/// Rebuild the overlay mesh whenever the cells change, spawning the overlay
+/// on first use and despawning it once the observer is cleared.
+fn sync_overlay(
+    mut commands: Commands,
+    viewshed: Res<Viewshed>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut overlay_query: Query<(Entity, &Mesh3d, &mut WorldPosition), With<ViewshedOverlay>>,
+    mut synced: Local<u64>,
+) {
+    if *synced == viewshed.revision {
+        return;
+    }
+    *synced = viewshed.revision;
+
+    let Some(observer) = viewshed.observer else {
+        for (entity, ..) in &overlay_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    let mesh = overlay_mesh(&viewshed, observer);
+    if let Ok((_, mesh_handle, mut world_position)) = overlay_query.single_mut() {
+        world_position.position = observer;
+        let _ = meshes.insert(&mesh_handle.0, mesh);
+        return;
+    }
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        // Seen from above and below alike.
+        cull_mode: None,
+        double_sided: true,
+        depth_bias: 100.0,
+        ..default()
+    });
+    commands.spawn((
+        Name::new("Viewshed overlay"),
+        ViewshedOverlay,
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::default(),
+        Visibility::default(),
+        WorldPosition::from_dvec3(observer),
+        NotShadowCaster,
+        NotShadowReceiver,
+    ));
+}
+
+/// The overlay mesh, relative to `observer`: a vertex per cell on its
+/// ground point, tinted by [`cell_color`].
+fn overlay_mesh(viewshed: &Viewshed, observer: DVec3) -> Mesh {
+    let frame = RadialFrame::from_ecef_position(observer);
+    let (east, north) = (frame.east.as_dvec3(), frame.north.as_dvec3());
+    let mut positions = vec![(frame.up * OVERLAY_LIFT_M).to_array()];
+    let mut colors = vec![[0.2, 0.6, 1.0, 1.0]];
+    for (index, cell) in viewshed.cells.iter().enumerate() {
+        // Cells without ground sit at the observer's height, fully clear.
+        let ground = match cell {
+            Cell::Tested { ground, .. } => *ground,
+            Cell::Pending | Cell::Unknown => {
+                let (x, y) = cell_offset(index, viewshed.radius_m);
+                (observer + east * x + north * y).normalize() * observer.length()
+            }
+        };
+        let cell_up = ground.normalize().as_vec3();
+        positions.push(((ground - observer).as_vec3() + cell_up * OVERLAY_LIFT_M).to_array());
+        colors.push(cell_color(cell));
+    }
+    let normals = vec![frame.up.to_array(); positions.len()];
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_indices(Indices::U32(overlay_indices()));
+    mesh
+}
+
+// ============================================================================
+// Panel
+// ============================================================================
+
+/// Resources for the viewshed panel.
+#[derive(SystemParam)]
+pub(super) struct ViewshedParams<'w> {
+    pub viewshed: ResMut<'w, Viewshed>,
+}
+
+/// Render the viewshed section: picking, radius and eye height, progress and
+/// the visible share.
+pub(super) fn render_viewshed(ui: &mut egui::Ui, params: &mut ViewshedParams) {
+    let viewshed = &mut *params.viewshed;
+    ui.collapsing(tr("viewshed.title"), |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut viewshed.picking, tr("viewshed.pick"))
+                .on_hover_text(tr("viewshed.pick.hover"));
+            if viewshed.observer.is_some() && ui.button(tr("common.clear")).clicked() {
+                viewshed.clear();
+            }
+        });
+
+        let mut changed = ui
+            .add(
+                egui::Slider::new(&mut viewshed.radius_m, 100.0..=20_000.0)
+                    .logarithmic(true)
+                    .suffix(" m")
+                    .text(tr("viewshed.radius")),
+            )
+            .drag_stopped();
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut viewshed.eye_height_m, 0.0..=500.0)
+                    .logarithmic(true)
+                    .suffix(" m")
+                    .text(tr("viewshed.eye_height")),
+            )
+            .drag_stopped();
+
+        let Some(observer) = viewshed.observer else {
+            ui.weak(tr("viewshed.no_observer"));
+            return;
+        };
+        if changed
+            || ui
+                .button(tr("viewshed.recompute"))
+                .on_hover_text(tr("viewshed.recompute.hover"))
+                .clicked()
+        {
+            viewshed.restart();
+        }
+
+        let (lat, lon) = ecef_to_lat_lon(observer);
+        ui.label(trf(
+            "viewshed.observer",
+            &[("coords", &fmt_lat_lon(lat, lon, 5))],
+        ));
+        let progress = viewshed.progress();
+        if progress < 1.0 {
+            ui.add(egui::ProgressBar::new(progress).show_percentage());
+        }
+        let (seen, hidden) = viewshed.counts();
+        if seen + hidden > 0 {
+            ui.label(trf(
+                "viewshed.visible",
+                &[
+                    (
+                        "percent",
+                        &fmt_number(100.0 * seen as f64 / (seen + hidden) as f64, 0),
+                    ),
+                    ("cells", &(seen + hidden)),
+                ],
+            ));
+        }
+        ui.weak(tr("viewshed.unloaded_note"));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_indices_cover_the_grid() {
+        let indices = overlay_indices();
+        assert_eq!(indices.len() % 3, 0);
+        assert_eq!(indices.len(), AZIMUTHS * 3 + (RINGS - 1) * AZIMUTHS * 6);
+        let max = *indices.iter().max().unwrap() as usize;
+        assert_eq!(max, AZIMUTHS * RINGS);
+    }
+
+    #[test]
+    fn cells_spiral_outward() {
+        let (x, y) = cell_offset(0, 1000.0);
+        assert!(x.abs() < 1e-9 && (y - 1000.0 / RINGS as f64).abs() < 1e-9);
+        // A quarter turn round the first ring faces east.
+        let (x, y) = cell_offset(AZIMUTHS / 4, 1000.0);
+        assert!((x - 1000.0 / RINGS as f64).abs() < 1e-9 && y.abs() < 1e-9);
+        let (x, y) = cell_offset(AZIMUTHS * RINGS - AZIMUTHS, 1000.0);
+        assert!(x.abs() < 1e-9 && (y - 1000.0).abs() < 1e-9);
+    }
+}
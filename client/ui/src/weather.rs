@@ -0,0 +1,182 @@
+//! Weather sub-tab of the Atmosphere tab.
+//!
+//! Sets the [`Weather`] by hand: the ground fog's visibility and depth, the
+//! haze on top of the atmosphere's aerosols, and the kind and intensity of
+//! precipitation around the camera. With live weather on (Settings →
+//! Network), the current conditions at the camera overwrite these whenever
+//! they're fetched. The [`Aurora`] is switched on and off here too.
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use veldera_places::LiveWeather;
+use veldera_sky::{
+    aurora::Aurora,
+    weather::{PrecipitationKind, Weather},
+};
+
+use crate::i18n::{fmt_number, tr, trf};
+
+pub(super) fn render_weather(
+    ui: &mut egui::Ui,
+    weather: &mut ResMut<Weather>,
+    aurora: &mut ResMut<Aurora>,
+    live: &LiveWeather,
+    now: f64,
+) {
+    render_live_status(ui, live, now);
+
+    // Edit a copy: every write rebuilds the medium's LUTs.
+    let mut edited = **weather;
+
+    egui::Grid::new("weather_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label(tr("weather.fog"));
+            let mut fog = edited.fog_visibility_m().is_some();
+            if ui.checkbox(&mut fog, "").changed() {
+                if fog {
+                    edited.set_fog_visibility_m(1000.0);
+                } else {
+                    edited.fog_density = 0.0;
+                }
+            }
+            ui.end_row();
+
+            if let Some(mut visibility) = edited.fog_visibility_m() {
+                ui.label(tr("weather.fog_visibility"));
+                if ui
+                    .add(egui::Slider::new(&mut visibility, 50.0..=20_000.0).logarithmic(true))
+                    .changed()
+                {
+                    edited.set_fog_visibility_m(visibility);
+                }
+                ui.end_row();
+
+                ui.label(tr("weather.fog_height"));
+                ui.add(
+                    egui::Slider::new(&mut edited.fog_height_m, 20.0..=1000.0).logarithmic(true),
+                )
+                .on_hover_text(tr("weather.fog_height.hover"));
+                ui.end_row();
+            }
+
+            ui.label(tr("weather.haze"));
+            ui.add(egui::Slider::new(&mut edited.haze, 0.0..=5.0))
+                .on_hover_text(tr("weather.haze.hover"));
+            ui.end_row();
+
+            ui.label(tr("weather.precipitation"));
+            ui.horizontal(|ui| {
+                for (kind, label) in [
+                    (PrecipitationKind::Rain, tr("weather.rain")),
+                    (PrecipitationKind::Snow, tr("weather.snow")),
+                ] {
+                    if ui
+                        .selectable_label(edited.precipitation == kind, label)
+                        .clicked()
+                    {
+                        edited.precipitation = kind;
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr("weather.intensity"));
+            ui.add(egui::Slider::new(
+                &mut edited.precipitation_intensity,
+                0.0..=1.0,
+            ));
+            ui.end_row();
+
+            if let Some(cover) = edited.cloud_cover {
+                ui.label(tr("weather.cloud_cover"));
+                ui.label(trf(
+                    "weather.percent",
+                    &[("percent", &fmt_number(f64::from(cover) * 100.0, 0))],
+                ))
+                .on_hover_text(tr("weather.cloud_cover.hover"));
+                ui.end_row();
+            }
+        });
+
+    if ui.button(tr("common.clear")).clicked() {
+        edited = Weather::default();
+    }
+    ui.label(tr("weather.rebuild_note"));
+
+    if edited != **weather {
+        **weather = edited;
+    }
+
+    ui.separator();
+    render_aurora(ui, aurora);
+}
+
+/// The aurora's switch and intensity.
+fn render_aurora(ui: &mut egui::Ui, aurora: &mut ResMut<Aurora>) {
+    let mut edited = **aurora;
+    egui::Grid::new("aurora_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label(tr("weather.aurora"));
+            ui.checkbox(&mut edited.enabled, "")
+                .on_hover_text(tr("weather.aurora.hover"));
+            ui.end_row();
+
+            ui.label(tr("weather.aurora_intensity"));
+            ui.add_enabled(
+                edited.enabled,
+                egui::Slider::new(&mut edited.intensity, 0.0..=4.0),
+            );
+            ui.end_row();
+        });
+    if edited != **aurora {
+        **aurora = edited;
+    }
+}
+
+/// Where the live conditions stand, if live weather is on.
+fn render_live_status(ui: &mut egui::Ui, live: &LiveWeather, now: f64) {
+    if !live.enabled {
+        return;
+    }
+
+    match live.current() {
+        Some(current) => {
+            let c = &current.conditions;
+            let age_min = (now - current.fetched_at) / 60.0;
+            ui.label(trf(
+                "weather.live",
+                &[
+                    ("time", &c.time),
+                    (
+                        "code",
+                        &c.weather_code.map_or("?".into(), |code| code.to_string()),
+                    ),
+                    (
+                        "visibility",
+                        &c.visibility.map_or("?".into(), |v| {
+                            format!("{} km", fmt_number(f64::from(v) / 1000.0, 1))
+                        }),
+                    ),
+                    (
+                        "rate",
+                        &fmt_number(f64::from(c.precipitation_rate_mm_per_h()), 1),
+                    ),
+                    ("minutes", &fmt_number(age_min, 0)),
+                ],
+            ));
+        }
+        None if live.is_loading() => {
+            ui.label(tr("weather.live.fetching"));
+        }
+        None => {
+            ui.label(tr("weather.live.waiting"));
+        }
+    }
+    if let Some(error) = live.error() {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+    ui.label(tr("weather.live.note"));
+    ui.separator();
+}
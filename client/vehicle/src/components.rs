@@ -5,11 +5,13 @@
 //! components are plain ECS data.
 
 use bevy::prelude::*;
+use glam::DVec3;
+use veldera_game_camera_state::Spectatable;
 
 /// Vehicle marker with metadata.
 #[derive(Component, Reflect, Clone, Default)]
 #[reflect(Component)]
-#[require(VehicleState, VehicleInput)]
+#[require(VehicleState, VehicleInput, VehicleDamage, Spectatable)]
 pub struct Vehicle {
     /// Display name for the vehicle.
     pub name: String,
@@ -255,6 +257,18 @@ pub struct VehicleInput {
     pub handbrake: bool,
 }
 
+/// Marks a vehicle whose [`VehicleInput`] is not the local player's to write,
+/// such as one driven by an AI or a replayed recording. The input system
+/// leaves such a vehicle's input alone, whoever is following it; the physics
+/// step in `FixedPreUpdate` reads whatever the driver last wrote.
+///
+/// The vehicle plugin inserts it while the player rides in a vehicle's
+/// passenger seat, and removes it again when they leave (unless it was
+/// already there), so the player's keys never drive the car they ride in.
+/// Multiplayer ghosts carry it while their peer is in a car.
+#[derive(Component, Default)]
+pub struct ExternalDriver;
+
 /// Per-wheel runtime state (fl, fr, rl, rr).
 #[derive(Default, Clone, Copy)]
 pub struct WheelState {
@@ -312,6 +326,19 @@ pub struct VehicleState {
     pub mass: f32,
 }
 
+/// Accumulated crash damage and the last place the vehicle stood safely
+/// (runtime only; reset on respawn).
+#[derive(Component, Default)]
+pub struct VehicleDamage {
+    /// Accumulated damage, 0 (pristine) to 1 (wrecked).
+    pub damage: f32,
+    /// Velocity change of the hardest impact so far (m/s, diagnostic).
+    pub worst_impact: f32,
+    /// ECEF position and rotation from the last step the vehicle stood
+    /// upright on all four wheels; the respawn target.
+    pub last_safe: Option<(DVec3, Quat)>,
+}
+
 /// Discovered wheel geometry and scene entities, inserted once the model
 /// scene has loaded. Vehicles without this component are not yet simulated.
 #[derive(Component)]
@@ -0,0 +1,193 @@
+//! Crash damage and respawning.
+//!
+//! Damage accumulates from the contact impulses Avian reports on the
+//! vehicle's body: each step's total impulse is converted to a velocity
+//! change, and the part above [`VehicleConfig::damage_threshold_speed`]
+//! counts. Past [`VehicleConfig::damage_degrade_start`] the engine's torque
+//! fades toward [`VehicleConfig::damage_min_engine_output`]. A respawn puts the
+//! vehicle back, repaired and upright, on the nearest gently sloped ground
+//! around where it is now, found by casting down onto the terrain in widening
+//! rings ([`VehicleConfig::respawn_rings`]). Failing that it returns to where it last stood upright on all four
+//! wheels, or, if it never has, drops onto whatever ground is beneath it.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use veldera_game_camera::FollowEntityTarget;
+use veldera_geo::{coords::RadialFrame, floating_origin::WorldPosition};
+use veldera_physics::{GameLayer, PhysicsState};
+
+use super::{
+    VehicleConfig, VehicleRespawnRequest,
+    components::{Vehicle, VehicleDamage, VehicleState},
+};
+
+/// Engine torque multiplier for `damage`: full output up to the degrade
+/// threshold, then a linear fade to the minimum output at full damage.
+pub fn engine_output_factor(config: &VehicleConfig, damage: f32) -> f32 {
+    let start = config.damage_degrade_start.clamp(0.0, 0.999);
+    let t = ((damage - start) / (1.0 - start)).clamp(0.0, 1.0);
+    1.0 - t * (1.0 - config.damage_min_engine_output.clamp(0.0, 1.0))
+}
+
+/// Convert last step's contact impulses on each vehicle body into damage.
+///
+/// Runs before the vehicle physics step, when the contact graph still holds
+/// the impulses the solver applied in the previous step. Resting contact
+/// (a car on its roof) produces a velocity change of only `g·dt` per step,
+/// far below the threshold.
+pub fn accumulate_collision_damage(
+    config: Res<VehicleConfig>,
+    collisions: Collisions,
+    mut query: Query<(Entity, &ComputedMass, &mut VehicleDamage), With<Vehicle>>,
+) {
+    for (entity, mass, mut damage) in &mut query {
+        let impulse: f32 = collisions
+            .iter()
+            .filter(|pair| pair.body1 == Some(entity) || pair.body2 == Some(entity))
+            .map(ContactPair::total_normal_impulse_magnitude)
+            .sum();
+        if impulse <= 0.0 {
+            continue;
+        }
+        let delta_v = impulse * mass.inverse();
+        damage.worst_impact = damage.worst_impact.max(delta_v);
+        let excess = delta_v - config.damage_threshold_speed;
+        if excess > 0.0 {
+            damage.damage = (damage.damage + excess * config.damage_per_speed).min(1.0);
+        }
+    }
+}
+
+/// Remember where each vehicle last stood upright on all four wheels.
+pub fn track_safe_ground(
+    config: Res<VehicleConfig>,
+    mut query: Query<(&VehicleState, &WorldPosition, &Rotation, &mut VehicleDamage)>,
+) {
+    for (state, world_pos, rotation, mut damage) in &mut query {
+        if state.grounded_wheels < 4 {
+            continue;
+        }
+        let up = RadialFrame::from_ecef_position(world_pos.position).up;
+        if (rotation.0 * Vec3::Y).dot(up) >= config.safe_upright_dot {
+            damage.last_safe = Some((world_pos.position, rotation.0));
+        }
+    }
+}
+
+/// Respawn the followed vehicle (or the first one), repaired and at rest, on
+/// the nearest safe ground around it, else at its last safe pose.
+pub fn process_vehicle_respawn_request(
+    config: Res<VehicleConfig>,
+    mut respawn_request: ResMut<VehicleRespawnRequest>,
+    physics_state: Res<PhysicsState>,
+    spatial_query: SpatialQuery,
+    follow_query: Query<&FollowEntityTarget>,
+    mut vehicle_query: Query<
+        (
+            Entity,
+            &mut VehicleDamage,
+            &mut WorldPosition,
+            &mut Position,
+            &mut Rotation,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        With<Vehicle>,
+    >,
+) {
+    if !respawn_request.pending {
+        return;
+    }
+    respawn_request.pending = false;
+
+    let followed = follow_query.iter().next().map(|follow| follow.target);
+    let Some(entity) = followed
+        .filter(|&entity| vehicle_query.contains(entity))
+        .or_else(|| vehicle_query.iter().next().map(|(entity, ..)| entity))
+    else {
+        return;
+    };
+    let Ok((
+        _,
+        mut damage,
+        mut world_pos,
+        mut position,
+        mut rotation,
+        mut linear_vel,
+        mut angular_vel,
+    )) = vehicle_query.get_mut(entity)
+    else {
+        return;
+    };
+
+    // Upright, keeping the current heading.
+    let frame = RadialFrame::from_ecef_position(world_pos.position);
+    let up = frame.up;
+    let forward = rotation.0 * Vec3::NEG_Z;
+    let forward = (forward - up * forward.dot(up)).normalize_or_zero();
+    let forward = if forward.length_squared() > 0.01 {
+        forward
+    } else {
+        frame.north
+    };
+    let upright = Transform::default().looking_to(forward, up).rotation;
+
+    // Cast down at `offset` from the vehicle (physics space, m); the hit
+    // point in ECEF and whether the ground there is gentle enough.
+    let filter = SpatialQueryFilter::default().with_mask([GameLayer::Ground]);
+    let ground_at = |offset: Vec3| {
+        spatial_query
+            .cast_ray(
+                position.0 + offset + up * config.respawn_cast_start,
+                Dir3::new_unchecked(-up),
+                config.respawn_cast_length,
+                true,
+                &filter,
+            )
+            .map(|hit| {
+                let point = offset + (config.respawn_cast_start - hit.distance) * up;
+                (
+                    world_pos.position + point.as_dvec3(),
+                    hit.normal.dot(up) >= config.respawn_ground_dot,
+                )
+            })
+    };
+
+    let east = frame.east;
+    let north = frame.north;
+    let samples = config.respawn_ring_samples.max(1);
+    let nearest_safe = std::iter::once(Vec3::ZERO)
+        .chain(config.respawn_rings.iter().flat_map(|&radius| {
+            (0..samples).map(move |i| {
+                let angle = std::f32::consts::TAU * i as f32 / samples as f32;
+                (east * angle.cos() + north * angle.sin()) * radius
+            })
+        }))
+        .filter_map(&ground_at)
+        .find_map(|(point, safe)| safe.then_some(point));
+
+    let (target, target_rotation) = match (nearest_safe, damage.last_safe) {
+        (Some(ground), _) => (ground, upright),
+        (None, Some(pose)) => pose,
+        // Never stood safely and nothing gentle nearby: drop onto whatever
+        // ground is below.
+        (None, None) => (
+            ground_at(Vec3::ZERO).map_or(world_pos.position, |(ground, _)| ground),
+            upright,
+        ),
+    };
+
+    let up = RadialFrame::from_ecef_position(target).up;
+    let ecef = target + (up * config.respawn_lift).as_dvec3();
+    let Some(physics_pos) = physics_state.to_physics(ecef) else {
+        return;
+    };
+    world_pos.position = ecef;
+    position.0 = physics_pos;
+    rotation.0 = target_rotation;
+    linear_vel.0 = Vec3::ZERO;
+    angular_vel.0 = Vec3::ZERO;
+    damage.damage = 0.0;
+    damage.worst_impact = 0.0;
+}
@@ -9,7 +9,9 @@
 mod audio;
 mod components;
 pub mod core;
+pub mod damage;
 pub mod physics;
+pub mod recorder;
 pub mod telemetry;
 mod visuals;
 
@@ -29,20 +31,21 @@ use serde::Deserialize;
 use veldera_config::ConfigPlugin;
 use veldera_game_camera::{
     CameraModeState, CameraModeTransitions, FlightCamera, FollowEntityTarget, FollowExitAnchor,
-    FollowedEntity,
+    FollowStyle, FollowedEntity, PassengerSeat,
 };
+use veldera_game_console::{CommandResult, ConsoleAppExt};
 use veldera_game_input::CameraAction;
 use veldera_game_player::{FpsController, LogicalPlayer};
 use veldera_geo::{
     coords::RadialFrame,
-    floating_origin::{FloatingOriginCamera, WorldPosition},
+    floating_origin::{DTransform, FloatingOriginCamera, WorldPosition},
 };
-use veldera_physics::{DespawnOutsidePhysicsRange, OriginShiftSystems, PhysicsState};
+use veldera_physics::{DebugPalette, DespawnOutsidePhysicsRange, OriginShiftSystems, PhysicsState};
 
 pub use components::{
-    DriveLayout, Vehicle, VehicleChassisConfig, VehicleEngineConfig, VehicleInput, VehicleModel,
-    VehicleState, VehicleSteeringConfig, VehicleSuspensionConfig, VehicleTireConfig,
-    VehicleTransmissionConfig, VehicleWheels, WheelState,
+    DriveLayout, ExternalDriver, Vehicle, VehicleChassisConfig, VehicleDamage, VehicleEngineConfig,
+    VehicleInput, VehicleModel, VehicleState, VehicleSteeringConfig, VehicleSuspensionConfig,
+    VehicleTireConfig, VehicleTransmissionConfig, VehicleWheels, WheelState,
 };
 
 /// Whether the debug UI's vehicle tab is currently open.
@@ -62,6 +65,16 @@ pub struct VehicleRightRequest {
     pub pending: bool,
 }
 
+/// Request to respawn the vehicle on the nearest safe ground, repaired.
+///
+/// Set by the host's debug UI or the respawn key; consumed by
+/// [`damage::process_vehicle_respawn_request`].
+#[derive(Resource, Default)]
+pub struct VehicleRespawnRequest {
+    /// Whether a respawn request is pending.
+    pub pending: bool,
+}
+
 /// Hot-reloadable global vehicle tuning, loaded from
 /// `assets/game/config/vehicle/vehicle.toml`. Per-vehicle physics lives in
 /// each vehicle's `.scn.ron`; this is the cross-vehicle behaviour.
@@ -83,6 +96,33 @@ pub struct VehicleConfig {
     pub engine_volume: f32,
     /// Engine voice volume at idle.
     pub engine_idle_volume: f32,
+    /// Velocity change (m/s) a single step's impacts must exceed before they
+    /// cause damage.
+    pub damage_threshold_speed: f32,
+    /// Damage per m/s of impact velocity change above the threshold.
+    pub damage_per_speed: f32,
+    /// Damage (0..1) above which engine output starts to fade.
+    pub damage_degrade_start: f32,
+    /// Fraction of engine output left at full damage.
+    pub damage_min_engine_output: f32,
+    /// Minimum `dot(chassis up, local up)` for a pose on all four wheels to
+    /// be remembered as a safe respawn point.
+    pub safe_upright_dot: f32,
+    /// Minimum `dot(ground normal, local up)` for ground to be safe to
+    /// respawn on (0.94 allows slopes up to about 20°).
+    pub respawn_ground_dot: f32,
+    /// Radii of the rings searched around the vehicle for safe ground,
+    /// nearest first (m). The vehicle's own spot is tried before them.
+    pub respawn_rings: Vec<f32>,
+    /// Ground casts per ring.
+    pub respawn_ring_samples: usize,
+    /// How far above the vehicle the ground casts start (m).
+    pub respawn_cast_start: f32,
+    /// How far the ground casts reach (m).
+    pub respawn_cast_length: f32,
+    /// Height above the ground (or the last safe pose) a respawned vehicle
+    /// is dropped from, so the wheels can settle (m).
+    pub respawn_lift: f32,
 }
 
 /// Gizmo config group for vehicle debug visualization.
@@ -91,8 +131,9 @@ pub struct VehicleDebugGizmos;
 
 /// Plugin for vehicle functionality.
 ///
-/// The host supplies the [`VehicleConfig`] path and sets [`VehicleTabOpen`] /
-/// [`VehicleRightRequest`] (both owned here) from its debug UI.
+/// The host supplies the [`VehicleConfig`] path and sets [`VehicleTabOpen`],
+/// [`VehicleRightRequest`] and [`VehicleRespawnRequest`] (all owned here) from
+/// its debug UI.
 pub struct VehiclePlugin {
     /// Path to the [`VehicleConfig`] TOML.
     pub config_path: &'static str,
@@ -124,22 +165,42 @@ impl Plugin for VehiclePlugin {
             .init_resource::<VehicleActions>()
             .init_resource::<VehicleTabOpen>()
             .init_resource::<VehicleRightRequest>()
+            .init_resource::<VehicleRespawnRequest>()
             .init_resource::<PendingVehicleSpawn>()
             .init_resource::<VehicleFolderLoader>()
+            .init_resource::<recorder::TelemetryRecorder>()
+            .add_console_command(
+                "vehicle",
+                "vehicle [list | spawn <name> | exit]",
+                "List, spawn or leave vehicles",
+                vehicle_command,
+            )
             .add_systems(
                 Startup,
                 (start_loading_vehicle_folder, configure_vehicle_debug_gizmos),
             )
             .add_systems(
                 FixedPreUpdate,
-                physics::vehicle_physics_system.after(OriginShiftSystems),
+                (
+                    damage::accumulate_collision_damage,
+                    physics::vehicle_physics_system,
+                    damage::track_safe_ground,
+                )
+                    .chain()
+                    .after(OriginShiftSystems),
             )
             .add_systems(
                 Update,
                 (
-                    physics::vehicle_input_system,
+                    (
+                        physics::mark_passenger_vehicle,
+                        physics::vehicle_input_system,
+                    )
+                        .chain(),
                     physics::process_vehicle_right_request,
+                    damage::process_vehicle_respawn_request,
                     visuals::animate_wheels,
+                    recorder::record_telemetry,
                 ),
             );
 
@@ -358,6 +419,79 @@ impl VehicleActions {
     }
 }
 
+/// The `vehicle` console command.
+fn vehicle_command(
+    In(args): In<Vec<String>>,
+    definitions: Res<VehicleDefinitions>,
+    mut actions: ResMut<VehicleActions>,
+    mode: Res<CameraModeState>,
+) -> CommandResult {
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            if definitions.vehicles.is_empty() {
+                return Ok("No vehicles loaded".to_string());
+            }
+            Ok(definitions
+                .vehicles
+                .iter()
+                .map(|def| format!("{}: {}", def.name, def.description))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        Some("spawn") => {
+            // Unquoted multi-word names work too.
+            let name = args[1..].join(" ");
+            if name.is_empty() {
+                return Err("Missing vehicle name".to_string());
+            }
+            let index = find_vehicle(&definitions.vehicles, &name)?;
+            actions.request_spawn(index);
+            Ok(format!("Spawning {}", definitions.vehicles[index].name))
+        }
+        Some("exit") => {
+            if !mode.is_follow_entity() {
+                return Err("Not in a vehicle".to_string());
+            }
+            actions.request_exit();
+            Ok("Leaving the vehicle".to_string())
+        }
+        Some(other) => Err(format!(
+            "Unknown option '{other}'; expected list, spawn or exit"
+        )),
+    }
+}
+
+/// Find a vehicle by name, ignoring case: an exact match, else the only name
+/// starting with `name`, else the only one containing it.
+fn find_vehicle(vehicles: &[VehicleDefinition], name: &str) -> Result<usize, String> {
+    let wanted = name.to_lowercase();
+    let names: Vec<_> = vehicles.iter().map(|def| def.name.to_lowercase()).collect();
+    if let Some(index) = names.iter().position(|candidate| *candidate == wanted) {
+        return Ok(index);
+    }
+    let matching = |test: fn(&str, &str) -> bool| -> Vec<usize> {
+        (0..names.len())
+            .filter(|&i| test(&names[i], &wanted))
+            .collect()
+    };
+    let mut found = matching(|candidate, wanted| candidate.starts_with(wanted));
+    if found.is_empty() {
+        found = matching(|candidate, wanted| candidate.contains(wanted));
+    }
+    match found.as_slice() {
+        [] => Err(format!("No vehicle called '{name}'; try 'vehicle list'")),
+        [index] => Ok(*index),
+        several => Err(format!(
+            "'{name}' could be {}",
+            several
+                .iter()
+                .map(|&i| vehicles[i].name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
 // ============================================================================
 // Vehicle spawning
 // ============================================================================
@@ -420,9 +554,14 @@ fn process_vehicle_actions(
                 .map(|w| w.rest_position.x.abs())
                 .fold(0.0, f32::max);
             let frame = RadialFrame::from_ecef_position(world_pos.position);
-            // Driver's side (-X), pushed out past the body, raised to
-            // standing height.
-            let side = rotation.0 * Vec3::new(-(half_width + 1.2), 0.0, 0.0);
+            // The side of the seat being left (the driver's is -X), pushed
+            // out past the body, raised to standing height.
+            let side_sign = if follow.style == FollowStyle::Passenger {
+                1.0
+            } else {
+                -1.0
+            };
+            let side = rotation.0 * Vec3::new(side_sign * (half_width + 1.2), 0.0, 0.0);
             exit_anchor.0 = Some(world_pos.position + side.as_dvec3() + frame.up.as_dvec3() * 1.0);
         }
         mode_transitions.request_exit();
@@ -581,11 +720,15 @@ fn on_vehicle_scene_ready(
 
     // Load the car model as a child. Wheel discovery, colliders, and the
     // angular inertia are completed by `visuals::on_vehicle_model_ready`
-    // once the model's scene instance is ready.
+    // once the model's scene instance is ready. The model root is part of the
+    // vehicle's double-precision hierarchy, so it tracks an exact
+    // `WorldPosition` however far the vehicle is from the camera.
+    let model_transform = Transform::from_scale(Vec3::splat(model_scale));
     let model_entity = commands
         .spawn((
             SceneRoot(asset_server.load(&model_path)),
-            Transform::from_scale(Vec3::splat(model_scale)),
+            model_transform,
+            DTransform::from_transform(&model_transform),
             visuals::VehicleModelRoot {
                 vehicle: vehicle_entity,
             },
@@ -605,10 +748,14 @@ fn on_vehicle_scene_ready(
 // Vehicle mode toggle
 // ============================================================================
 
-/// Toggle vehicle mode with E key.
+/// Toggle vehicle mode with the E and F keys.
 ///
-/// When following a vehicle, E exits to the previous camera mode.
-/// When not following, E enters the vehicle you're looking at (if within range).
+/// When driving or riding in a vehicle, either key exits to the previous
+/// camera mode. When not following, E takes the driver's seat of the vehicle
+/// you're looking at (if within range), and F its passenger seat, leaving its
+/// controls to whatever else drives it. While spectating (orbit), neither
+/// does anything; N leaves the orbit.
+#[allow(clippy::too_many_arguments)]
 fn toggle_vehicle_mode(
     action_query: Query<&ActionState<CameraAction>>,
     state: Res<CameraModeState>,
@@ -616,19 +763,26 @@ fn toggle_vehicle_mode(
     mut actions: ResMut<VehicleActions>,
     mut mode_transitions: ResMut<CameraModeTransitions>,
     camera_query: Query<(&FloatingOriginCamera, &Transform)>,
-    vehicle_query: Query<(Entity, &WorldPosition), With<Vehicle>>,
+    follow_query: Query<&FollowEntityTarget>,
+    vehicle_query: Query<(Entity, &WorldPosition, Has<PassengerSeat>), With<Vehicle>>,
 ) {
     let Ok(action_state) = action_query.single() else {
         return;
     };
 
-    if !action_state.just_pressed(&CameraAction::InteractVehicle) {
+    let ride = action_state.just_pressed(&CameraAction::RideVehicle);
+    if !ride && !action_state.just_pressed(&CameraAction::InteractVehicle) {
         return;
     }
 
     if state.is_follow_entity() {
-        // Exit the vehicle.
-        actions.request_exit();
+        // Exit the vehicle, unless only spectating one.
+        if follow_query
+            .iter()
+            .any(|follow| matches!(follow.style, FollowStyle::Chase | FollowStyle::Passenger))
+        {
+            actions.request_exit();
+        }
     } else {
         // Try to enter a vehicle you're looking at.
         let Ok((camera, camera_transform)) = camera_query.single() else {
@@ -641,7 +795,9 @@ fn toggle_vehicle_mode(
         // Find the best vehicle: must be within range and looking at it.
         let best = vehicle_query
             .iter()
-            .filter_map(|(entity, world_pos)| {
+            // The passenger seat is placed once the model has loaded.
+            .filter(|(_, _, seated)| !ride || *seated)
+            .filter_map(|(entity, world_pos, _)| {
                 let to_vehicle = world_pos.position - camera_pos;
                 let distance = to_vehicle.length();
 
@@ -662,8 +818,10 @@ fn toggle_vehicle_mode(
             // Prefer the vehicle most directly in front (highest dot product).
             .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 
-        if let Some((vehicle_entity, _)) = best {
-            mode_transitions.request_follow_entity(vehicle_entity);
+        match best {
+            Some((vehicle_entity, _)) if ride => mode_transitions.request_passenger(vehicle_entity),
+            Some((vehicle_entity, _)) => mode_transitions.request_follow_entity(vehicle_entity),
+            None => {}
         }
     }
 }
@@ -673,9 +831,11 @@ fn toggle_vehicle_mode(
 // ============================================================================
 
 /// Draw per-wheel gizmos: the suspension ray, the contact point, and the
-/// suspension force (colored green → red by tire saturation).
+/// suspension force (colored along the [`DebugPalette`] ramp by tire
+/// saturation).
 fn draw_wheel_gizmos(
     config: Res<VehicleConfig>,
+    palette: Res<DebugPalette>,
     mut gizmos: Gizmos<VehicleDebugGizmos>,
     vehicle_query: Query<(
         &Position,
@@ -699,7 +859,7 @@ fn draw_wheel_gizmos(
                 gizmos.sphere(Isometry3d::from_translation(contact), 0.06, css::ORANGE);
 
                 // Suspension force arrow, colored by tire saturation.
-                let color = Color::from(css::LIME).mix(&Color::from(css::RED), wheel.saturation);
+                let color = palette.ramp(wheel.saturation);
                 let force_end = contact + up * (wheel.suspension_force * config.force_gizmo_scale);
                 gizmos.arrow(contact, force_end, color);
             } else {
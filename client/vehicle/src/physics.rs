@@ -9,19 +9,20 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
-use veldera_game_camera::FollowEntityTarget;
+use veldera_game_camera::{FollowEntityTarget, FollowStyle};
 use veldera_game_camera_state::CameraModeState;
 use veldera_geo::{coords::RadialFrame, floating_origin::WorldPosition};
 use veldera_physics::GameLayer;
 
 use super::{
-    VehicleConfig, VehicleRightRequest,
+    VehicleConfig, VehicleRespawnRequest, VehicleRightRequest,
     components::{
-        Vehicle, VehicleChassisConfig, VehicleEngineConfig, VehicleInput, VehicleState,
-        VehicleSteeringConfig, VehicleSuspensionConfig, VehicleTireConfig,
-        VehicleTransmissionConfig, VehicleWheels,
+        ExternalDriver, Vehicle, VehicleChassisConfig, VehicleDamage, VehicleEngineConfig,
+        VehicleInput, VehicleState, VehicleSteeringConfig, VehicleSuspensionConfig,
+        VehicleTireConfig, VehicleTransmissionConfig, VehicleWheels,
     },
     core::{self, CarInput, CarParams, CarSimState, CarStepContext, WheelCastHit, WheelParams},
+    damage,
     telemetry::{self, TelemetrySnapshot},
 };
 
@@ -30,14 +31,48 @@ use super::{
 #[derive(Component, Default)]
 pub struct VehicleSim(pub CarSimState);
 
+/// Mark the vehicle ridden in as a passenger with [`ExternalDriver`] while
+/// the ride lasts, so the local player's input never drives it.
+///
+/// A vehicle that already had an [`ExternalDriver`] keeps it when the ride
+/// ends; only the marker inserted here is removed.
+pub fn mark_passenger_vehicle(
+    mut commands: Commands,
+    follow_query: Query<&FollowEntityTarget>,
+    vehicle_query: Query<Has<ExternalDriver>, With<Vehicle>>,
+    mut marked: Local<Option<Entity>>,
+) {
+    let ridden = follow_query
+        .iter()
+        .find(|follow| follow.style == FollowStyle::Passenger)
+        .map(|follow| follow.target)
+        .filter(|&entity| vehicle_query.contains(entity));
+    if ridden == *marked {
+        return;
+    }
+    if let Some(previous) = marked.take() {
+        commands.entity(previous).try_remove::<ExternalDriver>();
+    }
+    if let Some(entity) = ridden
+        && matches!(vehicle_query.get(entity), Ok(false))
+    {
+        commands.entity(entity).insert(ExternalDriver);
+        *marked = Some(entity);
+    }
+}
+
 /// Capture vehicle input from the action state.
 ///
-/// Only the vehicle the camera is following receives input; every other
-/// vehicle (parked cars, the vehicle just exited) gets zeroed so it doesn't
-/// keep driving itself — except the handbrake, which engages as a parking
-/// brake so an empty car holds on a slope instead of rolling away.
+/// Vehicles with an [`ExternalDriver`] are skipped: whatever drives them
+/// writes their [`VehicleInput`] itself. Otherwise only the vehicle the camera
+/// is chasing receives input (a spectator orbit or a passenger seat only
+/// watches it); every other vehicle (parked cars, the vehicle just exited,
+/// the one ridden in as a passenger) gets zeroed so it doesn't keep driving
+/// itself — except the handbrake, which engages as a parking brake so an
+/// empty car holds on a slope instead of rolling away.
 pub fn vehicle_input_system(
     mode: Res<CameraModeState>,
+    mut respawn_request: ResMut<VehicleRespawnRequest>,
     follow_query: Query<&FollowEntityTarget>,
     mut query: Query<
         (
@@ -45,10 +80,13 @@ pub fn vehicle_input_system(
             &ActionState<veldera_game_input::VehicleAction>,
             &mut VehicleInput,
         ),
-        With<Vehicle>,
+        (With<Vehicle>, Without<ExternalDriver>),
     >,
 ) {
-    let followed = follow_query.iter().next().map(|follow| follow.target);
+    let followed = follow_query
+        .iter()
+        .find(|follow| follow.style == FollowStyle::Chase)
+        .map(|follow| follow.target);
 
     // Each vehicle carries its own ActionState (all fed from the same
     // keyboard), so read the followed entity's rather than expecting a
@@ -59,6 +97,9 @@ pub fn vehicle_input_system(
             input.drive = drive.y;
             input.steer = drive.x;
             input.handbrake = action_state.pressed(&veldera_game_input::VehicleAction::Handbrake);
+            if action_state.just_pressed(&veldera_game_input::VehicleAction::Respawn) {
+                respawn_request.pending = true;
+            }
         } else {
             input.drive = 0.0;
             input.steer = 0.0;
@@ -69,7 +110,10 @@ pub fn vehicle_input_system(
 }
 
 /// Flatten the per-vehicle config components into core parameters.
-fn build_car_params(
+///
+/// Public so offline tools (`tools/vehicle_lab`) simulate exactly the
+/// parameters the game does.
+pub fn build_car_params(
     chassis: &VehicleChassisConfig,
     suspension: &VehicleSuspensionConfig,
     engine: &VehicleEngineConfig,
@@ -135,6 +179,7 @@ pub fn vehicle_physics_system(
         ),
         &VehicleWheels,
         &VehicleInput,
+        &VehicleDamage,
         &mut VehicleSim,
         &mut VehicleState,
         &Position,
@@ -155,6 +200,7 @@ pub fn vehicle_physics_system(
         (chassis, suspension, engine, transmission, steering, tire),
         wheels,
         input,
+        vehicle_damage,
         mut sim,
         mut state,
         position,
@@ -166,7 +212,10 @@ pub fn vehicle_physics_system(
         computed_com,
     ) in &mut query
     {
-        let params = build_car_params(chassis, suspension, engine, transmission, steering, tire);
+        let mut params =
+            build_car_params(chassis, suspension, engine, transmission, steering, tire);
+        params.peak_torque_nm *=
+            damage::engine_output_factor(&vehicle_config, vehicle_damage.damage);
         let wheel_params = wheel_params(wheels);
 
         // Suspension casts: a wheel-radius sphere along chassis-down,
@@ -347,3 +396,85 @@ pub fn process_vehicle_right_request(
         angular_vel.0 = Vec3::ZERO;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use veldera_game_camera_state::CameraMode;
+    use veldera_game_input::VehicleAction;
+
+    use super::*;
+
+    /// A vehicle whose driver's keys hold full throttle, and a camera
+    /// following it in `style`.
+    fn app(style: FollowStyle) -> (App, Entity, Entity) {
+        let mut app = App::new();
+        let mut mode = CameraModeState::default();
+        mode.set(CameraMode::FollowEntity, Some(CameraMode::Flycam));
+        app.insert_resource(mode)
+            .init_resource::<VehicleRespawnRequest>()
+            .add_systems(
+                Update,
+                (mark_passenger_vehicle, vehicle_input_system).chain(),
+            );
+
+        let mut keys = ActionState::<VehicleAction>::default();
+        keys.set_axis_pair(&VehicleAction::Drive, Vec2::new(0.5, 1.0));
+        let vehicle = app.world_mut().spawn((Vehicle::default(), keys)).id();
+        let camera = app
+            .world_mut()
+            .spawn(FollowEntityTarget {
+                target: vehicle,
+                style,
+            })
+            .id();
+        (app, vehicle, camera)
+    }
+
+    fn input(app: &App, vehicle: Entity) -> (f32, f32, bool) {
+        let input = app.world().get::<VehicleInput>(vehicle).unwrap();
+        (input.drive, input.steer, input.handbrake)
+    }
+
+    #[test]
+    fn ridden_vehicle_ignores_local_input() {
+        let (mut app, vehicle, camera) = app(FollowStyle::Passenger);
+        app.update();
+        assert!(app.world().get::<ExternalDriver>(vehicle).is_some());
+        assert_eq!(input(&app, vehicle), (0.0, 0.0, false));
+
+        // Whatever drives it keeps control for as long as the ride lasts.
+        app.world_mut()
+            .get_mut::<VehicleInput>(vehicle)
+            .unwrap()
+            .drive = -0.25;
+        app.update();
+        assert_eq!(input(&app, vehicle).0, -0.25);
+
+        // Taking the wheel hands it back to the keys.
+        app.world_mut()
+            .get_mut::<FollowEntityTarget>(camera)
+            .unwrap()
+            .style = FollowStyle::Chase;
+        app.update();
+        assert!(app.world().get::<ExternalDriver>(vehicle).is_none());
+        assert_eq!(input(&app, vehicle), (1.0, 0.5, false));
+    }
+
+    #[test]
+    fn ending_a_ride_keeps_a_preexisting_driver() {
+        let (mut app, vehicle, camera) = app(FollowStyle::Passenger);
+        app.world_mut().entity_mut(vehicle).insert(ExternalDriver);
+        app.update();
+        app.world_mut().entity_mut(camera).despawn();
+        app.update();
+        assert!(app.world().get::<ExternalDriver>(vehicle).is_some());
+    }
+
+    #[test]
+    fn unridden_vehicles_are_parked() {
+        let (mut app, vehicle, _) = app(FollowStyle::Orbit);
+        app.update();
+        assert!(app.world().get::<ExternalDriver>(vehicle).is_none());
+        assert_eq!(input(&app, vehicle), (0.0, 0.0, true));
+    }
+}
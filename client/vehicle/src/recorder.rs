@@ -0,0 +1,450 @@
+//! Fixed-rate telemetry recorder with CSV (and optional Parquet) export.
+//!
+//! [`telemetry`](super::telemetry) streams every physics step of the followed
+//! vehicle to a CSV while driving; the recorder is the interactive
+//! counterpart. It samples a chosen set of channel groups (inputs, speed,
+//! forces, altitude, position) from the followed vehicle and the camera at a
+//! fixed rate while the user has it running, buffers the samples in memory,
+//! and writes them to `recordings/telemetry-<unix time>.<ext>` on stop. The
+//! host's debug UI drives it through [`TelemetryRecorder::start`] and
+//! [`TelemetryRecorder::stop`].
+//!
+//! Parquet export needs the `parquet` feature.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use veldera_game_camera::FollowEntityTarget;
+use veldera_geo::{
+    coords::ecef_to_geodetic,
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+
+use super::components::{Vehicle, VehicleInput, VehicleState};
+
+/// Directory recordings are written to, relative to the working directory.
+const RECORDINGS_DIR: &str = "recordings";
+
+/// Default sample rate (Hz).
+const DEFAULT_SAMPLE_RATE_HZ: f32 = 20.0;
+
+/// Wheel column prefixes in fl, fr, rl, rr order.
+const WHEEL_PREFIXES: [&str; 4] = ["fl", "fr", "rl", "rr"];
+
+/// Channel groups the recorder can capture. Vehicle channels are empty cells
+/// in samples taken while no vehicle is spawned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecorderChannels {
+    /// Driver input and pedal outputs: drive, steer, handbrake, throttle,
+    /// brake.
+    pub inputs: bool,
+    /// Vehicle speed, forward speed, engine rpm and gear.
+    pub speed: bool,
+    /// Drive force and per-wheel suspension, longitudinal and lateral forces.
+    pub forces: bool,
+    /// Ellipsoidal height of the camera and the vehicle.
+    pub altitude: bool,
+    /// Latitude and longitude of the camera and the vehicle.
+    pub position: bool,
+}
+
+impl Default for RecorderChannels {
+    fn default() -> Self {
+        Self {
+            inputs: true,
+            speed: true,
+            forces: false,
+            altitude: true,
+            position: true,
+        }
+    }
+}
+
+impl RecorderChannels {
+    /// Column names for the enabled groups, after the leading `t` column.
+    fn columns(self) -> Vec<String> {
+        let mut columns = vec!["t".to_string()];
+        if self.inputs {
+            columns.extend(["drive", "steer", "handbrake", "throttle", "brake"].map(String::from));
+        }
+        if self.speed {
+            columns.extend(["speed", "fwd_speed", "rpm", "gear"].map(String::from));
+        }
+        if self.forces {
+            columns.push("drive_force".to_string());
+            for prefix in WHEEL_PREFIXES {
+                columns.extend(["load", "flong", "flat"].map(|force| format!("{prefix}_{force}")));
+            }
+        }
+        if self.altitude {
+            columns.extend(["cam_alt", "veh_alt"].map(String::from));
+        }
+        if self.position {
+            columns.extend(["cam_lat", "cam_lon", "veh_lat", "veh_lon"].map(String::from));
+        }
+        columns
+    }
+}
+
+/// File format a recording is exported as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// Comma-separated text with a header row.
+    #[default]
+    Csv,
+    /// Apache Parquet, one `f64` column per channel.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl RecordingFormat {
+    /// Every format this build can export.
+    pub const ALL: &[Self] = &[
+        Self::Csv,
+        #[cfg(feature = "parquet")]
+        Self::Parquet,
+    ];
+
+    /// Display name, also used as the file extension.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// An in-progress recording.
+struct Recording {
+    /// Column names, fixed when the recording starts.
+    columns: Vec<String>,
+    /// Channel groups captured, fixed when the recording starts.
+    channels: RecorderChannels,
+    /// Samples, row-major with `columns.len()` values per row. Missing
+    /// values are NaN.
+    values: Vec<f64>,
+    /// Time the recording started (s).
+    started: f64,
+    /// Time the next sample is due (s).
+    next_sample: f64,
+}
+
+/// Interactive telemetry recorder. Configure [`channels`](Self::channels),
+/// [`sample_rate_hz`](Self::sample_rate_hz) and [`format`](Self::format)
+/// while stopped; they are fixed for the duration of a recording.
+#[derive(Resource)]
+pub struct TelemetryRecorder {
+    /// Channel groups to capture.
+    pub channels: RecorderChannels,
+    /// Samples per second.
+    pub sample_rate_hz: f32,
+    /// Export format.
+    pub format: RecordingFormat,
+    recording: Option<Recording>,
+    last_export: Option<Result<PathBuf, String>>,
+}
+
+impl Default for TelemetryRecorder {
+    fn default() -> Self {
+        Self {
+            channels: RecorderChannels::default(),
+            sample_rate_hz: DEFAULT_SAMPLE_RATE_HZ,
+            format: RecordingFormat::default(),
+            recording: None,
+            last_export: None,
+        }
+    }
+}
+
+impl TelemetryRecorder {
+    /// Whether a recording is in progress.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Number of samples captured so far (0 when stopped).
+    pub fn sample_count(&self) -> usize {
+        self.recording.as_ref().map_or(0, |recording| {
+            recording.values.len() / recording.columns.len()
+        })
+    }
+
+    /// Seconds since the current recording started (0 when stopped).
+    pub fn duration(&self, time: &Time) -> f64 {
+        self.recording
+            .as_ref()
+            .map_or(0.0, |recording| time.elapsed_secs_f64() - recording.started)
+    }
+
+    /// Path of the last export, or why it failed.
+    pub fn last_export(&self) -> Option<&Result<PathBuf, String>> {
+        self.last_export.as_ref()
+    }
+
+    /// Start a new recording, discarding any in progress. The first sample
+    /// is taken on the next update.
+    pub fn start(&mut self, time: &Time) {
+        let now = time.elapsed_secs_f64();
+        self.recording = Some(Recording {
+            columns: self.channels.columns(),
+            channels: self.channels,
+            values: Vec::new(),
+            started: now,
+            next_sample: now,
+        });
+    }
+
+    /// Stop recording and export what was captured.
+    pub fn stop(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        let result = export(&recording, self.format).map_err(|e| e.to_string());
+        match &result {
+            Ok(path) => tracing::info!(
+                "Wrote {} telemetry samples to {}",
+                recording.values.len() / recording.columns.len(),
+                path.display()
+            ),
+            Err(e) => tracing::warn!("Failed to export telemetry recording: {e}"),
+        }
+        self.last_export = Some(result);
+    }
+}
+
+/// Capture a sample whenever one is due. Runs after the fixed-step physics,
+/// so each sample sees the latest vehicle state.
+pub fn record_telemetry(
+    time: Res<Time>,
+    mut recorder: ResMut<TelemetryRecorder>,
+    camera_query: Query<&FloatingOriginCamera>,
+    follow_query: Query<&FollowEntityTarget>,
+    vehicle_query: Query<(Entity, &VehicleState, &VehicleInput, &WorldPosition), With<Vehicle>>,
+) {
+    let period = 1.0 / f64::from(recorder.sample_rate_hz.max(0.1));
+    let Some(recording) = recorder.recording.as_mut() else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    if now < recording.next_sample {
+        return;
+    }
+    // Keep the grid, but don't backfill samples missed during a hitch.
+    let missed = ((now - recording.next_sample) / period).floor();
+    recording.next_sample += period * (missed + 1.0);
+
+    // Prefer the vehicle the camera is following, as the Vehicles tab does.
+    let followed = follow_query.iter().next().map(|follow| follow.target);
+    let vehicle = followed
+        .and_then(|entity| vehicle_query.get(entity).ok())
+        .or_else(|| vehicle_query.iter().next())
+        .map(|(_, state, input, position)| (state, input, position));
+    let camera = camera_query.single().ok().map(|camera| camera.position);
+
+    let channels = recording.channels;
+    let row = &mut recording.values;
+    row.push(now - recording.started);
+    if channels.inputs {
+        match vehicle {
+            Some((state, input, _)) => row.extend([
+                f64::from(input.drive),
+                f64::from(input.steer),
+                f64::from(u8::from(input.handbrake)),
+                f64::from(state.throttle),
+                f64::from(state.brake),
+            ]),
+            None => row.extend([f64::NAN; 5]),
+        }
+    }
+    if channels.speed {
+        match vehicle {
+            Some((state, _, _)) => row.extend([
+                f64::from(state.speed),
+                f64::from(state.forward_speed),
+                f64::from(state.rpm),
+                f64::from(state.gear),
+            ]),
+            None => row.extend([f64::NAN; 4]),
+        }
+    }
+    if channels.forces {
+        match vehicle {
+            Some((state, _, _)) => {
+                row.push(f64::from(state.drive_force));
+                for wheel in &state.wheels {
+                    row.extend([
+                        f64::from(wheel.suspension_force),
+                        f64::from(wheel.longitudinal_force),
+                        f64::from(wheel.lateral_force),
+                    ]);
+                }
+            }
+            None => row.extend([f64::NAN; 13]),
+        }
+    }
+    let camera_geodetic = camera.map(ecef_to_geodetic);
+    let vehicle_geodetic = vehicle.map(|(_, _, position)| ecef_to_geodetic(position.position));
+    if channels.altitude {
+        row.push(camera_geodetic.map_or(f64::NAN, |(_, _, height)| height));
+        row.push(vehicle_geodetic.map_or(f64::NAN, |(_, _, height)| height));
+    }
+    if channels.position {
+        let (cam_lat, cam_lon) = camera_geodetic.map_or((f64::NAN, f64::NAN), |g| (g.0, g.1));
+        let (veh_lat, veh_lon) = vehicle_geodetic.map_or((f64::NAN, f64::NAN), |g| (g.0, g.1));
+        row.extend([cam_lat, cam_lon, veh_lat, veh_lon]);
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Write `recording` to a fresh file under [`RECORDINGS_DIR`].
+#[cfg(not(target_family = "wasm"))]
+fn export(
+    recording: &Recording,
+    format: RecordingFormat,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    std::fs::create_dir_all(RECORDINGS_DIR)?;
+    let path =
+        PathBuf::from(RECORDINGS_DIR).join(format!("telemetry-{stamp}.{}", format.extension()));
+    let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    match format {
+        RecordingFormat::Csv => write_csv(recording, file)?,
+        #[cfg(feature = "parquet")]
+        RecordingFormat::Parquet => write_parquet(recording, file)?,
+    }
+    Ok(path)
+}
+
+/// Exporting writes to disk, which the web build can't.
+#[cfg(target_family = "wasm")]
+fn export(
+    _recording: &Recording,
+    _format: RecordingFormat,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Err("telemetry export is not available on the web".into())
+}
+
+/// Write `recording` as CSV: a header row, then one row per sample with
+/// missing values left empty.
+#[cfg(not(target_family = "wasm"))]
+fn write_csv(recording: &Recording, mut writer: impl std::io::Write) -> std::io::Result<()> {
+    writeln!(writer, "{}", recording.columns.join(","))?;
+    for row in recording.values.chunks_exact(recording.columns.len()) {
+        let mut line = String::new();
+        for (i, value) in row.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            if !value.is_nan() {
+                line.push_str(&value.to_string());
+            }
+        }
+        writeln!(writer, "{line}")?;
+    }
+    writer.flush()
+}
+
+/// Write `recording` as a single-row-group Parquet file with one nullable
+/// `f64` column per channel (missing values become nulls).
+#[cfg(all(feature = "parquet", not(target_family = "wasm")))]
+fn write_parquet(
+    recording: &Recording,
+    writer: impl std::io::Write + Send,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Float64Array, RecordBatch};
+    use parquet::arrow::ArrowWriter;
+
+    let stride = recording.columns.len();
+    let columns = recording.columns.iter().enumerate().map(|(c, name)| {
+        let array: Float64Array = recording
+            .values
+            .chunks_exact(stride)
+            .map(|row| Some(row[c]).filter(|value| !value.is_nan()))
+            .collect();
+        (name, Arc::new(array) as ArrayRef)
+    });
+    let batch = RecordBatch::try_from_iter(columns)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every channel group the sampler can push must have a matching column,
+    /// or rows and header drift apart.
+    #[test]
+    fn column_counts_match_sampler() {
+        let none = RecorderChannels {
+            inputs: false,
+            speed: false,
+            forces: false,
+            altitude: false,
+            position: false,
+        };
+        assert_eq!(none.columns().len(), 1);
+        let group = |channels: RecorderChannels| channels.columns().len() - 1;
+        assert_eq!(
+            group(RecorderChannels {
+                inputs: true,
+                ..none
+            }),
+            5
+        );
+        assert_eq!(
+            group(RecorderChannels {
+                speed: true,
+                ..none
+            }),
+            4
+        );
+        assert_eq!(
+            group(RecorderChannels {
+                forces: true,
+                ..none
+            }),
+            13
+        );
+        assert_eq!(
+            group(RecorderChannels {
+                altitude: true,
+                ..none
+            }),
+            2
+        );
+        assert_eq!(
+            group(RecorderChannels {
+                position: true,
+                ..none
+            }),
+            4
+        );
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn csv_leaves_missing_values_empty() {
+        let recording = Recording {
+            columns: vec!["t".into(), "speed".into()],
+            channels: RecorderChannels::default(),
+            values: vec![0.0, f64::NAN, 0.5, 12.25],
+            started: 0.0,
+            next_sample: 0.0,
+        };
+        let mut out = Vec::new();
+        write_csv(&recording, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "t,speed\n0,\n0.5,12.25\n");
+    }
+}
@@ -9,7 +9,8 @@
 //! - explicit mass properties (mass, centre of mass, box inertia) so the
 //!   car's handling is independent of collider density quirks,
 //! - convex-hull colliders on the body meshes only (wheels are handled by
-//!   the suspension raycasts, so wheel colliders would fight them).
+//!   the suspension raycasts, so wheel colliders would fight them),
+//! - a [`PassengerSeat`] for riding along, placed from the body bounds.
 //!
 //! Each frame it then drives the wheel nodes from the physics state: spin
 //! from rolling speed, steer yaw on the front axle, and vertical suspension
@@ -26,6 +27,7 @@ use super::{
     core,
     physics::VehicleSim,
 };
+use veldera_game_camera::PassengerSeat;
 use veldera_physics::GameLayer;
 
 /// Marker on the spawned model scene root, linking it back to its vehicle.
@@ -143,8 +145,18 @@ pub fn on_vehicle_model_ready(
         }
     }
 
+    // Passenger's eye: on the +X side (the driver sits at -X), about
+    // mid-length, a little below the roofline.
+    let body_centre = (body_min + body_max) * 0.5 * model_scale;
+    let eye_offset = Vec3::new(
+        body_centre.x + body_size.x * 0.22,
+        body_min.y * model_scale + body_size.y * 0.8,
+        body_centre.z,
+    );
+
     commands.entity(vehicle).insert((
         VehicleWheels { wheels },
+        PassengerSeat { eye_offset },
         VehicleSim::default(),
         AngularInertia::new(inertia),
         NoAutoAngularInertia,
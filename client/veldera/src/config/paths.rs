@@ -9,12 +9,14 @@
 //! `assets/engine` (a symlink to the top-level `engine_assets/` directory); the
 //! engine plugins default to those paths themselves, so they are not listed
 //! here. This module holds only the `assets/game/` gameplay config (launch,
-//! player, teleport, vehicle, and projectile).
+//! player, teleport, ambience, vehicle, projectile, multiplayer, remote control,
+//! and VR).
 
 // Launch (default spawn position + camera mode; read once at startup).
 pub const LAUNCH: &str = "game/config/launch.toml";
 
-// Player (first-person controller, body avatar, and the yeet launch mechanic).
+// Player (first-person controller, body avatar, the yeet launch mechanic,
+// swimming, and knockback).
 pub const FPS: &str = "game/config/player/fps.toml";
 pub const BODY: &str = "game/config/player/body/body.toml";
 pub const RAGDOLL: &str = "game/config/player/body/ragdoll.toml";
@@ -22,10 +24,18 @@ pub const LOCOMOTION: &str = "game/config/player/body/locomotion.toml";
 pub const YEET: &str = "game/config/player/yeet.toml";
 pub const EFFECTS: &str = "game/config/player/effects.toml";
 pub const LEAP_ARC: &str = "game/config/player/leap_arc.toml";
+pub const SWIM: &str = "game/config/player/swim.toml";
+pub const KNOCKBACK: &str = "game/config/player/knockback.toml";
 
 // Teleport / location services.
 pub const GEO: &str = "game/config/world/geo.toml";
 
+// Wind and city ambience.
+pub const AMBIENCE: &str = "game/config/world/ambience.toml";
+
+// Live weather: how the conditions at the camera map onto fog and precipitation.
+pub const WEATHER: &str = "game/config/world/weather.toml";
+
 // Live road-collider fitting (OSM fetch + grade-limited ribbon fit).
 pub const ROADS: &str = "game/config/world/roads.toml";
 
@@ -34,3 +44,14 @@ pub const VEHICLE: &str = "game/config/vehicle/vehicle.toml";
 
 // Projectiles.
 pub const PROJECTILE: &str = "game/config/physics/projectile.toml";
+
+// Multiplayer ghost mode.
+pub const MULTIPLAYER: &str = "game/config/multiplayer.toml";
+
+// Remote control (native only).
+#[cfg(not(target_family = "wasm"))]
+pub const REMOTE_CONTROL: &str = "game/config/remote.toml";
+
+// VR locomotion (only with the `xr` feature).
+#[cfg(feature = "xr")]
+pub const XR: &str = "game/config/xr.toml";
@@ -0,0 +1,612 @@
+//! Gameplay input recording and deterministic playback.
+//!
+//! `--record-input FILE` writes the camera and vehicle action states the game
+//! saw each frame (after focus gating) to a JSON-lines file: a header with
+//! where and when the session started, then one line per frame.
+//! `--replay-input FILE` launches at that start and feeds the recorded actions
+//! back in place of the keyboard and mouse, on a fixed timestep
+//! ([`TimeUpdateStrategy::ManualDuration`]), so a bug report can carry the
+//! exact interaction that triggered it. With `--exit-after-replay` the app
+//! quits once the recording runs out, for automated smoke tests.
+//!
+//! Recorded frames keep their real timestamps and playback walks them at its
+//! own fixed rate: each step takes every recorded frame up to its clock,
+//! pressing any button that was down in one of them and summing the mouse
+//! deltas, so a recording plays at its original game-time pace at any rate.
+//!
+//! Only the leafwing actions are covered: egui widgets and keys read straight
+//! from `ButtonInput` are not recorded. Terrain streams in as usual; pair with
+//! `--replay-session` to pin it down too.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use veldera_game_camera::CameraMode;
+use veldera_game_input::{CameraAction, GameInputSet, VehicleAction};
+use veldera_game_ui::{deep_link::parse_utc_datetime, settings::UserSettings};
+use veldera_sky::time_of_day::TimeOfDayState;
+
+use crate::launch_params::{DateTimeOverride, LaunchParams, ResolvedLaunch};
+
+/// Version written to (and required of) recording headers.
+const FORMAT_VERSION: u32 = 1;
+
+/// Playback rate (Hz) when `--replay-fps` isn't given.
+const DEFAULT_REPLAY_FPS: f64 = 60.0;
+
+/// Camera button actions, recorded as the list of those held.
+const CAMERA_BUTTONS: &[CameraAction] = &[
+    CameraAction::Ascend,
+    CameraAction::Descend,
+    CameraAction::Sprint,
+    CameraAction::ToggleCameraMode,
+    CameraAction::ToggleUi,
+    CameraAction::GrabCursor,
+    CameraAction::ReleaseCursor,
+    CameraAction::InteractVehicle,
+    CameraAction::RideVehicle,
+    CameraAction::CinematicOrbit,
+    CameraAction::Fire,
+    CameraAction::Point,
+    CameraAction::DropAnnotation,
+    CameraAction::CycleTerrainDebug,
+    CameraAction::ToggleConsole,
+    CameraAction::SkipTeleport,
+    CameraAction::HistoryBack,
+    CameraAction::HistoryForward,
+];
+
+/// Vehicle button actions, recorded as the list of those held.
+const VEHICLE_BUTTONS: &[VehicleAction] = &[VehicleAction::Handbrake, VehicleAction::Respawn];
+
+/// Plugin for input recording and playback. Does nothing unless
+/// [`InputRecorder`] or [`InputPlayback`] was inserted before it.
+pub struct InputRecordingPlugin;
+
+impl Plugin for InputRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(playback) = app.world().get_resource::<InputPlayback>() {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(playback.step));
+        }
+        app.add_systems(
+            PreUpdate,
+            (
+                play_input.run_if(resource_exists::<InputPlayback>),
+                record_input.run_if(resource_exists::<InputRecorder>),
+            )
+                .after(GameInputSet::Focus)
+                .before(GameInputSet::Intents),
+        );
+    }
+}
+
+/// First line of a recording.
+#[derive(Serialize, Deserialize)]
+struct RecordingHeader {
+    version: u32,
+    start: RecordedStart,
+}
+
+/// Where and when a recording started.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RecordedStart {
+    lat: f64,
+    lon: f64,
+    altitude: f64,
+    heading_deg: f64,
+    pitch_deg: f64,
+    camera_mode: CameraMode,
+    /// UTC date-time (`YYYY-MM-DDTHH:MM:SS`), so the lighting matches.
+    datetime_utc: String,
+}
+
+/// The actions of one recorded frame.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+struct InputFrame {
+    /// Seconds since the recording started.
+    t: f64,
+    movement: [f32; 2],
+    look: [f32; 2],
+    speed: f32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pressed: Vec<CameraAction>,
+    drive: [f32; 2],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    vehicle_pressed: Vec<VehicleAction>,
+}
+
+impl InputFrame {
+    fn capture(
+        t: f64,
+        camera: &ActionState<CameraAction>,
+        vehicle: Option<&ActionState<VehicleAction>>,
+    ) -> Self {
+        Self {
+            t,
+            movement: camera.axis_pair(&CameraAction::Move).to_array(),
+            look: camera.axis_pair(&CameraAction::Look).to_array(),
+            speed: camera.value(&CameraAction::AdjustSpeed),
+            pressed: held(camera, CAMERA_BUTTONS),
+            drive: vehicle.map_or([0.0; 2], |vehicle| {
+                vehicle.axis_pair(&VehicleAction::Drive).to_array()
+            }),
+            vehicle_pressed: vehicle
+                .map(|vehicle| held(vehicle, VEHICLE_BUTTONS))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn apply_camera(&self, state: &mut ActionState<CameraAction>) {
+        state.enable_all_actions();
+        state.set_axis_pair(&CameraAction::Move, Vec2::from(self.movement));
+        state.set_axis_pair(&CameraAction::Look, Vec2::from(self.look));
+        state.set_value(&CameraAction::AdjustSpeed, self.speed);
+        set_buttons(state, CAMERA_BUTTONS, &self.pressed);
+    }
+
+    fn apply_vehicle(&self, state: &mut ActionState<VehicleAction>) {
+        state.enable_all_actions();
+        state.set_axis_pair(&VehicleAction::Drive, Vec2::from(self.drive));
+        set_buttons(state, VEHICLE_BUTTONS, &self.vehicle_pressed);
+    }
+}
+
+fn held<A: Actionlike + Copy>(state: &ActionState<A>, buttons: &[A]) -> Vec<A> {
+    buttons
+        .iter()
+        .copied()
+        .filter(|button| state.pressed(button))
+        .collect()
+}
+
+fn set_buttons<A: Actionlike>(state: &mut ActionState<A>, buttons: &[A], pressed: &[A]) {
+    for button in buttons {
+        if pressed.contains(button) {
+            state.press(button);
+        } else {
+            state.release(button);
+        }
+    }
+    // Systems in the fixed-timestep loop read their own copy of the state.
+    state.set_fixed_update_state_from_state();
+}
+
+// ============================================================================
+// Recording
+// ============================================================================
+
+/// An input recording in progress. Starts with the first frame that has a
+/// camera.
+#[derive(Resource)]
+pub struct InputRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    started: bool,
+    /// Set after a write error; recording stops there.
+    failed: bool,
+    elapsed: f64,
+    frames: u64,
+}
+
+impl InputRecorder {
+    /// Create (or truncate) the recording file at `path`.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(File::create(path)?),
+            started: false,
+            failed: false,
+            elapsed: 0.0,
+            frames: 0,
+        })
+    }
+
+    /// Write one JSON line, flushed so a crash keeps everything up to it.
+    fn write_line(&mut self, value: &impl Serialize) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+fn record_input(
+    mut recorder: ResMut<InputRecorder>,
+    time: Res<Time>,
+    launch: Option<Res<ResolvedLaunch>>,
+    time_of_day: Res<TimeOfDayState>,
+    camera_query: Query<&ActionState<CameraAction>>,
+    vehicle_query: Query<&ActionState<VehicleAction>>,
+) {
+    if recorder.failed {
+        return;
+    }
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    let result = if recorder.started {
+        recorder.elapsed += time.delta_secs_f64();
+        recorder.frames += 1;
+        let frame = InputFrame::capture(recorder.elapsed, camera, vehicle_query.iter().next());
+        recorder.write_line(&frame)
+    } else {
+        let Some(launch) = launch else {
+            return;
+        };
+        let datetime = DateTimeOverride {
+            date: time_of_day.current_date(),
+            seconds: time_of_day.current_utc_seconds(),
+        };
+        let header = RecordingHeader {
+            version: FORMAT_VERSION,
+            start: RecordedStart {
+                lat: launch.lat,
+                lon: launch.lon,
+                altitude: launch.altitude,
+                heading_deg: launch.heading_deg,
+                pitch_deg: launch.pitch_deg,
+                camera_mode: launch.camera_mode,
+                datetime_utc: datetime.to_string(),
+            },
+        };
+        recorder.started = true;
+        recorder.frames = 1;
+        tracing::info!("Recording input to {}", recorder.path.display());
+        recorder.write_line(&header).and_then(|()| {
+            let frame = InputFrame::capture(0.0, camera, vehicle_query.iter().next());
+            recorder.write_line(&frame)
+        })
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            "Input recording to {} stopped after {} frames: {e}",
+            recorder.path.display(),
+            recorder.frames,
+        );
+        recorder.failed = true;
+    }
+}
+
+// ============================================================================
+// Playback
+// ============================================================================
+
+/// A recording being replayed. Starts with the first frame that has a camera.
+#[derive(Resource)]
+pub struct InputPlayback {
+    path: PathBuf,
+    start: RecordedStart,
+    frames: Vec<InputFrame>,
+    /// Fixed timestep the clock advances by each frame.
+    step: Duration,
+    exit_when_done: bool,
+    /// Index of the next recorded frame to play.
+    next: usize,
+    /// Playback time (s), once started.
+    clock: Option<f64>,
+    /// The last step's input; its buttons and axes hold until the next
+    /// recorded frame.
+    held: InputFrame,
+    finished: bool,
+}
+
+impl InputPlayback {
+    /// Load the recording at `path`, to play at `fps` (default 60 Hz).
+    pub fn load(path: &Path, fps: Option<f64>, exit_when_done: bool) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let invalid = |line: usize, e: serde_json::Error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {e}", line + 1),
+            )
+        };
+
+        let Some((index, line)) = lines.next() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "empty input recording",
+            ));
+        };
+        let header: RecordingHeader = serde_json::from_str(line).map_err(|e| invalid(index, e))?;
+        if header.version != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "input recording version {} (expected {FORMAT_VERSION})",
+                    header.version
+                ),
+            ));
+        }
+        let frames = lines
+            .map(|(index, line)| serde_json::from_str(line).map_err(|e| invalid(index, e)))
+            .collect::<io::Result<Vec<InputFrame>>>()?;
+
+        let fps = fps.unwrap_or(DEFAULT_REPLAY_FPS).clamp(1.0, 1000.0);
+        Ok(Self {
+            path: path.to_path_buf(),
+            start: header.start,
+            frames,
+            step: Duration::from_secs_f64(1.0 / fps),
+            exit_when_done,
+            next: 0,
+            clock: None,
+            held: InputFrame::default(),
+            finished: false,
+        })
+    }
+
+    /// Start the launch where the recording started, except for whatever
+    /// the command line overrides.
+    pub fn apply_start(&self, params: &mut LaunchParams) {
+        let start = &self.start;
+        params.lat.get_or_insert(start.lat);
+        params.lon.get_or_insert(start.lon);
+        params.altitude.get_or_insert(start.altitude);
+        params.heading.get_or_insert(start.heading_deg);
+        params.pitch.get_or_insert(start.pitch_deg);
+        params.camera_mode.get_or_insert(start.camera_mode);
+        if params.datetime.is_none() && params.datetime_local.is_none() {
+            match parse_utc_datetime(&start.datetime_utc) {
+                Ok((date, seconds)) => params.datetime = Some(DateTimeOverride { date, seconds }),
+                Err(e) => tracing::warn!("Ignoring the input recording's start time: {e}"),
+            }
+        }
+    }
+
+    /// The input for the playback step ending at `clock`: every recorded
+    /// frame up to it, merged.
+    fn advance(&mut self, clock: f64) -> InputFrame {
+        let mut step = InputFrame {
+            t: clock,
+            look: [0.0; 2],
+            speed: 0.0,
+            ..self.held.clone()
+        };
+        let mut first = true;
+        while let Some(frame) = self.frames.get(self.next)
+            && frame.t <= clock
+        {
+            if first {
+                step.pressed.clear();
+                step.vehicle_pressed.clear();
+                first = false;
+            }
+            step.movement = frame.movement;
+            step.drive = frame.drive;
+            step.look = (Vec2::from(step.look) + Vec2::from(frame.look)).to_array();
+            step.speed += frame.speed;
+            merge_pressed(&mut step.pressed, &frame.pressed);
+            merge_pressed(&mut step.vehicle_pressed, &frame.vehicle_pressed);
+            self.next += 1;
+        }
+        self.held = step.clone();
+        step
+    }
+}
+
+fn merge_pressed<A: PartialEq + Copy>(into: &mut Vec<A>, pressed: &[A]) {
+    for button in pressed {
+        if !into.contains(button) {
+            into.push(*button);
+        }
+    }
+}
+
+/// Drive the action states from the recording. While it plays, the input maps
+/// are detached so leafwing doesn't feed the real keyboard and mouse in; they
+/// come back when it ends.
+#[allow(clippy::type_complexity)]
+fn play_input(
+    mut commands: Commands,
+    mut playback: ResMut<InputPlayback>,
+    time: Res<Time>,
+    settings: Res<UserSettings>,
+    mut camera_query: Query<(
+        Entity,
+        &mut ActionState<CameraAction>,
+        Has<InputMap<CameraAction>>,
+    )>,
+    mut vehicle_query: Query<(
+        Entity,
+        &mut ActionState<VehicleAction>,
+        Has<InputMap<VehicleAction>>,
+    )>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if playback.finished {
+        return;
+    }
+    let Ok((camera_entity, mut camera, camera_mapped)) = camera_query.single_mut() else {
+        return;
+    };
+
+    let clock = match playback.clock {
+        Some(clock) => clock + time.delta_secs_f64(),
+        None => {
+            tracing::info!(
+                "Replaying {} input frames from {}",
+                playback.frames.len(),
+                playback.path.display()
+            );
+            0.0
+        }
+    };
+    playback.clock = Some(clock);
+    let step = playback.advance(clock);
+
+    if camera_mapped {
+        commands
+            .entity(camera_entity)
+            .remove::<InputMap<CameraAction>>();
+    }
+    step.apply_camera(&mut camera);
+    for (entity, mut vehicle, mapped) in &mut vehicle_query {
+        if mapped {
+            commands.entity(entity).remove::<InputMap<VehicleAction>>();
+        }
+        step.apply_vehicle(&mut vehicle);
+    }
+
+    if playback.next < playback.frames.len() {
+        return;
+    }
+    playback.finished = true;
+    tracing::info!(
+        "Input replay of {} finished at {clock:.2} s",
+        playback.path.display()
+    );
+    commands
+        .entity(camera_entity)
+        .insert(veldera_game_input::camera_input_map(&settings.bindings));
+    for (entity, ..) in &vehicle_query {
+        commands
+            .entity(entity)
+            .insert(veldera_game_input::default_vehicle_input_map());
+    }
+    if playback.exit_when_done {
+        exit.write(AppExit::Success);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FPS: f64 = 60.0;
+    const FRAMES: u32 = 90;
+
+    /// What the scripted input adds up to: the state a session's outcome
+    /// depends on, integrated at the fixed step.
+    #[derive(Resource, Default, Debug, PartialEq)]
+    struct Integrated {
+        position: Vec2,
+        look: Vec2,
+        speed: f32,
+        drive: Vec2,
+        fire_frames: u32,
+        handbrake_frames: u32,
+    }
+
+    fn integrate(
+        time: Res<Time>,
+        camera: Single<&ActionState<CameraAction>>,
+        vehicle: Single<&ActionState<VehicleAction>>,
+        mut state: ResMut<Integrated>,
+    ) {
+        let dt = time.delta_secs();
+        state.position += camera.axis_pair(&CameraAction::Move) * dt;
+        state.look += camera.axis_pair(&CameraAction::Look);
+        state.speed += camera.value(&CameraAction::AdjustSpeed);
+        state.drive += vehicle.axis_pair(&VehicleAction::Drive) * dt;
+        state.fire_frames += u32::from(camera.pressed(&CameraAction::Fire));
+        state.handbrake_frames += u32::from(vehicle.pressed(&VehicleAction::Handbrake));
+    }
+
+    /// Stand-in for the keyboard and mouse while recording.
+    fn script(
+        mut frame: Local<u32>,
+        mut camera: Single<&mut ActionState<CameraAction>>,
+        mut vehicle: Single<&mut ActionState<VehicleAction>>,
+    ) {
+        let n = *frame;
+        *frame += 1;
+        let t = n as f32;
+        camera.set_axis_pair(
+            &CameraAction::Move,
+            Vec2::new((t * 0.3).sin(), (n % 7) as f32 / 7.0),
+        );
+        camera.set_axis_pair(&CameraAction::Look, Vec2::new(t * 0.01, -0.5));
+        camera.set_value(
+            &CameraAction::AdjustSpeed,
+            if n % 10 == 0 { 1.0 } else { 0.0 },
+        );
+        if n % 4 < 2 {
+            camera.press(&CameraAction::Fire);
+        } else {
+            camera.release(&CameraAction::Fire);
+        }
+        vehicle.set_axis_pair(&VehicleAction::Drive, Vec2::new(0.5, -(t * 0.2).cos()));
+        if (10..20).contains(&n) {
+            vehicle.press(&VehicleAction::Handbrake);
+        } else {
+            vehicle.release(&VehicleAction::Handbrake);
+        }
+    }
+
+    /// A headless app with a camera and a vehicle, integrating their input.
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(bevy::time::TimePlugin)
+            .init_resource::<Integrated>()
+            .init_resource::<TimeOfDayState>()
+            .init_resource::<UserSettings>()
+            .add_systems(Update, integrate);
+        app.world_mut()
+            .spawn(ActionState::<CameraAction>::default());
+        app.world_mut()
+            .spawn(ActionState::<VehicleAction>::default());
+        app
+    }
+
+    #[test]
+    fn replay_reproduces_the_recorded_session() {
+        let dir = std::env::temp_dir().join(format!("veldera-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.jsonl");
+
+        let mut recording = app();
+        recording
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                1.0 / FPS,
+            )))
+            .insert_resource(ResolvedLaunch {
+                lat: 46.5,
+                lon: 7.9,
+                altitude: 3_000.0,
+                camera_mode: CameraMode::Flycam,
+                heading_deg: 90.0,
+                pitch_deg: -10.0,
+                datetime: None,
+            })
+            .insert_resource(InputRecorder::create(&path).unwrap())
+            .add_systems(First, script)
+            .add_plugins(InputRecordingPlugin);
+        for _ in 0..FRAMES {
+            recording.update();
+        }
+        let recorded = recording
+            .world_mut()
+            .remove_resource::<Integrated>()
+            .unwrap();
+        drop(recording);
+
+        let playback = InputPlayback::load(&path, Some(FPS), false).unwrap();
+        assert_eq!(playback.frames.len(), FRAMES as usize);
+        assert_eq!(playback.start.camera_mode, CameraMode::Flycam);
+        let mut replay = app();
+        replay
+            .insert_resource(playback)
+            .add_plugins(InputRecordingPlugin);
+        for _ in 0..FRAMES {
+            replay.update();
+        }
+
+        assert!(replay.world().resource::<InputPlayback>().finished);
+        assert_eq!(*replay.world().resource::<Integrated>(), recorded);
+        assert!(recorded.fire_frames > 0 && recorded.handbrake_frames == 10);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,14 +1,16 @@
 //! Launch parameter parsing for the viewer.
 //!
 //! On native, parameters are parsed from command-line arguments using clap.
-//! On WASM, defaults are used (CLI argument parsing is not available).
+//! On WASM, they come from the page URL's query string, in the same
+//! [`DeepLink`] format native accepts through `--link`.
 
-use std::fmt;
+use std::{fmt, path::PathBuf};
 
 use bevy::{prelude::*, reflect::TypePath};
 use serde::Deserialize;
 
 use veldera_game_camera_state::CameraMode;
+use veldera_game_ui::{deep_link::DeepLink, presets::LocationPreset};
 
 use veldera_sky::time_of_day::{SimpleDate, local_to_utc, seconds_to_hms};
 
@@ -34,6 +36,39 @@ pub struct LaunchParams {
     /// Optional local-time override at the spawn longitude, converted to UTC
     /// during [`LaunchParams::resolve`] (it needs the resolved longitude).
     pub datetime_local: Option<DateTimeOverride>,
+    /// Record or replay the terrain data session, if requested.
+    pub session: Option<SessionMode>,
+    /// Record or replay gameplay input, if requested.
+    pub input: Option<InputMode>,
+    /// Playback rate (Hz) of an input replay's fixed timestep.
+    pub replay_fps: Option<f64>,
+    /// Quit once an input replay has finished.
+    pub exit_after_replay: bool,
+    /// View through an OpenXR headset (needs the `xr` feature).
+    pub xr: bool,
+    /// Record every tracing span to this Chrome trace file.
+    pub trace_chrome: Option<PathBuf>,
+}
+
+/// Terrain data session capture/replay, for reproducing LOD and decode bugs
+/// without the live endpoint.
+#[derive(Debug, Clone)]
+pub enum SessionMode {
+    /// Record every bulk/node response into this directory.
+    Capture(PathBuf),
+    /// Serve every bulk/node response from the session recorded in this
+    /// directory.
+    Replay(PathBuf),
+}
+
+/// Gameplay input recording/playback, for reproducing interaction bugs and
+/// for smoke tests.
+#[derive(Debug, Clone)]
+pub enum InputMode {
+    /// Record the camera and vehicle actions into this file.
+    Record(PathBuf),
+    /// Replay the actions recorded in this file, from its start location.
+    Replay(PathBuf),
 }
 
 /// Hot-reloadable default launch parameters, loaded from
@@ -57,7 +92,8 @@ pub struct LaunchConfig {
 }
 
 /// Launch parameters with CLI overrides resolved against [`LaunchConfig`].
-#[derive(Debug, Clone, Copy)]
+/// Kept as a resource once the camera has spawned.
+#[derive(Resource, Debug, Clone, Copy)]
 pub struct ResolvedLaunch {
     pub lat: f64,
     pub lon: f64,
@@ -107,6 +143,37 @@ pub struct DateTimeOverride {
     pub seconds: f64,
 }
 
+impl LaunchParams {
+    /// Launch parameters from a shared link, with no session, input
+    /// recording, XR or trace.
+    fn from_link(link: DeepLink) -> Self {
+        Self {
+            lat: link.lat,
+            lon: link.lon,
+            altitude: link.altitude,
+            camera_mode: link.camera_mode,
+            heading: link.heading,
+            pitch: link.pitch,
+            datetime: link
+                .datetime
+                .map(|(date, seconds)| DateTimeOverride { date, seconds }),
+            ..Default::default()
+        }
+    }
+
+    /// Launch parameters starting at a curated preset's location and view.
+    fn from_preset(preset: &LocationPreset) -> Self {
+        Self {
+            lat: Some(preset.lat),
+            lon: Some(preset.lon),
+            altitude: Some(preset.altitude),
+            heading: Some(preset.heading_deg),
+            pitch: Some(preset.pitch_deg),
+            ..Default::default()
+        }
+    }
+}
+
 impl fmt::Display for DateTimeOverride {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let (h, m, s) = seconds_to_hms(self.seconds);
@@ -126,58 +193,18 @@ mod native {
 
     /// Parse a `YYYY-MM-DDTHH:MM:SS` string into a `DateTimeOverride`.
     fn parse_datetime(s: &str) -> Result<DateTimeOverride, String> {
-        // Accept both 'T' and ' ' as date/time separator.
-        let s = s.replace(' ', "T");
-        let parts: Vec<&str> = s.split('T').collect();
-        if parts.len() != 2 {
-            return Err(format!("expected YYYY-MM-DDTHH:MM:SS, got '{s}'"));
-        }
-
-        let date_parts: Vec<&str> = parts[0].split('-').collect();
-        let time_parts: Vec<&str> = parts[1].split(':').collect();
-
-        if date_parts.len() != 3 || time_parts.len() != 3 {
-            return Err(format!("expected YYYY-MM-DDTHH:MM:SS, got '{s}'"));
-        }
-
-        let year = date_parts[0]
-            .parse::<i32>()
-            .map_err(|e| format!("invalid year: {e}"))?;
-        let month = date_parts[1]
-            .parse::<u32>()
-            .map_err(|e| format!("invalid month: {e}"))?;
-        let day = date_parts[2]
-            .parse::<u32>()
-            .map_err(|e| format!("invalid day: {e}"))?;
-        let hour = time_parts[0]
-            .parse::<u32>()
-            .map_err(|e| format!("invalid hour: {e}"))?;
-        let minute = time_parts[1]
-            .parse::<u32>()
-            .map_err(|e| format!("invalid minute: {e}"))?;
-        let second = time_parts[2]
-            .parse::<u32>()
-            .map_err(|e| format!("invalid second: {e}"))?;
-
-        if !(1..=12).contains(&month) {
-            return Err(format!("month out of range: {month}"));
-        }
-        if !(1..=31).contains(&day) {
-            return Err(format!("day out of range: {day}"));
-        }
-        if hour >= 24 {
-            return Err(format!("hour out of range: {hour}"));
-        }
-        if minute >= 60 {
-            return Err(format!("minute out of range: {minute}"));
-        }
-        if second >= 60 {
-            return Err(format!("second out of range: {second}"));
-        }
+        let (date, seconds) = veldera_game_ui::deep_link::parse_utc_datetime(s)?;
+        Ok(DateTimeOverride { date, seconds })
+    }
 
-        Ok(DateTimeOverride {
-            date: SimpleDate::new(year, month, day),
-            seconds: f64::from(hour) * 3600.0 + f64::from(minute) * 60.0 + f64::from(second),
+    /// Look up a bundled location preset by id.
+    fn parse_preset(s: &str) -> Result<&'static LocationPreset, String> {
+        veldera_game_ui::presets::find(s).ok_or_else(|| {
+            let ids: Vec<&str> = veldera_game_ui::presets::presets()
+                .iter()
+                .map(|preset| preset.id.as_str())
+                .collect();
+            format!("unknown preset; choose one of: {}", ids.join(", "))
         })
     }
 
@@ -220,27 +247,100 @@ mod native {
         /// political timezones). Mutually exclusive with `--datetime`.
         #[arg(long, value_parser = parse_datetime, conflicts_with = "datetime")]
         datetime_local: Option<DateTimeOverride>,
+
+        /// Shared location link from the location tab's Share button: a full
+        /// URL or just its `?lat=...` query. Explicit flags override the
+        /// values it carries.
+        #[arg(long, value_parser = DeepLink::parse, allow_hyphen_values(true))]
+        link: Option<DeepLink>,
+
+        /// Start at a curated location preset, e.g. `grand-canyon` (see the
+        /// Location tab for the list). Explicit flags override its view.
+        #[arg(long, value_parser = parse_preset, conflicts_with = "link")]
+        preset: Option<&'static LocationPreset>,
+
+        /// Record every terrain bulk/node response into this (new)
+        /// directory, for later `--replay-session`.
+        #[arg(long, value_name = "DIR", conflicts_with = "replay_session")]
+        capture_session: Option<PathBuf>,
+
+        /// Serve every terrain bulk/node response from a session recorded
+        /// with `--capture-session`, instead of the network.
+        #[arg(long, value_name = "DIR", conflicts_with = "capture_session")]
+        replay_session: Option<PathBuf>,
+
+        /// Record the camera and vehicle actions into this file, for later
+        /// `--replay-input`.
+        #[arg(long, value_name = "FILE", conflicts_with = "replay_input")]
+        record_input: Option<PathBuf>,
+
+        /// Replay actions recorded with `--record-input` on a fixed timestep,
+        /// starting where the recording started (explicit location flags
+        /// override it). Pair with `--replay-session` for identical terrain.
+        #[arg(long, value_name = "FILE", conflicts_with = "record_input")]
+        replay_input: Option<PathBuf>,
+
+        /// Fixed timestep rate of `--replay-input`, in frames per second.
+        #[arg(long, value_name = "HZ", requires = "replay_input")]
+        replay_fps: Option<f64>,
+
+        /// Quit once `--replay-input` has finished, e.g. for smoke tests.
+        #[arg(long, requires = "replay_input")]
+        exit_after_replay: bool,
+
+        /// View through an OpenXR headset, flying with its controllers.
+        /// Needs a build with the `xr` feature and a running OpenXR runtime.
+        #[arg(long)]
+        xr: bool,
+
+        /// Record every tracing span (systems, terrain fetch/decode/spawn)
+        /// to this Chrome trace file, for `chrome://tracing` or Perfetto.
+        /// For Tracy, build with the `trace_tracy` feature and connect the
+        /// Tracy profiler instead.
+        #[arg(long, value_name = "FILE")]
+        trace_chrome: Option<PathBuf>,
     }
 
     pub fn parse() -> LaunchParams {
         let args = CliArgs::parse();
+        let base = match (args.link, args.preset) {
+            (Some(link), _) => LaunchParams::from_link(link),
+            (None, Some(preset)) => LaunchParams::from_preset(preset),
+            (None, None) => LaunchParams::default(),
+        };
         // `datetime_local` is kept raw here and converted to UTC in
         // `LaunchParams::resolve`, which has the resolved longitude (it may come
-        // from the config rather than `--lon`).
+        // from the config rather than `--lon`). A link or preset supplies the
+        // base view, which explicit flags override. An explicit local time also
+        // overrides the link's UTC time.
         LaunchParams {
-            lat: args.lat,
-            lon: args.lon,
-            altitude: args.altitude,
-            camera_mode: args.mode,
-            heading: args.heading,
-            pitch: args.pitch,
-            datetime: args.datetime,
+            lat: args.lat.or(base.lat),
+            lon: args.lon.or(base.lon),
+            altitude: args.altitude.or(base.altitude),
+            camera_mode: args.mode.or(base.camera_mode),
+            heading: args.heading.or(base.heading),
+            pitch: args.pitch.or(base.pitch),
+            datetime: args
+                .datetime
+                .or(base.datetime.filter(|_| args.datetime_local.is_none())),
             datetime_local: args.datetime_local,
+            session: args
+                .capture_session
+                .map(SessionMode::Capture)
+                .or_else(|| args.replay_session.map(SessionMode::Replay)),
+            input: args
+                .record_input
+                .map(InputMode::Record)
+                .or_else(|| args.replay_input.map(InputMode::Replay)),
+            replay_fps: args.replay_fps,
+            exit_after_replay: args.exit_after_replay,
+            xr: args.xr,
+            trace_chrome: args.trace_chrome,
         }
     }
 }
 
-/// Parse launch parameters from CLI args (native) or use defaults (WASM).
+/// Parse launch parameters from CLI args (native) or the page URL (WASM).
 pub fn parse() -> LaunchParams {
     #[cfg(not(target_family = "wasm"))]
     {
@@ -248,6 +348,15 @@ pub fn parse() -> LaunchParams {
     }
     #[cfg(target_family = "wasm")]
     {
-        LaunchParams::default()
+        let Some(url) = veldera_game_ui::deep_link::page_url() else {
+            return LaunchParams::default();
+        };
+        match DeepLink::parse(&url) {
+            Ok(link) => LaunchParams::from_link(link),
+            Err(error) => {
+                warn!("Ignoring malformed location link: {error}");
+                LaunchParams::default()
+            }
+        }
     }
 }
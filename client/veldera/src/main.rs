@@ -4,12 +4,16 @@
 //! 3D terrain data, with LOD-based loading and frustum culling.
 
 mod config;
+#[cfg(not(target_family = "wasm"))]
+mod input_recording;
 mod launch_params;
 mod physics;
 mod world;
 
 // Custom asset loaders and the CPU profiler now live in the engine umbrella.
 use bevy::{audio::SpatialListener, pbr::ScatteringMedium, prelude::*};
+#[cfg(not(target_family = "wasm"))]
+use launch_params::{InputMode, SessionMode};
 use launch_params::{LaunchConfig, LaunchParams, ResolvedLaunch};
 use veldera_async::AsyncRuntimePlugin;
 use veldera_clouds::CloudLayers;
@@ -18,9 +22,13 @@ use veldera_game_camera::{
     CameraConfig, CameraControllerPlugin, CameraMode, CameraModeTransitions,
 };
 use veldera_game_input::InputPlugin;
+use veldera_game_multiplayer::MultiplayerPlugin;
 use veldera_game_player::{PlayerConfigPaths, PlayerPlugin};
+#[cfg(not(target_family = "wasm"))]
+use veldera_game_remote::RemoteControlPlugin;
 use veldera_game_roads::RoadsPlugin;
-use veldera_game_ui::DebugUiPlugin;
+use veldera_game_tracks::TracksPlugin;
+use veldera_game_ui::{DebugUiPlugin, settings::UserSettings};
 use veldera_game_vehicle::VehiclePlugin;
 use veldera_geo::{
     coords::{enu_look_direction, lat_lon_to_ecef},
@@ -31,8 +39,10 @@ use veldera_sky::{
     clouds::{CloudConfig, CloudEngineConfig},
     time_of_day::TimeOfDayState,
 };
+#[cfg(not(target_family = "wasm"))]
+use veldera_terrain::loader::LoaderState;
 
-use crate::world::geo::GeoPlugin;
+use crate::world::{ambience::AmbiencePlugin, geo::GeoPlugin, weather::LiveWeatherPlugin};
 
 /// Plugin for the main application.
 pub struct AppPlugin;
@@ -52,11 +62,17 @@ impl Plugin for AppPlugin {
                 yeet: config::paths::YEET,
                 effects: config::paths::EFFECTS,
                 leap_arc: config::paths::LEAP_ARC,
+                swim: config::paths::SWIM,
+                knockback: config::paths::KNOCKBACK,
             }),
             GeoPlugin,
+            AmbiencePlugin,
+            LiveWeatherPlugin,
             DebugUiPlugin,
             VehiclePlugin::new(config::paths::VEHICLE),
             RoadsPlugin::new(config::paths::ROADS),
+            MultiplayerPlugin::new(config::paths::MULTIPLAYER),
+            TracksPlugin,
         ))
         // Terrain, physics, sky, atmosphere, clouds, and the celestial lights —
         // each at its default engine asset path.
@@ -66,6 +82,10 @@ impl Plugin for AppPlugin {
         ))
         .add_systems(Update, resolve_launch_and_spawn_camera)
         .add_plugins(physics::PhysicsPlugin);
+
+        // Remote control for demo installations.
+        #[cfg(not(target_family = "wasm"))]
+        app.add_plugins(RemoteControlPlugin::new(config::paths::REMOTE_CONTROL));
     }
 }
 
@@ -85,6 +105,7 @@ fn resolve_launch_and_spawn_camera(
     cloud_engine: config::Config<CloudEngineConfig>,
 
     params: Res<LaunchParams>,
+    settings: Res<UserSettings>,
 ) {
     if *spawned {
         return;
@@ -108,6 +129,7 @@ fn resolve_launch_and_spawn_camera(
     };
 
     let resolved = params.resolve(launch_cfg);
+    commands.insert_resource(resolved);
     let medium = media.add(ScatteringMedium::default());
     // The cloud engine settings are a global resource the renderer reads every
     // frame; install them from config now (before any `CloudLayers` exists) so
@@ -116,6 +138,7 @@ fn resolve_launch_and_spawn_camera(
     spawn_camera(
         &mut commands,
         &resolved,
+        &settings,
         camera_cfg.default_fov_deg,
         atmosphere_cfg,
         medium,
@@ -148,7 +171,8 @@ fn resolve_launch_and_spawn_camera(
 fn spawn_camera(
     commands: &mut Commands,
     resolved: &ResolvedLaunch,
-    fov_deg: f32,
+    settings: &UserSettings,
+    default_fov_deg: f32,
     atmosphere: &AtmosphereConfig,
     medium: Handle<ScatteringMedium>,
     clouds: CloudLayers,
@@ -164,10 +188,15 @@ fn spawn_camera(
     );
 
     commands.spawn((
-        // The engine camera rig: camera, projection (from `camera.toml`'s
-        // `default_fov_deg`, resolved before spawn), HDR + ACES + bloom, and the
-        // floating-origin and flight components.
-        world_camera_bundle(position, direction, up, fov_deg),
+        // The engine camera rig: camera, projection (the saved FoV, else
+        // `camera.toml`'s `default_fov_deg`, resolved before spawn), HDR +
+        // ACES + bloom, and the floating-origin and flight components.
+        world_camera_bundle(
+            position,
+            direction,
+            up,
+            settings.camera.fov_deg.unwrap_or(default_fov_deg),
+        ),
         // Atmosphere and cloud layers, both built from their configs (resolved
         // before spawn). `apply_atmosphere_config` / `apply_cloud_config` handle
         // later live edits.
@@ -175,8 +204,8 @@ fn spawn_camera(
         clouds,
         // Spatial audio listener for 3D sound.
         SpatialListener::default(),
-        // Input map for camera actions (gameplay).
-        veldera_game_input::default_camera_input_map(),
+        // Input map for camera actions (gameplay), with the saved bindings.
+        veldera_game_input::camera_input_map(&settings.bindings),
     ));
 }
 
@@ -198,31 +227,62 @@ fn main() {
         window.prevent_default_event_handling = true;
     }
 
-    app.add_plugins(
-        DefaultPlugins
-            .set(WindowPlugin {
-                primary_window: Some(window),
-                ..Default::default()
-            })
-            .set(AssetPlugin {
-                // We ship no `.meta` sidecars; skipping the check avoids 404
-                // spam on the web (Bevy #10157) and stray lookups on native.
-                // Hot-reloading of config TOML is driven by the `file_watcher`
-                // cargo feature (native only), not a runtime override here.
-                meta_check: bevy::asset::AssetMetaCheck::Never,
-                ..Default::default()
-            })
-            .set(bevy::log::LogPlugin {
-                // Hooks our `tracing-subscriber::Layer` that times
-                // every Bevy `system` span; the Profiler > Logic
-                // debug-UI subtab consumes the results. The
-                // `bevy/trace` feature must be on (we enable it in
-                // the native deps block of `Cargo.toml`) for system
-                // spans to actually emit.
-                custom_layer: profiler::install_layer,
-                ..Default::default()
-            }),
-    );
+    // Parse launch parameters (CLI args on native, URL query params on WASM).
+    #[allow(unused_mut)]
+    let mut params = launch_params::parse();
+
+    // Open the Chrome trace before `LogPlugin` installs the tracing layers,
+    // so startup is captured too. Reported once logging is up.
+    #[cfg(not(target_family = "wasm"))]
+    let trace_result = params
+        .trace_chrome
+        .as_ref()
+        .map(|path| (path, profiler::start_chrome_trace(path)));
+
+    let default_plugins = DefaultPlugins
+        .set(WindowPlugin {
+            primary_window: Some(window),
+            ..Default::default()
+        })
+        .set(AssetPlugin {
+            // We ship no `.meta` sidecars; skipping the check avoids 404
+            // spam on the web (Bevy #10157) and stray lookups on native.
+            // Hot-reloading of config TOML is driven by the `file_watcher`
+            // cargo feature (native only), not a runtime override here.
+            meta_check: bevy::asset::AssetMetaCheck::Never,
+            ..Default::default()
+        })
+        .set(bevy::log::LogPlugin {
+            // Hooks our `tracing-subscriber::Layer` that times
+            // every Bevy `system` span; the Profiler > Logic
+            // debug-UI subtab consumes the results. The
+            // `bevy/trace` feature must be on (we enable it in
+            // the native deps block of `Cargo.toml`) for system
+            // spans to actually emit.
+            custom_layer: profiler::install_layer,
+            ..Default::default()
+        });
+
+    // Asset sources must be registered before `AssetPlugin` builds.
+    #[cfg(feature = "shader_hot_reload")]
+    app.add_plugins(veldera_atmosphere::AtmosphereShaderSourcePlugin);
+
+    // With `--xr`, OpenXR takes over the window/render setup and adds the
+    // headset's eye cameras; the desktop window mirrors the head's view.
+    #[cfg(feature = "xr")]
+    if params.xr {
+        app.add_plugins(bevy_mod_openxr::add_xr_plugins(default_plugins))
+            .add_plugins(veldera_game_xr::XrViewerPlugin::new(config::paths::XR));
+    } else {
+        app.add_plugins(default_plugins);
+    }
+    #[cfg(not(feature = "xr"))]
+    {
+        if params.xr {
+            warn!("--xr needs a build with the `xr` feature; starting without VR");
+        }
+        app.add_plugins(default_plugins);
+    }
 
     // GPU/CPU timing instrumentation for every pass marked with
     // `pass_span` / `time_span` (the cloud + atmosphere crates do this
@@ -233,10 +293,63 @@ fn main() {
     app.add_plugins(bevy::render::diagnostic::RenderDiagnosticsPlugin);
     app.add_plugins(profiler::ProfilerPlugin);
 
-    // Parse launch parameters (CLI args on native, URL query params on WASM).
-    let params = launch_params::parse();
+    #[cfg(not(target_family = "wasm"))]
+    match trace_result {
+        Some((path, Ok(()))) => info!("Recording Chrome trace to {}", path.display()),
+        Some((path, Err(e))) => error!("Failed to start Chrome trace {}: {e}", path.display()),
+        None => {}
+    }
+
+    // Record or replay the terrain data session, if requested. Inserted
+    // ahead of the loader plugin's default state.
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(session) = &params.session {
+        let loader = match session {
+            SessionMode::Capture(dir) => LoaderState::capture_session(dir.clone()),
+            SessionMode::Replay(dir) => LoaderState::replay_session(dir.clone()),
+        };
+        match loader {
+            Ok(loader) => {
+                info!("Terrain session: {session:?}");
+                app.insert_resource(loader);
+            }
+            Err(e) => error!("Failed to open terrain session: {e}"),
+        }
+    }
+    // Record or replay gameplay input, if requested. A replay launches where
+    // its recording started, unless the command line says otherwise.
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(input) = params.input.clone() {
+        let opened = match &input {
+            InputMode::Record(path) => {
+                input_recording::InputRecorder::create(path).map(|recorder| {
+                    app.insert_resource(recorder);
+                })
+            }
+            InputMode::Replay(path) => input_recording::InputPlayback::load(
+                path,
+                params.replay_fps,
+                params.exit_after_replay,
+            )
+            .map(|playback| {
+                playback.apply_start(&mut params);
+                app.insert_resource(playback);
+            }),
+        };
+        match opened {
+            Ok(()) => {
+                info!("Gameplay input: {input:?}");
+                app.add_plugins(input_recording::InputRecordingPlugin);
+            }
+            Err(e) => error!("Failed to open input recording: {e}"),
+        }
+    }
     app.insert_resource(params);
 
+    // Saved user settings (camera, graphics, bindings, UI layout), loaded
+    // before any plugin builds so they seed the UI and the camera spawn.
+    app.insert_resource(UserSettings::load());
+
     // Add async runtime (Tokio on native, no-op on WASM).
     app.add_plugins(AsyncRuntimePlugin);
 
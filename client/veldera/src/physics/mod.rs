@@ -4,7 +4,8 @@
 //! The reusable physics integration — radial gravity, floating-origin shifting,
 //! terrain colliders, and collider streaming — lives in [`veldera_physics`] and
 //! is added by [`EngineWorldPlugins`](veldera_engine::EngineWorldPlugins) at its
-//! default path. This module adds only the gameplay-only projectile system.
+//! default path. This module adds only the gameplay-only projectile system and
+//! the paint splats projectiles leave on the terrain.
 
 mod projectile;
 
@@ -23,13 +24,17 @@ impl Plugin for PhysicsPlugin {
             config::paths::PROJECTILE,
         ))
         .init_resource::<projectile::ProjectileFireState>()
-        .add_systems(Startup, projectile::load_sounds)
+        .add_systems(
+            Startup,
+            (projectile::load_sounds, projectile::init_paint_splats),
+        )
         .add_systems(
             Update,
             (
                 projectile::click_to_fire_system,
                 projectile::despawn_projectiles,
                 projectile::projectile_collision_sound,
+                projectile::spawn_paint_splats,
             ),
         );
     }
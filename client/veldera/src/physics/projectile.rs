@@ -2,10 +2,20 @@
 //!
 //! Spawns physics-enabled spheres that can be shot from the camera.
 //! Left-click while cursor is grabbed to fire. Projectiles despawn when
-//! outside physics range or when their contact tile unloads.
+//! outside physics range or when their contact tile unloads. Each terrain
+//! impact leaves a paint splat in the projectile's colour, projected onto the
+//! ground as a [`TerrainDecal`].
+
+use std::collections::VecDeque;
 
 use avian3d::prelude::*;
-use bevy::{audio::Volume, prelude::*, reflect::TypePath};
+use bevy::{
+    asset::RenderAssetUsages,
+    audio::Volume,
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
 use glam::DVec3;
 use leafwing_input_manager::prelude::*;
 use rand::Rng;
@@ -13,10 +23,10 @@ use serde::Deserialize;
 
 use veldera_game_input::CameraAction;
 
-use veldera_game_camera_state::CameraModeState;
+use veldera_game_camera_state::{CameraModeState, Spectatable};
 use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
-use veldera_physics::DespawnOutsidePhysicsRange;
-use veldera_terrain::lod::LodState;
+use veldera_physics::{DespawnOutsidePhysicsRange, PhysicsState, TerrainCollider};
+use veldera_terrain::{decal::TerrainDecal, lod::LodState};
 
 /// Handle to the bounce sound asset.
 #[derive(Resource)]
@@ -43,6 +53,11 @@ pub struct ProjectileConfig {
     pub speed: f32,
     /// Minimum time between spawns (s).
     pub fire_debounce_secs: f32,
+    /// Paint splat half-size as a multiple of the projectile radius.
+    pub splat_size_scale: f32,
+    /// Maximum number of paint splats kept on the terrain; the oldest is
+    /// removed once exceeded. `0` disables splats.
+    pub max_splats: usize,
 }
 
 /// Tracks time since last projectile spawn for debouncing.
@@ -71,11 +86,28 @@ impl ProjectileFireState {
 
 /// Component marking an entity as a physics projectile.
 #[derive(Component)]
+#[require(Spectatable)]
 pub struct Projectile {
     /// Path of the tile the projectile last contacted (if any).
     pub contact_tile: Option<rocktree_decode::OctreePath>,
+    /// Base colour of the projectile, shared by the splats it leaves.
+    pub color: Color,
+    /// Projectile radius (m).
+    pub radius: f32,
 }
 
+/// Paint splats left on the terrain, oldest first.
+#[derive(Resource)]
+pub struct PaintSplats {
+    /// Soft-edged disc texture shared by every splat material.
+    texture: Handle<Image>,
+    /// Live splat entities, oldest at the front.
+    live: VecDeque<Entity>,
+}
+
+/// Edge length of the procedural splat texture (texels).
+const SPLAT_TEXTURE_SIZE: u32 = 64;
+
 /// System that fires projectiles on left-click when cursor is grabbed.
 ///
 /// Includes debouncing to prevent rapid-fire spam. Only fires in FPS mode.
@@ -91,6 +123,7 @@ pub fn click_to_fire_system(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     fire_sound: Option<Res<FireSoundHandle>>,
+    physics_state: Res<PhysicsState>,
     camera_query: Query<(&FloatingOriginCamera, &Transform)>,
 ) {
     // Advance the debounce timer.
@@ -127,6 +160,7 @@ pub fn click_to_fire_system(
 
     let camera_pos = camera.position;
     let camera_dir = transform.forward().as_vec3();
+    let physics_origin = physics_state.origin_camera_position().unwrap_or(camera_pos);
 
     spawn_projectile(
         &config,
@@ -135,6 +169,7 @@ pub fn click_to_fire_system(
         &mut materials,
         camera_pos,
         camera_dir,
+        physics_origin,
     );
 
     // Play fire sound 0.2m in front of player.
@@ -154,6 +189,8 @@ pub fn click_to_fire_system(
 }
 
 /// Spawn a projectile sphere from the camera position in the camera direction.
+/// `physics_origin` is the camera position physics positions are relative to
+/// ([`PhysicsState::origin_camera_position`]).
 fn spawn_projectile(
     config: &ProjectileConfig,
     commands: &mut Commands,
@@ -161,6 +198,7 @@ fn spawn_projectile(
     materials: &mut Assets<StandardMaterial>,
     camera_world_pos: DVec3,
     camera_dir: Vec3,
+    physics_origin: DVec3,
 ) -> Entity {
     let mut rng = rand::rng();
 
@@ -176,8 +214,9 @@ fn spawn_projectile(
     let offset = camera_dir * (radius * 3.0);
     let spawn_world_pos = camera_world_pos + offset.as_dvec3();
 
-    // Physics position is camera-relative (spawn is at offset from camera).
-    let physics_pos = Vec3::new(offset.x, offset.y, offset.z);
+    // Physics position is relative to the origin-shift camera position, not
+    // the live camera, which may have moved since the last shift.
+    let physics_pos = (spawn_world_pos - physics_origin).as_vec3();
 
     // Initial velocity in camera direction.
     let initial_velocity = camera_dir * config.speed;
@@ -195,6 +234,7 @@ fn spawn_projectile(
 
     commands
         .spawn((
+            Name::new("Projectile"),
             Mesh3d(mesh),
             MeshMaterial3d(material),
             Transform::from_translation(physics_pos),
@@ -205,7 +245,11 @@ fn spawn_projectile(
             Position(physics_pos),
             LinearVelocity(initial_velocity),
             Mass(mass),
-            Projectile { contact_tile: None },
+            Projectile {
+                contact_tile: None,
+                color,
+                radius,
+            },
             DespawnOutsidePhysicsRange,
         ))
         .id()
@@ -230,6 +274,108 @@ pub fn despawn_projectiles(
     }
 }
 
+/// Create the shared splat texture on startup.
+pub fn init_paint_splats(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let texture = images.add(splat_texture(SPLAT_TEXTURE_SIZE));
+    commands.insert_resource(PaintSplats {
+        texture,
+        live: VecDeque::new(),
+    });
+}
+
+/// A white disc with a ragged, soft edge on a transparent background.
+///
+/// The edge is wobbled by a few angular harmonics so splats don't read as
+/// perfect circles; the material's base colour tints it per projectile.
+fn splat_texture(size: u32) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            // Texel centre in [-1, 1].
+            let u = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let v = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let r = u.hypot(v);
+            let angle = v.atan2(u);
+            let edge = 0.75 + 0.08 * (angle * 5.0).sin() + 0.05 * (angle * 11.0 + 1.3).sin();
+            let alpha = ((edge - r) / 0.08).clamp(0.0, 1.0);
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.0) as u8]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Leave a paint splat where a projectile strikes the terrain.
+///
+/// The splat is centred on the projectile's position at first contact; its
+/// vertical reach covers the projectile's radius, so the decal lands on the
+/// ground beneath the sphere's centre.
+pub fn spawn_paint_splats(
+    mut commands: Commands,
+    config: Res<ProjectileConfig>,
+    mut collision_events: MessageReader<CollisionStart>,
+    splats: Option<ResMut<PaintSplats>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    projectile_query: Query<(&Projectile, &WorldPosition)>,
+    terrain_query: Query<(), With<TerrainCollider>>,
+) {
+    let Some(mut splats) = splats else {
+        collision_events.clear();
+        return;
+    };
+    if config.max_splats == 0 {
+        collision_events.clear();
+        return;
+    }
+
+    let mut rng = rand::rng();
+    for event in collision_events.read() {
+        let (projectile_entity, other) = if projectile_query.contains(event.collider1) {
+            (event.collider1, event.collider2)
+        } else {
+            (event.collider2, event.collider1)
+        };
+        let Ok((projectile, world_pos)) = projectile_query.get(projectile_entity) else {
+            continue;
+        };
+        if !terrain_query.contains(other) {
+            continue;
+        }
+
+        let half_size = projectile.radius * config.splat_size_scale;
+        let material = materials.add(StandardMaterial {
+            base_color: projectile.color,
+            base_color_texture: Some(splats.texture.clone()),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.9,
+            depth_bias: 1.0,
+            ..default()
+        });
+        let mut decal = TerrainDecal::new(half_size, material)
+            .with_rotation(rng.random_range(0.0..std::f32::consts::TAU));
+        decal.depth = half_size.max(projectile.radius * 2.0);
+
+        let entity = commands.spawn((decal, world_pos.clone())).id();
+        splats.live.push_back(entity);
+
+        while splats.live.len() > config.max_splats {
+            if let Some(oldest) = splats.live.pop_front() {
+                commands.entity(oldest).try_despawn();
+            }
+        }
+    }
+}
+
 /// Load sound assets on startup.
 pub fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(BounceSoundHandle(
@@ -0,0 +1,368 @@
+//! Continuous ambient audio driven by the camera.
+//!
+//! Two persistent voices, both silent until their conditions hold, so they
+//! never need spawning or despawning:
+//!
+//! - **Wind**: the teleport's wind loop, played endlessly. It swells with
+//!   altitude and with camera speed, and thins out through the upper
+//!   atmosphere: volume fades and the loop slows (so it darkens) until it's
+//!   silent in space.
+//! - **City**: a procedural low rumble (the vehicle engine's lock-free synth
+//!   pattern) that fades in near the ground where the terrain is dense. The
+//!   density proxy is the relief of the streamed surface around the camera:
+//!   buildings make the ground heights under a ring of raycasts scatter, open
+//!   fields and water don't.
+//!
+//! The wind ducks out while a teleport flight plays its own loop.
+
+use std::{
+    f32::consts::TAU,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use bevy::{
+    audio::{AddAudioSource, Decodable, Source, Volume},
+    prelude::*,
+    reflect::TypePath,
+};
+use glam::DVec3;
+use serde::Deserialize;
+
+use veldera_constants::EARTH_RADIUS_M_F64;
+use veldera_game_teleport::TeleportAnimation;
+use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_terrain::raycast::TerrainRaycast;
+
+use crate::config;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Per-sample one-pole time constant for the city voice's volume (s).
+const CITY_AMP_SLEW_TAU_S: f32 = 0.5;
+
+/// Directions of the density ring.
+const RING_SAMPLES: usize = 8;
+
+/// Plugin for the wind and city ambience.
+pub struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(config::ConfigPlugin::<AmbienceConfig>::new(
+            config::paths::AMBIENCE,
+        ))
+        .add_audio_source::<CityAmbienceSource>()
+        .init_resource::<AmbienceState>()
+        .add_systems(Startup, setup_ambience)
+        .add_systems(Update, (sample_surroundings, update_ambience).chain());
+    }
+}
+
+/// Hot-reloadable ambience tuning, loaded from
+/// `assets/game/config/world/ambience.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmbienceConfig {
+    /// Master switch. `false` silences both voices.
+    pub enabled: bool,
+    /// Wind volume at rest on the ground.
+    pub wind_floor_volume: f32,
+    /// Wind volume at full intensity.
+    pub wind_volume: f32,
+    /// Altitude (m) at which altitude alone brings the wind to full intensity.
+    pub wind_full_altitude_m: f32,
+    /// Camera speed (m/s) at which speed alone brings the wind to full
+    /// intensity.
+    pub wind_full_speed_mps: f32,
+    /// Altitude band (m) over which the wind is muffled to silence as the air
+    /// thins.
+    pub muffle_start_altitude_m: f32,
+    pub muffle_end_altitude_m: f32,
+    /// Playback speed of the fully muffled loop (1 = unmuffled).
+    pub muffled_playback_speed: f32,
+    /// Time constant (s) smoothing the camera speed.
+    pub speed_smoothing_secs: f32,
+    /// City rumble volume at full density.
+    pub city_volume: f32,
+    /// Height above the ground (m) below which the city is audible, fading
+    /// out by this height.
+    pub city_max_clearance_m: f32,
+    /// Surface relief (standard deviation of ring heights, m) at which the
+    /// city starts to be audible, and at which it's at full volume.
+    pub city_relief_min_m: f32,
+    pub city_relief_full_m: f32,
+    /// Radius (m) of the ring of ground raycasts measuring relief.
+    pub density_ring_radius_m: f64,
+    /// Height (m) above (and depth below) the camera and each ring point the
+    /// ground raycasts cover.
+    pub density_ray_height_m: f64,
+    /// Seconds between surroundings samples.
+    pub density_interval_secs: f32,
+}
+
+/// What the camera's surroundings sounded like at the last sample.
+#[derive(Resource, Default)]
+struct AmbienceState {
+    /// Camera position last frame, for the speed estimate.
+    last_position: Option<DVec3>,
+    /// Smoothed camera speed (m/s).
+    speed: f32,
+    /// Seconds until the next surroundings sample.
+    until_sample: f32,
+    /// Height above the ground (m), if the ground below is loaded.
+    clearance: Option<f32>,
+    /// Relief of the surface around the camera (m).
+    relief: f32,
+}
+
+/// Marker for the wind loop voice.
+#[derive(Component)]
+struct AmbientWind;
+
+/// Lock-free state driving the city synth: the target amplitude, stored as
+/// f32 bits.
+#[derive(Debug, Default)]
+struct CityShared {
+    amplitude: AtomicU32,
+}
+
+/// Resource holding the shared city synth state.
+#[derive(Resource)]
+struct CityAmbience {
+    shared: Arc<CityShared>,
+}
+
+/// The Bevy audio source asset for the city voice.
+#[derive(Asset, TypePath, Clone)]
+struct CityAmbienceSource {
+    shared: Arc<CityShared>,
+}
+
+impl Decodable for CityAmbienceSource {
+    type DecoderItem = f32;
+    type Decoder = CityDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        let sr = SAMPLE_RATE as f32;
+        CityDecoder {
+            shared: self.shared.clone(),
+            amp_slew: 1.0 - (-1.0 / (CITY_AMP_SLEW_TAU_S * sr)).exp(),
+            env: 0.0,
+            rumble: 0.0,
+            hiss: 0.0,
+            swell_phase: 0.0,
+            rng: 0x2545_f491,
+        }
+    }
+}
+
+/// The rodio [`Source`] behind [`CityAmbienceSource`]: an infinite mono
+/// distant-traffic bed. Heavily low-passed noise for the rumble, a quieter,
+/// brighter layer for the hiss of tyres, and a slow swell so it doesn't
+/// sound static.
+struct CityDecoder {
+    shared: Arc<CityShared>,
+    amp_slew: f32,
+    env: f32,
+    rumble: f32,
+    hiss: f32,
+    swell_phase: f32,
+    rng: u32,
+}
+
+impl Iterator for CityDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let target = f32::from_bits(self.shared.amplitude.load(Ordering::Relaxed));
+        self.env += (target - self.env) * self.amp_slew;
+
+        self.rng = self.rng.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let white = (self.rng >> 8) as f32 / (1u32 << 23) as f32 - 1.0;
+        self.rumble += (white - self.rumble) * 0.01;
+        self.hiss += (white - self.hiss) * 0.15;
+
+        self.swell_phase = (self.swell_phase + 0.07 / SAMPLE_RATE as f32).fract();
+        let swell = 0.8 + 0.2 * (self.swell_phase * TAU).sin();
+
+        let mix = 2.5 * self.rumble + 0.15 * self.hiss;
+        Some((mix * swell * self.env).clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for CityDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+    fn channels(&self) -> u16 {
+        1
+    }
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Spawn both voices, silent.
+fn setup_ambience(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut sources: ResMut<Assets<CityAmbienceSource>>,
+) {
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("game/sounds/135034__mrlindstrom__windloop6sec.wav")),
+        PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+        AmbientWind,
+    ));
+
+    let shared = Arc::new(CityShared::default());
+    let source = sources.add(CityAmbienceSource {
+        shared: shared.clone(),
+    });
+    commands.spawn((AudioPlayer(source), PlaybackSettings::ONCE));
+    commands.insert_resource(CityAmbience { shared });
+}
+
+/// Track the camera's speed every frame, and its clearance and the relief
+/// around it every `density_interval_secs` (the raycasts aren't free).
+fn sample_surroundings(
+    time: Res<Time>,
+    config: Res<AmbienceConfig>,
+    camera: Query<&FloatingOriginCamera>,
+    raycast: TerrainRaycast,
+    mut state: ResMut<AmbienceState>,
+) {
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    let position = camera.position;
+    if let Some(last) = state.last_position
+        && dt > 0.0
+    {
+        let speed = (position.distance(last) / f64::from(dt)) as f32;
+        let blend = 1.0 - (-dt / config.speed_smoothing_secs.max(1e-3)).exp();
+        state.speed += (speed - state.speed) * blend;
+    }
+    state.last_position = Some(position);
+
+    state.until_sample -= dt;
+    if state.until_sample > 0.0 {
+        return;
+    }
+    state.until_sample = config.density_interval_secs;
+
+    // Only finds ground within the ray height, which is all the city needs:
+    // any higher and it's out of earshot anyway.
+    let height = config.density_ray_height_m;
+    state.clearance = raycast
+        .ground_below(position, height)
+        .map(|hit| (position.length() - hit.position.length()) as f32);
+
+    // A ring of ground heights in the camera's tangent plane.
+    let up = position.normalize_or_zero();
+    let east = DVec3::Z.cross(up).try_normalize().unwrap_or(DVec3::X);
+    let north = up.cross(east);
+    let ground = position - up * f64::from(state.clearance.unwrap_or(0.0));
+    let heights: Vec<f64> = (0..RING_SAMPLES)
+        .filter_map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / RING_SAMPLES as f64;
+            let offset = (east * angle.cos() + north * angle.sin()) * config.density_ring_radius_m;
+            raycast
+                .ground_below(ground + offset, height)
+                .map(|hit| hit.position.length())
+        })
+        .collect();
+    state.relief = if heights.len() >= RING_SAMPLES / 2 {
+        let mean = heights.iter().sum::<f64>() / heights.len() as f64;
+        let variance =
+            heights.iter().map(|h| (h - mean).powi(2)).sum::<f64>() / heights.len() as f64;
+        variance.sqrt() as f32
+    } else {
+        0.0
+    };
+}
+
+/// Set both voices from the sampled surroundings.
+fn update_ambience(
+    config: Res<AmbienceConfig>,
+    state: Res<AmbienceState>,
+    camera: Query<&FloatingOriginCamera>,
+    teleport: Res<TeleportAnimation>,
+    mut wind: Query<&mut AudioSink, With<AmbientWind>>,
+    city: Option<Res<CityAmbience>>,
+) {
+    let altitude = camera
+        .single()
+        .map_or(0.0, |c| (c.position.length() - EARTH_RADIUS_M_F64) as f32);
+
+    let (wind_volume, wind_speed, city_volume) = if config.enabled {
+        ambience_levels(&config, altitude, &state)
+    } else {
+        (0.0, 1.0, 0.0)
+    };
+
+    if let Ok(mut sink) = wind.single_mut() {
+        let volume = if teleport.is_active() {
+            0.0
+        } else {
+            wind_volume
+        };
+        sink.set_volume(Volume::Linear(volume));
+        sink.set_speed(wind_speed);
+    }
+    if let Some(city) = city {
+        city.shared
+            .amplitude
+            .store(city_volume.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Wind volume, wind playback speed and city volume for a camera at
+/// `altitude`.
+fn ambience_levels(
+    config: &AmbienceConfig,
+    altitude: f32,
+    state: &AmbienceState,
+) -> (f32, f32, f32) {
+    let by_altitude = ramp(0.0, config.wind_full_altitude_m, altitude);
+    let by_speed = ramp(0.0, config.wind_full_speed_mps, state.speed);
+    let intensity = by_altitude.max(by_speed);
+    let air = 1.0
+        - ramp(
+            config.muffle_start_altitude_m,
+            config.muffle_end_altitude_m,
+            altitude,
+        );
+    let wind_volume = (config.wind_floor_volume
+        + (config.wind_volume - config.wind_floor_volume) * intensity)
+        * air;
+    let wind_speed = config.muffled_playback_speed + (1.0 - config.muffled_playback_speed) * air;
+
+    let near_ground = state.clearance.map_or(0.0, |clearance| {
+        1.0 - ramp(0.0, config.city_max_clearance_m, clearance)
+    });
+    let density = ramp(
+        config.city_relief_min_m,
+        config.city_relief_full_m,
+        state.relief,
+    );
+    let city_volume = config.city_volume * near_ground * density;
+
+    (wind_volume, wind_speed.max(0.01), city_volume)
+}
+
+/// 0 at `start`, 1 at `end`, smoothstepped between.
+fn ramp(start: f32, end: f32, x: f32) -> f32 {
+    if end <= start {
+        return f32::from(x >= end);
+    }
+    let t = ((x - start) / (end - start)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
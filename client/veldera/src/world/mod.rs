@@ -3,6 +3,11 @@
 //! The world subsystems are engine crates now — coordinates and floating origin
 //! in [`veldera_geo`], terrain streaming in `veldera_terrain`, celestial state
 //! in `veldera_sky`. What remains here is [`geo`], the client-side plugin that
-//! bundles the location services (geocoding/elevation + teleport) it builds on.
+//! bundles the location services (geocoding/elevation + teleport) it builds on,
+//! [`ambience`], the wind and city sound the camera's surroundings drive, and
+//! [`weather`], which lets the live conditions at the camera drive the sky's
+//! weather.
 
+pub mod ambience;
 pub mod geo;
+pub mod weather;
@@ -0,0 +1,165 @@
+//! Live weather: the current conditions at the camera drive the [`Weather`].
+//!
+//! While [`LiveWeather::enabled`] is set (the network settings' opt-in), the
+//! camera's location is looked up through [`veldera_places`] and each new set
+//! of conditions is mapped onto the weather:
+//!
+//! - **Visibility** below [`LiveWeatherConfig::fog_visibility_m`] becomes
+//!   ground fog of that visibility; clearer air clears the fog.
+//! - **Precipitation** becomes rain or snow, its intensity from the rate
+//!   relative to the configured full rates (or from the weather code, when the
+//!   amounts lag behind it).
+//! - **Cloud cover** is recorded on the weather as a fraction.
+//!
+//! The weather is only written when new conditions arrive, so it can still
+//! be edited by hand in between.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use veldera_async::TaskSpawner;
+use veldera_geo::{coords::ecef_to_lat_lon, floating_origin::FloatingOriginCamera};
+use veldera_places::{CachedConditions, CurrentConditions, HttpClient, LiveWeather};
+use veldera_sky::weather::{PrecipitationKind, Weather};
+
+use crate::config;
+
+/// Plugin driving the [`Weather`] from live conditions.
+pub struct LiveWeatherPlugin;
+
+impl Plugin for LiveWeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(config::ConfigPlugin::<LiveWeatherConfig>::new(
+            config::paths::WEATHER,
+        ))
+        .add_systems(Update, drive_live_weather);
+    }
+}
+
+/// Hot-reloadable mapping from live conditions to weather, loaded from
+/// `assets/game/config/world/weather.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LiveWeatherConfig {
+    /// Visibility (m) below which the conditions count as fog.
+    pub fog_visibility_m: f32,
+    /// Rain rate at full intensity (mm/h).
+    pub rain_full_mm_per_h: f32,
+    /// Snow rate at full intensity (mm/h of water).
+    pub snow_full_mm_per_h: f32,
+}
+
+/// Request the conditions at the camera, and apply them once they arrive.
+fn drive_live_weather(
+    mut live: ResMut<LiveWeather>,
+    mut weather: ResMut<Weather>,
+    mut applied: Local<Option<(i32, i32, f64)>>,
+    config: Res<LiveWeatherConfig>,
+    client: Res<HttpClient>,
+    spawner: TaskSpawner,
+    real_time: Res<Time<Real>>,
+    camera: Query<&FloatingOriginCamera>,
+) {
+    if !live.enabled {
+        *applied = None;
+        return;
+    }
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+
+    let (lat, lon) = ecef_to_lat_lon(camera.position);
+    live.request(lat, lon, real_time.elapsed_secs_f64(), &client, &spawner);
+
+    let Some(CachedConditions {
+        cell,
+        conditions,
+        fetched_at,
+    }) = live.current()
+    else {
+        return;
+    };
+    let key = (cell.0, cell.1, *fetched_at);
+    if *applied == Some(key) {
+        return;
+    }
+    *applied = Some(key);
+
+    let mut updated = *weather;
+    apply_conditions(&mut updated, conditions, &config);
+    if updated != *weather {
+        *weather = updated;
+    }
+}
+
+/// Map `conditions` onto `weather`, leaving what they don't cover alone.
+fn apply_conditions(
+    weather: &mut Weather,
+    conditions: &CurrentConditions,
+    config: &LiveWeatherConfig,
+) {
+    if let Some(visibility) = conditions.visibility {
+        if visibility < config.fog_visibility_m {
+            weather.set_fog_visibility_m(visibility);
+        } else {
+            weather.fog_density = 0.0;
+        }
+    }
+
+    let snow = conditions.is_snow();
+    let full_rate = if snow {
+        config.snow_full_mm_per_h
+    } else {
+        config.rain_full_mm_per_h
+    };
+    let measured =
+        (conditions.precipitation_rate_mm_per_h() / full_rate.max(f32::EPSILON)).min(1.0);
+    weather.precipitation_intensity = measured.max(conditions.reported_intensity());
+    if weather.precipitation_intensity > 0.0 {
+        weather.precipitation = if snow {
+            PrecipitationKind::Snow
+        } else {
+            PrecipitationKind::Rain
+        };
+    }
+
+    weather.cloud_cover = conditions
+        .cloud_cover
+        .map(|percent| (percent / 100.0).clamp(0.0, 1.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LiveWeatherConfig {
+        LiveWeatherConfig {
+            fog_visibility_m: 5_000.0,
+            rain_full_mm_per_h: 4.0,
+            snow_full_mm_per_h: 1.0,
+        }
+    }
+
+    #[test]
+    fn conditions_map_through_the_configured_thresholds() {
+        let mut weather = Weather::default();
+        let misty = CurrentConditions {
+            visibility: Some(6_000.0),
+            precipitation: Some(2.0),
+            ..default()
+        };
+        apply_conditions(&mut weather, &misty, &config());
+        // Above the configured fog threshold, and half the full rain rate.
+        assert_eq!(weather.fog_density, 0.0);
+        assert_eq!(weather.precipitation, PrecipitationKind::Rain);
+        assert!((weather.precipitation_intensity - 0.5).abs() < 1e-6);
+
+        let foggy = CurrentConditions {
+            visibility: Some(1_000.0),
+            ..default()
+        };
+        apply_conditions(&mut weather, &foggy, &config());
+        assert!(weather.fog_density > 0.0);
+        assert_eq!(weather.precipitation_intensity, 0.0);
+    }
+}
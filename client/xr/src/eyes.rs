@@ -0,0 +1,134 @@
+//! Per-eye atmosphere and clouds.
+//!
+//! The atmosphere and clouds render per camera, so each eye camera gets its
+//! own copy of the desktop camera's [`SphericalAtmosphere`],
+//! [`AtmosphereSettings`], and [`CloudLayers`], kept in step with live edits.
+//! The renderer then builds sky-view and aerial-view LUTs for each eye from
+//! that eye's own position and projection.
+//!
+//! Both eyes take the head's rotation as their atmosphere reference, so the
+//! sky-view LUTs are baked in the same frame and the skies match between eyes
+//! even on canted displays. Image-based lighting is not regenerated per eye:
+//! the eyes borrow the desktop camera's filtered environment map, which is
+//! baked from the head position.
+
+use bevy::{
+    camera::Exposure, core_pipeline::tonemapping::Tonemapping, light::EnvironmentMapLight,
+    prelude::*, render::view::Hdr, transform::TransformSystems,
+};
+use bevy_mod_xr::camera::XrCamera;
+
+use veldera_atmosphere::{AtmosphereSettings, SphericalAtmosphere, SphericalAtmosphereCamera};
+use veldera_clouds::CloudLayers;
+use veldera_geo::floating_origin::{FloatingOrigin, FloatingOriginCamera};
+
+pub(crate) struct XrEyesPlugin;
+
+impl Plugin for XrEyesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (attach_eye_views, sync_eye_views)
+                .chain()
+                .after(TransformSystems::Propagate),
+        );
+    }
+}
+
+/// The desktop camera's view components, as copied to the eyes.
+type DesktopView<'a> = (
+    &'a SphericalAtmosphere,
+    &'a AtmosphereSettings,
+    Option<&'a CloudLayers>,
+    &'a Tonemapping,
+    &'a Exposure,
+);
+
+/// Give newly spawned eye cameras the desktop camera's atmosphere, clouds,
+/// and tone mapping.
+fn attach_eye_views(
+    mut commands: Commands,
+    eyes: Query<Entity, (With<XrCamera>, Without<SphericalAtmosphere>)>,
+    desktop: Query<DesktopView, With<FloatingOriginCamera>>,
+) {
+    let Ok((atmosphere, settings, clouds, tonemapping, exposure)) = desktop.single() else {
+        return;
+    };
+    for eye in &eyes {
+        let mut entity = commands.entity(eye);
+        entity.insert((
+            atmosphere.clone(),
+            settings.clone(),
+            SphericalAtmosphereCamera::default(),
+            Hdr,
+            *tonemapping,
+            *exposure,
+        ));
+        if let Some(clouds) = clouds {
+            entity.insert(clouds.clone());
+        }
+    }
+}
+
+/// Place each eye's atmosphere camera, and carry over live edits to the
+/// desktop camera's atmosphere, clouds, and environment map.
+#[allow(clippy::type_complexity)]
+fn sync_eye_views(
+    mut commands: Commands,
+    origin: Res<FloatingOrigin>,
+    desktop: Query<
+        (
+            &Transform,
+            Ref<SphericalAtmosphere>,
+            Ref<AtmosphereSettings>,
+            Option<Ref<CloudLayers>>,
+            Option<Ref<EnvironmentMapLight>>,
+        ),
+        With<FloatingOriginCamera>,
+    >,
+    mut eyes: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut SphericalAtmosphereCamera,
+            &mut SphericalAtmosphere,
+            &mut AtmosphereSettings,
+            Option<&mut CloudLayers>,
+            Has<EnvironmentMapLight>,
+        ),
+        (With<XrCamera>, Without<FloatingOriginCamera>),
+    >,
+) {
+    let Ok((head, atmosphere, settings, clouds, environment)) = desktop.single() else {
+        return;
+    };
+    for (entity, eye, mut atmo_camera, mut eye_atmosphere, mut eye_settings, eye_clouds, lit) in
+        &mut eyes
+    {
+        let ecef = origin.position + eye.translation().as_dvec3();
+        *atmo_camera = SphericalAtmosphereCamera {
+            reference_rotation: Some(head.rotation),
+            ..SphericalAtmosphereCamera::from_ecef(ecef)
+        };
+
+        if atmosphere.is_changed() {
+            *eye_atmosphere = SphericalAtmosphere::clone(&atmosphere);
+        }
+        if settings.is_changed() {
+            *eye_settings = AtmosphereSettings::clone(&settings);
+        }
+        if let (Some(clouds), Some(mut eye_clouds)) = (&clouds, eye_clouds)
+            && clouds.is_changed()
+        {
+            *eye_clouds = CloudLayers::clone(clouds);
+        }
+        // The environment map appears once its first bake is filtered.
+        if let Some(environment) = &environment
+            && (environment.is_changed() || !lit)
+        {
+            commands
+                .entity(entity)
+                .insert(EnvironmentMapLight::clone(environment));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Controller input from OpenXR actions.
+//!
+//! One action set covers everything the viewer needs, with suggested
+//! bindings for the common controller profiles. Each frame the set is synced
+//! and its state copied into [`XrControllerInput`], so the locomotion systems
+//! never touch OpenXR directly. The right hand's aim pose is an action space
+//! on an [`XrAim`] entity under the tracking root, whose `Transform` the
+//! OpenXR plugin keeps current.
+
+use bevy::prelude::*;
+use bevy_mod_openxr::{
+    action_binding::{OxrSendActionBindings, OxrSuggestActionBinding},
+    action_set_attaching::OxrAttachActionSet,
+    action_set_syncing::{OxrActionSetSyncSet, OxrSyncActionSet},
+    openxr_session_running,
+    resources::OxrInstance,
+    session::OxrSession,
+    spaces::OxrSpaceExt,
+};
+use bevy_mod_xr::session::{XrSessionCreated, XrTrackingRoot};
+use openxr::{Action, ActionSet, Path, Posef, Vector2f};
+
+/// Interaction profiles bindings are suggested for, with the component paths
+/// for each action: fly stick, turn/climb stick, boost, teleport, and aim.
+const PROFILES: &[(&str, [&str; 5])] = &[
+    (
+        "/interaction_profiles/oculus/touch_controller",
+        [
+            "/user/hand/left/input/thumbstick",
+            "/user/hand/right/input/thumbstick",
+            "/user/hand/left/input/squeeze/value",
+            "/user/hand/right/input/trigger/value",
+            "/user/hand/right/input/aim/pose",
+        ],
+    ),
+    (
+        "/interaction_profiles/valve/index_controller",
+        [
+            "/user/hand/left/input/thumbstick",
+            "/user/hand/right/input/thumbstick",
+            "/user/hand/left/input/squeeze/value",
+            "/user/hand/right/input/trigger/value",
+            "/user/hand/right/input/aim/pose",
+        ],
+    ),
+    (
+        "/interaction_profiles/microsoft/motion_controller",
+        [
+            "/user/hand/left/input/thumbstick",
+            "/user/hand/right/input/thumbstick",
+            "/user/hand/left/input/squeeze/click",
+            "/user/hand/right/input/trigger/value",
+            "/user/hand/right/input/aim/pose",
+        ],
+    ),
+];
+
+pub(crate) struct XrInputPlugin;
+
+impl Plugin for XrInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrControllerInput>()
+            .add_systems(
+                Startup,
+                create_actions.run_if(resource_exists::<OxrInstance>),
+            )
+            .add_systems(OxrSendActionBindings, suggest_bindings)
+            .add_systems(XrSessionCreated, (attach_actions, spawn_aim_space))
+            .add_systems(
+                PreUpdate,
+                (
+                    sync_actions.before(OxrActionSetSyncSet),
+                    read_actions.after(OxrActionSetSyncSet),
+                )
+                    .run_if(openxr_session_running.and(resource_exists::<XrActions>)),
+            );
+    }
+}
+
+/// This frame's controller state, for the locomotion systems.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct XrControllerInput {
+    /// Left stick: fly along the gaze (y) and strafe (x).
+    pub fly: Vec2,
+    /// Right stick: snap turn (x) and climb or descend (y).
+    pub turn_climb: Vec2,
+    /// Left grip: boost flight speed.
+    pub boost: bool,
+    /// Right trigger: aim a teleport while held, go on release.
+    pub teleport: bool,
+}
+
+/// Marker for the entity tracking the right controller's aim pose.
+#[derive(Component)]
+pub struct XrAim;
+
+/// The viewer's OpenXR action set and actions.
+#[derive(Resource)]
+struct XrActions {
+    set: ActionSet,
+    fly: Action<Vector2f>,
+    turn_climb: Action<Vector2f>,
+    boost: Action<bool>,
+    teleport: Action<bool>,
+    aim: Action<Posef>,
+}
+
+fn create_actions(mut commands: Commands, instance: Res<OxrInstance>) {
+    let actions = (|| -> openxr::Result<XrActions> {
+        let set = instance.create_action_set("veldera", "Veldera", 0)?;
+        Ok(XrActions {
+            fly: set.create_action("fly", "Fly", &[])?,
+            turn_climb: set.create_action("turn_climb", "Turn and climb", &[])?,
+            boost: set.create_action("boost", "Boost", &[])?,
+            teleport: set.create_action("teleport", "Teleport", &[])?,
+            aim: set.create_action("aim", "Aim", &[])?,
+            set,
+        })
+    })();
+    match actions {
+        Ok(actions) => commands.insert_resource(actions),
+        Err(e) => error!("Failed to create OpenXR actions: {e}"),
+    }
+}
+
+fn suggest_bindings(
+    actions: Option<Res<XrActions>>,
+    mut bindings: MessageWriter<OxrSuggestActionBinding>,
+) {
+    let Some(actions) = actions else {
+        return;
+    };
+    let raw = [
+        actions.fly.as_raw(),
+        actions.turn_climb.as_raw(),
+        actions.boost.as_raw(),
+        actions.teleport.as_raw(),
+        actions.aim.as_raw(),
+    ];
+    for (profile, paths) in PROFILES {
+        for (action, path) in raw.iter().zip(paths) {
+            bindings.write(OxrSuggestActionBinding {
+                action: *action,
+                interaction_profile: (*profile).into(),
+                bindings: vec![(*path).into()],
+            });
+        }
+    }
+}
+
+fn attach_actions(actions: Option<Res<XrActions>>, mut attach: MessageWriter<OxrAttachActionSet>) {
+    if let Some(actions) = actions {
+        attach.write(OxrAttachActionSet(actions.set.clone()));
+    }
+}
+
+/// Spawn the aim-pose space under the tracking root.
+fn spawn_aim_space(
+    mut commands: Commands,
+    actions: Option<Res<XrActions>>,
+    session: Res<OxrSession>,
+    root: Query<Entity, With<XrTrackingRoot>>,
+) {
+    let (Some(actions), Ok(root)) = (actions, root.single()) else {
+        return;
+    };
+    match session.create_action_space(&actions.aim, Path::NULL, Isometry3d::IDENTITY) {
+        Ok(space) => {
+            commands.spawn((space, XrAim, Transform::default(), ChildOf(root)));
+        }
+        Err(e) => error!("Failed to create the OpenXR aim space: {e}"),
+    }
+}
+
+fn sync_actions(actions: Res<XrActions>, mut sync: MessageWriter<OxrSyncActionSet>) {
+    sync.write(OxrSyncActionSet(actions.set.clone()));
+}
+
+fn read_actions(
+    actions: Res<XrActions>,
+    session: Res<OxrSession>,
+    mut input: ResMut<XrControllerInput>,
+) {
+    let stick = |action: &Action<Vector2f>| {
+        action
+            .state(&session, Path::NULL)
+            .map(|s| Vec2::new(s.current_state.x, s.current_state.y))
+            .unwrap_or_default()
+    };
+    let button = |action: &Action<bool>| {
+        action
+            .state(&session, Path::NULL)
+            .is_ok_and(|s| s.current_state)
+    };
+    *input = XrControllerInput {
+        fly: stick(&actions.fly),
+        turn_climb: stick(&actions.turn_climb),
+        boost: button(&actions.boost),
+        teleport: button(&actions.teleport),
+    };
+}
@@ -0,0 +1,212 @@
+//! OpenXR stereo viewing for the gameplay client.
+//!
+//! With an OpenXR runtime available, the host builds its app with
+//! `bevy_mod_openxr`'s plugins and adds [`XrViewerPlugin`]. While a session
+//! runs:
+//!
+//! - The headset's tracking space is placed on the planet by [`XrRig`], and
+//!   the head pose is composed onto it each frame to drive the
+//!   floating-origin camera ([`rig`]). Terrain streaming, the sky, and every
+//!   other system that follows the camera therefore follow the head, and the
+//!   desktop window mirrors the head's view.
+//! - Each eye camera renders its own atmosphere and clouds, copied from the
+//!   desktop camera, with its own sky-view and aerial-view LUTs; both eyes
+//!   share the head's atmosphere frame so their skies match ([`eyes`]).
+//! - The controllers fly the rig (left stick along the gaze, right stick to
+//!   snap-turn and climb, left grip to boost) and teleport it to where the
+//!   right controller points at the terrain (hold the right trigger to aim,
+//!   release to go) ([`locomotion`]).
+//!
+//! Desktop input still reaches the flycam while the headset is on, but the
+//! rig overwrites the camera every frame; the flycam picks up from the head's
+//! last pose when the session ends.
+
+mod eyes;
+mod input;
+mod locomotion;
+pub mod rig;
+
+use bevy::{prelude::*, reflect::TypePath};
+use bevy_mod_xr::{
+    camera::XrCamera,
+    session::{XrState, XrTrackingRoot},
+};
+use serde::Deserialize;
+
+use veldera_camera::FlightCamera;
+use veldera_config::ConfigPlugin;
+use veldera_geo::floating_origin::{FloatingOriginCamera, FloatingOriginSystems};
+
+pub use input::{XrAim, XrControllerInput};
+pub use rig::{HeadPose, XrRig};
+
+/// Plugin for OpenXR stereo viewing.
+///
+/// The host supplies the [`XrConfig`] path, and must also add
+/// `bevy_mod_openxr`'s plugins (`add_xr_plugins`) in place of the default
+/// plugin group.
+pub struct XrViewerPlugin {
+    /// Path to the [`XrConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl XrViewerPlugin {
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Plugin for XrViewerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<XrConfig>::new(self.config_path))
+            .init_resource::<XrHead>()
+            .add_plugins((input::XrInputPlugin, eyes::XrEyesPlugin))
+            .configure_sets(
+                PostUpdate,
+                XrRigSystems
+                    .run_if(session_running)
+                    .before(FloatingOriginSystems::Sync),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    track_head,
+                    take_over_camera.run_if(not(resource_exists::<XrRig>)),
+                    (
+                        locomotion::fly,
+                        locomotion::snap_turn,
+                        locomotion::teleport,
+                        apply_rig,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<XrRig>),
+                )
+                    .chain()
+                    .in_set(XrRigSystems),
+            )
+            .add_systems(
+                PostUpdate,
+                release_camera.run_if(not(session_running).and(resource_exists::<XrRig>)),
+            );
+    }
+}
+
+/// System set in `PostUpdate` that moves the rig and places the camera and
+/// tracking root from it, ahead of the floating-origin sync.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XrRigSystems;
+
+/// Run condition: an OpenXR session is running.
+pub fn session_running(state: Option<Res<XrState>>) -> bool {
+    matches!(state.as_deref(), Some(XrState::Running))
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Hot-reloadable VR locomotion tuning, loaded from
+/// `assets/game/config/xr.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct XrConfig {
+    /// Flight speed at full stick deflection near the ground (m/s); scaled
+    /// with altitude like the flycam's.
+    pub fly_speed: f32,
+    /// Flight speed multiplier while the boost grip is held.
+    pub boost_multiplier: f32,
+    /// Stick deflection below which input is ignored (0–1).
+    pub stick_deadzone: f32,
+    /// Right-stick deflection that triggers a snap turn (0–1); the stick must
+    /// return inside the deadzone before the next.
+    pub snap_turn_threshold: f32,
+    /// Snap-turn step (degrees).
+    pub snap_turn_deg: f32,
+    /// Furthest terrain point a teleport can target (m).
+    pub teleport_max_distance_m: f64,
+}
+
+// ============================================================================
+// Head tracking
+// ============================================================================
+
+/// The latest head pose in tracking space, from the eye cameras.
+#[derive(Resource, Clone, Copy, Debug, Deref)]
+pub struct XrHead(pub HeadPose);
+
+impl Default for XrHead {
+    fn default() -> Self {
+        Self(HeadPose::DEFAULT)
+    }
+}
+
+/// Derive the head pose from the eye cameras' tracked local transforms.
+fn track_head(eyes: Query<(&XrCamera, &Transform)>, mut head: ResMut<XrHead>) {
+    let mut left = None;
+    let mut right = None;
+    for (eye, transform) in &eyes {
+        match eye.0 {
+            0 => left = Some(transform),
+            1 => right = Some(transform),
+            _ => {}
+        }
+    }
+    if let (Some(left), Some(right)) = (left, right) {
+        head.0 = HeadPose::from_eyes(left, right);
+    }
+}
+
+/// Seed the rig from the desktop camera when a session starts, so the head
+/// begins where the camera was, facing the same way.
+fn take_over_camera(
+    mut commands: Commands,
+    head: Res<XrHead>,
+    camera: Query<(&FloatingOriginCamera, &Transform), Without<XrTrackingRoot>>,
+) {
+    let Ok((camera, transform)) = camera.single() else {
+        return;
+    };
+    let mut rig = XrRig::facing(camera.position, transform.forward().as_dvec3());
+    rig.position -= rig.orientation() * head.translation;
+    commands.insert_resource(rig);
+    info!("VR session started; the headset now drives the camera");
+}
+
+/// Place the floating-origin camera at the head and the tracking root so the
+/// head lands at the render origin.
+#[allow(clippy::type_complexity)]
+fn apply_rig(
+    rig: Res<XrRig>,
+    head: Res<XrHead>,
+    mut camera: Query<
+        (
+            &mut FloatingOriginCamera,
+            &mut Transform,
+            Option<&mut FlightCamera>,
+        ),
+        Without<XrTrackingRoot>,
+    >,
+    mut root: Query<&mut Transform, With<XrTrackingRoot>>,
+) {
+    let Ok((mut camera, mut transform, flight)) = camera.single_mut() else {
+        return;
+    };
+    camera.position = rig.to_ecef(head.translation);
+    transform.rotation = rig.to_world_rotation(head.rotation).as_quat();
+    // Keep the flycam's heading current so it resumes from the head's view.
+    if let Some(mut flight) = flight {
+        flight.direction = *transform.forward();
+    }
+    for mut root in &mut root {
+        *root = rig.tracking_root_transform(&head);
+    }
+}
+
+/// Hand the camera back to the flycam when the session ends. The camera keeps
+/// the head's last pose; dropping the rig re-seeds it from the camera next
+/// session.
+fn release_camera(mut commands: Commands) {
+    commands.remove_resource::<XrRig>();
+    info!("VR session ended; the flycam drives the camera again");
+}
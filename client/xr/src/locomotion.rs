@@ -0,0 +1,134 @@
+//! Controller locomotion: flight, snap turning, and terrain teleport.
+//!
+//! All three move the [`XrRig`] rather than the camera; the rig systems then
+//! place the camera at the head.
+
+use bevy::prelude::*;
+use glam::DVec3;
+
+use veldera_camera::altitude_speed_factor;
+use veldera_geo::floating_origin::FloatingOrigin;
+use veldera_terrain::raycast::TerrainRaycast;
+
+use crate::{XrAim, XrConfig, XrControllerInput, XrHead, XrRig};
+
+/// Rescale a stick so the deadzone maps to zero and full deflection to one.
+fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+    stick * ((length - deadzone) / (1.0 - deadzone)).min(1.0) / length
+}
+
+/// Fly along the gaze with the left stick, and climb or descend along local
+/// up with the right stick.
+pub(crate) fn fly(
+    time: Res<Time>,
+    config: Res<XrConfig>,
+    input: Res<XrControllerInput>,
+    head: Res<XrHead>,
+    mut rig: ResMut<XrRig>,
+) {
+    let fly = apply_deadzone(input.fly, config.stick_deadzone);
+    let climb = apply_deadzone(Vec2::new(0.0, input.turn_climb.y), config.stick_deadzone).y;
+    if fly == Vec2::ZERO && climb == 0.0 {
+        return;
+    }
+
+    let rotation = rig.to_world_rotation(head.rotation);
+    let forward = rotation * DVec3::NEG_Z;
+    let right = rotation * DVec3::X;
+    let up = rig.position.normalize();
+    let direction = forward * f64::from(fly.y) + right * f64::from(fly.x) + up * f64::from(climb);
+
+    let mut speed = config.fly_speed * altitude_speed_factor(rig.to_ecef(head.translation));
+    if input.boost {
+        speed *= config.boost_multiplier;
+    }
+    let displacement = direction * f64::from(speed * time.delta_secs());
+    rig.translate(displacement);
+}
+
+/// Turn by a fixed step each time the right stick is pushed sideways past the
+/// threshold; it must return to the deadzone before the next step.
+pub(crate) fn snap_turn(
+    config: Res<XrConfig>,
+    input: Res<XrControllerInput>,
+    head: Res<XrHead>,
+    mut rig: ResMut<XrRig>,
+    mut armed: Local<bool>,
+) {
+    let x = input.turn_climb.x;
+    if x.abs() <= config.stick_deadzone {
+        *armed = true;
+        return;
+    }
+    if *armed && x.abs() >= config.snap_turn_threshold {
+        *armed = false;
+        let step = f64::from(config.snap_turn_deg).to_radians();
+        rig.turn_about_head(step * f64::from(x.signum()), &head);
+    }
+}
+
+/// Aim at the terrain with the right controller while the trigger is held,
+/// and move there when it is released.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn teleport(
+    config: Res<XrConfig>,
+    input: Res<XrControllerInput>,
+    head: Res<XrHead>,
+    origin: Res<FloatingOrigin>,
+    raycast: TerrainRaycast,
+    aim: Query<&GlobalTransform, With<XrAim>>,
+    mut rig: ResMut<XrRig>,
+    mut gizmos: Gizmos,
+    mut target: Local<Option<DVec3>>,
+) {
+    if !input.teleport {
+        if let Some(target) = target.take() {
+            rig.place_head_over(target, &head);
+        }
+        return;
+    }
+
+    let Ok(aim) = aim.single() else {
+        return;
+    };
+    let start = aim.translation();
+    let ray_origin = origin.position + start.as_dvec3();
+    let direction = aim.forward().as_dvec3();
+    let hit = raycast.cast_ray(ray_origin, direction, config.teleport_max_distance_m);
+    *target = hit.map(|hit| hit.position);
+
+    match hit {
+        Some(hit) => {
+            let end = (hit.position - origin.position).as_vec3();
+            gizmos.line(start, end, Color::srgb(0.3, 0.8, 1.0));
+            let normal = Dir3::new(hit.normal.as_vec3()).unwrap_or(Dir3::Y);
+            gizmos.circle(
+                Isometry3d::new(end, Quat::from_rotation_arc(Vec3::Z, *normal)),
+                0.5,
+                Color::srgb(0.3, 0.8, 1.0),
+            );
+        }
+        None => {
+            let end = start + aim.forward() * 50.0;
+            gizmos.line(start, end, Color::srgb(1.0, 0.3, 0.3));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadzone_rescales_to_full_range() {
+        assert_eq!(apply_deadzone(Vec2::new(0.1, 0.0), 0.2), Vec2::ZERO);
+        let full = apply_deadzone(Vec2::new(0.0, 1.0), 0.2);
+        assert!((full.y - 1.0).abs() < 1e-6);
+        let half = apply_deadzone(Vec2::new(0.6, 0.0), 0.2);
+        assert!((half.x - 0.5).abs() < 1e-6);
+    }
+}
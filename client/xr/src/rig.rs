@@ -0,0 +1,230 @@
+//! The VR rig: where the headset's tracking space sits on the planet.
+//!
+//! OpenXR reports head and controller poses in a tracking space whose origin
+//! is the play area's floor centre and whose +Y is up. [`XrRig`] places that
+//! space on the globe: its origin at an ECEF position, its +Y along local up,
+//! and its -Z at a compass heading. Locomotion moves the rig; the headset
+//! moves the head within it.
+//!
+//! Each frame the head pose is composed onto the rig in f64 to give the
+//! floating-origin camera's ECEF position, and the tracking root's render
+//! transform is set so the head lands at the render origin. The eyes, as
+//! children of the tracking root, then sit within a few centimetres of the
+//! origin wherever the rig is.
+
+use bevy::prelude::*;
+use glam::{DMat3, DQuat, DVec3};
+
+use veldera_constants::EARTH_RADIUS_M_F64;
+
+/// Lowest rig radius, matching the flycam's clamp.
+const MIN_RADIUS: f64 = EARTH_RADIUS_M_F64 - 100.0;
+
+/// Highest rig radius, matching the flycam's clamp.
+const MAX_RADIUS: f64 = EARTH_RADIUS_M_F64 + 10_000_000.0;
+
+/// Placement of the headset's tracking space on the planet.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct XrRig {
+    /// ECEF position of the tracking-space origin (m).
+    pub position: DVec3,
+    /// Compass heading of the tracking space's -Z axis (radians, clockwise
+    /// from north).
+    pub heading: f64,
+}
+
+impl XrRig {
+    /// A rig at `position` facing `heading`.
+    pub fn new(position: DVec3, heading: f64) -> Self {
+        Self { position, heading }
+    }
+
+    /// A rig whose tracking space faces the horizontal part of `direction`,
+    /// standing at `position`; for taking over from a desktop camera.
+    pub fn facing(position: DVec3, direction: DVec3) -> Self {
+        let (north, east, _) = tangent_basis(position);
+        let heading = direction.dot(east).atan2(direction.dot(north));
+        Self::new(position, if heading.is_finite() { heading } else { 0.0 })
+    }
+
+    /// Rotation from tracking space to ECEF axes.
+    pub fn orientation(&self) -> DQuat {
+        let (north, east, up) = tangent_basis(self.position);
+        // Tracking +X east, +Y up, -Z north at heading zero.
+        let enu = DQuat::from_mat3(&DMat3::from_cols(east, up, -north));
+        enu * DQuat::from_rotation_y(-self.heading)
+    }
+
+    /// ECEF position of a point given in tracking space.
+    pub fn to_ecef(&self, local: DVec3) -> DVec3 {
+        self.position + self.orientation() * local
+    }
+
+    /// ECEF-axis rotation of a rotation given in tracking space.
+    pub fn to_world_rotation(&self, local: DQuat) -> DQuat {
+        self.orientation() * local
+    }
+
+    /// Render transform for the tracking root that puts `head` at the render
+    /// origin.
+    pub fn tracking_root_transform(&self, head: &HeadPose) -> Transform {
+        let orientation = self.orientation();
+        Transform {
+            translation: (orientation * -head.translation).as_vec3(),
+            rotation: orientation.as_quat(),
+            scale: Vec3::ONE,
+        }
+    }
+
+    /// Move the rig by an ECEF displacement, keeping it within the flycam's
+    /// altitude range.
+    pub fn translate(&mut self, displacement: DVec3) {
+        let moved = self.position + displacement;
+        let radius = moved.length();
+        if radius < 1.0 {
+            return;
+        }
+        self.position = moved * (radius.clamp(MIN_RADIUS, MAX_RADIUS) / radius);
+    }
+
+    /// Turn the tracking space about local up by `angle` (radians, clockwise
+    /// seen from above), pivoting about the head so the view doesn't swing.
+    pub fn turn_about_head(&mut self, angle: f64, head: &HeadPose) {
+        let head_ecef = self.to_ecef(head.translation);
+        self.heading = (self.heading + angle).rem_euclid(std::f64::consts::TAU);
+        self.position += head_ecef - self.to_ecef(head.translation);
+    }
+
+    /// Move the rig so `head` stands over `target` (ECEF): the floor lands
+    /// on the target and the head keeps its height above the floor.
+    pub fn place_head_over(&mut self, target: DVec3, head: &HeadPose) {
+        // Take the frame at the target; the head's offset is small enough that
+        // local up barely differs at the final position.
+        self.position = target;
+        let horizontal = DVec3::new(head.translation.x, 0.0, head.translation.z);
+        self.position = target - self.orientation() * horizontal;
+    }
+}
+
+/// The head pose in tracking space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeadPose {
+    /// Head position (m).
+    pub translation: DVec3,
+    /// Head orientation.
+    pub rotation: DQuat,
+}
+
+impl HeadPose {
+    /// Standing at the tracking origin, eyes 1.7 m up, looking along -Z.
+    pub const DEFAULT: Self = Self {
+        translation: DVec3::new(0.0, 1.7, 0.0),
+        rotation: DQuat::IDENTITY,
+    };
+
+    /// The head between a pair of eye poses: their midpoint, halfway between
+    /// their orientations.
+    pub fn from_eyes(left: &Transform, right: &Transform) -> Self {
+        Self {
+            translation: (left.translation.as_dvec3() + right.translation.as_dvec3()) * 0.5,
+            rotation: left
+                .rotation
+                .as_dquat()
+                .slerp(right.rotation.as_dquat(), 0.5),
+        }
+    }
+}
+
+/// Local north, east, and up at an ECEF position, using the compass
+/// convention (`east = north × up`), with north falling back to +X at the
+/// poles.
+fn tangent_basis(position: DVec3) -> (DVec3, DVec3, DVec3) {
+    let up = position.normalize();
+    let mut north = (DVec3::Z - up * DVec3::Z.dot(up)).normalize_or_zero();
+    if north.length_squared() < 0.5 {
+        north = (DVec3::X - up * DVec3::X.dot(up)).normalize_or_zero();
+    }
+    (north, north.cross(up), up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn somewhere() -> DVec3 {
+        veldera_geo::coords::lat_lon_to_ecef(-33.86, 151.21, EARTH_RADIUS_M_F64 + 40.0)
+    }
+
+    #[test]
+    fn test_orientation_is_upright() {
+        let rig = XrRig::new(somewhere(), 1.2);
+        let up = rig.orientation() * DVec3::Y;
+        assert!((up - somewhere().normalize()).length() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_heading_is_clockwise_from_north() {
+        let (north, east, _) = tangent_basis(somewhere());
+        let forward = |heading: f64| XrRig::new(somewhere(), heading).orientation() * -DVec3::Z;
+        assert!((forward(0.0) - north).length() < TOLERANCE);
+        assert!((forward(std::f64::consts::FRAC_PI_2) - east).length() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_facing_recovers_heading() {
+        let rig = XrRig::new(somewhere(), 2.0);
+        let forward = rig.orientation() * DVec3::new(0.0, -0.5, -1.0);
+        let facing = XrRig::facing(somewhere(), forward);
+        assert!((facing.heading - 2.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_tracking_root_puts_head_at_origin() {
+        let rig = XrRig::new(somewhere(), 0.7);
+        let head = HeadPose {
+            translation: DVec3::new(0.3, 1.6, -0.4),
+            rotation: DQuat::from_rotation_y(0.5),
+        };
+        let root = rig.tracking_root_transform(&head);
+        let head_render = root.transform_point(head.translation.as_vec3());
+        assert!(head_render.length() < 1e-5, "{head_render}");
+    }
+
+    #[test]
+    fn test_turn_keeps_head_in_place() {
+        let mut rig = XrRig::new(somewhere(), 0.0);
+        let head = HeadPose {
+            translation: DVec3::new(1.0, 1.7, 0.5),
+            ..HeadPose::DEFAULT
+        };
+        let before = rig.to_ecef(head.translation);
+        rig.turn_about_head(0.8, &head);
+        assert!((rig.to_ecef(head.translation) - before).length() < 1e-6);
+        assert!((rig.heading - 0.8).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_place_head_over_target() {
+        let mut rig = XrRig::new(somewhere(), 0.3);
+        let head = HeadPose {
+            translation: DVec3::new(-0.6, 1.5, 0.9),
+            ..HeadPose::DEFAULT
+        };
+        let target = veldera_geo::coords::lat_lon_to_ecef(-33.9, 151.2, EARTH_RADIUS_M_F64);
+        rig.place_head_over(target, &head);
+        let head_ecef = rig.to_ecef(head.translation);
+        let up = target.normalize();
+        let height = (head_ecef - target).dot(up);
+        assert!((height - 1.5).abs() < 1e-6, "height {height}");
+        assert!(((head_ecef - target) - up * height).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_translate_clamps_altitude() {
+        let mut rig = XrRig::new(somewhere(), 0.0);
+        rig.translate(-somewhere().normalize() * 10_000.0);
+        assert!((rig.position.length() - MIN_RADIUS).abs() < 1e-6);
+    }
+}
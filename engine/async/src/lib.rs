@@ -33,11 +33,9 @@ mod native {
     ///
     /// On native, this wraps a Tokio `JoinHandle`. Calling [`cancel`](Self::cancel)
     /// aborts the underlying task immediately, which also aborts any in-flight
-    /// HTTP request.
-    #[allow(dead_code)]
+    /// HTTP request. Dropping the handle lets the task run to completion.
     pub struct SpawnedTask(tokio::task::JoinHandle<()>);
 
-    #[allow(dead_code)]
     impl SpawnedTask {
         /// Cancel the task. This aborts the Tokio task immediately.
         pub fn cancel(self) {
@@ -75,7 +73,6 @@ mod native {
         /// Like [`spawn`](Self::spawn), but returns a [`SpawnedTask`] handle.
         /// Cancelling the handle aborts the underlying Tokio task, which also
         /// aborts any in-flight HTTP request driven by the future.
-        #[allow(dead_code)]
         pub fn spawn_cancellable<F>(&self, future: F) -> SpawnedTask
         where
             F: Future<Output = ()> + Send + 'static,
@@ -91,21 +88,26 @@ mod native {
 mod wasm {
     use std::future::Future;
 
-    use bevy::{ecs::system::SystemParam, prelude::*, tasks::AsyncComputeTaskPool};
+    use bevy::{
+        ecs::system::SystemParam,
+        prelude::*,
+        tasks::{AsyncComputeTaskPool, futures_lite::future},
+    };
 
     /// Opaque handle to a spawned async task that can be cancelled.
     ///
-    /// On WASM, this wraps a Bevy `Task`. Dropping the handle (via
-    /// [`cancel`](Self::cancel)) stops the future from being polled.
-    #[allow(dead_code)]
-    pub struct SpawnedTask(bevy::tasks::Task<()>);
+    /// On WASM, Bevy tasks can't be cancelled (dropping one detaches it), so
+    /// the future is raced against a cancellation signal instead.
+    /// [`cancel`](Self::cancel) fires the signal, which drops the future; a
+    /// dropped `fetch` aborts its HTTP request. Dropping the handle lets the
+    /// task run to completion.
+    pub struct SpawnedTask(async_channel::Sender<()>);
 
-    #[allow(dead_code)]
     impl SpawnedTask {
-        /// Cancel the task by dropping the inner handle, which stops it
-        /// from being polled.
+        /// Cancel the task: it stops at its next poll and its future is
+        /// dropped.
         pub fn cancel(self) {
-            drop(self.0);
+            let _ = self.0.try_send(());
         }
     }
 
@@ -140,15 +142,24 @@ mod wasm {
         /// Spawn a background task and return a handle that can cancel it.
         ///
         /// Like [`spawn`](Self::spawn), but returns a [`SpawnedTask`] handle.
-        /// Cancelling the handle drops the task, preventing it from being polled
-        /// further.
-        #[allow(dead_code)]
+        /// Cancelling the handle drops the future, preventing it from being
+        /// polled further.
         pub fn spawn_cancellable<F>(&self, future: F) -> SpawnedTask
         where
             F: Future<Output = ()> + 'static,
         {
-            let task = AsyncComputeTaskPool::get().spawn_local(future);
-            SpawnedTask(task)
+            let (cancel_tx, cancel_rx) = async_channel::bounded::<()>(1);
+            let cancelled = async move {
+                // A closed channel means the handle was dropped, not
+                // cancelled: keep running.
+                if cancel_rx.recv().await.is_err() {
+                    future::pending::<()>().await;
+                }
+            };
+            AsyncComputeTaskPool::get()
+                .spawn_local(future::or(future, cancelled))
+                .detach();
+            SpawnedTask(cancel_tx)
         }
     }
 }
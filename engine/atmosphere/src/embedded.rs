@@ -6,12 +6,96 @@
 //! live at the crate root too — calling them from a deeper module (e.g.
 //! `resources/`) would compute a `resources/shaders/…` path that no registered
 //! asset matches. Routing every load through here keeps the paths in one place.
+//!
+//! With the `shader_hot_reload` feature the shaders are instead read from the
+//! crate's source tree through the [`SOURCE`] asset source, which the asset
+//! watcher reloads on save; see [`AtmosphereShaderSourcePlugin`].
 
 use bevy::{
-    asset::{AssetServer, Handle, load_embedded_asset},
+    app::App,
+    asset::{AssetServer, Handle},
     shader::Shader,
 };
 
+#[cfg(feature = "shader_hot_reload")]
+use bevy::{
+    app::Plugin,
+    asset::{AssetApp, io::AssetSourceBuilder},
+    ecs::resource::Resource,
+};
+#[cfg(not(feature = "shader_hot_reload"))]
+use bevy::{
+    asset::{embedded_asset, load_embedded_asset},
+    shader::load_shader_library,
+};
+
+/// Name of the asset source the shaders load from with `shader_hot_reload`.
+#[cfg(feature = "shader_hot_reload")]
+pub const SOURCE: &str = "veldera_atmosphere";
+
+/// Registers the [`SOURCE`] asset source over this crate's `src/`, so the
+/// atmosphere shaders load from disk and hot-reload on save.
+///
+/// Asset sources must exist before the `AssetPlugin` builds, so add this
+/// ahead of `DefaultPlugins`. Needs the source tree at its build-time path;
+/// a dev aid for native builds only.
+#[cfg(feature = "shader_hot_reload")]
+pub struct AtmosphereShaderSourcePlugin;
+
+#[cfg(feature = "shader_hot_reload")]
+impl Plugin for AtmosphereShaderSourcePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_asset_source(
+            SOURCE,
+            AssetSourceBuilder::platform_default(concat!(env!("CARGO_MANIFEST_DIR"), "/src"), None),
+        );
+    }
+}
+
+/// Make the shader libraries importable and the entry points loadable.
+#[cfg(not(feature = "shader_hot_reload"))]
+pub(crate) fn register_shaders(app: &mut App) {
+    load_shader_library!(app, "shaders/types.wgsl");
+    load_shader_library!(app, "shaders/functions.wgsl");
+    load_shader_library!(app, "shaders/bruneton_functions.wgsl");
+    load_shader_library!(app, "shaders/bindings.wgsl");
+
+    embedded_asset!(app, "shaders/transmittance_lut.wgsl");
+    embedded_asset!(app, "shaders/multiscattering_lut.wgsl");
+    embedded_asset!(app, "shaders/sky_view_lut.wgsl");
+    embedded_asset!(app, "shaders/aerial_view_lut.wgsl");
+    embedded_asset!(app, "shaders/render_sky.wgsl");
+    embedded_asset!(app, "shaders/environment.wgsl");
+}
+
+/// Strong handles to the shader libraries loaded from [`SOURCE`], so they
+/// stay loaded (and importable) for as long as the app runs.
+#[cfg(feature = "shader_hot_reload")]
+#[derive(Resource)]
+struct ShaderLibraries {
+    _handles: Vec<Handle<Shader>>,
+}
+
+/// Make the shader libraries importable by loading them from [`SOURCE`] and
+/// holding their handles in a resource. The entry points load on first use.
+#[cfg(feature = "shader_hot_reload")]
+pub(crate) fn register_shaders(app: &mut App) {
+    let asset_server = app.world().resource::<AssetServer>();
+    let libraries = [
+        "shaders/types.wgsl",
+        "shaders/functions.wgsl",
+        "shaders/bruneton_functions.wgsl",
+        "shaders/bindings.wgsl",
+    ]
+    .into_iter()
+    .map(|library| asset_server.load::<Shader>(format!("{SOURCE}://{library}")))
+    .collect();
+    app.insert_resource(ShaderLibraries {
+        _handles: libraries,
+    });
+}
+
+#[cfg(not(feature = "shader_hot_reload"))]
 macro_rules! shader_loader {
     ($name:ident, $path:literal) => {
         pub(crate) fn $name(asset_server: &AssetServer) -> Handle<Shader> {
@@ -20,6 +104,15 @@ macro_rules! shader_loader {
     };
 }
 
+#[cfg(feature = "shader_hot_reload")]
+macro_rules! shader_loader {
+    ($name:ident, $path:literal) => {
+        pub(crate) fn $name(asset_server: &AssetServer) -> Handle<Shader> {
+            asset_server.load(format!("{SOURCE}://{}", $path))
+        }
+    };
+}
+
 shader_loader!(transmittance_lut, "shaders/transmittance_lut.wgsl");
 shader_loader!(multiscattering_lut, "shaders/multiscattering_lut.wgsl");
 shader_loader!(sky_view_lut, "shaders/sky_view_lut.wgsl");
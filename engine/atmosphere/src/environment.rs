@@ -17,20 +17,24 @@
 //!   node is implemented as a [`ViewNode`] alongside the rest of the fork's
 //!   atmosphere nodes. Standalone [`LightProbe`]-attached probes are not
 //!   supported (yet).
+//! - The cubemap is only re-rendered when the sky has visibly changed: the
+//!   sun, the camera's local up, or its altitude has moved past the probe's
+//!   [`EnvironmentRefresh`] thresholds, or the refresh interval has elapsed.
+//!   In between, Bevy's filter keeps prefiltering the last cubemap.
 
 use bevy::{
     asset::{AssetServer, Assets, Handle, RenderAssetUsages},
     ecs::{
         component::Component,
         entity::Entity,
-        query::{QueryItem, With, Without},
+        query::{Has, QueryItem, With, Without},
         resource::Resource,
         system::{Commands, Query, Res, ResMut, lifetimeless::Read},
         world::World,
     },
     image::Image,
     light::GeneratedEnvironmentMapLight,
-    math::{Quat, UVec2},
+    math::{Quat, UVec2, Vec3},
     pbr::{GpuLights, LightMeta, ViewLightsUniformOffset},
     render::{
         diagnostic::RecordDiagnostics,
@@ -42,6 +46,7 @@ use bevy::{
         texture::{CachedTexture, GpuImage},
         view::{ViewUniform, ViewUniformOffset, ViewUniforms},
     },
+    time::Time,
     utils::default,
 };
 use tracing::warn;
@@ -49,8 +54,8 @@ use tracing::warn;
 use crate::{
     ExtractedAtmosphere, GpuAtmosphereSettings,
     resources::{
-        AtmosphereSampler, AtmosphereTextures, AtmosphereTransform, AtmosphereTransforms,
-        AtmosphereTransformsOffset, GpuAtmosphere,
+        AtmosphereFrameData, AtmosphereSampler, AtmosphereTextures, AtmosphereTransform,
+        AtmosphereTransforms, AtmosphereTransformsOffset, GpuAtmosphere,
     },
 };
 
@@ -80,6 +85,8 @@ pub struct SphericalAtmosphereEnvironmentMapLight {
     /// Cubemap face resolution. Must be a power of two; if it isn't, it will
     /// be rounded up.
     pub size: UVec2,
+    /// When the cubemap is re-rendered from the LUTs.
+    pub refresh: EnvironmentRefresh,
 }
 
 impl Default for SphericalAtmosphereEnvironmentMapLight {
@@ -88,10 +95,83 @@ impl Default for SphericalAtmosphereEnvironmentMapLight {
             intensity: 1.0,
             affects_lightmapped_mesh_diffuse: true,
             size: UVec2::splat(256),
+            refresh: EnvironmentRefresh::default(),
         }
     }
 }
 
+/// How far the sky must change before the environment cubemap is
+/// re-rendered. A probe refreshes when any threshold is crossed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvironmentRefresh {
+    /// Sun movement since the last refresh (radians).
+    pub sun_angle: f32,
+    /// Movement of the camera's local up since the last refresh (radians).
+    /// About 111 km of travel per degree on Earth.
+    pub up_angle: f32,
+    /// Change in camera radius since the last refresh (m).
+    pub altitude: f32,
+    /// Longest time between refreshes regardless of movement (s), so changes
+    /// the thresholds don't see (settings edits, moonlight) still land.
+    pub max_interval_secs: f32,
+}
+
+impl EnvironmentRefresh {
+    /// Re-render every frame.
+    pub const EVERY_FRAME: Self = Self {
+        sun_angle: 0.0,
+        up_angle: 0.0,
+        altitude: 0.0,
+        max_interval_secs: 0.0,
+    };
+}
+
+impl Default for EnvironmentRefresh {
+    fn default() -> Self {
+        Self {
+            // A quarter degree is a minute of simulated time and below what
+            // the eye picks out in a reflection.
+            sun_angle: 0.25_f32.to_radians(),
+            up_angle: 0.25_f32.to_radians(),
+            altitude: 25.0,
+            max_interval_secs: 2.0,
+        }
+    }
+}
+
+/// Render-world record of a probe's last cubemap refresh.
+#[derive(Component, Default)]
+pub(crate) struct EnvironmentRefreshState {
+    /// Sun direction, local up, camera radius, and time at the last refresh.
+    last: Option<RefreshSample>,
+    /// Whether the environment node re-renders the cubemap this frame.
+    due: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RefreshSample {
+    sun: Option<Vec3>,
+    up: Vec3,
+    radius: f32,
+    time: f32,
+}
+
+impl RefreshSample {
+    /// Whether the sky at `self` differs enough from `last` to re-render.
+    fn differs_from(&self, last: &Self, refresh: &EnvironmentRefresh) -> bool {
+        let angle = |a: Vec3, b: Vec3| a.dot(b).clamp(-1.0, 1.0).acos();
+        let sun_moved = match (self.sun, last.sun) {
+            (Some(sun), Some(last_sun)) => angle(sun, last_sun) >= refresh.sun_angle,
+            (None, None) => false,
+            _ => true,
+        };
+        sun_moved
+            || angle(self.up, last.up) >= refresh.up_angle
+            || (self.radius - last.radius).abs() >= refresh.altitude
+            || self.time - last.time >= refresh.max_interval_secs
+    }
+}
+
 /// Render-world component holding the cubemap target for the atmosphere probe.
 ///
 /// Created on the main world by [`prepare_atmosphere_probe_components`] for
@@ -244,7 +324,13 @@ pub(crate) fn prepare_probe_textures(
             dimension: Some(TextureViewDimension::D2Array),
             ..Default::default()
         });
-        if let Some(view_textures) = view_textures.iter().next() {
+        // The probe lives on a camera, so prefer that view's own LUTs; with
+        // several atmosphere views the first one's may be a different eye.
+        if let Some(view_textures) = view_textures
+            .get(probe)
+            .ok()
+            .or_else(|| view_textures.iter().next())
+        {
             commands.entity(probe).insert(AtmosphereProbeTextures {
                 environment: environment_view,
                 transmittance_lut: view_textures.transmittance_lut.clone(),
@@ -312,8 +398,60 @@ pub(crate) fn prepare_atmosphere_probe_bind_groups(
     }
 }
 
+/// Decide which probes re-render their cubemap this frame.
+///
+/// A probe only counts as refreshed once its textures and the compute
+/// pipeline are ready, so the first real render isn't skipped because the
+/// frames before it had nothing to draw into.
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_environment_refresh(
+    mut probes: Query<
+        (
+            Entity,
+            &SphericalAtmosphereEnvironmentMapLight,
+            Option<&mut EnvironmentRefreshState>,
+            Has<AtmosphereProbeTextures>,
+        ),
+        With<AtmosphereEnvironmentMap>,
+    >,
+    frame_data: Res<AtmosphereFrameData>,
+    pipeline_cache: Res<PipelineCache>,
+    pipelines: Res<AtmosphereProbePipeline>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let ready = pipeline_cache
+        .get_compute_pipeline(pipelines.environment)
+        .is_some();
+    let sun = frame_data.sun_direction();
+    for (entity, env_map_light, state, has_textures) in &mut probes {
+        let Some(view) = frame_data.view(entity) else {
+            continue;
+        };
+        let sample = RefreshSample {
+            sun,
+            up: view.local_up,
+            radius: view.camera_radius,
+            time: time.elapsed_secs(),
+        };
+        let Some(mut state) = state else {
+            commands
+                .entity(entity)
+                .insert(EnvironmentRefreshState::default());
+            continue;
+        };
+        state.due = state
+            .last
+            .is_none_or(|last| sample.differs_from(&last, &env_map_light.refresh));
+        if state.due && ready && has_textures {
+            state.last = Some(sample);
+        }
+    }
+}
+
 /// Render-graph node that dispatches the environment-map compute shader for
-/// every view that has both an atmosphere and a probe attached.
+/// every view that has both an atmosphere and a probe attached, on frames its
+/// [`EnvironmentRefresh`] calls for one.
 #[derive(Default)]
 pub(crate) struct EnvironmentNode;
 
@@ -326,6 +464,7 @@ impl ViewNode for EnvironmentNode {
         Read<AtmosphereTransformsOffset>,
         Read<ViewUniformOffset>,
         Read<ViewLightsUniformOffset>,
+        Option<Read<EnvironmentRefreshState>>,
     );
 
     fn run(
@@ -340,9 +479,14 @@ impl ViewNode for EnvironmentNode {
             atmosphere_transforms_offset,
             view_uniforms_offset,
             lights_uniforms_offset,
+            refresh_state,
         ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        if refresh_state.is_some_and(|state| !state.due) {
+            return Ok(());
+        }
+
         let pipeline_cache = world.resource::<PipelineCache>();
         let pipelines = world.resource::<AtmosphereProbePipeline>();
 
@@ -394,3 +538,39 @@ fn validate_environment_map_size(size: UVec2) -> UVec2 {
     }
     new_size
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(sun_degrees: f32, time: f32) -> RefreshSample {
+        let (sin, cos) = sun_degrees.to_radians().sin_cos();
+        RefreshSample {
+            sun: Some(Vec3::new(cos, sin, 0.0)),
+            up: Vec3::Y,
+            radius: 6.371e6,
+            time,
+        }
+    }
+
+    #[test]
+    fn refreshes_when_the_sun_moves() {
+        let refresh = EnvironmentRefresh::default();
+        let last = sample(10.0, 0.0);
+        assert!(!sample(10.1, 0.1).differs_from(&last, &refresh));
+        assert!(sample(10.5, 0.1).differs_from(&last, &refresh));
+    }
+
+    #[test]
+    fn refreshes_after_the_interval() {
+        let refresh = EnvironmentRefresh::default();
+        let last = sample(10.0, 0.0);
+        assert!(sample(10.0, refresh.max_interval_secs).differs_from(&last, &refresh));
+    }
+
+    #[test]
+    fn every_frame_always_refreshes() {
+        let last = sample(10.0, 0.0);
+        assert!(sample(10.0, 0.0).differs_from(&last, &EnvironmentRefresh::EVERY_FRAME));
+    }
+}
@@ -21,15 +21,15 @@ mod resources;
 mod sun_transmittance;
 
 use bevy::{
-    app::{App, Plugin, Update},
-    asset::{Handle, embedded_asset},
+    app::{App, Plugin, SubApp, Update},
+    asset::Handle,
     ecs::{
         component::Component,
         query::{Changed, QueryItem, With},
         schedule::IntoScheduleConfigs,
         system::{Query, lifetimeless::Read},
     },
-    math::{UVec2, UVec3, Vec3},
+    math::{Quat, UVec2, UVec3, Vec3},
     pbr::ScatteringMedium,
     reflect::{Reflect, std_traits::ReflectDefault},
     render::{
@@ -42,7 +42,6 @@ use bevy::{
         renderer::RenderAdapter,
         view::Hdr,
     },
-    shader::load_shader_library,
 };
 use tracing::warn;
 
@@ -53,23 +52,29 @@ use bevy::{
     prelude::Camera3d,
 };
 
-pub use environment::{AtmosphereEnvironmentMap, SphericalAtmosphereEnvironmentMapLight};
+#[cfg(feature = "shader_hot_reload")]
+pub use embedded::AtmosphereShaderSourcePlugin;
+pub use environment::{
+    AtmosphereEnvironmentMap, EnvironmentRefresh, SphericalAtmosphereEnvironmentMapLight,
+};
 pub use resources::{
-    AtmosphereLightsBuffer, AtmosphereTextures, AtmosphereTransform, AtmosphereTransforms,
-    AtmosphereTransformsOffset, ExtractedAtmosphereLights, GpuAtmosphere, GpuAtmosphereLight,
-    GpuAtmosphereLights, MAX_ATMOSPHERE_LIGHTS, RenderSkyBindGroupLayouts,
+    AtmosphereFrameData, AtmosphereLightsBuffer, AtmosphereTextures, AtmosphereTransform,
+    AtmosphereTransforms, AtmosphereTransformsOffset, AtmosphereViewData,
+    ExtractedAtmosphereLights, GpuAtmosphere, GpuAtmosphereLight, GpuAtmosphereLights,
+    MAX_ATMOSPHERE_LIGHTS, RenderSkyBindGroupLayouts,
 };
 pub use sun_transmittance::compute_sun_transmittance;
 
 use environment::{
     EnvironmentNode, init_atmosphere_probe_layout, init_atmosphere_probe_pipeline,
     prepare_atmosphere_probe_bind_groups, prepare_atmosphere_probe_components,
-    prepare_probe_textures,
+    prepare_probe_textures, update_environment_refresh,
 };
 pub use node::AtmosphereNode;
-use node::{AtmosphereLutsNode, RenderSkyNode};
+use node::{AtmosphereLutsFragmentNode, AtmosphereLutsNode, RenderSkyNode};
 use resources::{
-    AtmosphereBindGroupLayouts, AtmosphereLutPipelines, AtmosphereSampler,
+    AtmosphereBindGroupLayouts, AtmosphereLutFragmentLayouts, AtmosphereLutFragmentPipelines,
+    AtmosphereLutPath, AtmosphereLutPipelines, AtmosphereSampler, REDUCED_SKY_VIEW_LUT_FORMAT,
     prepare_atmosphere_bind_groups, prepare_atmosphere_lights_buffer, prepare_atmosphere_textures,
     prepare_atmosphere_transforms, prepare_atmosphere_uniforms, queue_render_sky_pipelines,
 };
@@ -79,17 +84,7 @@ pub struct SphericalAtmospherePlugin;
 
 impl Plugin for SphericalAtmospherePlugin {
     fn build(&self, app: &mut App) {
-        load_shader_library!(app, "shaders/types.wgsl");
-        load_shader_library!(app, "shaders/functions.wgsl");
-        load_shader_library!(app, "shaders/bruneton_functions.wgsl");
-        load_shader_library!(app, "shaders/bindings.wgsl");
-
-        embedded_asset!(app, "shaders/transmittance_lut.wgsl");
-        embedded_asset!(app, "shaders/multiscattering_lut.wgsl");
-        embedded_asset!(app, "shaders/sky_view_lut.wgsl");
-        embedded_asset!(app, "shaders/aerial_view_lut.wgsl");
-        embedded_asset!(app, "shaders/render_sky.wgsl");
-        embedded_asset!(app, "shaders/environment.wgsl");
+        embedded::register_shaders(app);
 
         app.add_plugins((
             ExtractComponentPlugin::<SphericalAtmosphere>::default(),
@@ -116,60 +111,56 @@ impl Plugin for SphericalAtmospherePlugin {
 
         let render_adapter = render_app.world().resource::<RenderAdapter>();
 
-        if !render_adapter
+        let compute_shaders = render_adapter
             .get_downlevel_capabilities()
             .flags
-            .contains(DownlevelFlags::COMPUTE_SHADERS)
-        {
-            warn!("SphericalAtmospherePlugin not loaded. GPU lacks support for compute shaders.");
-            return;
-        }
-
-        if !render_adapter
+            .contains(DownlevelFlags::COMPUTE_SHADERS);
+        let lut_usages = render_adapter
             .get_texture_format_features(TextureFormat::Rgba16Float)
-            .allowed_usages
-            .contains(TextureUsages::STORAGE_BINDING)
-        {
+            .allowed_usages;
+        let lut_path = if compute_shaders && lut_usages.contains(TextureUsages::STORAGE_BINDING) {
+            AtmosphereLutPath::Compute
+        } else if lut_usages.contains(TextureUsages::RENDER_ATTACHMENT) {
             warn!(
-                "SphericalAtmospherePlugin not loaded. GPU lacks support: TextureFormat::Rgba16Float does not support TextureUsages::STORAGE_BINDING."
+                "SphericalAtmospherePlugin: GPU lacks compute shaders or Rgba16Float storage \
+                 textures. Falling back to fragment-shader LUTs: an approximate sky without \
+                 multiple scattering or the sky environment map."
+            );
+            AtmosphereLutPath::Fragment
+        } else {
+            warn!(
+                "SphericalAtmospherePlugin not loaded. GPU can neither compute nor render to \
+                 TextureFormat::Rgba16Float LUTs."
             );
             return;
-        }
+        };
+        // Reduced-precision sky-view LUTs are written as storage textures,
+        // which few adapters allow for Rg11b10Ufloat.
+        let reduced_sky_view = render_adapter
+            .get_texture_format_features(REDUCED_SKY_VIEW_LUT_FORMAT)
+            .allowed_usages
+            .contains(TextureUsages::STORAGE_BINDING);
 
         render_app
-            .insert_resource(AtmosphereBindGroupLayouts::new())
+            .insert_resource(lut_path)
             .init_resource::<RenderSkyBindGroupLayouts>()
             .init_resource::<AtmosphereSampler>()
-            .init_resource::<AtmosphereLutPipelines>()
             .init_resource::<AtmosphereTransforms>()
             .init_resource::<AtmosphereLightsBuffer>()
             .init_resource::<ExtractedAtmosphereLights>()
+            .init_resource::<AtmosphereFrameData>()
             .init_resource::<SpecializedRenderPipelines<RenderSkyBindGroupLayouts>>()
-            .add_systems(
-                RenderStartup,
-                (
-                    resources::init_atmosphere_buffer,
-                    init_atmosphere_probe_layout,
-                    init_atmosphere_probe_pipeline,
-                )
-                    .chain(),
-            )
             .add_systems(
                 Render,
                 (
                     configure_camera_depth_usages.in_set(RenderSystems::ManageViews),
                     queue_render_sky_pipelines.in_set(RenderSystems::Queue),
                     prepare_atmosphere_textures.in_set(RenderSystems::PrepareResources),
-                    prepare_probe_textures
-                        .in_set(RenderSystems::PrepareResources)
-                        .after(prepare_atmosphere_textures),
                     prepare_atmosphere_uniforms
                         .before(RenderSystems::PrepareResources)
                         .after(RenderSystems::PrepareAssets),
                     prepare_atmosphere_transforms.in_set(RenderSystems::PrepareResources),
                     prepare_atmosphere_bind_groups.in_set(RenderSystems::PrepareBindGroups),
-                    prepare_atmosphere_probe_bind_groups.in_set(RenderSystems::PrepareBindGroups),
-                    resources::write_atmosphere_buffer.in_set(RenderSystems::PrepareResources),
                     // Must run before bind groups are made so the uniform
                     // is available for binding.
                     prepare_atmosphere_lights_buffer
@@ -177,32 +168,6 @@ impl Plugin for SphericalAtmospherePlugin {
                         .before(prepare_atmosphere_bind_groups),
                 ),
             )
-            .add_render_graph_node::<ViewNodeRunner<AtmosphereLutsNode>>(
-                Core3d,
-                AtmosphereNode::RenderLuts,
-            )
-            .add_render_graph_node::<ViewNodeRunner<EnvironmentNode>>(
-                Core3d,
-                AtmosphereNode::Environment,
-            )
-            .add_render_graph_edges(
-                Core3d,
-                (
-                    // END_PRE_PASSES -> RENDER_LUTS -> ENVIRONMENT -> MAIN_PASS
-                    Node3d::EndPrepasses,
-                    AtmosphereNode::RenderLuts,
-                    AtmosphereNode::Environment,
-                    Node3d::StartMainPass,
-                ),
-            )
-            // Ensure the IBL prefilter reads the cubemap *after* it is
-            // written. Without this, Bevy's Downsampling/Filtering graph
-            // can run before AtmosphereNode::Environment, producing zero
-            // irradiance for the first/every frame.
-            .add_render_graph_edges(
-                Core3d,
-                (AtmosphereNode::Environment, GeneratorNode::Downsampling),
-            )
             .add_render_graph_node::<ViewNodeRunner<RenderSkyNode>>(
                 Core3d,
                 AtmosphereNode::RenderSky,
@@ -215,9 +180,88 @@ impl Plugin for SphericalAtmospherePlugin {
                     Node3d::MainTransparentPass,
                 ),
             );
+
+        match lut_path {
+            AtmosphereLutPath::Compute => build_compute_luts(render_app, reduced_sky_view),
+            AtmosphereLutPath::Fragment => build_fragment_luts(render_app),
+        }
     }
 }
 
+/// LUTs from compute passes, plus the sky environment map (itself a compute
+/// pass, fed by the same LUTs).
+fn build_compute_luts(render_app: &mut SubApp, reduced_sky_view: bool) {
+    render_app
+        .insert_resource(AtmosphereBindGroupLayouts::new(reduced_sky_view))
+        .init_resource::<AtmosphereLutPipelines>()
+        .add_systems(
+            RenderStartup,
+            (
+                resources::init_atmosphere_buffer,
+                init_atmosphere_probe_layout,
+                init_atmosphere_probe_pipeline,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Render,
+            (
+                prepare_probe_textures
+                    .in_set(RenderSystems::PrepareResources)
+                    .after(prepare_atmosphere_textures),
+                prepare_atmosphere_probe_bind_groups.in_set(RenderSystems::PrepareBindGroups),
+                update_environment_refresh.in_set(RenderSystems::PrepareBindGroups),
+                resources::write_atmosphere_buffer.in_set(RenderSystems::PrepareResources),
+            ),
+        )
+        .add_render_graph_node::<ViewNodeRunner<AtmosphereLutsNode>>(
+            Core3d,
+            AtmosphereNode::RenderLuts,
+        )
+        .add_render_graph_node::<ViewNodeRunner<EnvironmentNode>>(
+            Core3d,
+            AtmosphereNode::Environment,
+        )
+        .add_render_graph_edges(
+            Core3d,
+            (
+                // END_PRE_PASSES -> RENDER_LUTS -> ENVIRONMENT -> MAIN_PASS
+                Node3d::EndPrepasses,
+                AtmosphereNode::RenderLuts,
+                AtmosphereNode::Environment,
+                Node3d::StartMainPass,
+            ),
+        )
+        // Ensure the IBL prefilter reads the cubemap *after* it is
+        // written. Without this, Bevy's Downsampling/Filtering graph
+        // can run before AtmosphereNode::Environment, producing zero
+        // irradiance for the first/every frame.
+        .add_render_graph_edges(
+            Core3d,
+            (AtmosphereNode::Environment, GeneratorNode::Downsampling),
+        );
+}
+
+/// The downlevel fallback: transmittance and sky-view LUTs from fullscreen
+/// fragment passes, and no environment map.
+fn build_fragment_luts(render_app: &mut SubApp) {
+    render_app
+        .insert_resource(AtmosphereLutFragmentLayouts::new())
+        .init_resource::<AtmosphereLutFragmentPipelines>()
+        .add_render_graph_node::<ViewNodeRunner<AtmosphereLutsFragmentNode>>(
+            Core3d,
+            AtmosphereNode::RenderLuts,
+        )
+        .add_render_graph_edges(
+            Core3d,
+            (
+                Node3d::EndPrepasses,
+                AtmosphereNode::RenderLuts,
+                Node3d::StartMainPass,
+            ),
+        );
+}
+
 /// Enables atmospheric scattering for a spherical planet.
 ///
 /// Add this component to an HDR camera along with [`SphericalAtmosphereCamera`] to enable
@@ -245,6 +289,38 @@ pub struct SphericalAtmosphere {
     /// A handle to a [`ScatteringMedium`], which describes the substance
     /// of the atmosphere and how it scatters light.
     pub medium: Handle<ScatteringMedium>,
+
+    /// Artistic multipliers on the physically-based in-scatter.
+    pub artistic: AtmosphereArtistic,
+}
+
+/// Non-physical overrides applied to the sky and aerial perspective when they
+/// are composited, on top of the LUTs, so changing them rebuilds nothing. The
+/// defaults leave the physically-based result untouched.
+#[derive(Clone, Copy, Reflect, PartialEq, Debug)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct AtmosphereArtistic {
+    /// Overall multiplier on the in-scattered light.
+    pub scattering_strength: f32,
+
+    /// Extra in-scatter towards the horizon, as a fraction of the in-scatter
+    /// there: 0 leaves it alone, 1 doubles it at the horizon line.
+    pub horizon_haze: f32,
+
+    /// Linear RGB tint multiplied into the in-scattered light.
+    pub sky_tint: Vec3,
+}
+
+impl Default for AtmosphereArtistic {
+    fn default() -> Self {
+        Self {
+            scattering_strength: 1.0,
+            horizon_haze: 0.0,
+            sky_tint: Vec3::ONE,
+        }
+    }
 }
 
 impl SphericalAtmosphere {
@@ -256,6 +332,7 @@ impl SphericalAtmosphere {
             top_radius: veldera_constants::ATMOSPHERE_TOP_RADIUS_M,
             ground_albedo: EARTH_ALBEDO,
             medium,
+            artistic: AtmosphereArtistic::default(),
         }
     }
 }
@@ -271,6 +348,7 @@ impl ExtractComponent for SphericalAtmosphere {
             top_radius: item.top_radius,
             ground_albedo: item.ground_albedo,
             medium: item.medium.id(),
+            artistic: item.artistic,
         })
     }
 }
@@ -282,6 +360,7 @@ pub struct ExtractedAtmosphere {
     pub top_radius: f32,
     pub ground_albedo: Vec3,
     pub medium: AssetId<ScatteringMedium>,
+    pub artistic: AtmosphereArtistic,
 }
 
 /// Camera component providing spherical planet position information.
@@ -315,6 +394,16 @@ pub struct SphericalAtmosphereCamera {
     ///
     /// For an ECEF position, this is `length(ecef_position)`.
     pub camera_radius: f32,
+
+    /// World-space rotation whose forward seeds the atmosphere frame, in place
+    /// of the view's own.
+    ///
+    /// The sky-view LUT is parametrized around the horizontal look direction,
+    /// so two views that each use their own (a stereo pair with canted
+    /// displays) would bake their LUTs in slightly different frames. Setting
+    /// both to the shared head rotation keeps the eyes' skies identical.
+    /// `None` uses the view's rotation.
+    pub reference_rotation: Option<Quat>,
 }
 
 impl SphericalAtmosphereCamera {
@@ -323,6 +412,7 @@ impl SphericalAtmosphereCamera {
         Self {
             local_up: ecef.normalize().as_vec3(),
             camera_radius: ecef.length() as f32,
+            reference_rotation: None,
         }
     }
 }
@@ -333,6 +423,7 @@ impl Default for SphericalAtmosphereCamera {
         Self {
             local_up: Vec3::Y,
             camera_radius: veldera_constants::EARTH_RADIUS_M,
+            reference_rotation: None,
         }
     }
 }
@@ -420,6 +511,10 @@ pub struct AtmosphereSettings {
     /// The rendering method to use for the atmosphere.
     pub rendering_method: AtmosphereMode,
 
+    /// Texture precision of the sky-view and aerial-view LUTs. Reduced
+    /// precision halves their bandwidth at some cost in banding.
+    pub lut_precision: LutPrecision,
+
     /// Where in each LUT ray-march segment the single density sample is taken
     /// (0 = segment start, 0.5 = middle, 1 = end). Lower biases toward the
     /// start, better approximating the exponential density falloff. Read by the
@@ -439,7 +534,8 @@ pub struct AtmosphereSettings {
     #[cfg_attr(feature = "serde", serde(default = "default_true"))]
     pub inscattering: bool,
 
-    /// Shadow single scattering where the planet occludes the sun. Enabled by
+    /// Shadow single scattering where the planet occludes the sun, softened
+    /// over the sun's disk (the Earth's shadow at twilight). Enabled by
     /// default.
     #[cfg_attr(feature = "serde", serde(default = "default_true"))]
     pub planet_shadow: bool,
@@ -472,9 +568,21 @@ pub struct AtmosphereSettings {
     #[cfg_attr(feature = "serde", serde(default = "default_true"))]
     pub light_extinction: bool,
 
+    /// Draw a procedural starfield behind the sky, seen through whatever the
+    /// atmosphere transmits (so only where the sky is dark). Compiled out of
+    /// the sky shader while off. Disabled by default.
+    pub stars: bool,
+
     /// Debug view: block the scene and show only the atmosphere in-scatter
     /// (aerial perspective) in isolation. Disabled by default.
     pub isolate_inscatter: bool,
+
+    /// Dither the sky: jitter the ray-march samples per pixel and add about
+    /// one 8-bit step of noise to the in-scatter, so smooth twilight
+    /// gradients don't band after tonemapping. Compiled out of the sky shader
+    /// while off. Enabled by default.
+    #[cfg_attr(feature = "serde", serde(default = "default_true"))]
+    pub dither: bool,
 }
 
 /// Default for the feature toggles, which are on unless the config turns them
@@ -503,13 +611,32 @@ pub struct GpuAtmosphereSettings {
     pub sky_max_samples: u32,
     pub rendering_method: u32,
     pub raymarch_midpoint_ratio: f32,
+    /// The aerial-view LUT stores `(ln(inscattering) - log_min) / log_range`
+    /// per channel, so the unorm [`LutPrecision::Reduced`] format can hold it.
+    /// Full precision stores the logarithm as is (0 and 1).
+    pub aerial_view_lut_log_min: f32,
+    pub aerial_view_lut_log_range: f32,
     /// Packed feature toggles read by the sky shaders. Bit 0 = isolate
-    /// in-scatter (debug); bits 1–6 = in-scatter, planet shadow, sun
-    /// transmittance, anisotropic phase, multiscattering, and environment-map
-    /// enables.
+    /// in-scatter (debug); bits 1–7 = in-scatter, planet shadow, sun
+    /// transmittance, anisotropic phase, multiscattering, environment-map and
+    /// starfield enables; bit 8 = reduced LUT precision; bit 9 = dithering.
+    /// See the `FEAT_*` constants.
     pub feature_flags: u32,
 }
 
+// Bits of [`GpuAtmosphereSettings::feature_flags`]. Must match the `FEAT_*`
+// constants in `functions.wgsl`.
+pub(crate) const FEAT_ISOLATE_INSCATTER: u32 = 1 << 0;
+pub(crate) const FEAT_INSCATTERING: u32 = 1 << 1;
+const FEAT_PLANET_SHADOW: u32 = 1 << 2;
+const FEAT_SUN_TRANSMITTANCE: u32 = 1 << 3;
+const FEAT_PHASE: u32 = 1 << 4;
+const FEAT_MULTISCATTERING: u32 = 1 << 5;
+const FEAT_ENVIRONMENT: u32 = 1 << 6;
+pub(crate) const FEAT_STARS: u32 = 1 << 7;
+pub(crate) const FEAT_REDUCED_LUTS: u32 = 1 << 8;
+pub(crate) const FEAT_DITHER: u32 = 1 << 9;
+
 impl Default for GpuAtmosphereSettings {
     fn default() -> Self {
         AtmosphereSettings::default().into()
@@ -518,14 +645,28 @@ impl Default for GpuAtmosphereSettings {
 
 impl From<AtmosphereSettings> for GpuAtmosphereSettings {
     fn from(s: AtmosphereSettings) -> Self {
-        // Bit layout must match the `FEAT_*` constants in `functions.wgsl`.
-        let feature_flags = (s.isolate_inscatter as u32)
-            | ((s.inscattering as u32) << 1)
-            | ((s.planet_shadow as u32) << 2)
-            | ((s.sun_transmittance as u32) << 3)
-            | ((s.phase_function as u32) << 4)
-            | ((s.multiscattering as u32) << 5)
-            | ((s.environment_map as u32) << 6);
+        let feature_flags = [
+            (s.isolate_inscatter, FEAT_ISOLATE_INSCATTER),
+            (s.inscattering, FEAT_INSCATTERING),
+            (s.planet_shadow, FEAT_PLANET_SHADOW),
+            (s.sun_transmittance, FEAT_SUN_TRANSMITTANCE),
+            (s.phase_function, FEAT_PHASE),
+            (s.multiscattering, FEAT_MULTISCATTERING),
+            (s.environment_map, FEAT_ENVIRONMENT),
+            (s.stars, FEAT_STARS),
+            (s.lut_precision == LutPrecision::Reduced, FEAT_REDUCED_LUTS),
+            (s.dither, FEAT_DITHER),
+        ]
+        .into_iter()
+        .filter(|&(enabled, _)| enabled)
+        .fold(0, |flags, (_, bit)| flags | bit);
+        let (aerial_view_lut_log_min, aerial_view_lut_log_range) = match s.lut_precision {
+            LutPrecision::Full => (0.0, 1.0),
+            LutPrecision::Reduced => (
+                REDUCED_AERIAL_VIEW_LOG_MIN,
+                REDUCED_AERIAL_VIEW_LOG_MAX - REDUCED_AERIAL_VIEW_LOG_MIN,
+            ),
+        };
         Self {
             transmittance_lut_size: s.transmittance_lut_size,
             multiscattering_lut_size: s.multiscattering_lut_size,
@@ -541,6 +682,8 @@ impl From<AtmosphereSettings> for GpuAtmosphereSettings {
             sky_max_samples: s.sky_max_samples,
             rendering_method: s.rendering_method as u32,
             raymarch_midpoint_ratio: s.raymarch_midpoint_ratio,
+            aerial_view_lut_log_min,
+            aerial_view_lut_log_range,
             feature_flags,
         }
     }
@@ -564,6 +707,26 @@ fn configure_camera_depth_usages(
     }
 }
 
+/// Natural-log bounds of the in-scatter the reduced-precision aerial-view LUT
+/// can hold. The lower bound matches the shader's `1e-6` floor; the upper one
+/// leaves headroom over a noon sky. With 8 bits this is ~11% per step.
+const REDUCED_AERIAL_VIEW_LOG_MIN: f32 = -14.0;
+const REDUCED_AERIAL_VIEW_LOG_MAX: f32 = 14.0;
+
+/// Texture precision of the sky-view and aerial-view LUTs.
+#[derive(Clone, Copy, Default, Reflect, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LutPrecision {
+    /// `Rgba16Float` for both.
+    #[default]
+    Full,
+    /// For bandwidth-limited GPUs: `Rg11b10Ufloat` for the sky-view LUT
+    /// (where the adapter can write it; otherwise it stays at full precision)
+    /// and log-encoded `Rgba8Unorm` for the aerial-view LUT. Expect some
+    /// banding in the sky gradient and the aerial perspective.
+    Reduced,
+}
+
 /// Selects how the atmosphere is rendered.
 #[repr(u32)]
 #[derive(Clone, Default, Reflect, Copy)]
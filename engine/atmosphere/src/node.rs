@@ -12,7 +12,8 @@ use bevy::{
         extract_component::DynamicUniformIndex,
         render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode},
         render_resource::{
-            ComputePass, ComputePassDescriptor, PipelineCache, RenderPassDescriptor,
+            ComputePass, ComputePassDescriptor, Operations, PipelineCache,
+            RenderPassColorAttachment, RenderPassDescriptor,
         },
         renderer::RenderContext,
         view::{ViewTarget, ViewUniformOffset},
@@ -24,7 +25,8 @@ use bevy::camera::MainPassResolutionOverride;
 use crate::{
     GpuAtmosphereSettings,
     resources::{
-        AtmosphereBindGroups, AtmosphereLutPipelines, AtmosphereTransformsOffset, GpuAtmosphere,
+        AtmosphereBindGroups, AtmosphereLutBindGroups, AtmosphereLutFragmentPipelines,
+        AtmosphereLutPipelines, AtmosphereTextures, AtmosphereTransformsOffset, GpuAtmosphere,
         RenderSkyPipelineId,
     },
 };
@@ -65,8 +67,28 @@ impl ViewNode for AtmosphereLutsNode {
         ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        let AtmosphereLutBindGroups::Compute {
+            transmittance_lut,
+            multiscattering_lut,
+            sky_view_lut,
+            aerial_view_lut,
+            reduced_sky_view,
+            reduced_aerial_view,
+        } = &bind_groups.luts
+        else {
+            return Ok(());
+        };
         let pipelines = world.resource::<AtmosphereLutPipelines>();
         let pipeline_cache = world.resource::<PipelineCache>();
+        let sky_view_lut_id = match pipelines.sky_view_lut_reduced {
+            Some(id) if *reduced_sky_view => id,
+            _ => pipelines.sky_view_lut,
+        };
+        let aerial_view_lut_id = if *reduced_aerial_view {
+            pipelines.aerial_view_lut_reduced
+        } else {
+            pipelines.aerial_view_lut
+        };
         let (
             Some(transmittance_lut_pipeline),
             Some(multiscattering_lut_pipeline),
@@ -75,8 +97,8 @@ impl ViewNode for AtmosphereLutsNode {
         ) = (
             pipeline_cache.get_compute_pipeline(pipelines.transmittance_lut),
             pipeline_cache.get_compute_pipeline(pipelines.multiscattering_lut),
-            pipeline_cache.get_compute_pipeline(pipelines.sky_view_lut),
-            pipeline_cache.get_compute_pipeline(pipelines.aerial_view_lut),
+            pipeline_cache.get_compute_pipeline(sky_view_lut_id),
+            pipeline_cache.get_compute_pipeline(aerial_view_lut_id),
         )
         else {
             return Ok(());
@@ -86,11 +108,11 @@ impl ViewNode for AtmosphereLutsNode {
 
         let command_encoder = render_context.command_encoder();
 
-        let mut luts_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
-            label: Some("atmosphere_luts"),
-            timestamp_writes: None,
-        });
-        let pass_span = diagnostics.pass_span(&mut luts_pass, "atmosphere_luts");
+        // One compute pass per LUT, each with its own pass span nested under
+        // `atmosphere_luts`, so the profiler can attribute the LUT cost
+        // (`render/atmosphere_luts/<lut>/elapsed_gpu`). Pass spans can't nest
+        // inside one pass: each opens a pipeline-statistics query.
+        let luts_span = diagnostics.time_span(command_encoder, "atmosphere_luts");
 
         fn dispatch_2d(compute_pass: &mut ComputePass, size: UVec2) {
             const WORKGROUP_SIZE: u32 = 16;
@@ -100,68 +122,214 @@ impl ViewNode for AtmosphereLutsNode {
         }
 
         // Transmittance LUT.
-        luts_pass.set_pipeline(transmittance_lut_pipeline);
-        luts_pass.set_bind_group(
-            0,
-            &bind_groups.transmittance_lut,
-            &[
-                atmosphere_uniforms_offset.index(),
-                settings_uniforms_offset.index(),
-            ],
-        );
-
-        dispatch_2d(&mut luts_pass, settings.transmittance_lut_size);
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("transmittance_lut"),
+                timestamp_writes: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "transmittance_lut");
+            pass.set_pipeline(transmittance_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                transmittance_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                ],
+            );
+            dispatch_2d(&mut pass, settings.transmittance_lut_size);
+            pass_span.end(&mut pass);
+        }
 
         // Multiscattering LUT.
-        luts_pass.set_pipeline(multiscattering_lut_pipeline);
-        luts_pass.set_bind_group(
-            0,
-            &bind_groups.multiscattering_lut,
-            &[
-                atmosphere_uniforms_offset.index(),
-                settings_uniforms_offset.index(),
-            ],
-        );
-
-        luts_pass.dispatch_workgroups(
-            settings.multiscattering_lut_size.x,
-            settings.multiscattering_lut_size.y,
-            1,
-        );
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("multiscattering_lut"),
+                timestamp_writes: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "multiscattering_lut");
+            pass.set_pipeline(multiscattering_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                multiscattering_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                ],
+            );
+            pass.dispatch_workgroups(
+                settings.multiscattering_lut_size.x,
+                settings.multiscattering_lut_size.y,
+                1,
+            );
+            pass_span.end(&mut pass);
+        }
 
         // Sky View LUT.
-        luts_pass.set_pipeline(sky_view_lut_pipeline);
-        luts_pass.set_bind_group(
-            0,
-            &bind_groups.sky_view_lut,
-            &[
-                atmosphere_uniforms_offset.index(),
-                settings_uniforms_offset.index(),
-                atmosphere_transforms_offset.index(),
-                view_uniforms_offset.offset,
-                lights_uniforms_offset.offset,
-            ],
-        );
-
-        dispatch_2d(&mut luts_pass, settings.sky_view_lut_size);
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("sky_view_lut"),
+                timestamp_writes: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "sky_view_lut");
+            pass.set_pipeline(sky_view_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                sky_view_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                    atmosphere_transforms_offset.index(),
+                    view_uniforms_offset.offset,
+                    lights_uniforms_offset.offset,
+                ],
+            );
+            dispatch_2d(&mut pass, settings.sky_view_lut_size);
+            pass_span.end(&mut pass);
+        }
 
         // Aerial View LUT.
-        luts_pass.set_pipeline(aerial_view_lut_pipeline);
-        luts_pass.set_bind_group(
-            0,
-            &bind_groups.aerial_view_lut,
-            &[
-                atmosphere_uniforms_offset.index(),
-                settings_uniforms_offset.index(),
-                atmosphere_transforms_offset.index(),
-                view_uniforms_offset.offset,
-                lights_uniforms_offset.offset,
-            ],
-        );
+        {
+            let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("aerial_view_lut"),
+                timestamp_writes: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "aerial_view_lut");
+            pass.set_pipeline(aerial_view_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                aerial_view_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                    atmosphere_transforms_offset.index(),
+                    view_uniforms_offset.offset,
+                    lights_uniforms_offset.offset,
+                ],
+            );
+            dispatch_2d(&mut pass, settings.aerial_view_lut_size.xy());
+            pass_span.end(&mut pass);
+        }
 
-        dispatch_2d(&mut luts_pass, settings.aerial_view_lut_size.xy());
+        luts_span.end(command_encoder);
 
-        pass_span.end(&mut luts_pass);
+        Ok(())
+    }
+}
+
+/// The [`AtmosphereLutPath::Fragment`] fallback of [`AtmosphereLutsNode`]:
+/// renders the transmittance and sky-view LUTs with fullscreen draws.
+///
+/// [`AtmosphereLutPath::Fragment`]: crate::resources::AtmosphereLutPath::Fragment
+#[derive(Default)]
+pub(super) struct AtmosphereLutsFragmentNode {}
+
+impl ViewNode for AtmosphereLutsFragmentNode {
+    type ViewQuery = (
+        Read<AtmosphereTextures>,
+        Read<AtmosphereBindGroups>,
+        Read<DynamicUniformIndex<GpuAtmosphere>>,
+        Read<DynamicUniformIndex<GpuAtmosphereSettings>>,
+        Read<AtmosphereTransformsOffset>,
+        Read<ViewUniformOffset>,
+        Read<ViewLightsUniformOffset>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (
+            textures,
+            bind_groups,
+            atmosphere_uniforms_offset,
+            settings_uniforms_offset,
+            atmosphere_transforms_offset,
+            view_uniforms_offset,
+            lights_uniforms_offset,
+        ): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let AtmosphereLutBindGroups::Fragment {
+            transmittance_lut,
+            sky_view_lut,
+        } = &bind_groups.luts
+        else {
+            return Ok(());
+        };
+        let pipelines = world.resource::<AtmosphereLutFragmentPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (Some(transmittance_lut_pipeline), Some(sky_view_lut_pipeline)) = (
+            pipeline_cache.get_render_pipeline(pipelines.transmittance_lut),
+            pipeline_cache.get_render_pipeline(pipelines.sky_view_lut),
+        ) else {
+            return Ok(());
+        };
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let command_encoder = render_context.command_encoder();
+        let luts_span = diagnostics.time_span(command_encoder, "atmosphere_luts");
+
+        // Transmittance LUT. The sky-view pass samples it, so it goes first.
+        {
+            let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("transmittance_lut"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &textures.transmittance_lut.default_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "transmittance_lut");
+            pass.set_pipeline(transmittance_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                transmittance_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                ],
+            );
+            pass.draw(0..3, 0..1);
+            pass_span.end(&mut pass);
+        }
+
+        // Sky View LUT.
+        {
+            let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("sky_view_lut"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &textures.sky_view_lut.default_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            let pass_span = diagnostics.pass_span(&mut pass, "sky_view_lut");
+            pass.set_pipeline(sky_view_lut_pipeline);
+            pass.set_bind_group(
+                0,
+                sky_view_lut,
+                &[
+                    atmosphere_uniforms_offset.index(),
+                    settings_uniforms_offset.index(),
+                    atmosphere_transforms_offset.index(),
+                    view_uniforms_offset.offset,
+                    lights_uniforms_offset.offset,
+                ],
+            );
+            pass.draw(0..3, 0..1);
+            pass_span.end(&mut pass);
+        }
+
+        luts_span.end(command_encoder);
 
         Ok(())
     }
@@ -204,10 +372,19 @@ impl ViewNode for RenderSkyNode {
     ) -> Result<(), NodeRunError> {
         let pipeline_cache = world.resource::<PipelineCache>();
         let Some(render_sky_pipeline) =
-            pipeline_cache.get_render_pipeline(render_sky_pipeline_id.0)
+            pipeline_cache.get_render_pipeline(render_sky_pipeline_id.main)
         else {
             return Ok(());
         };
+        // Without dual-source blending the in-scatter is added by a second
+        // draw; skip the pass until both halves are ready.
+        let inscattering_pipeline = match render_sky_pipeline_id.inscattering {
+            Some(id) => match pipeline_cache.get_render_pipeline(id) {
+                Some(pipeline) => Some(pipeline),
+                None => return Ok(()),
+            },
+            None => None,
+        };
 
         let diagnostics = render_context.diagnostic_recorder();
 
@@ -239,6 +416,10 @@ impl ViewNode for RenderSkyNode {
             ],
         );
         render_sky_pass.draw(0..3, 0..1);
+        if let Some(pipeline) = inscattering_pipeline {
+            render_sky_pass.set_render_pipeline(pipeline);
+            render_sky_pass.draw(0..3, 0..1);
+        }
 
         pass_span.end(&mut render_sky_pass);
 
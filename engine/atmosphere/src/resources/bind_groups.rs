@@ -24,22 +24,41 @@ use crate::{ExtractedAtmosphere, GpuAtmosphereSettings};
 
 use super::{
     gpu_types::GpuAtmosphere,
-    layouts::{AtmosphereBindGroupLayouts, RenderSkyBindGroupLayouts},
+    layouts::{
+        AtmosphereBindGroupLayouts, AtmosphereLutFragmentLayouts, RenderSkyBindGroupLayouts,
+    },
     lights::AtmosphereLightsBuffer,
     sampler::AtmosphereSampler,
-    textures::AtmosphereTextures,
+    textures::{AtmosphereTextures, REDUCED_AERIAL_VIEW_LUT_FORMAT, REDUCED_SKY_VIEW_LUT_FORMAT},
     transforms::AtmosphereTransforms,
 };
 
 #[derive(Component)]
 pub(crate) struct AtmosphereBindGroups {
-    pub transmittance_lut: BindGroup,
-    pub multiscattering_lut: BindGroup,
-    pub sky_view_lut: BindGroup,
-    pub aerial_view_lut: BindGroup,
+    pub luts: AtmosphereLutBindGroups,
     pub render_sky: BindGroup,
 }
 
+/// Bind groups of the LUT passes, per [`AtmosphereLutPath`].
+///
+/// [`AtmosphereLutPath`]: super::pipelines::AtmosphereLutPath
+pub(crate) enum AtmosphereLutBindGroups {
+    Compute {
+        transmittance_lut: BindGroup,
+        multiscattering_lut: BindGroup,
+        sky_view_lut: BindGroup,
+        aerial_view_lut: BindGroup,
+        /// The sky-view and aerial-view LUTs are at reduced precision, so
+        /// need the matching pipelines.
+        reduced_sky_view: bool,
+        reduced_aerial_view: bool,
+    },
+    Fragment {
+        transmittance_lut: BindGroup,
+        sky_view_lut: BindGroup,
+    },
+}
+
 #[derive(Copy, Clone, Debug)]
 struct ScatteringMediumMissingError(AssetId<ScatteringMedium>);
 
@@ -123,7 +142,9 @@ pub fn prepare_atmosphere_bind_groups(
         (With<Camera3d>, With<ExtractedAtmosphere>),
     >,
     render_device: Res<RenderDevice>,
-    layouts: Res<AtmosphereBindGroupLayouts>,
+    // Exactly one of these exists, depending on the `AtmosphereLutPath`.
+    layouts: Option<Res<AtmosphereBindGroupLayouts>>,
+    fragment_layouts: Option<Res<AtmosphereLutFragmentLayouts>>,
     render_sky_layouts: Res<RenderSkyBindGroupLayouts>,
     atmosphere_sampler: Res<AtmosphereSampler>,
     view_uniforms: Res<ViewUniforms>,
@@ -174,88 +195,157 @@ pub fn prepare_atmosphere_bind_groups(
             .get(atmosphere.medium)
             .ok_or(ScatteringMediumMissingError(atmosphere.medium))?;
 
-        let transmittance_lut = render_device.create_bind_group(
-            "transmittance_lut_bind_group",
-            &pipeline_cache.get_bind_group_layout(&layouts.transmittance_lut),
-            &BindGroupEntries::with_indices((
-                // Uniforms.
-                (0, atmosphere_binding.clone()),
-                (1, settings_binding.clone()),
-                // Scattering medium LUTs and sampler.
-                (5, &gpu_medium.density_lut_view),
-                (6, &gpu_medium.scattering_lut_view),
-                (7, medium_sampler.sampler()),
-                // Transmittance LUT storage texture.
-                (13, &textures.transmittance_lut.default_view),
-            )),
-        );
+        let luts = if let Some(layouts) = &layouts {
+            // Reduced-precision LUTs need the layouts matching their formats.
+            let reduced_sky_view =
+                textures.sky_view_lut.texture.format() == REDUCED_SKY_VIEW_LUT_FORMAT;
+            let reduced_aerial_view =
+                textures.aerial_view_lut.texture.format() == REDUCED_AERIAL_VIEW_LUT_FORMAT;
+            let sky_view_layout = match &layouts.sky_view_lut_reduced {
+                Some(layout) if reduced_sky_view => layout,
+                _ => &layouts.sky_view_lut,
+            };
+            let aerial_view_layout = if reduced_aerial_view {
+                &layouts.aerial_view_lut_reduced
+            } else {
+                &layouts.aerial_view_lut
+            };
 
-        let multiscattering_lut = render_device.create_bind_group(
-            "multiscattering_lut_bind_group",
-            &pipeline_cache.get_bind_group_layout(&layouts.multiscattering_lut),
-            &BindGroupEntries::with_indices((
-                // Uniforms.
-                (0, atmosphere_binding.clone()),
-                (1, settings_binding.clone()),
-                // Scattering medium LUTs and sampler.
-                (5, &gpu_medium.density_lut_view),
-                (6, &gpu_medium.scattering_lut_view),
-                (7, medium_sampler.sampler()),
-                // Atmosphere LUTs and sampler.
-                (8, &textures.transmittance_lut.default_view),
-                (12, &**atmosphere_sampler),
-                // Multiscattering LUT storage texture.
-                (13, &textures.multiscattering_lut.default_view),
-            )),
-        );
+            let transmittance_lut = render_device.create_bind_group(
+                "transmittance_lut_bind_group",
+                &pipeline_cache.get_bind_group_layout(&layouts.transmittance_lut),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                    // Transmittance LUT storage texture.
+                    (13, &textures.transmittance_lut.default_view),
+                )),
+            );
 
-        let sky_view_lut = render_device.create_bind_group(
-            "sky_view_lut_bind_group",
-            &pipeline_cache.get_bind_group_layout(&layouts.sky_view_lut),
-            &BindGroupEntries::with_indices((
-                // Uniforms.
-                (0, atmosphere_binding.clone()),
-                (1, settings_binding.clone()),
-                (2, transforms_binding.clone()),
-                (3, view_binding.clone()),
-                (4, lights_binding.clone()),
-                // Scattering medium LUTs and sampler.
-                (5, &gpu_medium.density_lut_view),
-                (6, &gpu_medium.scattering_lut_view),
-                (7, medium_sampler.sampler()),
-                // Atmosphere LUTs and sampler.
-                (8, &textures.transmittance_lut.default_view),
-                (9, &textures.multiscattering_lut.default_view),
-                (12, &**atmosphere_sampler),
-                (14, atmosphere_lights_binding.clone()),
-                // Sky view LUT storage texture.
-                (13, &textures.sky_view_lut.default_view),
-            )),
-        );
+            let multiscattering_lut = render_device.create_bind_group(
+                "multiscattering_lut_bind_group",
+                &pipeline_cache.get_bind_group_layout(&layouts.multiscattering_lut),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                    // Atmosphere LUTs and sampler.
+                    (8, &textures.transmittance_lut.default_view),
+                    (12, &**atmosphere_sampler),
+                    // Multiscattering LUT storage texture.
+                    (13, &textures.multiscattering_lut.default_view),
+                )),
+            );
 
-        let aerial_view_lut = render_device.create_bind_group(
-            "aerial_view_lut_bind_group",
-            &pipeline_cache.get_bind_group_layout(&layouts.aerial_view_lut),
-            &BindGroupEntries::with_indices((
-                // Uniforms.
-                (0, atmosphere_binding.clone()),
-                (1, settings_binding.clone()),
-                (2, transforms_binding.clone()),
-                (3, view_binding.clone()),
-                (4, lights_binding.clone()),
-                // Scattering medium LUTs and sampler.
-                (5, &gpu_medium.density_lut_view),
-                (6, &gpu_medium.scattering_lut_view),
-                (7, medium_sampler.sampler()),
-                // Atmosphere LUTs and sampler.
-                (8, &textures.transmittance_lut.default_view),
-                (9, &textures.multiscattering_lut.default_view),
-                (12, &**atmosphere_sampler),
-                (14, atmosphere_lights_binding.clone()),
-                // Aerial view LUT storage texture.
-                (13, &textures.aerial_view_lut.default_view),
-            )),
-        );
+            let sky_view_lut = render_device.create_bind_group(
+                "sky_view_lut_bind_group",
+                &pipeline_cache.get_bind_group_layout(sky_view_layout),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    (2, transforms_binding.clone()),
+                    (3, view_binding.clone()),
+                    (4, lights_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                    // Atmosphere LUTs and sampler.
+                    (8, &textures.transmittance_lut.default_view),
+                    (9, &textures.multiscattering_lut.default_view),
+                    (12, &**atmosphere_sampler),
+                    (14, atmosphere_lights_binding.clone()),
+                    // Sky view LUT storage texture.
+                    (13, &textures.sky_view_lut.default_view),
+                )),
+            );
+
+            let aerial_view_lut = render_device.create_bind_group(
+                "aerial_view_lut_bind_group",
+                &pipeline_cache.get_bind_group_layout(aerial_view_layout),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    (2, transforms_binding.clone()),
+                    (3, view_binding.clone()),
+                    (4, lights_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                    // Atmosphere LUTs and sampler.
+                    (8, &textures.transmittance_lut.default_view),
+                    (9, &textures.multiscattering_lut.default_view),
+                    (12, &**atmosphere_sampler),
+                    (14, atmosphere_lights_binding.clone()),
+                    // Aerial view LUT storage texture.
+                    (13, &textures.aerial_view_lut.default_view),
+                )),
+            );
+
+            AtmosphereLutBindGroups::Compute {
+                transmittance_lut,
+                multiscattering_lut,
+                sky_view_lut,
+                aerial_view_lut,
+                reduced_sky_view,
+                reduced_aerial_view,
+            }
+        } else if let Some(layouts) = &fragment_layouts {
+            let transmittance_lut = render_device.create_bind_group(
+                "transmittance_lut_fragment_bind_group",
+                &pipeline_cache.get_bind_group_layout(&layouts.transmittance_lut),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                )),
+            );
+
+            let sky_view_lut = render_device.create_bind_group(
+                "sky_view_lut_fragment_bind_group",
+                &pipeline_cache.get_bind_group_layout(sky_view_layout),
+                &BindGroupEntries::with_indices((
+                    // Uniforms.
+                    (0, atmosphere_binding.clone()),
+                    (1, settings_binding.clone()),
+                    (2, transforms_binding.clone()),
+                    (3, view_binding.clone()),
+                    (4, lights_binding.clone()),
+                    // Scattering medium LUTs and sampler.
+                    (5, &gpu_medium.density_lut_view),
+                    (6, &gpu_medium.scattering_lut_view),
+                    (7, medium_sampler.sampler()),
+                    // Atmosphere LUTs and sampler.
+                    (8, &textures.transmittance_lut.default_view),
+                    (9, &textures.multiscattering_lut.default_view),
+                    (12, &**atmosphere_sampler),
+                    (14, atmosphere_lights_binding.clone()),
+                )),
+            );
+
+            AtmosphereLutBindGroups::Fragment {
+                transmittance_lut,
+                sky_view_lut,
+            }
+        } else {
+            return Ok(());
+        };
 
         let render_sky = render_device.create_bind_group(
             "render_sky_bind_group",
@@ -287,13 +377,9 @@ pub fn prepare_atmosphere_bind_groups(
             )),
         );
 
-        commands.entity(entity).insert(AtmosphereBindGroups {
-            transmittance_lut,
-            multiscattering_lut,
-            sky_view_lut,
-            aerial_view_lut,
-            render_sky,
-        });
+        commands
+            .entity(entity)
+            .insert(AtmosphereBindGroups { luts, render_sky });
     }
 
     Ok(())
@@ -32,6 +32,9 @@ pub fn init_atmosphere_buffer(mut commands: Commands) {
                 ground_albedo: Vec3::ZERO,
                 bottom_radius: 0.0,
                 top_radius: 0.0,
+                sky_tint: Vec3::ONE,
+                scattering_strength: 1.0,
+                horizon_haze: 0.0,
             },
             settings: GpuAtmosphereSettings::default(),
         }),
@@ -49,7 +52,9 @@ pub(crate) fn write_atmosphere_buffer(
     atmosphere_entity: Query<(&GpuAtmosphere, &GpuAtmosphereSettings), With<Camera3d>>,
     mut atmosphere_buffer: ResMut<AtmosphereBuffer>,
 ) {
-    let Ok((atmosphere, settings)) = atmosphere_entity.single() else {
+    // Every view renders the same atmosphere (a stereo pair carries a copy per
+    // eye), so the first one speaks for all of them.
+    let Some((atmosphere, settings)) = atmosphere_entity.iter().next() else {
         return;
     };
 
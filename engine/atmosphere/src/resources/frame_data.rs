@@ -0,0 +1,73 @@
+//! Per-view atmosphere parameters published for other render passes.
+//!
+//! Render nodes outside this crate (spacecraft, orbit lines, custom planets)
+//! need the same sun direction and radii the sky was rendered with, or their
+//! lighting and horizon drift from the atmosphere's. [`AtmosphereFrameData`]
+//! is filled by the same system that writes the atmosphere transform
+//! uniform, so it always matches what `render_sky` reads this frame.
+
+use bevy::{
+    ecs::{entity::Entity, resource::Resource},
+    math::{Mat4, Vec3},
+    platform::collections::HashMap,
+};
+
+use super::gpu_types::GpuAtmosphereLight;
+
+/// Rec. 709 luminance weights, for picking the brightest light as the sun.
+const LUMINANCE: Vec3 = Vec3::new(0.2126, 0.7152, 0.0722);
+
+/// The atmosphere parameters one view was rendered with.
+#[derive(Clone, Copy, Debug)]
+pub struct AtmosphereViewData {
+    /// Orthonormal atmosphere basis (Y = `local_up`, Z = horizontal look
+    /// direction) translated to the view origin, in render-world space.
+    pub world_from_atmosphere: Mat4,
+    /// Normalized radial direction from the planet centre through the camera.
+    pub local_up: Vec3,
+    /// Distance from the planet centre to the camera (m).
+    pub camera_radius: f32,
+    /// Planet radius (m).
+    pub bottom_radius: f32,
+    /// Radius at which the atmosphere ends (m).
+    pub top_radius: f32,
+}
+
+/// Render-world resource: the atmosphere parameters of every atmosphere
+/// view this frame, plus the atmospheric lights they were lit by.
+///
+/// Written in [`RenderSystems::PrepareResources`]; read it from render graph
+/// nodes, or from systems ordered after that set.
+///
+/// [`RenderSystems::PrepareResources`]: bevy::render::RenderSystems::PrepareResources
+#[derive(Resource, Default)]
+pub struct AtmosphereFrameData {
+    pub(crate) views: HashMap<Entity, AtmosphereViewData>,
+    pub(crate) lights: Vec<GpuAtmosphereLight>,
+}
+
+impl AtmosphereFrameData {
+    /// Parameters for the view `entity`, if it renders an atmosphere.
+    pub fn view(&self, entity: Entity) -> Option<&AtmosphereViewData> {
+        self.views.get(&entity)
+    }
+
+    /// Every atmosphere view and its parameters.
+    pub fn views(&self) -> impl Iterator<Item = (Entity, &AtmosphereViewData)> {
+        self.views.iter().map(|(&entity, data)| (entity, data))
+    }
+
+    /// The atmospheric lights (sun, moon) as the sky shaders see them.
+    pub fn lights(&self) -> &[GpuAtmosphereLight] {
+        &self.lights
+    }
+
+    /// World-space unit vector toward the brightest atmospheric light, or
+    /// `None` if there are none.
+    pub fn sun_direction(&self) -> Option<Vec3> {
+        self.lights
+            .iter()
+            .max_by(|a, b| a.color.dot(LUMINANCE).total_cmp(&b.color.dot(LUMINANCE)))
+            .map(|light| light.direction_to_light)
+    }
+}
@@ -53,6 +53,11 @@ pub struct GpuAtmosphere {
     pub ground_albedo: Vec3,
     pub bottom_radius: f32,
     pub top_radius: f32,
+    /// [`AtmosphereArtistic`](crate::AtmosphereArtistic), applied in
+    /// `render_sky` only.
+    pub sky_tint: Vec3,
+    pub scattering_strength: f32,
+    pub horizon_haze: f32,
 }
 
 /// GPU uniform for atmosphere transforms including spherical planet support.
@@ -18,14 +18,32 @@ use bevy::{
 
 use crate::GpuAtmosphereSettings;
 
-use super::gpu_types::{AtmosphereTransform, GpuAtmosphere, GpuAtmosphereLights};
+use super::{
+    gpu_types::{AtmosphereTransform, GpuAtmosphere, GpuAtmosphereLights},
+    textures::{REDUCED_AERIAL_VIEW_LUT_FORMAT, REDUCED_SKY_VIEW_LUT_FORMAT},
+};
 
 #[derive(Resource)]
 pub(crate) struct AtmosphereBindGroupLayouts {
     pub transmittance_lut: BindGroupLayoutDescriptor,
     pub multiscattering_lut: BindGroupLayoutDescriptor,
     pub sky_view_lut: BindGroupLayoutDescriptor,
+    /// Writes a [`REDUCED_SKY_VIEW_LUT_FORMAT`] LUT; `None` where the adapter
+    /// can't.
+    pub sky_view_lut_reduced: Option<BindGroupLayoutDescriptor>,
     pub aerial_view_lut: BindGroupLayoutDescriptor,
+    /// Writes a [`REDUCED_AERIAL_VIEW_LUT_FORMAT`] LUT.
+    pub aerial_view_lut_reduced: BindGroupLayoutDescriptor,
+}
+
+/// Layouts for the [`AtmosphereLutPath::Fragment`] LUT passes: the compute
+/// layouts minus the storage-texture output, visible to the fragment stage.
+///
+/// [`AtmosphereLutPath::Fragment`]: super::pipelines::AtmosphereLutPath::Fragment
+#[derive(Resource)]
+pub(crate) struct AtmosphereLutFragmentLayouts {
+    pub transmittance_lut: BindGroupLayoutDescriptor,
+    pub sky_view_lut: BindGroupLayoutDescriptor,
 }
 
 #[derive(Resource)]
@@ -37,7 +55,9 @@ pub struct RenderSkyBindGroupLayouts {
 }
 
 impl AtmosphereBindGroupLayouts {
-    pub fn new() -> Self {
+    /// `reduced_sky_view`: whether the adapter can write
+    /// [`REDUCED_SKY_VIEW_LUT_FORMAT`] storage textures.
+    pub fn new(reduced_sky_view: bool) -> Self {
         let transmittance_lut = BindGroupLayoutDescriptor::new(
             "transmittance_lut_bind_group_layout",
             &BindGroupLayoutEntries::with_indices(
@@ -87,43 +107,121 @@ impl AtmosphereBindGroupLayouts {
             ),
         );
 
-        let sky_view_lut = BindGroupLayoutDescriptor::new(
-            "sky_view_lut_bind_group_layout",
+        let sky_view_lut =
+            sky_view_lut_layout("sky_view_lut_bind_group_layout", TextureFormat::Rgba16Float);
+        let sky_view_lut_reduced = reduced_sky_view.then(|| {
+            sky_view_lut_layout(
+                "sky_view_lut_reduced_bind_group_layout",
+                REDUCED_SKY_VIEW_LUT_FORMAT,
+            )
+        });
+        let aerial_view_lut = aerial_view_lut_layout(
+            "aerial_view_lut_bind_group_layout",
+            TextureFormat::Rgba16Float,
+        );
+        let aerial_view_lut_reduced = aerial_view_lut_layout(
+            "aerial_view_lut_reduced_bind_group_layout",
+            REDUCED_AERIAL_VIEW_LUT_FORMAT,
+        );
+
+        Self {
+            transmittance_lut,
+            multiscattering_lut,
+            sky_view_lut,
+            sky_view_lut_reduced,
+            aerial_view_lut,
+            aerial_view_lut_reduced,
+        }
+    }
+}
+
+/// Layout of the sky-view LUT compute pass, writing a `format` LUT.
+fn sky_view_lut_layout(label: &'static str, format: TextureFormat) -> BindGroupLayoutDescriptor {
+    BindGroupLayoutDescriptor::new(
+        label,
+        &BindGroupLayoutEntries::with_indices(
+            ShaderStages::COMPUTE,
+            (
+                (0, uniform_buffer::<GpuAtmosphere>(true)),
+                (1, uniform_buffer::<GpuAtmosphereSettings>(true)),
+                (2, uniform_buffer::<AtmosphereTransform>(true)),
+                (3, uniform_buffer::<ViewUniform>(true)),
+                (4, uniform_buffer::<GpuLights>(true)),
+                // Scattering medium LUTs and sampler.
+                (5, texture_2d(TextureSampleType::default())),
+                (6, texture_2d(TextureSampleType::default())),
+                (7, sampler(SamplerBindingType::Filtering)),
+                // Atmosphere LUTs and sampler.
+                (8, texture_2d(TextureSampleType::default())), // Transmittance.
+                (9, texture_2d(TextureSampleType::default())), // Multiscattering.
+                (12, sampler(SamplerBindingType::Filtering)),
+                // Per-light unattenuated emission (atmosphere uses this
+                // instead of the CPU-extinguished `lights` uniform).
+                (14, uniform_buffer::<GpuAtmosphereLights>(false)),
+                // Sky view LUT storage texture.
+                (
+                    13,
+                    texture_storage_2d(format, StorageTextureAccess::WriteOnly),
+                ),
+            ),
+        ),
+    )
+}
+
+/// Layout of the aerial-view LUT compute pass, writing a `format` LUT.
+fn aerial_view_lut_layout(label: &'static str, format: TextureFormat) -> BindGroupLayoutDescriptor {
+    BindGroupLayoutDescriptor::new(
+        label,
+        &BindGroupLayoutEntries::with_indices(
+            ShaderStages::COMPUTE,
+            (
+                (0, uniform_buffer::<GpuAtmosphere>(true)),
+                (1, uniform_buffer::<GpuAtmosphereSettings>(true)),
+                (2, uniform_buffer::<AtmosphereTransform>(true)),
+                (3, uniform_buffer::<ViewUniform>(true)),
+                (4, uniform_buffer::<GpuLights>(true)),
+                // Scattering medium LUTs and sampler.
+                (5, texture_2d(TextureSampleType::default())),
+                (6, texture_2d(TextureSampleType::default())),
+                (7, sampler(SamplerBindingType::Filtering)),
+                // Atmosphere LUTs and sampler.
+                (8, texture_2d(TextureSampleType::default())), // Transmittance.
+                (9, texture_2d(TextureSampleType::default())), // Multiscattering.
+                (12, sampler(SamplerBindingType::Filtering)),
+                // Per-light unattenuated emission (atmosphere uses this
+                // instead of the CPU-extinguished `lights` uniform).
+                (14, uniform_buffer::<GpuAtmosphereLights>(false)),
+                // Aerial view LUT storage texture.
+                (
+                    13,
+                    texture_storage_3d(format, StorageTextureAccess::WriteOnly),
+                ),
+            ),
+        ),
+    )
+}
+
+impl AtmosphereLutFragmentLayouts {
+    pub fn new() -> Self {
+        let transmittance_lut = BindGroupLayoutDescriptor::new(
+            "transmittance_lut_fragment_bind_group_layout",
             &BindGroupLayoutEntries::with_indices(
-                ShaderStages::COMPUTE,
+                ShaderStages::FRAGMENT,
                 (
                     (0, uniform_buffer::<GpuAtmosphere>(true)),
                     (1, uniform_buffer::<GpuAtmosphereSettings>(true)),
-                    (2, uniform_buffer::<AtmosphereTransform>(true)),
-                    (3, uniform_buffer::<ViewUniform>(true)),
-                    (4, uniform_buffer::<GpuLights>(true)),
                     // Scattering medium LUTs and sampler.
                     (5, texture_2d(TextureSampleType::default())),
                     (6, texture_2d(TextureSampleType::default())),
                     (7, sampler(SamplerBindingType::Filtering)),
-                    // Atmosphere LUTs and sampler.
-                    (8, texture_2d(TextureSampleType::default())), // Transmittance.
-                    (9, texture_2d(TextureSampleType::default())), // Multiscattering.
-                    (12, sampler(SamplerBindingType::Filtering)),
-                    // Per-light unattenuated emission (atmosphere uses this
-                    // instead of the CPU-extinguished `lights` uniform).
-                    (14, uniform_buffer::<GpuAtmosphereLights>(false)),
-                    // Sky view LUT storage texture.
-                    (
-                        13,
-                        texture_storage_2d(
-                            TextureFormat::Rgba16Float,
-                            StorageTextureAccess::WriteOnly,
-                        ),
-                    ),
                 ),
             ),
         );
 
-        let aerial_view_lut = BindGroupLayoutDescriptor::new(
-            "aerial_view_lut_bind_group_layout",
+        let sky_view_lut = BindGroupLayoutDescriptor::new(
+            "sky_view_lut_fragment_bind_group_layout",
             &BindGroupLayoutEntries::with_indices(
-                ShaderStages::COMPUTE,
+                ShaderStages::FRAGMENT,
                 (
                     (0, uniform_buffer::<GpuAtmosphere>(true)),
                     (1, uniform_buffer::<GpuAtmosphereSettings>(true)),
@@ -134,30 +232,20 @@ impl AtmosphereBindGroupLayouts {
                     (5, texture_2d(TextureSampleType::default())),
                     (6, texture_2d(TextureSampleType::default())),
                     (7, sampler(SamplerBindingType::Filtering)),
-                    // Atmosphere LUTs and sampler.
+                    // Atmosphere LUTs and sampler. The multiscattering LUT is
+                    // never written on this path, so it reads as zero.
                     (8, texture_2d(TextureSampleType::default())), // Transmittance.
                     (9, texture_2d(TextureSampleType::default())), // Multiscattering.
                     (12, sampler(SamplerBindingType::Filtering)),
-                    // Per-light unattenuated emission (atmosphere uses this
-                    // instead of the CPU-extinguished `lights` uniform).
+                    // Per-light unattenuated emission.
                     (14, uniform_buffer::<GpuAtmosphereLights>(false)),
-                    // Aerial view LUT storage texture.
-                    (
-                        13,
-                        texture_storage_3d(
-                            TextureFormat::Rgba16Float,
-                            StorageTextureAccess::WriteOnly,
-                        ),
-                    ),
                 ),
             ),
         );
 
         Self {
             transmittance_lut,
-            multiscattering_lut,
             sky_view_lut,
-            aerial_view_lut,
         }
     }
 }
@@ -2,11 +2,13 @@
 //! See NOTICE.md for attribution and licensing.
 //!
 //! Split by responsibility:
+//! - [`frame_data`] — per-view parameters published for other render passes.
 //! - [`gpu_types`] — shader-facing uniform structs.
 //! - [`lights`] — the atmospheric-lights uniform buffer.
 //! - [`sampler`] — the shared LUT sampler.
 //! - [`layouts`] — bind-group layout descriptors.
-//! - [`pipelines`] — LUT compute pipelines and the sky render pipeline.
+//! - [`pipelines`] — LUT pipelines (compute, or the fragment fallback) and the
+//!   sky render pipeline.
 //! - [`textures`] — per-view LUT textures.
 //! - [`transforms`] — per-view uniform/transform preparation.
 //! - [`buffer`] — the global single-atmosphere storage buffer.
@@ -14,6 +16,7 @@
 
 mod bind_groups;
 mod buffer;
+mod frame_data;
 mod gpu_types;
 mod layouts;
 mod lights;
@@ -22,6 +25,7 @@ mod sampler;
 mod textures;
 mod transforms;
 
+pub use frame_data::{AtmosphereFrameData, AtmosphereViewData};
 pub use gpu_types::{
     AtmosphereTransform, GpuAtmosphere, GpuAtmosphereLight, GpuAtmosphereLights,
     MAX_ATMOSPHERE_LIGHTS,
@@ -31,13 +35,16 @@ pub use lights::{AtmosphereLightsBuffer, ExtractedAtmosphereLights};
 pub use textures::AtmosphereTextures;
 pub use transforms::{AtmosphereTransforms, AtmosphereTransformsOffset};
 
-pub(crate) use bind_groups::{AtmosphereBindGroups, prepare_atmosphere_bind_groups};
+pub(crate) use bind_groups::{
+    AtmosphereBindGroups, AtmosphereLutBindGroups, prepare_atmosphere_bind_groups,
+};
 pub(crate) use buffer::{init_atmosphere_buffer, write_atmosphere_buffer};
-pub(crate) use layouts::AtmosphereBindGroupLayouts;
+pub(crate) use layouts::{AtmosphereBindGroupLayouts, AtmosphereLutFragmentLayouts};
 pub(crate) use lights::prepare_atmosphere_lights_buffer;
 pub(crate) use pipelines::{
-    AtmosphereLutPipelines, RenderSkyPipelineId, queue_render_sky_pipelines,
+    AtmosphereLutFragmentPipelines, AtmosphereLutPath, AtmosphereLutPipelines, RenderSkyPipelineId,
+    queue_render_sky_pipelines,
 };
 pub(crate) use sampler::AtmosphereSampler;
-pub(crate) use textures::prepare_atmosphere_textures;
+pub(crate) use textures::{REDUCED_SKY_VIEW_LUT_FORMAT, prepare_atmosphere_textures};
 pub(crate) use transforms::{prepare_atmosphere_transforms, prepare_atmosphere_uniforms};
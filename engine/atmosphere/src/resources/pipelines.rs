@@ -1,29 +1,59 @@
-//! LUT compute pipelines and the MSAA/dual-source-blend-specialised sky
-//! render pipeline.
+//! LUT compute pipelines and the sky render pipeline, specialised on MSAA,
+//! the blend path (dual-source, or a two-draw fallback) and the sky features
+//! in use.
 
 use bevy::{
+    core_pipeline::FullscreenShader,
     ecs::{
         component::Component,
         entity::Entity,
         query::With,
         resource::Resource,
-        system::{Commands, Query, Res, ResMut},
+        system::{Commands, Local, Query, Res, ResMut},
         world::{FromWorld, World},
     },
     render::{render_resource::*, renderer::RenderDevice, view::Msaa},
     utils::default,
 };
 
-use crate::ExtractedAtmosphere;
+use tracing::info;
 
-use super::layouts::{AtmosphereBindGroupLayouts, RenderSkyBindGroupLayouts};
+use crate::{
+    ExtractedAtmosphere, FEAT_DITHER, FEAT_INSCATTERING, FEAT_ISOLATE_INSCATTER, FEAT_STARS,
+    GpuAtmosphereSettings,
+};
+
+use super::{
+    gpu_types::GpuAtmosphereLights,
+    layouts::{
+        AtmosphereBindGroupLayouts, AtmosphereLutFragmentLayouts, RenderSkyBindGroupLayouts,
+    },
+    lights::ExtractedAtmosphereLights,
+};
+
+/// How the LUTs are generated, chosen from the adapter's capabilities.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum AtmosphereLutPath {
+    /// Compute passes writing storage textures: every LUT, plus the sky
+    /// environment map.
+    Compute,
+    /// Downlevel fallback (e.g. WebGL2): fullscreen fragment passes render the
+    /// transmittance and sky-view LUTs. Multiple scattering, the aerial-view
+    /// LUT and the environment map are unavailable, so the sky is an
+    /// approximation.
+    Fragment,
+}
 
 #[derive(Resource)]
 pub(crate) struct AtmosphereLutPipelines {
     pub transmittance_lut: CachedComputePipelineId,
     pub multiscattering_lut: CachedComputePipelineId,
     pub sky_view_lut: CachedComputePipelineId,
+    /// Writes the reduced-precision sky-view LUT, where the adapter can.
+    pub sky_view_lut_reduced: Option<CachedComputePipelineId>,
     pub aerial_view_lut: CachedComputePipelineId,
+    /// Writes the reduced-precision aerial-view LUT.
+    pub aerial_view_lut_reduced: CachedComputePipelineId,
 }
 
 impl FromWorld for AtmosphereLutPipelines {
@@ -53,6 +83,16 @@ impl FromWorld for AtmosphereLutPipelines {
             ..default()
         });
 
+        let sky_view_lut_reduced = layouts.sky_view_lut_reduced.as_ref().map(|layout| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("sky_view_lut_reduced_pipeline".into()),
+                layout: vec![layout.clone()],
+                shader: crate::embedded::sky_view_lut(world.resource()),
+                shader_defs: vec!["REDUCED_PRECISION".into()],
+                ..default()
+            })
+        });
+
         let aerial_view_lut = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
             label: Some("aerial_view_lut_pipeline".into()),
             layout: vec![layouts.aerial_view_lut.clone()],
@@ -60,22 +100,194 @@ impl FromWorld for AtmosphereLutPipelines {
             ..default()
         });
 
+        let aerial_view_lut_reduced =
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("aerial_view_lut_reduced_pipeline".into()),
+                layout: vec![layouts.aerial_view_lut_reduced.clone()],
+                shader: crate::embedded::aerial_view_lut(world.resource()),
+                shader_defs: vec!["REDUCED_PRECISION".into()],
+                ..default()
+            });
+
         Self {
             transmittance_lut,
             multiscattering_lut,
             sky_view_lut,
+            sky_view_lut_reduced,
             aerial_view_lut,
+            aerial_view_lut_reduced,
+        }
+    }
+}
+
+/// The sky pipelines a view draws, in order.
+/// The render pipelines of the [`AtmosphereLutPath::Fragment`] fallback.
+#[derive(Resource)]
+pub(crate) struct AtmosphereLutFragmentPipelines {
+    pub transmittance_lut: CachedRenderPipelineId,
+    pub sky_view_lut: CachedRenderPipelineId,
+}
+
+impl FromWorld for AtmosphereLutFragmentPipelines {
+    fn from_world(world: &mut World) -> Self {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let layouts = world.resource::<AtmosphereLutFragmentLayouts>();
+        let vertex = world.resource::<FullscreenShader>().to_vertex_state();
+
+        let lut_pipeline = |label: &'static str, layout: &BindGroupLayoutDescriptor, shader| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![layout.clone()],
+                vertex: vertex.clone(),
+                fragment: Some(FragmentState {
+                    shader,
+                    shader_defs: vec!["FRAGMENT_LUT".into()],
+                    targets: vec![Some(ColorTargetState {
+                        format: TextureFormat::Rgba16Float,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                    ..default()
+                }),
+                ..default()
+            })
+        };
+
+        Self {
+            transmittance_lut: lut_pipeline(
+                "transmittance_lut_fragment_pipeline",
+                &layouts.transmittance_lut,
+                crate::embedded::transmittance_lut(world.resource()),
+            ),
+            sky_view_lut: lut_pipeline(
+                "sky_view_lut_fragment_pipeline",
+                &layouts.sky_view_lut,
+                crate::embedded::sky_view_lut(world.resource()),
+            ),
         }
     }
 }
 
 #[derive(Component)]
-pub(crate) struct RenderSkyPipelineId(pub CachedRenderPipelineId);
+pub(crate) struct RenderSkyPipelineId {
+    /// The dual-source composite, or the fallback's transmittance multiply.
+    pub main: CachedRenderPipelineId,
+    /// The fallback's in-scatter add, drawn after [`Self::main`].
+    pub inscattering: Option<CachedRenderPipelineId>,
+}
 
+/// How a sky pipeline blends into the scene.
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub enum RenderSkyBlend {
+    /// `scene * transmittance + inscattering` in one draw, using the second
+    /// blend source for the per-channel transmittance.
+    DualSource,
+    /// Fallback, first draw: `scene * transmittance` (`TRANSMITTANCE_PASS`).
+    Transmittance,
+    /// Fallback, second draw: `scene + inscattering`.
+    Inscattering,
+}
+
+impl RenderSkyBlend {
+    fn color(self) -> BlendComponent {
+        let (src_factor, dst_factor) = match self {
+            Self::DualSource => (BlendFactor::One, BlendFactor::Src1),
+            Self::Transmittance => (BlendFactor::Zero, BlendFactor::Src),
+            Self::Inscattering => (BlendFactor::One, BlendFactor::One),
+        };
+        BlendComponent {
+            src_factor,
+            dst_factor,
+            operation: BlendOperation::Add,
+        }
+    }
+}
+
+/// What the sky pass does to the scene behind it.
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+pub enum AerialPerspectiveMode {
+    /// No in-scatter or extinction: the pass leaves the scene untouched and
+    /// skips the raymarch entirely.
+    Off,
+    /// In-scatter added over the scene, attenuated by transmittance.
+    On,
+    /// Debug view: the in-scatter alone, with the scene blocked.
+    Isolated,
+}
+
+/// Specialisation key for the sky pipeline. Features the view doesn't use are
+/// compiled out of the shader rather than branched around at runtime.
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 pub struct RenderSkyPipelineKey {
     pub msaa_samples: u32,
-    pub dual_source_blending: bool,
+    pub blend: RenderSkyBlend,
+    /// Some light draws a disk (`SUN_DISK`).
+    pub sun_disk: bool,
+    /// Procedural starfield behind the sky (`STARS`).
+    pub stars: bool,
+    /// Ray-march jitter and output dithering (`DITHER`).
+    pub dither: bool,
+    /// `AERIAL_PERSPECTIVE` / `AERIAL_PERSPECTIVE_ISOLATED`.
+    pub aerial_perspective: AerialPerspectiveMode,
+}
+
+impl RenderSkyPipelineKey {
+    /// Read the sky features from a view's settings and the current lights.
+    /// Isolating the in-scatter implies rendering it.
+    fn features(
+        msaa_samples: u32,
+        blend: RenderSkyBlend,
+        settings: &GpuAtmosphereSettings,
+        lights: &GpuAtmosphereLights,
+    ) -> Self {
+        let flags = settings.feature_flags;
+        let aerial_perspective = if flags & FEAT_ISOLATE_INSCATTER != 0 {
+            AerialPerspectiveMode::Isolated
+        } else if flags & FEAT_INSCATTERING != 0 {
+            AerialPerspectiveMode::On
+        } else {
+            AerialPerspectiveMode::Off
+        };
+        let count = (lights.count as usize).min(lights.lights.len());
+        let sun_disk = lights.lights[..count]
+            .iter()
+            .any(|light| light.sun_disk_angular_size > 0.0 && light.sun_disk_intensity > 0.0);
+        Self {
+            msaa_samples,
+            blend,
+            sun_disk,
+            stars: flags & FEAT_STARS != 0,
+            dither: flags & FEAT_DITHER != 0,
+            aerial_perspective,
+        }
+    }
+
+    fn label(&self) -> String {
+        let mut label = format!("render_sky_pipeline_{}", self.msaa_samples);
+        for (enabled, suffix) in [
+            (
+                self.blend == RenderSkyBlend::Transmittance,
+                "_transmittance",
+            ),
+            (self.blend == RenderSkyBlend::Inscattering, "_inscattering"),
+            (self.sun_disk, "_sun"),
+            (self.stars, "_stars"),
+            (self.dither, "_dither"),
+            (
+                self.aerial_perspective == AerialPerspectiveMode::Off,
+                "_no_aerial",
+            ),
+            (
+                self.aerial_perspective == AerialPerspectiveMode::Isolated,
+                "_isolated",
+            ),
+        ] {
+            if enabled {
+                label.push_str(suffix);
+            }
+        }
+        label
+    }
 }
 
 impl SpecializedRenderPipeline for RenderSkyBindGroupLayouts {
@@ -87,18 +299,31 @@ impl SpecializedRenderPipeline for RenderSkyBindGroupLayouts {
         if key.msaa_samples > 1 {
             shader_defs.push("MULTISAMPLED".into());
         }
-        if key.dual_source_blending {
-            shader_defs.push("DUAL_SOURCE_BLENDING".into());
+        match key.blend {
+            RenderSkyBlend::DualSource => shader_defs.push("DUAL_SOURCE_BLENDING".into()),
+            RenderSkyBlend::Transmittance => shader_defs.push("TRANSMITTANCE_PASS".into()),
+            RenderSkyBlend::Inscattering => {}
+        }
+        if key.sun_disk {
+            shader_defs.push("SUN_DISK".into());
+        }
+        if key.stars {
+            shader_defs.push("STARS".into());
+        }
+        if key.dither {
+            shader_defs.push("DITHER".into());
+        }
+        match key.aerial_perspective {
+            AerialPerspectiveMode::Off => {}
+            AerialPerspectiveMode::On => shader_defs.push("AERIAL_PERSPECTIVE".into()),
+            AerialPerspectiveMode::Isolated => {
+                shader_defs.push("AERIAL_PERSPECTIVE".into());
+                shader_defs.push("AERIAL_PERSPECTIVE_ISOLATED".into());
+            }
         }
-
-        let dst_factor = if key.dual_source_blending {
-            BlendFactor::Src1
-        } else {
-            BlendFactor::SrcAlpha
-        };
 
         RenderPipelineDescriptor {
-            label: Some(format!("render_sky_pipeline_{}", key.msaa_samples).into()),
+            label: Some(key.label().into()),
             layout: vec![if key.msaa_samples == 1 {
                 self.render_sky.clone()
             } else {
@@ -111,11 +336,7 @@ impl SpecializedRenderPipeline for RenderSkyBindGroupLayouts {
                 targets: vec![Some(ColorTargetState {
                     format: TextureFormat::Rgba16Float,
                     blend: Some(BlendState {
-                        color: BlendComponent {
-                            src_factor: BlendFactor::One,
-                            dst_factor,
-                            operation: BlendOperation::Add,
-                        },
+                        color: key.blend.color(),
                         alpha: BlendComponent {
                             src_factor: BlendFactor::Zero,
                             dst_factor: BlendFactor::One,
@@ -137,24 +358,48 @@ impl SpecializedRenderPipeline for RenderSkyBindGroupLayouts {
 
 #[allow(clippy::type_complexity)]
 pub fn queue_render_sky_pipelines(
-    views: Query<(Entity, &Msaa), (With<bevy::prelude::Camera>, With<ExtractedAtmosphere>)>,
+    views: Query<
+        (Entity, &Msaa, &GpuAtmosphereSettings),
+        (With<bevy::prelude::Camera>, With<ExtractedAtmosphere>),
+    >,
+    lights: Res<ExtractedAtmosphereLights>,
     pipeline_cache: Res<PipelineCache>,
     layouts: Res<RenderSkyBindGroupLayouts>,
     mut specializer: ResMut<SpecializedRenderPipelines<RenderSkyBindGroupLayouts>>,
     render_device: Res<RenderDevice>,
     mut commands: Commands,
+    mut reported_fallback: Local<bool>,
 ) {
-    for (entity, msaa) in &views {
-        let id = specializer.specialize(
-            &pipeline_cache,
-            &layouts,
-            RenderSkyPipelineKey {
-                msaa_samples: msaa.samples(),
-                dual_source_blending: render_device
-                    .features()
-                    .contains(WgpuFeatures::DUAL_SOURCE_BLENDING),
-            },
+    let dual_source_blending = render_device
+        .features()
+        .contains(WgpuFeatures::DUAL_SOURCE_BLENDING);
+    if !dual_source_blending && !*reported_fallback {
+        *reported_fallback = true;
+        info!(
+            "GPU lacks dual-source blending; compositing the sky in two draws \
+             (transmittance multiply, then in-scatter add)"
         );
-        commands.entity(entity).insert(RenderSkyPipelineId(id));
+    }
+
+    for (entity, msaa, settings) in &views {
+        let mut specialize = |blend| {
+            specializer.specialize(
+                &pipeline_cache,
+                &layouts,
+                RenderSkyPipelineKey::features(msaa.samples(), blend, settings, &lights.0),
+            )
+        };
+        let pipelines = if dual_source_blending {
+            RenderSkyPipelineId {
+                main: specialize(RenderSkyBlend::DualSource),
+                inscattering: None,
+            }
+        } else {
+            RenderSkyPipelineId {
+                main: specialize(RenderSkyBlend::Transmittance),
+                inscattering: Some(specialize(RenderSkyBlend::Inscattering)),
+            }
+        };
+        commands.entity(entity).insert(pipelines);
     }
 }
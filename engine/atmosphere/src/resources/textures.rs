@@ -5,7 +5,7 @@ use bevy::{
         component::Component,
         entity::Entity,
         query::With,
-        system::{Commands, Query, Res, ResMut},
+        system::{Commands, Local, Query, Res, ResMut},
     },
     image::ToExtents,
     render::{
@@ -15,7 +15,24 @@ use bevy::{
     },
 };
 
-use crate::{ExtractedAtmosphere, GpuAtmosphereSettings};
+use tracing::warn;
+
+use crate::{ExtractedAtmosphere, FEAT_REDUCED_LUTS, GpuAtmosphereSettings};
+
+use super::{layouts::AtmosphereBindGroupLayouts, pipelines::AtmosphereLutPath};
+
+/// Sky-view LUT format at [`LutPrecision::Reduced`]: unsigned HDR in 32 bits.
+/// Writable as a storage texture only on some adapters.
+///
+/// [`LutPrecision::Reduced`]: crate::LutPrecision::Reduced
+pub(crate) const REDUCED_SKY_VIEW_LUT_FORMAT: TextureFormat = TextureFormat::Rg11b10Ufloat;
+
+/// Aerial-view LUT format at [`LutPrecision::Reduced`]. The LUT holds signed
+/// log-space values, which the shaders remap into unorm range using
+/// [`GpuAtmosphereSettings::aerial_view_lut_log_min`] and `_log_range`.
+///
+/// [`LutPrecision::Reduced`]: crate::LutPrecision::Reduced
+pub(crate) const REDUCED_AERIAL_VIEW_LUT_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
 
 #[derive(Component)]
 pub struct AtmosphereTextures {
@@ -28,10 +45,61 @@ pub struct AtmosphereTextures {
 pub fn prepare_atmosphere_textures(
     views: Query<(Entity, &GpuAtmosphereSettings), With<ExtractedAtmosphere>>,
     render_device: Res<RenderDevice>,
+    lut_path: Res<AtmosphereLutPath>,
+    layouts: Option<Res<AtmosphereBindGroupLayouts>>,
     mut texture_cache: ResMut<TextureCache>,
     mut commands: Commands,
+    mut warned: Local<bool>,
 ) {
+    // Usages of the LUTs the fragment fallback generates (render targets) and
+    // of those it skips, which stay zero-initialised and are only sampled.
+    // The compute path writes every LUT.
+    let (generated, skipped) = match *lut_path {
+        AtmosphereLutPath::Compute => (
+            TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+            TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+        ),
+        AtmosphereLutPath::Fragment => (
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            TextureUsages::TEXTURE_BINDING,
+        ),
+    };
+
+    // Only the compute path writes reduced-precision LUTs, and the sky-view
+    // format only where the adapter supports it (see `SphericalAtmospherePlugin`).
+    let reduced_sky_view = layouts.is_some_and(|layouts| layouts.sky_view_lut_reduced.is_some());
+    let reduced_aerial_view = *lut_path == AtmosphereLutPath::Compute;
+
     for (entity, lut_settings) in &views {
+        let reduced = lut_settings.feature_flags & FEAT_REDUCED_LUTS != 0;
+        if reduced && !*warned {
+            *warned = true;
+            warn!(
+                "Atmosphere LUTs at reduced precision: sky view {}, aerial view {}. Expect \
+                 banding in the sky gradient and aerial perspective.",
+                if reduced_sky_view {
+                    "Rg11b10Ufloat"
+                } else {
+                    "Rgba16Float (adapter can't write Rg11b10Ufloat)"
+                },
+                if reduced_aerial_view {
+                    "log-encoded Rgba8Unorm"
+                } else {
+                    "Rgba16Float (not generated on the fragment-shader fallback)"
+                },
+            );
+        }
+        let sky_view_format = if reduced && reduced_sky_view {
+            REDUCED_SKY_VIEW_LUT_FORMAT
+        } else {
+            TextureFormat::Rgba16Float
+        };
+        let aerial_view_format = if reduced && reduced_aerial_view {
+            REDUCED_AERIAL_VIEW_LUT_FORMAT
+        } else {
+            TextureFormat::Rgba16Float
+        };
+
         let transmittance_lut = texture_cache.get(
             &render_device,
             TextureDescriptor {
@@ -41,7 +109,7 @@ pub fn prepare_atmosphere_textures(
                 sample_count: 1,
                 dimension: TextureDimension::D2,
                 format: TextureFormat::Rgba16Float,
-                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                usage: generated,
                 view_formats: &[],
             },
         );
@@ -55,7 +123,7 @@ pub fn prepare_atmosphere_textures(
                 sample_count: 1,
                 dimension: TextureDimension::D2,
                 format: TextureFormat::Rgba16Float,
-                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                usage: skipped,
                 view_formats: &[],
             },
         );
@@ -68,8 +136,8 @@ pub fn prepare_atmosphere_textures(
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: TextureDimension::D2,
-                format: TextureFormat::Rgba16Float,
-                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                format: sky_view_format,
+                usage: generated,
                 view_formats: &[],
             },
         );
@@ -82,8 +150,8 @@ pub fn prepare_atmosphere_textures(
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: TextureDimension::D3,
-                format: TextureFormat::Rgba16Float,
-                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                format: aerial_view_format,
+                usage: skipped,
                 view_formats: &[],
             },
         );
@@ -9,7 +9,7 @@ use bevy::{
         resource::Resource,
         system::{Commands, Query, Res, ResMut},
     },
-    math::{Affine3A, Mat4, Vec3A},
+    math::{Affine3A, Mat4, Vec3, Vec3A},
     prelude::Camera3d,
     render::{
         render_resource::*,
@@ -20,7 +20,11 @@ use bevy::{
 
 use crate::{ExtractedAtmosphere, SphericalAtmosphereCamera};
 
-use super::gpu_types::{AtmosphereTransform, GpuAtmosphere};
+use super::{
+    frame_data::{AtmosphereFrameData, AtmosphereViewData},
+    gpu_types::{AtmosphereTransform, GpuAtmosphere},
+    lights::ExtractedAtmosphereLights,
+};
 
 pub fn prepare_atmosphere_uniforms(
     mut commands: Commands,
@@ -31,6 +35,9 @@ pub fn prepare_atmosphere_uniforms(
             ground_albedo: atmosphere.ground_albedo,
             bottom_radius: atmosphere.bottom_radius,
             top_radius: atmosphere.top_radius,
+            sky_tint: atmosphere.artistic.sky_tint,
+            scattering_strength: atmosphere.artistic.scattering_strength,
+            horizon_haze: atmosphere.artistic.horizon_haze,
         });
     }
     Ok(())
@@ -66,17 +73,34 @@ impl AtmosphereTransformsOffset {
 /// instead of hardcoding `atmo_y = Vec3A::Y`, we use the `local_up` from
 /// the `SphericalAtmosphereCamera` component to properly orient the
 /// atmosphere coordinate system for the camera's position on the sphere.
+///
+/// Also publishes each view's parameters to [`AtmosphereFrameData`], so
+/// external render passes see exactly what the sky shaders do.
 #[allow(clippy::type_complexity)]
 pub fn prepare_atmosphere_transforms(
     views: Query<
-        (Entity, &ExtractedView, &SphericalAtmosphereCamera),
-        (With<ExtractedAtmosphere>, With<Camera3d>),
+        (
+            Entity,
+            &ExtractedView,
+            &SphericalAtmosphereCamera,
+            &ExtractedAtmosphere,
+        ),
+        With<Camera3d>,
     >,
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
     mut atmo_uniforms: ResMut<AtmosphereTransforms>,
+    lights: Res<ExtractedAtmosphereLights>,
+    mut frame_data: ResMut<AtmosphereFrameData>,
     mut commands: Commands,
 ) {
+    frame_data.views.clear();
+    frame_data.lights.clear();
+    let light_count = (lights.0.count as usize).min(lights.0.lights.len());
+    frame_data
+        .lights
+        .extend_from_slice(&lights.0.lights[..light_count]);
+
     let atmo_count = views.iter().len();
     let Some(mut writer) =
         atmo_uniforms
@@ -86,10 +110,20 @@ pub fn prepare_atmosphere_transforms(
         return;
     };
 
-    for (entity, view, spherical_camera) in &views {
+    for (entity, view, spherical_camera, atmosphere) in &views {
         let world_from_view = view.world_from_view.affine();
-        let camera_z = world_from_view.matrix3.z_axis;
-        let camera_y = world_from_view.matrix3.y_axis;
+        // Views sharing a reference rotation (the eyes of a stereo pair) share
+        // one atmosphere frame; only the translation stays per-view.
+        let (camera_z, camera_y) = match spherical_camera.reference_rotation {
+            Some(rotation) => (
+                Vec3A::from(rotation * Vec3::Z),
+                Vec3A::from(rotation * Vec3::Y),
+            ),
+            None => (
+                world_from_view.matrix3.z_axis,
+                world_from_view.matrix3.y_axis,
+            ),
+        };
 
         // KEY CHANGE: Use the local_up from SphericalAtmosphereCamera instead of Vec3A::Y.
         // This is the radial direction from the planet center through the camera position.
@@ -102,6 +136,17 @@ pub fn prepare_atmosphere_transforms(
 
         let world_from_atmosphere = Mat4::from(world_from_atmosphere);
 
+        frame_data.views.insert(
+            entity,
+            AtmosphereViewData {
+                world_from_atmosphere,
+                local_up: spherical_camera.local_up,
+                camera_radius: spherical_camera.camera_radius,
+                bottom_radius: atmosphere.bottom_radius,
+                top_radius: atmosphere.top_radius,
+            },
+        );
+
         commands.entity(entity).insert(AtmosphereTransformsOffset {
             index: writer.write(&AtmosphereTransform {
                 world_from_atmosphere,
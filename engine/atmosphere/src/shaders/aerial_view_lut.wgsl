@@ -11,7 +11,11 @@
 }
 
 
+#ifdef REDUCED_PRECISION
+@group(0) @binding(13) var aerial_view_lut_out: texture_storage_3d<rgba8unorm, write>;
+#else
 @group(0) @binding(13) var aerial_view_lut_out: texture_storage_3d<rgba16float, write>;
+#endif
 
 @compute
 @workgroup_size(16, 16, 1)
@@ -62,8 +66,10 @@ fn main(@builtin(global_invocation_id) idx: vec3<u32>) {
             }
         }
 
-        // Store in log space to allow linear interpolation of exponential values between slices.
+        // Store in log space to allow linear interpolation of exponential values between slices,
+        // remapped into the range the LUT format holds (identity at full precision).
         let log_inscattering = log(max(total_inscattering, vec3(1e-6)));
-        textureStore(aerial_view_lut_out, vec3(vec2<u32>(idx.xy), slice_i), vec4(log_inscattering, 0.0));
+        let encoded = (log_inscattering - settings.aerial_view_lut_log_min) / settings.aerial_view_lut_log_range;
+        textureStore(aerial_view_lut_out, vec3(vec2<u32>(idx.xy), slice_i), vec4(encoded, 0.0));
     }
 }
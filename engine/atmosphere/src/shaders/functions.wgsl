@@ -53,7 +53,8 @@ const MIN_EXTINCTION: vec3<f32> = vec3(1e-12);
 
 // Feature-toggle bits packed into `settings.feature_flags` (see
 // `AtmosphereSettings`). Bit set = feature on; bit 0 is the isolate-in-scatter
-// debug view.
+// debug view. The sky pass reads the in-scatter, isolate, starfield and dither
+// bits through its pipeline key (shader defs) rather than these.
 const FEAT_ISOLATE_INSCATTER: u32 = 1u;
 const FEAT_INSCATTERING: u32 = 2u;
 const FEAT_PLANET_SHADOW: u32 = 4u;
@@ -61,11 +62,16 @@ const FEAT_SUN_TRANSMITTANCE: u32 = 8u;
 const FEAT_PHASE: u32 = 16u;
 const FEAT_MULTISCATTERING: u32 = 32u;
 const FEAT_ENVIRONMENT: u32 = 64u;
+const FEAT_STARS: u32 = 128u;
+const FEAT_REDUCED_LUTS: u32 = 256u;
+const FEAT_DITHER: u32 = 512u;
 
 // During raymarching, each segment is sampled at a single point.
-// `settings.raymarch_midpoint_ratio` determines where in the segment that
+// `raymarch_atmosphere`'s `sample_offset`, normally
+// `settings.raymarch_midpoint_ratio`, determines where in the segment that
 // sample is taken (0.0 = start, 0.5 = middle, 1.0 = end); the default biases
-// toward the start to better approximate the exponential density falloff.
+// toward the start to better approximate the exponential density falloff. The
+// sky pass jitters it per pixel while dithering.
 
 // LUT UV PARAMETERIZATIONS
 
@@ -193,8 +199,9 @@ fn sample_aerial_view_lut(uv: vec2<f32>, t: f32) -> vec3<f32> {
     // position to recover the correct integral value.
     let t_slice = t_max / num_slices;
     let fade = saturate(t / t_slice);
-    // Recover the values from log space.
-    return exp(sample.rgb) * fade;
+    // Recover the values from (encoded) log space.
+    let log_inscattering = sample.rgb * settings.aerial_view_lut_log_range + settings.aerial_view_lut_log_min;
+    return exp(log_inscattering) * fade;
 }
 
 // ATMOSPHERE SAMPLING
@@ -253,7 +260,7 @@ fn sample_local_inscattering(local_scattering: vec3<f32>, ray_dir: vec3<f32>, at
         if (flags & FEAT_SUN_TRANSMITTANCE) == 0u {
             transmittance_to_light = vec3(1.0);
         }
-        var planet_visibility = f32(!ray_intersects_ground(local_r, mu_light));
+        var planet_visibility = light_planet_visibility(local_r, mu_light, light.sun_disk_angular_size);
         if (flags & FEAT_PLANET_SHADOW) == 0u {
             planet_visibility = 1.0;
         }
@@ -305,11 +312,24 @@ fn sample_sun_radiance(ray_dir_as: vec3<f32>) -> vec3<f32> {
     return sun_radiance;
 }
 
+// The planet's shadow on the atmosphere: the fraction of a light's disk above
+// the planet's horizon as seen from radius `r`. Spreading the shadow edge over
+// the disk gives the Earth's shadow rising opposite the sun at twilight the
+// soft boundary under the Belt of Venus, instead of a step that the LUTs
+// alias. Lights without a disk fall back to the hard test.
+fn light_planet_visibility(r: f32, mu_light: f32, angular_size: f32) -> f32 {
+    if angular_size <= 0.0 {
+        return f32(!ray_intersects_ground(r, mu_light));
+    }
+    return calculate_visible_sun_ratio(atmosphere, r, mu_light, angular_size);
+}
+
 fn calculate_visible_sun_ratio(atmosphere: Atmosphere, r: f32, mu: f32, sun_angular_size: f32) -> f32 {
     let bottom_radius = atmosphere.bottom_radius;
     // Calculate the angle between horizon and sun center.
     // Invert the horizon angle calculation to fix shading direction.
-    let horizon_cos = -sqrt(1.0 - (bottom_radius * bottom_radius) / (r * r));
+    // Samples can sit a hair under the surface; treat them as on it.
+    let horizon_cos = -sqrt(max(1.0 - (bottom_radius * bottom_radius) / (r * r), 0.0));
     let horizon_angle = fast_acos_4(horizon_cos);
     let sun_zenith_angle = fast_acos_4(mu);
 
@@ -491,7 +511,7 @@ fn raymarch_atmosphere(
     ray_dir: vec3<f32>,
     t_max: f32,
     max_samples: u32,
-    uv: vec2<f32>,
+    sample_offset: f32,
     ground: bool
 ) -> RaymarchResult {
     let r = length(pos);
@@ -521,7 +541,7 @@ fn raymarch_atmosphere(
     var optical_depth = vec3(0.0);
     for (var s = 0.0; s < sample_count; s += 1.0) {
         // Linear distribution from atmosphere entry to exit/ground.
-        let t_i = t_start + t_total * (s + settings.raymarch_midpoint_ratio) / sample_count;
+        let t_i = t_start + t_total * (s + sample_offset) / sample_count;
         let dt_i = (t_i - prev_t);
         prev_t = t_i;
 
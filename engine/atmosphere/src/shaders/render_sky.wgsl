@@ -1,7 +1,9 @@
 // Derived from Bevy 0.18 bevy_pbr atmosphere implementation.
 // See NOTICE.md for attribution and licensing.
 
+#ifdef DUAL_SOURCE_BLENDING
 enable dual_source_blending;
+#endif
 
 #import bevy_render::maths::ray_sphere_intersect
 
@@ -11,7 +13,7 @@ enable dual_source_blending;
         direction_world_to_atmosphere,
         uv_to_ray_direction, uv_to_ndc,
         sample_sun_radiance, ndc_to_camera_dist, raymarch_atmosphere,
-        get_view_position, max_atmosphere_distance, FEAT_ISOLATE_INSCATTER, FEAT_INSCATTERING
+        get_view_position, max_atmosphere_distance, RaymarchResult
     },
 };
 
@@ -23,91 +25,201 @@ enable dual_source_blending;
 @group(0) @binding(13) var depth_texture: texture_depth_2d;
 #endif
 
+// With dual-source blending one draw writes `scene * transmittance +
+// inscattering`. Without it the pipeline is drawn twice: first with
+// `TRANSMITTANCE_PASS` multiplying the scene by the transmittance, then adding
+// the in-scatter.
 struct RenderSkyOutput {
 #ifdef DUAL_SOURCE_BLENDING
     @location(0) @blend_src(0) inscattering: vec4<f32>,
     @location(0) @blend_src(1) transmittance: vec4<f32>,
 #else
-    @location(0) inscattering: vec4<f32>,
+    @location(0) color: vec4<f32>,
+#endif
+}
+
+// Procedural starfield: one candidate star per cell of a grid through which
+// the view direction moves, `STAR_DENSITY` of which hold a star. Fixed to the
+// world frame.
+const STAR_GRID: f32 = 600.0;
+const STAR_DENSITY: f32 = 0.03;
+// Luminance of the brightest star (cd/m²); fainter ones fall off steeply.
+const STAR_LUMINANCE: f32 = 4.0;
+
+fn star_hash(p: vec3<f32>) -> vec3<f32> {
+    var q = fract(p * vec3(0.1031, 0.1030, 0.0973));
+    q += dot(q, q.yxz + 33.33);
+    return fract((q.xxy + q.yxx) * q.zyx);
+}
+
+fn sample_stars(ray_dir_ws: vec3<f32>) -> vec3<f32> {
+    let p = ray_dir_ws * STAR_GRID;
+    // Pixel footprint in grid units; taken before any branch so the
+    // derivative stays in uniform control flow.
+    let footprint = max(length(fwidth(p)), 1e-4);
+    let cell = floor(p);
+    let h = star_hash(cell);
+    if h.x > STAR_DENSITY {
+        return vec3(0.0);
+    }
+    let star = cell + 0.2 + 0.6 * star_hash(cell + 17.0);
+    let coverage = max(1.0 - distance(p, star) / footprint, 0.0);
+    let brightness = pow(h.y, 6.0) * STAR_LUMINANCE;
+    let tint = mix(vec3(1.0, 0.8, 0.6), vec3(0.7, 0.8, 1.0), h.z);
+    return tint * brightness * coverage;
+}
+
+// Dithering against banding in smooth gradients. The ray-march sample offset
+// is jittered per pixel around `settings.raymarch_midpoint_ratio`, which
+// trades the stepping of a low sample count for fine noise, and the in-scatter
+// gets about one 8-bit step of triangular noise in an approximate display
+// space (a Reinhard curve and a square root standing in for the tonemapper
+// and the sRGB encoding).
+const DITHER_OFFSET_SPREAD: f32 = 0.5;
+const DITHER_STEP: f32 = 1.0 / 255.0;
+
+// Interleaved gradient noise (Jimenez 2014): a cheap per-pixel hash whose
+// energy sits at high frequencies, close enough to blue noise here.
+fn interleaved_gradient_noise(pixel: vec2<f32>) -> f32 {
+    return fract(52.9829189 * fract(dot(pixel, vec2(0.06711056, 0.00583715))));
+}
+
+fn dithered_sample_offset(pixel: vec2<f32>) -> f32 {
+#ifdef DITHER
+    let jitter = (interleaved_gradient_noise(pixel) - 0.5) * DITHER_OFFSET_SPREAD;
+    return saturate(settings.raymarch_midpoint_ratio + jitter);
+#else
+    return settings.raymarch_midpoint_ratio;
 #endif
 }
 
+fn dither_inscattering(color: vec3<f32>, pixel: vec2<f32>) -> vec3<f32> {
+    // Triangular noise in [-1, 1] from two decorrelated samples.
+    let noise = interleaved_gradient_noise(pixel + vec2(47.0, 17.0))
+        + interleaved_gradient_noise(pixel + vec2(11.0, 73.0)) - 1.0;
+    let c = max(color, vec3(0.0));
+    let display = sqrt(c / (1.0 + c));
+    // Fade out over the darkest step so black space stays black.
+    let amplitude = DITHER_STEP * saturate(display / DITHER_STEP);
+    let dithered = clamp(display + noise * amplitude, vec3(0.0), vec3(0.999));
+    let d2 = dithered * dithered;
+    return d2 / (1.0 - d2);
+}
+
+// The artistic overrides on the physically-based in-scatter. The haze boost
+// peaks where the ray grazes the local horizon.
+const HORIZON_HAZE_SHARPNESS: f32 = 8.0;
+
+fn apply_artistic(inscattering: vec3<f32>, ray_dir_as: vec3<f32>) -> vec3<f32> {
+    let horizon = pow(1.0 - abs(ray_dir_as.y), HORIZON_HAZE_SHARPNESS);
+    let boost = 1.0 + atmosphere.horizon_haze * horizon;
+    return inscattering * atmosphere.sky_tint * (atmosphere.scattering_strength * boost);
+}
+
+// A ray that hits no geometry: the in-scatter along it plus whatever lies
+// beyond, seen through it. The atmosphere provides its own background (black
+// space), so the clear colour is always blocked.
+fn render_sky_ray(world_pos: vec3<f32>, ray_dir_as: vec3<f32>, background: vec3<f32>, sample_offset: f32) -> RaymarchResult {
+    let r = length(world_pos);
+    let mu = ray_dir_as.y;
+    if r > atmosphere.top_radius && ray_sphere_intersect(r, mu, atmosphere.top_radius).x < 0.0 {
+        // Outside the atmosphere and missing it: pure black space, showing
+        // only the background.
+        return RaymarchResult(background, vec3(0.0));
+    }
+    // Always use raymarching - LUTs have artifacts with our spherical planet setup.
+    let t_max = max_atmosphere_distance(r, mu);
+    let result = raymarch_atmosphere(world_pos, ray_dir_as, t_max, settings.sky_max_samples, sample_offset, true);
+    let inscattering = apply_artistic(result.inscattering, ray_dir_as);
+    return RaymarchResult(inscattering + background * result.transmittance, vec3(0.0));
+}
+
+// A ray that hits geometry at `depth`: the aerial perspective up to it.
+fn render_geometry_ray(world_pos: vec3<f32>, ray_dir_as: vec3<f32>, depth: f32, uv: vec2<f32>, sample_offset: f32) -> RaymarchResult {
+    let t = ndc_to_camera_dist(vec3(uv_to_ndc(uv), depth));
+    let result = raymarch_atmosphere(world_pos, ray_dir_as, t, settings.sky_max_samples, sample_offset, false);
+    return RaymarchResult(apply_artistic(result.inscattering, ray_dir_as), result.transmittance);
+}
+
 @fragment
 fn main(in: FullscreenVertexOutput) -> RenderSkyOutput {
-    let depth = textureLoad(depth_texture, vec2<i32>(in.position.xy), 0);
-
+#ifdef AERIAL_PERSPECTIVE
     let ray_dir_ws = uv_to_ray_direction(in.uv);
     let world_pos = get_view_position();
-    let r = length(world_pos);
     // For the LUT lookups, mu should be relative to the atmosphere-space Y axis.
     let ray_dir_as = direction_world_to_atmosphere(ray_dir_ws);
-    let mu = ray_dir_as.y;
-    let max_samples = settings.sky_max_samples;
 
-    var transmittance: vec3<f32>;
-    var inscattering: vec3<f32>;
+    // What lies beyond the atmosphere: the sun disk (atmosphere-space ray
+    // direction) and the stars, each compiled in only when the view uses it.
+    var background = vec3(0.0);
+#ifdef SUN_DISK
+    background += sample_sun_radiance(ray_dir_as);
+#endif
+#ifdef STARS
+    background += sample_stars(ray_dir_ws);
+#endif
 
-    // Use atmosphere-space ray direction for sun radiance calculation.
-    let sun_radiance = sample_sun_radiance(ray_dir_as);
+#ifdef MULTISAMPLED
+    // The pass runs once per pixel but blends into every sample, so a pixel
+    // on a silhouette mixes the sky and geometry results by how many of its
+    // samples see each. Geometry samples share the nearest depth (reverse Z).
+    let pixel = vec2<i32>(in.position.xy);
+    let sample_count = textureNumSamples(depth_texture);
+    var sky_samples = 0u;
+    var depth = 0.0;
+    for (var i = 0u; i < sample_count; i++) {
+        let sample_depth = textureLoad(depth_texture, pixel, i32(i));
+        sky_samples += u32(sample_depth == 0.0);
+        depth = max(depth, sample_depth);
+    }
+    let sky_coverage = f32(sky_samples) / f32(sample_count);
+#else
+    let depth = textureLoad(depth_texture, vec2<i32>(in.position.xy), 0);
+    let sky_coverage = f32(depth == 0.0);
+#endif
 
-    // Always use raymarching - LUTs have artifacts with our spherical planet setup.
-    let is_outside_atmosphere = r > atmosphere.top_radius;
-
-    if depth == 0.0 {
-        // Looking at sky (no geometry hit).
-        if is_outside_atmosphere {
-            // Check if the ray intersects the atmosphere at all.
-            let atmo_hit = ray_sphere_intersect(r, mu, atmosphere.top_radius);
-            if atmo_hit.x < 0.0 {
-                // Ray doesn't intersect atmosphere - pure black space.
-                // Set transmittance to 0 to block the clear color, showing only the sun.
-                inscattering = sun_radiance;
-                transmittance = vec3(0.0);
-            } else {
-                // Ray intersects atmosphere - raymarch through it.
-                let t_max = max_atmosphere_distance(r, mu);
-                let result = raymarch_atmosphere(world_pos, ray_dir_as, t_max, max_samples, in.uv, true);
-                inscattering = result.inscattering + sun_radiance * result.transmittance;
-                // Block clear color - atmosphere provides its own background (black space).
-                transmittance = vec3(0.0);
-            }
-        } else {
-            // Inside atmosphere - raymarch and block clear color.
-            let t_max = max_atmosphere_distance(r, mu);
-            let result = raymarch_atmosphere(world_pos, ray_dir_as, t_max, max_samples, in.uv, true);
-            inscattering = result.inscattering + sun_radiance * result.transmittance;
-            // Block clear color for consistent rendering.
-            transmittance = vec3(0.0);
-        }
-    } else {
-        // Looking at geometry - raymarch to the geometry distance.
-        let t = ndc_to_camera_dist(vec3(uv_to_ndc(in.uv), depth));
-        let result = raymarch_atmosphere(world_pos, ray_dir_as, t, max_samples, in.uv, false);
-        inscattering = result.inscattering;
-        transmittance = result.transmittance;
+    let sample_offset = dithered_sample_offset(in.position.xy);
+    var result = RaymarchResult(vec3(0.0), vec3(0.0));
+    if sky_coverage > 0.0 {
+        let sky = render_sky_ray(world_pos, ray_dir_as, background, sample_offset);
+        result.inscattering += sky.inscattering * sky_coverage;
+    }
+    if sky_coverage < 1.0 {
+        // The sky samples' destination is the (blocked) clear colour, so
+        // weighting only the in-scatter keeps the resolved pixel right.
+        let geometry = render_geometry_ray(world_pos, ray_dir_as, depth, in.uv, sample_offset);
+        result.inscattering += geometry.inscattering * (1.0 - sky_coverage);
+        result.transmittance = geometry.transmittance;
     }
 
     // Exposure compensation.
-    inscattering *= view.exposure;
-
-    // Disabling in-scatter shows the scene without aerial perspective. This
-    // drops both the additive in-scatter glow AND the multiplicative
-    // transmittance (extinction) the atmosphere applies to the terrain, so the
-    // scene is left completely unaffected by the atmosphere.
-    if (settings.feature_flags & FEAT_INSCATTERING) == 0u {
-        inscattering = vec3(0.0);
-        transmittance = vec3(1.0);
-    }
+#ifdef DITHER
+    let inscattering = dither_inscattering(result.inscattering * view.exposure, in.position.xy);
+#else
+    let inscattering = result.inscattering * view.exposure;
+#endif
+
+#ifdef AERIAL_PERSPECTIVE_ISOLATED
     // Isolate in-scatter: block the scene and show only the aerial perspective.
-    if (settings.feature_flags & FEAT_ISOLATE_INSCATTER) != 0u {
-        transmittance = vec3(0.0);
-    }
+    let transmittance = vec3(0.0);
+#else
+    let transmittance = result.transmittance;
+#endif
+#else
+    // Aerial perspective off: the scene is left completely unaffected by the
+    // atmosphere, with neither the additive in-scatter glow nor the
+    // multiplicative transmittance (extinction), and nothing is raymarched.
+    let inscattering = vec3(0.0);
+    let transmittance = vec3(1.0);
+#endif
 
 #ifdef DUAL_SOURCE_BLENDING
     return RenderSkyOutput(vec4(inscattering, 0.0), vec4(transmittance, 1.0));
 #else
-    let mean_transmittance = (transmittance.r + transmittance.g + transmittance.b) / 3.0;
-    return RenderSkyOutput(vec4(inscattering, mean_transmittance));
+#ifdef TRANSMITTANCE_PASS
+    return RenderSkyOutput(vec4(transmittance, 1.0));
+#else
+    return RenderSkyOutput(vec4(inscattering, 0.0));
+#endif
 #endif
-
 }
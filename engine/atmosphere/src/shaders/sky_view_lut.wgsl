@@ -16,13 +16,30 @@
 }
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
 
+#ifdef FRAGMENT_LUT
+// Fallback for GPUs without compute shaders: render the LUT as a fullscreen
+// pass. The compute path samples at texel corners, so shift by half a pixel.
+@fragment
+fn main(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let uv = (in.position.xy - 0.5) / vec2<f32>(settings.sky_view_lut_size);
+    return vec4(sky_view_at(uv), 1.0);
+}
+#else
+#ifdef REDUCED_PRECISION
+@group(0) @binding(13) var sky_view_lut_out: texture_storage_2d<rg11b10ufloat, write>;
+#else
 @group(0) @binding(13) var sky_view_lut_out: texture_storage_2d<rgba16float, write>;
+#endif
 
 @compute
 @workgroup_size(16, 16, 1)
 fn main(@builtin(global_invocation_id) idx: vec3<u32>) {
     let uv = vec2<f32>(idx.xy) / vec2<f32>(settings.sky_view_lut_size);
+    textureStore(sky_view_lut_out, idx.xy, vec4(sky_view_at(uv), 1.0));
+}
+#endif
 
+fn sky_view_at(uv: vec2<f32>) -> vec3<f32> {
     let cam_pos = get_view_position();
     let r = length(cam_pos);
     var zenith_azimuth = sky_view_lut_uv_to_zenith_azimuth(r, uv);
@@ -37,7 +54,6 @@ fn main(@builtin(global_invocation_id) idx: vec3<u32>) {
     let t_max = max_atmosphere_distance(r, mu);
 
     // Raymarch in atmosphere space (position and ray direction both in atmosphere space).
-    let result = raymarch_atmosphere(atmo_pos, ray_dir_as, t_max, settings.sky_view_lut_samples, uv, true);
-
-    textureStore(sky_view_lut_out, idx.xy, vec4(result.inscattering, 1.0));
+    let result = raymarch_atmosphere(atmo_pos, ray_dir_as, t_max, settings.sky_view_lut_samples, settings.raymarch_midpoint_ratio, true);
+    return result.inscattering;
 }
@@ -12,20 +12,32 @@
 
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
 
+#ifdef FRAGMENT_LUT
+// Fallback for GPUs without compute shaders: render the LUT as a fullscreen
+// pass. Pixel centres land on the same texel centres as the compute path.
+@fragment
+fn main(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let uv = in.position.xy / vec2<f32>(settings.transmittance_lut_size);
+    return vec4(transmittance_at(uv), 1.0);
+}
+#else
 @group(0) @binding(13) var transmittance_lut_out: texture_storage_2d<rgba16float, write>;
 
 @compute
 @workgroup_size(16, 16, 1)
 fn main(@builtin(global_invocation_id) idx: vec3<u32>) {
     let uv: vec2<f32> = (vec2<f32>(idx.xy) + 0.5) / vec2<f32>(settings.transmittance_lut_size);
+    textureStore(transmittance_lut_out, idx.xy, vec4(transmittance_at(uv), 1.0));
+}
+#endif
+
+fn transmittance_at(uv: vec2<f32>) -> vec3<f32> {
     // Map UV coordinates to view height (r) and zenith cos angle (mu).
     let r_mu = transmittance_lut_uv_to_r_mu(uv);
 
     // Compute the optical depth from view height r to the top atmosphere boundary.
     let optical_depth = ray_optical_depth(r_mu.x, r_mu.y, settings.transmittance_lut_samples);
-    let transmittance = exp(-optical_depth);
-
-    textureStore(transmittance_lut_out, idx.xy, vec4(transmittance, 1.0));
+    return exp(-optical_depth);
 }
 
 /// Compute the optical depth of the atmosphere from the ground to the top atmosphere boundary
@@ -9,6 +9,10 @@ struct Atmosphere {
     bottom_radius: f32, // units: m
     // Radius at which we consider the atmosphere to 'end' for our calculations (from center of planet).
     top_radius: f32, // units: m
+    // Artistic overrides, applied by render_sky on top of the LUTs.
+    sky_tint: vec3<f32>,
+    scattering_strength: f32,
+    horizon_haze: f32,
 }
 
 struct AtmosphereSettings {
@@ -26,6 +30,8 @@ struct AtmosphereSettings {
     sky_max_samples: u32,
     rendering_method: u32,
     raymarch_midpoint_ratio: f32,
+    aerial_view_lut_log_min: f32,
+    aerial_view_lut_log_range: f32,
     feature_flags: u32,
 }
 
@@ -6,9 +6,12 @@ use bevy::prelude::*;
 use glam::DVec3;
 use veldera_input::{LookIntent, MovementIntent, ZoomIntent};
 
-use veldera_geo::floating_origin::{FloatingOrigin, FloatingOriginCamera};
+use veldera_geo::floating_origin::FloatingOriginCamera;
 
-use crate::{CameraConfig, FlightCamera, FreelookCameraSet, input_active, view_active};
+use crate::{
+    CameraConfig, FlightCamera, FlycamConstraintSet, FreelookCameraSet, TerrainFollow,
+    altitude_speed_factor, input_active,
+};
 
 // ============================================================================
 // Plugin
@@ -19,16 +22,24 @@ pub(super) struct FlycamPlugin;
 
 impl Plugin for FlycamPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.configure_sets(
+            Update,
+            FlycamConstraintSet
+                .in_set(FreelookCameraSet)
+                .after(camera_movement)
+                .before(follow_terrain),
+        )
+        .add_systems(
             Update,
             (
                 adjust_speed_with_scroll.run_if(input_active),
                 camera_look.run_if(input_active),
                 camera_movement.run_if(input_active),
-                // Sync floating origin AFTER camera systems update their position.
-                // `view_active` also covers FollowEntity mode, where the host's
-                // follow rig updates the camera position.
-                sync_floating_origin.run_if(view_active),
+                // Host constraints (`FlycamConstraintSet`) run here.
+                follow_terrain.run_if(input_active),
+                // The floating origin follows the camera later, in
+                // `PostUpdate` (`FloatingOriginSystems`), once every mode has
+                // moved it.
             )
                 .chain()
                 .in_set(FreelookCameraSet),
@@ -106,15 +117,8 @@ fn camera_movement(
     mut query: Query<(&mut FloatingOriginCamera, &mut Transform, &mut FlightCamera)>,
 ) {
     for (mut origin_camera, mut transform, mut camera) in &mut query {
-        // Calculate altitude-based speed using high-precision position.
-        let altitude = origin_camera.position.length() - veldera_constants::EARTH_RADIUS_M_F64;
-        let altitude = altitude.max(0.0);
-
         // Speed scales with altitude: faster when high, slower when near ground.
-        let speed_factor = ((altitude / 10000.0).max(1.0) + 1.0).powf(1.337) / 6.0;
-        let speed_factor = speed_factor.min(2600.0) as f32;
-
-        let mut speed = config.base_speed * speed_factor;
+        let mut speed = config.base_speed * altitude_speed_factor(origin_camera.position);
         if movement.sprint {
             speed *= config.boost_multiplier;
         }
@@ -179,9 +183,26 @@ fn camera_movement(
     }
 }
 
-/// Sync the floating origin resource with the camera position.
-fn sync_floating_origin(mut origin: ResMut<FloatingOrigin>, query: Query<&FloatingOriginCamera>) {
-    if let Ok(camera) = query.single() {
-        origin.position = camera.position;
+/// Lift the camera to the minimum clearance above the ground while terrain
+/// following is enabled.
+///
+/// Moving straight up along local up leaves the view direction unchanged, so
+/// only the position is adjusted.
+fn follow_terrain(
+    follow: Res<TerrainFollow>,
+    mut query: Query<&mut FloatingOriginCamera, With<FlightCamera>>,
+) {
+    if !follow.enabled {
+        return;
+    }
+    for mut origin_camera in &mut query {
+        let Some(height) = follow.height_above_ground(origin_camera.position) else {
+            continue;
+        };
+        if height < follow.min_clearance_m {
+            let lift = follow.min_clearance_m - height;
+            let radius = origin_camera.position.length() + lift;
+            origin_camera.position = origin_camera.position.normalize() * radius;
+        }
     }
 }
@@ -3,7 +3,9 @@
 //! Provides WASD movement with mouse look and altitude-based speed scaling,
 //! working with the floating-origin system for high-precision positioning, plus
 //! a viewer request API to set altitude, heading, or translate the camera by a
-//! precise great-circle distance.
+//! precise great-circle distance. Optional terrain following keeps the flycam
+//! a minimum height above the ground the host reports through
+//! [`TerrainFollow`].
 //!
 //! The crate is gameplay-agnostic: it has no notion of camera *modes*, the
 //! first-person player, or follow rigs. A host that wants more than freelook
@@ -17,7 +19,7 @@
 mod flycam;
 
 use bevy::{math::DVec3, prelude::*, reflect::TypePath};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use veldera_config::ConfigPlugin;
 use veldera_geo::floating_origin::FloatingOriginCamera;
 
@@ -29,6 +31,16 @@ use veldera_geo::floating_origin::FloatingOriginCamera;
 #[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FreelookCameraSet;
 
+/// System set in which a host constrains flycam movement (e.g. collision).
+///
+/// Runs within [`FreelookCameraSet`], after the flycam has applied this
+/// frame's movement and before terrain following and the floating-origin
+/// sync. The movement applied this frame is [`FlightCamera::velocity`] times
+/// the frame's delta time, so a constraint can recover where the camera
+/// started and adjust the end position.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlycamConstraintSet;
+
 /// Host-driven activation state for the freelook camera.
 ///
 /// The crate never reads camera modes; instead the host sets these flags each
@@ -81,7 +93,7 @@ pub struct CameraConfig {
 }
 
 /// Which style of teleport animation to use.
-#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Deserialize)]
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum TeleportAnimationMode {
     /// Classic Earth-looking mode: camera looks down at Earth during cruise.
     #[default]
@@ -186,6 +198,51 @@ impl TranslateRequest {
     }
 }
 
+// ============================================================================
+// Terrain following
+// ============================================================================
+
+/// Terrain following for the flycam, and the ground height beneath it.
+///
+/// The crate knows nothing about terrain: the host samples the ground under
+/// the camera each frame (before [`FreelookCameraSet`]) and reports it with
+/// [`set_ground_radius`](Self::set_ground_radius). While
+/// [`enabled`](Self::enabled), flycam movement never takes the camera closer
+/// than [`min_clearance_m`](Self::min_clearance_m) to that ground.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct TerrainFollow {
+    /// Clamp the flycam above the ground.
+    pub enabled: bool,
+    /// Minimum height above the ground (m) while enabled.
+    pub min_clearance_m: f64,
+    /// Distance from the Earth's centre to the ground beneath the camera (m),
+    /// if the host found any.
+    ground_radius: Option<f64>,
+}
+
+impl Default for TerrainFollow {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_clearance_m: 50.0,
+            ground_radius: None,
+        }
+    }
+}
+
+impl TerrainFollow {
+    /// Report the ground beneath the camera as a distance from the Earth's
+    /// centre (m), or `None` if no terrain is loaded there.
+    pub fn set_ground_radius(&mut self, ground_radius: Option<f64>) {
+        self.ground_radius = ground_radius;
+    }
+
+    /// Height of `position` (ECEF) above the last reported ground (m).
+    pub fn height_above_ground(&self, position: DVec3) -> Option<f64> {
+        self.ground_radius.map(|ground| position.length() - ground)
+    }
+}
+
 // ============================================================================
 // Plugin
 // ============================================================================
@@ -225,6 +282,7 @@ impl Plugin for FreelookCameraPlugin {
             .init_resource::<AltitudeRequest>()
             .init_resource::<HeadingRequest>()
             .init_resource::<TranslateRequest>()
+            .init_resource::<TerrainFollow>()
             .add_plugins(flycam::FlycamPlugin)
             .add_systems(
                 Update,
@@ -390,6 +448,15 @@ fn process_translate_request(
     }
 }
 
+/// Flight speed multiplier at an ECEF position: constant below 10 km
+/// altitude, then growing with it (capped), so flight stays controllable near
+/// the ground and still crosses continents from orbit.
+pub fn altitude_speed_factor(position: DVec3) -> f32 {
+    let altitude = (position.length() - veldera_constants::EARTH_RADIUS_M_F64).max(0.0);
+    let factor = ((altitude / 10000.0).max(1.0) + 1.0).powf(1.337) / 6.0;
+    factor.min(2600.0) as f32
+}
+
 /// Move an ECEF position `distance_m` metres along a compass bearing
 /// (clockwise from local north), staying on the same-radius sphere.
 /// The tangent basis matches the compass / shadow-bake convention
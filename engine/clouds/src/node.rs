@@ -1,6 +1,7 @@
 //! Render-graph nodes that drive the cloud passes.
 
 use bevy::{
+    camera::{MainPassResolutionOverride, Viewport},
     ecs::{query::QueryItem, system::lifetimeless::Read, world::World},
     pbr::ViewLightsUniformOffset,
     render::{
@@ -299,6 +300,7 @@ impl ViewNode for CloudCompositeNode {
         Read<DynamicUniformIndex<GpuCloudUniform>>,
         Read<ViewUniformOffset>,
         Read<AtmosphereTransformsOffset>,
+        Option<Read<MainPassResolutionOverride>>,
     );
 
     fn run(
@@ -313,6 +315,7 @@ impl ViewNode for CloudCompositeNode {
             cloud_offset,
             view_offset,
             transforms_offset,
+            resolution_override,
         ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
@@ -332,8 +335,10 @@ impl ViewNode for CloudCompositeNode {
         });
         let span = diagnostics.pass_span(pass.wgpu_pass(), "cloud_composite");
 
-        if let Some(viewport) = camera.viewport.as_ref() {
-            pass.set_camera_viewport(viewport);
+        if let Some(viewport) =
+            Viewport::from_viewport_and_override(camera.viewport.as_ref(), resolution_override)
+        {
+            pass.set_camera_viewport(&viewport);
         }
 
         pass.set_render_pipeline(composite_pipeline);
@@ -424,15 +429,22 @@ impl ViewNode for CloudShadowApplyNode {
         Read<ViewTarget>,
         Read<DynamicUniformIndex<GpuCloudUniform>>,
         Read<ViewUniformOffset>,
+        Option<Read<MainPassResolutionOverride>>,
     );
 
     fn run(
         &self,
         _graph: &mut RenderGraphContext,
         render_context: &mut RenderContext,
-        (camera, bind_groups, pipeline_ids, view_target, cloud_offset, view_offset): QueryItem<
-            Self::ViewQuery,
-        >,
+        (
+            camera,
+            bind_groups,
+            pipeline_ids,
+            view_target,
+            cloud_offset,
+            view_offset,
+            resolution_override,
+        ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
         let pipeline_cache = world.resource::<PipelineCache>();
@@ -451,8 +463,10 @@ impl ViewNode for CloudShadowApplyNode {
         });
         let span = diagnostics.pass_span(pass.wgpu_pass(), "cloud_shadow_apply");
 
-        if let Some(viewport) = camera.viewport.as_ref() {
-            pass.set_camera_viewport(viewport);
+        if let Some(viewport) =
+            Viewport::from_viewport_and_override(camera.viewport.as_ref(), resolution_override)
+        {
+            pass.set_camera_viewport(&viewport);
         }
 
         pass.set_render_pipeline(apply_pipeline);
@@ -485,6 +499,7 @@ impl ViewNode for CloudGodRaysNode {
         Read<ViewUniformOffset>,
         Read<DynamicUniformIndex<veldera_atmosphere::GpuAtmosphere>>,
         Read<AtmosphereTransformsOffset>,
+        Option<Read<MainPassResolutionOverride>>,
     );
 
     fn run(
@@ -500,6 +515,7 @@ impl ViewNode for CloudGodRaysNode {
             view_offset,
             atmosphere_offset,
             transforms_offset,
+            resolution_override,
         ): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
@@ -519,8 +535,10 @@ impl ViewNode for CloudGodRaysNode {
         });
         let span = diagnostics.pass_span(pass.wgpu_pass(), "cloud_god_rays");
 
-        if let Some(viewport) = camera.viewport.as_ref() {
-            pass.set_camera_viewport(viewport);
+        if let Some(viewport) =
+            Viewport::from_viewport_and_override(camera.viewport.as_ref(), resolution_override)
+        {
+            pass.set_camera_viewport(&viewport);
         }
 
         pass.set_render_pipeline(god_rays_pipeline);
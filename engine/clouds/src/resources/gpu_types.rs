@@ -268,6 +268,10 @@ pub struct GpuCloudUniform {
     pub cloud_march_max_distance: f32,
     pub aerial_lut_max_distance: f32,
     pub aerial_lut_fade_range: f32,
+    /// Decode of the aerial-view LUT's log in-scattering, mirrored from
+    /// `GpuAtmosphereSettings` (reduced-precision LUTs remap it to unorm).
+    pub aerial_lut_log_min: f32,
+    pub aerial_lut_log_range: f32,
     pub earth_shine_multiplier: f32,
     pub twilight_band_lo: f32,
     pub twilight_band_hi: f32,
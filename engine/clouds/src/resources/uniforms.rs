@@ -2,6 +2,7 @@
 //! reprojection bookkeeping and the climate-sim time state machine.
 
 use bevy::{
+    camera::MainPassResolutionOverride,
     ecs::{
         component::Component,
         entity::Entity,
@@ -11,7 +12,8 @@ use bevy::{
     render::{camera::ExtractedCamera, view::ExtractedView},
 };
 use veldera_atmosphere::{
-    ExtractedAtmosphere, ExtractedAtmosphereLights, SphericalAtmosphereCamera,
+    ExtractedAtmosphere, ExtractedAtmosphereLights, GpuAtmosphereSettings,
+    SphericalAtmosphereCamera,
 };
 
 use crate::{
@@ -65,8 +67,10 @@ pub fn prepare_cloud_uniforms(
         Option<&CloudCameraEcef>,
         Option<&CloudPrevFrame>,
         Option<&ExtractedCamera>,
+        Option<&MainPassResolutionOverride>,
         Option<&crate::CloudClimateMap>,
         Option<&CloudSimState>,
+        Option<&GpuAtmosphereSettings>,
     )>,
 ) {
     // Dominant-light direction: deferred to per-camera scope below
@@ -93,13 +97,19 @@ pub fn prepare_cloud_uniforms(
         cam_ecef,
         prev_state,
         camera,
+        resolution_override,
         climate_map,
         sim_state_prev,
+        atmosphere_settings,
     ) in &layers
     {
         let quality = cloud.quality;
-        let full_size = camera
-            .and_then(|c| c.physical_target_size)
+        // Under dynamic resolution the main pass (and so the depth the clouds
+        // test against) covers only the override's region; size everything
+        // from that so the cloud cost scales with the scene's.
+        let full_size = resolution_override
+            .map(|o| o.0)
+            .or_else(|| camera.and_then(|c| c.physical_target_size))
             .unwrap_or(UVec2::splat(1));
         let buffer_size = (full_size.as_vec2() * quality.resolution_scale())
             .max(Vec2::splat(1.0))
@@ -510,6 +520,8 @@ pub fn prepare_cloud_uniforms(
             cloud_march_max_distance: settings.cloud_march_max_distance,
             aerial_lut_max_distance: settings.aerial_lut_max_distance,
             aerial_lut_fade_range: settings.aerial_lut_fade_range,
+            aerial_lut_log_min: atmosphere_settings.map_or(0.0, |a| a.aerial_view_lut_log_min),
+            aerial_lut_log_range: atmosphere_settings.map_or(1.0, |a| a.aerial_view_lut_log_range),
             earth_shine_multiplier: settings.earth_shine_multiplier,
             twilight_band_lo: settings.twilight_band_lo,
             twilight_band_hi: settings.twilight_band_hi,
@@ -77,14 +77,16 @@ fn sample_sky_view(local_r: f32, dir_as: vec3<f32>) -> vec3<f32> {
 fn sample_aerial_inscattering(uv: vec2<f32>, t: f32) -> vec3<f32> {
     // Atmosphere uses `aerial_view_lut_max_distance`, but that uniform isn't
     // in our bind group; we mirror it as `cloud.aerial_lut_max_distance` in
-    // constants.wgsl. The texture stores log(inscattering); recover with exp.
+    // constants.wgsl. The texture stores log(inscattering), remapped by
+    // `aerial_lut_log_min`/`_range` at reduced precision; recover with exp.
     let num_slices = f32(textureDimensions(aerial_view_lut).z);
     let max_distance = cloud.aerial_lut_max_distance;
     let depth = saturate(t / max_distance - 0.5 / num_slices);
     let sample = textureSampleLevel(aerial_view_lut, lut_sampler, vec3(uv, depth), 0.0);
     let t_slice = max_distance / num_slices;
     let fade = saturate(t / t_slice);
-    return exp(sample.rgb) * fade;
+    let log_inscattering = sample.rgb * cloud.aerial_lut_log_range + cloud.aerial_lut_log_min;
+    return exp(log_inscattering) * fade;
 }
 
 // Standard Henyey-Greenstein phase function.
@@ -179,6 +179,8 @@ struct CloudUniform {
     cloud_march_max_distance: f32,
     aerial_lut_max_distance: f32,
     aerial_lut_fade_range: f32,
+    aerial_lut_log_min: f32,
+    aerial_lut_log_range: f32,
     earth_shine_multiplier: f32,
     twilight_band_lo: f32,
     twilight_band_hi: f32,
@@ -0,0 +1,214 @@
+//! GPU device-loss detection and resource rebuilds.
+//!
+//! A driver reset or GPU hang takes the wgpu device down, and everything
+//! uploaded to it with it. [`GpuRecoveryPlugin`] registers a device-lost
+//! callback on the render device, forwards the loss to the main world, and
+//! records it in [`GpuHealth`] for the log and the UI.
+//!
+//! Recovering from a lost device without a restart is not supported. wgpu
+//! can't revive a lost device, and Bevy's renderer holds its device in too
+//! many places (pipeline cache, bind group layouts, mesh allocator) to swap a
+//! new one in, so after a loss the app has to be restarted; [`GpuHealth`]
+//! says so for the UI to pass on, and rebuilds are refused from then on, as
+//! they would only upload onto the dead device.
+//!
+//! What [`GpuHealth::request_rebuild`] does cover is a surviving device that
+//! dropped resources: the engine rebuilds its own GPU resources from what it
+//! still holds on the CPU, never the network. Every loaded terrain node is
+//! respawned on fresh assets from the meshes and textures the streamer
+//! retains (see [`TerrainReuploadRequest`]), and each atmosphere environment
+//! probe gets a fresh cubemap, re-rendered from the LUTs on its next frame.
+//! The LUTs themselves are drawn from Bevy's texture cache every frame and
+//! need nothing.
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    light::GeneratedEnvironmentMapLight,
+    prelude::*,
+    render::{RenderApp, RenderStartup, renderer::RenderDevice},
+};
+use veldera_atmosphere::{AtmosphereEnvironmentMap, SphericalAtmosphereEnvironmentMapLight};
+
+use crate::terrain::lod::TerrainReuploadRequest;
+
+/// Plugin for device-loss detection and GPU resource rebuilds.
+pub struct GpuRecoveryPlugin;
+
+impl Plugin for GpuRecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        let signal = DeviceLossSignal::default();
+        app.insert_resource(signal.clone())
+            .init_resource::<GpuHealth>()
+            .add_systems(Update, (poll_device_loss, rebuild_gpu_resources).chain());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(signal)
+            .add_systems(RenderStartup, watch_device_loss);
+    }
+}
+
+/// A reported loss of the render device.
+#[derive(Clone, Debug)]
+pub struct DeviceLoss {
+    /// wgpu's reason for the loss.
+    pub reason: String,
+    /// The backend's description of it.
+    pub message: String,
+    /// App time when the main world noticed it (s).
+    pub at_secs: f64,
+}
+
+/// Render-device health, and the rebuild of the engine's GPU resources.
+#[derive(Resource, Default)]
+pub struct GpuHealth {
+    /// The device loss, if one has been reported.
+    loss: Option<DeviceLoss>,
+    /// Whether a rebuild runs next frame.
+    rebuild_requested: bool,
+    /// Rebuilds run so far.
+    rebuilds: u32,
+}
+
+impl GpuHealth {
+    /// The device loss, if one has been reported. The renderer can't recover
+    /// from it in-process; the app needs a restart.
+    pub fn loss(&self) -> Option<&DeviceLoss> {
+        self.loss.as_ref()
+    }
+
+    /// Rebuild the engine's GPU resources next frame. Ignored once the
+    /// device is lost, as there is nothing left to upload to.
+    pub fn request_rebuild(&mut self) {
+        self.rebuild_requested = self.loss.is_none();
+    }
+
+    /// Rebuilds run so far.
+    pub fn rebuilds(&self) -> u32 {
+        self.rebuilds
+    }
+}
+
+/// Hands a loss from wgpu's callback, which runs wherever the backend
+/// notices it, to the main world. Shared by both worlds.
+#[derive(Resource, Clone, Default)]
+struct DeviceLossSignal(Arc<Mutex<Option<(String, String)>>>);
+
+/// Register the device-lost callback on the render device.
+fn watch_device_loss(device: Res<RenderDevice>, signal: Res<DeviceLossSignal>) {
+    let signal = signal.0.clone();
+    device
+        .wgpu_device()
+        .set_device_lost_callback(move |reason, message| {
+            if let Ok(mut slot) = signal.lock() {
+                *slot = Some((format!("{reason:?}"), message));
+            }
+        });
+}
+
+/// Record a reported loss, cancelling any pending rebuild.
+fn poll_device_loss(signal: Res<DeviceLossSignal>, mut health: ResMut<GpuHealth>, time: Res<Time>) {
+    let Some((reason, message)) = signal.0.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    tracing::error!("GPU device lost ({reason}): {message}");
+    health.loss = Some(DeviceLoss {
+        reason,
+        message,
+        at_secs: time.elapsed_secs_f64(),
+    });
+    health.rebuild_requested = false;
+}
+
+/// Re-upload the terrain and recreate the atmosphere probes' cubemaps.
+fn rebuild_gpu_resources(
+    mut health: ResMut<GpuHealth>,
+    terrain: Option<ResMut<TerrainReuploadRequest>>,
+    probes: Query<
+        Entity,
+        (
+            With<SphericalAtmosphereEnvironmentMapLight>,
+            With<AtmosphereEnvironmentMap>,
+        ),
+    >,
+    mut commands: Commands,
+) {
+    if !std::mem::take(&mut health.rebuild_requested) {
+        return;
+    }
+    if let Some(mut terrain) = terrain {
+        terrain.wanted = true;
+    }
+    // The atmosphere recreates a probe's cubemap when it finds it missing.
+    for entity in &probes {
+        commands
+            .entity(entity)
+            .remove::<(AtmosphereEnvironmentMap, GeneratedEnvironmentMapLight)>();
+    }
+    health.rebuilds += 1;
+    tracing::info!("Rebuilding GPU resources (rebuild {})", health.rebuilds);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An app with the recovery systems, an atmosphere probe with its
+    /// cubemap, and the terrain's reupload request.
+    fn app() -> (App, Entity) {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<TerrainReuploadRequest>()
+            .add_plugins(GpuRecoveryPlugin);
+        let probe = app
+            .world_mut()
+            .spawn((
+                SphericalAtmosphereEnvironmentMapLight::default(),
+                AtmosphereEnvironmentMap {
+                    environment_map: Handle::default(),
+                    size: UVec2::splat(64),
+                },
+            ))
+            .id();
+        (app, probe)
+    }
+
+    #[test]
+    fn rebuild_reuploads_terrain_and_recreates_probe_cubemaps() {
+        let (mut app, probe) = app();
+        app.world_mut()
+            .resource_mut::<GpuHealth>()
+            .request_rebuild();
+        app.update();
+
+        assert!(app.world().resource::<TerrainReuploadRequest>().wanted);
+        assert!(app.world().get::<AtmosphereEnvironmentMap>(probe).is_none());
+        assert_eq!(app.world().resource::<GpuHealth>().rebuilds(), 1);
+    }
+
+    #[test]
+    fn a_lost_device_refuses_rebuilds() {
+        let (mut app, probe) = app();
+        *app.world().resource::<DeviceLossSignal>().0.lock().unwrap() =
+            Some(("Unknown".into(), "device hung".into()));
+        app.world_mut()
+            .resource_mut::<GpuHealth>()
+            .request_rebuild();
+        app.update();
+
+        let health = app.world().resource::<GpuHealth>();
+        assert_eq!(health.loss().unwrap().message, "device hung");
+        assert_eq!(health.rebuilds(), 0);
+        assert!(!app.world().resource::<TerrainReuploadRequest>().wanted);
+        assert!(app.world().get::<AtmosphereEnvironmentMap>(probe).is_some());
+
+        app.world_mut()
+            .resource_mut::<GpuHealth>()
+            .request_rebuild();
+        app.update();
+        assert_eq!(app.world().resource::<GpuHealth>().rebuilds(), 0);
+    }
+}
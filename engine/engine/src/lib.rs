@@ -3,8 +3,9 @@
 //! Re-exports every engine crate under a single dependency and namespace, so a
 //! client can depend on `veldera_engine` alone rather than wiring up each crate.
 //! It also owns the cross-cutting support that has no better home — the custom
-//! [`assets`] loaders and the in-game CPU [`profiler`] — and bundles the
-//! always-on infrastructure plugins into [`EnginePlugins`].
+//! [`assets`] loaders, the in-game CPU [`profiler`], and GPU device-loss
+//! [recovery](gpu_recovery) — and bundles the always-on infrastructure plugins
+//! into [`EnginePlugins`].
 //!
 //! The layered engine crates remain independently usable; this crate is a
 //! convenience, not a requirement.
@@ -16,11 +17,15 @@ pub use veldera_constants as constants;
 pub use veldera_geo as geo;
 pub use veldera_input as input;
 pub use veldera_physics as physics;
+pub use veldera_resolution as resolution;
 pub use veldera_sky as sky;
 pub use veldera_terrain as terrain;
 
 pub mod assets;
+pub mod gpu_recovery;
+pub mod panorama;
 pub mod profiler;
+pub mod tonemap_compare;
 
 use bevy::{
     app::{PluginGroup, PluginGroupBuilder},
@@ -34,14 +39,16 @@ use bevy::{
 
 use camera::FlightCamera;
 use geo::floating_origin::FloatingOriginCamera;
+use resolution::DynamicResolution;
 
 /// The engine's always-on, configuration-free infrastructure plugins.
 ///
 /// Covers the floating-origin world frame, the abstract input-intent layer,
-/// custom asset loaders, and the CPU profiler — everything a client needs
-/// regardless of which subsystems it enables. The freelook camera is added
-/// separately (gameplay clients layer their own mode machine over it); the rest
-/// of the configurable subsystems live in [`EngineWorldPlugins`].
+/// custom asset loaders, the CPU profiler, and GPU device-loss recovery —
+/// everything a client needs regardless of which subsystems it enables. The
+/// freelook camera is added separately (gameplay clients layer their own mode
+/// machine over it); the rest of the configurable subsystems live in
+/// [`EngineWorldPlugins`].
 pub struct EnginePlugins;
 
 impl PluginGroup for EnginePlugins {
@@ -51,6 +58,7 @@ impl PluginGroup for EnginePlugins {
             .add(input::InputIntentPlugin)
             .add(assets::AssetsPlugin)
             .add(profiler::ProfilerPlugin)
+            .add(gpu_recovery::GpuRecoveryPlugin)
     }
 }
 
@@ -58,12 +66,13 @@ impl PluginGroup for EnginePlugins {
 /// at its canonical engine asset paths.
 ///
 /// Composes [`TerrainPlugins`](terrain::TerrainPlugins), the physics integration,
-/// and [`SkyPlugins`](sky::SkyPlugins) — the block both the game and the
-/// reference viewer add identically. Each crate group defaults to its paths in
-/// the shared engine asset subtree; a client with a different layout adds the
-/// crate groups (or their constituents) individually instead. The camera is
-/// deliberately excluded so each client supplies its own (the game wraps the
-/// freelook camera in a mode machine).
+/// [`SkyPlugins`](sky::SkyPlugins), dynamic resolution scaling,
+/// [panorama capture](panorama), and the
+/// [tonemapper comparison](tonemap_compare) — the block both the game and the reference
+/// viewer add identically. Each crate group defaults to its paths in the shared
+/// engine asset subtree; a client with a different layout adds the crate groups
+/// (or their constituents) individually instead. The camera is deliberately excluded so each client supplies its own
+/// (the game wraps the freelook camera in a mode machine).
 pub struct EngineWorldPlugins;
 
 impl PluginGroup for EngineWorldPlugins {
@@ -72,17 +81,24 @@ impl PluginGroup for EngineWorldPlugins {
             .add_group(terrain::TerrainPlugins)
             .add(physics::PhysicsIntegrationPlugin::default())
             .add_group(sky::SkyPlugins)
+            .add(resolution::DynamicResolutionPlugin::default())
+            .add(panorama::PanoramaPlugin)
+            .add(tonemap_compare::TonemapComparePlugin)
     }
 }
 
+/// The world camera's exposure (EV100), calibrated for daytime.
+pub const DEFAULT_EV100: f32 = 13.0;
+
 /// The universal floating-origin camera rig, ready to spawn over an ECEF
 /// `position` looking along `direction` with local `up` (see
 /// [`enu_look_direction`](geo::coords::enu_look_direction)).
 ///
 /// Bundles the camera, its perspective projection (from `fov_deg`), the HDR +
-/// ACES + bloom pipeline the atmosphere needs, and the floating-origin and
-/// flight-camera components. It deliberately omits the atmosphere and cloud
-/// bundles (and any gameplay components): the caller composes those on top, e.g.
+/// ACES + bloom pipeline the atmosphere needs, dynamic resolution, and the
+/// floating-origin and flight-camera components. It deliberately omits the
+/// atmosphere and cloud bundles (and any gameplay components): the caller
+/// composes those on top, e.g.
 /// `commands.spawn((world_camera_bundle(p, d, u, fov), AtmosphereBundle::from_config(..), clouds))`,
 /// since a headless or gameplay client may want different extras.
 pub fn world_camera_bundle(
@@ -108,9 +124,14 @@ pub fn world_camera_bundle(
         Hdr,
         // Fixed exposure calibrated for daytime; CPU sun extinction darkens the
         // scene through twilight, so no eye-adaptation curve is needed.
-        Exposure { ev100: 13.0 },
+        Exposure {
+            ev100: DEFAULT_EV100,
+        },
         // Bloom gives the sun a natural glow.
         Bloom::NATURAL,
+        // Render the main pass below native resolution when the GPU falls
+        // behind its frame-time budget.
+        DynamicResolution::default(),
         FloatingOriginCamera::new(position),
         FlightCamera {
             direction,
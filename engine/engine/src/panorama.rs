@@ -0,0 +1,574 @@
+//! 360° panorama capture.
+//!
+//! Renders the view from the floating-origin camera's position as six 90°
+//! cube faces and stitches them into one equirectangular PNG (2:1, north in
+//! the middle, the horizon level), for sharing or for use as a skybox.
+//!
+//! Each face is its own camera rendering into an image, carrying copies of
+//! the main camera's atmosphere, clouds, tone mapping, exposure, and
+//! environment map, so the renderer builds sky-view and aerial-view LUTs for
+//! every face. All faces share one atmosphere reference rotation (the local
+//! level frame), so their sky-view LUTs are baked in the same frame and the
+//! sky lines up across the seams; bloom stays off for the same reason.
+//!
+//! While a capture runs, the terrain [`LodFocus`] reaches out to the horizon,
+//! so tiles behind the main camera stream in too. The faces are grabbed once
+//! loading settles (or after a timeout) and stitched on a background thread.
+//! The camera should hold still meanwhile. Native only.
+
+use std::path::PathBuf;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    camera::{Exposure, RenderTarget},
+    core_pipeline::tonemapping::Tonemapping,
+    light::EnvironmentMapLight,
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::{
+            Hdr,
+            screenshot::{Screenshot, ScreenshotCaptured},
+        },
+    },
+};
+use veldera_atmosphere::{AtmosphereSettings, SphericalAtmosphere, SphericalAtmosphereCamera};
+use veldera_clouds::CloudLayers;
+
+use crate::{
+    constants::EARTH_RADIUS_M_F64,
+    geo::{coords::RadialFrame, floating_origin::FloatingOriginCamera},
+    terrain::lod::{LodFocus, LodState},
+};
+
+/// Default panorama width (px); the height is half of it.
+pub const DEFAULT_PANORAMA_WIDTH: u32 = 8192;
+
+/// Smallest and largest panorama widths accepted.
+pub const PANORAMA_WIDTH_RANGE: (u32, u32) = (1024, 16384);
+
+/// Frames to render before grabbing the faces, however settled the terrain,
+/// so the per-face LUTs and environment map exist.
+const WARMUP_FRAMES: u32 = 10;
+
+/// Consecutive frames without terrain loads that count as settled.
+const SETTLED_FRAMES: u32 = 30;
+
+/// Longest wait for the terrain to settle before capturing anyway (s).
+const STREAMING_TIMEOUT_SECS: f32 = 30.0;
+
+/// Terrain focus radius beyond the horizon distance (m).
+const FOCUS_MARGIN_M: f64 = 10_000.0;
+
+/// Plugin for 360° panorama capture.
+pub struct PanoramaPlugin;
+
+impl Plugin for PanoramaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PanoramaCapture>().add_systems(
+            Update,
+            (start_capture, sync_face_views, advance_capture).chain(),
+        );
+    }
+}
+
+/// Where a capture is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanoramaStage {
+    /// Waiting for the terrain all around to load.
+    Streaming,
+    /// Reading the rendered faces back.
+    Capturing,
+    /// Stitching and saving on a background thread.
+    Stitching,
+}
+
+/// Panorama capture requests and progress.
+#[derive(Resource, Default)]
+pub struct PanoramaCapture {
+    /// Capture to start, as `(width, path)`.
+    requested: Option<(u32, PathBuf)>,
+    /// The capture in progress.
+    active: Option<ActiveCapture>,
+    /// How the last capture ended: the saved path, or what went wrong.
+    last_result: Option<Result<PathBuf, String>>,
+}
+
+impl PanoramaCapture {
+    /// Capture a panorama `width` pixels wide (clamped to
+    /// [`PANORAMA_WIDTH_RANGE`] and rounded to a multiple of 4) and save it
+    /// to `path` as a PNG. Ignored while a capture runs.
+    pub fn request(&mut self, width: u32, path: PathBuf) {
+        if self.active.is_none() {
+            let width = width.clamp(PANORAMA_WIDTH_RANGE.0, PANORAMA_WIDTH_RANGE.1) / 4 * 4;
+            self.requested = Some((width, path));
+        }
+    }
+
+    /// The stage of the capture in progress, if any.
+    pub fn stage(&self) -> Option<PanoramaStage> {
+        if self.requested.is_some() {
+            return Some(PanoramaStage::Streaming);
+        }
+        self.active.as_ref().map(|active| match active.stage {
+            Stage::Streaming { .. } => PanoramaStage::Streaming,
+            Stage::Capturing => PanoramaStage::Capturing,
+            Stage::Stitching(_) => PanoramaStage::Stitching,
+        })
+    }
+
+    /// How the last capture ended: the saved path, or what went wrong.
+    pub fn last_result(&self) -> Option<&Result<PathBuf, String>> {
+        self.last_result.as_ref()
+    }
+}
+
+/// A capture in progress.
+struct ActiveCapture {
+    path: PathBuf,
+    width: u32,
+    /// Face cameras, in [`FACES`] order.
+    cameras: Vec<Entity>,
+    /// Face render targets, in [`FACES`] order.
+    images: Vec<Handle<Image>>,
+    /// Read-back face pixels (RGBA8), in [`FACES`] order.
+    pixels: Vec<Option<Vec<u8>>>,
+    /// The terrain focus before the capture, restored after it.
+    previous_focus: LodFocus,
+    stage: Stage,
+}
+
+enum Stage {
+    Streaming {
+        frames: u32,
+        settled_frames: u32,
+        elapsed_secs: f32,
+    },
+    Capturing,
+    Stitching(std::thread::JoinHandle<Result<PathBuf, String>>),
+}
+
+/// A cube face: its forward and up directions in the local (east, north,
+/// up) frame.
+struct Face {
+    forward: Vec3,
+    up: Vec3,
+}
+
+impl Face {
+    /// The face's right direction, as a camera sees it.
+    fn right(&self) -> Vec3 {
+        self.forward.cross(self.up)
+    }
+}
+
+/// The six faces: north, east, south, west, up, down.
+const FACES: [Face; 6] = [
+    Face {
+        forward: Vec3::Y,
+        up: Vec3::Z,
+    },
+    Face {
+        forward: Vec3::X,
+        up: Vec3::Z,
+    },
+    Face {
+        forward: Vec3::NEG_Y,
+        up: Vec3::Z,
+    },
+    Face {
+        forward: Vec3::NEG_X,
+        up: Vec3::Z,
+    },
+    Face {
+        forward: Vec3::Z,
+        up: Vec3::NEG_Y,
+    },
+    Face {
+        forward: Vec3::NEG_Z,
+        up: Vec3::Y,
+    },
+];
+
+/// Marker for a panorama face camera.
+#[derive(Component)]
+struct PanoramaFace;
+
+/// The main camera's view components, as copied to the faces.
+type MainView<'a> = (
+    &'a FloatingOriginCamera,
+    &'a SphericalAtmosphere,
+    &'a AtmosphereSettings,
+    Option<&'a CloudLayers>,
+    &'a Tonemapping,
+    &'a Exposure,
+    Option<&'a EnvironmentMapLight>,
+);
+
+/// Spawn the face cameras for a requested capture.
+fn start_capture(
+    mut commands: Commands,
+    mut capture: ResMut<PanoramaCapture>,
+    mut images: ResMut<Assets<Image>>,
+    mut focus: ResMut<LodFocus>,
+    main: Query<MainView, Without<PanoramaFace>>,
+) {
+    if capture.active.is_some() {
+        return;
+    }
+    let Some((width, path)) = capture.requested.take() else {
+        return;
+    };
+    // Stitching runs on a thread, and browsers can't write files anyway.
+    if cfg!(target_family = "wasm") {
+        capture.last_result = Some(Err("Panorama capture is native only".to_string()));
+        return;
+    }
+    let Ok((camera, atmosphere, settings, clouds, tonemapping, exposure, environment)) =
+        main.single()
+    else {
+        capture.last_result = Some(Err("No camera to capture from".to_string()));
+        return;
+    };
+
+    let frame = RadialFrame::from_ecef_position(camera.position);
+    let level = Transform::default()
+        .looking_to(frame.north, frame.up)
+        .rotation;
+    // A face spans a quarter of the panorama's width.
+    let face_size = width / 4;
+    let mut cameras = Vec::with_capacity(FACES.len());
+    let mut handles = Vec::with_capacity(FACES.len());
+    for (index, face) in FACES.iter().enumerate() {
+        let image = images.add(Image::new_target_texture(
+            face_size,
+            face_size,
+            TextureFormat::Rgba8UnormSrgb,
+            None,
+        ));
+        let mut entity = commands.spawn((
+            Name::new(format!("Panorama face {index}")),
+            PanoramaFace,
+            Camera3d::default(),
+            Camera {
+                // Before the main camera, which draws the UI on top.
+                order: -1 - index as isize,
+                ..default()
+            },
+            RenderTarget::Image(image.clone().into()),
+            // The faces sit at the floating origin, i.e. at the main camera.
+            Transform::default()
+                .looking_to(to_world(&frame, face.forward), to_world(&frame, face.up)),
+            Projection::Perspective(PerspectiveProjection {
+                fov: std::f32::consts::FRAC_PI_2,
+                aspect_ratio: 1.0,
+                near: 1.0,
+                far: 100_000_000.0,
+            }),
+            Hdr,
+            *tonemapping,
+            *exposure,
+            atmosphere.clone(),
+            settings.clone(),
+            SphericalAtmosphereCamera {
+                reference_rotation: Some(level),
+                ..SphericalAtmosphereCamera::from_ecef(camera.position)
+            },
+        ));
+        if let Some(clouds) = clouds {
+            entity.insert(clouds.clone());
+        }
+        if let Some(environment) = environment {
+            entity.insert(environment.clone());
+        }
+        cameras.push(entity.id());
+        handles.push(image);
+    }
+
+    // Keep the terrain out to the horizon loaded, whichever way it lies.
+    let altitude = (camera.position.length() - EARTH_RADIUS_M_F64).max(0.0);
+    let horizon = (altitude * (2.0 * EARTH_RADIUS_M_F64 + altitude)).sqrt();
+    let previous_focus = *focus;
+    *focus = LodFocus {
+        point: Some(camera.position),
+        radius: horizon + FOCUS_MARGIN_M,
+    };
+
+    tracing::info!("Capturing a {width}×{} panorama", width / 2);
+    capture.active = Some(ActiveCapture {
+        path,
+        width,
+        cameras,
+        images: handles,
+        pixels: vec![None; FACES.len()],
+        previous_focus,
+        stage: Stage::Streaming {
+            frames: 0,
+            settled_frames: 0,
+            elapsed_secs: 0.0,
+        },
+    });
+}
+
+/// Keep the faces' atmosphere in step with the main camera's position and
+/// carry over live edits while the capture runs.
+#[allow(clippy::type_complexity)]
+fn sync_face_views(
+    mut commands: Commands,
+    main: Query<
+        (
+            &FloatingOriginCamera,
+            Ref<SphericalAtmosphere>,
+            Ref<AtmosphereSettings>,
+            Option<Ref<CloudLayers>>,
+            Option<Ref<EnvironmentMapLight>>,
+        ),
+        Without<PanoramaFace>,
+    >,
+    mut faces: Query<
+        (
+            Entity,
+            &mut SphericalAtmosphereCamera,
+            &mut SphericalAtmosphere,
+            &mut AtmosphereSettings,
+            Option<&mut CloudLayers>,
+            Has<EnvironmentMapLight>,
+        ),
+        With<PanoramaFace>,
+    >,
+) {
+    let Ok((camera, atmosphere, settings, clouds, environment)) = main.single() else {
+        return;
+    };
+    for (entity, mut atmo_camera, mut face_atmosphere, mut face_settings, face_clouds, lit) in
+        &mut faces
+    {
+        *atmo_camera = SphericalAtmosphereCamera {
+            reference_rotation: atmo_camera.reference_rotation,
+            ..SphericalAtmosphereCamera::from_ecef(camera.position)
+        };
+        if atmosphere.is_changed() {
+            *face_atmosphere = SphericalAtmosphere::clone(&atmosphere);
+        }
+        if settings.is_changed() {
+            *face_settings = AtmosphereSettings::clone(&settings);
+        }
+        if let (Some(clouds), Some(mut face_clouds)) = (&clouds, face_clouds)
+            && clouds.is_changed()
+        {
+            *face_clouds = CloudLayers::clone(clouds);
+        }
+        // The environment map appears once its first bake is filtered.
+        if let Some(environment) = &environment
+            && (environment.is_changed() || !lit)
+        {
+            commands
+                .entity(entity)
+                .insert(EnvironmentMapLight::clone(environment));
+        }
+    }
+}
+
+/// Wait for the terrain, read the faces back, then stitch and save them.
+fn advance_capture(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    lod_state: Res<LodState>,
+    mut focus: ResMut<LodFocus>,
+    mut capture: ResMut<PanoramaCapture>,
+) {
+    let capture = &mut *capture;
+    let Some(active) = &mut capture.active else {
+        return;
+    };
+    match &mut active.stage {
+        Stage::Streaming {
+            frames,
+            settled_frames,
+            elapsed_secs,
+        } => {
+            *frames += 1;
+            *elapsed_secs += time.delta_secs();
+            if lod_state.loading_node_count() == 0 {
+                *settled_frames += 1;
+            } else {
+                *settled_frames = 0;
+            }
+            let settled = *settled_frames >= SETTLED_FRAMES;
+            let timed_out = *elapsed_secs >= STREAMING_TIMEOUT_SECS;
+            if *frames < WARMUP_FRAMES || !(settled || timed_out) {
+                return;
+            }
+            if !settled {
+                tracing::warn!("Terrain still loading; capturing the panorama anyway");
+            }
+            for (index, image) in active.images.iter().enumerate() {
+                commands.spawn(Screenshot::image(image.clone())).observe(
+                    move |captured: On<ScreenshotCaptured>,
+                          mut capture: ResMut<PanoramaCapture>| {
+                        if let Some(active) = &mut capture.active {
+                            active.pixels[index] = captured.image.data.clone();
+                        }
+                    },
+                );
+            }
+            active.stage = Stage::Capturing;
+        }
+        Stage::Capturing => {
+            if active.pixels.iter().any(Option::is_none) {
+                return;
+            }
+            for &camera in &active.cameras {
+                commands.entity(camera).despawn();
+            }
+            *focus = active.previous_focus;
+            let faces: Vec<Vec<u8>> = active.pixels.iter_mut().filter_map(Option::take).collect();
+            let width = active.width;
+            let path = active.path.clone();
+            active.stage = Stage::Stitching(std::thread::spawn(move || {
+                let pixels = stitch(&faces, width);
+                save_png(pixels, width, width / 2, &path).map(|()| path)
+            }));
+        }
+        Stage::Stitching(thread) => {
+            if !thread.is_finished() {
+                return;
+            }
+            let Some(ActiveCapture {
+                stage: Stage::Stitching(thread),
+                ..
+            }) = capture.active.take()
+            else {
+                return;
+            };
+            let result = thread
+                .join()
+                .unwrap_or_else(|_| Err("Stitching panicked".to_string()));
+            match &result {
+                Ok(path) => tracing::info!("Saved panorama to {}", path.display()),
+                Err(e) => tracing::error!("Failed to save panorama: {e}"),
+            }
+            capture.last_result = Some(result);
+        }
+    }
+}
+
+/// Express a local (east, north, up) direction in world space.
+fn to_world(frame: &RadialFrame, local: Vec3) -> Vec3 {
+    frame.east * local.x + frame.north * local.y + frame.up * local.z
+}
+
+/// Resample the faces (RGBA8, in [`FACES`] order, each `width / 4` square)
+/// into a `width × width / 2` equirectangular image in the local frame, with
+/// north in the middle column.
+fn stitch(faces: &[Vec<u8>], width: u32) -> Vec<u8> {
+    let height = width / 2;
+    let face_size = width / 4;
+    let mut pixels = vec![0; width as usize * height as usize * 4];
+    for y in 0..height {
+        // Latitude at the pixel centre, +90° at the top.
+        let lat = std::f32::consts::PI * (0.5 - (y as f32 + 0.5) / height as f32);
+        for x in 0..width {
+            // Longitude from north, eastwards positive.
+            let lon = std::f32::consts::TAU * ((x as f32 + 0.5) / width as f32 - 0.5);
+            let local = Vec3::new(lat.cos() * lon.sin(), lat.cos() * lon.cos(), lat.sin());
+            // The face looking most directly along the ray contains it.
+            let (index, face) = FACES
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| local.dot(a.forward).total_cmp(&local.dot(b.forward)))
+                .expect("there are six faces");
+            let depth = local.dot(face.forward);
+            // Image coordinates: right and down from the top-left corner.
+            let u = (local.dot(face.right()) / depth * 0.5 + 0.5) * face_size as f32;
+            let v = (0.5 - local.dot(face.up) / depth * 0.5) * face_size as f32;
+            let colour = sample_bilinear(&faces[index], face_size, u, v);
+            let offset = (y as usize * width as usize + x as usize) * 4;
+            pixels[offset..offset + 4].copy_from_slice(&colour);
+        }
+    }
+    pixels
+}
+
+/// Bilinearly sample a square RGBA8 image at pixel coordinates `(u, v)`,
+/// clamping at the edges.
+fn sample_bilinear(image: &[u8], size: u32, u: f32, v: f32) -> [u8; 4] {
+    let max = size as f32 - 1.0;
+    let x = (u - 0.5).clamp(0.0, max);
+    let y = (v - 0.5).clamp(0.0, max);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(size - 1), (y0 + 1).min(size - 1));
+    let (fx, fy) = (x.fract(), y.fract());
+    let texel = |x: u32, y: u32, channel: usize| {
+        f32::from(image[(y as usize * size as usize + x as usize) * 4 + channel])
+    };
+    std::array::from_fn(|channel| {
+        let top = texel(x0, y0, channel) * (1.0 - fx) + texel(x1, y0, channel) * fx;
+        let bottom = texel(x0, y1, channel) * (1.0 - fx) + texel(x1, y1, channel) * fx;
+        (top * (1.0 - fy) + bottom * fy).round() as u8
+    })
+}
+
+/// Save RGBA8 `pixels` as a PNG at `path`, creating its directory.
+fn save_png(
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+    }
+    let image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    image
+        .try_into_dynamic()
+        .map_err(|e| e.to_string())?
+        .save(path)
+        .map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_form_a_right_handed_cube() {
+        for face in &FACES {
+            assert_eq!(face.forward.dot(face.up), 0.0);
+            assert_eq!(face.right().length(), 1.0);
+        }
+        // North's right is east, as seen looking north.
+        assert_eq!(FACES[0].right(), Vec3::X);
+    }
+
+    #[test]
+    fn stitches_each_direction_from_its_face() {
+        // Six 4×4 faces, each a flat shade of its index.
+        let faces: Vec<Vec<u8>> = (0..6u8)
+            .map(|index| [index * 40, 0, 0, 255].repeat(16))
+            .collect();
+        let width = 16;
+        let pixels = stitch(&faces, width);
+        let at = |x: u32, y: u32| pixels[((y * width + x) * 4) as usize];
+        // The middle row runs south, west, north, east, south.
+        assert_eq!(at(8, 4), 0); // North.
+        assert_eq!(at(12, 4), 40); // East, a quarter turn right.
+        assert_eq!(at(0, 4), 80); // South, at the edges.
+        assert_eq!(at(4, 4), 120); // West.
+        assert_eq!(at(8, 0), 160); // Up, the top row.
+        assert_eq!(at(8, 7), 200); // Down, the bottom row.
+    }
+}
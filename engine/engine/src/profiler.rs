@@ -16,18 +16,32 @@
 //! officially recommends those external tools, but neither is
 //! viewable in-process. This module keeps profiling visible in the
 //! same debug overlay as the rest of the diagnostics.
+//!
+//! For frame-by-frame attribution it can also record every span to a
+//! Chrome trace file ([`start_chrome_trace`], behind the client's
+//! `--trace-chrome` flag), viewable in `chrome://tracing` or Perfetto. The
+//! same layer stays installed but idle when no trace was started. Tracy
+//! captures go through Bevy's own `trace_tracy` feature instead.
 
 #[cfg(not(target_family = "wasm"))]
 mod native {
     use std::{
+        cell::Cell,
         collections::HashMap,
-        sync::{Mutex, OnceLock},
+        fmt::Write as _,
+        fs::File,
+        io::{self, BufWriter, Write as _},
+        path::Path,
+        sync::{
+            Mutex, OnceLock,
+            atomic::{AtomicU64, Ordering},
+        },
         time::{Duration, Instant},
     };
 
     use bevy::{
-        app::{App, Last, Plugin},
-        ecs::{resource::Resource, system::ResMut},
+        app::{App, AppExit, Last, Plugin},
+        ecs::{message::MessageReader, resource::Resource, system::ResMut},
         log::BoxedLayer,
     };
     use tracing::{
@@ -161,10 +175,231 @@ mod native {
         }
     }
 
+    // ========================================================================
+    // Chrome trace export
+    // ========================================================================
+
+    /// The trace file being written, once [`start_chrome_trace`] has run.
+    static CHROME_TRACE: OnceLock<ChromeTrace> = OnceLock::new();
+
+    /// Source of the small per-thread ids Chrome traces group events by.
+    static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+    thread_local! {
+        static THREAD_ID: Cell<Option<u64>> = const { Cell::new(None) };
+    }
+
+    struct ChromeTrace {
+        start: Instant,
+        /// `None` once the trace has been closed at exit; spans that end
+        /// after that (render thread teardown) are dropped.
+        out: Mutex<Option<BufWriter<File>>>,
+    }
+
+    /// Start recording every span to a Chrome trace (JSON array format) at
+    /// `path`. Call it before `LogPlugin` builds so startup is captured; the
+    /// file is finished when the app exits.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be created, or a trace is already being
+    /// recorded.
+    pub fn start_chrome_trace(path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(b"[\n")?;
+        CHROME_TRACE
+            .set(ChromeTrace {
+                start: Instant::now(),
+                out: Mutex::new(Some(out)),
+            })
+            .map_err(|_| io::Error::other("a Chrome trace is already being recorded"))
+    }
+
+    /// Per-span data for the trace: the event name and its recorded fields,
+    /// already formatted as the body of a JSON object.
+    struct TraceSpan {
+        name: String,
+        args: String,
+        entered_at: Option<Instant>,
+    }
+
+    /// Formats span fields as JSON object members. A `name` field (Bevy's
+    /// system spans) becomes the event name.
+    #[derive(Default)]
+    struct TraceFields {
+        name: Option<String>,
+        args: String,
+    }
+
+    impl TraceFields {
+        fn push(&mut self, field: &Field, value: &str) {
+            if field.name() == "name" {
+                self.name = Some(value.to_string());
+                return;
+            }
+            if !self.args.is_empty() {
+                self.args.push(',');
+            }
+            push_json_string(&mut self.args, field.name());
+            self.args.push(':');
+            push_json_string(&mut self.args, value);
+        }
+    }
+
+    impl Visit for TraceFields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.push(field, value);
+        }
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            let raw = format!("{value:?}");
+            self.push(field, raw.trim_matches('"'));
+        }
+    }
+
+    fn push_json_string(out: &mut String, value: &str) {
+        out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if c.is_control() => {
+                    let _ = write!(out, "\\u{:04x}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    /// Tracing layer that writes each span enter/exit as a complete ("X")
+    /// event to the [`CHROME_TRACE`] file. Idle until a trace is started.
+    pub struct ChromeTraceLayer;
+
+    impl<S> Layer<S> for ChromeTraceLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            if CHROME_TRACE.get().is_none() {
+                return;
+            }
+            let mut fields = TraceFields::default();
+            attrs.record(&mut fields);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(TraceSpan {
+                    name: fields
+                        .name
+                        .unwrap_or_else(|| attrs.metadata().name().to_string()),
+                    args: fields.args,
+                    entered_at: None,
+                });
+            }
+        }
+
+        fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id)
+                && let Some(data) = span.extensions_mut().get_mut::<TraceSpan>()
+            {
+                data.entered_at = Some(Instant::now());
+            }
+        }
+
+        fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+            let Some(trace) = CHROME_TRACE.get() else {
+                return;
+            };
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let mut extensions = span.extensions_mut();
+            let Some(data) = extensions.get_mut::<TraceSpan>() else {
+                return;
+            };
+            let Some(entered_at) = data.entered_at.take() else {
+                return;
+            };
+            let ts = entered_at
+                .saturating_duration_since(trace.start)
+                .as_secs_f64()
+                * 1e6;
+            let dur = entered_at.elapsed().as_secs_f64() * 1e6;
+
+            // The thread id goes between `head` and `tail`; it's assigned
+            // under the file lock, which the span's extensions aren't held
+            // across.
+            let mut head = String::with_capacity(128);
+            head.push_str("{\"name\":");
+            push_json_string(&mut head, &data.name);
+            head.push_str(",\"cat\":");
+            push_json_string(&mut head, span.metadata().target());
+            let _ = write!(
+                head,
+                ",\"ph\":\"X\",\"ts\":{ts:.3},\"dur\":{dur:.3},\"pid\":1,\"tid\":"
+            );
+            let tail = format!(",\"args\":{{{}}}}},", data.args);
+            drop(extensions);
+
+            let Ok(mut out) = trace.out.lock() else {
+                return;
+            };
+            if let Some(out) = out.as_mut() {
+                let tid = thread_id(out);
+                let _ = writeln!(out, "{head}{tid}{tail}");
+            }
+        }
+    }
+
+    /// This thread's trace id, announcing the thread (with its name) the
+    /// first time it's seen. Called with the trace file locked.
+    fn thread_id(out: &mut BufWriter<File>) -> u64 {
+        THREAD_ID.with(|cell| {
+            if let Some(tid) = cell.get() {
+                return tid;
+            }
+            let tid = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+            cell.set(Some(tid));
+            let mut name = String::new();
+            push_json_string(
+                &mut name,
+                std::thread::current().name().unwrap_or("unnamed"),
+            );
+            let _ = writeln!(
+                out,
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{tid},\"args\":{{\"name\":{name}}}}},"
+            );
+            tid
+        })
+    }
+
+    /// Finish the Chrome trace when the app exits, so the file is valid
+    /// JSON rather than relying on viewers tolerating a truncated array.
+    fn finish_chrome_trace(mut exits: MessageReader<AppExit>) {
+        if exits.read().next().is_none() {
+            return;
+        }
+        let Some(trace) = CHROME_TRACE.get() else {
+            return;
+        };
+        let Ok(mut out) = trace.out.lock() else {
+            return;
+        };
+        if let Some(mut file) = out.take() {
+            let result = file
+                .write_all(
+                    b"{\"name\":\"process_name\",\"ph\":\"M\",\"pid\":1,\"args\":{\"name\":\"veldera\"}}]\n",
+                )
+                .and_then(|()| file.flush());
+            if let Err(e) = result {
+                tracing::warn!("Failed to finish Chrome trace: {e}");
+            }
+        }
+    }
+
     /// `LogPlugin::custom_layer` callback. Returns the profiler layer
-    /// so it gets composed into the global tracing subscriber.
+    /// (and the Chrome trace layer, idle unless a trace was started) so
+    /// they get composed into the global tracing subscriber.
     pub fn install_layer(_app: &mut App) -> Option<BoxedLayer> {
-        Some(Box::new(ProfilerLayer))
+        Some(Box::new(ProfilerLayer.and_then(ChromeTraceLayer)))
     }
 
     /// Plugin: registers the snapshot resource and the drain system.
@@ -176,7 +411,7 @@ mod native {
         fn build(&self, app: &mut App) {
             app.insert_resource(CpuProfile::default())
                 .insert_resource(ProfilerSmoothing::default())
-                .add_systems(Last, drain_accumulator);
+                .add_systems(Last, (drain_accumulator, finish_chrome_trace));
         }
     }
 
@@ -246,7 +481,7 @@ mod native {
 }
 
 #[cfg(not(target_family = "wasm"))]
-pub use native::{CpuProfile, ProfilerPlugin, install_layer};
+pub use native::{CpuProfile, ProfilerPlugin, install_layer, start_chrome_trace};
 
 #[cfg(target_family = "wasm")]
 mod wasm_stub {
@@ -0,0 +1,443 @@
+//! Split-screen tonemapper comparison.
+//!
+//! While [`TonemapComparison::enabled`] is set, a second camera renders the
+//! main camera's view into an image through another [`Tonemapping`] (side B).
+//! The host paints the part of that image right of
+//! [`TonemapComparison::split`] over the window, so the left of the split
+//! shows the main camera (side A) and the right side B. The comparison camera
+//! carries copies of the main camera's atmosphere, clouds, exposure, bloom,
+//! and environment map, kept in step every frame.
+//!
+//! Each side's luminance histogram is read back every
+//! [`HISTOGRAM_INTERVAL_SECS`]: B's from its image, A's from a small probe
+//! camera rendering through the main camera's tonemapping.
+
+use bevy::{
+    camera::{Exposure, RenderTarget},
+    core_pipeline::tonemapping::Tonemapping,
+    light::EnvironmentMapLight,
+    post_process::bloom::Bloom,
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureFormat},
+        view::{
+            Hdr,
+            screenshot::{Screenshot, ScreenshotCaptured},
+        },
+    },
+    transform::TransformSystems,
+};
+use veldera_atmosphere::{AtmosphereSettings, SphericalAtmosphere, SphericalAtmosphereCamera};
+use veldera_clouds::CloudLayers;
+
+use crate::geo::floating_origin::FloatingOriginCamera;
+
+/// Seconds between histogram readbacks.
+pub const HISTOGRAM_INTERVAL_SECS: f32 = 0.5;
+
+/// Buckets in a [`LuminanceHistogram`].
+pub const HISTOGRAM_BINS: usize = 64;
+
+/// The A-side probe renders at this fraction of the window's size.
+const PROBE_SCALE: u32 = 4;
+
+/// Plugin for the split-screen tonemapper comparison.
+pub struct TonemapComparePlugin;
+
+impl Plugin for TonemapComparePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TonemapComparison>().add_systems(
+            PostUpdate,
+            (
+                toggle_comparison,
+                sync_comparison_views,
+                read_back_histograms,
+            )
+                .chain()
+                .after(TransformSystems::Propagate),
+        );
+    }
+}
+
+/// One side of the comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonSide {
+    /// The main camera, left of the split.
+    A,
+    /// The comparison camera, right of the split.
+    B,
+}
+
+impl ComparisonSide {
+    fn index(self) -> usize {
+        match self {
+            Self::A => 0,
+            Self::B => 1,
+        }
+    }
+}
+
+/// Split-screen comparison settings, and the state of a running comparison.
+#[derive(Resource)]
+pub struct TonemapComparison {
+    /// Render side B and show it right of the split.
+    pub enabled: bool,
+    /// Tonemapping for side B.
+    pub tonemapping: Tonemapping,
+    /// Where the split sits, as a fraction of the window's width.
+    pub split: f32,
+    active: Option<ActiveComparison>,
+    /// Latest histograms, by [`ComparisonSide::index`].
+    histograms: [Option<LuminanceHistogram>; 2],
+}
+
+impl Default for TonemapComparison {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tonemapping: Tonemapping::TonyMcMapface,
+            split: 0.5,
+            active: None,
+            histograms: [None, None],
+        }
+    }
+}
+
+impl TonemapComparison {
+    /// Side B's image, once the comparison is running. Paint the part right
+    /// of [`Self::split`] over the window.
+    pub fn image(&self) -> Option<&Handle<Image>> {
+        self.active.as_ref().map(|active| &active.image)
+    }
+
+    /// The latest luminance histogram of `side`, if one has been read back.
+    pub fn histogram(&self, side: ComparisonSide) -> Option<&LuminanceHistogram> {
+        self.histograms[side.index()].as_ref()
+    }
+}
+
+/// A running comparison.
+struct ActiveComparison {
+    /// Side B's camera.
+    camera: Entity,
+    /// Side B's render target, the size of the window.
+    image: Handle<Image>,
+    /// Side A's histogram probe.
+    probe: Entity,
+    /// The probe's render target.
+    probe_image: Handle<Image>,
+    /// Time until the next histogram readback (s).
+    next_readback_secs: f32,
+    /// Readbacks in flight, by [`ComparisonSide::index`].
+    pending: [bool; 2],
+}
+
+/// Distribution of pixel luma over a tonemapped image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuminanceHistogram {
+    /// Fraction of pixels in each of [`HISTOGRAM_BINS`] equal luma buckets,
+    /// darkest first.
+    pub bins: [f32; HISTOGRAM_BINS],
+    /// Mean luma (0–1).
+    pub mean: f32,
+    /// Fraction of pixels with a channel at full scale.
+    pub clipped: f32,
+}
+
+impl LuminanceHistogram {
+    /// Bin the Rec. 709 luma of sRGB-encoded RGBA8 `pixels`.
+    #[must_use]
+    pub fn from_rgba8(pixels: &[u8]) -> Self {
+        let mut counts = [0u32; HISTOGRAM_BINS];
+        let mut sum = 0.0;
+        let mut clipped = 0u32;
+        let mut total = 0u32;
+        for pixel in pixels.chunks_exact(4) {
+            let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(|c| f32::from(c) / 255.0);
+            let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let bin = ((luma * HISTOGRAM_BINS as f32) as usize).min(HISTOGRAM_BINS - 1);
+            counts[bin] += 1;
+            sum += luma;
+            clipped += u32::from(pixel[..3].contains(&255));
+            total += 1;
+        }
+        let scale = 1.0 / total.max(1) as f32;
+        Self {
+            bins: counts.map(|count| count as f32 * scale),
+            mean: sum * scale,
+            clipped: clipped as f32 * scale,
+        }
+    }
+}
+
+/// Marker for the comparison and probe cameras.
+#[derive(Component)]
+struct ComparisonView;
+
+/// The main camera's view components, as copied to the comparison views.
+type MainView<'a> = (
+    &'a Camera,
+    &'a Transform,
+    &'a Projection,
+    &'a Tonemapping,
+    &'a Exposure,
+    &'a SphericalAtmosphereCamera,
+    Ref<'a, SphericalAtmosphere>,
+    Ref<'a, AtmosphereSettings>,
+    Option<Ref<'a, CloudLayers>>,
+    Option<Ref<'a, Bloom>>,
+    Option<Ref<'a, EnvironmentMapLight>>,
+);
+
+/// Spawn or despawn the comparison views as the comparison is toggled.
+fn toggle_comparison(
+    mut commands: Commands,
+    mut comparison: ResMut<TonemapComparison>,
+    mut images: ResMut<Assets<Image>>,
+    main: Query<MainView, (With<FloatingOriginCamera>, Without<ComparisonView>)>,
+) {
+    if !comparison.enabled {
+        if let Some(active) = comparison.active.take() {
+            commands.entity(active.camera).despawn();
+            commands.entity(active.probe).despawn();
+            comparison.histograms = [None, None];
+        }
+        return;
+    }
+    if comparison.active.is_some() {
+        return;
+    }
+    let Ok((
+        camera,
+        transform,
+        projection,
+        tonemapping,
+        exposure,
+        atmo_camera,
+        atmosphere,
+        settings,
+        clouds,
+        bloom,
+        environment,
+    )) = main.single()
+    else {
+        return;
+    };
+    let Some(size) = camera.physical_target_size() else {
+        return;
+    };
+
+    let mut spawn_view = |name: &'static str, order: isize, size: UVec2, side: Tonemapping| {
+        let image = images.add(Image::new_target_texture(
+            size.x.max(1),
+            size.y.max(1),
+            TextureFormat::Rgba8UnormSrgb,
+            None,
+        ));
+        let mut entity = commands.spawn((
+            Name::new(name),
+            ComparisonView,
+            Camera3d::default(),
+            // Before the main camera, which draws the UI on top.
+            Camera { order, ..default() },
+            RenderTarget::Image(image.clone().into()),
+            *transform,
+            projection.clone(),
+            Hdr,
+            side,
+            *exposure,
+            atmo_camera.clone(),
+            SphericalAtmosphere::clone(&atmosphere),
+            AtmosphereSettings::clone(&settings),
+        ));
+        if let Some(clouds) = &clouds {
+            entity.insert(CloudLayers::clone(clouds));
+        }
+        if let Some(bloom) = &bloom {
+            entity.insert(Bloom::clone(bloom));
+        }
+        if let Some(environment) = &environment {
+            entity.insert(EnvironmentMapLight::clone(environment));
+        }
+        (entity.id(), image)
+    };
+    let (camera, image) = spawn_view("Tonemap comparison", -10, size, comparison.tonemapping);
+    let (probe, probe_image) = spawn_view(
+        "Tonemap comparison probe",
+        -11,
+        size / PROBE_SCALE,
+        *tonemapping,
+    );
+    comparison.active = Some(ActiveComparison {
+        camera,
+        image,
+        probe,
+        probe_image,
+        next_readback_secs: 0.0,
+        pending: [false, false],
+    });
+}
+
+/// Keep the comparison views in step with the main camera, carry over live
+/// edits, and keep their images sized to the window.
+#[allow(clippy::type_complexity)]
+fn sync_comparison_views(
+    mut commands: Commands,
+    comparison: Res<TonemapComparison>,
+    mut images: ResMut<Assets<Image>>,
+    main: Query<MainView, (With<FloatingOriginCamera>, Without<ComparisonView>)>,
+    mut views: Query<
+        (
+            &mut Transform,
+            &mut Projection,
+            &mut Tonemapping,
+            &mut Exposure,
+            &mut SphericalAtmosphereCamera,
+            &mut SphericalAtmosphere,
+            &mut AtmosphereSettings,
+        ),
+        With<ComparisonView>,
+    >,
+) {
+    let Some(active) = &comparison.active else {
+        return;
+    };
+    let Ok((
+        camera,
+        transform,
+        projection,
+        tonemapping,
+        exposure,
+        atmo_camera,
+        atmosphere,
+        settings,
+        clouds,
+        bloom,
+        environment,
+    )) = main.single()
+    else {
+        return;
+    };
+
+    if let Some(size) = camera.physical_target_size() {
+        for (handle, size) in [
+            (&active.image, size),
+            (&active.probe_image, size / PROBE_SCALE),
+        ] {
+            let size = size.max(UVec2::ONE);
+            let stale = images.get(handle).is_some_and(|image| image.size() != size);
+            if stale && let Some(image) = images.get_mut(handle) {
+                image.resize(Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                });
+            }
+        }
+    }
+
+    for (entity, side) in [
+        (active.camera, comparison.tonemapping),
+        (active.probe, *tonemapping),
+    ] {
+        let Ok((
+            mut view_transform,
+            mut view_projection,
+            mut view_tonemapping,
+            mut view_exposure,
+            mut view_atmo_camera,
+            mut view_atmosphere,
+            mut view_settings,
+        )) = views.get_mut(entity)
+        else {
+            continue;
+        };
+        *view_transform = *transform;
+        *view_projection = projection.clone();
+        view_tonemapping.set_if_neq(side);
+        if view_exposure.ev100 != exposure.ev100 {
+            *view_exposure = *exposure;
+        }
+        *view_atmo_camera = atmo_camera.clone();
+        if atmosphere.is_changed() {
+            *view_atmosphere = SphericalAtmosphere::clone(&atmosphere);
+        }
+        if settings.is_changed() {
+            *view_settings = AtmosphereSettings::clone(&settings);
+        }
+        let mut entity = commands.entity(entity);
+        if let Some(clouds) = &clouds
+            && clouds.is_changed()
+        {
+            entity.insert(CloudLayers::clone(clouds));
+        }
+        match &bloom {
+            Some(bloom) if bloom.is_changed() => {
+                entity.insert(Bloom::clone(bloom));
+            }
+            Some(_) => {}
+            None => {
+                entity.remove::<Bloom>();
+            }
+        }
+        // The environment map appears once its first bake is filtered.
+        if let Some(environment) = &environment
+            && environment.is_changed()
+        {
+            entity.insert(EnvironmentMapLight::clone(environment));
+        }
+    }
+}
+
+/// Read both sides back every [`HISTOGRAM_INTERVAL_SECS`] and bin them.
+fn read_back_histograms(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut comparison: ResMut<TonemapComparison>,
+) {
+    let Some(active) = &mut comparison.active else {
+        return;
+    };
+    active.next_readback_secs -= time.delta_secs();
+    if active.next_readback_secs > 0.0 || active.pending.iter().any(|&pending| pending) {
+        return;
+    }
+    active.next_readback_secs = HISTOGRAM_INTERVAL_SECS;
+    for (side, image) in [
+        (ComparisonSide::A, &active.probe_image),
+        (ComparisonSide::B, &active.image),
+    ] {
+        active.pending[side.index()] = true;
+        commands.spawn(Screenshot::image(image.clone())).observe(
+            move |captured: On<ScreenshotCaptured>, mut comparison: ResMut<TonemapComparison>| {
+                let comparison = &mut *comparison;
+                let Some(active) = &mut comparison.active else {
+                    return;
+                };
+                active.pending[side.index()] = false;
+                if let Some(pixels) = &captured.image.data {
+                    comparison.histograms[side.index()] =
+                        Some(LuminanceHistogram::from_rgba8(pixels));
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bins_luma_and_counts_clipping() {
+        // Black, white, and mid grey.
+        let pixels = [0, 0, 0, 255, 255, 255, 255, 255, 128, 128, 128, 255];
+        let histogram = LuminanceHistogram::from_rgba8(&pixels);
+        assert!((histogram.bins.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!((histogram.bins[0] - 1.0 / 3.0).abs() < 1e-6);
+        assert!((histogram.bins[HISTOGRAM_BINS - 1] - 1.0 / 3.0).abs() < 1e-6);
+        assert!((histogram.bins[HISTOGRAM_BINS / 2] - 1.0 / 3.0).abs() < 1e-6);
+        assert!((histogram.clipped - 1.0 / 3.0).abs() < 1e-6);
+        assert!((histogram.mean - (1.0 + 128.0 / 255.0) / 3.0).abs() < 1e-5);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Provides conversions between ECEF (Earth-Centered, Earth-Fixed) coordinates
 //! and geographic coordinates (latitude, longitude), plus the local tangent
-//! frame ([`RadialFrame`]) used to turn headings into world directions.
+//! frame ([`RadialFrame`]) used to turn headings into world directions, and
+//! the rotation between ECEF and an Earth-Centered Inertial (ECI) frame for
+//! objects whose motion is computed against the stars (see [`eci_to_ecef`]).
 
 use glam::{DVec3, Vec3};
 
@@ -137,6 +139,39 @@ pub fn ecef_to_geodetic(position: DVec3) -> (f64, f64, f64) {
     (lat.to_degrees(), lon.to_degrees(), height)
 }
 
+/// Greenwich Mean Sidereal Time (radians, in `0..2π`) at `days_since_j2000`
+/// (UT days from 2000-01-01 12:00).
+///
+/// The angle the Earth has turned under the equinox: the rotation between an
+/// ECI frame and ECEF about their shared Z axis. Uses the linear IAU 1982
+/// approximation, good to a fraction of an arcsecond over decades.
+pub fn gmst_rad(days_since_j2000: f64) -> f64 {
+    (280.460_618_37 + 360.985_647_366_29 * days_since_j2000)
+        .rem_euclid(360.0)
+        .to_radians()
+}
+
+/// Rotate an ECI position (X toward the equinox, Z toward the pole) into
+/// ECEF at a sidereal angle of `gmst` radians (see [`gmst_rad`]).
+///
+/// Ignores precession, nutation and polar motion, which is exact for the
+/// true-equator, mean-equinox frame SGP4 works in and within a fraction of a
+/// degree for other equatorial frames.
+pub fn eci_to_ecef(eci: DVec3, gmst: f64) -> DVec3 {
+    let (sin, cos) = gmst.sin_cos();
+    DVec3::new(
+        cos * eci.x + sin * eci.y,
+        -sin * eci.x + cos * eci.y,
+        eci.z,
+    )
+}
+
+/// Rotate an ECEF position into ECI at a sidereal angle of `gmst` radians;
+/// the inverse of [`eci_to_ecef`].
+pub fn ecef_to_eci(ecef: DVec3, gmst: f64) -> DVec3 {
+    eci_to_ecef(ecef, -gmst)
+}
+
 /// Initial camera look direction and local up at an ECEF `position`, for a
 /// compass `heading` and `pitch` in degrees.
 ///
@@ -225,6 +260,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eci_rotates_with_sidereal_time() {
+        // At J2000 noon the equinox sits at about 280.46° east of Greenwich.
+        assert!((gmst_rad(0.0).to_degrees() - 280.460_618_37).abs() < 1e-9);
+        // One sidereal day later the Earth has turned exactly once.
+        let sidereal_day = 360.0 / 360.985_647_366_29;
+        assert!((gmst_rad(sidereal_day) - gmst_rad(0.0)).abs() < 1e-9);
+
+        // A quarter turn in, the equinox direction lies over 90° W.
+        let x = eci_to_ecef(DVec3::X, std::f64::consts::FRAC_PI_2);
+        assert!((x - DVec3::NEG_Y).length() < 1e-12);
+
+        let eci = DVec3::new(6_778_000.0, -1_234_000.0, 2_345_000.0);
+        let gmst = gmst_rad(9_424.3);
+        let round_trip = ecef_to_eci(eci_to_ecef(eci, gmst), gmst);
+        assert!((round_trip - eci).length() < 1e-6);
+        assert_eq!(eci_to_ecef(eci, gmst).z, eci.z);
+    }
+
     #[test]
     fn ellipsoid_vertical_differs_from_spherical() {
         // At a mid-latitude the geodetic normal departs from the radial, so the
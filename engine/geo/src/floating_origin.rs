@@ -3,9 +3,20 @@
 //! Earth coordinates are millions of meters, which causes f32 precision issues.
 //! This system stores positions in f64 and renders relative to the camera,
 //! keeping all rendered positions within f32 precision range.
+//!
+//! Rebasing is centralised in [`FloatingOriginSystems`]: once per frame the
+//! origin snaps to the [`FloatingOriginCamera`], then every [`WorldPosition`]
+//! entity's render `Transform` is re-derived from it. Camera controllers only
+//! move the camera; nothing else shifts render transforms by a camera delta.
+//!
+//! Children that need a precise position of their own (attachment points,
+//! vehicle parts) carry a [`DTransform`]: a double-precision local transform.
+//! Propagation composes these down from the nearest [`WorldPosition`] ancestor
+//! in f64, so each child's [`WorldPosition`] is exact however far the hierarchy
+//! is from the origin; only the final camera-relative render transform is f32.
 
 use bevy::prelude::*;
-use glam::DVec3;
+use glam::{DQuat, DVec3};
 
 /// Plugin for floating origin coordinate system.
 pub struct FloatingOriginPlugin;
@@ -13,10 +24,40 @@ pub struct FloatingOriginPlugin;
 impl Plugin for FloatingOriginPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FloatingOrigin>()
-            .add_systems(PostUpdate, update_transforms_relative_to_origin);
+            .configure_sets(
+                PostUpdate,
+                (
+                    FloatingOriginSystems::Sync,
+                    FloatingOriginSystems::Propagate,
+                    FloatingOriginSystems::Rebase,
+                )
+                    .chain()
+                    .before(bevy::transform::TransformSystems::Propagate),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    sync_origin_to_camera.in_set(FloatingOriginSystems::Sync),
+                    propagate_world_positions.in_set(FloatingOriginSystems::Propagate),
+                    update_transforms_relative_to_origin.in_set(FloatingOriginSystems::Rebase),
+                ),
+            );
     }
 }
 
+/// The floating-origin rebasing steps, in order, in `PostUpdate`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatingOriginSystems {
+    /// Move [`FloatingOrigin`] to the [`FloatingOriginCamera`].
+    Sync,
+    /// Compose [`DTransform`] hierarchies into their children's
+    /// [`WorldPosition`]s.
+    Propagate,
+    /// Re-derive every root [`WorldPosition`] entity's `Transform` from the
+    /// origin.
+    Rebase,
+}
+
 /// The floating origin position in world (ECEF) coordinates.
 ///
 /// All entity transforms are computed relative to this position,
@@ -31,7 +72,7 @@ pub struct FloatingOrigin {
 ///
 /// This is the "true" position in ECEF coordinates (meters).
 /// The entity's Transform will be updated to be relative to the `FloatingOrigin`.
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Default)]
 pub struct WorldPosition {
     /// Position in ECEF coordinates (meters).
     pub position: DVec3,
@@ -44,13 +85,162 @@ impl WorldPosition {
     }
 }
 
-/// Update all entity transforms to be relative to the floating origin.
+/// Move the floating origin to the camera, after every camera controller has
+/// run for the frame.
+fn sync_origin_to_camera(mut origin: ResMut<FloatingOrigin>, query: Query<&FloatingOriginCamera>) {
+    if let Ok(camera) = query.single() {
+        origin.position = camera.position;
+    }
+}
+
+/// Double-precision local transform of an entity relative to its parent
+/// (`ChildOf`).
+///
+/// Under a [`WorldPosition`] ancestor, propagation sets this entity's
+/// [`WorldPosition`] from the composed f64 transforms, and its `Transform` to
+/// this local transform (cast to f32) for Bevy's own hierarchy propagation.
+/// Descendants without a `DTransform` still follow through Bevy's hierarchy,
+/// they just get no [`WorldPosition`] of their own.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[require(WorldPosition, Transform)]
+pub struct DTransform {
+    /// Translation relative to the parent (m, in the parent's scaled frame).
+    pub translation: DVec3,
+    /// Rotation relative to the parent.
+    pub rotation: DQuat,
+    /// Scale relative to the parent.
+    pub scale: DVec3,
+}
+
+impl Default for DTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl DTransform {
+    /// No translation, rotation, or scale.
+    pub const IDENTITY: Self = Self {
+        translation: DVec3::ZERO,
+        rotation: DQuat::IDENTITY,
+        scale: DVec3::ONE,
+    };
+
+    /// A pure translation.
+    pub fn from_translation(translation: DVec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Widen an f32 `Transform`.
+    pub fn from_transform(transform: &Transform) -> Self {
+        Self {
+            translation: transform.translation.as_dvec3(),
+            rotation: transform.rotation.as_dquat(),
+            scale: transform.scale.as_dvec3(),
+        }
+    }
+
+    /// Narrow to an f32 `Transform`. Only for small (local or camera-relative)
+    /// transforms; that is where f32 is safe.
+    pub fn to_transform(&self) -> Transform {
+        Transform {
+            translation: self.translation.as_vec3(),
+            rotation: self.rotation.as_quat(),
+            scale: self.scale.as_vec3(),
+        }
+    }
+
+    /// Apply this transform to `point`.
+    pub fn transform_point(&self, point: DVec3) -> DVec3 {
+        self.translation + self.rotation * (self.scale * point)
+    }
+
+    /// The transform of a `child` of this transform, in this transform's parent
+    /// frame.
+    #[must_use]
+    pub fn mul_transform(&self, child: &DTransform) -> DTransform {
+        DTransform {
+            translation: self.transform_point(child.translation),
+            rotation: self.rotation * child.rotation,
+            scale: self.scale * child.scale,
+        }
+    }
+}
+
+/// Compose [`DTransform`] hierarchies in f64 from each root [`WorldPosition`]
+/// entity (one without a `DTransform`), whose own orientation and scale come
+/// from its `Transform`.
+#[allow(clippy::type_complexity)]
+fn propagate_world_positions(
+    root_query: Query<(&WorldPosition, &Transform, &Children), Without<DTransform>>,
+    mut child_query: Query<
+        (
+            &DTransform,
+            &mut WorldPosition,
+            &mut Transform,
+            Option<&Children>,
+        ),
+        With<ChildOf>,
+    >,
+) {
+    for (world_pos, transform, children) in &root_query {
+        let global = DTransform {
+            translation: world_pos.position,
+            ..DTransform::from_transform(transform)
+        };
+        for &child in children {
+            propagate_child(&mut child_query, child, &global);
+        }
+    }
+}
+
+/// Set `entity`'s [`WorldPosition`] (and local `Transform`) under a parent at
+/// `parent`, then recurse into its children.
+#[allow(clippy::type_complexity)]
+fn propagate_child(
+    query: &mut Query<
+        (
+            &DTransform,
+            &mut WorldPosition,
+            &mut Transform,
+            Option<&Children>,
+        ),
+        With<ChildOf>,
+    >,
+    entity: Entity,
+    parent: &DTransform,
+) {
+    let Ok((local, mut world_pos, mut transform, children)) = query.get_mut(entity) else {
+        return;
+    };
+    let global = parent.mul_transform(local);
+    world_pos.position = global.translation;
+    transform.set_if_neq(local.to_transform());
+    let Some(children) = children else {
+        return;
+    };
+    // Copy the child list out so the query can be borrowed mutably again.
+    let children: Vec<Entity> = children.to_vec();
+    for child in children {
+        propagate_child(query, child, &global);
+    }
+}
+
+/// Update all root entity transforms to be relative to the floating origin.
+/// [`DTransform`] children keep their local `Transform`; Bevy's hierarchy
+/// propagation places them under their camera-relative parent.
 ///
 /// This system runs in `PostUpdate` to ensure camera movement is processed first.
 #[allow(clippy::type_complexity)]
 fn update_transforms_relative_to_origin(
     origin: Res<FloatingOrigin>,
-    mut query: Query<(&WorldPosition, &mut Transform), Without<FloatingOriginCamera>>,
+    mut query: Query<
+        (&WorldPosition, &mut Transform),
+        (Without<FloatingOriginCamera>, Without<DTransform>),
+    >,
 ) {
     for (world_pos, mut transform) in &mut query {
         // Compute position relative to origin.
@@ -76,3 +266,92 @@ impl FloatingOriginCamera {
         Self { position }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+    /// A hierarchy on the far side of the planet from the origin: at those
+    /// distances f32 spacing is ~1 m, so any f32 step in the chain shows.
+    #[test]
+    fn compose_exactly_at_antipode() {
+        let root = DTransform {
+            translation: DVec3::new(-EARTH_RADIUS_M, 0.0, 0.0),
+            rotation: DQuat::from_rotation_z(std::f64::consts::FRAC_PI_2),
+            scale: DVec3::ONE,
+        };
+        let wheel = DTransform::from_translation(DVec3::new(1.234_567, 0.0, 0.5));
+        let hub = DTransform {
+            translation: DVec3::new(0.0, 0.012_345, 0.0),
+            scale: DVec3::splat(2.0),
+            ..DTransform::IDENTITY
+        };
+
+        let global = root.mul_transform(&wheel).mul_transform(&hub);
+        // Rotating +X by 90° about Z gives +Y, and +Y gives -X.
+        let expected = DVec3::new(-EARTH_RADIUS_M - 0.012_345, 1.234_567, 0.5);
+        assert!(
+            global.translation.distance(expected) < 1e-6,
+            "{:?} vs {expected:?}",
+            global.translation
+        );
+        assert!((global.scale - DVec3::splat(2.0)).length() < 1e-12);
+
+        // Composition is associative, so the order children are folded in
+        // doesn't matter.
+        let folded = root.mul_transform(&wheel.mul_transform(&hub));
+        assert!(folded.translation.distance(global.translation) < 1e-6);
+    }
+
+    #[test]
+    fn propagates_world_positions_down_the_hierarchy() {
+        let mut app = App::new();
+        app.add_plugins(FloatingOriginPlugin);
+
+        // Camera on one side of the planet, vehicle on the other.
+        let camera_pos = DVec3::new(EARTH_RADIUS_M, 0.0, 0.0);
+        let vehicle_pos = DVec3::new(-EARTH_RADIUS_M, 0.25, 0.0);
+        app.world_mut()
+            .spawn((FloatingOriginCamera::new(camera_pos), Transform::default()));
+        let vehicle = app
+            .world_mut()
+            .spawn((WorldPosition::from_dvec3(vehicle_pos), Transform::default()))
+            .id();
+        let model = app
+            .world_mut()
+            .spawn((
+                DTransform {
+                    scale: DVec3::splat(0.5),
+                    ..DTransform::IDENTITY
+                },
+                ChildOf(vehicle),
+            ))
+            .id();
+        let wheel = app
+            .world_mut()
+            .spawn((
+                DTransform::from_translation(DVec3::new(0.8, -0.3, 1.3)),
+                ChildOf(model),
+            ))
+            .id();
+
+        app.update();
+
+        let world = app.world();
+        let wheel_world = world.get::<WorldPosition>(wheel).unwrap().position;
+        let expected = vehicle_pos + DVec3::new(0.4, -0.15, 0.65);
+        assert!(
+            wheel_world.distance(expected) < 1e-6,
+            "{wheel_world:?} vs {expected:?}"
+        );
+        // The child keeps its local transform; only the root is rebased.
+        assert_eq!(
+            *world.get::<Transform>(wheel).unwrap(),
+            Transform::from_xyz(0.8, -0.3, 1.3)
+        );
+        let root_translation = world.get::<Transform>(vehicle).unwrap().translation;
+        assert_eq!(root_translation, (vehicle_pos - camera_pos).as_vec3());
+    }
+}
@@ -1,25 +1,27 @@
-//! Radial gravity toward Earth center.
+//! Gravity toward the world's surface.
 //!
-//! Applies gravitational acceleration to all `RigidBody` entities. Since we're
-//! on a sphere, gravity points toward Earth center (negative normalized ECEF
-//! position) rather than along a fixed axis.
+//! Applies gravitational acceleration to all `RigidBody` entities, along the
+//! [`PhysicsWorldShape`]'s gravity direction. On the spherical Earth that's
+//! toward Earth center (negative normalized ECEF position) rather than along a
+//! fixed axis.
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use veldera_geo::floating_origin::WorldPosition;
 
-use crate::{ManualGravity, PhysicsConfig};
+use crate::{ManualGravity, PhysicsConfig, world_shape::PhysicsWorldShape};
 
-/// Apply radial gravity toward Earth center.
+/// Apply gravity toward the world's surface.
 ///
 /// Gravity direction is derived from each entity's [`WorldPosition`] (ECEF),
 /// ensuring it remains stable regardless of camera movement.
 /// Entities marked [`ManualGravity`] are excluded so they can integrate gravity
 /// themselves (e.g. a character controller that needs custom ground handling).
 #[allow(clippy::type_complexity)]
-pub fn apply_radial_gravity(
+pub fn apply_gravity(
     time: Res<Time>,
     config: Res<PhysicsConfig>,
+    shape: Res<PhysicsWorldShape>,
     mut query: Query<
         (&WorldPosition, &mut LinearVelocity),
         (With<RigidBody>, Without<ManualGravity>),
@@ -28,10 +30,73 @@ pub fn apply_radial_gravity(
     let dt = time.delta_secs();
 
     for (world_pos, mut velocity) in &mut query {
-        // Gravity points toward Earth center (negative normalized position).
-        let gravity_dir = -world_pos.position.normalize().as_vec3();
-
         // Apply gravitational acceleration: v += g * dt.
-        velocity.0 += gravity_dir * config.gravity * dt;
+        velocity.0 += shape.gravity_delta(world_pos.position, config.gravity, dt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use glam::DVec3;
+    use veldera_constants::EARTH_RADIUS_M_F64;
+
+    use super::*;
+    use crate::world_shape::Flat;
+
+    fn app(shape: PhysicsWorldShape) -> App {
+        let mut app = App::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(500));
+        app.insert_resource(time)
+            .insert_resource(PhysicsConfig { gravity: 10.0 })
+            .insert_resource(shape)
+            .add_systems(Update, apply_gravity);
+        app
+    }
+
+    fn falling(app: &mut App, position: DVec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                RigidBody::Dynamic,
+                WorldPosition::from_dvec3(position),
+                LinearVelocity::ZERO,
+            ))
+            .id()
+    }
+
+    #[test]
+    fn gravity_follows_the_world_shape() {
+        let mut sphere = app(PhysicsWorldShape::default());
+        let body = falling(&mut sphere, DVec3::new(0.0, EARTH_RADIUS_M_F64, 0.0));
+        let manual = sphere
+            .world_mut()
+            .spawn((
+                RigidBody::Dynamic,
+                WorldPosition::from_dvec3(DVec3::new(EARTH_RADIUS_M_F64, 0.0, 0.0)),
+                LinearVelocity::ZERO,
+                ManualGravity,
+            ))
+            .id();
+        sphere.update();
+        let velocity = sphere.world().get::<LinearVelocity>(body).unwrap().0;
+        assert!(
+            velocity.distance(Vec3::new(0.0, -5.0, 0.0)) < 1e-5,
+            "{velocity}"
+        );
+        assert_eq!(
+            sphere.world().get::<LinearVelocity>(manual).unwrap().0,
+            Vec3::ZERO
+        );
+
+        // On flat ground it's -Y wherever the body is.
+        let mut flat = app(PhysicsWorldShape(Box::new(Flat)));
+        let body = falling(&mut flat, DVec3::new(EARTH_RADIUS_M_F64, 0.0, 0.0));
+        flat.update();
+        assert_eq!(
+            flat.world().get::<LinearVelocity>(body).unwrap().0,
+            Vec3::new(0.0, -5.0, 0.0)
+        );
     }
 }
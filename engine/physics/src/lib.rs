@@ -11,6 +11,11 @@
 //! loaded yet, the deepest available ancestor is used as a fallback so
 //! entities can never fall through the ground.
 //!
+//! Those full-detail colliders stop at [`PhysicsStreamingConfig::range`].
+//! Beyond it a coarse tier (see [`PhysicsStreamingConfig::coarse_range`])
+//! covers the loaded terrain with a heavily simplified collider, so a vehicle
+//! outrunning the full-detail streaming still has ground under it.
+//!
 //! Under motion, distances along the velocity vector are compressed via
 //! [`MotionTracker::lead`] so colliders ahead of the player are upgraded
 //! before the player gets there.
@@ -19,18 +24,22 @@
 //! When the camera moves, all physics positions shift by -delta to maintain
 //! correct relative positions.
 //!
-//! The crate is gameplay-agnostic: it owns radial gravity, origin shifting,
+//! The crate is gameplay-agnostic: it owns gravity (along the
+//! [`PhysicsWorldShape`], the spherical Earth by default), origin shifting,
 //! and terrain colliders, but knows nothing about projectiles, vehicles, or
 //! camera modes. Entities that integrate gravity themselves opt out with
 //! [`ManualGravity`]; entities that should be cleaned up beyond
-//! [`PhysicsStreamingConfig::range`] carry [`DespawnOutsidePhysicsRange`].
+//! [`PhysicsStreamingConfig::physics_reach`] carry [`DespawnOutsidePhysicsRange`].
 
 mod gravity;
 mod layers;
+mod origin;
+mod palette;
 pub mod terrain;
 pub mod terrain_v2;
 pub mod terrain_v3;
 pub mod terrain_v4;
+mod world_shape;
 
 pub use avian3d::debug_render::DebugRender;
 use avian3d::{
@@ -50,18 +59,22 @@ use veldera_config::ConfigPlugin;
 use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
 
 pub use layers::GameLayer;
+pub use palette::DebugPalette;
 pub use terrain::TerrainCollider;
+pub use world_shape::{Flat, PhysicsWorldShape, Spherical, WorldShape};
 
 /// Marker component for entities that should despawn when outside physics range.
 ///
 /// Attach this to any physics entity (projectiles, vehicles, etc.) that should
 /// be automatically cleaned up when it moves beyond
-/// [`PhysicsStreamingConfig::range`] from the camera.
+/// [`PhysicsStreamingConfig::physics_reach`] from the camera.
 #[derive(Component, Default)]
 pub struct DespawnOutsidePhysicsRange;
 
 /// System set for the floating-origin shift applied to every physics
-/// `Position` in `FixedPreUpdate`.
+/// `Position` in `FixedPreUpdate`. The shift is the only place physics
+/// positions are re-based; code placing a body at an ECEF point converts it with
+/// [`PhysicsState::to_physics`] rather than subtracting a camera position.
 ///
 /// Systems that re-derive a `Position` from the previous frame's render
 /// `Transform` (e.g. a character controller's position sync) must run *after*
@@ -73,9 +86,9 @@ pub struct OriginShiftSystems;
 
 /// Marker component for `RigidBody` entities that integrate gravity themselves.
 ///
-/// [`apply_radial_gravity`](gravity::apply_radial_gravity) skips these so a
-/// character controller (or anything with bespoke ground handling) can apply
-/// its own gravity without fighting the engine's radial integration.
+/// [`apply_gravity`](gravity::apply_gravity) skips these so a character
+/// controller (or anything with bespoke ground handling) can apply its own
+/// gravity without fighting the engine's integration.
 #[derive(Component, Default)]
 pub struct ManualGravity;
 
@@ -100,9 +113,29 @@ pub const PHYSICS_FINEST_DEPTH: usize = rocktree_decode::MAX_LEVEL - 1;
 #[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct PhysicsStreamingConfig {
-    /// Maximum distance from the camera at which colliders are loaded (m), and
-    /// the despawn radius for [`DespawnOutsidePhysicsRange`] entities.
+    /// Maximum distance from the camera at which full-detail colliders are
+    /// loaded (m). Beyond it only the coarse tier (see
+    /// [`Self::coarse_range`]) collides.
     pub range: f64,
+    /// Outer radius (m) of the coarse collider tier: a cheap, heavily
+    /// simplified collider over the loaded terrain out to this distance, so
+    /// fast vehicles outrunning the full-detail colliders land on something
+    /// instead of the void. Re-tiered as the camera moves (see
+    /// [`Self::coarse_rebuild_distance`]). Zero disables the tier. Built by
+    /// the camera-centred collider algorithms.
+    pub coarse_range: f64,
+    /// Depth offset below [`PHYSICS_FINEST_DEPTH`] the coarse tier collides
+    /// at: loaded tiles finer than that are replaced by their ancestor at
+    /// this depth, so the tier's cost stays bounded however fine the render
+    /// set is.
+    pub coarse_depth_offset: usize,
+    /// Vertex-clustering tolerance (m) for the coarse tier, as
+    /// [`Self::collider_simplify_tolerance`] but far coarser.
+    pub coarse_simplify_tolerance: f64,
+    /// Rebuild the coarse tier once the camera has moved this far (m) from
+    /// where it was built. Also sizes the hole left for the full-detail
+    /// collider, so keep it well under that collider's reach.
+    pub coarse_rebuild_distance: f64,
     /// Radius (m) within which colliders mirror the loaded render set
     /// exactly (WYSIWYG): no banded selection, no fallbacks — collision is
     /// the displayed composite by construction. Beyond this radius the
@@ -263,6 +296,12 @@ pub struct PhysicsStreamingConfig {
 }
 
 impl PhysicsStreamingConfig {
+    /// Farthest distance (m) from the camera that any collider tier reaches:
+    /// the despawn radius for [`DespawnOutsidePhysicsRange`] entities.
+    pub fn physics_reach(&self) -> f64 {
+        self.range.max(self.coarse_range)
+    }
+
     /// Assemble the v3 voxel-wrap settings from the configured `wrap_*` knobs.
     pub fn wrap_settings(&self) -> veldera_terrain_collider::wrap::WrapSettings {
         veldera_terrain_collider::wrap::WrapSettings {
@@ -289,8 +328,9 @@ impl PhysicsStreamingConfig {
 
 /// Hot-reloadable global physics tuning, loaded from
 /// `assets/config/engine/physics/physics.toml`. Drives the manually-applied gravity for
-/// the radial-gravity system, the FPS controller, and vehicles (Avian's built-in
-/// gravity stays zero — we integrate radial gravity ourselves).
+/// the gravity system, the FPS controller, and vehicles (Avian's built-in
+/// gravity stays zero — we integrate gravity along the [`PhysicsWorldShape`]
+/// ourselves).
 #[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct PhysicsConfig {
@@ -359,7 +399,8 @@ impl Default for PhysicsIntegrationPlugin {
 
 impl Plugin for PhysicsIntegrationPlugin {
     fn build(&self, app: &mut App) {
-        // Disable default gravity - we apply radial gravity toward Earth center.
+        // Disable default gravity - we apply gravity along the world shape
+        // (toward Earth center on the sphere).
         app.add_plugins(PhysicsPlugins::default())
             // Add debug rendering plugin (disabled by default).
             .add_plugins(PhysicsDebugPlugin)
@@ -381,18 +422,20 @@ impl Plugin for PhysicsIntegrationPlugin {
                 transform_to_position: false,
                 ..Default::default()
             })
+            .init_resource::<PhysicsWorldShape>()
             .init_resource::<PhysicsState>()
             .init_resource::<MotionTracker>()
+            .init_resource::<DebugPalette>()
             .add_systems(Startup, configure_physics_debug_on_startup)
             .add_systems(
                 FixedPreUpdate,
-                apply_origin_shift
+                origin::apply_origin_shift
                     .in_set(OriginShiftSystems)
                     .before(PhysicsSystems::Prepare),
             )
             .add_systems(
                 FixedPostUpdate,
-                (gravity::apply_radial_gravity, sync_dynamic_world_position)
+                (gravity::apply_gravity, origin::sync_dynamic_world_position)
                     .chain()
                     .after(PhysicsSystems::Last),
             )
@@ -400,6 +443,14 @@ impl Plugin for PhysicsIntegrationPlugin {
                 Update,
                 (update_motion_tracker, despawn_outside_physics_range),
             );
+
+        #[cfg(debug_assertions)]
+        app.init_resource::<origin::OriginAudit>().add_systems(
+            FixedPreUpdate,
+            origin::audit_origin_shift
+                .after(OriginShiftSystems)
+                .before(PhysicsSystems::Prepare),
+        );
     }
 }
 
@@ -424,6 +475,14 @@ impl PhysicsState {
     pub fn origin_camera_position(&self) -> Option<DVec3> {
         self.last_camera_position
     }
+
+    /// The physics `Position` of the ECEF point `world`, relative to
+    /// [`origin_camera_position`](Self::origin_camera_position). `None` before
+    /// the first origin shift.
+    #[must_use]
+    pub fn to_physics(&self, world: DVec3) -> Option<Vec3> {
+        Some((world - self.last_camera_position?).as_vec3())
+    }
 }
 
 /// Tracks camera velocity by EWMA-smoothing frame-to-frame ECEF deltas.
@@ -540,62 +599,8 @@ pub fn is_physics_debug_enabled(config_store: &GizmoConfigStore) -> bool {
     config.enabled
 }
 
-/// Apply origin shift when camera moves.
-///
-/// All physics positions must shift by -delta when the camera moves so that
-/// relative positions stay stable. This runs BEFORE the physics simulation.
-fn apply_origin_shift(
-    camera_query: Query<&FloatingOriginCamera>,
-    mut physics_state: ResMut<PhysicsState>,
-    mut query: Query<&mut Position>,
-) {
-    let Ok(camera) = camera_query.single() else {
-        return;
-    };
-
-    let camera_pos = camera.position;
-
-    match physics_state.last_camera_position {
-        None => physics_state.last_camera_position = Some(camera_pos),
-        Some(last_pos) => {
-            let delta = camera_pos - last_pos;
-            // Only apply the shift when the delta is significant. The
-            // bookkeeping only advances when a shift is actually applied, so
-            // sub-threshold motion accumulates until it crosses the
-            // threshold instead of being dropped.
-            if delta.length_squared() > 1e-10 {
-                let shift = Vec3::new(-delta.x as f32, -delta.y as f32, -delta.z as f32);
-                for mut pos in &mut query {
-                    pos.0 += shift;
-                }
-                physics_state.last_camera_position = Some(camera_pos);
-            }
-        }
-    }
-}
-
-/// Sync WorldPosition from physics Position for dynamic bodies.
-///
-/// After physics simulation, dynamic bodies have authoritative Position values.
-/// We need to update their WorldPosition = camera + Position.
-#[allow(clippy::type_complexity)]
-fn sync_dynamic_world_position(
-    camera_query: Query<&FloatingOriginCamera>,
-    mut query: Query<(&Position, &mut WorldPosition), (With<RigidBody>, Without<TerrainCollider>)>,
-) {
-    let Ok(camera) = camera_query.single() else {
-        return;
-    };
-
-    let camera_pos = camera.position;
-
-    for (pos, mut world_pos) in &mut query {
-        world_pos.position = camera_pos + pos.0.as_dvec3();
-    }
-}
-
-/// Despawn entities marked with [`DespawnOutsidePhysicsRange`] when they exceed
-/// [`PhysicsStreamingConfig::range`].
+/// Despawn entities marked with [`DespawnOutsidePhysicsRange`] when they leave
+/// every collider tier ([`PhysicsStreamingConfig::physics_reach`]).
 fn despawn_outside_physics_range(
     mut commands: Commands,
     config: Res<PhysicsStreamingConfig>,
@@ -609,11 +614,10 @@ fn despawn_outside_physics_range(
     for (entity, world_pos) in &query {
         let distance = (world_pos.position - camera.position).length();
 
-        if distance > config.range {
+        let reach = config.physics_reach();
+        if distance > reach {
             tracing::debug!(
-                "Despawning entity: exceeded physics range ({:.0}m > {:.0}m)",
-                distance,
-                config.range
+                "Despawning entity: exceeded physics range ({distance:.0}m > {reach:.0}m)"
             );
             commands.entity(entity).despawn();
         }
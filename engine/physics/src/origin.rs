@@ -0,0 +1,318 @@
+//! Floating-origin rebasing for physics.
+//!
+//! Every physics `Position` is relative to the camera position recorded at the
+//! last applied origin shift ([`PhysicsState::origin_camera_position`]). This
+//! module is the one place that moves that reference: [`apply_origin_shift`]
+//! re-bases every `Position` by the camera's motion before each physics step,
+//! and [`sync_dynamic_world_position`] re-derives rigid bodies'
+//! [`WorldPosition`] from the simulated result afterwards. Render `Transform`s
+//! are re-based from [`WorldPosition`] by the floating-origin systems in
+//! [`veldera_geo`], so no other system needs to apply a camera delta itself.
+//!
+//! In debug builds, [`audit_origin_shift`] checks the invariants after every
+//! shift: no `Position` strays beyond f32-safe range, and every rigid body's
+//! `Position` still agrees with its [`WorldPosition`]. A body moved through
+//! only one of the two (a teleport writing `WorldPosition` alone, or a spawn
+//! placed against the live camera) is reported once instead of being silently
+//! snapped back by the next sync.
+
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
+
+use crate::{PhysicsState, terrain::TerrainCollider};
+
+/// Apply origin shift when camera moves.
+///
+/// All physics positions must shift by -delta when the camera moves so that
+/// relative positions stay stable. This runs BEFORE the physics simulation.
+pub(crate) fn apply_origin_shift(
+    camera_query: Query<&FloatingOriginCamera>,
+    mut physics_state: ResMut<PhysicsState>,
+    mut query: Query<&mut Position>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    let camera_pos = camera.position;
+
+    match physics_state.last_camera_position {
+        None => physics_state.last_camera_position = Some(camera_pos),
+        Some(last_pos) => {
+            let delta = camera_pos - last_pos;
+            // Only apply the shift when the delta is significant. The
+            // bookkeeping only advances when a shift is actually applied, so
+            // sub-threshold motion accumulates until it crosses the
+            // threshold instead of being dropped.
+            if delta.length_squared() > 1e-10 {
+                let shift = Vec3::new(-delta.x as f32, -delta.y as f32, -delta.z as f32);
+                for mut pos in &mut query {
+                    pos.0 += shift;
+                }
+                physics_state.last_camera_position = Some(camera_pos);
+            }
+        }
+    }
+}
+
+/// Sync WorldPosition from physics Position for dynamic bodies.
+///
+/// After physics simulation, dynamic bodies have authoritative Position values.
+/// We need to update their WorldPosition = origin + Position, against the same
+/// origin the shift re-based them to.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_dynamic_world_position(
+    physics_state: Res<PhysicsState>,
+    mut query: Query<(&Position, &mut WorldPosition), (With<RigidBody>, Without<TerrainCollider>)>,
+) {
+    let Some(origin) = physics_state.origin_camera_position() else {
+        return;
+    };
+
+    for (pos, mut world_pos) in &mut query {
+        world_pos.position = origin + pos.0.as_dvec3();
+    }
+}
+
+/// Distance (m) from the physics origin beyond which a `Position` is reported:
+/// f32 spacing there is ~4 mm, past where contacts stay stable, and nothing
+/// simulated should be this far from the camera (see
+/// [`PhysicsStreamingConfig::physics_reach`](crate::PhysicsStreamingConfig::physics_reach)).
+#[cfg(debug_assertions)]
+const F32_SAFE_RANGE_M: f32 = 50_000.0;
+
+/// Largest disagreement (m) between a rigid body's `Position` and its
+/// [`WorldPosition`] tolerated after a shift.
+#[cfg(debug_assertions)]
+const MAX_ORIGIN_DRIFT_M: f64 = 0.5;
+
+/// Entities [`audit_origin_shift`] has already reported. Each is logged once
+/// rather than every step it stays broken, and forgotten when it despawns.
+#[cfg(debug_assertions)]
+#[derive(Resource, Default, Debug)]
+pub(crate) struct OriginAudit {
+    reported: HashSet<Entity>,
+}
+
+#[cfg(debug_assertions)]
+impl OriginAudit {
+    /// Whether `entity` has been reported.
+    #[cfg(test)]
+    fn is_reported(&self, entity: Entity) -> bool {
+        self.reported.contains(&entity)
+    }
+}
+
+/// Report entities whose physics state breaks the floating-origin invariants,
+/// once per entity, by name where they have one.
+#[cfg(debug_assertions)]
+#[allow(clippy::type_complexity)]
+pub(crate) fn audit_origin_shift(
+    physics_state: Res<PhysicsState>,
+    mut audit: ResMut<OriginAudit>,
+    query: Query<
+        (
+            Entity,
+            Option<&Name>,
+            &Position,
+            Option<&WorldPosition>,
+            Has<RigidBody>,
+        ),
+        Without<TerrainCollider>,
+    >,
+) {
+    let Some(origin) = physics_state.origin_camera_position() else {
+        return;
+    };
+    audit.reported.retain(|entity| query.contains(*entity));
+
+    for (entity, name, position, world_pos, is_body) in &query {
+        if audit.reported.contains(&entity) {
+            continue;
+        }
+        let label = name.map_or_else(|| entity.to_string(), |name| format!("{name} ({entity})"));
+        let distance = position.0.length();
+        if !distance.is_finite() || distance > F32_SAFE_RANGE_M {
+            warn!(
+                "origin audit: {label} has physics position {} ({distance:.0} m from the \
+                 origin at {origin}), beyond f32-safe range ({F32_SAFE_RANGE_M:.0} m)",
+                position.0
+            );
+            audit.reported.insert(entity);
+            continue;
+        }
+        let Some(world_pos) = world_pos.filter(|_| is_body) else {
+            continue;
+        };
+        let drift = (origin + position.0.as_dvec3() - world_pos.position).length();
+        if drift > MAX_ORIGIN_DRIFT_M {
+            warn!(
+                "origin audit: {label} physics position {} disagrees with its WorldPosition \
+                 {} by {drift:.2} m; was it moved without re-basing through PhysicsState?",
+                position.0, world_pos.position
+            );
+            audit.reported.insert(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use glam::DVec3;
+
+    use super::*;
+
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+    /// The origin systems in their fixed-step order, without the solver in
+    /// between: shift, audit (in debug builds), then the post-step sync.
+    fn app(camera: DVec3) -> App {
+        let mut app = App::new();
+        app.init_resource::<PhysicsState>().add_systems(
+            Update,
+            (apply_origin_shift, sync_dynamic_world_position).chain(),
+        );
+        #[cfg(debug_assertions)]
+        app.init_resource::<OriginAudit>().add_systems(
+            Update,
+            audit_origin_shift
+                .after(apply_origin_shift)
+                .before(sync_dynamic_world_position),
+        );
+        app.world_mut().spawn(FloatingOriginCamera::new(camera));
+        app
+    }
+
+    fn move_camera(app: &mut App, position: DVec3) {
+        let mut cameras = app.world_mut().query::<&mut FloatingOriginCamera>();
+        cameras.single_mut(app.world_mut()).unwrap().position = position;
+    }
+
+    fn origin(app: &App) -> Option<DVec3> {
+        app.world()
+            .resource::<PhysicsState>()
+            .origin_camera_position()
+    }
+
+    #[test]
+    fn shift_applies_once_the_motion_crosses_the_threshold() {
+        let start = DVec3::new(EARTH_RADIUS_M, 0.0, 0.0);
+        let mut app = app(start);
+        let collider = app
+            .world_mut()
+            .spawn(Position(Vec3::new(3.0, 0.0, 0.0)))
+            .id();
+        app.update();
+        assert_eq!(origin(&app), Some(start));
+
+        // A micrometre is below the threshold: nothing moves, and the origin
+        // stays put so the motion isn't lost.
+        move_camera(&mut app, start + DVec3::new(1e-6, 0.0, 0.0));
+        app.update();
+        assert_eq!(origin(&app), Some(start));
+        assert_eq!(app.world().get::<Position>(collider).unwrap().0.x, 3.0);
+
+        // Ten metres later the whole accumulated delta is applied at once.
+        let moved = start + DVec3::new(10.0, 0.0, 0.0);
+        move_camera(&mut app, moved);
+        app.update();
+        assert_eq!(origin(&app), Some(moved));
+        let position = app.world().get::<Position>(collider).unwrap().0;
+        assert!(
+            (position - Vec3::new(-7.0, 0.0, 0.0)).length() < 1e-3,
+            "{position}"
+        );
+    }
+
+    #[test]
+    fn static_and_dynamic_entities_keep_their_world_positions() {
+        let start = DVec3::new(EARTH_RADIUS_M, 0.0, 0.0);
+        let mut app = app(start);
+        let world = start + DVec3::new(5.0, 2.0, 0.0);
+        let fixed = app
+            .world_mut()
+            .spawn((
+                Position(Vec3::new(5.0, 2.0, 0.0)),
+                WorldPosition::from_dvec3(world),
+            ))
+            .id();
+        let body = app
+            .world_mut()
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vec3::new(5.0, 2.0, 0.0)),
+                WorldPosition::from_dvec3(world),
+            ))
+            .id();
+        app.update();
+
+        move_camera(&mut app, start + DVec3::new(0.0, 100.0, 50.0));
+        app.update();
+        for entity in [fixed, body] {
+            let position = app.world().get::<Position>(entity).unwrap().0;
+            assert!(
+                (position - Vec3::new(5.0, -98.0, -50.0)).length() < 1e-3,
+                "{position}"
+            );
+            let world_pos = app.world().get::<WorldPosition>(entity).unwrap().position;
+            assert!(world_pos.distance(world) < 1e-3, "{world_pos}");
+        }
+
+        // A step that moves the body carries through to its WorldPosition;
+        // the static entity is left to whoever owns it.
+        app.world_mut().get_mut::<Position>(body).unwrap().0.x += 1.0;
+        app.world_mut().get_mut::<Position>(fixed).unwrap().0.x += 1.0;
+        app.world_mut()
+            .run_system_once(sync_dynamic_world_position)
+            .unwrap();
+        let body_world = app.world().get::<WorldPosition>(body).unwrap().position;
+        assert!(body_world.distance(world + DVec3::X) < 1e-3, "{body_world}");
+        let fixed_world = app.world().get::<WorldPosition>(fixed).unwrap().position;
+        assert_eq!(fixed_world, world);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn audit_reports_a_manual_position_shift_once() {
+        let start = DVec3::new(EARTH_RADIUS_M, 0.0, 0.0);
+        let mut app = app(start);
+        let spawn = |app: &mut App| {
+            app.world_mut()
+                .spawn((
+                    RigidBody::Dynamic,
+                    Position(Vec3::ZERO),
+                    WorldPosition::from_dvec3(start),
+                ))
+                .id()
+        };
+        let moved = spawn(&mut app);
+        let untouched = spawn(&mut app);
+        let stray = app
+            .world_mut()
+            .spawn(Position(Vec3::new(0.0, 0.0, 2.0 * F32_SAFE_RANGE_M)))
+            .id();
+        app.update();
+        assert!(app.world().resource::<OriginAudit>().is_reported(stray));
+        assert!(!app.world().resource::<OriginAudit>().is_reported(moved));
+
+        // Re-basing by hand, outside the shift, leaves Position and
+        // WorldPosition disagreeing by the shift.
+        app.world_mut().get_mut::<Position>(moved).unwrap().0.y += 5.0;
+        app.update();
+        let audit = app.world().resource::<OriginAudit>();
+        assert!(audit.is_reported(moved));
+        assert!(!audit.is_reported(untouched));
+        assert_eq!(audit.reported.len(), 2);
+
+        // Once despawned, an entity is forgotten.
+        app.world_mut().despawn(stray);
+        app.update();
+        assert!(!app.world().resource::<OriginAudit>().is_reported(stray));
+    }
+}
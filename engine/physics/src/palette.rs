@@ -0,0 +1,105 @@
+//! Colour schemes for debug gizmos and diagnostics plots.
+
+use bevy::{color::palettes::css, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Colour scheme shared by the physics and streaming gizmos and the
+/// diagnostics plots, selectable from the Settings tab.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugPalette {
+    /// Rainbow gradients, green-to-red ramps and saturated categories.
+    #[default]
+    Standard,
+    /// Viridis gradients, blue-to-vermillion ramps and the Okabe–Ito
+    /// categories, which stay distinguishable under the common forms of
+    /// colour blindness.
+    ColorblindSafe,
+}
+
+/// Viridis from 0.2 to 1.0: the darkest purples vanish against night
+/// terrain and the dark UI, so the gradient starts at indigo.
+const VIRIDIS: [[f32; 3]; 5] = [
+    [65.0, 68.0, 135.0],
+    [42.0, 120.0, 142.0],
+    [34.0, 168.0, 132.0],
+    [122.0, 209.0, 81.0],
+    [253.0, 231.0, 37.0],
+];
+
+/// Okabe–Ito, with grey standing in for black so every entry reads on a dark
+/// background.
+const OKABE_ITO: [(u8, u8, u8); 8] = [
+    (230, 159, 0),
+    (86, 180, 233),
+    (0, 158, 115),
+    (240, 228, 66),
+    (0, 114, 178),
+    (213, 94, 0),
+    (204, 121, 167),
+    (153, 153, 153),
+];
+
+impl DebugPalette {
+    /// Every palette, in picker order.
+    pub const ALL: [DebugPalette; 2] = [DebugPalette::Standard, DebugPalette::ColorblindSafe];
+
+    /// Sequential cool-to-warm gradient, `t` in `[0, 1]`.
+    #[must_use]
+    pub fn gradient(self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            // Blue → cyan → green → yellow → red.
+            DebugPalette::Standard => {
+                let (r, g, b) = if t < 0.25 {
+                    let k = t / 0.25;
+                    (60.0, 60.0 + k * 195.0, 220.0)
+                } else if t < 0.5 {
+                    let k = (t - 0.25) / 0.25;
+                    (60.0, 255.0, 220.0 - k * 220.0)
+                } else if t < 0.75 {
+                    let k = (t - 0.5) / 0.25;
+                    (60.0 + k * 195.0, 255.0, 0.0)
+                } else {
+                    let k = (t - 0.75) / 0.25;
+                    (255.0, 255.0 - k * 175.0, 0.0)
+                };
+                Color::srgb_u8(r as u8, g as u8, b as u8)
+            }
+            DebugPalette::ColorblindSafe => {
+                let scaled = t * (VIRIDIS.len() - 1) as f32;
+                let i = (scaled as usize).min(VIRIDIS.len() - 2);
+                let k = scaled - i as f32;
+                let [r, g, b] = std::array::from_fn(|c| {
+                    VIRIDIS[i][c] + (VIRIDIS[i + 1][c] - VIRIDIS[i][c]) * k
+                });
+                Color::srgb_u8(r as u8, g as u8, b as u8)
+            }
+        }
+    }
+
+    /// Good-to-bad ramp, `t` in `[0, 1]` (e.g. tire saturation).
+    #[must_use]
+    pub fn ramp(self, t: f32) -> Color {
+        let (good, bad) = match self {
+            DebugPalette::Standard => (Color::from(css::LIME), Color::from(css::RED)),
+            DebugPalette::ColorblindSafe => {
+                (Color::srgb_u8(0, 114, 178), Color::srgb_u8(213, 94, 0))
+            }
+        };
+        good.mix(&bad, t.clamp(0.0, 1.0))
+    }
+
+    /// The `index`th colour of a categorical set: `standard` (the call
+    /// site's own choice) under the standard palette, Okabe–Ito otherwise.
+    #[must_use]
+    pub fn categorical(self, index: usize, standard: Color) -> Color {
+        match self {
+            DebugPalette::Standard => standard,
+            DebugPalette::ColorblindSafe => {
+                let (r, g, b) = OKABE_ITO[index % OKABE_ITO.len()];
+                Color::srgb_u8(r, g, b)
+            }
+        }
+    }
+}
@@ -14,6 +14,9 @@
 //! there are no slab side-walls to expose at the boundary — the old banded voxel
 //! wrap's curtains are gone. 2.5D is the accepted scope; true 3D (tunnels, stacked
 //! freeways) is a later layer via OSM-carved passages, not a 3D extractor.
+//!
+//! Beyond the surface's reach, [`create_coarse_collider`] builds the coarse
+//! far-field tier: the same gathered soup, simplified and used directly.
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
@@ -66,7 +69,7 @@ pub fn create_height_collider(
     down: Vec3,
     settings: &HeightfieldSettings,
 ) -> Option<Collider> {
-    let (soup_vertices, soup_triangles) = combine_soup(tiles, down)?;
+    let (soup_vertices, soup_triangles) = combine_soup(tiles, down, &BASE_SETTINGS)?;
     let soup_tris = soup_triangles.len();
 
     let up = -down.normalize_or_zero();
@@ -95,7 +98,7 @@ pub fn create_octree_collider(
     down: Vec3,
     settings: &OctreeColliderSettings,
 ) -> Option<Collider> {
-    let (soup_vertices, soup_triangles) = combine_soup(tiles, down)?;
+    let (soup_vertices, soup_triangles) = combine_soup(tiles, down, &BASE_SETTINGS)?;
     let soup_tris = soup_triangles.len();
 
     let up = -down.normalize_or_zero();
@@ -125,14 +128,84 @@ pub fn create_octree_collider(
     Collider::try_trimesh(vertices, triangles).ok()
 }
 
+/// Settings for the coarse far-field tier: the clustering tolerance and skirt
+/// depth of its simplified soup, and the radius around the build centre left
+/// to the full-detail collider.
+#[derive(Debug, Clone, Copy)]
+pub struct CoarseColliderSettings {
+    /// Vertex-clustering tolerance (m).
+    pub simplify_tolerance: f32,
+    /// Boundary-skirt depth (m) sealing cracks between tiles. Zero disables.
+    pub skirt_depth: f32,
+    /// Triangles whose centroid lies within this distance (m) of the build
+    /// centre are dropped, so the tier never stacks a second, coarser layer
+    /// under the full-detail collider.
+    pub hole_radius: f32,
+}
+
+/// Build the coarse far-field collider: the tiles' octant-clipped soup,
+/// vertex-clustered down to `settings.simplify_tolerance` and used as-is, with
+/// no surface extraction, so it stays cheap over kilometres of terrain. The
+/// centre of the frame is left open (see [`CoarseColliderSettings::hole_radius`]).
+/// `tiles`/`down` as for [`create_height_collider`]. Returns `None` if nothing
+/// survives.
+pub fn create_coarse_collider(
+    tiles: &[(TileMeshes, u8)],
+    down: Vec3,
+    settings: &CoarseColliderSettings,
+) -> Option<Collider> {
+    let build_settings = BuildSettings {
+        skirt_depth: settings.skirt_depth,
+        simplify_tolerance: settings.simplify_tolerance,
+        ..BASE_SETTINGS
+    };
+    let (soup_vertices, soup_triangles) = combine_soup(tiles, down, &build_settings)?;
+    let soup_tris = soup_triangles.len();
+
+    // Drop the hole, then compact away the vertices only it used.
+    let hole_sq = settings.hole_radius * settings.hole_radius;
+    let mut remap = vec![u32::MAX; soup_vertices.len()];
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+    for triangle in soup_triangles {
+        let [a, b, c] = triangle.map(|i| soup_vertices[i as usize]);
+        if ((a + b + c) / 3.0).length_squared() < hole_sq {
+            continue;
+        }
+        triangles.push(triangle.map(|i| {
+            let slot = &mut remap[i as usize];
+            if *slot == u32::MAX {
+                *slot = vertices.len() as u32;
+                vertices.push(soup_vertices[i as usize]);
+            }
+            *slot
+        }));
+    }
+    if triangles.is_empty() {
+        return None;
+    }
+
+    info!(
+        target: "collider_v4",
+        "coarse build: {} tiles, soup {soup_tris} tris, surface {} tris",
+        tiles.len(),
+        triangles.len()
+    );
+    Collider::try_trimesh(vertices, triangles).ok()
+}
+
 /// Combine every tile's octant-clipped soup into one camera-centred soup. The tile
 /// offsets already place each in the frame, so concatenation needs no further
 /// shift. `None` when nothing survives.
-fn combine_soup(tiles: &[(TileMeshes, u8)], down: Vec3) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
+fn combine_soup(
+    tiles: &[(TileMeshes, u8)],
+    down: Vec3,
+    settings: &BuildSettings,
+) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
     let mut soup_vertices: Vec<Vec3> = Vec::new();
     let mut soup_triangles: Vec<[u32; 3]> = Vec::new();
     for (tile, mask) in tiles {
-        let Some(soup) = build_tile_geometry(tile, *mask, 0, &[], down, &BASE_SETTINGS) else {
+        let Some(soup) = build_tile_geometry(tile, *mask, 0, &[], down, settings) else {
             continue;
         };
         let base_index = soup_vertices.len() as u32;
@@ -0,0 +1,137 @@
+//! The shape of the world physics runs on.
+//!
+//! [`WorldShape`] answers what physics asks of the world: which way is up at
+//! a point, how high the point is above the reference surface, and where the
+//! point at a given height above it lies. Gravity pulls against up.
+//!
+//! The game runs on a [`Spherical`] Earth, held in the [`PhysicsWorldShape`]
+//! resource that [`apply_gravity`](crate::gravity::apply_gravity) reads. The
+//! vehicle lab steps its cars over [`Flat`] ground through the same trait, so
+//! the two integrate gravity and place ground contacts the same way instead of
+//! the lab mirroring the game by hand.
+//!
+//! The reference surface is the sphere or plane itself, not the streamed
+//! terrain: ground contact still comes from collider casts.
+
+use bevy::prelude::*;
+use glam::DVec3;
+use veldera_constants::EARTH_RADIUS_M_F64;
+
+/// Up, height and gravity for a world shape. Positions are world positions
+/// (ECEF on the sphere) in metres.
+pub trait WorldShape: Send + Sync + 'static {
+    /// Unit up vector at `position`.
+    fn up(&self, position: DVec3) -> DVec3;
+
+    /// Height (m) of `position` above the reference surface.
+    fn height(&self, position: DVec3) -> f64;
+
+    /// The point `height` m above the reference surface, above or below
+    /// `position`.
+    fn at_height(&self, position: DVec3, height: f64) -> DVec3;
+
+    /// Unit direction gravity pulls in at `position`.
+    fn gravity_direction(&self, position: DVec3) -> DVec3 {
+        -self.up(position)
+    }
+
+    /// Velocity change (m/s) from `gravity` (m/s²) acting for `dt` (s) at
+    /// `position`.
+    fn gravity_delta(&self, position: DVec3, gravity: f32, dt: f32) -> Vec3 {
+        self.gravity_direction(position).as_vec3() * gravity * dt
+    }
+}
+
+/// A sphere centred on the origin: the ECEF Earth.
+#[derive(Clone, Copy, Debug)]
+pub struct Spherical {
+    /// Radius of the reference surface (m).
+    pub radius_m: f64,
+}
+
+impl Default for Spherical {
+    fn default() -> Self {
+        Self {
+            radius_m: EARTH_RADIUS_M_F64,
+        }
+    }
+}
+
+impl WorldShape for Spherical {
+    fn up(&self, position: DVec3) -> DVec3 {
+        position.normalize_or_zero()
+    }
+
+    fn height(&self, position: DVec3) -> f64 {
+        position.length() - self.radius_m
+    }
+
+    fn at_height(&self, position: DVec3, height: f64) -> DVec3 {
+        self.up(position) * (self.radius_m + height)
+    }
+}
+
+/// The plane y = 0 with +Y up.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Flat;
+
+impl WorldShape for Flat {
+    fn up(&self, _position: DVec3) -> DVec3 {
+        DVec3::Y
+    }
+
+    fn height(&self, position: DVec3) -> f64 {
+        position.y
+    }
+
+    fn at_height(&self, position: DVec3, height: f64) -> DVec3 {
+        DVec3::new(position.x, height, position.z)
+    }
+}
+
+/// The world shape the physics plugin integrates gravity against.
+/// [`Spherical`] unless the host inserts another before adding the plugin.
+#[derive(Resource, Deref)]
+pub struct PhysicsWorldShape(pub Box<dyn WorldShape>);
+
+impl Default for PhysicsWorldShape {
+    fn default() -> Self {
+        Self(Box::new(Spherical::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spherical_measures_from_the_centre() {
+        let sphere = Spherical::default();
+        let position = DVec3::new(0.0, 3.0, 4.0).normalize() * (EARTH_RADIUS_M_F64 + 120.0);
+
+        assert!(sphere.up(position).distance(DVec3::new(0.0, 0.6, 0.8)) < 1e-12);
+        assert!((sphere.height(position) - 120.0).abs() < 1e-6);
+        let surface = sphere.at_height(position, 0.0);
+        assert!(sphere.height(surface).abs() < 1e-6);
+        assert!(surface.normalize().distance(sphere.up(position)) < 1e-12);
+        assert!(
+            sphere
+                .gravity_direction(position)
+                .distance(-sphere.up(position))
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn flat_measures_along_y() {
+        let position = DVec3::new(5.0, 2.5, -7.0);
+
+        assert_eq!(Flat.up(position), DVec3::Y);
+        assert_eq!(Flat.height(position), 2.5);
+        assert_eq!(Flat.at_height(position, 0.0), DVec3::new(5.0, 0.0, -7.0));
+        assert_eq!(
+            Flat.gravity_delta(position, 9.81, 0.5),
+            Vec3::new(0.0, -4.905, 0.0)
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Dynamic resolution scaling driven by GPU frame time.
+//!
+//! On a GPU that can't hold its frame budget at native resolution (dense
+//! cities on integrated graphics, say), a camera carrying [`DynamicResolution`]
+//! renders its main 3D passes into a smaller region of the view target, and an
+//! upscale pass stretches that region back over the full target before bloom
+//! and tone mapping. The render scale follows the smoothed GPU frame time
+//! towards [`DynamicResolutionConfig::target_gpu_ms`], within the configured
+//! limits, in quantised steps so the GPU isn't reallocating per-view buffers
+//! every frame.
+//!
+//! The scale reaches the renderer as Bevy's `MainPassResolutionOverride`, the
+//! same mechanism upscalers like DLSS use, so it shrinks only the main-pass
+//! viewport: the camera's logical viewport (and with it picking and
+//! screen-space UI) is untouched. The atmosphere's LUTs are sized by its
+//! `AtmosphereSettings`, not by the view, so they keep their configured
+//! resolution at every scale; the clouds size their buffers from the
+//! main-pass resolution, so their cost scales along with the scene's.
+
+mod upscale;
+
+use bevy::{diagnostic::DiagnosticsStore, platform::time::Instant, prelude::*, reflect::TypePath};
+use serde::Deserialize;
+use veldera_config::ConfigPlugin;
+
+pub use upscale::{DynamicResolutionNode, ViewUpscale};
+
+/// Plugin for dynamic resolution scaling.
+///
+/// Defaults to the config at [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+/// in the shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout. Only cameras carrying [`DynamicResolution`] scale.
+pub struct DynamicResolutionPlugin {
+    /// Path to the [`DynamicResolutionConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl DynamicResolutionPlugin {
+    /// Canonical config path within the shared engine asset subtree.
+    pub const DEFAULT_CONFIG_PATH: &'static str = "engine/config/rendering/dynamic_resolution.toml";
+
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Default for DynamicResolutionPlugin {
+    /// Load the config from [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONFIG_PATH)
+    }
+}
+
+impl Plugin for DynamicResolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<DynamicResolutionConfig>::new(
+            self.config_path,
+        ))
+        .add_plugins(upscale::UpscalePlugin)
+        .init_resource::<DynamicResolutionStats>()
+        .add_systems(Update, (measure_frame_time, adjust_render_scale).chain());
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Hot-reloadable dynamic resolution tuning, loaded from
+/// `assets/config/engine/rendering/dynamic_resolution.toml`.
+#[derive(Default, Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DynamicResolutionConfig {
+    /// Adjust the render scale automatically. When off, cameras render at
+    /// [`max_scale`](Self::max_scale).
+    pub enabled: bool,
+    /// GPU frame time the controller aims for (ms).
+    pub target_gpu_ms: f32,
+    /// Fraction of the target the frame time must fall below before the scale
+    /// rises again (0–1). The band between this and the target is the
+    /// controller's hysteresis.
+    pub upscale_headroom: f32,
+    /// Lowest render scale, as a fraction of the native resolution per axis.
+    pub min_scale: f32,
+    /// Highest render scale (at most 1).
+    pub max_scale: f32,
+    /// Granularity the scale moves in; every step reallocates the per-view
+    /// buffers sized from the main-pass resolution.
+    pub scale_step: f32,
+    /// Largest change applied in one adjustment.
+    pub max_change: f32,
+    /// Seconds between adjustments.
+    pub adjust_interval_s: f32,
+    /// Time constant of the frame-time smoothing (s).
+    pub smoothing_s: f32,
+}
+
+impl DynamicResolutionConfig {
+    /// The configured scale limits, sanitised to `0.1 ≤ min ≤ max ≤ 1`.
+    pub fn limits(&self) -> (f32, f32) {
+        let min = self.min_scale.clamp(0.1, 1.0);
+        (min, self.max_scale.clamp(min, 1.0))
+    }
+}
+
+// ============================================================================
+// Components and state
+// ============================================================================
+
+/// Opts a 3D camera into dynamic resolution and carries its current scale.
+///
+/// The scale is a fraction of the camera's physical viewport size per axis;
+/// the plugin's controller writes it, and a host may set it directly while
+/// [`DynamicResolutionConfig::enabled`] is off to pin a fixed scale.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DynamicResolution {
+    /// Current render scale (0–1].
+    pub scale: f32,
+}
+
+impl Default for DynamicResolution {
+    fn default() -> Self {
+        Self { scale: 1.0 }
+    }
+}
+
+/// Where the controller's frame time comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameTimeSource {
+    /// Sum of the render passes' GPU timestamps.
+    #[default]
+    Gpu,
+    /// Whole-frame wall-clock time, on backends without timestamp queries
+    /// (Metal, WebGPU) or when render diagnostics are off.
+    FrameTime,
+}
+
+/// The controller's smoothed frame-time reading, for display.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct DynamicResolutionStats {
+    /// Smoothed frame time (ms); `None` until the first sample.
+    pub frame_ms: Option<f32>,
+    /// What [`frame_ms`](Self::frame_ms) measures.
+    pub source: FrameTimeSource,
+}
+
+// ============================================================================
+// Controller
+// ============================================================================
+
+/// Render-diagnostic samples older than this belong to passes that have
+/// stopped running (one-shot bakes) and are left out of the frame total.
+const STALE_SAMPLE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Smooth this frame's GPU time into [`DynamicResolutionStats`].
+///
+/// The GPU time is the sum of the top-level render passes' `elapsed_gpu`
+/// diagnostics (`render/<pass>/elapsed_gpu`, recorded by Bevy's
+/// `RenderDiagnosticsPlugin`); nested spans are already inside their parent's
+/// time. Without any, the frame's wall-clock time stands in.
+fn measure_frame_time(
+    time: Res<Time>,
+    config: Res<DynamicResolutionConfig>,
+    diagnostics: Option<Res<DiagnosticsStore>>,
+    mut stats: ResMut<DynamicResolutionStats>,
+) {
+    let now = Instant::now();
+    let gpu_ms = diagnostics.as_deref().and_then(|diagnostics| {
+        let mut total = None;
+        for diagnostic in diagnostics.iter() {
+            let Some(pass) = diagnostic
+                .path()
+                .as_str()
+                .strip_prefix("render/")
+                .and_then(|rest| rest.strip_suffix("/elapsed_gpu"))
+            else {
+                continue;
+            };
+            if pass.contains('/') {
+                continue;
+            }
+            if let Some(measurement) = diagnostic.measurement()
+                && now.saturating_duration_since(measurement.time) <= STALE_SAMPLE
+            {
+                *total.get_or_insert(0.0) += measurement.value as f32;
+            }
+        }
+        total
+    });
+
+    let (sample, source) = match gpu_ms {
+        Some(ms) => (ms, FrameTimeSource::Gpu),
+        None => (time.delta_secs() * 1000.0, FrameTimeSource::FrameTime),
+    };
+    let smoothed = match stats.frame_ms {
+        Some(previous) if stats.source == source => {
+            let alpha = 1.0 - (-time.delta_secs() / config.smoothing_s.max(1e-3)).exp();
+            previous + (sample - previous) * alpha
+        }
+        _ => sample,
+    };
+    *stats = DynamicResolutionStats {
+        frame_ms: Some(smoothed),
+        source,
+    };
+}
+
+/// Step each camera's render scale towards the frame-time target.
+fn adjust_render_scale(
+    time: Res<Time>,
+    config: Res<DynamicResolutionConfig>,
+    stats: Res<DynamicResolutionStats>,
+    mut since_adjust: Local<f32>,
+    mut cameras: Query<&mut DynamicResolution>,
+) {
+    let (min, max) = config.limits();
+    if !config.enabled {
+        for mut camera in &mut cameras {
+            if camera.scale != max {
+                camera.scale = max;
+            }
+        }
+        return;
+    }
+
+    *since_adjust += time.delta_secs();
+    if *since_adjust < config.adjust_interval_s {
+        return;
+    }
+    *since_adjust = 0.0;
+    let Some(frame_ms) = stats.frame_ms else {
+        return;
+    };
+    for mut camera in &mut cameras {
+        let scale = next_scale(camera.scale.clamp(min, max), frame_ms, &config);
+        if scale != camera.scale {
+            camera.scale = scale;
+        }
+    }
+}
+
+/// The render scale to move to from `current` given the smoothed frame time.
+///
+/// Shading cost follows the pixel count, so the scale that would just meet the
+/// target is `current · √(target / frame)`. Over budget the scale drops towards
+/// it; under `upscale_headroom · target` it rises; in between it holds. Either
+/// way the change is capped at `max_change` and snapped down to whole
+/// `scale_step`s, so a rise needs room for at least one full step.
+fn next_scale(current: f32, frame_ms: f32, config: &DynamicResolutionConfig) -> f32 {
+    let (min, max) = config.limits();
+    let step = config.scale_step.max(0.01);
+    let max_change = config.max_change.max(step);
+    let ideal = current * (config.target_gpu_ms / frame_ms.max(0.01)).sqrt();
+
+    let next = if frame_ms > config.target_gpu_ms {
+        let lowered = (current - max_change).max(ideal);
+        // Always move at least one step down while over budget.
+        snap_down(lowered, step).min(current - step)
+    } else if frame_ms < config.target_gpu_ms * config.upscale_headroom {
+        let raised = (current + max_change).min(ideal);
+        snap_down(raised, step).max(current)
+    } else {
+        current
+    };
+    next.clamp(min, max)
+}
+
+/// Snap `value` down to a multiple of `step`, tolerating float error just
+/// below a grid line.
+fn snap_down(value: f32, step: f32) -> f32 {
+    (value / step + 1e-3).floor() * step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DynamicResolutionConfig {
+        DynamicResolutionConfig {
+            enabled: true,
+            target_gpu_ms: 20.0,
+            upscale_headroom: 0.8,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            scale_step: 0.05,
+            max_change: 0.15,
+            adjust_interval_s: 0.5,
+            smoothing_s: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_over_budget_lowers_scale() {
+        let config = config();
+        let next = next_scale(1.0, 25.0, &config);
+        // √(20 / 25) ≈ 0.894, snapped down to the step grid.
+        assert!((next - 0.85).abs() < 1e-4, "{next}");
+        // Slightly over budget still moves a whole step.
+        let next = next_scale(1.0, 20.5, &config);
+        assert!((next - 0.95).abs() < 1e-4, "{next}");
+    }
+
+    #[test]
+    fn test_change_is_capped() {
+        let config = config();
+        let next = next_scale(1.0, 100.0, &config);
+        assert!((next - 0.85).abs() < 1e-4, "{next}");
+        let next = next_scale(0.5, 1.0, &config);
+        assert!((next - 0.65).abs() < 1e-4, "{next}");
+    }
+
+    #[test]
+    fn test_holds_inside_hysteresis_band() {
+        let config = config();
+        assert_eq!(next_scale(0.75, 18.0, &config), 0.75);
+        assert_eq!(next_scale(0.75, 20.0, &config), 0.75);
+    }
+
+    #[test]
+    fn test_under_budget_raises_scale() {
+        let config = config();
+        // √(20 / 12) ≈ 1.29 × 0.7 ≈ 0.90, capped at +0.15.
+        let next = next_scale(0.7, 12.0, &config);
+        assert!((next - 0.85).abs() < 1e-4, "{next}");
+        // Not enough headroom for a whole step: hold.
+        let coarse = DynamicResolutionConfig {
+            scale_step: 0.1,
+            ..config
+        };
+        let next = next_scale(0.7, 15.5, &coarse);
+        assert!((next - 0.7).abs() < 1e-4, "{next}");
+    }
+
+    #[test]
+    fn test_scale_respects_limits() {
+        let config = config();
+        assert_eq!(next_scale(0.5, 100.0, &config), 0.5);
+        assert_eq!(next_scale(1.0, 1.0, &config), 1.0);
+        let inverted = DynamicResolutionConfig {
+            min_scale: 0.9,
+            max_scale: 0.6,
+            ..config
+        };
+        assert_eq!(inverted.limits(), (0.9, 0.9));
+    }
+}
@@ -0,0 +1,25 @@
+// Dynamic resolution upscale.
+//
+// The main pass rendered only the top-left `render_size` pixels of the view
+// target; stretch that region over the whole target with bilinear filtering.
+// The UV is clamped half a texel inside the region so the filter never blends
+// in the stale pixels beyond it.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct ViewUpscale {
+    render_size: vec2<f32>,
+    full_size: vec2<f32>,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> upscale: ViewUpscale;
+
+@fragment
+fn fs_main(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let uv_min = vec2(0.5) / upscale.full_size;
+    let uv_max = (upscale.render_size - 0.5) / upscale.full_size;
+    let uv = clamp(in.uv * upscale.render_size / upscale.full_size, uv_min, uv_max);
+    return textureSampleLevel(source, source_sampler, uv, 0.0);
+}
@@ -0,0 +1,280 @@
+//! Render-world half: the main-pass resolution override and the upscale pass.
+//!
+//! Each frame a scaled camera's render entity gets a `MainPassResolutionOverride`
+//! (which every main pass honours by shrinking its viewport) and a
+//! [`ViewUpscale`] uniform. After the main pass, [`DynamicResolutionNode::Upscale`]
+//! bilinearly stretches the rendered region over the whole view target, ahead
+//! of bloom and tone mapping so those run at full resolution.
+
+use bevy::{
+    asset::{embedded_asset, load_embedded_asset},
+    camera::MainPassResolutionOverride,
+    core_pipeline::{
+        FullscreenShader,
+        core_3d::graph::{Core3d, Node3d},
+    },
+    ecs::{query::QueryItem, system::lifetimeless::Read},
+    image::BevyDefault as _,
+    prelude::*,
+    render::{
+        Extract, ExtractSchedule, Render, RenderApp, RenderStartup, RenderSystems,
+        diagnostic::RecordDiagnostics,
+        extract_component::{ComponentUniforms, DynamicUniformIndex, UniformComponentPlugin},
+        render_graph::{
+            NodeRunError, RenderGraphContext, RenderGraphExt, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        sync_world::RenderEntity,
+        view::{ExtractedView, ViewTarget},
+    },
+    shader::Shader,
+};
+
+use crate::DynamicResolution;
+
+/// Render-graph labels for the dynamic resolution passes.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash, RenderLabel)]
+pub enum DynamicResolutionNode {
+    /// Stretches the scaled main-pass region over the full view target.
+    Upscale,
+}
+
+pub(crate) struct UpscalePlugin;
+
+impl Plugin for UpscalePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/upscale.wgsl");
+
+        app.add_plugins(UniformComponentPlugin::<ViewUpscale>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<SpecializedRenderPipelines<UpscalePipeline>>()
+            .add_systems(RenderStartup, init_upscale_pipeline)
+            .add_systems(ExtractSchedule, extract_dynamic_resolution)
+            .add_systems(Render, queue_upscale_pipelines.in_set(RenderSystems::Queue))
+            .add_render_graph_node::<ViewNodeRunner<UpscaleNode>>(
+                Core3d,
+                DynamicResolutionNode::Upscale,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::EndMainPass,
+                    DynamicResolutionNode::Upscale,
+                    Node3d::Bloom,
+                    Node3d::Tonemapping,
+                ),
+            );
+    }
+}
+
+/// Per-view upscale parameters, present only while a view renders below its
+/// full resolution.
+#[derive(Component, ShaderType, Clone, Copy, Debug)]
+pub struct ViewUpscale {
+    /// Size of the region the main pass rendered (physical pixels).
+    pub render_size: Vec2,
+    /// Size of the full view target (physical pixels).
+    pub full_size: Vec2,
+}
+
+/// Translate each scaled camera's [`DynamicResolution`] into a main-pass
+/// override and upscale uniform on its render entity, and strip both from
+/// views that are back at full resolution.
+fn extract_dynamic_resolution(
+    mut commands: Commands,
+    cameras: Extract<Query<(RenderEntity, &Camera, Option<&DynamicResolution>), With<Camera3d>>>,
+    upscaled: Query<(), With<ViewUpscale>>,
+) {
+    for (entity, camera, dynamic) in &cameras {
+        let sizes = dynamic
+            .filter(|_| camera.is_active)
+            .zip(camera.physical_viewport_size())
+            .map(|(dynamic, full)| {
+                let render = (full.as_vec2() * dynamic.scale.clamp(0.0, 1.0))
+                    .round()
+                    .as_uvec2()
+                    .clamp(UVec2::ONE, full);
+                (render, full)
+            })
+            .filter(|(render, full)| render != full);
+
+        match sizes {
+            Some((render, full)) => {
+                commands.entity(entity).insert((
+                    MainPassResolutionOverride(render),
+                    ViewUpscale {
+                        render_size: render.as_vec2(),
+                        full_size: full.as_vec2(),
+                    },
+                ));
+            }
+            None if upscaled.contains(entity) => {
+                commands
+                    .entity(entity)
+                    .remove::<(MainPassResolutionOverride, ViewUpscale, UpscalePipelineId)>();
+            }
+            None => {}
+        }
+    }
+}
+
+// ============================================================================
+// Pipeline
+// ============================================================================
+
+#[derive(Resource)]
+pub(crate) struct UpscalePipeline {
+    layout: BindGroupLayoutDescriptor,
+    sampler: Sampler,
+    fullscreen_shader: FullscreenShader,
+    fragment_shader: Handle<Shader>,
+}
+
+fn init_upscale_pipeline(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    fullscreen_shader: Res<FullscreenShader>,
+    asset_server: Res<AssetServer>,
+) {
+    let layout = BindGroupLayoutDescriptor::new(
+        "dynamic_resolution_upscale_bind_group_layout",
+        &BindGroupLayoutEntries::sequential(
+            ShaderStages::FRAGMENT,
+            (
+                texture_2d(TextureSampleType::Float { filterable: true }),
+                sampler(SamplerBindingType::Filtering),
+                uniform_buffer::<ViewUpscale>(true),
+            ),
+        ),
+    );
+    let sampler = render_device.create_sampler(&SamplerDescriptor {
+        label: Some("dynamic_resolution_upscale_sampler"),
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        ..default()
+    });
+
+    commands.insert_resource(UpscalePipeline {
+        layout,
+        sampler,
+        fullscreen_shader: fullscreen_shader.clone(),
+        fragment_shader: load_embedded_asset!(asset_server.as_ref(), "shaders/upscale.wgsl"),
+    });
+}
+
+impl SpecializedRenderPipeline for UpscalePipeline {
+    /// The view target's colour format.
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("dynamic_resolution_upscale_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: self.fullscreen_shader.to_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.fragment_shader.clone(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                ..default()
+            }),
+            ..default()
+        }
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct UpscalePipelineId(CachedRenderPipelineId);
+
+fn queue_upscale_pipelines(
+    mut commands: Commands,
+    views: Query<(Entity, &ExtractedView), With<ViewUpscale>>,
+    pipeline_cache: Res<PipelineCache>,
+    pipeline: Res<UpscalePipeline>,
+    mut specializer: ResMut<SpecializedRenderPipelines<UpscalePipeline>>,
+) {
+    for (entity, view) in &views {
+        let format = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let id = specializer.specialize(&pipeline_cache, &pipeline, format);
+        commands.entity(entity).insert(UpscalePipelineId(id));
+    }
+}
+
+// ============================================================================
+// Node
+// ============================================================================
+
+#[derive(Default)]
+pub(crate) struct UpscaleNode;
+
+impl ViewNode for UpscaleNode {
+    type ViewQuery = (
+        Read<ViewTarget>,
+        Read<UpscalePipelineId>,
+        Read<DynamicUniformIndex<ViewUpscale>>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, pipeline_id, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let upscale_pipeline = world.resource::<UpscalePipeline>();
+        let (Some(render_pipeline), Some(uniforms)) = (
+            pipeline_cache.get_render_pipeline(pipeline_id.0),
+            world.resource::<ComponentUniforms<ViewUpscale>>().binding(),
+        ) else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "dynamic_resolution_upscale_bind_group",
+            &pipeline_cache.get_bind_group_layout(&upscale_pipeline.layout),
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &upscale_pipeline.sampler,
+                uniforms,
+            )),
+        );
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("dynamic_resolution_upscale"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let span = diagnostics.pass_span(&mut pass, "dynamic_resolution_upscale");
+
+        pass.set_render_pipeline(render_pipeline);
+        pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        pass.draw(0..3, 0..1);
+
+        span.end(&mut pass);
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Ambient light from the sky.
+//!
+//! The fixed ambient floor [`CelestialLightsPlugin`](crate::celestial_lights)
+//! spawns is tuned for night, so at low sun (and wherever the sky
+//! environment map isn't lighting the scene) shadowed terrain goes nearly
+//! black. [`SkyAmbientPlugin`] drives [`GlobalAmbientLight`] from the sun
+//! instead: its brightness follows the sun's elevation through twilight, and
+//! its colour blends a clear-sky blue with the horizon tint the CPU
+//! transmittance gives sunlight at the camera, so sunsets fill shadows warm.
+
+use bevy::{light::GlobalAmbientLight, pbr::ScatteringMedium, prelude::*};
+use serde::Deserialize;
+use veldera_atmosphere::{
+    AtmosphereSettings, SphericalAtmosphere, SphericalAtmosphereEnvironmentMapLight,
+    compute_sun_transmittance,
+};
+
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use crate::{atmosphere::AtmosphereConfig, time_of_day::Sun};
+
+/// Linear colour of the clear sky with the sun high (scaled to unit
+/// luminance before use).
+const ZENITH_TINT: Vec3 = Vec3::new(0.62, 0.78, 1.0);
+
+/// Sun elevation cosine at the end of civil twilight (6° below the horizon),
+/// where skylight has faded out.
+const TWILIGHT_END_MU: f32 = -0.105;
+
+/// Sun elevation cosine past which the sky is at full daytime brightness.
+const FULL_DAY_MU: f32 = 0.3;
+
+/// Elevation cosine the horizon tint is sampled at when the sun is at or
+/// below the horizon, where the transmittance itself goes to zero.
+const HORIZON_SAMPLE_MU: f32 = 0.02;
+
+/// Rec. 709 luminance weights.
+const LUMINANCE: Vec3 = Vec3::new(0.2126, 0.7152, 0.0722);
+
+/// Tuning for the sky-driven ambient light. Lives in the `[ambient]` table of
+/// the atmosphere config.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SkyAmbientConfig {
+    /// Drive the ambient light from the sun. When off, the ambient holds at
+    /// `floor`.
+    pub enabled: bool,
+    /// Ambient luminance that's always present (cd/m²), for moonless nights.
+    pub floor: f32,
+    /// Sky ambient luminance with the sun high (cd/m²).
+    pub day: f32,
+    /// Fraction of `day` applied while the sky environment map is lighting
+    /// the scene, which already carries most of the skylight.
+    pub environment_map_share: f32,
+}
+
+impl Default for SkyAmbientConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            floor: 50.0,
+            day: 2000.0,
+            environment_map_share: 0.15,
+        }
+    }
+}
+
+/// Keeps [`GlobalAmbientLight`] in step with the sun.
+pub struct SkyAmbientPlugin;
+
+impl Plugin for SkyAmbientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, update_sky_ambient);
+    }
+}
+
+/// The ambient light for a sun at elevation cosine `sun_mu`, with sunlight
+/// at the camera tinted `horizon_tint`. `sky_share` scales the skylight
+/// above the floor. Returns the light's colour (unit luminance) and its
+/// luminance (cd/m²).
+pub fn sky_ambient(
+    config: &SkyAmbientConfig,
+    sun_mu: f32,
+    horizon_tint: Vec3,
+    sky_share: f32,
+) -> (Vec3, f32) {
+    let daylight = smoothstep(TWILIGHT_END_MU, FULL_DAY_MU, sun_mu);
+    // The tint only shows around sunrise and sunset; through the blue hour
+    // and the day the sky overhead is blue.
+    let warmth = smoothstep(TWILIGHT_END_MU * 0.5, 0.0, sun_mu)
+        * (1.0 - smoothstep(0.0, FULL_DAY_MU, sun_mu));
+    let tint = ZENITH_TINT.lerp(unit_luminance(horizon_tint), warmth);
+
+    let sky = config.day * daylight * sky_share;
+    let floor = config.floor.max(0.0);
+    let total = floor + sky;
+    if total <= 0.0 {
+        return (Vec3::ONE, 0.0);
+    }
+    let colour = (Vec3::ONE * floor + unit_luminance(tint) * sky) / total;
+    (colour, total)
+}
+
+/// Set the ambient light from the sun's elevation at the camera.
+fn update_sky_ambient(
+    config: Res<AtmosphereConfig>,
+    camera: Query<(
+        &FloatingOriginCamera,
+        &SphericalAtmosphere,
+        &AtmosphereSettings,
+        Has<SphericalAtmosphereEnvironmentMapLight>,
+    )>,
+    sun: Query<&Transform, With<Sun>>,
+    media: Res<Assets<ScatteringMedium>>,
+    ambient: Option<ResMut<GlobalAmbientLight>>,
+) {
+    let Some(mut ambient) = ambient else {
+        return;
+    };
+    let config = &config.ambient;
+    let (Ok((camera, atmosphere, settings, has_environment_map)), Ok(sun)) =
+        (camera.single(), sun.single())
+    else {
+        return;
+    };
+
+    let (colour, luminance) = if config.enabled {
+        let local_up = camera.position.normalize().as_vec3();
+        let sun_mu = sun.back().as_vec3().dot(local_up);
+        let horizon_tint = media.get(&atmosphere.medium).map_or(Vec3::ONE, |medium| {
+            compute_sun_transmittance(
+                atmosphere,
+                medium,
+                camera.position.length() as f32,
+                sun_mu.max(HORIZON_SAMPLE_MU),
+                settings.sun_transmittance_midpoint_ratio,
+            )
+        });
+        let sky_share = if has_environment_map && settings.environment_map {
+            config.environment_map_share
+        } else {
+            1.0
+        };
+        sky_ambient(config, sun_mu, horizon_tint, sky_share)
+    } else {
+        (Vec3::ONE, config.floor)
+    };
+
+    let colour = Color::linear_rgb(colour.x, colour.y, colour.z);
+    if ambient.color != colour || ambient.brightness != luminance {
+        ambient.color = colour;
+        ambient.brightness = luminance;
+    }
+}
+
+/// `colour` scaled to unit luminance; black stays black.
+fn unit_luminance(colour: Vec3) -> Vec3 {
+    let luminance = colour.dot(LUMINANCE);
+    if luminance > 1e-6 {
+        colour / luminance
+    } else {
+        Vec3::ZERO
+    }
+}
+
+pub(crate) fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WARM: Vec3 = Vec3::new(1.0, 0.5, 0.2);
+
+    #[test]
+    fn night_holds_the_floor() {
+        let config = SkyAmbientConfig::default();
+        let (colour, luminance) = sky_ambient(&config, -0.5, WARM, 1.0);
+        assert_eq!(luminance, config.floor);
+        assert_eq!(colour, Vec3::ONE);
+    }
+
+    #[test]
+    fn brightens_through_twilight() {
+        let config = SkyAmbientConfig::default();
+        let at = |mu| sky_ambient(&config, mu, WARM, 1.0).1;
+        assert!(at(-0.05) > config.floor);
+        assert!(at(0.0) > at(-0.05));
+        assert!(at(0.1) > at(0.0));
+        assert_eq!(at(0.9), config.floor + config.day);
+    }
+
+    #[test]
+    fn sunset_is_warmer_than_noon() {
+        let config = SkyAmbientConfig::default();
+        let sunset = sky_ambient(&config, 0.0, WARM, 1.0).0;
+        let noon = sky_ambient(&config, 0.9, WARM, 1.0).0;
+        assert!(sunset.x / sunset.z > noon.x / noon.z);
+    }
+}
@@ -14,15 +14,17 @@ use bevy::{
 };
 use serde::Deserialize;
 use veldera_atmosphere::{
-    AtmosphereSettings, ExtractedAtmosphereLights, GpuAtmosphereLight, MAX_ATMOSPHERE_LIGHTS,
-    SphericalAtmosphere, SphericalAtmosphereCamera, SphericalAtmosphereEnvironmentMapLight,
-    compute_sun_transmittance,
+    AtmosphereArtistic, AtmosphereSettings, ExtractedAtmosphereLights, GpuAtmosphereLight,
+    MAX_ATMOSPHERE_LIGHTS, SphericalAtmosphere, SphericalAtmosphereCamera,
+    SphericalAtmosphereEnvironmentMapLight, compute_sun_transmittance,
 };
 
 use veldera_config::ConfigPlugin;
 use veldera_constants::{ATMOSPHERE_TOP_RADIUS_M, EARTH_RADIUS_M};
 use veldera_geo::floating_origin::FloatingOriginCamera;
 
+use crate::{ambient::SkyAmbientConfig, weather::WeatherConfig};
+
 /// Hot-reloadable atmosphere tuning, loaded from
 /// `assets/config/engine/rendering/atmosphere.toml`.
 ///
@@ -43,6 +45,14 @@ pub struct AtmosphereConfig {
     /// fields hot-reload (the LUT textures are descriptor-cached, so a size
     /// change reallocates them).
     pub settings: AtmosphereSettings,
+    /// Artistic multipliers on the sky and aerial perspective (scattering
+    /// strength, horizon haze, tint). Applied at composite time, so they
+    /// hot-reload without rebuilding the LUTs.
+    pub artistic: AtmosphereArtistic,
+    /// The sky-driven ambient light (see [`crate::ambient`]).
+    pub ambient: SkyAmbientConfig,
+    /// Precipitation and fog tuning (see [`crate::weather`]).
+    pub weather: WeatherConfig,
 }
 
 /// Plugin that integrates spherical atmosphere with floating origin cameras.
@@ -104,7 +114,12 @@ impl Plugin for AtmosphereIntegrationPlugin {
 /// We pack the *unattenuated* emission (base_color × illuminance) plus disk
 /// parameters for each entity bearing [`AtmosphericLight`]. The atmosphere
 /// crate's render-world `prepare_atmosphere_lights_buffer` consumes this and
-/// writes the GPU uniform.
+/// writes the GPU uniform, and the shaders accumulate the scattering of every
+/// light in it, so a moonlit night gets its faint skylight from the moon.
+///
+/// Dark lights (a new moon, or one with its illuminance zeroed) are dropped
+/// so they cost no shader work, and if more than [`MAX_ATMOSPHERE_LIGHTS`]
+/// remain the brightest win rather than whichever iterate first.
 #[allow(clippy::type_complexity)]
 fn extract_atmosphere_lights(
     lights: Extract<
@@ -113,38 +128,45 @@ fn extract_atmosphere_lights(
             &DirectionalLight,
             &GlobalTransform,
             Option<&SunDisk>,
+            Option<&LightEclipse>,
         )>,
     >,
     mut extracted: ResMut<ExtractedAtmosphereLights>,
 ) {
-    let mut data = ExtractedAtmosphereLights::default();
-    let mut count: usize = 0;
-    for (atmo, dl, gt, sun_disk) in lights.iter() {
-        if count >= MAX_ATMOSPHERE_LIGHTS {
-            break;
-        }
-        // `Transform::looking_to(-direction, up)` made the entity's `back`
-        // axis point toward the light source. Use `GlobalTransform` so the
-        // value already reflects the latest update.
-        let direction_to_light = gt.back().as_vec3();
-        let base = atmo.base_color;
-        let color = Vec3::new(base.red, base.green, base.blue) * dl.illuminance;
-        // Match Bevy's `extract_lights`: when `SunDisk` is missing, fall
-        // back to `SunDisk::EARTH`, so a bare `DirectionalLight` still
-        // renders a visible disk in the atmosphere shader.
-        let (sun_disk_angular_size, sun_disk_intensity) = sun_disk
-            .map(|s| (s.angular_size, s.intensity))
-            .unwrap_or_else(|| (SunDisk::EARTH.angular_size, SunDisk::EARTH.intensity));
+    let mut candidates: Vec<GpuAtmosphereLight> = lights
+        .iter()
+        .map(|(atmo, dl, gt, sun_disk, eclipse)| {
+            // `Transform::looking_to(-direction, up)` made the entity's `back`
+            // axis point toward the light source. Use `GlobalTransform` so the
+            // value already reflects the latest update.
+            let direction_to_light = gt.back().as_vec3();
+            let base = atmo.base_color;
+            let color = Vec3::new(base.red, base.green, base.blue)
+                * dl.illuminance
+                * eclipse.map_or(Vec3::ONE, |e| e.transmission);
+            // Match Bevy's `extract_lights`: when `SunDisk` is missing, fall
+            // back to `SunDisk::EARTH`, so a bare `DirectionalLight` still
+            // renders a visible disk in the atmosphere shader.
+            let (sun_disk_angular_size, sun_disk_intensity) = sun_disk
+                .map(|s| (s.angular_size, s.intensity))
+                .unwrap_or_else(|| (SunDisk::EARTH.angular_size, SunDisk::EARTH.intensity));
+            GpuAtmosphereLight {
+                direction_to_light,
+                sun_disk_angular_size,
+                color,
+                sun_disk_intensity,
+            }
+        })
+        .filter(|light| light.color.max_element() > 0.0)
+        .collect();
+    candidates.sort_by(|a, b| b.color.max_element().total_cmp(&a.color.max_element()));
+    candidates.truncate(MAX_ATMOSPHERE_LIGHTS);
 
-        data.0.lights[count] = GpuAtmosphereLight {
-            direction_to_light,
-            sun_disk_angular_size,
-            color,
-            sun_disk_intensity,
-        };
-        count += 1;
+    let mut data = ExtractedAtmosphereLights::default();
+    data.0.count = candidates.len() as u32;
+    for (slot, light) in data.0.lights.iter_mut().zip(candidates) {
+        *slot = light;
     }
-    data.0.count = count as u32;
     *extracted = data;
 }
 
@@ -160,6 +182,25 @@ pub struct AtmosphericLight {
     pub base_color: LinearRgba,
 }
 
+/// Eclipse hook for an [`AtmosphericLight`]: scales its emission per channel
+/// before it reaches the sky, its disk and surface lighting. For a lunar
+/// eclipse, a dim red `transmission` on the Moon mimics the light the Earth's
+/// atmosphere bends into its shadow. What drives the value is up to the
+/// caller; without the component there is no eclipse.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LightEclipse {
+    /// Linear RGB fraction of the light still arriving (1 = no eclipse).
+    pub transmission: Vec3,
+}
+
+impl Default for LightEclipse {
+    fn default() -> Self {
+        Self {
+            transmission: Vec3::ONE,
+        }
+    }
+}
+
 /// Syncs `SphericalAtmosphereCamera` from `FloatingOriginCamera`.
 ///
 /// This system updates the atmosphere camera's local_up and camera_radius
@@ -200,16 +241,24 @@ fn sync_atmosphere_camera(
 /// imperceptible; at sunset it makes the sky a touch dimmer than fully
 /// physical. Removing that requires a separate "atmosphere light color"
 /// channel in the LUT shaders, which we can revisit if it becomes noticeable.
+///
+/// The floating-origin camera's atmosphere is the one used; other cameras
+/// (e.g. the eyes of a stereo view) carry copies of it.
 fn update_atmospheric_light_extinction(
-    camera: Query<&FloatingOriginCamera>,
-    atmospheres: Query<(&SphericalAtmosphere, &AtmosphereSettings), With<Camera3d>>,
+    camera: Query<(
+        &FloatingOriginCamera,
+        &SphericalAtmosphere,
+        &AtmosphereSettings,
+    )>,
     media: Res<Assets<ScatteringMedium>>,
-    mut lights: Query<(&Transform, &mut DirectionalLight, &AtmosphericLight)>,
+    mut lights: Query<(
+        &Transform,
+        &mut DirectionalLight,
+        &AtmosphericLight,
+        Option<&LightEclipse>,
+    )>,
 ) {
-    let Ok(camera) = camera.single() else {
-        return;
-    };
-    let Ok((atmosphere, settings)) = atmospheres.single() else {
+    let Ok((camera, atmosphere, settings)) = camera.single() else {
         return;
     };
     let Some(medium) = media.get(&atmosphere.medium) else {
@@ -219,7 +268,7 @@ fn update_atmospheric_light_extinction(
     let r = camera.position.length() as f32;
     let local_up = camera.position.normalize().as_vec3();
 
-    for (transform, mut light, atmo_light) in &mut lights {
+    for (transform, mut light, atmo_light, eclipse) in &mut lights {
         // The light's transform is `looking_to(-direction, Z)`, so its back
         // axis points toward the light source.
         let dir = transform.back().as_vec3();
@@ -236,6 +285,7 @@ fn update_atmospheric_light_extinction(
         } else {
             Vec3::ONE
         };
+        let transmittance = transmittance * eclipse.map_or(Vec3::ONE, |e| e.transmission);
         let base = atmo_light.base_color;
         light.color = Color::LinearRgba(LinearRgba::new(
             base.red * transmittance.x,
@@ -277,6 +327,7 @@ impl AtmosphereBundle {
                 top_radius: ATMOSPHERE_TOP_RADIUS_M,
                 ground_albedo: Vec3::from_array(config.ground_albedo),
                 medium,
+                artistic: config.artistic,
             },
             camera: SphericalAtmosphereCamera::from_ecef(initial_ecef),
             settings: config.settings.clone(),
@@ -291,10 +342,10 @@ impl AtmosphereBundle {
 }
 
 /// Apply [`AtmosphereConfig`] to the live atmosphere components whenever the
-/// config reloads, so editing `atmosphere.toml` updates the ground albedo and
-/// the [`AtmosphereSettings`] (LUT sizes, samples, render method) without
-/// restarting. The camera spawn does the initial build; this handles subsequent
-/// edits.
+/// config reloads, so editing `atmosphere.toml` updates the ground albedo, the
+/// artistic overrides and the [`AtmosphereSettings`] (LUT sizes, samples,
+/// render method) without restarting. The camera spawn does the initial build;
+/// this handles subsequent edits.
 fn apply_atmosphere_config(
     config: Res<AtmosphereConfig>,
     mut atmospheres: Query<&mut SphericalAtmosphere>,
@@ -306,6 +357,7 @@ fn apply_atmosphere_config(
     let albedo = Vec3::from_array(config.ground_albedo);
     for mut atmosphere in &mut atmospheres {
         atmosphere.ground_albedo = albedo;
+        atmosphere.artistic = config.artistic;
     }
     for mut s in &mut settings {
         *s = config.settings.clone();
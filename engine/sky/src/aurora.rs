@@ -0,0 +1,375 @@
+//! Aurora: glowing curtains over the auroral ovals.
+//!
+//! A ring of curtain hangs around each geomagnetic pole in the shell between
+//! 90 and 150 km, where electrons from the magnetosphere set atomic oxygen
+//! glowing: green low down, fading through a faint red-violet fringe above.
+//! The ovals sag toward the night side, where they're brightest, and fold and
+//! ripple over time.
+//!
+//! The curtains are one unlit, additively blended mesh, rebuilt every frame.
+//! Each vertex's glow is attenuated by the atmosphere between it and the
+//! camera, integrated on the CPU through the sky's own medium, so the
+//! curtains dim and redden toward the horizon and drop out behind it. They
+//! fade in as the sky at the camera darkens through twilight, so from the
+//! ground they only show at night.
+
+use std::f64::consts::TAU;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    camera::visibility::NoFrustumCulling,
+    light::NotShadowCaster,
+    mesh::{Indices, PrimitiveTopology},
+    pbr::ScatteringMedium,
+    prelude::*,
+};
+use glam::DVec3;
+use veldera_atmosphere::{AtmosphereSettings, SphericalAtmosphere, compute_sun_transmittance};
+use veldera_geo::{
+    coords::lat_lon_to_ecef,
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+
+use crate::{ambient::smoothstep, time_of_day::Sun};
+
+/// Geomagnetic poles (IGRF-14, 2025) as latitude and longitude in degrees.
+const GEOMAGNETIC_POLES_DEG: [(f64, f64); 2] = [(80.8, -72.6), (-80.8, 107.4)];
+
+/// Columns of curtain around each oval.
+const COLUMNS: usize = 192;
+
+/// Curtain rows, bottom to top: altitude (m) and glow (linear RGB). The
+/// lowest and highest rows are dark so the curtain fades out at its edges.
+const ROWS: [(f64, Vec3); 5] = [
+    (90_000.0, Vec3::ZERO),
+    (100_000.0, Vec3::new(0.12, 1.0, 0.3)),
+    (120_000.0, Vec3::new(0.1, 0.6, 0.2)),
+    (140_000.0, Vec3::new(0.3, 0.05, 0.2)),
+    (150_000.0, Vec3::ZERO),
+];
+
+/// Angular radius of the oval around its geomagnetic pole (degrees).
+const OVAL_COLATITUDE_DEG: f64 = 19.0;
+
+/// How much further from the pole the oval reaches at midnight than its
+/// mean, and how much less at noon (degrees).
+const OVAL_MIDNIGHT_SAG_DEG: f64 = 3.5;
+
+/// Folds in the curtain: angular amplitude (degrees), waves around the oval,
+/// and drift rate (rad/s).
+const FOLDS: [(f64, f64, f64); 3] = [(1.2, 3.0, 0.02), (0.4, 11.0, -0.11), (0.15, 37.0, 0.5)];
+
+/// Sun elevation cosine (2° below the horizon) above which the sky drowns
+/// the aurora out.
+const DUSK_MU: f32 = -0.035;
+
+/// Sun elevation cosine (12° below the horizon, nautical dusk) past which
+/// the aurora shows at full strength.
+const NIGHT_MU: f32 = -0.208;
+
+/// Floor under the divisor in [`segment_transmittance`].
+const MIN_TRANSMITTANCE: f32 = 1e-6;
+
+/// Plugin for the aurora.
+pub struct AuroraPlugin;
+
+impl Plugin for AuroraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Aurora>()
+            .init_resource::<AuroraViz>()
+            .add_systems(Update, update_aurora);
+    }
+}
+
+/// Settings for the aurora.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Aurora {
+    /// Whether the curtains are drawn.
+    pub enabled: bool,
+    /// Multiplier on the curtains' glow; 1 is a moderately active display.
+    pub intensity: f32,
+}
+
+impl Default for Aurora {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// The curtain entity and its mesh, rebuilt in place each frame. The entity
+/// is anchored at the planet's centre and hidden while the aurora is off.
+#[derive(Resource)]
+struct AuroraViz {
+    entity: Entity,
+    mesh: Handle<Mesh>,
+}
+
+impl FromWorld for AuroraViz {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(curtain_mesh());
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                // Emitted light adds to whatever is behind it.
+                alpha_mode: AlphaMode::Add,
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            });
+        let entity = world
+            .spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material),
+                Transform::default(),
+                WorldPosition::from_dvec3(DVec3::ZERO),
+                Visibility::Hidden,
+                NotShadowCaster,
+                // The bounds change every frame, and the ovals span the
+                // globe anyway.
+                NoFrustumCulling,
+                Name::new("aurora"),
+            ))
+            .id();
+        Self { entity, mesh }
+    }
+}
+
+/// The curtain mesh's vertex layout and triangles, with every vertex at the
+/// origin until the first rebuild.
+fn curtain_mesh() -> Mesh {
+    let vertices = GEOMAGNETIC_POLES_DEG.len() * COLUMNS * ROWS.len();
+    let mut indices = Vec::new();
+    for oval in 0..GEOMAGNETIC_POLES_DEG.len() {
+        for column in 0..COLUMNS {
+            let next = (column + 1) % COLUMNS;
+            for row in 0..ROWS.len() - 1 {
+                let index = |column: usize, row: usize| {
+                    ((oval * COLUMNS + column) * ROWS.len() + row) as u32
+                };
+                let (a, b) = (index(column, row), index(column, row + 1));
+                let (c, d) = (index(next, row), index(next, row + 1));
+                indices.extend([a, c, b, b, c, d]);
+            }
+        }
+    }
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; vertices]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0f32, 0.0, 1.0]; vertices]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[0.0f32; 4]; vertices]);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// How dark the sky at the camera is for the aurora to show against: 0 in
+/// daylight, 1 from nautical dusk on or from above the atmosphere.
+fn sky_darkness(atmosphere: &SphericalAtmosphere, camera: DVec3, sun_direction: DVec3) -> f32 {
+    let sun_mu = camera.normalize().dot(sun_direction) as f32;
+    let night = smoothstep(DUSK_MU, NIGHT_MU, sun_mu);
+    let space = smoothstep(
+        atmosphere.bottom_radius,
+        atmosphere.top_radius,
+        camera.length() as f32,
+    );
+    night.max(space)
+}
+
+/// Unit direction (ECEF) to a point on the oval around `pole`, `phi` radians
+/// round from magnetic midnight (the side facing away from the sun), after
+/// `seconds` of animation.
+fn oval_direction(pole: DVec3, sun_direction: DVec3, phi: f64, seconds: f64) -> DVec3 {
+    let midnight = (-sun_direction.reject_from_normalized(pole))
+        .try_normalize()
+        .unwrap_or_else(|| pole.any_orthonormal_vector());
+    let dawn = pole.cross(midnight);
+    let folds: f64 = FOLDS
+        .iter()
+        .map(|&(amplitude, waves, rate)| amplitude * (waves * phi + rate * seconds).sin())
+        .sum();
+    let colatitude = (OVAL_COLATITUDE_DEG + OVAL_MIDNIGHT_SAG_DEG * phi.cos() + folds).to_radians();
+    pole * colatitude.cos() + (midnight * phi.cos() + dawn * phi.sin()) * colatitude.sin()
+}
+
+/// Relative glow of the curtain `phi` radians from magnetic midnight: bright
+/// toward midnight, broken into shifting rays.
+fn column_glow(phi: f64, seconds: f64) -> f32 {
+    let midnight = 0.3 + 0.35 * (1.0 + phi.cos());
+    let rays = 0.6 + 0.4 * (23.0 * phi + 0.3 * seconds).sin() * (7.0 * phi - 0.05 * seconds).cos();
+    let pulse = 0.85 + 0.15 * (5.0 * phi + 0.7 * seconds).sin();
+    (midnight * rays * pulse) as f32
+}
+
+/// Spectral transmittance of the atmosphere along the straight path between
+/// two points (ECEF), or zero if the planet is in the way.
+///
+/// [`compute_sun_transmittance`] integrates from a point out to space. Past
+/// the higher of the two points the path only climbs, so the transmittance
+/// between them is the lower point's towards space with the higher point's
+/// divided back out.
+fn segment_transmittance(
+    atmosphere: &SphericalAtmosphere,
+    medium: &ScatteringMedium,
+    a: DVec3,
+    b: DVec3,
+    midpoint_ratio: f32,
+) -> Vec3 {
+    let (low, high) = if a.length_squared() <= b.length_squared() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let length = low.distance(high);
+    if length <= 0.0 {
+        return Vec3::ONE;
+    }
+    let direction = (high - low) / length;
+    // The terrain dips below the atmosphere's sphere toward the poles, so a
+    // point under it only counts as blocked if the path dips lower still.
+    let ground = f64::from(atmosphere.bottom_radius).min(low.length());
+    let closest = (-low.dot(direction)).clamp(0.0, length);
+    if (low + direction * closest).length() < ground {
+        return Vec3::ZERO;
+    }
+    let towards_space = |point: DVec3| {
+        compute_sun_transmittance(
+            atmosphere,
+            medium,
+            point.length() as f32,
+            point.normalize().dot(direction) as f32,
+            midpoint_ratio,
+        )
+    };
+    let from_high = towards_space(high).max(Vec3::splat(MIN_TRANSMITTANCE));
+    (towards_space(low) / from_high).min(Vec3::ONE)
+}
+
+/// Rebuild the curtains for the current time, sun and camera, or hide them
+/// while the aurora is off or the sky is too bright.
+#[allow(clippy::too_many_arguments)]
+fn update_aurora(
+    aurora: Res<Aurora>,
+    viz: Res<AuroraViz>,
+    time: Res<Time>,
+    camera: Query<(
+        &FloatingOriginCamera,
+        &SphericalAtmosphere,
+        &AtmosphereSettings,
+    )>,
+    sun: Query<&Transform, With<Sun>>,
+    media: Res<Assets<ScatteringMedium>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut visibility: Query<&mut Visibility>,
+) {
+    let Ok(mut visibility) = visibility.get_mut(viz.entity) else {
+        return;
+    };
+    let (Ok((camera, atmosphere, settings)), Ok(sun)) = (camera.single(), sun.single()) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    // The sun's transform looks away from the sun.
+    let sun_direction = sun.back().as_dvec3();
+    let strength = aurora.intensity * sky_darkness(atmosphere, camera.position, sun_direction);
+    let Some(medium) = media.get(&atmosphere.medium) else {
+        return;
+    };
+    if !aurora.enabled || strength <= 0.0 {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    let Some(mesh) = meshes.get_mut(&viz.mesh) else {
+        return;
+    };
+
+    let seconds = time.elapsed_secs_f64();
+    let vertices = GEOMAGNETIC_POLES_DEG.len() * COLUMNS * ROWS.len();
+    let mut positions = Vec::with_capacity(vertices);
+    let mut normals = Vec::with_capacity(vertices);
+    let mut colors = Vec::with_capacity(vertices);
+    for (oval, &(lat, lon)) in GEOMAGNETIC_POLES_DEG.iter().enumerate() {
+        let pole = lat_lon_to_ecef(lat, lon, 1.0);
+        // Offset the second oval's animation so the two don't move in step.
+        let seconds = seconds + oval as f64 * 1_000.0;
+        for column in 0..COLUMNS {
+            let phi = column as f64 / COLUMNS as f64 * TAU;
+            let up = oval_direction(pole, sun_direction, phi, seconds);
+            let glow = column_glow(phi, seconds) * strength;
+            for &(altitude, emission) in &ROWS {
+                let position = up * (f64::from(atmosphere.bottom_radius) + altitude);
+                let color = if emission == Vec3::ZERO {
+                    Vec3::ZERO
+                } else {
+                    emission
+                        * glow
+                        * segment_transmittance(
+                            atmosphere,
+                            medium,
+                            camera.position,
+                            position,
+                            settings.sun_transmittance_midpoint_ratio,
+                        )
+                };
+                positions.push(position.as_vec3().to_array());
+                normals.push(up.as_vec3().to_array());
+                colors.push(color.extend(1.0).to_array());
+            }
+        }
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    visibility.set_if_neq(Visibility::Inherited);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ovals_sit_at_high_latitudes_and_sag_toward_midnight() {
+        let (lat, lon) = GEOMAGNETIC_POLES_DEG[0];
+        let pole = lat_lon_to_ecef(lat, lon, 1.0);
+        let latitude = |direction: DVec3| direction.z.asin().to_degrees();
+        let sun = DVec3::X;
+        let midnight = latitude(oval_direction(pole, sun, 0.0, 0.0));
+        let noon = latitude(oval_direction(pole, sun, std::f64::consts::PI, 0.0));
+        assert!(midnight > 50.0 && noon > 50.0, "{midnight} {noon}");
+        assert!(midnight < noon);
+        assert!(column_glow(0.0, 0.0) > column_glow(std::f64::consts::PI, 0.0));
+    }
+
+    #[test]
+    fn the_lower_atmosphere_dims_and_the_planet_hides() {
+        let atmosphere = SphericalAtmosphere::earthlike(Handle::default());
+        let medium = ScatteringMedium::default();
+        let radius = f64::from(atmosphere.bottom_radius);
+        let ground = DVec3::new(radius + 10.0, 0.0, 0.0);
+        let through = |to: DVec3| segment_transmittance(&atmosphere, &medium, ground, to, 0.5);
+
+        let overhead = through(DVec3::new(radius + 110_000.0, 0.0, 0.0));
+        let low = through(DVec3::new(radius + 110_000.0, 900_000.0, 0.0));
+        assert!(overhead.y < 1.0 && low.y < overhead.y);
+        // Blue is scattered out of the long path more than red.
+        assert!(low.x > low.z);
+        // Either way round.
+        assert_eq!(
+            segment_transmittance(
+                &atmosphere,
+                &medium,
+                DVec3::new(radius + 110_000.0, 0.0, 0.0),
+                ground,
+                0.5
+            ),
+            overhead
+        );
+        // Over the horizon.
+        assert_eq!(through(DVec3::new(-radius, 0.0, 0.0)), Vec3::ZERO);
+    }
+}
@@ -33,7 +33,7 @@ pub fn spawn_celestial_lights(mut commands: Commands) {
     // readable through twilight and moonless night, but low enough that
     // photogrammetry textures (which bake in their captured-day reflectance)
     // don't look mid-day-bright. During the day this is dwarfed by direct sun and
-    // the env-map IBL.
+    // the env-map IBL. `SkyAmbientPlugin` raises it with the sun each frame.
     commands.insert_resource(GlobalAmbientLight {
         color: Color::WHITE,
         brightness: 50.0,
@@ -44,6 +44,7 @@ pub fn spawn_celestial_lights(mut commands: Commands) {
     // `RAW_SUNLIGHT` illuminance — the pre-scattering value — so the atmosphere
     // can filter it. The direction is updated each frame by the time-of-day
     // system from UTC, driving the day/night cycle as you fly around the globe.
+    // Its shadow cascades are fitted to the camera by `SunShadowPlugin`.
     commands.spawn((
         Sun,
         DirectionalLight {
@@ -6,25 +6,40 @@
 //!
 //! - [`time_of_day`] — the canonical UTC clock, sun direction, and sky colour.
 //! - [`moon`] — lunar position, phase, and directional light.
+//! - [`satellites`] — TLE parsing and SGP4 propagation of satellite positions.
 //! - [`atmosphere`] — integrates [`veldera_atmosphere`] with the floating-origin
 //!   camera and applies its hot-reloadable config.
+//! - [`preset`] — shareable `.atmo.ron` atmosphere looks, switchable at runtime.
+//! - [`weather`] — ground fog, haze and rain or snow layered over the
+//!   atmosphere.
+//! - [`aurora`] — glowing curtains over the auroral ovals, seen at night.
 //! - [`celestial_lights`] — spawns the sun/moon/ambient lights those renderers
 //!   consume.
+//! - [`ambient`] — drives the ambient light from the sun's elevation, so
+//!   shadows keep some skylight through twilight.
+//! - [`sun_shadows`] — fits the sun's cascaded shadow maps to the camera.
 //!
 //! Each config-backed plugin defaults to its canonical path in the shared engine
 //! asset subtree and accepts an override — the engine owns the config *types*,
 //! the app owns the asset layout.
 
+pub mod ambient;
 pub mod atmosphere;
+pub mod aurora;
 pub mod celestial_lights;
 pub mod clouds;
 pub mod moon;
+pub mod preset;
+pub mod satellites;
+pub mod sun_shadows;
 pub mod time_of_day;
+pub mod weather;
 
 use bevy::app::{PluginGroup, PluginGroupBuilder};
 
 /// The full sky stack: the time-of-day clock, the moon, the atmosphere and cloud
-/// renderers, and the sun/moon/ambient lights they consume.
+/// renderers with their presets, weather and aurora, the sun/moon/ambient
+/// lights they consume, the sky-driven ambient level, and the sun's shadows.
 ///
 /// Each config-backed plugin loads from its default engine asset path; a host
 /// with a different layout adds the constituent plugins individually instead.
@@ -36,7 +51,12 @@ impl PluginGroup for SkyPlugins {
             .add(time_of_day::TimeOfDayPlugin::default())
             .add(moon::MoonPlugin::default())
             .add(atmosphere::AtmosphereIntegrationPlugin::default())
+            .add(preset::AtmospherePresetPlugin::default())
+            .add(weather::WeatherPlugin)
+            .add(aurora::AuroraPlugin)
             .add(clouds::CloudIntegrationPlugin::default())
             .add(celestial_lights::CelestialLightsPlugin)
+            .add(ambient::SkyAmbientPlugin)
+            .add(sun_shadows::SunShadowPlugin)
     }
 }
@@ -19,6 +19,7 @@ use bevy::{light::SunDisk, prelude::*, reflect::TypePath};
 use serde::Deserialize;
 
 use veldera_config::ConfigPlugin;
+use veldera_geo::coords::gmst_rad;
 
 use crate::time_of_day::{SECONDS_PER_HOUR, TimeOfDayState, days_since_j2000};
 
@@ -237,7 +238,7 @@ fn equatorial_to_ecef(ra_deg: f64, dec_deg: f64, d: f64) -> Vec3 {
     // Greenwich Mean Sidereal Time in degrees. `d` already includes the
     // fractional day from UTC time, so the rotation term covers both calendar
     // drift and intra-day rotation in one coefficient.
-    let gmst_deg = wrap_deg(280.460_618 + 360.985_647_366 * d);
+    let gmst_deg = gmst_rad(d).to_degrees();
     // Hour angle of the body from Greenwich (westward).
     let gha_deg = wrap_deg(gmst_deg - ra_deg);
     // Sub-body geographic longitude (eastward positive) is the negative of GHA,
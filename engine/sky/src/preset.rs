@@ -0,0 +1,431 @@
+//! Shareable atmosphere looks, stored as `.atmo.ron` presets.
+//!
+//! An [`AtmospherePreset`] captures what makes a sky look the way it does: the
+//! [`SphericalAtmosphere`] radii and ground albedo, its artistic overrides, the
+//! terms of its [`ScatteringMedium`], and optionally the whole
+//! [`AtmosphereSettings`] block. Every preset in the preset folder is loaded
+//! through the asset server at startup and listed in [`AtmospherePresets`];
+//! [`AtmospherePresets::select`] applies one to the live atmosphere.
+//!
+//! Applying a preset writes the albedo, artistic overrides and settings into
+//! [`AtmosphereConfig`], so they last until `atmosphere.toml` reloads, and
+//! replaces the radii on every atmosphere camera. The medium becomes the
+//! clear-weather medium of the [`WeatherMedium`], which lays the current
+//! weather over it, or is replaced in place without the weather plugin.
+//! Editing the file of the selected preset re-applies it.
+//!
+//! ```ron
+//! (
+//!     name: "Hazy",
+//!     description: "Thick aerosol layer; washed-out horizon.",
+//!     ground_albedo: (0.3, 0.3, 0.3),
+//!     artistic: (horizon_haze: 0.5),
+//!     medium: (
+//!         terms: [
+//!             (
+//!                 scattering: (5.802e-6, 13.558e-6, 33.1e-6),
+//!                 falloff: Exponential(scale: 0.1333),
+//!                 phase: Rayleigh,
+//!             ),
+//!         ],
+//!     ),
+//! )
+//! ```
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedFolder, io::Reader},
+    pbr::{Falloff, PhaseFunction, ScatteringMedium, ScatteringTerm},
+    prelude::*,
+    reflect::TypePath,
+};
+use serde::Deserialize;
+use veldera_atmosphere::{AtmosphereArtistic, AtmosphereSettings, SphericalAtmosphere};
+use veldera_constants::{ATMOSPHERE_TOP_RADIUS_M, EARTH_RADIUS_M};
+
+use crate::{atmosphere::AtmosphereConfig, weather::WeatherMedium};
+
+/// Plugin that loads the atmosphere presets and applies the selected one.
+///
+/// Defaults to the presets in [`DEFAULT_FOLDER`](Self::DEFAULT_FOLDER) of the
+/// shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout.
+pub struct AtmospherePresetPlugin {
+    /// Asset folder holding the `.atmo.ron` presets.
+    pub folder: &'static str,
+}
+
+impl AtmospherePresetPlugin {
+    /// Canonical preset folder within the shared engine asset subtree.
+    pub const DEFAULT_FOLDER: &'static str = "engine/atmosphere";
+
+    /// Create the plugin, loading its presets from `folder`.
+    pub const fn new(folder: &'static str) -> Self {
+        Self { folder }
+    }
+}
+
+impl Default for AtmospherePresetPlugin {
+    /// Load the presets from [`DEFAULT_FOLDER`](Self::DEFAULT_FOLDER).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_FOLDER)
+    }
+}
+
+impl Plugin for AtmospherePresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AtmospherePreset>()
+            .register_asset_loader(AtmospherePresetLoader);
+
+        let folder = app
+            .world()
+            .resource::<AssetServer>()
+            .load_folder(self.folder);
+        app.insert_resource(AtmospherePresets {
+            folder: Some(folder),
+            ..default()
+        })
+        .add_systems(
+            Update,
+            (collect_atmosphere_presets, apply_atmosphere_preset).chain(),
+        );
+    }
+}
+
+/// A serialized atmosphere look, loaded from a `.atmo.ron` file.
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AtmospherePreset {
+    /// Name shown in the preset list.
+    pub name: String,
+    /// One-line description of the look.
+    pub description: String,
+    /// Radius of the planet (m). The terrain is Earth-sized, so other values
+    /// only suit views from orbit.
+    pub bottom_radius: f32,
+    /// Radius at which the atmosphere ends (m).
+    pub top_radius: f32,
+    /// Ground albedo (linear RGB, 0–1) the sky bounces light off.
+    pub ground_albedo: [f32; 3],
+    /// Artistic multipliers on the sky and aerial perspective.
+    pub artistic: AtmosphereArtistic,
+    /// LUT sizes, sample counts and feature toggles, written `Some((..))`.
+    /// Left alone when absent; when present, fields it leaves out take their
+    /// defaults.
+    pub settings: Option<AtmosphereSettings>,
+    /// The substance of the atmosphere.
+    pub medium: PresetMedium,
+}
+
+impl Default for AtmospherePreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            bottom_radius: EARTH_RADIUS_M,
+            top_radius: ATMOSPHERE_TOP_RADIUS_M,
+            ground_albedo: [0.3, 0.3, 0.3],
+            artistic: AtmosphereArtistic::default(),
+            settings: None,
+            medium: PresetMedium::default(),
+        }
+    }
+}
+
+/// Serialized form of a [`ScatteringMedium`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PresetMedium {
+    /// Samples of each term's falloff in the density LUT.
+    pub falloff_resolution: u32,
+    /// Samples of each term's phase function in the scattering LUT.
+    pub phase_resolution: u32,
+    /// The terms the medium is made of.
+    pub terms: Vec<PresetTerm>,
+}
+
+impl Default for PresetMedium {
+    fn default() -> Self {
+        Self {
+            falloff_resolution: 256,
+            phase_resolution: 256,
+            terms: Vec::new(),
+        }
+    }
+}
+
+impl PresetMedium {
+    /// The medium as a [`ScatteringMedium`] asset labelled `label`.
+    pub fn to_medium(&self, label: &str) -> ScatteringMedium {
+        ScatteringMedium::new(
+            self.falloff_resolution,
+            self.phase_resolution,
+            self.terms.iter().map(PresetTerm::to_term),
+        )
+        .with_label(format!("atmosphere_preset_{label}"))
+    }
+}
+
+/// Serialized form of a [`ScatteringTerm`].
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PresetTerm {
+    /// Absorption per metre at full density (linear RGB, m⁻¹).
+    pub absorption: [f32; 3],
+    /// Scattering per metre at full density (linear RGB, m⁻¹).
+    pub scattering: [f32; 3],
+    /// How the density falls off with altitude.
+    pub falloff: PresetFalloff,
+    /// How the term scatters light by angle.
+    pub phase: PresetPhase,
+}
+
+impl PresetTerm {
+    fn to_term(&self) -> ScatteringTerm {
+        ScatteringTerm {
+            absorption: Vec3::from_array(self.absorption),
+            scattering: Vec3::from_array(self.scattering),
+            falloff: match self.falloff {
+                PresetFalloff::Linear => Falloff::Linear,
+                PresetFalloff::Exponential { scale } => Falloff::Exponential { scale },
+                PresetFalloff::Tent { center, width } => Falloff::Tent { center, width },
+            },
+            phase: match self.phase {
+                PresetPhase::Isotropic => PhaseFunction::Isotropic,
+                PresetPhase::Rayleigh => PhaseFunction::Rayleigh,
+                PresetPhase::Mie { asymmetry } => PhaseFunction::Mie { asymmetry },
+            },
+        }
+    }
+}
+
+/// Serialized form of a [`Falloff`]; custom curves can't be stored. Altitudes
+/// are fractions of the atmosphere height.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum PresetFalloff {
+    /// Density falls linearly to zero at the top.
+    #[default]
+    Linear,
+    /// Exponential falloff with `scale` the scale height.
+    Exponential { scale: f32 },
+    /// A layer peaking at `center`, `width` wide.
+    Tent { center: f32, width: f32 },
+}
+
+/// Serialized form of a [`PhaseFunction`]; custom curves can't be stored.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum PresetPhase {
+    /// Scatters evenly in all directions.
+    #[default]
+    Isotropic,
+    /// Molecular scattering.
+    Rayleigh,
+    /// Aerosol scattering, forwards for a positive `asymmetry`.
+    Mie { asymmetry: f32 },
+}
+
+/// Errors from loading an [`AtmospherePreset`].
+#[derive(Debug)]
+pub enum AtmospherePresetLoaderError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file isn't a valid preset.
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for AtmospherePresetLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read atmosphere preset: {e}"),
+            Self::Ron(e) => write!(f, "invalid atmosphere preset: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AtmospherePresetLoaderError {}
+
+/// Loader for `.atmo.ron` atmosphere presets.
+#[derive(Default, TypePath)]
+struct AtmospherePresetLoader;
+
+impl AssetLoader for AtmospherePresetLoader {
+    type Asset = AtmospherePreset;
+    type Settings = ();
+    type Error = AtmospherePresetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(AtmospherePresetLoaderError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(AtmospherePresetLoaderError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atmo.ron"]
+    }
+}
+
+/// The loaded atmosphere presets, and which one is applied.
+#[derive(Resource, Default)]
+pub struct AtmospherePresets {
+    folder: Option<Handle<LoadedFolder>>,
+    /// Presets in the folder, once it has loaded.
+    presets: Vec<Handle<AtmospherePreset>>,
+    selected: Option<AssetId<AtmospherePreset>>,
+    /// Apply the selected preset at the next opportunity.
+    pending: bool,
+}
+
+impl AtmospherePresets {
+    /// The loaded presets, sorted by name. Presets that failed to load are
+    /// left out.
+    pub fn list<'a>(
+        &self,
+        assets: &'a Assets<AtmospherePreset>,
+    ) -> Vec<(AssetId<AtmospherePreset>, &'a AtmospherePreset)> {
+        let mut list: Vec<_> = self
+            .presets
+            .iter()
+            .filter_map(|handle| assets.get(handle).map(|preset| (handle.id(), preset)))
+            .collect();
+        list.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        list
+    }
+
+    /// The preset last selected, if any.
+    #[must_use]
+    pub fn selected(&self) -> Option<AssetId<AtmospherePreset>> {
+        self.selected
+    }
+
+    /// Apply the preset `id` to the live atmosphere.
+    pub fn select(&mut self, id: AssetId<AtmospherePreset>) {
+        self.selected = Some(id);
+        self.pending = true;
+    }
+}
+
+/// List the presets once the folder has loaded, and note edits to the
+/// selected one.
+fn collect_atmosphere_presets(
+    mut presets: ResMut<AtmospherePresets>,
+    mut events: MessageReader<AssetEvent<AtmospherePreset>>,
+    folders: Res<Assets<LoadedFolder>>,
+) {
+    for event in events.read() {
+        if let AssetEvent::Modified { id } = event
+            && presets.selected == Some(*id)
+        {
+            presets.pending = true;
+        }
+    }
+
+    if !presets.presets.is_empty() {
+        return;
+    }
+    let Some(folder) = presets.folder.as_ref().and_then(|h| folders.get(h)) else {
+        return;
+    };
+    let found: Vec<Handle<AtmospherePreset>> = folder
+        .handles
+        .iter()
+        .filter_map(|handle| handle.clone().try_typed::<AtmospherePreset>().ok())
+        .collect();
+    tracing::info!("Found {} atmosphere preset(s)", found.len());
+    presets.presets = found;
+}
+
+/// Write the selected preset into the config, the atmosphere cameras and
+/// their medium.
+pub(crate) fn apply_atmosphere_preset(
+    mut presets: ResMut<AtmospherePresets>,
+    assets: Res<Assets<AtmospherePreset>>,
+    mut config: ResMut<AtmosphereConfig>,
+    mut atmospheres: Query<&mut SphericalAtmosphere>,
+    mut media: ResMut<Assets<ScatteringMedium>>,
+    weather_medium: Option<ResMut<WeatherMedium>>,
+) {
+    if !presets.pending {
+        return;
+    }
+    let Some(preset) = presets.selected.and_then(|id| assets.get(id)) else {
+        return;
+    };
+    presets.pending = false;
+
+    config.ground_albedo = preset.ground_albedo;
+    config.artistic = preset.artistic;
+    if let Some(settings) = &preset.settings {
+        config.settings = settings.clone();
+    }
+
+    let medium = preset.medium.to_medium(&preset.name);
+    let mut media_ids = Vec::new();
+    for mut atmosphere in &mut atmospheres {
+        atmosphere.bottom_radius = preset.bottom_radius;
+        atmosphere.top_radius = preset.top_radius;
+        let id = atmosphere.medium.id();
+        if !media_ids.contains(&id) {
+            media_ids.push(id);
+        }
+    }
+    if let Some(mut weather_medium) = weather_medium {
+        weather_medium.set_base(medium);
+    } else {
+        // Every camera shares one medium; replace it in place so their
+        // handles stay valid.
+        for id in media_ids {
+            if let Some(current) = media.get_mut(id) {
+                *current = medium.clone();
+            }
+        }
+    }
+    tracing::info!("Applied atmosphere preset '{}'", preset.name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: [&str; 4] = [
+        include_str!("../../../engine_assets/atmosphere/clear_day.atmo.ron"),
+        include_str!("../../../engine_assets/atmosphere/hazy.atmo.ron"),
+        include_str!("../../../engine_assets/atmosphere/sunset_smog.atmo.ron"),
+        include_str!("../../../engine_assets/atmosphere/mars.atmo.ron"),
+    ];
+
+    #[test]
+    fn shipped_presets_parse() {
+        for text in SHIPPED {
+            let preset: AtmospherePreset = ron::de::from_str(text).unwrap();
+            assert!(!preset.name.is_empty());
+            assert!(!preset.medium.terms.is_empty(), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn omitted_fields_take_earth_defaults() {
+        let preset: AtmospherePreset = ron::de::from_str(
+            "(name: \"Ozone\", medium: (terms: [(absorption: (1.0, 2.0, 3.0), \
+             falloff: Tent(center: 0.75, width: 0.3))]))",
+        )
+        .unwrap();
+        assert_eq!(preset.bottom_radius, EARTH_RADIUS_M);
+        assert!(preset.settings.is_none());
+
+        let medium = preset.medium.to_medium(&preset.name);
+        assert_eq!(medium.falloff_resolution, 256);
+        assert_eq!(medium.terms[0].absorption, Vec3::new(1.0, 2.0, 3.0));
+        assert!(matches!(
+            medium.terms[0].falloff,
+            Falloff::Tent { center, .. } if center == 0.75
+        ));
+        assert!(matches!(medium.terms[0].phase, PhaseFunction::Isotropic));
+    }
+}
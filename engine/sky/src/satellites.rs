@@ -0,0 +1,604 @@
+//! Satellite positions from two-line element sets.
+//!
+//! Parses NORAD two-line element sets (TLEs) and propagates them with the
+//! near-Earth SGP4 model of [Spacetrack Report #3], giving each satellite's
+//! position in ECEF at any moment of the in-world clock. SGP4 works in the
+//! true-equator, mean-equinox inertial frame, which is turned into ECEF by the
+//! sidereal angle ([`eci_to_ecef`]).
+//!
+//! Only near-Earth orbits are modelled: objects with periods of 225 minutes
+//! or more (geostationary, Molniya, GPS) need the lunar and solar terms of
+//! SDP4, so [`Satellite::new`] rejects them. Accuracy is around a kilometre
+//! at the element set's epoch and degrades by a few kilometres a day from it.
+//!
+//! [Spacetrack Report #3]: https://celestrak.org/NORAD/documentation/spacetrk.pdf
+
+use std::{error::Error, f64::consts::TAU, fmt};
+
+use glam::DVec3;
+use veldera_geo::coords::{eci_to_ecef, gmst_rad};
+
+use crate::time_of_day::{SimpleDate, days_since_j2000};
+
+// WGS-72 constants, which the published element sets are fitted against.
+
+/// Earth's equatorial radius (km).
+const RADIUS_KM: f64 = 6_378.135;
+/// √(GM) in Earth radii^1.5 per minute.
+const XKE: f64 = 0.074_366_916_1;
+/// Second zonal harmonic.
+const J2: f64 = 0.001_082_616;
+/// Third zonal harmonic.
+const J3: f64 = -0.000_002_538_81;
+/// Fourth zonal harmonic.
+const J4: f64 = -0.000_001_655_97;
+const CK2: f64 = 0.5 * J2;
+const CK4: f64 = -0.375 * J4;
+/// Shortest period (minutes) of an orbit that needs SDP4's deep-space terms.
+const DEEP_SPACE_PERIOD_MIN: f64 = 225.0;
+const MINUTES_PER_DAY: f64 = 1_440.0;
+
+/// One parsed two-line element set.
+#[derive(Clone, Debug)]
+pub struct Tle {
+    /// Name from the title line, or the catalogue number if there was none.
+    pub name: String,
+    /// NORAD catalogue number.
+    pub catalog_number: u32,
+    /// Epoch in UT days since J2000.0 (2000-01-01 12:00).
+    pub epoch_days: f64,
+    /// Drag term, B* (1/Earth radii).
+    pub bstar: f64,
+    /// Inclination (radians).
+    pub inclination: f64,
+    /// Right ascension of the ascending node (radians).
+    pub raan: f64,
+    /// Eccentricity.
+    pub eccentricity: f64,
+    /// Argument of perigee (radians).
+    pub arg_perigee: f64,
+    /// Mean anomaly (radians).
+    pub mean_anomaly: f64,
+    /// Mean motion (revolutions per day).
+    pub mean_motion: f64,
+}
+
+/// A malformed element set.
+#[derive(Debug)]
+pub struct TleError {
+    /// 1-based line number.
+    pub line: usize,
+    /// What was wrong with it.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for TleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for TleError {}
+
+/// Parse element sets in the two- or three-line format, skipping blank lines
+/// and `#` comments. A title line before a set names it.
+pub fn parse_tles(text: &str) -> Result<Vec<Tle>, TleError> {
+    let mut tles = Vec::new();
+    let mut name: Option<String> = None;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end()))
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'));
+    while let Some((number, line)) = lines.next() {
+        if !line.starts_with("1 ") {
+            // Title lines are sometimes prefixed with "0 ".
+            name = Some(line.strip_prefix("0 ").unwrap_or(line).trim().to_string());
+            continue;
+        }
+        let Some((number2, line2)) = lines.next() else {
+            return Err(TleError {
+                line: number,
+                reason: "missing line 2",
+            });
+        };
+        tles.push(parse_lines(name.take(), (number, line), (number2, line2))?);
+    }
+    Ok(tles)
+}
+
+/// Parse one element set from its two numbered lines.
+fn parse_lines(
+    name: Option<String>,
+    (number1, line1): (usize, &str),
+    (number2, line2): (usize, &str),
+) -> Result<Tle, TleError> {
+    check_line(number1, line1, '1')?;
+    check_line(number2, line2, '2')?;
+    let field = |number: usize, line: &str, columns: std::ops::Range<usize>| {
+        line[columns].trim().parse::<f64>().map_err(|_| TleError {
+            line: number,
+            reason: "invalid number",
+        })
+    };
+    let angle = |columns| field(number2, line2, columns).map(f64::to_radians);
+
+    let catalog_number: u32 = line1[2..7].trim().parse().map_err(|_| TleError {
+        line: number1,
+        reason: "invalid catalogue number",
+    })?;
+    let year = field(number1, line1, 18..20)? as i32;
+    let year = if year < 57 { 2000 + year } else { 1900 + year };
+    let day = field(number1, line1, 20..32)?;
+    let january_first = SimpleDate {
+        year,
+        month: 1,
+        day: 1,
+    };
+    // Day 1.0 is midnight starting 1 January.
+    let epoch_days = days_since_j2000(january_first, 0.0) + day - 1.0;
+
+    Ok(Tle {
+        name: name.unwrap_or_else(|| catalog_number.to_string()),
+        catalog_number,
+        epoch_days,
+        bstar: parse_exponent(&line1[53..61]).ok_or(TleError {
+            line: number1,
+            reason: "invalid B*",
+        })?,
+        inclination: angle(8..16)?,
+        raan: angle(17..25)?,
+        eccentricity: field(number2, &format!("0.{}", &line2[26..33]), 0..9)?,
+        arg_perigee: angle(34..42)?,
+        mean_anomaly: angle(43..51)?,
+        mean_motion: field(number2, line2, 52..63)?,
+    })
+}
+
+/// Check a numbered line's length, leading digit and checksum.
+fn check_line(number: usize, line: &str, digit: char) -> Result<(), TleError> {
+    let error = |reason| {
+        Err(TleError {
+            line: number,
+            reason,
+        })
+    };
+    if !line.is_ascii() || line.len() < 69 {
+        return error("line too short");
+    }
+    if !line.starts_with(digit) {
+        return error("lines out of order");
+    }
+    // Sum of the digits, with each minus sign counting one, modulo 10.
+    let sum: u32 = line[..68]
+        .chars()
+        .map(|c| match c {
+            '-' => 1,
+            c => c.to_digit(10).unwrap_or(0),
+        })
+        .sum();
+    if line.as_bytes()[68] != b'0' + (sum % 10) as u8 {
+        return error("checksum mismatch");
+    }
+    Ok(())
+}
+
+/// Parse a TLE's packed exponent notation, where ` 12345-3` means
+/// `0.12345e-3`.
+fn parse_exponent(field: &str) -> Option<f64> {
+    let field = field.trim();
+    if field.len() < 3 {
+        return None;
+    }
+    let (mantissa, exponent) = field.split_at(field.len() - 2);
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let mantissa: f64 = format!("0.{digits}").parse().ok()?;
+    let exponent: i32 = exponent.parse().ok()?;
+    Some(sign * mantissa * 10f64.powi(exponent))
+}
+
+/// A satellite ready for propagation: its element set and the SGP4
+/// coefficients derived from it.
+#[derive(Clone, Debug)]
+pub struct Satellite {
+    /// The element set.
+    pub tle: Tle,
+    model: Sgp4,
+}
+
+impl Satellite {
+    /// Prepare a near-Earth satellite for propagation, or explain why it
+    /// can't be.
+    pub fn new(tle: Tle) -> Result<Self, &'static str> {
+        let model = Sgp4::new(&tle)?;
+        Ok(Self { tle, model })
+    }
+
+    /// Position in the inertial frame (m), `minutes` after the epoch, or
+    /// `None` once the orbit has decayed.
+    pub fn position_eci(&self, minutes: f64) -> Option<DVec3> {
+        self.model
+            .position(minutes)
+            .map(|p| p * (RADIUS_KM * 1_000.0))
+    }
+
+    /// Position in ECEF (m) at `days_since_j2000` on the UT clock, or `None`
+    /// once the orbit has decayed.
+    pub fn position_ecef(&self, days_since_j2000: f64) -> Option<DVec3> {
+        let minutes = (days_since_j2000 - self.tle.epoch_days) * MINUTES_PER_DAY;
+        self.position_eci(minutes)
+            .map(|p| eci_to_ecef(p, gmst_rad(days_since_j2000)))
+    }
+}
+
+/// SGP4 state: the epoch elements and the secular and drag coefficients
+/// derived from them. Lengths in Earth radii, times in minutes.
+#[derive(Clone, Debug)]
+struct Sgp4 {
+    inclination: f64,
+    raan: f64,
+    eccentricity: f64,
+    arg_perigee: f64,
+    mean_anomaly: f64,
+    bstar: f64,
+    /// Recovered mean motion (radians per minute) and semi-major axis.
+    xnodp: f64,
+    aodp: f64,
+    /// Low perigee: the higher-order drag terms are dropped.
+    simple: bool,
+    eta: f64,
+    c1: f64,
+    c4: f64,
+    c5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    sinmo: f64,
+    omgcof: f64,
+    xmcof: f64,
+    xnodcf: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    xlcof: f64,
+    aycof: f64,
+    xmdot: f64,
+    omgdot: f64,
+    xnodot: f64,
+    x3thm1: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+}
+
+impl Sgp4 {
+    /// Initialise the model from an element set.
+    fn new(tle: &Tle) -> Result<Self, &'static str> {
+        let n0 = tle.mean_motion * TAU / MINUTES_PER_DAY;
+        let e0 = tle.eccentricity;
+        if n0 <= 0.0 || !(0.0..1.0).contains(&e0) {
+            return Err("invalid orbit");
+        }
+        if TAU / n0 >= DEEP_SPACE_PERIOD_MIN {
+            return Err("deep-space orbit (needs SDP4)");
+        }
+        let i0 = tle.inclination;
+        let bstar = tle.bstar;
+
+        // Recover the original mean motion and semi-major axis from the
+        // Kozai mean motion in the element set.
+        let cosio = i0.cos();
+        let sinio = i0.sin();
+        let theta2 = cosio * cosio;
+        let x3thm1 = 3.0 * theta2 - 1.0;
+        let betao2 = 1.0 - e0 * e0;
+        let betao = betao2.sqrt();
+        let a1 = (XKE / n0).powf(2.0 / 3.0);
+        let del1 = 1.5 * CK2 * x3thm1 / (a1 * a1 * betao * betao2);
+        let ao = a1 * (1.0 - del1 * (1.0 / 3.0 + del1 * (1.0 + 134.0 / 81.0 * del1)));
+        let delo = 1.5 * CK2 * x3thm1 / (ao * ao * betao * betao2);
+        let xnodp = n0 / (1.0 + delo);
+        let aodp = ao / (1.0 - delo);
+
+        // Perigee below 220 km keeps only the simpler drag terms; the
+        // atmosphere's density parameter drops with the perigee below 156 km.
+        let perigee_km = (aodp * (1.0 - e0) - 1.0) * RADIUS_KM;
+        if perigee_km < 0.0 {
+            return Err("orbit below the surface");
+        }
+        let simple = perigee_km < 220.0;
+        let mut s4 = 1.0 + 78.0 / RADIUS_KM;
+        let mut qoms24 = ((120.0 - 78.0) / RADIUS_KM).powi(4);
+        if perigee_km < 156.0 {
+            let s = if perigee_km <= 98.0 {
+                20.0
+            } else {
+                perigee_km - 78.0
+            };
+            qoms24 = ((120.0 - s) / RADIUS_KM).powi(4);
+            s4 = s / RADIUS_KM + 1.0;
+        }
+
+        let pinvsq = 1.0 / (aodp * aodp * betao2 * betao2);
+        let tsi = 1.0 / (aodp - s4);
+        let eta = aodp * e0 * tsi;
+        let etasq = eta * eta;
+        let eeta = e0 * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qoms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let c2 = coef1
+            * xnodp
+            * (aodp * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.75 * CK2 * tsi / psisq * x3thm1 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let c1 = bstar * c2;
+        let a3ovk2 = -J3 / CK2;
+        let c3 = if e0 > 1e-4 {
+            coef * tsi * a3ovk2 * xnodp * sinio / e0
+        } else {
+            0.0
+        };
+        let x1mth2 = 1.0 - theta2;
+        let c4 = 2.0
+            * xnodp
+            * coef1
+            * aodp
+            * betao2
+            * (eta * (2.0 + 0.5 * etasq) + e0 * (0.5 + 2.0 * etasq)
+                - 2.0 * CK2 * tsi / (aodp * psisq)
+                    * (-3.0 * x3thm1 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75
+                            * x1mth2
+                            * (2.0 * etasq - eeta * (1.0 + etasq))
+                            * (2.0 * tle.arg_perigee).cos()));
+        let c5 = 2.0 * coef1 * aodp * betao2 * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+
+        // Secular rates of the mean anomaly, perigee and node.
+        let theta4 = theta2 * theta2;
+        let temp1 = 3.0 * CK2 * pinvsq * xnodp;
+        let temp2 = temp1 * CK2 * pinvsq;
+        let temp3 = 1.25 * CK4 * pinvsq * pinvsq * xnodp;
+        let xmdot = xnodp
+            + 0.5 * temp1 * betao * x3thm1
+            + 0.0625 * temp2 * betao * (13.0 - 78.0 * theta2 + 137.0 * theta4);
+        let x1m5th = 1.0 - 5.0 * theta2;
+        let omgdot = -0.5 * temp1 * x1m5th
+            + 0.0625 * temp2 * (7.0 - 114.0 * theta2 + 395.0 * theta4)
+            + temp3 * (3.0 - 36.0 * theta2 + 49.0 * theta4);
+        let xhdot1 = -temp1 * cosio;
+        let xnodot = xhdot1
+            + (0.5 * temp2 * (4.0 - 19.0 * theta2) + 2.0 * temp3 * (3.0 - 7.0 * theta2)) * cosio;
+
+        let omgcof = bstar * c3 * tle.arg_perigee.cos();
+        let xmcof = if e0 > 1e-4 {
+            -2.0 / 3.0 * coef * bstar / eeta
+        } else {
+            0.0
+        };
+        let xnodcf = 3.5 * betao2 * xhdot1 * c1;
+        let t2cof = 1.5 * c1;
+        // Guard the division for equatorial retrograde orbits.
+        let cosio_plus_1 = if (1.0 + cosio).abs() > 1.5e-12 {
+            1.0 + cosio
+        } else {
+            1.5e-12
+        };
+        let xlcof = 0.125 * a3ovk2 * sinio * (3.0 + 5.0 * cosio) / cosio_plus_1;
+        let aycof = 0.25 * a3ovk2 * sinio;
+        let delmo = (1.0 + eta * tle.mean_anomaly.cos()).powi(3);
+        let sinmo = tle.mean_anomaly.sin();
+        let x7thm1 = 7.0 * theta2 - 1.0;
+
+        let (mut d2, mut d3, mut d4, mut t3cof, mut t4cof, mut t5cof) =
+            (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        if !simple {
+            let c1sq = c1 * c1;
+            d2 = 4.0 * aodp * tsi * c1sq;
+            let temp = d2 * tsi * c1 / 3.0;
+            d3 = (17.0 * aodp + s4) * temp;
+            d4 = 0.5 * temp * aodp * tsi * (221.0 * aodp + 31.0 * s4) * c1;
+            t3cof = d2 + 2.0 * c1sq;
+            t4cof = 0.25 * (3.0 * d3 + c1 * (12.0 * d2 + 10.0 * c1sq));
+            t5cof =
+                0.2 * (3.0 * d4 + 12.0 * c1 * d3 + 6.0 * d2 * d2 + 15.0 * c1sq * (2.0 * d2 + c1sq));
+        }
+
+        Ok(Self {
+            inclination: i0,
+            raan: tle.raan,
+            eccentricity: e0,
+            arg_perigee: tle.arg_perigee,
+            mean_anomaly: tle.mean_anomaly,
+            bstar,
+            xnodp,
+            aodp,
+            simple,
+            eta,
+            c1,
+            c4,
+            c5,
+            d2,
+            d3,
+            d4,
+            delmo,
+            sinmo,
+            omgcof,
+            xmcof,
+            xnodcf,
+            t2cof,
+            t3cof,
+            t4cof,
+            t5cof,
+            xlcof,
+            aycof,
+            xmdot,
+            omgdot,
+            xnodot,
+            x3thm1,
+            x1mth2,
+            x7thm1,
+        })
+    }
+
+    /// Position (Earth radii) in the inertial frame `t` minutes after the
+    /// epoch, or `None` once the orbit has decayed.
+    fn position(&self, t: f64) -> Option<DVec3> {
+        // Secular gravity and atmospheric drag.
+        let xmdf = self.mean_anomaly + self.xmdot * t;
+        let omgadf = self.arg_perigee + self.omgdot * t;
+        let xnoddf = self.raan + self.xnodot * t;
+        let tsq = t * t;
+        let xnode = xnoddf + self.xnodcf * tsq;
+        let mut omega = omgadf;
+        let mut xmp = xmdf;
+        let mut tempa = 1.0 - self.c1 * t;
+        let mut tempe = self.bstar * self.c4 * t;
+        let mut templ = self.t2cof * tsq;
+        if !self.simple {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            xmp = xmdf + temp;
+            omega = omgadf - temp;
+            let tcube = tsq * t;
+            let tfour = t * tcube;
+            tempa -= self.d2 * tsq + self.d3 * tcube + self.d4 * tfour;
+            tempe += self.bstar * self.c5 * (xmp.sin() - self.sinmo);
+            templ += self.t3cof * tcube + tfour * (self.t4cof + t * self.t5cof);
+        }
+        let a = self.aodp * tempa * tempa;
+        let e = self.eccentricity - tempe;
+        if !(0.0..1.0).contains(&e) || a < 1.0 {
+            return None;
+        }
+        let xl = xmp + omega + xnode + self.xnodp * templ;
+        let beta = (1.0 - e * e).sqrt();
+
+        // Long-period periodics.
+        let axn = e * omega.cos();
+        let temp = 1.0 / (a * beta * beta);
+        let xll = temp * self.xlcof * axn;
+        let aynl = temp * self.aycof;
+        let xlt = xl + xll;
+        let ayn = e * omega.sin() + aynl;
+
+        // Kepler's equation for the eccentric longitude.
+        let capu = (xlt - xnode).rem_euclid(TAU);
+        let mut epw = capu;
+        for _ in 0..10 {
+            let (sinepw, cosepw) = epw.sin_cos();
+            let next = (capu - ayn * cosepw + axn * sinepw - epw)
+                / (1.0 - axn * cosepw - ayn * sinepw)
+                + epw;
+            let converged = (next - epw).abs() <= 1e-12;
+            epw = next;
+            if converged {
+                break;
+            }
+        }
+        let (sinepw, cosepw) = epw.sin_cos();
+
+        // Short-period preliminaries.
+        let ecose = axn * cosepw + ayn * sinepw;
+        let esine = axn * sinepw - ayn * cosepw;
+        let elsq = axn * axn + ayn * ayn;
+        let pl = a * (1.0 - elsq);
+        if pl <= 0.0 {
+            return None;
+        }
+        let r = a * (1.0 - ecose);
+        let betal = (1.0 - elsq).sqrt();
+        let temp3 = esine / (1.0 + betal);
+        let cosu = a / r * (cosepw - axn + ayn * temp3);
+        let sinu = a / r * (sinepw - ayn - axn * temp3);
+        let u = sinu.atan2(cosu);
+        let sin2u = 2.0 * sinu * cosu;
+        let cos2u = 2.0 * cosu * cosu - 1.0;
+        let temp1 = CK2 / pl;
+        let temp2 = temp1 / pl;
+
+        // Short-period periodics.
+        let cosio = self.inclination.cos();
+        let sinio = self.inclination.sin();
+        let rk = r * (1.0 - 1.5 * temp2 * betal * self.x3thm1) + 0.5 * temp1 * self.x1mth2 * cos2u;
+        let uk = u - 0.25 * temp2 * self.x7thm1 * sin2u;
+        let xnodek = xnode + 1.5 * temp2 * cosio * sin2u;
+        let xinck = self.inclination + 1.5 * temp2 * cosio * sinio * cos2u;
+        if rk < 1.0 {
+            return None;
+        }
+
+        // Orientation of the orbit.
+        let (sinuk, cosuk) = uk.sin_cos();
+        let (sinik, cosik) = xinck.sin_cos();
+        let (sinnok, cosnok) = xnodek.sin_cos();
+        let xmx = -sinnok * cosik;
+        let xmy = cosnok * cosik;
+        Some(
+            DVec3::new(
+                xmx * sinuk + cosnok * cosuk,
+                xmy * sinuk + sinnok * cosuk,
+                sinik * sinuk,
+            ) * rk,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vanguard 1, the reference case from Spacetrack Report #3's
+    /// successors (Vallado et al., "Revisiting Spacetrack Report #3").
+    const VANGUARD: &str = "\
+1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753
+2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+
+    #[test]
+    fn parses_element_sets() {
+        let tles = parse_tles(&format!("# comment\n\nVANGUARD 1\n{VANGUARD}\n")).unwrap();
+        let [tle] = &tles[..] else {
+            panic!("expected one element set");
+        };
+        assert_eq!(tle.name, "VANGUARD 1");
+        assert_eq!(tle.catalog_number, 5);
+        assert!((tle.bstar - 0.28098e-4).abs() < 1e-12);
+        assert!((tle.eccentricity - 0.1859667).abs() < 1e-12);
+        assert!((tle.mean_motion - 10.824_191_57).abs() < 1e-9);
+        // 2000-06-27 18:50:19 UT.
+        assert!((tle.epoch_days - 178.284_950_62).abs() < 1e-8);
+
+        let corrupted = VANGUARD.replace("4753", "4754");
+        assert_eq!(
+            parse_tles(&corrupted).unwrap_err().reason,
+            "checksum mismatch"
+        );
+    }
+
+    #[test]
+    fn propagates_the_reference_case() {
+        let tle = parse_tles(VANGUARD).unwrap().remove(0);
+        let satellite = Satellite::new(tle).unwrap();
+        // Published positions (km) at the epoch and six hours on, to 10 m.
+        for (minutes, expected) in [
+            (
+                0.0,
+                DVec3::new(7_022.465_292_66, -1_400.082_967_55, 0.039_951_55),
+            ),
+            (
+                360.0,
+                DVec3::new(-7_154.031_202_02, -3_783.176_825_04, -3_536.194_122_94),
+            ),
+        ] {
+            let position = satellite.position_eci(minutes).unwrap() / 1_000.0;
+            assert!(
+                position.distance(expected) < 0.01,
+                "{minutes} min: {position} vs {expected}"
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Cascaded shadow maps from the sun.
+//!
+//! The [`Sun`] light casts shadows through Bevy's cascaded shadow maps, which
+//! by default reach about a kilometre, fine for a room and useless for a
+//! mountain range seen from a hillside. [`SunShadowPlugin`] sets the cascades
+//! each frame from the [`SunShadowQuality`] and the camera's altitude: from
+//! the air the ground is never nearer than the altitude, so the cascades
+//! stretch out with it, and above [`MAX_SHADOW_ALTITUDE_M`] shadows are
+//! switched off since relief shadows are sub-pixel from orbit. They're also
+//! off once the sun is below the horizon at the camera.
+//!
+//! The cascades are fitted to the camera's view frustum every frame, and the
+//! sun only ever rotates, so recentring the floating origin doesn't disturb
+//! them. Which tiles cast is up to the terrain: every streamed tile casts and
+//! receives, with its prepass applying the same octant mask as its main pass
+//! so a parent tile's hidden octants don't shadow its children.
+
+use bevy::{
+    light::{CascadeShadowConfig, CascadeShadowConfigBuilder, DirectionalLightShadowMap},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use veldera_constants::EARTH_RADIUS_M_F64;
+
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use crate::time_of_day::Sun;
+
+/// Camera altitude above which the sun casts no shadows (m).
+pub const MAX_SHADOW_ALTITUDE_M: f32 = 60_000.0;
+
+/// Sun elevation cosine below which the sun casts no shadows: a little under
+/// the horizon, so peaks keep their shadows through sunset.
+const MIN_SHADOW_SUN_MU: f32 = -0.05;
+
+/// Keeps the sun's shadow cascades fitted to the camera.
+pub struct SunShadowPlugin;
+
+impl Plugin for SunShadowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SunShadowQuality>()
+            .add_systems(PostUpdate, update_sun_shadows);
+    }
+}
+
+/// How much the sun's shadows cost, picked in the Settings tab.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SunShadowQuality {
+    /// No sun shadows.
+    Off,
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+/// Cascade layout for one [`SunShadowQuality`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SunShadowSettings {
+    /// Number of cascades.
+    pub cascades: usize,
+    /// Width and height of each cascade's shadow map (texels).
+    pub map_size: usize,
+    /// Far bound of the first cascade on the ground (m).
+    pub first_cascade_m: f32,
+    /// Far bound of the last cascade on the ground (m).
+    pub max_distance_m: f32,
+}
+
+impl SunShadowQuality {
+    /// Every quality, from cheapest.
+    pub const ALL: [SunShadowQuality; 4] = [Self::Off, Self::Low, Self::Medium, Self::High];
+
+    /// The cascade layout, or `None` when shadows are off.
+    pub fn settings(self) -> Option<SunShadowSettings> {
+        match self {
+            SunShadowQuality::Off => None,
+            SunShadowQuality::Low => Some(SunShadowSettings {
+                cascades: 2,
+                map_size: 1024,
+                first_cascade_m: 150.0,
+                max_distance_m: 4_000.0,
+            }),
+            SunShadowQuality::Medium => Some(SunShadowSettings {
+                cascades: 3,
+                map_size: 2048,
+                first_cascade_m: 100.0,
+                max_distance_m: 12_000.0,
+            }),
+            SunShadowQuality::High => Some(SunShadowSettings {
+                cascades: 4,
+                map_size: 4096,
+                first_cascade_m: 60.0,
+                max_distance_m: 30_000.0,
+            }),
+        }
+    }
+}
+
+/// The `(first cascade far bound, maximum distance)` in view depth for a
+/// camera `altitude_m` above the ground. From the air the nearest ground is
+/// about the altitude away, so both bounds are pushed out past it.
+pub fn cascade_bounds(settings: &SunShadowSettings, altitude_m: f32) -> (f32, f32) {
+    let altitude = altitude_m.max(0.0);
+    let first = settings.first_cascade_m.max(altitude * 1.5);
+    let max = settings.max_distance_m.max(altitude * 6.0).max(first * 2.0);
+    (first, max)
+}
+
+/// Turn the sun's shadows on or off, and fit its cascades to the camera's
+/// altitude. Only writes when something changed, so the light isn't flagged
+/// every frame.
+fn update_sun_shadows(
+    quality: Res<SunShadowQuality>,
+    mut shadow_map: ResMut<DirectionalLightShadowMap>,
+    camera: Query<&FloatingOriginCamera>,
+    mut sun: Query<(&mut DirectionalLight, &mut CascadeShadowConfig, &Transform), With<Sun>>,
+    mut applied: Local<Option<(usize, f32, f32)>>,
+) {
+    let (Ok(camera), Ok((mut light, mut cascades, transform))) =
+        (camera.single(), sun.single_mut())
+    else {
+        return;
+    };
+    let altitude = (camera.position.length() - EARTH_RADIUS_M_F64) as f32;
+    let sun_mu = transform
+        .back()
+        .as_vec3()
+        .dot(camera.position.normalize().as_vec3());
+
+    let settings = quality
+        .settings()
+        .filter(|_| altitude < MAX_SHADOW_ALTITUDE_M && sun_mu > MIN_SHADOW_SUN_MU);
+    if light.shadows_enabled != settings.is_some() {
+        light.shadows_enabled = settings.is_some();
+    }
+    let Some(settings) = settings else {
+        return;
+    };
+
+    if shadow_map.size != settings.map_size {
+        shadow_map.size = settings.map_size;
+    }
+    let (first, max) = cascade_bounds(&settings, altitude);
+    // Refitting on every metre of climb would rebuild the cascades each
+    // frame; a few percent either way is invisible.
+    let key = (settings.cascades, first, max);
+    if let Some((count, applied_first, applied_max)) = *applied
+        && count == key.0
+        && (applied_first / first - 1.0).abs() < 0.05
+        && (applied_max / max - 1.0).abs() < 0.05
+    {
+        return;
+    }
+    *applied = Some(key);
+    *cascades = CascadeShadowConfigBuilder {
+        num_cascades: settings.cascades,
+        first_cascade_far_bound: first,
+        maximum_distance: max,
+        ..default()
+    }
+    .build();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cascades_follow_altitude() {
+        let settings = SunShadowQuality::Medium.settings().unwrap();
+        assert_eq!(
+            cascade_bounds(&settings, 0.0),
+            (settings.first_cascade_m, settings.max_distance_m)
+        );
+        let (first, max) = cascade_bounds(&settings, 10_000.0);
+        assert!(first >= 10_000.0);
+        assert!(max > first);
+        assert!(max >= cascade_bounds(&settings, 2_000.0).1);
+    }
+
+    #[test]
+    fn qualities_grow() {
+        assert!(SunShadowQuality::Off.settings().is_none());
+        let layouts: Vec<_> = SunShadowQuality::ALL[1..]
+            .iter()
+            .map(|quality| quality.settings().unwrap())
+            .collect();
+        for pair in layouts.windows(2) {
+            assert!(pair[1].cascades >= pair[0].cascades);
+            assert!(pair[1].map_size >= pair[0].map_size);
+            assert!(pair[1].max_distance_m > pair[0].max_distance_m);
+        }
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Provides real-time or manually controlled time that affects the sky color
 //! and sun direction, with support for longitude-based local time calculation.
-//! Includes accurate sun declination based on day of year.
+//! Includes accurate sun declination based on day of year
+//! ([`solar_declination_deg`]) and the day length it gives at a latitude
+//! ([`day_length_hours`]).
 
 use bevy::{prelude::*, reflect::TypePath};
 use serde::Deserialize;
@@ -250,11 +252,7 @@ impl TimeOfDayState {
     /// Declination ranges from -23.44° (winter solstice, ~Dec 21) to
     /// +23.44° (summer solstice, ~Jun 21).
     pub fn sun_declination_deg(&self) -> f64 {
-        let day = self.day_of_year() as f64;
-        // Approximate formula: declination = -23.44 * cos(360/365 * (day + 10))
-        // The +10 shifts so that the winter solstice (Dec 21, ~day 355) gives minimum.
-        let angle_rad = (360.0 / 365.0 * (day + 10.0)).to_radians();
-        -AXIAL_TILT_DEG * angle_rad.cos()
+        solar_declination_deg(self.current_date())
     }
 
     /// Returns the current local time at the given longitude as
@@ -311,6 +309,40 @@ impl TimeOfDayState {
 /// Seconds in an hour.
 pub const SECONDS_PER_HOUR: f64 = 3600.0;
 
+/// Altitude of the sun's centre at sunrise and sunset (degrees): its upper
+/// limb on the horizon (-0.27°), lifted by refraction (-0.57°). The standard
+/// the published sunrise tables use.
+pub const SUNRISE_ALTITUDE_DEG: f64 = -0.833;
+
+/// The sun's declination in degrees on `date`.
+///
+/// Declination ranges from -23.44° (winter solstice, ~Dec 21) to +23.44°
+/// (summer solstice, ~Jun 21), crossing 0 at the equinoxes.
+pub fn solar_declination_deg(date: SimpleDate) -> f64 {
+    let day = f64::from(date.day_of_year());
+    // Approximate formula: declination = -23.44 * cos(360/365 * (day + 10))
+    // The +10 shifts so that the winter solstice (Dec 21, ~day 355) gives minimum.
+    let angle_rad = (360.0 / 365.0 * (day + 10.0)).to_radians();
+    -AXIAL_TILT_DEG * angle_rad.cos()
+}
+
+/// Hours between sunrise and sunset at `lat_deg` when the sun's declination
+/// is `declination_deg`: 24 under the midnight sun, 0 in the polar night.
+pub fn day_length_hours(lat_deg: f64, declination_deg: f64) -> f64 {
+    let (lat, dec) = (lat_deg.to_radians(), declination_deg.to_radians());
+    // Sunrise equation: the hour angle at which the sun's centre sits at
+    // `SUNRISE_ALTITUDE_DEG`. Out of range means it never gets there.
+    let cos_hour_angle =
+        (SUNRISE_ALTITUDE_DEG.to_radians().sin() - lat.sin() * dec.sin()) / (lat.cos() * dec.cos());
+    if cos_hour_angle <= -1.0 {
+        24.0
+    } else if cos_hour_angle >= 1.0 {
+        0.0
+    } else {
+        2.0 * cos_hour_angle.acos().to_degrees() / 15.0
+    }
+}
+
 /// Seconds in a day.
 pub const SECONDS_PER_DAY: f64 = 86400.0;
 
@@ -626,6 +658,8 @@ fn update_sun_direction(
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     fn approx_eq(a: f64, b: f64) -> bool {
@@ -664,6 +698,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn declination_at_equinoxes_and_solstices() {
+        let equinox = solar_declination_deg(SimpleDate::new(2026, 3, 20));
+        let june = solar_declination_deg(SimpleDate::new(2026, 6, 21));
+        let december = solar_declination_deg(SimpleDate::new(2026, 12, 21));
+        assert!(equinox.abs() < 1.0, "March equinox: {equinox}");
+        assert!((june - AXIAL_TILT_DEG).abs() < 0.1, "June solstice: {june}");
+        assert!(
+            (december + AXIAL_TILT_DEG).abs() < 0.1,
+            "December solstice: {december}"
+        );
+    }
+
+    #[test]
+    fn day_lengths_at_known_latitudes() {
+        // (latitude, date, expected hours); the expectations are the
+        // published sunrise-to-sunset times, within five minutes.
+        let cases: &[(f64, SimpleDate, f64)] = &[
+            // Equator: a little over 12 h all year, from refraction.
+            (0.0, SimpleDate::new(2026, 3, 20), 12.0 + 7.0 / 60.0),
+            (0.0, SimpleDate::new(2026, 6, 21), 12.0 + 7.0 / 60.0),
+            // London, 51.5° N: 16 h 38 min and 7 h 50 min at the solstices.
+            (51.5, SimpleDate::new(2026, 6, 21), 16.0 + 38.0 / 60.0),
+            (51.5, SimpleDate::new(2026, 12, 21), 7.0 + 50.0 / 60.0),
+            // Sydney, 33.9° S: the seasons flip.
+            (-33.9, SimpleDate::new(2026, 6, 21), 9.0 + 54.0 / 60.0),
+            (-33.9, SimpleDate::new(2026, 12, 21), 14.0 + 25.0 / 60.0),
+        ];
+        for &(lat, date, expected) in cases {
+            let hours = day_length_hours(lat, solar_declination_deg(date));
+            assert!(
+                (hours - expected).abs() < 5.0 / 60.0,
+                "lat {lat} on {date:?}: {hours} h, expected {expected} h"
+            );
+        }
+
+        // Tromsø, 69.65° N: midnight sun and polar night.
+        let tromso = 69.65;
+        let june = solar_declination_deg(SimpleDate::new(2026, 6, 21));
+        let december = solar_declination_deg(SimpleDate::new(2026, 12, 21));
+        assert_eq!(day_length_hours(tromso, june), 24.0);
+        assert_eq!(day_length_hours(tromso, december), 0.0);
+    }
+
+    proptest! {
+        /// The declination stays within the axial tilt on every date.
+        #[test]
+        fn declination_within_tilt(year in 1900i32..2100, day in 0u32..366) {
+            let mut date = SimpleDate::new(year, 1, 1);
+            for _ in 0..day.min(date.days_in_year() - 1) {
+                date.advance_day();
+            }
+            prop_assert!(solar_declination_deg(date).abs() <= AXIAL_TILT_DEG);
+        }
+
+        /// Day lengths are within a day, mirror between the hemispheres,
+        /// and run a little over 12 h at the equinoxes outside the polar
+        /// regions.
+        #[test]
+        fn day_length_symmetries(lat in -89.0f64..89.0, dec in -23.44f64..23.44) {
+            let hours = day_length_hours(lat, dec);
+            prop_assert!((0.0..=24.0).contains(&hours));
+            prop_assert!((hours - day_length_hours(-lat, -dec)).abs() < 1e-9);
+            if lat.abs() < 60.0 {
+                let equinox = day_length_hours(lat, 0.0);
+                prop_assert!((12.0..12.25).contains(&equinox), "{lat}: {equinox}");
+            }
+        }
+
+        /// Summer days lengthen towards the pole.
+        #[test]
+        fn summer_days_lengthen_poleward(lat in 0.0f64..65.0, dec in 1.0f64..23.44) {
+            prop_assert!(day_length_hours(lat + 1.0, dec) >= day_length_hours(lat, dec));
+        }
+    }
+
     #[test]
     fn local_date_preserved_through_scrub() {
         // The motivating bug: at NYC at 19:30 local on 2026-05-16, UTC
@@ -0,0 +1,498 @@
+//! Weather: ground fog, haze and precipitation layered over the atmosphere.
+//!
+//! [`Weather`] holds the current conditions. They're set by hand for now (the
+//! Weather sub-tab of the debug UI), and are plain numbers so live weather
+//! data can drive them later.
+//!
+//! Fog and haze act on the scattering medium, so they show up in the sky, the
+//! aerial perspective and the sunlight's extinction alike. [`WeatherMedium`]
+//! keeps the clear-weather medium; whenever it or the weather changes, the
+//! atmosphere's medium is rebuilt from it, with its aerosol (Mie) terms scaled
+//! by the haze and a ground fog term added: a dense, forward-scattering layer
+//! whose density falls off exponentially over the fog height. Anything that
+//! replaces the medium wholesale (an atmosphere preset, say) goes through
+//! [`WeatherMedium::set_base`] so the weather stays on top.
+//!
+//! Precipitation thickens the haze, and fills the space around the camera
+//! with falling streaks (rain) or drifting flakes (snow). The drops carry a
+//! [`WorldPosition`] and wrap around inside a box that follows the camera, so
+//! a fixed pool of them is enough however far the camera travels.
+//!
+//! The tuning (drop pool and box, fog droplet phase and thinnest layer,
+//! precipitation haze) lives in the `[weather]` table of the atmosphere config
+//! as [`WeatherConfig`].
+
+use bevy::{
+    light::NotShadowCaster,
+    pbr::{Falloff, PhaseFunction, ScatteringMedium, ScatteringTerm},
+    prelude::*,
+};
+use serde::Deserialize;
+use veldera_atmosphere::SphericalAtmosphere;
+use veldera_geo::{
+    coords::RadialFrame,
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+
+use crate::{atmosphere::AtmosphereConfig, preset::apply_atmosphere_preset};
+
+/// Density LUT samples per fog scale height.
+const FOG_SAMPLES_PER_SCALE_HEIGHT: f32 = 4.0;
+
+/// Cap on the density LUT resolution a fog layer asks for.
+const MAX_FALLOFF_RESOLUTION: u32 = 2048;
+
+/// Koschmieder's constant: the extinction times the distance at which a dark
+/// object fades into the fog at 2% contrast.
+const VISIBILITY_CONTRAST: f32 = 3.912;
+
+/// Tuning for the weather's effects. Lives in the `[weather]` table of the
+/// atmosphere config.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeatherConfig {
+    /// Drops at full precipitation intensity.
+    pub max_drops: usize,
+    /// Half-width of the box the drops fill around the camera (m).
+    pub drop_box_half_width_m: f32,
+    /// Half-height of the box the drops fill around the camera (m).
+    pub drop_box_half_height_m: f32,
+    /// Forward-scattering asymmetry of fog droplets.
+    pub fog_asymmetry: f32,
+    /// Thinnest ground fog layer (m); thinner ones can't be resolved.
+    pub min_fog_height_m: f32,
+    /// Extra haze at full precipitation intensity, as a fraction of the haze.
+    pub precipitation_haze: f32,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            max_drops: 2000,
+            drop_box_half_width_m: 20.0,
+            drop_box_half_height_m: 15.0,
+            fog_asymmetry: 0.85,
+            min_fog_height_m: 20.0,
+            precipitation_haze: 1.0,
+        }
+    }
+}
+
+/// Plugin for the weather and its effects.
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .init_resource::<WeatherMedium>()
+            .init_resource::<PrecipitationAssets>()
+            .add_systems(
+                Update,
+                (
+                    apply_weather_to_medium.after(apply_atmosphere_preset),
+                    update_precipitation,
+                ),
+            );
+    }
+}
+
+/// What falls from the sky.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrecipitationKind {
+    #[default]
+    Rain,
+    Snow,
+}
+
+impl PrecipitationKind {
+    /// Fall speed (m/s).
+    fn fall_speed(self) -> f32 {
+        match self {
+            Self::Rain => 9.0,
+            Self::Snow => 1.2,
+        }
+    }
+
+    /// Amplitude of the sideways drift (m/s).
+    fn sway(self) -> f32 {
+        match self {
+            Self::Rain => 0.0,
+            Self::Snow => 0.4,
+        }
+    }
+}
+
+/// The current weather conditions.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Weather {
+    /// Ground fog extinction at the surface (m⁻¹); 0 for none. See
+    /// [`fog_visibility_m`](Self::fog_visibility_m).
+    pub fog_density: f32,
+    /// Scale height of the ground fog (m).
+    pub fog_height_m: f32,
+    /// Multiplier on the aerosols of the medium; 1 leaves them alone.
+    pub haze: f32,
+    /// What falls while `precipitation_intensity` is above 0.
+    pub precipitation: PrecipitationKind,
+    /// How hard it rains or snows, 0–1.
+    pub precipitation_intensity: f32,
+    /// Fraction of the sky covered by cloud, 0–1, if known. Recorded for
+    /// display only; the cloud layers' coverage still comes from their own
+    /// climate model.
+    pub cloud_cover: Option<f32>,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            fog_density: 0.0,
+            fog_height_m: 100.0,
+            haze: 1.0,
+            precipitation: PrecipitationKind::default(),
+            precipitation_intensity: 0.0,
+            cloud_cover: None,
+        }
+    }
+}
+
+impl Weather {
+    /// Distance a dark object stays visible through the ground fog at the
+    /// surface (m), or `None` without fog.
+    #[must_use]
+    pub fn fog_visibility_m(&self) -> Option<f32> {
+        (self.fog_density > 0.0).then(|| VISIBILITY_CONTRAST / self.fog_density)
+    }
+
+    /// Set the ground fog from a surface visibility (m).
+    pub fn set_fog_visibility_m(&mut self, visibility_m: f32) {
+        self.fog_density = VISIBILITY_CONTRAST / visibility_m.max(1.0);
+    }
+
+    /// Multiplier on the medium's aerosols: the haze, thickened by
+    /// precipitation.
+    fn aerosol_multiplier(&self, config: &WeatherConfig) -> f32 {
+        self.haze.max(0.0)
+            * (1.0 + config.precipitation_haze * self.precipitation_intensity.clamp(0.0, 1.0))
+    }
+
+    /// Drops to keep around the camera.
+    fn drop_count(&self, config: &WeatherConfig) -> usize {
+        (config.max_drops as f32 * self.precipitation_intensity.clamp(0.0, 1.0)).round() as usize
+    }
+}
+
+/// The clear-weather scattering medium the weather is laid over.
+#[derive(Resource, Default)]
+pub struct WeatherMedium {
+    /// Taken from the atmosphere the first time it's seen, unless set first.
+    base: Option<ScatteringMedium>,
+    /// Rebuild the atmosphere's medium at the next opportunity.
+    dirty: bool,
+}
+
+impl WeatherMedium {
+    /// The clear-weather medium, once known.
+    #[must_use]
+    pub fn base(&self) -> Option<&ScatteringMedium> {
+        self.base.as_ref()
+    }
+
+    /// Edit the clear-weather medium; the weather is re-applied over it.
+    pub fn base_mut(&mut self) -> Option<&mut ScatteringMedium> {
+        self.dirty = true;
+        self.base.as_mut()
+    }
+
+    /// Replace the clear-weather medium; the weather is re-applied over it.
+    pub fn set_base(&mut self, medium: ScatteringMedium) {
+        self.base = Some(medium);
+        self.dirty = true;
+    }
+}
+
+/// `base` under `weather`, for an atmosphere `atmosphere_height` (m) deep.
+fn weathered_medium(
+    base: &ScatteringMedium,
+    weather: &Weather,
+    config: &WeatherConfig,
+    atmosphere_height: f32,
+) -> ScatteringMedium {
+    let mut medium = base.clone();
+    let aerosols = weather.aerosol_multiplier(config);
+    for term in &mut medium.terms {
+        if matches!(term.phase, PhaseFunction::Mie { .. }) {
+            term.absorption *= aerosols;
+            term.scattering *= aerosols;
+        }
+    }
+
+    if weather.fog_density > 0.0 {
+        let scale = weather.fog_height_m.max(config.min_fog_height_m) / atmosphere_height.max(1.0);
+        // The density LUT spans the whole atmosphere; sample it finely enough
+        // to resolve a layer a few hundred metres deep.
+        let resolution =
+            ((FOG_SAMPLES_PER_SCALE_HEIGHT / scale).ceil() as u32).min(MAX_FALLOFF_RESOLUTION);
+        medium.falloff_resolution = medium.falloff_resolution.max(resolution);
+        medium.terms.push(ScatteringTerm {
+            absorption: Vec3::ZERO,
+            scattering: Vec3::splat(weather.fog_density),
+            falloff: Falloff::Exponential { scale },
+            phase: PhaseFunction::Mie {
+                asymmetry: config.fog_asymmetry,
+            },
+        });
+    }
+    medium
+}
+
+/// Rebuild the camera's medium when the weather, its tuning or the
+/// clear-weather medium changes.
+fn apply_weather_to_medium(
+    weather: Res<Weather>,
+    config: Res<AtmosphereConfig>,
+    mut weather_medium: ResMut<WeatherMedium>,
+    camera: Query<&SphericalAtmosphere, With<FloatingOriginCamera>>,
+    mut media: ResMut<Assets<ScatteringMedium>>,
+) {
+    let Ok(atmosphere) = camera.single() else {
+        return;
+    };
+    if weather_medium.base.is_none() {
+        // The first medium seen is the clear-weather one.
+        let Some(medium) = media.get(&atmosphere.medium) else {
+            return;
+        };
+        weather_medium.base = Some(medium.clone());
+        weather_medium.dirty = true;
+    }
+    if !weather.is_changed() && !config.is_changed() && !weather_medium.dirty {
+        return;
+    }
+    weather_medium.dirty = false;
+
+    let Some(base) = weather_medium.base() else {
+        return;
+    };
+    let weathered = weathered_medium(
+        base,
+        &weather,
+        &config.weather,
+        atmosphere.top_radius - atmosphere.bottom_radius,
+    );
+    if let Some(medium) = media.get_mut(&atmosphere.medium) {
+        *medium = weathered;
+    }
+}
+
+/// Shared drop meshes and materials, built once at startup.
+#[derive(Resource)]
+struct PrecipitationAssets {
+    /// Thin vertical streak, for rain.
+    streak_mesh: Handle<Mesh>,
+    /// Small sphere, for snow.
+    flake_mesh: Handle<Mesh>,
+    rain_material: Handle<StandardMaterial>,
+    snow_material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PrecipitationAssets {
+    fn from_world(world: &mut World) -> Self {
+        let (streak_mesh, flake_mesh) = {
+            let mut meshes = world.resource_mut::<Assets<Mesh>>();
+            (
+                meshes.add(Cuboid::new(0.01, 0.5, 0.01)),
+                meshes.add(Sphere::new(0.015)),
+            )
+        };
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let drop_material = |color: Color| StandardMaterial {
+            base_color: color,
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..Default::default()
+        };
+        Self {
+            streak_mesh,
+            flake_mesh,
+            rain_material: materials.add(drop_material(Color::srgba(0.7, 0.75, 0.8, 0.3))),
+            snow_material: materials.add(drop_material(Color::srgba(1.0, 1.0, 1.0, 0.85))),
+        }
+    }
+}
+
+/// One rain or snow drop.
+#[derive(Component)]
+struct PrecipitationDrop {
+    /// Phase of the sideways drift (rad).
+    phase: f32,
+}
+
+/// Keep as many drops around the camera as the precipitation asks for, and
+/// let them fall.
+#[allow(clippy::too_many_arguments)]
+fn update_precipitation(
+    weather: Res<Weather>,
+    config: Res<AtmosphereConfig>,
+    assets: Res<PrecipitationAssets>,
+    time: Res<Time>,
+    camera: Query<&FloatingOriginCamera>,
+    mut drops: Query<(
+        Entity,
+        &PrecipitationDrop,
+        &mut WorldPosition,
+        &mut Transform,
+    )>,
+    mut commands: Commands,
+    mut kind: Local<PrecipitationKind>,
+    mut seed: Local<u32>,
+) {
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+    let config = &config.weather;
+    let (half_width, half_height) = (config.drop_box_half_width_m, config.drop_box_half_height_m);
+    let frame = RadialFrame::from_ecef_position(camera.position);
+    let east = frame.up.cross(frame.north).normalize_or_zero();
+    let rotation = Quat::from_mat3(&Mat3::from_cols(east, frame.up, frame.north));
+
+    // A change of kind swaps every drop.
+    let mut existing = drops.iter().count();
+    if *kind != weather.precipitation {
+        *kind = weather.precipitation;
+        for (entity, ..) in &drops {
+            commands.entity(entity).despawn();
+        }
+        existing = 0;
+    }
+
+    let wanted = weather.drop_count(config);
+    if existing > wanted {
+        for (entity, ..) in drops.iter().take(existing - wanted) {
+            commands.entity(entity).despawn();
+        }
+    }
+    let (mesh, material) = match *kind {
+        PrecipitationKind::Rain => (&assets.streak_mesh, &assets.rain_material),
+        PrecipitationKind::Snow => (&assets.flake_mesh, &assets.snow_material),
+    };
+    for _ in existing..wanted {
+        let local = Vec3::new(
+            random_signed(&mut seed) * half_width,
+            random_signed(&mut seed) * half_height,
+            random_signed(&mut seed) * half_width,
+        );
+        let offset = east * local.x + frame.up * local.y + frame.north * local.z;
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_rotation(rotation),
+            WorldPosition::from_dvec3(camera.position + offset.as_dvec3()),
+            NotShadowCaster,
+            PrecipitationDrop {
+                phase: random_signed(&mut seed) * std::f32::consts::PI,
+            },
+            Name::new("weather_drop"),
+        ));
+    }
+
+    let dt = time.delta_secs();
+    let elapsed = time.elapsed_secs();
+    let fall = kind.fall_speed() * dt;
+    let sway = kind.sway() * dt;
+    for (_, drop, mut world_pos, mut transform) in &mut drops {
+        let offset = (world_pos.position - camera.position).as_vec3();
+        let drift = (elapsed + drop.phase).sin() * sway;
+        let local = Vec3::new(
+            wrap(offset.dot(east) + drift, half_width),
+            wrap(offset.dot(frame.up) - fall, half_height),
+            wrap(offset.dot(frame.north), half_width),
+        );
+        let offset = east * local.x + frame.up * local.y + frame.north * local.z;
+        world_pos.position = camera.position + offset.as_dvec3();
+        transform.rotation = rotation;
+    }
+}
+
+/// Wrap `value` into `-half..half`, so a drop leaving one side of the box
+/// comes back in on the other.
+fn wrap(value: f32, half: f32) -> f32 {
+    let half = half.max(f32::EPSILON);
+    (value + half).rem_euclid(2.0 * half) - half
+}
+
+/// The next value in `-1..1` from a tiny LCG. Drops only need cosmetic
+/// scatter, not statistical quality.
+fn random_signed(state: &mut u32) -> f32 {
+    *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+    (*state >> 8) as f32 / (1 << 23) as f32 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_weather_leaves_the_medium_alone() {
+        let base = ScatteringMedium::default();
+        let medium = weathered_medium(
+            &base,
+            &Weather::default(),
+            &WeatherConfig::default(),
+            100_000.0,
+        );
+        assert_eq!(medium.terms.len(), base.terms.len());
+        assert_eq!(medium.falloff_resolution, base.falloff_resolution);
+        for (a, b) in medium.terms.iter().zip(&base.terms) {
+            assert_eq!(a.scattering, b.scattering);
+        }
+    }
+
+    #[test]
+    fn fog_and_haze_thicken_the_medium() {
+        let base = ScatteringMedium::default();
+        let mut weather = Weather {
+            haze: 2.0,
+            ..default()
+        };
+        weather.set_fog_visibility_m(500.0);
+        let medium = weathered_medium(&base, &weather, &WeatherConfig::default(), 100_000.0);
+
+        // The Mie term doubles; the Rayleigh term is untouched.
+        assert_eq!(medium.terms[0].scattering, base.terms[0].scattering);
+        assert_eq!(medium.terms[1].scattering, base.terms[1].scattering * 2.0);
+
+        let fog = medium.terms.last().unwrap();
+        assert_eq!(medium.terms.len(), base.terms.len() + 1);
+        assert!((fog.scattering.x * 500.0 - VISIBILITY_CONTRAST).abs() < 1e-4);
+        assert!(medium.falloff_resolution > base.falloff_resolution);
+    }
+
+    #[test]
+    fn precipitation_follows_the_config() {
+        let weather = Weather {
+            precipitation_intensity: 0.5,
+            ..default()
+        };
+        let config = WeatherConfig {
+            max_drops: 100,
+            precipitation_haze: 2.0,
+            fog_asymmetry: 0.5,
+            ..default()
+        };
+        assert_eq!(weather.drop_count(&config), 50);
+        assert_eq!(weather.aerosol_multiplier(&config), 2.0);
+
+        let mut fogged = weather;
+        fogged.set_fog_visibility_m(500.0);
+        let medium = weathered_medium(&ScatteringMedium::default(), &fogged, &config, 100_000.0);
+        let fog = medium.terms.last().unwrap();
+        assert!(matches!(fog.phase, PhaseFunction::Mie { asymmetry } if asymmetry == 0.5));
+    }
+
+    #[test]
+    fn drops_wrap_around_the_box() {
+        assert_eq!(wrap(5.0, 20.0), 5.0);
+        assert_eq!(wrap(21.0, 20.0), -19.0);
+        assert_eq!(wrap(-16.0, 15.0), 14.0);
+    }
+}
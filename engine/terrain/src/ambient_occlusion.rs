@@ -0,0 +1,276 @@
+//! Screen-space ambient occlusion for the terrain.
+//!
+//! Photogrammetry bakes in the light it was captured under, but at street
+//! level the sky and environment lighting still reach into every alley and
+//! under every eave. Bevy's ground-truth SSAO on the world camera brings the
+//! contact shading back: it darkens only the ambient and environment light,
+//! so direct sun and its shadows are left alone.
+//!
+//! Bevy's effect samples a fixed world-space radius of about 0.7 m, which
+//! suits kerbs, walls and doorways; its reach behind surfaces is set by
+//! [`AmbientOcclusionConfig::thickness_m`]. The terrain material then scales
+//! the result by [`strength`](AmbientOcclusionConfig::strength) and fades it
+//! out between [`fade_start_m`](AmbientOcclusionConfig::fade_start_m) and
+//! [`fade_end_m`](AmbientOcclusionConfig::fade_end_m) from the camera, where
+//! the effect would only add noise to tiles a few pixels across. The
+//! atmosphere's aerial perspective composites over the lit terrain
+//! afterwards, so occlusion is hazed out with the terrain it darkens and
+//! never darkens the haze itself.
+//!
+//! SSAO needs the camera's MSAA off, so while it's on, FXAA stands in for
+//! the edge smoothing. It isn't available on WebGL2 or WebGPU, where the
+//! config is ignored.
+
+use bevy::{
+    anti_alias::fxaa::Fxaa,
+    core_pipeline::prepass::{DepthPrepass, NormalPrepass},
+    pbr::{ScreenSpaceAmbientOcclusion, ScreenSpaceAmbientOcclusionQualityLevel},
+    post_process::motion_blur::MotionBlur,
+    prelude::*,
+    reflect::TypePath,
+    render::view::Msaa,
+};
+use serde::{Deserialize, Serialize};
+use veldera_config::ConfigPlugin;
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use crate::{mesh::RocktreeMeshMarker, terrain_material::TerrainMaterial};
+
+/// Plugin that drives the world camera's SSAO from its config.
+///
+/// Defaults to the config at [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+/// in the shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout.
+pub struct AmbientOcclusionPlugin {
+    /// Path to the [`AmbientOcclusionConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl AmbientOcclusionPlugin {
+    /// Canonical [`AmbientOcclusionConfig`] path within the shared engine
+    /// asset subtree.
+    pub const DEFAULT_CONFIG_PATH: &'static str = "engine/config/rendering/ambient_occlusion.toml";
+
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Default for AmbientOcclusionPlugin {
+    /// Load the config from [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONFIG_PATH)
+    }
+}
+
+impl Plugin for AmbientOcclusionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<AmbientOcclusionConfig>::new(
+            self.config_path,
+        ))
+        .add_systems(
+            PostUpdate,
+            (apply_camera_ambient_occlusion, apply_terrain_occlusion),
+        );
+    }
+}
+
+/// SSAO settings, loaded from `ambient_occlusion.toml` and overridden by the
+/// graphics presets.
+#[derive(Asset, Resource, TypePath, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmbientOcclusionConfig {
+    /// Master toggle.
+    pub enabled: bool,
+    /// Sampling quality; higher is less noisy and costs more.
+    pub quality: AmbientOcclusionQuality,
+    /// How far behind a surface a sample must be to count as unoccluded
+    /// (m). Larger makes thin walls and poles occlude more.
+    pub thickness_m: f32,
+    /// How much of the computed occlusion is applied, 0 to 1.
+    pub strength: f32,
+    /// Camera distance where the occlusion starts to fade (m).
+    pub fade_start_m: f32,
+    /// Camera distance beyond which no occlusion is applied (m).
+    pub fade_end_m: f32,
+}
+
+impl Default for AmbientOcclusionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quality: AmbientOcclusionQuality::Medium,
+            thickness_m: 0.5,
+            strength: 0.8,
+            fade_start_m: 300.0,
+            fade_end_m: 1_500.0,
+        }
+    }
+}
+
+impl AmbientOcclusionConfig {
+    /// Strength in `.x` and the fade start and end in `.yz`, for the terrain
+    /// material. Disabled occlusion has zero strength.
+    pub fn uniform(&self) -> Vec4 {
+        let strength = if self.enabled {
+            self.strength.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        Vec4::new(
+            strength,
+            self.fade_start_m.max(0.0),
+            self.fade_end_m.max(self.fade_start_m + 1.0),
+            0.0,
+        )
+    }
+
+    /// The camera component for these settings.
+    fn component(&self) -> ScreenSpaceAmbientOcclusion {
+        ScreenSpaceAmbientOcclusion {
+            quality_level: self.quality.level(),
+            constant_object_thickness: self.thickness_m.max(0.01),
+        }
+    }
+}
+
+/// SSAO sampling quality, Bevy's levels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmbientOcclusionQuality {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Ultra,
+}
+
+impl AmbientOcclusionQuality {
+    /// Every level, from cheapest.
+    pub const ALL: [AmbientOcclusionQuality; 4] =
+        [Self::Low, Self::Medium, Self::High, Self::Ultra];
+
+    /// Short human-readable name.
+    pub fn label(self) -> &'static str {
+        match self {
+            AmbientOcclusionQuality::Low => "Low",
+            AmbientOcclusionQuality::Medium => "Medium",
+            AmbientOcclusionQuality::High => "High",
+            AmbientOcclusionQuality::Ultra => "Ultra",
+        }
+    }
+
+    fn level(self) -> ScreenSpaceAmbientOcclusionQualityLevel {
+        match self {
+            AmbientOcclusionQuality::Low => ScreenSpaceAmbientOcclusionQualityLevel::Low,
+            AmbientOcclusionQuality::Medium => ScreenSpaceAmbientOcclusionQualityLevel::Medium,
+            AmbientOcclusionQuality::High => ScreenSpaceAmbientOcclusionQualityLevel::High,
+            AmbientOcclusionQuality::Ultra => ScreenSpaceAmbientOcclusionQualityLevel::Ultra,
+        }
+    }
+}
+
+/// Add, update or remove SSAO on the world camera to match the config,
+/// swapping MSAA for FXAA while it's on.
+///
+/// The depth prepass is shared with the chase camera's motion blur, so it's
+/// only removed when motion blur isn't using it. It's also restored if motion
+/// blur took it away while SSAO still needs it.
+#[allow(clippy::type_complexity)]
+fn apply_camera_ambient_occlusion(
+    mut commands: Commands,
+    config: Res<AmbientOcclusionConfig>,
+    cameras: Query<
+        (
+            Entity,
+            Option<&ScreenSpaceAmbientOcclusion>,
+            Has<DepthPrepass>,
+            Has<MotionBlur>,
+        ),
+        With<FloatingOriginCamera>,
+    >,
+) {
+    let enabled = config.enabled && cfg!(not(target_family = "wasm"));
+    for (entity, current, has_depth_prepass, has_motion_blur) in &cameras {
+        match (enabled, current) {
+            (true, Some(current)) => {
+                let wanted = config.component();
+                if *current != wanted {
+                    commands.entity(entity).insert(wanted);
+                }
+                if !has_depth_prepass {
+                    commands.entity(entity).insert(DepthPrepass);
+                }
+            }
+            (true, None) => {
+                commands.entity(entity).insert((
+                    config.component(),
+                    DepthPrepass,
+                    NormalPrepass,
+                    Msaa::Off,
+                    Fxaa::default(),
+                ));
+            }
+            (false, Some(_)) => {
+                let mut camera = commands.entity(entity);
+                camera
+                    .remove::<(ScreenSpaceAmbientOcclusion, NormalPrepass, Fxaa)>()
+                    .insert(Msaa::default());
+                if !has_motion_blur {
+                    camera.remove::<DepthPrepass>();
+                }
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+/// Push the occlusion strength and fade into the terrain materials: every
+/// material when the config changes, otherwise only those of newly spawned
+/// meshes.
+fn apply_terrain_occlusion(
+    config: Res<AmbientOcclusionConfig>,
+    mut applied: Local<Option<Vec4>>,
+    meshes: Query<Ref<MeshMaterial3d<TerrainMaterial>>, With<RocktreeMeshMarker>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+) {
+    let uniform = config.uniform();
+    let all = *applied != Some(uniform);
+    *applied = Some(uniform);
+    for material in &meshes {
+        if !all && !material.is_added() {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.extension.ambient_occlusion = uniform;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_occlusion_has_no_strength() {
+        let config = AmbientOcclusionConfig {
+            enabled: false,
+            ..default()
+        };
+        assert_eq!(config.uniform().x, 0.0);
+    }
+
+    #[test]
+    fn fade_range_stays_ordered() {
+        let config = AmbientOcclusionConfig {
+            strength: 3.0,
+            fade_start_m: 500.0,
+            fade_end_m: 100.0,
+            ..default()
+        };
+        let uniform = config.uniform();
+        assert_eq!(uniform.x, 1.0);
+        assert!(uniform.z > uniform.y);
+    }
+}
@@ -0,0 +1,747 @@
+//! Named-area downloads ("download this city") into the tile cache.
+//!
+//! An [`AreaPrefetch`] job walks the octree from the root bulk, following
+//! only the bulks and nodes whose bounds touch a [`PrefetchArea`] (a circle
+//! around a point, or a latitude/longitude box) down to a maximum depth, and
+//! fetches every matching node into the disk cache without decoding it. An
+//! estimate walks the same bulks without fetching any nodes, counting them
+//! instead.
+//!
+//! Downloads are kept by name in `<OS data dir>/veldera/prefetch_areas.json`
+//! until they complete, so an interrupted or paused download can be resumed
+//! later, in this session or the next. Resuming walks the area again, but
+//! nodes already in the cache are skipped without touching the network.
+//!
+//! One job runs at a time, one request at a time, so downloading doesn't
+//! crowd out the LOD system's own requests. Jobs are refused while a session
+//! is attached, as for the startup prefetch. The web build has no persistent
+//! tile cache to download into, and the API is inert there.
+
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use bevy::prelude::*;
+use glam::DVec3;
+use rocktree::{BulkRequest, Client, NodeRequest};
+use rocktree_decode::OrientedBoundingBox;
+use serde::{Deserialize, Serialize};
+
+use veldera_async::TaskSpawner;
+use veldera_constants::EARTH_RADIUS_M_F64;
+use veldera_geo::coords::{ecef_to_lat_lon, lat_lon_to_ecef};
+
+use crate::loader::{LoaderState, TileCache};
+
+/// Deepest octree level a download can reach.
+pub const MAX_PREFETCH_DEPTH: usize = 21;
+
+/// Typical size of a node's data (bytes), for estimates before anything has
+/// been downloaded this session.
+const ESTIMATED_NODE_BYTES: u64 = 48 * 1024;
+
+/// Downloads named areas into the tile cache.
+pub struct AreaPrefetchPlugin;
+
+impl Plugin for AreaPrefetchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AreaPrefetch>()
+            .add_systems(Update, (finish_job, start_job).chain())
+            .add_systems(Last, save_areas);
+    }
+}
+
+/// An area to prefetch. A rectangle with `west > east` crosses the
+/// antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum PrefetchArea {
+    /// Everything within `radius_m` of a point (degrees).
+    Circle { lat: f64, lon: f64, radius_m: f64 },
+    /// A latitude/longitude box (degrees).
+    Rect {
+        south: f64,
+        west: f64,
+        north: f64,
+        east: f64,
+    },
+}
+
+impl PrefetchArea {
+    /// Whether `obb` reaches into the area, measured along the surface at
+    /// the box's distance from the planet's centre.
+    pub fn intersects(&self, obb: &OrientedBoundingBox) -> bool {
+        let Some(area) = self.normalized() else {
+            return false;
+        };
+        let radius = obb.center.length();
+        let reach = obb.extents.length();
+        // The shallowest boxes enclose the planet's centre.
+        if reach >= radius {
+            return true;
+        }
+        let direction = obb.center / radius;
+        match area {
+            Self::Circle { lat, lon, radius_m } => {
+                let centre = lat_lon_to_ecef(lat, lon, 1.0);
+                surface_distance(direction, centre, radius) <= radius_m + reach
+            }
+            Self::Rect {
+                south,
+                west,
+                north,
+                east,
+            } => {
+                let (lat, lon) = ecef_to_lat_lon(obb.center);
+                let nearest =
+                    lat_lon_to_ecef(lat.clamp(south, north), nearest_lon(lon, west, east), 1.0);
+                surface_distance(direction, nearest, radius) <= reach
+            }
+        }
+    }
+
+    /// Rough ground area covered (km²).
+    pub fn area_km2(&self) -> f64 {
+        match *self {
+            Self::Circle { radius_m, .. } => std::f64::consts::PI * (radius_m / 1000.0).powi(2),
+            Self::Rect {
+                south,
+                west,
+                north,
+                east,
+            } => {
+                let r = EARTH_RADIUS_M_F64 / 1000.0;
+                let band = north.to_radians().sin() - south.to_radians().sin();
+                (r * r * lon_span(west, east).to_radians() * band).abs()
+            }
+        }
+    }
+
+    /// The area in canonical form: latitudes ordered and within ±90°,
+    /// longitudes wrapped into [-180°, 180°), and a positive radius. `None`
+    /// if it has non-finite coordinates or covers nothing.
+    pub fn normalized(self) -> Option<Self> {
+        match self {
+            Self::Circle { lat, lon, radius_m } => {
+                (lat.is_finite() && lon.is_finite() && radius_m.is_finite() && radius_m > 0.0).then(
+                    || Self::Circle {
+                        lat: lat.clamp(-90.0, 90.0),
+                        lon: wrap_lon(lon),
+                        radius_m,
+                    },
+                )
+            }
+            Self::Rect {
+                south,
+                west,
+                north,
+                east,
+            } => {
+                if ![south, west, north, east].iter().all(|v| v.is_finite()) {
+                    return None;
+                }
+                let (south, north) = (south.min(north), south.max(north));
+                // A span of a full turn or more covers every longitude.
+                let (west, east) = if (east - west).abs() >= 360.0 {
+                    (-180.0, 180.0)
+                } else {
+                    (wrap_lon(west), wrap_lon(east))
+                };
+                (south < north && west != east).then(|| Self::Rect {
+                    south: south.clamp(-90.0, 90.0),
+                    west,
+                    north: north.clamp(-90.0, 90.0),
+                    east,
+                })
+            }
+        }
+    }
+}
+
+/// `lon` wrapped into [-180°, 180°).
+fn wrap_lon(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Longitudes covered going east from `west` to `east` (degrees), wrapping
+/// across the antimeridian when `west > east`.
+fn lon_span(west: f64, east: f64) -> f64 {
+    if west <= east {
+        east - west
+    } else {
+        east - west + 360.0
+    }
+}
+
+/// The longitude in the span from `west` east to `east` nearest to `lon`.
+fn nearest_lon(lon: f64, west: f64, east: f64) -> f64 {
+    let past_west = (lon - west).rem_euclid(360.0);
+    let span = lon_span(west, east);
+    if past_west <= span {
+        lon
+    } else if past_west - span < 360.0 - past_west {
+        east
+    } else {
+        west
+    }
+}
+
+/// Distance along a sphere of `radius` between two unit directions.
+fn surface_distance(a: DVec3, b: DVec3, radius: f64) -> f64 {
+    a.dot(b).clamp(-1.0, 1.0).acos() * radius
+}
+
+/// Approximate edge length (m) of the nodes at octree `depth`.
+pub fn approximate_node_size_m(depth: usize) -> f64 {
+    2.0 * EARTH_RADIUS_M_F64 / 2f64.powi(depth as i32)
+}
+
+/// A named download.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedArea {
+    pub name: String,
+    pub area: PrefetchArea,
+    /// Deepest octree level fetched.
+    pub max_depth: usize,
+    /// Whether every node was fetched; incomplete downloads can be resumed.
+    pub complete: bool,
+}
+
+/// Progress of a walk, shared with its task.
+#[derive(Debug, Default)]
+pub struct AreaProgress {
+    /// Bulks walked.
+    bulks: AtomicUsize,
+    /// Nodes found in the area.
+    nodes: AtomicUsize,
+    /// Of those, nodes confirmed to be in the tile cache.
+    warmed: AtomicUsize,
+    /// Of those, nodes that had to be fetched from the network.
+    fetched: AtomicUsize,
+    /// Bytes fetched from the network.
+    bytes: AtomicU64,
+    /// Bulks and nodes that failed to fetch.
+    failed: AtomicUsize,
+    /// Set to stop the walk early.
+    cancelled: AtomicBool,
+    /// Whether the walk has finished.
+    done: AtomicBool,
+}
+
+impl AreaProgress {
+    /// Bulks walked.
+    pub fn bulks(&self) -> usize {
+        self.bulks.load(Ordering::Relaxed)
+    }
+
+    /// Nodes found in the area so far.
+    pub fn nodes(&self) -> usize {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Nodes confirmed to be in the tile cache.
+    pub fn warmed(&self) -> usize {
+        self.warmed.load(Ordering::Relaxed)
+    }
+
+    /// Nodes that had to be fetched from the network.
+    pub fn fetched(&self) -> usize {
+        self.fetched.load(Ordering::Relaxed)
+    }
+
+    /// Bytes fetched from the network.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bulks and nodes that failed to fetch.
+    pub fn failed(&self) -> usize {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Whether the walk was stopped early.
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the walk has finished.
+    pub fn done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+/// A walk over an area: an estimate, or a named download.
+#[derive(Debug, Clone)]
+pub struct PrefetchJob {
+    /// The download's name, or `None` for an estimate.
+    pub name: Option<String>,
+    pub area: PrefetchArea,
+    pub max_depth: usize,
+    pub progress: Arc<AreaProgress>,
+}
+
+/// Named downloads and the job in progress, persisted across runs.
+#[derive(Resource, Debug)]
+pub struct AreaPrefetch {
+    areas: Vec<NamedArea>,
+    /// Job to start once the running one (if any) has stopped.
+    requested: Option<PrefetchJob>,
+    /// The running job.
+    job: Option<PrefetchJob>,
+    /// The last finished job, for display.
+    finished: Option<PrefetchJob>,
+    /// Bytes and nodes fetched this session, for estimates.
+    measured: (u64, usize),
+    /// Bumped on every change to `areas`; the save system writes when it
+    /// moves.
+    revision: u64,
+    path: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl Default for AreaPrefetch {
+    fn default() -> Self {
+        let path = areas_path();
+        let (areas, error) = match path.as_deref().map(load_areas) {
+            Some(Ok(saved)) => (saved.areas, None),
+            Some(Err(e)) => {
+                tracing::warn!("Failed to load prefetch areas: {e}");
+                (Vec::new(), Some(format!("Load failed: {e}")))
+            }
+            None => (Vec::new(), None),
+        };
+        Self {
+            areas,
+            requested: None,
+            job: None,
+            finished: None,
+            measured: (0, 0),
+            revision: 0,
+            path,
+            error,
+        }
+    }
+}
+
+impl AreaPrefetch {
+    /// Whether downloads land anywhere lasting. False on the web, where the
+    /// rest of the API is inert.
+    pub fn is_persistent(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// The named downloads, complete or not.
+    pub fn areas(&self) -> &[NamedArea] {
+        &self.areas
+    }
+
+    /// The running (or about to run) job.
+    pub fn job(&self) -> Option<&PrefetchJob> {
+        self.requested.as_ref().or(self.job.as_ref())
+    }
+
+    /// The last finished job.
+    pub fn finished(&self) -> Option<&PrefetchJob> {
+        self.finished.as_ref()
+    }
+
+    /// The last load, save, or start error, for display.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Estimated download size (bytes) of `nodes` nodes: their average size
+    /// so far this session, or a typical size before any download.
+    pub fn estimated_bytes(&self, nodes: usize) -> u64 {
+        let (bytes, fetched) = self.measured;
+        let average = if fetched > 0 {
+            bytes / fetched as u64
+        } else {
+            ESTIMATED_NODE_BYTES
+        };
+        average * nodes as u64
+    }
+
+    /// Count the nodes in `area` down to `max_depth`, without fetching them.
+    /// Stops the running job.
+    pub fn estimate(&mut self, area: PrefetchArea, max_depth: usize) {
+        self.request(None, area, max_depth);
+    }
+
+    /// Download `area` down to `max_depth` as `name`, replacing any download
+    /// of that name. Stops the running job.
+    pub fn download(&mut self, name: &str, area: PrefetchArea, max_depth: usize) {
+        let Some(area) = area.normalized() else {
+            self.error = Some("Start failed: the area is empty".to_string());
+            return;
+        };
+        let named = NamedArea {
+            name: name.to_string(),
+            area,
+            max_depth,
+            complete: false,
+        };
+        match self.areas.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = named,
+            None => self.areas.push(named),
+        }
+        self.revision += 1;
+        self.request(Some(name.to_string()), area, max_depth);
+    }
+
+    /// Resume (or repeat) the download called `name`.
+    pub fn resume(&mut self, name: &str) {
+        if let Some(named) = self.areas.iter().find(|named| named.name == name) {
+            let (area, max_depth) = (named.area, named.max_depth);
+            self.request(Some(name.to_string()), area, max_depth);
+        }
+    }
+
+    /// Stop the running job; a download stays resumable.
+    pub fn pause(&mut self) {
+        self.requested = None;
+        if let Some(job) = &self.job {
+            job.progress.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Forget the download called `name`, stopping it if it's running. Its
+    /// tiles stay in the cache.
+    pub fn remove(&mut self, name: &str) {
+        if self
+            .job()
+            .is_some_and(|job| job.name.as_deref() == Some(name))
+        {
+            self.pause();
+        }
+        self.areas.retain(|named| named.name != name);
+        self.revision += 1;
+    }
+
+    fn request(&mut self, name: Option<String>, area: PrefetchArea, max_depth: usize) {
+        self.pause();
+        let Some(area) = area.normalized() else {
+            self.error = Some("Start failed: the area is empty".to_string());
+            return;
+        };
+        self.requested = Some(PrefetchJob {
+            name,
+            area,
+            max_depth: max_depth.min(MAX_PREFETCH_DEPTH),
+            progress: Arc::default(),
+        });
+    }
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+/// On-disk form of the downloads.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedAreas {
+    areas: Vec<NamedArea>,
+}
+
+/// `<OS data dir>/veldera/prefetch_areas.json`.
+#[cfg(not(target_family = "wasm"))]
+fn areas_path() -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join("veldera")
+            .join("prefetch_areas.json"),
+    )
+}
+
+/// The web build has no persistent tile cache.
+#[cfg(target_family = "wasm")]
+fn areas_path() -> Option<PathBuf> {
+    None
+}
+
+/// Read the downloads at `path`; a missing file is no downloads.
+fn load_areas(path: &Path) -> Result<SavedAreas, String> {
+    let mut saved: SavedAreas = match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| e.to_string())?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => SavedAreas::default(),
+        Err(e) => return Err(e.to_string()),
+    };
+    saved
+        .areas
+        .retain_mut(|named| match named.area.normalized() {
+            Some(area) => {
+                named.area = area;
+                true
+            }
+            None => {
+                tracing::warn!("Dropping prefetch area {:?}: {:?}", named.name, named.area);
+                false
+            }
+        });
+    Ok(saved)
+}
+
+/// Write the downloads to disk whenever they change.
+fn save_areas(mut prefetch: ResMut<AreaPrefetch>, mut saved_revision: Local<u64>) {
+    if prefetch.revision == *saved_revision {
+        return;
+    }
+    *saved_revision = prefetch.revision;
+    let Some(path) = prefetch.path.clone() else {
+        return;
+    };
+    let saved = SavedAreas {
+        areas: prefetch.areas.clone(),
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|()| serde_json::to_string(&saved).map_err(|e| e.to_string()))
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    prefetch.error = result
+        .inspect_err(|e| tracing::warn!("Failed to save prefetch areas: {e}"))
+        .err()
+        .map(|e| format!("Save failed: {e}"));
+}
+
+// ============================================================================
+// Jobs
+// ============================================================================
+
+/// Start the requested job once the running one has stopped and the
+/// planetoid has loaded.
+fn start_job(mut prefetch: ResMut<AreaPrefetch>, loader: Res<LoaderState>, spawner: TaskSpawner) {
+    if prefetch.job.is_some() || prefetch.requested.is_none() || !prefetch.is_persistent() {
+        return;
+    }
+    let Some(planetoid) = &loader.planetoid else {
+        return;
+    };
+    let job = prefetch.requested.take().expect("checked above");
+    if loader.client.has_session() {
+        prefetch.error = Some("Prefetching is off while a session is attached".to_string());
+        return;
+    }
+    match &job.name {
+        Some(name) => tracing::info!("Downloading area '{name}'"),
+        None => tracing::info!("Estimating area download"),
+    }
+
+    let client = Arc::clone(&loader.client);
+    let root = BulkRequest::root(planetoid.root_epoch);
+    let (area, max_depth, download) = (job.area, job.max_depth, job.name.is_some());
+    let progress = Arc::clone(&job.progress);
+    spawner.spawn(async move {
+        walk_area(&client, root, area, max_depth, download, &progress).await;
+        progress.done.store(true, Ordering::Relaxed);
+    });
+    prefetch.error = None;
+    prefetch.job = Some(job);
+}
+
+/// Retire the running job once its walk has finished, marking a download
+/// that fetched everything complete.
+fn finish_job(mut prefetch: ResMut<AreaPrefetch>) {
+    let prefetch = &mut *prefetch;
+    if !prefetch.job.as_ref().is_some_and(|job| job.progress.done()) {
+        return;
+    }
+    let job = prefetch.job.take().expect("checked above");
+    let progress = &job.progress;
+    prefetch.measured.0 += progress.bytes();
+    prefetch.measured.1 += progress.fetched();
+    if let Some(name) = &job.name {
+        let complete = !progress.cancelled() && progress.failed() == 0;
+        tracing::info!(
+            "Area '{name}' {}: {} nodes, {} fetched ({:.1} MiB), {} failed",
+            if complete { "downloaded" } else { "stopped" },
+            progress.warmed(),
+            progress.fetched(),
+            progress.bytes() as f64 / (1024.0 * 1024.0),
+            progress.failed(),
+        );
+        if let Some(named) = prefetch.areas.iter_mut().find(|named| &named.name == name)
+            && named.complete != complete
+        {
+            named.complete = complete;
+            prefetch.revision += 1;
+        }
+    }
+    prefetch.finished = Some(job);
+}
+
+/// Walk the bulks touching `area` breadth-first from `root`, counting (and,
+/// when `download`, fetching) their nodes down to `max_depth`.
+async fn walk_area(
+    client: &Client<TileCache>,
+    root: BulkRequest,
+    area: PrefetchArea,
+    max_depth: usize,
+    download: bool,
+    progress: &AreaProgress,
+) {
+    let mut queue = VecDeque::from([root]);
+    while let Some(request) = queue.pop_front() {
+        if progress.cancelled() {
+            return;
+        }
+        let bulk = match client.fetch_bulk(&request).await {
+            Ok(bulk) => bulk,
+            Err(e) => {
+                tracing::debug!("Area prefetch: bulk '{}' failed: {e}", request.path);
+                progress.failed.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+        progress.bulks.fetch_add(1, Ordering::Relaxed);
+
+        for node in &bulk.nodes {
+            if !node.has_data || node.path.depth() > max_depth || !area.intersects(&node.obb) {
+                continue;
+            }
+            progress.nodes.fetch_add(1, Ordering::Relaxed);
+            if !download {
+                continue;
+            }
+            if progress.cancelled() {
+                return;
+            }
+            let request = NodeRequest::new(
+                node.path,
+                node.epoch,
+                node.texture_format,
+                node.imagery_epoch,
+            );
+            match client.warm_cache(&client.node_url(&request)).await {
+                Ok(fetched) => {
+                    progress.warmed.fetch_add(1, Ordering::Relaxed);
+                    if let Some(bytes) = fetched {
+                        progress.fetched.fetch_add(1, Ordering::Relaxed);
+                        progress.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+                    }
+                }
+                Err(e) => {
+                    tracing::debug!("Area prefetch: node '{}' failed: {e}", node.path);
+                    progress.failed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        for (&relative, &epoch) in &bulk.child_bulk_paths {
+            let path = bulk.path.extend(relative);
+            // A child bulk holds nodes below its own path's depth.
+            if path.depth() >= max_depth {
+                continue;
+            }
+            // The child bulk's head node lives in this bulk; its bounds
+            // cover the whole child bulk.
+            let inside = bulk
+                .nodes
+                .iter()
+                .find(|node| node.path == path)
+                .is_none_or(|node| area.intersects(&node.obb));
+            if inside {
+                queue.push_back(BulkRequest::new(path, epoch));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::DMat3;
+
+    use super::*;
+
+    /// A 100 m box on the surface at `(lat, lon)`.
+    fn obb_at(lat: f64, lon: f64) -> OrientedBoundingBox {
+        OrientedBoundingBox {
+            center: lat_lon_to_ecef(lat, lon, EARTH_RADIUS_M_F64),
+            extents: DVec3::splat(50.0),
+            orientation: DMat3::IDENTITY,
+        }
+    }
+
+    #[test]
+    fn circle_reaches_nearby_boxes_only() {
+        let area = PrefetchArea::Circle {
+            lat: 48.0,
+            lon: 11.0,
+            radius_m: 2000.0,
+        };
+        assert!(area.intersects(&obb_at(48.0, 11.0)));
+        // ~1.1 km north.
+        assert!(area.intersects(&obb_at(48.01, 11.0)));
+        // ~11 km north.
+        assert!(!area.intersects(&obb_at(48.1, 11.0)));
+    }
+
+    #[test]
+    fn rect_reaches_boxes_overlapping_its_edge() {
+        let area = PrefetchArea::Rect {
+            south: 48.0,
+            west: 11.0,
+            north: 48.1,
+            east: 11.2,
+        };
+        assert!(area.intersects(&obb_at(48.05, 11.1)));
+        // Centre ~30 m outside the south edge, but the box reaches over it.
+        assert!(area.intersects(&obb_at(47.9997, 11.1)));
+        assert!(!area.intersects(&obb_at(47.9, 11.1)));
+        assert!(!area.intersects(&obb_at(48.05, 11.5)));
+    }
+
+    #[test]
+    fn rect_wraps_across_the_antimeridian() {
+        let area = PrefetchArea::Rect {
+            south: -17.0,
+            west: 179.9,
+            north: -16.9,
+            east: -179.9,
+        };
+        assert!(area.intersects(&obb_at(-16.95, 179.95)));
+        assert!(area.intersects(&obb_at(-16.95, -179.95)));
+        assert!(!area.intersects(&obb_at(-16.95, 0.0)));
+        assert!(!area.intersects(&obb_at(-16.95, 179.5)));
+        // A 0.2° × 0.1° box, not a band around the planet.
+        assert!(area.area_km2() < 300.0);
+    }
+
+    #[test]
+    fn normalizing_orders_and_wraps_rects() {
+        let area = PrefetchArea::Rect {
+            south: 48.1,
+            west: 191.0,
+            north: 48.0,
+            east: 11.2,
+        };
+        let Some(PrefetchArea::Rect {
+            south,
+            west,
+            north,
+            east,
+        }) = area.normalized()
+        else {
+            panic!("rect dropped");
+        };
+        assert_eq!((south, north, east), (48.0, 48.1, 11.2));
+        assert!((west + 169.0).abs() < 1e-9);
+        assert_eq!(
+            PrefetchArea::Rect {
+                south: 48.0,
+                west: 11.0,
+                north: 48.0,
+                east: 11.2,
+            }
+            .normalized(),
+            None
+        );
+    }
+}
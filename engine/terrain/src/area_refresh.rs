@@ -0,0 +1,333 @@
+//! Tile ages for the current view, and a forced refresh of it.
+//!
+//! Every [`AGES_REFRESH_SECS`] the [`AreaRefresh`] resource summarises the
+//! loaded tiles inside the view frustum: when their data arrived and which
+//! data and imagery epochs they carry, so a stale area is easy to spot.
+//!
+//! [`AreaRefresh::request`] refreshes that area. The tiles' node data, and
+//! the bulk metadata they were found through, are dropped from the tile
+//! cache and their decoded textures from the texture cache. Once the drops
+//! are done the tiles are refetched from the network a few per frame
+//! (see [`EpochRefreshTuning`](crate::epoch_refresh::EpochRefreshTuning)),
+//! each staying on screen until its replacement lands. The planetoid epoch
+//! is checked at the same time, so newly published data is picked up through
+//! [`crate::epoch_refresh`] as well. The dropped bulks are fetched afresh
+//! the next time they're wanted. Replayed sessions never refresh.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use rocktree::{BulkRequest, NodeRequest};
+use rocktree_decode::OctreePath;
+
+use veldera_async::TaskSpawner;
+
+use crate::{
+    epoch_refresh::EpochRefresh,
+    loader::LoaderState,
+    lod::{LodChannels, LodState, LodTuning, poll_lod_node_tasks, refetch_loaded_node},
+    qos::LoadQos,
+};
+
+/// Time between rebuilds of the tile ages (s).
+pub const AGES_REFRESH_SECS: f64 = 1.0;
+
+/// Plugin for the tile ages and area refreshes.
+pub struct AreaRefreshPlugin;
+
+impl Plugin for AreaRefreshPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AreaRefresh>()
+            .init_resource::<AreaRefreshChannel>()
+            .add_systems(
+                Update,
+                (update_tile_ages, start_area_refresh, advance_area_refresh)
+                    .chain()
+                    .after(poll_lod_node_tasks),
+            );
+    }
+}
+
+/// When the tiles in view arrived, and which epochs they carry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileAges {
+    /// Loaded tiles in view.
+    pub tiles: usize,
+    /// Real time the longest-loaded tile arrived (s).
+    pub oldest_loaded_at: Option<f64>,
+    /// Real time the most recently loaded tile arrived (s).
+    pub newest_loaded_at: Option<f64>,
+    /// Tiles per data epoch.
+    pub epochs: BTreeMap<u32, usize>,
+    /// Tiles per imagery epoch; `None` counts tiles requested without one.
+    pub imagery_epochs: BTreeMap<Option<u32>, usize>,
+}
+
+impl TileAges {
+    fn add(&mut self, loaded_at: f64, epoch: u32, imagery_epoch: Option<u32>) {
+        self.tiles += 1;
+        self.oldest_loaded_at = Some(
+            self.oldest_loaded_at
+                .map_or(loaded_at, |t| t.min(loaded_at)),
+        );
+        self.newest_loaded_at = Some(
+            self.newest_loaded_at
+                .map_or(loaded_at, |t| t.max(loaded_at)),
+        );
+        *self.epochs.entry(epoch).or_default() += 1;
+        *self.imagery_epochs.entry(imagery_epoch).or_default() += 1;
+    }
+}
+
+/// Tile ages of the view, and the progress of an area refresh.
+#[derive(Resource, Default)]
+pub struct AreaRefresh {
+    ages: TileAges,
+    /// Real time of the last ages rebuild (s).
+    ages_updated_at: Option<f64>,
+    /// Refresh the view at the next opportunity.
+    requested: bool,
+    /// Whether cache entries are being dropped.
+    evicting: bool,
+    /// Loaded nodes waiting to be refetched.
+    pending_nodes: VecDeque<OctreePath>,
+    /// Real time the last refresh started (s).
+    last_started: Option<f64>,
+    nodes_refetched: usize,
+}
+
+impl AreaRefresh {
+    /// Refresh the tiles in view.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// The latest tile ages of the view.
+    #[must_use]
+    pub fn ages(&self) -> &TileAges {
+        &self.ages
+    }
+
+    /// Whether a refresh is requested or under way.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.requested || self.evicting || !self.pending_nodes.is_empty()
+    }
+
+    /// Nodes still to be refetched.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.pending_nodes.len()
+    }
+
+    /// Nodes refetched by the last refresh so far.
+    #[must_use]
+    pub fn nodes_refetched(&self) -> usize {
+        self.nodes_refetched
+    }
+
+    /// Real time the last refresh started (s).
+    #[must_use]
+    pub fn last_started(&self) -> Option<f64> {
+        self.last_started
+    }
+}
+
+/// Hands the refreshed nodes back once their cache entries are gone.
+#[derive(Resource)]
+struct AreaRefreshChannel {
+    tx: async_channel::Sender<Vec<OctreePath>>,
+    rx: async_channel::Receiver<Vec<OctreePath>>,
+}
+
+impl Default for AreaRefreshChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::bounded(1);
+        Self { tx, rx }
+    }
+}
+
+/// Loaded nodes with data whose bounds reach into the view frustum.
+fn loaded_nodes_in_view(lod_state: &LodState) -> Vec<OctreePath> {
+    let Some(frustum) = lod_state.frustum else {
+        return Vec::new();
+    };
+    lod_state
+        .loaded_nodes
+        .iter()
+        .filter(|path| lod_state.node_data.contains_key(*path))
+        .filter(|path| {
+            lod_state
+                .node_obbs
+                .get(*path)
+                .is_some_and(|obb| frustum.intersects_obb(obb))
+        })
+        .copied()
+        .collect()
+}
+
+/// Rebuild the tile ages every [`AGES_REFRESH_SECS`].
+fn update_tile_ages(
+    mut refresh: ResMut<AreaRefresh>,
+    lod_state: Res<LodState>,
+    real_time: Res<Time<Real>>,
+) {
+    let now = real_time.elapsed_secs_f64();
+    if refresh
+        .ages_updated_at
+        .is_some_and(|at| now - at < AGES_REFRESH_SECS)
+    {
+        return;
+    }
+    let mut ages = TileAges::default();
+    for path in loaded_nodes_in_view(&lod_state) {
+        if let (Some(data), Some(metadata)) = (
+            lod_state.node_data.get(&path),
+            lod_state.node_metadata(path),
+        ) {
+            ages.add(data.loaded_at, metadata.epoch, metadata.imagery_epoch);
+        }
+    }
+    refresh.ages = ages;
+    refresh.ages_updated_at = Some(now);
+}
+
+/// Drop the view's cache entries when a refresh is requested, and check the
+/// planetoid epoch.
+fn start_area_refresh(
+    mut refresh: ResMut<AreaRefresh>,
+    mut epoch_refresh: ResMut<EpochRefresh>,
+    lod_state: Res<LodState>,
+    loader: Res<LoaderState>,
+    channel: Res<AreaRefreshChannel>,
+    real_time: Res<Time<Real>>,
+    spawner: TaskSpawner,
+) {
+    if !refresh.requested || refresh.evicting {
+        return;
+    }
+    refresh.requested = false;
+    if loader.client.has_session() {
+        tracing::info!("Area refresh: skipped while replaying a session");
+        return;
+    }
+
+    let paths = loaded_nodes_in_view(&lod_state);
+    let mut urls = Vec::with_capacity(paths.len());
+    let mut bulks = HashSet::new();
+    for &path in &paths {
+        let Some(metadata) = lod_state.node_metadata(path) else {
+            continue;
+        };
+        urls.push(loader.client.node_url(&NodeRequest::new(
+            path,
+            metadata.epoch,
+            metadata.texture_format,
+            metadata.imagery_epoch,
+        )));
+        if let Some(textures) = loader.client.texture_cache() {
+            textures.remove_node(path);
+        }
+        // The bulk the node was found through; the root bulk stays, as the
+        // planetoid names its epoch.
+        let bulk_key = path.truncated((path.depth() - 1) / 4 * 4);
+        if !bulk_key.is_root() {
+            bulks.insert(bulk_key);
+        }
+    }
+    for bulk_key in bulks {
+        if let Some(bulk) = lod_state.bulks.get(&bulk_key) {
+            urls.push(
+                loader
+                    .client
+                    .bulk_url(&BulkRequest::new(bulk_key, bulk.epoch)),
+            );
+        }
+    }
+
+    tracing::info!(
+        "Area refresh: dropping {} cache entries for {} tiles in view",
+        urls.len(),
+        paths.len()
+    );
+    refresh.evicting = true;
+    refresh.pending_nodes.clear();
+    refresh.nodes_refetched = 0;
+    refresh.last_started = Some(real_time.elapsed_secs_f64());
+    epoch_refresh.request_check();
+
+    let client = loader.client.clone();
+    let tx = channel.tx.clone();
+    spawner.spawn(async move {
+        for url in urls {
+            if let Err(e) = client.evict(&url).await {
+                tracing::warn!("Area refresh: failed to drop '{url}' from the tile cache: {e}");
+            }
+        }
+        let _ = tx.send(paths).await;
+    });
+}
+
+/// Queue the nodes once their cache entries are gone, and refetch a few per
+/// frame.
+#[allow(clippy::too_many_arguments)]
+fn advance_area_refresh(
+    mut refresh: ResMut<AreaRefresh>,
+    mut lod_state: ResMut<LodState>,
+    mut qos: ResMut<LoadQos>,
+    channel: Res<AreaRefreshChannel>,
+    lod_channels: Res<LodChannels>,
+    loader: Res<LoaderState>,
+    tuning: Res<LodTuning>,
+    real_time: Res<Time<Real>>,
+    spawner: TaskSpawner,
+) {
+    if let Ok(paths) = channel.rx.try_recv() {
+        refresh.evicting = false;
+        refresh.pending_nodes.extend(paths);
+    }
+
+    let now = real_time.elapsed_secs_f64();
+    for _ in 0..tuning.epoch_refresh.max_node_refetches_per_frame {
+        let Some(path) = refresh.pending_nodes.pop_front() else {
+            break;
+        };
+        // Nodes that unloaded meanwhile come back from the network anyway.
+        if refetch_loaded_node(
+            &mut lod_state,
+            &mut qos,
+            &lod_channels,
+            &loader,
+            &spawner,
+            &tuning,
+            path,
+            now,
+        ) {
+            refresh.nodes_refetched += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ages_track_the_load_time_range_and_epochs() {
+        let mut ages = TileAges::default();
+        ages.add(12.0, 1000, Some(7));
+        ages.add(3.0, 1000, None);
+        ages.add(40.0, 1002, Some(7));
+
+        assert_eq!(ages.tiles, 3);
+        assert_eq!(
+            (ages.oldest_loaded_at, ages.newest_loaded_at),
+            (Some(3.0), Some(40.0))
+        );
+        assert_eq!(ages.epochs, BTreeMap::from([(1000, 2), (1002, 1)]));
+        assert_eq!(
+            ages.imagery_epochs,
+            BTreeMap::from([(None, 1), (Some(7), 2)])
+        );
+    }
+}
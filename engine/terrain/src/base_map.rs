@@ -0,0 +1,241 @@
+//! Equirectangular base maps painted from decoded nodes.
+//!
+//! A [`BaseMap`] is a whole-planet image in equirectangular projection over
+//! geodetic latitude and longitude. Each node painted into it has its meshes
+//! rasterized triangle by triangle at their true positions, sampling their
+//! own textures, so painting nodes shallowest first leaves the finest imagery
+//! on top. Triangles straddling the antimeridian are unwrapped onto one side,
+//! and the few smeared across the map by a pole are skipped.
+//!
+//! The proxy globe paints one at runtime when no baked base map is shipped,
+//! and the `bake-base-map` tool paints the shipped one offline from deeper
+//! levels.
+
+use glam::{DMat4, DVec3};
+use rocktree::{Mesh as RocktreeMesh, Node, TextureFormat};
+use rocktree_decode::{strip_to_triangles, texture::decode_bc1_block};
+use veldera_geo::coords::ecef_to_geodetic;
+
+/// An sRGB RGBA8 image in equirectangular projection: column 0 at 180° W,
+/// row 0 at the North Pole, twice as wide as it is high.
+pub struct BaseMap {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl BaseMap {
+    /// Widths outside this range are clamped.
+    pub const WIDTH_RANGE: (u32, u32) = (64, 16_384);
+
+    /// A map `width` texels wide, filled with the sRGB `color`.
+    pub fn filled(width: u32, color: [u8; 3]) -> Self {
+        let width = width.clamp(Self::WIDTH_RANGE.0, Self::WIDTH_RANGE.1) & !1;
+        let height = width / 2;
+        let [r, g, b] = color;
+        Self {
+            width,
+            height,
+            pixels: [r, g, b, 255].repeat((width * height) as usize),
+        }
+    }
+
+    /// Width in texels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in texels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA8 texels, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The RGBA8 texels, row by row.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Paint every mesh of `node`.
+    pub fn paint_node(&mut self, node: &Node) {
+        for mesh in &node.meshes {
+            self.paint_mesh(mesh, &node.matrix_globe_from_mesh);
+        }
+    }
+
+    /// Paint `mesh`'s texture, placed by `globe_from_mesh`.
+    pub fn paint_mesh(&mut self, mesh: &RocktreeMesh, globe_from_mesh: &DMat4) {
+        let Some(texture) = DecodedTexture::new(mesh) else {
+            return;
+        };
+        let width = f64::from(self.width);
+        let corners: Vec<Corner> = mesh
+            .vertices
+            .iter()
+            .map(|v| {
+                let local = DVec3::new(f64::from(v.x), f64::from(v.y), f64::from(v.z));
+                let uv = (
+                    (f32::from(v.u()) + mesh.uv_transform.offset.x) * mesh.uv_transform.scale.x,
+                    (f32::from(v.v()) + mesh.uv_transform.offset.y) * mesh.uv_transform.scale.y,
+                );
+                (self.project(globe_from_mesh.transform_point3(local)), uv)
+            })
+            .collect();
+
+        for triangle in strip_to_triangles(&mesh.indices).chunks_exact(3) {
+            let mut corner = [0, 1, 2].map(|i| corners[triangle[i] as usize]);
+            // Unwrap triangles straddling the antimeridian onto one side.
+            let xs = corner.map(|((x, _), _)| x);
+            if xs.iter().copied().fold(f64::MIN, f64::max)
+                - xs.iter().copied().fold(f64::MAX, f64::min)
+                > width / 2.0
+            {
+                for ((x, _), _) in &mut corner {
+                    if *x < width / 2.0 {
+                        *x += width;
+                    }
+                }
+            }
+            self.fill_triangle(corner, &texture);
+        }
+    }
+
+    /// Texel coordinates (continuous) of an ECEF position.
+    fn project(&self, position: DVec3) -> (f64, f64) {
+        let (lat, lon, _) = ecef_to_geodetic(position);
+        (
+            (lon + 180.0) / 360.0 * f64::from(self.width),
+            (90.0 - lat) / 180.0 * f64::from(self.height),
+        )
+    }
+
+    /// Fill the texels whose centres fall inside a triangle.
+    fn fill_triangle(&mut self, corner: [Corner; 3], texture: &DecodedTexture) {
+        let [((x0, y0), uv0), ((x1, y1), uv1), ((x2, y2), uv2)] = corner;
+        let area = (x1 - x0) * (y2 - y0) - (x2 - x0) * (y1 - y0);
+        let span = x0.max(x1).max(x2) - x0.min(x1).min(x2);
+        // Degenerate, or smeared across the map by a pole.
+        if area.abs() < 1e-12 || span > f64::from(self.width) / 4.0 {
+            return;
+        }
+        let min_x = x0.min(x1).min(x2).floor() as i64;
+        let max_x = x0.max(x1).max(x2).ceil() as i64;
+        let min_y = (y0.min(y1).min(y2).floor() as i64).max(0);
+        let max_y = (y0.max(y1).max(y2).ceil() as i64).min(i64::from(self.height) - 1);
+        for py in min_y..=max_y {
+            for px in min_x..=max_x {
+                let (cx, cy) = (px as f64 + 0.5, py as f64 + 0.5);
+                let w1 = ((cx - x0) * (y2 - y0) - (x2 - x0) * (cy - y0)) / area;
+                let w2 = ((x1 - x0) * (cy - y0) - (cx - x0) * (y1 - y0)) / area;
+                let w0 = 1.0 - w1 - w2;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+                let (w0, w1, w2) = (w0 as f32, w1 as f32, w2 as f32);
+                let u = uv0.0 * w0 + uv1.0 * w1 + uv2.0 * w2;
+                let v = uv0.1 * w0 + uv1.1 * w1 + uv2.1 * w2;
+                let column = px.rem_euclid(i64::from(self.width)) as usize;
+                let start = (py as usize * self.width as usize + column) * 4;
+                self.pixels[start..start + 3].copy_from_slice(&texture.sample(u, v));
+                self.pixels[start + 3] = 255;
+            }
+        }
+    }
+}
+
+/// A triangle corner: texel coordinates in the map and texture coordinates
+/// in the node's texture.
+type Corner = ((f64, f64), (f32, f32));
+
+/// A node texture decoded to sRGB bytes, for nearest-texel sampling.
+struct DecodedTexture {
+    width: usize,
+    height: usize,
+    texels: Vec<[u8; 3]>,
+}
+
+impl DecodedTexture {
+    /// Decode `mesh`'s texture, or `None` if it's empty or truncated.
+    fn new(mesh: &RocktreeMesh) -> Option<Self> {
+        let (width, height) = (mesh.texture_width as usize, mesh.texture_height as usize);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let data = &mesh.texture_data;
+        let mut texels = vec![[0; 3]; width * height];
+        match mesh.texture_format {
+            TextureFormat::Rgb | TextureFormat::Rgba => {
+                let stride = if mesh.texture_format == TextureFormat::Rgb {
+                    3
+                } else {
+                    4
+                };
+                for (i, texel) in texels.iter_mut().enumerate() {
+                    let bytes = data.get(i * stride..i * stride + 3)?;
+                    *texel = [bytes[0], bytes[1], bytes[2]];
+                }
+            }
+            TextureFormat::Dxt1 => {
+                let columns = width.div_ceil(4);
+                for row in 0..height.div_ceil(4) {
+                    for column in 0..columns {
+                        let start = (row * columns + column) * 8;
+                        let block = data.get(start..start + 8)?.try_into().ok()?;
+                        for (i, [r, g, b, _]) in decode_bc1_block(block).into_iter().enumerate() {
+                            let (x, y) = (column * 4 + i % 4, row * 4 + i / 4);
+                            if x < width && y < height {
+                                texels[y * width + x] = [r, g, b];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(Self {
+            width,
+            height,
+            texels,
+        })
+    }
+
+    fn sample(&self, u: f32, v: f32) -> [u8; 3] {
+        let x = ((u.clamp(0.0, 1.0) * self.width as f32) as usize).min(self.width - 1);
+        let y = ((v.clamp(0.0, 1.0) * self.height as f32) as usize).min(self.height - 1);
+        self.texels[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veldera_geo::coords::geodetic_to_ecef;
+
+    #[test]
+    fn triangles_paint_their_texels() {
+        let mut map = BaseMap::filled(64, [0; 3]);
+        let texture = DecodedTexture {
+            width: 1,
+            height: 1,
+            texels: vec![[10, 20, 30]],
+        };
+        let corner = |x, y| ((x, y), (0.5, 0.5));
+        map.fill_triangle(
+            [corner(4.0, 4.0), corner(12.0, 4.0), corner(4.0, 12.0)],
+            &texture,
+        );
+        let texel = |x: usize, y: usize| &map.pixels[(y * 64 + x) * 4..(y * 64 + x) * 4 + 3];
+        assert_eq!(texel(5, 5), [10, 20, 30]);
+        assert_eq!(texel(11, 11), [0, 0, 0]);
+    }
+
+    #[test]
+    fn projection_puts_the_prime_meridian_mid_map() {
+        let map = BaseMap::filled(64, [0; 3]);
+        let (x, y) = map.project(geodetic_to_ecef(0.0, 0.0, 0.0));
+        assert!((x - 32.0).abs() < 1e-6 && (y - 16.0).abs() < 1e-6);
+    }
+}
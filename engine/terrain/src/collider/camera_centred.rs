@@ -16,27 +16,36 @@
 //! voxel wrap builds per tile) into one soup and extracting the surface from it.
 //! The new collider replaces the old in one frame (double buffer), so there is
 //! never a frame without coverage.
+//!
+//! A second, coarse tier backs it out to
+//! [`PhysicsStreamingConfig::coarse_range`]: the loaded tiles collided at a
+//! fixed coarse depth, heavily simplified and used as-is
+//! ([`veldera_physics::terrain_v4::create_coarse_collider`]), with a hole left
+//! around its centre for the full-detail collider. It re-tiers on its own,
+//! longer rebuild cadence. [`ColliderTierStats`] reports both tiers' budgets
+//! for the diagnostics UI.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use glam::{DVec3, Quat, Vec3};
 use rocktree::Mesh as RocktreeMesh;
+use rocktree_decode::OctreePath;
 
 use veldera_async::TaskSpawner;
 use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
 use veldera_physics::{
-    DebugRender, GameLayer, PhysicsState, PhysicsStreamingConfig,
+    DebugRender, GameLayer, PHYSICS_FINEST_DEPTH, PhysicsState, PhysicsStreamingConfig,
     terrain_v4::{
-        HeightfieldSettings, Octree3dSettings, OctreeColliderSettings, TileMeshes,
-        create_height_collider, create_octree_collider,
+        CoarseColliderSettings, HeightfieldSettings, Octree3dSettings, OctreeColliderSettings,
+        TileMeshes, create_coarse_collider, create_height_collider, create_octree_collider,
     },
 };
 
 use crate::{
     collider::{COLLIDER, ColliderAlgorithm},
-    lod::{ColliderReconcile, LodState, poll_lod_node_tasks},
+    lod::{ColliderReconcile, LodState, effective_distance, poll_lod_node_tasks},
 };
 
 /// Settings for the single camera-centred 2.5D drivable-height surface: a quadtree
@@ -97,6 +106,9 @@ const MAX_RADIUS: f32 = HEIGHTFIELD.radius;
 /// enabled.
 const COLLIDER_COLOUR: Color = Color::srgb(0.4, 0.9, 0.45);
 
+/// Debug-wireframe colour of the coarse tier.
+const COARSE_COLLIDER_COLOUR: Color = Color::srgb(0.3, 0.55, 0.9);
+
 /// Rebuild once the camera has moved this far (m) from the build centre. Tied to
 /// the fine near-field scale, *not* the full reach: the fine cells are the precise
 /// surface the player actually stands on, so the whole collider must re-centre on
@@ -110,6 +122,7 @@ const REBUILD_DISTANCE: f32 = 18.0;
 /// the shared per-tile wireframe overlay is not registered here.
 pub(crate) fn register(app: &mut App) {
     app.init_resource::<ColliderV4State>()
+        .init_resource::<ColliderTierStats>()
         .init_resource::<ColliderV4BuildChannel>()
         .add_systems(
             Update,
@@ -126,23 +139,83 @@ pub(crate) fn register(app: &mut App) {
     app.add_systems(Update, crate::collider::shared::process_tile_dump_requests);
 }
 
-/// v4 reconcile state: the single live collider's entity, the world centre it was
-/// built at, and whether a rebuild is in flight.
+/// v4 reconcile state: the full-detail collider and the coarse tier behind it.
 #[derive(Resource, Default)]
 struct ColliderV4State {
+    fine: ColliderTierState,
+    coarse: ColliderTierState,
+}
+
+/// One tier's live collider entity, the world centre it was built at, and
+/// whether a rebuild is in flight.
+#[derive(Default)]
+struct ColliderTierState {
     entity: Option<Entity>,
     centre: Option<DVec3>,
     building: bool,
 }
 
+impl ColliderTierState {
+    /// How far the camera has moved from the build centre, if that is past
+    /// `threshold` (or nothing is built yet) and no rebuild is in flight.
+    fn rebuild_due(&self, camera_pos: DVec3, threshold: f64) -> Option<f64> {
+        if self.building {
+            return None;
+        }
+        let moved = self
+            .centre
+            .map_or(f64::INFINITY, |c| (camera_pos - c).length());
+        (moved > threshold).then_some(moved)
+    }
+}
+
+/// Which collider tier a build belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColliderTier {
+    /// The full-detail camera-centred surface.
+    Fine,
+    /// The simplified far-field tier.
+    Coarse,
+}
+
+/// Collider budget metrics per tier, for the diagnostics UI. Only present when a
+/// camera-centred algorithm is live.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ColliderTierStats {
+    /// The full-detail collider.
+    pub fine: TierStats,
+    /// The coarse far-field tier.
+    pub coarse: TierStats,
+}
+
+/// Budget metrics for one collider tier.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TierStats {
+    /// How far (m) the tier reaches from its build centre.
+    pub reach_m: f64,
+    /// Tiles gathered into the live collider.
+    pub tiles: usize,
+    /// Triangles in the live collider.
+    pub triangles: usize,
+    /// Builds committed since startup.
+    pub builds: u64,
+    /// Whether a rebuild is in flight.
+    pub building: bool,
+    /// Distance (m) from the camera to the live collider's build centre.
+    pub drift_m: f64,
+}
+
 /// Tags the v4 collider entity, so it is identifiable in the world (for
 /// inspection and any future teardown). The live entity is tracked in
 /// [`ColliderV4State`]; this is a marker, not the source of truth.
 #[derive(Component)]
-struct ColliderV4;
+pub(crate) struct ColliderV4;
 
 /// A finished off-thread build, awaiting commit.
 struct ColliderV4BuildResult {
+    tier: ColliderTier,
+    /// Tiles gathered into the build.
+    tiles: usize,
     /// World centre the collider was built relative to (the camera position at
     /// dispatch), so the commit can place it in the current origin frame.
     centre: DVec3,
@@ -165,13 +238,14 @@ impl Default for ColliderV4BuildChannel {
     }
 }
 
-/// Commit a finished build, then dispatch a rebuild off the main thread once the
-/// camera has moved past the threshold.
+/// Commit finished builds, then dispatch a rebuild of each tier off the main
+/// thread once the camera has moved past that tier's threshold.
 #[allow(clippy::too_many_arguments)]
 fn update_physics_colliders_v4(
     mut commands: Commands,
     lod_state: Res<LodState>,
     mut v4: ResMut<ColliderV4State>,
+    mut stats: ResMut<ColliderTierStats>,
     physics_state: Res<PhysicsState>,
     streaming: Res<PhysicsStreamingConfig>,
     camera_query: Query<&FloatingOriginCamera>,
@@ -188,26 +262,58 @@ fn update_physics_colliders_v4(
         .unwrap_or(camera.position);
 
     while let Ok(result) = channel.rx.try_recv() {
-        commit_build(&mut commands, &mut v4, camera_pos, result);
+        commit_build(&mut commands, &mut v4, &mut stats, camera_pos, result);
     }
 
-    if v4.building {
-        return;
+    let down = (-camera_pos.normalize_or_zero()).as_vec3();
+    if down != Vec3::ZERO {
+        dispatch_fine(
+            &lod_state, &streaming, &mut v4, &channel, &spawner, camera_pos, down,
+        );
+        if streaming.coarse_range > 0.0 {
+            dispatch_coarse(
+                &lod_state, &streaming, &mut v4, &channel, &spawner, camera_pos, down,
+            );
+        }
     }
-    let threshold = REBUILD_DISTANCE.max(2.0);
-    let moved = v4
-        .centre
-        .map_or(f64::INFINITY, |c| (camera_pos - c).length());
-    if moved <= f64::from(threshold) {
-        return;
+
+    // Turning the tier off drops its collider.
+    if streaming.coarse_range <= 0.0
+        && let Some(entity) = v4.coarse.entity.take()
+    {
+        commands.entity(entity).despawn();
+        v4.coarse.centre = None;
+        stats.coarse = TierStats::default();
     }
 
-    let down = (-camera_pos.normalize_or_zero()).as_vec3();
-    if down == Vec3::ZERO {
-        return;
+    let stats = &mut *stats;
+    for (tier, tier_stats, reach) in [
+        (&v4.fine, &mut stats.fine, f64::from(MAX_RADIUS)),
+        (&v4.coarse, &mut stats.coarse, streaming.coarse_range),
+    ] {
+        tier_stats.reach_m = reach;
+        tier_stats.building = tier.building;
+        tier_stats.drift_m = tier.centre.map_or(0.0, |c| (camera_pos - c).length());
     }
+}
+
+/// Rebuild the full-detail collider once the camera has moved
+/// [`REBUILD_DISTANCE`] from its centre.
+fn dispatch_fine(
+    lod_state: &LodState,
+    streaming: &PhysicsStreamingConfig,
+    v4: &mut ColliderV4State,
+    channel: &ColliderV4BuildChannel,
+    spawner: &TaskSpawner,
+    camera_pos: DVec3,
+    down: Vec3,
+) {
+    let threshold = REBUILD_DISTANCE.max(2.0);
+    let Some(moved) = v4.fine.rebuild_due(camera_pos, f64::from(threshold)) else {
+        return;
+    };
 
-    let tiles = gather_tiles(&lod_state, &streaming, camera_pos);
+    let tiles = gather_tiles(lod_state, streaming, camera_pos);
     if tiles.is_empty() {
         debug!(target: "collider_v4", "no tiles in range, deferring");
         return;
@@ -233,12 +339,68 @@ fn update_physics_colliders_v4(
         };
         let _ = tx
             .send(ColliderV4BuildResult {
+                tier: ColliderTier::Fine,
+                tiles: tile_refs.len(),
+                centre: camera_pos,
+                collider,
+            })
+            .await;
+    });
+    v4.fine.building = true;
+}
+
+/// Rebuild the coarse tier once the camera has moved
+/// [`PhysicsStreamingConfig::coarse_rebuild_distance`] from its centre.
+fn dispatch_coarse(
+    lod_state: &LodState,
+    streaming: &PhysicsStreamingConfig,
+    v4: &mut ColliderV4State,
+    channel: &ColliderV4BuildChannel,
+    spawner: &TaskSpawner,
+    camera_pos: DVec3,
+    down: Vec3,
+) {
+    let threshold = streaming.coarse_rebuild_distance.max(2.0);
+    let Some(moved) = v4.coarse.rebuild_due(camera_pos, threshold) else {
+        return;
+    };
+
+    let tiles = gather_coarse_tiles(lod_state, streaming, camera_pos);
+    if tiles.is_empty() {
+        return;
+    }
+    info!(
+        target: "collider_v4",
+        "coarse dispatch: {} tiles, camera moved {moved:.1} m (threshold {threshold:.1} m)",
+        tiles.len()
+    );
+
+    // The hole must stay inside the full-detail collider wherever the camera
+    // roams before the next coarse rebuild, allowing for the fine collider's own
+    // lag behind the camera.
+    let hole_radius = (f64::from(MAX_RADIUS - REBUILD_DISTANCE) - threshold).max(0.0);
+    let settings = CoarseColliderSettings {
+        simplify_tolerance: streaming.coarse_simplify_tolerance as f32,
+        skirt_depth: streaming.collider_skirt_depth as f32,
+        hole_radius: hole_radius as f32,
+    };
+    let tx = channel.tx.clone();
+    spawner.spawn(async move {
+        let tile_refs: Vec<(TileMeshes, u8)> = tiles
+            .iter()
+            .map(|(m, mask)| (m.as_tile_meshes(), *mask))
+            .collect();
+        let collider = create_coarse_collider(&tile_refs, down, &settings);
+        let _ = tx
+            .send(ColliderV4BuildResult {
+                tier: ColliderTier::Coarse,
+                tiles: tile_refs.len(),
                 centre: camera_pos,
                 collider,
             })
             .await;
     });
-    v4.building = true;
+    v4.coarse.building = true;
 }
 
 /// Gather the displayed composite tiles within the collider's reach of the
@@ -262,38 +424,113 @@ fn gather_tiles(
             if (node_data.world_position - camera_pos).length() > reach {
                 return None;
             }
-            Some((
-                OwnedTileMeshes {
-                    meshes: Arc::clone(&node_data.meshes),
-                    rotation: node_data.transform.rotation,
-                    scale: node_data.transform.scale,
-                    offset: (node_data.world_position - camera_pos).as_vec3(),
-                },
-                mask,
-            ))
+            Some((owned_tile(lod_state, *path, camera_pos)?, mask))
         })
         .collect()
 }
 
-/// Spawn a finished collider and atomically retire the previous one (double
-/// buffer). An empty build keeps the previous collider rather than opening a gap.
+/// Gather the coarse tier's tiles: every loaded tile within
+/// [`PhysicsStreamingConfig::coarse_range`], replaced by its ancestor at the
+/// tier's depth where it is finer, then composited (each tile masked by its
+/// selected children) as [`compute_physics_targets`] does for the WYSIWYG set.
+///
+/// [`compute_physics_targets`]: crate::collider::osm_roads::compute_physics_targets
+fn gather_coarse_tiles(
+    lod_state: &LodState,
+    streaming: &PhysicsStreamingConfig,
+    camera_pos: DVec3,
+) -> Vec<(OwnedTileMeshes, u8)> {
+    let depth = PHYSICS_FINEST_DEPTH.saturating_sub(streaming.coarse_depth_offset);
+    let mut targets: HashMap<OctreePath, u8> = HashMap::new();
+    for path in &lod_state.loaded_nodes {
+        if !lod_state.node_data.contains_key(path) {
+            continue;
+        }
+        let Some(obb) = lod_state.node_obbs.get(path) else {
+            continue;
+        };
+        if effective_distance(obb, camera_pos, DVec3::ZERO) > streaming.coarse_range {
+            continue;
+        }
+        let mut selected = *path;
+        while selected.depth() > depth {
+            let Some(parent) = selected.parent() else {
+                break;
+            };
+            if !lod_state.node_data.contains_key(&parent) {
+                break;
+            }
+            selected = parent;
+        }
+        targets.entry(selected).or_insert(0);
+    }
+    let paths: Vec<OctreePath> = targets.keys().copied().collect();
+    for path in &paths {
+        let Some(parent) = path.parent() else {
+            continue;
+        };
+        let octant = path
+            .octant_at(path.depth() - 1)
+            .expect("non-root path has a last octant");
+        if let Some(mask) = targets.get_mut(&parent) {
+            *mask |= 1 << octant;
+        }
+    }
+    targets
+        .into_iter()
+        .filter(|(_, mask)| *mask != 0xff)
+        .filter_map(|(path, mask)| Some((owned_tile(lod_state, path, camera_pos)?, mask)))
+        .collect()
+}
+
+/// Snapshot a loaded tile's build inputs, offset into the frame centred on
+/// `camera_pos`.
+fn owned_tile(
+    lod_state: &LodState,
+    path: OctreePath,
+    camera_pos: DVec3,
+) -> Option<OwnedTileMeshes> {
+    let node_data = lod_state.node_data.get(&path)?;
+    Some(OwnedTileMeshes {
+        meshes: Arc::clone(&node_data.meshes),
+        rotation: node_data.transform.rotation,
+        scale: node_data.transform.scale,
+        offset: (node_data.world_position - camera_pos).as_vec3(),
+    })
+}
+
+/// Spawn a finished collider and atomically retire its tier's previous one
+/// (double buffer). An empty build keeps the previous collider rather than
+/// opening a gap.
 fn commit_build(
     commands: &mut Commands,
     v4: &mut ColliderV4State,
+    stats: &mut ColliderTierStats,
     camera_pos: DVec3,
     result: ColliderV4BuildResult,
 ) {
-    v4.building = false;
+    let (state, tier_stats, colour) = match result.tier {
+        ColliderTier::Fine => (&mut v4.fine, &mut stats.fine, COLLIDER_COLOUR),
+        ColliderTier::Coarse => (&mut v4.coarse, &mut stats.coarse, COARSE_COLLIDER_COLOUR),
+    };
+    state.building = false;
 
     let Some(collider) = result.collider else {
         warn!(
             target: "collider_v4",
-            "empty build (no geometry wrapped); keeping previous collider"
+            "empty {:?} build (no geometry wrapped); keeping previous collider",
+            result.tier
         );
-        v4.centre = Some(result.centre);
+        state.centre = Some(result.centre);
         return;
     };
-    info!(target: "collider_v4", "commit: built");
+    info!(target: "collider_v4", "commit: built {:?}", result.tier);
+    tier_stats.tiles = result.tiles;
+    tier_stats.triangles = collider
+        .shape()
+        .as_trimesh()
+        .map_or(0, |trimesh| trimesh.indices().len());
+    tier_stats.builds += 1;
 
     // Camera-relative position in the commit-time origin frame; the mesh is built
     // relative to its centre, so this places it correctly.
@@ -310,13 +547,13 @@ fn commit_build(
                 [GameLayer::Ground],
                 [GameLayer::Ground, GameLayer::Vehicle, GameLayer::Ragdoll],
             ),
-            DebugRender::collider(COLLIDER_COLOUR),
+            DebugRender::collider(colour),
             ColliderV4,
         ))
         .id();
 
-    let old = v4.entity.replace(entity);
-    v4.centre = Some(result.centre);
+    let old = state.entity.replace(entity);
+    state.centre = Some(result.centre);
     if let Some(old) = old {
         commands.entity(old).despawn();
     }
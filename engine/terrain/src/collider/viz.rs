@@ -17,7 +17,9 @@
 //! draws the fitted-road overlay ([`draw_road_overlay`]). The render-mesh
 //! overlay ([`draw_render_mesh_wireframes`], the triangles the renderer actually
 //! rasterizes) is pipeline-agnostic and runs on every path. All share the
-//! [`LodVizGizmos`] group and the [`depth_color`] gradient.
+//! [`LodVizGizmos`] group and the [`depth_color`] gradient, and follow the
+//! [`DebugPalette`] picked in the Settings tab. [`apply_depth_tint`] carries
+//! the same gradient onto the terrain surface itself, as a coverage heat map.
 
 use std::{
     collections::{HashSet, hash_map::DefaultHasher},
@@ -26,6 +28,7 @@ use std::{
 
 use avian3d::prelude::ColliderAabb;
 use bevy::{
+    color::ColorToComponents,
     gizmos::config::GizmoConfigStore,
     mesh::{Indices, VertexAttributeValues},
     prelude::*,
@@ -33,12 +36,13 @@ use bevy::{
 use glam::{DQuat, DVec3};
 use rocktree_decode::{OctreePath, OrientedBoundingBox};
 use veldera_geo::floating_origin::FloatingOriginCamera;
-use veldera_physics::{DebugRender, TerrainCollider, is_physics_debug_enabled};
+use veldera_physics::{DebugPalette, DebugRender, TerrainCollider, is_physics_debug_enabled};
 
 use crate::{
     collider::shared::RoadOverlay,
     lod::{LodSnapshot, LodSnapshotRequest, LodState, SnapshotNodeState},
     mesh::RocktreeMeshMarker,
+    terrain_material::{TerrainDebugView, TerrainMaterial},
 };
 
 /// Filter for terrain-collider wireframe rendering, applied whenever the
@@ -82,6 +86,12 @@ pub struct LodVizSettings {
     pub draw_collider_tiles: bool,
     /// Draw dim OBBs of the nodes with in-flight load requests.
     pub draw_loading_nodes: bool,
+    /// Tint the terrain itself by the depth of the tile each mesh came from,
+    /// to spot missing detail and over-refinement at a glance. Unaffected
+    /// by the distance and depth filters.
+    pub tint_by_depth: bool,
+    /// Blend weight of the depth tint.
+    pub tint_strength: f32,
     /// Tiles whose OBB centre is farther than this from the camera are
     /// skipped (m).
     pub max_distance_m: f64,
@@ -97,6 +107,8 @@ impl Default for LodVizSettings {
             draw_render_tiles: false,
             draw_collider_tiles: false,
             draw_loading_nodes: false,
+            tint_by_depth: false,
+            tint_strength: 0.6,
             max_distance_m: 1500.0,
             depth_min: 0,
             depth_max: OctreePath::MAX_DEPTH,
@@ -115,26 +127,12 @@ pub struct LodVizGizmos;
 /// data range without bunching at the end.
 pub const COLOR_DEPTH_ANCHOR: f32 = 30.0;
 
-/// Map an octree depth to a colour along a cool-to-warm gradient
-/// (blue → cyan → green → yellow → red). Shared by the in-world gizmos and
-/// the streaming diagnostics tab so both views use the same colour language.
+/// Map an octree depth to a colour along the palette's cool-to-warm
+/// gradient. Shared by the in-world gizmos and the streaming diagnostics tab
+/// so both views use the same colour language.
 #[must_use]
-pub fn depth_color(depth: usize) -> Color {
-    let t = (depth as f32 / COLOR_DEPTH_ANCHOR).clamp(0.0, 1.0);
-    let (r, g, b) = if t < 0.25 {
-        let k = t / 0.25;
-        (60.0, 60.0 + k * 195.0, 220.0)
-    } else if t < 0.5 {
-        let k = (t - 0.25) / 0.25;
-        (60.0, 255.0, 220.0 - k * 220.0)
-    } else if t < 0.75 {
-        let k = (t - 0.5) / 0.25;
-        (60.0 + k * 195.0, 255.0, 0.0)
-    } else {
-        let k = (t - 0.75) / 0.25;
-        (255.0, 255.0 - k * 175.0, 0.0)
-    };
-    Color::srgb_u8(r as u8, g as u8, b as u8)
+pub fn depth_color(depth: usize, palette: DebugPalette) -> Color {
+    palette.gradient(depth as f32 / COLOR_DEPTH_ANCHOR)
 }
 
 /// Jitter a depth colour per tile so adjacent same-depth colliders (which would
@@ -166,6 +164,7 @@ fn tile_tint(base: Color, path: OctreePath) -> Color {
 pub(crate) fn reconcile_collider_wireframes(
     mut commands: Commands,
     filter: Res<ColliderVizFilter>,
+    palette: Res<DebugPalette>,
     config_store: Res<GizmoConfigStore>,
     colliders: Query<(
         Entity,
@@ -187,7 +186,7 @@ pub(crate) fn reconcile_collider_wireframes(
             && (filter.depth_min..=filter.depth_max).contains(&depth);
 
         let desired = if within {
-            DebugRender::collider(tile_tint(depth_color(depth), terrain.path))
+            DebugRender::collider(tile_tint(depth_color(depth, *palette), terrain.path))
         } else {
             DebugRender::none()
         };
@@ -209,6 +208,7 @@ pub(crate) fn configure_lod_viz_gizmos(mut config_store: ResMut<GizmoConfigStore
 #[allow(clippy::type_complexity)]
 pub(crate) fn draw_lod_viz(
     settings: Res<LodVizSettings>,
+    palette: Res<DebugPalette>,
     lod_state: Res<LodState>,
     snapshot: Res<LodSnapshot>,
     mut snapshot_request: ResMut<LodSnapshotRequest>,
@@ -253,7 +253,7 @@ pub(crate) fn draw_lod_viz(
                 &marker.obb,
                 camera_pos,
                 1.0,
-                depth_color(depth),
+                depth_color(depth, *palette),
             );
         }
     }
@@ -267,7 +267,7 @@ pub(crate) fn draw_lod_viz(
             // White-tinted and double-drawn (slightly inflated copy) so the
             // collider layer stands apart from the render layer when both
             // are enabled.
-            let color = depth_color(depth).mix(&Color::WHITE, 0.5);
+            let color = depth_color(depth, *palette).mix(&Color::WHITE, 0.5);
             draw_obb(&mut gizmos, &obb, camera_pos, 1.0, color);
             draw_obb(&mut gizmos, &obb, camera_pos, 1.01, color);
         }
@@ -278,12 +278,51 @@ pub(crate) fn draw_lod_viz(
             if node.state != SnapshotNodeState::Loading || !in_range(&node.obb, node.depth) {
                 continue;
             }
-            let color = depth_color(node.depth).with_alpha(0.35);
+            let color = depth_color(node.depth, *palette).with_alpha(0.35);
             draw_obb(&mut gizmos, &node.obb, camera_pos, 1.0, color);
         }
     }
 }
 
+/// Push the depth tint into the terrain materials (see
+/// [`LodVizSettings::tint_by_depth`]): every material when the tint or the
+/// palette changes, otherwise only those of newly spawned meshes.
+///
+/// Compares against the last applied values rather than relying on change
+/// detection, since the debug UI borrows the settings mutably every frame
+/// it's shown.
+pub(crate) fn apply_depth_tint(
+    settings: Res<LodVizSettings>,
+    palette: Res<DebugPalette>,
+    mut applied: Local<Option<(f32, DebugPalette)>>,
+    meshes: Query<(&RocktreeMeshMarker, Ref<MeshMaterial3d<TerrainMaterial>>)>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+) {
+    let strength = if settings.tint_by_depth {
+        settings.tint_strength.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let all = *applied != Some((strength, *palette));
+    if !all && strength == 0.0 {
+        return;
+    }
+    *applied = Some((strength, *palette));
+
+    for (marker, material) in &meshes {
+        if !all && !material.is_added() {
+            continue;
+        }
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        let [r, g, b, _] = depth_color(marker.path.depth(), *palette)
+            .to_linear()
+            .to_f32_array();
+        material.extension.depth_tint = Vec4::new(r, g, b, strength);
+    }
+}
+
 /// Draw an OBB as a camera-relative wireframe box, optionally inflated.
 fn draw_obb(
     gizmos: &mut Gizmos<LodVizGizmos>,
@@ -321,6 +360,7 @@ fn wireframe_alpha(distance: f32, radius: f32) -> f32 {
 /// the physics debug visualisation is disabled.
 pub(crate) fn draw_collider_wireframes(
     filter: Res<ColliderVizFilter>,
+    palette: Res<DebugPalette>,
     config_store: Res<GizmoConfigStore>,
     colliders: Query<(
         &TerrainCollider,
@@ -347,7 +387,7 @@ pub(crate) fn draw_collider_wireframes(
         let Some(trimesh) = collider.shape().as_trimesh() else {
             continue;
         };
-        let color = depth_color(depth);
+        let color = depth_color(depth, *palette);
         let vertex_world = |index: u32| position.0 + trimesh.vertices()[index as usize];
 
         // Each interior edge is shared by two triangles; draw it once.
@@ -408,10 +448,12 @@ impl Default for RenderMeshVizFilter {
 /// render path: hidden tiles are skipped and masked-octant vertices collapse
 /// to the mesh origin exactly like `terrain_material.wgsl`, so a triangle
 /// the GPU degenerates away vanishes here too. Orange, to read against the
-/// depth-coloured collider wireframes.
+/// depth-coloured collider wireframes. Also drawn while the
+/// [`TerrainDebugView::Wireframe`] view is active.
 #[allow(clippy::type_complexity)]
 pub(crate) fn draw_render_mesh_wireframes(
     filter: Res<RenderMeshVizFilter>,
+    debug_view: Res<TerrainDebugView>,
     camera_query: Query<&FloatingOriginCamera>,
     meshes: Res<Assets<Mesh>>,
     materials: Res<Assets<crate::terrain_material::TerrainMaterial>>,
@@ -424,7 +466,7 @@ pub(crate) fn draw_render_mesh_wireframes(
     )>,
     mut gizmos: Gizmos<LodVizGizmos>,
 ) {
-    if !filter.enabled {
+    if !filter.enabled && *debug_view != TerrainDebugView::Wireframe {
         return;
     }
     let Ok(camera) = camera_query.single() else {
@@ -544,6 +586,7 @@ const ROAD_TICK_M: f32 = 3.0;
 pub(crate) fn draw_road_overlay(
     settings: Res<RoadVizSettings>,
     overlay: Res<RoadOverlay>,
+    palette: Res<DebugPalette>,
     camera: Query<&FloatingOriginCamera>,
     mut gizmos: Gizmos<LodVizGizmos>,
 ) {
@@ -559,7 +602,7 @@ pub(crate) fn draw_road_overlay(
     let render = |p: DVec3| (p - camera_pos).as_vec3();
 
     for ribbon in &overlay.ribbons {
-        let color = road_class_color(ribbon.class);
+        let color = road_class_color(ribbon.class, *palette);
         for station in &ribbon.stations {
             // A vertical tick along the radial so the ribbon stands above the
             // photogrammetry wireframe.
@@ -593,8 +636,8 @@ pub(crate) fn draw_road_overlay(
 /// A distinct colour per road class byte (as carried on
 /// [`RoadOverlay`] ribbons); unknown classes fall
 /// back to white.
-fn road_class_color(class: u8) -> Color {
-    match class {
+fn road_class_color(class: u8, palette: DebugPalette) -> Color {
+    let standard = match class {
         0 => Color::srgb(1.0, 0.2, 0.2),  // motorway.
         1 => Color::srgb(1.0, 0.55, 0.0), // trunk.
         2 => Color::srgb(1.0, 0.85, 0.0), // primary.
@@ -602,6 +645,7 @@ fn road_class_color(class: u8) -> Color {
         4 => Color::srgb(0.2, 0.8, 0.9),  // tertiary.
         5 => Color::srgb(0.6, 0.6, 1.0),  // residential.
         6 => Color::srgb(0.7, 0.7, 0.7),  // unclassified.
-        _ => Color::WHITE,
-    }
+        _ => return Color::WHITE,
+    };
+    palette.categorical(usize::from(class), standard)
 }
@@ -0,0 +1,403 @@
+//! Projected terrain decals: persistent marks (scorch marks, paint splats)
+//! stamped onto the streamed terrain.
+//!
+//! A decal is an entity carrying [`TerrainDecal`] and a [`WorldPosition`] at
+//! the mark's ECEF centre. The tile under a mark is replaced by LoD refinement
+//! and evicted when the camera leaves, so a decal never owns the geometry it
+//! was cut from: it keeps only its ECEF placement, and
+//! [`project_terrain_decals`] re-cuts its mesh from whichever loaded tile
+//! currently covers that position whenever the covering tile changes. The cut
+//! mesh is expressed relative to the decal centre, so the floating-origin sync
+//! keeps it camera-relative like any other world entity.
+//!
+//! The crate supplies only the mechanism; what leaves a mark, what it looks
+//! like (the host-supplied material), and how many marks persist is gameplay
+//! policy.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+};
+use glam::DVec3;
+use rocktree_decode::{OctreePath, OrientedBoundingBox};
+
+use veldera_geo::{coords::RadialFrame, floating_origin::WorldPosition};
+
+use crate::lod::{LoadedNodeData, LodState, poll_lod_node_tasks};
+
+/// Plugin that keeps every [`TerrainDecal`] projected onto the currently
+/// displayed terrain.
+pub struct TerrainDecalPlugin;
+
+impl Plugin for TerrainDecalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, project_terrain_decals.after(poll_lod_node_tasks));
+    }
+}
+
+/// A mark projected onto the terrain around the entity's [`WorldPosition`].
+///
+/// The footprint is a square of `2 * half_size` metres in the local tangent
+/// plane, rotated by `rotation` about local up; terrain within `depth` metres
+/// above or below the centre receives the mark, so overhangs and the ground
+/// beneath a bridge are left alone. The mesh's UVs span the footprint
+/// (`(0, 0)` at the south-west corner before rotation), so `material` is
+/// typically an alpha-blended texture with a transparent border.
+#[derive(Component, Clone, Debug)]
+#[require(Transform, Visibility)]
+pub struct TerrainDecal {
+    /// Half the footprint's edge length (m).
+    pub half_size: f32,
+    /// Vertical reach above and below the centre (m).
+    pub depth: f32,
+    /// Rotation of the footprint about local up (radians).
+    pub rotation: f32,
+    /// Distance the cut surface is lifted along local up (m), to keep the mark
+    /// from z-fighting the tile it was cut from.
+    pub surface_offset: f32,
+    /// Material the cut mesh is drawn with.
+    pub material: Handle<StandardMaterial>,
+}
+
+impl TerrainDecal {
+    /// A decal with a `half_size` footprint, reaching as deep as it is wide,
+    /// lifted a few centimetres off the surface.
+    pub fn new(half_size: f32, material: Handle<StandardMaterial>) -> Self {
+        Self {
+            half_size,
+            depth: half_size,
+            rotation: 0.0,
+            surface_offset: 0.03,
+            material,
+        }
+    }
+
+    /// Rotate the footprint by `rotation` radians about local up.
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+/// The tile a decal's current mesh was cut from.
+///
+/// Absent until the first successful cut; compared against the covering tile
+/// on every re-evaluation so the mesh is only rebuilt when coverage changes.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectedDecal {
+    /// Path of the source tile.
+    pub tile: OctreePath,
+}
+
+/// Re-cut each decal's mesh from the deepest loaded tile covering its centre.
+///
+/// The covering tile is whatever the renderer displays at that point: a tile
+/// whose child covering the point has loaded is masked there, so the deepest
+/// loaded tile is the visible surface. Covering tiles only change when a node
+/// loads or unloads, so the scan runs for every decal only when the loaded set
+/// changed; new decals are always evaluated. A decal whose coverage vanished
+/// keeps its last mesh until another tile covers it.
+fn project_terrain_decals(
+    mut commands: Commands,
+    lod_state: Res<LodState>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut last_signature: Local<Option<(u64, usize)>>,
+    decals: Query<(
+        Entity,
+        &TerrainDecal,
+        &WorldPosition,
+        Option<&ProjectedDecal>,
+        Option<&Mesh3d>,
+    )>,
+    added: Query<(), Added<TerrainDecal>>,
+) {
+    let signature = (
+        lod_state.nodes_completed_version,
+        lod_state.loaded_nodes.len(),
+    );
+    let loaded_set_changed = *last_signature != Some(signature);
+    *last_signature = Some(signature);
+
+    for (entity, decal, world_pos, projected, mesh) in &decals {
+        if !loaded_set_changed && projected.is_some() && !added.contains(entity) {
+            continue;
+        }
+        let Some((tile, node)) = covering_tile(&lod_state, world_pos.position) else {
+            continue;
+        };
+        if projected.is_some_and(|p| p.tile == tile) {
+            continue;
+        }
+
+        let Some(cut) = cut_decal_mesh(node, world_pos.position, decal) else {
+            continue;
+        };
+        // Reuse the decal's mesh asset across re-cuts rather than leaking one
+        // per LoD change.
+        let existing = mesh.and_then(|Mesh3d(handle)| meshes.get_mut(handle).map(|m| (handle, m)));
+        let handle = match existing {
+            Some((handle, existing)) => {
+                *existing = cut;
+                handle.clone()
+            }
+            None => meshes.add(cut),
+        };
+        commands.entity(entity).insert((
+            Mesh3d(handle),
+            MeshMaterial3d(decal.material.clone()),
+            ProjectedDecal { tile },
+        ));
+    }
+}
+
+/// The deepest loaded tile (with node data) whose OBB contains `position`.
+fn covering_tile(lod_state: &LodState, position: DVec3) -> Option<(OctreePath, &LoadedNodeData)> {
+    lod_state
+        .loaded_nodes
+        .iter()
+        .filter(|path| {
+            lod_state
+                .node_obbs
+                .get(path)
+                .is_some_and(|obb| obb_contains(obb, position))
+        })
+        .filter_map(|path| lod_state.node_data.get(path).map(|data| (*path, data)))
+        .max_by_key(|(path, _)| path.depth())
+}
+
+/// Whether `point` lies inside `obb` (whose extents are half-extents).
+fn obb_contains(obb: &OrientedBoundingBox, point: DVec3) -> bool {
+    let local = obb.orientation.transpose() * (point - obb.center);
+    local.abs().cmple(obb.extents).all()
+}
+
+/// Cut the part of `node`'s surface inside `decal`'s footprint into a mesh
+/// relative to `centre`. Returns `None` when no terrain falls inside it.
+fn cut_decal_mesh(node: &LoadedNodeData, centre: DVec3, decal: &TerrainDecal) -> Option<Mesh> {
+    let frame = RadialFrame::from_ecef_position(centre);
+    let spin = Quat::from_axis_angle(frame.up, decal.rotation);
+    let basis = DecalBasis {
+        right: spin * frame.east,
+        forward: spin * frame.north,
+        up: frame.up,
+    };
+
+    // Tile vertices relative to the decal centre; f32 is ample within a tile.
+    let origin = (node.world_position - centre).as_vec3();
+    let transform = node.transform;
+    let triangles = node.meshes.iter().flat_map(|mesh| {
+        let positions: Vec<Vec3> = mesh
+            .vertices
+            .iter()
+            .map(|v| {
+                let local = Vec3::new(f32::from(v.x), f32::from(v.y), f32::from(v.z));
+                origin + transform.rotation * (transform.scale * local)
+            })
+            .collect();
+        rocktree_decode::strip_to_triangles(&mesh.indices)
+            .chunks_exact(3)
+            .map(|t| {
+                [
+                    positions[usize::from(t[0])],
+                    positions[usize::from(t[1])],
+                    positions[usize::from(t[2])],
+                ]
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let geometry = clip_decal_geometry(triangles, &basis, decal.half_size, decal.depth);
+    if geometry.positions.is_empty() {
+        return None;
+    }
+
+    let lift = basis.up * decal.surface_offset;
+    let positions: Vec<[f32; 3]> = geometry
+        .positions
+        .iter()
+        .map(|p| (*p + lift).to_array())
+        .collect();
+    let vertex_count = positions.len() as u32;
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, geometry.normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, geometry.uvs);
+    mesh.insert_indices(Indices::U32((0..vertex_count).collect()));
+    Some(mesh)
+}
+
+/// Orthonormal decal frame: `right`/`forward` span the footprint, `up` is the
+/// projection axis.
+struct DecalBasis {
+    right: Vec3,
+    forward: Vec3,
+    up: Vec3,
+}
+
+/// Un-indexed triangle soup clipped to a decal footprint.
+#[derive(Default)]
+struct DecalGeometry {
+    positions: Vec<Vec3>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+}
+
+/// Clip `triangles` (relative to the decal centre) to the footprint box:
+/// `±half_size` along `right`/`forward` and `±depth` along `up`.
+///
+/// Each triangle is clipped against the four side planes (Sutherland–Hodgman)
+/// so the mark has straight edges regardless of the tile's tessellation, then
+/// fan-triangulated. Triangles wholly above or below the vertical reach, or
+/// facing away from `up` (cliff undersides), are dropped. UVs come from the
+/// planar projection onto the footprint.
+fn clip_decal_geometry(
+    triangles: impl Iterator<Item = [Vec3; 3]>,
+    basis: &DecalBasis,
+    half_size: f32,
+    depth: f32,
+) -> DecalGeometry {
+    let mut geometry = DecalGeometry::default();
+    let planes = [
+        (basis.right, half_size),
+        (-basis.right, half_size),
+        (basis.forward, half_size),
+        (-basis.forward, half_size),
+    ];
+
+    for triangle in triangles {
+        if triangle.iter().all(|p| p.dot(basis.up) > depth)
+            || triangle.iter().all(|p| p.dot(basis.up) < -depth)
+        {
+            continue;
+        }
+        let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+        // Rocktree winding is inconsistent, so orient every face toward up.
+        let normal = if normal.dot(basis.up) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        let Some(normal) = normal.try_normalize() else {
+            continue;
+        };
+        if normal.dot(basis.up) <= 0.0 {
+            continue;
+        }
+
+        let mut polygon = triangle.to_vec();
+        for (axis, limit) in planes {
+            polygon = clip_polygon(&polygon, axis, limit);
+            if polygon.len() < 3 {
+                break;
+            }
+        }
+        if polygon.len() < 3 {
+            continue;
+        }
+
+        let uv = |p: Vec3| {
+            [
+                p.dot(basis.right) / (2.0 * half_size) + 0.5,
+                0.5 - p.dot(basis.forward) / (2.0 * half_size),
+            ]
+        };
+        for i in 1..polygon.len() - 1 {
+            for p in [polygon[0], polygon[i], polygon[i + 1]] {
+                geometry.positions.push(p);
+                geometry.normals.push(normal.to_array());
+                geometry.uvs.push(uv(p));
+            }
+        }
+    }
+
+    geometry
+}
+
+/// Clip a convex polygon to the half-space `p · axis <= limit`.
+fn clip_polygon(polygon: &[Vec3], axis: Vec3, limit: f32) -> Vec<Vec3> {
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let d_current = current.dot(axis) - limit;
+        let d_next = next.dot(axis) - limit;
+        if d_current <= 0.0 {
+            out.push(current);
+        }
+        if (d_current <= 0.0) != (d_next <= 0.0) {
+            let t = d_current / (d_current - d_next);
+            out.push(current.lerp(next, t));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::DMat3;
+
+    use super::*;
+
+    fn flat_basis() -> DecalBasis {
+        DecalBasis {
+            right: Vec3::X,
+            forward: Vec3::Y,
+            up: Vec3::Z,
+        }
+    }
+
+    #[test]
+    fn large_triangle_is_clipped_to_footprint() {
+        let triangle = [
+            Vec3::new(-10.0, -10.0, 0.0),
+            Vec3::new(10.0, -10.0, 0.0),
+            Vec3::new(0.0, 10.0, 0.0),
+        ];
+        let geometry = clip_decal_geometry(std::iter::once(triangle), &flat_basis(), 1.0, 1.0);
+        assert!(!geometry.positions.is_empty());
+        for p in &geometry.positions {
+            assert!(p.x.abs() <= 1.0 + 1e-5 && p.y.abs() <= 1.0 + 1e-5, "{p}");
+        }
+        for uv in &geometry.uvs {
+            assert!((0.0..=1.0).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1]));
+        }
+    }
+
+    #[test]
+    fn triangles_outside_vertical_reach_are_dropped() {
+        let above = [
+            Vec3::new(-1.0, -1.0, 5.0),
+            Vec3::new(1.0, -1.0, 5.0),
+            Vec3::new(0.0, 1.0, 5.0),
+        ];
+        let geometry = clip_decal_geometry(std::iter::once(above), &flat_basis(), 2.0, 1.0);
+        assert!(geometry.positions.is_empty());
+    }
+
+    #[test]
+    fn flipped_winding_still_faces_up() {
+        let triangle = [
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.0),
+        ];
+        let geometry = clip_decal_geometry(std::iter::once(triangle), &flat_basis(), 2.0, 1.0);
+        assert!(geometry.normals.iter().all(|n| n[2] > 0.99));
+    }
+
+    #[test]
+    fn obb_containment_respects_orientation() {
+        let obb = OrientedBoundingBox {
+            center: DVec3::new(100.0, 0.0, 0.0),
+            extents: DVec3::new(10.0, 1.0, 1.0),
+            orientation: DMat3::from_rotation_z(std::f64::consts::FRAC_PI_2),
+        };
+        // The long axis is rotated onto world Y.
+        assert!(obb_contains(&obb, DVec3::new(100.0, 9.0, 0.0)));
+        assert!(!obb_contains(&obb, DVec3::new(109.0, 0.0, 0.0)));
+    }
+}
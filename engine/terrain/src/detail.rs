@@ -0,0 +1,188 @@
+//! Session overrides of how much detail the render traversal streams: a depth
+//! cap, and a temporary boost around the camera.
+//!
+//! [`LodDetail::depth_cap`] stops the render traversal refining past a node
+//! depth. It lasts for the session only; nothing saves it.
+//!
+//! A [`DetailBoostRequest`] starts a [`DetailBoost`] at the camera's current
+//! position. Within its radius the traversal refines to
+//! [`DetailBoostTuning::tolerance_factor`] times the [`LodRefinement`]
+//! tolerance, ignoring the QoS and metered coarsening, so the spot streams at
+//! full detail whatever the heuristics say. The boost stays put when the
+//! camera moves on, and is bounded by the `[detail_boost]` table of the LOD
+//! config: it ends after a duration, and ends early once the loaded node
+//! count passes a budget, so it can't run the streamer out of memory. The
+//! depth cap still applies inside a boost, and the physics traversal is
+//! unaffected by either.
+
+use bevy::prelude::*;
+use glam::DVec3;
+use serde::Deserialize;
+
+use crate::lod::{LodRefinement, LodState, LodTuning, RefinementStrategy};
+
+/// Bounds on a detail boost. Lives in the `[detail_boost]` table of the LOD
+/// config.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetailBoostTuning {
+    /// Radius around the camera that a boost covers (m).
+    pub radius_m: f64,
+    /// Multiplier on the refinement tolerance inside the radius. Below 1 it
+    /// refines deeper; each halving is about one octree level.
+    pub tolerance_factor: f64,
+    /// How long a boost lasts (s).
+    pub duration_secs: f64,
+    /// Loaded render nodes above which a running boost is ended.
+    pub max_loaded_nodes: usize,
+}
+
+impl Default for DetailBoostTuning {
+    fn default() -> Self {
+        Self {
+            radius_m: 500.0,
+            tolerance_factor: 0.25,
+            duration_secs: 120.0,
+            max_loaded_nodes: 8000,
+        }
+    }
+}
+
+/// A running detail boost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetailBoost {
+    /// ECEF centre: where the camera was when the boost started.
+    pub center: DVec3,
+    /// Covered radius around `center` (m).
+    pub radius: f64,
+    /// Refinement rule inside the radius.
+    pub strategy: RefinementStrategy,
+    /// Real time the boost ends at (s).
+    pub expires_at: f64,
+}
+
+impl DetailBoost {
+    /// Whether a node whose bounds fit in a sphere of `bounding_radius`
+    /// around `node_center` reaches into the boost.
+    pub(crate) fn covers(&self, node_center: DVec3, bounding_radius: f64) -> bool {
+        node_center.distance(self.center) <= self.radius + bounding_radius
+    }
+}
+
+/// Session detail overrides for the render traversal. A change re-runs the
+/// traversal on the next frame.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct LodDetail {
+    /// Deepest node depth the render traversal loads, if capped.
+    pub depth_cap: Option<usize>,
+    boost: Option<DetailBoost>,
+    /// Whether the last boost was ended by the node budget.
+    ended_over_budget: bool,
+}
+
+impl LodDetail {
+    /// The running boost, if any.
+    pub fn boost(&self) -> Option<&DetailBoost> {
+        self.boost.as_ref()
+    }
+
+    /// End the running boost, if any.
+    pub fn cancel_boost(&mut self) {
+        self.boost = None;
+    }
+
+    /// Whether the last boost was ended early because the loaded node count
+    /// passed [`DetailBoostTuning::max_loaded_nodes`].
+    pub fn ended_over_budget(&self) -> bool {
+        self.ended_over_budget
+    }
+
+    /// Whether the render traversal may load the children of a node at
+    /// `depth`.
+    pub(crate) fn allows_refining(&self, depth: usize) -> bool {
+        self.depth_cap.is_none_or(|cap| depth < cap)
+    }
+}
+
+/// Request to start a [`DetailBoost`] at the camera, replacing any running
+/// one. Cleared once handled.
+#[derive(Resource, Default)]
+pub struct DetailBoostRequest {
+    pub wanted: bool,
+}
+
+/// Start requested boosts, and end them on expiry or over budget.
+pub(crate) fn update_lod_detail(
+    mut detail: ResMut<LodDetail>,
+    mut request: ResMut<DetailBoostRequest>,
+    lod_state: Res<LodState>,
+    refinement: Res<LodRefinement>,
+    tuning: Res<LodTuning>,
+    real_time: Res<Time<Real>>,
+) {
+    let now = real_time.elapsed_secs_f64();
+    let bounds = tuning.detail_boost;
+    let strategy = refinement.0.coarsened(bounds.tolerance_factor);
+
+    if std::mem::take(&mut request.wanted)
+        && let Some(metrics) = lod_state.lod_metrics
+    {
+        detail.boost = Some(DetailBoost {
+            center: metrics.camera_position,
+            radius: bounds.radius_m,
+            strategy,
+            expires_at: now + bounds.duration_secs,
+        });
+        detail.ended_over_budget = false;
+        tracing::info!(
+            "LOD: boosting detail within {:.0} m for {:.0} s",
+            bounds.radius_m,
+            bounds.duration_secs
+        );
+    }
+
+    let Some(boost) = detail.boost else {
+        return;
+    };
+    if now >= boost.expires_at {
+        detail.boost = None;
+        tracing::info!("LOD: detail boost expired");
+    } else if lod_state.loaded_nodes.len() > bounds.max_loaded_nodes {
+        detail.boost = None;
+        detail.ended_over_budget = true;
+        tracing::warn!(
+            "LOD: detail boost ended: {} loaded nodes is over the budget of {}",
+            lod_state.loaded_nodes.len(),
+            bounds.max_loaded_nodes
+        );
+    } else if boost.strategy != strategy {
+        // Follow refinement changes made while the boost runs.
+        detail.boost = Some(DetailBoost { strategy, ..boost });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_cap_stops_refinement_at_the_cap() {
+        let mut detail = LodDetail::default();
+        assert!(detail.allows_refining(25));
+        detail.depth_cap = Some(16);
+        assert!(detail.allows_refining(15));
+        assert!(!detail.allows_refining(16));
+    }
+
+    #[test]
+    fn boost_covers_nodes_reaching_into_its_radius() {
+        let boost = DetailBoost {
+            center: DVec3::ZERO,
+            radius: 100.0,
+            strategy: RefinementStrategy::default(),
+            expires_at: 0.0,
+        };
+        assert!(boost.covers(DVec3::new(150.0, 0.0, 0.0), 60.0));
+        assert!(!boost.covers(DVec3::new(150.0, 0.0, 0.0), 40.0));
+    }
+}
@@ -0,0 +1,447 @@
+//! Live data refresh when Google bumps the planetoid's epoch.
+//!
+//! Every [`EpochRefreshTuning::check_interval_secs`] the planetoid metadata
+//! is revalidated (see [`rocktree::Client::refresh_planetoid`]). When its
+//! root epoch has changed, the root bulk is fetched at the new epoch and
+//! diffed against the cached one: loaded nodes whose epochs changed are
+//! refetched, and cached child bulks whose epochs changed are queued and
+//! refreshed the same way, a few at a time, so the update works its way
+//! down the tree without crowding out normal streaming. Refetched nodes keep
+//! their old meshes until the new ones land. Bulks that aren't cached need
+//! nothing: the traversal fetches them at the new epoch from their
+//! refreshed parent.
+//!
+//! [`EpochRefresh`] exposes the last change and the refresh's progress, for
+//! the UI to announce it. Replayed sessions never refresh.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use rocktree::{BulkMetadata, BulkRequest, Planetoid};
+use rocktree_decode::OctreePath;
+use serde::Deserialize;
+
+use veldera_async::TaskSpawner;
+
+use crate::{
+    loader::LoaderState,
+    lod::{LodChannels, LodState, LodTuning, poll_lod_node_tasks, refetch_loaded_node},
+    qos::LoadQos,
+};
+
+/// Tuning for the epoch check and refresh. Lives in the `[epoch_refresh]`
+/// table of the LOD config.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EpochRefreshTuning {
+    /// Time between checks of the planetoid's epoch (s); 0 disables them.
+    pub check_interval_secs: f64,
+    /// Bulks refetched at once during a refresh.
+    pub max_concurrent_bulks: usize,
+    /// Loaded nodes refetched per frame during a refresh.
+    pub max_node_refetches_per_frame: usize,
+}
+
+impl Default for EpochRefreshTuning {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: 1800.0,
+            max_concurrent_bulks: 2,
+            max_node_refetches_per_frame: 8,
+        }
+    }
+}
+
+/// Checks the planetoid's epoch and refreshes the cached data after a change.
+pub struct EpochRefreshPlugin;
+
+impl Plugin for EpochRefreshPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EpochRefresh>()
+            .init_resource::<EpochRefreshChannels>()
+            .add_systems(
+                Update,
+                (schedule_epoch_check, poll_epoch_check, advance_refresh)
+                    .chain()
+                    .after(poll_lod_node_tasks),
+            );
+    }
+}
+
+/// A root epoch change seen while running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochChange {
+    /// The root epoch before the change.
+    pub from: u32,
+    /// The new root epoch.
+    pub to: u32,
+    /// Real time the change was noticed (s).
+    pub at: f64,
+}
+
+/// Epoch check schedule and refresh progress.
+#[derive(Resource, Default)]
+pub struct EpochRefresh {
+    /// Real time of the next scheduled check (s); `None` until the planetoid
+    /// has loaded.
+    next_check: Option<f64>,
+    /// Whether a check is in flight.
+    checking: bool,
+    /// Check at the next opportunity, whatever the schedule.
+    check_requested: bool,
+    /// Real time of the last completed check (s).
+    last_checked: Option<f64>,
+    last_change: Option<EpochChange>,
+    /// Bulks waiting to be refetched, with their new epochs.
+    pending_bulks: VecDeque<(OctreePath, u32)>,
+    in_flight_bulks: HashSet<OctreePath>,
+    /// Loaded nodes waiting to be refetched.
+    pending_nodes: VecDeque<OctreePath>,
+    bulks_refreshed: usize,
+    nodes_refetched: usize,
+}
+
+impl EpochRefresh {
+    /// Check the epoch now rather than at the next scheduled time.
+    pub fn request_check(&mut self) {
+        self.check_requested = true;
+    }
+
+    /// Whether a check is in flight.
+    #[must_use]
+    pub fn is_checking(&self) -> bool {
+        self.checking || self.check_requested
+    }
+
+    /// Real time of the last completed check (s).
+    #[must_use]
+    pub fn last_checked(&self) -> Option<f64> {
+        self.last_checked
+    }
+
+    /// The most recent epoch change, if any was seen this session.
+    #[must_use]
+    pub fn last_change(&self) -> Option<EpochChange> {
+        self.last_change
+    }
+
+    /// Whether changed data is still being refetched.
+    #[must_use]
+    pub fn is_refreshing(&self) -> bool {
+        self.remaining() > 0
+    }
+
+    /// Bulks and nodes still to be refetched, including those in flight.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.pending_bulks.len() + self.in_flight_bulks.len() + self.pending_nodes.len()
+    }
+
+    /// Bulks refreshed since the last change.
+    #[must_use]
+    pub fn bulks_refreshed(&self) -> usize {
+        self.bulks_refreshed
+    }
+
+    /// Nodes refetched since the last change.
+    #[must_use]
+    pub fn nodes_refetched(&self) -> usize {
+        self.nodes_refetched
+    }
+}
+
+type BulkResult = (OctreePath, Result<BulkMetadata, rocktree::Error>);
+
+/// Channels for the epoch check and the refreshed bulks.
+#[derive(Resource)]
+struct EpochRefreshChannels {
+    planetoid_rx: async_channel::Receiver<Result<Planetoid, rocktree::Error>>,
+    planetoid_tx: async_channel::Sender<Result<Planetoid, rocktree::Error>>,
+    bulk_rx: async_channel::Receiver<BulkResult>,
+    bulk_tx: async_channel::Sender<BulkResult>,
+}
+
+impl Default for EpochRefreshChannels {
+    fn default() -> Self {
+        let (planetoid_tx, planetoid_rx) = async_channel::bounded(1);
+        let (bulk_tx, bulk_rx) = async_channel::unbounded();
+        Self {
+            planetoid_rx,
+            planetoid_tx,
+            bulk_rx,
+            bulk_tx,
+        }
+    }
+}
+
+/// Start a check when one is due.
+fn schedule_epoch_check(
+    mut refresh: ResMut<EpochRefresh>,
+    loader: Res<LoaderState>,
+    tuning: Res<LodTuning>,
+    channels: Res<EpochRefreshChannels>,
+    real_time: Res<Time<Real>>,
+    spawner: TaskSpawner,
+) {
+    if loader.root_bulk.is_none() || loader.client.has_session() {
+        return;
+    }
+    let now = real_time.elapsed_secs_f64();
+    let interval = tuning.epoch_refresh.check_interval_secs;
+    // The planetoid was just loaded; the first check is an interval away.
+    let next_check = *refresh.next_check.get_or_insert(now + interval);
+    let due = interval > 0.0 && now >= next_check;
+    if refresh.checking || !(due || refresh.check_requested) {
+        return;
+    }
+
+    refresh.checking = true;
+    refresh.check_requested = false;
+    refresh.next_check = Some(now + interval);
+    let client = loader.client.clone();
+    let tx = channels.planetoid_tx.clone();
+    spawner.spawn(async move {
+        let _ = tx.send(client.refresh_planetoid().await).await;
+    });
+}
+
+/// Compare a finished check's root epoch with the current one, and queue
+/// the root bulk if it changed.
+fn poll_epoch_check(
+    mut refresh: ResMut<EpochRefresh>,
+    mut loader: ResMut<LoaderState>,
+    channels: Res<EpochRefreshChannels>,
+    real_time: Res<Time<Real>>,
+) {
+    let Ok(result) = channels.planetoid_rx.try_recv() else {
+        return;
+    };
+    let now = real_time.elapsed_secs_f64();
+    refresh.checking = false;
+    refresh.last_checked = Some(now);
+
+    let planetoid = match result {
+        Ok(planetoid) => planetoid,
+        Err(e) => {
+            tracing::warn!("Epoch check failed: {e}");
+            return;
+        }
+    };
+    let Some(current) = &loader.planetoid else {
+        return;
+    };
+    if planetoid.root_epoch == current.root_epoch {
+        tracing::debug!("Epoch check: still at epoch {}", planetoid.root_epoch);
+        return;
+    }
+
+    tracing::info!(
+        "Planetoid epoch changed from {} to {}; refreshing loaded data",
+        current.root_epoch,
+        planetoid.root_epoch
+    );
+    refresh.last_change = Some(EpochChange {
+        from: current.root_epoch,
+        to: planetoid.root_epoch,
+        at: now,
+    });
+    refresh.bulks_refreshed = 0;
+    refresh.nodes_refetched = 0;
+    // Whatever an earlier refresh still had queued is superseded.
+    refresh.pending_bulks.clear();
+    refresh.pending_nodes.clear();
+    refresh
+        .pending_bulks
+        .push_back((OctreePath::ROOT, planetoid.root_epoch));
+    loader.planetoid = Some(planetoid);
+}
+
+/// Swap in refreshed bulks, queue what they changed, and start the next
+/// bulk and node refetches.
+#[allow(clippy::too_many_arguments)]
+fn advance_refresh(
+    mut refresh: ResMut<EpochRefresh>,
+    mut loader: ResMut<LoaderState>,
+    mut lod_state: ResMut<LodState>,
+    mut qos: ResMut<LoadQos>,
+    channels: Res<EpochRefreshChannels>,
+    lod_channels: Res<LodChannels>,
+    tuning: Res<LodTuning>,
+    real_time: Res<Time<Real>>,
+    spawner: TaskSpawner,
+) {
+    while let Ok((path, result)) = channels.bulk_rx.try_recv() {
+        refresh.in_flight_bulks.remove(&path);
+        let bulk = match result {
+            Ok(bulk) => bulk,
+            Err(e) => {
+                tracing::warn!("Epoch refresh: failed to refetch bulk '{path}': {e}");
+                continue;
+            }
+        };
+        if path.is_root() {
+            loader.root_bulk = Some(bulk.clone());
+        }
+        // A bulk evicted meanwhile is fetched afresh if it's wanted again.
+        let Some(old) = lod_state.replace_bulk(path, bulk) else {
+            continue;
+        };
+        let changes = diff_bulks(&old, &lod_state.bulks[&path]);
+        for (child, epoch) in changes.child_bulks {
+            if lod_state.bulks.contains_key(&child) {
+                refresh.pending_bulks.push_back((child, epoch));
+            }
+        }
+        refresh.pending_nodes.extend(
+            changes
+                .nodes
+                .into_iter()
+                .filter(|node| lod_state.is_node_loaded(*node)),
+        );
+        refresh.bulks_refreshed += 1;
+    }
+
+    let max_bulks = tuning.epoch_refresh.max_concurrent_bulks.max(1);
+    while refresh.in_flight_bulks.len() < max_bulks
+        && let Some((path, epoch)) = refresh.pending_bulks.pop_front()
+    {
+        refresh.in_flight_bulks.insert(path);
+        let client = loader.client.clone();
+        let tx = channels.bulk_tx.clone();
+        spawner.spawn(async move {
+            let result = client.fetch_bulk(&BulkRequest::new(path, epoch)).await;
+            let _ = tx.send((path, result)).await;
+        });
+    }
+
+    let now = real_time.elapsed_secs_f64();
+    for _ in 0..tuning.epoch_refresh.max_node_refetches_per_frame {
+        let Some(path) = refresh.pending_nodes.pop_front() else {
+            break;
+        };
+        // Nodes that unloaded meanwhile come back at the new epoch anyway.
+        if refetch_loaded_node(
+            &mut lod_state,
+            &mut qos,
+            &lod_channels,
+            &loader,
+            &spawner,
+            &tuning,
+            path,
+            now,
+        ) {
+            refresh.nodes_refetched += 1;
+        }
+    }
+}
+
+/// What differs between two copies of a bulk.
+#[derive(Debug, Default, PartialEq)]
+struct BulkChanges {
+    /// Nodes whose data or imagery epoch changed, or that are new.
+    nodes: Vec<OctreePath>,
+    /// Child bulks whose epoch changed, as absolute paths with their new
+    /// epochs.
+    child_bulks: Vec<(OctreePath, u32)>,
+}
+
+fn diff_bulks(old: &BulkMetadata, new: &BulkMetadata) -> BulkChanges {
+    let old_nodes: HashMap<OctreePath, (u32, Option<u32>)> = old
+        .nodes
+        .iter()
+        .map(|node| (node.path, (node.epoch, node.imagery_epoch)))
+        .collect();
+    let nodes = new
+        .nodes
+        .iter()
+        .filter(|node| old_nodes.get(&node.path) != Some(&(node.epoch, node.imagery_epoch)))
+        .map(|node| node.path)
+        .collect();
+    let mut child_bulks: Vec<(OctreePath, u32)> = new
+        .child_bulk_paths
+        .iter()
+        .filter(|(relative, epoch)| old.child_bulk_paths.get(*relative) != Some(*epoch))
+        .map(|(relative, epoch)| (new.path.extend(*relative), *epoch))
+        .collect();
+    // Sorted so refreshes run in a stable order.
+    child_bulks.sort_unstable_by_key(|(path, _)| *path);
+    BulkChanges { nodes, child_bulks }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{DMat3, DVec3, Vec3};
+    use rocktree::{NodeMetadata, OrientedBoundingBox};
+
+    use super::*;
+
+    fn path(s: &str) -> OctreePath {
+        OctreePath::parse(s).unwrap()
+    }
+
+    fn node(p: &str, epoch: u32, imagery_epoch: Option<u32>) -> NodeMetadata {
+        NodeMetadata {
+            path: path(p),
+            meters_per_texel: 1.0,
+            obb: OrientedBoundingBox {
+                center: DVec3::ZERO,
+                extents: DVec3::ONE,
+                orientation: DMat3::IDENTITY,
+            },
+            has_data: true,
+            epoch,
+            texture_format: 1,
+            imagery_epoch,
+        }
+    }
+
+    fn bulk(nodes: Vec<NodeMetadata>, children: &[(&str, u32)]) -> BulkMetadata {
+        BulkMetadata {
+            path: path("0123"),
+            head_node_center: Vec3::ZERO,
+            meters_per_texel: Vec::new(),
+            nodes,
+            child_bulk_paths: children
+                .iter()
+                .map(|(relative, epoch)| (path(relative), *epoch))
+                .collect(),
+            epoch: 1,
+        }
+    }
+
+    #[test]
+    fn diff_finds_changed_nodes_and_child_bulks() {
+        let old = bulk(
+            vec![
+                node("01230", 1, None),
+                node("01231", 1, Some(5)),
+                node("01232", 1, None),
+            ],
+            &[("0000", 1), ("1111", 1)],
+        );
+        let new = bulk(
+            vec![
+                node("01230", 1, None),
+                // Imagery-only update.
+                node("01231", 1, Some(6)),
+                node("01232", 2, None),
+                // New node.
+                node("01233", 2, None),
+            ],
+            &[("0000", 1), ("1111", 2), ("2222", 2)],
+        );
+        let changes = diff_bulks(&old, &new);
+        assert_eq!(
+            changes.nodes,
+            vec![path("01231"), path("01232"), path("01233")]
+        );
+        assert_eq!(
+            changes.child_bulks,
+            vec![(path("01231111"), 2), (path("01232222"), 2)]
+        );
+        assert_eq!(diff_bulks(&new, &new), BulkChanges::default());
+    }
+}
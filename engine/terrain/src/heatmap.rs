@@ -0,0 +1,515 @@
+//! Visited-area heat map, for prefetching the user's usual haunts on startup.
+//!
+//! [`VisitHeatmap`] counts, for every bulk at or below
+//! [`MIN_RECORDED_DEPTH`], how many sessions loaded it, and keeps the counts
+//! in `<OS data dir>/veldera/visited_areas.json`. On startup the most visited
+//! bulks and their nodes are fetched into the tile cache in the background,
+//! without decoding, so the first flight over a home city streams from disk
+//! rather than the network.
+//!
+//! Recording is off until the user opts in from the Streaming tab; turning
+//! it off again forgets every entry and leaves only the opt-out in the file.
+//! The web build has no persistent tile cache to warm and records nothing.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use rocktree::{BulkRequest, Client, NodeRequest};
+use rocktree_decode::OctreePath;
+use serde::{Deserialize, Serialize};
+
+use veldera_async::TaskSpawner;
+
+use crate::loader::{LoaderState, TileCache};
+
+/// Shallowest bulk worth recording. Shallower bulks are loaded wherever the
+/// user goes, so they say nothing about where they go.
+pub const MIN_RECORDED_DEPTH: usize = 12;
+
+/// Most bulks kept in the file; the least visited are dropped beyond this.
+const MAX_ENTRIES: usize = 4096;
+
+/// Bulks prefetched on startup.
+const PREFETCH_BULKS: usize = 16;
+
+/// Nodes prefetched on startup, across all bulks.
+const PREFETCH_NODE_BUDGET: usize = 2048;
+
+/// Concurrent prefetch tasks. Kept low so prefetching doesn't crowd out the
+/// LOD system's own requests.
+const PREFETCH_WORKERS: usize = 4;
+
+/// Minimum time between saves while the map is changing (s).
+const SAVE_INTERVAL_SECS: f64 = 30.0;
+
+/// Records visited bulks and prefetches the most visited ones on startup.
+pub struct VisitHeatmapPlugin;
+
+impl Plugin for VisitHeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VisitHeatmap>()
+            .add_systems(Startup, start_prefetch)
+            .add_systems(Last, save_heatmap);
+    }
+}
+
+/// A recorded bulk.
+#[derive(Debug, Clone, Copy)]
+struct Visit {
+    /// Epoch the bulk was last loaded at.
+    epoch: u32,
+    /// Sessions that loaded the bulk.
+    sessions: u32,
+}
+
+/// Progress of the startup prefetch.
+#[derive(Debug, Default)]
+pub struct PrefetchProgress {
+    /// Nodes confirmed to be in the tile cache.
+    warmed: AtomicUsize,
+    /// Of those, nodes that had to be fetched from the network.
+    fetched: AtomicUsize,
+    /// Whether every prefetch task has finished.
+    done: AtomicBool,
+    /// Prefetch tasks still running.
+    running: AtomicUsize,
+}
+
+impl PrefetchProgress {
+    /// Nodes confirmed to be in the tile cache.
+    pub fn warmed(&self) -> usize {
+        self.warmed.load(Ordering::Relaxed)
+    }
+
+    /// Nodes that had to be fetched from the network.
+    pub fn fetched(&self) -> usize {
+        self.fetched.load(Ordering::Relaxed)
+    }
+
+    /// Whether the prefetch has finished (or never started).
+    pub fn done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+/// Per-bulk session counts, persisted across runs.
+#[derive(Resource, Debug)]
+pub struct VisitHeatmap {
+    enabled: bool,
+    entries: HashMap<OctreePath, Visit>,
+    /// Bulks already counted this session.
+    seen: HashSet<OctreePath>,
+    /// Bumped on every change; the save system writes when it moves.
+    revision: u64,
+    path: Option<PathBuf>,
+    error: Option<String>,
+    prefetch: Arc<PrefetchProgress>,
+}
+
+impl Default for VisitHeatmap {
+    fn default() -> Self {
+        let path = heatmap_path();
+        let (saved, error) = match path.as_deref().map(load_heatmap) {
+            Some(Ok(saved)) => (saved, None),
+            Some(Err(e)) => {
+                tracing::warn!("Failed to load visited areas: {e}");
+                (SavedHeatmap::default(), Some(format!("Load failed: {e}")))
+            }
+            None => (SavedHeatmap::default(), None),
+        };
+        let entries = saved
+            .bulks
+            .iter()
+            .filter_map(|bulk| {
+                let path = OctreePath::parse(&bulk.path).ok()?;
+                let visit = Visit {
+                    epoch: bulk.epoch,
+                    sessions: bulk.sessions,
+                };
+                Some((path, visit))
+            })
+            .collect();
+        Self {
+            enabled: saved.enabled,
+            entries,
+            seen: HashSet::new(),
+            revision: 0,
+            path,
+            error,
+            prefetch: Arc::new(PrefetchProgress {
+                done: AtomicBool::new(true),
+                ..default()
+            }),
+        }
+    }
+}
+
+impl VisitHeatmap {
+    /// Whether the map is saved anywhere. False on the web, where the rest
+    /// of the API is inert.
+    pub fn is_persistent(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Whether visits are being recorded.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn recording on or off. Turning it off forgets every entry.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if !enabled {
+            self.entries.clear();
+            self.seen.clear();
+        }
+        self.revision += 1;
+    }
+
+    /// Forget every recorded visit, keeping recording on.
+    pub fn forget(&mut self) {
+        self.entries.clear();
+        self.seen.clear();
+        self.revision += 1;
+    }
+
+    /// Recorded bulks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The last load or save error, for display.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Progress of the startup prefetch.
+    pub fn prefetch(&self) -> &PrefetchProgress {
+        &self.prefetch
+    }
+
+    /// Note that the bulk at `path` loaded at `epoch`. Each bulk counts once
+    /// per session, however often it's evicted and reloaded.
+    pub fn record(&mut self, path: OctreePath, epoch: u32) {
+        if !self.enabled || self.path.is_none() || path.depth() < MIN_RECORDED_DEPTH {
+            return;
+        }
+        if !self.seen.insert(path) {
+            return;
+        }
+        let visit = self
+            .entries
+            .entry(path)
+            .or_insert(Visit { epoch, sessions: 0 });
+        visit.epoch = epoch;
+        visit.sessions = visit.sessions.saturating_add(1);
+        self.revision += 1;
+    }
+
+    /// The `count` most visited bulks, most visited first. Ties go to the
+    /// deeper (more specific) bulk.
+    fn hottest(&self, count: usize) -> Vec<BulkRequest> {
+        let mut bulks: Vec<_> = self.entries.iter().collect();
+        bulks.sort_by(|(a_path, a), (b_path, b)| {
+            b.sessions
+                .cmp(&a.sessions)
+                .then(b_path.depth().cmp(&a_path.depth()))
+                .then(a_path.to_string().cmp(&b_path.to_string()))
+        });
+        bulks
+            .into_iter()
+            .take(count)
+            .map(|(&path, visit)| BulkRequest::new(path, visit.epoch))
+            .collect()
+    }
+
+    /// The map as saved: the most visited [`MAX_ENTRIES`] bulks.
+    fn to_saved(&self) -> SavedHeatmap {
+        let bulks = self
+            .hottest(MAX_ENTRIES)
+            .into_iter()
+            .map(|request| SavedBulk {
+                path: request.path.to_string(),
+                epoch: request.epoch,
+                sessions: self.entries[&request.path].sessions,
+            })
+            .collect();
+        SavedHeatmap {
+            enabled: self.enabled,
+            bulks,
+        }
+    }
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+/// On-disk form of the map.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedHeatmap {
+    enabled: bool,
+    bulks: Vec<SavedBulk>,
+}
+
+impl Default for SavedHeatmap {
+    fn default() -> Self {
+        // Where the user goes is theirs; nothing is recorded until they ask.
+        Self {
+            enabled: false,
+            bulks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBulk {
+    path: String,
+    epoch: u32,
+    sessions: u32,
+}
+
+/// `<OS data dir>/veldera/visited_areas.json`.
+#[cfg(not(target_family = "wasm"))]
+fn heatmap_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("veldera").join("visited_areas.json"))
+}
+
+/// The web build has nowhere to save to.
+#[cfg(target_family = "wasm")]
+fn heatmap_path() -> Option<PathBuf> {
+    None
+}
+
+/// Read the map at `path`; a missing file is an empty map with recording on.
+fn load_heatmap(path: &Path) -> Result<SavedHeatmap, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SavedHeatmap::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Write `saved` to `path`, creating its directory.
+fn write_heatmap(path: &Path, saved: &SavedHeatmap) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(saved).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Write the map when it has changed: at most every [`SAVE_INTERVAL_SECS`]
+/// while visits trickle in, immediately after a toggle or forget, and on
+/// exit.
+fn save_heatmap(
+    mut heatmap: ResMut<VisitHeatmap>,
+    mut exits: MessageReader<AppExit>,
+    real_time: Res<Time<Real>>,
+    mut saved: Local<(u64, f64)>,
+) {
+    let exiting = exits.read().count() > 0;
+    let (saved_revision, saved_at) = *saved;
+    if heatmap.revision == saved_revision {
+        return;
+    }
+    let now = real_time.elapsed_secs_f64();
+    // Toggles and forgets change the map without recording a visit; write
+    // those straight away so an opt-out sticks even if the app then crashes.
+    let cleared = heatmap.entries.is_empty();
+    if !exiting && !cleared && now - saved_at < SAVE_INTERVAL_SECS {
+        return;
+    }
+    *saved = (heatmap.revision, now);
+    let Some(path) = heatmap.path.clone() else {
+        return;
+    };
+    heatmap.error = write_heatmap(&path, &heatmap.to_saved())
+        .inspect_err(|e| tracing::warn!("Failed to save visited areas: {e}"))
+        .err()
+        .map(|e| format!("Save failed: {e}"));
+}
+
+// ============================================================================
+// Prefetch
+// ============================================================================
+
+/// Warm the tile cache with the most visited bulks and their nodes.
+///
+/// Skipped while a session is attached, so captures hold only what the LOD
+/// system asked for and replays stay offline.
+fn start_prefetch(heatmap: Res<VisitHeatmap>, loader: Res<LoaderState>, spawner: TaskSpawner) {
+    if !heatmap.enabled || heatmap.path.is_none() || loader.client.has_session() {
+        return;
+    }
+    let bulks = heatmap.hottest(PREFETCH_BULKS);
+    if bulks.is_empty() {
+        return;
+    }
+    tracing::info!("Prefetching {} most visited areas", bulks.len());
+
+    let progress = Arc::clone(&heatmap.prefetch);
+    progress.done.store(false, Ordering::Relaxed);
+    let workers = PREFETCH_WORKERS.min(bulks.len());
+    progress.running.store(workers, Ordering::Relaxed);
+    let budget = Arc::new(AtomicUsize::new(PREFETCH_NODE_BUDGET));
+    for worker in 0..workers {
+        let share: Vec<_> = bulks
+            .iter()
+            .skip(worker)
+            .step_by(workers)
+            .copied()
+            .collect();
+        let client = Arc::clone(&loader.client);
+        let progress = Arc::clone(&progress);
+        let budget = Arc::clone(&budget);
+        spawner.spawn(async move {
+            for bulk in share {
+                prefetch_bulk(&client, bulk, &progress, &budget).await;
+            }
+            if progress.running.fetch_sub(1, Ordering::Relaxed) == 1 {
+                progress.done.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+/// Warm one bulk and as many of its nodes as `budget` allows, shallowest
+/// first.
+async fn prefetch_bulk(
+    client: &Client<TileCache>,
+    request: BulkRequest,
+    progress: &PrefetchProgress,
+    budget: &AtomicUsize,
+) {
+    // Warming first means the decode below reads from disk.
+    if let Err(e) = client.warm_cache(&client.bulk_url(&request)).await {
+        tracing::debug!("Prefetch: bulk '{}' failed: {e}", request.path);
+        return;
+    }
+    let bulk = match client.fetch_bulk(&request).await {
+        Ok(bulk) => bulk,
+        Err(e) => {
+            tracing::debug!("Prefetch: bulk '{}' failed: {e}", request.path);
+            return;
+        }
+    };
+    let mut nodes: Vec<_> = bulk.nodes.iter().filter(|node| node.has_data).collect();
+    nodes.sort_by_key(|node| node.path.depth());
+    for node in nodes {
+        let claimed = budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+            left.checked_sub(1)
+        });
+        if claimed.is_err() {
+            return;
+        }
+        let request = NodeRequest::new(
+            node.path,
+            node.epoch,
+            node.texture_format,
+            node.imagery_epoch,
+        );
+        match client.warm_cache(&client.node_url(&request)).await {
+            Ok(fetched) => {
+                progress.warmed.fetch_add(1, Ordering::Relaxed);
+                if fetched.is_some() {
+                    progress.fetched.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Err(e) => tracing::debug!("Prefetch: node '{}' failed: {e}", node.path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heatmap() -> VisitHeatmap {
+        VisitHeatmap {
+            enabled: true,
+            entries: HashMap::new(),
+            seen: HashSet::new(),
+            revision: 0,
+            path: Some(PathBuf::from("visited_areas.json")),
+            error: None,
+            prefetch: Arc::default(),
+        }
+    }
+
+    fn path(s: &str) -> OctreePath {
+        OctreePath::parse(s).unwrap()
+    }
+
+    #[test]
+    fn counts_each_bulk_once_per_session() {
+        let mut map = heatmap();
+        let bulk = path("0123456701234567");
+        map.record(bulk, 1);
+        map.record(bulk, 1);
+        assert_eq!(map.entries[&bulk].sessions, 1);
+
+        // A new session counts it again.
+        map.seen.clear();
+        map.record(bulk, 2);
+        assert_eq!(map.entries[&bulk].sessions, 2);
+        assert_eq!(map.entries[&bulk].epoch, 2);
+    }
+
+    #[test]
+    fn ignores_shallow_bulks_and_opt_out() {
+        let mut map = heatmap();
+        map.record(path("0123"), 1);
+        assert!(map.is_empty());
+
+        map.record(path("012345670123"), 1);
+        map.set_enabled(false);
+        assert!(map.is_empty());
+        map.record(path("012345670124"), 1);
+        assert!(map.is_empty());
+        assert!(!map.to_saved().enabled);
+    }
+
+    #[test]
+    fn records_nothing_until_opted_in() {
+        let saved: SavedHeatmap = serde_json::from_str("{}").unwrap();
+        assert!(!saved.enabled);
+        assert!(!SavedHeatmap::default().enabled);
+    }
+
+    #[test]
+    fn hottest_prefers_frequent_then_deep() {
+        let mut map = heatmap();
+        let shallow = path("012345670123");
+        let deep = path("0123456701234567");
+        let rare = path("012345670124");
+        for session in 0..3 {
+            map.seen.clear();
+            map.record(shallow, 1);
+            map.record(deep, 1);
+            if session == 0 {
+                map.record(rare, 1);
+            }
+        }
+        let hottest: Vec<_> = map.hottest(3).iter().map(|r| r.path).collect();
+        assert_eq!(hottest, [deep, shallow, rare]);
+    }
+}
@@ -1,32 +1,89 @@
 //! Streaming terrain for planet-scale Veldera worlds.
 //!
 //! Owns the rocktree level-of-detail pipeline end to end:
+//! - [`ambient_occlusion`] adds screen-space ambient occlusion to the world
+//!   camera, scaled and faded by the terrain material.
+//! - [`area_prefetch`] downloads named areas into the tile cache, resumably.
+//! - [`area_refresh`] reports when the tiles in view were loaded and at which
+//!   epochs, and refetches them on request.
+//! - [`base_map`] paints decoded nodes into an equirectangular image of the
+//!   whole planet, for the proxy globe and the offline base map bake.
+//! - [`decal`] projects decals (scorch marks, paint splats) onto the covering
+//!   terrain tile, re-cutting them as the LOD refines.
+//! - [`detail`] caps the render traversal's depth for the session, and boosts
+//!   the detail around a spot for a while on request.
+//! - [`epoch_refresh`] notices when the planetoid's epoch changes and refreshes
+//!   the loaded data in place.
+//! - [`heatmap`] records which areas the user visits across sessions and
+//!   prefetches the most visited ones into the tile cache on startup.
+//! - [`load_errors`] records failed node and bulk loads and schedules their
+//!   retries.
 //! - [`loader`] bootstraps the planetoid and root bulk metadata.
 //! - [`lod`] walks the octree each frame to decide which nodes to load, render,
 //!   and give physics colliders, driving both the render and physics refinement
 //!   rules from a single traversal.
+//! - [`memory`] accounts the bytes held by the loaded meshes, textures, node
+//!   data, colliders and caches, per subsystem and per LOD level.
 //! - [`mesh`] converts rocktree meshes and textures into Bevy assets.
+//! - [`network`] caps the download rate and implements the metered mode that
+//!   trades detail for data use.
+//! - [`pick`] tracks the terrain under the cursor.
+//! - [`proxy_globe`] draws a coarse, baked stand-in for the whole planet
+//!   beneath the streamed tiles, so the globe is never missing pieces.
+//! - [`qos`] adapts load concurrency and traversal depth to the measured
+//!   request latency and failure rate.
+//! - [`query`] exposes the loaded nodes' bounds, LOD levels and mesh stats to
+//!   analytics tooling and coverage overlays, and exports them as JSON.
+//! - [`raycast`] casts rays against the loaded meshes in double precision, for
+//!   picking, measurement, and line of sight beyond the physics colliders.
 //! - [`terrain_material`] is the octant-masked material that hides vertices in
-//!   octants whose children have loaded, for seamless LOD transitions.
+//!   octants whose children have loaded, for seamless LOD transitions, with an
+//!   optional snow and distance-desaturation stylization.
+//! - [`water`] recognises water in the tile textures and shades it as water,
+//!   with waves and sun glint.
 //!
 //! The crate is gameplay-agnostic: it reads the floating-origin camera from
 //! [`veldera_geo`] and produces colliders via [`veldera_physics`], but knows
 //! nothing about players, vehicles, or camera modes.
 
+pub mod ambient_occlusion;
+pub mod area_prefetch;
+pub mod area_refresh;
+pub mod base_map;
 pub mod collider;
+pub mod decal;
+pub mod detail;
+pub mod epoch_refresh;
+pub mod heatmap;
+pub mod load_errors;
 pub mod loader;
 pub mod lod;
+pub mod memory;
 pub mod mesh;
+pub mod network;
+pub mod pick;
+pub mod proxy_globe;
+pub mod qos;
+pub mod query;
+pub mod raycast;
 pub mod terrain_material;
+pub mod water;
 
 use bevy::app::{PluginGroup, PluginGroupBuilder};
 
-/// The full terrain stack: planetoid loading, the LOD traversal and culling, and
-/// the octant-masked terrain material.
+/// The full terrain stack: planetoid loading, the LOD traversal and culling, the
+/// visited-area and named-area prefetches, the view's tile ages and refresh,
+/// the octant-masked terrain material and its water shading, the proxy globe
+/// beneath it, projected decals, screen-space ambient occlusion, long-range
+/// raycasts, cursor picking, and memory accounting.
 ///
-/// [`LodPlugin`](lod::LodPlugin) loads its tuning config from the default engine
-/// asset path; a host with a different layout adds the constituent plugins
-/// individually instead.
+/// [`LodPlugin`](lod::LodPlugin),
+/// [`TerrainMaterialPlugin`](terrain_material::TerrainMaterialPlugin),
+/// [`WaterPlugin`](water::WaterPlugin),
+/// [`ProxyGlobePlugin`](proxy_globe::ProxyGlobePlugin) and
+/// [`AmbientOcclusionPlugin`](ambient_occlusion::AmbientOcclusionPlugin) load
+/// their configs from the default engine asset paths; a host with a different
+/// layout adds the constituent plugins individually instead.
 pub struct TerrainPlugins;
 
 impl PluginGroup for TerrainPlugins {
@@ -34,6 +91,18 @@ impl PluginGroup for TerrainPlugins {
         PluginGroupBuilder::start::<Self>()
             .add(loader::DataLoaderPlugin)
             .add(lod::LodPlugin::default())
-            .add(terrain_material::TerrainMaterialPlugin)
+            .add(network::NetworkPlugin)
+            .add(epoch_refresh::EpochRefreshPlugin)
+            .add(area_refresh::AreaRefreshPlugin)
+            .add(heatmap::VisitHeatmapPlugin)
+            .add(area_prefetch::AreaPrefetchPlugin)
+            .add(terrain_material::TerrainMaterialPlugin::default())
+            .add(water::WaterPlugin::default())
+            .add(proxy_globe::ProxyGlobePlugin::default())
+            .add(decal::TerrainDecalPlugin)
+            .add(ambient_occlusion::AmbientOcclusionPlugin::default())
+            .add(raycast::TerrainRaycastPlugin)
+            .add(pick::TerrainPickerPlugin)
+            .add(memory::TerrainMemoryPlugin)
     }
 }
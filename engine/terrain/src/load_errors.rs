@@ -0,0 +1,288 @@
+//! Ledger of failed node and bulk loads, with automatic retries.
+//!
+//! Every failed fetch or decode in the LOD system is recorded in
+//! [`LoadErrorLedger`], keyed by request, with running counts per
+//! [`LoadErrorKind`]. A failed node or bulk isn't requested again until the
+//! ledger releases it: transient failures (see
+//! [`rocktree::Error::is_transient`]) are retried automatically with
+//! exponential backoff, up to [`MAX_AUTO_RETRIES`] times; anything else
+//! waits for [`LoadErrorLedger::request_retry`], which the Streaming tab
+//! offers per entry. A successful load clears the entry.
+
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rocktree_decode::OctreePath;
+
+use crate::{lod::LodState, qos::RequestKind};
+
+/// Automatic retries of a transiently failing request before giving up.
+pub const MAX_AUTO_RETRIES: u32 = 4;
+
+/// Delay before the first automatic retry (s); doubles with every attempt.
+const RETRY_BASE_SECS: f64 = 2.0;
+
+/// Most entries kept; the oldest are forgotten beyond this.
+const MAX_ENTRIES: usize = 256;
+
+/// Broad class of a load failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadErrorKind {
+    /// The request didn't complete: connection, DNS, or timeout.
+    Network,
+    /// The server answered with an error status.
+    Status,
+    /// The response couldn't be decoded.
+    Decode,
+    /// The tile cache failed.
+    Cache,
+    /// A replayed session has no such response.
+    Session,
+}
+
+impl LoadErrorKind {
+    pub const ALL: [LoadErrorKind; 5] = [
+        Self::Network,
+        Self::Status,
+        Self::Decode,
+        Self::Cache,
+        Self::Session,
+    ];
+
+    /// Classify a rocktree error.
+    #[must_use]
+    pub fn of(error: &rocktree::Error) -> Self {
+        match error {
+            rocktree::Error::Http { .. } => Self::Network,
+            rocktree::Error::HttpStatus { .. } => Self::Status,
+            rocktree::Error::Protobuf { .. }
+            | rocktree::Error::Decode(_)
+            | rocktree::Error::InvalidData { .. } => Self::Decode,
+            rocktree::Error::Cache { .. } => Self::Cache,
+            rocktree::Error::Session { .. } | rocktree::Error::NotRecorded { .. } => Self::Session,
+        }
+    }
+
+    /// Short label for the UI.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Status => "HTTP status",
+            Self::Decode => "decode",
+            Self::Cache => "cache",
+            Self::Session => "session",
+        }
+    }
+}
+
+/// Where a failed request stands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryState {
+    /// Retried automatically at this real time (s).
+    Scheduled(f64),
+    /// Released for another attempt; waiting for the traversal to request
+    /// it again.
+    Retrying,
+    /// Not retried until asked: a permanent error, or out of automatic
+    /// retries.
+    Manual,
+}
+
+/// A failed request.
+#[derive(Debug, Clone)]
+pub struct LoadError {
+    pub kind: RequestKind,
+    pub path: OctreePath,
+    pub error_kind: LoadErrorKind,
+    /// The latest failure's message.
+    pub message: String,
+    /// Failed attempts so far.
+    pub attempts: u32,
+    /// Real time of the latest failure (s).
+    pub failed_at: f64,
+    pub retry: RetryState,
+}
+
+/// Recent load failures and their retry schedule.
+#[derive(Resource, Default)]
+pub struct LoadErrorLedger {
+    /// Entries by request, oldest first.
+    entries: VecDeque<LoadError>,
+    /// Failures seen this session, per kind.
+    counts: HashMap<LoadErrorKind, u64>,
+}
+
+impl LoadErrorLedger {
+    /// Record a failed request and schedule its retry.
+    pub fn record_failure(
+        &mut self,
+        kind: RequestKind,
+        path: OctreePath,
+        error: &rocktree::Error,
+        now: f64,
+    ) {
+        let error_kind = LoadErrorKind::of(error);
+        *self.counts.entry(error_kind).or_default() += 1;
+
+        let attempts = self
+            .position(kind, path)
+            .and_then(|index| self.entries.remove(index))
+            .map_or(1, |entry| entry.attempts + 1);
+        let retry = if error.is_transient() && attempts <= MAX_AUTO_RETRIES {
+            RetryState::Scheduled(now + RETRY_BASE_SECS * f64::from(1 << (attempts - 1)))
+        } else {
+            RetryState::Manual
+        };
+        self.entries.push_back(LoadError {
+            kind,
+            path,
+            error_kind,
+            message: error.to_string(),
+            attempts,
+            failed_at: now,
+            retry,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Forget a request that has now succeeded.
+    pub fn record_success(&mut self, kind: RequestKind, path: OctreePath) {
+        if let Some(index) = self.position(kind, path) {
+            self.entries.remove(index);
+        }
+    }
+
+    /// Retry a request at the next opportunity, however it failed.
+    pub fn request_retry(&mut self, kind: RequestKind, path: OctreePath) {
+        if let Some(index) = self.position(kind, path) {
+            self.entries[index].retry = RetryState::Scheduled(f64::NEG_INFINITY);
+        }
+    }
+
+    /// Retry every request waiting for a manual retry.
+    pub fn retry_all(&mut self) {
+        for entry in &mut self.entries {
+            if entry.retry == RetryState::Manual {
+                entry.retry = RetryState::Scheduled(f64::NEG_INFINITY);
+            }
+        }
+    }
+
+    /// Release the requests whose retry is due at `now`.
+    fn take_due(&mut self, now: f64) -> Vec<(RequestKind, OctreePath)> {
+        let mut due = Vec::new();
+        for entry in &mut self.entries {
+            if let RetryState::Scheduled(at) = entry.retry
+                && at <= now
+            {
+                entry.retry = RetryState::Retrying;
+                due.push((entry.kind, entry.path));
+            }
+        }
+        due
+    }
+
+    fn position(&self, kind: RequestKind, path: OctreePath) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.kind == kind && entry.path == path)
+    }
+
+    /// Current entries, newest first.
+    pub fn entries(&self) -> impl Iterator<Item = &LoadError> {
+        self.entries.iter().rev()
+    }
+
+    /// Number of current entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no request is currently failing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Failures of `kind` seen this session.
+    #[must_use]
+    pub fn count(&self, kind: LoadErrorKind) -> u64 {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Forget the entries and counts.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.counts.clear();
+    }
+}
+
+/// Hand the requests whose retry is due back to the traversal.
+pub(crate) fn release_due_retries(
+    mut ledger: ResMut<LoadErrorLedger>,
+    mut lod_state: ResMut<LodState>,
+    real_time: Res<Time<Real>>,
+) {
+    for (kind, path) in ledger.take_due(real_time.elapsed_secs_f64()) {
+        lod_state.clear_failure(kind, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(s: &str) -> OctreePath {
+        OctreePath::parse(s).unwrap()
+    }
+
+    fn network_error() -> rocktree::Error {
+        rocktree::Error::Http {
+            url: "https://example.com".to_string(),
+            message: "timed out".to_string(),
+        }
+    }
+
+    #[test]
+    fn transient_failures_back_off_then_give_up() {
+        let mut ledger = LoadErrorLedger::default();
+        let node = path("0123");
+        let mut now = 0.0;
+        for attempt in 1..=MAX_AUTO_RETRIES {
+            ledger.record_failure(RequestKind::Node, node, &network_error(), now);
+            let delay = RETRY_BASE_SECS * f64::from(1 << (attempt - 1));
+            assert!(ledger.take_due(now + delay - 0.1).is_empty());
+            now += delay;
+            assert_eq!(ledger.take_due(now), vec![(RequestKind::Node, node)]);
+        }
+        ledger.record_failure(RequestKind::Node, node, &network_error(), now);
+        let entry = ledger.entries().next().unwrap();
+        assert_eq!(entry.attempts, MAX_AUTO_RETRIES + 1);
+        assert_eq!(entry.retry, RetryState::Manual);
+        assert_eq!(ledger.count(LoadErrorKind::Network), 5);
+        assert_eq!(ledger.len(), 1);
+
+        ledger.request_retry(RequestKind::Node, node);
+        assert_eq!(ledger.take_due(now), vec![(RequestKind::Node, node)]);
+        ledger.record_success(RequestKind::Node, node);
+        assert!(ledger.is_empty());
+    }
+
+    #[test]
+    fn permanent_failures_wait_for_a_manual_retry() {
+        let mut ledger = LoadErrorLedger::default();
+        let error = rocktree::Error::InvalidData {
+            context: "mesh texture",
+            detail: "no textures found".to_string(),
+        };
+        ledger.record_failure(RequestKind::Bulk, path("01"), &error, 0.0);
+        assert_eq!(ledger.count(LoadErrorKind::Decode), 1);
+        assert!(ledger.take_due(1e9).is_empty());
+        ledger.retry_all();
+        assert_eq!(ledger.take_due(0.0), vec![(RequestKind::Bulk, path("01"))]);
+    }
+}
@@ -10,10 +10,12 @@
 use std::sync::Arc;
 
 use bevy::prelude::*;
-use rocktree::{BulkMetadata, BulkRequest, Client, Planetoid};
+use rocktree::{BulkMetadata, BulkRequest, CacheControl, Client, DecodedTextureCache, Planetoid};
 
 use veldera_async::TaskSpawner;
 
+use crate::network::NetworkTuning;
+
 /// The tile cache backing the rocktree client: a persistent on-disk cache on
 /// native, an in-memory cache in the browser (which has no filesystem and
 /// keeps its own HTTP cache anyway).
@@ -37,6 +39,26 @@ fn default_cache() -> TileCache {
     rocktree::MemoryCache::new()
 }
 
+/// How long the cached planetoid metadata is trusted before it's
+/// revalidated (s). It names the root epoch, so a stale copy would keep
+/// serving old imagery from the tile cache.
+const PLANETOID_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// Construct the client with the default tile and decoded-texture caches.
+fn default_client() -> Client<TileCache> {
+    Client::builder()
+        .cache(default_cache())
+        .cache_control(CacheControl::RevalidateUnversioned {
+            max_age: std::time::Duration::from_secs(PLANETOID_MAX_AGE_SECS),
+        })
+        // Sized from the default tuning until the LOD config loads; see
+        // `NetworkTuning::texture_cache_mib`.
+        .texture_cache(DecodedTextureCache::new(
+            NetworkTuning::default().texture_cache_bytes(),
+        ))
+        .build()
+}
+
 /// Plugin for loading Google Earth data.
 pub struct DataLoaderPlugin;
 
@@ -63,13 +85,37 @@ pub struct LoaderState {
 impl Default for LoaderState {
     fn default() -> Self {
         Self {
-            client: Arc::new(Client::with_cache(default_cache())),
+            client: Arc::new(default_client()),
             planetoid: None,
             root_bulk: None,
         }
     }
 }
 
+#[cfg(not(target_family = "wasm"))]
+impl LoaderState {
+    /// Loader state whose client records every response it receives into
+    /// the session directory `dir`, for later replay.
+    pub fn capture_session(dir: impl Into<std::path::PathBuf>) -> rocktree::Result<Self> {
+        let recorder = rocktree::SessionRecorder::create(dir)?;
+        Ok(Self::with_session(rocktree::Session::Capture(recorder)))
+    }
+
+    /// Loader state whose client serves every response from the session
+    /// recorded in `dir` instead of the network or the tile cache.
+    pub fn replay_session(dir: impl Into<std::path::PathBuf>) -> rocktree::Result<Self> {
+        let replay = rocktree::SessionReplay::open(dir)?;
+        Ok(Self::with_session(rocktree::Session::Replay(replay)))
+    }
+
+    fn with_session(session: rocktree::Session) -> Self {
+        Self {
+            client: Arc::new(default_client().with_session(session)),
+            ..default()
+        }
+    }
+}
+
 /// Channels for receiving loaded data from background tasks.
 #[derive(Resource)]
 pub struct LoaderChannels {
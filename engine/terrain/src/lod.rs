@@ -6,8 +6,9 @@
 //! A single octree walk per frame ([`unified_bfs_traversal`]) evaluates
 //! both refinement rules per node:
 //!
-//! - **Render rule** refines on screen-space error and frustum-culls,
-//!   producing renderable nodes and the meshes shown on screen.
+//! - **Render rule** refines by the [`LodRefinement`] strategy (screen-space
+//!   error by default) and frustum-culls, producing renderable nodes and the
+//!   meshes shown on screen.
 //! - **Physics rule** refines on distance-banded target depth (see
 //!   [`PhysicsStreamingConfig::bands`](veldera_physics::PhysicsStreamingConfig))
 //!   with no frustum culling, producing exactly one terrain collider per region
@@ -43,27 +44,36 @@ use std::{
     sync::Arc,
 };
 
-use bevy::{light::NotShadowCaster, prelude::*, reflect::TypePath};
+use bevy::{prelude::*, reflect::TypePath};
 use glam::{DMat4, DVec3};
 use rocktree::{
-    BulkMetadata, BulkRequest, Frustum, LodMetrics, Mesh as RocktreeMesh, Node, NodeMetadata,
-    NodeRequest,
+    BulkMetadata, BulkRequest, DepthRange, Frustum, LodMetrics, Mesh as RocktreeMesh, Node,
+    NodeMetadata, NodeRequest, TextureScale,
 };
 use rocktree_decode::{OctreePath, OrientedBoundingBox};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     collider::{
         self, COLLIDER,
         viz::{
-            ColliderVizFilter, LodVizGizmos, LodVizSettings, configure_lod_viz_gizmos, draw_lod_viz,
+            ColliderVizFilter, LodVizGizmos, LodVizSettings, apply_depth_tint,
+            configure_lod_viz_gizmos, draw_lod_viz,
         },
     },
+    detail::{DetailBoostRequest, DetailBoostTuning, LodDetail, update_lod_detail},
+    epoch_refresh::EpochRefreshTuning,
+    heatmap::VisitHeatmap,
+    load_errors::{LoadErrorLedger, release_due_retries},
     loader::LoaderState,
     mesh::{
         RocktreeMeshMarker, convert_mesh, convert_texture, matrix_to_world_position_and_transform,
+        skirt_offset,
     },
-    terrain_material::{TerrainMaterial, TerrainMaterialExtension},
+    network::NetworkTuning,
+    qos::{LoadQos, QosTuning, RequestKind},
+    query::NodeExportRequest,
+    terrain_material::{TerrainMaterial, TerrainMaterialExtension, TerrainStyle},
 };
 
 // The tile-dump request resource lives in the shared collider core but is
@@ -71,11 +81,14 @@ use crate::{
 // resource is registered unconditionally so the dump button stays wired on
 // every collider path (a no-op on the raw-tiles path, where nothing reads it).
 pub use crate::collider::shared::TileDumpRequest;
+// Re-exported so hosts and the diagnostics UI can configure `LodRefinement`
+// without depending on `rocktree` directly.
+pub use rocktree::RefinementStrategy;
 
-use veldera_async::TaskSpawner;
+use veldera_async::{SpawnedTask, TaskSpawner};
 use veldera_config::ConfigPlugin;
 use veldera_constants::EARTH_RADIUS_M_F64;
-use veldera_geo::floating_origin::FloatingOriginCamera;
+use veldera_geo::floating_origin::{FloatingOriginCamera, WorldPosition};
 use veldera_physics::{
     MotionTracker, PhysicsStreamingConfig, desired_physics_depth, within_innermost_band,
 };
@@ -104,6 +117,18 @@ pub struct LodTuning {
     /// right under the player; kept small so nodes behind the camera aren't
     /// rendered for no benefit.
     pub force_visible_radius: f64,
+    /// Radius around the camera within which loaded nodes stay visible in
+    /// `cull_meshes` even outside the view frustum, so off-screen terrain
+    /// still casts sun shadows into view (m). Bevy's own per-view culling
+    /// keeps them out of the main pass. 0 = off-screen nodes cast nothing.
+    pub shadow_caster_radius: f64,
+    /// Depth of the skirt hung below each tile's seams to hide cracks
+    /// between LOD levels, in the tile's texels (see [`crate::mesh`]). Coarser
+    /// tiles part further from their neighbours, so the skirt scales with
+    /// them. 0 = no skirts.
+    pub skirt_depth_texels: f32,
+    /// Skirt depth floor and ceiling (m).
+    pub skirt_depth_range_m: (f32, f32),
     /// BFS-skip tolerance: camera moves below this distance (m) reuse the
     /// previous frame's traversal instead of re-walking the octree.
     pub bfs_pos_epsilon: f64,
@@ -113,6 +138,61 @@ pub struct LodTuning {
     /// BFS-skip tolerance: lead-vector changes below this length (m) are
     /// treated as unchanged.
     pub bfs_lead_epsilon: f64,
+    /// Load-priority bonus for a node with no loaded ancestor: nothing at
+    /// all is drawn (or collides) there until it arrives.
+    pub hole_priority_bonus: f64,
+    /// Load-priority penalty for a node outside the view frustum (kept
+    /// loaded for turning around, physics, or the focus point).
+    pub offscreen_priority_penalty: f64,
+    /// Resolution tile textures are decoded at. Lower tiers cut decode time
+    /// and texture memory on low-end devices; tiles already loaded keep
+    /// their resolution until they are reloaded.
+    pub texture_quality: TextureQuality,
+    /// Adaptive load concurrency and traversal depth (see [`crate::qos`]).
+    pub qos: QosTuning,
+    /// Bandwidth cap and metered mode (see [`crate::network`]).
+    pub network: NetworkTuning,
+    /// Live refresh after an epoch change (see [`crate::epoch_refresh`]).
+    pub epoch_refresh: EpochRefreshTuning,
+    /// Bounds on a "boost detail here" (see [`crate::detail`]).
+    pub detail_boost: DetailBoostTuning,
+}
+
+impl LodTuning {
+    /// Skirt depth for a tile of `meters_per_texel` (m); 0 when skirts are
+    /// off.
+    pub fn skirt_depth_m(&self, meters_per_texel: f32) -> f32 {
+        if self.skirt_depth_texels <= 0.0 {
+            return 0.0;
+        }
+        let (min, max) = self.skirt_depth_range_m;
+        (meters_per_texel * self.skirt_depth_texels).clamp(min, max.max(min))
+    }
+}
+
+/// Tile texture resolution tier, the viewer-facing side of
+/// [`TextureScale`]. Ordered from finest to coarsest.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextureQuality {
+    /// Full resolution.
+    #[default]
+    Full,
+    /// Half resolution.
+    Half,
+    /// Quarter resolution.
+    Quarter,
+}
+
+impl TextureQuality {
+    /// The decode scale for this tier.
+    pub fn scale(self) -> TextureScale {
+        match self {
+            TextureQuality::Full => TextureScale::Full,
+            TextureQuality::Half => TextureScale::Half,
+            TextureQuality::Quarter => TextureScale::Quarter,
+        }
+    }
 }
 
 /// Plugin for LOD management and frustum culling.
@@ -149,6 +229,29 @@ impl Default for LodPlugin {
 #[derive(Resource, Default)]
 pub struct FreezeLod(pub bool);
 
+/// How the render traversal decides a tile is too coarse for its distance.
+/// Exposed in the Streaming debug tab; a change re-runs the traversal on the
+/// next frame.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct LodRefinement(pub RefinementStrategy);
+
+/// A host-chosen point whose surroundings stay loaded regardless of where the
+/// camera looks.
+///
+/// Tiles whose bounds reach within `radius` of `point` are treated as visible
+/// by the render traversal, exactly like tiles within
+/// [`LodTuning::keep_loaded_radius`] of the camera, so they refine to the
+/// camera's screen-space error and survive leaving the frustum. Used by
+/// showcase cameras that circle a landmark, where otherwise every revolution
+/// would evict and re-stream the far side. `None` disables it.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct LodFocus {
+    /// ECEF point to keep loaded around, if any.
+    pub point: Option<DVec3>,
+    /// Retention radius around `point` (m).
+    pub radius: f64,
+}
+
 impl Plugin for LodPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<LodState>()
@@ -157,11 +260,24 @@ impl Plugin for LodPlugin {
             .init_resource::<LodSnapshotRequest>()
             .init_resource::<LodScratch>()
             .init_resource::<FreezeLod>()
+            .init_resource::<LodRefinement>()
+            .init_resource::<LodFocus>()
+            .init_resource::<LodDetail>()
+            .init_resource::<DetailBoostRequest>()
+            .init_resource::<LoadQos>()
+            .init_resource::<LoadErrorLedger>()
+            .init_resource::<NodeExportRequest>()
+            .init_resource::<NodeReloadRequest>()
+            .init_resource::<TerrainReuploadRequest>()
             .add_plugins(ConfigPlugin::<LodTuning>::new(self.config_path))
             .add_systems(
                 Update,
                 (
+                    process_node_reload_requests,
+                    process_terrain_reupload_requests,
+                    release_due_retries,
                     update_frustum,
+                    update_lod_detail,
                     update_lod_requests,
                     poll_lod_bulk_tasks,
                     poll_lod_node_tasks,
@@ -173,7 +289,8 @@ impl Plugin for LodPlugin {
             .init_resource::<LodVizSettings>()
             .init_gizmo_group::<LodVizGizmos>()
             .add_systems(Startup, configure_lod_viz_gizmos)
-            .add_systems(Update, draw_lod_viz.after(ColliderReconcile));
+            .add_systems(Update, draw_lod_viz.after(ColliderReconcile))
+            .add_systems(PostUpdate, apply_depth_tint);
 
         // The shared overlay wiring (the host-filled `RoadOverlay`, the
         // render-mesh and road overlay filter resources the diagnostics UI reads
@@ -182,6 +299,9 @@ impl Plugin for LodPlugin {
         // state, and its own overlays (see `collider::COLLIDER`).
         collider::shared::register_shared(app);
         collider::register(app);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, crate::query::process_node_export_requests);
     }
 }
 
@@ -232,6 +352,9 @@ pub struct SnapshotNode {
 pub struct SnapshotCounters {
     pub render_loaded: usize,
     pub render_loading: usize,
+    /// Node loads cancelled since startup because they left every potential
+    /// set before completing.
+    pub render_cancelled: u64,
     pub physics_colliders: usize,
     /// Selected collider targets without a live collider entity yet.
     pub physics_pending: usize,
@@ -281,6 +404,24 @@ pub struct LodSnapshotRequest {
     pub wanted: bool,
 }
 
+/// UI → streaming-system request: when `path` is set, the next frame drops
+/// that node's meshes so the traversal fetches and spawns it afresh. Its
+/// cached data stays until the reload lands, so a collider built on it
+/// keeps working. Fetches go through the usual caches.
+#[derive(Resource, Default)]
+pub struct NodeReloadRequest {
+    pub path: Option<OctreePath>,
+}
+
+/// Recovery → streaming-system request: when `wanted` is set, the next frame
+/// rebuilds every loaded node's mesh, texture and material assets from its
+/// retained [`LoadedNodeData`], without refetching anything, and respawns its
+/// entities on them. For when the GPU copies have been lost.
+#[derive(Resource, Default)]
+pub struct TerrainReuploadRequest {
+    pub wanted: bool,
+}
+
 /// Cached data for a loaded node, used for physics collider creation.
 #[derive(Clone)]
 pub struct LoadedNodeData {
@@ -292,9 +433,11 @@ pub struct LoadedNodeData {
     pub transform: Transform,
     /// World position of the node.
     pub world_position: DVec3,
-    /// Meters per texel (LOD metric). Stored for debugging/future use.
-    #[allow(dead_code)]
+    /// Meters per texel (LOD metric), reported by [`crate::query`].
     pub meters_per_texel: f32,
+    /// Real time the data arrived (s), for the tile ages in
+    /// [`crate::area_refresh`].
+    pub loaded_at: f64,
 }
 
 /// State for LOD management.
@@ -302,22 +445,31 @@ pub struct LoadedNodeData {
 pub struct LodState {
     /// Paths of nodes that are currently being loaded.
     loading_nodes: HashSet<OctreePath>,
+    /// Handles of the in-flight node loads, for cancelling the ones no
+    /// traversal wants any more.
+    node_tasks: HashMap<OctreePath, SpawnedTask>,
+    /// Node loads cancelled so far because their paths left every potential
+    /// set.
+    cancelled_node_loads: u64,
     /// Paths of nodes that are currently loaded and rendered.
     pub(crate) loaded_nodes: HashSet<OctreePath>,
     /// Paths of bulks that are currently being loaded.
     loading_bulks: HashSet<OctreePath>,
-    /// Paths of bulks that failed to load (to avoid retrying).
+    /// Paths of bulks that failed to load, held back until the
+    /// [`LoadErrorLedger`] releases them for a retry.
     failed_bulks: HashSet<OctreePath>,
+    /// Paths of nodes that failed to load, likewise.
+    failed_nodes: HashSet<OctreePath>,
     /// Cached bulk metadata by path.
-    bulks: HashMap<OctreePath, BulkMetadata>,
+    pub(crate) bulks: HashMap<OctreePath, BulkMetadata>,
     /// Node OBBs from bulk metadata, keyed by node path.
     pub(crate) node_obbs: HashMap<OctreePath, OrientedBoundingBox>,
     /// Spawned entities per node path, for despawning on unload.
     node_entities: HashMap<OctreePath, Vec<Entity>>,
     /// Current view frustum (updated each frame).
-    frustum: Option<Frustum>,
+    pub(crate) frustum: Option<Frustum>,
     /// Current LOD metrics (updated each frame).
-    lod_metrics: Option<LodMetrics>,
+    pub(crate) lod_metrics: Option<LodMetrics>,
     /// Cached node data for physics collider creation.
     pub(crate) node_data: HashMap<OctreePath, LoadedNodeData>,
     /// Per-bulk node lookup index, keyed by bulk path. Maps a node's
@@ -328,7 +480,7 @@ pub struct LodState {
     /// frontier expansion. With ~639 bulks × ~150 nodes/bulk and frontier
     /// sizes in the thousands per frame, this turns tens of thousands of
     /// per-frame HashMap inserts into amortised zero.
-    bulk_node_indices: HashMap<OctreePath, HashMap<OctreePath, usize>>,
+    pub(crate) bulk_node_indices: HashMap<OctreePath, HashMap<OctreePath, usize>>,
     /// Physics collider entities keyed by node path, with the octant mask
     /// each entity was built with (so mask changes trigger a rebuild). The
     /// single source of truth for "what collider entities exist", written by
@@ -390,6 +542,12 @@ impl LodState {
         self.loaded_nodes.contains(&path)
     }
 
+    /// Get the number of render nodes currently loading.
+    #[must_use]
+    pub fn loading_node_count(&self) -> usize {
+        self.loading_nodes.len()
+    }
+
     /// Get the number of active physics colliders.
     #[must_use]
     pub fn physics_collider_count(&self) -> usize {
@@ -464,6 +622,46 @@ impl LodState {
         }
         self.live_descendant_bits(path) == 0xff
     }
+
+    /// Let the traversal request a failed node or bulk again.
+    pub(crate) fn clear_failure(&mut self, kind: RequestKind, path: OctreePath) {
+        // Bumping the versions invalidates the BFS skip signature, so the
+        // next traversal re-requests the path.
+        match kind {
+            RequestKind::Node => {
+                if self.failed_nodes.remove(&path) {
+                    self.nodes_completed_version = self.nodes_completed_version.wrapping_add(1);
+                }
+            }
+            RequestKind::Bulk => {
+                if self.failed_bulks.remove(&path) {
+                    self.bulks_version = self.bulks_version.wrapping_add(1);
+                }
+            }
+        }
+    }
+
+    /// Swap in a newer copy of a cached bulk, returning the old one, or
+    /// `None` (inserting nothing) if the bulk isn't cached. Cached OBBs of
+    /// its nodes are updated in place, so nothing drops out meanwhile.
+    pub(crate) fn replace_bulk(
+        &mut self,
+        path: OctreePath,
+        bulk: BulkMetadata,
+    ) -> Option<BulkMetadata> {
+        if !self.bulks.contains_key(&path) {
+            return None;
+        }
+        for node in &bulk.nodes {
+            if let Some(obb) = self.node_obbs.get_mut(&node.path) {
+                *obb = node.obb;
+            }
+        }
+        let index = build_bulk_node_index(path, &bulk);
+        self.bulk_node_indices.insert(path, index);
+        self.bulks_version = self.bulks_version.wrapping_add(1);
+        self.bulks.insert(path, bulk)
+    }
 }
 
 /// Channels for receiving loaded data from background tasks.
@@ -576,6 +774,57 @@ pub struct LodScratch {
     /// whether the current frame's BFS can be skipped entirely (camera
     /// hasn't moved, view hasn't rotated, no new bulks loaded, etc.).
     last_bfs_signature: Option<BfsSignature>,
+    /// Node loads waiting for a free slot, highest priority first after
+    /// each frame's re-scoring.
+    load_queue: Vec<QueuedLoad>,
+}
+
+/// A node load waiting in [`LodScratch::load_queue`].
+struct QueuedLoad {
+    node: NodeMetadata,
+    /// Requested by the physics BFS (which gets its own share of slots).
+    physics: bool,
+    /// Score from [`load_priority`] this frame; higher loads sooner.
+    priority: f64,
+}
+
+/// Load priority of `node` for the current view; higher loads sooner.
+///
+/// Combines the node's projected texel error (how much sharper the view gets
+/// when it arrives), its distance (nearer first among similar errors), and
+/// whether it fills a hole: a region with no loaded ancestor shows nothing
+/// at all, which is far worse than showing a coarse parent. The hole bonus
+/// and off-screen penalty come from `tuning`.
+fn load_priority(
+    node: &NodeMetadata,
+    lod_state: &LodState,
+    frustum: &Frustum,
+    lod_metrics: &LodMetrics,
+    tuning: &LodTuning,
+) -> f64 {
+    let distance = lod_metrics
+        .camera_position
+        .distance(node.obb.center)
+        .max(1.0);
+    let error_px = f64::from(node.meters_per_texel) * lod_metrics.pixels_per_meter / distance;
+    let mut priority = error_px.ln_1p() - 0.5 * (distance / 1000.0).ln_1p();
+
+    let mut ancestor = node.path.parent();
+    let mut covered = false;
+    while let Some(path) = ancestor {
+        if lod_state.loaded_nodes.contains(&path) {
+            covered = true;
+            break;
+        }
+        ancestor = path.parent();
+    }
+    if !covered {
+        priority += tuning.hole_priority_bonus;
+    }
+    if !frustum.intersects_obb(&node.obb) {
+        priority -= tuning.offscreen_priority_penalty;
+    }
+    priority
 }
 
 /// Captures the inputs that determine BFS output. If two consecutive
@@ -600,6 +849,12 @@ struct BfsSignature {
     nodes_completed_version: u64,
     /// Render-BFS retention radius — slider changes invalidate.
     keep_loaded_radius: f64,
+    /// Host retention focus — moving or clearing it invalidates.
+    focus: LodFocus,
+    /// Render refinement rule — switching or retuning it invalidates.
+    refinement: RefinementStrategy,
+    /// Depth cap and detail boost — any change invalidates.
+    detail: LodDetail,
 }
 
 impl BfsSignature {
@@ -607,6 +862,9 @@ impl BfsSignature {
         if self.bulks_version != other.bulks_version
             || self.nodes_completed_version != other.nodes_completed_version
             || (self.keep_loaded_radius - other.keep_loaded_radius).abs() > 0.0
+            || self.focus != other.focus
+            || self.refinement != other.refinement
+            || self.detail != other.detail
         {
             return false;
         }
@@ -671,6 +929,8 @@ struct UnifiedWalkCtx<'a> {
     is_low_altitude: bool,
     camera_pos: DVec3,
     lead: DVec3,
+    focus: LodFocus,
+    detail: LodDetail,
 }
 
 /// The pre-branch physics distance bands (m → target depth), used by the
@@ -709,6 +969,8 @@ fn unified_bfs_traversal(
     lod_metrics: LodMetrics,
     camera_pos: DVec3,
     lead: DVec3,
+    focus: LodFocus,
+    detail: LodDetail,
 ) {
     scratch.render_result.clear();
     scratch.physics_result.clear();
@@ -726,6 +988,8 @@ fn unified_bfs_traversal(
         is_low_altitude,
         camera_pos,
         lead,
+        focus,
+        detail,
     };
 
     // The root bulk is always cached at OctreePath::ROOT by `update_lod_requests`.
@@ -839,12 +1103,26 @@ fn unified_walk(
         // -------- render-side decision --------
         let centre_dist = ctx.camera_pos.distance(child_node.obb.center);
         let is_nearby_render = centre_dist <= ctx.tuning.keep_loaded_radius;
+        // Bounding-sphere test, so large ancestors overlapping the focus area
+        // descend even when their centre is far away.
+        let in_focus = ctx.focus.point.is_some_and(|p| {
+            p.distance(child_node.obb.center) <= ctx.focus.radius + child_node.obb.extents.length()
+        });
         let in_frustum = ctx.frustum.intersects_obb(&child_node.obb);
-        let render_visible = in_frustum || (ctx.is_low_altitude && is_nearby_render);
+        let render_visible = in_frustum || (ctx.is_low_altitude && is_nearby_render) || in_focus;
+        let boosted = ctx.detail.boost().is_some_and(|boost| {
+            boost.covers(child_node.obb.center, child_node.obb.extents.length())
+                && ctx
+                    .lod_metrics
+                    .with_strategy(boost.strategy)
+                    .should_refine(child_node.obb.center, child_node.meters_per_texel)
+        });
         let render_should_refine = render_visible
-            && ctx
-                .lod_metrics
-                .should_refine(child_node.obb.center, child_node.meters_per_texel);
+            && ctx.detail.allows_refining(child_path.depth())
+            && (boosted
+                || ctx
+                    .lod_metrics
+                    .should_refine(child_node.obb.center, child_node.meters_per_texel));
 
         // -------- physics-side decision --------
         let phys_dist = effective_distance(&child_node.obb, ctx.camera_pos, ctx.lead);
@@ -1092,6 +1370,11 @@ fn unload_obsolete(
         }
     }
 
+    // Failures out of view don't need holding back any more.
+    lod_state
+        .failed_nodes
+        .retain(|path| retained_nodes.contains(path));
+
     // Drop node_data for paths not retained AND not currently backing a
     // physics collider.
     let stale_node_data: Vec<OctreePath> = lod_state
@@ -1133,11 +1416,90 @@ fn unload_obsolete(
     }
 }
 
+/// Unload the node named by a [`NodeReloadRequest`], if it's loaded, and
+/// invalidate the traversal's skip signature so it's requested again.
+fn process_node_reload_requests(
+    mut request: ResMut<NodeReloadRequest>,
+    mut lod_state: ResMut<LodState>,
+    mut commands: Commands,
+) {
+    let Some(path) = request.path.take() else {
+        return;
+    };
+    if !lod_state.loaded_nodes.remove(&path) {
+        tracing::debug!("LOD: not reloading node '{path}': not loaded");
+        return;
+    }
+    if let Some(entities) = lod_state.node_entities.remove(&path) {
+        for entity in entities {
+            commands.entity(entity).despawn();
+        }
+    }
+    lod_state.nodes_completed_version = lod_state.nodes_completed_version.wrapping_add(1);
+    tracing::info!("LOD: reloading node '{path}'");
+}
+
+/// Respawn every loaded node from its retained data when a
+/// [`TerrainReuploadRequest`] asks, so its meshes and textures are uploaded
+/// to the GPU afresh.
+#[allow(clippy::too_many_arguments)]
+fn process_terrain_reupload_requests(
+    mut request: ResMut<TerrainReuploadRequest>,
+    mut lod_state: ResMut<LodState>,
+    markers: Query<&RocktreeMeshMarker>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    tuning: Res<LodTuning>,
+    terrain_style: Res<TerrainStyle>,
+) {
+    if !std::mem::take(&mut request.wanted) {
+        return;
+    }
+    let lod_state = &mut *lod_state;
+    let mut reuploaded = 0;
+    for &path in &lod_state.loaded_nodes {
+        let Some(data) = lod_state.node_data.get(&path) else {
+            continue;
+        };
+        let old = lod_state.node_entities.remove(&path).unwrap_or_default();
+        let obb = lod_state.node_obbs.get(&path).copied().or_else(|| {
+            old.iter()
+                .find_map(|&entity| markers.get(entity).ok().map(|marker| marker.obb))
+        });
+        let Some(obb) = obb else {
+            lod_state.node_entities.insert(path, old);
+            continue;
+        };
+        for entity in old {
+            commands.entity(entity).despawn();
+        }
+        let entities = spawn_node_meshes(
+            &mut commands,
+            &mut meshes,
+            &mut images,
+            &mut materials,
+            &tuning,
+            &terrain_style,
+            path,
+            obb,
+            data,
+        );
+        lod_state.node_entities.insert(path, entities);
+        reuploaded += 1;
+    }
+    tracing::info!("LOD: re-uploaded {reuploaded} loaded nodes from their retained data");
+}
+
 /// Update the frustum from the camera.
 fn update_frustum(
     mut lod_state: ResMut<LodState>,
     camera_query: Query<(&Transform, &Projection, &FloatingOriginCamera), With<Camera3d>>,
     windows: Query<&Window>,
+    refinement: Res<LodRefinement>,
+    qos: Res<LoadQos>,
+    tuning: Res<LodTuning>,
 ) {
     let Ok((transform, projection, floating_camera)) = camera_query.single() else {
         return;
@@ -1176,7 +1538,7 @@ fn update_frustum(
 
     // Compute view-projection matrix in world space.
     let vp = proj_d * view_d;
-    lod_state.frustum = Some(Frustum::from_matrix(vp));
+    lod_state.frustum = Some(Frustum::from_view_projection(vp, DepthRange::ZeroToOne));
 
     // Camera forward direction in world space. Bevy cameras look down
     // -Z by convention. Used to detect "no rotation since last frame"
@@ -1188,11 +1550,10 @@ fn update_frustum(
         .single()
         .ok()
         .map_or(720.0, |w| f64::from(w.physical_height()));
-    lod_state.lod_metrics = Some(LodMetrics::new(
-        camera_pos_d,
-        f64::from(perspective.fov),
-        screen_height,
-    ));
+    lod_state.lod_metrics = Some(
+        LodMetrics::new(camera_pos_d, f64::from(perspective.fov), screen_height)
+            .with_strategy(tuning.network.apply(qos.apply(refinement.0))),
+    );
 }
 
 /// Update LOD requests using BFS traversal from root.
@@ -1204,14 +1565,17 @@ fn update_frustum(
 fn update_lod_requests(
     mut commands: Commands,
     time: Res<Time>,
+    real_time: Res<Time<Real>>,
     loader_state: Res<LoaderState>,
     mut lod_state: ResMut<LodState>,
     mut scratch: ResMut<LodScratch>,
+    mut qos: ResMut<LoadQos>,
     channels: Res<LodChannels>,
     motion: Res<MotionTracker>,
     tuning: Res<LodTuning>,
     streaming: Res<PhysicsStreamingConfig>,
     freeze: Res<FreezeLod>,
+    (focus, detail): (Res<LodFocus>, Res<LodDetail>),
     mut snapshot_request: ResMut<LodSnapshotRequest>,
     mut snapshot: ResMut<LodSnapshot>,
     spawner: TaskSpawner,
@@ -1250,6 +1614,9 @@ fn update_lod_requests(
         bulks_version: lod_state.bulks_version,
         nodes_completed_version: lod_state.nodes_completed_version,
         keep_loaded_radius: tuning.keep_loaded_radius,
+        focus: *focus,
+        refinement: lod_metrics.strategy,
+        detail: *detail,
     };
     // When frozen, always reuse the previous traversal (as long as one exists),
     // bypassing the signature comparison entirely.
@@ -1285,6 +1652,8 @@ fn update_lod_requests(
             lod_metrics,
             lod_metrics.camera_position,
             motion.lead(),
+            *focus,
+            *detail,
         );
 
         scratch.last_bfs_signature = Some(current_signature);
@@ -1405,55 +1774,102 @@ fn update_lod_requests(
             &retained_bulks,
             &collider_targets,
         );
+        cancel_unwanted_node_loads(&mut lod_state, &mut qos, &scratch, &collider_targets);
     }
 
-    // Limit concurrent loads. Bumped from the original 20 to absorb a
-    // BFS frame's worth of fine-LoD requests in one pass — at 20 we
-    // routinely dropped 40+ excess requests per frame, and only
-    // recovered them through the `nodes_completed_version` BFS re-run
-    // path which trickles in slowly.
-    let max_node_loads: usize = 64;
-    let max_bulk_loads = 16;
-
-    // Split this frame's free load slots between the two queues, with each
-    // side's unused share rolling over to the other. Physics requests are
-    // the collision safety net and must never be starved by a flood of
-    // fine render meshes; equally, a strict physics-first ordering starved
-    // render loads during movement (visible as slow tile pop-in). Drain
-    // the scratch vectors so capacity is reused next frame; HashSet insert
-    // in the filter dedupes any duplicate path either BFS produced.
-    // Disjoint mutable borrows of the two BFS result fields via
-    // destructuring so chained drains compile.
+    // Limit concurrent loads. The caps adapt to the measured latency and
+    // failure rate (see `crate::qos`): a fast link absorbs a BFS frame's
+    // worth of fine-LoD requests in one pass, while a slow one isn't
+    // flooded with requests that only queue behind each other.
+    let now = real_time.elapsed_secs_f64();
+    qos.adjust(&tuning.qos, now);
+    let max_node_loads = qos.node_limit();
+    let max_bulk_loads = qos.bulk_limit();
+
+    // Refresh the load queue. A fresh traversal replaces it outright (its
+    // requests are everything still wanted); on skipped frames the requests
+    // that didn't fit last time keep waiting. Drain the scratch vectors so
+    // capacity is reused next frame; the HashSet dedupes any duplicate path
+    // either BFS produced. Disjoint mutable borrows of the BFS result fields
+    // via destructuring so chained drains compile.
     let LodScratch {
         render_result,
         physics_result,
+        load_queue,
         ..
     } = &mut *scratch;
-    let mut seen_paths: HashSet<OctreePath> = HashSet::new();
-    let physics_nodes: Vec<NodeMetadata> = physics_result
-        .nodes_to_load
-        .drain(..)
-        .filter(|n| seen_paths.insert(n.path))
-        .collect();
-    let render_nodes: Vec<NodeMetadata> = render_result
+    if !can_skip_bfs {
+        load_queue.clear();
+    }
+    let mut seen_paths: HashSet<OctreePath> = load_queue.iter().map(|q| q.node.path).collect();
+    let fresh = physics_result
         .nodes_to_load
         .drain(..)
-        .filter(|n| seen_paths.insert(n.path))
-        .collect();
+        .map(|node| (node, true))
+        .chain(
+            render_result
+                .nodes_to_load
+                .drain(..)
+                .map(|node| (node, false)),
+        );
+    for (node, physics) in fresh {
+        // Failed nodes wait for the ledger to release them.
+        if lod_state.failed_nodes.contains(&node.path) {
+            continue;
+        }
+        if seen_paths.insert(node.path) {
+            load_queue.push(QueuedLoad {
+                node,
+                physics,
+                priority: 0.0,
+            });
+        }
+    }
 
+    // Re-score everything waiting against this frame's view, so the most
+    // useful requests go out first however long the others have waited.
+    for queued in load_queue.iter_mut() {
+        queued.priority = load_priority(&queued.node, &lod_state, &frustum, &lod_metrics, &tuning);
+    }
+    load_queue.sort_unstable_by(|a, b| b.priority.total_cmp(&a.priority));
+
+    // Split this frame's free load slots between the physics and render
+    // requests, with each side's unused share rolling over to the other.
+    // Physics requests are the collision safety net and must never be
+    // starved by a flood of fine render meshes; equally, a strict
+    // physics-first ordering starved render loads during movement (visible
+    // as slow tile pop-in).
+    let physics_queued = load_queue.iter().filter(|q| q.physics).count();
+    let render_queued = load_queue.len() - physics_queued;
     let available = max_node_loads.saturating_sub(lod_state.loading_nodes.len());
-    let physics_take = physics_nodes.len().min(available.div_ceil(2));
-    let render_take = render_nodes.len().min(available - physics_take);
+    if load_queue.len() > available {
+        qos.mark_saturated();
+    }
+    let physics_take = physics_queued.min(available.div_ceil(2));
+    let render_take = render_queued.min(available - physics_take);
     // Roll any unused render share back to physics.
-    let physics_take = physics_nodes.len().min(available - render_take);
+    let mut physics_take = physics_queued.min(available - render_take);
+    let mut render_take = render_take;
 
-    for node_meta in physics_nodes
-        .into_iter()
-        .take(physics_take)
-        .chain(render_nodes.into_iter().take(render_take))
-    {
+    let mut dispatch = Vec::with_capacity(physics_take + render_take);
+    load_queue.retain(|queued| {
+        let take = if queued.physics {
+            &mut physics_take
+        } else {
+            &mut render_take
+        };
+        if *take == 0 {
+            return true;
+        }
+        *take -= 1;
+        dispatch.push(queued.node.clone());
+        false
+    });
+
+    for node_meta in dispatch {
         let path = node_meta.path;
         lod_state.loading_nodes.insert(path);
+        qos.request_started(RequestKind::Node, path, now);
 
         let client = Arc::clone(&loader_state.client);
         let request = NodeRequest::new(
@@ -1461,14 +1877,21 @@ fn update_lod_requests(
             node_meta.epoch,
             node_meta.texture_format,
             node_meta.imagery_epoch,
+        )
+        .with_texture_scale(
+            tuning
+                .network
+                .texture_quality(tuning.texture_quality)
+                .scale(),
         );
 
         let tx = channels.node_tx.clone();
 
-        spawner.spawn(async move {
+        let task = spawner.spawn_cancellable(async move {
             let result = client.fetch_node(&request).await;
             let _ = tx.send((path, result)).await;
         });
+        lod_state.node_tasks.insert(path, task);
     }
 
     // Merge bulk load requests, dedup similarly. `render_result` /
@@ -1483,10 +1906,12 @@ fn update_lod_requests(
 
     for (path, epoch) in merged_bulks {
         if lod_state.loading_bulks.len() >= max_bulk_loads {
+            qos.mark_saturated();
             break;
         }
 
         lod_state.loading_bulks.insert(path);
+        qos.request_started(RequestKind::Bulk, path, now);
 
         let client = Arc::clone(&loader_state.client);
         let request = BulkRequest::new(path, epoch);
@@ -1500,10 +1925,57 @@ fn update_lod_requests(
     }
 }
 
+/// Abort in-flight node loads whose paths have left every traversal's
+/// potential set (a turn-around leaves dozens of them behind), so their
+/// fetches stop and nothing decodes or spawns them.
+///
+/// On native this aborts the Tokio task and with it the HTTP request; on the
+/// web the fetch future is dropped, which aborts the browser request.
+fn cancel_unwanted_node_loads(
+    lod_state: &mut LodState,
+    qos: &mut LoadQos,
+    scratch: &LodScratch,
+    collider_targets: &HashMap<OctreePath, u8>,
+) {
+    let wanted = |path: &OctreePath| {
+        scratch.render_result.potential_nodes.contains(path)
+            || scratch.physics_result.potential_nodes.contains(path)
+            || collider_targets.contains_key(path)
+    };
+    let unwanted: Vec<OctreePath> = lod_state
+        .loading_nodes
+        .iter()
+        .filter(|path| !wanted(path))
+        .copied()
+        .collect();
+    if unwanted.is_empty() {
+        return;
+    }
+
+    for path in &unwanted {
+        lod_state.loading_nodes.remove(path);
+        if let Some(task) = lod_state.node_tasks.remove(path) {
+            task.cancel();
+        }
+        qos.request_cancelled(RequestKind::Node, *path);
+    }
+    lod_state.cancelled_node_loads += unwanted.len() as u64;
+    tracing::debug!("LOD: cancelled {} unwanted node loads", unwanted.len());
+}
+
 /// Poll bulk loading results from channel.
-fn poll_lod_bulk_tasks(mut lod_state: ResMut<LodState>, channels: Res<LodChannels>) {
+fn poll_lod_bulk_tasks(
+    mut lod_state: ResMut<LodState>,
+    mut qos: ResMut<LoadQos>,
+    mut ledger: ResMut<LoadErrorLedger>,
+    mut heatmap: Option<ResMut<VisitHeatmap>>,
+    channels: Res<LodChannels>,
+    real_time: Res<Time<Real>>,
+) {
+    let now = real_time.elapsed_secs_f64();
     while let Ok((path, result)) = channels.bulk_rx.try_recv() {
         lod_state.loading_bulks.remove(&path);
+        qos.request_finished(RequestKind::Bulk, path, now, result.is_ok());
 
         match result {
             Ok(bulk) => {
@@ -1512,6 +1984,10 @@ fn poll_lod_bulk_tasks(mut lod_state: ResMut<LodState>, channels: Res<LodChannel
                     bulk.path,
                     bulk.nodes.len()
                 );
+                if let Some(heatmap) = heatmap.as_mut() {
+                    heatmap.record(path, bulk.epoch);
+                }
+                ledger.record_success(RequestKind::Bulk, path);
                 let index = build_bulk_node_index(path, &bulk);
                 lod_state.bulks.insert(path, bulk);
                 lod_state.bulk_node_indices.insert(path, index);
@@ -1519,12 +1995,59 @@ fn poll_lod_bulk_tasks(mut lod_state: ResMut<LodState>, channels: Res<LodChannel
             }
             Err(e) => {
                 tracing::debug!("LOD: Failed to load bulk '{}': {}", path, e);
+                ledger.record_failure(RequestKind::Bulk, path, &e, now);
                 lod_state.failed_bulks.insert(path);
             }
         }
     }
 }
 
+/// Fetch a loaded node again with its current bulk metadata. It stays
+/// displayed until the new data lands in `poll_lod_node_tasks`, which
+/// replaces its meshes. Returns `false`, starting nothing, if the node isn't
+/// loaded, is already loading, or its bulk isn't cached.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn refetch_loaded_node(
+    lod_state: &mut LodState,
+    qos: &mut LoadQos,
+    channels: &LodChannels,
+    loader_state: &LoaderState,
+    spawner: &TaskSpawner,
+    tuning: &LodTuning,
+    path: OctreePath,
+    now: f64,
+) -> bool {
+    if !lod_state.loaded_nodes.contains(&path) || lod_state.loading_nodes.contains(&path) {
+        return false;
+    }
+    let Some(node_meta) = lod_state.node_metadata(path) else {
+        return false;
+    };
+    let request = NodeRequest::new(
+        path,
+        node_meta.epoch,
+        node_meta.texture_format,
+        node_meta.imagery_epoch,
+    )
+    .with_texture_scale(
+        tuning
+            .network
+            .texture_quality(tuning.texture_quality)
+            .scale(),
+    );
+
+    lod_state.loading_nodes.insert(path);
+    qos.request_started(RequestKind::Node, path, now);
+    let client = Arc::clone(&loader_state.client);
+    let tx = channels.node_tx.clone();
+    let task = spawner.spawn_cancellable(async move {
+        let result = client.fetch_node(&request).await;
+        let _ = tx.send((path, result)).await;
+    });
+    lod_state.node_tasks.insert(path, task);
+    true
+}
+
 /// Build the relative-path → node-index lookup for a freshly loaded bulk.
 ///
 /// `bulk_key` is the bulk's full path (used as the key in
@@ -1543,6 +2066,7 @@ fn build_bulk_node_index(bulk_key: OctreePath, bulk: &BulkMetadata) -> HashMap<O
 }
 
 /// Poll node loading results from channel and spawn meshes.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn poll_lod_node_tasks(
     mut commands: Commands,
     mut lod_state: ResMut<LodState>,
@@ -1550,9 +2074,21 @@ pub(crate) fn poll_lod_node_tasks(
     mut materials: ResMut<Assets<TerrainMaterial>>,
     mut images: ResMut<Assets<Image>>,
     channels: Res<LodChannels>,
+    tuning: Res<LodTuning>,
+    terrain_style: Res<TerrainStyle>,
+    mut qos: ResMut<LoadQos>,
+    mut ledger: ResMut<LoadErrorLedger>,
+    real_time: Res<Time<Real>>,
 ) {
+    let now = real_time.elapsed_secs_f64();
     while let Ok((path, result)) = channels.node_rx.try_recv() {
-        lod_state.loading_nodes.remove(&path);
+        lod_state.node_tasks.remove(&path);
+        // A cancelled load can still deliver if it finished before the abort
+        // landed; drop its result unseen.
+        if !lod_state.loading_nodes.remove(&path) {
+            continue;
+        }
+        qos.request_finished(RequestKind::Node, path, now, result.is_ok());
         // Invalidates the BFS skip signature so requests previously
         // dropped by the per-frame cap get re-queued on the next BFS
         // run.
@@ -1560,6 +2096,8 @@ pub(crate) fn poll_lod_node_tasks(
 
         match result {
             Ok(node) => {
+                let _span = tracing::info_span!("lod_spawn_node", path = %path).entered();
+                ledger.record_success(RequestKind::Node, path);
                 // Look up the real OBB from bulk metadata.
                 let obb = lod_state
                     .node_obbs
@@ -1575,66 +2113,114 @@ pub(crate) fn poll_lod_node_tasks(
 
                 lod_state.loaded_nodes.insert(path);
 
+                // A refetched node (see `refetch_loaded_node`) kept its old
+                // meshes until now; replace them.
+                if let Some(entities) = lod_state.node_entities.remove(&path) {
+                    for entity in entities {
+                        commands.entity(entity).despawn();
+                    }
+                }
+
                 let (world_position, transform) =
                     matrix_to_world_position_and_transform(&node.matrix_globe_from_mesh);
 
-                // Cache node data for physics collider creation.
-                lod_state.node_data.insert(
+                // Cache node data for physics collider creation (and for
+                // re-uploading, see `TerrainReuploadRequest`).
+                let data = LoadedNodeData {
+                    meshes: Arc::new(node.meshes),
+                    transform,
+                    world_position: world_position.position,
+                    meters_per_texel: node.meters_per_texel,
+                    loaded_at: now,
+                };
+                let entities = spawn_node_meshes(
+                    &mut commands,
+                    &mut meshes,
+                    &mut images,
+                    &mut materials,
+                    &tuning,
+                    &terrain_style,
                     path,
-                    LoadedNodeData {
-                        meshes: Arc::new(node.meshes.clone()),
-                        transform,
-                        world_position: world_position.position,
-                        meters_per_texel: node.meters_per_texel,
-                    },
+                    obb,
+                    &data,
                 );
-
-                // Spawn mesh entities and track them for later despawning.
-                let entities = lod_state.node_entities.entry(path).or_default();
-                for rocktree_mesh in &node.meshes {
-                    let mesh = convert_mesh(rocktree_mesh);
-                    let texture = convert_texture(rocktree_mesh);
-
-                    let mesh_handle = meshes.add(mesh);
-                    let texture_handle = images.add(texture);
-
-                    let material = materials.add(TerrainMaterial {
-                        base: StandardMaterial {
-                            base_color_texture: Some(texture_handle),
-                            // Disable specular reflections for terrain.
-                            reflectance: 0.0,
-                            ..default()
-                        },
-                        extension: TerrainMaterialExtension {
-                            octant_mask: UVec4::ZERO,
-                        },
-                    });
-
-                    let entity = commands
-                        .spawn((
-                            Mesh3d(mesh_handle),
-                            MeshMaterial3d(material),
-                            transform,
-                            world_position.clone(),
-                            RocktreeMeshMarker {
-                                path: node.path,
-                                obb,
-                                meters_per_texel: node.meters_per_texel,
-                            },
-                            // Terrain receives shadows but doesn't cast them.
-                            NotShadowCaster,
-                        ))
-                        .id();
-                    entities.push(entity);
-                }
+                lod_state.node_data.insert(path, data);
+                lod_state.node_entities.insert(path, entities);
             }
             Err(e) => {
                 tracing::warn!("LOD: Failed to load node '{}': {}", path, e);
+                ledger.record_failure(RequestKind::Node, path, &e, now);
+                lod_state.failed_nodes.insert(path);
             }
         }
     }
 }
 
+/// Convert a node's meshes and textures into fresh assets and spawn an entity
+/// for each, returning the entities.
+#[allow(clippy::too_many_arguments)]
+fn spawn_node_meshes(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<TerrainMaterial>,
+    tuning: &LodTuning,
+    terrain_style: &TerrainStyle,
+    path: OctreePath,
+    obb: OrientedBoundingBox,
+    data: &LoadedNodeData,
+) -> Vec<Entity> {
+    let skirt = skirt_offset(
+        &data.transform,
+        data.world_position,
+        tuning.skirt_depth_m(data.meters_per_texel),
+    );
+
+    let mut entities = Vec::with_capacity(data.meshes.len());
+    for rocktree_mesh in data.meshes.iter() {
+        let (mesh, texture) = tracing::info_span!("lod_convert").in_scope(|| {
+            (
+                convert_mesh(rocktree_mesh, skirt),
+                convert_texture(rocktree_mesh),
+            )
+        });
+
+        // Queues the assets for Bevy's render-world upload
+        // (`prepare_assets` system spans cover the GPU side).
+        let upload = tracing::info_span!("lod_upload").entered();
+        let mesh_handle = meshes.add(mesh);
+        let texture_handle = images.add(texture);
+
+        let material = materials.add(TerrainMaterial {
+            base: StandardMaterial {
+                base_color_texture: Some(texture_handle),
+                // Disable specular reflections for terrain.
+                reflectance: 0.0,
+                ..default()
+            },
+            extension: TerrainMaterialExtension::new(data.world_position, terrain_style),
+        });
+        drop(upload);
+
+        let _spawn = tracing::info_span!("lod_spawn").entered();
+        let entity = commands
+            .spawn((
+                Mesh3d(mesh_handle),
+                MeshMaterial3d(material),
+                data.transform,
+                WorldPosition::from_dvec3(data.world_position),
+                RocktreeMeshMarker {
+                    path,
+                    obb,
+                    meters_per_texel: data.meters_per_texel,
+                },
+            ))
+            .id();
+        entities.push(entity);
+    }
+    entities
+}
+
 /// Cull meshes based on frustum visibility and update per-vertex octant masks.
 ///
 /// Uses the real OBB from bulk metadata (stored on each mesh entity) for
@@ -1673,13 +2259,14 @@ fn cull_meshes(
     let camera_pos = lod_state.lod_metrics.map(|m| m.camera_position);
 
     for (marker, material_handle, mut visibility) in &mut query {
-        // Check frustum visibility, with proximity exception.
+        // Check frustum visibility, with proximity and shadow-caster exceptions.
         let in_frustum = frustum.intersects_obb(&marker.obb);
         let force_visible = camera_pos.is_some_and(|cam_pos| {
             let altitude = cam_pos.length() - EARTH_RADIUS_M_F64;
             let distance = cam_pos.distance(marker.obb.center);
-            altitude <= tuning.proximity_loading_max_altitude
-                && distance <= tuning.force_visible_radius
+            (altitude <= tuning.proximity_loading_max_altitude
+                && distance <= tuning.force_visible_radius)
+                || distance <= tuning.shadow_caster_radius
         });
 
         if !in_frustum && !force_visible {
@@ -1827,6 +2414,171 @@ fn populate_snapshot(
 
     counters.render_loaded = lod_state.loaded_nodes.len();
     counters.render_loading = lod_state.loading_nodes.len();
+    counters.render_cancelled = lod_state.cancelled_node_loads;
 
     snapshot.counters = counters;
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::DMat3;
+    use rocktree::{TextureFormat, UvTransform, Vertex};
+
+    use super::*;
+
+    fn path(s: &str) -> OctreePath {
+        OctreePath::parse(s).unwrap()
+    }
+
+    /// One textured triangle, as a node's retained data holds it.
+    fn node_data() -> LoadedNodeData {
+        let vertex = |x, y| Vertex { x, y, ..default() };
+        LoadedNodeData {
+            meshes: Arc::new(vec![RocktreeMesh {
+                vertices: vec![vertex(0, 0), vertex(1, 0), vertex(0, 1)],
+                indices: vec![0, 1, 2],
+                uv_transform: UvTransform::default(),
+                normals: Vec::new(),
+                texture_data: vec![255; 3],
+                texture_format: TextureFormat::Rgb,
+                texture_width: 1,
+                texture_height: 1,
+                has_octant_data: false,
+            }]),
+            transform: Transform::IDENTITY,
+            world_position: DVec3::new(EARTH_RADIUS_M_F64, 0.0, 0.0),
+            meters_per_texel: 1.0,
+            loaded_at: 0.0,
+        }
+    }
+
+    /// An app with a bare terrain state and asset stores.
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<LodState>()
+            .init_resource::<LodTuning>()
+            .init_resource::<TerrainStyle>()
+            .init_resource::<TerrainReuploadRequest>()
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<Assets<TerrainMaterial>>();
+        app
+    }
+
+    #[test]
+    fn reupload_respawns_loaded_nodes_from_retained_data() {
+        let mut app = app();
+        app.add_systems(Update, process_terrain_reupload_requests);
+        let node = path("02");
+        let stale = app.world_mut().spawn(Mesh3d(Handle::default())).id();
+        {
+            let mut lod_state = app.world_mut().resource_mut::<LodState>();
+            lod_state.loaded_nodes.insert(node);
+            lod_state.node_data.insert(node, node_data());
+            lod_state.node_entities.insert(node, vec![stale]);
+            lod_state.node_obbs.insert(
+                node,
+                OrientedBoundingBox {
+                    center: DVec3::new(EARTH_RADIUS_M_F64, 0.0, 0.0),
+                    extents: DVec3::splat(50.0),
+                    orientation: DMat3::IDENTITY,
+                },
+            );
+        }
+
+        // Nothing happens until asked.
+        app.update();
+        assert!(app.world().get_entity(stale).is_ok());
+
+        app.world_mut()
+            .resource_mut::<TerrainReuploadRequest>()
+            .wanted = true;
+        app.update();
+
+        assert!(app.world().get_entity(stale).is_err());
+        let lod_state = app.world().resource::<LodState>();
+        let &[fresh] = lod_state.node_entities[&node].as_slice() else {
+            panic!("expected one entity per retained mesh");
+        };
+        let mesh = app.world().get::<Mesh3d>(fresh).unwrap();
+        assert!(app.world().resource::<Assets<Mesh>>().contains(&mesh.0));
+        assert_eq!(app.world().resource::<Assets<Image>>().len(), 1);
+        assert_eq!(
+            app.world().get::<RocktreeMeshMarker>(fresh).unwrap().path,
+            node
+        );
+        // Rebuilt from what was kept: nothing was fetched, and the data stays
+        // for the next rebuild.
+        assert!(lod_state.loading_nodes.is_empty());
+        assert!(lod_state.node_data.contains_key(&node));
+        assert!(!app.world().resource::<TerrainReuploadRequest>().wanted);
+    }
+    /// A fetched node for `path` holding [`node_data`]'s triangle, as the
+    /// loader delivers it.
+    fn fetched_node(path: OctreePath) -> Node {
+        let data = node_data();
+        Node {
+            path,
+            matrix_globe_from_mesh: DMat4::from_translation(data.world_position),
+            meters_per_texel: data.meters_per_texel,
+            obb: OrientedBoundingBox {
+                center: data.world_position,
+                extents: DVec3::splat(50.0),
+                orientation: DMat3::IDENTITY,
+            },
+            meshes: data.meshes.as_ref().clone(),
+        }
+    }
+
+    #[test]
+    fn loads_leaving_every_potential_set_are_cancelled_and_dropped() {
+        let mut app = app();
+        app.init_resource::<LodChannels>()
+            .init_resource::<LoadErrorLedger>()
+            .init_resource::<Time<Real>>()
+            .add_systems(Update, poll_lod_node_tasks);
+
+        // One in-flight load wanted by each traversal, and one nobody wants.
+        let [render, physics, collider, stale] = ["02", "03", "12", "13"].map(path);
+        let mut scratch = LodScratch::default();
+        scratch.render_result.potential_nodes.insert(render);
+        scratch.physics_result.potential_nodes.insert(physics);
+        let collider_targets = HashMap::from([(collider, 0)]);
+        let mut lod_state = LodState::default();
+        let mut qos = LoadQos::default();
+        for node in [render, physics, collider, stale] {
+            lod_state.loading_nodes.insert(node);
+            qos.request_started(RequestKind::Node, node, 0.0);
+        }
+
+        cancel_unwanted_node_loads(&mut lod_state, &mut qos, &scratch, &collider_targets);
+        assert_eq!(
+            lod_state.loading_nodes,
+            HashSet::from([render, physics, collider])
+        );
+        assert_eq!(lod_state.cancelled_node_loads, 1);
+        app.insert_resource(lod_state).insert_resource(qos);
+
+        // The stale fetch finished before the abort landed, so its result
+        // still arrives, alongside a wanted one.
+        let channels = app.world().resource::<LodChannels>();
+        for node in [stale, render] {
+            channels
+                .node_tx
+                .try_send((node, Ok(fetched_node(node))))
+                .unwrap();
+        }
+        app.update();
+
+        let lod_state = app.world().resource::<LodState>();
+        assert!(lod_state.loaded_nodes.contains(&render));
+        assert!(lod_state.node_entities.contains_key(&render));
+        assert!(!lod_state.loaded_nodes.contains(&stale));
+        assert!(!lod_state.node_data.contains_key(&stale));
+        assert!(!lod_state.node_entities.contains_key(&stale));
+        assert_eq!(lod_state.loading_nodes, HashSet::from([physics, collider]));
+        // Only the wanted node was decoded into assets.
+        assert_eq!(app.world().resource::<Assets<Mesh>>().len(), 1);
+        assert_eq!(app.world().resource::<Assets<Image>>().len(), 1);
+    }
+}
@@ -0,0 +1,282 @@
+//! Byte accounting for what the terrain holds, per subsystem and per LOD
+//! level.
+//!
+//! Every [`MEMORY_REFRESH_SECS`] the [`TerrainMemory`] report is rebuilt from
+//! the live data, so it can't drift from what's actually allocated:
+//!
+//! - **Render meshes** and **render textures** are the loaded tiles' vertex,
+//!   index and texel data as uploaded to the GPU. Bevy keeps a main-world copy
+//!   of each as well, so they count against both VRAM and RAM.
+//! - **Node data** is the decoded rocktree meshes the streamer retains per
+//!   loaded node, for colliders, raycasts, decals and re-uploads.
+//! - **Colliders** are the terrain trimeshes' vertices and triangles (parry's
+//!   acceleration structure on top is not counted), per tile or, for the
+//!   camera-centred collider, undivided by depth.
+//! - **Texture cache** is the decoded-texture LRU behind node loads.
+//! - **Bulk metadata** is the cached bulks' node records.
+//!
+//! The first four are also broken down by node depth. Sizes are of the
+//! payloads; allocator and driver overheads aren't visible from here.
+
+use std::collections::BTreeMap;
+
+use avian3d::prelude::Collider;
+use bevy::prelude::*;
+use rocktree_decode::OctreePath;
+use veldera_physics::TerrainCollider;
+
+use crate::{
+    collider::camera_centred::ColliderV4, loader::LoaderState, lod::LodState,
+    mesh::RocktreeMeshMarker, query::NodeStats, terrain_material::TerrainMaterial,
+};
+
+/// Time between rebuilds of the report (s).
+pub const MEMORY_REFRESH_SECS: f64 = 1.0;
+
+/// Plugin that keeps [`TerrainMemory`] up to date.
+pub struct TerrainMemoryPlugin;
+
+impl Plugin for TerrainMemoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainMemory>()
+            .add_systems(Update, update_terrain_memory);
+    }
+}
+
+/// Where terrain memory goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryCategory {
+    RenderMeshes,
+    RenderTextures,
+    NodeData,
+    Colliders,
+    TextureCache,
+    BulkMetadata,
+}
+
+impl MemoryCategory {
+    /// Every category, in display order.
+    pub const ALL: [Self; 6] = [
+        Self::RenderMeshes,
+        Self::RenderTextures,
+        Self::NodeData,
+        Self::Colliders,
+        Self::TextureCache,
+        Self::BulkMetadata,
+    ];
+
+    /// Display name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RenderMeshes => "Render meshes",
+            Self::RenderTextures => "Render textures",
+            Self::NodeData => "Node data",
+            Self::Colliders => "Colliders",
+            Self::TextureCache => "Texture cache",
+            Self::BulkMetadata => "Bulk metadata",
+        }
+    }
+
+    /// Whether the bytes also live in video memory.
+    pub fn on_gpu(self) -> bool {
+        matches!(self, Self::RenderMeshes | Self::RenderTextures)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Bytes and item count of one category.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub bytes: u64,
+    /// Meshes, textures, nodes, colliders, cache entries or bulks.
+    pub items: usize,
+}
+
+impl MemoryUsage {
+    fn add(&mut self, bytes: u64) {
+        self.bytes += bytes;
+        self.items += 1;
+    }
+}
+
+/// The latest terrain memory report.
+#[derive(Resource, Default)]
+pub struct TerrainMemory {
+    totals: [MemoryUsage; MemoryCategory::ALL.len()],
+    by_depth: BTreeMap<usize, [MemoryUsage; MemoryCategory::ALL.len()]>,
+    /// Real time of the last rebuild (s), if there's been one.
+    updated_at: Option<f64>,
+}
+
+impl TerrainMemory {
+    /// Usage of `category` across all depths.
+    pub fn total(&self, category: MemoryCategory) -> MemoryUsage {
+        self.totals[category.index()]
+    }
+
+    /// Bytes held in RAM, counting Bevy's main-world copies of the GPU data.
+    pub fn ram_bytes(&self) -> u64 {
+        self.totals.iter().map(|usage| usage.bytes).sum()
+    }
+
+    /// Bytes uploaded to the GPU.
+    pub fn vram_bytes(&self) -> u64 {
+        MemoryCategory::ALL
+            .into_iter()
+            .filter(|category| category.on_gpu())
+            .map(|category| self.total(category).bytes)
+            .sum()
+    }
+
+    /// Usage of `category` at each node depth that has any, shallowest
+    /// first. Caches and bulk metadata aren't broken down.
+    pub fn by_depth(
+        &self,
+        category: MemoryCategory,
+    ) -> impl Iterator<Item = (usize, MemoryUsage)> + '_ {
+        self.by_depth
+            .iter()
+            .map(move |(&depth, usages)| (depth, usages[category.index()]))
+    }
+
+    /// Node depths with any usage, shallowest first.
+    pub fn depths(&self) -> impl Iterator<Item = usize> + '_ {
+        self.by_depth.keys().copied()
+    }
+
+    /// Real time of the last rebuild (s), if there's been one.
+    pub fn updated_at(&self) -> Option<f64> {
+        self.updated_at
+    }
+
+    fn record(&mut self, category: MemoryCategory, depth: Option<usize>, bytes: u64) {
+        self.totals[category.index()].add(bytes);
+        if let Some(depth) = depth {
+            self.by_depth.entry(depth).or_default()[category.index()].add(bytes);
+        }
+    }
+}
+
+/// Rebuild the report every [`MEMORY_REFRESH_SECS`].
+#[allow(clippy::too_many_arguments)]
+fn update_terrain_memory(
+    mut memory: ResMut<TerrainMemory>,
+    tiles: Query<(
+        &RocktreeMeshMarker,
+        &Mesh3d,
+        &MeshMaterial3d<TerrainMaterial>,
+    )>,
+    colliders: Query<
+        (Option<&TerrainCollider>, &Collider),
+        Or<(With<TerrainCollider>, With<ColliderV4>)>,
+    >,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<TerrainMaterial>>,
+    images: Res<Assets<Image>>,
+    lod_state: Res<LodState>,
+    loader: Res<LoaderState>,
+    real_time: Res<Time<Real>>,
+) {
+    let now = real_time.elapsed_secs_f64();
+    if memory
+        .updated_at
+        .is_some_and(|at| now - at < MEMORY_REFRESH_SECS)
+    {
+        return;
+    }
+    let mut report = TerrainMemory {
+        updated_at: Some(now),
+        ..default()
+    };
+
+    for (marker, mesh, material) in &tiles {
+        let depth = Some(marker.path.depth());
+        if let Some(mesh) = meshes.get(&mesh.0) {
+            report.record(MemoryCategory::RenderMeshes, depth, mesh_bytes(mesh));
+        }
+        if let Some(image) = materials
+            .get(&material.0)
+            .and_then(|material| material.base.base_color_texture.as_ref())
+            .and_then(|texture| images.get(texture))
+        {
+            let bytes = image.data.as_ref().map_or(0, Vec::len) as u64;
+            report.record(MemoryCategory::RenderTextures, depth, bytes);
+        }
+    }
+
+    for (path, data) in &lod_state.node_data {
+        let stats = NodeStats::from_meshes(&data.meshes);
+        let bytes = (stats.texture_bytes + stats.geometry_bytes) as u64;
+        report.record(MemoryCategory::NodeData, Some(path.depth()), bytes);
+    }
+
+    for (terrain, collider) in &colliders {
+        let Some(trimesh) = collider.shape().as_trimesh() else {
+            continue;
+        };
+        let bytes =
+            std::mem::size_of_val(trimesh.vertices()) + std::mem::size_of_val(trimesh.indices());
+        let depth = terrain.map(|terrain| terrain.path.depth());
+        report.record(MemoryCategory::Colliders, depth, bytes as u64);
+    }
+
+    if let Some(cache) = loader.client.texture_cache() {
+        let stats = cache.stats();
+        report.totals[MemoryCategory::TextureCache.index()] = MemoryUsage {
+            bytes: stats.bytes as u64,
+            items: stats.entries,
+        };
+    }
+
+    for bulk in lod_state.bulks.values() {
+        let bytes = std::mem::size_of_val(bulk.nodes.as_slice())
+            + bulk.child_bulk_paths.len() * std::mem::size_of::<(OctreePath, u32)>();
+        report.record(MemoryCategory::BulkMetadata, None, bytes as u64);
+    }
+
+    *memory = report;
+}
+
+/// Vertex and index buffer bytes of a mesh.
+fn mesh_bytes(mesh: &Mesh) -> u64 {
+    let vertices = mesh.count_vertices() as u64 * mesh.get_vertex_size();
+    let indices = mesh.indices().map_or(0, |indices| match indices {
+        bevy::mesh::Indices::U16(indices) => indices.len() * 2,
+        bevy::mesh::Indices::U32(indices) => indices.len() * 4,
+    });
+    vertices + indices as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_add_up_by_category_and_depth() {
+        let mut memory = TerrainMemory::default();
+        memory.record(MemoryCategory::RenderMeshes, Some(12), 100);
+        memory.record(MemoryCategory::RenderMeshes, Some(14), 50);
+        memory.record(MemoryCategory::RenderTextures, Some(12), 400);
+        memory.record(MemoryCategory::BulkMetadata, None, 7);
+
+        let meshes = memory.total(MemoryCategory::RenderMeshes);
+        assert_eq!((meshes.bytes, meshes.items), (150, 2));
+        assert_eq!(memory.vram_bytes(), 550);
+        assert_eq!(memory.ram_bytes(), 557);
+        assert_eq!(memory.depths().collect::<Vec<_>>(), [12, 14]);
+        let at_12: Vec<_> = memory.by_depth(MemoryCategory::RenderTextures).collect();
+        assert_eq!(
+            at_12[0],
+            (
+                12,
+                MemoryUsage {
+                    bytes: 400,
+                    items: 1
+                }
+            )
+        );
+    }
+}
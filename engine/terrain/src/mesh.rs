@@ -2,6 +2,20 @@
 //!
 //! Converts rocktree mesh data (packed vertices, triangle strips) to Bevy's
 //! mesh format (positions, normals, UVs, triangle lists).
+//!
+//! Adjacent tiles at different LOD levels don't share their border vertices,
+//! so their edges can part by a few metres and let the sky show through. Each
+//! converted mesh therefore gets a skirt: every seam edge is extruded straight
+//! down, so a gap looks into the skirt's stretched border texels instead.
+//! Seams are the tile's border plus the borders between its octants, since
+//! masking an octant opens the same kind of gap against the child tiles that
+//! replace it. Skirt vertices keep their source vertex's octant, so a masked
+//! octant's skirt collapses with it.
+//!
+//! Each vertex also carries its [water likeness](crate::water) from the
+//! texture around it, for the terrain shader's ocean shading.
+
+use std::collections::HashMap;
 
 use bevy::{
     asset::RenderAssetUsages,
@@ -10,21 +24,25 @@ use bevy::{
 };
 use rocktree::{Mesh as RocktreeMesh, TextureFormat};
 
+use crate::water::TextureBlocks;
+
 /// Convert a rocktree mesh to a Bevy mesh.
 ///
 /// The mesh vertices are in mesh-local coordinates (0-255 range).
 /// Apply the node's `matrix_globe_from_mesh` transform to position correctly.
-pub fn convert_mesh(rocktree_mesh: &RocktreeMesh) -> Mesh {
+/// `skirt_offset` is the mesh-local displacement of the skirt's bottom edge
+/// (see [`skirt_offset`]); zero leaves the skirt out.
+pub fn convert_mesh(rocktree_mesh: &RocktreeMesh, skirt_offset: Vec3) -> Mesh {
     let vertices = &rocktree_mesh.vertices;
     let uv_transform = &rocktree_mesh.uv_transform;
 
     // Convert packed vertices to separate position and UV arrays.
-    let positions: Vec<[f32; 3]> = vertices
+    let mut positions: Vec<[f32; 3]> = vertices
         .iter()
         .map(|v| [f32::from(v.x), f32::from(v.y), f32::from(v.z)])
         .collect();
 
-    let uvs: Vec<[f32; 2]> = vertices
+    let mut uvs: Vec<[f32; 2]> = vertices
         .iter()
         .map(|v| {
             // Apply UV transform: uv = (texcoord + offset) * scale.
@@ -35,21 +53,59 @@ pub fn convert_mesh(rocktree_mesh: &RocktreeMesh) -> Mesh {
         .collect();
 
     // Convert triangle strip indices to triangle list.
-    let triangle_indices = strip_to_triangles(&rocktree_mesh.indices);
+    let mut triangle_indices = strip_to_triangles(&rocktree_mesh.indices);
 
     // Per-vertex octant index (0-7) stored in the red channel of vertex color.
     // Used by the shader to mask vertices whose octant has a loaded child.
     // When octant data is missing, use 255 as a sentinel so the shader never
     // masks these vertices (bit 255 % 32 = bit 31 is never set in octant_mask).
+    // The green channel holds the water likeness.
     let octant_sentinel = if rocktree_mesh.has_octant_data {
         None
     } else {
         Some(255.0)
     };
-    let colors: Vec<[f32; 4]> = vertices
+    let texture_blocks = TextureBlocks::new(rocktree_mesh);
+    let mut colors: Vec<[f32; 4]> = vertices
         .iter()
-        .map(|v| [octant_sentinel.unwrap_or(f32::from(v.w)), 0.0, 0.0, 1.0])
+        .zip(&uvs)
+        .map(|(v, &uv)| {
+            let water = texture_blocks
+                .as_ref()
+                .map_or(0.0, |blocks| blocks.water_likeness(uv));
+            [octant_sentinel.unwrap_or(f32::from(v.w)), water, 0.0, 1.0]
+        })
         .collect();
+    let mut normals = rocktree_mesh.normals.clone();
+
+    if skirt_offset != Vec3::ZERO && normals.len() == positions.len() {
+        let has_octants = rocktree_mesh.has_octant_data;
+        let edges = seam_edges(&triangle_indices, |i| {
+            if has_octants {
+                vertices[i as usize].w
+            } else {
+                0
+            }
+        });
+        // One skirt vertex below each seam vertex, copying its texture
+        // coordinate, normal and octant.
+        let mut below: HashMap<u32, u32> = HashMap::new();
+        let mut lower = |i: u32| {
+            *below.entry(i).or_insert_with(|| {
+                let index = positions.len() as u32;
+                let top = Vec3::from_array(positions[i as usize]);
+                positions.push((top + skirt_offset).to_array());
+                uvs.push(uvs[i as usize]);
+                colors.push(colors[i as usize]);
+                normals.push(normals[i as usize]);
+                index
+            })
+        };
+        for (a, b) in edges {
+            let (a_low, b_low) = (lower(a), lower(b));
+            triangle_indices.extend([a, b, b_low, a, b_low, a_low]);
+        }
+    }
 
     // Build the Bevy mesh with normals for lit rendering.
     let mut mesh = Mesh::new(
@@ -64,11 +120,45 @@ pub fn convert_mesh(rocktree_mesh: &RocktreeMesh) -> Mesh {
     // Use the original normals from Google Earth data to ensure seamless
     // lighting across tile boundaries. These normals are consistent at
     // shared edges between adjacent tiles.
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, rocktree_mesh.normals.clone());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
 
     mesh
 }
 
+/// The mesh-local displacement that drops a skirt `depth_m` metres straight
+/// down, for a mesh placed by `transform` at ECEF `globe_position`.
+pub fn skirt_offset(transform: &Transform, globe_position: glam::DVec3, depth_m: f32) -> Vec3 {
+    let down = -globe_position.normalize_or_zero().as_vec3() * depth_m;
+    (transform.rotation.inverse() * down) / transform.scale
+}
+
+/// The edges on the border of each single-octant region of a triangle list:
+/// edges with exactly one neighbouring triangle whose vertices all share the
+/// edge's octant. That takes in the mesh's own border, and the seams against
+/// other octants and against triangles straddling two. Edges come back in
+/// their triangle's winding.
+fn seam_edges(triangles: &[u32], octant: impl Fn(u32) -> u8) -> Vec<(u32, u32)> {
+    let mut edges: HashMap<(u32, u32), ((u32, u32), u32)> = HashMap::new();
+    for triangle in triangles.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+        if octant(a) != octant(b) || octant(b) != octant(c) {
+            continue;
+        }
+        for (from, to) in [(a, b), (b, c), (c, a)] {
+            let key = (from.min(to), from.max(to));
+            edges.entry(key).or_insert(((from, to), 0)).1 += 1;
+        }
+    }
+    let mut seams: Vec<(u32, u32)> = edges
+        .into_values()
+        .filter(|&(_, count)| count == 1)
+        .map(|(edge, _)| edge)
+        .collect();
+    // Keep the output independent of the hash order.
+    seams.sort_unstable();
+    seams
+}
+
 /// Convert a triangle strip to a triangle list.
 ///
 /// Handles degenerate triangles (where two or more indices are the same).
@@ -207,6 +297,26 @@ mod tests {
         assert_eq!(triangles, vec![0, 1, 2, 1, 3, 2]);
     }
 
+    #[test]
+    fn seam_edges_find_borders_and_octant_seams() {
+        // Two triangles sharing the diagonal 1-2 of a quad.
+        let quad = [0, 1, 2, 1, 3, 2];
+        assert_eq!(
+            seam_edges(&quad, |_| 0),
+            vec![(0, 1), (1, 3), (2, 0), (3, 2)]
+        );
+        // Split across octants, the diagonal is a seam for both.
+        let octant = |i: u32| u8::from(i == 3);
+        assert_eq!(seam_edges(&quad, octant), vec![(0, 1), (1, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn skirt_drops_towards_the_globe_centre() {
+        let transform = Transform::from_scale(Vec3::splat(2.0));
+        let offset = skirt_offset(&transform, glam::DVec3::new(0.0, 0.0, 6.4e6), 4.0);
+        assert!(offset.abs_diff_eq(Vec3::new(0.0, 0.0, -2.0), 1e-6));
+    }
+
     #[test]
     fn test_strip_to_triangles_degenerate() {
         // Degenerate: indices 0,1,1 and 1,1,2.
@@ -0,0 +1,148 @@
+//! Bandwidth cap and metered-connection mode.
+//!
+//! [`NetworkTuning`] (the `[network]` table of the LOD config) caps the
+//! rocktree client's download rate with its token bucket (see
+//! [`rocktree::bandwidth`]), which this module refills every frame. Requests
+//! held back by the cap read as slow to the adaptive controller in
+//! [`crate::qos`], which then sheds concurrency and depth on its own.
+//!
+//! Metered mode is for mobile hotspots and other pay-per-byte links: the
+//! traversal refines to a coarser tolerance, so it requests fewer and
+//! shallower tiles, and tiles decode at a lower texture tier.
+//!
+//! The table also sizes the client's decoded-texture cache, which saves
+//! re-downloading and re-decoding tiles of a revisited area; the web build
+//! gets its own, smaller budget.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    loader::LoaderState,
+    lod::{LodTuning, RefinementStrategy, TextureQuality},
+};
+
+/// Tuning for the bandwidth cap and metered mode. Lives in the `[network]`
+/// table of the LOD config.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkTuning {
+    /// Download cap (KiB/s); 0 is unlimited.
+    pub bandwidth_limit_kib_per_sec: f64,
+    /// Minimise data use.
+    pub metered: bool,
+    /// Refinement tolerance multiplier while metered.
+    pub metered_coarsening: f64,
+    /// Finest texture tier while metered.
+    pub metered_texture_quality: TextureQuality,
+    /// Decoded textures kept after their nodes unload, on native (MiB).
+    pub texture_cache_mib: f64,
+    /// The same budget in the browser, where memory is tighter (MiB).
+    pub web_texture_cache_mib: f64,
+}
+
+impl Default for NetworkTuning {
+    fn default() -> Self {
+        Self {
+            bandwidth_limit_kib_per_sec: 0.0,
+            metered: false,
+            metered_coarsening: 2.0,
+            metered_texture_quality: TextureQuality::Quarter,
+            texture_cache_mib: 128.0,
+            web_texture_cache_mib: 32.0,
+        }
+    }
+}
+
+impl NetworkTuning {
+    /// The download cap (bytes/s), if any.
+    pub fn limit_bytes_per_sec(&self) -> Option<u64> {
+        (self.bandwidth_limit_kib_per_sec > 0.0)
+            .then(|| (self.bandwidth_limit_kib_per_sec * 1024.0) as u64)
+    }
+
+    /// The decoded-texture cache budget for this platform (bytes).
+    pub fn texture_cache_bytes(&self) -> usize {
+        let mib = if cfg!(target_family = "wasm") {
+            self.web_texture_cache_mib
+        } else {
+            self.texture_cache_mib
+        };
+        (mib.max(0.0) * 1024.0 * 1024.0) as usize
+    }
+
+    /// `strategy` coarsened for metered mode, when it's on.
+    pub fn apply(&self, strategy: RefinementStrategy) -> RefinementStrategy {
+        if self.metered && self.metered_coarsening > 1.0 {
+            strategy.coarsened(self.metered_coarsening)
+        } else {
+            strategy
+        }
+    }
+
+    /// `quality`, lowered to the metered tier when metered mode is on.
+    pub fn texture_quality(&self, quality: TextureQuality) -> TextureQuality {
+        if self.metered {
+            quality.max(self.metered_texture_quality)
+        } else {
+            quality
+        }
+    }
+}
+
+/// Applies the bandwidth cap and texture cache budget to the rocktree client.
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, (update_bandwidth_limiter, update_texture_cache));
+    }
+}
+
+/// Keep the client's cap in step with the tuning and refill its bucket.
+fn update_bandwidth_limiter(
+    loader: Res<LoaderState>,
+    tuning: Res<LodTuning>,
+    real_time: Res<Time<Real>>,
+) {
+    let bandwidth = loader.client.bandwidth();
+    bandwidth.set_limit(tuning.network.limit_bytes_per_sec());
+    bandwidth.tick(real_time.delta_secs_f64());
+}
+
+/// Resize the decoded-texture cache when the tuning changes.
+fn update_texture_cache(loader: Res<LoaderState>, tuning: Res<LodTuning>) {
+    if !tuning.is_changed() && !loader.is_changed() {
+        return;
+    }
+    if let Some(textures) = loader.client.texture_cache() {
+        textures.set_max_bytes(tuning.network.texture_cache_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metered_only_ever_lowers_texture_quality() {
+        let tuning = NetworkTuning {
+            metered: true,
+            metered_texture_quality: TextureQuality::Half,
+            ..default()
+        };
+        assert_eq!(
+            tuning.texture_quality(TextureQuality::Full),
+            TextureQuality::Half
+        );
+        assert_eq!(
+            tuning.texture_quality(TextureQuality::Quarter),
+            TextureQuality::Quarter
+        );
+        let unmetered = NetworkTuning::default();
+        assert_eq!(
+            unmetered.texture_quality(TextureQuality::Full),
+            TextureQuality::Full
+        );
+    }
+}
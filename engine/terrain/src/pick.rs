@@ -0,0 +1,82 @@
+//! Screen-space terrain picking: what lies under the cursor.
+//!
+//! A thin layer over [`TerrainRaycast`]: [`TerrainPicker`] holds the terrain
+//! under the primary window's cursor, refreshed every frame, for the UI and
+//! anything else interested in the pointer. The cast runs against the loaded
+//! render meshes, so it reaches terrain far beyond the physics colliders.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use veldera_geo::floating_origin::FloatingOriginCamera;
+
+use crate::raycast::{TerrainHit, TerrainRaycast, TerrainRaycastSystems};
+
+/// Longest pick ray (m); enough to reach the horizon from orbit.
+const MAX_PICK_DISTANCE: f64 = 20_000_000.0;
+
+/// Plugin that keeps [`TerrainPicker`] pointed at the terrain under the
+/// cursor.
+pub struct TerrainPickerPlugin;
+
+impl Plugin for TerrainPickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainPicker>()
+            .add_systems(Update, update_cursor_pick.after(TerrainRaycastSystems));
+    }
+}
+
+/// The terrain under the primary window's cursor.
+#[derive(Resource, Default, PartialEq)]
+pub struct TerrainPicker {
+    /// Cursor position (logical pixels) of the last pick, if the cursor is
+    /// in the window.
+    cursor: Option<Vec2>,
+    /// Terrain hit under the cursor, if any.
+    hit: Option<TerrainHit>,
+}
+
+impl TerrainPicker {
+    /// The terrain under the cursor, if the cursor is over loaded terrain.
+    pub fn hit(&self) -> Option<&TerrainHit> {
+        self.hit.as_ref()
+    }
+
+    /// The cursor position (logical pixels) the hit was picked at.
+    pub fn cursor(&self) -> Option<Vec2> {
+        self.cursor
+    }
+}
+
+/// Terrain under `viewport_position` (logical pixels) of `camera`.
+pub fn pick_viewport(
+    raycast: &TerrainRaycast,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    origin: &FloatingOriginCamera,
+    viewport_position: Vec2,
+) -> Option<TerrainHit> {
+    // The camera sits at the floating origin, so the ray is camera-relative.
+    let ray = camera
+        .viewport_to_world(camera_transform, viewport_position)
+        .ok()?;
+    raycast.cast_ray(
+        origin.position + ray.origin.as_dvec3(),
+        ray.direction.as_dvec3(),
+        MAX_PICK_DISTANCE,
+    )
+}
+
+/// Pick the terrain under the primary window's cursor.
+fn update_cursor_pick(
+    raycast: TerrainRaycast,
+    mut picker: ResMut<TerrainPicker>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &FloatingOriginCamera)>,
+) {
+    let cursor = window_query.single().ok().and_then(Window::cursor_position);
+    let hit = cursor.and_then(|cursor| {
+        let (camera, camera_transform, origin) = camera_query.single().ok()?;
+        pick_viewport(&raycast, camera, camera_transform, origin, cursor)
+    });
+    picker.set_if_neq(TerrainPicker { cursor, hit });
+}
@@ -0,0 +1,540 @@
+//! A coarse stand-in for the whole planet beneath the streamed tiles.
+//!
+//! The LOD traversal only loads what's in view and near enough to matter, so
+//! from high up the streamed surface ends at the edge of the loaded area and
+//! the globe's silhouette has bites out of it. [`ProxyGlobePlugin`] draws a
+//! low-poly WGS84 ellipsoid underneath everything, textured with imagery baked
+//! from the shallowest nodes, so there is always a planet to look at.
+//!
+//! The texture is normally the [`BaseMap`] the `bake-base-map` tool paints
+//! offline from deeper levels than a runtime bake could afford, shipped at
+//! [`ProxyGlobePlugin::DEFAULT_BASE_MAP_PATH`]. Without it, once the root bulk
+//! has loaded, a background task fetches every node down to
+//! [`ProxyGlobeConfig::bake_depth`] and paints them into a base map of its
+//! own, shallowest first so finer nodes paint over coarser ones. The texture
+//! is updated after each depth, so the globe sharpens as the bake goes.
+//! Anything the nodes don't cover keeps [`ProxyGlobeConfig::fill_color`].
+//!
+//! The proxy is sunk below the ellipsoid so the streamed tiles, which sit on
+//! the real relief, always cover it where they exist. The sink grows with the
+//! camera's altitude, from [`ProxyGlobeConfig::min_sink_m`] near the ground,
+//! where it must stay below low-lying land, up to
+//! [`ProxyGlobeConfig::max_sink_m`], which keeps its limb hidden behind the
+//! loaded tiles' own at every height while staying sub-pixel from orbit. It
+//! neither casts shadows nor takes part in raycasts or physics.
+
+use std::sync::Arc;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    light::NotShadowCaster,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    transform::TransformSystems,
+};
+use glam::DVec3;
+use rocktree::{Client, NodeRequest};
+use serde::Deserialize;
+
+use veldera_async::TaskSpawner;
+use veldera_config::ConfigPlugin;
+use veldera_geo::{
+    coords::{WGS84_SEMI_MAJOR, ecef_to_geodetic, geodetic_to_ecef},
+    floating_origin::{FloatingOriginCamera, WorldPosition},
+};
+
+use crate::{
+    base_map::BaseMap,
+    loader::{LoaderState, TileCache},
+};
+
+/// Deepest node level the bake can reach: the root bulk's own depth, so the
+/// bake never has to fetch further bulks.
+pub const MAX_BAKE_DEPTH: usize = 4;
+
+/// Plugin that draws and bakes the proxy globe.
+///
+/// Defaults to the config at [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+/// and the base map at [`DEFAULT_BASE_MAP_PATH`](Self::DEFAULT_BASE_MAP_PATH)
+/// in the shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout.
+pub struct ProxyGlobePlugin {
+    /// Path to the [`ProxyGlobeConfig`] TOML.
+    pub config_path: &'static str,
+    /// Path to the baked base map PNG.
+    pub base_map_path: &'static str,
+}
+
+impl ProxyGlobePlugin {
+    /// Canonical [`ProxyGlobeConfig`] path within the shared engine asset
+    /// subtree.
+    pub const DEFAULT_CONFIG_PATH: &'static str = "engine/config/rendering/proxy_globe.toml";
+
+    /// Canonical base map path within the shared engine asset subtree, where
+    /// the `bake-base-map` tool writes it.
+    pub const DEFAULT_BASE_MAP_PATH: &'static str = "engine/world/base_map.png";
+
+    /// Create the plugin, loading its config from `config_path` and its base
+    /// map from `base_map_path`.
+    pub const fn new(config_path: &'static str, base_map_path: &'static str) -> Self {
+        Self {
+            config_path,
+            base_map_path,
+        }
+    }
+}
+
+impl Default for ProxyGlobePlugin {
+    /// Load the config from [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+    /// and the base map from
+    /// [`DEFAULT_BASE_MAP_PATH`](Self::DEFAULT_BASE_MAP_PATH).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONFIG_PATH, Self::DEFAULT_BASE_MAP_PATH)
+    }
+}
+
+impl Plugin for ProxyGlobePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<ProxyGlobeConfig>::new(self.config_path))
+            .insert_resource(BaseMapPath(self.base_map_path))
+            .init_resource::<ProxyGlobeBake>()
+            .add_systems(Update, (spawn_proxy_globe, start_bake, poll_bake).chain())
+            .add_systems(
+                PostUpdate,
+                update_proxy_globe.before(TransformSystems::Propagate),
+            );
+    }
+}
+
+/// Asset path of the baked base map. If it's missing, the globe bakes its
+/// own at runtime.
+#[derive(Resource)]
+struct BaseMapPath(&'static str);
+
+/// Proxy globe settings, loaded from `proxy_globe.toml`.
+#[derive(Asset, Resource, TypePath, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProxyGlobeConfig {
+    /// Master toggle.
+    pub enabled: bool,
+    /// Deepest node level baked into the texture at runtime, at most
+    /// [`MAX_BAKE_DEPTH`]. Read when the bake starts.
+    pub bake_depth: usize,
+    /// Width of the runtime-baked equirectangular texture (texels); the
+    /// height is half this. Read when the bake starts.
+    pub texture_width: u32,
+    /// Longitude segments of the ellipsoid mesh; latitude gets half as many.
+    /// Read when the globe is spawned.
+    pub segments: u32,
+    /// Sink below the ellipsoid with the camera on the ground (m).
+    pub min_sink_m: f32,
+    /// Sink per metre of camera altitude.
+    pub sink_per_altitude: f32,
+    /// Largest sink, reached high above the ground (m).
+    pub max_sink_m: f32,
+    /// Linear RGB colour of anything the baked nodes don't cover.
+    pub fill_color: [f32; 3],
+}
+
+impl Default for ProxyGlobeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bake_depth: 3,
+            texture_width: 2048,
+            segments: 256,
+            min_sink_m: 600.0,
+            sink_per_altitude: 0.01,
+            max_sink_m: 10_000.0,
+            fill_color: [0.01, 0.03, 0.06],
+        }
+    }
+}
+
+impl ProxyGlobeConfig {
+    /// How far below the ellipsoid the proxy sits for a camera `altitude_m`
+    /// above it (m).
+    pub fn sink_m(&self, altitude_m: f32) -> f32 {
+        (altitude_m.max(0.0) * self.sink_per_altitude)
+            .clamp(self.min_sink_m, self.max_sink_m.max(self.min_sink_m))
+    }
+}
+
+/// Marker for the proxy globe entity.
+#[derive(Component)]
+pub struct ProxyGlobe;
+
+/// Progress of the proxy globe's bake.
+#[derive(Resource)]
+pub struct ProxyGlobeBake {
+    /// The globe's material, once it has been spawned.
+    material: Option<Handle<StandardMaterial>>,
+    /// The runtime-baked texture.
+    image: Option<Handle<Image>>,
+    /// The shipped base map, while it loads and once it has.
+    base_map: Option<Handle<Image>>,
+    /// Whether the globe shows the shipped base map.
+    uses_base_map: bool,
+    /// Whether the bake has been started this session.
+    started: bool,
+    /// Nodes rasterized into the texture so far.
+    nodes_baked: usize,
+    /// Node fetches that failed.
+    nodes_failed: usize,
+    /// Whether the bake has finished.
+    done: bool,
+    updates_rx: async_channel::Receiver<BakeUpdate>,
+    updates_tx: async_channel::Sender<BakeUpdate>,
+}
+
+impl Default for ProxyGlobeBake {
+    fn default() -> Self {
+        let (updates_tx, updates_rx) = async_channel::unbounded();
+        Self {
+            material: None,
+            image: None,
+            base_map: None,
+            uses_base_map: false,
+            started: false,
+            nodes_baked: 0,
+            nodes_failed: 0,
+            done: false,
+            updates_rx,
+            updates_tx,
+        }
+    }
+}
+
+impl ProxyGlobeBake {
+    /// Whether the globe shows the shipped base map rather than a runtime
+    /// bake.
+    pub fn uses_base_map(&self) -> bool {
+        self.uses_base_map
+    }
+
+    /// Nodes rasterized into the texture so far.
+    pub fn nodes_baked(&self) -> usize {
+        self.nodes_baked
+    }
+
+    /// Node fetches that failed.
+    pub fn nodes_failed(&self) -> usize {
+        self.nodes_failed
+    }
+
+    /// Whether the bake has finished.
+    pub fn done(&self) -> bool {
+        self.done
+    }
+}
+
+/// The texture after another depth has been baked into it.
+struct BakeUpdate {
+    pixels: Vec<u8>,
+    nodes_baked: usize,
+    nodes_failed: usize,
+    done: bool,
+}
+
+// ============================================================================
+// Systems
+// ============================================================================
+
+/// Spawn the globe, with a fill-coloured texture, once, and start loading the
+/// base map.
+fn spawn_proxy_globe(
+    mut commands: Commands,
+    config: Res<ProxyGlobeConfig>,
+    base_map_path: Res<BaseMapPath>,
+    asset_server: Res<AssetServer>,
+    mut bake: ResMut<ProxyGlobeBake>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if bake.material.is_some() {
+        return;
+    }
+    let image = images.add(base_map_image(filled_base_map(&config)));
+    let material = materials.add(StandardMaterial {
+        base_color_texture: Some(image.clone()),
+        perceptual_roughness: 1.0,
+        // Matte, like the terrain it stands in for.
+        reflectance: 0.0,
+        ..default()
+    });
+    commands.spawn((
+        Name::new("Proxy globe"),
+        ProxyGlobe,
+        Mesh3d(meshes.add(ellipsoid_mesh(config.segments))),
+        MeshMaterial3d(material),
+        Transform::default(),
+        WorldPosition::from_dvec3(DVec3::ZERO),
+        NotShadowCaster,
+        if config.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+    ));
+    bake.material = Some(material);
+    bake.image = Some(image);
+    bake.base_map = Some(asset_server.load(base_map_path.0));
+}
+
+/// Show the base map once it has loaded, or, if it's missing, start the
+/// bake once the root bulk has loaded.
+fn start_bake(
+    config: Res<ProxyGlobeConfig>,
+    mut bake: ResMut<ProxyGlobeBake>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    loader: Res<LoaderState>,
+    spawner: TaskSpawner,
+) {
+    if bake.started || !config.enabled {
+        return;
+    }
+    let Some(base_map) = bake.base_map.clone() else {
+        return;
+    };
+    if asset_server.is_loaded(&base_map) {
+        if let Some(material) = bake.material.as_ref().and_then(|m| materials.get_mut(m)) {
+            material.base_color_texture = Some(base_map);
+        }
+        tracing::info!("Proxy globe: using the baked base map");
+        bake.uses_base_map = true;
+        bake.started = true;
+        bake.done = true;
+        return;
+    }
+    if !asset_server.load_state(&base_map).is_failed() {
+        return;
+    }
+    let Some(root) = &loader.root_bulk else {
+        return;
+    };
+    bake.started = true;
+    bake.base_map = None;
+
+    let max_depth = config.bake_depth.clamp(1, MAX_BAKE_DEPTH);
+    let mut requests: Vec<_> = root
+        .nodes
+        .iter()
+        .filter(|node| node.has_data && node.path.depth() <= max_depth)
+        .map(|node| {
+            NodeRequest::new(
+                node.path,
+                node.epoch,
+                node.texture_format,
+                node.imagery_epoch,
+            )
+        })
+        .collect();
+    requests.sort_by_key(|request| request.path.depth());
+    tracing::info!(
+        "Proxy globe: baking {} nodes down to depth {max_depth}",
+        requests.len()
+    );
+
+    let client = Arc::clone(&loader.client);
+    let image = filled_base_map(&config);
+    let tx = bake.updates_tx.clone();
+    spawner.spawn(async move {
+        bake_nodes(&client, requests, image, &tx).await;
+    });
+}
+
+/// Copy each baked depth into the globe's texture.
+fn poll_bake(mut bake: ResMut<ProxyGlobeBake>, mut images: ResMut<Assets<Image>>) {
+    let mut latest = None;
+    while let Ok(update) = bake.updates_rx.try_recv() {
+        latest = Some(update);
+    }
+    let Some(update) = latest else {
+        return;
+    };
+    bake.nodes_baked = update.nodes_baked;
+    bake.nodes_failed = update.nodes_failed;
+    bake.done = update.done;
+    if update.done {
+        tracing::info!(
+            "Proxy globe: baked {} nodes ({} failed)",
+            update.nodes_baked,
+            update.nodes_failed
+        );
+    }
+    if let Some(handle) = &bake.image
+        && let Some(image) = images.get_mut(handle)
+        && image.data.as_ref().map(Vec::len) == Some(update.pixels.len())
+    {
+        image.data = Some(update.pixels);
+    }
+}
+
+/// Sink the globe for the camera's altitude and follow the config's toggle.
+/// Only writes when something changed, so the mesh isn't re-extracted every
+/// frame.
+fn update_proxy_globe(
+    config: Res<ProxyGlobeConfig>,
+    camera: Query<&FloatingOriginCamera>,
+    mut globe: Query<(&mut Transform, &mut Visibility), With<ProxyGlobe>>,
+) {
+    let (Ok(camera), Ok((mut transform, mut visibility))) = (camera.single(), globe.single_mut())
+    else {
+        return;
+    };
+    let wanted = if config.enabled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    visibility.set_if_neq(wanted);
+
+    let (_, _, altitude) = ecef_to_geodetic(camera.position);
+    let scale = (1.0 - f64::from(config.sink_m(altitude as f32)) / WGS84_SEMI_MAJOR) as f32;
+    // A scale step of 1e-6 is about 6 m at the surface.
+    if (transform.scale.x - scale).abs() > 1e-6 {
+        transform.scale = Vec3::splat(scale);
+    }
+}
+
+// ============================================================================
+// Baking
+// ============================================================================
+
+/// Fetch and rasterize `requests` in order, sending the texture after each
+/// depth.
+async fn bake_nodes(
+    client: &Client<TileCache>,
+    requests: Vec<NodeRequest>,
+    mut image: BaseMap,
+    tx: &async_channel::Sender<BakeUpdate>,
+) {
+    let (mut baked, mut failed) = (0, 0);
+    for (i, request) in requests.iter().enumerate() {
+        match client.fetch_node(request).await {
+            Ok(node) => {
+                image.paint_node(&node);
+                baked += 1;
+            }
+            Err(e) => {
+                tracing::debug!("Proxy globe: node '{}' failed: {e}", request.path);
+                failed += 1;
+            }
+        }
+        let last_of_depth = requests
+            .get(i + 1)
+            .is_none_or(|next| next.path.depth() != request.path.depth());
+        if last_of_depth {
+            let update = BakeUpdate {
+                pixels: image.pixels().to_vec(),
+                nodes_baked: baked,
+                nodes_failed: failed,
+                done: i + 1 == requests.len(),
+            };
+            if tx.send(update).await.is_err() {
+                return;
+            }
+        }
+    }
+    if requests.is_empty() {
+        let _ = tx
+            .send(BakeUpdate {
+                pixels: image.into_pixels(),
+                nodes_baked: 0,
+                nodes_failed: 0,
+                done: true,
+            })
+            .await;
+    }
+}
+
+/// A base map filled with the config's fill colour.
+fn filled_base_map(config: &ProxyGlobeConfig) -> BaseMap {
+    let [r, g, b] = config.fill_color;
+    let [r, g, b, _] = Color::linear_rgb(r, g, b).to_srgba().to_u8_array();
+    BaseMap::filled(config.texture_width.min(8192), [r, g, b])
+}
+
+/// Upload a base map as an sRGB texture.
+fn base_map_image(map: BaseMap) -> Image {
+    let size = Extent3d {
+        width: map.width(),
+        height: map.height(),
+        depth_or_array_layers: 1,
+    };
+    Image::new(
+        size,
+        TextureDimension::D2,
+        map.into_pixels(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+// ============================================================================
+// Mesh
+// ============================================================================
+
+/// The WGS84 ellipsoid in ECEF, `segments` around and half that from pole to
+/// pole, with equirectangular texture coordinates and geodetic normals. The
+/// seam at the antimeridian has its own column of vertices.
+fn ellipsoid_mesh(segments: u32) -> Mesh {
+    let columns = segments.clamp(8, 1024);
+    let rows = columns / 2;
+    let mut positions = Vec::with_capacity(((columns + 1) * (rows + 1)) as usize);
+    let mut normals = Vec::with_capacity(positions.capacity());
+    let mut uvs = Vec::with_capacity(positions.capacity());
+    for row in 0..=rows {
+        let v = row as f32 / rows as f32;
+        let lat = 90.0 - f64::from(v) * 180.0;
+        for column in 0..=columns {
+            let u = column as f32 / columns as f32;
+            let lon = f64::from(u) * 360.0 - 180.0;
+            positions.push(geodetic_to_ecef(lat, lon, 0.0).as_vec3().to_array());
+            let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+            let (sin_lon, cos_lon) = lon.to_radians().sin_cos();
+            normals.push([
+                (cos_lat * cos_lon) as f32,
+                (cos_lat * sin_lon) as f32,
+                sin_lat as f32,
+            ]);
+            uvs.push([u, v]);
+        }
+    }
+    let stride = columns + 1;
+    let mut indices = Vec::with_capacity((columns * rows * 6) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let a = row * stride + column;
+            let b = a + stride;
+            // Counter-clockwise seen from outside, with +z north.
+            indices.extend([a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sink_follows_altitude_within_bounds() {
+        let config = ProxyGlobeConfig::default();
+        assert_eq!(config.sink_m(0.0), config.min_sink_m);
+        assert_eq!(config.sink_m(1e9), config.max_sink_m);
+        assert!(config.sink_m(200_000.0) > config.sink_m(80_000.0));
+    }
+}
@@ -0,0 +1,350 @@
+//! Adaptive load concurrency for LOD streaming.
+//!
+//! [`LoadQos`] times every node and bulk request and tracks smoothed latency
+//! and failure rate. A few times a second it retunes two knobs, AIMD-style:
+//!
+//! - **Concurrency.** While the queue is full and requests come back well
+//!   within [`QosTuning::target_latency_secs`], the node load cap grows
+//!   additively. Slow or failing requests shrink it multiplicatively, so a
+//!   saturated link stops being flooded with requests that only queue
+//!   behind each other.
+//! - **Traversal depth.** When even a reduced cap can't keep latency down,
+//!   the render refinement tolerance is coarsened a step at a time (see
+//!   [`RefinementStrategy::coarsened`]), so the traversal asks for fewer,
+//!   shallower tiles. Steps are undone once the link recovers.
+//!
+//! Bulk metadata loads are capped at a fixed fraction of the node cap
+//! ([`QosTuning::bulks_per_node_load`]).
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+use rocktree_decode::OctreePath;
+
+use crate::lod::RefinementStrategy;
+
+/// Completed requests needed between adjustments, so a handful of outliers
+/// can't swing the controller.
+const MIN_SAMPLES_PER_ADJUSTMENT: u32 = 8;
+
+/// Tuning for the adaptive controller. Lives in the `[qos]` table of the LOD
+/// config.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QosTuning {
+    /// Adapt concurrency and depth to the link. When off, the node cap stays
+    /// at `initial_node_loads` and the traversal at full detail.
+    pub enabled: bool,
+    /// Node load cap at startup, and the fixed cap when disabled.
+    pub initial_node_loads: usize,
+    /// Lower bound on the node load cap.
+    pub min_node_loads: usize,
+    /// Upper bound on the node load cap.
+    pub max_node_loads: usize,
+    /// Smoothed request latency the controller aims to stay under (s).
+    pub target_latency_secs: f64,
+    /// Smoothed failure rate above which the controller backs off.
+    pub max_failure_rate: f64,
+    /// Most refinement coarsening steps the controller may apply.
+    pub max_coarsening_steps: u32,
+    /// Refinement tolerance multiplier per coarsening step.
+    pub coarsening_per_step: f64,
+    /// Minimum time between adjustments (s).
+    pub adjust_interval_secs: f64,
+    /// Smoothing factor for the latency and failure-rate averages, per
+    /// completed request. Higher reacts faster but jitters more.
+    pub ema_alpha: f64,
+    /// Bulk metadata loads allowed per node load.
+    pub bulks_per_node_load: f64,
+}
+
+impl Default for QosTuning {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_node_loads: 64,
+            min_node_loads: 8,
+            max_node_loads: 192,
+            target_latency_secs: 0.6,
+            max_failure_rate: 0.1,
+            max_coarsening_steps: 6,
+            coarsening_per_step: 1.25,
+            adjust_interval_secs: 0.5,
+            ema_alpha: 0.1,
+            bulks_per_node_load: 0.25,
+        }
+    }
+}
+
+/// Kind of streaming request being timed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    /// Node mesh and texture data.
+    Node,
+    /// Bulk metadata.
+    Bulk,
+}
+
+/// Live state of the adaptive controller. Read by the Streaming tab.
+#[derive(Resource, Debug)]
+pub struct LoadQos {
+    /// Current node load cap (fractional, so additive increases accumulate).
+    node_limit: f64,
+    /// Current refinement coarsening step (0 = full detail).
+    coarsening_steps: u32,
+    /// Smoothed request latency (s).
+    latency_secs: f64,
+    /// Smoothed fraction of requests that failed.
+    failure_rate: f64,
+    /// Completed requests since the last adjustment.
+    samples: u32,
+    /// Whether the cap was the limiting factor since the last adjustment.
+    saturated: bool,
+    /// Time of the last adjustment (s, real time).
+    last_adjust: f64,
+    /// Issue time of every in-flight request (s, real time).
+    in_flight: HashMap<(RequestKind, OctreePath), f64>,
+    /// Whether `node_limit` has been seeded from the tuning yet.
+    initialised: bool,
+    /// The tuning last passed to [`adjust`](Self::adjust), which the
+    /// averages and limits between adjustments follow too.
+    tuning: QosTuning,
+}
+
+impl Default for LoadQos {
+    fn default() -> Self {
+        Self {
+            node_limit: QosTuning::default().initial_node_loads as f64,
+            coarsening_steps: 0,
+            latency_secs: 0.0,
+            failure_rate: 0.0,
+            samples: 0,
+            saturated: false,
+            last_adjust: 0.0,
+            in_flight: HashMap::new(),
+            initialised: false,
+            tuning: QosTuning::default(),
+        }
+    }
+}
+
+impl LoadQos {
+    /// Maximum concurrent node loads.
+    pub fn node_limit(&self) -> usize {
+        self.node_limit as usize
+    }
+
+    /// Maximum concurrent bulk metadata loads.
+    pub fn bulk_limit(&self) -> usize {
+        ((self.node_limit * self.tuning.bulks_per_node_load) as usize).max(2)
+    }
+
+    /// Smoothed request latency (s).
+    pub fn latency_secs(&self) -> f64 {
+        self.latency_secs
+    }
+
+    /// Smoothed fraction of requests that failed.
+    pub fn failure_rate(&self) -> f64 {
+        self.failure_rate
+    }
+
+    /// Current coarsening step (0 = full detail).
+    pub fn coarsening_steps(&self) -> u32 {
+        self.coarsening_steps
+    }
+
+    /// The refinement tolerance multiplier for the current step.
+    pub fn coarsening_factor(&self) -> f64 {
+        self.tuning
+            .coarsening_per_step
+            .powi(self.coarsening_steps as i32)
+    }
+
+    /// `strategy` with the current coarsening applied.
+    pub fn apply(&self, strategy: RefinementStrategy) -> RefinementStrategy {
+        if self.coarsening_steps == 0 {
+            strategy
+        } else {
+            strategy.coarsened(self.coarsening_factor())
+        }
+    }
+
+    /// Note that a request went out at `now`.
+    pub fn request_started(&mut self, kind: RequestKind, path: OctreePath, now: f64) {
+        self.in_flight.insert((kind, path), now);
+    }
+
+    /// Forget a request that was cancelled before completing; it says
+    /// nothing about the link.
+    pub fn request_cancelled(&mut self, kind: RequestKind, path: OctreePath) {
+        self.in_flight.remove(&(kind, path));
+    }
+
+    /// Note that the load queue had more wanted requests than free slots.
+    pub fn mark_saturated(&mut self) {
+        self.saturated = true;
+    }
+
+    /// Fold a completed request into the averages.
+    pub fn request_finished(&mut self, kind: RequestKind, path: OctreePath, now: f64, ok: bool) {
+        let Some(started) = self.in_flight.remove(&(kind, path)) else {
+            return;
+        };
+        let latency = (now - started).max(0.0);
+        let alpha = self.tuning.ema_alpha.clamp(0.0, 1.0);
+        if self.latency_secs <= 0.0 {
+            // Seed the average with the first sample rather than ramping up from zero.
+            self.latency_secs = latency;
+        } else {
+            self.latency_secs += alpha * (latency - self.latency_secs);
+        }
+        let failed = if ok { 0.0 } else { 1.0 };
+        self.failure_rate += alpha * (failed - self.failure_rate);
+        self.samples += 1;
+    }
+
+    /// Retune the caps and depth if enough has been observed since the last
+    /// adjustment.
+    pub fn adjust(&mut self, tuning: &QosTuning, now: f64) {
+        self.tuning = *tuning;
+        let min = tuning.min_node_loads.max(1) as f64;
+        let max = (tuning.max_node_loads as f64).max(min);
+        if !self.initialised || !tuning.enabled {
+            self.node_limit = (tuning.initial_node_loads as f64).clamp(min, max);
+            self.coarsening_steps = 0;
+            self.initialised = tuning.enabled;
+            self.samples = 0;
+            self.last_adjust = now;
+            return;
+        }
+        if now - self.last_adjust < tuning.adjust_interval_secs
+            || self.samples < MIN_SAMPLES_PER_ADJUSTMENT
+        {
+            return;
+        }
+
+        let failing = self.failure_rate > tuning.max_failure_rate;
+        let slow = self.latency_secs > tuning.target_latency_secs;
+        if failing || slow {
+            // Back off harder on failures than on mere slowness.
+            self.node_limit *= if failing { 0.5 } else { 0.8 };
+            // Only shed depth once concurrency is already near its floor.
+            if self.node_limit <= min * 2.0 {
+                self.coarsening_steps =
+                    (self.coarsening_steps + 1).min(tuning.max_coarsening_steps);
+            }
+        } else if self.latency_secs < tuning.target_latency_secs * 0.5 {
+            if self.coarsening_steps > 0 {
+                self.coarsening_steps -= 1;
+            } else if self.saturated {
+                self.node_limit += 4.0;
+            }
+        }
+        self.node_limit = self.node_limit.clamp(min, max);
+        self.samples = 0;
+        self.saturated = false;
+        self.last_adjust = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `count` requests of `latency` seconds, then adjust.
+    fn run(qos: &mut LoadQos, tuning: &QosTuning, now: &mut f64, latency: f64, ok: bool) {
+        for i in 0..MIN_SAMPLES_PER_ADJUSTMENT {
+            let path = OctreePath::parse(&format!("{}", i % 8)).unwrap();
+            qos.request_started(RequestKind::Node, path, *now);
+            qos.request_finished(RequestKind::Node, path, *now + latency, ok);
+        }
+        qos.mark_saturated();
+        *now += tuning.adjust_interval_secs;
+        qos.adjust(tuning, *now);
+    }
+
+    #[test]
+    fn grows_on_a_fast_link() {
+        let tuning = QosTuning::default();
+        let mut qos = LoadQos::default();
+        let mut now = 0.0;
+        qos.adjust(&tuning, now);
+        for _ in 0..10 {
+            run(&mut qos, &tuning, &mut now, 0.05, true);
+        }
+        assert!(qos.node_limit() > tuning.initial_node_loads);
+        assert_eq!(qos.coarsening_steps(), 0);
+    }
+
+    #[test]
+    fn backs_off_and_coarsens_on_a_slow_link() {
+        let tuning = QosTuning::default();
+        let mut qos = LoadQos::default();
+        let mut now = 0.0;
+        qos.adjust(&tuning, now);
+        for _ in 0..20 {
+            run(&mut qos, &tuning, &mut now, 3.0, true);
+        }
+        assert_eq!(qos.node_limit(), tuning.min_node_loads);
+        assert_eq!(qos.coarsening_steps(), tuning.max_coarsening_steps);
+    }
+
+    #[test]
+    fn recovers_depth_before_growing() {
+        let tuning = QosTuning::default();
+        let mut qos = LoadQos::default();
+        let mut now = 0.0;
+        qos.adjust(&tuning, now);
+        for _ in 0..20 {
+            run(&mut qos, &tuning, &mut now, 3.0, false);
+        }
+        // The averages need a while to come back down before anything relaxes.
+        for _ in 0..40 {
+            run(&mut qos, &tuning, &mut now, 0.05, true);
+        }
+        assert_eq!(qos.coarsening_steps(), 0);
+        assert!(qos.node_limit() > tuning.min_node_loads);
+    }
+
+    #[test]
+    fn follows_the_tuned_step_bulk_share_and_smoothing() {
+        let tuning = QosTuning {
+            coarsening_per_step: 2.0,
+            bulks_per_node_load: 0.5,
+            ema_alpha: 1.0,
+            ..Default::default()
+        };
+        let mut qos = LoadQos::default();
+        let mut now = 0.0;
+        qos.adjust(&tuning, now);
+        assert_eq!(qos.bulk_limit(), tuning.initial_node_loads / 2);
+
+        run(&mut qos, &tuning, &mut now, 0.05, true);
+        // With no smoothing the average is just the latest sample.
+        run(&mut qos, &tuning, &mut now, 2.0, true);
+        assert_eq!(qos.latency_secs(), 2.0);
+        for _ in 0..20 {
+            run(&mut qos, &tuning, &mut now, 3.0, true);
+        }
+        assert_eq!(
+            qos.coarsening_factor(),
+            2f64.powi(tuning.max_coarsening_steps as i32)
+        );
+    }
+
+    #[test]
+    fn disabled_holds_initial_cap() {
+        let tuning = QosTuning {
+            enabled: false,
+            ..Default::default()
+        };
+        let mut qos = LoadQos::default();
+        let mut now = 0.0;
+        for _ in 0..10 {
+            run(&mut qos, &tuning, &mut now, 3.0, false);
+        }
+        assert_eq!(qos.node_limit(), tuning.initial_node_loads);
+        assert_eq!(qos.coarsening_steps(), 0);
+    }
+}
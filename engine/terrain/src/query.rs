@@ -0,0 +1,481 @@
+//! Read-only queries over the loaded LOD data, for analytics tooling and
+//! coverage overlays.
+//!
+//! [`LodState`] keeps each loaded node's bounding box, decoded meshes and LOD
+//! metric; the methods here expose them without handing out the internal
+//! maps. They reflect what is loaded (and so renderable) as of the last node
+//! poll, not what the traversal currently wants. Iteration order is
+//! unspecified.
+//!
+//! [`NodeExportRequest`] writes the same data to `dumps/nodes-<unix>.json`
+//! for offline analysis, mirroring the tile dump. [`LodState::node_details`]
+//! adds what the bulk metadata says about a single node (epochs, texture
+//! format) for the node inspector.
+
+use bevy::prelude::*;
+use glam::DVec3;
+use rocktree::{Mesh as RocktreeMesh, NodeMetadata, TextureFormat};
+use rocktree_decode::{OctreePath, OrientedBoundingBox};
+use serde::Serialize;
+use veldera_geo::coords::ecef_to_lat_lon;
+
+use crate::lod::LodState;
+
+/// A latitude/longitude box in degrees. `west > east` means the box crosses
+/// the antimeridian.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct LatLonBounds {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl LatLonBounds {
+    /// The whole globe.
+    pub const GLOBE: Self = Self {
+        south: -90.0,
+        west: -180.0,
+        north: 90.0,
+        east: 180.0,
+    };
+
+    #[must_use]
+    pub const fn new(south: f64, west: f64, north: f64, east: f64) -> Self {
+        Self {
+            south,
+            west,
+            north,
+            east,
+        }
+    }
+
+    /// Whether the point is inside the box (edges inclusive).
+    #[must_use]
+    pub fn contains(&self, lat_deg: f64, lon_deg: f64) -> bool {
+        (self.south..=self.north).contains(&lat_deg)
+            && self
+                .lon_ranges()
+                .any(|(west, east)| (west..=east).contains(&lon_deg))
+    }
+
+    /// Whether the two boxes overlap (edges inclusive).
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.south <= other.north
+            && other.south <= self.north
+            && self.lon_ranges().any(|(west, east)| {
+                other
+                    .lon_ranges()
+                    .any(|(other_west, other_east)| west <= other_east && other_west <= east)
+            })
+    }
+
+    /// The longitude span as one or two non-wrapping ranges.
+    fn lon_ranges(&self) -> impl Iterator<Item = (f64, f64)> {
+        let wraps = self.west > self.east;
+        let first = if wraps {
+            (self.west, 180.0)
+        } else {
+            (self.west, self.east)
+        };
+        std::iter::once(first).chain(wraps.then_some((-180.0, self.east)))
+    }
+
+    /// The box around an OBB's corners.
+    ///
+    /// Conservative for tiles: a box whose corners spread over more than
+    /// 180° of longitude either way round is taken to cover every
+    /// longitude, and to reach the pole on its side of the equator.
+    #[must_use]
+    pub fn around_obb(obb: &OrientedBoundingBox) -> Self {
+        let mut lats = [0.0; 8];
+        let mut lons = [0.0; 8];
+        for (i, corner) in obb_corners(obb).into_iter().enumerate() {
+            (lats[i], lons[i]) = ecef_to_lat_lon(corner);
+        }
+        let south = lats.iter().copied().fold(f64::INFINITY, f64::min);
+        let north = lats.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let span = |lons: &[f64; 8]| {
+            let west = lons.iter().copied().fold(f64::INFINITY, f64::min);
+            let east = lons.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (west, east)
+        };
+        let (west, east) = span(&lons);
+        if east - west <= 180.0 {
+            return Self::new(south, west, north, east);
+        }
+        // Try the other way round: shift the western hemisphere by a turn.
+        let shifted = lons.map(|lon| if lon < 0.0 { lon + 360.0 } else { lon });
+        let (west, east) = span(&shifted);
+        if east - west <= 180.0 {
+            let wrap = |lon: f64| if lon > 180.0 { lon - 360.0 } else { lon };
+            return Self::new(south, wrap(west), north, wrap(east));
+        }
+        let (lat, _) = ecef_to_lat_lon(obb.center);
+        if lat >= 0.0 {
+            Self::new(south, -180.0, 90.0, 180.0)
+        } else {
+            Self::new(-90.0, -180.0, north, 180.0)
+        }
+    }
+}
+
+/// The eight corners of an OBB (ECEF).
+fn obb_corners(obb: &OrientedBoundingBox) -> [DVec3; 8] {
+    std::array::from_fn(|i| {
+        let sign = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+        obb.center + obb.orientation * (obb.extents * DVec3::new(sign(0), sign(1), sign(2)))
+    })
+}
+
+/// A loaded node: where it is and how detailed it is.
+#[derive(Clone, Copy, Debug)]
+pub struct LoadedNode {
+    pub path: OctreePath,
+    /// LOD level: the path's octree depth.
+    pub depth: usize,
+    /// Bounding box from bulk metadata (ECEF).
+    pub obb: OrientedBoundingBox,
+    /// The node's LOD metric (m per texel).
+    pub meters_per_texel: f32,
+}
+
+impl LoadedNode {
+    /// The lat/lon box around the node (see [`LatLonBounds::around_obb`]).
+    #[must_use]
+    pub fn lat_lon_bounds(&self) -> LatLonBounds {
+        LatLonBounds::around_obb(&self.obb)
+    }
+}
+
+/// Geometry and texture totals over a node's meshes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NodeStats {
+    pub mesh_count: usize,
+    pub vertex_count: usize,
+    /// Non-degenerate triangles in the meshes' strips.
+    pub triangle_count: usize,
+    /// Texture pixels, at the resolution they were loaded at.
+    pub texture_texels: u64,
+    /// Texture data as decoded (RGB or DXT1).
+    pub texture_bytes: usize,
+    /// Vertex, normal and index data as decoded.
+    pub geometry_bytes: usize,
+}
+
+impl NodeStats {
+    pub(crate) fn from_meshes(meshes: &[RocktreeMesh]) -> Self {
+        meshes.iter().fold(Self::default(), |mut stats, mesh| {
+            stats.mesh_count += 1;
+            stats.vertex_count += mesh.vertices.len();
+            stats.triangle_count += strip_triangle_count(&mesh.indices);
+            stats.texture_texels += u64::from(mesh.texture_width) * u64::from(mesh.texture_height);
+            stats.texture_bytes += mesh.texture_data.len();
+            stats.geometry_bytes += std::mem::size_of_val(mesh.vertices.as_slice())
+                + std::mem::size_of_val(mesh.normals.as_slice())
+                + std::mem::size_of_val(mesh.indices.as_slice());
+            stats
+        })
+    }
+}
+
+/// Triangles in a strip, skipping the degenerate ones that join its runs.
+fn strip_triangle_count(strip: &[u16]) -> usize {
+    strip
+        .windows(3)
+        .filter(|t| t[0] != t[1] && t[1] != t[2] && t[0] != t[2])
+        .count()
+}
+
+impl LodState {
+    /// Every loaded node.
+    pub fn loaded_nodes(&self) -> impl Iterator<Item = LoadedNode> + '_ {
+        self.loaded_nodes
+            .iter()
+            .filter_map(|path| self.loaded_node(*path))
+    }
+
+    /// A loaded node, or `None` if `path` isn't loaded.
+    #[must_use]
+    pub fn loaded_node(&self, path: OctreePath) -> Option<LoadedNode> {
+        let data = self.node_data.get(&path)?;
+        let obb = *self.node_obbs.get(&path)?;
+        Some(LoadedNode {
+            path,
+            depth: path.depth(),
+            obb,
+            meters_per_texel: data.meters_per_texel,
+        })
+    }
+
+    /// The loaded nodes whose lat/lon box overlaps `bounds`.
+    pub fn loaded_nodes_in(&self, bounds: LatLonBounds) -> impl Iterator<Item = LoadedNode> + '_ {
+        self.loaded_nodes()
+            .filter(move |node| node.lat_lon_bounds().intersects(&bounds))
+    }
+
+    /// Mesh and texture totals for a loaded node. Walks its index strips, so
+    /// call it per node of interest rather than every frame for every node.
+    #[must_use]
+    pub fn node_stats(&self, path: OctreePath) -> Option<NodeStats> {
+        self.node_data
+            .get(&path)
+            .map(|data| NodeStats::from_meshes(&data.meshes))
+    }
+}
+
+// ============================================================================
+// Node details
+// ============================================================================
+
+/// Everything known about one node: its bulk metadata and, if it's loaded,
+/// its decoded meshes.
+#[derive(Clone, Debug)]
+pub struct NodeDetails {
+    pub path: OctreePath,
+    /// LOD level: the path's octree depth.
+    pub depth: usize,
+    /// Bounding box from bulk metadata (ECEF).
+    pub obb: OrientedBoundingBox,
+    /// The node's LOD metric (m per texel).
+    pub meters_per_texel: f32,
+    /// Data epoch the node is fetched at.
+    pub epoch: u32,
+    /// Imagery epoch, for nodes whose textures are versioned separately.
+    pub imagery_epoch: Option<u32>,
+    /// Texture format requested from the server (see
+    /// [`requested_texture_format_name`]).
+    pub requested_texture_format: i32,
+    /// Whether the node is loaded and rendered.
+    pub loaded: bool,
+    /// Per-mesh texture size and decoded format; empty unless the node's
+    /// meshes are cached.
+    pub textures: Vec<(u32, u32, TextureFormat)>,
+    /// Mesh and texture totals; `None` unless the node's meshes are cached.
+    pub stats: Option<NodeStats>,
+}
+
+/// Name of a texture format as requested from the server.
+#[must_use]
+pub fn requested_texture_format_name(format: i32) -> String {
+    match format {
+        1 => "JPEG".to_string(),
+        6 => "CRN (DXT1)".to_string(),
+        other => format!("format {other}"),
+    }
+}
+
+impl LodState {
+    /// The bulk metadata entry for a node, if its bulk is cached.
+    ///
+    /// A node at depth `d` lives in the bulk rooted `(d - 1) / 4 * 4` levels
+    /// down, keyed by its path relative to that bulk.
+    #[must_use]
+    pub fn node_metadata(&self, path: OctreePath) -> Option<&NodeMetadata> {
+        let bulk_key = path.truncated((path.depth().checked_sub(1)? / 4) * 4);
+        let relative = path.strip_prefix(bulk_key)?;
+        let index = *self.bulk_node_indices.get(&bulk_key)?.get(&relative)?;
+        self.bulks.get(&bulk_key)?.nodes.get(index)
+    }
+
+    /// What the metadata and loaded data say about a node, or `None` if its
+    /// bulk isn't cached.
+    #[must_use]
+    pub fn node_details(&self, path: OctreePath) -> Option<NodeDetails> {
+        let metadata = self.node_metadata(path)?;
+        let data = self.node_data.get(&path);
+        Some(NodeDetails {
+            path,
+            depth: path.depth(),
+            obb: metadata.obb,
+            meters_per_texel: metadata.meters_per_texel,
+            epoch: metadata.epoch,
+            imagery_epoch: metadata.imagery_epoch,
+            requested_texture_format: metadata.texture_format,
+            loaded: self.loaded_nodes.contains(&path),
+            textures: data.map_or_else(Vec::new, |data| {
+                data.meshes
+                    .iter()
+                    .map(|mesh| (mesh.texture_width, mesh.texture_height, mesh.texture_format))
+                    .collect()
+            }),
+            stats: data.map(|data| NodeStats::from_meshes(&data.meshes)),
+        })
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// One node in a node export.
+#[derive(Clone, Debug, Serialize)]
+pub struct NodeRecord {
+    pub path: String,
+    pub depth: usize,
+    /// OBB centre (ECEF, m).
+    pub center: [f64; 3],
+    /// OBB half-extents along its axes (m).
+    pub extents: [f64; 3],
+    /// OBB axes, column-major.
+    pub orientation: [f64; 9],
+    pub bounds: LatLonBounds,
+    pub meters_per_texel: f32,
+    pub stats: NodeStats,
+}
+
+impl LodState {
+    /// Every loaded node with its stats, sorted by path, for export.
+    #[must_use]
+    pub fn node_records(&self) -> Vec<NodeRecord> {
+        let mut records: Vec<NodeRecord> = self
+            .loaded_nodes()
+            .map(|node| NodeRecord {
+                path: node.path.to_string(),
+                depth: node.depth,
+                center: node.obb.center.to_array(),
+                extents: node.obb.extents.to_array(),
+                orientation: node.obb.orientation.to_cols_array(),
+                bounds: node.lat_lon_bounds(),
+                meters_per_texel: node.meters_per_texel,
+                stats: self.node_stats(node.path).unwrap_or_default(),
+            })
+            .collect();
+        records.sort_by(|a, b| a.path.cmp(&b.path));
+        records
+    }
+}
+
+/// UI → LOD request: when `wanted` is set, the next frame writes every
+/// loaded node's record to `dumps/nodes-<unix-secs>.json`. Native only; a
+/// no-op on wasm.
+#[derive(Resource, Default)]
+pub struct NodeExportRequest {
+    pub wanted: bool,
+}
+
+/// Write the node export when requested.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn process_node_export_requests(
+    mut request: ResMut<NodeExportRequest>,
+    lod_state: Res<LodState>,
+) {
+    if !request.wanted {
+        return;
+    }
+    request.wanted = false;
+
+    let records = lod_state.node_records();
+    let path = format!(
+        "dumps/nodes-{}.json",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    );
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all("dumps")?;
+        let file = std::fs::File::create(&path)?;
+        serde_json::to_writer(std::io::BufWriter::new(file), &records)
+            .map_err(std::io::Error::other)
+    };
+    match write() {
+        Ok(()) => tracing::info!("exported {} node(s) to {path}", records.len()),
+        Err(e) => tracing::warn!("failed to write node export to {path}: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::DMat3;
+    use veldera_geo::coords::lat_lon_to_ecef;
+
+    use super::*;
+
+    #[test]
+    fn bounds_intersect_across_the_antimeridian() {
+        let pacific = LatLonBounds::new(-10.0, 170.0, 10.0, -170.0);
+        assert!(pacific.contains(0.0, 179.0));
+        assert!(pacific.contains(0.0, -179.0));
+        assert!(!pacific.contains(0.0, 0.0));
+
+        assert!(pacific.intersects(&LatLonBounds::new(-1.0, -175.0, 1.0, -160.0)));
+        assert!(pacific.intersects(&LatLonBounds::new(-1.0, 175.0, 1.0, 178.0)));
+        assert!(!pacific.intersects(&LatLonBounds::new(-1.0, 0.0, 1.0, 10.0)));
+        assert!(!pacific.intersects(&LatLonBounds::new(20.0, 175.0, 30.0, 178.0)));
+        assert!(LatLonBounds::GLOBE.intersects(&pacific));
+    }
+
+    #[test]
+    fn obb_bounds_wrap_at_the_antimeridian() {
+        let obb = OrientedBoundingBox {
+            center: lat_lon_to_ecef(0.0, 180.0, 6_371_000.0),
+            extents: DVec3::splat(10_000.0),
+            orientation: DMat3::IDENTITY,
+        };
+        let bounds = LatLonBounds::around_obb(&obb);
+        assert!(bounds.west > bounds.east, "{bounds:?}");
+        assert!(bounds.contains(0.0, 180.0));
+        assert!(bounds.contains(0.0, -179.95));
+        assert!(!bounds.contains(0.0, 0.0));
+    }
+
+    #[test]
+    fn node_metadata_resolves_the_owning_bulk() {
+        let node = |path: &str, epoch: u32| NodeMetadata {
+            path: OctreePath::parse(path).unwrap(),
+            meters_per_texel: 1.0,
+            obb: OrientedBoundingBox {
+                center: DVec3::ZERO,
+                extents: DVec3::ONE,
+                orientation: DMat3::IDENTITY,
+            },
+            has_data: true,
+            epoch,
+            texture_format: 6,
+            imagery_epoch: None,
+        };
+        let mut lod_state = LodState::default();
+        for (bulk_path, nodes) in [
+            (OctreePath::ROOT, vec![node("0123", 1)]),
+            (OctreePath::parse("0123").unwrap(), vec![node("01234", 2)]),
+        ] {
+            let index = nodes
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (n.path.strip_prefix(bulk_path).unwrap(), i))
+                .collect();
+            lod_state.bulk_node_indices.insert(bulk_path, index);
+            lod_state.bulks.insert(
+                bulk_path,
+                rocktree::BulkMetadata {
+                    path: bulk_path,
+                    head_node_center: glam::Vec3::ZERO,
+                    meters_per_texel: Vec::new(),
+                    nodes,
+                    child_bulk_paths: Default::default(),
+                    epoch: 0,
+                },
+            );
+        }
+
+        let epoch = |path: &str| {
+            lod_state
+                .node_metadata(OctreePath::parse(path).unwrap())
+                .map(|n| n.epoch)
+        };
+        // The last level of a bulk lives in it, not in the child bulk it roots.
+        assert_eq!(epoch("0123"), Some(1));
+        assert_eq!(epoch("01234"), Some(2));
+        assert_eq!(epoch("01235"), None);
+        assert_eq!(epoch(""), None);
+    }
+
+    #[test]
+    fn strip_triangles_skip_degenerates() {
+        // Two runs joined by the usual repeated indices.
+        assert_eq!(strip_triangle_count(&[0, 1, 2, 3, 3, 4, 4, 5, 6]), 3);
+        assert_eq!(strip_triangle_count(&[0, 1]), 0);
+    }
+}
@@ -0,0 +1,584 @@
+//! Long-range raycasts against the loaded terrain meshes.
+//!
+//! Physics colliders only exist in a small radius around the camera, so
+//! collider raycasts can't reach terrain tens of kilometres away. These
+//! raycasts instead intersect the rocktree meshes themselves, which are kept
+//! on the CPU in [`LodState`] for every loaded node, in double precision so
+//! they hold up at any distance from the floating origin.
+//!
+//! To avoid testing every loaded node, [`TerrainRayIndex`] arranges the loaded
+//! nodes into their octree hierarchy, each node bounded by a sphere enclosing
+//! its own OBB and all of its loaded descendants. A ray walks the hierarchy
+//! nearest-first, skipping whole subtrees it misses or that lie beyond the
+//! nearest hit so far, and only intersects triangles of nodes whose own OBB it
+//! enters. The renderer's octant masking is mirrored: a parent's triangles in
+//! an octant whose child has loaded are skipped, so a hit is always on the
+//! surface actually drawn.
+//!
+//! [`TerrainRaycast`] is the system parameter for queries: nearest hits for
+//! picking and measurement, and line-of-sight tests.
+
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use glam::DVec3;
+use rocktree_decode::{OctreePath, OrientedBoundingBox};
+
+use veldera_geo::coords::ecef_to_lat_lon;
+
+use crate::lod::{LoadedNodeData, LodState, poll_lod_node_tasks};
+
+/// Plugin that keeps [`TerrainRayIndex`] in step with the loaded nodes.
+pub struct TerrainRaycastPlugin;
+
+impl Plugin for TerrainRaycastPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainRayIndex>().add_systems(
+            Update,
+            rebuild_terrain_ray_index
+                .in_set(TerrainRaycastSystems)
+                .after(poll_lod_node_tasks),
+        );
+    }
+}
+
+/// System set in which [`TerrainRayIndex`] is rebuilt; run raycasts after it
+/// to see the nodes loaded this frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TerrainRaycastSystems;
+
+/// A ray's intersection with the loaded terrain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerrainHit {
+    /// Hit position (ECEF).
+    pub position: DVec3,
+    /// Latitude of the hit (degrees).
+    pub lat_deg: f64,
+    /// Longitude of the hit (degrees).
+    pub lon_deg: f64,
+    /// Height of the hit above the reference sphere (m).
+    pub altitude: f64,
+    /// Distance from the ray origin (m).
+    pub distance: f64,
+    /// Unit surface normal of the hit triangle, facing the ray origin.
+    pub normal: DVec3,
+    /// Path of the node whose mesh was hit.
+    pub path: OctreePath,
+}
+
+impl TerrainHit {
+    fn new(position: DVec3, distance: f64, normal: DVec3, path: OctreePath) -> Self {
+        let (lat_deg, lon_deg) = ecef_to_lat_lon(position);
+        Self {
+            position,
+            lat_deg,
+            lon_deg,
+            altitude: position.length() - veldera_constants::EARTH_RADIUS_M_F64,
+            distance,
+            normal,
+            path,
+        }
+    }
+}
+
+// ============================================================================
+// Hierarchy
+// ============================================================================
+
+/// The loaded nodes arranged into their octree hierarchy for raycasts.
+///
+/// Rebuilt whenever the loaded set changes. A node's parent in the index is
+/// its nearest loaded ancestor, so the hierarchy stays connected even when an
+/// intermediate node has been evicted.
+#[derive(Resource, Default)]
+pub struct TerrainRayIndex {
+    /// Loaded-set signature the index was built for.
+    signature: Option<(u64, usize)>,
+    /// Every indexed node.
+    nodes: HashMap<OctreePath, IndexNode>,
+    /// Nodes with no loaded ancestor.
+    roots: Vec<OctreePath>,
+}
+
+/// One node of the [`TerrainRayIndex`].
+#[derive(Debug)]
+struct IndexNode {
+    /// Sphere enclosing the node's OBB and every indexed descendant.
+    bound: BoundingSphere,
+    /// Nearest loaded descendants.
+    children: Vec<OctreePath>,
+    /// Octants whose direct child has loaded, hiding this node's vertices
+    /// there.
+    octant_mask: u8,
+}
+
+impl TerrainRayIndex {
+    /// Number of indexed nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether no nodes are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Arrange `obbs` (node paths and OBBs) into the hierarchy.
+    fn build<'a>(obbs: impl Iterator<Item = (OctreePath, &'a OrientedBoundingBox)>) -> Self {
+        let mut nodes: HashMap<OctreePath, IndexNode> = obbs
+            .map(|(path, obb)| {
+                let node = IndexNode {
+                    bound: BoundingSphere {
+                        center: obb.center,
+                        radius: obb.extents.length(),
+                    },
+                    children: Vec::new(),
+                    octant_mask: 0,
+                };
+                (path, node)
+            })
+            .collect();
+
+        // Link each node to its nearest loaded ancestor.
+        let mut paths: Vec<OctreePath> = nodes.keys().copied().collect();
+        let mut roots = Vec::new();
+        for &path in &paths {
+            if let Some(parent) = path.parent()
+                && let Some(octant) = path.octant_at(path.depth() - 1)
+                && let Some(node) = nodes.get_mut(&parent)
+            {
+                node.octant_mask |= 1 << octant;
+            }
+            let mut ancestor = path.parent();
+            while let Some(candidate) = ancestor
+                && !nodes.contains_key(&candidate)
+            {
+                ancestor = candidate.parent();
+            }
+            match ancestor.and_then(|ancestor| nodes.get_mut(&ancestor)) {
+                Some(node) => node.children.push(path),
+                None => roots.push(path),
+            }
+        }
+
+        // Grow the bounds bottom-up, deepest first.
+        paths.sort_by_key(|path| std::cmp::Reverse(path.depth()));
+        for path in paths {
+            let node = &nodes[&path];
+            let bound = node
+                .children
+                .iter()
+                .fold(node.bound, |bound, child| bound.merge(&nodes[child].bound));
+            if let Some(node) = nodes.get_mut(&path) {
+                node.bound = bound;
+            }
+        }
+
+        Self {
+            signature: None,
+            nodes,
+            roots,
+        }
+    }
+}
+
+/// Rebuild the index when nodes have loaded or unloaded.
+fn rebuild_terrain_ray_index(lod_state: Res<LodState>, mut index: ResMut<TerrainRayIndex>) {
+    let signature = (
+        lod_state.nodes_completed_version,
+        lod_state.loaded_nodes.len(),
+    );
+    if index.signature == Some(signature) {
+        return;
+    }
+    // Nodes without mesh data are still indexed so they mask their parents'
+    // octants, as they do in the renderer; raycasts skip their triangles.
+    *index = TerrainRayIndex::build(
+        lod_state
+            .loaded_nodes
+            .iter()
+            .filter_map(|path| Some((*path, lod_state.node_obbs.get(path)?))),
+    );
+    index.signature = Some(signature);
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+/// System parameter for raycasting against the loaded terrain meshes.
+#[derive(SystemParam)]
+pub struct TerrainRaycast<'w> {
+    lod_state: Res<'w, LodState>,
+    index: Res<'w, TerrainRayIndex>,
+}
+
+impl TerrainRaycast<'_> {
+    /// Nearest terrain hit along the ray from `origin` (ECEF) in `direction`,
+    /// within `max_distance` metres.
+    pub fn cast_ray(
+        &self,
+        origin: DVec3,
+        direction: DVec3,
+        max_distance: f64,
+    ) -> Option<TerrainHit> {
+        let direction = direction.try_normalize()?;
+        let mut best = None;
+        let mut roots = self.entered(&self.index.roots, origin, direction, max_distance);
+        roots.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (entry, path) in roots {
+            self.visit(path, entry, origin, direction, max_distance, &mut best);
+        }
+        best.map(|(distance, normal, path)| {
+            TerrainHit::new(origin + direction * distance, distance, normal, path)
+        })
+    }
+
+    /// Whether the straight line from `from` to `to` (ECEF) clears the loaded
+    /// terrain.
+    ///
+    /// Terrain within `tolerance` metres of either end is ignored, so points
+    /// resting on the surface can still see each other.
+    pub fn line_of_sight(&self, from: DVec3, to: DVec3, tolerance: f64) -> bool {
+        let offset = to - from;
+        let length = offset.length();
+        if length <= 2.0 * tolerance {
+            return true;
+        }
+        let direction = offset / length;
+        let start = from + direction * tolerance;
+        self.cast_ray(start, direction, length - 2.0 * tolerance)
+            .is_none()
+    }
+
+    /// Ground hit straight below `position` (ECEF), searching from `height`
+    /// metres above it; for measuring terrain height at a point.
+    pub fn ground_below(&self, position: DVec3, height: f64) -> Option<TerrainHit> {
+        let up = position.try_normalize()?;
+        self.cast_ray(position + up * height, -up, height * 2.0)
+    }
+
+    /// The subset of `paths` whose bounds the ray enters within
+    /// `max_distance`, with their entry distances.
+    fn entered(
+        &self,
+        paths: &[OctreePath],
+        origin: DVec3,
+        direction: DVec3,
+        max_distance: f64,
+    ) -> Vec<(f64, OctreePath)> {
+        paths
+            .iter()
+            .filter_map(|path| {
+                let node = self.index.nodes.get(path)?;
+                let entry = node.bound.ray_entry(origin, direction, max_distance)?;
+                Some((entry, *path))
+            })
+            .collect()
+    }
+
+    /// Intersect the subtree at `path` (whose bound the ray enters at
+    /// `entry`), updating `best`.
+    fn visit(
+        &self,
+        path: OctreePath,
+        entry: f64,
+        origin: DVec3,
+        direction: DVec3,
+        max_distance: f64,
+        best: &mut Option<(f64, DVec3, OctreePath)>,
+    ) {
+        let nearest = best.map_or(max_distance, |(distance, ..)| distance);
+        if entry > nearest {
+            return;
+        }
+        let Some(node) = self.index.nodes.get(&path) else {
+            return;
+        };
+
+        // The node's own triangles, unless its children cover it entirely.
+        // The index may be a frame stale, so the node may have unloaded.
+        if node.octant_mask != 0xff
+            && let Some(obb) = self.lod_state.node_obbs.get(&path)
+            && let Some(data) = self.lod_state.node_data.get(&path)
+            && ray_obb_entry(obb, origin, direction, nearest).is_some()
+            && let Some((distance, normal)) =
+                intersect_node(data, node.octant_mask, origin, direction, nearest)
+        {
+            *best = Some((distance, normal, path));
+        }
+
+        let nearest = best.map_or(max_distance, |(distance, ..)| distance);
+        let mut children = self.entered(&node.children, origin, direction, nearest);
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (entry, child) in children {
+            self.visit(child, entry, origin, direction, max_distance, best);
+        }
+    }
+}
+
+// ============================================================================
+// Geometry
+// ============================================================================
+
+/// A bounding sphere.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct BoundingSphere {
+    center: DVec3,
+    radius: f64,
+}
+
+impl BoundingSphere {
+    /// The smallest sphere enclosing both `self` and `other`.
+    fn merge(&self, other: &Self) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.length();
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        Self {
+            center: self.center + offset * ((radius - self.radius) / distance),
+            radius,
+        }
+    }
+
+    /// Distance along the (unit) ray at which it enters the sphere (0 if it
+    /// starts inside), or `None` if it misses within `max_distance`.
+    fn ray_entry(&self, origin: DVec3, direction: DVec3, max_distance: f64) -> Option<f64> {
+        let to_center = self.center - origin;
+        let along = to_center.dot(direction);
+        let closest_sq = to_center.length_squared() - along * along;
+        let radius_sq = self.radius * self.radius;
+        if closest_sq > radius_sq {
+            return None;
+        }
+        let half_chord = (radius_sq - closest_sq).sqrt();
+        if along + half_chord < 0.0 {
+            return None;
+        }
+        let entry = (along - half_chord).max(0.0);
+        (entry <= max_distance).then_some(entry)
+    }
+}
+
+/// Distance along the ray at which it enters `obb` (0 if it starts inside),
+/// or `None` if it misses within `max_distance`.
+fn ray_obb_entry(
+    obb: &OrientedBoundingBox,
+    origin: DVec3,
+    direction: DVec3,
+    max_distance: f64,
+) -> Option<f64> {
+    // Slab test in the box's frame.
+    let inverse = obb.orientation.transpose();
+    let local_origin = inverse * (origin - obb.center);
+    let local_direction = inverse * direction;
+    let mut near = 0.0_f64;
+    let mut far = max_distance;
+    for axis in 0..3 {
+        let (o, d, extent) = (local_origin[axis], local_direction[axis], obb.extents[axis]);
+        if d.abs() < 1e-12 {
+            if o.abs() > extent {
+                return None;
+            }
+            continue;
+        }
+        let (t0, t1) = ((-extent - o) / d, (extent - o) / d);
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+        if near > far {
+            return None;
+        }
+    }
+    Some(near)
+}
+
+/// Nearest intersection of the ray with `node`'s visible triangles closer
+/// than `max_distance`, as `(distance, normal)`.
+///
+/// Triangles touching a vertex in a `masked` octant are skipped, as the
+/// vertex shader collapses them.
+fn intersect_node(
+    node: &LoadedNodeData,
+    masked: u8,
+    origin: DVec3,
+    direction: DVec3,
+    max_distance: f64,
+) -> Option<(f64, DVec3)> {
+    let rotation = node.transform.rotation.as_dquat();
+    let scale = node.transform.scale.as_dvec3();
+    let mut best: Option<(f64, DVec3)> = None;
+    for mesh in node.meshes.iter() {
+        let is_masked =
+            |octant: u8| mesh.has_octant_data && octant < 8 && masked & (1 << octant) != 0;
+        let positions: Vec<Option<DVec3>> = mesh
+            .vertices
+            .iter()
+            .map(|v| {
+                (!is_masked(v.w)).then(|| {
+                    let local = DVec3::new(f64::from(v.x), f64::from(v.y), f64::from(v.z));
+                    node.world_position + rotation * (scale * local)
+                })
+            })
+            .collect();
+        for triangle in rocktree_decode::strip_to_triangles(&mesh.indices).chunks_exact(3) {
+            let (Some(a), Some(b), Some(c)) = (
+                positions[usize::from(triangle[0])],
+                positions[usize::from(triangle[1])],
+                positions[usize::from(triangle[2])],
+            ) else {
+                continue;
+            };
+            let nearest = best.map_or(max_distance, |(distance, _)| distance);
+            if let Some(distance) = ray_triangle(origin, direction, [a, b, c])
+                && distance < nearest
+            {
+                let normal = (b - a).cross(c - a).normalize_or_zero();
+                let normal = if normal.dot(direction) > 0.0 {
+                    -normal
+                } else {
+                    normal
+                };
+                best = Some((distance, normal));
+            }
+        }
+    }
+    best
+}
+
+/// Möller–Trumbore ray/triangle intersection, double-sided. Returns the
+/// distance along the (unit) ray.
+fn ray_triangle(origin: DVec3, direction: DVec3, [a, b, c]: [DVec3; 3]) -> Option<f64> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv_det;
+    (t >= 0.0).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::DMat3;
+
+    use super::*;
+
+    fn cube(center: DVec3, half: f64) -> OrientedBoundingBox {
+        OrientedBoundingBox {
+            center,
+            extents: DVec3::splat(half),
+            orientation: DMat3::IDENTITY,
+        }
+    }
+
+    #[test]
+    fn ray_hits_triangle_from_either_side() {
+        let triangle = [
+            DVec3::new(-1.0, -1.0, 0.0),
+            DVec3::new(1.0, -1.0, 0.0),
+            DVec3::new(0.0, 1.0, 0.0),
+        ];
+        let down = ray_triangle(DVec3::new(0.0, 0.0, 5.0), DVec3::NEG_Z, triangle);
+        assert_eq!(down, Some(5.0));
+        let up = ray_triangle(DVec3::new(0.0, 0.0, -2.0), DVec3::Z, triangle);
+        assert_eq!(up, Some(2.0));
+        assert!(ray_triangle(DVec3::new(3.0, 0.0, 5.0), DVec3::NEG_Z, triangle).is_none());
+        assert!(ray_triangle(DVec3::new(0.0, 0.0, 5.0), DVec3::Z, triangle).is_none());
+    }
+
+    #[test]
+    fn ray_enters_rotated_obb() {
+        let obb = OrientedBoundingBox {
+            center: DVec3::new(10.0, 0.0, 0.0),
+            extents: DVec3::new(1.0, 1.0, 1.0),
+            orientation: DMat3::from_rotation_z(std::f64::consts::FRAC_PI_4),
+        };
+        // Along the x axis the rotated box's corner sits sqrt(2) in front of
+        // the centre.
+        let entry = ray_obb_entry(&obb, DVec3::ZERO, DVec3::X, 100.0).unwrap();
+        assert!((entry - (10.0 - std::f64::consts::SQRT_2)).abs() < 1e-9);
+        assert!(ray_obb_entry(&obb, DVec3::ZERO, DVec3::Y, 100.0).is_none());
+        assert!(ray_obb_entry(&obb, DVec3::ZERO, DVec3::X, 5.0).is_none());
+        // Starting inside enters immediately.
+        assert_eq!(ray_obb_entry(&obb, obb.center, DVec3::Y, 100.0), Some(0.0));
+    }
+
+    #[test]
+    fn merged_sphere_encloses_both() {
+        let a = BoundingSphere {
+            center: DVec3::ZERO,
+            radius: 1.0,
+        };
+        let b = BoundingSphere {
+            center: DVec3::new(10.0, 0.0, 0.0),
+            radius: 2.0,
+        };
+        let merged = a.merge(&b);
+        assert!((merged.radius - 6.5).abs() < 1e-9);
+        assert!(merged.center.distance(DVec3::new(5.5, 0.0, 0.0)) < 1e-9);
+        // A sphere inside another merges to the outer one.
+        let inner = BoundingSphere {
+            center: DVec3::new(10.5, 0.0, 0.0),
+            radius: 0.5,
+        };
+        assert_eq!(b.merge(&inner), b);
+
+        assert_eq!(
+            a.ray_entry(DVec3::new(-5.0, 0.0, 0.0), DVec3::X, 10.0),
+            Some(4.0)
+        );
+        assert_eq!(a.ray_entry(DVec3::ZERO, DVec3::X, 10.0), Some(0.0));
+        assert!(
+            a.ray_entry(DVec3::new(5.0, 0.0, 0.0), DVec3::X, 10.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn hierarchy_links_nearest_loaded_ancestor() {
+        let root = OctreePath::ROOT.push(0);
+        let child = root.push(3);
+        // Neither the node between `child` and `grandchild` nor the parent
+        // of `other_root` is loaded.
+        let grandchild = child.push(1).push(5);
+        let other_root = OctreePath::ROOT.push(7).push(7);
+        let obbs = [
+            (root, cube(DVec3::ZERO, 10.0)),
+            (child, cube(DVec3::new(5.0, 0.0, 0.0), 5.0)),
+            (grandchild, cube(DVec3::new(30.0, 0.0, 0.0), 1.0)),
+            (other_root, cube(DVec3::new(0.0, 100.0, 0.0), 1.0)),
+        ];
+        let index = TerrainRayIndex::build(obbs.iter().map(|(path, obb)| (*path, obb)));
+
+        assert_eq!(index.len(), 4);
+        let mut roots = index.roots.clone();
+        roots.sort_by_key(|path| path.depth());
+        assert_eq!(roots, vec![root, other_root]);
+        assert_eq!(index.nodes[&root].children, vec![child]);
+        assert_eq!(index.nodes[&child].children, vec![grandchild]);
+        assert_eq!(index.nodes[&root].octant_mask, 1 << 3);
+        assert_eq!(index.nodes[&child].octant_mask, 0);
+
+        // The root's bound grows to reach the out-of-box grandchild.
+        let bound = index.nodes[&root].bound;
+        assert!(bound.center.distance(DVec3::new(31.0, 0.0, 0.0)) <= bound.radius + 1e-9);
+    }
+}
@@ -2,38 +2,157 @@
 //!
 //! Extends `StandardMaterial` with per-vertex octant masking to hide vertices
 //! in octants that have loaded children, enabling seamless LOD transitions.
+//!
+//! It also carries an optional stylization layer, [`TerrainStyle`]: a snow tint
+//! on high, upward-facing ground and a gentle desaturation with distance. Off by
+//! default, since the photogrammetry is meant to read as photographed. The vertex
+//! shader reconstructs each vertex's ECEF position from the mesh's globe origin
+//! and derives its height above the WGS84 ellipsoid and local vertical, so the
+//! snow line holds across tiles and LOD levels. The same height draws the
+//! optional contour lines, with every few lines drawn heavier as index
+//! contours; they have their own toggle, independent of the stylization.
+//!
+//! The shader also shades water, where the mesh's per-vertex
+//! [water likeness](crate::water) agrees with flat ground near sea level,
+//! with waves and a glossy sun glint.
+//!
+//! The prepass, which also renders the sun's shadow maps, has its own vertex
+//! shader applying the same octant mask, so a parent tile's hidden octants
+//! neither write depth nor shadow the children drawn in their place.
+//!
+//! A debug tint, [`TerrainMaterialExtension::depth_tint`], can wash each tile
+//! in a colour chosen by the LOD overlay (see
+//! [`LodVizSettings::tint_by_depth`](crate::collider::viz::LodVizSettings)).
+//!
+//! For diagnosing mesh and texture quality, [`TerrainDebugView`] swaps the
+//! shading for a faceted wireframe view, a UV checker, a texel-density ramp,
+//! or an additive overdraw count. It also has two analysis views for route
+//! planning, tinting the terrain by slope angle or by aspect (the compass
+//! direction a slope faces) from the mesh normals, with the bands set by
+//! [`TerrainAnalysis`].
 
 use bevy::{
     asset::embedded_asset,
+    math::DVec3,
     mesh::MeshVertexBufferLayoutRef,
     pbr::{ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline},
     prelude::*,
     render::render_resource::{
-        AsBindGroup, RenderPipelineDescriptor, SpecializedMeshPipelineError,
+        AsBindGroup, RenderPipelineDescriptor, ShaderType, SpecializedMeshPipelineError,
     },
     shader::ShaderRef,
 };
+use serde::Deserialize;
+use veldera_config::ConfigPlugin;
+
+use crate::{
+    mesh::RocktreeMeshMarker,
+    water::{WaterUniform, wave_origin},
+};
+
+/// Plugin that registers the terrain material and its stylization config.
+///
+/// Defaults to the config at [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+/// in the shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout.
+pub struct TerrainMaterialPlugin {
+    /// Path to the [`TerrainStyle`] TOML.
+    pub config_path: &'static str,
+}
 
-/// Plugin that registers the terrain material.
-pub struct TerrainMaterialPlugin;
+impl TerrainMaterialPlugin {
+    /// Canonical [`TerrainStyle`] path within the shared engine asset subtree.
+    pub const DEFAULT_CONFIG_PATH: &'static str = "engine/config/rendering/terrain_style.toml";
+
+    /// Create the plugin, loading its stylization config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Default for TerrainMaterialPlugin {
+    /// Load the stylization config from [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONFIG_PATH)
+    }
+}
 
 impl Plugin for TerrainMaterialPlugin {
     fn build(&self, app: &mut App) {
         embedded_asset!(app, "terrain_material.wgsl");
-        app.add_plugins(MaterialPlugin::<TerrainMaterial>::default());
+        embedded_asset!(app, "terrain_prepass.wgsl");
+        app.add_plugins(MaterialPlugin::<TerrainMaterial>::default())
+            .add_plugins(ConfigPlugin::<TerrainStyle>::new(self.config_path))
+            .init_resource::<TerrainDebugView>()
+            .init_resource::<TerrainAnalysis>()
+            .add_systems(PostUpdate, (apply_terrain_style, apply_debug_view));
     }
 }
 
 /// Terrain material: StandardMaterial extended with octant masking.
 pub type TerrainMaterial = ExtendedMaterial<StandardMaterial, TerrainMaterialExtension>;
 
-/// Extension to StandardMaterial that adds octant masking for LOD transitions.
+/// Extension to StandardMaterial that adds octant masking for LOD transitions
+/// and the optional [`TerrainStyle`] layer.
 #[derive(Asset, AsBindGroup, TypePath, Debug, Clone, Default)]
 pub struct TerrainMaterialExtension {
     /// Bitmask of octants to hide (bit `i` set = octant `i` has a loaded child).
     /// Stored in `.x`; padded to 16 bytes for WebGL compatibility.
     #[uniform(100)]
     pub octant_mask: UVec4,
+    /// ECEF position of the mesh's local origin (m) in `.xyz`, from which the
+    /// shader rebuilds per-vertex globe positions. `f32` costs about half a
+    /// metre at Earth radius, which is plenty for a snow line.
+    #[uniform(101)]
+    pub globe_origin: Vec4,
+    /// The stylization parameters, mirrored from [`TerrainStyle`].
+    #[uniform(102)]
+    pub style: TerrainStyleUniform,
+    /// Debug tint: linear RGB in `.rgb`, blend weight in `.a` (0 = off).
+    #[uniform(103)]
+    pub depth_tint: Vec4,
+    /// [`TerrainDebugView::shader_index`] in `.x` (0 = off) and the base
+    /// colour texture's size in texels in `.yz`, for the density ramp.
+    #[uniform(104)]
+    pub debug_view: Vec4,
+    /// [`TerrainAnalysis`] slope band edges (degrees), ascending.
+    #[uniform(105)]
+    pub analysis_bands: Vec4,
+    /// [`TerrainAnalysis`] aspect minimum slope (degrees) in `.x` and tint
+    /// opacity in `.y`.
+    #[uniform(106)]
+    pub analysis_params: Vec4,
+    /// SSAO strength in `.x` and fade start and end distance (m) in `.yz`,
+    /// from [`AmbientOcclusionConfig`](crate::ambient_occlusion::AmbientOcclusionConfig).
+    #[uniform(107)]
+    pub ambient_occlusion: Vec4,
+    /// Water detection and shading parameters, from
+    /// [`WaterConfig`](crate::water::WaterConfig).
+    #[uniform(108)]
+    pub water: WaterUniform,
+    /// The globe origin reduced modulo the wave period (m) in `.xyz`, the
+    /// phase origin of the water's waves (see [`wave_origin`]).
+    #[uniform(109)]
+    pub wave_origin: Vec4,
+}
+
+impl TerrainMaterialExtension {
+    /// Extension for a mesh whose local origin sits at `globe_origin` (ECEF),
+    /// with no octants masked yet.
+    pub fn new(globe_origin: DVec3, style: &TerrainStyle) -> Self {
+        Self {
+            octant_mask: UVec4::ZERO,
+            globe_origin: globe_origin.as_vec3().extend(0.0),
+            style: style.uniform(),
+            depth_tint: Vec4::ZERO,
+            debug_view: Vec4::ZERO,
+            analysis_bands: Vec4::ZERO,
+            analysis_params: Vec4::ZERO,
+            ambient_occlusion: Vec4::ZERO,
+            water: WaterUniform::default(),
+            wave_origin: wave_origin(globe_origin),
+        }
+    }
 }
 
 impl MaterialExtension for TerrainMaterialExtension {
@@ -42,8 +161,14 @@ impl MaterialExtension for TerrainMaterialExtension {
     }
 
     fn fragment_shader() -> ShaderRef {
-        // Use the default PBR fragment shader.
-        ShaderRef::Default
+        // The standard PBR fragment, with the stylization applied to the base
+        // colour ahead of lighting.
+        "embedded://veldera_terrain/terrain_material.wgsl".into()
+    }
+
+    fn prepass_vertex_shader() -> ShaderRef {
+        // Depth and shadow passes, with the octant mask applied.
+        "embedded://veldera_terrain/terrain_prepass.wgsl".into()
     }
 
     fn specialize(
@@ -57,3 +182,327 @@ impl MaterialExtension for TerrainMaterialExtension {
         Ok(())
     }
 }
+
+// ============================================================================
+// Stylization
+// ============================================================================
+
+/// Optional non-photoreal shading for the terrain, loaded from
+/// `terrain_style.toml`.
+///
+/// Snow needs both altitude and an upward-facing surface: it fades in over
+/// `snow_fade_m` above `snow_line_m`, and over `snow_slope_fade` below
+/// `snow_min_up` (the cosine between the surface normal and the local
+/// vertical), so cliffs stay bare. Desaturation ramps from nothing at
+/// `desaturate_start_m` from the camera to `desaturate_amount` at
+/// `desaturate_end_m`, on top of the atmosphere's aerial perspective.
+/// Contours are drawn every `contour_interval_m` when `contours` is on,
+/// whatever `enabled` says, with every `contour_major_every`th line heavier.
+#[derive(Asset, Resource, TypePath, Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainStyle {
+    /// Master toggle. Off keeps the textures exactly as photographed.
+    pub enabled: bool,
+    /// Height above the WGS84 ellipsoid where snow begins (m).
+    pub snow_line_m: f32,
+    /// Height band above the snow line over which snow reaches full cover (m).
+    pub snow_fade_m: f32,
+    /// Minimum normal-vs-vertical cosine for full snow cover.
+    pub snow_min_up: f32,
+    /// Cosine band below `snow_min_up` over which snow fades out.
+    pub snow_slope_fade: f32,
+    /// Linear RGB snow tint.
+    pub snow_color: [f32; 3],
+    /// Blend weight of full snow cover; below 1 the texture shows through.
+    pub snow_strength: f32,
+    /// Camera distance where desaturation begins (m).
+    pub desaturate_start_m: f32,
+    /// Camera distance where desaturation reaches `desaturate_amount` (m).
+    pub desaturate_end_m: f32,
+    /// Fraction of saturation removed at `desaturate_end_m` and beyond.
+    pub desaturate_amount: f32,
+    /// Contour line toggle.
+    pub contours: bool,
+    /// Height between contour lines (m).
+    pub contour_interval_m: f32,
+    /// Every this many contours is a heavier index contour.
+    pub contour_major_every: u32,
+    /// Linear RGB contour colour.
+    pub contour_color: [f32; 3],
+    /// Blend weight of the contour lines.
+    pub contour_opacity: f32,
+}
+
+impl Default for TerrainStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snow_line_m: 2_800.0,
+            snow_fade_m: 600.0,
+            snow_min_up: 0.75,
+            snow_slope_fade: 0.2,
+            snow_color: [0.9, 0.92, 0.96],
+            snow_strength: 0.85,
+            desaturate_start_m: 2_000.0,
+            desaturate_end_m: 40_000.0,
+            desaturate_amount: 0.3,
+            contours: false,
+            contour_interval_m: 50.0,
+            contour_major_every: 5,
+            contour_color: [0.22, 0.12, 0.04],
+            contour_opacity: 0.8,
+        }
+    }
+}
+
+impl TerrainStyle {
+    /// The GPU-side parameters. A disabled style zeroes both effect strengths
+    /// so the shader leaves the base colour untouched; likewise disabled
+    /// contours.
+    pub fn uniform(&self) -> TerrainStyleUniform {
+        let enabled = if self.enabled { 1.0 } else { 0.0 };
+        let contours = if self.contours { 1.0 } else { 0.0 };
+        let [r, g, b] = self.snow_color;
+        let [cr, cg, cb] = self.contour_color;
+        TerrainStyleUniform {
+            snow_color: Vec4::new(r, g, b, self.snow_strength.clamp(0.0, 1.0) * enabled),
+            snow_altitude: Vec2::new(self.snow_line_m, self.snow_fade_m.max(1.0)),
+            snow_slope: Vec2::new(self.snow_min_up, self.snow_slope_fade.max(1e-3)),
+            desaturate_range: Vec2::new(
+                self.desaturate_start_m,
+                self.desaturate_end_m.max(self.desaturate_start_m + 1.0),
+            ),
+            desaturate_amount: self.desaturate_amount.clamp(0.0, 1.0) * enabled,
+            contour_color: Vec4::new(cr, cg, cb, self.contour_opacity.clamp(0.0, 1.0) * contours),
+            contour_spacing: Vec2::new(
+                self.contour_interval_m.max(1.0),
+                self.contour_major_every.max(1) as f32,
+            ),
+        }
+    }
+
+    /// Height between index contours (m).
+    pub fn major_contour_interval_m(&self) -> f32 {
+        self.contour_interval_m.max(1.0) * self.contour_major_every.max(1) as f32
+    }
+}
+
+/// [`TerrainStyle`] packed for the shader.
+#[derive(ShaderType, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TerrainStyleUniform {
+    /// Snow tint in `.rgb`, full-cover blend weight in `.a` (0 = disabled).
+    pub snow_color: Vec4,
+    /// Snow line and fade band (m).
+    pub snow_altitude: Vec2,
+    /// Full-cover normal cosine and fade band.
+    pub snow_slope: Vec2,
+    /// Desaturation start and end distance (m).
+    pub desaturate_range: Vec2,
+    /// Maximum desaturation (0 = disabled).
+    pub desaturate_amount: f32,
+    /// Contour tint in `.rgb`, blend weight in `.a` (0 = disabled).
+    pub contour_color: Vec4,
+    /// Contour interval (m) and lines per index contour.
+    pub contour_spacing: Vec2,
+}
+
+/// Push the current [`TerrainStyle`] into every terrain material when it
+/// changes.
+///
+/// Compares against the last applied uniform rather than relying on change
+/// detection: the debug UI borrows the config mutably every frame it's shown,
+/// and touching every material re-uploads all of their bind groups.
+fn apply_terrain_style(
+    style: Res<TerrainStyle>,
+    mut applied: Local<Option<TerrainStyleUniform>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+) {
+    let uniform = style.uniform();
+    if *applied == Some(uniform) {
+        return;
+    }
+    *applied = Some(uniform);
+    for (_, material) in materials.iter_mut() {
+        material.extension.style = uniform;
+    }
+}
+
+// ============================================================================
+// Debug views
+// ============================================================================
+
+/// Diagnostic shading for the terrain, cycled from the keyboard and picked in
+/// the Rendering tab.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TerrainDebugView {
+    /// Normal shading.
+    #[default]
+    Off,
+    /// Flat grey with per-triangle normals, so every facet reads on its own,
+    /// plus the render-mesh wireframe overlay near the camera (see
+    /// [`RenderMeshVizFilter`](crate::collider::viz::RenderMeshVizFilter)).
+    Wireframe,
+    /// A 16×16 checker over each tile's texture space, tinted red along U
+    /// and green along V, to expose stretching and seams.
+    UvChecker,
+    /// Texels per metre on screen as a colour ramp: blue at 1/16 texel/m or
+    /// less, through green, to red at 64 texels/m or more.
+    TexelDensity,
+    /// Every rasterized terrain fragment adds a fixed amount of light with
+    /// depth testing against other terrain disabled, so overlapping LOD
+    /// levels and folded photogrammetry glow brighter.
+    Overdraw,
+    /// Slope angle against the local vertical, in the [`TerrainAnalysis`]
+    /// bands: untinted below the first edge, then yellow, orange, red and
+    /// purple, as on avalanche terrain maps.
+    Slope,
+    /// The compass direction each slope faces, as a hue wheel (north red,
+    /// east yellow-green, south cyan, west violet); ground flatter than
+    /// [`TerrainAnalysis::aspect_min_slope_deg`] is untinted.
+    Aspect,
+}
+
+impl TerrainDebugView {
+    /// Every view, in cycle order.
+    pub const ALL: [TerrainDebugView; 7] = [
+        TerrainDebugView::Off,
+        TerrainDebugView::Wireframe,
+        TerrainDebugView::UvChecker,
+        TerrainDebugView::TexelDensity,
+        TerrainDebugView::Overdraw,
+        TerrainDebugView::Slope,
+        TerrainDebugView::Aspect,
+    ];
+
+    /// The view after this one, wrapping back to [`Off`](Self::Off).
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&view| view == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Short human-readable name.
+    pub fn label(self) -> &'static str {
+        match self {
+            TerrainDebugView::Off => "Off",
+            TerrainDebugView::Wireframe => "Wireframe",
+            TerrainDebugView::UvChecker => "UV checker",
+            TerrainDebugView::TexelDensity => "Texel density",
+            TerrainDebugView::Overdraw => "Overdraw",
+            TerrainDebugView::Slope => "Slope",
+            TerrainDebugView::Aspect => "Aspect",
+        }
+    }
+
+    /// The mode number `terrain_material.wgsl` switches on.
+    fn shader_index(self) -> f32 {
+        match self {
+            TerrainDebugView::Off => 0.0,
+            TerrainDebugView::Wireframe => 1.0,
+            TerrainDebugView::UvChecker => 2.0,
+            TerrainDebugView::TexelDensity => 3.0,
+            TerrainDebugView::Overdraw => 4.0,
+            TerrainDebugView::Slope => 5.0,
+            TerrainDebugView::Aspect => 6.0,
+        }
+    }
+
+    /// Whether this is one of the slope/aspect analysis views.
+    pub fn is_analysis(self) -> bool {
+        matches!(self, TerrainDebugView::Slope | TerrainDebugView::Aspect)
+    }
+}
+
+/// Bands for the [`Slope`](TerrainDebugView::Slope) and
+/// [`Aspect`](TerrainDebugView::Aspect) views, adjusted in the Rendering tab.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct TerrainAnalysis {
+    /// Slope band edges (degrees). The defaults follow common avalanche
+    /// terrain maps: 27°, 30°, 35° and 40°.
+    pub slope_bands_deg: [f32; 4],
+    /// Slopes flatter than this have no meaningful aspect (degrees).
+    pub aspect_min_slope_deg: f32,
+    /// How strongly the analysis colours cover the texture, 0 to 1.
+    pub opacity: f32,
+}
+
+impl Default for TerrainAnalysis {
+    fn default() -> Self {
+        Self {
+            slope_bands_deg: [27.0, 30.0, 35.0, 40.0],
+            aspect_min_slope_deg: 5.0,
+            opacity: 0.7,
+        }
+    }
+}
+
+impl TerrainAnalysis {
+    /// The band edges, forced ascending, for the shader.
+    fn bands_uniform(&self) -> Vec4 {
+        let mut edges = self.slope_bands_deg;
+        for i in 1..edges.len() {
+            edges[i] = edges[i].max(edges[i - 1]);
+        }
+        Vec4::from_array(edges)
+    }
+
+    /// The aspect cutoff and opacity, for the shader.
+    fn params_uniform(&self) -> Vec4 {
+        Vec4::new(
+            self.aspect_min_slope_deg.max(0.0),
+            self.opacity.clamp(0.0, 1.0),
+            0.0,
+            0.0,
+        )
+    }
+}
+
+/// Push the current [`TerrainDebugView`] and [`TerrainAnalysis`] into the
+/// terrain materials: every material when either changes, otherwise only
+/// those of newly spawned meshes.
+///
+/// Overdraw switches the materials to additive blending, which moves them to
+/// the transparent pass: terrain stops writing depth, so each layer adds to
+/// the count instead of hiding the ones behind it.
+fn apply_debug_view(
+    view: Res<TerrainDebugView>,
+    analysis: Res<TerrainAnalysis>,
+    mut applied: Local<Option<(TerrainDebugView, TerrainAnalysis)>>,
+    meshes: Query<Ref<MeshMaterial3d<TerrainMaterial>>, With<RocktreeMeshMarker>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+    images: Res<Assets<Image>>,
+) {
+    let all = *applied != Some((*view, *analysis));
+    if !all && *view == TerrainDebugView::Off {
+        return;
+    }
+    *applied = Some((*view, *analysis));
+    let (bands, params) = (analysis.bands_uniform(), analysis.params_uniform());
+
+    let alpha_mode = if *view == TerrainDebugView::Overdraw {
+        AlphaMode::Add
+    } else {
+        AlphaMode::Opaque
+    };
+    for material in &meshes {
+        if !all && !material.is_added() {
+            continue;
+        }
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        let texture_size = material
+            .base
+            .base_color_texture
+            .as_ref()
+            .and_then(|texture| images.get(texture))
+            .map_or(Vec2::ONE, |image| image.size_f32());
+        material.extension.debug_view =
+            Vec4::new(view.shader_index(), texture_size.x, texture_size.y, 0.0);
+        material.extension.analysis_bands = bands;
+        material.extension.analysis_params = params;
+        if material.base.alpha_mode != alpha_mode {
+            material.base.alpha_mode = alpha_mode;
+        }
+    }
+}
@@ -1,8 +1,9 @@
 #import bevy_pbr::{
     pbr_fragment::pbr_input_from_standard_material,
-    pbr_functions::alpha_discard,
-    mesh_view_bindings::view,
-    forward_io::{Vertex, VertexOutput},
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    pbr_types::STANDARD_MATERIAL_FLAGS_UNLIT_BIT,
+    mesh_view_bindings::{globals, view},
+    forward_io::{Vertex, VertexOutput, FragmentOutput},
     mesh_functions,
     view_transformations::position_world_to_clip,
 }
@@ -11,6 +12,73 @@
 // Padded to vec4 for WebGL 16-byte uniform alignment.
 @group(#{MATERIAL_BIND_GROUP}) @binding(100) var<uniform> octant_mask: vec4<u32>;
 
+// ECEF position of the mesh's local origin in `.xyz` (metres).
+@group(#{MATERIAL_BIND_GROUP}) @binding(101) var<uniform> globe_origin: vec4<f32>;
+
+// Optional stylization; see `TerrainStyle`. Zero strengths disable it.
+struct TerrainStyle {
+    // Tint in `.rgb`, full-cover blend weight in `.a`.
+    snow_color: vec4<f32>,
+    // Snow line and fade band (m).
+    snow_altitude: vec2<f32>,
+    // Full-cover normal cosine and fade band.
+    snow_slope: vec2<f32>,
+    // Desaturation start and end distance (m).
+    desaturate_range: vec2<f32>,
+    desaturate_amount: f32,
+    // Contour tint in `.rgb`, blend weight in `.a`.
+    contour_color: vec4<f32>,
+    // Contour interval (m) and lines per index contour.
+    contour_spacing: vec2<f32>,
+}
+@group(#{MATERIAL_BIND_GROUP}) @binding(102) var<uniform> style: TerrainStyle;
+
+// Debug tint by LOD depth: linear colour in `.rgb`, blend weight in `.a`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(103) var<uniform> depth_tint: vec4<f32>;
+
+// Debug view (see `TerrainDebugView`): mode in `.x`, base colour texture size
+// in texels in `.yz`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(104) var<uniform> debug_view: vec4<f32>;
+
+const DEBUG_WIREFRAME: u32 = 1u;
+const DEBUG_UV_CHECKER: u32 = 2u;
+const DEBUG_TEXEL_DENSITY: u32 = 3u;
+const DEBUG_OVERDRAW: u32 = 4u;
+const DEBUG_SLOPE: u32 = 5u;
+const DEBUG_ASPECT: u32 = 6u;
+
+// Analysis bands (see `TerrainAnalysis`): slope band edges in degrees,
+// ascending.
+@group(#{MATERIAL_BIND_GROUP}) @binding(105) var<uniform> analysis_bands: vec4<f32>;
+
+// Aspect minimum slope (degrees) in `.x`, tint opacity in `.y`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(106) var<uniform> analysis_params: vec4<f32>;
+
+// SSAO strength in `.x`, fade start and end distance (m) in `.yz`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(107) var<uniform> ambient_occlusion: vec4<f32>;
+
+// Water detection and shading; see `WaterConfig`.
+struct Water {
+    // Strength (0 = disabled), minimum likeness, maximum altitude (m) and
+    // minimum normal cosine.
+    detection: vec4<f32>,
+    // Deep-water tint in `.rgb`, blend weight in `.a`.
+    color: vec4<f32>,
+    // Roughness, wave strength and wave fade distance (m).
+    surface: vec4<f32>,
+}
+@group(#{MATERIAL_BIND_GROUP}) @binding(108) var<uniform> water: Water;
+
+// The mesh origin's ECEF position modulo `WAVE_PERIOD_M` in `.xyz`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(109) var<uniform> wave_origin: vec4<f32>;
+
+// Must match `water::WAVE_PERIOD_M`.
+const WAVE_PERIOD_M: f32 = 2048.0;
+
+// WGS84 semi-axes (m).
+const WGS84_A: f32 = 6378137.0;
+const WGS84_B: f32 = 6356752.3;
+
 @vertex
 fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
@@ -58,9 +126,22 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #endif
 
 #ifdef VERTEX_COLORS
-    // Override vertex color to white - the red channel contains octant data,
-    // not actual color. We've already used it for masking above.
-    out.color = vec4(1.0, 1.0, 1.0, 1.0);
+    // The red channel held octant data, not colour, and is spent. Reuse the
+    // varying to hand the fragment stage the vertex's height above the
+    // ellipsoid (`.x`) and geodetic vertical (`.yzw`), rebuilt in globe space
+    // from the mesh origin; the fragment stage restores white before shading.
+    // The vertical's length past 1 carries the green channel's water
+    // likeness: it's nearly constant across a triangle, so it interpolates
+    // along with the length.
+    let world_origin = world_from_local[3].xyz;
+    let globe_position = globe_origin.xyz + (out.world_position.xyz - world_origin);
+    let direction = normalize(globe_position);
+    let ellipsoid_radius = inverseSqrt(
+        dot(direction.xy, direction.xy) / (WGS84_A * WGS84_A)
+            + direction.z * direction.z / (WGS84_B * WGS84_B));
+    let altitude = length(globe_position) - ellipsoid_radius;
+    let up = normalize(vec3(globe_position.xy, globe_position.z * (WGS84_A * WGS84_A) / (WGS84_B * WGS84_B)));
+    out.color = vec4(altitude, up * (1.0 + vertex.color.g));
 #endif
 
 #ifdef VERTEX_OUTPUT_INSTANCE_INDEX
@@ -69,3 +150,246 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 
     return out;
 }
+
+// Blue → cyan → green → yellow → red, `t` in [0, 1].
+fn debug_ramp(t: f32) -> vec3<f32> {
+    let k = clamp(t, 0.0, 1.0) * 4.0;
+    if k < 1.0 {
+        return mix(vec3(0.0, 0.05, 0.8), vec3(0.0, 0.8, 0.8), k);
+    } else if k < 2.0 {
+        return mix(vec3(0.0, 0.8, 0.8), vec3(0.05, 0.8, 0.0), k - 1.0);
+    } else if k < 3.0 {
+        return mix(vec3(0.05, 0.8, 0.0), vec3(0.9, 0.8, 0.0), k - 2.0);
+    }
+    return mix(vec3(0.9, 0.8, 0.0), vec3(0.9, 0.05, 0.0), k - 3.0);
+}
+
+// Fully saturated hue, `h` in turns.
+fn hue(h: f32) -> vec3<f32> {
+    let k = fract(h) * 6.0;
+    return clamp(vec3(abs(k - 3.0) - 1.0, 2.0 - abs(k - 2.0), 2.0 - abs(k - 4.0)), vec3(0.0), vec3(1.0));
+}
+
+// Avalanche-map colour for a slope in degrees, with its coverage in `.a`:
+// untinted below the first band edge.
+fn slope_band(degrees: f32) -> vec4<f32> {
+    if degrees < analysis_bands.x {
+        return vec4(0.0);
+    } else if degrees < analysis_bands.y {
+        return vec4(0.95, 0.9, 0.1, 1.0);
+    } else if degrees < analysis_bands.z {
+        return vec4(0.95, 0.55, 0.05, 1.0);
+    } else if degrees < analysis_bands.w {
+        return vec4(0.9, 0.1, 0.05, 1.0);
+    }
+    return vec4(0.55, 0.1, 0.75, 1.0);
+}
+
+// Slope of a sum of travelling waves at `position` (m, modulo
+// `WAVE_PERIOD_M`), as a gradient for the caller to project onto the surface.
+// Each wave vector is a whole number of cycles per period along each ECEF
+// axis, so the pattern repeats exactly over the period, and each frequency a
+// whole number of cycles per minute, close to deep water's dispersion, so it
+// also repeats over the hour after which `globals.time` wraps. Waves too fine
+// for the pixel, judged from the position's screen derivatives `dx` and `dy`,
+// fade out rather than alias.
+fn wave_slope(position: vec3<f32>, dx: vec3<f32>, dy: vec3<f32>, time: f32) -> vec3<f32> {
+    // Cycles per period in `.xyz`, cycles per minute in `.w`.
+    let waves = array<vec4<f32>, 6>(
+        vec4(52.0, -31.0, 37.0, 14.0),
+        vec4(-44.0, 58.0, 21.0, 14.0),
+        vec4(97.0, 40.0, -66.0, 18.0),
+        vec4(-71.0, -103.0, 52.0, 19.0),
+        vec4(181.0, -127.0, 96.0, 26.0),
+        vec4(-150.0, 211.0, -133.0, 28.0),
+    );
+    let weights = array<f32, 6>(0.3, 0.3, 0.2, 0.2, 0.12, 0.1);
+    var slope = vec3(0.0);
+    for (var i = 0u; i < 6u; i++) {
+        let k = waves[i].xyz * (6.2831853 / WAVE_PERIOD_M);
+        let phase = dot(k, position) - waves[i].w * (6.2831853 / 60.0) * time;
+        let resolved = 1.0 - smoothstep(1.0, 3.0, abs(dot(k, dx)) + abs(dot(k, dy)));
+        slope += normalize(k) * cos(phase) * weights[i] * resolved;
+    }
+    return slope;
+}
+
+@fragment
+fn fragment(
+    vertex_output: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var in = vertex_output;
+    let debug_mode = u32(debug_view.x + 0.5);
+
+    // Overdraw: the material blends additively with zero alpha, so every
+    // fragment adds this step regardless of what's behind it.
+    if debug_mode == DEBUG_OVERDRAW {
+        var out: FragmentOutput;
+        out.color = vec4(0.08, 0.04, 0.01, 0.0);
+        return out;
+    }
+
+#ifdef VERTEX_COLORS
+    let altitude = in.color.x;
+    let up = normalize(in.color.yzw);
+    let water_likeness = length(in.color.yzw) - 1.0;
+    in.color = vec4(1.0);
+#endif
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var base = pbr_input.material.base_color.rgb;
+
+#ifdef SCREEN_SPACE_AMBIENT_OCCLUSION
+    // Scale the SSAO Bevy folded into the occlusion terms, fading it out with
+    // distance where tiles are too small on screen for it to mean anything.
+    let occlusion_distance = length(in.world_position.xyz - view.world_position);
+    let occlusion = ambient_occlusion.x
+        * (1.0 - smoothstep(ambient_occlusion.y, ambient_occlusion.z, occlusion_distance));
+    pbr_input.diffuse_occlusion = mix(vec3(1.0), pbr_input.diffuse_occlusion, occlusion);
+    pbr_input.specular_occlusion = mix(1.0, pbr_input.specular_occlusion, occlusion);
+#endif
+
+#ifdef VERTEX_COLORS
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    // Water: water-like texture on flat ground near sea level gets a tint,
+    // waves and a glossy sun glint. Debug views show the photo as it is.
+    if water.detection.x > 0.0 && debug_mode == 0u {
+        let flatness = dot(normalize(pbr_input.world_normal), up);
+        let wetness = water.detection.x
+            * smoothstep(water.detection.y, water.detection.y * 2.0, water_likeness)
+            * smoothstep(water.detection.w - 0.005, water.detection.w, flatness)
+            * (1.0 - smoothstep(water.detection.z - 20.0, water.detection.z, altitude));
+        // Mesh-local offsets added to the origin's phase, so the waves line up
+        // across tiles whatever the floating origin does. Derivatives are
+        // taken here, outside the non-uniform branch.
+        let world_from_local = mesh_functions::get_world_from_local(in.instance_index);
+        let wave_position = wave_origin.xyz + (in.world_position.xyz - world_from_local[3].xyz);
+        let wave_dx = dpdx(wave_position);
+        let wave_dy = dpdy(wave_position);
+        if wetness > 0.0 {
+            let distance = length(in.world_position.xyz - view.world_position);
+            let strength = water.surface.y * (1.0 - smoothstep(0.0, water.surface.z, distance));
+            let slope = wave_slope(wave_position, wave_dx, wave_dy, globals.time) * strength;
+            let wave_normal = normalize(up - (slope - up * dot(slope, up)));
+            pbr_input.N = normalize(mix(pbr_input.N, wave_normal, wetness));
+            base = mix(base, water.color.rgb, water.color.a * wetness);
+            pbr_input.material.perceptual_roughness = mix(
+                pbr_input.material.perceptual_roughness, water.surface.x, wetness);
+            // Water's 2% Fresnel reflectance at normal incidence.
+            pbr_input.material.reflectance = mix(
+                pbr_input.material.reflectance, vec3(0.35), wetness);
+        }
+    }
+#endif
+
+    // Snow settles on high ground that faces up; steep faces stay bare.
+    if style.snow_color.a > 0.0 {
+        let facing = dot(pbr_input.world_normal, up);
+        let by_altitude = smoothstep(
+            style.snow_altitude.x, style.snow_altitude.x + style.snow_altitude.y, altitude);
+        let by_slope = smoothstep(
+            style.snow_slope.x - style.snow_slope.y, style.snow_slope.x, facing);
+        base = mix(base, style.snow_color.rgb, style.snow_color.a * by_altitude * by_slope);
+    }
+#endif
+
+    // Wash out distant terrain a little, beyond the atmosphere's own haze.
+    if style.desaturate_amount > 0.0 {
+        let distance = length(in.world_position.xyz - view.world_position);
+        let amount = style.desaturate_amount
+            * smoothstep(style.desaturate_range.x, style.desaturate_range.y, distance);
+        let luminance = dot(base, vec3(0.2126, 0.7152, 0.0722));
+        base = mix(base, vec3(luminance), amount);
+    }
+
+#ifdef VERTEX_COLORS
+    // Contour lines about a pixel wide, measured in screen-space derivatives
+    // of the height; index contours twice that. Minor lines fade out where
+    // they would crowd into a solid fill.
+    if style.contour_color.a > 0.0 {
+        let level = altitude / style.contour_spacing.x;
+        let level_width = max(fwidth(level), 1e-5);
+        let minor = 1.0 - smoothstep(0.5, 1.0, abs(fract(level + 0.5) - 0.5) / level_width);
+        let index_level = level / style.contour_spacing.y;
+        let index_width = max(fwidth(index_level), 1e-5);
+        let major = 1.0 - smoothstep(1.0, 1.5, abs(fract(index_level + 0.5) - 0.5) / index_width);
+        let crowding = 1.0 - smoothstep(0.1, 0.3, level_width);
+        let line = max(minor * crowding * 0.6, major);
+        base = mix(base, style.contour_color.rgb, style.contour_color.a * line);
+    }
+#endif
+
+    // Debug coverage tint, modulated by the texture's brightness so the
+    // terrain stays legible underneath.
+    if depth_tint.a > 0.0 {
+        let luminance = dot(base, vec3(0.2126, 0.7152, 0.0722));
+        base = mix(base, depth_tint.rgb * (0.5 + luminance), depth_tint.a);
+    }
+
+#ifdef VERTEX_COLORS
+    // Slope and aspect from the mesh normal against the geodetic vertical,
+    // washed over the texture's brightness like the depth tint.
+    if debug_mode == DEBUG_SLOPE || debug_mode == DEBUG_ASPECT {
+        let normal = normalize(pbr_input.world_normal);
+        let facing = dot(normal, up);
+        // Faces seen from below (folded photogrammetry) read as their mirror.
+        let slope = degrees(acos(clamp(abs(facing), 0.0, 1.0)));
+        var tint = slope_band(slope);
+        if debug_mode == DEBUG_ASPECT {
+            // World axes are ECEF-aligned, so `+z` is the North Pole.
+            let east = normalize(cross(vec3(0.0, 0.0, 1.0), up));
+            let north = cross(up, east);
+            let downhill = normal * sign(facing) - up * abs(facing);
+            let aspect = atan2(dot(downhill, east), dot(downhill, north));
+            let coverage = select(0.0, 1.0, slope >= analysis_params.x);
+            tint = vec4(hue(aspect / 6.2831853), coverage);
+        }
+        let luminance = dot(base, vec3(0.2126, 0.7152, 0.0722));
+        base = mix(base, tint.rgb * (0.5 + luminance), tint.a * analysis_params.y);
+    }
+#endif
+
+    if debug_mode == DEBUG_WIREFRAME {
+        // Light each triangle by its own plane, facing the viewer, so the
+        // tessellation shows everywhere rather than only near the camera.
+        let dx = dpdx(in.world_position.xyz);
+        let dy = dpdy(in.world_position.xyz);
+        var facet = normalize(cross(dx, dy));
+        facet = select(facet, -facet, dot(facet, pbr_input.V) < 0.0);
+        pbr_input.N = facet;
+        pbr_input.world_normal = facet;
+        base = vec3(0.6);
+    }
+
+#ifdef VERTEX_UVS_A
+    if debug_mode == DEBUG_UV_CHECKER {
+        let cell = vec2<i32>(floor(in.uv * 16.0));
+        let shade = select(0.9, 0.35, ((cell.x + cell.y) & 1) != 0);
+        base = shade * vec3(0.4 + 0.6 * in.uv.x, 0.4 + 0.6 * in.uv.y, 0.4);
+    }
+
+    if debug_mode == DEBUG_TEXEL_DENSITY {
+        // Texels crossed per metre travelled across the surface, on a log
+        // scale from 1/16 to 64.
+        let texels = in.uv * debug_view.yz;
+        let texel_step = length(dpdx(texels)) + length(dpdy(texels));
+        let metre_step = length(dpdx(in.world_position.xyz)) + length(dpdy(in.world_position.xyz));
+        let per_metre = texel_step / max(metre_step, 1e-6);
+        base = debug_ramp((log2(max(per_metre, 1e-6)) + 4.0) / 10.0);
+    }
+#endif
+
+    pbr_input.material.base_color = vec4(base, pbr_input.material.base_color.a);
+
+    var out: FragmentOutput;
+    if (pbr_input.material.flags & STANDARD_MATERIAL_FLAGS_UNLIT_BIT) == 0u {
+        out.color = apply_pbr_lighting(pbr_input);
+    } else {
+        out.color = pbr_input.material.base_color;
+    }
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
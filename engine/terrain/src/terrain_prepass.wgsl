@@ -0,0 +1,84 @@
+// Prepass vertex shader for the terrain: Bevy's prepass vertex stage with the
+// octant mask applied, so hidden octants write no depth and, in the sun's
+// shadow pass, cast no shadows over the child tiles that replace them.
+// Terrain is never skinned or morphed, so those paths are left out.
+
+#import bevy_pbr::{
+    mesh_functions,
+    prepass_io::{Vertex, VertexOutput},
+    view_transformations::position_world_to_clip,
+}
+
+// Same binding as in `terrain_material.wgsl`.
+@group(#{MATERIAL_BIND_GROUP}) @binding(100) var<uniform> octant_mask: vec4<u32>;
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+
+    // Collapse masked vertices to the local origin, as the main pass does.
+    // Terrain meshes always carry vertex colours, with the octant in R.
+#ifdef VERTEX_COLORS
+    let octant = u32(vertex.color.r + 0.5);
+    let mask = select(1.0, 0.0, ((octant_mask.x >> octant) & 1u) != 0u);
+#else
+    let mask = 1.0;
+#endif
+    let position = vertex.position * mask;
+
+    out.world_position = mesh_functions::mesh_position_local_to_world(
+        world_from_local, vec4<f32>(position, 1.0));
+    out.position = position_world_to_clip(out.world_position.xyz);
+#ifdef UNCLIPPED_DEPTH_ORTHO_EMULATION
+    out.unclipped_depth = out.position.z;
+    out.position.z = min(out.position.z, 1.0); // Clamp depth to avoid clipping
+#endif
+
+#ifdef VERTEX_UVS_A
+    out.uv = vertex.uv * mask;
+#endif
+
+#ifdef VERTEX_UVS_B
+    out.uv_b = vertex.uv_b * mask;
+#endif
+
+#ifdef NORMAL_PREPASS_OR_DEFERRED_PREPASS
+#ifdef VERTEX_NORMALS
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(
+        vertex.normal,
+        vertex.instance_index
+    );
+#endif
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(
+        world_from_local,
+        vertex.tangent,
+        vertex.instance_index
+    );
+#endif
+#endif // NORMAL_PREPASS_OR_DEFERRED_PREPASS
+
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+
+#ifdef MOTION_VECTOR_PREPASS
+    let previous_world_from_local =
+        mesh_functions::get_previous_world_from_local(vertex.instance_index);
+    out.previous_world_position = mesh_functions::mesh_position_local_to_world(
+        previous_world_from_local, vec4<f32>(position, 1.0));
+#endif
+
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex.instance_index;
+#endif
+
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex.instance_index, world_from_local[3]);
+#endif
+
+    return out;
+}
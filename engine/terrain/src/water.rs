@@ -0,0 +1,349 @@
+//! Water detection and ocean shading for the terrain.
+//!
+//! The photogrammetry has no notion of water: the sea is a blurry blue photo
+//! of itself, draped over a flat mesh. Nothing in the tile data says which
+//! triangles are wet, so water is recognised from what it looks like. At mesh
+//! conversion each vertex gets a water likeness from the texture around it
+//! (see [`TextureBlocks`]): blue rather than red, not too bright, and smooth
+//! over a dozen texels, as open water is and roofs, cars and fields mostly
+//! aren't. The terrain shader then requires the surface to also be flat and
+//! near sea level, from the height and vertical it already rebuilds for the
+//! stylization, which rules out blue roofs on hills and shadowed slopes.
+//!
+//! Where all three agree, the shader swaps the photo for a water surface: a
+//! deep-water tint blended over the texture, so shallows and reefs still show
+//! through, a low roughness that catches the sun's specular, and a few
+//! travelling waves perturbing the normal. The waves are periodic over
+//! [`WAVE_PERIOD_M`], and each material carries its origin's ECEF position
+//! reduced modulo that period (computed in `f64`), so the pattern lines up
+//! across tiles and doesn't crawl as the floating origin moves. They flatten
+//! out beyond [`WaterConfig::wave_fade_m`], where they'd only shimmer.
+
+use bevy::{math::DVec3, prelude::*, reflect::TypePath, render::render_resource::ShaderType};
+use rocktree::{Mesh as RocktreeMesh, TextureFormat};
+use rocktree_decode::texture::decode_bc1_block;
+use serde::Deserialize;
+use veldera_config::ConfigPlugin;
+
+use crate::{mesh::RocktreeMeshMarker, terrain_material::TerrainMaterial};
+
+/// Period of the wave pattern along each ECEF axis (m). Must match
+/// `WAVE_PERIOD_M` in `terrain_material.wgsl`.
+pub const WAVE_PERIOD_M: f64 = 2048.0;
+
+/// Plugin that drives the terrain's water shading from its config.
+///
+/// Defaults to the config at [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH)
+/// in the shared engine asset subtree; override via [`new`](Self::new) for a
+/// different asset layout.
+pub struct WaterPlugin {
+    /// Path to the [`WaterConfig`] TOML.
+    pub config_path: &'static str,
+}
+
+impl WaterPlugin {
+    /// Canonical [`WaterConfig`] path within the shared engine asset subtree.
+    pub const DEFAULT_CONFIG_PATH: &'static str = "engine/config/rendering/water.toml";
+
+    /// Create the plugin, loading its config from `config_path`.
+    pub const fn new(config_path: &'static str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Default for WaterPlugin {
+    /// Load the config from [`DEFAULT_CONFIG_PATH`](Self::DEFAULT_CONFIG_PATH).
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONFIG_PATH)
+    }
+}
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigPlugin::<WaterConfig>::new(self.config_path))
+            .add_systems(PostUpdate, apply_terrain_water);
+    }
+}
+
+/// Water shading settings, loaded from `water.toml`.
+#[derive(Asset, Resource, TypePath, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterConfig {
+    /// Master toggle. Off leaves water as photographed.
+    pub enabled: bool,
+    /// Water likeness at which shading begins; full at twice this, capped at
+    /// 1. Lower catches more water and more false positives.
+    pub min_likeness: f32,
+    /// Height above the WGS84 ellipsoid above which nothing is water (m).
+    /// The geoid sits within about 100 m of the ellipsoid, so this is loose.
+    pub max_altitude_m: f32,
+    /// Minimum normal-vs-vertical cosine for water; steeper is never water.
+    pub min_flatness: f32,
+    /// Linear RGB deep-water tint.
+    pub color: [f32; 3],
+    /// Blend weight of the tint over the texture.
+    pub color_blend: f32,
+    /// Perceptual roughness of the water surface.
+    pub roughness: f32,
+    /// How far the waves tilt the normal.
+    pub wave_strength: f32,
+    /// Camera distance beyond which the waves have flattened out (m).
+    pub wave_fade_m: f32,
+}
+
+impl Default for WaterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_likeness: 0.35,
+            max_altitude_m: 80.0,
+            min_flatness: 0.995,
+            color: [0.01, 0.035, 0.06],
+            color_blend: 0.55,
+            roughness: 0.08,
+            wave_strength: 0.15,
+            wave_fade_m: 3_000.0,
+        }
+    }
+}
+
+impl WaterConfig {
+    /// The GPU-side parameters. Disabled water has zero strength, which the
+    /// shader skips.
+    pub fn uniform(&self) -> WaterUniform {
+        let [r, g, b] = self.color;
+        WaterUniform {
+            detection: Vec4::new(
+                if self.enabled { 1.0 } else { 0.0 },
+                self.min_likeness.clamp(0.01, 1.0),
+                self.max_altitude_m,
+                self.min_flatness.clamp(0.0, 0.999),
+            ),
+            color: Vec4::new(r, g, b, self.color_blend.clamp(0.0, 1.0)),
+            surface: Vec4::new(
+                self.roughness.clamp(0.02, 1.0),
+                self.wave_strength.max(0.0),
+                self.wave_fade_m.max(1.0),
+                0.0,
+            ),
+        }
+    }
+}
+
+/// [`WaterConfig`] packed for the shader.
+#[derive(ShaderType, Debug, Clone, Copy, Default, PartialEq)]
+pub struct WaterUniform {
+    /// Strength (0 = disabled), minimum likeness, maximum altitude (m) and
+    /// minimum normal cosine.
+    pub detection: Vec4,
+    /// Tint in `.rgb`, blend weight in `.a`.
+    pub color: Vec4,
+    /// Roughness, wave strength and wave fade distance (m).
+    pub surface: Vec4,
+}
+
+/// A mesh origin's ECEF position reduced modulo [`WAVE_PERIOD_M`], as the
+/// phase origin of its material's waves.
+pub fn wave_origin(globe_origin: DVec3) -> Vec4 {
+    globe_origin
+        .rem_euclid(DVec3::splat(WAVE_PERIOD_M))
+        .as_vec3()
+        .extend(0.0)
+}
+
+/// Push the water parameters into the terrain materials: every material when
+/// the config changes, otherwise only those of newly spawned meshes.
+fn apply_terrain_water(
+    config: Res<WaterConfig>,
+    mut applied: Local<Option<WaterUniform>>,
+    meshes: Query<Ref<MeshMaterial3d<TerrainMaterial>>, With<RocktreeMeshMarker>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+) {
+    let uniform = config.uniform();
+    let all = *applied != Some(uniform);
+    *applied = Some(uniform);
+    for material in &meshes {
+        if !all && !material.is_added() {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.extension.water = uniform;
+        }
+    }
+}
+
+// ============================================================================
+// Detection
+// ============================================================================
+
+/// A tile texture summarised as 4×4 texel blocks (DXT1's own block size):
+/// each block's mean sRGB colour and how much its texels stray from it.
+pub struct TextureBlocks {
+    columns: usize,
+    rows: usize,
+    mean: Vec<[f32; 3]>,
+    spread: Vec<f32>,
+}
+
+impl TextureBlocks {
+    /// Summarise `mesh`'s texture, or `None` if it's too small or truncated.
+    pub fn new(mesh: &RocktreeMesh) -> Option<Self> {
+        let (width, height) = (mesh.texture_width as usize, mesh.texture_height as usize);
+        let (columns, rows) = (width / 4, height / 4);
+        if columns == 0 || rows == 0 {
+            return None;
+        }
+        let data = &mesh.texture_data;
+        let mut blocks = Self {
+            columns,
+            rows,
+            mean: Vec::with_capacity(columns * rows),
+            spread: Vec::with_capacity(columns * rows),
+        };
+        let mut texels = [[0.0; 3]; 16];
+        for row in 0..rows {
+            for column in 0..columns {
+                match mesh.texture_format {
+                    TextureFormat::Dxt1 => {
+                        let start = (row * width.div_ceil(4) + column) * 8;
+                        let block = data.get(start..start + 8)?.try_into().ok()?;
+                        for (texel, [r, g, b, _]) in texels.iter_mut().zip(decode_bc1_block(block))
+                        {
+                            *texel = bytes_to_rgb([r, g, b]);
+                        }
+                    }
+                    TextureFormat::Rgb | TextureFormat::Rgba => {
+                        let stride = if mesh.texture_format == TextureFormat::Rgb {
+                            3
+                        } else {
+                            4
+                        };
+                        for (i, texel) in texels.iter_mut().enumerate() {
+                            let (x, y) = (column * 4 + i % 4, row * 4 + i / 4);
+                            let start = (y * width + x) * stride;
+                            let bytes = data.get(start..start + 3)?;
+                            *texel = bytes_to_rgb([bytes[0], bytes[1], bytes[2]]);
+                        }
+                    }
+                }
+                let (mean, spread) = block_stats(&texels);
+                blocks.mean.push(mean);
+                blocks.spread.push(spread);
+            }
+        }
+        Some(blocks)
+    }
+
+    /// Water likeness, 0 to 1, of the 3×3 blocks around texture coordinate
+    /// `uv`: their average colour, and their spread both within and between
+    /// blocks.
+    pub fn water_likeness(&self, uv: [f32; 2]) -> f32 {
+        let column = (uv[0].clamp(0.0, 1.0) * self.columns as f32) as usize;
+        let row = (uv[1].clamp(0.0, 1.0) * self.rows as f32) as usize;
+        let (column, row) = (column.min(self.columns - 1), row.min(self.rows - 1));
+
+        let mut neighbours = Vec::with_capacity(9);
+        for y in row.saturating_sub(1)..=(row + 1).min(self.rows - 1) {
+            for x in column.saturating_sub(1)..=(column + 1).min(self.columns - 1) {
+                neighbours.push(y * self.columns + x);
+            }
+        }
+        let count = neighbours.len() as f32;
+        let mut mean = [0.0; 3];
+        for &index in &neighbours {
+            for channel in 0..3 {
+                mean[channel] += self.mean[index][channel] / count;
+            }
+        }
+        let spread = neighbours
+            .iter()
+            .map(|&index| self.spread[index] + colour_distance(self.mean[index], mean))
+            .sum::<f32>()
+            / count;
+        colour_likeness(mean, spread)
+    }
+}
+
+/// How much a patch of mean sRGB colour `mean`, whose texels stray from it by
+/// `spread` on average, looks like open water, 0 to 1.
+///
+/// Water from above ranges from near-black navy to turquoise over sand: blue
+/// well above red and not far below green, and never bright, which leaves out
+/// surf, cloud and concrete. It's also smooth at the scale of a few metres,
+/// which most blue things on land (roofs, cars, pools' tiled edges) aren't.
+pub fn colour_likeness(mean: [f32; 3], spread: f32) -> f32 {
+    let [r, g, b] = mean;
+    let blueness = smoothstep(0.02, 0.1, b - r) * smoothstep(-0.12, -0.02, b - g);
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let darkness = 1.0 - smoothstep(0.45, 0.65, luminance);
+    let smoothness = 1.0 - smoothstep(0.03, 0.09, spread);
+    blueness * darkness * smoothness
+}
+
+/// Mean colour and mean distance from it of a block of texels.
+fn block_stats(texels: &[[f32; 3]; 16]) -> ([f32; 3], f32) {
+    let mut sum = [0.0; 3];
+    for texel in texels {
+        for channel in 0..3 {
+            sum[channel] += texel[channel];
+        }
+    }
+    let mean = sum.map(|channel| channel / 16.0);
+    let spread = texels
+        .iter()
+        .map(|texel| colour_distance(*texel, mean))
+        .sum::<f32>()
+        / 16.0;
+    (mean, spread)
+}
+
+/// Mean absolute difference across the channels.
+fn colour_distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((a[0] - b[0]).abs() + (a[1] - b[1]).abs() + (a[2] - b[2]).abs()) / 3.0
+}
+
+fn bytes_to_rgb(bytes: [u8; 3]) -> [f32; 3] {
+    bytes.map(|channel| f32::from(channel) / 255.0)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn water_colours_score_above_land() {
+        let ocean = colour_likeness([0.04, 0.12, 0.22], 0.01);
+        let lagoon = colour_likeness([0.2, 0.6, 0.62], 0.02);
+        let forest = colour_likeness([0.15, 0.25, 0.1], 0.01);
+        let surf = colour_likeness([0.85, 0.9, 0.95], 0.01);
+        let blue_roofs = colour_likeness([0.1, 0.2, 0.4], 0.15);
+        assert!(ocean > 0.9);
+        assert!(lagoon > 0.5);
+        for land in [forest, surf, blue_roofs] {
+            assert!(land < 0.05, "{land}");
+        }
+    }
+
+    #[test]
+    fn dxt1_blocks_decode_their_palette() {
+        // Pure blue and black endpoints, every texel on the 2/3 blend.
+        let block = [0x1f, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff];
+        let texels = decode_bc1_block(&block).map(|[r, g, b, _]| bytes_to_rgb([r, g, b]));
+        let (mean, spread) = block_stats(&texels);
+        assert!((mean[2] - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(spread, 0.0);
+    }
+
+    #[test]
+    fn wave_origin_is_periodic() {
+        let origin = DVec3::new(4_000_000.5, -300.25, 5_000_000.0);
+        let shifted = origin + DVec3::splat(WAVE_PERIOD_M * 3.0);
+        assert_eq!(wave_origin(origin), wave_origin(shifted));
+        assert!(wave_origin(origin).max_element() < WAVE_PERIOD_M as f32);
+    }
+}
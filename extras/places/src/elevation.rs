@@ -2,19 +2,56 @@
 
 use serde::Deserialize;
 
+/// Locations per batched request, keeping the query string a sane length.
+const BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    results: Vec<ElevationResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ElevationResult {
+    elevation: f64,
+}
+
 /// Fetch elevation from Open Elevation API.
 pub async fn fetch_elevation(client: &reqwest::Client, lat: f64, lon: f64) -> Result<f64, String> {
-    #[derive(Debug, Deserialize)]
-    struct Response {
-        results: Vec<Result>,
-    }
+    fetch_elevations(client, &[(lat, lon)])
+        .await?
+        .first()
+        .copied()
+        .ok_or_else(|| "No elevation data returned".to_string())
+}
 
-    #[derive(Debug, Deserialize)]
-    struct Result {
-        elevation: f64,
+/// Fetch elevations for many `(lat, lon)` points from the Open Elevation API,
+/// in batches. Results are in input order.
+pub async fn fetch_elevations(
+    client: &reqwest::Client,
+    points: &[(f64, f64)],
+) -> Result<Vec<f64>, String> {
+    let mut elevations = Vec::with_capacity(points.len());
+    for batch in points.chunks(BATCH_SIZE) {
+        let results = fetch_batch(client, batch).await?;
+        if results.len() != batch.len() {
+            return Err(format!(
+                "Expected {} elevations, got {}",
+                batch.len(),
+                results.len()
+            ));
+        }
+        elevations.extend(results);
     }
+    Ok(elevations)
+}
 
-    let url = format!("https://api.open-elevation.com/api/v1/lookup?locations={lat},{lon}");
+async fn fetch_batch(client: &reqwest::Client, points: &[(f64, f64)]) -> Result<Vec<f64>, String> {
+    let locations = points
+        .iter()
+        .map(|(lat, lon)| format!("{lat:.6},{lon:.6}"))
+        .collect::<Vec<_>>()
+        .join("|");
+    let url = format!("https://api.open-elevation.com/api/v1/lookup?locations={locations}");
 
     let response = client
         .get(&url)
@@ -31,8 +68,5 @@ pub async fn fetch_elevation(client: &reqwest::Client, lat: f64, lon: f64) -> Re
         .await
         .map_err(|e| format!("Failed to parse elevation response: {e}"))?;
 
-    data.results
-        .first()
-        .map(|r| r.elevation)
-        .ok_or_else(|| "No elevation data returned".to_string())
+    Ok(data.results.iter().map(|r| r.elevation).collect())
 }
@@ -1,17 +1,27 @@
-//! Location data services: forward/reverse geocoding and elevation lookup.
+//! Location data services: forward/reverse geocoding, elevation lookup,
+//! current weather and satellite element sets.
 //!
-//! Wraps the OpenStreetMap Nominatim and Open Elevation APIs behind a shared
-//! [`HttpClient`] and exposes geocoding as a Bevy resource. Consumers drive
-//! searches through [`GeocodingState`] or call [`fetch_elevation`] directly;
-//! results arrive asynchronously via [`veldera_async`]'s task spawner.
+//! Wraps the OpenStreetMap Nominatim, Open Elevation, Open-Meteo and CelesTrak
+//! APIs behind a shared [`HttpClient`] and exposes geocoding and weather as
+//! Bevy resources. Consumers drive searches through [`GeocodingState`],
+//! weather through [`LiveWeather`], or call [`fetch_elevation`] and
+//! [`fetch_tles`] directly; results arrive asynchronously via
+//! [`veldera_async`]'s task spawner.
 
 mod elevation;
 mod geocoding;
+mod satellites;
+mod weather;
 
 use bevy::prelude::*;
 
-pub use elevation::fetch_elevation;
+pub use elevation::{fetch_elevation, fetch_elevations};
 pub use geocoding::{GEOCODING_THROTTLE_SECS, GeocodingResult, GeocodingState};
+pub use satellites::fetch_tles;
+pub use weather::{
+    CachedConditions, CurrentConditions, LIVE_WEATHER_CELL_DEG, LIVE_WEATHER_REFRESH_SECS,
+    LIVE_WEATHER_THROTTLE_SECS, LiveWeather, WeatherCell, fetch_current_conditions, weather_cell,
+};
 
 /// User agent for API requests.
 const USER_AGENT: &str = "veldera/0.1 (https://github.com/philpax/veldera)";
@@ -31,7 +41,7 @@ impl HttpClient {
     }
 }
 
-/// Sets up the shared HTTP client and geocoding state.
+/// Sets up the shared HTTP client, geocoding state and live weather state.
 ///
 /// Elevation lookups are stateless ([`fetch_elevation`]), so they need no
 /// resource of their own; callers supply the [`HttpClient`].
@@ -48,6 +58,13 @@ impl Plugin for PlacesPlugin {
 
         app.insert_resource(client)
             .init_resource::<GeocodingState>()
-            .add_systems(Update, geocoding::poll_geocoding_results);
+            .init_resource::<LiveWeather>()
+            .add_systems(
+                Update,
+                (
+                    geocoding::poll_geocoding_results,
+                    weather::poll_live_weather,
+                ),
+            );
     }
 }
@@ -0,0 +1,25 @@
+//! Satellite element sets from CelesTrak.
+
+/// CelesTrak's "visual" group: the hundred or so brightest satellites, as
+/// three-line element sets.
+const VISUAL_GROUP_URL: &str =
+    "https://celestrak.org/NORAD/elements/gp.php?GROUP=visual&FORMAT=tle";
+
+/// Fetch current two-line element sets for the brightest satellites, as text
+/// for `veldera_sky::satellites::parse_tles`.
+pub async fn fetch_tles(client: &reqwest::Client) -> Result<String, String> {
+    let response = client
+        .get(VISUAL_GROUP_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Satellite request failed: {e}"))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Satellite HTTP {}", response.status()));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read satellite response: {e}"))
+}
@@ -0,0 +1,294 @@
+//! Current weather conditions via the Open-Meteo API.
+//!
+//! [`LiveWeather`] fetches the conditions for a location on request and keeps
+//! them per grid cell of [`LIVE_WEATHER_CELL_DEG`], so a camera moving about
+//! one area costs one request per [`LIVE_WEATHER_REFRESH_SECS`]. Only the
+//! centre of the cell is sent, never the exact location, and nothing is sent
+//! until [`LiveWeather::enabled`] is set.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+use veldera_async::TaskSpawner;
+
+use crate::HttpClient;
+
+/// Size of the grid cells conditions are fetched and cached for (degrees,
+/// about 11 km of latitude).
+pub const LIVE_WEATHER_CELL_DEG: f64 = 0.1;
+
+/// How long fetched conditions stay current (s). Open-Meteo updates its
+/// current conditions every 15 minutes.
+pub const LIVE_WEATHER_REFRESH_SECS: f64 = 900.0;
+
+/// Minimum time between requests (s), however fast the camera moves.
+pub const LIVE_WEATHER_THROTTLE_SECS: f64 = 10.0;
+
+/// Cached conditions older than this are dropped (s).
+const CACHE_EXPIRY_SECS: f64 = 4.0 * LIVE_WEATHER_REFRESH_SECS;
+
+/// A grid cell of [`LIVE_WEATHER_CELL_DEG`], as (latitude, longitude) indices.
+pub type WeatherCell = (i32, i32);
+
+/// Current conditions at a location. Fields the API leaves out are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct CurrentConditions {
+    /// Observation time (ISO 8601, UTC).
+    #[serde(default)]
+    pub time: String,
+    /// Length of the period the amounts cover (s).
+    #[serde(default)]
+    pub interval: Option<f32>,
+    /// WMO weather interpretation code.
+    #[serde(default)]
+    pub weather_code: Option<u8>,
+    /// Total cloud cover (%).
+    #[serde(default)]
+    pub cloud_cover: Option<f32>,
+    /// Horizontal visibility (m).
+    #[serde(default)]
+    pub visibility: Option<f32>,
+    /// Precipitation over the interval, rain and snow water (mm).
+    #[serde(default)]
+    pub precipitation: Option<f32>,
+    /// Snowfall over the interval (cm).
+    #[serde(default)]
+    pub snowfall: Option<f32>,
+}
+
+impl CurrentConditions {
+    /// Precipitation rate (mm/h of water).
+    #[must_use]
+    pub fn precipitation_rate_mm_per_h(&self) -> f32 {
+        let interval_h = self.interval.unwrap_or(3600.0).max(60.0) / 3600.0;
+        self.precipitation.unwrap_or(0.0).max(0.0) / interval_h
+    }
+
+    /// Whether the precipitation falls as snow.
+    #[must_use]
+    pub fn is_snow(&self) -> bool {
+        // WMO codes 71–77 and 85–86 are snowfall, snow grains and showers.
+        self.snowfall.is_some_and(|cm| cm > 0.0)
+            || self
+                .weather_code
+                .is_some_and(|code| matches!(code, 71..=77 | 85 | 86))
+    }
+
+    /// Precipitation intensity the weather code reports, 0–1, for when the
+    /// amounts lag behind it.
+    #[must_use]
+    pub fn reported_intensity(&self) -> f32 {
+        match self.weather_code {
+            Some(51 | 56 | 61 | 66 | 71 | 77 | 80 | 85) => 0.25,
+            Some(53 | 63 | 73 | 81) => 0.5,
+            Some(55 | 57 | 65 | 67 | 75 | 82 | 86) => 0.8,
+            Some(95..=99) => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Conditions fetched for a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedConditions {
+    pub cell: WeatherCell,
+    pub conditions: CurrentConditions,
+    /// Real time the conditions arrived (s).
+    pub fetched_at: f64,
+}
+
+/// The grid cell containing `lat`, `lon`.
+#[must_use]
+pub fn weather_cell(lat: f64, lon: f64) -> WeatherCell {
+    (
+        (lat / LIVE_WEATHER_CELL_DEG).floor() as i32,
+        (lon / LIVE_WEATHER_CELL_DEG).floor() as i32,
+    )
+}
+
+/// Centre of `cell`, as (latitude, longitude).
+fn cell_centre(cell: WeatherCell) -> (f64, f64) {
+    (
+        (f64::from(cell.0) + 0.5) * LIVE_WEATHER_CELL_DEG,
+        (f64::from(cell.1) + 0.5) * LIVE_WEATHER_CELL_DEG,
+    )
+}
+
+type FetchResult = (WeatherCell, Result<CurrentConditions, String>);
+
+/// State for live weather lookups.
+#[derive(Resource)]
+pub struct LiveWeather {
+    /// Whether to fetch conditions at all. Off by default, as a request
+    /// sends the (coarse) location to Open-Meteo.
+    pub enabled: bool,
+    cache: HashMap<WeatherCell, CachedConditions>,
+    /// The cell last asked about.
+    cell: Option<WeatherCell>,
+    /// The cell being fetched.
+    in_flight: Option<WeatherCell>,
+    /// Real time of the last request (s).
+    last_request_time: Option<f64>,
+    error: Option<String>,
+    result_rx: async_channel::Receiver<FetchResult>,
+    result_tx: async_channel::Sender<FetchResult>,
+}
+
+impl Default for LiveWeather {
+    fn default() -> Self {
+        let (result_tx, result_rx) = async_channel::bounded(1);
+        Self {
+            enabled: false,
+            cache: HashMap::default(),
+            cell: None,
+            in_flight: None,
+            last_request_time: None,
+            error: None,
+            result_rx,
+            result_tx,
+        }
+    }
+}
+
+impl LiveWeather {
+    /// Fetch the conditions at `lat`, `lon` unless they're cached and
+    /// current, a request is in flight, or requests are throttled. Does
+    /// nothing while disabled.
+    pub fn request(
+        &mut self,
+        lat: f64,
+        lon: f64,
+        current_time: f64,
+        client: &HttpClient,
+        spawner: &TaskSpawner<'_, '_>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let cell = weather_cell(lat, lon);
+        self.cell = Some(cell);
+        let fresh = self
+            .cache
+            .get(&cell)
+            .is_some_and(|cached| current_time - cached.fetched_at < LIVE_WEATHER_REFRESH_SECS);
+        let throttled = self
+            .last_request_time
+            .is_some_and(|t| current_time - t < LIVE_WEATHER_THROTTLE_SECS);
+        if fresh || throttled || self.in_flight.is_some() {
+            return;
+        }
+
+        self.in_flight = Some(cell);
+        self.last_request_time = Some(current_time);
+
+        let (lat, lon) = cell_centre(cell);
+        let tx = self.result_tx.clone();
+        let client = client.0.clone();
+        spawner.spawn(async move {
+            let result = fetch_current_conditions(&client, lat, lon).await;
+            let _ = tx.send((cell, result)).await;
+        });
+    }
+
+    /// The conditions for the cell last asked about, if fetched.
+    #[must_use]
+    pub fn current(&self) -> Option<&CachedConditions> {
+        self.cell.and_then(|cell| self.cache.get(&cell))
+    }
+
+    /// Whether a request is in flight.
+    #[must_use]
+    pub fn is_loading(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// The error of the last request, if it failed.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// Poll for fetched conditions, and drop stale ones.
+pub(super) fn poll_live_weather(mut live: ResMut<LiveWeather>, real_time: Res<Time<Real>>) {
+    let Ok((cell, result)) = live.result_rx.try_recv() else {
+        return;
+    };
+    let now = real_time.elapsed_secs_f64();
+    live.in_flight = None;
+    match result {
+        Ok(conditions) => {
+            live.cache
+                .retain(|_, cached| now - cached.fetched_at < CACHE_EXPIRY_SECS);
+            live.cache.insert(
+                cell,
+                CachedConditions {
+                    cell,
+                    conditions,
+                    fetched_at: now,
+                },
+            );
+            live.error = None;
+        }
+        Err(e) => live.error = Some(e),
+    }
+}
+
+/// Fetch the current conditions at `lat`, `lon` from Open-Meteo.
+pub async fn fetch_current_conditions(
+    client: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+) -> Result<CurrentConditions, String> {
+    #[derive(Debug, Deserialize)]
+    struct Response {
+        current: CurrentConditions,
+    }
+
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={lat:.2}&longitude={lon:.2}\
+         &current=weather_code,cloud_cover,visibility,precipitation,snowfall"
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Weather request failed: {e}"))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Weather HTTP {}", response.status()));
+    }
+
+    let data: Response = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse weather response: {e}"))?;
+
+    Ok(data.current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_snap_to_the_grid() {
+        assert_eq!(weather_cell(51.507, -0.128), (515, -2));
+        let (lat, lon) = cell_centre((515, -2));
+        assert!((lat - 51.55).abs() < 1e-9 && (lon + 0.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn amounts_become_rates() {
+        let conditions = CurrentConditions {
+            interval: Some(900.0),
+            precipitation: Some(0.5),
+            weather_code: Some(73),
+            ..Default::default()
+        };
+        assert!((conditions.precipitation_rate_mm_per_h() - 2.0).abs() < 1e-6);
+        assert!(conditions.is_snow());
+        assert_eq!(conditions.reported_intensity(), 0.5);
+    }
+}
@@ -0,0 +1,135 @@
+//! GPX (GPS Exchange Format) parsing.
+
+use crate::{Error, Track, TrackFile, TrackPoint, Waypoint, child_text, track_point};
+
+/// Parse a GPX document.
+pub(crate) fn parse(text: &str) -> Result<TrackFile, Error> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    if root.tag_name().name() != "gpx" {
+        return Err(Error::UnexpectedRoot(root.tag_name().name().to_string()));
+    }
+
+    let mut file = TrackFile {
+        name: root
+            .children()
+            .find(|n| n.tag_name().name() == "metadata")
+            .and_then(|metadata| child_text(metadata, "name"))
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    for element in root.children().filter(roxmltree::Node::is_element) {
+        match element.tag_name().name() {
+            "wpt" => {
+                let point = parse_point(element)?;
+                let name = child_text(element, "name").map_or_else(
+                    || format!("Waypoint {}", file.waypoints.len() + 1),
+                    str::to_string,
+                );
+                file.waypoints.push(Waypoint { name, point });
+            }
+            "trk" => {
+                let name = child_text(element, "name").map_or_else(
+                    || format!("Track {}", file.tracks.len() + 1),
+                    str::to_string,
+                );
+                let segments: Vec<_> = element
+                    .children()
+                    .filter(|n| n.tag_name().name() == "trkseg")
+                    .collect();
+                let numbered = segments.len() > 1;
+                for (index, segment) in segments.into_iter().enumerate() {
+                    let points = parse_points(segment, "trkpt")?;
+                    if points.is_empty() {
+                        continue;
+                    }
+                    let name = if numbered {
+                        format!("{name} ({})", index + 1)
+                    } else {
+                        name.clone()
+                    };
+                    file.tracks.push(Track { name, points });
+                }
+            }
+            "rte" => {
+                let name = child_text(element, "name").map_or_else(
+                    || format!("Route {}", file.tracks.len() + 1),
+                    str::to_string,
+                );
+                let points = parse_points(element, "rtept")?;
+                if !points.is_empty() {
+                    file.tracks.push(Track { name, points });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(file)
+}
+
+/// Parse every `tag` child of `parent` as a point.
+fn parse_points(parent: roxmltree::Node, tag: &str) -> Result<Vec<TrackPoint>, Error> {
+    parent
+        .children()
+        .filter(|n| n.tag_name().name() == tag)
+        .map(parse_point)
+        .collect()
+}
+
+/// Parse a point element's `lat`/`lon` attributes and optional `ele` child.
+fn parse_point(node: roxmltree::Node) -> Result<TrackPoint, Error> {
+    let attribute = |name| {
+        let value = node.attribute(name).unwrap_or_default();
+        value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| Error::InvalidCoordinate(format!("{name}=\"{value}\"")))
+    };
+    let elevation = child_text(node, "ele").and_then(|ele| ele.parse().ok());
+    track_point(attribute("lat")?, attribute("lon")?, elevation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>Morning ride</name></metadata>
+  <wpt lat="47.1" lon="8.5"><ele>410</ele><name>Start</name></wpt>
+  <trk>
+    <name>Loop</name>
+    <trkseg>
+      <trkpt lat="47.1" lon="8.5"><ele>410.5</ele></trkpt>
+      <trkpt lat="47.2" lon="8.6"/>
+    </trkseg>
+    <trkseg>
+      <trkpt lat="47.3" lon="8.7"/>
+    </trkseg>
+  </trk>
+  <rte><rtept lat="1" lon="2"/></rte>
+</gpx>"#;
+
+    #[test]
+    fn parses_tracks_segments_routes_and_waypoints() {
+        let file = parse(SAMPLE).unwrap();
+        assert_eq!(file.name.as_deref(), Some("Morning ride"));
+        assert_eq!(file.waypoints.len(), 1);
+        assert_eq!(file.waypoints[0].name, "Start");
+        assert_eq!(file.waypoints[0].point.elevation, Some(410.0));
+
+        let names: Vec<_> = file.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Loop (1)", "Loop (2)", "Route 3"]);
+        assert_eq!(file.tracks[0].points.len(), 2);
+        assert_eq!(file.tracks[0].points[1].elevation, None);
+    }
+
+    #[test]
+    fn rejects_bad_coordinates() {
+        let text = r#"<gpx><wpt lat="95" lon="0"/></gpx>"#;
+        assert!(matches!(parse(text), Err(Error::InvalidCoordinate(_))));
+        let text = r#"<gpx><wpt lat="north" lon="0"/></gpx>"#;
+        assert!(matches!(parse(text), Err(Error::InvalidCoordinate(_))));
+    }
+}
@@ -0,0 +1,152 @@
+//! KML (Keyhole Markup Language) parsing.
+
+use crate::{Error, Track, TrackFile, TrackPoint, Waypoint, child_text, track_point};
+
+/// Parse a KML document.
+pub(crate) fn parse(text: &str) -> Result<TrackFile, Error> {
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    if root.tag_name().name() != "kml" {
+        return Err(Error::UnexpectedRoot(root.tag_name().name().to_string()));
+    }
+
+    let mut file = TrackFile {
+        name: root
+            .descendants()
+            .find(|n| n.tag_name().name() == "Document")
+            .and_then(|document| child_text(document, "name"))
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    for placemark in root
+        .descendants()
+        .filter(|n| n.tag_name().name() == "Placemark")
+    {
+        let name = child_text(placemark, "name");
+        for geometry in placemark.descendants().filter(roxmltree::Node::is_element) {
+            match geometry.tag_name().name() {
+                "Point" => {
+                    let Some(coordinates) = child_text(geometry, "coordinates") else {
+                        continue;
+                    };
+                    let Some(point) = parse_coordinates(coordinates)?.into_iter().next() else {
+                        continue;
+                    };
+                    let name = name.map_or_else(
+                        || format!("Waypoint {}", file.waypoints.len() + 1),
+                        str::to_string,
+                    );
+                    file.waypoints.push(Waypoint { name, point });
+                }
+                "LineString" => {
+                    let Some(coordinates) = child_text(geometry, "coordinates") else {
+                        continue;
+                    };
+                    push_track(&mut file, name, parse_coordinates(coordinates)?);
+                }
+                // `gx:Track`: one `gx:coord` per point, space-separated.
+                "Track" => {
+                    let points = geometry
+                        .children()
+                        .filter(|n| n.tag_name().name() == "coord")
+                        .filter_map(|n| n.text())
+                        .map(|coord| parse_tuple(coord.split_whitespace()))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    push_track(&mut file, name, points);
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Add a non-empty track, naming it after its placemark if it has a name.
+fn push_track(file: &mut TrackFile, name: Option<&str>, points: Vec<TrackPoint>) {
+    if points.is_empty() {
+        return;
+    }
+    let name = name.map_or_else(
+        || format!("Track {}", file.tracks.len() + 1),
+        str::to_string,
+    );
+    file.tracks.push(Track { name, points });
+}
+
+/// Parse a `coordinates` element: whitespace-separated `lon,lat[,alt]` tuples.
+fn parse_coordinates(text: &str) -> Result<Vec<TrackPoint>, Error> {
+    text.split_whitespace()
+        .map(|tuple| parse_tuple(tuple.split(',')))
+        .collect()
+}
+
+/// Parse one `lon lat [alt]` tuple.
+fn parse_tuple<'a>(mut parts: impl Iterator<Item = &'a str>) -> Result<TrackPoint, Error> {
+    let mut next = || -> Result<Option<f64>, Error> {
+        parts
+            .next()
+            .map(|part| {
+                part.trim()
+                    .parse::<f64>()
+                    .map_err(|_| Error::InvalidCoordinate(part.to_string()))
+            })
+            .transpose()
+    };
+    let missing = || Error::InvalidCoordinate("missing coordinate".to_string());
+    let lon = next()?.ok_or_else(missing)?;
+    let lat = next()?.ok_or_else(missing)?;
+    let elevation = next()?;
+    track_point(lat, lon, elevation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+  <Document>
+    <name>Hike</name>
+    <Placemark><name>Summit</name><Point><coordinates>8.6,47.2,1200</coordinates></Point></Placemark>
+    <Placemark>
+      <name>Trail</name>
+      <LineString><coordinates>
+        8.5,47.1,400 8.55,47.15,800
+        8.6,47.2,1200
+      </coordinates></LineString>
+    </Placemark>
+    <Placemark>
+      <gx:Track>
+        <when>2024-01-01T00:00:00Z</when>
+        <gx:coord>8.5 47.1 400</gx:coord>
+        <gx:coord>8.6 47.2</gx:coord>
+      </gx:Track>
+    </Placemark>
+  </Document>
+</kml>"#;
+
+    #[test]
+    fn parses_points_line_strings_and_gx_tracks() {
+        let file = parse(SAMPLE).unwrap();
+        assert_eq!(file.name.as_deref(), Some("Hike"));
+        assert_eq!(file.waypoints.len(), 1);
+        assert_eq!(file.waypoints[0].name, "Summit");
+        assert_eq!(file.waypoints[0].point.elevation, Some(1200.0));
+
+        assert_eq!(file.tracks.len(), 2);
+        assert_eq!(file.tracks[0].name, "Trail");
+        assert_eq!(file.tracks[0].points.len(), 3);
+        assert_eq!(file.tracks[0].points[1].lat, 47.15);
+        assert_eq!(file.tracks[1].name, "Track 2");
+        assert_eq!(file.tracks[1].points[1].elevation, None);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(matches!(
+            parse("<gpx></gpx>"),
+            Err(Error::UnexpectedRoot(_))
+        ));
+    }
+}
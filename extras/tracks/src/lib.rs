@@ -0,0 +1,167 @@
+//! GPX and KML track import.
+//!
+//! Parses the tracks and waypoints out of GPS exchange files into plain
+//! lat/lon data; converting to ECEF and drawing them is the client's job.
+//! Both formats are read into a [`TrackFile`]:
+//!
+//! - **GPX**: `trk`/`trkseg` (one [`Track`] per segment), `rte` (one track
+//!   per route), and `wpt` waypoints.
+//! - **KML**: `Placemark`s holding a `LineString` or `gx:Track` become tracks
+//!   (one per line in a `MultiGeometry`), and `Point` placemarks become
+//!   waypoints.
+
+mod gpx;
+mod kml;
+
+use std::{error::Error as StdError, fmt, path::Path};
+
+/// The tracks and waypoints read from one file.
+#[derive(Clone, Debug, Default)]
+pub struct TrackFile {
+    /// Document name, if the file gives one.
+    pub name: Option<String>,
+    /// Polylines, in file order.
+    pub tracks: Vec<Track>,
+    /// Named points, in file order.
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// A named polyline.
+#[derive(Clone, Debug)]
+pub struct Track {
+    /// Display name (the file's, or a generated one).
+    pub name: String,
+    /// Points along the track, in order.
+    pub points: Vec<TrackPoint>,
+}
+
+/// One point of a track or waypoint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrackPoint {
+    /// Latitude (degrees).
+    pub lat: f64,
+    /// Longitude (degrees).
+    pub lon: f64,
+    /// Elevation above sea level (m), if recorded.
+    pub elevation: Option<f64>,
+}
+
+/// A named point of interest.
+#[derive(Clone, Debug)]
+pub struct Waypoint {
+    /// Display name.
+    pub name: String,
+    /// Where it is.
+    pub point: TrackPoint,
+}
+
+/// A supported file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// GPS Exchange Format.
+    Gpx,
+    /// Keyhole Markup Language.
+    Kml,
+}
+
+impl Format {
+    /// Guess the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gpx" => Some(Self::Gpx),
+            "kml" => Some(Self::Kml),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur while parsing a track file.
+#[derive(Debug)]
+pub enum Error {
+    /// The file isn't well-formed XML.
+    Xml(roxmltree::Error),
+    /// The root element isn't the one the format expects.
+    UnexpectedRoot(String),
+    /// A coordinate couldn't be parsed or is out of range.
+    InvalidCoordinate(String),
+    /// The file holds no tracks or waypoints.
+    Empty,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xml(e) => write!(f, "invalid xml: {e}"),
+            Self::UnexpectedRoot(root) => write!(f, "unexpected root element <{root}>"),
+            Self::InvalidCoordinate(value) => write!(f, "invalid coordinate: {value}"),
+            Self::Empty => write!(f, "no tracks or waypoints found"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Xml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<roxmltree::Error> for Error {
+    fn from(e: roxmltree::Error) -> Self {
+        Self::Xml(e)
+    }
+}
+
+/// Parse `text` as a track file in `format`.
+pub fn parse(format: Format, text: &str) -> Result<TrackFile, Error> {
+    let file = match format {
+        Format::Gpx => gpx::parse(text)?,
+        Format::Kml => kml::parse(text)?,
+    };
+    if file.tracks.is_empty() && file.waypoints.is_empty() {
+        return Err(Error::Empty);
+    }
+    Ok(file)
+}
+
+/// Build a [`TrackPoint`], rejecting out-of-range coordinates.
+fn track_point(lat: f64, lon: f64, elevation: Option<f64>) -> Result<TrackPoint, Error> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(Error::InvalidCoordinate(format!("{lat}, {lon}")));
+    }
+    Ok(TrackPoint {
+        lat,
+        lon,
+        elevation: elevation.filter(|e| e.is_finite()),
+    })
+}
+
+/// Text of the first child element named `name` (namespace ignored), trimmed.
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.tag_name().name() == name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("ride.GPX")), Some(Format::Gpx));
+        assert_eq!(Format::from_path(Path::new("a/b.kml")), Some(Format::Kml));
+        assert_eq!(Format::from_path(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn empty_documents_are_rejected() {
+        let error = parse(Format::Gpx, r#"<gpx version="1.1"></gpx>"#).unwrap_err();
+        assert!(matches!(error, Error::Empty));
+    }
+}
@@ -0,0 +1,189 @@
+//! Throughput benchmarks for the per-node mesh decoders.
+//!
+//! Inputs are synthesised deterministically at a few node sizes, the largest
+//! matching the densest nodes seen in the wild (indices are `u16`, so a node
+//! never has more than 65536 vertices).
+//!
+//! Run with `cargo bench -p rocktree-decode`.
+//!
+//! `unpack_vertices` has an SSE2 path on x86-64, benched here against
+//! `unpack_vertices_scalar`, the portable loop it must match. Best of five
+//! runs on an x86-64 Xeon (rustc 1.95, release), allocation included:
+//!
+//! | vertices | scalar   | SSE2     |
+//! |---------:|---------:|---------:|
+//! |    1 024 |  1.46 µs |  0.89 µs |
+//! |   16 384 | 24.31 µs | 14.54 µs |
+//! |   65 535 | 94.46 µs | 60.89 µs |
+//!
+//! The other decoders are scalar. Their loops avoid bounds checks and
+//! divisions, but the texcoord and index streams carry a modulo or a varint
+//! from one element to the next, which doesn't split into lanes. Numbers
+//! vary by machine: to judge a change, run the benches on its parent and on
+//! the change, on the same machine, and compare.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use rocktree_decode::{
+    Vertex, strip_to_triangles, unpack_for_normals, unpack_indices, unpack_normals,
+    unpack_tex_coords, unpack_vertices, vertices::unpack_vertices_scalar,
+};
+
+/// Vertex counts to benchmark at.
+const VERTEX_COUNTS: [usize; 3] = [1_024, 16_384, 65_535];
+
+/// Entries in the synthesised normal lookup table.
+const NORMAL_TABLE_LEN: usize = 4_096;
+
+/// Deterministic byte stream (xorshift), so runs are comparable.
+fn bytes(len: usize, seed: u32) -> Vec<u8> {
+    let mut state = seed.max(1);
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+/// Append `value` as a varint.
+fn push_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// A packed strip of about two indices per vertex: mostly small deltas, one
+/// new vertex in four, and the occasional long jump back.
+fn packed_indices(vertex_count: usize) -> Vec<u8> {
+    let strip_len = vertex_count * 2;
+    let noise = bytes(strip_len, 7);
+    let mut packed = Vec::with_capacity(strip_len + 4);
+    push_varint(&mut packed, strip_len as u32);
+    for &n in &noise {
+        let value = match n % 16 {
+            0..=3 => 0,
+            4..=14 => u32::from(n % 4) + 1,
+            _ => u32::from(n) * 8,
+        };
+        push_varint(&mut packed, value);
+    }
+    packed
+}
+
+/// A packed texcoord buffer for `vertex_count` vertices.
+fn packed_tex_coords(vertex_count: usize) -> Vec<u8> {
+    let mut packed = vec![0xFF, 0x0F, 0xFF, 0x0F]; // 4096 x 4096 texels.
+    packed.extend(bytes(vertex_count * 4, 3));
+    packed
+}
+
+/// A packed `for_normals` table.
+fn packed_for_normals() -> Vec<u8> {
+    let mut packed = (NORMAL_TABLE_LEN as u16).to_le_bytes().to_vec();
+    packed.push(6);
+    packed.extend(bytes(NORMAL_TABLE_LEN * 2, 5));
+    packed
+}
+
+/// Per-vertex normal indices into a [`NORMAL_TABLE_LEN`]-entry table.
+fn packed_normal_indices(vertex_count: usize) -> Vec<u8> {
+    let indices: Vec<u16> = bytes(vertex_count * 2, 11)
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]) % NORMAL_TABLE_LEN as u16)
+        .collect();
+    let mut packed: Vec<u8> = indices.iter().map(|i| i.to_le_bytes()[0]).collect();
+    packed.extend(indices.iter().map(|i| i.to_le_bytes()[1]));
+    packed
+}
+
+fn bench_vertices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unpack_vertices");
+    for count in VERTEX_COUNTS {
+        let packed = bytes(count * 3, 1);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &packed, |b, packed| {
+            b.iter(|| unpack_vertices(black_box(packed)).unwrap());
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("unpack_vertices_scalar");
+    for count in VERTEX_COUNTS {
+        let packed = bytes(count * 3, 1);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &packed, |b, packed| {
+            b.iter(|| unpack_vertices_scalar(black_box(packed)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_tex_coords(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unpack_tex_coords");
+    for count in VERTEX_COUNTS {
+        let packed = packed_tex_coords(count);
+        let mut vertices = vec![Vertex::default(); count];
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &packed, |b, packed| {
+            b.iter(|| unpack_tex_coords(black_box(packed), black_box(&mut vertices)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_indices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unpack_indices");
+    for count in VERTEX_COUNTS {
+        let packed = packed_indices(count);
+        group.throughput(Throughput::Elements(count as u64 * 2));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &packed, |b, packed| {
+            b.iter(|| unpack_indices(black_box(packed)).unwrap());
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("strip_to_triangles");
+    for count in VERTEX_COUNTS {
+        let strip = unpack_indices(&packed_indices(count)).unwrap();
+        group.throughput(Throughput::Elements(strip.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &strip, |b, strip| {
+            b.iter(|| strip_to_triangles(black_box(strip)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_normals(c: &mut Criterion) {
+    let for_normals = packed_for_normals();
+    c.bench_function("unpack_for_normals", |b| {
+        b.iter(|| unpack_for_normals(black_box(&for_normals)).unwrap());
+    });
+
+    let lookup = unpack_for_normals(&for_normals).unwrap();
+    let mut group = c.benchmark_group("unpack_normals");
+    for count in VERTEX_COUNTS {
+        let packed = packed_normal_indices(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &packed, |b, packed| {
+            b.iter(|| {
+                unpack_normals(Some(black_box(packed)), Some(black_box(&lookup)), count).unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_vertices,
+    bench_tex_coords,
+    bench_indices,
+    bench_normals
+);
+criterion_main!(benches);
@@ -19,6 +19,7 @@
 //! - [`unpack_obb`]: Decode oriented bounding box from 15 bytes
 //! - [`unpack_path_and_flags`]: Extract octant path and flags from metadata
 //! - [`texture::decode_texture`]: Decode JPEG or CRN textures to RGBA
+//! - [`texture::decode_crn_to_bc1`]: Transcode CRN textures to BC1 blocks
 
 mod error;
 mod varint;
@@ -158,40 +158,29 @@ pub fn unpack_normals(
     match (mesh_normals, for_normals) {
         (Some(normals), Some(lookup)) if !normals.is_empty() && !lookup.is_empty() => {
             let count = normals.len() / 2;
+            let (low, high) = normals.split_at(count);
             let mut output = Vec::with_capacity(count * 4);
 
-            for i in 0..count {
+            for (&low, &high) in low.iter().zip(high) {
                 // Index is stored as low byte + high byte << 8.
-                let low = normals[i] as usize;
-                let high = normals[count + i] as usize;
-                let j = low + (high << 8);
+                let j = usize::from(u16::from_le_bytes([low, high]));
 
                 let base = j * 3;
-                if base + 2 >= lookup.len() {
+                let Some(&[x, y, z]) = lookup.get(base..base + 3) else {
                     return Err(DecodeError::IndexOutOfBounds {
                         index: j,
                         len: lookup.len() / 3,
                     });
-                }
+                };
 
-                output.push(lookup[base]);
-                output.push(lookup[base + 1]);
-                output.push(lookup[base + 2]);
-                output.push(0); // Padding.
+                output.extend_from_slice(&[x, y, z, 0]); // Padding.
             }
 
             Ok(output)
         }
         _ => {
             // Return default normals (pointing "up" in normalized space).
-            let mut output = Vec::with_capacity(vertex_count * 4);
-            for _ in 0..vertex_count {
-                output.push(127); // x
-                output.push(127); // y
-                output.push(127); // z
-                output.push(0); // padding
-            }
-            Ok(output)
+            Ok([127, 127, 127, 0].repeat(vertex_count))
         }
     }
 }
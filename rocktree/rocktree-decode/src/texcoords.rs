@@ -59,24 +59,28 @@ pub fn unpack_tex_coords(packed: &[u8], vertices: &mut [Vertex]) -> DecodeResult
     let v_mod = u32::from(u16::from_le_bytes([packed[2], packed[3]])) + 1;
 
     let data = &packed[4..];
-
-    // Delta-decode UVs with modulo arithmetic.
-    let mut u: u32 = 0;
-    let mut v: u32 = 0;
-
-    for i in 0..count {
-        let u_low = u32::from(data[i]);
-        let v_low = u32::from(data[count + i]);
-        let u_high = u32::from(data[count * 2 + i]);
-        let v_high = u32::from(data[count * 3 + i]);
-
-        u = (u + u_low + (u_high << 8)) % u_mod;
-        v = (v + v_low + (v_high << 8)) % v_mod;
-
-        // u and v are always < u_mod/v_mod which are at most 65536, so they fit in u16.
+    let (u_low, rest) = data.split_at(count);
+    let (v_low, rest) = rest.split_at(count);
+    let (u_high, v_high) = rest.split_at(count);
+
+    // Delta-decode UVs with modulo arithmetic. The deltas are summed without
+    // reducing (so consecutive iterations don't wait on a division) and each
+    // sum is reduced with a multiply-based modulo instead.
+    let u_div = FastMod::new(u_mod);
+    let v_div = FastMod::new(v_mod);
+    let mut u_sum: u32 = 0;
+    let mut v_sum: u32 = 0;
+
+    let planes = u_low.iter().zip(v_low).zip(u_high.iter().zip(v_high));
+    for (vertex, ((&u_low, &v_low), (&u_high, &v_high))) in vertices.iter_mut().zip(planes) {
+        u_sum = u_div.keep_small(u_sum + u32::from(u16::from_le_bytes([u_low, u_high])));
+        v_sum = v_div.keep_small(v_sum + u32::from(u16::from_le_bytes([v_low, v_high])));
+
+        // The reduced values are < u_mod/v_mod which are at most 65536, so
+        // they fit in u16.
         {
-            vertices[i].u = u as u16;
-            vertices[i].v = v as u16;
+            vertex.u = u_div.rem(u_sum) as u16;
+            vertex.v = v_div.rem(v_sum) as u16;
         }
     }
 
@@ -88,6 +92,38 @@ pub fn unpack_tex_coords(packed: &[u8], vertices: &mut [Vertex]) -> DecodeResult
     })
 }
 
+/// `value % divisor` by multiplication, for a fixed divisor (Lemire et al.,
+/// "Faster Remainder by Direct Computation", 2019). Exact for every `u32`
+/// value and nonzero divisor.
+#[derive(Clone, Copy)]
+struct FastMod {
+    divisor: u32,
+    multiplier: u64,
+}
+
+impl FastMod {
+    fn new(divisor: u32) -> Self {
+        Self {
+            divisor,
+            // Wraps to 0 for a divisor of 1, which still gives remainder 0.
+            multiplier: (u64::MAX / u64::from(divisor)).wrapping_add(1),
+        }
+    }
+
+    #[inline]
+    fn rem(self, value: u32) -> u32 {
+        let low_bits = self.multiplier.wrapping_mul(u64::from(value));
+        ((u128::from(low_bits) * u128::from(self.divisor)) >> 64) as u32
+    }
+
+    /// Reduce a running sum before adding another `u16` delta could
+    /// overflow it. Only long buffers ever take the branch.
+    #[inline]
+    fn keep_small(self, sum: u32) -> u32 {
+        if sum >= 1 << 31 { self.rem(sum) } else { sum }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +192,39 @@ mod tests {
         assert_eq!(vertices[1].v(), 10);
     }
 
+    #[test]
+    fn test_unpack_tex_coords_matches_scalar() {
+        // Moduli at both extremes and in between, with deltas large enough to
+        // wrap several times.
+        let count = 300;
+        for modulus in [1u32, 2, 100, 4096, 65535, 65536] {
+            let mut packed = ((modulus - 1) as u16).to_le_bytes().to_vec();
+            packed.extend(((modulus - 1) as u16).to_le_bytes());
+            packed.extend((0..count * 4).map(|i| (i * 97 + 13) as u8));
+            let mut vertices = vec![Vertex::default(); count];
+            unpack_tex_coords(&packed, &mut vertices).unwrap();
+
+            let data = &packed[4..];
+            let (mut u, mut v) = (0u32, 0u32);
+            for (i, vertex) in vertices.iter().enumerate() {
+                u = (u + u32::from(data[i]) + (u32::from(data[count * 2 + i]) << 8)) % modulus;
+                v = (v + u32::from(data[count + i]) + (u32::from(data[count * 3 + i]) << 8))
+                    % modulus;
+                assert_eq!((u32::from(vertex.u()), u32::from(vertex.v())), (u, v));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_mod_large_values() {
+        for divisor in [1, 3, 4096, 65536] {
+            let fast = FastMod::new(divisor);
+            for value in [0, 1, divisor, u32::MAX / 2, (1 << 31) + 65535, u32::MAX] {
+                assert_eq!(fast.rem(value), value % divisor);
+            }
+        }
+    }
+
     #[test]
     fn test_unpack_tex_coords_buffer_too_small() {
         let mut vertices = vec![Vertex::default(); 1];
@@ -0,0 +1,77 @@
+//! BC1 (DXT1) block decompression.
+
+/// Decode one 8-byte BC1 block to 16 RGBA pixels in row-major order.
+///
+/// Blocks in three-color mode decode their fourth color as opaque black,
+/// since the textures this crate decodes carry no alpha.
+#[must_use]
+pub fn decode_bc1_block(block: &[u8; 8]) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let selectors = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+
+    let a = rgb565_to_rgb(c0);
+    let b = rgb565_to_rgb(c1);
+    let mix = |f: fn(u16, u16) -> u16| -> [u8; 4] {
+        let channel = |i: usize| f(u16::from(a[i]), u16::from(b[i])) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+    let palette = if c0 > c1 {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(|a, b| (2 * a + b) / 3),
+            mix(|a, b| (a + 2 * b) / 3),
+        ]
+    } else {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(|a, b| (a + b) / 2),
+            [0, 0, 0, 255],
+        ]
+    };
+
+    std::array::from_fn(|i| palette[(selectors >> (i * 2)) as usize & 3])
+}
+
+/// Expand an RGB565 color to 8 bits per channel, replicating high bits
+/// into the low ones.
+fn rgb565_to_rgb(color: u16) -> [u8; 3] {
+    let r = (color >> 11) as u8 & 0x1f;
+    let g = (color >> 5) as u8 & 0x3f;
+    let b = color as u8 & 0x1f;
+    [r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb565_to_rgb_extremes() {
+        assert_eq!(rgb565_to_rgb(0xffff), [255, 255, 255]);
+        assert_eq!(rgb565_to_rgb(0x0000), [0, 0, 0]);
+        assert_eq!(rgb565_to_rgb(0xf800), [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_decode_bc1_block_four_color() {
+        // White and black endpoints; selectors 0, 1, 2, 3 repeating.
+        let block = [0xff, 0xff, 0x00, 0x00, 0xe4, 0xe4, 0xe4, 0xe4];
+        let pixels = decode_bc1_block(&block);
+        assert_eq!(pixels[0], [255, 255, 255, 255]);
+        assert_eq!(pixels[1], [0, 0, 0, 255]);
+        assert_eq!(pixels[2], [170, 170, 170, 255]);
+        assert_eq!(pixels[3], [85, 85, 85, 255]);
+    }
+
+    #[test]
+    fn test_decode_bc1_block_three_color() {
+        // Black then white endpoints select three-color mode.
+        let block = [0x00, 0x00, 0xff, 0xff, 0xe4, 0x00, 0x00, 0x00];
+        let pixels = decode_bc1_block(&block);
+        assert_eq!(pixels[2], [127, 127, 127, 255]);
+        assert_eq!(pixels[3], [0, 0, 0, 255]);
+    }
+}
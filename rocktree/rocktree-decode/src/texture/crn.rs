@@ -1,19 +1,90 @@
 //! Crunch (CRN) texture decoding.
 //!
 //! CRN is a compressed texture format that stores DXT1-encoded data
-//! in a highly compressed form. This module decodes CRN to RGBA pixels.
+//! in a highly compressed form: palettes of block endpoints and selectors,
+//! and per-block palette indices, all Huffman-coded. This module is a
+//! pure-Rust decoder for the DXT1 textures Google Earth serves.
+//!
+//! Decoding is streamed: [`CrnDecoder`] reconstructs the texture two block
+//! rows at a time, so it can emit BC1 blocks for direct GPU upload
+//! ([`decode_crn_to_bc1`]) or expand straight into RGBA pixels
+//! ([`decode_crn_to_rgba`]) without materialising the other form.
+
+mod huffman;
 
 use crate::{
     error::{DecodeError, DecodeResult},
-    texture::DecodedTexture,
+    texture::{DecodedTexture, bc1},
 };
-use texture2ddecoder::CrnTextureInfo;
+use huffman::{BitReader, HuffmanModel};
+
+/// File signature, "Hx".
+const SIGNATURE: u16 = 0x4878;
+
+/// Header size with one mip level, the smallest valid header.
+const MIN_HEADER_SIZE: usize = 74;
+
+/// The DXT1 format code; the only format decoded here.
+const FORMAT_DXT1: u8 = 0;
+
+/// Bytes per BC1 block.
+pub const BC1_BLOCK_SIZE: usize = 8;
+
+/// A BC1 (DXT1) block: two RGB565 endpoints, then 16 2-bit selectors.
+pub type Bc1Block = [u8; BC1_BLOCK_SIZE];
+
+/// Maps linear selector order (endpoint 0, 1/3, 2/3, endpoint 1) to DXT1
+/// selector values.
+const DXT1_FROM_LINEAR: [u32; 4] = [0, 2, 3, 1];
+
+/// Which endpoint tile each block of a 2x2 chunk uses, per chunk encoding.
+/// Blocks are ordered top-left, top-right, bottom-left, bottom-right.
+const CHUNK_TILES: [[usize; 4]; 8] = [
+    [0, 0, 0, 0],
+    [0, 0, 1, 1],
+    [0, 1, 0, 1],
+    [0, 0, 1, 2],
+    [1, 2, 0, 0],
+    [0, 1, 0, 2],
+    [1, 0, 2, 0],
+    [0, 1, 2, 3],
+];
+
+/// Number of endpoint tiles per chunk encoding.
+const CHUNK_TILE_COUNTS: [usize; 8] = [1, 2, 2, 3, 3, 3, 3, 4];
+
+/// Fewest bits a chunk can take: one endpoint and four selector deltas.
+const MIN_CHUNK_BITS: usize = 5;
+
+/// A BC1 texture decoded from CRN.
+#[derive(Debug, Clone)]
+pub struct Bc1Texture {
+    /// BC1 blocks in row-major order, [`BC1_BLOCK_SIZE`] bytes each.
+    pub blocks: Vec<u8>,
+    /// Texture width in pixels.
+    pub width: u32,
+    /// Texture height in pixels.
+    pub height: u32,
+}
+
+impl Bc1Texture {
+    /// Number of blocks across.
+    #[must_use]
+    pub fn blocks_x(&self) -> usize {
+        (self.width as usize).div_ceil(4)
+    }
+
+    /// Number of blocks down.
+    #[must_use]
+    pub fn blocks_y(&self) -> usize {
+        (self.height as usize).div_ceil(4)
+    }
+}
 
 /// Decode CRN (Crunch) data to RGBA pixels.
 ///
-/// This performs two-stage decoding:
-/// 1. CRN -> DXT1 (block-compressed)
-/// 2. DXT1 -> RGBA (uncompressed)
+/// Each pair of block rows is expanded to pixels as soon as it is decoded;
+/// the BC1 form of the whole texture is never held in memory.
 ///
 /// # Arguments
 ///
@@ -25,59 +96,693 @@ use texture2ddecoder::CrnTextureInfo;
 ///
 /// # Errors
 ///
-/// Returns an error if CRN decoding fails.
+/// Returns an error if the data is not a valid DXT1 CRN texture.
 pub fn decode_crn_to_rgba(data: &[u8]) -> DecodeResult<DecodedTexture> {
-    // Get texture info from CRN header.
-    let mut info = CrnTextureInfo::default();
+    let decoder = CrnDecoder::new(data)?;
+    let width = decoder.width() as usize;
+    let height = decoder.height() as usize;
+    let mut rgba = vec![0u8; width * height * 4];
 
-    // Data length cast: texture files are always < 4GB.
-    let data_len = data.len() as u32;
-    let success = info.crnd_get_texture_info(data, data_len);
+    decoder.decode_block_rows(|block_y, blocks| {
+        for (block_x, block) in blocks.iter().enumerate() {
+            let pixels = bc1::decode_bc1_block(block);
+            for (row, row_pixels) in pixels.chunks_exact(4).enumerate() {
+                let y = block_y * 4 + row;
+                if y >= height {
+                    break;
+                }
+                let x = block_x * 4;
+                let columns = (width - x).min(4);
+                let start = (y * width + x) * 4;
+                rgba[start..start + columns * 4]
+                    .copy_from_slice(row_pixels[..columns].as_flattened());
+            }
+        }
+    })?;
 
-    if !success {
-        return Err(DecodeError::InvalidFormat {
-            context: "crn",
-            detail: "failed to read CRN header".to_string(),
-        });
+    Ok(DecodedTexture::new(rgba, decoder.width(), decoder.height()))
+}
+
+/// Decode CRN (Crunch) data to BC1 (DXT1) blocks, for upload as a
+/// compressed texture.
+///
+/// # Errors
+///
+/// Returns an error if the data is not a valid DXT1 CRN texture.
+pub fn decode_crn_to_bc1(data: &[u8]) -> DecodeResult<Bc1Texture> {
+    let decoder = CrnDecoder::new(data)?;
+    let mut blocks = Vec::with_capacity(decoder.blocks_x() * decoder.blocks_y() * BC1_BLOCK_SIZE);
+    decoder.decode_block_rows(|_, row| blocks.extend_from_slice(row.as_flattened()))?;
+    Ok(Bc1Texture {
+        blocks,
+        width: decoder.width(),
+        height: decoder.height(),
+    })
+}
+
+/// Streaming decoder for the top mip level of a DXT1 CRN texture.
+///
+/// Construction parses the header and decodes the endpoint and selector
+/// palettes; [`CrnDecoder::decode_block_rows`] then reconstructs the blocks.
+pub struct CrnDecoder<'a> {
+    width: u32,
+    height: u32,
+    /// Compressed block data of the top mip level.
+    level: &'a [u8],
+    chunk_encoding_model: HuffmanModel,
+    endpoint_delta_model: HuffmanModel,
+    selector_delta_model: HuffmanModel,
+    /// Endpoint pairs, as the first four bytes of a BC1 block.
+    endpoints: Vec<u32>,
+    /// Selectors, as the last four bytes of a BC1 block.
+    selectors: Vec<u32>,
+}
+
+/// A palette's location and entry count in the file.
+#[derive(Clone, Copy)]
+struct Palette {
+    offset: usize,
+    size: usize,
+    count: usize,
+}
+
+impl<'a> CrnDecoder<'a> {
+    /// Parse the header and palettes of a CRN texture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is truncated or corrupt, or isn't a
+    /// single-face DXT1 texture.
+    pub fn new(data: &'a [u8]) -> DecodeResult<Self> {
+        if data.len() < MIN_HEADER_SIZE {
+            return Err(invalid(format!(
+                "{} bytes is too short for a header",
+                data.len()
+            )));
+        }
+        if be(data, 0, 2) as u16 != SIGNATURE {
+            return Err(invalid("bad signature".to_string()));
+        }
+        let header_size = be(data, 2, 2) as usize;
+        let data_size = be(data, 6, 4) as usize;
+        let width = be(data, 12, 2);
+        let height = be(data, 14, 2);
+        let levels = data[16] as usize;
+        let faces = data[17];
+        let format = data[18];
+        if header_size < MIN_HEADER_SIZE || data_size > data.len() {
+            return Err(invalid("header sizes out of range".to_string()));
+        }
+        if width == 0 || height == 0 {
+            return Err(invalid("empty texture".to_string()));
+        }
+        if levels == 0 || 70 + levels * 4 > header_size.min(data_size) {
+            return Err(invalid(format!("bad mip level count {levels}")));
+        }
+        if faces != 1 {
+            return Err(invalid(format!("unsupported face count {faces}")));
+        }
+        if format != FORMAT_DXT1 {
+            return Err(invalid(format!("unsupported format {format}")));
+        }
+        let data = &data[..data_size];
+
+        let palette = |at: usize| Palette {
+            offset: be(data, at, 3) as usize,
+            size: be(data, at + 3, 3) as usize,
+            count: be(data, at + 6, 2) as usize,
+        };
+        let endpoint_palette = palette(33);
+        let selector_palette = palette(41);
+        if endpoint_palette.count == 0 || selector_palette.count == 0 {
+            return Err(invalid("missing color palettes".to_string()));
+        }
+        let tables = section(data, be(data, 67, 3) as usize, be(data, 65, 2) as usize)?;
+
+        let level_start = be(data, 70, 4) as usize;
+        let level_end = if levels > 1 {
+            be(data, 74, 4) as usize
+        } else {
+            data.len()
+        };
+        if level_end <= level_start {
+            return Err(invalid("mip level offsets out of order".to_string()));
+        }
+        let level = section(data, level_start, level_end - level_start)?;
+
+        // Every chunk codes at least five symbols of at least one bit, so a
+        // corrupt size can't claim more chunks than the level data could
+        // hold (and make the caller allocate for them).
+        let chunks = (width as usize).div_ceil(8) * (height as usize).div_ceil(8);
+        if chunks * MIN_CHUNK_BITS > level.len() * 8 {
+            return Err(invalid(format!(
+                "{width}x{height} texture from {} bytes of level data",
+                level.len()
+            )));
+        }
+
+        let mut reader = BitReader::new(tables);
+        let chunk_encoding_model = reader.read_model()?;
+        let endpoint_delta_model = reader.read_model()?;
+        let selector_delta_model = reader.read_model()?;
+
+        Ok(Self {
+            width,
+            height,
+            level,
+            chunk_encoding_model,
+            endpoint_delta_model,
+            selector_delta_model,
+            endpoints: decode_endpoints(data, endpoint_palette)?,
+            selectors: decode_selectors(data, selector_palette)?,
+        })
+    }
+
+    /// Texture width in pixels.
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Texture height in pixels.
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of blocks across.
+    #[must_use]
+    pub fn blocks_x(&self) -> usize {
+        (self.width as usize).div_ceil(4)
+    }
+
+    /// Number of blocks down.
+    #[must_use]
+    pub fn blocks_y(&self) -> usize {
+        (self.height as usize).div_ceil(4)
     }
 
-    let width = info.width;
-    let height = info.height;
+    /// Decode the texture's blocks, calling `emit` once per block row, top
+    /// to bottom, with the row index and its blocks.
+    ///
+    /// Blocks are coded in 2x2 chunks, so rows are decoded in pairs; only
+    /// those two rows are held at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block data is corrupt. Rows already emitted
+    /// stay emitted.
+    pub fn decode_block_rows(&self, mut emit: impl FnMut(usize, &[Bc1Block])) -> DecodeResult<()> {
+        let blocks_x = self.blocks_x();
+        let blocks_y = self.blocks_y();
+        let chunks_x = blocks_x.div_ceil(2);
+        let chunks_y = blocks_y.div_ceil(2);
+
+        // Two rows of blocks, padded to whole chunks.
+        let padded_x = chunks_x * 2;
+        let mut rows = vec![[0u8; BC1_BLOCK_SIZE]; padded_x * 2];
+
+        let mut reader = BitReader::new(self.level);
+        let mut chunk_encodings = 1u32;
+        let mut endpoint_index = 0;
+        let mut selector_index = 0;
+
+        for chunk_y in 0..chunks_y {
+            // Chunk rows alternate direction.
+            for step in 0..chunks_x {
+                let chunk_x = if chunk_y % 2 == 1 {
+                    chunks_x - 1 - step
+                } else {
+                    step
+                };
 
-    // Allocate output buffer for RGBA data (4 bytes per pixel).
-    // texture2ddecoder outputs as packed u32 (RGBA).
-    let pixel_count = (width as usize) * (height as usize);
-    let mut rgba_u32 = vec![0u32; pixel_count];
+                // Chunk encodings come three to a symbol; the marker bit
+                // tells when they run out.
+                if chunk_encodings == 1 {
+                    chunk_encodings = u32::from(reader.decode(&self.chunk_encoding_model)?) | 512;
+                }
+                let encoding = (chunk_encodings & 7) as usize;
+                chunk_encodings >>= 3;
 
-    // Decode CRN directly to RGBA.
-    texture2ddecoder::decode_crunch(data, width as usize, height as usize, &mut rgba_u32).map_err(
-        |e| DecodeError::InvalidFormat {
-            context: "crn",
-            detail: format!("failed to decode CRN: {e}"),
-        },
-    )?;
+                let mut tiles = [0u32; 4];
+                for tile in &mut tiles[..CHUNK_TILE_COUNTS[encoding]] {
+                    let delta = reader.decode(&self.endpoint_delta_model)?;
+                    endpoint_index = step_index(endpoint_index, delta, self.endpoints.len())?;
+                    *tile = self.endpoints[endpoint_index];
+                }
 
-    // Convert BGRA u32 to RGBA byte array.
-    let rgba_bytes = bgra_u32_to_rgba_bytes(rgba_u32);
+                for (i, &tile) in CHUNK_TILES[encoding].iter().enumerate() {
+                    let delta = reader.decode(&self.selector_delta_model)?;
+                    selector_index = step_index(selector_index, delta, self.selectors.len())?;
 
-    Ok(DecodedTexture::new(rgba_bytes, width, height))
+                    let block = &mut rows[(i / 2) * padded_x + chunk_x * 2 + i % 2];
+                    block[..4].copy_from_slice(&tiles[tile].to_le_bytes());
+                    block[4..].copy_from_slice(&self.selectors[selector_index].to_le_bytes());
+                }
+            }
+
+            let (top, bottom) = rows.split_at(padded_x);
+            emit(chunk_y * 2, &top[..blocks_x]);
+            if chunk_y * 2 + 1 < blocks_y {
+                emit(chunk_y * 2 + 1, &bottom[..blocks_x]);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Advance a palette index by a coded delta, wrapping once.
+fn step_index(index: usize, delta: u16, len: usize) -> DecodeResult<usize> {
+    let mut next = index + usize::from(delta);
+    if next >= len {
+        next -= len;
+    }
+    if next >= len {
+        return Err(DecodeError::IndexOutOfBounds { index: next, len });
+    }
+    Ok(next)
 }
 
-/// Convert packed BGRA u32 values to RGBA byte array.
-fn bgra_u32_to_rgba_bytes(data: Vec<u32>) -> Vec<u8> {
-    let mut bytes = Vec::with_capacity(data.len() * 4);
-    for pixel in data {
-        // texture2ddecoder outputs BGRA32: u32 is 0xAARRGGBB (A high, B low).
-        // to_le_bytes() gives [B, G, R, A], so we swap R and B for RGBA.
-        let [b, g, r, a] = pixel.to_le_bytes();
-        bytes.extend_from_slice(&[r, g, b, a]);
+/// Decode the endpoint palette: six delta-coded components per entry,
+/// packed as BC1's two RGB565 endpoints.
+fn decode_endpoints(data: &[u8], palette: Palette) -> DecodeResult<Vec<u32>> {
+    let mut reader = BitReader::new(section(data, palette.offset, palette.size)?);
+    let five_bit = reader.read_model()?;
+    let six_bit = reader.read_model()?;
+
+    let mut components = [0u32; 6];
+    let mut endpoints = Vec::with_capacity(palette.count);
+    for _ in 0..palette.count {
+        // Red, green, blue of the first endpoint, then of the second.
+        for (i, component) in components.iter_mut().enumerate() {
+            let (model, mask) = if i % 3 == 1 {
+                (&six_bit, 63)
+            } else {
+                (&five_bit, 31)
+            };
+            *component = (*component + u32::from(reader.decode(model)?)) & mask;
+        }
+        let [r0, g0, b0, r1, g1, b1] = components;
+        endpoints.push(b0 | g0 << 5 | r0 << 11 | b1 << 16 | g1 << 21 | r1 << 27);
+    }
+    Ok(endpoints)
+}
+
+/// Decode the selector palette: each entry is eight symbols, each coding
+/// the deltas of two of the block's 16 linear selectors.
+fn decode_selectors(data: &[u8], palette: Palette) -> DecodeResult<Vec<u32>> {
+    let mut reader = BitReader::new(section(data, palette.offset, palette.size)?);
+    let model = reader.read_model()?;
+    if model.symbol_count() > 49 {
+        return Err(invalid("selector delta model too large".to_string()));
+    }
+
+    let mut linear = [0i32; 16];
+    let mut selectors = Vec::with_capacity(palette.count);
+    for _ in 0..palette.count {
+        for pair in linear.chunks_exact_mut(2) {
+            // Symbols enumerate (delta0, delta1) pairs in -3..=3.
+            let symbol = i32::from(reader.decode(&model)?);
+            pair[0] = (pair[0] + symbol % 7 - 3) & 3;
+            pair[1] = (pair[1] + symbol / 7 - 3) & 3;
+        }
+        let selector = linear.iter().enumerate().fold(0, |bits, (i, &value)| {
+            bits | DXT1_FROM_LINEAR[value as usize] << (i * 2)
+        });
+        selectors.push(selector);
+    }
+    Ok(selectors)
+}
+
+/// The `len` bytes of `data` at `offset`.
+fn section(data: &[u8], offset: usize, len: usize) -> DecodeResult<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .filter(|section| !section.is_empty())
+        .ok_or_else(|| invalid(format!("section {offset}+{len} out of bounds")))
+}
+
+/// Big-endian unsigned integer of `len` (at most 4) bytes at `offset`.
+/// Callers have checked the header is long enough.
+fn be(data: &[u8], offset: usize, len: usize) -> u32 {
+    data[offset..offset + len]
+        .iter()
+        .fold(0, |value, &byte| value << 8 | u32::from(byte))
+}
+
+fn invalid(detail: String) -> DecodeError {
+    DecodeError::InvalidFormat {
+        context: "crn",
+        detail,
     }
-    bytes
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use huffman::CODE_LENGTH_ORDER;
+    use proptest::prelude::*;
+
+    /// MSB-first bit writer, the inverse of the decoder's reader.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bits: u32,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, bits: u32) {
+            for i in (0..bits).rev() {
+                if self.bits.is_multiple_of(8) {
+                    self.bytes.push(0);
+                }
+                let bit = (value >> i) as u8 & 1;
+                *self.bytes.last_mut().unwrap() |= bit << (7 - self.bits % 8);
+                self.bits += 1;
+            }
+        }
+
+        /// Send a model over `symbol_count` symbols in which every symbol of
+        /// `used` has the same code length. Returns that length.
+        fn write_model(&mut self, symbol_count: usize, used: &[u32]) -> u32 {
+            let len = (used.len() as u32).next_power_of_two().ilog2().max(1);
+            self.write(symbol_count as u32, 14);
+
+            // Code lengths 0, `len` and the short zero run get 2-bit codes
+            // 00, 01 and 10.
+            self.write(21, 5);
+            for code in CODE_LENGTH_ORDER {
+                let size = u32::from(code == 0 || u32::from(code) == len || code == 17) * 2;
+                self.write(size, 3);
+            }
+            let mut symbol = 0;
+            while symbol < symbol_count {
+                let zeros = (symbol..symbol_count)
+                    .take_while(|s| !used.contains(&(*s as u32)))
+                    .count()
+                    .min(10);
+                if zeros >= 3 {
+                    self.write(0b10, 2);
+                    self.write(zeros as u32 - 3, 3);
+                    symbol += zeros;
+                } else {
+                    let size = if used.contains(&(symbol as u32)) {
+                        0b01
+                    } else {
+                        0b00
+                    };
+                    self.write(size, 2);
+                    symbol += 1;
+                }
+            }
+            len
+        }
+
+        fn finish(self) -> Vec<u8> {
+            self.bytes
+        }
+    }
+
+    /// A model in which `used` symbols have equal-length codes.
+    struct Code {
+        used: Vec<u32>,
+        len: u32,
+    }
+
+    impl Code {
+        fn new(writer: &mut BitWriter, symbol_count: usize, mut used: Vec<u32>) -> Self {
+            used.sort_unstable();
+            used.dedup();
+            let len = writer.write_model(symbol_count, &used);
+            Self { used, len }
+        }
+
+        fn write(&self, writer: &mut BitWriter, symbol: u32) {
+            let code = self.used.binary_search(&symbol).unwrap();
+            writer.write(code as u32, self.len);
+        }
+    }
+
+    fn put(header: &mut [u8], offset: usize, len: usize, value: usize) {
+        for i in 0..len {
+            header[offset + i] = (value >> (8 * (len - 1 - i))) as u8;
+        }
+    }
+
+    /// The first chunk encoding whose endpoint tiles fit `blocks` (missing
+    /// blocks fit anything), so blocks sharing endpoints share a tile.
+    fn chunk_encoding(blocks: &[Option<u32>]) -> usize {
+        (0..8)
+            .find(|&encoding| {
+                let tiles = CHUNK_TILES[encoding];
+                (0..4).all(|i| {
+                    (0..4).all(|j| {
+                        tiles[i] != tiles[j]
+                            || blocks[i].is_none()
+                            || blocks[j].is_none()
+                            || blocks[i] == blocks[j]
+                    })
+                })
+            })
+            .unwrap()
+    }
+
+    /// Encode BC1 blocks (row-major, `div_ceil(4)` blocks per row) as a
+    /// single-level CRN texture, giving each chunk the fewest endpoint tiles
+    /// its blocks allow.
+    fn encode_crn(width: u32, height: u32, blocks: &[Bc1Block]) -> Vec<u8> {
+        let blocks_x = (width as usize).div_ceil(4);
+        let blocks_y = (height as usize).div_ceil(4);
+        let chunks_x = blocks_x.div_ceil(2);
+        let chunks_y = blocks_y.div_ceil(2);
+        assert_eq!(blocks.len(), blocks_x * blocks_y);
+
+        let block_at =
+            |x: usize, y: usize| (x < blocks_x && y < blocks_y).then(|| blocks[y * blocks_x + x]);
+        let word =
+            |block: &Bc1Block, at: usize| u32::from_le_bytes(block[at..at + 4].try_into().unwrap());
+
+        let mut endpoints: Vec<u32> = blocks.iter().map(|b| word(b, 0)).collect();
+        endpoints.sort_unstable();
+        endpoints.dedup();
+        let mut selectors: Vec<u32> = blocks.iter().map(|b| word(b, 4)).collect();
+        selectors.sort_unstable();
+        selectors.dedup();
+
+        // Endpoint palette.
+        let components = |e: u32| {
+            [
+                e >> 11 & 31,
+                e >> 5 & 63,
+                e & 31,
+                e >> 27 & 31,
+                e >> 21 & 63,
+                e >> 16 & 31,
+            ]
+        };
+        let mut deltas = Vec::new();
+        let mut previous = [0u32; 6];
+        for &endpoint in &endpoints {
+            let current = components(endpoint);
+            for i in 0..6 {
+                let mask = if i % 3 == 1 { 63 } else { 31 };
+                deltas.push((i, current[i].wrapping_sub(previous[i]) & mask));
+            }
+            previous = current;
+        }
+        let mut writer = BitWriter::default();
+        let five_bit = Code::new(
+            &mut writer,
+            32,
+            deltas
+                .iter()
+                .filter(|d| d.0 % 3 != 1)
+                .map(|d| d.1)
+                .collect(),
+        );
+        let six_bit = Code::new(
+            &mut writer,
+            64,
+            deltas
+                .iter()
+                .filter(|d| d.0 % 3 == 1)
+                .map(|d| d.1)
+                .collect(),
+        );
+        for &(i, delta) in &deltas {
+            let code = if i % 3 == 1 { &six_bit } else { &five_bit };
+            code.write(&mut writer, delta);
+        }
+        let endpoint_section = writer.finish();
+
+        // Selector palette.
+        const LINEAR_FROM_DXT1: [i32; 4] = [0, 3, 1, 2];
+        let mut symbols = Vec::new();
+        let mut previous = [0i32; 16];
+        for &selector in &selectors {
+            let current: [i32; 16] =
+                std::array::from_fn(|i| LINEAR_FROM_DXT1[(selector >> (i * 2)) as usize & 3]);
+            for j in 0..8 {
+                let d0 = (current[2 * j] - previous[2 * j]) & 3;
+                let d1 = (current[2 * j + 1] - previous[2 * j + 1]) & 3;
+                symbols.push((d0 + 3 + 7 * (d1 + 3)) as u32);
+            }
+            previous = current;
+        }
+        let mut writer = BitWriter::default();
+        let selector_code = Code::new(&mut writer, 49, symbols.clone());
+        for &symbol in &symbols {
+            selector_code.write(&mut writer, symbol);
+        }
+        let selector_section = writer.finish();
+
+        // Level data: serpentine chunk rows.
+        let mut chunk_encodings = Vec::new();
+        let mut endpoint_deltas = Vec::new();
+        let mut selector_deltas = Vec::new();
+        let (mut endpoint_index, mut selector_index) = (0, 0);
+        for chunk_y in 0..chunks_y {
+            for step in 0..chunks_x {
+                let chunk_x = if chunk_y % 2 == 1 {
+                    chunks_x - 1 - step
+                } else {
+                    step
+                };
+                let chunk_blocks: Vec<_> = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .iter()
+                    .map(|&(dx, dy)| block_at(chunk_x * 2 + dx, chunk_y * 2 + dy))
+                    .collect();
+                let delta = |index: &mut usize, palette: &[u32], value: Option<u32>| {
+                    let next = value.map_or(*index, |v| palette.binary_search(&v).unwrap());
+                    let delta = (next + palette.len() - *index) % palette.len();
+                    *index = next;
+                    delta as u32
+                };
+                let block_endpoints: Vec<_> = chunk_blocks
+                    .iter()
+                    .map(|block| block.map(|b| word(&b, 0)))
+                    .collect();
+                let encoding = chunk_encoding(&block_endpoints);
+                chunk_encodings.push(encoding);
+                let tiles = CHUNK_TILES[encoding];
+                for tile in 0..CHUNK_TILE_COUNTS[encoding] {
+                    let endpoint = (0..4)
+                        .filter(|&i| tiles[i] == tile)
+                        .find_map(|i| block_endpoints[i]);
+                    endpoint_deltas.push(delta(&mut endpoint_index, &endpoints, endpoint));
+                }
+                for block in &chunk_blocks {
+                    selector_deltas.push(delta(
+                        &mut selector_index,
+                        &selectors,
+                        block.map(|b| word(&b, 4)),
+                    ));
+                }
+            }
+        }
+
+        // Chunk encodings go three to a symbol, the first in the low bits.
+        let chunk_symbols: Vec<u32> = chunk_encodings
+            .chunks(3)
+            .map(|group| {
+                group
+                    .iter()
+                    .rev()
+                    .fold(0, |symbol, &encoding| symbol << 3 | encoding as u32)
+            })
+            .collect();
+
+        let mut writer = BitWriter::default();
+        let chunk_code = Code::new(&mut writer, 512, chunk_symbols.clone());
+        let endpoint_code = Code::new(&mut writer, endpoints.len(), endpoint_deltas.clone());
+        let selector_delta_code = Code::new(&mut writer, selectors.len(), selector_deltas.clone());
+        let tables_section = writer.finish();
+
+        let mut writer = BitWriter::default();
+        let (mut e, mut s) = (endpoint_deltas.iter(), selector_deltas.iter());
+        for (chunk, &encoding) in chunk_encodings.iter().enumerate() {
+            if chunk % 3 == 0 {
+                chunk_code.write(&mut writer, chunk_symbols[chunk / 3]);
+            }
+            for _ in 0..CHUNK_TILE_COUNTS[encoding] {
+                endpoint_code.write(&mut writer, *e.next().unwrap());
+            }
+            for _ in 0..4 {
+                selector_delta_code.write(&mut writer, *s.next().unwrap());
+            }
+        }
+        let level_section = writer.finish();
+
+        let mut file = vec![0u8; MIN_HEADER_SIZE];
+        let append = |file: &mut Vec<u8>, section: &[u8]| {
+            let offset = file.len();
+            file.extend_from_slice(section);
+            (offset, section.len())
+        };
+        let tables = append(&mut file, &tables_section);
+        let endpoint_range = append(&mut file, &endpoint_section);
+        let selector_range = append(&mut file, &selector_section);
+        let level = append(&mut file, &level_section);
+
+        let size = file.len();
+        let header = &mut file[..MIN_HEADER_SIZE];
+        put(header, 0, 2, usize::from(SIGNATURE));
+        put(header, 2, 2, MIN_HEADER_SIZE);
+        put(header, 6, 4, size);
+        put(header, 12, 2, width as usize);
+        put(header, 14, 2, height as usize);
+        header[16] = 1;
+        header[17] = 1;
+        header[18] = FORMAT_DXT1;
+        put(header, 33, 3, endpoint_range.0);
+        put(header, 36, 3, endpoint_range.1);
+        put(header, 39, 2, endpoints.len());
+        put(header, 41, 3, selector_range.0);
+        put(header, 44, 3, selector_range.1);
+        put(header, 47, 2, selectors.len());
+        put(header, 65, 2, tables.1);
+        put(header, 67, 3, tables.0);
+        put(header, 70, 4, level.0);
+        file
+    }
+
+    /// Deterministic pseudo-random BC1 blocks for a `width` x `height`
+    /// texture, drawn from small palettes so palette indices repeat. Each
+    /// 2x2 chunk shares distinct endpoints between its blocks as one of the
+    /// eight chunk encodings does, so every encoding gets coded.
+    fn test_blocks(width: u32, height: u32, seed: u64) -> Vec<Bc1Block> {
+        let blocks_x = (width as usize).div_ceil(4);
+        let blocks_y = (height as usize).div_ceil(4);
+        let mut state = seed | 1;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let endpoints: Vec<u32> = (0..7).map(|_| next() as u32).collect();
+        let selectors: Vec<u32> = (0..5).map(|_| next() as u32).collect();
+        let mut blocks = vec![[0u8; 8]; blocks_x * blocks_y];
+        for chunk_y in 0..blocks_y.div_ceil(2) {
+            for chunk_x in 0..blocks_x.div_ceil(2) {
+                let encoding = next() as usize % 8;
+                let first = next() as usize;
+                let tile_endpoints: Vec<u32> = (0..CHUNK_TILE_COUNTS[encoding])
+                    .map(|tile| endpoints[(first + tile) % 7])
+                    .collect();
+                for (i, &tile) in CHUNK_TILES[encoding].iter().enumerate() {
+                    let (x, y) = (chunk_x * 2 + i % 2, chunk_y * 2 + i / 2);
+                    if x < blocks_x && y < blocks_y {
+                        let block = &mut blocks[y * blocks_x + x];
+                        block[..4].copy_from_slice(&tile_endpoints[tile].to_le_bytes());
+                        block[4..].copy_from_slice(&selectors[next() as usize % 5].to_le_bytes());
+                    }
+                }
+            }
+        }
+        blocks
+    }
 
     #[test]
     fn test_decode_crn_invalid() {
@@ -88,15 +793,143 @@ mod tests {
     }
 
     #[test]
-    fn test_bgra_u32_to_rgba_bytes() {
-        // BGRA32: 0xAARRGGBB -> to_le_bytes gives [B, G, R, A] -> output [R, G, B, A].
-        let input = vec![0x11223344u32, 0xAABBCCDDu32];
-        let bytes = bgra_u32_to_rgba_bytes(input);
-
-        // 0x11223344: A=0x11, R=0x22, G=0x33, B=0x44 -> RGBA = [0x22, 0x33, 0x44, 0x11].
-        // 0xAABBCCDD: A=0xAA, R=0xBB, G=0xCC, B=0xDD -> RGBA = [0xBB, 0xCC, 0xDD, 0xAA].
-        assert_eq!(bytes.len(), 8);
-        assert_eq!(&bytes[0..4], &[0x22, 0x33, 0x44, 0x11]);
-        assert_eq!(&bytes[4..8], &[0xBB, 0xCC, 0xDD, 0xAA]);
+    fn test_decode_crn_to_bc1_roundtrip() {
+        for (width, height) in [(4, 4), (16, 8), (12, 20), (36, 28)] {
+            let blocks = test_blocks(width, height, u64::from(width * 31 + height));
+            let crn = encode_crn(width, height, &blocks);
+
+            let texture = decode_crn_to_bc1(&crn).unwrap();
+            assert_eq!((texture.width, texture.height), (width, height));
+            assert_eq!(texture.blocks, blocks.as_flattened());
+        }
+    }
+
+    #[test]
+    fn test_decode_crn_to_rgba_matches_bc1() {
+        // Odd sizes leave partial blocks on the right and bottom edges.
+        for (width, height) in [(8, 8), (5, 3), (13, 9), (1, 17)] {
+            let blocks_x = (width as usize).div_ceil(4);
+            let blocks = test_blocks(width, height, u64::from(width + height * 7));
+            let crn = encode_crn(width, height, &blocks);
+
+            let texture = decode_crn_to_rgba(&crn).unwrap();
+            assert!(texture.is_valid());
+            for y in 0..height as usize {
+                for x in 0..width as usize {
+                    let pixels = bc1::decode_bc1_block(&blocks[(y / 4) * blocks_x + x / 4]);
+                    let start = (y * width as usize + x) * 4;
+                    assert_eq!(texture.data[start..start + 4], pixels[(y % 4) * 4 + x % 4]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_block_rows_in_order() {
+        let blocks = test_blocks(12, 20, 9);
+        let crn = encode_crn(12, 20, &blocks);
+        let decoder = CrnDecoder::new(&crn).unwrap();
+        let mut rows = Vec::new();
+        decoder
+            .decode_block_rows(|y, row| {
+                assert_eq!(row, &blocks[y * 3..y * 3 + 3]);
+                rows.push(y);
+            })
+            .unwrap();
+        assert_eq!(rows, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_encoder_codes_every_chunk_encoding() {
+        let blocks = test_blocks(64, 64, 5);
+        let blocks_x = 16;
+        let mut used = [false; 8];
+        for chunk_y in 0..8 {
+            for chunk_x in 0..8 {
+                let chunk: Vec<_> = (0..4)
+                    .map(|i| {
+                        let block = blocks[(chunk_y * 2 + i / 2) * blocks_x + chunk_x * 2 + i % 2];
+                        Some(u32::from_le_bytes(block[..4].try_into().unwrap()))
+                    })
+                    .collect();
+                used[chunk_encoding(&chunk)] = true;
+            }
+        }
+        assert_eq!(used, [true; 8]);
+    }
+
+    /// The reference decoder the crate used before this one, as a check
+    /// that the test encoder and this decoder agree with crunch itself.
+    #[test]
+    fn test_decode_crn_matches_texture2ddecoder() {
+        for (width, height) in [(64, 64), (36, 28), (13, 9), (256, 256)] {
+            let blocks = test_blocks(width, height, u64::from(width ^ height << 9));
+            let crn = encode_crn(width, height, &blocks);
+            let (width, height) = (width as usize, height as usize);
+
+            let mut expected = vec![0u32; width * height];
+            texture2ddecoder::decode_crunch(&crn, width, height, &mut expected).unwrap();
+
+            let texture = decode_crn_to_bc1(&crn).unwrap();
+            let mut actual = vec![0u32; width * height];
+            texture2ddecoder::decode_bc1(&texture.blocks, width, height, &mut actual).unwrap();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_crn_rejects_unsupported_format() {
+        let mut crn = encode_crn(4, 4, &test_blocks(4, 4, 1));
+        crn[18] = 2;
+        assert!(matches!(
+            CrnDecoder::new(&crn),
+            Err(DecodeError::InvalidFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_crn_truncated_level() {
+        let crn = encode_crn(16, 16, &test_blocks(16, 16, 3));
+        // Drop the level data's last byte but keep the header consistent.
+        let mut truncated = crn[..crn.len() - 1].to_vec();
+        put(&mut truncated, 6, 4, crn.len() - 1);
+        assert!(decode_crn_to_bc1(&truncated).is_err());
+    }
+
+    proptest! {
+        /// Arbitrary bytes never panic.
+        #[test]
+        fn fuzz_random_bytes(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+            let _ = decode_crn_to_rgba(&data);
+        }
+
+        /// Corrupting a valid file never panics, and whatever still decodes
+        /// has the advertised size.
+        #[test]
+        fn fuzz_corrupted_file(
+            seed in any::<u64>(),
+            flips in proptest::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+            truncate in any::<prop::sample::Index>(),
+        ) {
+            let blocks = test_blocks(21, 18, seed);
+            let mut crn = encode_crn(21, 18, &blocks);
+            for (index, value) in flips {
+                let at = index.index(crn.len());
+                crn[at] ^= value;
+            }
+            if seed % 4 == 0 {
+                crn.truncate(truncate.index(crn.len()));
+            }
+
+            if let Ok(texture) = decode_crn_to_rgba(&crn) {
+                prop_assert!(texture.is_valid());
+            }
+            if let Ok(texture) = decode_crn_to_bc1(&crn) {
+                prop_assert_eq!(
+                    texture.blocks.len(),
+                    texture.blocks_x() * texture.blocks_y() * BC1_BLOCK_SIZE
+                );
+            }
+        }
     }
 }
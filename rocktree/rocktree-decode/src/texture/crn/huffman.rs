@@ -0,0 +1,294 @@
+//! Bit reading and static Huffman models for the crunch format.
+//!
+//! Crunch streams are read MSB-first. Every section starts with the Huffman
+//! models it uses, each sent as a list of code lengths that is itself
+//! Huffman-coded (the same scheme as DEFLATE's dynamic blocks). Codes are
+//! canonical: shorter codes first, ties broken by symbol order.
+
+use crate::error::{DecodeError, DecodeResult};
+
+/// Longest code length crunch produces.
+const MAX_CODE_SIZE: usize = 16;
+
+/// Most symbols a model may have.
+const MAX_SYMBOLS: u32 = 8192;
+
+/// Bits used to send a model's symbol count (enough for [`MAX_SYMBOLS`]).
+const SYMBOL_COUNT_BITS: u32 = 14;
+
+/// Size of the code-length alphabet: lengths 0-16 plus four run codes.
+const CODE_LENGTH_CODES: usize = 21;
+
+/// Code-length code that repeats zero 3-10 times.
+const SMALL_ZERO_RUN: u16 = 17;
+/// Code-length code that repeats zero 11-138 times.
+const LARGE_ZERO_RUN: u16 = 18;
+/// Code-length code that repeats the previous length 3-6 times.
+const SMALL_REPEAT: u16 = 19;
+/// Code-length code that repeats the previous length 7-70 times.
+const LARGE_REPEAT: u16 = 20;
+
+/// Order in which the code-length alphabet's own code lengths are sent, so
+/// trailing unused entries can be left off.
+pub(super) const CODE_LENGTH_ORDER: [u8; CODE_LENGTH_CODES] = [
+    17, 18, 19, 20, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15, 16,
+];
+
+/// Largest direct lookup table, in bits of code.
+const MAX_TABLE_BITS: u32 = 11;
+
+/// MSB-first bit reader over one crunch section.
+///
+/// Reads past the end of the section see zero bytes, as crunch's own decoder
+/// does: the last symbol's lookahead may run over the end. Actually
+/// consuming bits beyond the end is an error.
+pub(super) struct BitReader<'a> {
+    data: &'a [u8],
+    /// Next byte of `data` to load into `buf`.
+    next: usize,
+    /// Unconsumed bits, left-aligned.
+    buf: u64,
+    /// Number of valid bits in `buf`.
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            next: 0,
+            buf: 0,
+            count: 0,
+        }
+    }
+
+    fn refill(&mut self) {
+        while self.count <= 56 {
+            let byte = self.data.get(self.next).copied().unwrap_or(0);
+            self.next += 1;
+            self.buf |= u64::from(byte) << (56 - self.count);
+            self.count += 8;
+        }
+    }
+
+    fn consume(&mut self, bits: u32) -> DecodeResult<()> {
+        self.buf <<= bits;
+        self.count -= bits;
+        let consumed = self.next * 8 - self.count as usize;
+        if consumed > self.data.len() * 8 {
+            return Err(DecodeError::UnexpectedEof { context: "crn" });
+        }
+        Ok(())
+    }
+
+    /// Read `bits` (at most 32) bits as an unsigned integer.
+    pub(super) fn read_bits(&mut self, bits: u32) -> DecodeResult<u32> {
+        debug_assert!(bits <= 32);
+        if bits == 0 {
+            return Ok(0);
+        }
+        self.refill();
+        let value = (self.buf >> (64 - bits)) as u32;
+        self.consume(bits)?;
+        Ok(value)
+    }
+
+    /// Decode one symbol with `model`.
+    pub(super) fn decode(&mut self, model: &HuffmanModel) -> DecodeResult<u16> {
+        self.refill();
+        let (symbol, len) = model
+            .lookup(self.buf)
+            .ok_or_else(|| invalid("invalid huffman code"))?;
+        self.consume(len)?;
+        Ok(symbol)
+    }
+
+    /// Read a model sent as Huffman-coded code lengths.
+    pub(super) fn read_model(&mut self) -> DecodeResult<HuffmanModel> {
+        let symbol_count = self.read_bits(SYMBOL_COUNT_BITS)?;
+        if symbol_count == 0 || symbol_count > MAX_SYMBOLS {
+            return Err(invalid("huffman model symbol count out of range"));
+        }
+
+        // The code-length alphabet's own lengths, 3 bits each.
+        let sent = self.read_bits(5)? as usize;
+        if sent == 0 || sent > CODE_LENGTH_CODES {
+            return Err(invalid("huffman code length count out of range"));
+        }
+        let mut code_length_sizes = [0u8; CODE_LENGTH_CODES];
+        for &code in &CODE_LENGTH_ORDER[..sent] {
+            code_length_sizes[usize::from(code)] = self.read_bits(3)? as u8;
+        }
+        let code_length_model = HuffmanModel::new(&code_length_sizes)?;
+
+        let symbol_count = symbol_count as usize;
+        let mut sizes = vec![0u8; symbol_count];
+        let mut filled = 0;
+        while filled < symbol_count {
+            let code = self.decode(&code_length_model)?;
+            let (value, run) = match code {
+                0..=16 => (code as u8, 1),
+                SMALL_ZERO_RUN => (0, self.read_bits(3)? as usize + 3),
+                LARGE_ZERO_RUN => (0, self.read_bits(7)? as usize + 11),
+                SMALL_REPEAT | LARGE_REPEAT => {
+                    let run = if code == SMALL_REPEAT {
+                        self.read_bits(2)? as usize + 3
+                    } else {
+                        self.read_bits(6)? as usize + 7
+                    };
+                    let previous = match filled.checked_sub(1).map(|i| sizes[i]) {
+                        Some(previous) if previous != 0 => previous,
+                        _ => return Err(invalid("huffman repeat without a previous length")),
+                    };
+                    (previous, run)
+                }
+                _ => return Err(invalid("invalid huffman code length code")),
+            };
+            let run_end = filled + run;
+            if run_end > symbol_count {
+                return Err(invalid("huffman code length run overflows the model"));
+            }
+            sizes[filled..run_end].fill(value);
+            filled = run_end;
+        }
+
+        HuffmanModel::new(&sizes)
+    }
+}
+
+/// A canonical Huffman code, decoded through a direct lookup table for
+/// short codes and a per-length search for long ones.
+pub(super) struct HuffmanModel {
+    /// Number of symbols in the alphabet.
+    symbol_count: usize,
+    /// Bits indexed by `table`; 0 if every code is searched.
+    table_bits: u32,
+    /// Symbol and code length for each `table_bits`-bit prefix; codes
+    /// longer than `table_bits` (or prefixes matching no code) hold 0.
+    table: Vec<u32>,
+    /// For each code length, one past its largest code, left-aligned to 16
+    /// bits; 0 for lengths with no codes.
+    limits: [u32; MAX_CODE_SIZE + 1],
+    /// For each code length, the index into `sorted` of its first code,
+    /// minus that code's value.
+    offsets: [i32; MAX_CODE_SIZE + 1],
+    /// Symbols ordered by (code length, symbol).
+    sorted: Vec<u16>,
+    /// Longest code length in use.
+    max_len: u32,
+}
+
+impl HuffmanModel {
+    /// Build the canonical code for per-symbol code lengths (0 = unused).
+    pub(super) fn new(sizes: &[u8]) -> DecodeResult<Self> {
+        let mut counts = [0u32; MAX_CODE_SIZE + 1];
+        for &size in sizes {
+            if usize::from(size) > MAX_CODE_SIZE {
+                return Err(invalid("huffman code length too long"));
+            }
+            counts[usize::from(size)] += 1;
+        }
+        counts[0] = 0;
+
+        let mut limits = [0u32; MAX_CODE_SIZE + 1];
+        let mut offsets = [0i32; MAX_CODE_SIZE + 1];
+        let mut first_codes = [0u32; MAX_CODE_SIZE + 1];
+        let mut starts = [0usize; MAX_CODE_SIZE + 1];
+        let mut code = 0u32;
+        let mut used = 0usize;
+        let mut min_len = 0;
+        let mut max_len = 0;
+        for len in 1..=MAX_CODE_SIZE {
+            let count = counts[len];
+            if count > 0 {
+                if code + count > 1 << len {
+                    return Err(invalid("huffman code is over-subscribed"));
+                }
+                if min_len == 0 {
+                    min_len = len as u32;
+                }
+                max_len = len as u32;
+                first_codes[len] = code;
+                starts[len] = used;
+                limits[len] = (code + count) << (16 - len);
+                offsets[len] = used as i32 - code as i32;
+            }
+            used += count as usize;
+            code = (code + count) << 1;
+        }
+        if used == 0 {
+            return Err(invalid("huffman model has no codes"));
+        }
+
+        let mut sorted = vec![0u16; used];
+        let mut next = starts;
+        for (symbol, &size) in sizes.iter().enumerate() {
+            if size != 0 {
+                sorted[next[usize::from(size)]] = symbol as u16;
+                next[usize::from(size)] += 1;
+            }
+        }
+
+        // Same table sizing as crunch: none for tiny alphabets, otherwise
+        // one bit more than needed to index every symbol, capped.
+        let table_bits = if sizes.len() > 16 {
+            (1 + (sizes.len() as u32).next_power_of_two().ilog2()).min(MAX_TABLE_BITS)
+        } else {
+            0
+        };
+        let table_bits = if table_bits <= min_len { 0 } else { table_bits };
+        let mut table = vec![0u32; if table_bits == 0 { 0 } else { 1 << table_bits }];
+        for len in 1..=table_bits as usize {
+            let fill = table_bits as usize - len;
+            for i in 0..counts[len] {
+                let code = first_codes[len] + i;
+                let symbol = u32::from(sorted[starts[len] + i as usize]);
+                let entry = symbol | (len as u32) << 16;
+                let start = (code as usize) << fill;
+                table[start..start + (1 << fill)].fill(entry);
+            }
+        }
+
+        Ok(Self {
+            symbol_count: sizes.len(),
+            table_bits,
+            table,
+            limits,
+            offsets,
+            sorted,
+            max_len,
+        })
+    }
+
+    /// Number of symbols in the alphabet.
+    pub(super) fn symbol_count(&self) -> usize {
+        self.symbol_count
+    }
+
+    /// The symbol and code length at the top of `bits` (left-aligned).
+    fn lookup(&self, bits: u64) -> Option<(u16, u32)> {
+        if self.table_bits != 0 {
+            let entry = self.table[(bits >> (64 - self.table_bits)) as usize];
+            if entry != 0 {
+                return Some((entry as u16, entry >> 16));
+            }
+        }
+        let top = (bits >> 48) as u32;
+        for len in (self.table_bits + 1)..=self.max_len {
+            let limit = self.limits[len as usize];
+            if top < limit {
+                let code = top >> (16 - len);
+                let index = self.offsets[len as usize] + code as i32;
+                return self.sorted.get(index as usize).map(|&symbol| (symbol, len));
+            }
+        }
+        None
+    }
+}
+
+fn invalid(detail: &str) -> DecodeError {
+    DecodeError::InvalidFormat {
+        context: "crn",
+        detail: detail.to_string(),
+    }
+}
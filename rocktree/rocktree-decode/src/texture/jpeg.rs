@@ -1,11 +1,18 @@
 //! JPEG texture decoding.
+//!
+//! The backend is picked at compile time:
+//!
+//! - `turbojpeg` feature (native only): libjpeg-turbo, which downscales in
+//!   the DCT domain, so reduced-scale decodes do proportionally less work.
+//! - `zune-jpeg` feature: zune-jpeg, decoding straight to RGBA.
+//! - Otherwise: the `image` crate.
+//!
+//! Backends without DCT-domain scaling decode in full and box-filter.
 
 use crate::{
     error::{DecodeError, DecodeResult},
-    texture::DecodedTexture,
+    texture::{DecodedTexture, TextureScale},
 };
-use image::ImageReader;
-use std::io::Cursor;
 
 /// Decode JPEG data to RGBA pixels.
 ///
@@ -21,6 +28,86 @@ use std::io::Cursor;
 ///
 /// Returns an error if JPEG decoding fails.
 pub fn decode_jpeg_to_rgba(data: &[u8]) -> DecodeResult<DecodedTexture> {
+    decode_jpeg_to_rgba_scaled(data, TextureScale::Full)
+}
+
+/// Decode JPEG data to RGBA pixels at a fraction of full resolution.
+///
+/// Dimensions are divided by the scale's divisor, rounding up.
+///
+/// # Errors
+///
+/// Returns an error if JPEG decoding fails.
+pub fn decode_jpeg_to_rgba_scaled(
+    data: &[u8],
+    scale: TextureScale,
+) -> DecodeResult<DecodedTexture> {
+    decode_scaled(data, scale)
+}
+
+#[cfg(all(feature = "turbojpeg", not(target_family = "wasm")))]
+fn decode_scaled(data: &[u8], scale: TextureScale) -> DecodeResult<DecodedTexture> {
+    use turbojpeg::{Decompressor, Image, PixelFormat, ScalingFactor};
+
+    let factor = match scale {
+        TextureScale::Full => ScalingFactor::ONE,
+        TextureScale::Half => ScalingFactor::ONE_HALF,
+        TextureScale::Quarter => ScalingFactor::ONE_QUARTER,
+    };
+    let mut decompressor = Decompressor::new().map_err(decode_error)?;
+    let header = decompressor.read_header(data).map_err(decode_error)?;
+    decompressor
+        .set_scaling_factor(factor)
+        .map_err(decode_error)?;
+
+    // libjpeg-turbo rounds scaled dimensions up, as `TextureScale` does.
+    let width = scale.apply(header.width as u32);
+    let height = scale.apply(header.height as u32);
+    let mut image = Image {
+        pixels: vec![0; width as usize * height as usize * 4],
+        width: width as usize,
+        pitch: width as usize * 4,
+        height: height as usize,
+        format: PixelFormat::RGBA,
+    };
+    decompressor
+        .decompress(data, image.as_deref_mut())
+        .map_err(decode_error)?;
+
+    Ok(DecodedTexture::new(image.pixels, width, height))
+}
+
+#[cfg(not(all(feature = "turbojpeg", not(target_family = "wasm"))))]
+fn decode_scaled(data: &[u8], scale: TextureScale) -> DecodeResult<DecodedTexture> {
+    Ok(decode_full(data)?.downscale(scale))
+}
+
+#[cfg(all(
+    feature = "zune-jpeg",
+    not(all(feature = "turbojpeg", not(target_family = "wasm")))
+))]
+fn decode_full(data: &[u8]) -> DecodeResult<DecodedTexture> {
+    use zune_core::{bytestream::ZCursor, colorspace::ColorSpace, options::DecoderOptions};
+    use zune_jpeg::JpegDecoder;
+
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+    let mut decoder = JpegDecoder::new_with_options(ZCursor::new(data), options);
+    let pixels = decoder.decode().map_err(decode_error)?;
+    let (width, height) = decoder
+        .dimensions()
+        .ok_or_else(|| decode_error("missing image dimensions"))?;
+
+    Ok(DecodedTexture::new(pixels, width as u32, height as u32))
+}
+
+#[cfg(not(any(
+    feature = "zune-jpeg",
+    all(feature = "turbojpeg", not(target_family = "wasm"))
+)))]
+fn decode_full(data: &[u8]) -> DecodeResult<DecodedTexture> {
+    use image::ImageReader;
+    use std::io::Cursor;
+
     let reader = ImageReader::new(Cursor::new(data))
         .with_guessed_format()
         .map_err(|e| DecodeError::InvalidFormat {
@@ -28,10 +115,7 @@ pub fn decode_jpeg_to_rgba(data: &[u8]) -> DecodeResult<DecodedTexture> {
             detail: format!("failed to detect image format: {e}"),
         })?;
 
-    let img = reader.decode().map_err(|e| DecodeError::InvalidFormat {
-        context: "jpeg",
-        detail: format!("failed to decode image: {e}"),
-    })?;
+    let img = reader.decode().map_err(decode_error)?;
 
     let rgba = img.to_rgba8();
     let width = rgba.width();
@@ -41,6 +125,13 @@ pub fn decode_jpeg_to_rgba(data: &[u8]) -> DecodeResult<DecodedTexture> {
     Ok(DecodedTexture::new(pixels, width, height))
 }
 
+fn decode_error(e: impl std::fmt::Display) -> DecodeError {
+    DecodeError::InvalidFormat {
+        context: "jpeg",
+        detail: format!("failed to decode image: {e}"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,6 +174,16 @@ mod tests {
         assert_eq!(texture.data.len(), 4); // 1x1 RGBA.
     }
 
+    #[test]
+    fn test_decode_jpeg_scaled_rounds_up() {
+        // A 1x1 image stays 1x1 at every scale.
+        for scale in [TextureScale::Half, TextureScale::Quarter] {
+            let texture = decode_jpeg_to_rgba_scaled(MINIMAL_JPEG, scale).unwrap();
+            assert_eq!((texture.width, texture.height), (1, 1));
+            assert!(texture.is_valid());
+        }
+    }
+
     #[test]
     fn test_decode_jpeg_invalid() {
         let invalid = [0x00, 0x01, 0x02, 0x03];
@@ -4,13 +4,19 @@
 //! - JPEG: Standard lossy image format
 //! - CRN-DXT1: Crunch-compressed DXT1 textures
 //!
-//! Both formats produce RGBA pixel data suitable for GPU upload.
+//! Both formats produce RGBA pixel data suitable for GPU upload. CRN
+//! textures can also be transcoded to BC1 blocks with [`decode_crn_to_bc1`],
+//! for GPUs that sample compressed textures directly.
 
+mod bc1;
 mod crn;
 mod jpeg;
 
-pub use crn::decode_crn_to_rgba;
-pub use jpeg::decode_jpeg_to_rgba;
+pub use bc1::decode_bc1_block;
+pub use crn::{
+    BC1_BLOCK_SIZE, Bc1Block, Bc1Texture, CrnDecoder, decode_crn_to_bc1, decode_crn_to_rgba,
+};
+pub use jpeg::{decode_jpeg_to_rgba, decode_jpeg_to_rgba_scaled};
 
 use crate::error::{DecodeError, DecodeResult};
 
@@ -23,6 +29,39 @@ pub enum TextureFormat {
     CrnDxt1,
 }
 
+/// Fraction of full resolution to decode a texture at.
+///
+/// Lower scales cut texture memory, and JPEG decode time where the backend
+/// supports downscaling while decoding (see [`decode_jpeg_to_rgba_scaled`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextureScale {
+    /// Full resolution.
+    #[default]
+    Full,
+    /// Half width and height.
+    Half,
+    /// Quarter width and height.
+    Quarter,
+}
+
+impl TextureScale {
+    /// The factor each dimension is divided by.
+    #[must_use]
+    pub const fn divisor(self) -> u32 {
+        match self {
+            TextureScale::Full => 1,
+            TextureScale::Half => 2,
+            TextureScale::Quarter => 4,
+        }
+    }
+
+    /// Scale one dimension, rounding up so nothing shrinks to zero.
+    #[must_use]
+    pub const fn apply(self, size: u32) -> u32 {
+        size.div_ceil(self.divisor())
+    }
+}
+
 /// Decoded texture data.
 #[derive(Debug, Clone)]
 pub struct DecodedTexture {
@@ -50,6 +89,40 @@ impl DecodedTexture {
     pub fn is_valid(&self) -> bool {
         self.data.len() == (self.width as usize) * (self.height as usize) * 4
     }
+
+    /// Downscale by box-filtering each `divisor`-square of pixels. Edge
+    /// boxes that overhang the texture average only the pixels they cover.
+    #[must_use]
+    pub fn downscale(self, scale: TextureScale) -> Self {
+        let divisor = scale.divisor() as usize;
+        if divisor == 1 {
+            return self;
+        }
+        let (width, height) = (self.width as usize, self.height as usize);
+        let out_width = scale.apply(self.width) as usize;
+        let out_height = scale.apply(self.height) as usize;
+
+        let mut data = Vec::with_capacity(out_width * out_height * 4);
+        for out_y in 0..out_height {
+            let rows = out_y * divisor..((out_y + 1) * divisor).min(height);
+            for out_x in 0..out_width {
+                let columns = out_x * divisor..((out_x + 1) * divisor).min(width);
+                let mut sum = [0u32; 4];
+                for y in rows.clone() {
+                    let row =
+                        &self.data[(y * width + columns.start) * 4..(y * width + columns.end) * 4];
+                    for pixel in row.chunks_exact(4) {
+                        for (total, &channel) in sum.iter_mut().zip(pixel) {
+                            *total += u32::from(channel);
+                        }
+                    }
+                }
+                let count = (rows.len() * columns.len()) as u32;
+                data.extend(sum.map(|total| ((total + count / 2) / count) as u8));
+            }
+        }
+        Self::new(data, out_width as u32, out_height as u32)
+    }
 }
 
 /// Decode a texture from compressed data.
@@ -69,9 +142,25 @@ impl DecodedTexture {
 ///
 /// Returns an error if decoding fails.
 pub fn decode_texture(data: &[u8], format: TextureFormat) -> DecodeResult<DecodedTexture> {
+    decode_texture_scaled(data, format, TextureScale::Full)
+}
+
+/// Decode a texture from compressed data at a fraction of full resolution.
+///
+/// JPEG textures are scaled by [`decode_jpeg_to_rgba_scaled`]; CRN textures
+/// are decoded in full and box-filtered.
+///
+/// # Errors
+///
+/// Returns an error if decoding fails.
+pub fn decode_texture_scaled(
+    data: &[u8],
+    format: TextureFormat,
+    scale: TextureScale,
+) -> DecodeResult<DecodedTexture> {
     match format {
-        TextureFormat::Jpeg => decode_jpeg_to_rgba(data),
-        TextureFormat::CrnDxt1 => decode_crn_to_rgba(data),
+        TextureFormat::Jpeg => decode_jpeg_to_rgba_scaled(data, scale),
+        TextureFormat::CrnDxt1 => Ok(decode_crn_to_rgba(data)?.downscale(scale)),
     }
 }
 
@@ -141,6 +230,29 @@ mod tests {
         assert!(matches!(result, Err(DecodeError::BufferTooSmall { .. })));
     }
 
+    #[test]
+    fn test_texture_scale_apply() {
+        assert_eq!(TextureScale::Full.apply(256), 256);
+        assert_eq!(TextureScale::Half.apply(255), 128);
+        assert_eq!(TextureScale::Quarter.apply(1), 1);
+    }
+
+    #[test]
+    fn test_downscale_averages_boxes() {
+        // 3x2: a 2x2 box of 0/100/200/100 and a 1x2 edge box of 10/30.
+        let gray = |v: u8| [v, v, v, 255];
+        let data = [gray(0), gray(100), gray(10), gray(200), gray(100), gray(30)].concat();
+        let texture = DecodedTexture::new(data, 3, 2).downscale(TextureScale::Half);
+        assert_eq!((texture.width, texture.height), (2, 1));
+        assert_eq!(texture.data, [gray(100), gray(20)].concat());
+    }
+
+    #[test]
+    fn test_downscale_full_is_identity() {
+        let texture = DecodedTexture::new(vec![7; 16], 2, 2).downscale(TextureScale::Full);
+        assert_eq!(texture.data, vec![7; 16]);
+    }
+
     #[test]
     fn test_decoded_texture_is_valid() {
         let texture = DecodedTexture::new(vec![0; 16], 2, 2);
@@ -20,7 +20,16 @@ use crate::error::{DecodeError, DecodeResult};
 /// # Errors
 ///
 /// Returns an error if the buffer ends before the varint is complete.
+#[inline]
 pub fn read_varint(data: &[u8], offset: &mut usize) -> DecodeResult<u32> {
+    // Fast path: most values (e.g. strip index deltas) fit in one byte.
+    if let Some(&byte) = data.get(*offset)
+        && byte & 0x80 == 0
+    {
+        *offset += 1;
+        return Ok(u32::from(byte));
+    }
+
     let mut result: u32 = 0;
     let mut shift: u32 = 0;
 
@@ -12,10 +12,49 @@ use crate::{
 ///
 /// Output: N vertices with x, y, z filled in (w, u, v are zeroed).
 ///
+/// On x86-64 whole runs of sixteen vertices are decoded with SSE2
+/// prefix sums, and the scalar loop finishes the tail; the result is the
+/// same as [`unpack_vertices_scalar`]'s.
+///
 /// # Errors
 ///
 /// Returns an error if the input length is not divisible by 3.
 pub fn unpack_vertices(packed: &[u8]) -> DecodeResult<Vec<Vertex>> {
+    let (xs, ys, zs) = split_planes(packed)?;
+    let mut vertices = vec![Vertex::default(); xs.len()];
+
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: SSE2 is part of the x86-64 baseline.
+    #[allow(unsafe_code)]
+    let (done, start) = unsafe { sse2::delta_decode(xs, ys, zs, &mut vertices) };
+    #[cfg(not(target_arch = "x86_64"))]
+    let (done, start) = (0, [0; 3]);
+
+    delta_decode(
+        &xs[done..],
+        &ys[done..],
+        &zs[done..],
+        &mut vertices[done..],
+        start,
+    );
+    Ok(vertices)
+}
+
+/// [`unpack_vertices`] without the SIMD path: the reference the SIMD path is
+/// tested against, and the baseline the benches compare it with.
+///
+/// # Errors
+///
+/// Returns an error if the input length is not divisible by 3.
+pub fn unpack_vertices_scalar(packed: &[u8]) -> DecodeResult<Vec<Vertex>> {
+    let (xs, ys, zs) = split_planes(packed)?;
+    let mut vertices = vec![Vertex::default(); xs.len()];
+    delta_decode(xs, ys, zs, &mut vertices, [0; 3]);
+    Ok(vertices)
+}
+
+/// Split the packed data into its X, Y and Z planes.
+fn split_planes(packed: &[u8]) -> DecodeResult<(&[u8], &[u8], &[u8])> {
     if !packed.len().is_multiple_of(3) {
         return Err(DecodeError::InvalidFormat {
             context: "vertices",
@@ -24,29 +63,140 @@ pub fn unpack_vertices(packed: &[u8]) -> DecodeResult<Vec<Vertex>> {
     }
 
     let count = packed.len() / 3;
-    let mut vertices = vec![Vertex::default(); count];
-
-    // Delta-decode each component plane.
-    // The data is arranged as [X0..Xn, Y0..Yn, Z0..Zn].
-    let mut x: u8 = 0;
-    let mut y: u8 = 0;
-    let mut z: u8 = 0;
-
-    for i in 0..count {
-        x = x.wrapping_add(packed[i]);
-        y = y.wrapping_add(packed[count + i]);
-        z = z.wrapping_add(packed[count * 2 + i]);
-
-        vertices[i].x = x;
-        vertices[i].y = y;
-        vertices[i].z = z;
+    let (xs, rest) = packed.split_at(count);
+    let (ys, zs) = rest.split_at(count);
+    Ok((xs, ys, zs))
+}
+
+/// Delta-decode the component planes into `vertices`, continuing from the
+/// running sums in `start`. Zipping the planes with the output keeps the
+/// loop free of bounds checks.
+fn delta_decode(xs: &[u8], ys: &[u8], zs: &[u8], vertices: &mut [Vertex], start: [u8; 3]) {
+    let [mut x, mut y, mut z] = start;
+
+    let planes = xs.iter().zip(ys).zip(zs);
+    for (vertex, ((&dx, &dy), &dz)) in vertices.iter_mut().zip(planes) {
+        x = x.wrapping_add(dx);
+        y = y.wrapping_add(dy);
+        z = z.wrapping_add(dz);
+
+        vertex.x = x;
+        vertex.y = y;
+        vertex.z = z;
     }
+}
 
-    Ok(vertices)
+/// SSE2 delta decoding, sixteen vertices at a time.
+///
+/// Each plane's sixteen deltas get an in-register prefix sum (four
+/// shift-and-add steps) plus the running sum carried over from the previous
+/// chunk, and the three planes are then interleaved with zeroes into whole
+/// 8-byte vertices, two per store.
+#[cfg(target_arch = "x86_64")]
+#[allow(unsafe_code)]
+mod sse2 {
+    use std::arch::x86_64::{
+        __m128i, _mm_add_epi8, _mm_cvtsi128_si32, _mm_loadu_si128, _mm_setzero_si128,
+        _mm_shuffle_epi32, _mm_shufflehi_epi16, _mm_slli_si128, _mm_storeu_si128,
+        _mm_unpackhi_epi8, _mm_unpackhi_epi16, _mm_unpackhi_epi32, _mm_unpacklo_epi8,
+        _mm_unpacklo_epi16, _mm_unpacklo_epi32,
+    };
+
+    use crate::Vertex;
+
+    /// Vertices decoded per chunk.
+    pub const LANES: usize = 16;
+
+    /// Decode every whole chunk of the planes into `vertices`, returning how
+    /// many vertices were decoded and the running sums after them.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support SSE2.
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn delta_decode(
+        xs: &[u8],
+        ys: &[u8],
+        zs: &[u8],
+        vertices: &mut [Vertex],
+    ) -> (usize, [u8; 3]) {
+        let count = vertices.len().min(xs.len()).min(ys.len()).min(zs.len());
+        let done = count - count % LANES;
+        let zero = _mm_setzero_si128();
+        let (mut x_sum, mut y_sum, mut z_sum) = (zero, zero, zero);
+
+        for i in (0..done).step_by(LANES) {
+            // SAFETY: `i + LANES <= done <= count`, and every plane holds at
+            // least `count` bytes.
+            let (x, y, z) = unsafe {
+                (
+                    _mm_loadu_si128(xs.as_ptr().add(i).cast()),
+                    _mm_loadu_si128(ys.as_ptr().add(i).cast()),
+                    _mm_loadu_si128(zs.as_ptr().add(i).cast()),
+                )
+            };
+            let x = prefix_sum(x, x_sum);
+            let y = prefix_sum(y, y_sum);
+            let z = prefix_sum(z, z_sum);
+            x_sum = broadcast_last(x);
+            y_sum = broadcast_last(y);
+            z_sum = broadcast_last(z);
+
+            // Bytes to (x, y) pairs and (z, 0) pairs, pairs to (x, y, z, 0)
+            // quads, and each quad padded with the zeroed u and v.
+            let xy_low = _mm_unpacklo_epi8(x, y);
+            let xy_high = _mm_unpackhi_epi8(x, y);
+            let z_low = _mm_unpacklo_epi8(z, zero);
+            let z_high = _mm_unpackhi_epi8(z, zero);
+            let quads = [
+                _mm_unpacklo_epi16(xy_low, z_low),
+                _mm_unpackhi_epi16(xy_low, z_low),
+                _mm_unpacklo_epi16(xy_high, z_high),
+                _mm_unpackhi_epi16(xy_high, z_high),
+            ];
+            // SAFETY: `Vertex` is 8 bytes with no padding and valid for any
+            // bits, so the chunk's LANES vertices are 8 whole 16-byte stores,
+            // all in bounds as above.
+            let out = unsafe { vertices.as_mut_ptr().add(i) }.cast::<__m128i>();
+            for (k, quad) in quads.into_iter().enumerate() {
+                // SAFETY: as above.
+                unsafe {
+                    _mm_storeu_si128(out.add(2 * k), _mm_unpacklo_epi32(quad, zero));
+                    _mm_storeu_si128(out.add(2 * k + 1), _mm_unpackhi_epi32(quad, zero));
+                }
+            }
+        }
+
+        let last = |sum: __m128i| _mm_cvtsi128_si32(sum).to_le_bytes()[0];
+        (done, [last(x_sum), last(y_sum), last(z_sum)])
+    }
+
+    /// Inclusive prefix sum of the sixteen bytes of `deltas`, plus `carry`
+    /// (a running sum broadcast to every byte).
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn prefix_sum(deltas: __m128i, carry: __m128i) -> __m128i {
+        let sums = _mm_add_epi8(deltas, _mm_slli_si128::<1>(deltas));
+        let sums = _mm_add_epi8(sums, _mm_slli_si128::<2>(sums));
+        let sums = _mm_add_epi8(sums, _mm_slli_si128::<4>(sums));
+        let sums = _mm_add_epi8(sums, _mm_slli_si128::<8>(sums));
+        _mm_add_epi8(sums, carry)
+    }
+
+    /// The last byte of `sums`, broadcast to every byte.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn broadcast_last(sums: __m128i) -> __m128i {
+        let high = _mm_unpackhi_epi8(sums, sums);
+        let high = _mm_shufflehi_epi16::<0xFF>(high);
+        _mm_shuffle_epi32::<0xFF>(high)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -92,6 +242,34 @@ mod tests {
         assert_eq!(result[1].x, 4); // 250 + 10 = 260, wraps to 4
     }
 
+    #[test]
+    fn simd_path_matches_scalar_around_chunk_boundaries() {
+        for count in [15, 16, 17, 31, 32, 33, 1_000, 65_535] {
+            // Large deltas, so every plane wraps many times.
+            let packed: Vec<u8> = (0..count * 3).map(|i| (i * 131 + 7) as u8).collect();
+            assert_eq!(
+                unpack_vertices(&packed).unwrap(),
+                unpack_vertices_scalar(&packed).unwrap(),
+                "{count} vertices"
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn simd_path_matches_scalar(
+            planes in proptest::collection::vec(any::<[u8; 3]>(), 0..300),
+        ) {
+            let packed: Vec<u8> = (0..3)
+                .flat_map(|plane| planes.iter().map(move |vertex| vertex[plane]))
+                .collect();
+            prop_assert_eq!(
+                unpack_vertices(&packed).unwrap(),
+                unpack_vertices_scalar(&packed).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_unpack_vertices_invalid_length() {
         // Length not divisible by 3
@@ -0,0 +1,251 @@
+//! Token-bucket bandwidth limiting for network fetches.
+//!
+//! Every [`Client`](crate::Client) owns a [`BandwidthLimiter`]. Network
+//! fetches wait in [`BandwidthLimiter::acquire`] while the bucket is empty
+//! and debit the bytes they download once they complete; cache hits are
+//! free. Response sizes aren't known up front, so the bucket can dip below
+//! zero by however much the requests already in flight bring back, and new
+//! requests then wait until it has refilled.
+//!
+//! The limiter keeps no clock of its own, to stay runtime-agnostic: the host
+//! calls [`BandwidthLimiter::tick`] with the elapsed time, which refills the
+//! bucket and wakes waiting requests. It's unlimited until
+//! [`BandwidthLimiter::set_limit`] is called, so hosts that never tick are
+//! unaffected.
+
+use std::{
+    sync::{Mutex, PoisonError},
+    task::{Poll, Waker},
+};
+
+/// Window the download rate is averaged over (s).
+const RATE_WINDOW_SECS: f64 = 1.0;
+
+/// Token bucket shared by a client's network fetches.
+#[derive(Debug, Default)]
+pub struct BandwidthLimiter {
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    /// Refill rate (bytes/s), or `None` for unlimited.
+    limit: Option<u64>,
+    /// Bytes that may still be fetched; negative after a burst.
+    tokens: f64,
+    /// Tasks waiting for tokens, one waker each however often they poll.
+    waiters: Vec<Waker>,
+    /// Bytes downloaded since the limiter was created.
+    total: u64,
+    /// Bytes downloaded in the current rate window.
+    window_bytes: u64,
+    /// Time elapsed in the current rate window (s).
+    window_secs: f64,
+    /// Download rate over the last complete window (bytes/s).
+    rate: f64,
+}
+
+impl BandwidthLimiter {
+    /// Create an unlimited limiter.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Cap downloads at `bytes_per_sec`, or lift the cap with `None`. The
+    /// bucket holds one second's worth, and starts full, so waiting requests
+    /// are released either way.
+    pub fn set_limit(&self, bytes_per_sec: Option<u64>) {
+        let mut state = self.state();
+        if state.limit == bytes_per_sec {
+            return;
+        }
+        state.limit = bytes_per_sec;
+        if let Some(limit) = bytes_per_sec {
+            state.tokens = limit as f64;
+        }
+        state.wake_if_open();
+    }
+
+    /// The current cap (bytes/s), if any.
+    #[must_use]
+    pub fn limit(&self) -> Option<u64> {
+        self.state().limit
+    }
+
+    /// Advance the limiter by `elapsed_secs`: refill the bucket, wake
+    /// requests waiting for it, and update the download rate.
+    pub fn tick(&self, elapsed_secs: f64) {
+        let mut state = self.state();
+        if let Some(limit) = state.limit {
+            let burst = limit as f64;
+            state.tokens = (state.tokens + limit as f64 * elapsed_secs).min(burst);
+            state.wake_if_open();
+        }
+        state.window_secs += elapsed_secs;
+        if state.window_secs >= RATE_WINDOW_SECS {
+            state.rate = state.window_bytes as f64 / state.window_secs;
+            state.window_bytes = 0;
+            state.window_secs = 0.0;
+        }
+    }
+
+    /// Debit `bytes` downloaded from the network.
+    pub fn record(&self, bytes: usize) {
+        let mut state = self.state();
+        if state.limit.is_some() {
+            state.tokens -= bytes as f64;
+        }
+        state.total += bytes as u64;
+        state.window_bytes += bytes as u64;
+    }
+
+    /// Wait until a request may go out.
+    pub fn acquire(&self) -> impl Future<Output = ()> + '_ {
+        std::future::poll_fn(|cx| {
+            let mut state = self.state();
+            if state.is_open() {
+                Poll::Ready(())
+            } else {
+                // A task re-polled before the bucket refilled is already
+                // queued; don't add its waker again.
+                if !state
+                    .waiters
+                    .iter()
+                    .any(|waker| waker.will_wake(cx.waker()))
+                {
+                    state.waiters.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Whether requests are currently waiting for the bucket to refill.
+    #[must_use]
+    pub fn is_throttling(&self) -> bool {
+        !self.state().is_open()
+    }
+
+    /// Bytes downloaded since the limiter was created.
+    #[must_use]
+    pub fn total_bytes(&self) -> u64 {
+        self.state().total
+    }
+
+    /// Download rate over the last second (bytes/s).
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        self.state().rate
+    }
+}
+
+impl LimiterState {
+    /// Whether a request may go out now.
+    fn is_open(&self) -> bool {
+        self.limit.is_none() || self.tokens > 0.0
+    }
+
+    /// Wake every waiting request if the bucket has tokens.
+    fn wake_if_open(&mut self) {
+        if self.is_open() {
+            self.waiters.drain(..).for_each(Waker::wake);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::pin,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        task::{Context, Wake},
+    };
+
+    use super::*;
+
+    fn poll_acquire(limiter: &BandwidthLimiter) -> bool {
+        let mut cx = Context::from_waker(Waker::noop());
+        pin!(limiter.acquire()).poll(&mut cx).is_ready()
+    }
+
+    /// Counts how often it's woken.
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn unlimited_never_waits() {
+        let limiter = BandwidthLimiter::new();
+        limiter.record(10_000_000);
+        assert!(poll_acquire(&limiter));
+        assert_eq!(limiter.total_bytes(), 10_000_000);
+    }
+
+    #[test]
+    fn waits_for_the_bucket_to_refill() {
+        let limiter = BandwidthLimiter::new();
+        limiter.set_limit(Some(1000));
+        assert!(poll_acquire(&limiter));
+
+        // A burst overdraws the bucket by 1500 bytes.
+        limiter.record(2500);
+        assert!(!poll_acquire(&limiter));
+        assert!(limiter.is_throttling());
+
+        limiter.tick(1.0);
+        assert!(!poll_acquire(&limiter));
+        limiter.tick(0.6);
+        assert!(poll_acquire(&limiter));
+
+        // Lifting the cap releases waiters straight away.
+        limiter.record(5000);
+        limiter.set_limit(None);
+        assert!(poll_acquire(&limiter));
+    }
+
+    #[test]
+    fn repolls_queue_one_waker_and_a_new_limit_wakes_it() {
+        let limiter = BandwidthLimiter::new();
+        limiter.set_limit(Some(1000));
+        limiter.record(2000);
+
+        let count = Arc::new(CountingWaker::default());
+        let waker = Waker::from(count.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut acquire = pin!(limiter.acquire());
+        for _ in 0..3 {
+            assert!(acquire.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(limiter.state().waiters.len(), 1);
+
+        // Raising the cap refills the bucket, which must release the waiter
+        // without waiting for the next tick.
+        limiter.set_limit(Some(4000));
+        assert_eq!(count.0.load(Ordering::Relaxed), 1);
+        assert!(limiter.state().waiters.is_empty());
+        assert!(acquire.as_mut().poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn measures_the_rate_per_window() {
+        let limiter = BandwidthLimiter::new();
+        limiter.record(3000);
+        limiter.tick(0.5);
+        assert_eq!(limiter.rate(), 0.0);
+        limiter.tick(0.5);
+        assert_eq!(limiter.rate(), 3000.0);
+    }
+}
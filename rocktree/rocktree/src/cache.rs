@@ -8,6 +8,16 @@
 //! - [`MemoryCache`]: In-memory cache with optional size limits
 //! - [`FilesystemCache`]: Disk-based cache (native only)
 //! - [`NoCache`]: Passthrough implementation that caches nothing
+//!
+//! # Revalidation
+//!
+//! Caches may also keep the HTTP [`Validators`] (`ETag`, `Last-Modified`)
+//! each response came with. When the client's [`CacheControl`] policy says
+//! an entry is stale, it sends a conditional request with them and, on
+//! `304 Not Modified`, keeps serving the cached bytes without downloading
+//! them again. [`MemoryCache`] and [`FilesystemCache`] store validators;
+//! other caches can leave the default methods, which store none, and should
+//! be used with [`CacheControl::Immutable`].
 
 #[cfg(not(target_family = "wasm"))]
 use crate::error::Error;
@@ -17,6 +27,7 @@ use std::{
     future::Future,
     pin::Pin,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 /// Future type for cache get operations.
@@ -28,6 +39,101 @@ pub type CacheFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>
 /// Future type for cache contains operations.
 pub type ContainsFuture<'a> = Pin<Box<dyn Future<Output = Result<bool>> + Send + 'a>>;
 
+/// Future type for cache validator lookups.
+pub type ValidatorsFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<Validators>>> + Send + 'a>>;
+
+/// HTTP validators stored with a cached response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// The response's `ETag` header.
+    pub etag: Option<String>,
+    /// The response's `Last-Modified` header.
+    pub last_modified: Option<String>,
+    /// When the entry was last fetched or revalidated, in seconds since the
+    /// Unix epoch.
+    pub validated_at: u64,
+}
+
+impl Validators {
+    /// Whether there is anything to make a conditional request with.
+    #[must_use]
+    pub fn is_conditional(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// When the client revalidates cached responses.
+///
+/// Tile URLs carry their epoch, so a newer epoch is a different URL and
+/// tiles never go stale. Only the planetoid metadata, which names the
+/// current root epoch, lives at a fixed URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheControl {
+    /// Serve cached entries as they are, forever.
+    #[default]
+    Immutable,
+    /// Revalidate the planetoid metadata once it is older than `max_age`;
+    /// epoch-versioned tiles are served as they are.
+    RevalidateUnversioned {
+        /// How long a response stays fresh.
+        max_age: Duration,
+    },
+    /// Revalidate every entry once it is older than `max_age`.
+    Revalidate {
+        /// How long a response stays fresh.
+        max_age: Duration,
+    },
+}
+
+impl CacheControl {
+    /// How long an entry stays fresh, or `None` if it never goes stale.
+    /// `versioned` is whether its URL carries an epoch.
+    #[must_use]
+    pub fn max_age(self, versioned: bool) -> Option<Duration> {
+        match self {
+            CacheControl::Immutable => None,
+            CacheControl::RevalidateUnversioned { max_age } => (!versioned).then_some(max_age),
+            CacheControl::Revalidate { max_age } => Some(max_age),
+        }
+    }
+
+    /// Whether an entry with `validators` must be revalidated at `now`
+    /// (seconds since the Unix epoch). Entries without validators have
+    /// unknown age and count as stale; without a clock (`now` is `None`)
+    /// nothing does.
+    #[must_use]
+    pub fn is_stale(
+        self,
+        versioned: bool,
+        validators: Option<&Validators>,
+        now: Option<u64>,
+    ) -> bool {
+        let (Some(max_age), Some(now)) = (self.max_age(versioned), now) else {
+            return false;
+        };
+        validators.is_none_or(|v| now.saturating_sub(v.validated_at) >= max_age.as_secs())
+    }
+}
+
+/// The current time in seconds since the Unix epoch. `None` on the web,
+/// where `SystemTime` is unavailable; the browser's own HTTP cache
+/// revalidates there instead.
+#[must_use]
+pub(crate) fn unix_now() -> Option<u64> {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs())
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        None
+    }
+}
+
 /// A cache for storing fetched data.
 ///
 /// The cache is keyed by URL and stores raw bytes. Implementations may
@@ -53,6 +159,17 @@ pub trait Cache: Send + Sync {
 
     /// Clear all cached data.
     fn clear(&self) -> CacheFuture<'_>;
+
+    /// Get the validators stored with an entry. The default stores none.
+    fn get_validators(&self, _url: &str) -> ValidatorsFuture<'_> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Store validators for an entry, replacing any earlier ones. The
+    /// default discards them.
+    fn put_validators(&self, _url: &str, _validators: Validators) -> CacheFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
 }
 
 /// A cache that stores nothing (passthrough).
@@ -107,6 +224,8 @@ pub struct MemoryCache {
 #[derive(Debug, Default)]
 struct MemoryCacheInner {
     entries: HashMap<String, Vec<u8>>,
+    /// Validators for entries that have them.
+    validators: HashMap<String, Validators>,
     /// Insertion order for LRU eviction.
     order: Vec<String>,
     current_size: usize,
@@ -191,6 +310,7 @@ impl Cache for MemoryCache {
                 if let Some(old_data) = cache.entries.remove(&oldest) {
                     cache.current_size -= old_data.len();
                 }
+                cache.validators.remove(&oldest);
             }
         }
 
@@ -213,16 +333,33 @@ impl Cache for MemoryCache {
             cache.current_size -= data.len();
             cache.order.retain(|k| k != url);
         }
+        cache.validators.remove(url);
         Box::pin(async { Ok(()) })
     }
 
     fn clear(&self) -> CacheFuture<'_> {
         let mut cache = self.data.write().unwrap();
         cache.entries.clear();
+        cache.validators.clear();
         cache.order.clear();
         cache.current_size = 0;
         Box::pin(async { Ok(()) })
     }
+
+    fn get_validators(&self, url: &str) -> ValidatorsFuture<'_> {
+        let data = self.data.read().unwrap();
+        let result = data.validators.get(url).cloned();
+        Box::pin(async move { Ok(result) })
+    }
+
+    fn put_validators(&self, url: &str, validators: Validators) -> CacheFuture<'_> {
+        let mut cache = self.data.write().unwrap();
+        // Validators only make sense alongside their entry.
+        if cache.entries.contains_key(url) {
+            cache.validators.insert(url.to_string(), validators);
+        }
+        Box::pin(async { Ok(()) })
+    }
 }
 
 /// A disk-backed cache storing one file per URL (native only).
@@ -233,7 +370,9 @@ impl Cache for MemoryCache {
 /// (temp file + rename), so a crash mid-write never leaves a torn entry.
 ///
 /// No TTL: rocktree data is epoch-versioned and the epoch is part of the URL,
-/// so a superseded entry is simply never requested again. The cache shares the
+/// so a superseded entry is simply never requested again. Validators live in
+/// a `.meta` sidecar next to the entry, as text lines: the URL, then
+/// `validated <secs>`, `etag <value>`, and `last-modified <value>`. The cache shares the
 /// `<cache dir>/veldera` root with the rest of the project (see
 /// [`FilesystemCache::veldera`]) but keeps its own `rocktree` subdirectory and
 /// its own type — nothing is shared with other caches but the root path.
@@ -269,6 +408,11 @@ impl FilesystemCache {
         self.dir.join(format!("{:016x}", fnv1a(url)))
     }
 
+    /// The on-disk path for a URL's validators.
+    fn meta_path_for(&self, url: &str) -> std::path::PathBuf {
+        self.path_for(url).with_extension("meta")
+    }
+
     /// Read the entry at `path`, returning its data only if the stored URL
     /// matches `url` (guarding against the rare filename-hash collision).
     fn read_verified(path: &std::path::Path, url: &str) -> Result<Option<Vec<u8>>> {
@@ -313,28 +457,85 @@ impl Cache for FilesystemCache {
     }
 
     fn remove(&self, url: &str) -> CacheFuture<'_> {
-        let result = match std::fs::remove_file(self.path_for(url)) {
+        let result = [self.path_for(url), self.meta_path_for(url)]
+            .iter()
+            .try_for_each(|path| match std::fs::remove_file(path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(Error::Cache {
+                    operation: "remove",
+                    message: e.to_string(),
+                }),
+            });
+        Box::pin(async move { result })
+    }
+
+    fn clear(&self) -> CacheFuture<'_> {
+        let result = match std::fs::remove_dir_all(&self.dir) {
             Ok(()) => Ok(()),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
             Err(e) => Err(Error::Cache {
-                operation: "remove",
+                operation: "clear",
                 message: e.to_string(),
             }),
         };
         Box::pin(async move { result })
     }
 
-    fn clear(&self) -> CacheFuture<'_> {
-        let result = match std::fs::remove_dir_all(&self.dir) {
-            Ok(()) => Ok(()),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    fn get_validators(&self, url: &str) -> ValidatorsFuture<'_> {
+        let result = match std::fs::read_to_string(self.meta_path_for(url)) {
+            Ok(text) => Ok(parse_validators(&text, url)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
             Err(e) => Err(Error::Cache {
-                operation: "clear",
+                operation: "read validators",
                 message: e.to_string(),
             }),
         };
         Box::pin(async move { result })
     }
+
+    fn put_validators(&self, url: &str, validators: Validators) -> CacheFuture<'_> {
+        let result = write_file(
+            &self.dir,
+            &self.meta_path_for(url),
+            format_validators(url, &validators).as_bytes(),
+        );
+        Box::pin(async move { result })
+    }
+}
+
+/// Serialize a `.meta` sidecar. Header values can't contain newlines, so
+/// one line per field is unambiguous.
+#[cfg(not(target_family = "wasm"))]
+fn format_validators(url: &str, validators: &Validators) -> String {
+    let mut text = format!("{url}\nvalidated {}\n", validators.validated_at);
+    if let Some(etag) = &validators.etag {
+        text.push_str(&format!("etag {etag}\n"));
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        text.push_str(&format!("last-modified {last_modified}\n"));
+    }
+    text
+}
+
+/// Parse a `.meta` sidecar, or `None` if it's malformed or belongs to
+/// another URL.
+#[cfg(not(target_family = "wasm"))]
+fn parse_validators(text: &str, url: &str) -> Option<Validators> {
+    let mut lines = text.lines();
+    if lines.next()? != url {
+        return None;
+    }
+    let mut validators = Validators::default();
+    for line in lines {
+        match line.split_once(' ')? {
+            ("validated", secs) => validators.validated_at = secs.parse().ok()?,
+            ("etag", etag) => validators.etag = Some(etag.to_string()),
+            ("last-modified", date) => validators.last_modified = Some(date.to_string()),
+            _ => {}
+        }
+    }
+    Some(validators)
 }
 
 /// Split a stored entry into its URL and data halves, or `None` if the buffer
@@ -349,8 +550,7 @@ fn split_entry(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
     Some(rest.split_at(url_len))
 }
 
-/// Write a URL/data entry to `path` atomically (temp file + rename), creating
-/// `dir` if needed.
+/// Write a URL/data entry to `path` atomically, creating `dir` if needed.
 #[cfg(not(target_family = "wasm"))]
 fn write_entry(
     dir: &std::path::Path,
@@ -358,6 +558,17 @@ fn write_entry(
     url: &str,
     data: &[u8],
 ) -> Result<()> {
+    let mut entry = Vec::with_capacity(4 + url.len() + data.len());
+    entry.extend_from_slice(&(url.len() as u32).to_le_bytes());
+    entry.extend_from_slice(url.as_bytes());
+    entry.extend_from_slice(data);
+    write_file(dir, path, &entry)
+}
+
+/// Write `contents` to `path` atomically (temp file + rename), creating `dir`
+/// if needed.
+#[cfg(not(target_family = "wasm"))]
+fn write_file(dir: &std::path::Path, path: &std::path::Path, contents: &[u8]) -> Result<()> {
     use std::{
         io::Write,
         sync::atomic::{AtomicU64, Ordering},
@@ -376,9 +587,7 @@ fn write_entry(
     ));
     let write = || -> std::io::Result<()> {
         let mut file = std::fs::File::create(&tmp)?;
-        file.write_all(&(url.len() as u32).to_le_bytes())?;
-        file.write_all(url.as_bytes())?;
-        file.write_all(data)?;
+        file.write_all(contents)?;
         file.sync_all()?;
         std::fs::rename(&tmp, path)
     };
@@ -554,6 +763,91 @@ mod tests {
         assert!(!dir.exists());
     }
 
+    #[test]
+    fn test_memory_cache_validators() {
+        let cache = MemoryCache::with_max_size(4);
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            validated_at: 100,
+        };
+
+        // Validators without an entry are dropped.
+        block_on(cache.put_validators("http://a", validators.clone())).unwrap();
+        assert_eq!(block_on(cache.get_validators("http://a")).unwrap(), None);
+
+        block_on(cache.put("http://a", vec![1, 2, 3])).unwrap();
+        block_on(cache.put_validators("http://a", validators.clone())).unwrap();
+        assert_eq!(
+            block_on(cache.get_validators("http://a")).unwrap(),
+            Some(validators)
+        );
+
+        // Evicting the entry evicts its validators too.
+        block_on(cache.put("http://b", vec![4, 5])).unwrap();
+        assert_eq!(block_on(cache.get_validators("http://a")).unwrap(), None);
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn test_filesystem_cache_validators() {
+        let dir = std::env::temp_dir().join(format!(
+            "veldera_fscache_validators_test_{}",
+            std::process::id()
+        ));
+        let cache = FilesystemCache::new(&dir);
+        let validators = Validators {
+            etag: Some("W/\"a b\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            validated_at: 1_700_000_000,
+        };
+
+        block_on(cache.put("https://x/a", vec![1])).unwrap();
+        assert_eq!(block_on(cache.get_validators("https://x/a")).unwrap(), None);
+        block_on(cache.put_validators("https://x/a", validators.clone())).unwrap();
+        assert_eq!(
+            block_on(cache.get_validators("https://x/a")).unwrap(),
+            Some(validators.clone())
+        );
+
+        // A sidecar written for another URL is ignored.
+        assert_eq!(
+            parse_validators(
+                &format_validators("https://x/b", &validators),
+                "https://x/a"
+            ),
+            None
+        );
+
+        block_on(cache.remove("https://x/a")).unwrap();
+        assert_eq!(block_on(cache.get_validators("https://x/a")).unwrap(), None);
+        block_on(cache.clear()).unwrap();
+    }
+
+    #[test]
+    fn test_cache_control_staleness() {
+        let hour = Duration::from_secs(3600);
+        let validated = Validators {
+            validated_at: 1000,
+            ..Validators::default()
+        };
+        let fresh = Some(1000 + 3599);
+        let stale = Some(1000 + 3600);
+
+        assert!(!CacheControl::Immutable.is_stale(false, None, stale));
+
+        let unversioned = CacheControl::RevalidateUnversioned { max_age: hour };
+        assert!(!unversioned.is_stale(true, Some(&validated), stale));
+        assert!(!unversioned.is_stale(false, Some(&validated), fresh));
+        assert!(unversioned.is_stale(false, Some(&validated), stale));
+
+        let all = CacheControl::Revalidate { max_age: hour };
+        assert!(all.is_stale(true, Some(&validated), stale));
+        // Unknown age is stale, unless there is no clock at all.
+        assert!(all.is_stale(true, None, fresh));
+        assert!(!all.is_stale(true, None, None));
+    }
+
     #[test]
     fn test_memory_cache_update() {
         let cache = MemoryCache::new();
@@ -3,18 +3,29 @@
 //! This module provides the main `Client` type for downloading planetoid metadata,
 //! bulk metadata, and node data from Google Earth's servers.
 
+#[cfg(not(target_family = "wasm"))]
+use crate::session::{ResponseSource, Session};
 use crate::{
-    cache::{Cache, NoCache},
+    bandwidth::BandwidthLimiter,
+    cache::{Cache, CacheControl, NoCache, Validators, unix_now},
     error::{Error, Result},
+    texture_cache::{DecodedTexture, DecodedTextureCache, TextureKey},
     types::{
         BulkMetadata, BulkRequest, Mesh, Node, NodeMetadata, NodeRequest, Planetoid, TextureFormat,
     },
 };
 use glam::{DMat4, Vec3};
 use prost::Message;
-use rocktree_decode::{OctreePath, OrientedBoundingBox};
+use reqwest::{
+    StatusCode,
+    header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use rocktree_decode::{OctreePath, OrientedBoundingBox, texture::TextureScale};
 use rocktree_proto as proto;
 use std::sync::Arc;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Instant;
+use tracing::Instrument;
 
 /// Base URL for Google Earth's rocktree API.
 const BASE_URL: &str = "https://kh.google.com/rt/earth/";
@@ -29,11 +40,24 @@ const BASE_URL: &str = "https://kh.google.com/rt/earth/";
 /// ```ignore
 /// let client = Client::new();
 /// let planetoid = client.fetch_planetoid().await?;
+///
+/// // Or, with a cache that revalidates the planetoid metadata daily:
+/// let client = Client::builder()
+///     .cache(MemoryCache::new())
+///     .cache_control(CacheControl::RevalidateUnversioned {
+///         max_age: Duration::from_secs(24 * 60 * 60),
+///     })
+///     .build();
 /// ```
 pub struct Client<C: Cache = NoCache> {
     http: reqwest::Client,
     cache: Arc<C>,
+    cache_control: CacheControl,
     base_url: String,
+    texture_cache: Option<Arc<DecodedTextureCache>>,
+    bandwidth: BandwidthLimiter,
+    #[cfg(not(target_family = "wasm"))]
+    session: Option<Arc<Session>>,
 }
 
 impl Client<NoCache> {
@@ -43,7 +67,12 @@ impl Client<NoCache> {
         Self {
             http: reqwest::Client::new(),
             cache: Arc::new(NoCache),
+            cache_control: CacheControl::Immutable,
             base_url: BASE_URL.to_string(),
+            texture_cache: None,
+            bandwidth: BandwidthLimiter::new(),
+            #[cfg(not(target_family = "wasm"))]
+            session: None,
         }
     }
 }
@@ -54,6 +83,110 @@ impl Default for Client<NoCache> {
     }
 }
 
+impl Client<NoCache> {
+    /// Start building a client; see [`ClientBuilder`].
+    #[must_use]
+    pub fn builder() -> ClientBuilder<NoCache> {
+        ClientBuilder::new()
+    }
+}
+
+/// Builder for a [`Client`] with a cache, cache policy, and the other
+/// optional parts configured up front.
+#[must_use]
+pub struct ClientBuilder<C: Cache = NoCache> {
+    http: Option<reqwest::Client>,
+    cache: C,
+    cache_control: CacheControl,
+    base_url: String,
+    texture_cache: Option<DecodedTextureCache>,
+    #[cfg(not(target_family = "wasm"))]
+    session: Option<Session>,
+}
+
+impl ClientBuilder<NoCache> {
+    /// A builder for a client with no caching.
+    pub fn new() -> Self {
+        Self {
+            http: None,
+            cache: NoCache,
+            cache_control: CacheControl::Immutable,
+            base_url: BASE_URL.to_string(),
+            texture_cache: None,
+            #[cfg(not(target_family = "wasm"))]
+            session: None,
+        }
+    }
+}
+
+impl Default for ClientBuilder<NoCache> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Cache> ClientBuilder<C> {
+    /// Store responses in `cache`.
+    pub fn cache<D: Cache>(self, cache: D) -> ClientBuilder<D> {
+        ClientBuilder {
+            http: self.http,
+            cache,
+            cache_control: self.cache_control,
+            base_url: self.base_url,
+            texture_cache: self.texture_cache,
+            #[cfg(not(target_family = "wasm"))]
+            session: self.session,
+        }
+    }
+
+    /// When to revalidate cached responses. Defaults to
+    /// [`CacheControl::Immutable`].
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.cache_control = cache_control;
+        self
+    }
+
+    /// Send requests through `http` instead of a default client.
+    pub fn http(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Fetch from `base_url` instead of Google Earth's servers.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Keep decoded node textures in `cache`; see
+    /// [`Client::with_texture_cache`].
+    pub fn texture_cache(mut self, cache: DecodedTextureCache) -> Self {
+        self.texture_cache = Some(cache);
+        self
+    }
+
+    /// Capture to or replay from `session`; see [`Client::with_session`].
+    #[cfg(not(target_family = "wasm"))]
+    pub fn session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Build the client.
+    pub fn build(self) -> Client<C> {
+        Client {
+            http: self.http.unwrap_or_default(),
+            cache: Arc::new(self.cache),
+            cache_control: self.cache_control,
+            base_url: self.base_url,
+            texture_cache: self.texture_cache.map(Arc::new),
+            bandwidth: BandwidthLimiter::new(),
+            #[cfg(not(target_family = "wasm"))]
+            session: self.session.map(Arc::new),
+        }
+    }
+}
+
 impl<C: Cache> Client<C> {
     /// Create a new client with a custom cache.
     #[must_use]
@@ -61,7 +194,12 @@ impl<C: Cache> Client<C> {
         Self {
             http: reqwest::Client::new(),
             cache: Arc::new(cache),
+            cache_control: CacheControl::Immutable,
             base_url: BASE_URL.to_string(),
+            texture_cache: None,
+            bandwidth: BandwidthLimiter::new(),
+            #[cfg(not(target_family = "wasm"))]
+            session: None,
         }
     }
 
@@ -71,7 +209,12 @@ impl<C: Cache> Client<C> {
         Self {
             http,
             cache: Arc::new(cache),
+            cache_control: CacheControl::Immutable,
             base_url: BASE_URL.to_string(),
+            texture_cache: None,
+            bandwidth: BandwidthLimiter::new(),
+            #[cfg(not(target_family = "wasm"))]
+            session: None,
         }
     }
 
@@ -82,6 +225,56 @@ impl<C: Cache> Client<C> {
         self
     }
 
+    /// Keep decoded node textures in `cache`, so nodes fetched again skip
+    /// texture decoding.
+    #[must_use]
+    pub fn with_texture_cache(mut self, cache: DecodedTextureCache) -> Self {
+        self.texture_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// When cached responses are revalidated.
+    #[must_use]
+    pub fn cache_control(&self) -> CacheControl {
+        self.cache_control
+    }
+
+    /// The decoded-texture cache, if one was configured.
+    #[must_use]
+    pub fn texture_cache(&self) -> Option<&DecodedTextureCache> {
+        self.texture_cache.as_deref()
+    }
+
+    /// The limiter every network fetch goes through. Unlimited until the
+    /// host sets a cap (see [`BandwidthLimiter`]).
+    #[must_use]
+    pub fn bandwidth(&self) -> &BandwidthLimiter {
+        &self.bandwidth
+    }
+
+    /// Capture every response to, or replay responses from, a session
+    /// directory. See [`Session`].
+    #[cfg(not(target_family = "wasm"))]
+    #[must_use]
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Some(Arc::new(session));
+        self
+    }
+
+    /// Whether responses are being captured to or replayed from a session.
+    #[cfg(not(target_family = "wasm"))]
+    #[must_use]
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Sessions are native-only.
+    #[cfg(target_family = "wasm")]
+    #[must_use]
+    pub fn has_session(&self) -> bool {
+        false
+    }
+
     /// Fetch the root planetoid metadata.
     ///
     /// This returns information about the planet including radius and the
@@ -91,14 +284,34 @@ impl<C: Cache> Client<C> {
     ///
     /// Returns an error if the HTTP request fails or the response cannot be decoded.
     pub async fn fetch_planetoid(&self) -> Result<Planetoid> {
-        let url = format!("{}PlanetoidMetadata", self.base_url);
-        let data = self.fetch_bytes(&url).await?;
+        let data = self.fetch_bytes(&self.planetoid_url()).await?;
+        Self::decode_planetoid(&data)
+    }
+
+    /// Fetch the root planetoid metadata, revalidating a cached copy however
+    /// fresh the [`CacheControl`] policy says it is, to notice a new root
+    /// epoch while running.
+    ///
+    /// Bypasses any session, like [`Self::warm_cache`]; callers that replay
+    /// should check [`Self::has_session`] and skip refreshing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response cannot be decoded, or if the HTTP
+    /// request fails and nothing is cached.
+    pub async fn refresh_planetoid(&self) -> Result<Planetoid> {
+        let (data, _) = self
+            .fetch_bytes_uncaptured(&self.planetoid_url(), true)
+            .await?;
+        Self::decode_planetoid(&data)
+    }
 
-        let proto =
-            proto::PlanetoidMetadata::decode(data.as_slice()).map_err(|e| Error::Protobuf {
-                context: "planetoid metadata",
-                message: e.to_string(),
-            })?;
+    /// Decode planetoid metadata from protobuf.
+    fn decode_planetoid(data: &[u8]) -> Result<Planetoid> {
+        let proto = proto::PlanetoidMetadata::decode(data).map_err(|e| Error::Protobuf {
+            context: "planetoid metadata",
+            message: e.to_string(),
+        })?;
 
         let root_epoch = proto
             .root_node_metadata
@@ -124,8 +337,13 @@ impl<C: Cache> Client<C> {
             "{}BulkMetadata/pb=!1m2!1s{}!2u{}",
             self.base_url, request.path, request.epoch
         );
-        let data = self.fetch_bytes(&url).await?;
+        let data = self
+            .fetch_bytes(&url)
+            .instrument(tracing::info_span!("rocktree_fetch", kind = "bulk", path = %request.path))
+            .await?;
 
+        let _span =
+            tracing::info_span!("rocktree_decode", kind = "bulk", path = %request.path).entered();
         let proto = proto::BulkMetadata::decode(data.as_slice()).map_err(|e| Error::Protobuf {
             context: "bulk metadata",
             message: e.to_string(),
@@ -153,14 +371,19 @@ impl<C: Cache> Client<C> {
                 self.base_url, request.path, request.epoch, request.texture_format
             )
         };
-        let data = self.fetch_bytes(&url).await?;
+        let data = self
+            .fetch_bytes(&url)
+            .instrument(tracing::info_span!("rocktree_fetch", kind = "node", path = %request.path))
+            .await?;
 
+        let _span =
+            tracing::info_span!("rocktree_decode", kind = "node", path = %request.path).entered();
         let proto = proto::NodeData::decode(data.as_slice()).map_err(|e| Error::Protobuf {
             context: "node data",
             message: e.to_string(),
         })?;
 
-        Self::decode_node_data(request.path, &proto)
+        self.decode_node_data(request, &proto)
     }
 
     /// Fetch raw bytes from a URL, using cache if available.
@@ -171,6 +394,36 @@ impl<C: Cache> Client<C> {
         self.fetch_bytes(url).await
     }
 
+    /// Make sure `url` is in the tile cache, fetching it if it isn't, without
+    /// decoding it. Returns the size of the response when a network fetch was
+    /// needed, or `None` when it was already cached.
+    ///
+    /// Bypasses any session, so prefetches never end up in a capture; callers
+    /// that replay should check [`Self::has_session`] and skip warming.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache lookup or the HTTP request fails.
+    pub async fn warm_cache(&self, url: &str) -> Result<Option<usize>> {
+        if self.cache.contains(url).await? {
+            return Ok(None);
+        }
+        self.fetch_bytes_uncaptured(url, false)
+            .await
+            .map(|(data, cached)| (!cached).then_some(data.len()))
+    }
+
+    /// Drop `url` from the tile cache, so the next fetch goes to the network.
+    /// Decoded textures aren't affected; see
+    /// [`DecodedTextureCache::remove_node`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache fails to remove the entry.
+    pub async fn evict(&self, url: &str) -> Result<()> {
+        self.cache.remove(url).await
+    }
+
     /// Build the URL for fetching bulk metadata.
     #[must_use]
     pub fn bulk_url(&self, request: &BulkRequest) -> String {
@@ -202,23 +455,129 @@ impl<C: Cache> Client<C> {
         format!("{}PlanetoidMetadata", self.base_url)
     }
 
+    /// Fetch raw bytes from a URL, going through the session if one is set.
+    #[cfg(not(target_family = "wasm"))]
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let Some(session) = &self.session else {
+            return self
+                .fetch_bytes_uncaptured(url, false)
+                .await
+                .map(|(data, _)| data);
+        };
+        match session.as_ref() {
+            Session::Replay(replay) => replay.next(url),
+            Session::Capture(recorder) => {
+                let started = Instant::now();
+                let result = self.fetch_bytes_uncaptured(url, false).await;
+                let source = match &result {
+                    Ok((_, true)) => ResponseSource::Cache,
+                    _ => ResponseSource::Network,
+                };
+                let result = result.map(|(data, _)| data);
+                if let Err(e) = recorder.record(url, started, source, &result) {
+                    tracing::warn!(url, "failed to record session response: {e}");
+                }
+                result
+            }
+        }
+    }
+
     /// Fetch raw bytes from a URL, using cache if available.
+    #[cfg(target_family = "wasm")]
     async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        self.fetch_bytes_uncaptured(url, false)
+            .await
+            .map(|(data, _)| data)
+    }
+
+    /// Fetch raw bytes from a URL, using cache if available. Also returns
+    /// whether the bytes came from the cache.
+    ///
+    /// Stale entries (see [`CacheControl`]), and with `revalidate` any cached
+    /// entry, are revalidated with a conditional request and served from the
+    /// cache on `304 Not Modified`. If revalidation fails, the cached entry
+    /// is served rather than the error.
+    async fn fetch_bytes_uncaptured(&self, url: &str, revalidate: bool) -> Result<(Vec<u8>, bool)> {
         // Check cache first.
+        let mut stale = None;
         if let Some(data) = self.cache.get(url).await? {
-            tracing::debug!(url, "cache hit");
-            return Ok(data);
+            let validators = if self.cache_control == CacheControl::Immutable && !revalidate {
+                None
+            } else {
+                self.cache.get_validators(url).await?
+            };
+            let versioned = url != self.planetoid_url();
+            if !revalidate
+                && !self
+                    .cache_control
+                    .is_stale(versioned, validators.as_ref(), unix_now())
+            {
+                tracing::debug!(url, "cache hit");
+                return Ok((data, true));
+            }
+            stale = Some((data, validators));
         }
 
-        tracing::debug!(url, "fetching");
+        // Wait for the bandwidth cap, if any.
+        self.bandwidth.acquire().await;
+
+        // Without validators, a stale entry is simply fetched again.
+        let validators = stale
+            .as_ref()
+            .and_then(|(_, validators)| validators.as_ref())
+            .filter(|validators| validators.is_conditional());
+        let result = self.fetch_from_network(url, validators).await;
+        match (result, stale) {
+            (Ok(Some(data)), _) => Ok((data, false)),
+            (Ok(None), Some((data, _))) => Ok((data, true)),
+            (Ok(None), None) => unreachable!("304 without a conditional request"),
+            (Err(e), Some((data, _))) => {
+                tracing::warn!(url, "revalidation failed, serving the cached copy: {e}");
+                Ok((data, true))
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// Fetch `url` from the network and store it in the cache. With
+    /// `validators`, the request is conditional, and `None` means the cached
+    /// copy is still current.
+    async fn fetch_from_network(
+        &self,
+        url: &str,
+        validators: Option<&Validators>,
+    ) -> Result<Option<Vec<u8>>> {
+        let mut request = self.http.get(url);
+        if let Some(validators) = validators {
+            tracing::debug!(url, "revalidating");
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        } else {
+            tracing::debug!(url, "fetching");
+        }
 
-        // Fetch from network.
-        let response = self.http.get(url).send().await.map_err(|e| Error::Http {
+        let response = request.send().await.map_err(|e| Error::Http {
             url: url.to_string(),
             message: e.to_string(),
         })?;
 
         let status = response.status();
+        if status == StatusCode::NOT_MODIFIED
+            && let Some(validators) = validators
+        {
+            // Servers may send updated validators with a 304.
+            let mut refreshed = validators_from_headers(response.headers());
+            refreshed.etag = refreshed.etag.or_else(|| validators.etag.clone());
+            refreshed.last_modified = refreshed
+                .last_modified
+                .or_else(|| validators.last_modified.clone());
+            self.cache.put_validators(url, refreshed).await?;
+            return Ok(None);
+        }
         if !status.is_success() {
             return Err(Error::HttpStatus {
                 url: url.to_string(),
@@ -226,16 +585,21 @@ impl<C: Cache> Client<C> {
             });
         }
 
+        let validators = validators_from_headers(response.headers());
         let data = response.bytes().await.map_err(|e| Error::Http {
             url: url.to_string(),
             message: e.to_string(),
         })?;
         let data = data.to_vec();
+        self.bandwidth.record(data.len());
 
-        // Store in cache.
+        // Store in cache, with validators if they'll ever be used.
         self.cache.put(url, data.clone()).await?;
+        if self.cache_control != CacheControl::Immutable {
+            self.cache.put_validators(url, validators).await?;
+        }
 
-        Ok(data)
+        Ok(Some(data))
     }
 
     /// Decode bulk metadata from protobuf.
@@ -345,7 +709,8 @@ impl<C: Cache> Client<C> {
     }
 
     /// Decode node data from protobuf.
-    fn decode_node_data(path: OctreePath, proto: &proto::NodeData) -> Result<Node> {
+    fn decode_node_data(&self, request: &NodeRequest, proto: &proto::NodeData) -> Result<Node> {
+        let path = request.path;
         let matrix_data: &[f64] = &proto.matrix_globe_from_mesh;
         let matrix_globe_from_mesh = if matrix_data.len() == 16 {
             DMat4::from_cols_array(matrix_data.try_into().unwrap_or(&[0.0; 16]))
@@ -362,8 +727,27 @@ impl<C: Cache> Client<C> {
 
         let mut meshes = Vec::new();
 
-        for mesh_proto in &proto.meshes {
-            let mesh = Self::decode_mesh(mesh_proto, normal_lookup.as_deref())?;
+        for (index, mesh_proto) in proto.meshes.iter().enumerate() {
+            let decode_texture = || {
+                let _span = tracing::info_span!("decode_texture", mesh = index).entered();
+                Self::decode_texture(mesh_proto, request.texture_scale)
+            };
+            let texture = match &self.texture_cache {
+                Some(cache) => cache.get_or_decode(
+                    TextureKey {
+                        path,
+                        epoch: request.epoch,
+                        imagery_epoch: request.imagery_epoch,
+                        texture_format: request.texture_format,
+                        scale: request.texture_scale,
+                        mesh: index,
+                    },
+                    decode_texture,
+                )?,
+                None => decode_texture()?,
+            };
+            let mesh = tracing::info_span!("decode_mesh", mesh = index)
+                .in_scope(|| Self::decode_mesh(mesh_proto, normal_lookup.as_deref(), texture))?;
             meshes.push(mesh);
         }
 
@@ -383,8 +767,12 @@ impl<C: Cache> Client<C> {
         })
     }
 
-    /// Decode a mesh from protobuf.
-    fn decode_mesh(proto: &proto::Mesh, normal_lookup: Option<&[u8]>) -> Result<Mesh> {
+    /// Decode a mesh from protobuf, with its texture already decoded.
+    fn decode_mesh(
+        proto: &proto::Mesh,
+        normal_lookup: Option<&[u8]>,
+        texture: DecodedTexture,
+    ) -> Result<Mesh> {
         // Unpack vertices.
         let vertices_data = proto.vertices.as_deref().unwrap_or(&[]);
         let mut vertices = rocktree_decode::unpack_vertices(vertices_data)?;
@@ -439,9 +827,7 @@ impl<C: Cache> Client<C> {
         // Decode per-vertex normals from the mesh's normal indices and the node's lookup table.
         let normals = Self::decode_normals(proto, normal_lookup, vertices.len());
 
-        // Decode texture.
-        let (texture_data, texture_format, texture_width, texture_height) =
-            Self::decode_texture(proto)?;
+        let (texture_data, texture_format, texture_width, texture_height) = texture;
 
         Ok(Mesh {
             vertices,
@@ -490,8 +876,8 @@ impl<C: Cache> Client<C> {
         }
     }
 
-    /// Decode texture data from a mesh.
-    fn decode_texture(mesh: &proto::Mesh) -> Result<(Vec<u8>, TextureFormat, u32, u32)> {
+    /// Decode texture data from a mesh at `scale`.
+    fn decode_texture(mesh: &proto::Mesh, scale: TextureScale) -> Result<DecodedTexture> {
         let textures = &mesh.texture;
         if textures.is_empty() {
             return Err(Error::InvalidData {
@@ -512,7 +898,8 @@ impl<C: Cache> Client<C> {
         let format = texture.format.unwrap_or(proto::texture::Format::Jpg as i32);
         match format {
             f if f == proto::texture::Format::Jpg as i32 => {
-                let decoded = rocktree_decode::texture::decode_jpeg_to_rgba(tex_data)?;
+                let decoded =
+                    rocktree_decode::texture::decode_jpeg_to_rgba_scaled(tex_data, scale)?;
                 // Return as RGBA since we fully decode JPEG.
                 Ok((
                     decoded.data,
@@ -522,7 +909,8 @@ impl<C: Cache> Client<C> {
                 ))
             }
             f if f == proto::texture::Format::CrnDxt1 as i32 => {
-                let decoded = rocktree_decode::texture::decode_crn_to_rgba(tex_data)?;
+                let decoded =
+                    rocktree_decode::texture::decode_crn_to_rgba(tex_data)?.downscale(scale);
                 // Return as RGBA since we fully decode CRN.
                 Ok((
                     decoded.data,
@@ -539,6 +927,21 @@ impl<C: Cache> Client<C> {
     }
 }
 
+/// The validators in a response's headers, stamped with the current time.
+fn validators_from_headers(headers: &HeaderMap) -> Validators {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+        validated_at: unix_now().unwrap_or(0),
+    }
+}
+
 /// Select the best texture format from available formats bitmask.
 fn select_texture_format(available: i32) -> i32 {
     // Preference order: CRN_DXT1 (6), JPG (1).
@@ -586,4 +989,28 @@ mod tests {
         let client = Client::new();
         assert!(client.base_url.starts_with("https://"));
     }
+
+    #[test]
+    fn test_builder_carries_cache_control() {
+        let control = CacheControl::RevalidateUnversioned {
+            max_age: std::time::Duration::from_secs(60),
+        };
+        let client = Client::builder()
+            .cache(crate::MemoryCache::new())
+            .cache_control(control)
+            .base_url("http://localhost/")
+            .build();
+        assert_eq!(client.cache_control(), control);
+        assert_eq!(client.planetoid_url(), "http://localhost/PlanetoidMetadata");
+    }
+
+    #[test]
+    fn test_validators_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        let validators = validators_from_headers(&headers);
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(validators.last_modified, None);
+        assert!(validators.is_conditional());
+    }
 }
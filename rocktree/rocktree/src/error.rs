@@ -38,6 +38,18 @@ pub enum Error {
         /// The error message.
         message: String,
     },
+    /// Session capture or replay failed.
+    Session {
+        /// The operation that failed.
+        operation: &'static str,
+        /// The error message.
+        message: String,
+    },
+    /// A replayed session has no response for the requested URL.
+    NotRecorded {
+        /// The URL that was requested.
+        url: String,
+    },
     /// Invalid data in response.
     InvalidData {
         /// Context for where the error occurred.
@@ -47,6 +59,24 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Whether retrying the request later might succeed: network failures,
+    /// timeouts, rate limiting and server errors, and cache I/O. Decode
+    /// errors and missing recordings fail the same way every time.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Http { .. } | Error::Cache { .. } => true,
+            Error::HttpStatus { status, .. } => matches!(status, 408 | 429 | 500..=599),
+            Error::Protobuf { .. }
+            | Error::Decode(_)
+            | Error::Session { .. }
+            | Error::NotRecorded { .. }
+            | Error::InvalidData { .. } => false,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -63,6 +93,12 @@ impl fmt::Display for Error {
             Error::Cache { operation, message } => {
                 write!(f, "cache {operation} failed: {message}")
             }
+            Error::Session { operation, message } => {
+                write!(f, "session {operation} failed: {message}")
+            }
+            Error::NotRecorded { url } => {
+                write!(f, "no recorded response for {url}")
+            }
             Error::InvalidData { context, detail } => {
                 write!(f, "invalid {context}: {detail}")
             }
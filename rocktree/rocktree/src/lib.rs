@@ -25,20 +25,28 @@
 //! let bulk = client.fetch_bulk(BulkRequest::root(planetoid.root_epoch)).await?;
 //! ```
 
+pub mod bandwidth;
 pub mod cache;
 mod client;
 mod error;
+#[cfg(not(target_family = "wasm"))]
+pub mod session;
+pub mod texture_cache;
 pub mod types;
 
+pub use bandwidth::BandwidthLimiter;
 #[cfg(not(target_family = "wasm"))]
 pub use cache::FilesystemCache;
-pub use cache::{Cache, MemoryCache, NoCache};
-pub use client::Client;
+pub use cache::{Cache, CacheControl, MemoryCache, NoCache, Validators};
+pub use client::{Client, ClientBuilder};
 pub use error::{Error, Result};
+#[cfg(not(target_family = "wasm"))]
+pub use session::{Session, SessionRecorder, SessionReplay};
+pub use texture_cache::{DecodedTextureCache, TextureCacheStats};
 pub use types::{
-    BulkMetadata, BulkRequest, Frustum, LodMetrics, Mesh, Node, NodeMetadata, NodeRequest,
-    Planetoid, TextureFormat,
+    BulkMetadata, BulkRequest, DepthRange, Frustum, LodMetrics, Mesh, Node, NodeMetadata,
+    NodeRequest, Planetoid, RefinementStrategy, TextureFormat,
 };
 
 // Re-export decode types for convenience.
-pub use rocktree_decode::{OrientedBoundingBox, UvTransform, Vertex};
+pub use rocktree_decode::{OrientedBoundingBox, UvTransform, Vertex, texture::TextureScale};
@@ -0,0 +1,356 @@
+//! Session capture and replay for deterministic debugging.
+//!
+//! A [`Session`] sits in front of the [`Client`](crate::Client)'s cache and
+//! network:
+//!
+//! - [`Session::Capture`] records every response the client hands back
+//!   (whether it came from the cache or the network, and including failures)
+//!   to a session directory, along with when it was requested and how long it
+//!   took.
+//! - [`Session::Replay`] serves responses from a recorded session directory
+//!   instead of touching the cache or the network, so a LOD or decode bug
+//!   seen once can be reproduced without the live endpoint.
+//!
+//! # Layout
+//!
+//! A session directory holds `session.tsv`, one line per response in the
+//! order they completed:
+//!
+//! ```text
+//! <seq>\t<offset_ms>\t<latency_ms>\t<source>\t<outcome>\t<url>
+//! ```
+//!
+//! `source` is `cache` or `net`; `outcome` is `ok`, `status=<code>` or
+//! `error`. Bodies of `ok` responses (and messages of `error` ones) are
+//! stored next to it as `<seq>.bin`. Each line is flushed as soon as it is
+//! written, so a session survives the process crashing mid-decode.
+//!
+//! Replay serves the responses recorded for each URL in their recorded
+//! order, repeating the last one once they run out. Timing is recorded for
+//! inspection only; replayed responses are returned immediately.
+
+use crate::error::{Error, Result};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+/// Name of the index file in a session directory.
+const INDEX_FILE: &str = "session.tsv";
+
+/// Capture or replay of a client's responses.
+pub enum Session {
+    /// Record every response to a session directory.
+    Capture(SessionRecorder),
+    /// Serve responses from a recorded session directory.
+    Replay(SessionReplay),
+}
+
+/// Where a captured response came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSource {
+    /// The client's cache.
+    Cache,
+    /// The network.
+    Network,
+}
+
+impl ResponseSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResponseSource::Cache => "cache",
+            ResponseSource::Network => "net",
+        }
+    }
+}
+
+// ============================================================================
+// Capture
+// ============================================================================
+
+/// Records responses to a session directory.
+pub struct SessionRecorder {
+    dir: PathBuf,
+    start: Instant,
+    state: Mutex<RecorderState>,
+}
+
+struct RecorderState {
+    index: BufWriter<File>,
+    next_seq: u64,
+}
+
+impl SessionRecorder {
+    /// Start recording a new session into `dir`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or index file can't be created, or
+    /// if `dir` already holds a session.
+    pub fn create(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| session_error("create", &dir, &e))?;
+        let index_path = dir.join(INDEX_FILE);
+        let index =
+            File::create_new(&index_path).map_err(|e| session_error("create", &index_path, &e))?;
+        Ok(Self {
+            dir,
+            start: Instant::now(),
+            state: Mutex::new(RecorderState {
+                index: BufWriter::new(index),
+                next_seq: 0,
+            }),
+        })
+    }
+
+    /// The directory being recorded into.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record the outcome of a request for `url` issued at `started`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body or index line can't be written.
+    pub fn record(
+        &self,
+        url: &str,
+        started: Instant,
+        source: ResponseSource,
+        outcome: &Result<Vec<u8>>,
+    ) -> Result<()> {
+        let offset_ms = started.duration_since(self.start).as_millis();
+        let latency_ms = started.elapsed().as_millis();
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        let message;
+        let (kind, body) = match outcome {
+            Ok(data) => ("ok".to_string(), Some(data.as_slice())),
+            Err(Error::HttpStatus { status, .. }) => (format!("status={status}"), None),
+            Err(e) => {
+                message = e.to_string();
+                ("error".to_string(), Some(message.as_bytes()))
+            }
+        };
+        if let Some(body) = body {
+            let body_path = self.dir.join(body_file(seq));
+            fs::write(&body_path, body).map_err(|e| session_error("write", &body_path, &e))?;
+        }
+
+        let index_path = self.dir.join(INDEX_FILE);
+        writeln!(
+            state.index,
+            "{seq}\t{offset_ms}\t{latency_ms}\t{}\t{kind}\t{url}",
+            source.as_str()
+        )
+        .and_then(|()| state.index.flush())
+        .map_err(|e| session_error("write", &index_path, &e))
+    }
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// Serves responses from a recorded session directory.
+pub struct SessionReplay {
+    dir: PathBuf,
+    responses: Mutex<HashMap<String, VecDeque<RecordedResponse>>>,
+}
+
+/// One recorded response.
+#[derive(Debug, Clone)]
+struct RecordedResponse {
+    seq: u64,
+    outcome: RecordedOutcome,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RecordedOutcome {
+    Ok,
+    Status(u16),
+    Error,
+}
+
+impl SessionReplay {
+    /// Load the session recorded in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index file can't be read or is malformed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let index_path = dir.join(INDEX_FILE);
+        let index =
+            fs::read_to_string(&index_path).map_err(|e| session_error("read", &index_path, &e))?;
+
+        let mut responses: HashMap<String, VecDeque<RecordedResponse>> = HashMap::new();
+        for (line_number, line) in index.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let (url, response) = parse_index_line(line).ok_or_else(|| Error::InvalidData {
+                context: "session index",
+                detail: format!("malformed line {}: {line}", line_number + 1),
+            })?;
+            responses.entry(url).or_default().push_back(response);
+        }
+
+        Ok(Self {
+            dir,
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// The directory being replayed from.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of distinct URLs with recorded responses.
+    #[must_use]
+    pub fn url_count(&self) -> usize {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// The next recorded response for `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the recorded error if the request failed when captured,
+    /// [`Error::NotRecorded`] if `url` was never requested during capture, or
+    /// an error if the body file can't be read.
+    pub fn next(&self, url: &str) -> Result<Vec<u8>> {
+        let response = {
+            let mut responses = self
+                .responses
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let queue = responses.get_mut(url).ok_or_else(|| Error::NotRecorded {
+                url: url.to_string(),
+            })?;
+            if queue.len() > 1 {
+                queue.pop_front()
+            } else {
+                queue.front().cloned()
+            }
+        };
+        let Some(response) = response else {
+            return Err(Error::NotRecorded {
+                url: url.to_string(),
+            });
+        };
+
+        let body = || {
+            let path = self.dir.join(body_file(response.seq));
+            fs::read(&path).map_err(|e| session_error("read", &path, &e))
+        };
+        match response.outcome {
+            RecordedOutcome::Ok => body(),
+            RecordedOutcome::Status(status) => Err(Error::HttpStatus {
+                url: url.to_string(),
+                status,
+            }),
+            RecordedOutcome::Error => Err(Error::Http {
+                url: url.to_string(),
+                message: String::from_utf8_lossy(&body()?).into_owned(),
+            }),
+        }
+    }
+}
+
+/// Parse one `session.tsv` line into its URL and response.
+fn parse_index_line(line: &str) -> Option<(String, RecordedResponse)> {
+    let mut fields = line.splitn(6, '\t');
+    let seq = fields.next()?.parse().ok()?;
+    let _offset_ms = fields.next()?;
+    let _latency_ms = fields.next()?;
+    let _source = fields.next()?;
+    let outcome = match fields.next()? {
+        "ok" => RecordedOutcome::Ok,
+        "error" => RecordedOutcome::Error,
+        other => RecordedOutcome::Status(other.strip_prefix("status=")?.parse().ok()?),
+    };
+    let url = fields.next()?.to_string();
+    Some((url, RecordedResponse { seq, outcome }))
+}
+
+/// Name of the body file for response `seq`.
+fn body_file(seq: u64) -> String {
+    format!("{seq:08}.bin")
+}
+
+fn session_error(operation: &'static str, path: &Path, error: &std::io::Error) -> Error {
+    Error::Session {
+        operation,
+        message: format!("{}: {error}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rocktree-session-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn capture_then_replay_roundtrip() {
+        let dir = temp_dir("roundtrip");
+        let recorder = SessionRecorder::create(&dir).unwrap();
+        let started = Instant::now();
+        recorder
+            .record("a", started, ResponseSource::Network, &Ok(vec![1, 2]))
+            .unwrap();
+        recorder
+            .record("a", started, ResponseSource::Cache, &Ok(vec![3]))
+            .unwrap();
+        let not_found = Err(Error::HttpStatus {
+            url: "b".to_string(),
+            status: 404,
+        });
+        recorder
+            .record("b", started, ResponseSource::Network, &not_found)
+            .unwrap();
+        drop(recorder);
+
+        let replay = SessionReplay::open(&dir).unwrap();
+        assert_eq!(replay.url_count(), 2);
+        assert_eq!(replay.next("a").unwrap(), vec![1, 2]);
+        assert_eq!(replay.next("a").unwrap(), vec![3]);
+        // The last response repeats.
+        assert_eq!(replay.next("a").unwrap(), vec![3]);
+        assert!(matches!(
+            replay.next("b"),
+            Err(Error::HttpStatus { status: 404, .. })
+        ));
+        assert!(matches!(replay.next("c"), Err(Error::NotRecorded { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_overwrite_a_session() {
+        let dir = temp_dir("overwrite");
+        drop(SessionRecorder::create(&dir).unwrap());
+        assert!(SessionRecorder::create(&dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
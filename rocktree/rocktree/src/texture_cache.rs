@@ -0,0 +1,309 @@
+//! In-memory LRU of decoded node textures.
+//!
+//! JPEG and CRN decoding dominate node decode time, and a node that unloads
+//! when the camera leaves an area is fetched again (often from the tile
+//! cache) when it comes back. [`DecodedTextureCache`] keeps the decoded
+//! pixels of recent textures, keyed by everything that determines them
+//! (node path and epochs, texture format, decode scale, mesh index), so a
+//! re-entered area skips texture decoding entirely.
+//!
+//! Recency is kept in an ordered index beside the entries, so a hit or an
+//! eviction costs a logarithmic update rather than a scan of the cache.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use rocktree_decode::{OctreePath, texture::TextureScale};
+
+use crate::{error::Result, types::TextureFormat};
+
+/// Everything that determines a mesh's decoded texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureKey {
+    /// Node path.
+    pub path: OctreePath,
+    /// Node data epoch.
+    pub epoch: u32,
+    /// Imagery epoch, if the request named one.
+    pub imagery_epoch: Option<u32>,
+    /// Requested texture format.
+    pub texture_format: i32,
+    /// Decode scale.
+    pub scale: TextureScale,
+    /// Index of the mesh within the node.
+    pub mesh: usize,
+}
+
+/// A decoded texture: pixels, format, and size.
+pub type DecodedTexture = (Vec<u8>, TextureFormat, u32, u32);
+
+/// Hit/miss counters and occupancy of a [`DecodedTextureCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextureCacheStats {
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups that had to decode.
+    pub misses: u64,
+    /// Textures currently cached.
+    pub entries: usize,
+    /// Bytes of pixel data currently cached.
+    pub bytes: usize,
+}
+
+impl TextureCacheStats {
+    /// Fraction of lookups served from the cache, or `None` before the first
+    /// lookup.
+    #[must_use]
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// A byte-budgeted LRU of decoded textures, shareable across decode tasks.
+#[derive(Debug)]
+pub struct DecodedTextureCache {
+    inner: Mutex<Inner>,
+    max_bytes: AtomicUsize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<TextureKey, Entry>,
+    /// Keys by `last_used`, least recently used first.
+    recency: BTreeMap<u64, TextureKey>,
+    bytes: usize,
+    /// Monotonic use counter; an entry's `last_used` is its value at the
+    /// entry's most recent hit or insert.
+    clock: u64,
+}
+
+impl Inner {
+    /// Drop least recently used entries until at most `max_bytes` remain.
+    fn evict_to(&mut self, max_bytes: usize) {
+        while self.bytes > max_bytes {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.bytes -= evicted.texture.0.len();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    texture: DecodedTexture,
+    last_used: u64,
+}
+
+impl DecodedTextureCache {
+    /// Create a cache holding at most `max_bytes` of pixel data.
+    #[must_use]
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            max_bytes: AtomicUsize::new(max_bytes),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Return the cached texture for `key`, or run `decode` and cache its
+    /// result. Decode errors are passed through and not cached.
+    pub fn get_or_decode(
+        &self,
+        key: TextureKey,
+        decode: impl FnOnce() -> Result<DecodedTexture>,
+    ) -> Result<DecodedTexture> {
+        if let Some(texture) = self.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(texture);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        // Decode outside the lock so concurrent decodes don't serialise.
+        let texture = decode()?;
+        self.insert(key, texture.clone());
+        Ok(texture)
+    }
+
+    /// The budget for pixel data (bytes).
+    #[must_use]
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Change the budget, evicting least recently used textures down to it.
+    pub fn set_max_bytes(&self, max_bytes: usize) {
+        if self.max_bytes.swap(max_bytes, Ordering::Relaxed) <= max_bytes {
+            return;
+        }
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.evict_to(max_bytes);
+        }
+    }
+
+    /// Current counters and occupancy.
+    #[must_use]
+    pub fn stats(&self) -> TextureCacheStats {
+        let (entries, bytes) = self
+            .inner
+            .lock()
+            .map_or((0, 0), |inner| (inner.entries.len(), inner.bytes));
+        TextureCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries,
+            bytes,
+        }
+    }
+
+    /// Drop every cached texture of the node at `path`, whatever its epochs
+    /// and scale. Returns how many were dropped.
+    pub fn remove_node(&self, path: OctreePath) -> usize {
+        let Ok(mut inner) = self.inner.lock() else {
+            return 0;
+        };
+        let inner = &mut *inner;
+        let before = inner.entries.len();
+        let mut freed = 0;
+        inner.entries.retain(|key, entry| {
+            let keep = key.path != path;
+            if !keep {
+                freed += entry.texture.0.len();
+                inner.recency.remove(&entry.last_used);
+            }
+            keep
+        });
+        inner.bytes -= freed;
+        before - inner.entries.len()
+    }
+
+    fn get(&self, key: &TextureKey) -> Option<DecodedTexture> {
+        let mut inner = self.inner.lock().ok()?;
+        let inner = &mut *inner;
+        let entry = inner.entries.get_mut(key)?;
+        inner.clock += 1;
+        inner.recency.remove(&entry.last_used);
+        inner.recency.insert(inner.clock, *key);
+        entry.last_used = inner.clock;
+        Some(entry.texture.clone())
+    }
+
+    fn insert(&self, key: TextureKey, texture: DecodedTexture) {
+        let size = texture.0.len();
+        let max_bytes = self.max_bytes();
+        if size > max_bytes {
+            return;
+        }
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.recency.insert(last_used, key);
+        if let Some(old) = inner.entries.insert(key, Entry { texture, last_used }) {
+            inner.recency.remove(&old.last_used);
+            inner.bytes -= old.texture.0.len();
+        }
+        inner.bytes += size;
+        inner.evict_to(max_bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(mesh: usize) -> TextureKey {
+        TextureKey {
+            path: OctreePath::parse("0123").unwrap(),
+            epoch: 1,
+            imagery_epoch: None,
+            texture_format: 1,
+            scale: TextureScale::Full,
+            mesh,
+        }
+    }
+
+    fn texture(bytes: usize) -> DecodedTexture {
+        (vec![0; bytes], TextureFormat::Rgba, 1, 1)
+    }
+
+    #[test]
+    fn second_lookup_hits() {
+        let cache = DecodedTextureCache::new(1024);
+        let mut decodes = 0;
+        for _ in 0..2 {
+            cache
+                .get_or_decode(key(0), || {
+                    decodes += 1;
+                    Ok(texture(16))
+                })
+                .unwrap();
+        }
+        assert_eq!(decodes, 1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.hit_rate(), Some(0.5));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = DecodedTextureCache::new(32);
+        cache.get_or_decode(key(0), || Ok(texture(16))).unwrap();
+        cache.get_or_decode(key(1), || Ok(texture(16))).unwrap();
+        // Touch 0 so 1 is the oldest when 2 arrives.
+        cache.get_or_decode(key(0), || Ok(texture(16))).unwrap();
+        cache.get_or_decode(key(2), || Ok(texture(16))).unwrap();
+
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(2)).is_some());
+        assert_eq!(cache.stats().bytes, 32);
+    }
+
+    #[test]
+    fn shrinking_the_budget_evicts_oldest_first() {
+        let cache = DecodedTextureCache::new(64);
+        for mesh in 0..4 {
+            cache.get_or_decode(key(mesh), || Ok(texture(16))).unwrap();
+        }
+        cache.get_or_decode(key(0), || Ok(texture(16))).unwrap();
+
+        cache.set_max_bytes(32);
+        assert_eq!(cache.max_bytes(), 32);
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(2)).is_none());
+        let inner = cache.inner.lock().unwrap();
+        assert_eq!(inner.recency.len(), inner.entries.len());
+        assert_eq!(inner.bytes, 32);
+    }
+
+    #[test]
+    fn remove_node_drops_only_that_node() {
+        let cache = DecodedTextureCache::new(1024);
+        let other = TextureKey {
+            path: OctreePath::parse("0124").unwrap(),
+            ..key(0)
+        };
+        for key in [key(0), key(1), other] {
+            cache.get_or_decode(key, || Ok(texture(16))).unwrap();
+        }
+
+        assert_eq!(cache.remove_node(key(0).path), 2);
+        assert!(cache.get(&key(0)).is_none());
+        assert!(cache.get(&other).is_some());
+        assert_eq!(cache.stats().bytes, 16);
+    }
+}
@@ -5,8 +5,10 @@
 
 use std::collections::HashMap;
 
-use glam::{DMat4, DVec3, Vec3};
-use rocktree_decode::{OctreePath, OrientedBoundingBox, UvTransform, Vertex};
+use glam::{DMat4, DVec3, DVec4, Vec3};
+use rocktree_decode::{
+    OctreePath, OrientedBoundingBox, UvTransform, Vertex, texture::TextureScale,
+};
 
 /// Texture format for mesh textures.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -143,6 +145,9 @@ pub struct NodeRequest {
     pub texture_format: i32,
     /// Imagery epoch (optional).
     pub imagery_epoch: Option<u32>,
+    /// Resolution to decode the node's textures at. Not part of the
+    /// request URL: the same response is fetched (and cached) at every scale.
+    pub texture_scale: TextureScale,
 }
 
 impl NodeRequest {
@@ -159,79 +164,94 @@ impl NodeRequest {
             epoch,
             texture_format,
             imagery_epoch,
+            texture_scale: TextureScale::Full,
         }
     }
+
+    /// Decode the node's textures at `scale` rather than full resolution.
+    #[must_use]
+    pub fn with_texture_scale(mut self, scale: TextureScale) -> Self {
+        self.texture_scale = scale;
+        self
+    }
+}
+
+/// Clip-space depth convention of a projection matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthRange {
+    /// Visible depth maps to `-w..=w` (OpenGL).
+    NegativeOneToOne,
+    /// Visible depth maps to `0..=w` (wgpu, Vulkan, Direct3D). Covers
+    /// reverse-Z projections too, which only swap which end is near.
+    ZeroToOne,
 }
 
 /// A frustum for culling nodes based on their OBBs.
+///
+/// Built from any view-projection matrix: symmetric or off-center
+/// perspective, orthographic, oblique near planes, and infinite far planes
+/// (whose degenerate far plane culls nothing). Multi-view setups build one
+/// frustum per view.
 #[derive(Debug, Clone, Copy)]
 pub struct Frustum {
     /// Frustum planes (6 planes for a standard view frustum).
-    /// Each plane is represented as (normal, distance).
+    /// Each plane is represented as (normal, distance), with the normal
+    /// pointing inwards; a zero normal marks a degenerate plane.
     planes: [(DVec3, f64); 6],
 }
 
 impl Frustum {
-    /// Create a frustum from a view-projection matrix.
+    /// Create a frustum from a view-projection matrix with OpenGL depth
+    /// ([`DepthRange::NegativeOneToOne`]).
+    ///
+    /// Use [`from_view_projection`](Self::from_view_projection) for
+    /// projections with `0..=1` depth, such as glam's `perspective_rh`.
     #[must_use]
     pub fn from_matrix(vp: DMat4) -> Self {
-        // Extract frustum planes from view-projection matrix.
-        let m = vp.to_cols_array_2d();
+        Self::from_view_projection(vp, DepthRange::NegativeOneToOne)
+    }
+
+    /// Create a frustum from a view-projection matrix whose clip-space
+    /// depth follows `depth`.
+    #[must_use]
+    pub fn from_view_projection(vp: DMat4, depth: DepthRange) -> Self {
+        // A point is inside when its clip coordinates satisfy
+        // -w <= x <= w, -w <= y <= w and the depth bounds; each inequality
+        // is a plane in world space (Gribb & Hartmann).
+        let [x, y, z, w] = [vp.row(0), vp.row(1), vp.row(2), vp.row(3)];
+        let near = match depth {
+            DepthRange::NegativeOneToOne => w + z,
+            DepthRange::ZeroToOne => z,
+        };
 
         // Left, right, bottom, top, near, far planes.
-        let planes = [
-            Self::normalize_plane(
-                m[0][3] + m[0][0],
-                m[1][3] + m[1][0],
-                m[2][3] + m[2][0],
-                m[3][3] + m[3][0],
-            ),
-            Self::normalize_plane(
-                m[0][3] - m[0][0],
-                m[1][3] - m[1][0],
-                m[2][3] - m[2][0],
-                m[3][3] - m[3][0],
-            ),
-            Self::normalize_plane(
-                m[0][3] + m[0][1],
-                m[1][3] + m[1][1],
-                m[2][3] + m[2][1],
-                m[3][3] + m[3][1],
-            ),
-            Self::normalize_plane(
-                m[0][3] - m[0][1],
-                m[1][3] - m[1][1],
-                m[2][3] - m[2][1],
-                m[3][3] - m[3][1],
-            ),
-            Self::normalize_plane(
-                m[0][3] + m[0][2],
-                m[1][3] + m[1][2],
-                m[2][3] + m[2][2],
-                m[3][3] + m[3][2],
-            ),
-            Self::normalize_plane(
-                m[0][3] - m[0][2],
-                m[1][3] - m[1][2],
-                m[2][3] - m[2][2],
-                m[3][3] - m[3][2],
-            ),
-        ];
+        let planes = [w + x, w - x, w + y, w - y, near, w - z].map(Self::normalize_plane);
 
         Self { planes }
     }
 
-    fn normalize_plane(a: f64, b: f64, c: f64, d: f64) -> (DVec3, f64) {
-        let normal = DVec3::new(a, b, c);
+    fn normalize_plane(plane: DVec4) -> (DVec3, f64) {
+        let normal = plane.truncate();
         let length = normal.length();
         if length > 0.0 {
-            (normal / length, d / length)
+            (normal / length, plane.w / length)
         } else {
             (DVec3::ZERO, 0.0)
         }
     }
 
+    /// Test if a point is inside the frustum.
+    #[must_use]
+    pub fn contains_point(&self, point: DVec3) -> bool {
+        self.planes
+            .iter()
+            .all(|&(normal, distance)| normal.dot(point) + distance >= 0.0)
+    }
+
     /// Test if an oriented bounding box intersects the frustum.
+    ///
+    /// Conservative: never rejects a box that intersects, but may accept a
+    /// box near a frustum edge that lies outside.
     #[must_use]
     pub fn intersects_obb(&self, obb: &OrientedBoundingBox) -> bool {
         for &(normal, distance) in &self.planes {
@@ -251,6 +271,85 @@ impl Frustum {
     }
 }
 
+/// How [`LodMetrics::should_refine`] decides whether a node is too coarse.
+///
+/// Every strategy compares a node's texel size (`meters_per_texel`) against
+/// what is acceptable at the node's distance from the camera; they differ in
+/// what "acceptable" means.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefinementStrategy {
+    /// Refine while a texel projects to more than `max_error_px` screen
+    /// pixels. Adapts to the field of view and screen resolution.
+    ScreenSpaceError {
+        /// Largest acceptable projected texel size (px).
+        max_error_px: f64,
+    },
+    /// Refine while texels are larger than `max_per_km` meters for every
+    /// kilometer of distance. Independent of the screen, so the same view
+    /// streams the same tiles on every device.
+    MetersPerTexel {
+        /// Largest acceptable texel size per kilometer of distance (m/km).
+        max_per_km: f64,
+    },
+    /// Refine to fixed resolution rings: within `first_band_m` texels may be
+    /// up to `first_band_meters_per_texel`, and each ring out to twice the
+    /// previous distance allows texels twice as large. Gives stable, visible
+    /// LoD boundaries, which makes transition artifacts easy to find.
+    DistanceBands {
+        /// Radius of the innermost band (m).
+        first_band_m: f64,
+        /// Largest acceptable texel size in the innermost band (m).
+        first_band_meters_per_texel: f64,
+    },
+}
+
+impl RefinementStrategy {
+    /// The default screen-space error strategy, tuned to match the C++
+    /// client's refine aggressiveness.
+    pub const SCREEN_SPACE_ERROR: Self = Self::ScreenSpaceError { max_error_px: 0.6 };
+
+    /// A meters-per-texel strategy roughly matching
+    /// [`SCREEN_SPACE_ERROR`](Self::SCREEN_SPACE_ERROR) on a 1080p screen
+    /// with a 60° field of view.
+    pub const METERS_PER_TEXEL: Self = Self::MetersPerTexel { max_per_km: 0.64 };
+
+    /// A distance-band strategy roughly matching
+    /// [`SCREEN_SPACE_ERROR`](Self::SCREEN_SPACE_ERROR) on a 1080p screen
+    /// with a 60° field of view.
+    pub const DISTANCE_BANDS: Self = Self::DistanceBands {
+        first_band_m: 250.0,
+        first_band_meters_per_texel: 0.16,
+    };
+
+    /// The same strategy with its tolerance scaled by `factor`: above 1 it
+    /// accepts coarser texels and so stops refining sooner (each octree level
+    /// halves the texel size, so 2 is about one level shallower).
+    #[must_use]
+    pub fn coarsened(self, factor: f64) -> Self {
+        match self {
+            Self::ScreenSpaceError { max_error_px } => Self::ScreenSpaceError {
+                max_error_px: max_error_px * factor,
+            },
+            Self::MetersPerTexel { max_per_km } => Self::MetersPerTexel {
+                max_per_km: max_per_km * factor,
+            },
+            Self::DistanceBands {
+                first_band_m,
+                first_band_meters_per_texel,
+            } => Self::DistanceBands {
+                first_band_m,
+                first_band_meters_per_texel: first_band_meters_per_texel * factor,
+            },
+        }
+    }
+}
+
+impl Default for RefinementStrategy {
+    fn default() -> Self {
+        Self::SCREEN_SPACE_ERROR
+    }
+}
+
 /// Screen-space error metric for LOD decisions.
 #[derive(Debug, Clone, Copy)]
 pub struct LodMetrics {
@@ -258,12 +357,13 @@ pub struct LodMetrics {
     pub camera_position: DVec3,
     /// Pixels per meter at distance 1 from camera.
     pub pixels_per_meter: f64,
-    /// Minimum pixel error to trigger LOD switch.
-    pub error_threshold: f64,
+    /// Rule deciding when a node is refined.
+    pub strategy: RefinementStrategy,
 }
 
 impl LodMetrics {
-    /// Create LOD metrics from camera parameters.
+    /// Create LOD metrics from camera parameters, using the default
+    /// [`RefinementStrategy`].
     #[must_use]
     pub fn new(camera_position: DVec3, fov_y: f64, screen_height: f64) -> Self {
         // pixels_per_meter = screen_height / (2 * tan(fov_y / 2))
@@ -271,13 +371,21 @@ impl LodMetrics {
         Self {
             camera_position,
             pixels_per_meter,
-            error_threshold: 0.6, // Tuned to match C++ refine aggressiveness.
+            strategy: RefinementStrategy::default(),
         }
     }
 
+    /// Use `strategy` to decide refinement.
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: RefinementStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     /// Check if a node should be refined based on LOD.
     ///
-    /// Returns true if the node's screen-space error exceeds the threshold.
+    /// Returns true if the node's texels are too coarse for its distance
+    /// under the active [`RefinementStrategy`].
     #[must_use]
     pub fn should_refine(&self, node_center: DVec3, meters_per_texel: f32) -> bool {
         let distance = self.camera_position.distance(node_center);
@@ -285,15 +393,32 @@ impl LodMetrics {
             return true;
         }
 
-        // Screen-space error in pixels.
-        let error = f64::from(meters_per_texel) * self.pixels_per_meter / distance;
-        error > self.error_threshold
+        let meters_per_texel = f64::from(meters_per_texel);
+        match self.strategy {
+            RefinementStrategy::ScreenSpaceError { max_error_px } => {
+                // Screen-space error in pixels.
+                let error = meters_per_texel * self.pixels_per_meter / distance;
+                error > max_error_px
+            }
+            RefinementStrategy::MetersPerTexel { max_per_km } => {
+                meters_per_texel > max_per_km * distance / 1000.0
+            }
+            RefinementStrategy::DistanceBands {
+                first_band_m,
+                first_band_meters_per_texel,
+            } => {
+                let band = (distance / first_band_m).log2().ceil().max(0.0);
+                meters_per_texel > first_band_meters_per_texel * band.exp2()
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use glam::{DMat3, DQuat};
+    use proptest::prelude::*;
 
     #[test]
     fn test_bulk_request_root() {
@@ -336,4 +461,298 @@ mod tests {
         // Far node with small texels should not refine.
         assert!(!metrics.should_refine(DVec3::new(100000.0, 0.0, 0.0), 0.1));
     }
+
+    #[test]
+    fn test_lod_meters_per_texel_ignores_screen() {
+        let strategy = RefinementStrategy::MetersPerTexel { max_per_km: 1.0 };
+        let small = LodMetrics::new(DVec3::ZERO, 1.0, 480.0).with_strategy(strategy);
+        let large = LodMetrics::new(DVec3::ZERO, 1.0, 4320.0).with_strategy(strategy);
+        let centre = DVec3::new(2000.0, 0.0, 0.0);
+
+        // 2 km away, so texels up to 2 m are fine.
+        for metrics in [small, large] {
+            assert!(metrics.should_refine(centre, 2.5));
+            assert!(!metrics.should_refine(centre, 1.5));
+        }
+    }
+
+    #[test]
+    fn test_lod_distance_bands() {
+        let metrics = LodMetrics::new(DVec3::ZERO, 1.0, 1080.0).with_strategy(
+            RefinementStrategy::DistanceBands {
+                first_band_m: 100.0,
+                first_band_meters_per_texel: 0.5,
+            },
+        );
+
+        // Inner band: 0.5 m.
+        assert!(metrics.should_refine(DVec3::new(50.0, 0.0, 0.0), 0.6));
+        // Third band (200-400 m): 2 m.
+        assert!(!metrics.should_refine(DVec3::new(300.0, 0.0, 0.0), 1.9));
+        assert!(metrics.should_refine(DVec3::new(300.0, 0.0, 0.0), 2.1));
+    }
+
+    #[test]
+    fn test_lod_coarsened_refines_less() {
+        let centre = DVec3::new(2000.0, 0.0, 0.0);
+        let metrics = LodMetrics::new(DVec3::ZERO, 1.0, 1080.0)
+            .with_strategy(RefinementStrategy::MetersPerTexel { max_per_km: 1.0 });
+        let coarse = metrics.with_strategy(metrics.strategy.coarsened(2.0));
+
+        // 2 km away: 2 m texels normally, 4 m once coarsened.
+        assert!(metrics.should_refine(centre, 3.0));
+        assert!(!coarse.should_refine(centre, 3.0));
+        assert!(coarse.should_refine(centre, 4.5));
+    }
+
+    // ========================================================================
+    // Frustum
+    // ========================================================================
+
+    /// Off-center right-handed perspective projection onto the near-plane
+    /// window `left..right`, `bottom..top`; `far: None` is infinite.
+    fn off_center_perspective(
+        [left, right, bottom, top]: [f64; 4],
+        near: f64,
+        far: Option<f64>,
+        depth: DepthRange,
+    ) -> DMat4 {
+        let (z_scale, z_offset) = match (depth, far) {
+            (DepthRange::ZeroToOne, Some(far)) => (-far / (far - near), -far * near / (far - near)),
+            (DepthRange::ZeroToOne, None) => (-1.0, -near),
+            (DepthRange::NegativeOneToOne, Some(far)) => (
+                -(far + near) / (far - near),
+                -2.0 * far * near / (far - near),
+            ),
+            (DepthRange::NegativeOneToOne, None) => (-1.0, -2.0 * near),
+        };
+        DMat4::from_cols(
+            DVec4::new(2.0 * near / (right - left), 0.0, 0.0, 0.0),
+            DVec4::new(0.0, 2.0 * near / (top - bottom), 0.0, 0.0),
+            DVec4::new(
+                (right + left) / (right - left),
+                (top + bottom) / (top - bottom),
+                z_scale,
+                -1.0,
+            ),
+            DVec4::new(0.0, 0.0, z_offset, 0.0),
+        )
+    }
+
+    /// Replace the near plane of a `0..=1` depth projection with the
+    /// view-space `plane`, which faces away from the camera (Lengyel's
+    /// oblique near-plane clipping).
+    fn oblique(projection: DMat4, plane: DVec4) -> DMat4 {
+        let corner =
+            projection.inverse() * DVec4::new(plane.x.signum(), plane.y.signum(), 1.0, 1.0);
+        let row = plane / plane.dot(corner);
+        let mut rows = [0, 1, 2, 3].map(|i| projection.row(i));
+        rows[2] = row;
+        DMat4::from_cols(rows[0], rows[1], rows[2], rows[3]).transpose()
+    }
+
+    /// Signed clip-space margin of `point`: positive inside, negative
+    /// outside, relative to `w`.
+    fn clip_margin(vp: DMat4, depth: DepthRange, point: DVec3) -> f64 {
+        let clip = vp * point.extend(1.0);
+        let near = match depth {
+            DepthRange::NegativeOneToOne => clip.w + clip.z,
+            DepthRange::ZeroToOne => clip.z,
+        };
+        [
+            clip.w + clip.x,
+            clip.w - clip.x,
+            clip.w + clip.y,
+            clip.w - clip.y,
+            near,
+            clip.w - clip.z,
+        ]
+        .into_iter()
+        .fold(f64::INFINITY, f64::min)
+    }
+
+    /// A random view-projection: off-center perspective, possibly infinite
+    /// or oblique, seen from a random pose.
+    fn arb_view_projection() -> impl Strategy<Value = (DMat4, DepthRange)> {
+        (
+            (-2.0..0.5f64, 0.1..2.0f64, -2.0..0.5f64, 0.1..2.0f64),
+            0.1..5.0f64,
+            prop::option::of(10.0..500.0f64),
+            any::<bool>(),
+            prop::option::of((-0.5..0.5f64, -0.5..0.5f64)),
+            (-20.0..20.0f64, -20.0..20.0f64, -20.0..20.0f64),
+            (
+                -1.0..1.0f64,
+                -1.0..1.0f64,
+                -1.0..1.0f64,
+                0.0..std::f64::consts::TAU,
+            ),
+        )
+            .prop_map(
+                |((left, width, bottom, height), near, far, gl, tilt, eye, (ax, ay, az, angle))| {
+                    let window = [left, left + width, bottom, bottom + height];
+                    let mut depth = if gl {
+                        DepthRange::NegativeOneToOne
+                    } else {
+                        DepthRange::ZeroToOne
+                    };
+                    let mut projection = off_center_perspective(window, near, far, depth);
+                    if let (Some((tx, ty)), Some(_)) = (tilt, far) {
+                        // Tilted near plane through (0, 0, -near).
+                        depth = DepthRange::ZeroToOne;
+                        projection = off_center_perspective(window, near, far, depth);
+                        let normal = DVec3::new(tx, ty, -1.0).normalize();
+                        let plane = normal.extend(-normal.dot(DVec3::new(0.0, 0.0, -near)));
+                        projection = oblique(projection, plane);
+                    }
+                    let axis = DVec3::new(ax, ay, az).try_normalize().unwrap_or(DVec3::Y);
+                    let camera = DMat4::from_rotation_translation(
+                        DQuat::from_axis_angle(axis, angle),
+                        DVec3::new(eye.0, eye.1, eye.2),
+                    );
+                    (projection * camera.inverse(), depth)
+                },
+            )
+    }
+
+    /// A random point around the frustum, as normalized device coordinates
+    /// (slightly beyond the visible range) for [`unproject`].
+    fn arb_ndc() -> impl Strategy<Value = DVec3> {
+        (-1.5..1.5f64, -1.5..1.5f64, -1.5..1.5f64).prop_map(|(x, y, z)| DVec3::new(x, y, z))
+    }
+
+    /// The world-space point at normalized device coordinates `ndc`, if it
+    /// is finite and within a sane distance.
+    fn unproject(vp: DMat4, ndc: DVec3) -> Option<DVec3> {
+        let point = vp.inverse().project_point3(ndc);
+        (point.is_finite() && point.length() < 1.0e5).then_some(point)
+    }
+
+    fn arb_obb_shape() -> impl Strategy<Value = (DVec3, DMat3)> {
+        (
+            (0.01..15.0f64, 0.01..15.0f64, 0.01..15.0f64),
+            (
+                -1.0..1.0f64,
+                -1.0..1.0f64,
+                -1.0..1.0f64,
+                0.0..std::f64::consts::TAU,
+            ),
+        )
+            .prop_map(|(extents, (ax, ay, az, angle))| {
+                let axis = DVec3::new(ax, ay, az).try_normalize().unwrap_or(DVec3::X);
+                (
+                    DVec3::new(extents.0, extents.1, extents.2),
+                    DMat3::from_quat(DQuat::from_axis_angle(axis, angle)),
+                )
+            })
+    }
+
+    /// The OBB's corners, centre and a lattice of interior points.
+    fn obb_samples(obb: &OrientedBoundingBox) -> impl Iterator<Item = DVec3> + '_ {
+        let steps = [-1.0, -0.5, 0.0, 0.5, 1.0];
+        steps.into_iter().flat_map(move |x| {
+            steps.into_iter().flat_map(move |y| {
+                steps.into_iter().map(move |z| {
+                    obb.center + obb.orientation * (obb.extents * DVec3::new(x, y, z))
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn test_frustum_zero_to_one_near_plane() {
+        // Symmetric 90° frustum looking down -Z, near 1, far 100.
+        let projection = off_center_perspective(
+            [-1.0, 1.0, -1.0, 1.0],
+            1.0,
+            Some(100.0),
+            DepthRange::ZeroToOne,
+        );
+        let frustum = Frustum::from_view_projection(projection, DepthRange::ZeroToOne);
+
+        assert!(frustum.contains_point(DVec3::new(0.0, 0.0, -1.5)));
+        assert!(!frustum.contains_point(DVec3::new(0.0, 0.0, -0.5)));
+        assert!(!frustum.contains_point(DVec3::new(0.0, 0.0, -101.0)));
+
+        // Reading the same matrix as OpenGL depth misplaces the near plane.
+        let gl = Frustum::from_matrix(projection);
+        assert!(gl.contains_point(DVec3::new(0.0, 0.0, -0.7)));
+    }
+
+    #[test]
+    fn test_frustum_infinite_far_plane() {
+        let projection =
+            off_center_perspective([-1.0, 1.0, -1.0, 1.0], 1.0, None, DepthRange::ZeroToOne);
+        let frustum = Frustum::from_view_projection(projection, DepthRange::ZeroToOne);
+        assert!(frustum.contains_point(DVec3::new(0.0, 0.0, -1.0e9)));
+    }
+
+    #[test]
+    fn test_frustum_off_center() {
+        // Window entirely right of the view axis, as one eye of a stereo pair.
+        let projection = off_center_perspective(
+            [0.2, 1.0, -0.5, 0.5],
+            1.0,
+            Some(100.0),
+            DepthRange::ZeroToOne,
+        );
+        let frustum = Frustum::from_view_projection(projection, DepthRange::ZeroToOne);
+        assert!(frustum.contains_point(DVec3::new(5.0, 0.0, -10.0)));
+        assert!(!frustum.contains_point(DVec3::new(0.0, 0.0, -10.0)));
+    }
+
+    proptest! {
+        /// Point containment agrees with clip-space bounds, away from the
+        /// boundary where rounding decides.
+        #[test]
+        fn frustum_contains_point_matches_clip_space(
+            (vp, depth) in arb_view_projection(),
+            ndc in arb_ndc(),
+        ) {
+            let point = unproject(vp, ndc);
+            prop_assume!(point.is_some());
+            let point = point.unwrap();
+            let frustum = Frustum::from_view_projection(vp, depth);
+            let margin = clip_margin(vp, depth, point);
+            prop_assume!(margin.abs() > 1e-6);
+            prop_assert_eq!(frustum.contains_point(point), margin > 0.0);
+        }
+
+        /// An OBB with any sampled point inside the frustum is never culled.
+        #[test]
+        fn frustum_never_culls_visible_obb(
+            (vp, depth) in arb_view_projection(),
+            ndc in arb_ndc(),
+            (extents, orientation) in arb_obb_shape(),
+        ) {
+            let center = unproject(vp, ndc);
+            prop_assume!(center.is_some());
+            let obb = OrientedBoundingBox { center: center.unwrap(), extents, orientation };
+            let frustum = Frustum::from_view_projection(vp, depth);
+            if obb_samples(&obb).any(|p| clip_margin(vp, depth, p) > 1e-9) {
+                prop_assert!(frustum.intersects_obb(&obb));
+            }
+        }
+
+        /// An OBB entirely outside one frustum plane is culled.
+        #[test]
+        fn frustum_culls_obb_outside_a_plane(
+            (vp, depth) in arb_view_projection(),
+            ndc in arb_ndc(),
+            (extents, orientation) in arb_obb_shape(),
+        ) {
+            let center = unproject(vp, ndc);
+            prop_assume!(center.is_some());
+            let obb = OrientedBoundingBox { center: center.unwrap(), extents, orientation };
+            let frustum = Frustum::from_view_projection(vp, depth);
+            // The samples include the corners, so this is the whole box.
+            let outside_one_plane = frustum.planes.iter().any(|&(normal, distance)| {
+                normal != DVec3::ZERO
+                    && obb_samples(&obb).all(|p| normal.dot(p) + distance < -1e-9)
+            });
+            if outside_one_plane {
+                prop_assert!(!frustum.intersects_obb(&obb));
+            }
+        }
+    }
 }
@@ -0,0 +1,270 @@
+//! Bakes the shallowest levels of the rocktree imagery into the
+//! equirectangular base map the proxy globe shows beneath the streamed
+//! tiles.
+//!
+//! Pipeline:
+//!
+//! 1. Fetch the planetoid and root bulk metadata through the same
+//!    on-disk tile cache the client uses, so nodes the client (or an
+//!    earlier bake) already downloaded aren't fetched again.
+//! 2. If `base_map.json` beside the output records the same root epoch,
+//!    depth and width, and the PNG exists, stop: the map is up to date.
+//!    `--force` bakes anyway.
+//! 3. Walk the bulks breadth-first, collecting every node with data down
+//!    to `--depth` (default 5; the root bulk alone reaches depth 4).
+//! 4. Fetch and decode the nodes a level at a time, a bounded number in
+//!    flight, and paint each into the map as it arrives. Shallower levels
+//!    are painted first, so finer imagery lands on top.
+//! 5. Write an RGB PNG `--width` texels wide (default 4096; the height is
+//!    half) to `engine_assets/world/base_map.png`, which the runtime loads
+//!    as `engine/world/base_map.png`, plus the `base_map.json` record.
+//!
+//! Anything no node covers keeps the deep-ocean fill colour.
+//!
+//! Run: `cargo run --release -p bake-base-map [-- --depth N --width N --force]`.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs::{File, create_dir_all},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use rocktree::{BulkRequest, Client, FilesystemCache, NodeRequest};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use veldera_terrain::base_map::BaseMap;
+
+const OUTPUT_RELATIVE: &str = "engine_assets/world/base_map.png";
+const RECORD_RELATIVE: &str = "engine_assets/world/base_map.json";
+
+const DEFAULT_DEPTH: usize = 5;
+/// Depth 7 is already a few hundred thousand nodes.
+const MAX_DEPTH: usize = 7;
+const DEFAULT_WIDTH: u32 = 4096;
+/// Node fetches in flight at once.
+const CONCURRENCY: usize = 32;
+/// sRGB colour of anything no node covers: the proxy globe's default
+/// fill, a deep ocean blue.
+const FILL_COLOR: [u8; 3] = [26, 48, 69];
+
+/// What a bake was made from, written beside the map so unchanged
+/// reruns can skip the bake.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct BakeRecord {
+    root_epoch: u32,
+    depth: usize,
+    width: u32,
+    nodes: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let mut depth = DEFAULT_DEPTH;
+    let mut width = DEFAULT_WIDTH;
+    let mut force = false;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--depth" => {
+                depth = args.get(i + 1).ok_or("--depth needs a value")?.parse()?;
+                i += 2;
+            }
+            "--width" => {
+                width = args.get(i + 1).ok_or("--width needs a value")?.parse()?;
+                i += 2;
+            }
+            "--force" => {
+                force = true;
+                i += 1;
+            }
+            other => return Err(format!("unknown argument '{other}'").into()),
+        }
+    }
+    if !(1..=MAX_DEPTH).contains(&depth) {
+        return Err(format!("--depth must be between 1 and {MAX_DEPTH}").into());
+    }
+
+    let project_root = find_workspace_root()?;
+    let output_path = project_root.join(OUTPUT_RELATIVE);
+    let record_path = project_root.join(RECORD_RELATIVE);
+    if let Some(parent) = output_path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    let cache = FilesystemCache::veldera().unwrap_or_else(|| {
+        FilesystemCache::new(std::env::temp_dir().join("veldera").join("rocktree"))
+    });
+    let client = Arc::new(Client::builder().cache(cache).build());
+
+    println!("Fetching planetoid and root bulk...");
+    let planetoid = client.fetch_planetoid().await?;
+    let root = BulkRequest::root(planetoid.root_epoch);
+
+    let map = BaseMap::filled(width, FILL_COLOR);
+    let previous: Option<BakeRecord> = std::fs::read_to_string(&record_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok());
+    if !force
+        && output_path.exists()
+        && previous.as_ref().is_some_and(|record| {
+            record.root_epoch == planetoid.root_epoch
+                && record.depth == depth
+                && record.width == map.width()
+        })
+    {
+        println!(
+            "{} is up to date (root epoch {}); pass --force to bake anyway.",
+            output_path.display(),
+            planetoid.root_epoch
+        );
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    println!("Walking bulks down to depth {depth}...");
+    let levels = collect_nodes(&client, root, depth).await;
+    let total: usize = levels.iter().map(Vec::len).sum();
+    println!("  {total} nodes in {:.1}s", start.elapsed().as_secs_f32());
+
+    let start = Instant::now();
+    let (map, baked, failed) = paint_levels(&client, levels, map).await;
+    println!(
+        "Painted {baked} nodes ({failed} failed) in {:.1}s",
+        start.elapsed().as_secs_f32()
+    );
+
+    println!("Writing {}", output_path.display());
+    let rgb: Vec<u8> = map
+        .pixels()
+        .chunks_exact(4)
+        .flat_map(|texel| [texel[0], texel[1], texel[2]])
+        .collect();
+    write_rgb_png(&output_path, map.width(), map.height(), &rgb)?;
+
+    let record = BakeRecord {
+        root_epoch: planetoid.root_epoch,
+        depth,
+        width: map.width(),
+        nodes: baked,
+    };
+    std::fs::write(&record_path, serde_json::to_string_pretty(&record)?)?;
+
+    println!("Done.");
+    Ok(())
+}
+
+/// Walk the bulks breadth-first from `root`, returning the requests for
+/// every node with data down to `max_depth`, grouped by depth (index 0 is
+/// depth 1).
+async fn collect_nodes(
+    client: &Client<FilesystemCache>,
+    root: BulkRequest,
+    max_depth: usize,
+) -> Vec<Vec<NodeRequest>> {
+    let mut levels: Vec<Vec<NodeRequest>> = (0..max_depth).map(|_| Vec::new()).collect();
+    let mut queue = VecDeque::from([root]);
+    while let Some(request) = queue.pop_front() {
+        let bulk = match client.fetch_bulk(&request).await {
+            Ok(bulk) => bulk,
+            Err(e) => {
+                eprintln!("  bulk '{}' failed: {e}", request.path);
+                continue;
+            }
+        };
+        for node in &bulk.nodes {
+            let node_depth = node.path.depth();
+            if node.has_data && (1..=max_depth).contains(&node_depth) {
+                levels[node_depth - 1].push(NodeRequest::new(
+                    node.path,
+                    node.epoch,
+                    node.texture_format,
+                    node.imagery_epoch,
+                ));
+            }
+        }
+        for (&relative, &epoch) in &bulk.child_bulk_paths {
+            let path = bulk.path.extend(relative);
+            // A child bulk holds nodes below its own path's depth.
+            if path.depth() < max_depth {
+                queue.push_back(BulkRequest::new(path, epoch));
+            }
+        }
+    }
+    levels
+}
+
+/// Fetch each level's nodes, [`CONCURRENCY`] at a time, and paint them
+/// into `map` as they arrive, level by level. Returns the map and the
+/// painted and failed node counts.
+async fn paint_levels(
+    client: &Arc<Client<FilesystemCache>>,
+    levels: Vec<Vec<NodeRequest>>,
+    mut map: BaseMap,
+) -> (BaseMap, usize, usize) {
+    let (mut baked, mut failed) = (0, 0);
+    for (index, level) in levels.into_iter().enumerate() {
+        let count = level.len();
+        let mut pending = level.into_iter();
+        let mut in_flight = JoinSet::new();
+        loop {
+            while in_flight.len() < CONCURRENCY
+                && let Some(request) = pending.next()
+            {
+                let client = Arc::clone(client);
+                in_flight.spawn(async move {
+                    let result = client.fetch_node(&request).await;
+                    (request, result)
+                });
+            }
+            let Some(joined) = in_flight.join_next().await else {
+                break;
+            };
+            match joined {
+                Ok((_, Ok(node))) => {
+                    map.paint_node(&node);
+                    baked += 1;
+                }
+                Ok((request, Err(e))) => {
+                    eprintln!("  node '{}' failed: {e}", request.path);
+                    failed += 1;
+                }
+                Err(e) => {
+                    eprintln!("  node task failed: {e}");
+                    failed += 1;
+                }
+            }
+        }
+        println!("  depth {}: {count} nodes", index + 1);
+    }
+    (map, baked, failed)
+}
+
+/// Walk up from this crate's manifest dir to find the workspace root —
+/// the directory whose `Cargo.toml` contains `[workspace]`.
+fn find_workspace_root() -> Result<PathBuf, Box<dyn Error>> {
+    let start = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    for candidate in start.ancestors() {
+        let manifest = candidate.join("Cargo.toml");
+        if let Ok(text) = std::fs::read_to_string(&manifest)
+            && text.contains("[workspace]")
+        {
+            return Ok(candidate.to_path_buf());
+        }
+    }
+    Err("could not locate workspace root from this binary's manifest dir".into())
+}
+
+fn write_rgb_png(path: &Path, w: u32, h: u32, data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, w, h);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(data)?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Stored maneuver results and the comparison that flags regressions.
+//!
+//! Baselines are JSON keyed vehicle slug → maneuver → metric, so a diff of the
+//! file after `--update-baseline` reads as a per-number changelog.
+
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+use crate::maneuver::Metrics;
+
+/// Results for every vehicle and maneuver: slug → maneuver name → metrics.
+pub type Results = BTreeMap<String, BTreeMap<String, Metrics>>;
+
+/// A metric that moved beyond tolerance (or disappeared) relative to the
+/// baseline.
+pub struct Deviation {
+    pub vehicle: String,
+    pub maneuver: String,
+    pub metric: String,
+    pub baseline: f64,
+    /// `None` if the current run no longer reports the metric.
+    pub current: Option<f64>,
+}
+
+/// Read the baselines at `path`; a missing file is an empty baseline.
+pub fn load(path: &Path) -> Result<Results, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(Results::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Merge `current` into the baselines at `path`, replacing the maneuvers that
+/// were run and keeping everything else. Values are stored to four decimal
+/// places, far below any tolerance, so the file doesn't carry `f32` noise.
+pub fn update(path: &Path, current: &Results) -> Result<(), Box<dyn Error>> {
+    let mut baseline = load(path)?;
+    for (vehicle, maneuvers) in current {
+        let stored = baseline.entry(vehicle.clone()).or_default();
+        for (maneuver, metrics) in maneuvers {
+            let rounded = metrics
+                .iter()
+                .map(|(metric, value)| (metric.clone(), (value * 1e4).round() / 1e4))
+                .collect();
+            stored.insert(maneuver.clone(), rounded);
+        }
+    }
+    let mut json = serde_json::to_string_pretty(&baseline)?;
+    json.push('\n');
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Compare `current` against `baseline`. A metric passes while it stays within
+/// `tolerance` of the baseline value, relative to its magnitude but never
+/// tighter than `tolerance` absolute (so near-zero metrics don't demand exact
+/// equality). Maneuvers without a baseline are skipped.
+pub fn compare(baseline: &Results, current: &Results, tolerance: f64) -> Vec<Deviation> {
+    let mut deviations = Vec::new();
+    for (vehicle, maneuvers) in current {
+        for (maneuver, metrics) in maneuvers {
+            let Some(expected) = baseline.get(vehicle).and_then(|b| b.get(maneuver)) else {
+                continue;
+            };
+            for (metric, &baseline_value) in expected {
+                let current_value = metrics.get(metric).copied();
+                let within = current_value.is_some_and(|value| {
+                    (value - baseline_value).abs() <= tolerance * baseline_value.abs().max(1.0)
+                });
+                if !within {
+                    deviations.push(Deviation {
+                        vehicle: vehicle.clone(),
+                        maneuver: maneuver.clone(),
+                        metric: metric.clone(),
+                        baseline: baseline_value,
+                        current: current_value,
+                    });
+                }
+            }
+        }
+    }
+    deviations
+}
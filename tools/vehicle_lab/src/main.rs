@@ -0,0 +1,232 @@
+//! Offline scenario runner for vehicle tuning and physics regression checks.
+//!
+//! Loads vehicle definitions (`.scn.ron`, plus the car glb they reference)
+//! exactly as the game does, drives each through scripted maneuvers on flat
+//! ground with the game's own physics core, and reports a few metrics per
+//! maneuver: 0–100 km/h time, slalom path tracking and body roll, drop-test
+//! suspension loads and settling. Results are compared against stored
+//! baselines, so a change to `veldera_game_vehicle::core` or to a vehicle's
+//! tuning that shifts any number beyond tolerance fails the run.
+//!
+//! ```text
+//! vehicle-lab <vehicle.scn.ron>... [--assets <dir>] [--maneuver <name>]...
+//!             [--csv <dir>] [--baseline <file>] [--update-baseline]
+//!             [--tolerance <fraction>]
+//! ```
+//!
+//! Maneuvers are `accel_0_100`, `slalom`, and `drop`; all run by default.
+//! `--assets` is the asset root the scenes' model paths resolve against
+//! (default `client/veldera/assets`, for running from the workspace root);
+//! gravity is read from the engine's `physics.toml` under it too.
+//! `--csv` writes every step of every run as `<dir>/<vehicle>_<maneuver>.csv`,
+//! in the in-game telemetry format (`client/vehicle/analyze_telemetry.py`
+//! reads it). `--baseline` picks the baseline file (default
+//! `tools/vehicle_lab/baselines.json`); `--update-baseline` records this run
+//! into it instead of comparing. `--tolerance` is the allowed relative drift
+//! per metric (default 0.02).
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use veldera_game_vehicle::telemetry::TelemetryOutput;
+
+use baseline::Results;
+use maneuver::Maneuver;
+use rig::Environment;
+
+mod baseline;
+mod maneuver;
+mod model;
+mod rig;
+mod scene;
+
+/// Asset root the scenes' model paths resolve against, from the workspace root.
+const DEFAULT_ASSETS: &str = "client/veldera/assets";
+
+/// Baseline file, from the workspace root.
+const DEFAULT_BASELINE: &str = "tools/vehicle_lab/baselines.json";
+
+/// Allowed relative drift per metric.
+const DEFAULT_TOLERANCE: f64 = 0.02;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let mut scenes: Vec<PathBuf> = Vec::new();
+    let mut assets = PathBuf::from(DEFAULT_ASSETS);
+    let mut maneuvers: Vec<Maneuver> = Vec::new();
+    let mut csv_dir: Option<PathBuf> = None;
+    let mut baseline_path = PathBuf::from(DEFAULT_BASELINE);
+    let mut update_baseline = false;
+    let mut tolerance = DEFAULT_TOLERANCE;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--assets" => {
+                assets = args.get(i + 1).ok_or("--assets needs a directory")?.into();
+                i += 2;
+            }
+            "--maneuver" => {
+                let name = args.get(i + 1).ok_or("--maneuver needs a name")?;
+                maneuvers.push(Maneuver::from_name(name).ok_or_else(|| {
+                    let names: Vec<_> = Maneuver::ALL.iter().map(|m| m.name()).collect();
+                    format!("unknown maneuver '{name}' (expected one of {names:?})")
+                })?);
+                i += 2;
+            }
+            "--csv" => {
+                csv_dir = Some(args.get(i + 1).ok_or("--csv needs a directory")?.into());
+                i += 2;
+            }
+            "--baseline" => {
+                baseline_path = args.get(i + 1).ok_or("--baseline needs a file")?.into();
+                i += 2;
+            }
+            "--update-baseline" => {
+                update_baseline = true;
+                i += 1;
+            }
+            "--tolerance" => {
+                tolerance = args
+                    .get(i + 1)
+                    .ok_or("--tolerance needs a value")?
+                    .parse()?;
+                i += 2;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {flag}").into()),
+            scene => {
+                scenes.push(scene.into());
+                i += 1;
+            }
+        }
+    }
+    if scenes.is_empty() {
+        eprintln!(
+            "usage: vehicle-lab <vehicle.scn.ron>... [--assets <dir>] [--maneuver <name>]... \
+             [--csv <dir>] [--baseline <file>] [--update-baseline] [--tolerance <fraction>]"
+        );
+        std::process::exit(2);
+    }
+    if maneuvers.is_empty() {
+        maneuvers = Maneuver::ALL.to_vec();
+    }
+    if let Some(dir) = &csv_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let env = Environment::load(&assets)?;
+    let mut results = Results::new();
+    for scene_path in &scenes {
+        let vehicle = scene::load_vehicle(scene_path, &assets)?;
+        println!(
+            "{} ({}): {:.0} kg, wheel radius {:.3} m",
+            vehicle.name, vehicle.slug, vehicle.params.mass, vehicle.wheels[0].radius
+        );
+        for &maneuver in &maneuvers {
+            let metrics = match &csv_dir {
+                Some(dir) => {
+                    let path = dir.join(format!("{}_{}.csv", vehicle.slug, maneuver.name()));
+                    let mut csv = CsvOutput::create(&path)?;
+                    let metrics = maneuver.run(&vehicle, env, &mut csv);
+                    csv.finish()?;
+                    metrics
+                }
+                None => maneuver.run(&vehicle, env, &mut DiscardOutput),
+            };
+            println!("  {}", maneuver.name());
+            for (metric, value) in &metrics {
+                println!("    {metric:<24} {value:>10.3}");
+            }
+            results
+                .entry(vehicle.slug.clone())
+                .or_default()
+                .insert(maneuver.name().to_string(), metrics);
+        }
+    }
+
+    if update_baseline {
+        baseline::update(&baseline_path, &results)?;
+        println!("baseline updated: {}", baseline_path.display());
+        return Ok(());
+    }
+
+    let baseline = baseline::load(&baseline_path)?;
+    let deviations = baseline::compare(&baseline, &results, tolerance);
+    if deviations.is_empty() {
+        println!(
+            "all metrics within {:.1}% of {}",
+            tolerance * 100.0,
+            baseline_path.display()
+        );
+        return Ok(());
+    }
+    println!(
+        "\n{} metric(s) drifted from the baseline:",
+        deviations.len()
+    );
+    for deviation in &deviations {
+        let current = deviation
+            .current
+            .map_or_else(|| "missing".to_string(), |value| format!("{value:.3}"));
+        println!(
+            "  {}/{}/{}: {:.3} -> {current}",
+            deviation.vehicle, deviation.maneuver, deviation.metric, deviation.baseline
+        );
+    }
+    std::process::exit(1);
+}
+
+/// Buffered CSV telemetry file. The game's `FileTelemetryOutput` reopens the
+/// file per row, which is fine at 64 rows a second of play but slow for a
+/// batch of runs.
+struct CsvOutput {
+    writer: BufWriter<File>,
+    error: Option<std::io::Error>,
+}
+
+impl CsvOutput {
+    fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            error: None,
+        })
+    }
+
+    /// Flush, surfacing the first write error.
+    fn finish(mut self) -> std::io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.error.is_none()
+            && let Err(error) = writeln!(self.writer, "{line}")
+        {
+            self.error = Some(error);
+        }
+    }
+}
+
+impl TelemetryOutput for CsvOutput {
+    fn write_header(&mut self, header: &str) {
+        self.write_line(header);
+    }
+
+    fn write_row(&mut self, row: &str) {
+        self.write_line(row);
+    }
+}
+
+/// Telemetry sink for runs without `--csv`.
+struct DiscardOutput;
+
+impl TelemetryOutput for DiscardOutput {
+    fn write_header(&mut self, _header: &str) {}
+
+    fn write_row(&mut self, _row: &str) {}
+}
@@ -0,0 +1,331 @@
+//! Scripted test maneuvers and the metrics each one reports.
+//!
+//! Every maneuver drives a fresh [`Rig`] with a closed-loop script, streams
+//! each step to a telemetry output, and condenses the run into a handful of
+//! named numbers. The simulation is deterministic, so any change in a metric
+//! is a change in the physics (or the vehicle's tuning).
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use veldera_game_vehicle::{
+    core::CarInput,
+    telemetry::{self, TelemetryOutput},
+};
+
+use crate::{
+    model::LabVehicle,
+    rig::{Environment, Rig},
+};
+
+/// Named results of one maneuver.
+pub type Metrics = BTreeMap<String, f64>;
+
+/// Ride height the flat-ground maneuvers start from (m): just above the rest
+/// pose, so the car drops onto its springs.
+const START_HEIGHT: f32 = 0.05;
+
+/// Time the car is left to settle on its springs before a maneuver (s).
+const SETTLE_TIME: f32 = 2.0;
+
+/// 100 km/h in m/s.
+const SPEED_100_KMH: f32 = 100.0 / 3.6;
+
+/// Give up on reaching 100 km/h after this long at full throttle (s).
+const ACCEL_TIMEOUT: f32 = 30.0;
+
+/// Slalom entry speed (m/s), held by a proportional pedal.
+const SLALOM_SPEED: f32 = 60.0 / 3.6;
+/// Straight run-up before the first cone (m).
+const SLALOM_RUN_UP: f32 = 120.0;
+/// Cone spacing (m).
+const SLALOM_SPACING: f32 = 18.0;
+/// Number of cones.
+const SLALOM_CONES: usize = 6;
+/// Lateral offset of the path at each cone (m).
+const SLALOM_AMPLITUDE: f32 = 1.75;
+/// Pure-pursuit lookahead along the path (m).
+const SLALOM_LOOKAHEAD: f32 = 8.0;
+/// Abandon a slalom that hasn't finished after this long (s).
+const SLALOM_TIMEOUT: f32 = 60.0;
+
+/// Height of the chassis origin for the drop test (m).
+const DROP_HEIGHT: f32 = 1.0;
+/// How long the drop test runs after release (s).
+const DROP_DURATION: f32 = 5.0;
+/// Vertical speed below which the dropped car counts as settled (m/s).
+const DROP_SETTLED_SPEED: f32 = 0.05;
+
+/// A scripted maneuver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Maneuver {
+    /// Full throttle from rest until 100 km/h.
+    Accel,
+    /// Weave through a line of cones at 60 km/h under a path-following driver.
+    Slalom,
+    /// Release the car from a metre up and watch it land and settle.
+    Drop,
+}
+
+impl Maneuver {
+    /// Every maneuver, in report order.
+    pub const ALL: [Self; 3] = [Self::Accel, Self::Slalom, Self::Drop];
+
+    /// Stable name, used on the command line, in CSV file names, and as the
+    /// baseline key.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Accel => "accel_0_100",
+            Self::Slalom => "slalom",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Look up a maneuver by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|maneuver| maneuver.name() == name)
+    }
+
+    /// Run the maneuver on `vehicle` in `env`, writing every step to
+    /// `output`.
+    pub fn run(
+        self,
+        vehicle: &LabVehicle,
+        env: Environment,
+        output: &mut dyn TelemetryOutput,
+    ) -> Metrics {
+        telemetry::reset_telemetry_to(output);
+        match self {
+            Self::Accel => accel(vehicle, env, output),
+            Self::Slalom => slalom(vehicle, env, output),
+            Self::Drop => drop_test(vehicle, env, output),
+        }
+    }
+}
+
+/// Step the rig with `input` for `seconds`.
+fn hold(rig: &mut Rig, input: CarInput, seconds: f32, output: &mut dyn TelemetryOutput) {
+    for _ in 0..(seconds / rig.env.dt).round() as usize {
+        telemetry::emit_telemetry_to(&rig.step(input), output);
+    }
+}
+
+/// Full throttle from a standstill: time and distance to 100 km/h, and the
+/// number of upshifts on the way.
+fn accel(vehicle: &LabVehicle, env: Environment, output: &mut dyn TelemetryOutput) -> Metrics {
+    let mut rig = Rig::new(vehicle, env, START_HEIGHT);
+    hold(&mut rig, CarInput::default(), SETTLE_TIME, output);
+
+    let start = rig.position;
+    let start_time = rig.elapsed;
+    let mut upshifts = 0;
+    let mut peak_rpm: f32 = 0.0;
+    let full_throttle = CarInput {
+        drive: 1.0,
+        ..Default::default()
+    };
+    while rig.last.forward_speed < SPEED_100_KMH && rig.elapsed - start_time < ACCEL_TIMEOUT {
+        let gear = rig.state.gear;
+        telemetry::emit_telemetry_to(&rig.step(full_throttle), output);
+        if rig.state.gear > gear {
+            upshifts += 1;
+        }
+        peak_rpm = peak_rpm.max(rig.state.rpm);
+    }
+
+    let reached = rig.last.forward_speed >= SPEED_100_KMH;
+    Metrics::from([
+        ("reached_100_kmh".into(), f64::from(u8::from(reached))),
+        ("time_0_100_s".into(), f64::from(rig.elapsed - start_time)),
+        (
+            "distance_0_100_m".into(),
+            f64::from(rig.position.distance(start)),
+        ),
+        ("upshifts".into(), f64::from(upshifts)),
+        ("peak_rpm".into(), f64::from(peak_rpm)),
+    ])
+}
+
+/// Lateral offset of the slalom path `s` metres down the track (+X is right).
+fn slalom_path(s: f32) -> f32 {
+    let along = s - SLALOM_RUN_UP;
+    let end = SLALOM_SPACING * (SLALOM_CONES as f32 + 1.0);
+    if along <= 0.0 || along >= end {
+        0.0
+    } else {
+        SLALOM_AMPLITUDE * (std::f32::consts::PI * along / SLALOM_SPACING).sin()
+    }
+}
+
+/// Cone weave at 60 km/h: how closely a pure-pursuit driver can hold the
+/// path, and how hard the chassis works doing it.
+fn slalom(vehicle: &LabVehicle, env: Environment, output: &mut dyn TelemetryOutput) -> Metrics {
+    let mut rig = Rig::new(vehicle, env, START_HEIGHT);
+    hold(&mut rig, CarInput::default(), SETTLE_TIME, output);
+
+    let max_steer = vehicle.params.max_steer_angle.max(1e-3);
+    let section = SLALOM_RUN_UP..SLALOM_RUN_UP + SLALOM_SPACING * (SLALOM_CONES as f32 + 1.0);
+    let start_time = rig.elapsed;
+    let mut max_error: f32 = 0.0;
+    let mut min_speed = f32::INFINITY;
+    let mut peak_lateral_accel: f32 = 0.0;
+    let mut peak_roll: f32 = 0.0;
+    let mut peak_saturation: f32 = 0.0;
+    let mut completed = false;
+
+    while rig.elapsed - start_time < SLALOM_TIMEOUT {
+        // The track runs down -Z from the origin.
+        let s = -rig.position.z;
+        if s >= section.end {
+            completed = true;
+            break;
+        }
+
+        // Pure pursuit: steer toward the path point a lookahead ahead, at
+        // the angle it makes with the chassis forward axis.
+        let target_s = s + SLALOM_LOOKAHEAD;
+        let target = Vec3::new(slalom_path(target_s), 0.0, -target_s);
+        let to_target = target - rig.position;
+        let bearing = to_target
+            .dot(rig.right())
+            .atan2(to_target.dot(rig.forward()));
+        let input = CarInput {
+            drive: ((SLALOM_SPEED - rig.last.forward_speed) * 0.5).clamp(-1.0, 1.0),
+            steer: (bearing / max_steer).clamp(-1.0, 1.0),
+            handbrake: false,
+        };
+
+        let velocity = rig.velocity;
+        telemetry::emit_telemetry_to(&rig.step(input), output);
+
+        if section.contains(&s) {
+            max_error = max_error.max((rig.position.x - slalom_path(s)).abs());
+            min_speed = min_speed.min(rig.last.speed);
+            let lateral_accel = ((rig.velocity - velocity) / env.dt).dot(rig.right());
+            peak_lateral_accel = peak_lateral_accel.max(lateral_accel.abs());
+            peak_roll = peak_roll.max(rig.right().y.clamp(-1.0, 1.0).asin().abs());
+            for wheel in &rig.last.wheels {
+                peak_saturation = peak_saturation.max(wheel.saturation);
+            }
+        }
+    }
+
+    Metrics::from([
+        ("completed".into(), f64::from(u8::from(completed))),
+        ("max_tracking_error_m".into(), f64::from(max_error)),
+        (
+            "min_speed_kmh".into(),
+            f64::from(if min_speed.is_finite() {
+                min_speed * 3.6
+            } else {
+                0.0
+            }),
+        ),
+        (
+            "peak_lateral_accel_g".into(),
+            f64::from(peak_lateral_accel / env.gravity),
+        ),
+        ("peak_roll_deg".into(), f64::from(peak_roll.to_degrees())),
+        ("peak_tire_saturation".into(), f64::from(peak_saturation)),
+    ])
+}
+
+/// Drop from a metre up with no input: peak suspension load, whether the
+/// springs bottom out, and how long the body takes to settle.
+fn drop_test(vehicle: &LabVehicle, env: Environment, output: &mut dyn TelemetryOutput) -> Metrics {
+    let mut rig = Rig::new(vehicle, env, DROP_HEIGHT);
+
+    let mut peak_load: f32 = 0.0;
+    let mut peak_compression: f32 = 0.0;
+    let mut bottomed_steps = 0;
+    let mut rebounds = 0;
+    let mut settled_at = 0.0;
+    let mut was_rising = false;
+    for _ in 0..(DROP_DURATION / env.dt).round() as usize {
+        telemetry::emit_telemetry_to(&rig.step(CarInput::default()), output);
+        for wheel in &rig.last.wheels {
+            peak_load = peak_load.max(wheel.suspension_force);
+            peak_compression = peak_compression.max(wheel.compression);
+        }
+        if rig
+            .last
+            .wheels
+            .iter()
+            .any(|wheel| wheel.compression >= 0.999)
+        {
+            bottomed_steps += 1;
+        }
+        let vertical = rig.velocity.y;
+        let rising = vertical > DROP_SETTLED_SPEED;
+        if rising && !was_rising {
+            rebounds += 1;
+        }
+        was_rising = rising;
+        if vertical.abs() > DROP_SETTLED_SPEED || rig.angular_velocity.length() > 0.05 {
+            settled_at = rig.elapsed;
+        }
+    }
+
+    let static_load = vehicle.params.mass * env.gravity / 4.0;
+    Metrics::from([
+        ("peak_load_ratio".into(), f64::from(peak_load / static_load)),
+        ("peak_compression".into(), f64::from(peak_compression)),
+        (
+            "bottomed_out_s".into(),
+            f64::from(bottomed_steps as f32 * env.dt),
+        ),
+        ("rebounds".into(), f64::from(rebounds)),
+        ("settle_time_s".into(), f64::from(settled_at)),
+        ("rest_height_m".into(), f64::from(rig.position.y)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::scene;
+
+    /// Counts the telemetry rows, discarding them.
+    #[derive(Default)]
+    struct RowCount(usize);
+
+    impl TelemetryOutput for RowCount {
+        fn write_header(&mut self, _header: &str) {}
+
+        fn write_row(&mut self, _row: &str) {
+            self.0 += 1;
+        }
+    }
+
+    /// The game's asset root, from this crate.
+    fn assets() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../client/veldera/assets")
+    }
+
+    #[test]
+    fn drop_test_settles_within_bounds() {
+        let assets = assets();
+        let env = Environment::load(&assets).unwrap();
+        let vehicle =
+            scene::load_vehicle(&assets.join("game/vehicles/sedan.scn.ron"), &assets).unwrap();
+
+        let mut rows = RowCount::default();
+        let metrics = Maneuver::Drop.run(&vehicle, env, &mut rows);
+        assert_eq!(rows.0, (DROP_DURATION / env.dt).round() as usize);
+
+        // Lands, rebounds at most once, and comes to rest on its springs
+        // well before the run ends.
+        assert!(metrics["settle_time_s"] < f64::from(DROP_DURATION) / 2.0);
+        assert!(metrics["rebounds"] <= 1.0);
+        assert!(metrics["rest_height_m"].abs() < 0.05);
+        assert!((1.0..10.0).contains(&metrics["peak_load_ratio"]));
+
+        // The same inputs give the same numbers.
+        let again = Maneuver::Drop.run(&vehicle, env, &mut RowCount::default());
+        assert_eq!(again, metrics);
+    }
+}
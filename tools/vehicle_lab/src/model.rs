@@ -0,0 +1,133 @@
+//! The simulated vehicle: core parameters plus the geometry the game measures
+//! from the car glb once its scene loads.
+//!
+//! Mirrors `visuals::on_vehicle_model_ready` in `veldera_game_vehicle`: wheel
+//! rest positions are the `wheel_*` node translations, wheel radii are half the
+//! wheel meshes' vertical extent, and the angular inertia is a box over the
+//! `body` meshes' bounds. Mesh bounds come straight from the glTF accessor
+//! min/max, which the split tool always writes.
+
+use std::{error::Error, path::Path};
+
+use glam::Vec3;
+use veldera_game_vehicle::core::{CarParams, WheelParams, box_inertia};
+
+/// Wheel node names in fl, fr, rl, rr order (see `tools/split_car_pack`).
+const WHEEL_NODE_NAMES: [&str; 4] = ["wheel_fl", "wheel_fr", "wheel_rl", "wheel_rr"];
+
+/// Name of the chassis body node.
+const BODY_NODE_NAME: &str = "body";
+
+/// Everything the rig needs to simulate one vehicle.
+#[derive(Clone, Debug)]
+pub struct LabVehicle {
+    /// Display name from the scene's `Vehicle` component.
+    pub name: String,
+    /// File-name slug (`sedan` for `sedan.scn.ron`), used for CSV names.
+    pub slug: String,
+    /// Flattened per-vehicle tuning.
+    pub params: CarParams,
+    /// Wheel geometry in fl, fr, rl, rr order.
+    pub wheels: [WheelParams; 4],
+    /// Centre of mass in chassis space (m).
+    pub center_of_mass: Vec3,
+    /// Principal angular inertia (kg·m²).
+    pub inertia: Vec3,
+}
+
+/// Geometry measured from a car glb.
+pub struct ModelGeometry {
+    /// Wheel geometry in fl, fr, rl, rr order.
+    pub wheels: [WheelParams; 4],
+    /// Full extents of the body meshes (m).
+    pub body_size: Vec3,
+}
+
+impl ModelGeometry {
+    /// Measure the car glb at `path`, scaled by `scale`. `front_driven` and
+    /// `rear_driven` come from the transmission's drive layout.
+    pub fn load(
+        path: &Path,
+        scale: f32,
+        front_driven: bool,
+        rear_driven: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let gltf = gltf::Gltf::open(path)?;
+        let find_named = |target: &str| {
+            gltf.document
+                .nodes()
+                .find(|node| node.name() == Some(target))
+                .ok_or_else(|| format!("{} has no '{target}' node", path.display()))
+        };
+
+        let mut wheels = [WheelParams {
+            rest_position: Vec3::ZERO,
+            radius: 0.0,
+            steered: false,
+            driven: false,
+            handbraked: false,
+        }; 4];
+        for (slot, node_name) in WHEEL_NODE_NAMES.iter().enumerate() {
+            let node = find_named(node_name)?;
+            let (min, max) =
+                subtree_bounds(&node).ok_or_else(|| format!("wheel '{node_name}' has no mesh"))?;
+            let (translation, _, _) = node.transform().decomposed();
+            let front = slot < 2;
+            wheels[slot] = WheelParams {
+                rest_position: Vec3::from_array(translation) * scale,
+                radius: (max.y - min.y) * 0.5 * scale,
+                steered: front,
+                driven: if front { front_driven } else { rear_driven },
+                handbraked: !front,
+            };
+        }
+
+        let body = find_named(BODY_NODE_NAME)?;
+        let (body_min, body_max) = subtree_bounds(&body).ok_or("vehicle body has no meshes")?;
+
+        Ok(Self {
+            wheels,
+            body_size: (body_max - body_min) * scale,
+        })
+    }
+}
+
+impl LabVehicle {
+    /// Assemble a vehicle from its tuning and measured geometry.
+    pub fn new(
+        name: String,
+        slug: String,
+        params: CarParams,
+        center_of_mass: Vec3,
+        geometry: ModelGeometry,
+    ) -> Self {
+        let inertia = box_inertia(params.mass, geometry.body_size);
+        Self {
+            name,
+            slug,
+            params,
+            wheels: geometry.wheels,
+            center_of_mass,
+            inertia,
+        }
+    }
+}
+
+/// Union of the mesh bounds in a node's subtree, in the node's local space
+/// (the split tool keeps mesh data unscaled within nodes).
+fn subtree_bounds(node: &gltf::Node) -> Option<(Vec3, Vec3)> {
+    let mut min = Vec3::splat(f32::INFINITY);
+    let mut max = Vec3::splat(f32::NEG_INFINITY);
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                let bounds = primitive.bounding_box();
+                min = min.min(Vec3::from_array(bounds.min));
+                max = max.max(Vec3::from_array(bounds.max));
+            }
+        }
+        stack.extend(node.children());
+    }
+    min.cmple(max).all().then_some((min, max))
+}
@@ -0,0 +1,189 @@
+//! A minimal rigid-body integrator around [`core::step_car`] over [`Flat`]
+//! ground at y = 0, standing in for Avian.
+//!
+//! The game hands `step_car` sphere-cast hits from Avian and writes the
+//! returned velocities back; here the casts are solved analytically against
+//! the ground plane and the velocities integrated with semi-implicit Euler at
+//! the game's fixed timestep, under the game's gravity (see [`Environment`]).
+//! Gravity, up and ground height come from the engine's [`WorldShape`], as
+//! the game's gravity system takes them from its spherical one.
+//! Chassis-versus-ground collision is not modelled, so a car that bottoms out
+//! sinks through rather than landing on its belly.
+
+use std::{error::Error, fs, path::Path};
+
+use bevy::time::{Fixed, Time};
+use glam::{Mat3, Quat, Vec3};
+use veldera_game_vehicle::{
+    core::{
+        self, CarInput, CarSimState, CarStepContext, CarStepOutput, WheelCastHit,
+        wheel_cast_length, wheel_hardpoint,
+    },
+    telemetry::TelemetrySnapshot,
+};
+use veldera_physics::{Flat, PhysicsConfig, PhysicsIntegrationPlugin, WorldShape};
+
+use crate::model::LabVehicle;
+
+/// The world the rig steps in, read from the same sources as the game's.
+#[derive(Clone, Copy, Debug)]
+pub struct Environment {
+    /// Fixed timestep (s): the game runs vehicle physics in `FixedPreUpdate`
+    /// at Bevy's default `Time<Fixed>` rate.
+    pub dt: f32,
+    /// Gravity (m/s²), from the engine's [`PhysicsConfig`].
+    pub gravity: f32,
+}
+
+impl Environment {
+    /// Read gravity from the engine's physics config under the asset root
+    /// `assets`, and the timestep from a default `Time<Fixed>`.
+    pub fn load(assets: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = assets.join(PhysicsIntegrationPlugin::DEFAULT_PHYSICS_PATH);
+        let text = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let config: PhysicsConfig = toml::from_str(&text)
+            .map_err(|e| format!("failed to parse {}: {e}", path.display()))?;
+        Ok(Self {
+            dt: Time::<Fixed>::default().timestep().as_secs_f32(),
+            gravity: config.gravity,
+        })
+    }
+}
+
+/// The lab's ground.
+const GROUND: Flat = Flat;
+
+/// One vehicle on flat ground.
+pub struct Rig<'a> {
+    vehicle: &'a LabVehicle,
+    /// Timestep and gravity.
+    pub env: Environment,
+    inv_inertia_local: Mat3,
+    /// Persistent core state (gear, rpm, steer angle, wheel speeds).
+    pub state: CarSimState,
+    /// Chassis origin (m). The model origin sits on the ground under the
+    /// wheel centroid, so `y` is the ride height relative to the rest pose.
+    pub position: Vec3,
+    /// Chassis rotation; identity faces -Z with +Y up.
+    pub rotation: Quat,
+    /// Linear velocity of the centre of mass (m/s).
+    pub velocity: Vec3,
+    /// Angular velocity (rad/s).
+    pub angular_velocity: Vec3,
+    /// Simulated time (s).
+    pub elapsed: f32,
+    /// The most recent step's output.
+    pub last: CarStepOutput,
+}
+
+impl<'a> Rig<'a> {
+    /// Place `vehicle` at rest, upright and facing -Z, with its origin
+    /// `height` above the ground.
+    pub fn new(vehicle: &'a LabVehicle, env: Environment, height: f32) -> Self {
+        Self {
+            vehicle,
+            env,
+            // Same floor the game applies to the principal moments.
+            inv_inertia_local: Mat3::from_diagonal(vehicle.inertia.max(Vec3::ONE).recip()),
+            state: CarSimState::default(),
+            position: Vec3::Y * height,
+            rotation: Quat::IDENTITY,
+            velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            elapsed: 0.0,
+            last: CarStepOutput::default(),
+        }
+    }
+
+    /// Chassis forward (-Z) in world space.
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * Vec3::NEG_Z
+    }
+
+    /// Chassis right (+X) in world space.
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    /// Advance one fixed step, returning the telemetry row for it.
+    pub fn step(&mut self, input: CarInput) -> TelemetrySnapshot {
+        let params = &self.vehicle.params;
+        let Environment { dt, gravity } = self.env;
+        self.velocity += GROUND.gravity_delta(self.position.as_dvec3(), gravity, dt);
+
+        // The game starts each cast a radius plus the travel above the
+        // hardpoint and subtracts that raise from the hit distance; against a
+        // plane that's the same as solving from the hardpoint directly and
+        // letting the distance go negative when the wheel is buried, down to
+        // the raise itself (a cast that starts inside the ground hits at 0).
+        let down = self.rotation * Vec3::NEG_Y;
+        let up = GROUND.up(self.position.as_dvec3()).as_vec3();
+        let mut hits: [Option<WheelCastHit>; 4] = [None; 4];
+        if down.dot(up) < -1e-3 {
+            for (hit, wheel) in hits.iter_mut().zip(&self.vehicle.wheels) {
+                let origin = self.position + self.rotation * wheel_hardpoint(wheel, params);
+                let raise = wheel.radius + params.suspension_travel;
+                let height = GROUND.height(origin.as_dvec3()) as f32;
+                let distance = ((height - wheel.radius) / -down.dot(up)).max(-raise);
+                if distance <= wheel_cast_length(params) {
+                    let center = (origin + down * distance).as_dvec3();
+                    *hit = Some(WheelCastHit {
+                        distance,
+                        normal: GROUND.up(center).as_vec3(),
+                        point: GROUND.at_height(center, 0.0).as_vec3(),
+                    });
+                }
+            }
+        }
+
+        let rotation = Mat3::from_quat(self.rotation);
+        let ctx = CarStepContext {
+            position: self.position,
+            rotation: self.rotation,
+            world_com: self.position + self.rotation * self.vehicle.center_of_mass,
+            linear_velocity: self.velocity,
+            angular_velocity: self.angular_velocity,
+            inv_inertia_world: rotation * self.inv_inertia_local * rotation.transpose(),
+            gravity,
+            dt,
+        };
+        let output = core::step_car(
+            params,
+            &self.vehicle.wheels,
+            &mut self.state,
+            &input,
+            &hits,
+            &ctx,
+        );
+
+        // Integrate about the centre of mass, as Avian does.
+        self.velocity = output.linear_velocity;
+        self.angular_velocity = output.angular_velocity;
+        let world_com = ctx.world_com + self.velocity * dt;
+        if self.angular_velocity.length_squared() > 0.0 {
+            self.rotation =
+                (Quat::from_scaled_axis(self.angular_velocity * dt) * self.rotation).normalize();
+        }
+        self.position = world_com - self.rotation * self.vehicle.center_of_mass;
+        self.elapsed += dt;
+
+        let snapshot = TelemetrySnapshot {
+            elapsed: self.elapsed,
+            dt,
+            drive: input.drive,
+            steer: input.steer,
+            handbrake: input.handbrake,
+            throttle: output.throttle,
+            brake: output.brake,
+            gear: self.state.gear,
+            rpm: self.state.rpm,
+            speed: output.speed,
+            forward_speed: output.forward_speed,
+            steer_angle: self.state.steer_angle,
+            wheels: output.wheels,
+        };
+        self.last = output;
+        snapshot
+    }
+}
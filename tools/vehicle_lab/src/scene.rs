@@ -0,0 +1,104 @@
+//! Load a vehicle definition (`.scn.ron`) the way the game does: through a
+//! Bevy type registry into a `DynamicScene`, then `FromReflect` into the
+//! typed config components.
+
+use std::{error::Error, fs, path::Path};
+
+use bevy::{
+    reflect::{FromReflect, TypePath, TypeRegistry},
+    scene::{DynamicScene, serde::SceneDeserializer},
+};
+use serde::de::DeserializeSeed;
+use veldera_game_camera::FollowCameraConfig;
+use veldera_game_vehicle::{
+    DriveLayout, Vehicle, VehicleChassisConfig, VehicleEngineConfig, VehicleModel,
+    VehicleSteeringConfig, VehicleSuspensionConfig, VehicleTireConfig, VehicleTransmissionConfig,
+    physics::build_car_params,
+};
+
+use crate::model::{LabVehicle, ModelGeometry};
+
+/// Load the vehicle defined by the scene at `path`, resolving its model path
+/// against `assets`.
+pub fn load_vehicle(path: &Path, assets: &Path) -> Result<LabVehicle, Box<dyn Error>> {
+    // Every component type a vehicle scene may carry must be registered, or
+    // deserialization fails on the first unknown one.
+    let mut registry = TypeRegistry::default();
+    registry.register::<Vehicle>();
+    registry.register::<VehicleChassisConfig>();
+    registry.register::<VehicleSuspensionConfig>();
+    registry.register::<VehicleEngineConfig>();
+    registry.register::<VehicleTransmissionConfig>();
+    registry.register::<VehicleSteeringConfig>();
+    registry.register::<VehicleTireConfig>();
+    registry.register::<DriveLayout>();
+    registry.register::<VehicleModel>();
+    registry.register::<FollowCameraConfig>();
+
+    let text = fs::read_to_string(path)?;
+    let mut deserializer = ron::de::Deserializer::from_str(&text)?;
+    let scene = SceneDeserializer {
+        type_registry: &registry,
+    }
+    .deserialize(&mut deserializer)?;
+
+    let vehicle: Vehicle = component(&scene, path)?;
+    let chassis: VehicleChassisConfig = component(&scene, path)?;
+    let suspension: VehicleSuspensionConfig = component(&scene, path)?;
+    let engine: VehicleEngineConfig = component(&scene, path)?;
+    let transmission: VehicleTransmissionConfig = component(&scene, path)?;
+    let steering: VehicleSteeringConfig = component(&scene, path)?;
+    let tire: VehicleTireConfig = component(&scene, path)?;
+    let model: VehicleModel = component(&scene, path)?;
+
+    let (front_driven, rear_driven) = match transmission.drive {
+        DriveLayout::Front => (true, false),
+        DriveLayout::Rear => (false, true),
+        DriveLayout::All => (true, true),
+    };
+    // Strip the `#Scene0` label; the geometry comes from the whole file.
+    let model_path = model.path.split('#').next().unwrap_or_default();
+    let geometry = ModelGeometry::load(
+        &assets.join(model_path),
+        model.scale,
+        front_driven,
+        rear_driven,
+    )?;
+
+    let slug = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".scn.ron"))
+        .unwrap_or(&vehicle.name)
+        .to_string();
+    let params = build_car_params(
+        &chassis,
+        &suspension,
+        &engine,
+        &transmission,
+        &steering,
+        &tire,
+    );
+    Ok(LabVehicle::new(
+        vehicle.name,
+        slug,
+        params,
+        chassis.center_of_mass,
+        geometry,
+    ))
+}
+
+/// The first component of type `T` in `scene`.
+fn component<T: FromReflect + TypePath>(scene: &DynamicScene, path: &Path) -> Result<T, String> {
+    scene
+        .entities
+        .iter()
+        .flat_map(|entity| &entity.components)
+        .filter(|component| {
+            component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_path() == T::type_path())
+        })
+        .find_map(|component| T::from_reflect(component.as_partial_reflect()))
+        .ok_or_else(|| format!("{} has no {}", path.display(), T::short_type_path()))
+}